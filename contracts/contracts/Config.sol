@@ -93,4 +93,7 @@ contract Config {
 
     /// @dev Auth fact reset timelock
     uint256 constant AUTH_FACT_RESET_TIMELOCK = 1 days;
+
+    /// @dev Max length of the payload of the message sent to the zkSync account
+    uint256 constant MAX_MESSAGE_PAYLOAD_LENGTH = 1024;
 }
@@ -38,6 +38,9 @@ interface Events {
         uint256 expirationBlock
     );
 
+    /// @notice Message to the zkSync account sent from L1. Emitted when the message is sent.
+    event MessageSent(address indexed sender, address indexed recipient, uint64 serialId, bytes payload);
+
     /// @notice Deposit committed event.
     event DepositCommit(
         uint32 indexed zkSyncBlockId,
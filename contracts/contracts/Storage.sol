@@ -155,4 +155,7 @@ contract Storage {
     /// @dev Timer for authFacts entry reset (address, nonce -> timer).
     /// @dev Used when user wants to reset `authFacts` for some nonce.
     mapping(address => mapping(uint32 => uint256)) internal authFactsResetTimer;
+
+    /// @notice Total number of the messages sent to the zkSync accounts, serial id of the next message
+    uint64 public totalMessagesSent;
 }
@@ -332,6 +332,18 @@ contract ZkSync is UpgradeableMaster, Storage, Config, Events, ReentrancyGuard {
         pendingBalances[packedBalanceKey].gasReserveValue = FILLED_GAS_RESERVE_VALUE;
     }
 
+    /// @notice Send the message to the zkSync account, e.g. to notify it about the L1 contract call
+    /// @dev Messages don't change the zkSync state, they're only delivered to the recipient by the operator
+    /// @param _recipient The address of the zkSync account the message is addressed to
+    /// @param _payload Arbitrary message payload
+    function sendMessage(address _recipient, bytes calldata _payload) external nonReentrant {
+        requireActive();
+        require(_payload.length <= MAX_MESSAGE_PAYLOAD_LENGTH, "M1"); // message payload is too long
+
+        emit MessageSent(msg.sender, _recipient, totalMessagesSent, _payload);
+        totalMessagesSent++;
+    }
+
     /// @notice Register full exit request - pack pubdata, add priority request
     /// @notice DEPRECATED: use requestFullExit instead.
     /// @param _accountId Numerical id of the account
@@ -735,7 +747,7 @@ contract ZkSync is UpgradeableMaster, Storage, Config, Events, ReentrancyGuard {
     /// @notice Checks that change operation is correct
     function verifyChangePubkey(bytes memory _ethWitness, Operations.ChangePubKey memory _changePk)
         internal
-        pure
+        view
         returns (bool)
     {
         Operations.ChangePubkeyType changePkType = Operations.ChangePubkeyType(uint8(_ethWitness[0]));
@@ -745,6 +757,8 @@ contract ZkSync is UpgradeableMaster, Storage, Config, Events, ReentrancyGuard {
             return verifyChangePubkeyCREATE2(_ethWitness, _changePk);
         } else if (changePkType == Operations.ChangePubkeyType.OldECRECOVER) {
             return verifyChangePubkeyOldECRECOVER(_ethWitness, _changePk);
+        } else if (changePkType == Operations.ChangePubkeyType.Guardians) {
+            return verifyChangePubkeyGuardians(_ethWitness, _changePk);
         } else {
             revert("G"); // Incorrect ChangePubKey type
         }
@@ -827,6 +841,99 @@ contract ZkSync is UpgradeableMaster, Storage, Config, Events, ReentrancyGuard {
         return recoveredAddress == _changePk.owner && _changePk.nonce == 0;
     }
 
+    /// @notice Checks that pubkey change is authorized by the quorum of the guardians registered by the account owner
+    /// @param _ethWitness Guardian set: version (4 bytes), quorum (1 byte), timelock (4 bytes), guardians count (1 byte)
+    /// and guardians (20 bytes each), owner signature of the guardian set (65 bytes), then `quorum` guardian signatures of the
+    /// recovery message: guardian index (1 byte) + signature (65 bytes), with the strictly increasing indices
+    /// @param _changePk Parsed change pubkey operation
+    /// @dev The version of the guardian set and the timelock of the recovery are not checked here: the registrations and
+    /// the recoveries are not recorded on-chain, so they're enforced by the operator. The owner trusts the operator not to
+    /// execute the recovery authorized by a replaced guardian set or before the timelock passes
+    function verifyChangePubkeyGuardians(bytes memory _ethWitness, Operations.ChangePubKey memory _changePk)
+        internal
+        view
+        returns (bool)
+    {
+        (uint256 offset, bytes memory guardians, uint8 quorum, bytes32 guardiansHash) =
+            readGuardianSet(_ethWitness, _changePk.accountId);
+        bytes memory ownerSignature;
+        (offset, ownerSignature) = Bytes.read(_ethWitness, offset, 65);
+        address owner = Utils.recoverAddressFromEthSignature(ownerSignature, personalMessageHash(guardiansHash));
+        if (owner != _changePk.owner || owner == address(0)) {
+            return false;
+        }
+
+        bytes32 recoveryHash =
+            personalMessageHash(
+                keccak256(abi.encodePacked("zkSync recovery", guardiansHash, _changePk.pubKeyHash, _changePk.nonce))
+            );
+        return verifyGuardianSignatures(_ethWitness, offset, guardians, quorum, recoveryHash);
+    }
+
+    /// @notice Reads the guardian set from the change pubkey witness
+    /// @return offset - offset of the owner signature
+    /// @return guardians - addresses of the guardians, 20 bytes each
+    /// @return quorum - number of the guardian signatures required
+    /// @return guardiansHash - hash of the guardian set signed by the account owner, bound to the chain ID and
+    /// the address of this contract, so the signatures can't be replayed on the other networks
+    function readGuardianSet(bytes memory _ethWitness, uint32 _accountId)
+        internal
+        view
+        returns (
+            uint256 offset,
+            bytes memory guardians,
+            uint8 quorum,
+            bytes32 guardiansHash
+        )
+    {
+        offset = 1; // offset is 1 because we skip type of ChangePubkey
+        uint32 version;
+        uint32 timelock;
+        uint8 guardiansCount;
+        (offset, version) = Bytes.readUInt32(_ethWitness, offset);
+        (offset, quorum) = Bytes.readUint8(_ethWitness, offset);
+        (offset, timelock) = Bytes.readUInt32(_ethWitness, offset);
+        (offset, guardiansCount) = Bytes.readUint8(_ethWitness, offset);
+        require(quorum > 0 && quorum <= guardiansCount, "Q"); // invalid guardians quorum
+        (offset, guardians) = Bytes.read(_ethWitness, offset, uint256(guardiansCount) * 20);
+        uint256 chainId;
+        assembly {
+            chainId := chainid()
+        }
+        guardiansHash = keccak256(
+            abi.encodePacked("zkSync guardians", chainId, address(this), _accountId, version, quorum, timelock, guardians)
+        );
+    }
+
+    /// @notice Checks that `_quorum` distinct guardians signed the message
+    function verifyGuardianSignatures(
+        bytes memory _ethWitness,
+        uint256 _offset,
+        bytes memory _guardians,
+        uint8 _quorum,
+        bytes32 _messageHash
+    ) internal pure returns (bool) {
+        uint256 nextIndex = 0; // indices are strictly increasing, so every guardian is counted once
+        for (uint8 i = 0; i < _quorum; ++i) {
+            uint8 index;
+            bytes memory signature;
+            (_offset, index) = Bytes.readUint8(_ethWitness, _offset);
+            (_offset, signature) = Bytes.read(_ethWitness, _offset, 65);
+            require(index >= nextIndex && uint256(index) * 20 < _guardians.length, "R"); // invalid guardian index
+            nextIndex = uint256(index) + 1;
+            address guardian = Bytes.bytesToAddress(_guardians, uint256(index) * 20);
+            if (Utils.recoverAddressFromEthSignature(signature, _messageHash) != guardian) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    /// @notice Returns the hash signed by `personal_sign` for the 32 bytes message
+    function personalMessageHash(bytes32 _message) internal pure returns (bytes32) {
+        return keccak256(abi.encodePacked("\x19Ethereum Signed Message:\n32", _message));
+    }
+
     /// @dev Creates block commitment from its data
     /// @dev _offsetCommitment - hash of the array where 1 is stored in chunk where onchainOperation begins and 0 for other chunks
     function createBlockCommitment(
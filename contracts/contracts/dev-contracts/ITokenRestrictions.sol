@@ -0,0 +1,16 @@
+// SPDX-License-Identifier: UNLICENSED
+
+pragma solidity ^0.7.0;
+
+/// @notice Restrictions implemented by some of the ERC20 tokens (e.g. USDC, USDT) that may block the transfers.
+/// @dev Tokens usually implement only a part of these functions, the missing ones are ignored by the server.
+interface ITokenRestrictions {
+    /// @notice Returns true if all the token transfers are paused
+    function paused() external view returns (bool);
+
+    /// @notice Returns true if the transfers to and from the account are blocked (USDC-like tokens)
+    function isBlacklisted(address _account) external view returns (bool);
+
+    /// @notice Returns true if the transfers to and from the account are blocked (USDT-like tokens)
+    function isBlackListed(address _account) external view returns (bool);
+}
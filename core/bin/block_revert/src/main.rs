@@ -7,18 +7,32 @@ use web3::{
     contract::Options,
     types::{TransactionReceipt, U256, U64},
 };
-use zksync_config::ZkSyncConfig;
+use zksync_config::{
+    configs::database::{DBConfig, TreeCacheBackend},
+    ZkSyncConfig,
+};
 use zksync_eth_client::EthereumGateway;
 use zksync_storage::StorageProcessor;
+use zksync_tree_cache::TreeCacheStorage;
 use zksync_types::{aggregated_operations::stored_block_info, block::Block, BlockNumber, H256};
 
 // TODO: don't use anyhow (ZKS-588)
 async fn revert_blocks_in_storage(
     storage: &mut StorageProcessor<'_>,
     last_block: BlockNumber,
+    last_commited_block: BlockNumber,
+    reject_txs: bool,
+    db_config: &DBConfig,
 ) -> anyhow::Result<()> {
     let mut transaction = storage.start_transaction().await?;
 
+    let revert_id = transaction
+        .chain()
+        .block_schema()
+        .record_block_revert(last_block, last_commited_block, !reject_txs)
+        .await?;
+    println!("Revert is recorded with id {}", revert_id);
+
     transaction
         .chain()
         .block_schema()
@@ -56,6 +70,12 @@ async fn revert_blocks_in_storage(
         .remove_account_pubkey_updates(last_block)
         .await?;
     println!("`account_pubkey_updates` table is cleaned");
+    transaction
+        .chain()
+        .state_schema()
+        .remove_tx_account_updates(last_block)
+        .await?;
+    println!("`tx_account_updates` table is cleaned");
 
     transaction
         .chain()
@@ -69,6 +89,11 @@ async fn revert_blocks_in_storage(
         .remove_executed_priority_operations(last_block)
         .await?;
     println!("`executed_priority_operations` table is cleaned");
+    transaction
+        .l1_messages_schema()
+        .remove_messages(last_block)
+        .await?;
+    println!("`l1_messages` table is cleaned");
     transaction
         .chain()
         .operations_schema()
@@ -103,16 +128,49 @@ async fn revert_blocks_in_storage(
         .await?;
     println!("`eth_parameters` table is updated");
 
+    if reject_txs {
+        transaction
+            .chain()
+            .mempool_schema()
+            .reject_executed_txs(last_block, revert_id)
+            .await?;
+        println!("`reverted_transactions`, `executed_transactions` tables are updated");
+        transaction
+            .fast_withdrawals_schema()
+            .release_reverted_intents(revert_id)
+            .await?;
+        println!("`fast_withdrawal_intents` table is updated");
+    } else {
+        transaction
+            .chain()
+            .mempool_schema()
+            .return_executed_txs_to_mempool(last_block)
+            .await?;
+        println!("`mempool_txs`, `executed_transactions` tables are updated");
+    }
+
     transaction
         .chain()
-        .mempool_schema()
-        .return_executed_txs_to_mempool(last_block)
+        .block_schema()
+        .resume_block_production()
         .await?;
-    println!("`mempool_txs`, `executed_transactions` tables are updated");
+    println!("`block_production_halt` table is cleaned");
 
     transaction.commit().await?;
 
+    if db_config.tree_cache_backend == TreeCacheBackend::RocksDb {
+        for component in zksync_tree_cache::COMPONENTS {
+            TreeCacheStorage::open(db_config, component)?
+                .remove_after(storage, last_block)
+                .await?;
+        }
+        println!("RocksDB account tree caches are cleaned");
+    }
+
     println!("Blocks were reverted in storage");
+    println!(
+        "Restart the server to resync the state and mempool, eth_sender resyncs automatically"
+    );
     Ok(())
 }
 
@@ -171,6 +229,36 @@ async fn revert_blocks_on_contract(
     Ok(())
 }
 
+/// Checks that there are no Ethereum transactions in flight for the blocks to revert.
+///
+/// Blocks are committed without waiting for the proofs of the previous blocks, so the commit
+/// and the proof transactions for the reverted blocks may still be pending. If such transaction
+/// is mined after the revert, the state of the contract won't match the reverted storage.
+// TODO: don't use anyhow (ZKS-588)
+async fn ensure_no_pending_eth_operations(
+    storage: &mut StorageProcessor<'_>,
+    last_correct_block: BlockNumber,
+) -> anyhow::Result<()> {
+    let unconfirmed_operations = storage
+        .ethereum_schema()
+        .load_unconfirmed_operations()
+        .await?;
+    for operation in unconfirmed_operations {
+        if let Some((_, aggregated_op)) = &operation.op {
+            let (_, last_affected_block) = aggregated_op.get_block_range();
+            ensure!(
+                last_affected_block <= last_correct_block,
+                "Ethereum operation {} ({:?}) for blocks up to {} is not confirmed yet. \
+                 Stop the eth_sender and wait for the pending transactions to be mined",
+                operation.id,
+                operation.op_type,
+                last_affected_block
+            );
+        }
+    }
+    Ok(())
+}
+
 // TODO: don't use anyhow (ZKS-588)
 async fn get_blocks(
     last_commited_block: BlockNumber,
@@ -207,8 +295,14 @@ enum Command {
 #[structopt(about = "Tool to revert blocks in zkSync network on contract and/or in storage")]
 struct Opt {
     /// Last correct block, tool reverts blocks with numbers greater than this field.
+    #[structopt(long, required_unless = "blocks", conflicts_with = "blocks")]
+    last_correct_block: Option<u32>,
+    /// Amount of the last committed blocks to revert.
     #[structopt(long)]
-    last_correct_block: u32,
+    blocks: Option<u32>,
+    /// Reject the transactions of the reverted blocks instead of returning them to the mempool.
+    #[structopt(long)]
+    reject_txs: bool,
     #[structopt(subcommand)]
     command: Command,
     /// Private key of operator which will call the contract function.
@@ -248,13 +342,30 @@ async fn main() -> anyhow::Result<()> {
         "Last committed block {} verified {}",
         &last_commited_block, &last_verified_block
     );
+    let last_correct_block = match (opt.last_correct_block, opt.blocks) {
+        (Some(last_correct_block), _) => last_correct_block,
+        (None, Some(blocks)) => {
+            ensure!(
+                blocks <= *last_commited_block,
+                "There are only {} committed blocks",
+                last_commited_block
+            );
+            *last_commited_block - blocks
+        }
+        (None, None) => bail!("Either last correct block or amount of blocks must be provided"),
+    };
     ensure!(
-        *last_verified_block <= opt.last_correct_block,
+        *last_verified_block <= last_correct_block,
         "Some blocks to revert are already verified"
     );
+    ensure!(
+        last_correct_block <= *last_commited_block,
+        "Last correct block is greater than the last committed block"
+    );
 
-    let blocks_to_revert = *last_commited_block - opt.last_correct_block;
-    let last_block = BlockNumber(opt.last_correct_block);
+    let blocks_to_revert = *last_commited_block - last_correct_block;
+    let last_block = BlockNumber(last_correct_block);
+    ensure_no_pending_eth_operations(&mut storage, last_block).await?;
 
     match opt.command {
         Command::All => {
@@ -262,7 +373,14 @@ async fn main() -> anyhow::Result<()> {
             let blocks = get_blocks(last_commited_block, blocks_to_revert, &mut storage).await?;
             println!("Last block for revert {}", &last_block);
             revert_blocks_on_contract(&mut storage, &client, &blocks).await?;
-            revert_blocks_in_storage(&mut storage, last_block).await?;
+            revert_blocks_in_storage(
+                &mut storage,
+                last_block,
+                last_commited_block,
+                opt.reject_txs,
+                &config.db,
+            )
+            .await?;
         }
         Command::Contract => {
             println!("Start reverting blocks in contract");
@@ -271,7 +389,14 @@ async fn main() -> anyhow::Result<()> {
         }
         Command::Storage => {
             println!("Start reverting blocks in database");
-            revert_blocks_in_storage(&mut storage, last_block).await?;
+            revert_blocks_in_storage(
+                &mut storage,
+                last_block,
+                last_commited_block,
+                opt.reject_txs,
+                &config.db,
+            )
+            .await?;
         }
     }
 
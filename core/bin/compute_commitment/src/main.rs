@@ -0,0 +1,222 @@
+//! Tool to re-derive the commitment of the zkSync block from the data published on L1.
+//!
+//! The tool needs only an Ethereum node: it finds the `commitBlocks` transaction of the block,
+//! computes the commitment and the rest of the `StoredBlockInfo` from the block public data
+//! (see `zksync_types::block_commitment`), and, once the block is executed, compares them with the
+//! `StoredBlockInfo` accepted by the contract in the `executeBlocks` call. The result is printed
+//! as JSON, and the tool exits with an error if the values don't match.
+//!
+//! The contract ABI is loaded from the zkSync repository, so `ZKSYNC_HOME` must be set.
+
+use anyhow::{bail, ensure, format_err};
+use ethabi::{Contract, Token};
+use serde::Serialize;
+use structopt::StructOpt;
+use web3::{
+    transports::Http,
+    types::{BlockNumber as EthBlockNumber, FilterBuilder, TransactionId},
+    Web3,
+};
+use zksync_contracts::zksync_contract;
+use zksync_types::{
+    block_commitment::{compute_block_commitment, decode_commit_blocks, StoredBlockInfo},
+    AccountId, Address, BlockNumber, H256,
+};
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "zkSync block commitment calculator", author = "Matter Labs")]
+#[structopt(
+    about = "Tool to re-derive the block commitment submitted on L1 from the block public data"
+)]
+struct Opt {
+    /// Number of the block to compute the commitment for.
+    #[structopt(long)]
+    block: u32,
+    /// Ethereum node URL.
+    #[structopt(long, env = "ETH_CLIENT_WEB3_URL")]
+    web3_url: String,
+    /// Address of the zkSync contract.
+    #[structopt(
+        long = "contract",
+        env = "CONTRACTS_CONTRACT_ADDR",
+        parse(try_from_str = parse_address)
+    )]
+    contract_address: Address,
+    /// Ethereum block to start looking for the zkSync transactions from.
+    #[structopt(long, default_value = "0")]
+    from_eth_block: u64,
+}
+
+fn parse_address(address: &str) -> Result<Address, String> {
+    address
+        .trim_start_matches("0x")
+        .parse()
+        .map_err(|err| format!("Invalid address {}: {}", address, err))
+}
+
+/// Result of the commitment computation.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CommitmentReport {
+    block_number: BlockNumber,
+    fee_account: AccountId,
+    previous_state_hash: H256,
+    /// Hash of the `commitBlocks` transaction the block is committed in.
+    commit_tx_hash: H256,
+    /// `StoredBlockInfo` computed from the public data.
+    stored_block_info: StoredBlockInfo,
+    /// Hash of the `StoredBlockInfo` as it's kept by the contract.
+    stored_block_hash: H256,
+    /// Hash of the `executeBlocks` transaction, if the block is executed.
+    execute_tx_hash: Option<H256>,
+    /// Whether the computed `StoredBlockInfo` matches the one accepted by the contract,
+    /// if the block is executed.
+    matches_executed_block: Option<bool>,
+}
+
+struct L1Data {
+    web3: Web3<Http>,
+    contract: Contract,
+    contract_address: Address,
+    from_eth_block: u64,
+}
+
+impl L1Data {
+    /// Returns the input of the last transaction that has emitted the event for the block.
+    async fn event_tx(
+        &self,
+        event: &str,
+        block_number: BlockNumber,
+    ) -> anyhow::Result<Option<(H256, Vec<u8>)>> {
+        let topic = self.contract.event(event)?.signature();
+        let filter = FilterBuilder::default()
+            .address(vec![self.contract_address])
+            .topics(
+                Some(vec![topic]),
+                Some(vec![H256::from_low_u64_be(u64::from(*block_number))]),
+                None,
+                None,
+            )
+            .from_block(EthBlockNumber::Number(self.from_eth_block.into()))
+            .to_block(EthBlockNumber::Latest)
+            .build();
+        // The block can be committed again after the revert, so the last event is the actual one.
+        let tx_hash = match self
+            .web3
+            .eth()
+            .logs(filter)
+            .await?
+            .into_iter()
+            .filter_map(|log| log.transaction_hash)
+            .last()
+        {
+            Some(tx_hash) => tx_hash,
+            None => return Ok(None),
+        };
+
+        let tx = self
+            .web3
+            .eth()
+            .transaction(TransactionId::Hash(tx_hash))
+            .await?
+            .ok_or_else(|| format_err!("Transaction {:?} is not found", tx_hash))?;
+        Ok(Some((tx_hash, tx.input.0)))
+    }
+
+    fn decode_input(&self, function: &str, input: &[u8]) -> anyhow::Result<Vec<Token>> {
+        let function = self.contract.function(function)?;
+        ensure!(
+            input.len() >= 4 && input[..4] == function.short_signature(),
+            "Transaction is not a {} call",
+            function.name
+        );
+        Ok(function.decode_input(&input[4..])?)
+    }
+}
+
+/// Finds the `StoredBlockInfo` of the block in the `executeBlocks` arguments.
+fn executed_block_info(
+    tokens: &[Token],
+    block_number: BlockNumber,
+) -> anyhow::Result<StoredBlockInfo> {
+    let blocks = match tokens {
+        [Token::Array(blocks)] => blocks,
+        _ => bail!("Unexpected executeBlocks arguments: {:?}", tokens),
+    };
+    for block in blocks {
+        // `ExecuteBlockInfo` is `(storedBlock, pendingOnchainOpsPubdata)`.
+        let stored_block = match block {
+            Token::Tuple(fields) if !fields.is_empty() => StoredBlockInfo::from_token(&fields[0])?,
+            _ => bail!("Unexpected ExecuteBlockInfo value: {:?}", block),
+        };
+        if stored_block.block_number == block_number {
+            return Ok(stored_block);
+        }
+    }
+    bail!(
+        "Block #{} is not executed by the transaction",
+        *block_number
+    )
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opt = Opt::from_args();
+    let block_number = BlockNumber(opt.block);
+    // The config may list several nodes, any of them will do.
+    let web3_url = opt.web3_url.split(',').next().unwrap_or_default();
+    let l1 = L1Data {
+        web3: Web3::new(Http::new(web3_url)?),
+        contract: zksync_contract(),
+        contract_address: opt.contract_address,
+        from_eth_block: opt.from_eth_block,
+    };
+
+    let (commit_tx_hash, commit_input) = l1
+        .event_tx("BlockCommit", block_number)
+        .await?
+        .ok_or_else(|| format_err!("Block #{} is not committed", *block_number))?;
+    let input = decode_commit_blocks(&l1.decode_input("commitBlocks", &commit_input)?)?
+        .into_iter()
+        .find(|input| input.block_number == block_number)
+        .ok_or_else(|| {
+            format_err!(
+                "Block #{} is not committed by the transaction {:?}",
+                *block_number,
+                commit_tx_hash
+            )
+        })?;
+    let stored_block_info = compute_block_commitment(&input)?;
+
+    let (execute_tx_hash, matches_executed_block) =
+        match l1.event_tx("BlockVerification", block_number).await? {
+            Some((tx_hash, execute_input)) => {
+                let executed = executed_block_info(
+                    &l1.decode_input("executeBlocks", &execute_input)?,
+                    block_number,
+                )?;
+                (Some(tx_hash), Some(executed == stored_block_info))
+            }
+            None => (None, None),
+        };
+
+    let report = CommitmentReport {
+        block_number,
+        fee_account: input.fee_account,
+        previous_state_hash: input.previous_state_hash,
+        commit_tx_hash,
+        stored_block_hash: stored_block_info.hash(),
+        stored_block_info,
+        execute_tx_hash,
+        matches_executed_block,
+    };
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    if report.matches_executed_block == Some(false) {
+        bail!(
+            "Computed data of the block #{} doesn't match the data accepted by the contract",
+            *block_number
+        );
+    }
+    Ok(())
+}
@@ -1,10 +1,29 @@
+use std::fmt;
+
+use anyhow::Context;
 use ethabi::ParamType;
 
 use zksync_types::{AccountId, BlockNumber, ZkSyncOp, H256};
 
 use crate::rollup_ops::RollupOpsBlock;
 
-pub fn rollup_ops_blocks_from_bytes(input_data: Vec<u8>) -> Result<RollupOpsBlock, anyhow::Error> {
+/// Input data of the transaction doesn't have the shape of the legacy block commitment,
+/// e.g. it's the commitment of the newer contract version.
+#[derive(Debug)]
+pub struct CommitmentShapeError(&'static str);
+
+impl fmt::Display for CommitmentShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl std::error::Error for CommitmentShapeError {}
+
+pub fn rollup_ops_blocks_from_bytes(
+    input_data: Vec<u8>,
+    strict_pubdata: bool,
+) -> Result<RollupOpsBlock, anyhow::Error> {
     let block_number_argument_id = 0;
     let fee_account_argument_id = 1;
     let public_data_argument_id = 3;
@@ -21,10 +40,7 @@ pub fn rollup_ops_blocks_from_bytes(input_data: Vec<u8>) -> Result<RollupOpsBloc
         input_data.as_slice(),
     )
     .map_err(|_| {
-        anyhow::Error::from(Box::new(std::io::Error::new(
-            std::io::ErrorKind::NotFound,
-            "can't get decoded parameters from commitment transaction",
-        )))
+        CommitmentShapeError("can't get decoded parameters from commitment transaction")
     })?;
 
     if let (
@@ -36,7 +52,7 @@ pub fn rollup_ops_blocks_from_bytes(input_data: Vec<u8>) -> Result<RollupOpsBloc
         &decoded_commitment_parameters[fee_account_argument_id],
         &decoded_commitment_parameters[public_data_argument_id],
     ) {
-        let ops = get_rollup_ops_from_data(public_data.as_slice())?;
+        let ops = get_rollup_ops_from_data(public_data.as_slice(), strict_pubdata)?;
         let fee_account = AccountId(fee_acc.as_u32());
 
         let block = RollupOpsBlock {
@@ -48,26 +64,34 @@ pub fn rollup_ops_blocks_from_bytes(input_data: Vec<u8>) -> Result<RollupOpsBloc
         };
         Ok(block)
     } else {
-        Err(std::io::Error::new(
-            std::io::ErrorKind::NotFound,
-            "can't parse commitment parameters",
-        )
-        .into())
+        Err(CommitmentShapeError("can't parse commitment parameters").into())
     }
 }
 
-pub fn get_rollup_ops_from_data(data: &[u8]) -> Result<Vec<ZkSyncOp>, anyhow::Error> {
+/// Decodes the operations from the block public data.
+/// In `strict` mode, non-canonical encodings of the operations are rejected.
+pub fn get_rollup_ops_from_data(data: &[u8], strict: bool) -> Result<Vec<ZkSyncOp>, anyhow::Error> {
     let mut current_pointer = 0;
     let mut ops = vec![];
     while current_pointer < data.len() {
         let op_type: u8 = data[current_pointer];
 
-        let pub_data_size = ZkSyncOp::public_data_length(op_type)?;
+        let pub_data_size = ZkSyncOp::public_data_length(op_type).with_context(|| {
+            format!(
+                "Unknown operation type 0x{:02x} at pubdata offset {}",
+                op_type, current_pointer
+            )
+        })?;
 
         let pre = current_pointer;
         let post = pre + pub_data_size;
 
-        let op = ZkSyncOp::from_public_data(&data[pre..post])?;
+        let op = if strict {
+            ZkSyncOp::from_public_data_strict(&data[pre..post])
+        } else {
+            ZkSyncOp::from_public_data(&data[pre..post])
+        }
+        .with_context(|| format!("Failed to decode operation at pubdata offset {}", pre))?;
 
         ops.push(op);
         current_pointer += pub_data_size;
@@ -79,7 +103,9 @@ pub fn get_rollup_ops_from_data(data: &[u8]) -> Result<Vec<ZkSyncOp>, anyhow::Er
 mod test {
     use num::BigUint;
 
-    use zksync_types::operations::ChangePubKeyOp;
+    use zksync_types::operations::{
+        ChangePubKeyOp, NoopOp, PublicDataDecodeError, UnexpectedOperationType,
+    };
     use zksync_types::tx::{ChangePubKey, TxSignature};
     use zksync_types::{
         AccountId, Close, CloseOp, Deposit, DepositOp, FullExit, FullExitOp, Nonce, PubKeyHash,
@@ -101,7 +127,7 @@ mod test {
             account_id: AccountId(6),
         }));
         let pub_data1 = op1.public_data();
-        let op2 = get_rollup_ops_from_data(&pub_data1)
+        let op2 = get_rollup_ops_from_data(&pub_data1, false)
             .expect("cant get ops from data")
             .pop()
             .expect("empty ops array");
@@ -109,6 +135,43 @@ mod test {
         assert_eq!(pub_data1, pub_data2);
     }
 
+    #[test]
+    fn test_strict_pubdata() {
+        let priority_op = Deposit {
+            from: "1111111111111111111111111111111111111111".parse().unwrap(),
+            token: TokenId(1),
+            amount: 10u32.into(),
+            to: "7777777777777777777777777777777777777777".parse().unwrap(),
+        };
+        let op = ZkSyncOp::Deposit(Box::new(DepositOp {
+            priority_op,
+            account_id: AccountId(6),
+        }));
+        let mut pub_data = op.public_data();
+        assert!(get_rollup_ops_from_data(&pub_data, true).is_ok());
+
+        // Garbage in the padding is ignored by the decoder, but makes the encoding non-canonical.
+        *pub_data.last_mut().unwrap() = 0x01;
+        assert!(get_rollup_ops_from_data(&pub_data, false).is_ok());
+        assert!(get_rollup_ops_from_data(&pub_data, true).is_err());
+    }
+
+    /// Checks that the decoding errors are kept in the error chain along with their location.
+    #[test]
+    fn test_decoding_errors() {
+        let err = get_rollup_ops_from_data(&[0xff, 0x00], false).unwrap_err();
+        assert!(err.is::<UnexpectedOperationType>());
+
+        let mut pub_data = ZkSyncOp::Noop(NoopOp {}).public_data();
+        pub_data.extend(ZkSyncOp::Noop(NoopOp {}).public_data());
+        *pub_data.last_mut().unwrap() = 0x01;
+        let err = get_rollup_ops_from_data(&pub_data, false).unwrap_err();
+        assert!(err.is::<PublicDataDecodeError>());
+        assert!(err
+            .to_string()
+            .contains(&format!("offset {}", pub_data.len() / 2)));
+    }
+
     #[test]
     fn test_part_exit() {
         let tx = Withdraw::new(
@@ -127,7 +190,7 @@ mod test {
             account_id: AccountId(3),
         }));
         let pub_data1 = op1.public_data();
-        let op2 = get_rollup_ops_from_data(&pub_data1)
+        let op2 = get_rollup_ops_from_data(&pub_data1, false)
             .expect("cant get ops from data")
             .pop()
             .expect("empty ops array");
@@ -147,7 +210,7 @@ mod test {
             withdraw_amount: Some(BigUint::from(444u32).into()),
         }));
         let pub_data1 = op1.public_data();
-        let op2 = get_rollup_ops_from_data(&pub_data1)
+        let op2 = get_rollup_ops_from_data(&pub_data1, false)
             .expect("cant get ops from data")
             .pop()
             .expect("empty ops array");
@@ -167,7 +230,7 @@ mod test {
             withdraw_amount: None,
         }));
         let pub_data1 = op1.public_data();
-        let op2 = get_rollup_ops_from_data(&pub_data1)
+        let op2 = get_rollup_ops_from_data(&pub_data1, false)
             .expect("cant get ops from data")
             .pop()
             .expect("empty ops array");
@@ -194,7 +257,7 @@ mod test {
             to: AccountId(12),
         }));
         let pub_data1 = op1.public_data();
-        let op2 = get_rollup_ops_from_data(&pub_data1)
+        let op2 = get_rollup_ops_from_data(&pub_data1, false)
             .expect("cant get ops from data")
             .pop()
             .expect("empty ops array");
@@ -221,7 +284,7 @@ mod test {
             to: AccountId(12),
         }));
         let pub_data1 = op1.public_data();
-        let op2 = get_rollup_ops_from_data(&pub_data1)
+        let op2 = get_rollup_ops_from_data(&pub_data1, false)
             .expect("cant get ops from data")
             .pop()
             .expect("empty ops array");
@@ -242,7 +305,7 @@ mod test {
             account_id: AccountId(11),
         }));
         let pub_data1 = op1.public_data();
-        let op2 = get_rollup_ops_from_data(&pub_data1)
+        let op2 = get_rollup_ops_from_data(&pub_data1, false)
             .expect("cant get ops from data")
             .pop()
             .expect("empty ops array");
@@ -268,7 +331,7 @@ mod test {
             account_id: AccountId(11),
         }));
         let pub_data1 = op1.public_data();
-        let op2 = get_rollup_ops_from_data(&pub_data1)
+        let op2 = get_rollup_ops_from_data(&pub_data1, false)
             .expect("cant get ops from data")
             .pop()
             .expect("empty ops array");
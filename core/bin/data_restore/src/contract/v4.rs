@@ -1,7 +1,7 @@
 use ethabi::{ParamType, Token};
 
 use crate::{contract::default::get_rollup_ops_from_data, rollup_ops::RollupOpsBlock};
-use zksync_types::{AccountId, BlockNumber, H256};
+use zksync_types::{pubdata_compression::decompress_pubdata, AccountId, BlockNumber, H256};
 
 fn decode_commitment_parameters(input_data: Vec<u8>) -> anyhow::Result<Vec<Token>> {
     let commit_operation = ParamType::Tuple(vec![
@@ -35,7 +35,10 @@ fn decode_commitment_parameters(input_data: Vec<u8>) -> anyhow::Result<Vec<Token
     })
 }
 
-pub fn rollup_ops_blocks_from_bytes(data: Vec<u8>) -> anyhow::Result<Vec<RollupOpsBlock>> {
+pub fn rollup_ops_blocks_from_bytes(
+    data: Vec<u8>,
+    strict_pubdata: bool,
+) -> anyhow::Result<Vec<RollupOpsBlock>> {
     let root_hash_argument_id = 0;
     let public_data_argument_id = 1;
     let timestamp_argument_id = 2;
@@ -84,7 +87,9 @@ pub fn rollup_ops_blocks_from_bytes(data: Vec<u8>) -> anyhow::Result<Vec<RollupO
                     &operation[op_block_number_argument_id],
                     &operation[timestamp_argument_id],
                 ) {
-                    let ops = get_rollup_ops_from_data(public_data.as_slice())?;
+                    // Blocks committed with the newer protocol versions have compressed pubdata.
+                    let public_data = decompress_pubdata(public_data)?;
+                    let ops = get_rollup_ops_from_data(public_data.as_slice(), strict_pubdata)?;
                     blocks.push(RollupOpsBlock {
                         block_num: BlockNumber(block_number.as_u32()),
                         ops,
@@ -152,7 +157,7 @@ mod test {
             00000000000000000000000000000000000000000000",
         )
         .expect("Failed to decode commit tx data");
-        let blocks = rollup_ops_blocks_from_bytes(input_data[4..].to_vec()).unwrap();
+        let blocks = rollup_ops_blocks_from_bytes(input_data[4..].to_vec(), false).unwrap();
         assert_eq!(blocks.len(), 1);
         let block = blocks[0].clone();
         assert_eq!(block.block_num, BlockNumber(25));
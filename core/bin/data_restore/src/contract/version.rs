@@ -43,11 +43,15 @@ impl ZkSyncContractVersion {
     pub fn rollup_ops_blocks_from_bytes(
         &self,
         data: Vec<u8>,
+        strict_pubdata: bool,
     ) -> anyhow::Result<Vec<RollupOpsBlock>> {
         use ZkSyncContractVersion::*;
         let res = match self {
-            V0 | V1 | V2 | V3 => vec![contract::default::rollup_ops_blocks_from_bytes(data)?],
-            V4 => contract::v4::rollup_ops_blocks_from_bytes(data)?,
+            V0 | V1 | V2 | V3 => vec![contract::default::rollup_ops_blocks_from_bytes(
+                data,
+                strict_pubdata,
+            )?],
+            V4 => contract::v4::rollup_ops_blocks_from_bytes(data, strict_pubdata)?,
         };
         Ok(res)
     }
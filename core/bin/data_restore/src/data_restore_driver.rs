@@ -8,7 +8,9 @@ use web3::{
 use zksync_contracts::{governance_contract, upgrade_gatekeeper};
 use zksync_crypto::Fr;
 
-use zksync_types::{AccountId, AccountMap, AccountUpdate, BlockNumber};
+use zksync_types::{
+    amount_bounds::AmountBounds, AccountId, AccountMap, AccountUpdate, BlockNumber,
+};
 // Local deps
 use crate::{
     contract::{get_genesis_account, ZkSyncDeployedContract},
@@ -70,6 +72,12 @@ pub struct DataRestoreDriver<T: Transport, I> {
     /// available in finite mode, and intended for tests.
     pub final_hash: Option<Fr>,
     pub available_block_chunk_sizes: Vec<usize>,
+    /// Strict pubdata flag. In strict mode, operations are re-encoded after decoding
+    /// and the blocks with non-canonical pubdata are rejected.
+    pub strict_pubdata: bool,
+    /// Sanity bounds of the amounts and fees, the operations exceeding them
+    /// are reported, but still applied.
+    pub amount_bounds: AmountBounds,
     phantom_data: PhantomData<I>,
 }
 
@@ -124,6 +132,8 @@ where
             final_hash,
             phantom_data: Default::default(),
             available_block_chunk_sizes,
+            strict_pubdata: false,
+            amount_bounds: AmountBounds::default(),
         }
     }
 
@@ -405,9 +415,23 @@ where
                 }
             }
 
-            let block = RollupOpsBlock::get_rollup_ops_blocks(&self.web3, &event)
-                .await
-                .expect("Cant get new operation blocks from events");
+            let block =
+                RollupOpsBlock::get_rollup_ops_blocks(&self.web3, &event, self.strict_pubdata)
+                    .await
+                    .expect("Cant get new operation blocks from events");
+            for ops_block in &block {
+                for op in &ops_block.ops {
+                    // The blocks are already committed on L1, so they must be restored as is:
+                    // refusing to apply one would stall the restore forever.
+                    if let Err(err) = op.check_amount_bounds(&self.amount_bounds) {
+                        vlog::error!(
+                            "Block #{} contains an operation out of the amount bounds: {}",
+                            *ops_block.block_num,
+                            err
+                        );
+                    }
+                }
+            }
             blocks.extend(block);
             last_event_tx_hash = Some(event.transaction_hash);
         }
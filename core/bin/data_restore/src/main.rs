@@ -4,7 +4,7 @@ use web3::transports::Http;
 use zksync_config::configs::{ChainConfig, ContractsConfig as EnvContractsConfig, ETHClientConfig};
 use zksync_crypto::convert::FeConvert;
 use zksync_storage::ConnectionPool;
-use zksync_types::{Address, H256};
+use zksync_types::{amount_bounds::AmountBounds, Address, H256};
 
 use web3::Web3;
 use zksync_data_restore::contract::ZkSyncDeployedContract;
@@ -44,6 +44,10 @@ struct Opt {
     /// Provides a path to the configuration file for data restore
     #[structopt(long = "config", name = "config")]
     config_path: Option<String>,
+
+    /// Rejects the blocks which pubdata is not the canonical encoding of the operations
+    #[structopt(long)]
+    strict_pubdata: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -53,6 +57,8 @@ pub struct ContractsConfig {
     genesis_tx_hash: H256,
     contract_addr: Address,
     available_block_chunk_sizes: Vec<usize>,
+    #[serde(default)]
+    amount_bounds: Vec<String>,
 }
 
 impl ContractsConfig {
@@ -72,6 +78,7 @@ impl ContractsConfig {
             genesis_tx_hash: contracts_opts.genesis_tx_hash,
             contract_addr: contracts_opts.contract_addr,
             available_block_chunk_sizes: chain_opts.state_keeper.block_chunk_sizes,
+            amount_bounds: chain_opts.mempool.amount_bounds,
         }
     }
 }
@@ -116,6 +123,9 @@ async fn main() {
         contract,
         config.available_block_chunk_sizes,
     );
+    driver.strict_pubdata = opt.strict_pubdata;
+    driver.amount_bounds =
+        AmountBounds::parse(&config.amount_bounds).expect("Invalid amount bounds provided");
 
     let mut interactor = DatabaseStorageInteractor::new(storage);
     // If genesis is argument is present - there will be fetching contracts creation transactions to get first eth block and genesis acc address
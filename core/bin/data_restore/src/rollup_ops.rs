@@ -2,7 +2,7 @@ use web3::{Transport, Web3};
 
 use zksync_types::operations::ZkSyncOp;
 
-use crate::contract;
+use crate::contract::{self, default::CommitmentShapeError};
 use crate::eth_tx_helpers::{get_ethereum_transaction, get_input_data_from_ethereum_transaction};
 use crate::events::BlockEvent;
 use zksync_types::{AccountId, BlockNumber, H256};
@@ -29,21 +29,75 @@ impl RollupOpsBlock {
     ///
     /// * `web3` - Web3 provider url
     /// * `event_data` - Rollup contract event description
-    ///
+    /// * `strict_pubdata` - Reject non-canonical encodings of the operations
     ///
     pub async fn get_rollup_ops_blocks<T: Transport>(
         web3: &Web3<T>,
         event_data: &BlockEvent,
+        strict_pubdata: bool,
     ) -> anyhow::Result<Vec<Self>> {
         let transaction = get_ethereum_transaction(web3, &event_data.transaction_hash).await?;
         let input_data = get_input_data_from_ethereum_transaction(&transaction)?;
-        let blocks = if let Ok(block) =
-            contract::default::rollup_ops_blocks_from_bytes(input_data.clone())
-        {
-            vec![block]
-        } else {
-            contract::v4::rollup_ops_blocks_from_bytes(input_data)?
-        };
-        Ok(blocks)
+        Self::from_input_data(input_data, strict_pubdata)
+    }
+
+    /// Decodes the blocks from the input data of the commitment transaction. The newer format
+    /// is tried only if the input doesn't have the shape of the legacy commitment: the errors
+    /// of decoding the legacy commitment, e.g. the rejected non-canonical pubdata, are returned as is.
+    pub fn from_input_data(input_data: Vec<u8>, strict_pubdata: bool) -> anyhow::Result<Vec<Self>> {
+        match contract::default::rollup_ops_blocks_from_bytes(input_data.clone(), strict_pubdata) {
+            Ok(block) => Ok(vec![block]),
+            Err(err) if err.is::<CommitmentShapeError>() => {
+                contract::v4::rollup_ops_blocks_from_bytes(input_data, strict_pubdata)
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{operations::PublicDataDecodeError, AccountId, Deposit, DepositOp, TokenId};
+
+    use super::*;
+
+    fn legacy_commitment(public_data: Vec<u8>) -> Vec<u8> {
+        ethabi::encode(&[
+            ethabi::Token::Uint(1.into()),
+            ethabi::Token::Uint(0.into()),
+            ethabi::Token::Array(vec![]),
+            ethabi::Token::Bytes(public_data),
+            ethabi::Token::Bytes(vec![]),
+            ethabi::Token::Array(vec![]),
+        ])
+    }
+
+    /// Checks that only the input not shaped as the legacy commitment is decoded as the newer one.
+    #[test]
+    fn commitment_format_fallback() {
+        let op = ZkSyncOp::Deposit(Box::new(DepositOp {
+            priority_op: Deposit {
+                from: Default::default(),
+                token: TokenId(1),
+                amount: 10u32.into(),
+                to: Default::default(),
+            },
+            account_id: AccountId(6),
+        }));
+        let mut public_data = op.public_data();
+        let blocks =
+            RollupOpsBlock::from_input_data(legacy_commitment(public_data.clone()), true).unwrap();
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].ops.len(), 1);
+
+        // Non-canonical pubdata of the legacy commitment is rejected by the legacy decoder.
+        *public_data.last_mut().unwrap() = 0x01;
+        let err =
+            RollupOpsBlock::from_input_data(legacy_commitment(public_data), true).unwrap_err();
+        assert!(err.is::<PublicDataDecodeError>());
+
+        // Input which is not the legacy commitment is decoded by the newer decoder.
+        let err = RollupOpsBlock::from_input_data(vec![0xff; 4], true).unwrap_err();
+        assert!(!err.is::<CommitmentShapeError>());
     }
 }
@@ -64,9 +64,9 @@ fn exit_circuit() -> impl Circuit<Engine> + Clone {
                     pub_key_hash: None,
                     address: None,
                 },
-                account_path: vec![None; params::account_tree_depth()],
+                account_path: vec![None; params::account_tree_depth()].into(),
                 balance_value: None,
-                balance_subtree_path: vec![None; params::balance_tree_depth()],
+                balance_subtree_path: vec![None; params::balance_tree_depth()].into(),
             },
         },
     }
@@ -105,9 +105,9 @@ fn zksync_circuit(block_chunks: usize) -> impl Circuit<Engine> + Clone {
                     pub_key_hash: None,
                     address: None,
                 },
-                account_path: vec![None; params::account_tree_depth()],
+                account_path: vec![None; params::account_tree_depth()].into(),
                 balance_value: None,
-                balance_subtree_path: vec![None; params::balance_tree_depth()],
+                balance_subtree_path: vec![None; params::balance_tree_depth()].into(),
             },
         },
         rhs: OperationBranch {
@@ -119,9 +119,9 @@ fn zksync_circuit(block_chunks: usize) -> impl Circuit<Engine> + Clone {
                     pub_key_hash: None,
                     address: None,
                 },
-                account_path: vec![None; params::account_tree_depth()],
+                account_path: vec![None; params::account_tree_depth()].into(),
                 balance_value: None,
-                balance_subtree_path: vec![None; params::balance_tree_depth()],
+                balance_subtree_path: vec![None; params::balance_tree_depth()].into(),
             },
         },
     };
@@ -7,16 +7,20 @@ fn main() {
     let data = hex::decode(&hex_data).expect("failed to decode hex");
 
     let mut unparsed_data = data.as_slice();
+    let mut offset = 0;
     while !unparsed_data.is_empty() {
         let op_type = unparsed_data[0];
-        let op_data_len = ZkSyncOp::public_data_length(op_type).expect("wrong op type");
+        let op_data_len = ZkSyncOp::public_data_length(op_type)
+            .unwrap_or_else(|err| panic!("{} 0x{:02x} at offset {}", err, op_type, offset));
         assert!(
             data.len() > op_data_len,
             "not enough bytes in the pubdata for current op"
         );
         let (current_op, unparsed) = unparsed_data.split_at(op_data_len);
-        let op = ZkSyncOp::from_public_data(&current_op).expect("failed to parse pubdata");
+        let op = ZkSyncOp::from_public_data(&current_op)
+            .unwrap_or_else(|err| panic!("failed to parse pubdata at offset {}: {}", offset, err));
         println!("{:#?}", op);
         unparsed_data = unparsed;
+        offset += op_data_len;
     }
 }
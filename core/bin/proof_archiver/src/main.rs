@@ -0,0 +1,246 @@
+//! Tool to export the verified blocks into the archives for the long-term audit retention,
+//! and to verify the exported archives.
+//!
+//! `export` writes an archive per verified block (see `zksync_types::proof_archive`) and records
+//! its hash in the `proof_archives` table. By default, the blocks verified since the last export
+//! are archived. The verification key of the block size is loaded from the keys directory and
+//! stored in the archive along with its hash and the keys version.
+//!
+//! `verify` needs neither the database, an Ethereum node nor the keys directory: the commitment
+//! is re-derived from the public data of the block, and the block proof is checked against the
+//! verification key stored in the archive. The hash and the version of the key are reported, so
+//! they can be compared with the keys the contract was deployed with. The result is printed as
+//! JSON, and the tool exits with an error if any of the archives is not valid.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, format_err};
+use serde::Serialize;
+use structopt::StructOpt;
+use zksync_prover_utils::{fs_utils::get_keys_version, verify_block_proof, PlonkVerificationKey};
+use zksync_storage::StorageProcessor;
+use zksync_types::{
+    proof_archive::{ArchivedAggregatedProof, ArchivedVerificationKey, ProofArchive},
+    BlockNumber, H256,
+};
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "zkSync proof archiver", author = "Matter Labs")]
+#[structopt(about = "Tool to export and verify the archives of the verified blocks")]
+enum Opt {
+    /// Exports the archives of the verified blocks.
+    Export {
+        /// First block to export, the block following the last exported one by default.
+        #[structopt(long)]
+        from: Option<u32>,
+        /// Last block to export, the last verified block by default.
+        #[structopt(long)]
+        to: Option<u32>,
+        /// Directory to write the archives to.
+        #[structopt(long, default_value = "proof-archives")]
+        out_dir: PathBuf,
+    },
+    /// Verifies the archives.
+    Verify {
+        /// Archive files to verify.
+        #[structopt(required = true)]
+        files: Vec<PathBuf>,
+        /// Only check the consistency of the archives, without verifying the block proofs.
+        #[structopt(long)]
+        skip_proof_verification: bool,
+    },
+}
+
+fn archive_file_name(block_number: BlockNumber) -> String {
+    format!("block-{:010}.proof-archive.json", *block_number)
+}
+
+async fn export_block(
+    storage: &mut StorageProcessor<'_>,
+    block_number: BlockNumber,
+    out_dir: &Path,
+) -> anyhow::Result<String> {
+    let block = storage
+        .chain()
+        .block_schema()
+        .get_block(block_number)
+        .await?
+        .ok_or_else(|| format_err!("Block #{} is not stored", *block_number))?;
+    let previous_block = storage
+        .chain()
+        .block_schema()
+        .get_block(BlockNumber(*block_number - 1))
+        .await?
+        .ok_or_else(|| format_err!("Block #{} is not stored", *block_number - 1))?;
+    let proof = storage
+        .prover_schema()
+        .load_proof(block_number)
+        .await?
+        .ok_or_else(|| format_err!("Proof of the block #{} is not stored", *block_number))?;
+    let aggregated_proof = storage
+        .prover_schema()
+        .load_aggregated_proof_for_block(block_number)
+        .await?
+        .map(|(first_block, last_block, proof)| ArchivedAggregatedProof {
+            first_block,
+            last_block,
+            proof: proof.serialize_aggregated_proof(),
+        });
+    let verification_key =
+        PlonkVerificationKey::read_verification_key_for_main_circuit(block.block_chunks_size)
+            .map_err(|err| {
+                format_err!(
+                    "Verification key for the block size {} can't be loaded: {}",
+                    block.block_chunks_size,
+                    err
+                )
+            })?;
+    let verification_key =
+        ArchivedVerificationKey::new(get_keys_version(), verification_key.to_bytes());
+
+    let archive = ProofArchive::new(
+        &block,
+        previous_block.get_eth_encoded_root(),
+        proof,
+        verification_key,
+        aggregated_proof,
+    );
+    archive.check()?;
+    let bytes = archive.to_json();
+
+    // The archive is renamed once it's written, so an interrupted export doesn't leave
+    // a truncated archive behind.
+    let file_name = archive_file_name(block_number);
+    let tmp_path = out_dir.join(format!("{}.tmp", file_name));
+    fs::write(&tmp_path, &bytes)?;
+    fs::rename(&tmp_path, out_dir.join(&file_name))?;
+
+    storage
+        .proof_archives_schema()
+        .record_archive(block_number, &file_name, ProofArchive::archive_hash(&bytes))
+        .await?;
+    Ok(file_name)
+}
+
+async fn export(from: Option<u32>, to: Option<u32>, out_dir: PathBuf) -> anyhow::Result<()> {
+    let mut storage = StorageProcessor::establish_connection().await?;
+    let last_verified_block = storage
+        .chain()
+        .block_schema()
+        .get_last_verified_confirmed_block()
+        .await?;
+
+    let from = match from {
+        Some(from) => BlockNumber(from),
+        None => storage
+            .proof_archives_schema()
+            .last_archived_block()
+            .await?
+            .map(|block| block + 1)
+            .unwrap_or(BlockNumber(1)),
+    };
+    let to = to.map(BlockNumber).unwrap_or(last_verified_block);
+    if to > last_verified_block {
+        bail!(
+            "Block #{} is not verified yet, the last verified block is #{}",
+            *to,
+            *last_verified_block
+        );
+    }
+    if from > to || *from == 0 {
+        println!("There are no blocks to export");
+        return Ok(());
+    }
+
+    fs::create_dir_all(&out_dir)?;
+    for block_number in *from..=*to {
+        let file_name = export_block(&mut storage, BlockNumber(block_number), &out_dir).await?;
+        println!("Block #{} is exported to {}", block_number, file_name);
+    }
+    Ok(())
+}
+
+/// Result of the archive verification.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VerificationReport {
+    file: PathBuf,
+    /// Hash of the archive file, to compare with the hash recorded by the server.
+    archive_hash: Option<H256>,
+    block_number: Option<BlockNumber>,
+    /// Hash of the `StoredBlockInfo` kept by the contract for the block.
+    stored_block_hash: Option<H256>,
+    /// Version of the circuit keys the verification key belongs to.
+    key_version: Option<String>,
+    /// Hash of the verification key the block proof is checked with.
+    verification_key_hash: Option<H256>,
+    /// Whether the block proof is valid, unless its verification is skipped.
+    proof_verified: Option<bool>,
+    error: Option<String>,
+}
+
+fn verify_archive(report: &mut VerificationReport, verify_proof: bool) -> anyhow::Result<()> {
+    let bytes = fs::read(&report.file)?;
+    report.archive_hash = Some(ProofArchive::archive_hash(&bytes));
+    let archive = ProofArchive::from_json(&bytes)?;
+    report.block_number = Some(archive.block_number);
+    archive.check()?;
+    report.stored_block_hash = Some(archive.stored_block_info.hash());
+    report.key_version = Some(archive.verification_key.key_version.clone());
+    report.verification_key_hash = Some(archive.verification_key.key_hash);
+
+    if verify_proof {
+        let vk = PlonkVerificationKey::from_bytes(&archive.verification_key.key)
+            .map_err(|err| format_err!("Verification key can't be decoded: {}", err))?;
+        let verified = verify_block_proof(&archive.proof, &vk)?;
+        report.proof_verified = Some(verified);
+        if !verified {
+            bail!("Block proof is not valid");
+        }
+    }
+    Ok(())
+}
+
+fn verify(files: Vec<PathBuf>, skip_proof_verification: bool) -> anyhow::Result<()> {
+    let mut reports = Vec::new();
+    for file in files {
+        let mut report = VerificationReport {
+            file,
+            archive_hash: None,
+            block_number: None,
+            stored_block_hash: None,
+            key_version: None,
+            verification_key_hash: None,
+            proof_verified: None,
+            error: None,
+        };
+        if let Err(err) = verify_archive(&mut report, !skip_proof_verification) {
+            report.error = Some(err.to_string());
+        }
+        reports.push(report);
+    }
+    println!("{}", serde_json::to_string_pretty(&reports)?);
+
+    let failed = reports
+        .iter()
+        .filter(|report| report.error.is_some())
+        .count();
+    if failed > 0 {
+        bail!("{} of {} archives are not valid", failed, reports.len());
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    match Opt::from_args() {
+        Opt::Export { from, to, out_dir } => export(from, to, out_dir).await,
+        Opt::Verify {
+            files,
+            skip_proof_verification,
+        } => verify(files, skip_proof_verification),
+    }
+}
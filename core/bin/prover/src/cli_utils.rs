@@ -6,7 +6,7 @@ use structopt::StructOpt;
 use zksync_config::configs::ProverConfig as EnvProverConfig;
 use zksync_utils::{get_env, parse_env};
 // Local deps
-use crate::{client, prover_work_cycle, ProverConfig, ProverImpl, ShutdownRequest};
+use crate::{client, grpc_client, prover_work_cycle, ProverConfig, ProverImpl, ShutdownRequest};
 
 fn api_client_from_env() -> client::ApiClient {
     let server_api_url = parse_env("API_PROVER_URL");
@@ -15,6 +15,13 @@ fn api_client_from_env() -> client::ApiClient {
     client::ApiClient::new(&server_api_url, request_timout, &secret)
 }
 
+fn grpc_api_client_from_env() -> grpc_client::GrpcApiClient {
+    let server_api_url = get_env("API_PROVER_GRPC_URL");
+    let secret = get_env("API_PROVER_SECRET_AUTH");
+    grpc_client::GrpcApiClient::new(&server_api_url, &secret)
+        .expect("Failed to create the prover gRPC client")
+}
+
 #[derive(StructOpt)]
 #[structopt(
     name = "zkSync operator node",
@@ -37,7 +44,6 @@ where
     // used env
     let prover_options = EnvProverConfig::from_env();
     let prover_config = <PROVER as ProverImpl>::Config::from_env();
-    let api_client = api_client_from_env();
     let prover = PROVER::create_from_config(prover_config);
 
     let _sentry_guard = vlog::init();
@@ -66,12 +72,26 @@ where
         .expect("Failed to register ctrlc handler");
     }
 
-    prover_work_cycle(
-        prover,
-        api_client,
-        shutdown_request,
-        prover_options,
-        &worker_name,
-    )
-    .await;
+    if prover_options.prover.use_grpc {
+        vlog::info!("talking to the prover server over gRPC");
+        let api_client = grpc_api_client_from_env();
+        prover_work_cycle(
+            prover,
+            api_client,
+            shutdown_request,
+            prover_options,
+            &worker_name,
+        )
+        .await;
+    } else {
+        let api_client = api_client_from_env();
+        prover_work_cycle(
+            prover,
+            api_client,
+            shutdown_request,
+            prover_options,
+            &worker_name,
+        )
+        .await;
+    }
 }
@@ -1,17 +1,71 @@
 // Built-in deps
-use std::time::Duration;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 // External deps
 use anyhow::format_err;
 use backoff::future::FutureOperation;
 use backoff::Error::{Permanent, Transient};
 use futures::Future;
-use reqwest::Url;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, CONTENT_TYPE},
+    Url,
+};
 use vlog::*;
 // Workspace deps
 use crate::auth_utils::AuthTokenGenerator;
-use zksync_prover_utils::api::{
-    ProverInputRequest, ProverInputResponse, ProverOutputRequest, ProverStopped, WorkingOn,
+use zksync_prover_utils::{
+    api::{
+        ProverInputRequest, ProverInputResponse, ProverOutputRequest, ProverStopped, ReleasedJob,
+        WorkingOn,
+    },
+    encoding::PayloadEncoding,
 };
+use zksync_types::{
+    protocol_version::{COMPONENTS_PROTOCOL_VERSION, PROTOCOL_VERSION_HEADER},
+    prover::ProofProgress,
+};
+
+/// Repeats the function execution on the exponential backoff principle.
+pub(crate) async fn with_retries<I, E, Fn, Fut>(operation: Fn) -> anyhow::Result<I>
+where
+    Fn: FnMut() -> Fut,
+    Fut: Future<Output = Result<I, backoff::Error<E>>>,
+    E: std::fmt::Display,
+{
+    let notify = |err, next_after: Duration| {
+        let duration_secs = next_after.as_millis() as f32 / 1000.0f32;
+
+        log::warn!(
+            "Failed to reach server err: <{}>, retrying after: {:.1}s",
+            err,
+            duration_secs,
+        )
+    };
+
+    operation
+        .retry_notify(get_backoff(), notify)
+        .await
+        .map_err(|e| {
+            format_err!(
+                "Prover can't reach server, for the max elapsed time of the backoff: {}",
+                e
+            )
+        })
+}
+
+/// Returns default prover options for backoff configuration.
+fn get_backoff() -> backoff::ExponentialBackoff {
+    backoff::ExponentialBackoff {
+        current_interval: Duration::from_secs(1),
+        initial_interval: Duration::from_secs(1),
+        multiplier: 1.5f64,
+        max_interval: Duration::from_secs(10),
+        max_elapsed_time: Some(Duration::from_secs(2 * 60)),
+        ..Default::default()
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct ApiClient {
@@ -19,10 +73,14 @@ pub struct ApiClient {
     working_on_url: Url,
     publish_url: Url,
     stopped_url: Url,
+    release_job_url: Url,
     // Client keeps connection pool inside, so it is recommended to reuse it (see docstring for reqwest::Client).
     http_client: reqwest::Client,
     // A generator that create the authentication token upon request to any endpoint.
     auth_token_generator: AuthTokenGenerator,
+    // Encoding of the last received job, the proofs are published with the same encoding,
+    // since it's known to be supported by the server.
+    publish_encoding: Arc<Mutex<PayloadEncoding>>,
 }
 
 impl ApiClient {
@@ -41,48 +99,43 @@ impl ApiClient {
             working_on_url: base_url.join("/working_on").unwrap(),
             publish_url: base_url.join("/publish").unwrap(),
             stopped_url: base_url.join("/stopped").unwrap(),
+            release_job_url: base_url.join("/release_job").unwrap(),
             http_client,
             auth_token_generator,
+            publish_encoding: Default::default(),
         }
     }
 
-    /// Repeats the function execution on the exponential backoff principle.
-    async fn with_retries<I, E, Fn, Fut>(&self, operation: Fn) -> anyhow::Result<I>
-    where
-        Fn: FnMut() -> Fut,
-        Fut: Future<Output = Result<I, backoff::Error<E>>>,
-        E: std::fmt::Display,
-    {
-        let notify = |err, next_after: Duration| {
-            let duration_secs = next_after.as_millis() as f32 / 1000.0f32;
-
-            log::warn!(
-                "Failed to reach server err: <{}>, retrying after: {:.1}s",
-                err,
-                duration_secs,
-            )
-        };
-
-        operation
-            .retry_notify(Self::get_backoff(), notify)
-            .await
-            .map_err(|e| {
-                format_err!(
-                    "Prover can't reach server, for the max elapsed time of the backoff: {}",
-                    e
-                )
+    /// Returns the protocol version header and the headers linking the server-side handling
+    /// of the request to the current span.
+    fn request_headers() -> HeaderMap {
+        let mut headers: HeaderMap = vlog::trace_context_headers(&vlog::Span::current())
+            .into_iter()
+            .filter_map(|(name, value)| {
+                Some((
+                    HeaderName::from_bytes(name.as_bytes()).ok()?,
+                    HeaderValue::from_str(&value).ok()?,
+                ))
             })
+            .collect();
+        headers.insert(
+            PROTOCOL_VERSION_HEADER,
+            HeaderValue::from(COMPONENTS_PROTOCOL_VERSION),
+        );
+        headers
     }
 
-    /// Returns default prover options for backoff configuration.
-    fn get_backoff() -> backoff::ExponentialBackoff {
-        backoff::ExponentialBackoff {
-            current_interval: Duration::from_secs(1),
-            initial_interval: Duration::from_secs(1),
-            multiplier: 1.5f64,
-            max_interval: Duration::from_secs(10),
-            max_elapsed_time: Some(Duration::from_secs(2 * 60)),
-            ..Default::default()
+    /// Checks the errors of the response which can't be fixed by retrying the request.
+    fn check_response_status(
+        response: &reqwest::Response,
+    ) -> Result<(), backoff::Error<anyhow::Error>> {
+        match response.status() {
+            reqwest::StatusCode::UNAUTHORIZED => Err(Permanent(format_err!("authorization error"))),
+            reqwest::StatusCode::CONFLICT => Err(Permanent(format_err!(
+                "protocol version {} is not supported by the prover server",
+                COMPONENTS_PROTOCOL_VERSION
+            ))),
+            _ => Ok(()),
         }
     }
 
@@ -103,55 +156,76 @@ impl crate::ApiClient for ApiClient {
                 .http_client
                 .get(self.get_job_url.clone())
                 .bearer_auth(&self.get_encoded_token()?)
+                .headers(Self::request_headers())
+                .header(ACCEPT, PayloadEncoding::accept_header())
                 .json(&req)
                 .send()
                 .await
                 .map_err(|e| format_err!("failed to send get job request: {}", e))?;
 
-            if response.status() == reqwest::StatusCode::UNAUTHORIZED {
-                return Err(Permanent(format_err!("authorization error")));
-            }
+            Self::check_response_status(&response)?;
 
-            response
-                .json()
+            let content_type = response
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_owned);
+            let encoding =
+                PayloadEncoding::from_content_type(content_type.as_deref()).map_err(Transient)?;
+            let body = response
+                .bytes()
                 .await
-                .map_err(|e| Transient(format_err!("failed parse json on get job request: {}", e)))
+                .map_err(|e| Transient(format_err!("failed to read get job response: {}", e)))?;
+            let job = encoding
+                .decode(&body)
+                .map_err(|e| Transient(format_err!("failed to parse get job response: {}", e)))?;
+
+            *self.publish_encoding.lock().unwrap() = encoding;
+            Ok(job)
         });
 
-        self.with_retries(operation).await
+        with_retries(operation).await
     }
 
-    async fn working_on(&self, job_id: i32, prover_name: &str) -> anyhow::Result<()> {
+    async fn working_on(
+        &self,
+        job_id: i32,
+        prover_name: &str,
+        progress: Option<ProofProgress>,
+    ) -> anyhow::Result<()> {
         let operation = (|| async {
             log::trace!(
-                "sending working_on job_id: {}, prover_name: {}",
+                "sending working_on job_id: {}, prover_name: {}, progress: {:?}",
                 job_id,
-                prover_name
+                prover_name,
+                progress
             );
 
             let response = self
                 .http_client
                 .post(self.working_on_url.clone())
                 .bearer_auth(&self.get_encoded_token()?)
+                .headers(Self::request_headers())
                 .json(&WorkingOn {
                     job_id,
                     prover_name: prover_name.to_string(),
+                    progress,
                 })
                 .send()
                 .await
                 .map_err(|e| Transient(format_err!("failed to send working_on request: {}", e)))?;
 
-            if response.status() == reqwest::StatusCode::UNAUTHORIZED {
-                return Err(Permanent(format_err!("authorization error")));
-            }
+            Self::check_response_status(&response)?;
 
             Ok(())
         });
 
-        self.with_retries(operation).await
+        with_retries(operation).await
     }
 
     async fn publish(&self, data: ProverOutputRequest) -> anyhow::Result<()> {
+        let encoding = *self.publish_encoding.lock().unwrap();
+        let body = encoding.encode(&data)?;
         let operation = (|| async {
             log::trace!("Trying publish proof: {:?}", data);
 
@@ -159,19 +233,19 @@ impl crate::ApiClient for ApiClient {
                 .http_client
                 .post(self.publish_url.clone())
                 .bearer_auth(&self.get_encoded_token()?)
-                .json(&data)
+                .headers(Self::request_headers())
+                .header(CONTENT_TYPE, encoding.content_type())
+                .body(body.clone())
                 .send()
                 .await
                 .map_err(|e| Transient(format_err!("failed to send publish request: {}", e)))?;
 
-            if response.status() == reqwest::StatusCode::UNAUTHORIZED {
-                return Err(Permanent(format_err!("authorization error")));
-            }
+            Self::check_response_status(&response)?;
 
             Ok(())
         });
 
-        self.with_retries(operation).await
+        with_retries(operation).await
     }
 
     async fn prover_stopped(&self, prover_name: String) -> anyhow::Result<()> {
@@ -180,6 +254,7 @@ impl crate::ApiClient for ApiClient {
                 .http_client
                 .post(self.stopped_url.clone())
                 .bearer_auth(&self.get_encoded_token()?)
+                .headers(Self::request_headers())
                 .json(&ProverStopped {
                     prover_name: prover_name.clone(),
                 })
@@ -189,13 +264,34 @@ impl crate::ApiClient for ApiClient {
                     Transient(format_err!("failed to send prover_stopped request: {}", e))
                 })?;
 
-            if response.status() == reqwest::StatusCode::UNAUTHORIZED {
-                return Err(Permanent(format_err!("authorization error")));
-            }
+            Self::check_response_status(&response)?;
+
+            Ok(())
+        });
+
+        with_retries(operation).await
+    }
+
+    async fn release_job(&self, job_id: i32, prover_name: &str) -> anyhow::Result<()> {
+        let operation = (|| async {
+            let response = self
+                .http_client
+                .post(self.release_job_url.clone())
+                .bearer_auth(&self.get_encoded_token()?)
+                .headers(Self::request_headers())
+                .json(&ReleasedJob {
+                    prover_name: prover_name.to_string(),
+                    job_id,
+                })
+                .send()
+                .await
+                .map_err(|e| Transient(format_err!("failed to send release_job request: {}", e)))?;
+
+            Self::check_response_status(&response)?;
 
             Ok(())
         });
 
-        self.with_retries(operation).await
+        with_retries(operation).await
     }
 }
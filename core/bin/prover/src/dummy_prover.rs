@@ -1,9 +1,10 @@
-use crate::{ProverConfig, ProverImpl};
+use crate::{ProgressReporter, ProverConfig, ProverImpl};
 use anyhow::Error;
 use zksync_config::ZkSyncConfig;
 use zksync_crypto::proof::PrecomputedSampleProofs;
 use zksync_prover_utils::api::{JobRequestData, JobResultData};
 use zksync_prover_utils::fs_utils::load_precomputed_proofs;
+use zksync_types::prover::ProofProgress;
 
 #[derive(Debug)]
 pub struct DummyProverConfig {
@@ -37,7 +38,12 @@ impl ProverImpl for DummyProver {
         }
     }
 
-    fn create_proof(&self, data: JobRequestData) -> Result<JobResultData, Error> {
+    fn create_proof(
+        &self,
+        data: JobRequestData,
+        progress: &ProgressReporter,
+    ) -> Result<JobResultData, Error> {
+        progress.report(ProofProgress::WitnessBuilt);
         let empty_proof = match data {
             JobRequestData::AggregatedBlockProof(single_proofs) => {
                 let mut aggregated_proof = self.precomputed_proofs.aggregated_proof.clone();
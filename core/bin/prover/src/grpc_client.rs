@@ -0,0 +1,253 @@
+// Built-in deps
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+// External deps
+use anyhow::format_err;
+use backoff::Error::{Permanent, Transient};
+use futures::stream;
+use tonic::{
+    transport::{Channel, Endpoint},
+    Code, Request, Status,
+};
+// Workspace deps
+use crate::{auth_utils::AuthTokenGenerator, client::with_retries};
+use zksync_prover_utils::{
+    api::{ProverInputRequest, ProverInputResponse, ProverOutputRequest},
+    encoding::PayloadEncoding,
+    grpc::{
+        proof_parts,
+        proto::{
+            prover_client::ProverClient, JobRequest, ProofHeader, ReleaseJobRequest,
+            StoppedRequest, WorkingOnRequest,
+        },
+        receive_parts, AUTHORIZATION_METADATA,
+    },
+};
+use zksync_types::{
+    protocol_version::{COMPONENTS_PROTOCOL_VERSION, PROTOCOL_VERSION_HEADER},
+    prover::ProofProgress,
+    BlockNumber,
+};
+
+/// Client of the prover server gRPC interface.
+///
+/// Unlike the HTTP client, the requests have no overall timeout: the job data and the proofs
+/// are streamed in chunks, so the transfer of the big payloads doesn't fail while it progresses.
+#[derive(Debug, Clone)]
+pub struct GrpcApiClient {
+    client: ProverClient<Channel>,
+    // A generator that create the authentication token upon request to any endpoint.
+    auth_token_generator: AuthTokenGenerator,
+    // Encoding of the last received job, the proofs are published with the same encoding.
+    publish_encoding: Arc<Mutex<PayloadEncoding>>,
+}
+
+impl GrpcApiClient {
+    // The time for which the authorization token will be valid.
+    const AUTH_TOKEN_LIFETIME: Duration = Duration::from_secs(10);
+
+    pub fn new(url: &str, secret: &str) -> anyhow::Result<Self> {
+        // The connection is established once the first request is sent,
+        // so the prover may be started before the server.
+        let channel = Endpoint::from_shared(url.to_owned())?.connect_lazy()?;
+        let auth_token_generator =
+            AuthTokenGenerator::new(secret.to_string(), Self::AUTH_TOKEN_LIFETIME);
+        Ok(Self {
+            client: ProverClient::new(channel),
+            auth_token_generator,
+            publish_encoding: Default::default(),
+        })
+    }
+
+    /// Attaches the authentication token and the protocol version to the request.
+    fn request<T>(&self, message: T) -> anyhow::Result<Request<T>> {
+        let token = self
+            .auth_token_generator
+            .encode()
+            .map_err(|e| format_err!("failed generate authorization token: {}", e))?;
+
+        let mut request = Request::new(message);
+        let metadata = request.metadata_mut();
+        metadata.insert(AUTHORIZATION_METADATA, format!("Bearer {}", token).parse()?);
+        metadata.insert(
+            PROTOCOL_VERSION_HEADER,
+            COMPONENTS_PROTOCOL_VERSION.to_string().parse()?,
+        );
+        Ok(request)
+    }
+
+    /// Converts the error status, the ones which can't be fixed by retrying the request
+    /// are permanent.
+    fn status_error(status: Status) -> backoff::Error<anyhow::Error> {
+        match status.code() {
+            Code::Unauthenticated => Permanent(format_err!("authorization error")),
+            Code::FailedPrecondition => Permanent(format_err!(
+                "protocol version {} is not supported by the prover server",
+                COMPONENTS_PROTOCOL_VERSION
+            )),
+            Code::AlreadyExists | Code::InvalidArgument => {
+                Permanent(format_err!("request is rejected: {}", status.message()))
+            }
+            _ => Transient(format_err!("gRPC request failed: {}", status)),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::ApiClient for GrpcApiClient {
+    async fn get_job(&self, req: ProverInputRequest) -> anyhow::Result<ProverInputResponse> {
+        let aux_data = serde_json::to_vec(&req.aux_data)?;
+        let accepted_content_types: Vec<_> = PayloadEncoding::ALL
+            .iter()
+            .map(|encoding| encoding.content_type().to_owned())
+            .collect();
+        let operation = (|| async {
+            log::trace!("get prover job over gRPC");
+
+            let request = self.request(JobRequest {
+                prover_name: req.prover_name.clone(),
+                aux_data: aux_data.clone(),
+                accepted_content_types: accepted_content_types.clone(),
+            })?;
+            let parts = self
+                .client
+                .clone()
+                .assign_job(request)
+                .await
+                .map_err(Self::status_error)?
+                .into_inner();
+            let job = receive_parts(parts).await.map_err(Self::status_error)?;
+
+            let response = match job {
+                Some((header, data)) => {
+                    // The servers unaware of the binary encodings leave the content type empty.
+                    let content_type =
+                        Some(header.content_type.as_str()).filter(|value| !value.is_empty());
+                    let encoding =
+                        PayloadEncoding::from_content_type(content_type).map_err(Permanent)?;
+                    let data = encoding.decode(&data).map_err(|e| {
+                        Permanent(format_err!("failed to parse the job data: {}", e))
+                    })?;
+                    *self.publish_encoding.lock().unwrap() = encoding;
+
+                    ProverInputResponse {
+                        job_id: header.job_id,
+                        first_block: BlockNumber(header.first_block),
+                        last_block: BlockNumber(header.last_block),
+                        data: Some(data),
+                        protocol_version: Some(header.protocol_version),
+                    }
+                }
+                None => ProverInputResponse {
+                    job_id: 0,
+                    first_block: BlockNumber(0),
+                    last_block: BlockNumber(0),
+                    data: None,
+                    protocol_version: Some(COMPONENTS_PROTOCOL_VERSION),
+                },
+            };
+            Ok(response)
+        });
+
+        with_retries(operation).await
+    }
+
+    async fn working_on(
+        &self,
+        job_id: i32,
+        prover_name: &str,
+        progress: Option<ProofProgress>,
+    ) -> anyhow::Result<()> {
+        let progress_json = match progress {
+            Some(progress) => serde_json::to_vec(&progress)?,
+            None => Vec::new(),
+        };
+        let operation = (|| async {
+            log::trace!(
+                "sending working_on job_id: {}, prover_name: {}, progress: {:?}",
+                job_id,
+                prover_name,
+                progress
+            );
+
+            let request = self.request(WorkingOnRequest {
+                prover_name: prover_name.to_string(),
+                job_id,
+                progress: progress_json.clone(),
+            })?;
+            self.client
+                .clone()
+                .working_on(request)
+                .await
+                .map_err(Self::status_error)?;
+
+            Ok(())
+        });
+
+        with_retries(operation).await
+    }
+
+    async fn publish(&self, data: ProverOutputRequest) -> anyhow::Result<()> {
+        let encoding = *self.publish_encoding.lock().unwrap();
+        let payload = encoding.encode(&data.data)?;
+        let operation = (|| async {
+            log::trace!("Trying publish proof for job {} over gRPC", data.job_id);
+
+            let header = ProofHeader {
+                job_id: data.job_id,
+                first_block: *data.first_block,
+                last_block: *data.last_block,
+                protocol_version: data.protocol_version.unwrap_or(COMPONENTS_PROTOCOL_VERSION),
+                content_type: encoding.content_type().to_owned(),
+            };
+            let parts = stream::iter(proof_parts(header, payload.clone()));
+            let request = self.request(parts)?;
+            self.client
+                .clone()
+                .publish_proof(request)
+                .await
+                .map_err(Self::status_error)?;
+
+            Ok(())
+        });
+
+        with_retries(operation).await
+    }
+
+    async fn prover_stopped(&self, prover_name: String) -> anyhow::Result<()> {
+        let operation = (|| async {
+            let request = self.request(StoppedRequest {
+                prover_name: prover_name.clone(),
+            })?;
+            self.client
+                .clone()
+                .stopped(request)
+                .await
+                .map_err(Self::status_error)?;
+
+            Ok(())
+        });
+
+        with_retries(operation).await
+    }
+
+    async fn release_job(&self, job_id: i32, prover_name: &str) -> anyhow::Result<()> {
+        let operation = (|| async {
+            let request = self.request(ReleaseJobRequest {
+                prover_name: prover_name.to_string(),
+                job_id,
+            })?;
+            self.client
+                .clone()
+                .release_job(request)
+                .await
+                .map_err(Self::status_error)?;
+
+            Ok(())
+        });
+
+        with_retries(operation).await
+    }
+}
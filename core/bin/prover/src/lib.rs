@@ -2,10 +2,14 @@ pub mod auth_utils;
 pub mod cli_utils;
 pub mod client;
 pub mod dummy_prover;
+pub mod grpc_client;
 pub mod plonk_step_by_step_prover;
 
 // Built-in deps
-use futures::{pin_mut, FutureExt};
+use futures::{
+    channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    pin_mut, FutureExt, StreamExt,
+};
 use std::fmt::Debug;
 use std::sync::{
     atomic::{AtomicBool, AtomicI32, Ordering},
@@ -13,6 +17,7 @@ use std::sync::{
 };
 use std::time::Duration;
 use tokio::sync::oneshot;
+use vlog::Instrument;
 // External deps
 use zksync_crypto::rand::{
     distributions::{IndependentSample, Range},
@@ -24,6 +29,10 @@ use zksync_prover_utils::api::{
     JobRequestData, JobResultData, ProverInputRequest, ProverInputRequestAuxData,
     ProverInputResponse, ProverOutputRequest,
 };
+use zksync_types::{
+    protocol_version::{check_protocol_version, COMPONENTS_PROTOCOL_VERSION},
+    prover::ProofProgress,
+};
 
 const ABSENT_PROVER_ID: i32 = -1;
 
@@ -66,6 +75,28 @@ impl ShutdownRequest {
     }
 }
 
+/// Reporter of the proof generation progress. The reported stages are sent to the prover
+/// server along with the heartbeats, so the progress of the job is visible to the operator.
+#[derive(Debug, Clone)]
+pub struct ProgressReporter {
+    sender: UnboundedSender<ProofProgress>,
+}
+
+impl ProgressReporter {
+    /// Creates the reporter along with the receiver of the reported stages.
+    pub fn new() -> (Self, UnboundedReceiver<ProofProgress>) {
+        let (sender, receiver) = unbounded();
+        (Self { sender }, receiver)
+    }
+
+    /// Reports the stage of the proof generation.
+    pub fn report(&self, progress: ProofProgress) {
+        vlog::debug!("Proof generation progress: {:?}", progress);
+        // The receiver is dropped once the job is finished, the progress is no longer needed then.
+        self.sender.unbounded_send(progress).unwrap_or_default();
+    }
+}
+
 /// Trait that provides type needed by prover to initialize.
 pub trait ProverConfig {
     fn from_env() -> Self;
@@ -83,27 +114,41 @@ pub trait ProverImpl {
         Default::default()
         // TODO: Add the ability to define different config (ZKS-283).
     }
-    /// Resource heavy operation
-    fn create_proof(&self, data: JobRequestData) -> anyhow::Result<JobResultData>;
+    /// Resource heavy operation, reports the stages of the proof generation to the `progress`.
+    fn create_proof(
+        &self,
+        data: JobRequestData,
+        progress: &ProgressReporter,
+    ) -> anyhow::Result<JobResultData>;
 }
 #[async_trait::async_trait]
 pub trait ApiClient: Debug {
     async fn get_job(&self, req: ProverInputRequest) -> anyhow::Result<ProverInputResponse>;
-    async fn working_on(&self, job_id: i32, prover_name: &str) -> anyhow::Result<()>;
+    async fn working_on(
+        &self,
+        job_id: i32,
+        prover_name: &str,
+        progress: Option<ProofProgress>,
+    ) -> anyhow::Result<()>;
     async fn publish(&self, data: ProverOutputRequest) -> anyhow::Result<()>;
     async fn prover_stopped(&self, prover_name: String) -> anyhow::Result<()>;
+    /// Returns the job the prover can't prove to the server, so it's given to another prover.
+    async fn release_job(&self, job_id: i32, prover_name: &str) -> anyhow::Result<()>;
 }
 
 async fn compute_proof_no_blocking<PROVER>(
     prover: PROVER,
     data: JobRequestData,
+    progress: ProgressReporter,
 ) -> anyhow::Result<(PROVER, JobResultData)>
 where
     PROVER: ProverImpl + Send + Sync + 'static,
 {
     let (result_sender, result_receiver) = oneshot::channel();
     std::thread::spawn(move || {
-        let prover_with_proof = prover.create_proof(data).map(|proof| (prover, proof));
+        let prover_with_proof = prover
+            .create_proof(data, &progress)
+            .map(|proof| (prover, proof));
         result_sender.send(prover_with_proof).unwrap_or_default();
     });
     result_receiver.await?
@@ -111,14 +156,17 @@ where
 
 /// Endlessly sends requests to the server, in case of not receiving a response
 /// notifies about it in the logs, but does not quit.
+/// The heartbeats carry the last reported progress, and the progress changes are sent at once.
 async fn heartbeat_future_handle<CLIENT>(
     client: CLIENT,
     prover_name: &str,
     job_id: i32,
     heartbeat_interval: Duration,
+    mut progress_receiver: UnboundedReceiver<ProofProgress>,
 ) where
     CLIENT: 'static + Sync + Send + ApiClient,
 {
+    let mut progress = None;
     loop {
         let timeout_value = {
             let between = Range::new(0.8f64, 2.0);
@@ -126,12 +174,20 @@ async fn heartbeat_future_handle<CLIENT>(
             let random_multiplier = between.ind_sample(&mut rng);
             Duration::from_secs((heartbeat_interval.as_secs_f64() * random_multiplier) as u64)
         };
-        tokio::time::delay_for(timeout_value).await;
-
-        vlog::info!("Starting sending heartbeats for job with ID: {}", job_id);
+        let timeout = tokio::time::delay_for(timeout_value).fuse();
+        pin_mut!(timeout);
+        futures::select! {
+            _ = timeout => {
+                vlog::info!("Starting sending heartbeats for job with ID: {}", job_id);
+            },
+            // Once the prover is done, the stream is terminated and the branch is not selected.
+            new_progress = progress_receiver.select_next_some() => {
+                progress = Some(new_progress);
+            },
+        }
 
         client
-            .working_on(job_id, &prover_name)
+            .working_on(job_id, &prover_name, progress)
             .await
             .map_err(|e| vlog::warn!("Failed to send heartbeat: {}", e))
             .unwrap_or_default();
@@ -177,12 +233,22 @@ pub async fn prover_work_cycle<PROVER, CLIENT>(
             data: job_data,
             first_block,
             last_block,
+            protocol_version,
         } = prover_input_response;
         let job_data = if let Some(job_data) = job_data {
             job_data
         } else {
             continue;
         };
+        // The proof of the job created by the server of the incompatible version may be
+        // invalid, so the job is left for the other provers.
+        if let Err(err) = check_protocol_version(protocol_version) {
+            vlog::error!("Can't prove job {}: {}", job_id, err);
+            if let Err(err) = client.release_job(job_id, prover_name).await {
+                vlog::warn!("Failed to release job {}: {}", job_id, err);
+            }
+            continue;
+        }
 
         vlog::info!(
             "got job id: {}, blocks: [{}, {}]",
@@ -191,14 +257,27 @@ pub async fn prover_work_cycle<PROVER, CLIENT>(
             last_block
         );
 
+        // Requests to the prover server are sent within this span, so the
+        // server-side handling of them is attached to the same trace.
+        let span = vlog::info_span!(
+            "prove_blocks",
+            job_id,
+            first_block = %first_block,
+            last_block = %last_block
+        );
+        let (progress_reporter, progress_receiver) = ProgressReporter::new();
         let heartbeat_future_handle = heartbeat_future_handle(
             client.clone(),
             prover_name,
             job_id,
             prover_options.prover.heartbeat_interval(),
+            progress_receiver,
         )
+        .instrument(span.clone())
         .fuse();
-        let compute_proof_future = compute_proof_no_blocking(prover, job_data).fuse();
+        let compute_proof_future = compute_proof_no_blocking(prover, job_data, progress_reporter)
+            .instrument(span.clone())
+            .fuse();
 
         pin_mut!(heartbeat_future_handle, compute_proof_future);
 
@@ -216,13 +295,23 @@ pub async fn prover_work_cycle<PROVER, CLIENT>(
         };
         prover = ret_prover;
 
+        // The final stage is reported explicitly, since the heartbeats are no longer sent.
+        client
+            .working_on(job_id, prover_name, Some(ProofProgress::Done))
+            .instrument(span.clone())
+            .await
+            .map_err(|e| vlog::warn!("Failed to report the proof progress: {}", e))
+            .unwrap_or_default();
+
         client
             .publish(ProverOutputRequest {
                 job_id,
                 first_block,
                 last_block,
                 data: proof,
+                protocol_version: Some(COMPONENTS_PROTOCOL_VERSION),
             })
+            .instrument(span)
             .await
             .map_err(|e| vlog::warn!("Failed to publish proof: {}", e))
             .unwrap_or_default();
@@ -7,9 +7,10 @@ use zksync_crypto::Engine;
 use zksync_prover_utils::aggregated_proofs::{gen_aggregate_proof, prepare_proof_data};
 use zksync_prover_utils::api::{JobRequestData, JobResultData};
 use zksync_prover_utils::{PlonkVerificationKey, SetupForStepByStepProver};
+use zksync_types::prover::ProofProgress;
 use zksync_utils::parse_env;
 // Local deps
-use crate::{ProverConfig, ProverImpl};
+use crate::{ProgressReporter, ProverConfig, ProverImpl};
 use zksync_prover_utils::fs_utils::load_precomputed_proofs;
 
 /// We prepare some data before making proof for each block size, so we cache it in case next block
@@ -54,6 +55,7 @@ impl PlonkStepByStepProver {
         &self,
         witness: zksync_circuit::circuit::ZkSyncCircuit<'_, Engine>,
         block_size: usize,
+        progress: &ProgressReporter,
     ) -> anyhow::Result<SingleProof> {
         // we do this way here so old precomp is dropped
         let valid_cached_precomp = {
@@ -63,17 +65,21 @@ impl PlonkStepByStepProver {
                 .take()
                 .filter(|p| p.block_size == block_size)
         };
+        // The setup of the cached computations is already done, so its stages are not reported.
         let precomp = if let Some(precomp) = valid_cached_precomp {
             precomp
         } else {
-            let setup = SetupForStepByStepProver::prepare_setup_for_step_by_step_prover(
-                witness.clone(),
-                self.config.download_setup_from_network,
-            )?;
+            let setup =
+                SetupForStepByStepProver::prepare_setup_for_step_by_step_prover_with_progress(
+                    witness.clone(),
+                    self.config.download_setup_from_network,
+                    |stage| progress.report(stage),
+                )?;
             PreparedComputations { block_size, setup }
         };
 
         let vk = PlonkVerificationKey::read_verification_key_for_main_circuit(block_size)?;
+        progress.report(ProofProgress::Proving);
         let verified_proof = precomp
             .setup
             .gen_step_by_step_proof_using_prepared_setup(witness, &vk)?;
@@ -86,6 +92,7 @@ impl PlonkStepByStepProver {
     fn create_aggregated_block_proof(
         &self,
         proofs: Vec<(SingleProof, usize)>,
+        progress: &ProgressReporter,
     ) -> anyhow::Result<AggregatedProof> {
         // drop setup cache
         {
@@ -123,6 +130,9 @@ impl PlonkStepByStepProver {
             .collect();
 
         let (vks, proof_data) = prepare_proof_data(&self.config.all_block_sizes, padded_proofs);
+        progress.report(ProofProgress::WitnessBuilt);
+        // The setup of the recursive circuit is prepared and the circuit is proven in one go.
+        progress.report(ProofProgress::Proving);
         gen_aggregate_proof(
             vks,
             proof_data,
@@ -135,7 +145,11 @@ impl PlonkStepByStepProver {
 impl ProverImpl for PlonkStepByStepProver {
     type Config = PlonkStepByStepProverConfig;
 
-    fn create_proof(&self, data: JobRequestData) -> Result<JobResultData, anyhow::Error> {
+    fn create_proof(
+        &self,
+        data: JobRequestData,
+        progress: &ProgressReporter,
+    ) -> Result<JobResultData, anyhow::Error> {
         let proof = match data {
             JobRequestData::AggregatedBlockProof(proofs_to_aggregate) => {
                 let block_sizes = proofs_to_aggregate
@@ -143,7 +157,7 @@ impl ProverImpl for PlonkStepByStepProver {
                     .map(|(_, s)| *s)
                     .collect::<Vec<_>>();
 
-                let aggregate_proof = self.create_aggregated_block_proof(proofs_to_aggregate).map_err(|e| {
+                let aggregate_proof = self.create_aggregated_block_proof(proofs_to_aggregate, progress).map_err(|e| {
                     anyhow::format_err!("Failed to aggregate block proofs, num proofs: {}, block sizes: {:?}, err {}", block_sizes.len(), &block_sizes, e)
                 })?;
 
@@ -151,8 +165,9 @@ impl ProverImpl for PlonkStepByStepProver {
             }
             JobRequestData::BlockProof(zksync_circuit, block_size) => {
                 let zksync_circuit = zksync_circuit.into_circuit();
+                progress.report(ProofProgress::WitnessBuilt);
                 let proof = self
-                    .create_single_block_proof(zksync_circuit, block_size)
+                    .create_single_block_proof(zksync_circuit, block_size, progress)
                     .map_err(|e| {
                         anyhow::format_err!(
                             "Failed to create single block proof, block size: {}, err: {}",
@@ -26,8 +26,11 @@ use zksync_prover_utils::api::{
     JobRequestData, ProverInputRequest, ProverInputResponse, ProverOutputRequest,
 };
 use zksync_types::{
-    block::smallest_block_size_for_chunks, operations::DepositOp, Account, AccountId, Address,
-    BlockNumber, Deposit, TokenId,
+    block::smallest_block_size_for_chunks,
+    operations::DepositOp,
+    protocol_version::{COMPONENTS_PROTOCOL_VERSION, MIN_COMPATIBLE_PROTOCOL_VERSION},
+    prover::ProofProgress,
+    Account, AccountId, Address, BlockNumber, Deposit, TokenId,
 };
 
 /// Set of different parameters needed for the prover to work
@@ -249,14 +252,96 @@ async fn test_publishing_proof() {
     };
 }
 
+/// Checks that the jobs of the incompatible protocol version are released instead of being proved.
+#[tokio::test]
+async fn test_releasing_incompatible_job() {
+    let MockProverConfigs {
+        plonk_config: _,
+        dummy_config,
+        prover_options,
+        shutdown_request,
+        prover_name,
+    } = MockProverConfigs::default();
+
+    let prover = DummyProver::create_from_config(dummy_config);
+    let client = MockApiClient {
+        incompatible_jobs: true,
+        ..Default::default()
+    };
+
+    let prover_work_cycle = zksync_prover::prover_work_cycle(
+        prover,
+        client.clone(),
+        shutdown_request.clone(),
+        prover_options.clone(),
+        &prover_name,
+    )
+    .fuse();
+    let timeout = tokio::time::delay_for(Duration::from_secs(10)).fuse();
+
+    pin_mut!(prover_work_cycle, timeout);
+
+    futures::select! {
+        _ = prover_work_cycle => panic!("prover work ended too quickly"),
+        _ = timeout => {
+            shutdown_request.set();
+            assert_eq!(client.released_jobs.lock().await.first(), Some(&0));
+            assert!(client.published_prof.lock().await.is_empty());
+        },
+    };
+}
+
+#[tokio::test]
+async fn test_reporting_progress() {
+    let MockProverConfigs {
+        plonk_config: _,
+        dummy_config,
+        prover_options,
+        shutdown_request,
+        prover_name,
+    } = MockProverConfigs::default();
+
+    let prover = DummyProver::create_from_config(dummy_config);
+    let client = MockApiClient::default();
+
+    let prover_work_cycle = zksync_prover::prover_work_cycle(
+        prover,
+        client.clone(),
+        shutdown_request.clone(),
+        prover_options.clone(),
+        &prover_name,
+    )
+    .fuse();
+    let timeout = tokio::time::delay_for(Duration::from_secs(10)).fuse();
+
+    pin_mut!(prover_work_cycle, timeout);
+
+    futures::select! {
+        _ = prover_work_cycle => panic!("prover work ended too quickly"),
+        _ = timeout => {
+            shutdown_request.set();
+            // The completion is reported before the proof is published.
+            let progress = client.progress.lock().await.get(&0).cloned().unwrap_or_default();
+            assert_eq!(progress.last(), Some(&ProofProgress::Done));
+            assert!(client.published_prof.lock().await.get(&0).is_some());
+        },
+    };
+}
+
 #[derive(Debug, Clone, Default)]
 struct MockApiClient {
     /// All published proofs are saved by `job_id`.
     published_prof: Arc<Mutex<HashMap<i32, ProverOutputRequest>>>,
     /// Received heartbeats from `self.working_on()`.
     working_on: Arc<Mutex<HashMap<i32, String>>>,
+    /// Progress reported along with the heartbeats.
+    progress: Arc<Mutex<HashMap<i32, Vec<ProofProgress>>>>,
     /// `gob_id` of the last work that has not yet been submitted.
     last_job_id: Arc<Mutex<i32>>,
+    /// Whether the jobs are created by the server of the incompatible protocol version.
+    incompatible_jobs: bool,
+    /// Jobs released by the prover.
+    released_jobs: Arc<Mutex<Vec<i32>>>,
 }
 
 #[async_trait::async_trait]
@@ -269,16 +354,34 @@ impl zksync_prover::ApiClient for MockApiClient {
             first_block: BlockNumber(1),
             last_block: BlockNumber(1),
             data: Some(test_data_for_prover()),
+            protocol_version: if self.incompatible_jobs {
+                Some(MIN_COMPATIBLE_PROTOCOL_VERSION - 1)
+            } else {
+                Some(COMPONENTS_PROTOCOL_VERSION)
+            },
         };
 
         Ok(response)
     }
 
-    async fn working_on(&self, job_id: i32, prover_name: &str) -> anyhow::Result<()> {
+    async fn working_on(
+        &self,
+        job_id: i32,
+        prover_name: &str,
+        progress: Option<ProofProgress>,
+    ) -> anyhow::Result<()> {
         self.working_on
             .lock()
             .await
             .insert(job_id, prover_name.to_string());
+        if let Some(progress) = progress {
+            self.progress
+                .lock()
+                .await
+                .entry(job_id)
+                .or_default()
+                .push(progress);
+        }
 
         Ok(())
     }
@@ -292,4 +395,10 @@ impl zksync_prover::ApiClient for MockApiClient {
     async fn prover_stopped(&self, _: String) -> anyhow::Result<()> {
         Ok(())
     }
+
+    async fn release_job(&self, job_id: i32, _: &str) -> anyhow::Result<()> {
+        self.released_jobs.lock().await.push(job_id);
+
+        Ok(())
+    }
 }
@@ -1,21 +1,31 @@
-use futures::{channel::mpsc, executor::block_on, SinkExt, StreamExt};
-use std::cell::RefCell;
+use futures::{channel::mpsc, StreamExt};
+use std::{path::PathBuf, time::Duration};
 use structopt::StructOpt;
 use zksync_api::run_api;
-use zksync_core::{genesis_init, run_core, wait_for_tasks};
+use zksync_core::{
+    audit_replay::run_audit_replay, genesis_init, leader_election::Leadership, run_core,
+    snapshot_sync::run_snapshot_sync, wait_for_tasks,
+};
 use zksync_eth_client::EthereumGateway;
-use zksync_eth_sender::run_eth_sender;
+use zksync_eth_sender::{run_eth_sender, run_shadow_eth_sender_if_enabled};
 use zksync_forced_exit_requests::run_forced_exit_requests_actors;
 use zksync_gateway_watcher::run_gateway_watcher_if_multiplexed;
 use zksync_prometheus_exporter::run_prometheus_exporter;
 use zksync_witness_generator::run_prover_server;
 
-use zksync_config::ZkSyncConfig;
+use zksync_config::{ConfigReloader, ZkSyncConfig};
 use zksync_storage::ConnectionPool;
+use zksync_tree_cache::TreeCacheStorage;
+use zksync_utils::{shutdown::ShutdownCoordinator, supervisor::Supervisor};
+
+/// Time given to the actors to finish their work after the shutdown is requested.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(60);
 
 #[derive(Debug, Clone, Copy)]
 pub enum ServerCommand {
     Genesis,
+    AuditReplay,
+    SnapshotSync,
     Launch,
 }
 
@@ -25,15 +35,31 @@ struct Opt {
     /// Generate genesis block for the first contract deployment
     #[structopt(long)]
     genesis: bool,
+    /// Re-execute all the stored blocks from genesis, check the state roots against the stored
+    /// and L1-committed ones, and print the report signed by the operator key
+    #[structopt(long)]
+    audit_replay: bool,
+    /// Initialize the empty database with the state snapshot served by the given server instance
+    #[structopt(long)]
+    snapshot_sync: Option<String>,
+    /// Directory the snapshot chunks are downloaded to, so the interrupted sync can be resumed
+    #[structopt(long, default_value = "snapshot")]
+    snapshot_dir: PathBuf,
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let opt = Opt::from_args();
     let config = ZkSyncConfig::from_env();
+    config.validate()?;
     let mut _sentry_guard = None;
     let server_mode = if opt.genesis {
         ServerCommand::Genesis
+    } else if opt.audit_replay {
+        ServerCommand::AuditReplay
+    } else if opt.snapshot_sync.is_some() {
+        _sentry_guard = vlog::init();
+        ServerCommand::SnapshotSync
     } else {
         _sentry_guard = vlog::init();
         ServerCommand::Launch
@@ -45,6 +71,21 @@ async fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
+    if let ServerCommand::AuditReplay = server_mode {
+        vlog::info!("Performing the audit replay of the stored blocks");
+        let report = run_audit_replay(&config).await?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        anyhow::ensure!(report.report.passed, "Audit replay found divergences");
+        return Ok(());
+    }
+
+    if let ServerCommand::SnapshotSync = server_mode {
+        let peer_url = opt.snapshot_sync.unwrap();
+        vlog::info!("Syncing the state snapshot from {}", peer_url);
+        run_snapshot_sync(&config, &peer_url, &opt.snapshot_dir).await?;
+        return Ok(());
+    }
+
     // It's a `ServerCommand::Launch`, perform the usual routine.
     vlog::info!("Running the zkSync server");
 
@@ -53,21 +94,63 @@ async fn main() -> anyhow::Result<()> {
 
     let gateway_watcher_task_opt = run_gateway_watcher_if_multiplexed(eth_gateway.clone(), &config);
 
-    // Handle Ctrl+C
+    // Panicked actors notify about themselves via the stop signal channel,
+    // while Ctrl+C and SIGTERM start the graceful shutdown.
     let (stop_signal_sender, mut stop_signal_receiver) = mpsc::channel(256);
+    let shutdown = ShutdownCoordinator::new();
+    let mut shutdown_signal = shutdown.signal();
     {
-        let stop_signal_sender = RefCell::new(stop_signal_sender.clone());
-        ctrlc::set_handler(move || {
-            let mut sender = stop_signal_sender.borrow_mut();
-            block_on(sender.send(true)).expect("Ctrl+C signal send");
-        })
-        .expect("Error setting Ctrl+C handler");
+        let shutdown_trigger = shutdown.trigger();
+        ctrlc::set_handler(move || shutdown_trigger.trigger())
+            .expect("Error setting Ctrl+C handler");
     }
 
+    // Failed actors are restarted by the supervisor.
+    let supervisor = Supervisor::default();
+
+    // Apply the reloadable config values on SIGHUP.
+    let config_reloader = ConfigReloader::new(&config);
+    config_reloader.clone().reload_on_sighup();
+
     // Run prometheus data exporter.
     let (prometheus_task_handle, counter_task_handle) =
         run_prometheus_exporter(connection_pool.clone(), config.api.prometheus.port, true);
 
+    // Run API actors.
+    vlog::info!("Starting the API server actors");
+    let mut api_task_handle = run_api(
+        connection_pool.clone(),
+        stop_signal_sender.clone(),
+        eth_gateway.clone(),
+        &config,
+        &config_reloader,
+        &supervisor,
+    );
+
+    // In the active/standby mode, the components changing the state are run by the leader only,
+    // while the standby instance serves the read API until it takes over the leadership.
+    let leadership_opt = if config.failover.enabled {
+        vlog::info!("Waiting for the leadership");
+        tokio::select! {
+            leadership = Leadership::acquire(&config.failover) => Some(leadership),
+            _ = &mut api_task_handle => {
+                panic!("API server actors aren't supposed to finish their execution")
+            },
+            _ = stop_signal_receiver.next() => {
+                vlog::warn!("Stop signal received, shutting down");
+                return Ok(());
+            }
+            _ = shutdown_signal.recv() => {
+                vlog::warn!("Termination signal received, shutting down");
+                return Ok(());
+            }
+        }
+    } else {
+        None
+    };
+    let fencing_token = leadership_opt.as_ref().map(Leadership::fencing_token);
+    let mut leadership_watch_opt = leadership_opt.map(Leadership::watch);
+
     // Run core actors.
     vlog::info!("Starting the Core actors");
     let core_task_handles = run_core(
@@ -75,32 +158,45 @@ async fn main() -> anyhow::Result<()> {
         stop_signal_sender.clone(),
         eth_gateway.clone(),
         &config,
+        fencing_token,
+        &shutdown,
+        &supervisor,
     )
     .await
     .expect("Unable to start Core actors");
 
-    // Run API actors.
-    vlog::info!("Starting the API server actors");
-    let api_task_handle = run_api(
+    // Run Ethereum sender actors.
+    vlog::info!("Starting the Ethereum sender actors");
+    let eth_sender_task_handle = run_eth_sender(
         connection_pool.clone(),
-        stop_signal_sender.clone(),
         eth_gateway.clone(),
-        &config,
+        config.clone(),
+        &config_reloader,
+        &shutdown,
+        &supervisor,
     );
-
-    // Run Ethereum sender actors.
-    vlog::info!("Starting the Ethereum sender actors");
-    let eth_sender_task_handle =
-        run_eth_sender(connection_pool.clone(), eth_gateway.clone(), config.clone());
+    let shadow_eth_sender_task_opt = run_shadow_eth_sender_if_enabled(
+        connection_pool.clone(),
+        &config,
+        &config_reloader,
+        &shutdown,
+        &supervisor,
+    )
+    .await
+    .expect("Unable to start the shadow Ethereum Sender actors");
 
     // Run prover server & witness generator.
     vlog::info!("Starting the Prover server actors");
-    let database = zksync_witness_generator::database::Database::new(connection_pool.clone());
+    let tree_cache = TreeCacheStorage::open(&config.db, zksync_tree_cache::WITNESS_GENERATOR)
+        .expect("Unable to open the account tree cache storage");
+    let database =
+        zksync_witness_generator::database::Database::new(connection_pool.clone(), tree_cache);
     run_prover_server(database, stop_signal_sender, ZkSyncConfig::from_env());
 
     vlog::info!("Starting the ForcedExitRequests actors");
     let forced_exit_requests_task_handle = run_forced_exit_requests_actors(connection_pool, config);
 
+    let mut leadership_lost = false;
     tokio::select! {
         _ = async { wait_for_tasks(core_task_handles).await } => {
             // We don't need to do anything here, since Core actors will panic upon future resolving.
@@ -111,9 +207,17 @@ async fn main() -> anyhow::Result<()> {
         _ = async { gateway_watcher_task_opt.unwrap().await }, if gateway_watcher_task_opt.is_some() => {
             panic!("Gateway Watcher actors aren't supposed to finish their execution")
         }
+        _ = async { leadership_watch_opt.as_mut().unwrap().lost().await }, if leadership_watch_opt.is_some() => {
+            // The new leader may already be running, so the actors are stopped right away.
+            vlog::error!("Leadership is lost, shutting down");
+            leadership_lost = true;
+        }
         _ = async { eth_sender_task_handle.await } => {
             panic!("Ethereum Sender actors aren't supposed to finish their execution")
         },
+        _ = async { shadow_eth_sender_task_opt.unwrap().await }, if shadow_eth_sender_task_opt.is_some() => {
+            panic!("Shadow Ethereum Sender actors aren't supposed to finish their execution")
+        },
         _ = async { prometheus_task_handle.await } => {
             panic!("Prometheus exporter actors aren't supposed to finish their execution")
         },
@@ -126,7 +230,21 @@ async fn main() -> anyhow::Result<()> {
         _ = async { stop_signal_receiver.next().await } => {
             vlog::warn!("Stop signal received, shutting down");
         }
+        _ = shutdown_signal.recv() => {
+            vlog::warn!("Termination signal received, waiting for the actors to finish their work");
+            let not_drained = shutdown.wait_drained(SHUTDOWN_TIMEOUT).await;
+            if !not_drained.is_empty() {
+                vlog::error!("Actors {:?} did not finish their work in time", not_drained);
+            }
+        }
     };
 
+    // Let the standby instance take over right away rather than once the connection is closed.
+    if let Some(leadership_watch) = leadership_watch_opt {
+        if !leadership_lost {
+            leadership_watch.release().await;
+        }
+    }
+
     Ok(())
 }
@@ -0,0 +1,70 @@
+use std::path::Path;
+use structopt::StructOpt;
+use zksync_config::ZkSyncConfig;
+use zksync_storage::StorageProcessor;
+use zksync_tree_cache::RocksDbTreeCache;
+use zksync_types::BlockNumber;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "zkSync account tree cache migrator", author = "Matter Labs")]
+#[structopt(about = "Tool to move the account tree caches from Postgres to RocksDB")]
+struct Opt {
+    /// Remove the caches from Postgres once they're copied, except for the genesis one.
+    #[structopt(long)]
+    clean_postgres: bool,
+}
+
+// TODO: don't use anyhow (ZKS-588)
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opt = Opt::from_args();
+    let config = ZkSyncConfig::from_env();
+    let mut storage = StorageProcessor::establish_connection().await?;
+
+    // The components only load the latest cache, so there is no need to copy the older ones.
+    let (block, tree_cache) = match storage
+        .chain()
+        .block_schema()
+        .get_account_tree_cache()
+        .await?
+    {
+        Some(latest_cache) => latest_cache,
+        None => {
+            println!("There are no account tree caches in Postgres");
+            return Ok(());
+        }
+    };
+    println!(
+        "Latest account tree cache in Postgres is for block {}",
+        block
+    );
+
+    for component in zksync_tree_cache::COMPONENTS {
+        let path = Path::new(&config.db.tree_cache_path).join(component);
+        let cache = RocksDbTreeCache::open(&path, config.db.tree_cache_size_mb)?;
+        match cache.load_latest()? {
+            Some((cached_block, _)) if cached_block >= block => {
+                println!(
+                    "RocksDB cache of {} already has block {}, skipping",
+                    component, cached_block
+                );
+            }
+            _ => {
+                cache.store(block, &tree_cache)?;
+                println!("Cache is copied to {}", path.display());
+            }
+        }
+    }
+
+    if opt.clean_postgres {
+        storage
+            .chain()
+            .block_schema()
+            .remove_account_tree_cache(BlockNumber(0))
+            .await?;
+        println!("`account_tree_cache` table is cleaned");
+    }
+
+    println!("Set `DATABASE_TREE_CACHE_BACKEND=rocksdb` and restart the server and witness generator to use the copied caches");
+    Ok(())
+}
@@ -16,6 +16,7 @@ fn generate_transactions(number: usize) -> IncomingTxBatchForFee {
         tx_types,
         addresses,
         token_like: TokenLike::Symbol("wBTC".to_string()),
+        sender: None,
     }
 }
 
@@ -33,6 +34,7 @@ fn get_txs_fee(client: Client, url: String) {
         tx_type: TxFeeTypes::Withdraw,
         address: Address::random(),
         token_like: TokenLike::Symbol("wBTC".to_string()),
+        sender: None,
     };
 
     let res = client
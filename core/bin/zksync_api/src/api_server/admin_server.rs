@@ -10,15 +10,35 @@ use actix_web_httpauth::extractors::{
     AuthenticationError,
 };
 use actix_web_httpauth::middleware::HttpAuthentication;
+use chrono::{DateTime, Utc};
 use futures::channel::mpsc;
 use jsonwebtoken::errors::Error as JwtError;
 use jsonwebtoken::{decode, DecodingKey, Validation};
 use serde::{Deserialize, Serialize};
 
 // Local uses
+use zksync_config::{ConfigReloader, ReloadableParams};
+use zksync_crypto::rand::{thread_rng, Rng};
+use zksync_eth_client::EthereumGateway;
 use zksync_storage::ConnectionPool;
-use zksync_types::{tokens, Address, TokenId};
-use zksync_utils::panic_notify::ThreadPanicNotify;
+use zksync_types::{
+    aggregated_operations::AggregatedActionType,
+    api_namespace::{ApiNamespace, ApiNamespaceLimits, CreatedApiNamespace},
+    deposit_refund::DepositRefundStatus,
+    fee_refund::FeeRefundStatus,
+    fee_sweep::FeeSweepStatus,
+    key_audit::OperatorKey,
+    revenue::RevenuePeriod,
+    tokens,
+    webhooks::{WebhookEventType, WebhookSubscriptionId},
+    Address, BlockNumber, SerialId, TokenId, U256,
+};
+use zksync_utils::{
+    panic_notify::ThreadPanicNotify,
+    supervisor::{HealthReport, Supervisor},
+};
+
+use crate::utils::token_db_cache::TokenDBCache;
 
 #[derive(Debug, Serialize, Deserialize)]
 struct PayloadAuthToken {
@@ -32,6 +52,9 @@ struct PayloadAuthToken {
 struct AppState {
     secret_auth: String,
     connection_pool: ConnectionPool,
+    config_reloader: ConfigReloader,
+    supervisor: Supervisor,
+    eth_gateway: EthereumGateway,
 }
 
 impl AppState {
@@ -57,6 +80,117 @@ struct AddTokenRequest {
     pub decimals: u8,
 }
 
+/// Corrected metadata of the already added token.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct UpdateTokenRequest {
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+/// Changes of the token flags, omitted flags are left intact.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct UpdateTokenFlagsRequest {
+    pub disabled: Option<bool>,
+    pub fee_eligible: Option<bool>,
+}
+
+/// Subscription of the URL to the webhook notifications.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct AddWebhookRequest {
+    pub url: String,
+    pub event_type: WebhookEventType,
+    /// If set, only the notifications related to this address are sent.
+    pub address: Option<Address>,
+    /// Secret used to sign the notification payloads.
+    pub secret: String,
+    /// API namespace the subscription belongs to.
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct WebhooksQuery {
+    /// Namespace to load the subscriptions of, all the subscriptions by default.
+    pub namespace: Option<String>,
+}
+
+/// Namespace of the API serving a white-label frontend.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct AddNamespaceRequest {
+    pub name: String,
+    #[serde(flatten)]
+    pub limits: ApiNamespaceLimits,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+struct WebhookDeliveriesQuery {
+    pub limit: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+struct RevenueQuery {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Period to group the revenue by, hourly by default.
+    pub period: Option<RevenuePeriod>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+struct KeyAuditQuery {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Key to export the usage of, all the keys by default.
+    pub key: Option<OperatorKey>,
+    /// ID of the last entry of the previous page.
+    pub after: Option<i64>,
+    pub limit: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+struct DepositRefundsQuery {
+    /// Status of the refunds to load, flagged by default.
+    pub status: Option<DepositRefundStatus>,
+    pub limit: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+struct FeeSweepsQuery {
+    /// Status of the sweeps to load, all sweeps by default.
+    pub status: Option<FeeSweepStatus>,
+    pub limit: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+struct FeeRefundsQuery {
+    /// Status of the refunds to load, all refunds by default.
+    pub status: Option<FeeRefundStatus>,
+    pub limit: u32,
+}
+
+/// Status of the server aggregated for the operator dashboards.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct StatusOverview {
+    /// Last block created by the state keeper.
+    pub last_saved_block: BlockNumber,
+    /// Last block which commit is confirmed on Ethereum.
+    pub last_committed_block: BlockNumber,
+    /// Last block which execution is confirmed on Ethereum.
+    pub last_verified_block: BlockNumber,
+    pub mempool_txs: u32,
+    pub pending_prover_jobs: u32,
+    /// Aggregated operations which are not sent to Ethereum yet.
+    pub unprocessed_eth_operations: u32,
+    /// Aggregated operations sent to Ethereum but not confirmed yet.
+    pub unconfirmed_eth_operations: u32,
+    /// Balance of the account sending the operator transactions to Ethereum,
+    /// missing if the Ethereum node is unavailable.
+    pub operator_balance: Option<U256>,
+    pub gas_price_limit: U256,
+    pub health: HealthReport,
+}
+
 struct AuthTokenValidator<'a> {
     decoding_key: DecodingKey<'a>,
 }
@@ -122,10 +256,600 @@ async fn add_token(
             vlog::warn!("failed add token to database in progress request: {}", e);
             actix_web::error::ErrorInternalServerError("storage layer error")
         })?;
+    TokenDBCache::invalidate_all();
+
+    Ok(HttpResponse::Ok().json(token))
+}
+
+/// Fixes the symbol and decimals of the token. Address and ID of the token can't be changed,
+/// since they're already used in the transactions.
+async fn update_token(
+    data: web::Data<AppState>,
+    token_id: web::Path<u16>,
+    request: web::Json<UpdateTokenRequest>,
+) -> actix_web::Result<HttpResponse> {
+    let token_id = TokenId(token_id.into_inner());
+    let mut storage = data.access_storage().await?;
+
+    let updated = storage
+        .tokens_schema()
+        .update_token_metadata(token_id, &request.symbol, request.decimals)
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed update token in database in progress request: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+    if !updated {
+        return Err(actix_web::error::ErrorNotFound("token not found"));
+    }
+    TokenDBCache::invalidate_all();
+    vlog::info!(
+        "Token {} metadata is updated: symbol {}, decimals {}",
+        *token_id,
+        request.symbol,
+        request.decimals
+    );
 
+    let token = storage
+        .tokens_schema()
+        .get_token(token_id.into())
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed load token from database in progress request: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
     Ok(HttpResponse::Ok().json(token))
 }
 
+/// Disables the token or changes its fee eligibility.
+async fn update_token_flags(
+    data: web::Data<AppState>,
+    token_id: web::Path<u16>,
+    request: web::Json<UpdateTokenFlagsRequest>,
+) -> actix_web::Result<HttpResponse> {
+    let token_id = TokenId(token_id.into_inner());
+    let mut storage = data.access_storage().await?;
+
+    let token = storage
+        .tokens_schema()
+        .get_token(token_id.into())
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed load token from database in progress request: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+    if token.is_none() {
+        return Err(actix_web::error::ErrorNotFound("token not found"));
+    }
+
+    let mut flags = storage
+        .tokens_schema()
+        .get_token_flags(token_id)
+        .await
+        .map_err(|e| {
+            vlog::warn!(
+                "failed load token flags from database in progress request: {}",
+                e
+            );
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+    if let Some(disabled) = request.disabled {
+        flags.disabled = disabled;
+    }
+    if let Some(fee_eligible) = request.fee_eligible {
+        flags.fee_eligible = fee_eligible;
+    }
+
+    storage
+        .tokens_schema()
+        .store_token_flags(token_id, flags)
+        .await
+        .map_err(|e| {
+            vlog::warn!(
+                "failed store token flags in database in progress request: {}",
+                e
+            );
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+    TokenDBCache::invalidate_all();
+    vlog::info!("Token {} flags are updated: {:?}", *token_id, flags);
+
+    Ok(HttpResponse::Ok().json(flags))
+}
+
+/// Subscribes the URL to the webhook notifications.
+async fn add_webhook(
+    data: web::Data<AppState>,
+    request: web::Json<AddWebhookRequest>,
+) -> actix_web::Result<HttpResponse> {
+    if reqwest::Url::parse(&request.url).is_err() {
+        return Err(actix_web::error::ErrorBadRequest("invalid webhook url"));
+    }
+    if request.secret.is_empty() {
+        return Err(actix_web::error::ErrorBadRequest("webhook secret is empty"));
+    }
+
+    let mut storage = data.access_storage().await?;
+    if let Some(namespace) = &request.namespace {
+        let namespaces = storage
+            .api_namespaces_schema()
+            .load_namespaces()
+            .await
+            .map_err(|e| {
+                vlog::warn!("failed load api namespaces from database: {}", e);
+                actix_web::error::ErrorInternalServerError("storage layer error")
+            })?;
+        if !namespaces
+            .iter()
+            .any(|existing| &existing.name == namespace)
+        {
+            return Err(actix_web::error::ErrorBadRequest("unknown namespace"));
+        }
+    }
+    let subscription = storage
+        .webhooks_schema()
+        .add_subscription(
+            &request.url,
+            request.event_type,
+            request.address,
+            &request.secret,
+            request.namespace.as_deref(),
+        )
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed add webhook to database in progress request: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+    vlog::info!(
+        "Webhook {} is subscribed to {} notifications",
+        subscription.url,
+        subscription.event_type
+    );
+
+    Ok(HttpResponse::Ok().json(subscription))
+}
+
+/// Returns the webhook subscriptions, optionally only the ones of the namespace.
+async fn webhooks(
+    data: web::Data<AppState>,
+    query: web::Query<WebhooksQuery>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let mut subscriptions = storage
+        .webhooks_schema()
+        .load_subscriptions()
+        .await
+        .map_err(|e| {
+            vlog::warn!(
+                "failed load webhooks from database in progress request: {}",
+                e
+            );
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+    if let Some(namespace) = &query.namespace {
+        subscriptions.retain(|subscription| subscription.namespace.as_ref() == Some(namespace));
+    }
+
+    Ok(HttpResponse::Ok().json(subscriptions))
+}
+
+/// Removes the webhook subscription along with its deliveries.
+async fn remove_webhook(
+    data: web::Data<AppState>,
+    id: web::Path<WebhookSubscriptionId>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let removed = storage
+        .webhooks_schema()
+        .remove_subscription(id.into_inner())
+        .await
+        .map_err(|e| {
+            vlog::warn!(
+                "failed remove webhook from database in progress request: {}",
+                e
+            );
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+    if !removed {
+        return Err(actix_web::error::ErrorNotFound("webhook not found"));
+    }
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Returns the latest deliveries of the webhook, newest first.
+async fn webhook_deliveries(
+    data: web::Data<AppState>,
+    id: web::Path<WebhookSubscriptionId>,
+    query: web::Query<WebhookDeliveriesQuery>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let deliveries = storage
+        .webhooks_schema()
+        .load_deliveries(id.into_inner(), query.limit)
+        .await
+        .map_err(|e| {
+            vlog::warn!(
+                "failed load webhook deliveries from database in progress request: {}",
+                e
+            );
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+
+    Ok(HttpResponse::Ok().json(deliveries))
+}
+
+/// Creates the API namespace and returns its API key. The key is not stored,
+/// so it can't be obtained later.
+async fn add_namespace(
+    data: web::Data<AppState>,
+    request: web::Json<AddNamespaceRequest>,
+) -> actix_web::Result<HttpResponse> {
+    if !ApiNamespace::is_valid_name(&request.name) {
+        return Err(actix_web::error::ErrorBadRequest("invalid namespace name"));
+    }
+
+    let api_key = hex::encode(thread_rng().gen::<[u8; 32]>());
+    let mut storage = data.access_storage().await?;
+    let namespace = storage
+        .api_namespaces_schema()
+        .add_namespace(
+            &request.name,
+            ApiNamespace::api_key_hash(&api_key),
+            request.limits,
+        )
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed add api namespace to database: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?
+        .ok_or_else(|| actix_web::error::ErrorConflict("namespace already exists"))?;
+    vlog::info!("API namespace {} is created", namespace.name);
+
+    Ok(HttpResponse::Ok().json(CreatedApiNamespace { namespace, api_key }))
+}
+
+/// Returns all the API namespaces.
+async fn namespaces(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let namespaces = storage
+        .api_namespaces_schema()
+        .load_namespaces()
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed load api namespaces from database: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+
+    Ok(HttpResponse::Ok().json(namespaces))
+}
+
+/// Updates the quotas of the API namespace. API servers apply them once their caches expire.
+async fn update_namespace(
+    data: web::Data<AppState>,
+    name: web::Path<String>,
+    limits: web::Json<ApiNamespaceLimits>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let namespace = storage
+        .api_namespaces_schema()
+        .update_limits(&name, limits.into_inner())
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed update api namespace in database: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?
+        .ok_or_else(|| actix_web::error::ErrorNotFound("namespace not found"))?;
+    vlog::info!("API namespace {} limits are updated", namespace.name);
+
+    Ok(HttpResponse::Ok().json(namespace))
+}
+
+/// Removes the API namespace along with its webhook subscriptions.
+async fn remove_namespace(
+    data: web::Data<AppState>,
+    name: web::Path<String>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let removed = storage
+        .api_namespaces_schema()
+        .remove_namespace(&name)
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed remove api namespace from database: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+    if !removed {
+        return Err(actix_web::error::ErrorNotFound("namespace not found"));
+    }
+    vlog::info!("API namespace {} is removed", name);
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Returns the fees collected by the operator within `[from, to)` interval,
+/// grouped by the token and the period.
+async fn revenue(
+    data: web::Data<AppState>,
+    query: web::Query<RevenueQuery>,
+) -> actix_web::Result<HttpResponse> {
+    if query.from >= query.to {
+        return Err(actix_web::error::ErrorBadRequest(
+            "`from` must be earlier than `to`",
+        ));
+    }
+
+    let mut storage = data.access_storage().await?;
+    let revenue = storage
+        .revenue_schema()
+        .load_revenue(
+            query.from,
+            query.to,
+            query.period.unwrap_or(RevenuePeriod::Hour),
+        )
+        .await
+        .map_err(|e| {
+            vlog::warn!(
+                "failed load operator revenue from database in progress request: {}",
+                e
+            );
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+
+    Ok(HttpResponse::Ok().json(revenue))
+}
+
+/// Exports the audit log of the operator keys usage within `[from, to)` interval.
+/// Entries are paginated by ID, the next page starts after the last returned entry.
+async fn key_audit(
+    data: web::Data<AppState>,
+    query: web::Query<KeyAuditQuery>,
+) -> actix_web::Result<HttpResponse> {
+    const MAX_LIMIT: u32 = 1000;
+
+    if query.from >= query.to {
+        return Err(actix_web::error::ErrorBadRequest(
+            "`from` must be earlier than `to`",
+        ));
+    }
+    if query.limit == 0 || query.limit > MAX_LIMIT {
+        return Err(actix_web::error::ErrorBadRequest(format!(
+            "`limit` must be between 1 and {}",
+            MAX_LIMIT
+        )));
+    }
+
+    let mut storage = data.access_storage().await?;
+    let records = storage
+        .key_audit_schema()
+        .load_key_usage(query.from, query.to, query.key, query.after, query.limit)
+        .await
+        .map_err(|e| {
+            vlog::warn!(
+                "failed load key usage audit log from database in progress request: {}",
+                e
+            );
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+
+    Ok(HttpResponse::Ok().json(records))
+}
+
+/// Returns the deposits flagged by `eth_watch` in the given status, oldest first.
+async fn deposit_refunds(
+    data: web::Data<AppState>,
+    query: web::Query<DepositRefundsQuery>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let refunds = storage
+        .deposit_refunds_schema()
+        .load_refunds(
+            query.status.unwrap_or(DepositRefundStatus::Flagged),
+            query.limit,
+        )
+        .await
+        .map_err(|e| {
+            vlog::warn!(
+                "failed load deposit refunds from database in progress request: {}",
+                e
+            );
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+
+    Ok(HttpResponse::Ok().json(refunds))
+}
+
+/// Moves the flagged deposit to the new status, responds with the updated refund.
+/// Only the deposits awaiting the operator decision or with the failed refund can be updated.
+async fn resolve_deposit_refund(
+    data: web::Data<AppState>,
+    serial_id: SerialId,
+    status: DepositRefundStatus,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let mut schema = storage.deposit_refunds_schema();
+    let updated = schema
+        .update_status(
+            serial_id,
+            &[DepositRefundStatus::Flagged, DepositRefundStatus::Failed],
+            status,
+            None,
+        )
+        .await
+        .map_err(|e| {
+            vlog::warn!(
+                "failed update deposit refund in database in progress request: {}",
+                e
+            );
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+    let refund = schema.get_refund(serial_id).await.map_err(|e| {
+        vlog::warn!(
+            "failed load deposit refund from database in progress request: {}",
+            e
+        );
+        actix_web::error::ErrorInternalServerError("storage layer error")
+    })?;
+
+    match refund {
+        None => Err(actix_web::error::ErrorNotFound("deposit is not flagged")),
+        Some(refund) if !updated => Err(actix_web::error::ErrorConflict(format!(
+            "deposit refund is already {}",
+            refund.status.as_str()
+        ))),
+        Some(refund) => Ok(HttpResponse::Ok().json(refund)),
+    }
+}
+
+/// Requests the refund of the flagged deposit, the refund is sent by the server
+/// if `ETH_WATCH_DEPOSIT_REFUNDS_ENABLED` is set.
+async fn request_deposit_refund(
+    data: web::Data<AppState>,
+    serial_id: web::Path<SerialId>,
+) -> actix_web::Result<HttpResponse> {
+    resolve_deposit_refund(data, serial_id.into_inner(), DepositRefundStatus::Requested).await
+}
+
+/// Marks the flagged deposit as the one that won't be refunded.
+async fn dismiss_deposit_refund(
+    data: web::Data<AppState>,
+    serial_id: web::Path<SerialId>,
+) -> actix_web::Result<HttpResponse> {
+    resolve_deposit_refund(data, serial_id.into_inner(), DepositRefundStatus::Dismissed).await
+}
+
+/// Returns the sweeps of the collected fees to the treasury, newest first.
+async fn fee_sweeps(
+    data: web::Data<AppState>,
+    query: web::Query<FeeSweepsQuery>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let sweeps = storage
+        .fee_sweeps_schema()
+        .load_sweeps(query.status, query.limit)
+        .await
+        .map_err(|e| {
+            vlog::warn!(
+                "failed load fee sweeps from database in progress request: {}",
+                e
+            );
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+
+    Ok(HttpResponse::Ok().json(sweeps))
+}
+
+/// Returns the refunds of the fees paid over the required ones, newest first.
+async fn fee_refunds(
+    data: web::Data<AppState>,
+    query: web::Query<FeeRefundsQuery>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let refunds = storage
+        .fee_refunds_schema()
+        .load_refunds(query.status, query.limit)
+        .await
+        .map_err(|e| {
+            vlog::warn!(
+                "failed load fee refunds from database in progress request: {}",
+                e
+            );
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+
+    Ok(HttpResponse::Ok().json(refunds))
+}
+
+/// Re-reads the config files and applies the values that are safe to change on a running server.
+/// Responds with the applied values, or with the list of problems if the new config is invalid.
+async fn reload_config(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    match data.config_reloader.reload() {
+        Ok(params) => Ok(HttpResponse::Ok().json(params)),
+        Err(errors) => {
+            vlog::warn!("Config is not reloaded. {}", errors);
+            Ok(HttpResponse::BadRequest()
+                .json(errors.0.iter().map(ToString::to_string).collect::<Vec<_>>()))
+        }
+    }
+}
+
+/// Returns the currently applied values of the reloadable config options.
+async fn reloadable_config(data: web::Data<AppState>) -> web::Json<ReloadableParams> {
+    web::Json(data.config_reloader.current())
+}
+
+/// Returns the status of the supervised components.
+async fn health(data: web::Data<AppState>) -> HttpResponse {
+    let report = data.supervisor.health();
+    if report.healthy {
+        HttpResponse::Ok().json(report)
+    } else {
+        HttpResponse::ServiceUnavailable().json(report)
+    }
+}
+
+async fn load_status_overview(data: &AppState) -> anyhow::Result<StatusOverview> {
+    let mut storage = data.connection_pool.access_storage().await?;
+    let last_saved_block = storage
+        .chain()
+        .block_schema()
+        .get_last_saved_block()
+        .await?;
+    let last_committed_block = storage
+        .chain()
+        .operations_schema()
+        .get_last_block_by_aggregated_action(AggregatedActionType::CommitBlocks, Some(true))
+        .await?;
+    let last_verified_block = storage
+        .chain()
+        .block_schema()
+        .get_last_verified_confirmed_block()
+        .await?;
+    let mempool_txs = storage.chain().mempool_schema().count_txs().await?;
+    let pending_prover_jobs = storage.prover_schema().pending_jobs_count().await?;
+    let unprocessed_eth_operations = storage
+        .ethereum_schema()
+        .count_unprocessed_operations()
+        .await?;
+    let unconfirmed_eth_operations = storage
+        .ethereum_schema()
+        .count_unconfirmed_operations()
+        .await?;
+    let gas_price_limit = storage.ethereum_schema().load_gas_price_limit().await?;
+    drop(storage);
+
+    let operator_balance = match data.eth_gateway.sender_eth_balance().await {
+        Ok(balance) => Some(balance),
+        Err(err) => {
+            vlog::warn!("Failed to load the operator balance: {}", err);
+            None
+        }
+    };
+
+    Ok(StatusOverview {
+        last_saved_block,
+        last_committed_block,
+        last_verified_block,
+        mempool_txs,
+        pending_prover_jobs,
+        unprocessed_eth_operations,
+        unconfirmed_eth_operations,
+        operator_balance,
+        gas_price_limit,
+        health: data.supervisor.health(),
+    })
+}
+
+/// Returns the status of the blocks processing, the queues and the components in a single response.
+async fn status_overview(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let overview = load_status_overview(&data).await.map_err(|e| {
+        vlog::warn!("failed load the status overview: {}", e);
+        actix_web::error::ErrorInternalServerError("storage layer error")
+    })?;
+
+    Ok(HttpResponse::Ok().json(overview))
+}
+
 async fn run_server(app_state: AppState, bind_to: SocketAddr) {
     HttpServer::new(move || {
         let auth = HttpAuthentication::bearer(move |req, credentials| async {
@@ -144,6 +868,36 @@ async fn run_server(app_state: AppState, bind_to: SocketAddr) {
             .wrap(vlog::actix_middleware())
             .app_data(web::Data::new(app_state.clone()))
             .route("/tokens", web::post().to(add_token))
+            .route("/tokens/{id}", web::put().to(update_token))
+            .route("/tokens/{id}/flags", web::put().to(update_token_flags))
+            .route("/webhooks", web::post().to(add_webhook))
+            .route("/webhooks", web::get().to(webhooks))
+            .route("/webhooks/{id}", web::delete().to(remove_webhook))
+            .route(
+                "/webhooks/{id}/deliveries",
+                web::get().to(webhook_deliveries),
+            )
+            .route("/namespaces", web::post().to(add_namespace))
+            .route("/namespaces", web::get().to(namespaces))
+            .route("/namespaces/{name}", web::put().to(update_namespace))
+            .route("/namespaces/{name}", web::delete().to(remove_namespace))
+            .route("/revenue", web::get().to(revenue))
+            .route("/key_audit", web::get().to(key_audit))
+            .route("/deposit_refunds", web::get().to(deposit_refunds))
+            .route(
+                "/deposit_refunds/{serial_id}/refund",
+                web::post().to(request_deposit_refund),
+            )
+            .route(
+                "/deposit_refunds/{serial_id}/dismiss",
+                web::post().to(dismiss_deposit_refund),
+            )
+            .route("/fee_sweeps", web::get().to(fee_sweeps))
+            .route("/fee_refunds", web::get().to(fee_refunds))
+            .route("/config", web::get().to(reloadable_config))
+            .route("/config/reload", web::post().to(reload_config))
+            .route("/health", web::get().to(health))
+            .route("/status/overview", web::get().to(status_overview))
     })
     .workers(1)
     .bind(&bind_to)
@@ -157,6 +911,9 @@ pub fn start_admin_server(
     bind_to: SocketAddr,
     secret_auth: String,
     connection_pool: zksync_storage::ConnectionPool,
+    config_reloader: ConfigReloader,
+    supervisor: Supervisor,
+    eth_gateway: EthereumGateway,
     panic_notify: mpsc::Sender<bool>,
 ) {
     thread::Builder::new()
@@ -167,6 +924,9 @@ pub fn start_admin_server(
                 let app_state = AppState {
                     connection_pool,
                     secret_auth,
+                    config_reloader,
+                    supervisor,
+                    eth_gateway,
                 };
 
                 run_server(app_state, bind_to).await;
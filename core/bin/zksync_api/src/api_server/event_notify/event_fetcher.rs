@@ -1,4 +1,4 @@
-use super::ExecutedOps;
+use super::{ExecutedOps, ExpiredTx};
 use futures::{channel::mpsc, SinkExt};
 use std::time::{Duration, Instant};
 use zksync_storage::ConnectionPool;
@@ -6,9 +6,13 @@ use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
     block::ExecutedOperations,
     block::PendingBlock,
+    tx::TxHash,
     BlockNumber,
 };
 
+/// Max number of the expired transactions loaded from the database at once.
+const EXPIRED_TXS_LIMIT: u32 = 1_000;
+
 /// Simple awaiter for the database futures, which will add a log entry upon DB failure
 /// and execute `on_exit` statement.
 macro_rules! await_db {
@@ -24,7 +28,7 @@ macro_rules! await_db {
 }
 
 /// Event fetcher is an actor which polls the database from time to time in order to see
-/// whether new blocks were committed or verified.
+/// whether new blocks were committed or verified, or the mempool transactions expired.
 ///
 /// Once tha new data is available, it is sent to the `OperationNotifier`, which broadcasts it
 /// to the subscribers.
@@ -36,9 +40,11 @@ pub struct EventFetcher {
     last_committed_block: BlockNumber,
     last_verified_block: BlockNumber,
     pending_block: Option<PendingBlock>,
+    last_expired_tx_id: i64,
 
     operations_sender: mpsc::Sender<AggregatedOperation>,
     txs_sender: mpsc::Sender<ExecutedOps>,
+    expired_txs_sender: mpsc::Sender<Vec<ExpiredTx>>,
 }
 
 impl EventFetcher {
@@ -47,6 +53,7 @@ impl EventFetcher {
         miniblock_interval: Duration,
         operations_sender: mpsc::Sender<AggregatedOperation>,
         txs_sender: mpsc::Sender<ExecutedOps>,
+        expired_txs_sender: mpsc::Sender<Vec<ExpiredTx>>,
     ) -> anyhow::Result<Self> {
         let mut fetcher = EventFetcher {
             miniblock_interval,
@@ -55,9 +62,11 @@ impl EventFetcher {
             last_committed_block: BlockNumber(0),
             last_verified_block: BlockNumber(0),
             pending_block: None,
+            last_expired_tx_id: 0,
 
             operations_sender,
             txs_sender,
+            expired_txs_sender,
         };
 
         let pending_block = fetcher.load_pending_block().await?;
@@ -66,6 +75,7 @@ impl EventFetcher {
 
         fetcher.last_committed_block = last_committed_block;
         fetcher.last_verified_block = last_verified_block;
+        fetcher.last_expired_tx_id = fetcher.last_expired_tx_id().await?;
         if let Some(block) = pending_block {
             // We only want to set this field if the pending block is actually the latest block (ahead of last committed one).
             if block.number > fetcher.last_committed_block {
@@ -114,6 +124,15 @@ impl EventFetcher {
                     self.txs_sender.send(executed_ops).await.unwrap_or_default();
                 }
             }
+
+            // 4. Load the transactions expired in the mempool.
+            let expired_txs = await_db!(self.load_expired_txs(), continue);
+            if !expired_txs.is_empty() {
+                self.expired_txs_sender
+                    .send(expired_txs)
+                    .await
+                    .unwrap_or_default();
+            }
         }
     }
 
@@ -201,6 +220,51 @@ impl EventFetcher {
         Ok(pending_block)
     }
 
+    async fn load_expired_txs(&mut self) -> anyhow::Result<Vec<ExpiredTx>> {
+        let start = Instant::now();
+        let mut storage = self
+            .db_pool
+            .access_storage()
+            .await
+            .expect("Can't get access to the storage");
+
+        let stored_txs = storage
+            .chain()
+            .mempool_schema()
+            .load_expired_txs(self.last_expired_tx_id, EXPIRED_TXS_LIMIT)
+            .await?;
+        if let Some(last_tx) = stored_txs.last() {
+            self.last_expired_tx_id = last_tx.id;
+        }
+        let expired_txs = stored_txs
+            .into_iter()
+            .filter_map(|stored_tx| {
+                Some(ExpiredTx {
+                    tx_hash: TxHash::from_slice(&stored_tx.tx_hash)?,
+                    reason: stored_tx.reason,
+                })
+            })
+            .collect();
+
+        metrics::histogram!("api.event_fetcher.load_expired_txs", start.elapsed());
+        Ok(expired_txs)
+    }
+
+    async fn last_expired_tx_id(&mut self) -> anyhow::Result<i64> {
+        let mut storage = self
+            .db_pool
+            .access_storage()
+            .await
+            .expect("Can't get access to the storage");
+
+        let last_id = storage
+            .chain()
+            .mempool_schema()
+            .last_expired_tx_id()
+            .await?;
+        Ok(last_id)
+    }
+
     async fn last_committed_block(&mut self) -> anyhow::Result<BlockNumber> {
         let start = Instant::now();
         let mut storage = self
@@ -8,7 +8,11 @@ use std::time::Duration;
 use zksync_storage::ConnectionPool;
 use zksync_types::tx::TxHash;
 use zksync_types::BlockNumber;
-use zksync_types::{block::ExecutedOperations, ActionType, Address};
+use zksync_types::{
+    aggregated_operations::AggregatedOperation, block::ExecutedOperations, ActionType, Address,
+};
+
+use crate::utils::account_cache::AccountIdCache;
 
 use self::{event_fetcher::EventFetcher, operation_notifier::OperationNotifier};
 
@@ -25,6 +29,13 @@ pub struct ExecutedOps {
     pub block_number: BlockNumber,
 }
 
+/// Transaction removed from the mempool since it was not executed in time.
+#[derive(Debug)]
+pub struct ExpiredTx {
+    pub tx_hash: TxHash,
+    pub reason: String,
+}
+
 pub enum EventSubscribeRequest {
     Transaction {
         hash: TxHash,
@@ -62,6 +73,7 @@ pub fn start_sub_notifier(
 ) -> tokio::task::JoinHandle<()> {
     let (new_block_sender, mut new_block_receiver) = mpsc::channel(NOTIFIER_CHANNEL_CAPACITY);
     let (new_txs_sender, mut new_txs_receiver) = mpsc::channel(NOTIFIER_CHANNEL_CAPACITY);
+    let (expired_txs_sender, mut expired_txs_receiver) = mpsc::channel(NOTIFIER_CHANNEL_CAPACITY);
 
     let mut notifier = OperationNotifier::new(api_requests_caches_size, db_pool.clone());
 
@@ -71,6 +83,7 @@ pub fn start_sub_notifier(
             miniblock_interval,
             new_block_sender,
             new_txs_sender,
+            expired_txs_sender,
         )
         .await
         .expect("Unable to create event fetcher");
@@ -81,6 +94,11 @@ pub fn start_sub_notifier(
             select! {
                 new_block = new_block_receiver.next() => {
                     if let Some(new_block) = new_block {
+                        if let AggregatedOperation::CommitBlocks(op) = &new_block {
+                            AccountIdCache::handle_executed_operations(
+                                op.blocks.iter().flat_map(|block| &block.block_transactions),
+                            );
+                        }
                         notifier.handle_new_block(new_block)
                             .await
                             .map_err(|e| vlog::warn!("Failed to handle new block: {}",e))
@@ -89,11 +107,17 @@ pub fn start_sub_notifier(
                 },
                 new_exec_batch = new_txs_receiver.next() => {
                     if let Some(new_exec_batch) = new_exec_batch {
+                        AccountIdCache::handle_executed_operations(&new_exec_batch.operations);
                         notifier.handle_new_executed_batch(new_exec_batch)
                             .map_err(|e| vlog::warn!("Failed to handle new exec batch: {}",e))
                             .unwrap_or_default();
                     }
                 },
+                expired_txs = expired_txs_receiver.next() => {
+                    if let Some(expired_txs) = expired_txs {
+                        notifier.handle_expired_txs(expired_txs);
+                    }
+                },
                 new_sub = subscription_stream.next() => {
                     if let Some(new_sub) = new_sub {
                         notifier.handle_notify_req(new_sub)
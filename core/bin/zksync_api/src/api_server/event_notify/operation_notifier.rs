@@ -11,7 +11,7 @@ use zksync_types::{block::ExecutedOperations, AccountId, ActionType, Address, Pr
 
 use super::{
     state::NotifierState, sub_store::SubStorage, EventNotifierRequest, EventSubscribeRequest,
-    ExecutedOps,
+    ExecutedOps, ExpiredTx,
 };
 
 pub struct OperationNotifier {
@@ -138,6 +138,7 @@ impl OperationNotifier {
                             block_number: i64::from(*block_number),
                             committed: true,
                             verified: action == ActionType::VERIFY,
+                            finality: action.into(),
                         }),
                     };
                     self.tx_subs.notify(hash, action, resp);
@@ -150,6 +151,7 @@ impl OperationNotifier {
                             block_number: i64::from(*block_number),
                             committed: true,
                             verified: action == ActionType::VERIFY,
+                            finality: action.into(),
                         }),
                     };
                     self.prior_op_subs.notify(PriorityOpId(id), action, resp);
@@ -172,6 +174,19 @@ impl OperationNotifier {
         Ok(())
     }
 
+    /// Notifies the subscribers of the transactions expired in the mempool.
+    /// Expired transactions will never be executed, so both commit and verify subscribers
+    /// receive the final response.
+    pub fn handle_expired_txs(&mut self, expired_txs: Vec<ExpiredTx>) {
+        for expired_tx in expired_txs {
+            let resp = expired_tx_info(expired_tx.reason);
+            self.tx_subs
+                .notify(expired_tx.tx_hash, ActionType::COMMIT, resp.clone());
+            self.tx_subs
+                .notify(expired_tx.tx_hash, ActionType::VERIFY, resp);
+        }
+    }
+
     /// Removes provided subscription from the list.
     fn handle_unsub(&mut self, sub_id: SubscriptionId) -> Result<(), anyhow::Error> {
         self.prior_op_subs.remove(sub_id.clone())?;
@@ -245,6 +260,10 @@ impl OperationNotifier {
         let tx_receipt = self.state.get_tx_receipt(&hash).await?;
 
         if let Some(receipt) = tx_receipt {
+            let finality = self
+                .state
+                .get_block_finality(BlockNumber(receipt.block_number as u32))
+                .await?;
             let tx_info_resp = TransactionInfoResp {
                 executed: true,
                 success: Some(receipt.success),
@@ -253,6 +272,7 @@ impl OperationNotifier {
                     block_number: receipt.block_number,
                     committed: receipt.success,
                     verified: receipt.verified,
+                    finality,
                 }),
             };
             match action {
@@ -267,6 +287,10 @@ impl OperationNotifier {
                     }
                 }
             }
+        } else if let Some(reason) = self.state.get_tx_expiry_reason(&hash).await? {
+            self.tx_subs
+                .respond_once(sub_id, sub, expired_tx_info(reason))?;
+            return Ok(());
         }
 
         self.tx_subs.insert_new(sub_id, sub, hash, action)?;
@@ -292,3 +316,13 @@ impl OperationNotifier {
         Ok(())
     }
 }
+
+/// Response for the transaction expired in the mempool.
+fn expired_tx_info(reason: String) -> TransactionInfoResp {
+    TransactionInfoResp {
+        executed: false,
+        success: Some(false),
+        fail_reason: Some(reason),
+        block: None,
+    }
+}
@@ -6,6 +6,7 @@ use zksync_storage::chain::operations::records::StoredExecutedPriorityOperation;
 use zksync_storage::chain::operations_ext::records::TxReceiptResponse;
 use zksync_storage::ConnectionPool;
 use zksync_types::aggregated_operations::AggregatedActionType;
+use zksync_types::finality::Finality;
 use zksync_types::tx::TxHash;
 use zksync_types::BlockNumber;
 use zksync_types::{AccountId, ActionType, Address};
@@ -63,6 +64,21 @@ impl NotifierState {
         Ok(res)
     }
 
+    /// Returns the reason the transaction expired in the mempool for, if it did.
+    pub async fn get_tx_expiry_reason(
+        &mut self,
+        hash: &TxHash,
+    ) -> Result<Option<String>, anyhow::Error> {
+        let mut storage = self.db_pool.access_storage().await?;
+        let expired_tx = storage
+            .chain()
+            .mempool_schema()
+            .get_expired_tx(*hash)
+            .await?;
+
+        Ok(expired_tx.map(|expired_tx| expired_tx.reason))
+    }
+
     pub async fn get_block_info(
         &mut self,
         block_number: BlockNumber,
@@ -89,11 +105,18 @@ impl NotifierState {
                     .await
                     .map(|operation| operation.confirmed)
                     .unwrap_or_default();
+                let finality = transaction
+                    .chain()
+                    .block_schema()
+                    .load_finalized_blocks()
+                    .await?
+                    .block_finality(block_with_op.block_number);
 
                 BlockInfo {
                     block_number: i64::from(*block_with_op.block_number),
                     committed: true,
                     verified,
+                    finality,
                 }
             } else {
                 // Tx is executed, but block is not created. Probably, it's in the pending block,
@@ -120,6 +143,24 @@ impl NotifierState {
         Ok(Some(res))
     }
 
+    pub async fn get_block_finality(
+        &self,
+        block_number: BlockNumber,
+    ) -> Result<Finality, anyhow::Error> {
+        let start = Instant::now();
+        let finalized = self
+            .db_pool
+            .access_storage()
+            .await?
+            .chain()
+            .block_schema()
+            .load_finalized_blocks()
+            .await?;
+
+        metrics::histogram!("api.notifier.get_block_finality", start.elapsed());
+        Ok(finalized.block_finality(block_number))
+    }
+
     pub async fn get_executed_priority_operation(
         &mut self,
         serial_id: u32,
@@ -174,15 +215,17 @@ impl NotifierState {
             anyhow::bail!("AccountId is unknown");
         };
 
+        let finality = account_state_finality(action);
         let account_state = if let Some(account) = match action {
             ActionType::COMMIT => account_state.committed,
             ActionType::VERIFY => account_state.verified,
         }
         .map(|(_, a)| a)
         {
-            ResponseAccountState::try_restore(&mut storage, &self.tokens_cache, account).await?
+            ResponseAccountState::try_restore(&mut storage, &self.tokens_cache, account, finality)
+                .await?
         } else {
-            ResponseAccountState::default()
+            ResponseAccountState::empty(finality)
         };
 
         metrics::histogram!("api.notifier.get_account_info", start.elapsed());
@@ -215,7 +258,8 @@ impl NotifierState {
         };
 
         let account = if let Some(account) = stored_account {
-            ResponseAccountState::try_restore(&mut storage, &self.tokens_cache, account)
+            let finality = account_state_finality(action);
+            ResponseAccountState::try_restore(&mut storage, &self.tokens_cache, account, finality)
                 .await
                 .ok()
         } else {
@@ -225,3 +269,12 @@ impl NotifierState {
         Ok(account)
     }
 }
+
+/// The committed state of the account includes the blocks not committed on L1 yet,
+/// so only the verified state has the finality of the corresponding action.
+fn account_state_finality(action: ActionType) -> Finality {
+    match action {
+        ActionType::COMMIT => Finality::Pending,
+        ActionType::VERIFY => Finality::Executed,
+    }
+}
@@ -10,9 +10,10 @@ pub use rest::v1;
 // External uses
 use futures::channel::mpsc;
 // Workspace uses
-use zksync_config::ZkSyncConfig;
+use zksync_config::{ConfigReloader, ZkSyncConfig};
 use zksync_eth_client::EthereumGateway;
 use zksync_storage::ConnectionPool;
+use zksync_utils::supervisor::Supervisor;
 // Local uses
 use crate::fee_ticker::TickerRequest;
 use crate::signature_checker;
@@ -25,6 +26,7 @@ mod rest;
 pub mod rpc_server;
 mod rpc_subscriptions;
 mod tx_sender;
+mod tx_simulator;
 
 /// Amount of threads used by each server to serve requests.
 const THREADS_PER_SERVER: usize = 128;
@@ -36,11 +38,20 @@ pub fn start_api_server(
     ticker_request_sender: mpsc::Sender<TickerRequest>,
     eth_gateway: EthereumGateway,
     config: &ZkSyncConfig,
+    config_reloader: ConfigReloader,
+    supervisor: Supervisor,
 ) {
     let (sign_check_sender, sign_check_receiver) = mpsc::channel(32768);
 
     signature_checker::start_sign_checker_detached(
-        eth_gateway,
+        eth_gateway.clone(),
+        config
+            .api
+            .common
+            .withdrawal_checked_tokens
+            .iter()
+            .cloned()
+            .collect(),
         sign_check_receiver,
         panic_notify.clone(),
     );
@@ -52,6 +63,7 @@ pub fn start_api_server(
         panic_notify.clone(),
         ticker_request_sender.clone(),
         sign_check_sender.clone(),
+        eth_gateway.clone(),
         config.clone(),
     );
 
@@ -67,6 +79,9 @@ pub fn start_api_server(
         config.api.admin.bind_addr(),
         config.api.admin.secret_auth.clone(),
         connection_pool.clone(),
+        config_reloader,
+        supervisor,
+        eth_gateway,
         panic_notify.clone(),
     );
 
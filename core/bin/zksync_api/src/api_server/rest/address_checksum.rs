@@ -0,0 +1,298 @@
+//! Checksums of the addresses passed to the REST API.
+//!
+//! The guard checks the EIP-55 checksums of all the addresses in the request: the path segments,
+//! the query parameters and the JSON body. The addresses are recognized by their form, so the
+//! check doesn't depend on the endpoint, and the request with the mistyped address is rejected
+//! before it gets to the handler. The rejected addresses are reported with the dedicated error
+//! code, so the clients can tell the bad checksum from the malformed address.
+
+// Built-in uses
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+// External uses
+use actix_web::{
+    dev::{Payload, Service, ServiceRequest, ServiceResponse, Transform},
+    error::PayloadError,
+    web::{self, Bytes, BytesMut},
+    HttpMessage,
+};
+use futures::{
+    future::{ok, FutureExt, LocalBoxFuture, Ready},
+    stream, StreamExt,
+};
+
+// Workspace uses
+use zksync_types::{
+    address_checksum::{
+        check_json_addresses, looks_like_address, parse_address, AddressError, ChecksumStrictness,
+    },
+    api_error::ApiErrorCode,
+    Address,
+};
+
+// Local uses
+use super::v1::ApiError;
+
+/// Bodies larger than this are rejected, since they're not accepted by the JSON extractors anyway.
+const MAX_BODY_SIZE: usize = 256 * 1024;
+
+/// Converts the address parsing error into the API error.
+pub(crate) fn address_error(err: AddressError) -> ApiError {
+    let code = match &err {
+        AddressError::Malformed(_) => ApiErrorCode::MalformedAddress,
+        AddressError::BadChecksum(_) => ApiErrorCode::InvalidAddressChecksum,
+    };
+    ApiError::bad_request(err).error_code(code)
+}
+
+/// Parses the address passed in the request path. The path is already checked by
+/// [`AddressChecksumGuard`], so only the malformed addresses are rejected here.
+pub(crate) fn parse_path_address(value: &str) -> Result<Address, ApiError> {
+    parse_address(value, ChecksumStrictness::Disabled).map_err(address_error)
+}
+
+/// Middleware rejecting the requests with the invalid address checksums.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct AddressChecksumGuard {
+    strictness: ChecksumStrictness,
+}
+
+impl AddressChecksumGuard {
+    pub fn new(strictness: ChecksumStrictness) -> Self {
+        Self { strictness }
+    }
+
+    fn check_url(&self, req: &ServiceRequest) -> Result<(), AddressError> {
+        let query = web::Query::<Vec<(String, String)>>::from_query(req.query_string())
+            .map(web::Query::into_inner)
+            .unwrap_or_default();
+        req.path()
+            .split('/')
+            .chain(query.iter().map(|(_, value)| value.as_str()))
+            .filter(|value| looks_like_address(value))
+            .try_for_each(|value| parse_address(value, self.strictness).map(drop))
+    }
+
+    async fn check(&self, req: &mut ServiceRequest) -> Result<(), ApiError> {
+        if self.strictness == ChecksumStrictness::Disabled {
+            return Ok(());
+        }
+        self.check_url(req).map_err(address_error)?;
+        self.check_body(req).await
+    }
+
+    async fn check_body(&self, req: &mut ServiceRequest) -> Result<(), ApiError> {
+        if req.content_type() != "application/json" {
+            return Ok(());
+        }
+
+        let mut payload = req.take_payload();
+        let mut body = BytesMut::new();
+        while let Some(chunk) = payload.next().await {
+            let chunk = chunk.map_err(ApiError::bad_request)?;
+            if body.len() + chunk.len() > MAX_BODY_SIZE {
+                return Err(ApiError::bad_request("Request body is too large"));
+            }
+            body.extend_from_slice(&chunk);
+        }
+        let body = body.freeze();
+
+        // Malformed bodies are left to the handlers, so they're reported as usual.
+        let result = match serde_json::from_slice::<serde_json::Value>(&body) {
+            Ok(value) => check_json_addresses(&value, self.strictness).map_err(address_error),
+            Err(_) => Ok(()),
+        };
+        // The body is consumed, so it's put back for the handler.
+        req.set_payload(Payload::Stream(Box::pin(stream::once(async move {
+            Ok::<Bytes, PayloadError>(body)
+        }))));
+        result
+    }
+}
+
+impl<S, B> Transform<S> for AddressChecksumGuard
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>
+        + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type InitError = ();
+    type Transform = AddressChecksumGuardMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(AddressChecksumGuardMiddleware {
+            service: Rc::new(RefCell::new(service)),
+            guard: *self,
+        })
+    }
+}
+
+pub(crate) struct AddressChecksumGuardMiddleware<S> {
+    service: Rc<RefCell<S>>,
+    guard: AddressChecksumGuard,
+}
+
+impl<S, B> Service for AddressChecksumGuardMiddleware<S>
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>
+        + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.borrow_mut().poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let guard = self.guard;
+        async move {
+            if let Err(err) = guard.check(&mut req).await {
+                metrics::counter!("api.rest.rejected_addresses", 1);
+                return Err(err.into());
+            }
+            // The service must not stay borrowed while the request is being handled.
+            let response = service.borrow_mut().call(req);
+            response.await
+        }
+        .boxed_local()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http::StatusCode, test, App, HttpResponse, ResponseError};
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    const CHECKSUMMED: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+    const TYPO: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD";
+
+    async fn check(strictness: ChecksumStrictness, req: test::TestRequest) -> Option<ApiErrorCode> {
+        let mut req = req.to_srv_request();
+        AddressChecksumGuard::new(strictness)
+            .check(&mut req)
+            .await
+            .err()
+            .map(|err| err.body.error_type.expect("error code is set"))
+    }
+
+    #[actix_rt::test]
+    async fn url_addresses() {
+        let strict = ChecksumStrictness::Strict;
+        let get = |uri: &str| test::TestRequest::get().uri(uri);
+
+        assert_eq!(
+            check(strict, get(&format!("/accounts/{}", CHECKSUMMED))).await,
+            None
+        );
+        assert_eq!(
+            check(strict, get(&format!("/accounts/{}", TYPO))).await,
+            Some(ApiErrorCode::InvalidAddressChecksum)
+        );
+        // The lowercase addresses have no checksum, so they're rejected in the strict mode only.
+        let lowercase = format!("/accounts/{}", CHECKSUMMED.to_lowercase());
+        assert_eq!(
+            check(strict, get(&lowercase)).await,
+            Some(ApiErrorCode::InvalidAddressChecksum)
+        );
+        assert_eq!(
+            check(ChecksumStrictness::MixedCase, get(&lowercase)).await,
+            None
+        );
+        assert_eq!(
+            check(
+                strict,
+                get(&format!("/messages?address={}", &CHECKSUMMED[..41]))
+            )
+            .await,
+            Some(ApiErrorCode::MalformedAddress)
+        );
+        assert_eq!(
+            check(
+                ChecksumStrictness::Disabled,
+                get(&format!("/accounts/{}", TYPO))
+            )
+            .await,
+            None
+        );
+    }
+
+    #[actix_rt::test]
+    async fn body_addresses() {
+        let strict = ChecksumStrictness::Strict;
+        let post = |body: Value| test::TestRequest::post().set_json(&body);
+
+        // The bodies are serialized by the clients, so the lowercase addresses are accepted.
+        assert_eq!(
+            check(strict, post(json!({ "to": CHECKSUMMED.to_lowercase() }))).await,
+            None
+        );
+        assert_eq!(
+            check(strict, post(json!({ "txs": [{ "to": TYPO }] }))).await,
+            Some(ApiErrorCode::InvalidAddressChecksum)
+        );
+        assert_eq!(
+            check(
+                strict,
+                post(json!({ "to": format!("{}g", &CHECKSUMMED[..41]) }))
+            )
+            .await,
+            Some(ApiErrorCode::MalformedAddress)
+        );
+    }
+
+    /// Checks that the rejected requests don't reach the handler, and the accepted ones
+    /// reach it with the body intact.
+    #[actix_rt::test]
+    async fn guarded_service() {
+        let mut app = test::init_service(
+            App::new()
+                .wrap(AddressChecksumGuard::new(ChecksumStrictness::MixedCase))
+                .route(
+                    "/echo",
+                    web::post().to(|body: web::Json<Value>| async move {
+                        HttpResponse::Ok().json(body.into_inner())
+                    }),
+                ),
+        )
+        .await;
+
+        let body = json!({ "to": CHECKSUMMED, "amount": "1" });
+        let req = test::TestRequest::post()
+            .uri("/echo")
+            .set_json(&body)
+            .to_request();
+        let response = app.call(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(test::read_body_json::<Value, _>(response).await, body);
+
+        let req = test::TestRequest::post()
+            .uri("/echo")
+            .set_json(&json!({ "to": TYPO }))
+            .to_request();
+        let err = match app.call(req).await {
+            Ok(_) => panic!("Request with the invalid address must be rejected"),
+            Err(err) => err,
+        };
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::BAD_REQUEST
+        );
+    }
+}
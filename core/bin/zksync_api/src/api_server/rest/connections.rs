@@ -0,0 +1,267 @@
+//! HTTP stack settings and connection metrics of the REST API server.
+//!
+//! Clients submitting transactions at high rates must reuse the connections instead of opening
+//! a new one per request, otherwise they run out of the ephemeral ports. The server keeps the idle
+//! connections alive for the configured time and, if the TLS certificate is configured, negotiates
+//! HTTP/2 via ALPN, so the requests are multiplexed over a single connection.
+//!
+//! The connections are tracked by the peer socket address: the connection data can't be kept in
+//! the extensions set by `on_connect`, since they are moved into the first request of the connection.
+//! Every connection gets `ConnectionStats` in the shared `ConnectionRegistry`, counting the requests
+//! served by it. Once the connection is closed, its lifetime and the number of the served requests
+//! are reported, so the effect of the keep-alive settings is visible. The server doesn't notify
+//! about the closed connections, so the connection is considered closed once it's idle for longer
+//! than the server keeps it alive, or once another connection is opened from the same socket.
+
+// Built-in uses
+use std::{
+    any::Any,
+    collections::HashMap,
+    fs::File,
+    io::BufReader,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+// External uses
+use actix_web::http::Version;
+use anyhow::format_err;
+use rustls::{
+    internal::pemfile::{certs, pkcs8_private_keys, rsa_private_keys},
+    NoClientAuth, ServerConfig,
+};
+use tokio::net::TcpStream;
+use tokio_rustls::server::TlsStream;
+// Workspace uses
+use zksync_config::configs::api::RestApi;
+
+/// Statistics of the single connection, reported when the connection is closed.
+#[derive(Debug, Clone, PartialEq)]
+struct ConnectionStats {
+    opened_at: Instant,
+    last_active_at: Instant,
+    requests: u64,
+}
+
+impl ConnectionStats {
+    fn new(now: Instant) -> Self {
+        metrics::counter!("api.rest.connections.opened", 1);
+        Self {
+            opened_at: now,
+            last_active_at: now,
+            requests: 0,
+        }
+    }
+
+    fn report(&self) {
+        metrics::histogram!(
+            "api.rest.connections.lifetime",
+            self.last_active_at - self.opened_at
+        );
+        metrics::histogram!("api.rest.connections.requests", self.requests as f64);
+    }
+}
+
+/// Open connections of the server, shared by all the workers.
+#[derive(Debug)]
+pub struct ConnectionRegistry {
+    connections: Mutex<HashMap<SocketAddr, ConnectionStats>>,
+    /// Time after which the idle connection is considered closed by the server.
+    idle_timeout: Duration,
+}
+
+impl ConnectionRegistry {
+    pub fn new(config: &RestApi) -> Arc<Self> {
+        let idle_timeout = std::cmp::max(
+            Duration::from_secs(config.keep_alive_secs),
+            Duration::from_millis(config.client_timeout_ms),
+        );
+        Arc::new(Self::with_idle_timeout(idle_timeout))
+    }
+
+    fn with_idle_timeout(idle_timeout: Duration) -> Self {
+        Self {
+            connections: Mutex::default(),
+            idle_timeout,
+        }
+    }
+
+    /// Callback of the `HttpServer::on_connect`, starts tracking the new connection.
+    pub fn on_connect(&self, connection: &dyn Any) {
+        match peer_addr(connection) {
+            Some(peer) => self.open(peer, Instant::now()),
+            None => vlog::debug!("Peer address of the new connection is unknown"),
+        }
+    }
+
+    /// Counts the request towards its connection and reports the HTTP version used.
+    pub fn record_request(&self, peer: Option<SocketAddr>, version: Version) {
+        if let Some(peer) = peer {
+            self.touch(peer, Instant::now());
+        }
+        let version = match version {
+            Version::HTTP_2 => "2",
+            Version::HTTP_11 => "1.1",
+            Version::HTTP_10 => "1.0",
+            _ => "other",
+        };
+        metrics::counter!("api.rest.requests_by_version", 1, "version" => version);
+    }
+
+    fn open(&self, peer: SocketAddr, now: Instant) {
+        let mut connections = self.connections.lock().unwrap();
+        self.close_idle(&mut connections, now);
+        // The socket is reused only after the previous connection from it is closed.
+        if let Some(closed) = connections.insert(peer, ConnectionStats::new(now)) {
+            closed.report();
+        }
+        metrics::gauge!("api.rest.connections.open", connections.len() as f64);
+    }
+
+    fn touch(&self, peer: SocketAddr, now: Instant) {
+        let mut connections = self.connections.lock().unwrap();
+        // The connection may be already considered closed if the request took too long.
+        let stats = connections
+            .entry(peer)
+            .or_insert_with(|| ConnectionStats::new(now));
+        stats.requests += 1;
+        stats.last_active_at = now;
+    }
+
+    fn close_idle(&self, connections: &mut HashMap<SocketAddr, ConnectionStats>, now: Instant) {
+        connections.retain(|_, stats| {
+            let is_open = now.duration_since(stats.last_active_at) <= self.idle_timeout;
+            if !is_open {
+                stats.report();
+            }
+            is_open
+        });
+    }
+}
+
+/// Returns the peer address of the plain or TLS connection.
+fn peer_addr(connection: &dyn Any) -> Option<SocketAddr> {
+    if let Some(stream) = connection.downcast_ref::<TcpStream>() {
+        stream.peer_addr().ok()
+    } else if let Some(stream) = connection.downcast_ref::<TlsStream<TcpStream>>() {
+        stream.get_ref().0.peer_addr().ok()
+    } else {
+        None
+    }
+}
+
+/// Loads the TLS config of the server from the PEM-encoded certificate chain and private key.
+/// Both HTTP/2 and HTTP/1.1 are offered to the clients via ALPN.
+pub fn tls_config(config: &RestApi) -> anyhow::Result<Option<ServerConfig>> {
+    let (cert_path, key_path) = match config.tls_files() {
+        Some(files) => files,
+        None => return Ok(None),
+    };
+
+    let cert_chain = certs(&mut BufReader::new(File::open(cert_path)?))
+        .map_err(|_| format_err!("Malformed TLS certificate chain in {}", cert_path))?;
+    let mut keys = pkcs8_private_keys(&mut BufReader::new(File::open(key_path)?))
+        .map_err(|_| format_err!("Malformed TLS private key in {}", key_path))?;
+    if keys.is_empty() {
+        keys = rsa_private_keys(&mut BufReader::new(File::open(key_path)?))
+            .map_err(|_| format_err!("Malformed TLS private key in {}", key_path))?;
+    }
+    let key = keys
+        .into_iter()
+        .next()
+        .ok_or_else(|| format_err!("No TLS private key found in {}", key_path))?;
+
+    let mut tls_config = ServerConfig::new(NoClientAuth::new());
+    tls_config.set_single_cert(cert_chain, key)?;
+    tls_config.set_protocols(&[b"h2".to_vec(), b"http/1.1".to_vec()]);
+    Ok(Some(tls_config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+    fn peer(port: u16) -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], port))
+    }
+
+    fn stats(registry: &ConnectionRegistry, port: u16) -> Option<ConnectionStats> {
+        registry
+            .connections
+            .lock()
+            .unwrap()
+            .get(&peer(port))
+            .cloned()
+    }
+
+    /// Checks that the requests are counted towards the connection they came from.
+    #[test]
+    fn requests_are_counted_per_connection() {
+        let registry = ConnectionRegistry::with_idle_timeout(IDLE_TIMEOUT);
+        let now = Instant::now();
+
+        registry.open(peer(1), now);
+        registry.open(peer(2), now);
+        for _ in 0..3 {
+            registry.touch(peer(1), now + Duration::from_secs(1));
+        }
+        registry.touch(peer(2), now + Duration::from_secs(2));
+
+        let first = stats(&registry, 1).unwrap();
+        assert_eq!(first.requests, 3);
+        assert_eq!(first.last_active_at, now + Duration::from_secs(1));
+        assert_eq!(stats(&registry, 2).unwrap().requests, 1);
+    }
+
+    /// Checks that the new connection from the same socket replaces the closed one.
+    #[test]
+    fn reused_socket_starts_new_connection() {
+        let registry = ConnectionRegistry::with_idle_timeout(IDLE_TIMEOUT);
+        let now = Instant::now();
+
+        registry.open(peer(1), now);
+        registry.touch(peer(1), now);
+        registry.open(peer(1), now + Duration::from_secs(1));
+
+        let stats = stats(&registry, 1).unwrap();
+        assert_eq!(stats.requests, 0);
+        assert_eq!(stats.opened_at, now + Duration::from_secs(1));
+    }
+
+    /// Checks that the connections idle for longer than the server keeps them alive are removed.
+    #[test]
+    fn idle_connections_are_closed() {
+        let registry = ConnectionRegistry::with_idle_timeout(IDLE_TIMEOUT);
+        let now = Instant::now();
+
+        registry.open(peer(1), now);
+        registry.open(peer(2), now);
+        registry.touch(peer(2), now + IDLE_TIMEOUT);
+        registry.open(peer(3), now + IDLE_TIMEOUT + Duration::from_secs(1));
+
+        assert!(stats(&registry, 1).is_none());
+        assert!(stats(&registry, 2).is_some());
+        assert!(stats(&registry, 3).is_some());
+    }
+
+    /// Checks that the requests of the connection considered closed start tracking it again.
+    #[test]
+    fn untracked_connection_is_restored() {
+        let registry = ConnectionRegistry::with_idle_timeout(IDLE_TIMEOUT);
+        let now = Instant::now();
+
+        registry.touch(peer(1), now);
+        assert_eq!(stats(&registry, 1).unwrap().requests, 1);
+    }
+
+    /// Checks that the connections of the unknown types are not tracked.
+    #[test]
+    fn unknown_connection_is_ignored() {
+        let registry = ConnectionRegistry::with_idle_timeout(IDLE_TIMEOUT);
+
+        registry.on_connect(&());
+        assert!(registry.connections.lock().unwrap().is_empty());
+    }
+}
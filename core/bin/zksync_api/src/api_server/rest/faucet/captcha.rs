@@ -0,0 +1,71 @@
+//! Verification of the captcha solved by the faucet users.
+
+// Built-in uses
+
+// External uses
+use serde::Deserialize;
+
+// Workspace uses
+use zksync_config::FaucetConfig;
+
+// Local uses
+
+#[async_trait::async_trait]
+pub trait CaptchaVerifier: Send + Sync {
+    /// Checks the captcha response submitted by the user with the given IP address.
+    async fn verify(&self, response: &str, remote_ip: &str) -> anyhow::Result<bool>;
+}
+
+/// Verifier calling the `siteverify` endpoint of the captcha service. The endpoint has
+/// the same interface in hCaptcha and reCAPTCHA.
+pub struct HttpCaptchaVerifier {
+    client: reqwest::Client,
+    verify_url: String,
+    secret: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct VerifyResponse {
+    success: bool,
+}
+
+impl HttpCaptchaVerifier {
+    pub fn new(config: &FaucetConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            verify_url: config.captcha_verify_url.clone(),
+            secret: config.captcha_secret.clone(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl CaptchaVerifier for HttpCaptchaVerifier {
+    async fn verify(&self, response: &str, remote_ip: &str) -> anyhow::Result<bool> {
+        let params = [
+            ("secret", self.secret.as_str()),
+            ("response", response),
+            ("remoteip", remote_ip),
+        ];
+        let response: VerifyResponse = self
+            .client
+            .post(&self.verify_url)
+            .form(&params)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(response.success)
+    }
+}
+
+/// Verifier used when the captcha is not required.
+pub struct NoCaptchaVerifier;
+
+#[async_trait::async_trait]
+impl CaptchaVerifier for NoCaptchaVerifier {
+    async fn verify(&self, _response: &str, _remote_ip: &str) -> anyhow::Result<bool> {
+        Ok(true)
+    }
+}
@@ -0,0 +1,14 @@
+// External uses
+use actix_web::{web, Scope};
+
+// Workspace uses
+
+// Local uses
+pub use self::v01::ApiFaucetData;
+
+mod captcha;
+mod v01;
+
+pub(crate) fn api_scope(data: ApiFaucetData) -> Scope {
+    web::scope("/api/faucet").service(v01::api_scope(data))
+}
@@ -0,0 +1,371 @@
+//! Test tokens faucet part of API implementation.
+//!
+//! The faucet sends a fixed amount of the requested token to the requesting address with
+//! a transfer signed by the faucet account. The number of requests is limited per recipient
+//! address and per IP address, and the requests may be required to be confirmed by a captcha.
+//! The quotas are consumed by the sent transfers only.
+//!
+//! The IP address of the client is the address of the peer, unless the peer is the configured
+//! trusted proxy: the proxy headers can be forged by the clients.
+//!
+//! Transfers are sent directly to the mempool without a fee. The nonce of the faucet account
+//! is tracked in memory, so the faucet must only be enabled on a single API server replica.
+
+// Built-in uses
+use std::{net::IpAddr, sync::Arc, time::Instant};
+
+// External uses
+use actix_web::{
+    web::{self, Json},
+    HttpRequest, Scope,
+};
+use bigdecimal::BigDecimal;
+use num::BigUint;
+use tokio::sync::Mutex;
+
+// Workspace uses
+pub use zksync_api_client::rest::faucet::{
+    FaucetInfo, FaucetRequest, FaucetResponse, FaucetStatus,
+};
+use zksync_config::{FaucetConfig, ZkSyncConfig};
+use zksync_crypto::{convert::FeConvert, priv_key_from_fs, Fs, PrivateKey};
+use zksync_storage::ConnectionPool;
+use zksync_types::{
+    helpers::closest_packable_token_amount,
+    token_amount::token_unit,
+    tx::{TimeRange, TxHash},
+    AccountId, Address, Nonce, SignedZkSyncTx, TokenId, Transfer, ZkSyncTx,
+};
+
+// Local uses
+use super::captcha::{CaptchaVerifier, HttpCaptchaVerifier, NoCaptchaVerifier};
+use crate::{
+    api_server::rest::v1::{Error as ApiError, JsonResult},
+    core_api_client::CoreApiClient,
+    utils::shared_counters::SharedCounters,
+};
+
+struct FaucetAccount {
+    address: Address,
+    private_key: PrivateKey,
+    /// ID and the next nonce of the account. Loaded from the database on the first
+    /// transfer and after the rejected ones.
+    state: Mutex<Option<(AccountId, Nonce)>>,
+}
+
+/// Shared data between `/api/faucet/v0.1/` endpoints.
+#[derive(Clone)]
+pub struct ApiFaucetData {
+    connection_pool: ConnectionPool,
+    core_api_client: CoreApiClient,
+    config: FaucetConfig,
+    /// Numbers of the requests per recipient and per IP address.
+    quotas: SharedCounters,
+    captcha_verifier: Arc<dyn CaptchaVerifier>,
+    account: Arc<FaucetAccount>,
+}
+
+impl ApiFaucetData {
+    /// Creates the faucet data. It has to be shared by all the server workers,
+    /// since the nonce of the faucet account is tracked in memory.
+    pub fn new(connection_pool: ConnectionPool, config: &ZkSyncConfig) -> Self {
+        let faucet_config = config.faucet.clone();
+        let private_key = Fs::from_hex(&faucet_config.account_private_key)
+            .map(priv_key_from_fs)
+            .expect("Invalid faucet account private key");
+        let captcha_verifier: Arc<dyn CaptchaVerifier> = if faucet_config.captcha_required() {
+            Arc::new(HttpCaptchaVerifier::new(&faucet_config))
+        } else {
+            Arc::new(NoCaptchaVerifier)
+        };
+
+        Self {
+            core_api_client: CoreApiClient::new(config.api.private.url.clone()),
+            quotas: SharedCounters::new(connection_pool.clone(), &config.api.common),
+            account: Arc::new(FaucetAccount {
+                address: faucet_config.account_address,
+                private_key,
+                state: Mutex::new(None),
+            }),
+            connection_pool,
+            captcha_verifier,
+            config: faucet_config,
+        }
+    }
+
+    /// Returns the daily quotas the request is counted against, along with their limits.
+    fn quotas(&self, to: Address, remote_ip: IpAddr) -> [(String, u64); 2] {
+        [
+            (
+                format!("faucet:address:{:x}", to),
+                self.config.requests_per_address,
+            ),
+            (
+                format!("faucet:ip:{}", remote_ip),
+                self.config.requests_per_ip,
+            ),
+        ]
+    }
+
+    async fn check_quotas(&self, quotas: &[(String, u64)]) -> Result<(), ApiError> {
+        for (key, limit) in quotas {
+            let requests = self.quotas.load(key).await.map_err(ApiError::internal)?;
+            if requests >= BigDecimal::from(*limit) {
+                metrics::counter!("api.faucet.quota_exceeded", 1);
+                return Err(ApiError::too_many_requests("Faucet quota exceeded"));
+            }
+        }
+        Ok(())
+    }
+
+    async fn consume_quotas(&self, quotas: &[(String, u64)]) -> Result<(), ApiError> {
+        for (key, _) in quotas {
+            self.quotas
+                .increment(key, BigDecimal::from(1), chrono::Duration::days(1))
+                .await
+                .map_err(ApiError::internal)?;
+        }
+        Ok(())
+    }
+
+    async fn load_account_state(&self) -> anyhow::Result<(AccountId, Nonce)> {
+        let mut storage = self.connection_pool.access_storage().await?;
+        let state = storage
+            .chain()
+            .account_schema()
+            .account_state_by_address(self.account.address)
+            .await?;
+
+        state
+            .committed
+            .map(|(account_id, account)| (account_id, account.nonce))
+            .ok_or_else(|| anyhow::format_err!("Faucet account doesn't exist"))
+    }
+
+    /// Sends the transfer if the quotas allow it, and counts it against them once it's accepted.
+    async fn send_transfer(
+        &self,
+        to: Address,
+        token: TokenId,
+        amount: BigUint,
+        quotas: &[(String, u64)],
+    ) -> Result<TxHash, ApiError> {
+        // Transfers are sent one by one to keep the nonces correct, so the quotas
+        // can't be exceeded by the concurrent requests either.
+        let mut state = self.account.state.lock().await;
+        self.check_quotas(quotas).await?;
+        let (account_id, nonce) = match *state {
+            Some(state) => state,
+            None => self
+                .load_account_state()
+                .await
+                .map_err(ApiError::internal)?,
+        };
+
+        let transfer = Transfer::new_signed(
+            account_id,
+            self.account.address,
+            to,
+            token,
+            amount,
+            BigUint::from(0u32),
+            nonce,
+            TimeRange::default(),
+            &self.account.private_key,
+        )
+        .map_err(ApiError::internal)?;
+        let tx = SignedZkSyncTx {
+            tx: ZkSyncTx::Transfer(Box::new(transfer)),
+            eth_sign_data: None,
+        };
+        let tx_hash = tx.tx.hash();
+
+        let result = self.core_api_client.send_tx(tx).await;
+        *state = match &result {
+            Ok(Ok(())) => Some((account_id, nonce + 1)),
+            _ => None,
+        };
+        result
+            .map_err(ApiError::internal)?
+            .map_err(ApiError::internal)?;
+
+        self.consume_quotas(quotas).await?;
+        Ok(tx_hash)
+    }
+}
+
+/// Returns the IP address of the client. The `X-Forwarded-For` header is only taken into account
+/// for the requests coming from the trusted proxy, and only its last address is used, since that's
+/// the one appended by the proxy, while the rest may be forged by the client.
+fn remote_ip(request: &HttpRequest, trusted_proxy: Option<IpAddr>) -> Option<IpAddr> {
+    let peer_ip = request.peer_addr()?.ip();
+    if trusted_proxy != Some(peer_ip) {
+        return Some(peer_ip);
+    }
+
+    let forwarded_ip = request
+        .headers()
+        .get_all("x-forwarded-for")
+        .last()
+        .and_then(|header| header.to_str().ok())
+        .and_then(|header| header.rsplit(',').next())
+        .and_then(|ip| ip.trim().parse().ok());
+    Some(forwarded_ip.unwrap_or(peer_ip))
+}
+
+async fn get_status(data: web::Data<ApiFaucetData>) -> JsonResult<FaucetStatus> {
+    let response = if data.config.enabled {
+        FaucetStatus::Enabled(FaucetInfo {
+            tokens: data.config.tokens.clone(),
+            amount: data.config.amount,
+            captcha_required: data.config.captcha_required(),
+        })
+    } else {
+        FaucetStatus::Disabled
+    };
+
+    Ok(Json(response))
+}
+
+async fn request_tokens(
+    data: web::Data<ApiFaucetData>,
+    request: HttpRequest,
+    params: web::Json<FaucetRequest>,
+) -> JsonResult<FaucetResponse> {
+    let start = Instant::now();
+    let params = params.into_inner();
+    let remote_ip = remote_ip(&request, data.config.trusted_proxy())
+        .ok_or_else(|| ApiError::bad_request("Unknown client address"))?;
+
+    let token = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?
+        .tokens_schema()
+        .get_token(params.token)
+        .await
+        .map_err(ApiError::internal)?
+        .ok_or_else(|| ApiError::bad_request("Unknown token"))?;
+    if !data.config.tokens.is_empty() && !data.config.tokens.contains(&token.symbol) {
+        return Err(ApiError::bad_request(
+            "Token is not dispensed by the faucet",
+        ));
+    }
+
+    if data.config.captcha_required() {
+        let captcha = params
+            .captcha
+            .as_deref()
+            .ok_or_else(|| ApiError::bad_request("Captcha is required"))?;
+        let is_valid = data
+            .captcha_verifier
+            .verify(captcha, &remote_ip.to_string())
+            .await
+            .map_err(ApiError::internal)?;
+        if !is_valid {
+            return Err(ApiError::bad_request("Captcha is invalid"));
+        }
+    }
+
+    let quotas = data.quotas(params.address, remote_ip);
+    // The quotas are checked before sending the transfer as well,
+    // so the exhausted ones don't wait for the other transfers.
+    data.check_quotas(&quotas).await?;
+
+    let amount = BigUint::from(data.config.amount) * token_unit(token.decimals);
+    let amount = closest_packable_token_amount(&amount);
+    let tx_hash = data
+        .send_transfer(params.address, token.id, amount.clone(), &quotas)
+        .await?;
+
+    let formatted_amount = token.format_amount(&amount);
+    vlog::info!(
+        "Faucet sent {} {} to {:?}, tx: {}",
+        formatted_amount,
+        token.symbol,
+        params.address,
+        tx_hash
+    );
+    metrics::histogram!("api.faucet.v01.request_tokens", start.elapsed());
+    Ok(Json(FaucetResponse {
+        tx_hash,
+        amount,
+        formatted_amount,
+    }))
+}
+
+pub fn api_scope(data: ApiFaucetData) -> Scope {
+    let is_enabled = data.config.enabled;
+
+    // `status` endpoint should always be there
+    let scope = web::scope("v0.1")
+        .data(data)
+        .route("status", web::get().to(get_status));
+
+    if is_enabled {
+        scope.route("request", web::post().to(request_tokens))
+    } else {
+        scope
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::v1::test_utils::TestServerConfig;
+    use actix_web::test::TestRequest;
+
+    /// Checks that the proxy headers are only trusted for the requests of the trusted proxy.
+    #[test]
+    fn client_ip() {
+        let proxy: IpAddr = "10.0.0.1".parse().unwrap();
+        let client: IpAddr = "1.2.3.4".parse().unwrap();
+        let request = |peer: &str| {
+            TestRequest::default()
+                .peer_addr(peer.parse().unwrap())
+                .header("x-forwarded-for", "5.6.7.8, 1.2.3.4")
+                .to_http_request()
+        };
+
+        assert_eq!(
+            remote_ip(&request("10.0.0.1:80"), Some(proxy)),
+            Some(client)
+        );
+        assert_eq!(remote_ip(&request("10.0.0.1:80"), None), Some(proxy));
+        assert_eq!(
+            remote_ip(&request("9.9.9.9:80"), Some(proxy)),
+            Some("9.9.9.9".parse().unwrap())
+        );
+        assert_eq!(
+            remote_ip(&TestRequest::default().to_http_request(), Some(proxy)),
+            None
+        );
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn test_disabled_faucet() -> anyhow::Result<()> {
+        let mut cfg = TestServerConfig::default();
+        cfg.config.faucet.enabled = false;
+        let (client, server) = cfg
+            .start_server_with_scope(String::from("api/faucet"), move |cfg| {
+                api_scope(ApiFaucetData::new(cfg.pool.clone(), &cfg.config))
+            });
+
+        assert_eq!(client.get_faucet_status().await?, FaucetStatus::Disabled);
+
+        let request = FaucetRequest {
+            address: Address::repeat_byte(1),
+            token: TokenId(0).into(),
+            captcha: None,
+        };
+        assert!(client.request_test_tokens(request).await.is_err());
+
+        server.stop().await;
+        Ok(())
+    }
+}
@@ -1,7 +1,12 @@
 //! Utilities for the REST API.
 
 use crate::core_api_client::EthBlockId;
-use actix_web::{HttpResponse, Result as ActixResult};
+use actix_web::{
+    http::header::{CACHE_CONTROL, ETAG, IF_NONE_MATCH},
+    web::Bytes,
+    HttpRequest, HttpResponse, Result as ActixResult,
+};
+use serde::Serialize;
 use std::collections::HashMap;
 use zksync_storage::chain::{
     block::records::BlockDetails,
@@ -161,3 +166,85 @@ pub async fn parse_tx_id(
 
     Ok((parts[0], parts[1]))
 }
+
+/// `Cache-Control` value for the data that can't change anymore.
+const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// Serialized response with the data that can't change anymore (e.g. verified block).
+///
+/// Such responses are served with the long-lived `Cache-Control` header, so the clients and proxies
+/// may cache them, and with the `ETag` header, so the clients that already have the data receive
+/// an empty `304 Not Modified` response.
+#[derive(Debug, Clone)]
+pub struct ImmutableResponse {
+    body: Bytes,
+    etag: String,
+}
+
+impl ImmutableResponse {
+    pub fn new(value: &impl Serialize) -> serde_json::Result<Self> {
+        let body = serde_json::to_vec(value)?;
+        let etag = format!("\"{}\"", hex::encode(&tiny_keccak::keccak256(&body)[..16]));
+        Ok(Self {
+            body: body.into(),
+            etag,
+        })
+    }
+
+    pub fn respond_to(&self, req: &HttpRequest) -> HttpResponse {
+        let not_modified = req
+            .headers()
+            .get_all(IF_NONE_MATCH)
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|etag| etag.trim() == self.etag || etag.trim() == "*");
+        let mut response = if not_modified {
+            HttpResponse::NotModified()
+        } else {
+            HttpResponse::Ok()
+        };
+        response
+            .header(CACHE_CONTROL, IMMUTABLE_CACHE_CONTROL)
+            .header(ETAG, self.etag.as_str());
+
+        if not_modified {
+            response.finish()
+        } else {
+            response
+                .content_type("application/json")
+                .body(self.body.clone())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http::StatusCode, test::TestRequest};
+
+    #[test]
+    fn immutable_response_etag() {
+        let response = ImmutableResponse::new(&serde_json::json!({ "block_number": 1 })).unwrap();
+
+        let req = TestRequest::default().to_http_request();
+        let resp = response.respond_to(&req);
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(CACHE_CONTROL).unwrap(),
+            IMMUTABLE_CACHE_CONTROL
+        );
+        let etag = resp.headers().get(ETAG).unwrap().clone();
+
+        // Client already has the same data.
+        let req = TestRequest::default()
+            .header(IF_NONE_MATCH, etag)
+            .to_http_request();
+        assert_eq!(response.respond_to(&req).status(), StatusCode::NOT_MODIFIED);
+
+        // Client has outdated data.
+        let req = TestRequest::default()
+            .header(IF_NONE_MATCH, "\"outdated\"")
+            .to_http_request();
+        assert_eq!(response.respond_to(&req).status(), StatusCode::OK);
+    }
+}
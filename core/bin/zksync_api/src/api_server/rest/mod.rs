@@ -1,32 +1,78 @@
-use actix_cors::Cors;
-use actix_web::{web, App, HttpResponse, HttpServer};
-use futures::channel::mpsc;
-use std::net::SocketAddr;
+use actix_web::{
+    dev::Service,
+    http::{
+        header::{HeaderName, HeaderValue},
+        KeepAlive,
+    },
+    web, App, HttpResponse, HttpServer,
+};
+use futures::{channel::mpsc, FutureExt};
+use std::{net::SocketAddr, time::Instant};
+use vlog::Instrument;
+use zksync_eth_client::EthereumGateway;
 use zksync_storage::ConnectionPool;
 use zksync_types::H160;
 
 use zksync_utils::panic_notify::ThreadPanicNotify;
 
-use self::v01::api_decl::ApiV01;
+use self::{
+    address_checksum::AddressChecksumGuard,
+    namespaces::NamespaceGuard,
+    v01::api_decl::ApiV01,
+    versioning::{cors, version_headers, ApiVersion},
+};
 use crate::{fee_ticker::TickerRequest, signature_checker::VerifySignatureRequest};
 
 use super::tx_sender::TxSender;
 use zksync_config::ZkSyncConfig;
 
+pub(crate) mod address_checksum;
+mod connections;
+mod faucet;
 mod forced_exit_requests;
 mod helpers;
+pub(crate) mod namespaces;
+pub(crate) mod response_signer;
 mod v01;
 pub mod v02;
 pub mod v1;
+mod versioning;
+
+/// Header carrying the correlation ID of the request.
+const REQUEST_ID_HEADER: &str = "x-request-id";
 
 async fn start_server(
     api_v01: ApiV01,
     fee_ticker: mpsc::Sender<TickerRequest>,
     sign_verifier: mpsc::Sender<VerifySignatureRequest>,
+    eth_gateway: EthereumGateway,
     bind_to: SocketAddr,
 ) {
-    HttpServer::new(move || {
+    // Faucet data is shared by all the workers to keep the nonce of the faucet account.
+    let faucet_data = faucet::ApiFaucetData::new(api_v01.connection_pool.clone(), &api_v01.config);
+    // Namespace quotas are shared by all the workers as well.
+    let namespace_guard = NamespaceGuard::new(
+        api_v01.connection_pool.clone(),
+        &api_v01.config.api.common,
+    );
+    // Snapshots are expensive to build, so they're shared by all the workers too.
+    let snapshots_data = v1::snapshots::ApiSnapshotsData::new(
+        api_v01.connection_pool.clone(),
+        api_v01.config.api.rest.snapshots_secret_auth.clone(),
+    );
+    let address_checksum_guard =
+        AddressChecksumGuard::new(api_v01.config.api.common.address_checksum);
+    let server_config = api_v01.config.api.rest.clone();
+    let tls_config =
+        connections::tls_config(&server_config).expect("Failed to load the REST API TLS config");
+    let connection_registry = connections::ConnectionRegistry::new(&server_config);
+    let on_connect_registry = connection_registry.clone();
+
+    let server = HttpServer::new(move || {
         let api_v01 = api_v01.clone();
+        let connection_registry = connection_registry.clone();
+
+        let rest_config = api_v01.config.api.rest.clone();
 
         let api_v1_scope = {
             let tx_sender = TxSender::new(
@@ -35,11 +81,23 @@ async fn start_server(
                 fee_ticker.clone(),
                 &api_v01.config,
             );
-            v1::api_scope(tx_sender, &api_v01.config)
+            v1::api_scope(
+                tx_sender,
+                &api_v01.config,
+                snapshots_data.clone(),
+                eth_gateway.clone(),
+            )
+            .wrap(namespace_guard.clone())
+            .wrap(cors(&rest_config.cors_allowed_origins))
+            .wrap(version_headers(ApiVersion::V1))
         };
 
         let forced_exit_requests_api_scope =
-            forced_exit_requests::api_scope(api_v01.connection_pool.clone(), &api_v01.config);
+            forced_exit_requests::api_scope(api_v01.connection_pool.clone(), &api_v01.config)
+                .wrap(cors(&rest_config.forced_exit_cors_allowed_origins));
+
+        let faucet_api_scope =
+            faucet::api_scope(faucet_data.clone()).wrap(cors(&rest_config.cors_allowed_origins));
 
         let api_v02_scope = {
             let tx_sender = TxSender::new(
@@ -49,14 +107,76 @@ async fn start_server(
                 &api_v01.config,
             );
             v02::api_scope(tx_sender, &api_v01.config)
+                .wrap(namespace_guard.clone())
+                .wrap(cors(&rest_config.cors_allowed_origins))
+                .wrap(version_headers(ApiVersion::V02))
         };
+
+        let api_v01_scope = api_v01
+            .into_scope()
+            .wrap(cors(&rest_config.cors_allowed_origins))
+            .wrap(version_headers(ApiVersion::V01));
+
         App::new()
-            .wrap(Cors::new().send_wildcard().max_age(3600).finish())
+            .wrap(address_checksum_guard)
             .wrap(vlog::actix_middleware())
-            .service(api_v01.into_scope())
+            // Report the latency of every request, labeled by the matched route.
+            .wrap_fn(move |req, srv| {
+                let start = Instant::now();
+                connection_registry.record_request(req.peer_addr(), req.version());
+                srv.call(req).map(move |res| {
+                    if let Ok(res) = &res {
+                        let path = res
+                            .request()
+                            .match_pattern()
+                            .unwrap_or_else(|| "unknown".to_string());
+                        metrics::histogram!(
+                            "api.rest.request",
+                            start.elapsed(),
+                            "path" => path,
+                            "status" => res.status().as_u16().to_string()
+                        );
+                    }
+                    res
+                })
+            })
+            // Attach the correlation ID to everything logged while handling the request.
+            // The ID provided by the client is reused, so requests can be traced through proxies.
+            .wrap_fn(|req, srv| {
+                let request_id = req
+                    .headers()
+                    .get(REQUEST_ID_HEADER)
+                    .and_then(|value| value.to_str().ok())
+                    .map(ToString::to_string)
+                    .unwrap_or_else(vlog::new_correlation_id);
+                let span = vlog::info_span!(
+                    "http_request",
+                    request_id = %request_id,
+                    method = %req.method(),
+                    path = %req.path()
+                );
+                // Continue the trace started by the client, if any.
+                let headers = req
+                    .headers()
+                    .iter()
+                    .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)));
+                vlog::set_remote_parent(&span, headers);
+                srv.call(req).instrument(span).map(move |res| {
+                    res.map(|mut res| {
+                        if let Ok(value) = HeaderValue::from_str(&request_id) {
+                            res.headers_mut()
+                                .insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+                        }
+                        res
+                    })
+                })
+            })
+            .service(api_v01_scope)
             .service(api_v1_scope)
             .service(forced_exit_requests_api_scope)
+            .service(faucet_api_scope)
             .service(api_v02_scope)
+            .service(response_signer::signer_info_resource(&api_v01.config))
             // Endpoint needed for js isReachable
             .route(
                 "/favicon.ico",
@@ -64,12 +184,29 @@ async fn start_server(
             )
     })
     .workers(super::THREADS_PER_SERVER)
-    .bind(bind_to)
-    .unwrap()
-    .shutdown_timeout(1)
-    .run()
-    .await
-    .expect("REST API server has crashed");
+    .keep_alive(match server_config.keep_alive_secs {
+        0 => KeepAlive::Disabled,
+        secs => KeepAlive::Timeout(secs as usize),
+    })
+    .client_timeout(server_config.client_timeout_ms)
+    .max_connections(server_config.max_connections)
+    .max_connection_rate(server_config.max_connection_rate)
+    .backlog(server_config.backlog)
+    .on_connect(move |connection, _| on_connect_registry.on_connect(connection));
+
+    let server = match tls_config {
+        Some(tls_config) => {
+            vlog::info!("REST API is served over TLS with HTTP/2 support");
+            server.bind_rustls(bind_to, tls_config)
+        }
+        None => server.bind(bind_to),
+    };
+    server
+        .unwrap()
+        .shutdown_timeout(1)
+        .run()
+        .await
+        .expect("REST API server has crashed");
 }
 
 /// Start HTTP REST API
@@ -81,6 +218,7 @@ pub(super) fn start_server_thread_detached(
     panic_notify: mpsc::Sender<bool>,
     fee_ticker: mpsc::Sender<TickerRequest>,
     sign_verifier: mpsc::Sender<VerifySignatureRequest>,
+    eth_gateway: EthereumGateway,
     config: ZkSyncConfig,
 ) {
     std::thread::Builder::new()
@@ -92,7 +230,7 @@ pub(super) fn start_server_thread_detached(
                 let api_v01 = ApiV01::new(connection_pool, contract_address, config.clone());
                 api_v01.spawn_network_status_updater(panic_notify);
 
-                start_server(api_v01, fee_ticker, sign_verifier, listen_addr).await;
+                start_server(api_v01, fee_ticker, sign_verifier, eth_gateway, listen_addr).await;
             });
         })
         .expect("Api server thread");
@@ -0,0 +1,235 @@
+//! API namespaces of the white-label frontends.
+//!
+//! Requests carrying the API key in the `zksync-api-key` header are attributed to the namespace
+//! of the key and counted against its per-minute quota, the requests with an unknown key are
+//! rejected. The resolved namespace is stored in the request extensions, so the handlers can
+//! apply its settings, e.g. the fee markup. Requests without the header are served as before.
+//!
+//! The JSON RPC API resolves the namespaces with the same guard, see `rpc_server::namespaces`.
+
+// Built-in uses
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+// External uses
+use actix_web::{
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    HttpMessage, HttpRequest,
+};
+use bigdecimal::BigDecimal;
+use futures::future::{ok, FutureExt, LocalBoxFuture, Ready};
+use thiserror::Error;
+
+// Workspace uses
+use zksync_config::configs::api::Common;
+use zksync_storage::ConnectionPool;
+use zksync_types::{api_error::ApiErrorCode, api_namespace::ApiNamespace, H256};
+
+// Local uses
+use super::v1::ApiError;
+use crate::utils::shared_counters::SharedCounters;
+
+/// Header carrying the API key of the namespace.
+pub(crate) const API_KEY_HEADER: &str = "zksync-api-key";
+/// Namespaces are cached for this time, so the updated quotas are applied within it.
+const NAMESPACES_CACHE_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Error)]
+pub(crate) enum NamespaceError {
+    #[error("Unknown API key")]
+    UnknownApiKey,
+    #[error("Namespace quota is exceeded")]
+    QuotaExceeded,
+    #[error("Internal error: {0}")]
+    Internal(anyhow::Error),
+}
+
+impl From<NamespaceError> for ApiError {
+    fn from(err: NamespaceError) -> Self {
+        match err {
+            NamespaceError::UnknownApiKey => ApiError::unauthorized(err.to_string()),
+            NamespaceError::QuotaExceeded => ApiError::too_many_requests(err.to_string())
+                .error_code(ApiErrorCode::RateLimitExceeded)
+                .retry_after(60),
+            NamespaceError::Internal(err) => ApiError::internal(err),
+        }
+    }
+}
+
+/// Returns the namespace the request was made in, if any.
+pub(crate) fn request_namespace(req: &HttpRequest) -> Option<ApiNamespace> {
+    req.extensions().get::<ApiNamespace>().cloned()
+}
+
+/// Middleware resolving the namespaces of the requests and enforcing their quotas.
+/// The quotas are shared by all the workers, so the guard must be created once per server.
+/// Only the existing namespaces are cached, so the cache is bounded by their number.
+#[derive(Debug, Clone)]
+pub(crate) struct NamespaceGuard {
+    pool: ConnectionPool,
+    counters: SharedCounters,
+    /// Namespaces by the API key hashes.
+    cache: Arc<Mutex<HashMap<H256, (ApiNamespace, Instant)>>>,
+}
+
+impl NamespaceGuard {
+    pub fn new(pool: ConnectionPool, config: &Common) -> Self {
+        Self {
+            counters: SharedCounters::new(pool.clone(), config),
+            pool,
+            cache: Arc::default(),
+        }
+    }
+
+    async fn resolve(&self, api_key: &str) -> anyhow::Result<Option<ApiNamespace>> {
+        let api_key_hash = ApiNamespace::api_key_hash(api_key);
+        if let Some((namespace, cached_at)) = self
+            .cache
+            .lock()
+            .expect("namespaces cache lock")
+            .get(&api_key_hash)
+        {
+            if cached_at.elapsed() < NAMESPACES_CACHE_TTL {
+                return Ok(Some(namespace.clone()));
+            }
+        }
+
+        let mut storage = self.pool.access_storage().await?;
+        let namespace = storage
+            .api_namespaces_schema()
+            .get_namespace_by_key(api_key_hash)
+            .await?;
+        // The unknown keys are not cached: anyone can send arbitrarily many of them.
+        let mut cache = self.cache.lock().expect("namespaces cache lock");
+        match &namespace {
+            Some(namespace) => {
+                cache.insert(api_key_hash, (namespace.clone(), Instant::now()));
+            }
+            None => {
+                cache.remove(&api_key_hash);
+            }
+        }
+        Ok(namespace)
+    }
+
+    /// Returns the namespace of the API key, or an error if the key is unknown
+    /// or the quota of the namespace is exceeded.
+    pub(crate) async fn authorize_key(
+        &self,
+        api_key: Option<&str>,
+    ) -> Result<Option<ApiNamespace>, NamespaceError> {
+        let api_key = match api_key {
+            Some(api_key) => api_key,
+            None => return Ok(None),
+        };
+        let namespace = self
+            .resolve(api_key)
+            .await
+            .map_err(NamespaceError::Internal)?
+            .ok_or(NamespaceError::UnknownApiKey)?;
+
+        metrics::counter!(
+            "api.namespace_requests",
+            1,
+            "namespace" => namespace.name.clone()
+        );
+        if namespace.requests_per_minute > 0 {
+            let requests = self
+                .counters
+                .increment(
+                    &format!("namespace:{}", namespace.name),
+                    BigDecimal::from(1),
+                    chrono::Duration::minutes(1),
+                )
+                .await
+                .map_err(NamespaceError::Internal)?;
+            if requests > BigDecimal::from(namespace.requests_per_minute) {
+                metrics::counter!(
+                    "api.namespace_rate_limited",
+                    1,
+                    "namespace" => namespace.name.clone()
+                );
+                return Err(NamespaceError::QuotaExceeded);
+            }
+        }
+        Ok(Some(namespace))
+    }
+
+    /// Returns the namespace of the request, or an error if the API key is unknown
+    /// or the quota of the namespace is exceeded.
+    async fn authorize(&self, req: &ServiceRequest) -> Result<Option<ApiNamespace>, ApiError> {
+        let api_key = match req.headers().get(API_KEY_HEADER) {
+            Some(api_key) => Some(
+                api_key
+                    .to_str()
+                    .map_err(|_| ApiError::unauthorized("Invalid API key"))?,
+            ),
+            None => None,
+        };
+        Ok(self.authorize_key(api_key).await?)
+    }
+}
+
+impl<S, B> Transform<S> for NamespaceGuard
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>
+        + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type InitError = ();
+    type Transform = NamespaceGuardMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(NamespaceGuardMiddleware {
+            service: Rc::new(RefCell::new(service)),
+            guard: self.clone(),
+        })
+    }
+}
+
+pub(crate) struct NamespaceGuardMiddleware<S> {
+    service: Rc<RefCell<S>>,
+    guard: NamespaceGuard,
+}
+
+impl<S, B> Service for NamespaceGuardMiddleware<S>
+where
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>
+        + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.borrow_mut().poll_ready(cx)
+    }
+
+    fn call(&mut self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let guard = self.guard.clone();
+        async move {
+            if let Some(namespace) = guard.authorize(&req).await? {
+                req.extensions_mut().insert(namespace);
+            }
+            // The service must not stay borrowed while the request is being handled.
+            let response = service.borrow_mut().call(req);
+            response.await
+        }
+        .boxed_local()
+    }
+}
@@ -0,0 +1,161 @@
+//! Signing of the critical API responses by the operator key.
+//!
+//! See `zksync_api_client::rest::v1::signed_responses` for the format of the signed responses.
+
+// Built-in uses
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// External uses
+use actix_web::{web, HttpRequest, HttpResponse, Resource};
+use serde::Serialize;
+
+// Workspace uses
+use zksync_api_client::rest::v1::{
+    canonical_json, request_hash, response_signature_message, ResponseSignerInfo,
+    RESPONSE_SIGNATURE_HEADER, RESPONSE_SIGNER_PATH, RESPONSE_TIMESTAMP_HEADER,
+};
+use zksync_config::ZkSyncConfig;
+use zksync_types::{tx::PackedEthSignature, Address, H256};
+
+// Local uses
+use super::v1::{Error as ApiError, JsonResult};
+
+#[derive(Debug, Clone)]
+pub struct ResponseSigner {
+    private_key: H256,
+    address: Address,
+}
+
+impl ResponseSigner {
+    /// Creates the signer if the responses signing is enabled.
+    pub fn from_config(config: &ZkSyncConfig) -> Option<Self> {
+        if !config.api.rest.sign_responses {
+            return None;
+        }
+
+        let private_key = config.api.rest.response_signing_key;
+        let address = PackedEthSignature::address_from_private_key(&private_key)
+            .expect("Invalid response signing key");
+        Some(Self {
+            private_key,
+            address,
+        })
+    }
+
+    /// Serializes the value into the canonical JSON and signs it along with the hash
+    /// of the request and the timestamp.
+    fn sign<T: Serialize>(
+        &self,
+        request_hash: H256,
+        timestamp: u64,
+        value: &T,
+    ) -> anyhow::Result<(String, PackedEthSignature)> {
+        let body = canonical_json(&serde_json::to_value(value)?);
+        let message = response_signature_message(request_hash, timestamp, body.as_bytes());
+        let signature = PackedEthSignature::sign(&self.private_key, &message)?;
+        Ok((body, signature))
+    }
+}
+
+/// Responds to the request with the JSON value signed by the operator if the signer is configured.
+pub fn signed_json<T: Serialize>(
+    signer: Option<&ResponseSigner>,
+    req: &HttpRequest,
+    value: &T,
+) -> Result<HttpResponse, ApiError> {
+    let signer = match signer {
+        Some(signer) => signer,
+        None => return Ok(HttpResponse::Ok().json(value)),
+    };
+
+    let path_and_query = req
+        .uri()
+        .path_and_query()
+        .map(|path_and_query| path_and_query.as_str())
+        .unwrap_or_else(|| req.path());
+    let request_hash = request_hash(req.method().as_str(), path_and_query);
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Incorrect system time")
+        .as_secs();
+    let (body, signature) = signer
+        .sign(request_hash, timestamp, value)
+        .map_err(ApiError::internal)?;
+    Ok(HttpResponse::Ok()
+        .content_type("application/json")
+        .header(
+            RESPONSE_SIGNATURE_HEADER,
+            format!("0x{}", hex::encode(&signature.serialize_packed()[..])),
+        )
+        .header(RESPONSE_TIMESTAMP_HEADER, timestamp.to_string())
+        .body(body))
+}
+
+async fn response_signer_info(
+    signer: web::Data<Option<ResponseSigner>>,
+) -> JsonResult<ResponseSignerInfo> {
+    let signer = signer
+        .get_ref()
+        .as_ref()
+        .ok_or_else(|| ApiError::not_found("Responses are not signed"))?;
+    Ok(web::Json(ResponseSignerInfo {
+        address: signer.address,
+    }))
+}
+
+/// Well-known endpoint publishing the address of the responses signer.
+pub fn signer_info_resource(config: &ZkSyncConfig) -> Resource {
+    web::resource(RESPONSE_SIGNER_PATH)
+        .data(ResponseSigner::from_config(config))
+        .route(web::get().to(response_signer_info))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_api_client::rest::v1::{recover_response_signer, request_hash};
+
+    #[test]
+    fn signed_response_is_verifiable() {
+        let signer = ResponseSigner {
+            private_key: H256::repeat_byte(0x11),
+            address: PackedEthSignature::address_from_private_key(&H256::repeat_byte(0x11))
+                .unwrap(),
+        };
+        let value = serde_json::json!({ "nonce": 1, "balances": { "ETH": "100" } });
+        let request = request_hash("get", "/api/v1/accounts/1");
+        assert_eq!(request, request_hash("GET", "/api/v1/accounts/1"));
+        let timestamp = 1_600_000_000;
+
+        let (body, signature) = signer.sign(request, timestamp, &value).unwrap();
+        assert_eq!(body, r#"{"balances":{"ETH":"100"},"nonce":1}"#);
+        let signature = format!("0x{}", hex::encode(&signature.serialize_packed()[..]));
+        assert_eq!(
+            recover_response_signer(request, timestamp, body.as_bytes(), &signature),
+            Some(signer.address)
+        );
+        // The signature doesn't match another body, request or timestamp.
+        assert_ne!(
+            recover_response_signer(
+                request,
+                timestamp,
+                br#"{"balances":{"ETH":"101"},"nonce":1}"#,
+                &signature
+            ),
+            Some(signer.address)
+        );
+        assert_ne!(
+            recover_response_signer(
+                request_hash("GET", "/api/v1/accounts/2"),
+                timestamp,
+                body.as_bytes(),
+                &signature
+            ),
+            Some(signer.address)
+        );
+        assert_ne!(
+            recover_response_signer(request, timestamp + 1, body.as_bytes(), &signature),
+            Some(signer.address)
+        );
+    }
+}
@@ -4,11 +4,13 @@ use crate::{
     api_server::rest::{
         helpers::*,
         v01::{caches::Caches, network_status::SharedNetworkStatus},
+        versioning::ApiVersion,
     },
     core_api_client::{CoreApiClient, EthBlockId},
 };
-use actix_web::{web, HttpResponse, Result as ActixResult};
+use actix_web::{web, HttpRequest, HttpResponse, Result as ActixResult};
 use futures::channel::mpsc;
+use serde::Serialize;
 use zksync_config::ZkSyncConfig;
 use zksync_storage::{
     chain::{
@@ -53,7 +55,7 @@ impl ApiV01 {
 
     /// Creates an actix-web `Scope`, which can be mounted to the Http server.
     pub fn into_scope(self) -> actix_web::Scope {
-        web::scope("/api/v0.1")
+        web::scope(ApiVersion::V01.prefix())
             .data(self)
             .route("/testnet_config", web::get().to(Self::testnet_config))
             .route("/status", web::get().to(Self::status))
@@ -115,6 +117,35 @@ impl ApiV01 {
         HttpResponse::InternalServerError().finish()
     }
 
+    /// Returns the cached response if the requested data can't change anymore.
+    pub(crate) fn cached_response(&self, req: &HttpRequest) -> Option<HttpResponse> {
+        self.caches
+            .immutable_responses
+            .get(&req.uri().to_string())
+            .map(|response| response.respond_to(req))
+    }
+
+    /// Responds with the data, caching the response if the data can't change anymore.
+    pub(crate) fn json_response(
+        &self,
+        req: &HttpRequest,
+        value: &impl Serialize,
+        immutable: bool,
+    ) -> ActixResult<HttpResponse> {
+        if !immutable {
+            return Ok(HttpResponse::Ok().json(value));
+        }
+
+        let response = ImmutableResponse::new(value).map_err(|err| {
+            vlog::warn!("Cannot serialize the response: '{}';", err);
+            HttpResponse::InternalServerError().finish()
+        })?;
+        self.caches
+            .immutable_responses
+            .insert(req.uri().to_string(), response.clone());
+        Ok(response.respond_to(req))
+    }
+
     // Spawns future updating SharedNetworkStatus in the current `actix::System`
     pub fn spawn_network_status_updater(&self, panic_notify: mpsc::Sender<bool>) {
         self.network_status
@@ -245,6 +276,18 @@ impl ApiV01 {
         Ok(blocks.pop())
     }
 
+    /// Returns `true` if the block exists and is verified.
+    pub async fn is_block_verified(
+        &self,
+        block_id: BlockNumber,
+    ) -> Result<bool, actix_web::error::Error> {
+        let block = self.get_block_info(block_id).await?;
+        Ok(matches!(
+            block,
+            Some(block) if block.block_number == *block_id as i64 && block_verified(&block)
+        ))
+    }
+
     pub async fn get_block_by_height_or_hash(
         &self,
         query: String,
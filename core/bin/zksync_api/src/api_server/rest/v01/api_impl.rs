@@ -7,11 +7,13 @@
 use crate::api_server::{
     helpers::try_parse_hash,
     rest::{
-        helpers::{deposit_op_to_tx_by_hash, parse_tx_id, priority_op_to_tx_history},
+        helpers::{
+            block_verified, deposit_op_to_tx_by_hash, parse_tx_id, priority_op_to_tx_history,
+        },
         v01::{api_decl::ApiV01, types::*},
     },
 };
-use actix_web::{web, HttpResponse, Result as ActixResult};
+use actix_web::{web, HttpRequest, HttpResponse, Result as ActixResult};
 use num::{rational::Ratio, BigUint, FromPrimitive};
 use std::time::Instant;
 use zksync_storage::chain::operations_ext::SearchDirection;
@@ -175,11 +177,16 @@ impl ApiV01 {
 
     pub async fn tx_history_older_than(
         self_: web::Data<Self>,
+        req: HttpRequest,
         web::Path(address): web::Path<Address>,
         web::Query(query): web::Query<TxHistoryQuery>,
     ) -> ActixResult<HttpResponse> {
         let start = Instant::now();
-        let tx_id = query.tx_id.as_ref().map(|s| s.as_ref()).unwrap_or("-");
+        if let Some(response) = self_.cached_response(&req) {
+            metrics::histogram!("api.v01.tx_history_older_than", start.elapsed());
+            return Ok(response);
+        }
+        let tx_id_str = query.tx_id.as_deref().unwrap_or("-");
         let limit = query.limit.unwrap_or(MAX_LIMIT);
 
         const MAX_LIMIT: u64 = 100;
@@ -189,7 +196,7 @@ impl ApiV01 {
         let mut storage = self_.access_storage().await?;
         let mut transaction = storage.start_transaction().await.map_err(Self::db_error)?;
 
-        let tx_id = parse_tx_id(&tx_id, &mut transaction).await?;
+        let tx_id = parse_tx_id(tx_id_str, &mut transaction).await?;
 
         let direction = SearchDirection::Older;
         let transactions_history = transaction
@@ -210,8 +217,13 @@ impl ApiV01 {
 
         transaction.commit().await.map_err(Self::db_error)?;
 
+        // Older transactions can't be added to the page, so once all of them are verified,
+        // the page can't change anymore (unless it starts from the latest transaction).
+        let immutable =
+            !matches!(tx_id_str, "" | "-") && transactions_history.iter().all(|item| item.verified);
+        let response = self_.json_response(&req, &transactions_history, immutable);
         metrics::histogram!("api.v01.tx_history_older_than", start.elapsed());
-        ok_json!(transactions_history)
+        response
     }
 
     pub async fn tx_history_newer_than(
@@ -310,9 +322,14 @@ impl ApiV01 {
 
     pub async fn executed_tx_by_hash(
         self_: web::Data<Self>,
+        req: HttpRequest,
         web::Path(tx_hash_hex): web::Path<String>,
     ) -> ActixResult<HttpResponse> {
         let start = Instant::now();
+        if let Some(response) = self_.cached_response(&req) {
+            metrics::histogram!("api.v01.executed_tx_by_hash", start.elapsed());
+            return Ok(response);
+        }
         if tx_hash_hex.len() < 2 {
             return Err(HttpResponse::BadRequest().finish().into());
         }
@@ -321,8 +338,10 @@ impl ApiV01 {
 
         let tx_receipt = self_.get_tx_receipt(transaction_hash).await?;
 
+        let immutable = matches!(&tx_receipt, Some(receipt) if receipt.verified);
+        let response = self_.json_response(&req, &tx_receipt, immutable);
         metrics::histogram!("api.v01.executed_tx_by_hash", start.elapsed());
-        ok_json!(tx_receipt)
+        response
     }
 
     pub async fn tx_by_hash(
@@ -400,13 +419,19 @@ impl ApiV01 {
 
     pub async fn block_tx(
         self_: web::Data<Self>,
+        req: HttpRequest,
         web::Path((block_id, tx_id)): web::Path<(BlockNumber, u32)>,
     ) -> ActixResult<HttpResponse> {
         let start = Instant::now();
+        if let Some(response) = self_.cached_response(&req) {
+            metrics::histogram!("api.v01.block_tx", start.elapsed());
+            return Ok(response);
+        }
         let exec_ops = self_.get_block_executed_ops(block_id).await?;
 
         let result = if let Some(exec_op) = exec_ops.get(tx_id as usize) {
-            ok_json!(exec_op.clone())
+            let immutable = self_.is_block_verified(block_id).await?;
+            self_.json_response(&req, exec_op, immutable)
         } else {
             Err(HttpResponse::NotFound().finish().into())
         };
@@ -449,12 +474,18 @@ impl ApiV01 {
 
     pub async fn block_by_id(
         self_: web::Data<Self>,
+        req: HttpRequest,
         web::Path(block_id): web::Path<BlockNumber>,
     ) -> ActixResult<HttpResponse> {
         let start = Instant::now();
+        if let Some(response) = self_.cached_response(&req) {
+            metrics::histogram!("api.v01.block_by_id", start.elapsed());
+            return Ok(response);
+        }
         let block = self_.get_block_info(block_id).await?;
         let result = if let Some(block) = block {
-            ok_json!(block)
+            let immutable = block.block_number == *block_id as i64 && block_verified(&block);
+            self_.json_response(&req, &block, immutable)
         } else {
             Err(HttpResponse::NotFound().finish().into())
         };
@@ -464,9 +495,15 @@ impl ApiV01 {
 
     pub async fn block_transactions(
         self_: web::Data<Self>,
+        req: HttpRequest,
         web::Path(block_id): web::Path<BlockNumber>,
     ) -> ActixResult<HttpResponse> {
         let start = Instant::now();
+        if let Some(response) = self_.cached_response(&req) {
+            metrics::histogram!("api.v01.block_transactions", start.elapsed());
+            return Ok(response);
+        }
+        let immutable = self_.is_block_verified(block_id).await?;
         let mut storage = self_.access_storage().await?;
 
         let txs = storage
@@ -479,8 +516,9 @@ impl ApiV01 {
                 HttpResponse::InternalServerError().finish()
             })?;
 
+        let response = self_.json_response(&req, &txs, immutable);
         metrics::histogram!("api.v01.block_transactions", start.elapsed());
-        ok_json!(txs)
+        response
     }
 
     pub async fn explorer_search(
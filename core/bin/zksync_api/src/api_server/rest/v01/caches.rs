@@ -1,4 +1,6 @@
-use crate::utils::shared_lru_cache::SharedLruCache;
+use crate::{
+    api_server::rest::helpers::ImmutableResponse, utils::shared_lru_cache::SharedLruCache,
+};
 use zksync_storage::chain::{
     block::records::BlockDetails,
     operations_ext::records::{PriorityOpReceiptResponse, TxReceiptResponse},
@@ -13,6 +15,8 @@ pub struct Caches {
     pub block_executed_ops: SharedLruCache<u32, Vec<ExecutedOperations>>,
     pub blocks_info: SharedLruCache<u32, BlockDetails>,
     pub blocks_by_height_or_hash: SharedLruCache<String, BlockDetails>,
+    /// Serialized responses with the immutable data, keyed by the request URI.
+    pub immutable_responses: SharedLruCache<String, ImmutableResponse>,
 }
 
 impl Caches {
@@ -23,6 +27,7 @@ impl Caches {
             block_executed_ops: SharedLruCache::new(caches_size),
             blocks_info: SharedLruCache::new(caches_size),
             blocks_by_height_or_hash: SharedLruCache::new(caches_size),
+            immutable_responses: SharedLruCache::new(caches_size),
         }
     }
 }
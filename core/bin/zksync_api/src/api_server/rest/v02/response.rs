@@ -10,7 +10,10 @@ use std::collections::HashMap;
 use zksync_types::network::Network;
 
 use crate::api_server::rest::v02::error::UnreachableError;
-use crate::api_server::rest::v02::{error, ApiVersion, SharedData};
+use crate::api_server::rest::{
+    v02::{error, SharedData},
+    versioning::ApiVersion,
+};
 
 #[derive(Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -1,21 +1,40 @@
 //! Accounts part of API implementation.
 
 // Built-in uses
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 // External uses
 use actix_web::{
     web::{self, Json},
-    Scope,
+    HttpRequest, HttpResponse, Scope,
 };
+use tokio::sync::Mutex;
 
 // Workspace uses
+use zksync_crypto::{
+    circuit::account_proof::AccountStateProof, convert::FeConvert, params::account_tree_depth, Fr,
+};
 use zksync_storage::{ConnectionPool, QueryResult, StorageProcessor};
-use zksync_types::{AccountId, Address, BlockNumber, TokenId};
+use zksync_types::{
+    finality::Finality, tx::ChangePubKeyOnchainAuthStatus, AccountId, AccountTree, Address,
+    BlockNumber, Nonce, PubKeyHash, TokenId, TokenLike,
+};
 
 // Local uses
-use crate::{core_api_client::CoreApiClient, utils::token_db_cache::TokenDBCache};
+use crate::{
+    api_server::rest::response_signer::{signed_json, ResponseSigner},
+    core_api_client::CoreApiClient,
+    eth_checker::EthereumChecker,
+    utils::{
+        account_cache::AccountIdCache, shared_lru_cache::SharedLruCache,
+        token_db_cache::TokenDBCache,
+    },
+};
 
-use super::{ApiError, JsonResult};
+use super::{ApiError, FinalityQuery, JsonResult};
 use zksync_config::ZkSyncConfig;
 
 use self::types::{
@@ -24,19 +43,22 @@ use self::types::{
         pending_account_op_receipt_from_priority_op, search_direction_as_storage,
         tx_receipt_from_response, validate_receipts_query,
     },
-    AccountReceiptsQuery, SearchDirection,
+    AccountReceiptsQuery, AccountStateProofQuery, ChangePubKeyAuthQuery, SearchDirection,
 };
 // Public uses
 pub use self::types::{
     convert::account_state_from_storage, AccountInfo, AccountOpReceipt, AccountQuery,
-    AccountReceipts, AccountState, AccountTxReceipt, DepositingBalances, DepositingFunds,
-    PendingAccountOpReceipt, TxLocation,
+    AccountReceipts, AccountState, AccountStateAtFinality, AccountStateProofInfo, AccountTxReceipt,
+    DepositingBalances, DepositingFunds, PendingAccountOpReceipt, TxLocation,
 };
 
 #[cfg(test)]
 mod tests;
 mod types;
 
+/// Onchain `ChangePubKey` authorization statuses are cached for about an Ethereum block.
+const ONCHAIN_AUTH_STATUS_TTL: Duration = Duration::from_secs(15);
+
 fn unable_to_find_token(token_id: TokenId) -> anyhow::Error {
     anyhow::anyhow!("Unable to find token with ID {}", *token_id)
 }
@@ -54,22 +76,45 @@ fn parse_account_query(query: String) -> Result<AccountQuery, ApiError> {
 struct ApiAccountsData {
     pool: ConnectionPool,
     tokens: TokenDBCache,
+    accounts: AccountIdCache,
     core_api_client: CoreApiClient,
     confirmations_for_eth_event: BlockNumber,
+    /// Account tree restored for the latest requested state proof.
+    /// Restoring the tree is expensive, so the proofs for the same block reuse it. The lock is held
+    /// only while the tree is being restored, so the restorations don't run concurrently, while
+    /// the proofs are built from the shared tree.
+    state_tree: Arc<Mutex<Option<(BlockNumber, Arc<AccountTree>)>>>,
+    /// Signer of the account info responses, if they are signed.
+    response_signer: Option<ResponseSigner>,
+    /// Checker querying the onchain `ChangePubKey` authorizations from the zkSync contract.
+    eth_checker: EthereumChecker,
+    /// Recently queried onchain `ChangePubKey` authorization statuses along with the time
+    /// they were queried at.
+    onchain_auth_statuses:
+        SharedLruCache<(Address, Nonce, PubKeyHash), (Instant, ChangePubKeyOnchainAuthStatus)>,
 }
 
 impl ApiAccountsData {
     fn new(
         pool: ConnectionPool,
         tokens: TokenDBCache,
+        accounts: AccountIdCache,
         core_api_client: CoreApiClient,
         confirmations_for_eth_event: BlockNumber,
+        response_signer: Option<ResponseSigner>,
+        eth_checker: EthereumChecker,
+        caches_size: usize,
     ) -> Self {
         Self {
             pool,
             tokens,
+            accounts,
             core_api_client,
             confirmations_for_eth_event,
+            state_tree: Arc::new(Mutex::new(None)),
+            response_signer,
+            eth_checker,
+            onchain_auth_statuses: SharedLruCache::new(caches_size),
         }
     }
 
@@ -89,17 +134,14 @@ impl ApiAccountsData {
     }
 
     async fn account_id(
+        &self,
         storage: &mut StorageProcessor<'_>,
         query: AccountQuery,
     ) -> QueryResult<Option<AccountId>> {
         match query {
             AccountQuery::Id(id) => Ok(Some(id)),
             AccountQuery::Address(address) => {
-                storage
-                    .chain()
-                    .account_schema()
-                    .account_id_by_address(address)
-                    .await
+                self.accounts.account_id_by_address(storage, address).await
             }
         }
     }
@@ -108,19 +150,98 @@ impl ApiAccountsData {
         match query {
             AccountQuery::Id(id) => {
                 let mut storage = self.access_storage().await?;
-                storage
-                    .chain()
-                    .account_schema()
-                    .account_address_by_id(id)
-                    .await
+                self.accounts.account_address_by_id(&mut storage, id).await
             }
             AccountQuery::Address(address) => Ok(Some(address)),
         }
     }
 
+    async fn account_state_proof(
+        &self,
+        query: AccountQuery,
+        token: TokenLike,
+        block: Option<BlockNumber>,
+    ) -> QueryResult<Option<AccountStateProofInfo>> {
+        let mut storage = self.access_storage().await?;
+        let account_id = if let Some(id) = self.account_id(&mut storage, query).await? {
+            id
+        } else {
+            return Ok(None);
+        };
+        let token = if let Some(token) = self.tokens.get_token(&mut storage, token).await? {
+            token
+        } else {
+            return Ok(None);
+        };
+
+        let block_number = match block {
+            Some(block) => block,
+            None => {
+                storage
+                    .chain()
+                    .block_schema()
+                    .get_last_verified_confirmed_block()
+                    .await?
+            }
+        };
+        let block = storage
+            .chain()
+            .block_schema()
+            .load_block_range(block_number, 1)
+            .await?
+            .into_iter()
+            .find(|block| block.block_number == i64::from(*block_number));
+        let state_root = if let Some(block) = block {
+            Fr::from_bytes(&block.new_state_root)?
+        } else {
+            return Ok(None);
+        };
+
+        let tree = {
+            let mut state_tree = self.state_tree.lock().await;
+            match &*state_tree {
+                Some((cached, tree)) if *cached == block_number => tree.clone(),
+                _ => {
+                    let (_, accounts) = storage
+                        .chain()
+                        .state_schema()
+                        .load_committed_state(Some(block_number))
+                        .await?;
+                    let mut tree = AccountTree::new(account_tree_depth());
+                    tree.insert_many(accounts.into_iter().map(|(id, account)| (*id, account)));
+                    let tree = Arc::new(tree);
+                    *state_tree = Some((block_number, tree.clone()));
+                    tree
+                }
+            }
+        };
+
+        anyhow::ensure!(
+            tree.root_hash() == state_root,
+            "Restored state does not match the root hash of block {}",
+            block_number
+        );
+
+        let account = if let Some(account) = tree.get(*account_id) {
+            account.clone()
+        } else {
+            return Ok(None);
+        };
+        let balance = account.get_balance(token.id);
+        let proof =
+            AccountStateProof::new(&tree, *account_id, &account.into(), u32::from(*token.id));
+
+        Ok(Some(AccountStateProofInfo {
+            block_number,
+            state_root,
+            balance: balance.into(),
+            proof,
+        }))
+    }
+
     async fn account_info(&self, query: AccountQuery) -> QueryResult<Option<AccountInfo>> {
         let mut storage = self.access_storage().await?;
-        let account_id = if let Some(id) = Self::account_id(&mut storage, query).await? {
+        let account_id = if let Some(id) = self.account_id(&mut storage, query).await? {
             id
         } else {
             return Ok(None);
@@ -139,12 +260,18 @@ impl ApiAccountsData {
             return Ok(None);
         };
 
-        let committed = account_state_from_storage(&mut storage, &self.tokens, &account).await?;
+        let committed =
+            account_state_from_storage(&mut storage, &self.tokens, &account, Finality::Pending)
+                .await?;
         let verified = match account_state.verified {
             Some((_id, account)) => {
-                account_state_from_storage(&mut storage, &self.tokens, &account).await?
+                account_state_from_storage(&mut storage, &self.tokens, &account, Finality::Executed)
+                    .await?
             }
-            None => AccountState::default(),
+            None => AccountState {
+                finality: Finality::Executed,
+                ..AccountState::default()
+            },
         };
 
         let depositing = {
@@ -173,6 +300,54 @@ impl ApiAccountsData {
         Ok(Some(info))
     }
 
+    /// Returns the account state obtained in the last block that has reached the finality.
+    async fn account_state(
+        &self,
+        query: AccountQuery,
+        finality: Finality,
+    ) -> QueryResult<Option<AccountStateAtFinality>> {
+        let mut storage = self.access_storage().await?;
+        let account_id = if let Some(id) = self.account_id(&mut storage, query).await? {
+            id
+        } else {
+            return Ok(None);
+        };
+
+        let block_number = match storage
+            .chain()
+            .block_schema()
+            .load_finalized_blocks()
+            .await?
+            .last_block(finality)
+        {
+            Some(block_number) => block_number,
+            // The pending state includes all the blocks sealed by the server.
+            None => {
+                storage
+                    .chain()
+                    .block_schema()
+                    .get_last_saved_block()
+                    .await?
+            }
+        };
+        let account = storage
+            .chain()
+            .account_schema()
+            .account_state_at_block(account_id, block_number)
+            .await?;
+
+        let state = match account {
+            Some(account) => {
+                account_state_from_storage(&mut storage, &self.tokens, &account, finality).await?
+            }
+            None => return Ok(None),
+        };
+        Ok(Some(AccountStateAtFinality {
+            block_number,
+            state,
+        }))
+    }
+
     async fn tx_receipts(
         &self,
         address: Address,
@@ -193,8 +368,16 @@ impl ApiAccountsData {
                 limit as u64,
             )
             .await?;
+        let finalized = storage
+            .chain()
+            .block_schema()
+            .load_finalized_blocks()
+            .await?;
 
-        Ok(items.into_iter().map(tx_receipt_from_response).collect())
+        Ok(items
+            .into_iter()
+            .map(|item| tx_receipt_from_response(item, &finalized))
+            .collect())
     }
 
     async fn op_receipts(
@@ -217,8 +400,16 @@ impl ApiAccountsData {
                 limit as u64,
             )
             .await?;
+        let finalized = storage
+            .chain()
+            .block_schema()
+            .load_finalized_blocks()
+            .await?;
 
-        Ok(items.into_iter().map(op_receipt_from_response).collect())
+        Ok(items
+            .into_iter()
+            .map(|item| op_receipt_from_response(item, &finalized))
+            .collect())
     }
 
     async fn pending_op_receipts(
@@ -234,26 +425,62 @@ impl ApiAccountsData {
 
         Ok(receipts)
     }
+
+    async fn change_pubkey_onchain_auth(
+        &self,
+        address: Address,
+        query: ChangePubKeyAuthQuery,
+    ) -> anyhow::Result<ChangePubKeyOnchainAuthStatus> {
+        let key = (address, query.nonce, query.pub_key_hash);
+        if let Some((queried_at, status)) = self.onchain_auth_statuses.get(&key) {
+            if queried_at.elapsed() < ONCHAIN_AUTH_STATUS_TTL {
+                return Ok(status);
+            }
+        }
+
+        let status = self
+            .eth_checker
+            .pubkey_change_auth_status(address, query.nonce, query.pub_key_hash)
+            .await?;
+        self.onchain_auth_statuses
+            .insert(key, (Instant::now(), status.clone()));
+        Ok(status)
+    }
 }
 
 // Server implementation
 
 async fn account_info(
+    req: HttpRequest,
     data: web::Data<ApiAccountsData>,
     web::Path(query): web::Path<String>,
-) -> JsonResult<Option<AccountInfo>> {
+) -> Result<HttpResponse, ApiError> {
     let query = parse_account_query(query)?;
 
-    data.account_info(query)
+    let account_info = data.account_info(query).await.map_err(ApiError::internal)?;
+    signed_json(data.response_signer.as_ref(), &req, &account_info)
+}
+
+async fn account_state(
+    data: web::Data<ApiAccountsData>,
+    web::Path(account_query): web::Path<String>,
+    web::Query(finality_query): web::Query<FinalityQuery>,
+) -> JsonResult<Option<AccountStateAtFinality>> {
+    let query = parse_account_query(account_query)?;
+
+    let state = data
+        .account_state(query, finality_query.min_finality())
         .await
-        .map(Json)
-        .map_err(ApiError::internal)
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(state))
 }
 
 async fn account_tx_receipts(
     data: web::Data<ApiAccountsData>,
     web::Path(account_query): web::Path<String>,
     web::Query(location_query): web::Query<AccountReceiptsQuery>,
+    web::Query(finality_query): web::Query<FinalityQuery>,
 ) -> JsonResult<Vec<AccountTxReceipt>> {
     let (location, direction, limit) = validate_receipts_query(location_query)?;
     let address = data.find_account_address(account_query).await?;
@@ -261,7 +488,10 @@ async fn account_tx_receipts(
     let receipts = data
         .tx_receipts(address, location, direction, *limit)
         .await
-        .map_err(ApiError::internal)?;
+        .map_err(ApiError::internal)?
+        .into_iter()
+        .filter(|receipt| finality_query.is_reached_by(receipt.finality))
+        .collect();
 
     Ok(Json(receipts))
 }
@@ -270,6 +500,7 @@ async fn account_op_receipts(
     data: web::Data<ApiAccountsData>,
     web::Path(account_query): web::Path<String>,
     web::Query(location_query): web::Query<AccountReceiptsQuery>,
+    web::Query(finality_query): web::Query<FinalityQuery>,
 ) -> JsonResult<Vec<AccountOpReceipt>> {
     let (location, direction, limit) = validate_receipts_query(location_query)?;
     let address = data.find_account_address(account_query).await?;
@@ -277,7 +508,10 @@ async fn account_op_receipts(
     let receipts = data
         .op_receipts(address, location, direction, *limit)
         .await
-        .map_err(ApiError::internal)?;
+        .map_err(ApiError::internal)?
+        .into_iter()
+        .filter(|receipt| receipt.finality >= finality_query.min_finality())
+        .collect();
 
     Ok(Json(receipts))
 }
@@ -296,22 +530,55 @@ async fn account_pending_receipts(
     Ok(Json(receipts))
 }
 
+async fn account_state_proof(
+    data: web::Data<ApiAccountsData>,
+    web::Path((account_query, token)): web::Path<(String, String)>,
+    web::Query(proof_query): web::Query<AccountStateProofQuery>,
+) -> JsonResult<Option<AccountStateProofInfo>> {
+    let query = parse_account_query(account_query)?;
+
+    data.account_state_proof(query, TokenLike::parse(&token), proof_query.block)
+        .await
+        .map(Json)
+        .map_err(ApiError::internal)
+}
+
+async fn account_change_pubkey_onchain_auth(
+    data: web::Data<ApiAccountsData>,
+    web::Path(account_query): web::Path<String>,
+    web::Query(auth_query): web::Query<ChangePubKeyAuthQuery>,
+) -> JsonResult<ChangePubKeyOnchainAuthStatus> {
+    let address = data.find_account_address(account_query).await?;
+
+    data.change_pubkey_onchain_auth(address, auth_query)
+        .await
+        .map(Json)
+        .map_err(ApiError::internal)
+}
+
 pub fn api_scope(
     pool: ConnectionPool,
     config: &ZkSyncConfig,
     tokens: TokenDBCache,
+    accounts: AccountIdCache,
     core_api_client: CoreApiClient,
+    eth_checker: EthereumChecker,
 ) -> Scope {
     let data = ApiAccountsData::new(
         pool,
         tokens,
+        accounts,
         core_api_client,
         BlockNumber(config.eth_watch.confirmations_for_eth_event as u32),
+        ResponseSigner::from_config(config),
+        eth_checker,
+        config.api.common.caches_size,
     );
 
     web::scope("accounts")
         .data(data)
         .route("{id}", web::get().to(account_info))
+        .route("{id}/state", web::get().to(account_state))
         .route(
             "{id}/transactions/receipts",
             web::get().to(account_tx_receipts),
@@ -324,4 +591,9 @@ pub fn api_scope(
             "{id}/operations/pending",
             web::get().to(account_pending_receipts),
         )
+        .route("{id}/proofs/{token}", web::get().to(account_state_proof))
+        .route(
+            "{id}/change_pubkey/onchain_auth",
+            web::get().to(account_change_pubkey_onchain_auth),
+        )
 }
@@ -10,21 +10,30 @@ use serde_json::json;
 use tokio::sync::Mutex;
 
 // Workspace uses
+use zksync_eth_client::{clients::mock::MockEthereum, EthereumGateway};
 use zksync_storage::{
     chain::operations_ext::records::{AccountOpReceiptResponse, AccountTxReceiptResponse},
     ConnectionPool, StorageProcessor,
 };
-use zksync_types::{tx::TxHash, AccountId, Address, BlockNumber, ExecutedOperations, H256};
+use zksync_types::{
+    finality::{Finality, FinalizedBlocks},
+    tx::TxHash,
+    AccountId, Address, BlockNumber, ExecutedOperations, H256,
+};
 
 // Local uses
 use crate::{
     api_server::v1::{
-        test_utils::{dummy_deposit_op, TestServerConfig},
+        test_utils::{
+            core_version_headers, dummy_deposit_op, TestServerConfig, COMMITTED_BLOCKS_COUNT,
+            EXECUTED_BLOCKS_COUNT,
+        },
         transactions::Receipt,
         Client,
     },
     core_api_client::CoreApiClient,
-    utils::token_db_cache::TokenDBCache,
+    eth_checker::EthereumChecker,
+    utils::{account_cache::AccountIdCache, token_db_cache::TokenDBCache},
 };
 
 use super::{
@@ -56,6 +65,7 @@ fn get_unconfirmed_ops_loopback(
         let ops_handle = ops_handle.clone();
         let deposits_handle = deposits_handle.clone();
         App::new()
+            .wrap(core_version_headers())
             .service(
                 web::scope("unconfirmed_ops")
                     .data(ops_handle)
@@ -97,7 +107,12 @@ impl TestServer {
                 cfg.pool.clone(),
                 &cfg.config,
                 TokenDBCache::new(),
+                AccountIdCache::new(cfg.config.api.common.caches_size),
                 core_client.clone(),
+                EthereumChecker::new(
+                    EthereumGateway::Mock(MockEthereum::default()),
+                    Default::default(),
+                ),
             )
         });
 
@@ -166,7 +181,29 @@ async fn accounts_scope() -> anyhow::Result<()> {
 
     let account_info = client.account_info(account_id).await?.unwrap();
     let address = account_info.address;
-    assert_eq!(client.account_info(address).await?, Some(account_info));
+    assert_eq!(account_info.committed.finality, Finality::Pending);
+    assert_eq!(account_info.verified.finality, Finality::Executed);
+    assert_eq!(
+        client.account_info(address).await?,
+        Some(account_info.clone())
+    );
+
+    // Get account state at the different finality levels.
+    let pending_state = client.account_state(account_id, None).await?.unwrap();
+    assert_eq!(
+        pending_state.block_number,
+        BlockNumber(COMMITTED_BLOCKS_COUNT)
+    );
+    assert_eq!(pending_state.state, account_info.committed);
+    let executed_state = client
+        .account_state(address, Some(Finality::Executed))
+        .await?
+        .unwrap();
+    assert_eq!(
+        executed_state.block_number,
+        BlockNumber(EXECUTED_BLOCKS_COUNT)
+    );
+    assert_eq!(executed_state.state.finality, Finality::Executed);
 
     // Provide unconfirmed pending deposits.
     *server.pending_deposits.lock().await = json!([
@@ -209,6 +246,7 @@ async fn accounts_scope() -> anyhow::Result<()> {
             reason: Some("Unknown token".to_string())
         }
     );
+    assert_eq!(receipts[0].finality, None);
     assert_eq!(receipts[2].index, Some(3));
     assert_eq!(
         receipts[2].receipt,
@@ -216,6 +254,21 @@ async fn accounts_scope() -> anyhow::Result<()> {
             block: BlockNumber(1)
         }
     );
+    assert_eq!(receipts[2].finality, Some(Finality::Executed));
+    // Rejected transactions are reported regardless of the requested finality.
+    let executed_receipts = client
+        .account_tx_receipts_at_finality(
+            address,
+            AccountReceipts::newer_than(BlockNumber(0), None),
+            10,
+            Finality::Executed,
+        )
+        .await?;
+    assert_eq!(executed_receipts[0], receipts[0]);
+    assert!(executed_receipts
+        .iter()
+        .skip(1)
+        .all(|receipt| receipt.finality == Some(Finality::Executed)));
 
     // Get a reversed list of receipts with requests from the end.
     let receipts: Vec<_> = receipts.into_iter().rev().collect();
@@ -266,45 +319,50 @@ async fn accounts_scope() -> anyhow::Result<()> {
             index: 1,
             receipt: Receipt::Verified {
                 block: BlockNumber(1)
-            }
+            },
+            finality: Finality::Executed,
         }
     );
     assert_eq!(
         client
-            .account_op_receipts(
+            .account_op_receipts_at_finality(
                 address,
                 AccountReceipts::newer_than(BlockNumber(1), Some(0)),
-                10
+                10,
+                Finality::Executed
             )
             .await?,
         receipts
     );
     assert_eq!(
         client
-            .account_op_receipts(
+            .account_op_receipts_at_finality(
                 address,
                 AccountReceipts::older_than(BlockNumber(2), Some(0)),
-                10
+                10,
+                Finality::Executed
             )
             .await?,
         receipts
     );
     assert_eq!(
         client
-            .account_op_receipts(
+            .account_op_receipts_at_finality(
                 account_id,
                 AccountReceipts::newer_than(BlockNumber(1), Some(0)),
-                10
+                10,
+                Finality::Executed
             )
             .await?,
         receipts
     );
     assert_eq!(
         client
-            .account_op_receipts(
+            .account_op_receipts_at_finality(
                 account_id,
                 AccountReceipts::older_than(BlockNumber(2), Some(0)),
-                10
+                10,
+                Finality::Executed
             )
             .await?,
         receipts
@@ -371,6 +429,7 @@ fn account_tx_response_to_receipt() {
                 index: Some(1),
                 hash: TxHash::default(),
                 receipt: Receipt::Executed,
+                finality: Some(Finality::Pending),
             },
         ),
         (
@@ -387,6 +446,7 @@ fn account_tx_response_to_receipt() {
                 index: None,
                 hash: TxHash::default(),
                 receipt: Receipt::Executed,
+                finality: Some(Finality::Pending),
             },
         ),
         (
@@ -405,6 +465,7 @@ fn account_tx_response_to_receipt() {
                 receipt: Receipt::Rejected {
                     reason: Some("Oops".to_string()),
                 },
+                finality: None,
             },
         ),
         (
@@ -423,6 +484,7 @@ fn account_tx_response_to_receipt() {
                 receipt: Receipt::Committed {
                     block: BlockNumber(1),
                 },
+                finality: Some(Finality::Pending),
             },
         ),
         (
@@ -441,12 +503,13 @@ fn account_tx_response_to_receipt() {
                 receipt: Receipt::Verified {
                     block: BlockNumber(1),
                 },
+                finality: Some(Finality::Pending),
             },
         ),
     ];
 
     for (resp, expected_receipt) in cases {
-        let actual_receipt = tx_receipt_from_response(resp);
+        let actual_receipt = tx_receipt_from_response(resp, &FinalizedBlocks::default());
         assert_eq!(actual_receipt, expected_receipt);
     }
 }
@@ -470,6 +533,7 @@ fn account_op_response_to_receipt() {
                 index: 1,
                 hash: H256::default(),
                 receipt: Receipt::Executed,
+                finality: Finality::Pending,
             },
         ),
         (
@@ -486,6 +550,7 @@ fn account_op_response_to_receipt() {
                 receipt: Receipt::Committed {
                     block: BlockNumber(1),
                 },
+                finality: Finality::Pending,
             },
         ),
         (
@@ -502,12 +567,13 @@ fn account_op_response_to_receipt() {
                 receipt: Receipt::Verified {
                     block: BlockNumber(1),
                 },
+                finality: Finality::Pending,
             },
         ),
     ];
 
     for (resp, expected_receipt) in cases {
-        let actual_receipt = op_receipt_from_response(resp);
+        let actual_receipt = op_receipt_from_response(resp, &FinalizedBlocks::default());
         assert_eq!(actual_receipt, expected_receipt);
     }
 }
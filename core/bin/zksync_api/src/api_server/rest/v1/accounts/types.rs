@@ -6,8 +6,9 @@ use std::collections::BTreeMap;
 // Workspace uses
 pub use zksync_api_client::rest::v1::accounts::{
     AccountInfo, AccountOpReceipt, AccountQuery, AccountReceipts, AccountReceiptsQuery,
-    AccountState, AccountTxReceipt, DepositingBalances, DepositingFunds, PendingAccountOpReceipt,
-    SearchDirection, TxLocation,
+    AccountState, AccountStateAtFinality, AccountStateProofInfo, AccountStateProofQuery,
+    AccountTxReceipt, ChangePubKeyAuthQuery, DepositingBalances, DepositingFunds,
+    PendingAccountOpReceipt, SearchDirection, TxLocation,
 };
 use zksync_storage::{
     chain::operations_ext::{
@@ -16,7 +17,11 @@ use zksync_storage::{
     },
     QueryResult, StorageProcessor,
 };
-use zksync_types::{tx::TxHash, Account, BlockNumber, PriorityOp, ZkSyncPriorityOp, H256};
+use zksync_types::{
+    finality::{Finality, FinalizedBlocks},
+    tx::TxHash,
+    Account, BlockNumber, PriorityOp, ZkSyncPriorityOp, H256,
+};
 
 // Local uses
 use crate::{api_server::v1::MAX_LIMIT, utils::token_db_cache::TokenDBCache};
@@ -33,6 +38,7 @@ pub(super) mod convert {
         storage: &mut StorageProcessor<'_>,
         tokens: &TokenDBCache,
         account: &Account,
+        finality: Finality,
     ) -> QueryResult<AccountState> {
         let mut balances = BTreeMap::new();
         for (token_id, balance) in account.get_nonzero_balances() {
@@ -48,6 +54,7 @@ pub(super) mod convert {
             balances,
             nonce: account.nonce,
             pub_key_hash: account.pub_key_hash,
+            finality,
         })
     }
 
@@ -126,7 +133,10 @@ pub(super) mod convert {
         Ok((location, direction, query.limit))
     }
 
-    pub fn tx_receipt_from_response(inner: AccountTxReceiptResponse) -> AccountTxReceipt {
+    pub fn tx_receipt_from_response(
+        inner: AccountTxReceiptResponse,
+        finalized: &FinalizedBlocks,
+    ) -> AccountTxReceipt {
         let block = BlockNumber(inner.block_number as u32);
         let index = inner.block_index.map(|x| x as u32);
         let hash = TxHash::from_slice(&inner.tx_hash).unwrap_or_else(|| {
@@ -143,6 +153,7 @@ pub(super) mod convert {
                 receipt: Receipt::Rejected {
                     reason: inner.fail_reason,
                 },
+                finality: None,
             };
         }
 
@@ -161,12 +172,16 @@ pub(super) mod convert {
 
         AccountTxReceipt {
             index,
+            finality: receipt.finality(finalized),
             receipt,
             hash,
         }
     }
 
-    pub fn op_receipt_from_response(inner: AccountOpReceiptResponse) -> AccountOpReceipt {
+    pub fn op_receipt_from_response(
+        inner: AccountOpReceiptResponse,
+        finalized: &FinalizedBlocks,
+    ) -> AccountOpReceipt {
         let block = BlockNumber(inner.block_number as u32);
         let index = inner.block_index as u32;
         let hash = H256::from_slice(&inner.eth_hash);
@@ -188,6 +203,7 @@ pub(super) mod convert {
             index,
             receipt,
             hash,
+            finality: finalized.block_finality(block),
         }
     }
 
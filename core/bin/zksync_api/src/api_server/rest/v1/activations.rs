@@ -0,0 +1,63 @@
+//! Account activations part of API implementation.
+//!
+//! Senders of the funds prepay the `ChangePubKey` of the fresh recipients, so the recipients
+//! can activate their accounts without paying the fee.
+//! See `zksync_types::activations` for the details.
+
+// Built-in uses
+
+// External uses
+use actix_web::{
+    web::{self, Json},
+    Scope,
+};
+
+// Workspace uses
+use zksync_types::activations::{ActivationHint, PrepaidActivation};
+
+// Local uses
+use super::{Error as ApiError, JsonResult};
+use crate::api_server::{rest::address_checksum::parse_path_address, tx_sender::TxSender};
+
+/// Shared data between `api/v1/activations` endpoints.
+#[derive(Clone)]
+struct ApiActivationsData {
+    tx_sender: TxSender,
+}
+
+// Server implementation
+
+async fn prepay_activation(
+    data: web::Data<ApiActivationsData>,
+    Json(activation): Json<PrepaidActivation>,
+) -> JsonResult<()> {
+    data.tx_sender
+        .submit_prepaid_activation(activation)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(()))
+}
+
+async fn activation_hint(
+    data: web::Data<ApiActivationsData>,
+    web::Path(address): web::Path<String>,
+) -> JsonResult<ActivationHint> {
+    let address = parse_path_address(&address)?;
+    let hint = data
+        .tx_sender
+        .activation_hint(address)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(hint))
+}
+
+pub fn api_scope(tx_sender: TxSender) -> Scope {
+    let data = ApiActivationsData { tx_sender };
+
+    web::scope("activations")
+        .data(data)
+        .route("", web::post().to(prepay_activation))
+        .route("{address}", web::get().to(activation_hint))
+}
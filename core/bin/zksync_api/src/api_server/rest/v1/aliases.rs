@@ -0,0 +1,314 @@
+//! Aliases part of API implementation.
+//!
+//! Accounts register the human-readable aliases by submitting the signed registration requests,
+//! so the users can send funds to the aliases instead of the addresses.
+//! See `zksync_types::account_alias` for the details.
+//!
+//! Only the accounts with the signing key set can register the aliases, so squatting the names
+//! costs the `ChangePubKey` fee for every squatted alias, since each account has at most one alias.
+
+// Built-in uses
+use std::convert::TryFrom;
+
+// External uses
+use actix_web::{
+    web::{self, Json},
+    Scope,
+};
+use chrono::Utc;
+
+// Workspace uses
+use zksync_storage::{aliases::AliasRegistration, ConnectionPool};
+use zksync_types::{
+    account::PubKeyHash,
+    account_alias::{validate_alias, AccountAlias, AliasRegistrationRequest},
+    tx::SignatureDomain,
+};
+
+// Local uses
+use super::{Error as ApiError, JsonResult};
+use crate::api_server::rest::address_checksum::parse_path_address;
+
+/// Max difference between the request timestamp and the server time, in milliseconds.
+const MAX_REQUEST_TIME_DRIFT_MS: i64 = 5 * 60 * 1000;
+
+/// Shared data between `api/v1/aliases` endpoints.
+#[derive(Clone)]
+struct ApiAliasesData {
+    pool: ConnectionPool,
+    /// Network the signed messages must be bound to, see `SignatureDomain`.
+    signature_domain: Option<SignatureDomain>,
+}
+
+// Server implementation
+
+/// Checks that the request timestamp (in milliseconds) is close to the server time.
+fn is_request_time_valid(timestamp: u64) -> bool {
+    i64::try_from(timestamp)
+        .ok()
+        .and_then(|timestamp| Utc::now().timestamp_millis().checked_sub(timestamp))
+        .and_then(i64::checked_abs)
+        .map_or(false, |drift| drift <= MAX_REQUEST_TIME_DRIFT_MS)
+}
+
+async fn register(
+    data: web::Data<ApiAliasesData>,
+    Json(request): Json<AliasRegistrationRequest>,
+) -> JsonResult<AccountAlias> {
+    validate_alias(&request.alias)
+        .map_err(|err| ApiError::bad_request("Incorrect alias").detail(err))?;
+    if !is_request_time_valid(request.timestamp) {
+        return Err(ApiError::bad_request("Request timestamp is out of range")
+            .detail("Request must be signed not earlier than 5 minutes ago"));
+    }
+    if !request.verify(data.signature_domain.as_ref()) {
+        return Err(ApiError::bad_request("Incorrect signature")
+            .detail("Registration request must be signed by the account address"));
+    }
+
+    let mut storage = data
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    let account = storage
+        .chain()
+        .account_schema()
+        .account_state_by_address(request.address)
+        .await
+        .map_err(ApiError::internal)?
+        .committed;
+    let is_activated =
+        matches!(account, Some((_, account)) if account.pub_key_hash != PubKeyHash::default());
+    if !is_activated {
+        return Err(ApiError::bad_request("Account is not activated")
+            .detail("Only the accounts with the signing key set can register the aliases"));
+    }
+
+    let registration = storage
+        .aliases_schema()
+        .register_alias(&request.alias, request.address, request.timestamp)
+        .await
+        .map_err(ApiError::internal)?;
+    match registration {
+        AliasRegistration::Registered => {}
+        AliasRegistration::AliasTaken => {
+            return Err(ApiError::bad_request("Alias is already taken")
+                .detail("Alias is registered by another account"));
+        }
+        AliasRegistration::OutdatedRequest => {
+            return Err(ApiError::bad_request("Request has already been processed")
+                .detail("Request must be newer than the previous registration of the account"));
+        }
+    }
+
+    let alias = storage
+        .aliases_schema()
+        .get_alias(&request.alias)
+        .await
+        .map_err(ApiError::internal)?
+        .ok_or_else(|| ApiError::internal("Registered alias is not found"))?;
+
+    metrics::counter!("api.v1.aliases.registered", 1);
+    Ok(Json(alias))
+}
+
+async fn resolve(
+    data: web::Data<ApiAliasesData>,
+    web::Path(alias): web::Path<String>,
+) -> JsonResult<Option<AccountAlias>> {
+    let mut storage = data
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    let alias = storage
+        .aliases_schema()
+        .get_alias(&alias)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(alias))
+}
+
+async fn account_alias(
+    data: web::Data<ApiAliasesData>,
+    web::Path(address): web::Path<String>,
+) -> JsonResult<Option<AccountAlias>> {
+    let address = parse_path_address(&address)?;
+    let mut storage = data
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    let alias = storage
+        .aliases_schema()
+        .get_account_alias(address)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(alias))
+}
+
+pub fn api_scope(pool: ConnectionPool, signature_domain: Option<SignatureDomain>) -> Scope {
+    let data = ApiAliasesData {
+        pool,
+        signature_domain,
+    };
+
+    web::scope("aliases")
+        .data(data)
+        .route("", web::post().to(register))
+        .route("account/{address}", web::get().to(account_alias))
+        .route("{alias}", web::get().to(resolve))
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_api_client::rest::v1::ClientError;
+    use zksync_types::{
+        account::PubKeyHash, tx::PackedEthSignature, AccountId, AccountUpdate, Address,
+        BlockNumber, Nonce, H256,
+    };
+
+    use super::{
+        super::test_utils::{TestServerConfig, COMMITTED_BLOCKS_COUNT},
+        *,
+    };
+
+    fn registration_request(
+        private_key: &H256,
+        alias: &str,
+        timestamp: u64,
+        domain: &SignatureDomain,
+    ) -> AliasRegistrationRequest {
+        let address = PackedEthSignature::address_from_private_key(private_key).unwrap();
+        let message = AliasRegistrationRequest::message(alias, address, timestamp, Some(domain));
+        AliasRegistrationRequest {
+            alias: alias.into(),
+            address,
+            timestamp,
+            signature: PackedEthSignature::sign(private_key, message.as_bytes()).unwrap(),
+        }
+    }
+
+    #[test]
+    fn request_time() {
+        let now = Utc::now().timestamp_millis() as u64;
+        assert!(is_request_time_valid(now));
+        assert!(is_request_time_valid(now - 60_000));
+        assert!(!is_request_time_valid(now - 600_000));
+        assert!(!is_request_time_valid(now + 600_000));
+        assert!(!is_request_time_valid(0));
+        assert!(!is_request_time_valid(i64::MAX as u64 + 1));
+        assert!(!is_request_time_valid(u64::MAX));
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn aliases_scope() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let owner_key = H256::repeat_byte(11);
+        let owner = PackedEthSignature::address_from_private_key(&owner_key)?;
+        let inactive_key = H256::repeat_byte(12);
+        let inactive = PackedEthSignature::address_from_private_key(&inactive_key)?;
+        let create = |address| AccountUpdate::Create {
+            address,
+            nonce: Nonce(0),
+        };
+        cfg.pool
+            .access_storage()
+            .await?
+            .chain()
+            .state_schema()
+            .commit_state_update(
+                BlockNumber(COMMITTED_BLOCKS_COUNT),
+                &[
+                    (AccountId(0xa11a), create(owner)),
+                    (
+                        AccountId(0xa11a),
+                        AccountUpdate::ChangePubKeyHash {
+                            old_pub_key_hash: PubKeyHash::default(),
+                            new_pub_key_hash: PubKeyHash::from_hex(
+                                "sync:0000000000000000000000000000000000000001",
+                            )?,
+                            old_nonce: Nonce(0),
+                            new_nonce: Nonce(1),
+                        },
+                    ),
+                    (AccountId(0xa11b), create(inactive)),
+                ],
+                0,
+            )
+            .await?;
+
+        let domain = SignatureDomain::new(
+            cfg.config.eth_client.chain_id.into(),
+            cfg.config.contracts.contract_addr,
+        );
+        let (client, server) =
+            cfg.start_server(move |cfg| api_scope(cfg.pool.clone(), Some(domain)));
+
+        let now = Utc::now().timestamp_millis() as u64;
+        let request = registration_request(&owner_key, "alice", now, &domain);
+
+        // The request must be signed by the owner of the account.
+        let error = client
+            .register_alias(AliasRegistrationRequest {
+                signature: registration_request(&inactive_key, "alice", now, &domain).signature,
+                ..request.clone()
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ClientError::BadRequest { .. }));
+        // The request must be recent.
+        let error = client
+            .register_alias(registration_request(&owner_key, "alice", u64::MAX, &domain))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ClientError::BadRequest { .. }));
+        // Only the activated accounts can register the aliases.
+        let error = client
+            .register_alias(registration_request(&inactive_key, "bob", now, &domain))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ClientError::BadRequest { .. }));
+        let error = client
+            .register_alias(registration_request(
+                &H256::repeat_byte(13),
+                "carol",
+                now,
+                &domain,
+            ))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ClientError::BadRequest { .. }));
+
+        let alias = client.register_alias(request.clone()).await?;
+        assert_eq!(alias.alias, "alice");
+        assert_eq!(alias.address, owner);
+        assert_eq!(client.resolve_alias("alice").await?, Some(alias.clone()));
+        assert_eq!(client.account_alias(owner).await?, Some(alias));
+        assert_eq!(client.resolve_alias("bob").await?, None);
+        assert_eq!(client.account_alias(inactive).await?, None);
+        assert_eq!(client.account_alias(Address::repeat_byte(1)).await?, None);
+        // The same request can't be replayed.
+        let error = client.register_alias(request).await.unwrap_err();
+        assert!(matches!(error, ClientError::BadRequest { .. }));
+
+        // Registering the new alias releases the previous one.
+        let alias = client
+            .register_alias(registration_request(&owner_key, "alice2", now + 1, &domain))
+            .await?;
+        assert_eq!(client.resolve_alias("alice2").await?, Some(alias));
+        assert_eq!(client.resolve_alias("alice").await?, None);
+
+        server.stop().await;
+        Ok(())
+    }
+}
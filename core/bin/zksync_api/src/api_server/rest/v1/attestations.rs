@@ -0,0 +1,192 @@
+//! Attestations part of API implementation.
+//!
+//! The owner of the account proves that it controls the Ethereum address of the account
+//! by signing the challenge issued by the server, and the attestation is recorded, so the exchanges
+//! can verify the deposit addresses. See `zksync_types::address_attestation` for the details.
+//!
+//! Every address may have a limited number of the challenges waiting for the response, and the expired
+//! challenges are removed when the new ones are issued.
+
+// Built-in uses
+
+// External uses
+use actix_web::{
+    web::{self, Json},
+    Scope,
+};
+use chrono::{Duration, Utc};
+
+// Workspace uses
+use zksync_crypto::rand::{thread_rng, Rng};
+use zksync_storage::ConnectionPool;
+use zksync_types::{
+    address_attestation::{
+        AddressAttestation, AttestationChallenge, AttestationChallengeRequest, AttestationResponse,
+        AttestationStatus, CHALLENGE_LIFETIME_SECS, MAX_PENDING_CHALLENGES,
+    },
+    tx::SignatureDomain,
+    H256,
+};
+
+// Local uses
+use super::{Error as ApiError, JsonResult};
+use crate::api_server::rest::address_checksum::parse_path_address;
+
+/// Max number of the attestations of the address returned at once.
+const MAX_ADDRESS_ATTESTATIONS: u32 = 100;
+
+/// Shared data between `api/v1/attestations` endpoints.
+#[derive(Clone)]
+struct ApiAttestationsData {
+    pool: ConnectionPool,
+    /// Network the signed messages must be bound to, see `SignatureDomain`.
+    signature_domain: Option<SignatureDomain>,
+}
+
+// Server implementation
+
+async fn issue_challenge(
+    data: web::Data<ApiAttestationsData>,
+    Json(request): Json<AttestationChallengeRequest>,
+) -> JsonResult<AttestationChallenge> {
+    let mut storage = data
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    let account_id = storage
+        .chain()
+        .account_schema()
+        .account_id_by_address(request.address)
+        .await
+        .map_err(ApiError::internal)?
+        .ok_or_else(|| {
+            ApiError::bad_request("Account does not exist")
+                .detail("Only the addresses of the zkSync accounts can be attested")
+        })?;
+
+    let mut attestations = storage.attestations_schema();
+    let removed = attestations
+        .remove_expired_challenges()
+        .await
+        .map_err(ApiError::internal)?;
+    metrics::counter!("api.v1.attestations.expired_challenges", removed);
+    let pending = attestations
+        .count_pending_challenges(request.address)
+        .await
+        .map_err(ApiError::internal)?;
+    if pending >= MAX_PENDING_CHALLENGES {
+        return Err(
+            ApiError::too_many_requests("Too many challenges for the address")
+                .detail("Answer the issued challenges or wait until they expire")
+                .retry_after(CHALLENGE_LIFETIME_SECS as u64),
+        );
+    }
+
+    let challenge = AttestationChallenge::new(
+        H256::from(thread_rng().gen::<[u8; 32]>()),
+        request.address,
+        account_id,
+        Utc::now() + Duration::seconds(CHALLENGE_LIFETIME_SECS),
+        data.signature_domain.as_ref(),
+    );
+    attestations
+        .store_challenge(&challenge)
+        .await
+        .map_err(ApiError::internal)?;
+
+    metrics::counter!("api.v1.attestations.challenges", 1);
+    Ok(Json(challenge))
+}
+
+async fn submit_response(
+    data: web::Data<ApiAttestationsData>,
+    Json(response): Json<AttestationResponse>,
+) -> JsonResult<AddressAttestation> {
+    let mut storage = data
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    let attestation = storage
+        .attestations_schema()
+        .get_attestation(response.challenge)
+        .await
+        .map_err(ApiError::internal)?
+        .ok_or_else(|| ApiError::bad_request("Unknown challenge"))?;
+    if attestation.status != AttestationStatus::Pending {
+        return Err(ApiError::bad_request("Challenge can't be answered")
+            .detail(format!("Challenge is {:?}", attestation.status).to_lowercase()));
+    }
+
+    // The incorrect signatures are not recorded, so the challenge can't be spoiled by anyone
+    // who has seen it.
+    if !attestation.verify(&response.signature, data.signature_domain.as_ref()) {
+        metrics::counter!("api.v1.attestations.rejected", 1);
+        return Err(ApiError::bad_request("Incorrect signature")
+            .detail("Challenge must be signed by the owner of the address"));
+    }
+    let attestation = storage
+        .attestations_schema()
+        .record_verified(response.challenge)
+        .await
+        .map_err(ApiError::internal)?
+        .ok_or_else(|| {
+            ApiError::bad_request("Challenge can't be answered")
+                .detail("Challenge was answered concurrently or has just expired")
+        })?;
+
+    metrics::counter!("api.v1.attestations.verified", 1);
+    Ok(Json(attestation))
+}
+
+async fn attestation(
+    data: web::Data<ApiAttestationsData>,
+    web::Path(challenge): web::Path<H256>,
+) -> JsonResult<Option<AddressAttestation>> {
+    let mut storage = data
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    let attestation = storage
+        .attestations_schema()
+        .get_attestation(challenge)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(attestation))
+}
+
+async fn address_attestations(
+    data: web::Data<ApiAttestationsData>,
+    web::Path(address): web::Path<String>,
+) -> JsonResult<Vec<AddressAttestation>> {
+    let address = parse_path_address(&address)?;
+    let mut storage = data
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    let attestations = storage
+        .attestations_schema()
+        .address_attestations(address, MAX_ADDRESS_ATTESTATIONS)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(attestations))
+}
+
+pub fn api_scope(pool: ConnectionPool, signature_domain: Option<SignatureDomain>) -> Scope {
+    let data = ApiAttestationsData {
+        pool,
+        signature_domain,
+    };
+
+    web::scope("attestations")
+        .data(data)
+        .route("challenges", web::post().to(issue_challenge))
+        .route("", web::post().to(submit_response))
+        .route("challenges/{challenge}", web::get().to(attestation))
+        .route("address/{address}", web::get().to(address_attestations))
+}
@@ -1,6 +1,7 @@
 //! Blocks part of API implementation.
 
 // Built-in uses
+use std::collections::BTreeMap;
 
 // External uses
 use actix_web::{
@@ -9,13 +10,21 @@ use actix_web::{
 };
 
 // Workspace uses
-pub use zksync_api_client::rest::v1::{BlockInfo, TransactionInfo};
+pub use zksync_api_client::rest::v1::{
+    AccountStateDiff, BalanceDiff, BlockInfo, BlockRevertsQuery, BlockStateDiff, FinalityQuery,
+    PubKeyHashDiff, TransactionInfo,
+};
 use zksync_crypto::{convert::FeConvert, Fr};
 use zksync_storage::{chain::block::records, ConnectionPool, QueryResult};
-use zksync_types::{tx::TxHash, BlockNumber};
+use zksync_types::{
+    block::BlockRevert,
+    finality::{Finality, FinalizedBlocks},
+    tx::TxHash,
+    AccountUpdate, AccountUpdates, BlockNumber, TokenId,
+};
 
 // Local uses
-use super::{Error as ApiError, JsonResult, Pagination, PaginationQuery};
+use super::{Error as ApiError, JsonResult, Pagination, PaginationQuery, MAX_LIMIT};
 use crate::{
     api_server::helpers::try_parse_tx_hash, utils::block_details_cache::BlockDetailsCache,
 };
@@ -76,6 +85,32 @@ impl ApiBlocksData {
             .get_block_transactions(block_number)
             .await
     }
+
+    /// Returns the last blocks that have reached each of the finality levels.
+    async fn finalized_blocks(&self) -> QueryResult<FinalizedBlocks> {
+        let mut storage = self.pool.access_storage().await?;
+        storage.chain().block_schema().load_finalized_blocks().await
+    }
+
+    /// Returns the changes of the accounts state stored when the block was sealed.
+    async fn block_state_diff(&self, block_number: BlockNumber) -> QueryResult<AccountUpdates> {
+        let mut storage = self.pool.access_storage().await?;
+        storage
+            .chain()
+            .state_schema()
+            .load_state_diff_for_block(block_number)
+            .await
+    }
+
+    /// Returns the latest reverts of the unverified blocks.
+    async fn block_reverts(&self, limit: u32) -> QueryResult<Vec<BlockRevert>> {
+        let mut storage = self.pool.access_storage().await?;
+        storage
+            .chain()
+            .block_schema()
+            .load_block_reverts(limit)
+            .await
+    }
 }
 
 pub(super) mod convert {
@@ -83,9 +118,13 @@ pub(super) mod convert {
 
     use super::*;
 
-    pub fn block_info_from_details(inner: records::BlockDetails) -> BlockInfo {
+    pub fn block_info_from_details(
+        inner: records::BlockDetails,
+        finalized: &FinalizedBlocks,
+    ) -> BlockInfo {
+        let block_number = BlockNumber(inner.block_number as u32);
         BlockInfo {
-                block_number: BlockNumber(inner.block_number as u32),
+                block_number,
                 new_state_root: Fr::from_bytes(&inner.new_state_root).unwrap_or_else(|err| {
                     panic!(
                         "Database provided an incorrect new_state_root field: {:?}, an error occurred {}",
@@ -111,11 +150,13 @@ pub(super) mod convert {
                 }),
                 committed_at: inner.committed_at,
                 verified_at: inner.verified_at,
+                finality: finalized.block_finality(block_number),
             }
     }
 
     pub fn transaction_info_from_transaction_item(
         inner: records::BlockTransactionItem,
+        finality: Finality,
     ) -> TransactionInfo {
         TransactionInfo {
             tx_hash: try_parse_tx_hash(&inner.tx_hash).unwrap_or_else(|err| {
@@ -129,6 +170,90 @@ pub(super) mod convert {
             success: inner.success,
             fail_reason: inner.fail_reason,
             created_at: inner.created_at,
+            finality,
+        }
+    }
+
+    /// Merges the account updates made by the block into the per-account diff.
+    pub fn block_state_diff_from_updates(
+        block_number: BlockNumber,
+        updates: AccountUpdates,
+    ) -> BlockStateDiff {
+        let mut accounts = BTreeMap::new();
+        for (account_id, update) in updates {
+            let (old_nonce, new_nonce) = match &update {
+                AccountUpdate::Create { nonce, .. } | AccountUpdate::Delete { nonce, .. } => {
+                    (*nonce, *nonce)
+                }
+                AccountUpdate::UpdateBalance {
+                    old_nonce,
+                    new_nonce,
+                    ..
+                }
+                | AccountUpdate::ChangePubKeyHash {
+                    old_nonce,
+                    new_nonce,
+                    ..
+                } => (*old_nonce, *new_nonce),
+            };
+
+            // Balances are collected into the map first, so the changes of the same token
+            // are merged.
+            let (diff, balances): &mut (_, BTreeMap<TokenId, BalanceDiff>) =
+                accounts.entry(account_id).or_insert_with(|| {
+                    let diff = AccountStateDiff {
+                        account_id,
+                        created: None,
+                        deleted: None,
+                        old_nonce,
+                        new_nonce,
+                        balances: Vec::new(),
+                        pub_key_hash: None,
+                    };
+                    (diff, BTreeMap::new())
+                });
+            diff.new_nonce = new_nonce;
+
+            match update {
+                AccountUpdate::Create { address, .. } => diff.created = Some(address),
+                AccountUpdate::Delete { address, .. } => diff.deleted = Some(address),
+                AccountUpdate::UpdateBalance {
+                    balance_update: (token, old_balance, new_balance),
+                    ..
+                } => {
+                    balances
+                        .entry(token)
+                        .or_insert_with(|| BalanceDiff {
+                            token,
+                            old_balance: old_balance.into(),
+                            new_balance: Default::default(),
+                        })
+                        .new_balance = new_balance.into();
+                }
+                AccountUpdate::ChangePubKeyHash {
+                    old_pub_key_hash,
+                    new_pub_key_hash,
+                    ..
+                } => {
+                    diff.pub_key_hash
+                        .get_or_insert(PubKeyHashDiff {
+                            old_pub_key_hash,
+                            new_pub_key_hash,
+                        })
+                        .new_pub_key_hash = new_pub_key_hash;
+                }
+            }
+        }
+
+        BlockStateDiff {
+            block_number,
+            accounts: accounts
+                .into_iter()
+                .map(|(_, (mut diff, balances))| {
+                    diff.balances = balances.into_iter().map(|(_, balance)| balance).collect();
+                    diff
+                })
+                .collect(),
         }
     }
 
@@ -144,28 +269,41 @@ pub(super) mod convert {
 async fn block_by_id(
     data: web::Data<ApiBlocksData>,
     web::Path(block_number): web::Path<BlockNumber>,
+    web::Query(query): web::Query<FinalityQuery>,
 ) -> JsonResult<Option<BlockInfo>> {
+    let finalized = data.finalized_blocks().await.map_err(ApiError::internal)?;
+    if !finalized.is_finalized(block_number, query.min_finality()) {
+        return Ok(Json(None));
+    }
+
     Ok(Json(
         data.block_info(block_number)
             .await
             .map_err(ApiError::internal)?
-            .map(convert::block_info_from_details),
+            .map(|block| convert::block_info_from_details(block, &finalized)),
     ))
 }
 
 async fn block_transactions(
     data: web::Data<ApiBlocksData>,
     web::Path(block_number): web::Path<BlockNumber>,
+    web::Query(query): web::Query<FinalityQuery>,
 ) -> JsonResult<Vec<TransactionInfo>> {
+    let finalized = data.finalized_blocks().await.map_err(ApiError::internal)?;
+    if !finalized.is_finalized(block_number, query.min_finality()) {
+        return Ok(Json(vec![]));
+    }
+
     let transactions = data
         .block_transactions(block_number)
         .await
         .map_err(ApiError::internal)?;
 
+    let finality = finalized.block_finality(block_number);
     Ok(Json(
         transactions
             .into_iter()
-            .map(convert::transaction_info_from_transaction_item)
+            .map(|tx| convert::transaction_info_from_transaction_item(tx, finality))
             .collect(),
     ))
 }
@@ -173,10 +311,18 @@ async fn block_transactions(
 async fn blocks_range(
     data: web::Data<ApiBlocksData>,
     web::Query(pagination): web::Query<PaginationQuery>,
+    web::Query(query): web::Query<FinalityQuery>,
 ) -> JsonResult<Vec<BlockInfo>> {
     let (pagination, limit) = pagination.into_inner()?;
     let max = pagination.into_max(limit)?;
 
+    let finalized = data.finalized_blocks().await.map_err(ApiError::internal)?;
+    // Blocks that haven't reached the requested finality are skipped.
+    let max = match finalized.last_block(query.min_finality()) {
+        Some(last_block) => Some(max.map_or(last_block, |max| max.min(last_block))),
+        None => max,
+    };
+
     let range = data
         .blocks_range(max, limit)
         .await
@@ -187,31 +333,75 @@ async fn blocks_range(
         range
             .into_iter()
             .filter(|block| block.block_number > *after as i64)
-            .map(convert::block_info_from_details)
+            .map(|block| convert::block_info_from_details(block, &finalized))
             .collect()
     } else {
         range
             .into_iter()
-            .map(convert::block_info_from_details)
+            .map(|block| convert::block_info_from_details(block, &finalized))
             .collect()
     };
 
     Ok(Json(range))
 }
 
+async fn block_state_diff(
+    data: web::Data<ApiBlocksData>,
+    web::Path(block_number): web::Path<BlockNumber>,
+) -> JsonResult<Option<BlockStateDiff>> {
+    let block = data
+        .block_info(block_number)
+        .await
+        .map_err(ApiError::internal)?;
+    if block.is_none() {
+        return Ok(Json(None));
+    }
+
+    let updates = data
+        .block_state_diff(block_number)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(Some(convert::block_state_diff_from_updates(
+        block_number,
+        updates,
+    ))))
+}
+
+async fn block_reverts(
+    data: web::Data<ApiBlocksData>,
+    web::Query(query): web::Query<BlockRevertsQuery>,
+) -> JsonResult<Vec<BlockRevert>> {
+    if query.limit == 0 || query.limit > MAX_LIMIT {
+        return Err(ApiError::bad_request("Incorrect limit")
+            .detail(format!("Limit should be between 1 and {}", MAX_LIMIT)));
+    }
+
+    let reverts = data
+        .block_reverts(query.limit)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(reverts))
+}
+
 pub fn api_scope(pool: ConnectionPool, cache: BlockDetailsCache) -> Scope {
     let data = ApiBlocksData::new(pool, cache);
 
     web::scope("blocks")
         .data(data)
         .route("", web::get().to(blocks_range))
+        .route("reverts", web::get().to(block_reverts))
         .route("{id}", web::get().to(block_by_id))
         .route("{id}/transactions", web::get().to(block_transactions))
+        .route("{id}/state_diff", web::get().to(block_state_diff))
 }
 
 #[cfg(test)]
 mod tests {
     use super::{super::test_utils::TestServerConfig, *};
+    use num::BigUint;
+    use zksync_types::{AccountId, Address, Nonce, PubKeyHash};
 
     #[actix_rt::test]
     #[cfg_attr(
@@ -226,7 +416,7 @@ mod tests {
             cfg.start_server(|cfg| api_scope(cfg.pool.clone(), BlockDetailsCache::new(10)));
 
         // Block requests part
-        let blocks: Vec<BlockInfo> = {
+        let (blocks, finalized): (Vec<BlockInfo>, _) = {
             let mut storage = cfg.pool.access_storage().await?;
 
             let blocks = storage
@@ -234,17 +424,24 @@ mod tests {
                 .block_schema()
                 .load_block_range(BlockNumber(10), 10)
                 .await?;
+            let finalized = storage
+                .chain()
+                .block_schema()
+                .load_finalized_blocks()
+                .await?;
 
-            blocks
+            let blocks = blocks
                 .into_iter()
-                .map(convert::block_info_from_details)
-                .collect()
+                .map(|block| convert::block_info_from_details(block, &finalized))
+                .collect();
+            (blocks, finalized)
         };
 
         assert_eq!(
             client.block_by_id(BlockNumber(1)).await?.unwrap(),
             blocks[7]
         );
+        assert_eq!(blocks[7].finality, Finality::Executed);
         assert_eq!(client.blocks_range(Pagination::Last, 10).await?, blocks);
         assert_eq!(
             client
@@ -259,6 +456,26 @@ mod tests {
             &blocks[0..1]
         );
 
+        // Finality part.
+        assert_eq!(
+            client
+                .blocks_range_at_finality(Pagination::Last, 10, Finality::Executed)
+                .await?,
+            &blocks[5..8]
+        );
+        assert_eq!(
+            client
+                .block_by_id_at_finality(BlockNumber(4), Finality::Executed)
+                .await?,
+            None
+        );
+        assert_eq!(
+            client
+                .block_by_id_at_finality(BlockNumber(4), Finality::Verified)
+                .await?,
+            Some(blocks[4].clone())
+        );
+
         // Transaction requests part.
         let expected_txs: Vec<TransactionInfo> = {
             let mut storage = cfg.pool.access_storage().await?;
@@ -271,16 +488,138 @@ mod tests {
 
             transactions
                 .into_iter()
-                .map(convert::transaction_info_from_transaction_item)
+                .map(|tx| {
+                    convert::transaction_info_from_transaction_item(
+                        tx,
+                        finalized.block_finality(BlockNumber(1)),
+                    )
+                })
                 .collect()
         };
         assert_eq!(
             client.block_transactions(BlockNumber(1)).await?,
             expected_txs
         );
+        assert_eq!(
+            client
+                .block_transactions_at_finality(BlockNumber(1), Finality::Executed)
+                .await?,
+            expected_txs
+        );
         assert_eq!(client.block_transactions(BlockNumber(6)).await?, vec![]);
+        // Block 5 contains transactions, but it's not executed yet.
+        assert_eq!(
+            client
+                .block_transactions_at_finality(BlockNumber(5), Finality::Executed)
+                .await?,
+            vec![]
+        );
+
+        // Block reverts part.
+        assert_eq!(client.block_reverts(10).await?, vec![]);
+
+        // State diff part.
+        let expected_diff = {
+            let mut storage = cfg.pool.access_storage().await?;
+            let updates = storage
+                .chain()
+                .state_schema()
+                .load_state_diff_for_block(BlockNumber(1))
+                .await?;
+            convert::block_state_diff_from_updates(BlockNumber(1), updates)
+        };
+        assert_eq!(
+            client.block_state_diff(BlockNumber(1)).await?,
+            Some(expected_diff)
+        );
+        assert_eq!(client.block_state_diff(BlockNumber(100)).await?, None);
 
         server.stop().await;
         Ok(())
     }
+
+    #[test]
+    fn state_diff_merging() {
+        let address = Address::repeat_byte(1);
+        let deleted_address = Address::repeat_byte(2);
+        let balance_update =
+            |old_nonce: u32, token: u16, old: u32, new: u32| AccountUpdate::UpdateBalance {
+                old_nonce: Nonce(old_nonce),
+                new_nonce: Nonce(old_nonce + 1),
+                balance_update: (TokenId(token), old.into(), new.into()),
+            };
+        let updates = vec![
+            (
+                AccountId(3),
+                AccountUpdate::Create {
+                    address,
+                    nonce: Nonce(0),
+                },
+            ),
+            (AccountId(3), balance_update(0, 0, 0, 100)),
+            (AccountId(1), balance_update(5, 2, 10, 7)),
+            (AccountId(3), balance_update(1, 0, 100, 60)),
+            (
+                AccountId(3),
+                AccountUpdate::ChangePubKeyHash {
+                    old_pub_key_hash: PubKeyHash::default(),
+                    new_pub_key_hash: PubKeyHash { data: [1; 20] },
+                    old_nonce: Nonce(2),
+                    new_nonce: Nonce(3),
+                },
+            ),
+            (
+                AccountId(4),
+                AccountUpdate::Delete {
+                    address: deleted_address,
+                    nonce: Nonce(2),
+                },
+            ),
+        ];
+
+        let diff = convert::block_state_diff_from_updates(BlockNumber(1), updates);
+        assert_eq!(
+            diff.accounts,
+            vec![
+                AccountStateDiff {
+                    account_id: AccountId(1),
+                    created: None,
+                    deleted: None,
+                    old_nonce: Nonce(5),
+                    new_nonce: Nonce(6),
+                    balances: vec![BalanceDiff {
+                        token: TokenId(2),
+                        old_balance: BigUint::from(10u32).into(),
+                        new_balance: BigUint::from(7u32).into(),
+                    }],
+                    pub_key_hash: None,
+                },
+                AccountStateDiff {
+                    account_id: AccountId(3),
+                    created: Some(address),
+                    deleted: None,
+                    old_nonce: Nonce(0),
+                    new_nonce: Nonce(3),
+                    balances: vec![BalanceDiff {
+                        token: TokenId(0),
+                        old_balance: BigUint::from(0u32).into(),
+                        new_balance: BigUint::from(60u32).into(),
+                    }],
+                    pub_key_hash: Some(PubKeyHashDiff {
+                        old_pub_key_hash: PubKeyHash::default(),
+                        new_pub_key_hash: PubKeyHash { data: [1; 20] },
+                    }),
+                },
+                AccountStateDiff {
+                    account_id: AccountId(4),
+                    created: None,
+                    deleted: Some(deleted_address),
+                    old_nonce: Nonce(2),
+                    new_nonce: Nonce(2),
+                    balances: Vec::new(),
+                    pub_key_hash: None,
+                },
+            ]
+        );
+    }
 }
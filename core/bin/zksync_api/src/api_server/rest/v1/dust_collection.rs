@@ -0,0 +1,92 @@
+//! Dust collection part of API implementation.
+//!
+//! Owners of the accounts exclude them from the dust collection by submitting the signed
+//! opt-out requests. The registry of the opted out accounts is public.
+//! See `zksync_types::dust_collection` for the details.
+
+// Built-in uses
+
+// External uses
+use actix_web::{
+    web::{self, Json},
+    Scope,
+};
+
+// Workspace uses
+use zksync_storage::ConnectionPool;
+use zksync_types::{
+    dust_collection::{DustCollectionOptOut, DustCollectionStatus},
+    tx::SignatureDomain,
+};
+
+// Local uses
+use super::{Error as ApiError, JsonResult};
+use crate::api_server::rest::address_checksum::parse_path_address;
+
+/// Shared data between `api/v1/dust_collection` endpoints.
+#[derive(Clone)]
+struct ApiDustCollectionData {
+    pool: ConnectionPool,
+    /// Network the signed messages must be bound to, see `SignatureDomain`.
+    signature_domain: Option<SignatureDomain>,
+}
+
+// Server implementation
+
+async fn opt_out(
+    data: web::Data<ApiDustCollectionData>,
+    Json(opt_out): Json<DustCollectionOptOut>,
+) -> JsonResult<()> {
+    if !opt_out.verify(data.signature_domain.as_ref()) {
+        return Err(ApiError::bad_request("Incorrect signature")
+            .detail("Opt-out message must be signed by the account address"));
+    }
+
+    let mut storage = data
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    storage
+        .dust_collection_schema()
+        .store_opt_out(opt_out.address)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(()))
+}
+
+async fn status(
+    data: web::Data<ApiDustCollectionData>,
+    web::Path(address): web::Path<String>,
+) -> JsonResult<DustCollectionStatus> {
+    let address = parse_path_address(&address)?;
+    let mut storage = data
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    let opted_out_at = storage
+        .dust_collection_schema()
+        .get_opt_out(address)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(DustCollectionStatus {
+        address,
+        opted_out: opted_out_at.is_some(),
+        opted_out_at,
+    }))
+}
+
+pub fn api_scope(pool: ConnectionPool, signature_domain: Option<SignatureDomain>) -> Scope {
+    let data = ApiDustCollectionData {
+        pool,
+        signature_domain,
+    };
+
+    web::scope("dust_collection")
+        .data(data)
+        .route("opt_outs", web::post().to(opt_out))
+        .route("opt_outs/{address}", web::get().to(status))
+}
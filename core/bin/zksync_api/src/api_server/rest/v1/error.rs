@@ -3,10 +3,14 @@ use std::fmt::{self, Display};
 
 // External uses
 use actix_web::{dev::Body, http::HeaderValue, HttpResponse, ResponseError};
-use reqwest::{header::CONTENT_TYPE, StatusCode};
+use reqwest::{
+    header::{CONTENT_TYPE, RETRY_AFTER},
+    StatusCode,
+};
 
 // Workspace uses
 pub use zksync_api_client::rest::v1::ErrorBody;
+use zksync_types::api_error::ApiErrorCode;
 
 // Local uses
 
@@ -17,6 +21,8 @@ pub struct Error {
     pub http_code: StatusCode,
     /// HTTP error content serialized into JSON.
     pub body: ErrorBody,
+    /// Time after which the request may be retried, in seconds.
+    pub retry_after: Option<u64>,
 }
 
 impl Error {
@@ -35,11 +41,26 @@ impl Error {
         Self::with_code(StatusCode::NOT_IMPLEMENTED, title)
     }
 
+    /// Creates a new Error with the UNAUTHORIZED (401) status code.
+    pub fn unauthorized(title: impl Display) -> Self {
+        Self::with_code(StatusCode::UNAUTHORIZED, title)
+    }
+
     /// Creates a new Error with the NOT_FOUND (404) status code.
     pub fn not_found(title: impl Display) -> Self {
         Self::with_code(StatusCode::NOT_FOUND, title)
     }
 
+    /// Creates a new Error with the TOO_MANY_REQUESTS (429) status code.
+    pub fn too_many_requests(title: impl Display) -> Self {
+        Self::with_code(StatusCode::TOO_MANY_REQUESTS, title)
+    }
+
+    /// Creates a new Error with the SERVICE_UNAVAILABLE (503) status code.
+    pub fn service_unavailable(title: impl Display) -> Self {
+        Self::with_code(StatusCode::SERVICE_UNAVAILABLE, title)
+    }
+
     fn with_code(http_code: StatusCode, title: impl Display) -> Self {
         Self {
             http_code,
@@ -47,6 +68,7 @@ impl Error {
                 title: title.to_string(),
                 ..ErrorBody::default()
             },
+            retry_after: None,
         }
     }
 
@@ -67,6 +89,19 @@ impl Error {
         self.body.code = Some(code);
         self
     }
+
+    /// Sets the time after which the request may be retried, in seconds.
+    pub fn retry_after(mut self, seconds: u64) -> Self {
+        self.retry_after = Some(seconds);
+        self
+    }
+
+    /// Sets the stable API error code together with its name.
+    pub fn error_code(mut self, code: ApiErrorCode) -> Self {
+        self.body.code = Some(code.rest_code());
+        self.body.error_type = Some(code);
+        self
+    }
 }
 
 impl Display for Error {
@@ -82,6 +117,10 @@ impl ResponseError for Error {
 
     fn error_response(&self) -> actix_web::HttpResponse {
         let mut resp = HttpResponse::new(self.status_code());
+        if let Some(retry_after) = self.retry_after {
+            resp.headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(retry_after));
+        }
 
         match serde_json::to_vec_pretty(&self.body) {
             Ok(body) => {
@@ -0,0 +1,59 @@
+//! Event log part of API implementation.
+//!
+//! Exposes the durable log of the chain events, which can be consumed starting from any offset.
+//! See `zksync_types::event` for the details.
+
+// Built-in uses
+
+// External uses
+use actix_web::{
+    web::{self, Json},
+    Scope,
+};
+
+// Workspace uses
+use zksync_api_client::rest::v1::EventsQuery;
+use zksync_storage::ConnectionPool;
+use zksync_types::event::EventRecord;
+
+// Local uses
+use super::{Error as ApiError, JsonResult, MAX_LIMIT};
+
+/// Shared data between `api/v1/events` endpoints.
+#[derive(Debug, Clone)]
+struct ApiEventsData {
+    pool: ConnectionPool,
+}
+
+// Server implementation
+
+async fn events(
+    data: web::Data<ApiEventsData>,
+    web::Query(query): web::Query<EventsQuery>,
+) -> JsonResult<Vec<EventRecord>> {
+    if query.limit == 0 || query.limit > MAX_LIMIT {
+        return Err(ApiError::bad_request("Incorrect limit")
+            .detail(format!("Limit should be between 1 and {}", MAX_LIMIT)));
+    }
+
+    let mut storage = data
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    let events = storage
+        .event_schema()
+        .load_events(query.from, query.limit)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(events))
+}
+
+pub fn api_scope(pool: ConnectionPool) -> Scope {
+    let data = ApiEventsData { pool };
+
+    web::scope("events")
+        .data(data)
+        .route("", web::get().to(events))
+}
@@ -0,0 +1,90 @@
+//! Fast withdrawals part of API implementation.
+//!
+//! Users offer their withdrawals to the liquidity providers, which discover the pending
+//! intents and fulfill them with the payout transfers.
+//! See `zksync_types::fast_withdrawals` for the protocol details.
+
+// Built-in uses
+
+// External uses
+use actix_web::{
+    web::{self, Json},
+    Scope,
+};
+
+// Workspace uses
+use zksync_api_client::rest::v1::{IncomingTx, PendingIntentsQuery};
+use zksync_types::{
+    fast_withdrawals::{FastWithdrawalIntent, FastWithdrawalIntentId, FastWithdrawalOffer},
+    tx::TxHash,
+};
+
+// Local uses
+use super::{Error as ApiError, JsonResult, MAX_LIMIT};
+use crate::api_server::{rpc_server::types::TxWithSignature, tx_sender::TxSender};
+
+/// Shared data between `api/v1/fast_withdrawals` endpoints.
+#[derive(Clone)]
+struct ApiFastWithdrawalsData {
+    tx_sender: TxSender,
+}
+
+// Server implementation
+
+async fn submit_intent(
+    data: web::Data<ApiFastWithdrawalsData>,
+    Json(intent): Json<FastWithdrawalIntent>,
+) -> JsonResult<FastWithdrawalIntentId> {
+    let id = data
+        .tx_sender
+        .submit_fast_withdrawal_intent(intent)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(id))
+}
+
+async fn pending_intents(
+    data: web::Data<ApiFastWithdrawalsData>,
+    web::Query(query): web::Query<PendingIntentsQuery>,
+) -> JsonResult<Vec<FastWithdrawalOffer>> {
+    if query.limit == 0 || query.limit > MAX_LIMIT {
+        return Err(ApiError::bad_request("Incorrect limit")
+            .detail(format!("Limit should be between 1 and {}", MAX_LIMIT)));
+    }
+    let offers = data
+        .tx_sender
+        .pending_fast_withdrawal_offers(query.token, query.limit)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(offers))
+}
+
+async fn fulfill_intent(
+    data: web::Data<ApiFastWithdrawalsData>,
+    web::Path(id): web::Path<FastWithdrawalIntentId>,
+    Json(body): Json<IncomingTx>,
+) -> JsonResult<Vec<TxHash>> {
+    let transfer = TxWithSignature {
+        tx: body.tx,
+        signature: body.signature,
+    };
+    let tx_hashes = data
+        .tx_sender
+        .fulfill_fast_withdrawal_intent(id, transfer)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(tx_hashes))
+}
+
+pub fn api_scope(tx_sender: TxSender) -> Scope {
+    let data = ApiFastWithdrawalsData { tx_sender };
+
+    web::scope("fast_withdrawals")
+        .data(data)
+        .route("", web::post().to(submit_intent))
+        .route("pending", web::get().to(pending_intents))
+        .route("{id}/fulfill", web::post().to(fulfill_intent))
+}
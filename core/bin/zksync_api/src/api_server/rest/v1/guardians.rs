@@ -0,0 +1,359 @@
+//! Guardians part of API implementation.
+//!
+//! Accounts register the guardians allowed to restore the access to the account, and the
+//! guardians initiate the recoveries starting the timelock of the guardian set. Once it passes,
+//! the mempool accepts the `ChangePubKey` transaction authorized by the guardians.
+//! See `zksync_types::guardians` for the details.
+
+// Built-in uses
+
+// External uses
+use actix_web::{
+    web::{self, Json},
+    Scope,
+};
+use chrono::{Duration, Utc};
+
+// Workspace uses
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::{
+    guardians::{
+        GuardianRecovery, GuardianRecoveryRequest, GuardianRegistration,
+        GuardianRegistrationRequest,
+    },
+    tx::SignatureDomain,
+    Account, AccountId,
+};
+
+// Local uses
+use super::{Error as ApiError, JsonResult};
+
+/// Shared data between `api/v1/guardians` endpoints.
+#[derive(Clone)]
+struct ApiGuardiansData {
+    pool: ConnectionPool,
+    /// Network the guardian signatures are bound to. Unlike the other messages, the guardian
+    /// sets are always bound to it, since their signatures are checked by the contract.
+    signature_domain: SignatureDomain,
+}
+
+async fn committed_account(
+    storage: &mut StorageProcessor<'_>,
+    account_id: AccountId,
+) -> Result<Account, ApiError> {
+    storage
+        .chain()
+        .account_schema()
+        .last_committed_state_for_account(account_id)
+        .await
+        .map_err(ApiError::internal)?
+        .ok_or_else(|| ApiError::bad_request("Account does not exist"))
+}
+
+// Server implementation
+
+async fn register(
+    data: web::Data<ApiGuardiansData>,
+    Json(request): Json<GuardianRegistrationRequest>,
+) -> JsonResult<GuardianRegistration> {
+    request
+        .guardian_set
+        .check()
+        .map_err(|err| ApiError::bad_request("Incorrect guardian set").detail(err))?;
+
+    let mut storage = data
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    let account = committed_account(&mut storage, request.account_id).await?;
+    if !request.guardian_set.verify_owner(
+        request.account_id,
+        &data.signature_domain,
+        account.address,
+        &request.owner_signature,
+    ) {
+        return Err(ApiError::bad_request("Incorrect signature")
+            .detail("Guardian set must be signed by the account address"));
+    }
+
+    let registration = GuardianRegistration {
+        account_id: request.account_id,
+        address: account.address,
+        guardian_set: request.guardian_set,
+        owner_signature: request.owner_signature,
+        registered_at: Utc::now(),
+    };
+    let registered = storage
+        .guardians_schema()
+        .register_guardians(&registration)
+        .await
+        .map_err(ApiError::internal)?;
+    if !registered {
+        return Err(
+            ApiError::bad_request("Guardian set has already been registered")
+                .detail("Version of the set must be greater than the one of the registered set"),
+        );
+    }
+
+    metrics::counter!("api.v1.guardians.registered", 1);
+    Ok(Json(registration))
+}
+
+async fn guardians(
+    data: web::Data<ApiGuardiansData>,
+    web::Path(account_id): web::Path<u32>,
+) -> JsonResult<Option<GuardianRegistration>> {
+    let mut storage = data
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    let registration = storage
+        .guardians_schema()
+        .get_guardians(AccountId(account_id))
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(registration))
+}
+
+async fn initiate_recovery(
+    data: web::Data<ApiGuardiansData>,
+    Json(request): Json<GuardianRecoveryRequest>,
+) -> JsonResult<GuardianRecovery> {
+    let mut storage = data
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    let registration = storage
+        .guardians_schema()
+        .get_guardians(request.account_id)
+        .await
+        .map_err(ApiError::internal)?
+        .ok_or_else(|| ApiError::bad_request("Account has no guardians"))?;
+    // The recovery is bound to the nonce, so it's cancelled by any transaction of the owner.
+    let account = committed_account(&mut storage, request.account_id).await?;
+    if account.nonce != request.nonce {
+        return Err(ApiError::bad_request("Nonce mismatch")
+            .detail("Recovery must be initiated for the current nonce of the account"));
+    }
+
+    let guardian_set = &registration.guardian_set;
+    let recovery_hash = guardian_set.recovery_hash(
+        request.account_id,
+        &data.signature_domain,
+        &request.new_pk_hash,
+        request.nonce,
+    );
+    if !guardian_set.verify_quorum(&recovery_hash, &request.signatures) {
+        return Err(
+            ApiError::bad_request("Incorrect signatures").detail(format!(
+                "Recovery must be signed by {} guardians in the order of their indices",
+                guardian_set.quorum
+            )),
+        );
+    }
+
+    let recovery = storage
+        .guardians_schema()
+        .initiate_recovery(
+            request.account_id,
+            &request.new_pk_hash,
+            request.nonce,
+            &request.signatures,
+            Duration::seconds(guardian_set.timelock.into()),
+        )
+        .await
+        .map_err(ApiError::internal)?;
+
+    metrics::counter!("api.v1.guardians.recoveries_initiated", 1);
+    Ok(Json(recovery))
+}
+
+async fn recoveries(
+    data: web::Data<ApiGuardiansData>,
+    web::Path(account_id): web::Path<u32>,
+) -> JsonResult<Vec<GuardianRecovery>> {
+    let mut storage = data
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    let recoveries = storage
+        .guardians_schema()
+        .load_recoveries(AccountId(account_id))
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(recoveries))
+}
+
+pub fn api_scope(pool: ConnectionPool, signature_domain: SignatureDomain) -> Scope {
+    let data = ApiGuardiansData {
+        pool,
+        signature_domain,
+    };
+
+    web::scope("guardians")
+        .data(data)
+        .route("", web::post().to(register))
+        .route("recoveries", web::post().to(initiate_recovery))
+        .route("{account_id}", web::get().to(guardians))
+        .route("{account_id}/recoveries", web::get().to(recoveries))
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_api_client::rest::v1::ClientError;
+    use zksync_types::{
+        account::PubKeyHash,
+        guardians::{GuardianSet, GuardianSignature},
+        tx::PackedEthSignature,
+        AccountUpdate, BlockNumber, Nonce, H256,
+    };
+
+    use super::{
+        super::test_utils::{TestServerConfig, COMMITTED_BLOCKS_COUNT},
+        *,
+    };
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn guardians_scope() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let owner_key = H256::repeat_byte(7);
+        let owner = PackedEthSignature::address_from_private_key(&owner_key)?;
+        let account_id = AccountId(0xbeef);
+        cfg.pool
+            .access_storage()
+            .await?
+            .chain()
+            .state_schema()
+            .commit_state_update(
+                BlockNumber(COMMITTED_BLOCKS_COUNT),
+                &[(
+                    account_id,
+                    AccountUpdate::Create {
+                        address: owner,
+                        nonce: Nonce(0),
+                    },
+                )],
+                0,
+            )
+            .await?;
+
+        let domain = SignatureDomain::new(
+            cfg.config.eth_client.chain_id.into(),
+            cfg.config.contracts.contract_addr,
+        );
+        let (client, server) = cfg.start_server(move |cfg| api_scope(cfg.pool.clone(), domain));
+
+        assert_eq!(client.guardians(account_id).await?, None);
+
+        let guardian_keys: Vec<_> = (1..=3).map(H256::repeat_byte).collect();
+        let guardian_set = GuardianSet {
+            version: 1,
+            guardians: guardian_keys
+                .iter()
+                .map(|key| PackedEthSignature::address_from_private_key(key).unwrap())
+                .collect(),
+            quorum: 2,
+            timelock: 86400,
+        };
+        let owner_signature = PackedEthSignature::sign(
+            &owner_key,
+            guardian_set.hash(account_id, &domain).as_bytes(),
+        )?;
+        let request = GuardianRegistrationRequest {
+            account_id,
+            guardian_set: guardian_set.clone(),
+            owner_signature: owner_signature.clone(),
+        };
+
+        // The set must be signed by the owner of the account.
+        let error = client
+            .register_guardians(GuardianRegistrationRequest {
+                owner_signature: PackedEthSignature::sign(
+                    &guardian_keys[0],
+                    guardian_set.hash(account_id, &domain).as_bytes(),
+                )?,
+                ..request.clone()
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ClientError::BadRequest { .. }));
+
+        let registration = client.register_guardians(request.clone()).await?;
+        assert_eq!(registration.address, owner);
+        assert_eq!(registration.guardian_set, guardian_set);
+        let registered = client.guardians(account_id).await?.unwrap();
+        assert_eq!(registered.guardian_set, guardian_set);
+        assert_eq!(registered.owner_signature, owner_signature);
+        // The same version can't be registered again.
+        let error = client.register_guardians(request).await.unwrap_err();
+        assert!(matches!(error, ClientError::BadRequest { .. }));
+
+        let new_pk_hash = PubKeyHash::default();
+        let recovery_hash = guardian_set.recovery_hash(account_id, &domain, &new_pk_hash, Nonce(0));
+        let signatures: Vec<_> = [0_u8, 2]
+            .iter()
+            .map(|&index| GuardianSignature {
+                index,
+                signature: PackedEthSignature::sign(
+                    &guardian_keys[index as usize],
+                    recovery_hash.as_bytes(),
+                )
+                .unwrap(),
+            })
+            .collect();
+        let request = GuardianRecoveryRequest {
+            account_id,
+            new_pk_hash,
+            nonce: Nonce(0),
+            signatures: signatures.clone(),
+        };
+
+        // The quorum of the guardians must sign the recovery.
+        let error = client
+            .initiate_guardian_recovery(GuardianRecoveryRequest {
+                signatures: signatures[..1].to_vec(),
+                ..request.clone()
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ClientError::BadRequest { .. }));
+        // The recovery is bound to the current nonce of the account.
+        let error = client
+            .initiate_guardian_recovery(GuardianRecoveryRequest {
+                nonce: Nonce(1),
+                ..request.clone()
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ClientError::BadRequest { .. }));
+
+        let recovery = client.initiate_guardian_recovery(request.clone()).await?;
+        assert_eq!(recovery.account_id, account_id);
+        assert_eq!(recovery.signatures, signatures);
+        assert_eq!(
+            recovery.executable_at - recovery.initiated_at,
+            Duration::seconds(86400)
+        );
+        // Initiating the same recovery again doesn't restart the timelock.
+        assert_eq!(client.initiate_guardian_recovery(request).await?, recovery);
+        assert_eq!(
+            client.guardian_recoveries(account_id).await?,
+            vec![recovery]
+        );
+
+        server.stop().await;
+        Ok(())
+    }
+}
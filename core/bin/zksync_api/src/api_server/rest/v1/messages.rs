@@ -0,0 +1,62 @@
+//! L1 messages part of API implementation.
+//!
+//! Serves the messages sent to the zkSync accounts from L1 via the `sendMessage` method
+//! of the zkSync contract. See `zksync_types::l1_message` for the details.
+
+// Built-in uses
+
+// External uses
+use actix_web::{
+    web::{self, Json},
+    Scope,
+};
+
+// Workspace uses
+use zksync_api_client::rest::v1::L1MessagesQuery;
+use zksync_storage::ConnectionPool;
+use zksync_types::l1_message::DeliveredL1Message;
+
+// Local uses
+use super::{Error as ApiError, JsonResult, MAX_LIMIT};
+use crate::api_server::rest::address_checksum::parse_path_address;
+
+/// Shared data between `api/v1/messages` endpoints.
+#[derive(Debug, Clone)]
+struct ApiMessagesData {
+    pool: ConnectionPool,
+}
+
+// Server implementation
+
+async fn account_messages(
+    data: web::Data<ApiMessagesData>,
+    web::Path(address): web::Path<String>,
+    web::Query(query): web::Query<L1MessagesQuery>,
+) -> JsonResult<Vec<DeliveredL1Message>> {
+    let address = parse_path_address(&address)?;
+    if query.limit == 0 || query.limit > MAX_LIMIT {
+        return Err(ApiError::bad_request("Incorrect limit")
+            .detail(format!("Limit should be between 1 and {}", MAX_LIMIT)));
+    }
+
+    let mut storage = data
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    let messages = storage
+        .l1_messages_schema()
+        .load_account_messages(address, query.from_id, query.limit)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(messages))
+}
+
+pub fn api_scope(pool: ConnectionPool) -> Scope {
+    let data = ApiMessagesData { pool };
+
+    web::scope("messages")
+        .data(data)
+        .route("{address}", web::get().to(account_messages))
+}
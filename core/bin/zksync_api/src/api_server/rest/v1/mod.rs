@@ -9,22 +9,38 @@ use actix_web::{
 pub use Error as ApiError;
 // Workspace uses
 pub use zksync_api_client::rest::v1::{
-    Client, ClientError, Pagination, PaginationQuery, MAX_LIMIT,
+    Client, ClientError, FinalityQuery, Pagination, PaginationQuery, MAX_LIMIT,
 };
 use zksync_config::ZkSyncConfig;
+use zksync_eth_client::EthereumGateway;
 
 // Local uses
-use crate::api_server::tx_sender::TxSender;
+use self::snapshots::ApiSnapshotsData;
+use crate::{
+    api_server::{rest::versioning::ApiVersion, tx_sender::TxSender},
+    eth_checker::EthereumChecker,
+};
 
 // Public uses
 pub use self::error::{Error, ErrorBody};
 
 pub(crate) mod accounts;
+mod activations;
+mod aliases;
+mod attestations;
 mod blocks;
 mod config;
+mod dust_collection;
 pub mod error;
+mod events;
+mod fast_withdrawals;
+mod guardians;
+mod messages;
+mod nonce_reservations;
 mod operations;
+mod priority_queue;
 mod search;
+pub(crate) mod snapshots;
 #[cfg(test)]
 pub mod test_utils;
 mod tokens;
@@ -32,25 +48,71 @@ mod transactions;
 
 pub type JsonResult<T> = std::result::Result<web::Json<T>, Error>;
 
-pub(crate) fn api_scope(tx_sender: TxSender, zk_config: &ZkSyncConfig) -> Scope {
-    web::scope("/api/v1")
+pub(crate) fn api_scope(
+    tx_sender: TxSender,
+    zk_config: &ZkSyncConfig,
+    snapshots_data: ApiSnapshotsData,
+    eth_gateway: EthereumGateway,
+) -> Scope {
+    let mut scope = web::scope(ApiVersion::V1.prefix())
         .service(accounts::api_scope(
             tx_sender.pool.clone(),
             zk_config,
             tx_sender.tokens.clone(),
+            tx_sender.accounts.clone(),
             tx_sender.core_api_client.clone(),
+            EthereumChecker::new(eth_gateway, Default::default()),
         ))
         .service(config::api_scope(&zk_config))
         .service(blocks::api_scope(
             tx_sender.pool.clone(),
             tx_sender.blocks.clone(),
         ))
-        .service(transactions::api_scope(tx_sender.clone()))
+        .service(transactions::api_scope(tx_sender.clone(), zk_config))
+        .service(events::api_scope(tx_sender.pool.clone()))
+        .service(dust_collection::api_scope(
+            tx_sender.pool.clone(),
+            tx_sender.signature_domain,
+        ))
+        .service(fast_withdrawals::api_scope(tx_sender.clone()))
+        .service(messages::api_scope(tx_sender.pool.clone()))
+        .service(activations::api_scope(tx_sender.clone()))
+        .service(nonce_reservations::api_scope(
+            tx_sender.pool.clone(),
+            zk_config,
+            tx_sender.signature_domain,
+        ))
         .service(operations::api_scope(tx_sender.pool.clone()))
+        .service(attestations::api_scope(
+            tx_sender.pool.clone(),
+            tx_sender.signature_domain,
+        ))
+        .service(priority_queue::api_scope(
+            tx_sender.pool.clone(),
+            tx_sender.core_api_client.clone(),
+            zk_config,
+        ))
         .service(search::api_scope(tx_sender.pool.clone()))
         .service(tokens::api_scope(
             tx_sender.pool.clone(),
             tx_sender.tokens,
             tx_sender.ticker_requests,
-        ))
+        ));
+
+    if zk_config.api.rest.snapshots_enabled {
+        scope = scope.service(snapshots::api_scope(snapshots_data));
+    }
+    if zk_config.api.rest.guardians_enabled {
+        scope = scope.service(guardians::api_scope(
+            tx_sender.pool.clone(),
+            tx_sender.network_domain,
+        ));
+    }
+    if zk_config.api.rest.aliases_enabled {
+        scope = scope.service(aliases::api_scope(
+            tx_sender.pool,
+            tx_sender.signature_domain,
+        ));
+    }
+    scope
 }
@@ -0,0 +1,157 @@
+//! Nonce reservations part of API implementation.
+//!
+//! Accounts sending many transactions concurrently reserve the ranges of their nonces
+//! by submitting the signed reservation requests, so the transactions from the reserved
+//! range may arrive to the mempool out of order.
+//! See `zksync_types::nonce_reservation` for the details.
+
+// Built-in uses
+use std::convert::TryFrom;
+
+// External uses
+use actix_web::{
+    web::{self, Json},
+    Scope,
+};
+use chrono::{Duration, Utc};
+
+// Workspace uses
+use zksync_config::ZkSyncConfig;
+use zksync_storage::{nonce_reservations::NonceReservationOutcome, ConnectionPool};
+use zksync_types::{
+    nonce_reservation::{NonceReservation, NonceReservationRequest},
+    tx::SignatureDomain,
+    Nonce,
+};
+
+// Local uses
+use super::{Error as ApiError, JsonResult};
+use crate::api_server::rest::address_checksum::parse_path_address;
+
+/// Max difference between the request timestamp and the server time, in milliseconds.
+const MAX_REQUEST_TIME_DRIFT_MS: i64 = 5 * 60 * 1000;
+
+/// Shared data between `api/v1/nonce_reservations` endpoints.
+#[derive(Clone)]
+struct ApiNonceReservationsData {
+    pool: ConnectionPool,
+    max_reservation_size: u32,
+    reservation_ttl: Duration,
+    /// Network the signed messages must be bound to, see `SignatureDomain`.
+    signature_domain: Option<SignatureDomain>,
+}
+
+// Server implementation
+
+async fn reserve(
+    data: web::Data<ApiNonceReservationsData>,
+    Json(request): Json<NonceReservationRequest>,
+) -> JsonResult<NonceReservation> {
+    if request.count == 0 || request.count > data.max_reservation_size {
+        return Err(
+            ApiError::bad_request("Incorrect amount of nonces").detail(format!(
+                "Amount of reserved nonces must be between 1 and {}",
+                data.max_reservation_size
+            )),
+        );
+    }
+    let now = Utc::now().timestamp_millis();
+    let time_drift = i64::try_from(request.timestamp)
+        .ok()
+        .and_then(|timestamp| now.checked_sub(timestamp))
+        .and_then(i64::checked_abs);
+    if time_drift.map_or(true, |time_drift| time_drift > MAX_REQUEST_TIME_DRIFT_MS) {
+        return Err(ApiError::bad_request("Request timestamp is out of range")
+            .detail("Request must be signed not earlier than 5 minutes ago"));
+    }
+    if !request.verify(data.signature_domain.as_ref()) {
+        return Err(ApiError::bad_request("Incorrect signature")
+            .detail("Reservation request must be signed by the account address"));
+    }
+
+    let mut storage = data
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    let committed_nonce = storage
+        .chain()
+        .account_schema()
+        .account_state_by_address(request.address)
+        .await
+        .map_err(ApiError::internal)?
+        .committed
+        .map(|(_, account)| account.nonce)
+        .unwrap_or(Nonce(0));
+
+    let mut reservations = storage.nonce_reservations_schema();
+    // The requests signed before the drift window are rejected anyway,
+    // so the expired reservations made by them are not needed for the replay checks.
+    let removed = reservations
+        .remove_expired_reservations((now - MAX_REQUEST_TIME_DRIFT_MS) as u64)
+        .await
+        .map_err(ApiError::internal)?;
+    metrics::counter!("api.v1.nonce_reservations.expired", removed);
+    let outcome = reservations
+        .reserve_nonces(
+            request.address,
+            committed_nonce,
+            request.count,
+            request.timestamp,
+            Utc::now() + data.reservation_ttl,
+        )
+        .await
+        .map_err(ApiError::internal)?;
+    let reservation = match outcome {
+        NonceReservationOutcome::Reserved(reservation) => reservation,
+        NonceReservationOutcome::Replayed => {
+            return Err(ApiError::bad_request("Request has already been processed")
+                .detail("Each reservation request must have a unique timestamp"));
+        }
+        NonceReservationOutcome::NonceOverflow => {
+            return Err(ApiError::bad_request("Incorrect amount of nonces")
+                .detail("Reserved nonces must not exceed the maximum nonce"));
+        }
+    };
+
+    metrics::counter!("api.v1.nonce_reservations.reserved", request.count as u64);
+    Ok(Json(reservation))
+}
+
+async fn active_reservations(
+    data: web::Data<ApiNonceReservationsData>,
+    web::Path(address): web::Path<String>,
+) -> JsonResult<Vec<NonceReservation>> {
+    let address = parse_path_address(&address)?;
+    let mut storage = data
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    let reservations = storage
+        .nonce_reservations_schema()
+        .load_active_reservations(address)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(reservations))
+}
+
+pub fn api_scope(
+    pool: ConnectionPool,
+    config: &ZkSyncConfig,
+    signature_domain: Option<SignatureDomain>,
+) -> Scope {
+    let data = ApiNonceReservationsData {
+        pool,
+        max_reservation_size: config.chain.mempool.max_nonce_reservation_size,
+        reservation_ttl: Duration::from_std(config.chain.mempool.nonce_reservation_ttl())
+            .expect("Nonce reservation TTL is too big"),
+        signature_domain,
+    };
+
+    web::scope("nonce_reservations")
+        .data(data)
+        .route("", web::post().to(reserve))
+        .route("{address}", web::get().to(active_reservations))
+}
@@ -10,16 +10,20 @@ use actix_web::{
 
 // Workspace uses
 use zksync_api_client::rest::v1::{
-    PriorityOpData, PriorityOpQuery, PriorityOpQueryError, PriorityOpReceipt,
+    FinalityQuery, PriorityOpCostsQuery, PriorityOpData, PriorityOpQuery, PriorityOpQueryError,
+    PriorityOpReceipt,
 };
 use zksync_storage::{
     chain::operations::records::StoredExecutedPriorityOperation, ConnectionPool, QueryResult,
     StorageProcessor,
 };
-use zksync_types::{BlockNumber, H256};
+use zksync_types::{
+    priority_op_cost::{PriorityOpCost, PriorityOpCostSummary},
+    BlockNumber, H256,
+};
 
 // Local uses
-use super::{transactions::Receipt, Error as ApiError, JsonResult};
+use super::{transactions::Receipt, Error as ApiError, JsonResult, MAX_LIMIT};
 
 /// Shared data between `api/v1/operations` endpoints.
 #[derive(Debug, Clone)]
@@ -68,26 +72,59 @@ impl ApiOperationsData {
 
         let block = BlockNumber(block_info.block_number as u32);
         let index = executed_op.block_index as u32;
+        let finality = storage
+            .chain()
+            .block_schema()
+            .load_finalized_blocks()
+            .await?
+            .block_finality(block);
 
         let receipt = if block_info.verify_tx_hash.is_some() {
             PriorityOpReceipt {
                 status: Receipt::Verified { block },
                 index: Some(index),
+                finality,
             }
         } else if block_info.commit_tx_hash.is_some() {
             PriorityOpReceipt {
                 status: Receipt::Committed { block },
                 index: Some(index),
+                finality,
             }
         } else {
             PriorityOpReceipt {
                 status: Receipt::Executed,
                 index: None,
+                finality,
             }
         };
 
         Ok(Some(receipt))
     }
+
+    pub async fn priority_op_cost(
+        &self,
+        query: PriorityOpQuery,
+    ) -> QueryResult<Option<PriorityOpCost>> {
+        let mut storage = self.pool.access_storage().await?;
+
+        let executed_op = executed_priority_op_for_query(query, &mut storage).await?;
+        let serial_id = match executed_op {
+            Some(executed_op) => executed_op.priority_op_serialid as u64,
+            None => return Ok(None),
+        };
+
+        storage
+            .priority_op_costs_schema()
+            .load_cost(serial_id)
+            .await
+    }
+
+    pub async fn priority_op_costs(&self, limit: u32) -> QueryResult<Vec<PriorityOpCostSummary>> {
+        let mut storage = self.pool.access_storage().await?;
+
+        storage.priority_op_costs_schema().load_summary(limit).await
+    }
 }
 
 async fn executed_priority_op_for_query(
@@ -138,10 +175,15 @@ mod convert {
 async fn priority_op(
     data: web::Data<ApiOperationsData>,
     web::Path(path): web::Path<String>,
+    web::Query(finality_query): web::Query<FinalityQuery>,
 ) -> JsonResult<Option<PriorityOpReceipt>> {
     let query = PriorityOpQuery::from_path(path)?;
 
-    let receipt = data.priority_op(query).await.map_err(ApiError::internal)?;
+    let receipt = data
+        .priority_op(query)
+        .await
+        .map_err(ApiError::internal)?
+        .filter(|receipt| receipt.finality >= finality_query.min_finality());
     Ok(Json(receipt))
 }
 
@@ -158,19 +200,50 @@ async fn priority_op_data(
     Ok(Json(data))
 }
 
+async fn priority_op_cost(
+    data: web::Data<ApiOperationsData>,
+    web::Path(path): web::Path<String>,
+) -> JsonResult<Option<PriorityOpCost>> {
+    let query = PriorityOpQuery::from_path(path)?;
+
+    let cost = data
+        .priority_op_cost(query)
+        .await
+        .map_err(ApiError::internal)?;
+    Ok(Json(cost))
+}
+
+async fn priority_op_costs(
+    data: web::Data<ApiOperationsData>,
+    web::Query(query): web::Query<PriorityOpCostsQuery>,
+) -> JsonResult<Vec<PriorityOpCostSummary>> {
+    if query.limit == 0 || query.limit > MAX_LIMIT {
+        return Err(ApiError::bad_request("Incorrect limit")
+            .detail(format!("Limit should be between 1 and {}", MAX_LIMIT)));
+    }
+
+    let summary = data
+        .priority_op_costs(query.limit)
+        .await
+        .map_err(ApiError::internal)?;
+    Ok(Json(summary))
+}
+
 pub fn api_scope(pool: ConnectionPool) -> Scope {
     let data = ApiOperationsData::new(pool);
 
     web::scope("operations")
         .data(data)
+        .route("costs", web::get().to(priority_op_costs))
         .route("{id}", web::get().to(priority_op))
         .route("{id}/data", web::get().to(priority_op_data))
+        .route("{id}/cost", web::get().to(priority_op_cost))
 }
 
 #[cfg(test)]
 mod tests {
     use zksync_storage::test_data::dummy_ethereum_tx_hash;
-    use zksync_types::{AccountId, Address};
+    use zksync_types::{finality::Finality, AccountId, Address};
 
     use crate::api_server::v1::test_utils::{dummy_deposit_op, dummy_full_exit_op};
 
@@ -199,13 +272,17 @@ mod tests {
             status: Receipt::Verified {
                 block: BlockNumber(2),
             },
+            finality: Finality::Executed,
         };
         assert_eq!(
             client.priority_op(VERIFIED_OP_SERIAL_ID).await?.as_ref(),
             Some(&expected_receipt)
         );
         assert_eq!(
-            client.priority_op(verified_op_hash).await?.as_ref(),
+            client
+                .priority_op_at_finality(verified_op_hash, Finality::Executed)
+                .await?
+                .as_ref(),
             Some(&expected_receipt)
         );
 
@@ -236,11 +313,13 @@ mod tests {
         // Check committed priority operation.
         let committed_eth_hash = dummy_ethereum_tx_hash(COMMITTED_OP_SERIAL_ID as i64);
 
+        // The proof of the block is already verified on L1.
         let expected_receipt = PriorityOpReceipt {
             index: Some(1),
             status: Receipt::Committed {
                 block: BlockNumber(4),
             },
+            finality: Finality::Verified,
         };
         assert_eq!(
             client.priority_op(COMMITTED_OP_SERIAL_ID).await?.as_ref(),
@@ -250,6 +329,10 @@ mod tests {
             client.priority_op(committed_eth_hash).await?.as_ref(),
             Some(&expected_receipt)
         );
+        assert!(client
+            .priority_op_at_finality(committed_eth_hash, Finality::Executed)
+            .await?
+            .is_none());
 
         let expected_data = PriorityOpData {
             data: dummy_full_exit_op(AccountId(1), Address::default(), 16, 3).op,
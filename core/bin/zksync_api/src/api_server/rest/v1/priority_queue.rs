@@ -0,0 +1,201 @@
+//! Priority queue part of API implementation.
+//!
+//! Exposes the priority operations which are confirmed on L1 but not yet included
+//! into a zkSync block, along with an estimated inclusion time derived from the
+//! recent block cadence, and the serial IDs of the last processed operations.
+
+// Built-in uses
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// External uses
+use actix_web::{web, Scope};
+use chrono::{TimeZone, Utc};
+
+// Workspace uses
+use zksync_api_client::rest::v1::{PriorityQueueItem, ProcessedPriorityOps};
+use zksync_config::ZkSyncConfig;
+use zksync_storage::ConnectionPool;
+
+// Local uses
+use super::{Error as ApiError, JsonResult};
+use crate::core_api_client::CoreApiClient;
+
+/// Amount of the latest blocks used to estimate the block cadence.
+const CADENCE_WINDOW: u32 = 10;
+
+/// Shared data between `api/v1/priority_queue` endpoints.
+#[derive(Debug, Clone)]
+struct ApiPriorityQueueData {
+    pool: ConnectionPool,
+    core_api_client: CoreApiClient,
+    max_block_chunks: usize,
+}
+
+/// Estimates the inclusion times (as UNIX timestamps) for the queued operations
+/// with the given sizes in chunks.
+///
+/// `recent_timestamps` are the timestamps of the latest blocks, starting from the
+/// newest one. The operations are assumed to be packed into blocks of `max_block_chunks`
+/// chunks in the queue order, and the blocks are assumed to be sealed with the average
+/// interval observed within `recent_timestamps`.
+fn estimate_inclusion_times(
+    recent_timestamps: &[u64],
+    now: u64,
+    op_chunks: &[usize],
+    max_block_chunks: usize,
+) -> Vec<Option<u64>> {
+    let interval = match (recent_timestamps.first(), recent_timestamps.last()) {
+        (Some(newest), Some(oldest)) if recent_timestamps.len() > 1 && newest > oldest => {
+            Some((newest - oldest) / (recent_timestamps.len() as u64 - 1))
+        }
+        _ => None,
+    };
+    let (newest, interval) = match (recent_timestamps.first(), interval) {
+        (Some(&newest), Some(interval)) if max_block_chunks > 0 => (newest, interval),
+        _ => return vec![None; op_chunks.len()],
+    };
+
+    let next_block_time = std::cmp::max(now, newest + interval);
+    let mut used_chunks = 0;
+    op_chunks
+        .iter()
+        .map(|chunks| {
+            used_chunks += chunks;
+            let blocks_ahead = (used_chunks.saturating_sub(1) / max_block_chunks) as u64;
+            Some(next_block_time + blocks_ahead * interval)
+        })
+        .collect()
+}
+
+// Server implementation
+
+async fn priority_queue(
+    data: web::Data<ApiPriorityQueueData>,
+) -> JsonResult<Vec<PriorityQueueItem>> {
+    let mut storage = data
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    let first_serial_id = storage
+        .chain()
+        .operations_schema()
+        .get_next_priority_op_serial_id()
+        .await
+        .map_err(ApiError::internal)?;
+    let recent_timestamps = storage
+        .chain()
+        .block_schema()
+        .get_recent_block_timestamps(CADENCE_WINDOW)
+        .await
+        .map_err(ApiError::internal)?;
+    drop(storage);
+
+    let ops = data
+        .core_api_client
+        .get_priority_queue(first_serial_id)
+        .await
+        .map_err(ApiError::internal)?;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Incorrect system time")
+        .as_secs();
+    let op_chunks = ops.iter().map(|op| op.data.chunks()).collect::<Vec<_>>();
+    let estimations =
+        estimate_inclusion_times(&recent_timestamps, now, &op_chunks, data.max_block_chunks);
+
+    let items = ops
+        .into_iter()
+        .zip(estimations)
+        .enumerate()
+        .map(|(position, (op, estimation))| PriorityQueueItem {
+            serial_id: op.serial_id,
+            position,
+            eth_hash: op.eth_hash,
+            eth_block: op.eth_block,
+            deadline_block: op.deadline_block,
+            data: op.data,
+            estimated_inclusion: estimation.map(|timestamp| Utc.timestamp(timestamp as i64, 0)),
+        })
+        .collect();
+
+    Ok(web::Json(items))
+}
+
+async fn processed_priority_ops(
+    data: web::Data<ApiPriorityQueueData>,
+) -> JsonResult<ProcessedPriorityOps> {
+    let mut storage = data
+        .pool
+        .access_storage()
+        .await
+        .map_err(ApiError::internal)?;
+    let next_executed_serial_id = storage
+        .chain()
+        .operations_schema()
+        .get_next_priority_op_serial_id()
+        .await
+        .map_err(ApiError::internal)?;
+    let next_sealed_serial_id = storage
+        .chain()
+        .block_schema()
+        .get_next_sealed_priority_op_serial_id()
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(web::Json(ProcessedPriorityOps {
+        last_executed_serial_id: next_executed_serial_id.checked_sub(1),
+        last_sealed_serial_id: next_sealed_serial_id.checked_sub(1),
+    }))
+}
+
+pub fn api_scope(
+    pool: ConnectionPool,
+    core_api_client: CoreApiClient,
+    config: &ZkSyncConfig,
+) -> Scope {
+    let max_block_chunks = config
+        .chain
+        .state_keeper
+        .block_chunk_sizes
+        .iter()
+        .copied()
+        .max()
+        .unwrap_or_default();
+    let data = ApiPriorityQueueData {
+        pool,
+        core_api_client,
+        max_block_chunks,
+    };
+
+    web::scope("priority_queue")
+        .data(data)
+        .route("", web::get().to(priority_queue))
+        .route("processed", web::get().to(processed_priority_ops))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inclusion_time_estimation() {
+        // Blocks are sealed every 10 seconds, the last one was sealed at 130.
+        let timestamps = [130, 120, 110, 100];
+
+        let estimations = estimate_inclusion_times(&timestamps, 135, &[6, 6, 6, 3], 10);
+        assert_eq!(
+            estimations,
+            vec![Some(140), Some(150), Some(150), Some(160)]
+        );
+
+        // The next block is overdue, so it is expected to be sealed right now.
+        let estimations = estimate_inclusion_times(&timestamps, 200, &[6], 10);
+        assert_eq!(estimations, vec![Some(200)]);
+
+        // Not enough data to estimate the cadence.
+        assert_eq!(estimate_inclusion_times(&[130], 135, &[6], 10), vec![None]);
+        assert_eq!(estimate_inclusion_times(&[], 135, &[6], 10), vec![None]);
+    }
+}
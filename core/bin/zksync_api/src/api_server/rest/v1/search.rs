@@ -1,6 +1,7 @@
 //! Search part of API implementation.
 
 // Built-in uses
+use std::str::FromStr;
 
 // External uses
 use actix_web::{
@@ -9,8 +10,12 @@ use actix_web::{
 };
 
 // Workspace uses
-use zksync_api_client::rest::v1::BlockSearchQuery;
-use zksync_storage::{ConnectionPool, QueryResult};
+use zksync_api_client::rest::v1::{BlockSearchQuery, SearchResult};
+use zksync_storage::{
+    chain::operations::records::StoredExecutedPriorityOperation, ConnectionPool, QueryResult,
+    StorageProcessor,
+};
+use zksync_types::{tx::TxHash, Address, BlockNumber, TokenLike, H256};
 
 // Local uses
 use super::{
@@ -37,11 +42,147 @@ impl ApiSearchData {
             .block_schema()
             .find_block_by_height_or_hash(query)
             .await;
+        let finalized = storage
+            .chain()
+            .block_schema()
+            .load_finalized_blocks()
+            .await?;
+
+        Ok(block.map(|block| block_info_from_details(block, &finalized)))
+    }
+
+    /// Resolves the query to all the matching entities, so the caller doesn't have to guess
+    /// the kind of the identifier.
+    async fn search(&self, query: String) -> QueryResult<Vec<SearchResult>> {
+        let mut storage = self.pool.access_storage().await?;
+        let query = query.trim();
+        let mut results = Vec::new();
+
+        if let Some(address) = parse_address(query) {
+            let account_id = storage
+                .chain()
+                .account_schema()
+                .account_id_by_address(address)
+                .await?;
+            results.push(SearchResult::Account {
+                address,
+                account_id,
+            });
+            return Ok(results);
+        }
+
+        let hash = parse_hash(query);
+        if let Some(hash) = hash {
+            let tx = storage
+                .chain()
+                .operations_schema()
+                .get_executed_operation(hash.as_bytes())
+                .await?;
+            if let Some(tx) = tx {
+                results.push(SearchResult::Transaction {
+                    tx_hash: TxHash::from_slice(&tx.tx_hash).unwrap(),
+                    block_number: BlockNumber(tx.block_number as u32),
+                });
+            }
+
+            let priority_op = storage
+                .chain()
+                .operations_schema()
+                .get_executed_priority_operation_by_hash(hash.as_bytes())
+                .await?;
+            results.extend(priority_op.map(priority_op_result));
+        }
+
+        let serial_id = query.parse::<u32>().ok();
+        if let Some(serial_id) = serial_id {
+            let priority_op = storage
+                .chain()
+                .operations_schema()
+                .get_executed_priority_operation(serial_id)
+                .await?;
+            results.extend(priority_op.map(priority_op_result));
+        }
+
+        // Covers both the block numbers and the hashes related to the block.
+        // Block search expects a well-formed query, so other queries are not passed there.
+        if hash.is_some() || serial_id.is_some() {
+            let block = storage
+                .chain()
+                .block_schema()
+                .find_block_by_height_or_hash(query.to_owned())
+                .await;
+            results.extend(block.map(|block| SearchResult::Block {
+                block_number: BlockNumber(block.block_number as u32),
+            }));
+        }
+
+        if results.is_empty() {
+            results.extend(search_token(&mut storage, query).await?);
+        }
+
+        Ok(results)
+    }
+}
+
+/// Parses the account address either in the `0x{..}` or in the `sync:{..}` form.
+fn parse_address(query: &str) -> Option<Address> {
+    let hex = query
+        .strip_prefix("0x")
+        .or_else(|| query.strip_prefix("sync:"))?;
+    if hex.len() != 40 {
+        return None;
+    }
+    Address::from_str(hex).ok()
+}
+
+/// Parses the 32-byte hash either without prefix or with one of the `0x`, `sync-tx:`
+/// and `sync-bl:` prefixes.
+fn parse_hash(query: &str) -> Option<H256> {
+    let hex = query
+        .strip_prefix("0x")
+        .or_else(|| query.strip_prefix("sync-tx:"))
+        .or_else(|| query.strip_prefix("sync-bl:"))
+        .unwrap_or(query);
+    if hex.len() != 64 {
+        return None;
+    }
+    H256::from_str(hex).ok()
+}
 
-        Ok(block.map(block_info_from_details))
+fn priority_op_result(op: StoredExecutedPriorityOperation) -> SearchResult {
+    SearchResult::PriorityOperation {
+        serial_id: op.priority_op_serialid as u64,
+        eth_hash: H256::from_slice(&op.eth_hash),
+        block_number: BlockNumber(op.block_number as u32),
     }
 }
 
+async fn search_token(
+    storage: &mut StorageProcessor<'_>,
+    query: &str,
+) -> QueryResult<Option<SearchResult>> {
+    // Symbols are stored in the upper case, but users tend to type them in any case.
+    let token = match storage
+        .tokens_schema()
+        .get_token(TokenLike::Symbol(query.to_owned()))
+        .await?
+    {
+        Some(token) => Some(token),
+        None => {
+            storage
+                .tokens_schema()
+                .get_token(TokenLike::Symbol(query.to_uppercase()))
+                .await?
+        }
+    };
+
+    Ok(token.map(|token| SearchResult::Token {
+        token_id: token.id,
+        symbol: token.symbol,
+        address: token.address,
+    }))
+}
+
 // Server implementation
 
 async fn block_search(
@@ -56,18 +197,28 @@ async fn block_search(
     Ok(Json(block_info))
 }
 
+async fn unified_search(
+    data: web::Data<ApiSearchData>,
+    web::Query(query): web::Query<BlockSearchQuery>,
+) -> JsonResult<Vec<SearchResult>> {
+    let results = data.search(query.query).await.map_err(ApiError::internal)?;
+
+    Ok(Json(results))
+}
+
 pub fn api_scope(pool: ConnectionPool) -> Scope {
     let data = ApiSearchData::new(pool);
 
     web::scope("search")
         .data(data)
         .route("", web::get().to(block_search))
+        .route("unified", web::get().to(unified_search))
 }
 
 #[cfg(test)]
 mod tests {
     use super::{super::test_utils::TestServerConfig, *};
-    use zksync_types::BlockNumber;
+    use zksync_types::TokenId;
 
     #[actix_rt::test]
     #[cfg_attr(
@@ -110,6 +261,27 @@ mod tests {
             block_info
         );
 
+        // Unified search resolves the blocks, the tokens and the addresses.
+        assert!(client.search("1").await?.contains(&SearchResult::Block {
+            block_number: BlockNumber(1)
+        }));
+        let token = SearchResult::Token {
+            token_id: TokenId(1),
+            symbol: "PHNX".to_owned(),
+            address: "0x38A2fDc11f526Ddd5a607C1F251C065f40fBF2f7".parse()?,
+        };
+        assert_eq!(client.search("PHNX").await?, vec![token.clone()]);
+        assert_eq!(client.search("phnx").await?, vec![token]);
+        let address = Address::repeat_byte(0x42);
+        assert_eq!(
+            client.search(format!("{:?}", address)).await?,
+            vec![SearchResult::Account {
+                address,
+                account_id: None
+            }]
+        );
+        assert_eq!(client.search("unknown").await?, vec![]);
+
         server.stop().await;
         Ok(())
     }
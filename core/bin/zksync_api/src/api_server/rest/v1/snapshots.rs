@@ -0,0 +1,315 @@
+//! Snapshots part of API implementation.
+//!
+//! Serves the snapshots of the last verified state, so the new server instances can bootstrap
+//! from this one. See `zksync_types::snapshot` for the details. Building a snapshot requires
+//! loading the whole state, so it's rebuilt at most once per `SNAPSHOT_REFRESH_INTERVAL`.
+//! The previous snapshots are served for a while, so the importers downloading them don't
+//! have to start over once the new snapshot is built.
+//!
+//! The snapshot contains the whole state, so it's served only to the requests authorized
+//! by the token (JWT) signed with `API_REST_SNAPSHOTS_SECRET_AUTH`.
+
+// Built-in uses
+use std::{collections::VecDeque, sync::Arc};
+
+// External uses
+use actix_web::{web, Scope};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use chrono::{Duration, Utc};
+use jsonwebtoken::{decode, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, RwLock};
+
+// Workspace uses
+use zksync_storage::ConnectionPool;
+use zksync_types::{
+    aggregated_operations::AggregatedActionType,
+    snapshot::{SnapshotChunk, SnapshotMetadata, SNAPSHOT_CHUNK_SIZE},
+    Account, AccountId, BlockNumber,
+};
+
+// Local uses
+use super::{Error as ApiError, JsonResult};
+
+/// Minimum time between the snapshot rebuilds, in minutes.
+const SNAPSHOT_REFRESH_INTERVAL: i64 = 10;
+/// Number of the latest snapshots being served.
+const SNAPSHOTS_KEPT: usize = 3;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PayloadAuthToken {
+    /// Subject (whom auth token refers to).
+    sub: String,
+    /// Expiration time (as UTC timestamp).
+    exp: usize,
+}
+
+#[derive(Debug)]
+struct Snapshot {
+    metadata: SnapshotMetadata,
+    chunks: Vec<Vec<(AccountId, Account)>>,
+}
+
+/// Shared data between `api/v1/snapshots` endpoints.
+/// The snapshots are shared by all the workers, so the data must be created once per server.
+#[derive(Debug, Clone)]
+pub(crate) struct ApiSnapshotsData {
+    pool: ConnectionPool,
+    /// Secret the authorization tokens are signed with.
+    secret_auth: String,
+    /// The latest snapshots, ordered by the block number.
+    snapshots: Arc<RwLock<VecDeque<Arc<Snapshot>>>>,
+    /// Prevents the snapshot from being built by several requests at once.
+    build_lock: Arc<Mutex<()>>,
+}
+
+impl ApiSnapshotsData {
+    pub fn new(pool: ConnectionPool, secret_auth: String) -> Self {
+        Self {
+            pool,
+            secret_auth,
+            snapshots: Arc::default(),
+            build_lock: Arc::default(),
+        }
+    }
+
+    async fn build_snapshot(&self) -> anyhow::Result<Snapshot> {
+        let mut storage = self.pool.access_storage().await?;
+        let mut transaction = storage.start_transaction().await?;
+
+        let (block_number, accounts) = transaction
+            .chain()
+            .state_schema()
+            .load_verified_state()
+            .await?;
+        let block = transaction
+            .chain()
+            .block_schema()
+            .get_block(block_number)
+            .await?
+            .ok_or_else(|| anyhow::format_err!("Block #{} is not found", *block_number))?;
+        let commit_tx_hash = transaction
+            .ethereum_schema()
+            .aggregated_op_final_hash(block_number, AggregatedActionType::CommitBlocks)
+            .await?;
+        let mut tokens: Vec<_> = transaction
+            .tokens_schema()
+            .load_tokens()
+            .await?
+            .into_iter()
+            .map(|(_, token)| token)
+            .collect();
+        transaction.commit().await?;
+
+        tokens.sort_by_key(|token| token.id);
+        // The accounts are moved into the chunks, so the state is never copied.
+        let accounts_count = accounts.len();
+        let mut accounts: Vec<_> = accounts.into_iter().collect();
+        accounts.sort_by_key(|(id, _)| *id);
+        let mut chunks =
+            Vec::with_capacity((accounts_count + SNAPSHOT_CHUNK_SIZE - 1) / SNAPSHOT_CHUNK_SIZE);
+        let mut accounts = accounts.into_iter().peekable();
+        while accounts.peek().is_some() {
+            chunks.push(accounts.by_ref().take(SNAPSHOT_CHUNK_SIZE).collect());
+        }
+
+        Ok(Snapshot {
+            metadata: SnapshotMetadata {
+                block,
+                commit_tx_hash,
+                tokens,
+                accounts_count,
+                chunk_size: SNAPSHOT_CHUNK_SIZE,
+                chunks_count: chunks.len(),
+                created_at: Utc::now(),
+            },
+            chunks,
+        })
+    }
+
+    /// Returns the latest snapshot, rebuilding it if it's outdated.
+    async fn latest_snapshot(&self) -> anyhow::Result<Arc<Snapshot>> {
+        let _build_guard = self.build_lock.lock().await;
+        let latest = self.snapshots.read().await.back().cloned();
+        if let Some(latest) = &latest {
+            let age = Utc::now() - latest.metadata.created_at;
+            if age < Duration::minutes(SNAPSHOT_REFRESH_INTERVAL) {
+                return Ok(latest.clone());
+            }
+
+            let last_verified_block = self
+                .pool
+                .access_storage()
+                .await?
+                .chain()
+                .block_schema()
+                .get_last_verified_confirmed_block()
+                .await?;
+            if last_verified_block == latest.metadata.block_number() {
+                return Ok(latest.clone());
+            }
+        }
+
+        let snapshot = Arc::new(self.build_snapshot().await?);
+        vlog::info!(
+            "Built the state snapshot at block #{}: {} accounts",
+            *snapshot.metadata.block_number(),
+            snapshot.metadata.accounts_count
+        );
+        metrics::counter!("api.v1.snapshots.built", 1);
+
+        // The snapshot rebuilt at the same block replaces the outdated one.
+        let same_block = latest.map_or(false, |latest| {
+            latest.metadata.block_number() == snapshot.metadata.block_number()
+        });
+        let mut snapshots = self.snapshots.write().await;
+        if same_block {
+            snapshots.pop_back();
+        }
+        snapshots.push_back(snapshot.clone());
+        if snapshots.len() > SNAPSHOTS_KEPT {
+            snapshots.pop_front();
+        }
+        Ok(snapshot)
+    }
+
+    fn authorize(&self, credentials: &BearerAuth) -> Result<(), ApiError> {
+        decode::<PayloadAuthToken>(
+            credentials.token(),
+            &DecodingKey::from_secret(self.secret_auth.as_bytes()),
+            &Validation::default(),
+        )
+        .map_err(|err| ApiError::unauthorized("Invalid authorization token").detail(err))?;
+        Ok(())
+    }
+
+    async fn snapshot(&self, block_number: BlockNumber) -> Option<Arc<Snapshot>> {
+        self.snapshots
+            .read()
+            .await
+            .iter()
+            .find(|snapshot| snapshot.metadata.block_number() == block_number)
+            .cloned()
+    }
+}
+
+// Server implementation
+
+async fn latest(
+    data: web::Data<ApiSnapshotsData>,
+    credentials: BearerAuth,
+) -> JsonResult<SnapshotMetadata> {
+    data.authorize(&credentials)?;
+    let snapshot = data.latest_snapshot().await.map_err(ApiError::internal)?;
+    Ok(web::Json(snapshot.metadata.clone()))
+}
+
+async fn chunk(
+    data: web::Data<ApiSnapshotsData>,
+    credentials: BearerAuth,
+    web::Path((block_number, index)): web::Path<(BlockNumber, usize)>,
+) -> JsonResult<Option<SnapshotChunk>> {
+    data.authorize(&credentials)?;
+    let snapshot = match data.snapshot(block_number).await {
+        Some(snapshot) => snapshot,
+        None => return Ok(web::Json(None)),
+    };
+    let accounts = snapshot.chunks.get(index).cloned().ok_or_else(|| {
+        ApiError::bad_request("Incorrect chunk index").detail(format!(
+            "Snapshot consists of {} chunks",
+            snapshot.metadata.chunks_count
+        ))
+    })?;
+
+    metrics::counter!("api.v1.snapshots.chunks_served", 1);
+    Ok(web::Json(Some(SnapshotChunk {
+        block_number,
+        index,
+        accounts,
+    })))
+}
+
+pub(crate) fn api_scope(data: ApiSnapshotsData) -> Scope {
+    web::scope("snapshots")
+        .data(data)
+        .route("latest", web::get().to(latest))
+        .route("{block_number}/chunks/{index}", web::get().to(chunk))
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonwebtoken::{encode, EncodingKey, Header};
+
+    use zksync_api_client::rest::v1::ClientError;
+
+    use super::{super::test_utils::TestServerConfig, *};
+
+    fn auth_token(secret: &str) -> String {
+        let claims = PayloadAuthToken {
+            sub: "snapshot-sync".into(),
+            exp: (Utc::now() + Duration::hours(1)).timestamp() as usize,
+        };
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn test_snapshots_scope() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let (client, server) = cfg.start_server(move |cfg| {
+            api_scope(ApiSnapshotsData::new(cfg.pool.clone(), "secret".into()))
+        });
+
+        // Tokens signed with another secret are rejected.
+        let error = client
+            .latest_snapshot(&auth_token("another"))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, ClientError::BadRequest { .. }),
+            "Incorrect error type: got {:?} instead of BadRequest",
+            error
+        );
+
+        let token = auth_token("secret");
+        let metadata = client.latest_snapshot(&token).await?;
+        let mut accounts_count = 0;
+        for index in 0..metadata.chunks_count {
+            let chunk = client
+                .snapshot_chunk(&token, metadata.block_number(), index)
+                .await?
+                .expect("Snapshot must be served");
+            assert_eq!(chunk.index, index);
+            accounts_count += chunk.accounts.len();
+        }
+        assert_eq!(accounts_count, metadata.accounts_count);
+
+        // The chunk of the unknown snapshot isn't served.
+        assert!(client
+            .snapshot_chunk(&token, metadata.block_number() + 1, 0)
+            .await?
+            .is_none());
+        let error = client
+            .snapshot_chunk(&token, metadata.block_number(), metadata.chunks_count)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, ClientError::BadRequest { .. }),
+            "Incorrect error type: got {:?} instead of BadRequest",
+            error
+        );
+
+        server.stop().await;
+        Ok(())
+    }
+}
@@ -3,7 +3,7 @@
 // Built-in uses
 
 // External uses
-use actix_web::{web, App, Scope};
+use actix_web::{middleware::DefaultHeaders, web, App, Scope};
 use chrono::Utc;
 use once_cell::sync::Lazy;
 use tokio::sync::Mutex;
@@ -27,6 +27,7 @@ use zksync_types::{
     aggregated_operations::AggregatedActionType,
     helpers::{apply_updates, closest_packable_fee_amount, closest_packable_token_amount},
     operations::{ChangePubKeyOp, TransferToNewOp},
+    protocol_version::{COMPONENTS_PROTOCOL_VERSION, PROTOCOL_VERSION_HEADER},
     prover::ProverJobType,
     tx::ChangePubKeyType,
     AccountId, AccountMap, Address, BlockNumber, Deposit, DepositOp, ExecutedOperations,
@@ -49,6 +50,14 @@ pub const VERIFIED_BLOCKS_COUNT: u32 = 5;
 /// Number of executed blocks.
 pub const EXECUTED_BLOCKS_COUNT: u32 = 3;
 
+/// Protocol version headers of the Core responses, checked by the `CoreApiClient`.
+pub fn core_version_headers() -> DefaultHeaders {
+    DefaultHeaders::new().header(
+        PROTOCOL_VERSION_HEADER,
+        COMPONENTS_PROTOCOL_VERSION.to_string(),
+    )
+}
+
 #[derive(Debug, Clone)]
 pub struct TestServerConfig {
     pub config: ZkSyncConfig,
@@ -16,7 +16,7 @@ use futures::{
 // Workspace uses
 use zksync_api_client::rest::v1::{TokenPriceKind, TokenPriceQuery};
 use zksync_storage::{ConnectionPool, QueryResult};
-use zksync_types::{Token, TokenLike};
+use zksync_types::{tokens::TokenFeeStatus, Token, TokenLike};
 
 use crate::{
     fee_ticker::{PriceError, TickerRequest, TokenPriceRequestType},
@@ -81,8 +81,22 @@ impl ApiTokensData {
             Err(PriceError::TokenNotFound(_)) => Ok(None),
             Err(PriceError::DBError(err)) => Err(anyhow::format_err!(err)),
             Err(PriceError::ApiError(err)) => Err(anyhow::format_err!(err)),
+            Err(PriceError::UnreliablePrice(err)) => Err(anyhow::format_err!(err)),
         }
     }
+
+    async fn token_fee_status(&self, token: TokenLike) -> QueryResult<Option<TokenFeeStatus>> {
+        let (status_sender, status_receiver) = oneshot::channel();
+        self.fee_ticker
+            .clone()
+            .send(TickerRequest::GetTokenFeeStatus {
+                token,
+                response: status_sender,
+            })
+            .await?;
+
+        status_receiver.await?
+    }
 }
 
 // Server implementation
@@ -126,6 +140,19 @@ async fn token_price(
     Ok(Json(price))
 }
 
+async fn token_fee_status(
+    data: web::Data<ApiTokensData>,
+    web::Path(token_like): web::Path<String>,
+) -> JsonResult<Option<TokenFeeStatus>> {
+    let token_like = TokenLike::parse(&token_like);
+
+    let status = data
+        .token_fee_status(token_like)
+        .await
+        .map_err(ApiError::internal)?;
+    Ok(Json(status))
+}
+
 pub fn api_scope(
     pool: ConnectionPool,
     tokens_db: TokenDBCache,
@@ -138,13 +165,14 @@ pub fn api_scope(
         .route("", web::get().to(tokens))
         .route("{id}", web::get().to(token_by_id))
         .route("{id}/price", web::get().to(token_price))
+        .route("{id}/fee_status", web::get().to(token_fee_status))
 }
 
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
 
-    use zksync_types::{Address, TokenId};
+    use zksync_types::{tokens::FeeTokenIneligibility, Address, TokenId};
 
     use super::{super::test_utils::TestServerConfig, *};
 
@@ -179,6 +207,19 @@ mod tests {
 
                         response.send(msg).expect("Unable to send response");
                     }
+                    TickerRequest::GetTokenFeeStatus { token, response } => {
+                        let status = match token {
+                            TokenLike::Id(token_id) if prices.contains_key(&token) => {
+                                Some(TokenFeeStatus::ineligible(
+                                    token_id,
+                                    FeeTokenIneligibility::InsufficientLiquidity,
+                                ))
+                            }
+                            _ => None,
+                        };
+
+                        response.send(Ok(status)).expect("Unable to send response");
+                    }
                     _ => unreachable!("Unsupported request"),
                 }
             }
@@ -233,6 +274,17 @@ mod tests {
             "Incorrect error type: got {:?} instead of BadRequest",
             error
         );
+        // Fee status requests
+        let status = client.token_fee_status(&TokenLike::Id(TokenId(1))).await?;
+        assert_eq!(
+            status.and_then(|status| status.reason),
+            Some(FeeTokenIneligibility::InsufficientLiquidity)
+        );
+        assert_eq!(
+            client.token_fee_status(&TokenLike::Id(TokenId(2))).await?,
+            None
+        );
+
         // Tokens requests
         let expected_tokens = {
             let mut storage = cfg.pool.access_storage().await?;
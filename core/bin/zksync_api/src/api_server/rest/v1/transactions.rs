@@ -1,71 +1,65 @@
 //! Transactions part of API implementation.
 
 // Built-in uses
+use std::str::FromStr;
 
 // External uses
 use actix_web::{
     web::{self, Json},
-    Scope,
+    HttpRequest, HttpResponse, Scope,
 };
+use num::BigUint;
 
 // Workspace uses
 pub use zksync_api_client::rest::v1::{
-    FastProcessingQuery, IncomingTx, IncomingTxBatch, IncomingTxBatchForFee, IncomingTxForFee,
-    Receipt, TxData,
+    FastProcessingQuery, FinalityQuery, IncomingTx, IncomingTxBatch, IncomingTxBatchForFee,
+    IncomingTxForFee, PackingDiagnostics, Receipt, TxAdmission, TxData, TxSimulationRequest,
+    TxSimulationResult, TxStatus, TxTrace, TxTraceStep,
 };
+use zksync_config::ZkSyncConfig;
 use zksync_storage::{
     chain::operations_ext::records::TxReceiptResponse, QueryResult, StorageProcessor,
 };
-use zksync_types::{tx::TxHash, BatchFee, BlockNumber, Fee, SignedZkSyncTx};
+use zksync_types::{
+    api_error::ApiErrorCategory, fee_refund::EffectiveFee, helpers::PackableAmounts, tx::TxHash,
+    withdrawal_execution::WithdrawalExecution, AccountId, AccountUpdate, BatchFee, BlockNumber,
+    Fee, SignedZkSyncTx, TokenLike,
+};
 // Local uses
 use super::{Error as ApiError, JsonResult, Pagination, PaginationQuery};
 use crate::api_server::rpc_server::types::TxWithSignature;
-use crate::api_server::tx_sender::{SubmitError, TxSender};
-
-#[derive(Debug, Clone, Copy)]
-pub enum SumbitErrorCode {
-    AccountCloseDisabled = 101,
-    InvalidParams = 102,
-    UnsupportedFastProcessing = 103,
-    IncorrectTx = 104,
-    TxAdd = 105,
-    InappropriateFeeToken = 106,
-
-    Internal = 110,
-    CommunicationCoreServer = 111,
-    Other = 112,
-}
-
-impl SumbitErrorCode {
-    fn from_err(err: &SubmitError) -> Self {
-        match err {
-            SubmitError::AccountCloseDisabled => Self::AccountCloseDisabled,
-            SubmitError::InvalidParams(_) => Self::InvalidParams,
-            SubmitError::UnsupportedFastProcessing => Self::UnsupportedFastProcessing,
-            SubmitError::IncorrectTx(_) => Self::IncorrectTx,
-            SubmitError::TxAdd(_) => Self::TxAdd,
-            SubmitError::InappropriateFeeToken => Self::InappropriateFeeToken,
-            SubmitError::CommunicationCoreServer(_) => Self::CommunicationCoreServer,
-            SubmitError::Internal(_) => Self::Internal,
-            SubmitError::Other(_) => Self::Other,
-        }
-    }
-
-    fn as_code(self) -> u64 {
-        self as u64
-    }
-}
+use crate::api_server::{
+    rest::{
+        namespaces::request_namespace,
+        response_signer::{signed_json, ResponseSigner},
+    },
+    tx_sender::{SubmitError, TxSender},
+    tx_simulator::TxSimulator,
+};
+use crate::fee_ticker::BatchFeeTx;
+use crate::tx_error::TxAddError;
 
 impl From<SubmitError> for ApiError {
     fn from(inner: SubmitError) -> Self {
-        let internal_code = SumbitErrorCode::from_err(&inner).as_code();
-
-        if let SubmitError::Internal(err) = &inner {
-            ApiError::internal(err)
-        } else {
-            ApiError::bad_request(inner)
+        let code = inner.code();
+        let rest_code = inner.rest_code();
+
+        match &inner {
+            SubmitError::Internal(err) => ApiError::internal(err),
+            SubmitError::FeeQuotingSuspended => ApiError::service_unavailable(inner),
+            SubmitError::TxAdd(TxAddError::Overloaded(retry_after)) => {
+                ApiError::service_unavailable(inner).retry_after(*retry_after)
+            }
+            SubmitError::TxAdd(TxAddError::ChangePubKeyRateLimited(retry_after)) => {
+                ApiError::too_many_requests(inner).retry_after(*retry_after)
+            }
+            SubmitError::TxAdd(TxAddError::GuardianRecoveryTimelocked(retry_after)) => {
+                ApiError::bad_request(inner).retry_after(*retry_after)
+            }
+            _ => ApiError::bad_request(inner),
         }
-        .code(internal_code)
+        .error_code(code)
+        .code(rest_code)
     }
 }
 
@@ -73,11 +67,22 @@ impl From<SubmitError> for ApiError {
 #[derive(Clone)]
 struct ApiTransactionsData {
     tx_sender: TxSender,
+    tx_simulator: TxSimulator,
+    /// Signer of the transaction status responses, if they are signed.
+    response_signer: Option<ResponseSigner>,
 }
 
 impl ApiTransactionsData {
-    fn new(tx_sender: TxSender) -> Self {
-        Self { tx_sender }
+    fn new(tx_sender: TxSender, response_signer: Option<ResponseSigner>) -> Self {
+        let tx_simulator = TxSimulator::new(
+            tx_sender.pool.clone(),
+            tx_sender.max_number_of_transactions_per_batch,
+        );
+        Self {
+            tx_sender,
+            tx_simulator,
+            response_signer,
+        }
     }
 
     async fn tx_receipt(
@@ -91,11 +96,42 @@ impl ApiTransactionsData {
             .await
     }
 
-    async fn tx_status(&self, tx_hash: TxHash) -> QueryResult<Option<Receipt>> {
+    /// Returns the canonical hash of the transaction, which may be requested by its alias.
+    async fn resolve_tx_hash(
+        storage: &mut StorageProcessor<'_>,
+        tx_hash: TxHash,
+    ) -> QueryResult<TxHash> {
+        storage
+            .chain()
+            .operations_ext_schema()
+            .resolve_tx_hash(tx_hash)
+            .await
+    }
+
+    async fn tx_status(&self, tx_hash: TxHash) -> QueryResult<Option<TxStatus>> {
         let mut storage = self.tx_sender.pool.access_storage().await?;
+        let receipt = Self::tx_receipt_status(&mut storage, tx_hash).await?;
+        if let Some(receipt) = receipt {
+            let finalized = storage
+                .chain()
+                .block_schema()
+                .load_finalized_blocks()
+                .await?;
+            Ok(Some(TxStatus::new(receipt, &finalized)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Returns the receipt of the transaction, which may be requested by its alias.
+    async fn tx_receipt_status(
+        storage: &mut StorageProcessor<'_>,
+        tx_hash: TxHash,
+    ) -> QueryResult<Option<Receipt>> {
+        let tx_hash = Self::resolve_tx_hash(storage, tx_hash).await?;
 
         let tx_receipt = {
-            if let Some(tx_receipt) = Self::tx_receipt(&mut storage, tx_hash).await? {
+            if let Some(tx_receipt) = Self::tx_receipt(storage, tx_hash).await? {
                 tx_receipt
             } else {
                 let tx_in_mempool = storage
@@ -106,8 +142,25 @@ impl ApiTransactionsData {
 
                 let tx_receipt = if tx_in_mempool {
                     Some(Receipt::Pending)
+                } else if let Some(expired_tx) = storage
+                    .chain()
+                    .mempool_schema()
+                    .get_expired_tx(tx_hash)
+                    .await?
+                {
+                    Some(Receipt::Rejected {
+                        reason: Some(expired_tx.reason),
+                    })
                 } else {
-                    None
+                    // Transactions of the reverted blocks may be rejected by the operator.
+                    storage
+                        .chain()
+                        .block_schema()
+                        .get_reverted_tx_block(tx_hash.as_ref())
+                        .await?
+                        .map(|block| Receipt::Rejected {
+                            reason: Some(format!("Block {} was reverted", block)),
+                        })
                 };
                 return Ok(tx_receipt);
             }
@@ -157,6 +210,7 @@ impl ApiTransactionsData {
 
     async fn tx_data(&self, tx_hash: TxHash) -> QueryResult<Option<SignedZkSyncTx>> {
         let mut storage = self.tx_sender.pool.access_storage().await?;
+        let tx_hash = Self::resolve_tx_hash(&mut storage, tx_hash).await?;
 
         let operation = storage
             .chain()
@@ -176,17 +230,112 @@ impl ApiTransactionsData {
             storage.chain().mempool_schema().get_tx(tx_hash).await
         }
     }
+
+    async fn tx_trace(&self, tx_hash: TxHash) -> QueryResult<Option<TxTrace>> {
+        let mut storage = self.tx_sender.pool.access_storage().await?;
+        let tx_hash = Self::resolve_tx_hash(&mut storage, tx_hash).await?;
+
+        let trace = storage
+            .chain()
+            .state_schema()
+            .load_tx_account_updates(&tx_hash)
+            .await?
+            .map(|(block_number, updates)| TxTrace {
+                tx_hash,
+                block_number,
+                steps: updates
+                    .into_iter()
+                    .map(|(account_id, update)| trace_step(account_id, update))
+                    .collect(),
+            });
+        Ok(trace)
+    }
+
+    async fn withdrawal_execution(
+        &self,
+        tx_hash: TxHash,
+    ) -> QueryResult<Option<WithdrawalExecution>> {
+        let mut storage = self.tx_sender.pool.access_storage().await?;
+        let tx_hash = Self::resolve_tx_hash(&mut storage, tx_hash).await?;
+
+        storage
+            .chain()
+            .operations_schema()
+            .withdrawal_execution(&tx_hash)
+            .await
+    }
+
+    async fn effective_fee(&self, tx_hash: TxHash) -> QueryResult<Option<EffectiveFee>> {
+        let mut storage = self.tx_sender.pool.access_storage().await?;
+        let tx_hash = Self::resolve_tx_hash(&mut storage, tx_hash).await?;
+
+        let refund = match storage.fee_refunds_schema().get_refund(&tx_hash).await? {
+            Some(refund) => refund,
+            None => return Ok(None),
+        };
+        let token = storage
+            .tokens_schema()
+            .get_token(TokenLike::Id(refund.token))
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Unknown token {} of the fee refund", refund.token))?;
+        Ok(Some(EffectiveFee::new(refund, &token)))
+    }
+}
+
+fn trace_step(account_id: AccountId, update: AccountUpdate) -> TxTraceStep {
+    match update {
+        AccountUpdate::Create { address, nonce } => TxTraceStep::CreateAccount {
+            account_id,
+            address,
+            nonce,
+        },
+        AccountUpdate::Delete { address, nonce } => TxTraceStep::DeleteAccount {
+            account_id,
+            address,
+            nonce,
+        },
+        AccountUpdate::UpdateBalance {
+            old_nonce,
+            new_nonce,
+            balance_update: (token, old_balance, new_balance),
+        } => TxTraceStep::UpdateBalance {
+            account_id,
+            token,
+            old_balance: old_balance.into(),
+            new_balance: new_balance.into(),
+            old_nonce,
+            new_nonce,
+        },
+        AccountUpdate::ChangePubKeyHash {
+            old_pub_key_hash,
+            new_pub_key_hash,
+            old_nonce,
+            new_nonce,
+        } => TxTraceStep::ChangePubKeyHash {
+            account_id,
+            old_pub_key_hash,
+            new_pub_key_hash,
+            old_nonce,
+            new_nonce,
+        },
+    }
 }
 
 // Server implementation
 
 async fn tx_status(
+    req: HttpRequest,
     data: web::Data<ApiTransactionsData>,
     web::Path(tx_hash): web::Path<TxHash>,
-) -> JsonResult<Option<Receipt>> {
-    let tx_status = data.tx_status(tx_hash).await.map_err(ApiError::internal)?;
+    web::Query(query): web::Query<FinalityQuery>,
+) -> Result<HttpResponse, ApiError> {
+    let tx_status = data
+        .tx_status(tx_hash)
+        .await
+        .map_err(ApiError::internal)?
+        .filter(|status| query.is_reached_by(status.finality));
 
-    Ok(Json(tx_status))
+    signed_json(data.response_signer.as_ref(), &req, &tx_status)
 }
 
 async fn tx_data(
@@ -198,10 +347,43 @@ async fn tx_data(
     Ok(Json(tx_data.map(TxData::from)))
 }
 
+async fn tx_trace(
+    data: web::Data<ApiTransactionsData>,
+    web::Path(tx_hash): web::Path<TxHash>,
+) -> JsonResult<Option<TxTrace>> {
+    let tx_trace = data.tx_trace(tx_hash).await.map_err(ApiError::internal)?;
+
+    Ok(Json(tx_trace))
+}
+
+async fn withdrawal_execution(
+    data: web::Data<ApiTransactionsData>,
+    web::Path(tx_hash): web::Path<TxHash>,
+) -> JsonResult<Option<WithdrawalExecution>> {
+    let execution = data
+        .withdrawal_execution(tx_hash)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(execution))
+}
+
+async fn effective_fee(
+    data: web::Data<ApiTransactionsData>,
+    web::Path(tx_hash): web::Path<TxHash>,
+) -> JsonResult<Option<EffectiveFee>> {
+    let fee = data
+        .effective_fee(tx_hash)
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(Json(fee))
+}
+
 async fn tx_receipt_by_id(
     data: web::Data<ApiTransactionsData>,
     web::Path((tx_hash, receipt_id)): web::Path<(TxHash, u32)>,
-) -> JsonResult<Option<Receipt>> {
+) -> JsonResult<Option<TxStatus>> {
     // At the moment we store only last receipt, so this endpoint is just only a stub.
     if receipt_id > 0 {
         return Ok(Json(None));
@@ -216,7 +398,7 @@ async fn tx_receipts(
     data: web::Data<ApiTransactionsData>,
     web::Path(tx_hash): web::Path<TxHash>,
     web::Query(pagination): web::Query<PaginationQuery>,
-) -> JsonResult<Vec<Receipt>> {
+) -> JsonResult<Vec<TxStatus>> {
     let (pagination, _limit) = pagination.into_inner()?;
     // At the moment we store only last receipt, so this endpoint is just only a stub.
     let is_some = match pagination {
@@ -236,12 +418,19 @@ async fn tx_receipts(
 
 async fn submit_tx(
     data: web::Data<ApiTransactionsData>,
+    req: HttpRequest,
     Json(body): Json<IncomingTx>,
     web::Query(query): web::Query<FastProcessingQuery>,
 ) -> JsonResult<TxHash> {
     let tx_hash = data
         .tx_sender
-        .submit_tx(body.tx, body.signature, query.fast_processing)
+        .with_namespace(request_namespace(&req).as_ref())
+        .submit_tx_idempotent(
+            body.idempotency_key,
+            body.tx,
+            body.signature,
+            query.fast_processing,
+        )
         .await
         .map_err(ApiError::from)?;
 
@@ -250,6 +439,7 @@ async fn submit_tx(
 
 async fn submit_tx_batch(
     data: web::Data<ApiTransactionsData>,
+    req: HttpRequest,
     Json(body): Json<IncomingTxBatch>,
 ) -> JsonResult<Vec<TxHash>> {
     let txs = body
@@ -264,19 +454,87 @@ async fn submit_tx_batch(
     let signatures = body.signature;
     let tx_hashes = data
         .tx_sender
-        .submit_txs_batch(txs, Some(signatures))
+        .with_namespace(request_namespace(&req).as_ref())
+        .submit_txs_batch_idempotent(body.idempotency_key, txs, Some(signatures))
         .await
         .map_err(ApiError::from)?;
 
     Ok(Json(tx_hashes))
 }
 
+/// Converts the result of the submission dry run into the admission verdict. Internal errors
+/// say nothing about the transactions, so they're reported as the errors of the request.
+fn tx_admission(result: Result<(), SubmitError>) -> Result<TxAdmission, ApiError> {
+    match result {
+        Ok(()) => Ok(TxAdmission::accepted()),
+        Err(err) if err.code().category() == ApiErrorCategory::Internal => Err(err.into()),
+        Err(err) => Ok(TxAdmission::rejected(
+            err.rest_code(),
+            err.code(),
+            err.to_string(),
+        )),
+    }
+}
+
+async fn would_accept_tx(
+    data: web::Data<ApiTransactionsData>,
+    req: HttpRequest,
+    Json(body): Json<IncomingTx>,
+    web::Query(query): web::Query<FastProcessingQuery>,
+) -> JsonResult<TxAdmission> {
+    let result = data
+        .tx_sender
+        .with_namespace(request_namespace(&req).as_ref())
+        .would_accept_tx(body.tx, body.signature, query.fast_processing)
+        .await;
+
+    tx_admission(result).map(Json)
+}
+
+async fn would_accept_tx_batch(
+    data: web::Data<ApiTransactionsData>,
+    req: HttpRequest,
+    Json(body): Json<IncomingTxBatch>,
+) -> JsonResult<TxAdmission> {
+    let txs = body
+        .txs
+        .into_iter()
+        .map(|tx| TxWithSignature {
+            tx,
+            signature: None,
+        })
+        .collect();
+    let result = data
+        .tx_sender
+        .with_namespace(request_namespace(&req).as_ref())
+        .would_accept_txs_batch(txs, Some(body.signature))
+        .await;
+
+    tx_admission(result).map(Json)
+}
+
+async fn simulate_txs(
+    data: web::Data<ApiTransactionsData>,
+    Json(body): Json<TxSimulationRequest>,
+) -> JsonResult<TxSimulationResult> {
+    let result = data
+        .tx_simulator
+        .simulate(body.txs, body.unsigned)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(result))
+}
+
 async fn get_txs_fee_in_wei(
     data: web::Data<ApiTransactionsData>,
+    req: HttpRequest,
     Json(body): Json<IncomingTxForFee>,
 ) -> JsonResult<Fee> {
+    // Fees quoted to the white-label frontends include their markups.
     let fee = data
         .tx_sender
+        .with_namespace(request_namespace(&req).as_ref())
         .get_txs_fee_in_wei(body.tx_type, body.address, body.token_like)
         .await?;
     Ok(Json(fee))
@@ -284,15 +542,30 @@ async fn get_txs_fee_in_wei(
 
 async fn get_txs_batch_fee_in_wei(
     data: web::Data<ApiTransactionsData>,
+    req: HttpRequest,
     Json(body): Json<IncomingTxBatchForFee>,
 ) -> JsonResult<BatchFee> {
+    // If the sender is known, the transactions are charged for their marginal cost.
+    let origin = match body.sender {
+        Some(sender) => {
+            let token = data
+                .tx_sender
+                .token_info_from_id(body.token_like.clone())
+                .await
+                .map_err(ApiError::from)?;
+            Some((sender, token.id))
+        }
+        None => None,
+    };
     let txs = body
         .tx_types
         .into_iter()
         .zip(body.addresses.into_iter())
+        .map(|(tx_type, address)| BatchFeeTx::new(tx_type, address, origin))
         .collect();
     let fee = data
         .tx_sender
+        .with_namespace(request_namespace(&req).as_ref())
         .get_txs_batch_fee_in_wei(txs, body.token_like)
         .await
         .map_err(ApiError::from)?;
@@ -300,13 +573,27 @@ async fn get_txs_batch_fee_in_wei(
     Ok(Json(fee))
 }
 
-pub fn api_scope(tx_sender: TxSender) -> Scope {
-    let data = ApiTransactionsData::new(tx_sender);
+async fn packable_amounts(web::Path(amount): web::Path<String>) -> JsonResult<PackingDiagnostics> {
+    let amount = BigUint::from_str(&amount)
+        .map_err(|err| ApiError::bad_request("Incorrect amount").detail(err))?;
+
+    let too_large = || ApiError::bad_request("Amount is too large to be packed");
+    Ok(Json(PackingDiagnostics {
+        token_amount: PackableAmounts::try_token_amount(&amount).ok_or_else(too_large)?,
+        fee_amount: PackableAmounts::try_fee_amount(&amount).ok_or_else(too_large)?,
+    }))
+}
+
+pub fn api_scope(tx_sender: TxSender, config: &ZkSyncConfig) -> Scope {
+    let data = ApiTransactionsData::new(tx_sender, ResponseSigner::from_config(config));
 
     web::scope("transactions")
         .data(data)
         .route("{tx_hash}", web::get().to(tx_status))
         .route("{tx_hash}/data", web::get().to(tx_data))
+        .route("{tx_hash}/trace", web::get().to(tx_trace))
+        .route("{tx_hash}/withdrawal", web::get().to(withdrawal_execution))
+        .route("{tx_hash}/effective_fee", web::get().to(effective_fee))
         .route(
             "{tx_hash}/receipts/{receipt_id}",
             web::get().to(tx_receipt_by_id),
@@ -314,8 +601,12 @@ pub fn api_scope(tx_sender: TxSender) -> Scope {
         .route("{tx_hash}/receipts", web::get().to(tx_receipts))
         .route("submit", web::post().to(submit_tx))
         .route("submit/batch", web::post().to(submit_tx_batch))
+        .route("would_accept", web::post().to(would_accept_tx))
+        .route("would_accept/batch", web::post().to(would_accept_tx_batch))
+        .route("simulate", web::post().to(simulate_txs))
         .route("fee/batch", web::post().to(get_txs_batch_fee_in_wei))
         .route("fee", web::post().to(get_txs_fee_in_wei))
+        .route("packable/{amount}", web::get().to(packable_amounts))
 }
 
 #[cfg(test)]
@@ -330,6 +621,9 @@ mod tests {
     use zksync_storage::ConnectionPool;
     use zksync_test_account::ZkSyncAccount;
     use zksync_types::{
+        api_error::ApiErrorCode,
+        fee_refund::FeeRefundStatus,
+        finality::Finality,
         tokens::{Token, TokenLike},
         tx::{EthBatchSignData, EthBatchSignatures, PackedEthSignature, TxEthSignature},
         AccountId, BlockNumber, Fee, Nonce,
@@ -344,7 +638,7 @@ mod tests {
         signature_checker::{VerifiedTx, VerifySignatureRequest},
     };
 
-    use super::super::test_utils::{TestServerConfig, TestTransactions};
+    use super::super::test_utils::{core_version_headers, TestServerConfig, TestTransactions};
     use super::*;
 
     fn submit_txs_loopback() -> (CoreApiClient, actix_web::test::TestServer) {
@@ -358,10 +652,32 @@ mod tests {
             Json(Ok(()))
         }
 
+        // The mempool checks reject the transfers with the nonce other than zero,
+        // so the dry run is distinguishable from the submission.
+        async fn check_tx(tx: Json<SignedZkSyncTx>) -> Json<Result<(), TxAddError>> {
+            if tx.nonce() != Nonce(0) {
+                return Json(Err(TxAddError::NonceMismatch));
+            }
+            Json(Ok(()))
+        }
+
+        async fn check_txs_batch(
+            txs: Json<(Vec<SignedZkSyncTx>, Vec<TxEthSignature>)>,
+        ) -> Json<Result<(), TxAddError>> {
+            let (txs, _) = txs.into_inner();
+            if txs.iter().any(|tx| tx.nonce() != Nonce(0)) {
+                return Json(Err(TxAddError::NonceMismatch));
+            }
+            Json(Ok(()))
+        }
+
         let server = actix_web::test::start(move || {
             App::new()
+                .wrap(core_version_headers())
                 .route("new_tx", web::post().to(send_tx))
                 .route("new_txs_batch", web::post().to(send_txs_batch))
+                .route("check_tx", web::post().to(check_tx))
+                .route("check_txs_batch", web::post().to(check_txs_batch))
         });
 
         let url = server.url("").trim_end_matches('/').to_owned();
@@ -408,6 +724,7 @@ mod tests {
                         };
                         response.send(Ok(!is_phnx)).unwrap_or_default();
                     }
+                    TickerRequest::GetTokenFeeStatus { .. } => unreachable!(),
                     TickerRequest::GetBatchTxFee {
                         response,
                         transactions,
@@ -465,6 +782,7 @@ mod tests {
                 .common
                 .fee_free_accounts
                 .push(AccountId(0xfee));
+            cfg.config.chain.fee_refund.enabled = true;
             let pool = cfg.pool.clone();
             cfg.fill_database().await?;
 
@@ -472,13 +790,16 @@ mod tests {
             let fee_ticker = dummy_fee_ticker();
 
             let (api_client, api_server) = cfg.start_server(move |cfg| {
-                api_scope(TxSender::with_client(
-                    core_client.clone(),
-                    cfg.pool.clone(),
-                    sign_verifier.clone(),
-                    fee_ticker.clone(),
+                api_scope(
+                    TxSender::with_client(
+                        core_client.clone(),
+                        cfg.pool.clone(),
+                        sign_verifier.clone(),
+                        fee_ticker.clone(),
+                        &cfg.config,
+                    ),
                     &cfg.config,
-                ))
+                )
             });
 
             Ok((
@@ -508,6 +829,9 @@ mod tests {
         test_bad_fee_token().await?;
         test_fast_processing_flag().await?;
         test_fee_free_accounts().await?;
+        test_would_accept().await?;
+        test_packable_amounts().await?;
+        test_fee_refunds().await?;
         Ok(())
     }
 
@@ -561,24 +885,24 @@ mod tests {
         assert!(client.tx_receipt_by_id(unknown_tx_hash, 0).await?.is_none());
 
         // Tx receipts.
+        let verified_status = TxStatus {
+            receipt: Receipt::Verified {
+                block: BlockNumber(1),
+            },
+            finality: Some(Finality::Executed),
+        };
         let queries = vec![
             (
                 (committed_tx_hash, Pagination::Before(BlockNumber(1)), 1),
-                vec![Receipt::Verified {
-                    block: BlockNumber(1),
-                }],
+                vec![verified_status.clone()],
             ),
             (
                 (committed_tx_hash, Pagination::Last, 1),
-                vec![Receipt::Verified {
-                    block: BlockNumber(1),
-                }],
+                vec![verified_status.clone()],
             ),
             (
                 (committed_tx_hash, Pagination::Before(BlockNumber(2)), 1),
-                vec![Receipt::Verified {
-                    block: BlockNumber(1),
-                }],
+                vec![verified_status.clone()],
             ),
             (
                 (committed_tx_hash, Pagination::After(BlockNumber(0)), 1),
@@ -603,15 +927,45 @@ mod tests {
         // Tx status and data for committed transaction.
         assert_eq!(
             client.tx_status(committed_tx_hash).await?,
-            Some(Receipt::Verified {
-                block: BlockNumber(1)
-            })
+            Some(verified_status.clone())
+        );
+        assert_eq!(
+            client
+                .tx_status_at_finality(committed_tx_hash, Finality::Executed)
+                .await?,
+            Some(verified_status)
         );
         assert_eq!(
             SignedZkSyncTx::from(client.tx_data(committed_tx_hash).await?.unwrap()).hash(),
             committed_tx_hash
         );
 
+        // Trace of the committed transaction.
+        let expected_updates = {
+            let mut storage = server.pool.access_storage().await?;
+            storage
+                .chain()
+                .state_schema()
+                .store_tx_account_updates(BlockNumber(1), &[(committed_tx_hash, 0..2)])
+                .await?;
+            storage
+                .chain()
+                .state_schema()
+                .load_state_diff_for_block(BlockNumber(1))
+                .await?
+        };
+        let trace = client.tx_trace(committed_tx_hash).await?.unwrap();
+        assert_eq!(trace.block_number, BlockNumber(1));
+        assert_eq!(
+            trace.steps,
+            expected_updates[..2]
+                .iter()
+                .cloned()
+                .map(|(account_id, update)| trace_step(account_id, update))
+                .collect::<Vec<_>>()
+        );
+        assert!(client.tx_trace(unknown_tx_hash).await?.is_none());
+
         // Tx status and data for pending transaction.
         let tx_hash = {
             let mut storage = server.pool.access_storage().await?;
@@ -629,7 +983,20 @@ mod tests {
 
             tx_hash
         };
-        assert_eq!(client.tx_status(tx_hash).await?, Some(Receipt::Pending));
+        assert_eq!(
+            client.tx_status(tx_hash).await?,
+            Some(TxStatus {
+                receipt: Receipt::Pending,
+                finality: Some(Finality::Pending),
+            })
+        );
+        // Pending transaction hasn't reached any finality yet.
+        assert_eq!(
+            client
+                .tx_status_at_finality(tx_hash, Finality::Committed)
+                .await?,
+            None
+        );
         assert_eq!(
             SignedZkSyncTx::from(client.tx_data(tx_hash).await?.unwrap()).hash(),
             tx_hash
@@ -641,10 +1008,31 @@ mod tests {
         assert!(client.tx_data(tx_hash).await?.is_none());
 
         // Submit correct transaction.
-        let tx = TestServerConfig::gen_zk_txs(1_00).txs[0].0.clone();
+        let tx = TestServerConfig::gen_zk_txs(100).txs[0].0.clone();
         let expected_tx_hash = tx.hash();
         assert_eq!(client.submit_tx(tx, None, None).await?, expected_tx_hash);
 
+        // Retried submission with the same idempotency key returns the original hash.
+        let txs = TestServerConfig::gen_zk_txs(100).txs;
+        let (tx, other_tx) = (txs[0].0.clone(), txs[1].0.clone());
+        let expected_tx_hash = tx.hash();
+        let idempotency_key = format!("payment-{}", expected_tx_hash);
+        for _ in 0..2 {
+            assert_eq!(
+                client
+                    .submit_tx_idempotent(tx.clone(), None, None, idempotency_key.clone())
+                    .await?,
+                expected_tx_hash
+            );
+        }
+        // The key can't be reused for another transaction.
+        assert!(client
+            .submit_tx_idempotent(other_tx, None, None, idempotency_key)
+            .await
+            .unwrap_err()
+            .to_string()
+            .contains("idempotency key is already used"));
+
         // Submit transaction without fee.
         let tx = TestServerConfig::gen_zk_txs(0).txs[0].0.clone();
         assert!(client
@@ -655,7 +1043,7 @@ mod tests {
             .contains("Transaction fee is too low"));
 
         // Submit correct transactions batch.
-        let TestTransactions { acc, txs } = TestServerConfig::gen_zk_txs(1_00);
+        let TestTransactions { acc, txs } = TestServerConfig::gen_zk_txs(100);
         let eth = Token::new(TokenId(0), Default::default(), "ETH", 18);
         let (good_batch, tx_hashes): (Vec<_>, Vec<_>) = txs
             .into_iter()
@@ -690,11 +1078,120 @@ mod tests {
         Ok(())
     }
 
+    /// This test checks the following criteria:
+    ///
+    /// - Packing diagnostics report the closest packable values for the non-packable amounts.
+    /// - Amounts too large to be packed at all are rejected instead of crashing the server.
+    async fn test_packable_amounts() -> anyhow::Result<()> {
+        let (client, server) = TestServer::new().await?;
+
+        let diagnostics = client.packable_amounts(1_000_000_000_000_001u64).await?;
+        assert!(!diagnostics.token_amount.lossless);
+        assert!(diagnostics.token_amount.floor < diagnostics.token_amount.ceil);
+        assert!(!diagnostics.fee_amount.lossless);
+        assert!(
+            client
+                .packable_amounts(1000u64)
+                .await?
+                .token_amount
+                .lossless
+        );
+
+        let too_large = BigUint::from(u128::max_value()) + 1u32;
+        assert!(client
+            .packable_amounts(too_large)
+            .await
+            .unwrap_err()
+            .to_string()
+            .contains("Amount is too large to be packed"));
+
+        server.stop().await;
+        Ok(())
+    }
+
     /// This test checks the following criteria:
     ///
     /// - Attempt to pay fees in an inappropriate token fails for single txs.
     /// - Attempt to pay fees in an inappropriate token fails for single batch.
     /// - Batch with an inappropriate token still can be processed if the fee is covered with a common token.
+    async fn test_would_accept() -> anyhow::Result<()> {
+        let (client, server) = TestServer::new().await?;
+
+        let from = ZkSyncAccount::rand();
+        from.set_account_id(Some(AccountId(0xdead)));
+        let to = ZkSyncAccount::rand();
+
+        // Transaction paying the fee in the token not allowed for fees would be rejected.
+        let (tx, eth_sig) = from.sign_transfer(
+            TokenId(1),
+            "PHNX",
+            100u64.into(),
+            100u64.into(),
+            &to.address,
+            Some(Nonce(0)),
+            false,
+            Default::default(),
+        );
+        let admission = client
+            .would_accept_tx(
+                ZkSyncTx::Transfer(Box::new(tx)),
+                eth_sig.map(TxEthSignature::EthereumSignature),
+                None,
+            )
+            .await?;
+        assert!(!admission.accepted);
+        assert_eq!(
+            admission.rejection.unwrap().error_type,
+            ApiErrorCode::InappropriateFeeToken
+        );
+
+        // Transaction paying the fee in ETH passes all the checks.
+        let (tx, eth_sig) = from.sign_transfer(
+            TokenId(0),
+            "ETH",
+            100u64.into(),
+            100u64.into(),
+            &to.address,
+            Some(Nonce(0)),
+            false,
+            Default::default(),
+        );
+        let admission = client
+            .would_accept_tx(
+                ZkSyncTx::Transfer(Box::new(tx)),
+                eth_sig.map(TxEthSignature::EthereumSignature),
+                None,
+            )
+            .await?;
+        assert_eq!(admission, TxAdmission::accepted());
+
+        // Transaction passing the API checks is still rejected by the mempool ones.
+        let (tx, eth_sig) = from.sign_transfer(
+            TokenId(0),
+            "ETH",
+            100u64.into(),
+            100u64.into(),
+            &to.address,
+            Some(Nonce(1)),
+            false,
+            Default::default(),
+        );
+        let admission = client
+            .would_accept_tx(
+                ZkSyncTx::Transfer(Box::new(tx)),
+                eth_sig.map(TxEthSignature::EthereumSignature),
+                None,
+            )
+            .await?;
+        assert_eq!(
+            admission.rejection.unwrap().error_type,
+            ApiErrorCode::NonceMismatch
+        );
+
+        server.stop().await;
+        Ok(())
+    }
+
     async fn test_bad_fee_token() -> anyhow::Result<()> {
         let (client, server) = TestServer::new().await?;
 
@@ -806,6 +1303,95 @@ mod tests {
         Ok(())
     }
 
+    /// Checks that the fees paid over the required ones are recorded to be refunded on submission,
+    /// both for the single transactions and the batches.
+    async fn test_fee_refunds() -> anyhow::Result<()> {
+        let (client, server) = TestServer::new().await?;
+
+        let from = ZkSyncAccount::rand();
+        from.set_account_id(Some(AccountId(0xf00)));
+        let to = ZkSyncAccount::rand();
+
+        let (tx, eth_sig) = from.sign_transfer(
+            TokenId(0),
+            "ETH",
+            100u64.into(),
+            1000u64.into(),
+            &to.address,
+            Some(Nonce(0)),
+            false,
+            Default::default(),
+        );
+        let transfer = ZkSyncTx::Transfer(Box::new(tx));
+        let tx_hash = client
+            .submit_tx(
+                transfer,
+                eth_sig.map(TxEthSignature::EthereumSignature),
+                None,
+            )
+            .await?;
+        let fee = client.effective_fee(tx_hash).await?.unwrap();
+        assert_eq!(fee.token, TokenId(0));
+        assert_eq!(fee.signed_fee, BigUint::from(1000u32));
+        assert!(fee.actual_fee < fee.signed_fee);
+        // Nothing is refunded until the transaction is executed.
+        assert_eq!(fee.effective_fee, fee.signed_fee);
+        assert_eq!(fee.formatted_effective_fee, "0.000000000000001");
+        assert_eq!(fee.refund_status, FeeRefundStatus::Pending);
+
+        // The excess of the batch fee is refunded to the transaction paying it.
+        let (tx, _) = from.sign_transfer(
+            TokenId(0),
+            "ETH",
+            100u64.into(),
+            0u64.into(),
+            &to.address,
+            Some(Nonce(1)),
+            false,
+            Default::default(),
+        );
+        let free_tx = ZkSyncTx::Transfer(Box::new(tx));
+        let (tx, _) = from.sign_transfer(
+            TokenId(0),
+            "ETH",
+            100u64.into(),
+            200u64.into(),
+            &to.address,
+            Some(Nonce(2)),
+            false,
+            Default::default(),
+        );
+        let fee_tx = ZkSyncTx::Transfer(Box::new(tx));
+        let (free_tx_hash, fee_tx_hash) = (free_tx.hash(), fee_tx.hash());
+
+        let eth = Token::new(TokenId(0), Default::default(), "ETH", 18);
+        let batch = vec![free_tx, fee_tx];
+        let txs = batch
+            .iter()
+            .zip(std::iter::repeat(eth))
+            .map(|(tx, token)| (tx.clone(), token, tx.account()))
+            .collect::<Vec<_>>();
+        let batch_signature = {
+            let batch_message = EthBatchSignData::get_batch_sign_message(txs);
+            let eth_private_key = from
+                .try_get_eth_private_key()
+                .expect("should have eth private key");
+            let eth_sig = PackedEthSignature::sign(eth_private_key, &batch_message).unwrap();
+            EthBatchSignatures::Single(TxEthSignature::EthereumSignature(eth_sig))
+        };
+        client.submit_tx_batch(batch, batch_signature).await?;
+
+        assert!(client.effective_fee(free_tx_hash).await?.is_none());
+        let fee = client.effective_fee(fee_tx_hash).await?.unwrap();
+        assert_eq!(fee.signed_fee, BigUint::from(200u32));
+        // The batch of two transactions costs 2 in the test fee ticker.
+        assert_eq!(fee.actual_fee, BigUint::from(2u32));
+        assert_eq!(fee.refund_status, FeeRefundStatus::Pending);
+
+        server.stop().await;
+        Ok(())
+    }
+
     /// This test checks the following:
     ///
     /// Fee free account can pay zero fee in single tx.
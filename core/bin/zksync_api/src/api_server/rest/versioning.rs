@@ -0,0 +1,123 @@
+//! Versioning of the REST API.
+//!
+//! Each API version is mounted under its own prefix (e.g. `/api/v0.2`), so the response shape
+//! of a newer version can be changed without breaking the clients of the older ones.
+//! Every response carries the `X-Api-Version` header with the version that served it.
+//! Responses of the deprecated versions also carry the `Deprecation` header and the link
+//! to the successor version, so the integrations can notice that they should migrate.
+
+// External uses
+use actix_cors::{Cors, CorsFactory};
+use actix_web::{http::header::LINK, middleware::DefaultHeaders};
+use serde::Serialize;
+
+const API_VERSION_HEADER: &str = "x-api-version";
+const DEPRECATION_HEADER: &str = "deprecation";
+
+/// Max age of the CORS preflight responses in seconds.
+const CORS_MAX_AGE: usize = 3600;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiVersion {
+    V01,
+    V1,
+    V02,
+}
+
+impl ApiVersion {
+    /// Path prefix of the version endpoints.
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Self::V01 => "/api/v0.1",
+            Self::V1 => "/api/v1",
+            Self::V02 => "/api/v0.2",
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::V01 => "v0.1",
+            Self::V1 => "v1",
+            Self::V02 => "v0.2",
+        }
+    }
+
+    /// Version that replaces the deprecated one.
+    pub fn successor(self) -> Option<Self> {
+        match self {
+            Self::V01 | Self::V1 => Some(Self::V02),
+            Self::V02 => None,
+        }
+    }
+}
+
+/// Middleware adding the version headers to the responses.
+pub fn version_headers(version: ApiVersion) -> DefaultHeaders {
+    let headers = DefaultHeaders::new().header(API_VERSION_HEADER, version.as_str());
+    match version.successor() {
+        Some(successor) => headers.header(DEPRECATION_HEADER, "true").header(
+            LINK,
+            format!("<{}>; rel=\"successor-version\"", successor.prefix()),
+        ),
+        None => headers,
+    }
+}
+
+/// Creates the CORS policy allowing the requests from the given origins (`*` allows any origin).
+pub fn cors(allowed_origins: &[String]) -> CorsFactory {
+    let mut cors = Cors::new().max_age(CORS_MAX_AGE);
+    if allowed_origins.iter().any(|origin| origin == "*") {
+        cors = cors.send_wildcard();
+    } else {
+        for origin in allowed_origins {
+            cors = cors.allowed_origin(origin);
+        }
+    }
+    cors.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, web, App, HttpResponse};
+
+    async fn request(version: ApiVersion, origin: &str) -> actix_web::dev::ServiceResponse {
+        let mut app = test::init_service(
+            App::new().service(
+                web::scope(version.prefix())
+                    .wrap(cors(&["https://wallet.zksync.io".to_string()]))
+                    .wrap(version_headers(version))
+                    .route("/status", web::get().to(|| HttpResponse::Ok().finish())),
+            ),
+        )
+        .await;
+        let req = test::TestRequest::get()
+            .uri(&format!("{}/status", version.prefix()))
+            .header("origin", origin)
+            .to_request();
+        test::call_service(&mut app, req).await
+    }
+
+    #[actix_rt::test]
+    async fn deprecated_version_headers() {
+        let resp = request(ApiVersion::V01, "https://wallet.zksync.io").await;
+        assert!(resp.status().is_success());
+        assert_eq!(resp.headers().get(API_VERSION_HEADER).unwrap(), "v0.1");
+        assert_eq!(resp.headers().get(DEPRECATION_HEADER).unwrap(), "true");
+        assert_eq!(
+            resp.headers().get(LINK).unwrap(),
+            "</api/v0.2>; rel=\"successor-version\""
+        );
+
+        let resp = request(ApiVersion::V02, "https://wallet.zksync.io").await;
+        assert_eq!(resp.headers().get(API_VERSION_HEADER).unwrap(), "v0.2");
+        assert!(resp.headers().get(DEPRECATION_HEADER).is_none());
+    }
+
+    #[actix_rt::test]
+    async fn cors_rejects_unknown_origin() {
+        let resp = request(ApiVersion::V02, "https://unknown.origin").await;
+        assert!(resp.status().is_client_error());
+    }
+}
@@ -0,0 +1,112 @@
+//! Checksums of the addresses passed to the JSON RPC API.
+//!
+//! Same as the REST API guard, the middleware checks the EIP-55 checksums of all the addresses
+//! found in the call parameters, so the methods don't have to check them one by one.
+
+// External uses
+use jsonrpc_core::{
+    futures::future::{self as future01, Either, FutureResult},
+    middleware::NoopFuture,
+    Call, Metadata, Middleware, Output,
+};
+// Workspace uses
+use zksync_types::{
+    address_checksum::{check_json_addresses, AddressError, ChecksumStrictness},
+    api_error::ApiErrorCode,
+};
+// Local uses
+use super::error::rpc_error;
+
+fn address_error(err: AddressError) -> jsonrpc_core::Error {
+    let code = match &err {
+        AddressError::Malformed(_) => ApiErrorCode::MalformedAddress,
+        AddressError::BadChecksum(_) => ApiErrorCode::InvalidAddressChecksum,
+    };
+    rpc_error(code, err.to_string(), None)
+}
+
+/// Middleware rejecting the calls with the invalid address checksums.
+#[derive(Debug, Clone, Copy)]
+pub struct AddressChecksumMiddleware {
+    strictness: ChecksumStrictness,
+}
+
+impl AddressChecksumMiddleware {
+    pub fn new(strictness: ChecksumStrictness) -> Self {
+        Self { strictness }
+    }
+}
+
+impl<M: Metadata> Middleware<M> for AddressChecksumMiddleware {
+    type Future = NoopFuture;
+    type CallFuture = FutureResult<Option<Output>, ()>;
+
+    fn on_call<F, X>(&self, call: Call, meta: M, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, M) -> X + Send + Sync,
+        X: jsonrpc_core::futures::Future<Item = Option<Output>, Error = ()> + Send + 'static,
+    {
+        if let Call::MethodCall(method_call) = &call {
+            let params = serde_json::to_value(&method_call.params).unwrap_or_default();
+            if let Err(err) = check_json_addresses(&params, self.strictness) {
+                metrics::counter!("api.rpc.rejected_addresses", 1);
+                let output = Output::from(
+                    Err(address_error(err)),
+                    method_call.id.clone(),
+                    method_call.jsonrpc,
+                );
+                return Either::A(future01::ok(Some(output)));
+            }
+        }
+        Either::B(next(call, meta))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonrpc_core::{MetaIoHandler, Params, Value};
+    use serde_json::json;
+
+    use super::*;
+
+    const CHECKSUMMED: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+    const TYPO: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD";
+
+    /// Calls the method echoing its parameters and returns the response.
+    fn call(strictness: ChecksumStrictness, params: Value) -> Value {
+        let mut io = MetaIoHandler::with_middleware(AddressChecksumMiddleware::new(strictness));
+        io.add_method("echo", |params: Params| {
+            Ok(serde_json::to_value(params).unwrap())
+        });
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": "echo", "params": params });
+        let response = io
+            .handle_request_sync(&request.to_string(), ())
+            .expect("response to the method call");
+        serde_json::from_str(&response).unwrap()
+    }
+
+    fn error_code(response: &Value) -> Option<i64> {
+        response["error"]["code"].as_i64()
+    }
+
+    #[test]
+    fn call_addresses() {
+        let strict = ChecksumStrictness::Strict;
+
+        let params = json!([CHECKSUMMED, { "to": CHECKSUMMED.to_lowercase() }]);
+        assert_eq!(call(strict, params.clone())["result"], params);
+
+        assert_eq!(
+            error_code(&call(strict, json!([{ "txs": [{ "to": TYPO }] }]))),
+            Some(ApiErrorCode::InvalidAddressChecksum.rpc_code())
+        );
+        assert_eq!(
+            error_code(&call(strict, json!([&CHECKSUMMED[..41]]))),
+            Some(ApiErrorCode::MalformedAddress.rpc_code())
+        );
+        assert_eq!(
+            error_code(&call(ChecksumStrictness::Disabled, json!([TYPO]))),
+            None
+        );
+    }
+}
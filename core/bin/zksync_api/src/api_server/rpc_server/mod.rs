@@ -6,7 +6,7 @@ use futures::{
     channel::{mpsc, oneshot},
     SinkExt,
 };
-use jsonrpc_core::{Error, IoHandler, MetaIoHandler, Metadata, Middleware, Result};
+use jsonrpc_core::{Error, MetaIoHandler, Middleware, Result};
 use jsonrpc_http_server::ServerBuilder;
 
 // Workspace uses
@@ -18,25 +18,36 @@ use zksync_storage::{
     },
     ConnectionPool, StorageProcessor,
 };
-use zksync_types::{tx::TxHash, Address, BlockNumber, TokenLike, TxFeeTypes};
+use zksync_types::{
+    finality::{Finality, FinalizedBlocks},
+    tx::TxHash,
+    Address, BlockNumber, TokenLike, TxFeeTypes,
+};
 
 // Local uses
 use crate::{
-    fee_ticker::{PriceError, ResponseBatchFee, ResponseFee, TickerRequest, TokenPriceRequestType},
+    api_server::rest::namespaces::NamespaceGuard,
+    fee_ticker::{
+        BatchFeeTx, PriceError, ResponseBatchFee, ResponseFee, TickerRequest, TokenPriceRequestType,
+    },
     signature_checker::VerifySignatureRequest,
     utils::shared_lru_cache::AsyncLruCache,
 };
 use bigdecimal::BigDecimal;
 use zksync_utils::panic_notify::ThreadPanicNotify;
 
+mod address_checksum;
 pub mod error;
+mod namespaces;
 mod rpc_impl;
 mod rpc_trait;
 pub mod types;
 
-pub use self::rpc_trait::Rpc;
 use self::types::*;
-use super::tx_sender::TxSender;
+pub use self::{
+    address_checksum::AddressChecksumMiddleware, namespaces::RequestMetadata, rpc_trait::Rpc,
+};
+use super::tx_sender::{SubmitError, TxSender};
 
 #[derive(Clone)]
 pub struct RpcApp {
@@ -49,6 +60,7 @@ pub struct RpcApp {
     pub confirmations_for_eth_event: u64,
 
     tx_sender: TxSender,
+    namespaces: NamespaceGuard,
 }
 
 impl RpcApp {
@@ -64,6 +76,7 @@ impl RpcApp {
         let api_requests_caches_size = config.api.common.caches_size;
         let confirmations_for_eth_event = config.eth_watch.confirmations_for_eth_event;
 
+        let namespaces = NamespaceGuard::new(connection_pool.clone(), &config.api.common);
         let tx_sender = TxSender::new(
             connection_pool,
             sign_verify_request_sender,
@@ -81,12 +94,27 @@ impl RpcApp {
             confirmations_for_eth_event,
 
             tx_sender,
+            namespaces,
         }
     }
 
-    pub fn extend<T: Metadata, S: Middleware<T>>(self, io: &mut MetaIoHandler<T, S>) {
+    pub fn extend<S: Middleware<RequestMetadata>>(
+        self,
+        io: &mut MetaIoHandler<RequestMetadata, S>,
+    ) {
         io.extend_with(self.to_delegate())
     }
+
+    /// Resolves the API namespace of the call and counts the call against its quota.
+    /// The returned app serves the call with the settings of the namespace.
+    async fn in_namespace(self, meta: RequestMetadata) -> Result<Self> {
+        let namespace = self
+            .namespaces
+            .authorize_key(meta.api_key().as_deref())
+            .await?;
+        let tx_sender = self.tx_sender.with_namespace(namespace.as_ref());
+        Ok(Self { tx_sender, ..self })
+    }
 }
 
 impl RpcApp {
@@ -192,6 +220,20 @@ impl RpcApp {
         Ok(res)
     }
 
+    async fn get_finalized_blocks(&self) -> Result<FinalizedBlocks> {
+        let start = Instant::now();
+        let res = self
+            .access_storage()
+            .await?
+            .chain()
+            .block_schema()
+            .load_finalized_blocks()
+            .await
+            .map_err(|_| Error::internal_error())?;
+        metrics::histogram!("api.rpc.get_finalized_blocks", start.elapsed());
+        Ok(res)
+    }
+
     async fn get_tx_receipt(&self, tx_hash: TxHash) -> Result<Option<TxReceiptResponse>> {
         let start = Instant::now();
         let res = if let Some(tx_receipt) = self
@@ -202,19 +244,21 @@ impl RpcApp {
             Some(tx_receipt)
         } else {
             let mut storage = self.access_storage().await?;
-            let tx_receipt = storage
-                .chain()
-                .operations_ext_schema()
-                .tx_receipt(tx_hash.as_ref())
-                .await
-                .map_err(|err| {
-                    vlog::warn!(
-                        "Internal Server Error: '{}'; input: {}",
-                        err,
-                        tx_hash.to_string()
-                    );
-                    Error::internal_error()
-                })?;
+            let tx_receipt = async {
+                // The transaction may be requested by its alias.
+                let mut operations_ext = storage.chain().operations_ext_schema();
+                let tx_hash = operations_ext.resolve_tx_hash(tx_hash).await?;
+                operations_ext.tx_receipt(tx_hash.as_ref()).await
+            }
+            .await
+            .map_err(|err| {
+                vlog::warn!(
+                    "Internal Server Error: '{}'; input: {}",
+                    err,
+                    tx_hash.to_string()
+                );
+                Error::internal_error()
+            })?;
 
             if let Some(tx_receipt) = tx_receipt.clone() {
                 if tx_receipt.verified {
@@ -231,6 +275,34 @@ impl RpcApp {
         Ok(res)
     }
 
+    /// Returns the reason the transaction expired in the mempool for, if it did.
+    async fn get_tx_expiry_reason(&self, tx_hash: TxHash) -> Result<Option<String>> {
+        let mut storage = self.access_storage().await?;
+        let expired_tx = async {
+            let tx_hash = storage
+                .chain()
+                .operations_ext_schema()
+                .resolve_tx_hash(tx_hash)
+                .await?;
+            storage
+                .chain()
+                .mempool_schema()
+                .get_expired_tx(tx_hash)
+                .await
+        }
+        .await
+        .map_err(|err| {
+            vlog::warn!(
+                "Internal Server Error: '{}'; input: {}",
+                err,
+                tx_hash.to_string()
+            );
+            Error::internal_error()
+        })?;
+
+        Ok(expired_tx.map(|expired_tx| expired_tx.reason))
+    }
+
     async fn token_allowed_for_fees(
         mut ticker_request_sender: mpsc::Sender<TickerRequest>,
         token: TokenLike,
@@ -254,7 +326,7 @@ impl RpcApp {
 
     async fn ticker_batch_fee_request(
         mut ticker_request_sender: mpsc::Sender<TickerRequest>,
-        transactions: Vec<(TxFeeTypes, Address)>,
+        transactions: Vec<BatchFeeTx>,
         token: TokenLike,
     ) -> Result<ResponseBatchFee> {
         let req = oneshot::channel();
@@ -268,6 +340,9 @@ impl RpcApp {
             .expect("ticker receiver dropped");
         let resp = req.1.await.expect("ticker answer sender dropped");
         resp.map_err(|err| {
+            if let Some(PriceError::UnreliablePrice(_)) = err.downcast_ref() {
+                return SubmitError::FeeQuotingSuspended.into();
+            }
             vlog::warn!("Internal Server Error: '{}'; input: {:?}", err, token,);
             Error::internal_error()
         })
@@ -291,6 +366,9 @@ impl RpcApp {
             .expect("ticker receiver dropped");
         let resp = req.1.await.expect("ticker answer sender dropped");
         resp.map_err(|err| {
+            if let Some(PriceError::UnreliablePrice(_)) = err.downcast_ref() {
+                return SubmitError::FeeQuotingSuspended.into();
+            }
             vlog::warn!(
                 "Internal Server Error: '{}'; input: {:?}, {:?}",
                 err,
@@ -318,6 +396,7 @@ impl RpcApp {
         let resp = req.1.await.expect("ticker answer sender dropped");
         resp.map_err(|err| match err {
             PriceError::TokenNotFound(msg) => Error::invalid_params(msg),
+            PriceError::UnreliablePrice(_) => SubmitError::FeeQuotingSuspended.into(),
             _ => {
                 vlog::warn!("Internal Server Error: '{}'; input: {:?}", err, token);
                 Error::internal_error()
@@ -337,8 +416,8 @@ impl RpcApp {
 
         let mut result = AccountStateInfo {
             account_id: None,
-            committed: Default::default(),
-            verified: Default::default(),
+            committed: ResponseAccountState::empty(Finality::Pending),
+            verified: ResponseAccountState::empty(Finality::Executed),
         };
 
         if let Some((account_id, committed_state)) = account_info.committed {
@@ -347,6 +426,7 @@ impl RpcApp {
                 &mut storage,
                 &self.tx_sender.tokens,
                 committed_state,
+                Finality::Pending,
             )
             .await?;
         };
@@ -356,6 +436,7 @@ impl RpcApp {
                 &mut storage,
                 &self.tx_sender.tokens,
                 verified_state,
+                Finality::Executed,
             )
             .await?;
         };
@@ -409,6 +490,8 @@ pub fn start_rpc_server(
     config: &ZkSyncConfig,
 ) {
     let addr = config.api.json_rpc.http_bind_addr();
+    let keep_alive = config.api.json_rpc.http_keep_alive;
+    let address_checksum = AddressChecksumMiddleware::new(config.api.common.address_checksum);
 
     let rpc_app = RpcApp::new(
         connection_pool,
@@ -418,11 +501,12 @@ pub fn start_rpc_server(
     );
     std::thread::spawn(move || {
         let _panic_sentinel = ThreadPanicNotify(panic_notify);
-        let mut io = IoHandler::new();
+        let mut io = MetaIoHandler::with_middleware(address_checksum);
         rpc_app.extend(&mut io);
 
-        let server = ServerBuilder::new(io)
+        let server = ServerBuilder::with_meta_extractor(io, RequestMetadata::from_http_request)
             .threads(super::THREADS_PER_SERVER)
+            .keep_alive(keep_alive)
             .start_http(&addr)
             .unwrap();
         server.wait();
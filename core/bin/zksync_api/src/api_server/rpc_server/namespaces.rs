@@ -0,0 +1,123 @@
+//! API namespaces of the white-label frontends in the JSON RPC API.
+//!
+//! Same as in the REST API, the HTTP requests carry the API key in the `zksync-api-key` header.
+//! The WebSocket handshake can't carry the custom headers in the browsers, so the connection
+//! sets its API key with the `set_api_key` method instead, and the key applies to all the
+//! following calls of the connection. The calls are counted against the quota of the namespace
+//! and are served with its settings, see `RpcApp::in_namespace`.
+//!
+//! Each server has its own guard, so in the stateful mode (where the counters are kept
+//! in memory) the quota is enforced per server, same as the fee subsidies.
+
+// Built-in uses
+use std::sync::{Arc, RwLock};
+
+// External uses
+use jsonrpc_core::Metadata;
+use jsonrpc_http_server::hyper;
+use jsonrpc_pubsub::{PubSubMetadata, Session};
+
+// Workspace uses
+use zksync_types::api_error::ApiErrorCode;
+
+// Local uses
+use super::error::rpc_error;
+use crate::api_server::rest::namespaces::{NamespaceError, API_KEY_HEADER};
+
+/// Metadata of the JSON RPC calls.
+#[derive(Clone, Default)]
+pub struct RequestMetadata {
+    /// API key of the HTTP request, or the one set for the WebSocket connection.
+    /// The key is shared by all the calls of the connection.
+    api_key: Arc<RwLock<Option<String>>>,
+    /// Session of the WebSocket connection.
+    session: Option<Arc<Session>>,
+}
+
+impl RequestMetadata {
+    /// Reads the API key from the headers of the HTTP request.
+    pub fn from_http_request(req: &hyper::Request<hyper::Body>) -> Self {
+        // Malformed keys are kept so that the call is rejected rather than served
+        // outside of the namespace.
+        let api_key = req
+            .headers()
+            .get(API_KEY_HEADER)
+            .map(|api_key| String::from_utf8_lossy(api_key.as_bytes()).into_owned());
+        Self {
+            api_key: Arc::new(RwLock::new(api_key)),
+            session: None,
+        }
+    }
+
+    pub fn from_session(session: Arc<Session>) -> Self {
+        Self {
+            api_key: Arc::default(),
+            session: Some(session),
+        }
+    }
+
+    pub fn api_key(&self) -> Option<String> {
+        self.api_key.read().expect("api key lock").clone()
+    }
+
+    pub fn set_api_key(&self, api_key: String) {
+        *self.api_key.write().expect("api key lock") = Some(api_key);
+    }
+}
+
+impl Metadata for RequestMetadata {}
+
+impl PubSubMetadata for RequestMetadata {
+    fn session(&self) -> Option<Arc<Session>> {
+        self.session.clone()
+    }
+}
+
+impl From<NamespaceError> for jsonrpc_core::Error {
+    fn from(err: NamespaceError) -> Self {
+        match err {
+            NamespaceError::UnknownApiKey => {
+                rpc_error(ApiErrorCode::InvalidParams, err.to_string(), None)
+            }
+            NamespaceError::QuotaExceeded => {
+                rpc_error(ApiErrorCode::RateLimitExceeded, err.to_string(), None)
+            }
+            NamespaceError::Internal(err) => rpc_error(
+                ApiErrorCode::Internal,
+                "Internal error",
+                Some(err.to_string()),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_key_of_the_request() {
+        let req = hyper::Request::builder()
+            .header(API_KEY_HEADER, "key")
+            .body(hyper::Body::empty())
+            .unwrap();
+        assert_eq!(
+            RequestMetadata::from_http_request(&req).api_key(),
+            Some("key".to_string())
+        );
+
+        let req = hyper::Request::new(hyper::Body::empty());
+        assert_eq!(RequestMetadata::from_http_request(&req).api_key(), None);
+    }
+
+    /// Checks that the key set by one call of the connection applies to the following ones.
+    #[test]
+    fn api_key_is_shared_by_connection_calls() {
+        let connection_meta = RequestMetadata::default();
+        let call_meta = connection_meta.clone();
+        assert_eq!(call_meta.api_key(), None);
+
+        connection_meta.set_api_key("key".to_string());
+        assert_eq!(call_meta.api_key(), Some("key".to_string()));
+    }
+}
@@ -5,15 +5,19 @@ use bigdecimal::BigDecimal;
 use jsonrpc_core::{Error, Result};
 // Workspace uses
 use zksync_types::{
+    api_error::ApiErrorCode,
     tx::{EthBatchSignatures, TxEthSignature, TxHash},
-    Address, BatchFee, Fee, Token, TokenLike, TxFeeTypes, ZkSyncTx,
+    Address, BatchFee, BlockNumber, Fee, Token, TokenLike, TxFeeTypes, ZkSyncTx,
 };
 
 // Local uses
-use crate::{api_server::tx_sender::SubmitError, fee_ticker::TokenPriceRequestType};
+use crate::{
+    api_server::tx_sender::SubmitError,
+    fee_ticker::{BatchFeeTx, TokenPriceRequestType},
+};
 
 use super::{types::*, RpcApp};
-use crate::api_server::rpc_server::error::RpcErrorCodes;
+use crate::api_server::rpc_server::error::rpc_error;
 
 impl RpcApp {
     pub async fn _impl_account_info(self, address: Address) -> Result<AccountInfoResp> {
@@ -44,12 +48,17 @@ impl RpcApp {
         let executed_op = self.get_executed_priority_operation(serial_id).await?;
         let result = if let Some(executed_op) = executed_op {
             let block = self.get_block_info(executed_op.block_number).await?;
+            let finality = self
+                .get_finalized_blocks()
+                .await?
+                .block_finality(BlockNumber(executed_op.block_number as u32));
             ETHOpInfoResp {
                 executed: true,
                 block: Some(BlockInfo {
                     block_number: executed_op.block_number,
                     committed: true,
                     verified: block.map(|b| b.verified_at.is_some()).unwrap_or_default(),
+                    finality,
                 }),
             }
         } else {
@@ -70,6 +79,18 @@ impl RpcApp {
     pub async fn _impl_tx_info(self, tx_hash: TxHash) -> Result<TransactionInfoResp> {
         let start = Instant::now();
         let stored_receipt = self.get_tx_receipt(tx_hash).await?;
+        let expiry_reason = if stored_receipt.is_none() {
+            self.get_tx_expiry_reason(tx_hash).await?
+        } else {
+            None
+        };
+        let finality = match &stored_receipt {
+            Some(receipt) => self
+                .get_finalized_blocks()
+                .await?
+                .block_finality(BlockNumber(receipt.block_number as u32)),
+            None => Default::default(),
+        };
         metrics::histogram!("api.rpc.tx_info", start.elapsed());
         Ok(if let Some(stored_receipt) = stored_receipt {
             TransactionInfoResp {
@@ -80,8 +101,17 @@ impl RpcApp {
                     block_number: stored_receipt.block_number,
                     committed: true,
                     verified: stored_receipt.verified,
+                    finality,
                 }),
             }
+        } else if let Some(reason) = expiry_reason {
+            // Expired transactions will never be executed.
+            TransactionInfoResp {
+                executed: false,
+                success: Some(false),
+                fail_reason: Some(reason),
+                block: None,
+            }
         } else {
             TransactionInfoResp {
                 executed: false,
@@ -188,13 +218,18 @@ impl RpcApp {
         if !token_allowed {
             return Err(SubmitError::InappropriateFeeToken.into());
         }
-        let result = Self::ticker_request(ticker.clone(), tx_type, address, token.clone()).await?;
+        // Fees quoted to the white-label frontends include their markups.
+        let result = Self::ticker_request(ticker.clone(), tx_type, address, token.clone())
+            .await?
+            .with_markup(self.tx_sender.fee_markup_percent);
 
         let token = self.tx_sender.token_info_from_id(token).await?;
         let allowed_subsidy = self
             .tx_sender
             .subsidy_accumulator
-            .get_allowed_subsidy(&token.address);
+            .get_allowed_subsidy(&token.address)
+            .await
+            .map_err(SubmitError::internal)?;
         let fee = if allowed_subsidy >= result.subsidy_size_usd {
             result.subsidy_fee
         } else {
@@ -210,14 +245,15 @@ impl RpcApp {
         tx_types: Vec<TxFeeTypes>,
         addresses: Vec<Address>,
         token: TokenLike,
+        sender: Option<Address>,
     ) -> Result<BatchFee> {
         let start = Instant::now();
         if tx_types.len() != addresses.len() {
-            return Err(Error {
-                code: RpcErrorCodes::IncorrectTx.into(),
-                message: "Number of tx_types must be equal to the number of addresses".to_string(),
-                data: None,
-            });
+            return Err(rpc_error(
+                ApiErrorCode::InvalidParams,
+                "Number of tx_types must be equal to the number of addresses",
+                None,
+            ));
         }
 
         let ticker = self.tx_sender.ticker_requests.clone();
@@ -226,15 +262,25 @@ impl RpcApp {
             return Err(SubmitError::InappropriateFeeToken.into());
         }
 
-        let transactions: Vec<(TxFeeTypes, Address)> =
-            (tx_types.iter().cloned().zip(addresses.iter().cloned())).collect();
-        let result = Self::ticker_batch_fee_request(ticker, transactions, token.clone()).await?;
+        let token_info = self.tx_sender.token_info_from_id(token.clone()).await?;
+        let origin = sender.map(|sender| (sender, token_info.id));
+        let transactions: Vec<BatchFeeTx> = tx_types
+            .iter()
+            .cloned()
+            .zip(addresses.iter().cloned())
+            .map(|(tx_type, address)| BatchFeeTx::new(tx_type, address, origin))
+            .collect();
+        let result = Self::ticker_batch_fee_request(ticker, transactions, token)
+            .await?
+            .with_markup(self.tx_sender.fee_markup_percent);
 
-        let token = self.tx_sender.token_info_from_id(token).await?;
+        let token = token_info;
         let allowed_subsidy = self
             .tx_sender
             .subsidy_accumulator
-            .get_allowed_subsidy(&token.address);
+            .get_allowed_subsidy(&token.address)
+            .await
+            .map_err(SubmitError::internal)?;
         let fee = if allowed_subsidy >= result.subsidy_size_usd {
             result.subsidy_fee
         } else {
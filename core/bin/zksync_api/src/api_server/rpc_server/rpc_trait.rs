@@ -13,103 +13,144 @@ use zksync_types::{
 };
 
 // Local uses
-use super::{types::*, RpcApp};
+use super::{types::*, RequestMetadata, RpcApp};
 
 pub type FutureResp<T> = Box<dyn futures01::Future<Item = T, Error = Error> + Send>;
 
 #[rpc]
 pub trait Rpc {
-    #[rpc(name = "account_info", returns = "AccountInfoResp")]
-    fn account_info(&self, addr: Address) -> FutureResp<AccountInfoResp>;
+    type Metadata;
 
-    #[rpc(name = "ethop_info", returns = "ETHOpInfoResp")]
-    fn ethop_info(&self, serial_id: u32) -> FutureResp<ETHOpInfoResp>;
+    #[rpc(meta, name = "account_info", returns = "AccountInfoResp")]
+    fn account_info(&self, meta: Self::Metadata, addr: Address) -> FutureResp<AccountInfoResp>;
 
-    #[rpc(name = "tx_info", returns = "ETHOpInfoResp")]
-    fn tx_info(&self, hash: TxHash) -> FutureResp<TransactionInfoResp>;
+    #[rpc(meta, name = "ethop_info", returns = "ETHOpInfoResp")]
+    fn ethop_info(&self, meta: Self::Metadata, serial_id: u32) -> FutureResp<ETHOpInfoResp>;
 
-    #[rpc(name = "tx_submit", returns = "TxHash")]
+    #[rpc(meta, name = "tx_info", returns = "ETHOpInfoResp")]
+    fn tx_info(&self, meta: Self::Metadata, hash: TxHash) -> FutureResp<TransactionInfoResp>;
+
+    #[rpc(meta, name = "tx_submit", returns = "TxHash")]
     fn tx_submit(
         &self,
+        meta: Self::Metadata,
         tx: Box<ZkSyncTx>,
         signature: Box<Option<TxEthSignature>>,
         fast_processing: Option<bool>,
     ) -> FutureResp<TxHash>;
 
-    #[rpc(name = "submit_txs_batch", returns = "Vec<TxHash>")]
+    #[rpc(meta, name = "submit_txs_batch", returns = "Vec<TxHash>")]
     fn submit_txs_batch(
         &self,
+        meta: Self::Metadata,
         txs: Vec<TxWithSignature>,
         eth_signatures: Option<EthBatchSignatures>,
     ) -> FutureResp<Vec<TxHash>>;
 
-    #[rpc(name = "contract_address", returns = "ContractAddressResp")]
-    fn contract_address(&self) -> FutureResp<ContractAddressResp>;
+    #[rpc(meta, name = "contract_address", returns = "ContractAddressResp")]
+    fn contract_address(&self, meta: Self::Metadata) -> FutureResp<ContractAddressResp>;
 
     /// "ETH" | #ERC20_ADDRESS => {Token}
-    #[rpc(name = "tokens", returns = "Token")]
-    fn tokens(&self) -> FutureResp<HashMap<String, Token>>;
+    #[rpc(meta, name = "tokens", returns = "Token")]
+    fn tokens(&self, meta: Self::Metadata) -> FutureResp<HashMap<String, Token>>;
 
     // _address argument is left for the backward compatibility.
-    #[rpc(name = "get_tx_fee", returns = "Fee")]
+    #[rpc(meta, name = "get_tx_fee", returns = "Fee")]
     fn get_tx_fee(
         &self,
+        meta: Self::Metadata,
         tx_type: TxFeeTypes,
         _address: Address,
         token_like: TokenLike,
     ) -> FutureResp<Fee>;
 
     // _addresses argument is left for the backward compatibility.
-    #[rpc(name = "get_txs_batch_fee_in_wei", returns = "BatchFee")]
+    // If the sender is known, the transactions are charged for their marginal cost.
+    #[rpc(meta, name = "get_txs_batch_fee_in_wei", returns = "BatchFee")]
     fn get_txs_batch_fee_in_wei(
         &self,
+        meta: Self::Metadata,
         tx_types: Vec<TxFeeTypes>,
         _addresses: Vec<Address>,
         token_like: TokenLike,
+        sender: Option<Address>,
     ) -> FutureResp<BatchFee>;
 
-    #[rpc(name = "get_token_price", returns = "BigDecimal")]
-    fn get_token_price(&self, token_like: TokenLike) -> FutureResp<BigDecimal>;
+    #[rpc(meta, name = "get_token_price", returns = "BigDecimal")]
+    fn get_token_price(
+        &self,
+        meta: Self::Metadata,
+        token_like: TokenLike,
+    ) -> FutureResp<BigDecimal>;
 
-    #[rpc(name = "get_confirmations_for_eth_op_amount", returns = "u64")]
-    fn get_confirmations_for_eth_op_amount(&self) -> FutureResp<u64>;
+    #[rpc(meta, name = "get_confirmations_for_eth_op_amount", returns = "u64")]
+    fn get_confirmations_for_eth_op_amount(&self, meta: Self::Metadata) -> FutureResp<u64>;
 
-    #[rpc(name = "get_eth_tx_for_withdrawal", returns = "Option<String>")]
-    fn get_eth_tx_for_withdrawal(&self, withdrawal_hash: TxHash) -> FutureResp<Option<String>>;
+    #[rpc(meta, name = "get_eth_tx_for_withdrawal", returns = "Option<String>")]
+    fn get_eth_tx_for_withdrawal(
+        &self,
+        meta: Self::Metadata,
+        withdrawal_hash: TxHash,
+    ) -> FutureResp<Option<String>>;
 
     #[rpc(name = "get_zksync_version", returns = "String")]
     fn get_zksync_version(&self) -> Result<String, Error>;
 }
 
 impl Rpc for RpcApp {
-    fn account_info(&self, addr: Address) -> FutureResp<AccountInfoResp> {
+    type Metadata = RequestMetadata;
+
+    fn account_info(&self, meta: Self::Metadata, addr: Address) -> FutureResp<AccountInfoResp> {
         let handle = self.runtime_handle.clone();
         let self_ = self.clone();
-        let resp = async move { handle.spawn(self_._impl_account_info(addr)).await.unwrap() };
+        let resp = async move {
+            handle
+                .spawn(async move {
+                    self_
+                        .in_namespace(meta)
+                        .await?
+                        ._impl_account_info(addr)
+                        .await
+                })
+                .await
+                .unwrap()
+        };
         Box::new(resp.boxed().compat())
     }
 
-    fn ethop_info(&self, serial_id: u32) -> FutureResp<ETHOpInfoResp> {
+    fn ethop_info(&self, meta: Self::Metadata, serial_id: u32) -> FutureResp<ETHOpInfoResp> {
         let handle = self.runtime_handle.clone();
         let self_ = self.clone();
         let resp = async move {
             handle
-                .spawn(self_._impl_ethop_info(serial_id))
+                .spawn(async move {
+                    self_
+                        .in_namespace(meta)
+                        .await?
+                        ._impl_ethop_info(serial_id)
+                        .await
+                })
                 .await
                 .unwrap()
         };
         Box::new(resp.boxed().compat())
     }
 
-    fn tx_info(&self, hash: TxHash) -> FutureResp<TransactionInfoResp> {
+    fn tx_info(&self, meta: Self::Metadata, hash: TxHash) -> FutureResp<TransactionInfoResp> {
         let handle = self.runtime_handle.clone();
         let self_ = self.clone();
-        let resp = async move { handle.spawn(self_._impl_tx_info(hash)).await.unwrap() };
+        let resp = async move {
+            handle
+                .spawn(async move { self_.in_namespace(meta).await?._impl_tx_info(hash).await })
+                .await
+                .unwrap()
+        };
         Box::new(resp.boxed().compat())
     }
 
     fn tx_submit(
         &self,
+        meta: Self::Metadata,
         tx: Box<ZkSyncTx>,
         signature: Box<Option<TxEthSignature>>,
         fast_processing: Option<bool>,
@@ -118,7 +159,13 @@ impl Rpc for RpcApp {
         let self_ = self.clone();
         let resp = async move {
             handle
-                .spawn(self_._impl_tx_submit(tx, signature, fast_processing))
+                .spawn(async move {
+                    self_
+                        .in_namespace(meta)
+                        .await?
+                        ._impl_tx_submit(tx, signature, fast_processing)
+                        .await
+                })
                 .await
                 .unwrap()
         };
@@ -127,6 +174,7 @@ impl Rpc for RpcApp {
 
     fn submit_txs_batch(
         &self,
+        meta: Self::Metadata,
         txs: Vec<TxWithSignature>,
         eth_signatures: Option<EthBatchSignatures>,
     ) -> FutureResp<Vec<TxHash>> {
@@ -134,29 +182,52 @@ impl Rpc for RpcApp {
         let self_ = self.clone();
         let resp = async move {
             handle
-                .spawn(self_._impl_submit_txs_batch(txs, eth_signatures))
+                .spawn(async move {
+                    self_
+                        .in_namespace(meta)
+                        .await?
+                        ._impl_submit_txs_batch(txs, eth_signatures)
+                        .await
+                })
                 .await
                 .unwrap()
         };
         Box::new(resp.boxed().compat())
     }
 
-    fn contract_address(&self) -> FutureResp<ContractAddressResp> {
+    fn contract_address(&self, meta: Self::Metadata) -> FutureResp<ContractAddressResp> {
         let handle = self.runtime_handle.clone();
         let self_ = self.clone();
-        let resp = async move { handle.spawn(self_._impl_contract_address()).await.unwrap() };
+        let resp = async move {
+            handle
+                .spawn(async move {
+                    self_
+                        .in_namespace(meta)
+                        .await?
+                        ._impl_contract_address()
+                        .await
+                })
+                .await
+                .unwrap()
+        };
         Box::new(resp.boxed().compat())
     }
 
-    fn tokens(&self) -> FutureResp<HashMap<String, Token>> {
+    fn tokens(&self, meta: Self::Metadata) -> FutureResp<HashMap<String, Token>> {
         let handle = self.runtime_handle.clone();
         let self_ = self.clone();
-        let resp = async move { handle.spawn(self_._impl_tokens()).await.unwrap() };
+        let resp = async move {
+            handle
+                .spawn(async move { self_.in_namespace(meta).await?._impl_tokens().await })
+                .await
+                .unwrap()
+        };
         Box::new(resp.boxed().compat())
     }
 
     fn get_tx_fee(
         &self,
+        meta: Self::Metadata,
         tx_type: TxFeeTypes,
         address: Address,
         token_like: TokenLike,
@@ -165,7 +236,13 @@ impl Rpc for RpcApp {
         let self_ = self.clone();
         let resp = async move {
             handle
-                .spawn(self_._impl_get_tx_fee(tx_type, address, token_like))
+                .spawn(async move {
+                    self_
+                        .in_namespace(meta)
+                        .await?
+                        ._impl_get_tx_fee(tx_type, address, token_like)
+                        .await
+                })
                 .await
                 .unwrap()
         };
@@ -174,51 +251,85 @@ impl Rpc for RpcApp {
 
     fn get_txs_batch_fee_in_wei(
         &self,
+        meta: Self::Metadata,
         tx_types: Vec<TxFeeTypes>,
         addresses: Vec<Address>,
         token_like: TokenLike,
+        sender: Option<Address>,
     ) -> FutureResp<BatchFee> {
         let handle = self.runtime_handle.clone();
         let self_ = self.clone();
         let resp = async move {
             handle
-                .spawn(self_._impl_get_txs_batch_fee_in_wei(tx_types, addresses, token_like))
+                .spawn(async move {
+                    self_
+                        .in_namespace(meta)
+                        .await?
+                        ._impl_get_txs_batch_fee_in_wei(tx_types, addresses, token_like, sender)
+                        .await
+                })
                 .await
                 .unwrap()
         };
         Box::new(resp.boxed().compat())
     }
 
-    fn get_token_price(&self, token_like: TokenLike) -> FutureResp<BigDecimal> {
+    fn get_token_price(
+        &self,
+        meta: Self::Metadata,
+        token_like: TokenLike,
+    ) -> FutureResp<BigDecimal> {
         let handle = self.runtime_handle.clone();
         let self_ = self.clone();
         let resp = async move {
             handle
-                .spawn(self_._impl_get_token_price(token_like))
+                .spawn(async move {
+                    self_
+                        .in_namespace(meta)
+                        .await?
+                        ._impl_get_token_price(token_like)
+                        .await
+                })
                 .await
                 .unwrap()
         };
         Box::new(resp.boxed().compat())
     }
 
-    fn get_confirmations_for_eth_op_amount(&self) -> FutureResp<u64> {
+    fn get_confirmations_for_eth_op_amount(&self, meta: Self::Metadata) -> FutureResp<u64> {
         let handle = self.runtime_handle.clone();
         let self_ = self.clone();
         let resp = async move {
             handle
-                .spawn(self_._impl_get_confirmations_for_eth_op_amount())
+                .spawn(async move {
+                    self_
+                        .in_namespace(meta)
+                        .await?
+                        ._impl_get_confirmations_for_eth_op_amount()
+                        .await
+                })
                 .await
                 .unwrap()
         };
         Box::new(resp.boxed().compat())
     }
 
-    fn get_eth_tx_for_withdrawal(&self, withdrawal_hash: TxHash) -> FutureResp<Option<String>> {
+    fn get_eth_tx_for_withdrawal(
+        &self,
+        meta: Self::Metadata,
+        withdrawal_hash: TxHash,
+    ) -> FutureResp<Option<String>> {
         let handle = self.runtime_handle.clone();
         let self_ = self.clone();
         let resp = async move {
             handle
-                .spawn(self_._impl_get_eth_tx_for_withdrawal(withdrawal_hash))
+                .spawn(async move {
+                    self_
+                        .in_namespace(meta)
+                        .await?
+                        ._impl_get_eth_tx_for_withdrawal(withdrawal_hash)
+                        .await
+                })
                 .await
                 .unwrap()
         };
@@ -8,8 +8,8 @@ use serde::{Deserialize, Serialize};
 // Workspace uses
 use zksync_storage::StorageProcessor;
 use zksync_types::{
-    tx::TxEthSignature, Account, AccountId, Address, Nonce, PriorityOp, PubKeyHash, TokenId,
-    ZkSyncPriorityOp, ZkSyncTx,
+    finality::Finality, tx::TxEthSignature, Account, AccountId, Address, Nonce, PriorityOp,
+    PubKeyHash, TokenId, ZkSyncPriorityOp, ZkSyncTx,
 };
 use zksync_utils::{BigUintSerdeAsRadix10Str, BigUintSerdeWrapper};
 
@@ -31,13 +31,25 @@ pub struct ResponseAccountState {
     pub balances: HashMap<String, BigUintSerdeWrapper>,
     pub nonce: Nonce,
     pub pub_key_hash: PubKeyHash,
+    /// Finality of the state: the committed state is the latest one known to the server,
+    /// while the verified state is final.
+    pub finality: Finality,
 }
 
 impl ResponseAccountState {
+    /// Returns the state of the nonexistent account.
+    pub fn empty(finality: Finality) -> Self {
+        Self {
+            finality,
+            ..Default::default()
+        }
+    }
+
     pub async fn try_restore(
         storage: &mut StorageProcessor<'_>,
         tokens: &TokenDBCache,
         account: Account,
+        finality: Finality,
     ) -> Result<Self> {
         let inner = account_state_from_storage(storage, tokens, &account)
             .await
@@ -51,6 +63,7 @@ impl ResponseAccountState {
             balances,
             nonce: inner.nonce,
             pub_key_hash: inner.pub_key_hash,
+            finality,
         })
     }
 }
@@ -132,6 +145,8 @@ pub struct BlockInfo {
     pub block_number: i64,
     pub committed: bool,
     pub verified: bool,
+    /// Finality the block has reached on L1.
+    pub finality: Finality,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
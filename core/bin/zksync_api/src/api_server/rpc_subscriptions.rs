@@ -15,7 +15,10 @@ use zksync_types::{tx::TxHash, ActionType, Address};
 use crate::fee_ticker::TickerRequest;
 use crate::{
     api_server::event_notify::{start_sub_notifier, EventNotifierRequest, EventSubscribeRequest},
-    api_server::rpc_server::types::{ETHOpInfoResp, ResponseAccountState, TransactionInfoResp},
+    api_server::rpc_server::{
+        types::{ETHOpInfoResp, ResponseAccountState, TransactionInfoResp},
+        AddressChecksumMiddleware, RequestMetadata,
+    },
     signature_checker::VerifySignatureRequest,
 };
 use zksync_config::ZkSyncConfig;
@@ -79,10 +82,14 @@ pub trait RpcPubSub {
         meta: Option<Self::Metadata>,
         subscription: SubscriptionId,
     ) -> Result<bool>;
+
+    /// Sets the API key of the namespace the following calls of the connection are made in.
+    #[rpc(meta, name = "set_api_key")]
+    fn set_api_key(&self, meta: Self::Metadata, api_key: String) -> Result<bool>;
 }
 
 impl RpcPubSub for RpcSubApp {
-    type Metadata = Arc<Session>;
+    type Metadata = RequestMetadata;
 
     // subscribe - sub id, sink
     // unsub - sub id
@@ -167,6 +174,12 @@ impl RpcPubSub for RpcSubApp {
             .unwrap_or_default();
         Ok(true)
     }
+
+    fn set_api_key(&self, meta: Self::Metadata, api_key: String) -> Result<bool> {
+        // The key is checked by the following calls, same as the key of the HTTP requests.
+        meta.set_api_key(api_key);
+        Ok(true)
+    }
 }
 
 struct RpcSubApp {
@@ -182,6 +195,7 @@ pub fn start_ws_server(
     config: &ZkSyncConfig,
 ) {
     let addr = config.api.json_rpc.ws_bind_addr();
+    let address_checksum = AddressChecksumMiddleware::new(config.api.common.address_checksum);
 
     let (event_sub_sender, event_sub_receiver) = mpsc::channel(2048);
 
@@ -202,7 +216,7 @@ pub fn start_ws_server(
     std::thread::spawn(move || {
         let _panic_sentinel = ThreadPanicNotify(panic_notify);
 
-        let mut io = PubSubHandler::new(MetaIoHandler::default());
+        let mut io = PubSubHandler::new(MetaIoHandler::with_middleware(address_checksum));
 
         req_rpc_app.extend(&mut io);
 
@@ -218,7 +232,9 @@ pub fn start_ws_server(
 
         let server = jsonrpc_ws_server::ServerBuilder::with_meta_extractor(
             io,
-            |context: &RequestContext| Arc::new(Session::new(context.sender())),
+            |context: &RequestContext| {
+                RequestMetadata::from_session(Arc::new(Session::new(context.sender())))
+            },
         )
         .max_connections(1000)
         .event_loop_executor(task_executor.executor())
@@ -2,7 +2,6 @@
 
 // Built-in uses
 use std::iter::FromIterator;
-use std::sync::{Arc, RwLock};
 use std::{
     collections::{HashMap, HashSet},
     fmt::Display,
@@ -11,7 +10,7 @@ use std::{
 
 // External uses
 use bigdecimal::BigDecimal;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use futures::{
     channel::{mpsc, oneshot},
     prelude::*,
@@ -21,27 +20,52 @@ use num::{bigint::ToBigInt, rational::Ratio, BigUint, CheckedSub, Zero};
 use thiserror::Error;
 
 // Workspace uses
-use zksync_config::ZkSyncConfig;
-use zksync_storage::{chain::account::records::EthAccountType, ConnectionPool};
+use vlog::Instrument;
+use zksync_config::{configs::failure_policy::FailurePolicy, ZkSyncConfig};
+use zksync_storage::{chain::account::records::EthAccountType, ConnectionPool, StorageProcessor};
 use zksync_types::{
+    activations::{ActivationHint, PrepaidActivation},
+    api_error::ApiErrorCode,
+    api_namespace::ApiNamespace,
+    fast_withdrawals::{
+        FastWithdrawalIntent, FastWithdrawalIntentId, FastWithdrawalOffer,
+        StoredFastWithdrawalIntent,
+    },
+    helpers::PackableAmounts,
     tx::{
-        EthBatchSignData, EthBatchSignatures, EthSignData, SignedZkSyncTx, TxEthSignature, TxHash,
+        ChangePubKeyEthAuthData, EthBatchSignData, EthBatchSignatures, EthSignData,
+        SignatureDomain, SignedZkSyncTx, TxEthSignature, TxHash,
     },
-    AccountId, Address, BatchFee, Fee, Token, TokenId, TokenLike, TxFeeTypes, ZkSyncTx, H160,
+    AccountId, Address, BatchFee, Fee, PubKeyHash, Token, TokenId, TokenLike, TxFeeTypes, ZkSyncTx,
+    H160,
 };
-use zksync_utils::ratio_to_big_decimal;
+use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal};
 
 // Local uses
 use crate::{
     api_server::forced_exit_checker::{ForcedExitAccountAgeChecker, ForcedExitChecker},
     api_server::rpc_server::types::TxWithSignature,
     core_api_client::CoreApiClient,
-    fee_ticker::{ResponseBatchFee, ResponseFee, TickerRequest, TokenPriceRequestType},
+    fee_ticker::{
+        BatchFeeTx, PriceError, ResponseBatchFee, ResponseFee, TickerRequest, TokenPriceRequestType,
+    },
     signature_checker::{BatchRequest, RequestData, TxRequest, VerifiedTx, VerifySignatureRequest},
     tx_error::TxAddError,
-    utils::{block_details_cache::BlockDetailsCache, token_db_cache::TokenDBCache},
+    utils::{
+        account_cache::AccountIdCache, block_details_cache::BlockDetailsCache,
+        shared_counters::SharedCounters, token_db_cache::TokenDBCache,
+    },
 };
 
+/// Max length of the idempotency key supplied with the submitted transactions.
+const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 128;
+/// Period after which the key of the unfinished submission is released, so that the client
+/// can retry if the server crashed in the middle of the submission. Submission to the mempool
+/// takes seconds, so the submissions are not expected to be in progress for that long.
+const IDEMPOTENT_SUBMISSION_TIMEOUT_SECS: i64 = 60;
+/// Precision of the paid subsidies (in USD) stored in the shared counters.
+const SUBSIDY_PRECISION: usize = 18;
+
 #[derive(Clone)]
 pub struct TxSender {
     pub core_api_client: CoreApiClient,
@@ -50,6 +74,7 @@ pub struct TxSender {
 
     pub pool: ConnectionPool,
     pub tokens: TokenDBCache,
+    pub accounts: AccountIdCache,
 
     pub forced_exit_checker: ForcedExitChecker,
     pub blocks: BlockDetailsCache,
@@ -61,72 +86,84 @@ pub struct TxSender {
     pub max_number_of_authors_per_batch: usize,
 
     pub subsidy_accumulator: SubsidyAccumulator,
+    /// Counters of the transactions submitted by the accounts, shared by the API replicas.
+    pub submission_counters: SharedCounters,
+    /// Whether the transactions are accepted while the shared counters are not available.
+    pub shared_state_failure_policy: FailurePolicy,
+    /// Max time to wait for the shared counters, `None` if there is no timeout.
+    pub shared_state_timeout: Option<std::time::Duration>,
+    /// Max number of transactions an account can submit per minute, 0 means no limit.
+    pub max_txs_per_account_per_minute: u64,
+    /// Period during which the idempotency keys of the submissions are remembered.
+    pub idempotency_key_ttl: chrono::Duration,
+    /// Network the Ethereum signatures of the transactions must be bound to,
+    /// `None` if the protocol version doesn't require it yet.
+    pub signature_domain: Option<SignatureDomain>,
+    /// Network the signatures checked by the contract (i.e. the guardian sets) are bound to
+    /// regardless of the protocol version.
+    pub network_domain: SignatureDomain,
+    /// Whether the fees exceeding the required ones are recorded to be refunded.
+    pub fee_refunds_enabled: bool,
+    /// Markup of the required fees in percents, set for the requests made in the API namespace
+    /// of a white-label frontend, see `with_namespace`.
+    pub fee_markup_percent: Option<u32>,
 }
 
 /// Used to store paid subsidy and daily limit
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SubsidyAccumulator {
     /// Subsidy limit for token address in USD (e.g. 1 -> 1 USD)
     daily_limits: HashMap<Address, Ratio<BigUint>>,
-    /// Paid subsidy per token, dated by time when first subsidy is paid
-    #[allow(clippy::type_complexity)]
-    limit_used: Arc<RwLock<HashMap<Address, (Ratio<BigUint>, chrono::DateTime<Utc>)>>>,
-}
-
-impl Clone for SubsidyAccumulator {
-    fn clone(&self) -> Self {
-        Self {
-            daily_limits: self.daily_limits.clone(),
-            limit_used: Arc::clone(&self.limit_used),
-        }
-    }
+    /// Paid subsidy per token, reset once a day since the first subsidy is paid.
+    limit_used: SharedCounters,
 }
 
 impl SubsidyAccumulator {
-    pub fn new(daily_limits: HashMap<Address, Ratio<BigUint>>) -> Self {
+    pub fn new(daily_limits: HashMap<Address, Ratio<BigUint>>, limit_used: SharedCounters) -> Self {
         Self {
             daily_limits,
-            limit_used: Arc::new(RwLock::new(HashMap::new())),
+            limit_used,
         }
     }
 
-    pub fn get_allowed_subsidy(&self, token_address: &Address) -> Ratio<BigUint> {
+    fn counter_key(token_address: &Address) -> String {
+        format!("subsidy:{:x}", token_address)
+    }
+
+    pub async fn get_allowed_subsidy(
+        &self,
+        token_address: &Address,
+    ) -> anyhow::Result<Ratio<BigUint>> {
         let limit = self
             .daily_limits
             .get(token_address)
             .cloned()
             .unwrap_or_else(|| Ratio::from_integer(0u32.into()));
-        let used = self.limit_used.read().expect("subsidy counter rlock");
-        let subsidy_used = used
-            .get(token_address)
-            .map(|(subs, _)| subs.clone())
-            .unwrap_or_else(|| Ratio::from_integer(0u32.into()));
-        limit
+        let subsidy_used = self
+            .limit_used
+            .load(&Self::counter_key(token_address))
+            .await?;
+        let subsidy_used = big_decimal_to_ratio(&subsidy_used)?;
+        Ok(limit
             .checked_sub(&subsidy_used)
-            .unwrap_or_else(|| Ratio::from_integer(0u32.into()))
+            .unwrap_or_else(|| Ratio::from_integer(0u32.into())))
     }
 
-    pub fn get_total_paid_subsidy(&self, token_address: &Address) -> Ratio<BigUint> {
-        let used = self.limit_used.read().expect("subsidy counter rlock");
-        used.get(token_address)
-            .map(|(subs, _)| subs.clone())
-            .unwrap_or_else(|| Ratio::from_integer(0u32.into()))
-    }
-
-    pub fn add_used_subsidy(&self, token_address: &Address, subsidy_amount: Ratio<BigUint>) {
-        let mut used = self.limit_used.write().expect("subsidy counter wlock");
-        let new_value = if let Some((mut old_amount, creation_time)) = used.remove(token_address) {
-            if Utc::now().signed_duration_since(creation_time) >= chrono::Duration::days(1) {
-                (Ratio::from_integer(0u32.into()), Utc::now())
-            } else {
-                old_amount += subsidy_amount;
-                (old_amount, creation_time)
-            }
-        } else {
-            (Ratio::from_integer(0u32.into()), Utc::now())
-        };
-
-        used.insert(*token_address, new_value);
+    /// Records the paid subsidy and returns the total subsidy paid for the token.
+    pub async fn add_used_subsidy(
+        &self,
+        token_address: &Address,
+        subsidy_amount: Ratio<BigUint>,
+    ) -> anyhow::Result<Ratio<BigUint>> {
+        let total = self
+            .limit_used
+            .increment(
+                &Self::counter_key(token_address),
+                ratio_to_big_decimal(&subsidy_amount, SUBSIDY_PRECISION),
+                chrono::Duration::days(1),
+            )
+            .await?;
+        big_decimal_to_ratio(&total)
     }
 }
 
@@ -144,6 +181,10 @@ pub enum SubmitError {
     TxAdd(TxAddError),
     #[error("Chosen token is not suitable for paying fees.")]
     InappropriateFeeToken,
+    #[error("Too many transactions were submitted by the account, try again later.")]
+    RateLimitExceeded,
+    #[error("Fee quoting is suspended due to unreliable token prices, try again later.")]
+    FeeQuotingSuspended,
 
     #[error("Communication error with the core server: {0}.")]
     CommunicationCoreServer(String),
@@ -169,6 +210,40 @@ impl SubmitError {
     pub fn invalid_params(msg: impl Display) -> Self {
         Self::InvalidParams(msg.to_string())
     }
+
+    /// Returns the stable code of the error reported to the API clients.
+    pub fn code(&self) -> ApiErrorCode {
+        match self {
+            Self::AccountCloseDisabled => ApiErrorCode::AccountCloseDisabled,
+            Self::InvalidParams(_) => ApiErrorCode::InvalidParams,
+            Self::UnsupportedFastProcessing => ApiErrorCode::UnsupportedFastProcessing,
+            Self::IncorrectTx(_) => ApiErrorCode::IncorrectTx,
+            Self::TxAdd(err) => ApiErrorCode::from(*err),
+            Self::InappropriateFeeToken => ApiErrorCode::InappropriateFeeToken,
+            Self::RateLimitExceeded => ApiErrorCode::RateLimitExceeded,
+            Self::FeeQuotingSuspended => ApiErrorCode::FeeQuotingSuspended,
+            Self::CommunicationCoreServer(_) => ApiErrorCode::CoreServerUnavailable,
+            Self::Internal(_) => ApiErrorCode::Internal,
+            Self::Other(_) => ApiErrorCode::Other,
+        }
+    }
+
+    /// Returns the numeric code of the JSON RPC error.
+    pub fn rpc_code(&self) -> i64 {
+        match self {
+            // Unlike the other internal errors, the internal mempool error had no specific code.
+            Self::TxAdd(TxAddError::Other) => ApiErrorCode::RPC_OTHER_CODE,
+            _ => self.code().rpc_code(),
+        }
+    }
+
+    /// Returns the numeric code of the REST API error.
+    pub fn rest_code(&self) -> u64 {
+        match self {
+            Self::TxAdd(_) => ApiErrorCode::REST_TX_ADD_CODE,
+            _ => self.code().rest_code(),
+        }
+    }
 }
 
 #[macro_export]
@@ -183,6 +258,14 @@ macro_rules! internal_error {
     }};
 }
 
+/// Converts the fee ticker error, distinguishing the fee quoting suspended by the price sanity check.
+fn ticker_error(err: anyhow::Error) -> SubmitError {
+    match err.downcast_ref::<PriceError>() {
+        Some(PriceError::UnreliablePrice(_)) => SubmitError::FeeQuotingSuspended,
+        _ => internal_error!(err),
+    }
+}
+
 impl TxSender {
     pub fn new(
         connection_pool: ConnectionPool,
@@ -213,7 +296,9 @@ impl TxSender {
         let max_number_of_authors_per_batch =
             config.api.common.max_number_of_authors_per_batch as usize;
 
-        let subsidy_accumulator = SubsidyAccumulator::new(config.ticker.get_subsidy_limits());
+        let shared_counters = SharedCounters::new(connection_pool.clone(), &config.api.common);
+        let subsidy_accumulator =
+            SubsidyAccumulator::new(config.ticker.get_subsidy_limits(), shared_counters.clone());
 
         Self {
             core_api_client,
@@ -221,6 +306,7 @@ impl TxSender {
             sign_verify_requests: sign_verify_request_sender,
             ticker_requests: ticker_request_sender,
             tokens: TokenDBCache::new(),
+            accounts: AccountIdCache::new(config.api.common.caches_size),
             forced_exit_checker: ForcedExitChecker::new(config),
             enforce_pubkey_change_fee: config.api.common.enforce_pubkey_change_fee,
             blocks: BlockDetailsCache::new(config.api.common.caches_size),
@@ -229,21 +315,143 @@ impl TxSender {
             max_number_of_transactions_per_batch,
             max_number_of_authors_per_batch,
             subsidy_accumulator,
+            submission_counters: shared_counters,
+            shared_state_failure_policy: config.failure_policy.shared_state,
+            shared_state_timeout: config.failure_policy.shared_state_timeout(),
+            max_txs_per_account_per_minute: config.api.common.max_txs_per_account_per_minute,
+            idempotency_key_ttl: config.api.common.idempotency_key_ttl(),
+            signature_domain: SignatureDomain::for_protocol_version(
+                config.eth_sender.sender.protocol_version,
+                config.eth_client.chain_id.into(),
+                config.contracts.contract_addr,
+            ),
+            network_domain: SignatureDomain::new(
+                config.eth_client.chain_id.into(),
+                config.contracts.contract_addr,
+            ),
+            fee_refunds_enabled: config.chain.fee_refund.enabled,
+            fee_markup_percent: None,
+        }
+    }
+
+    /// Returns the sender serving the requests made in the API namespace: both the quoted
+    /// and the required fees include the markup of the namespace.
+    pub fn with_namespace(&self, namespace: Option<&ApiNamespace>) -> Self {
+        Self {
+            fee_markup_percent: namespace.and_then(|namespace| namespace.fee_markup_percent),
+            ..self.clone()
         }
     }
 
+    /// Returns the message the Ethereum signature of the transaction must correspond to.
+    fn eth_sign_message(&self, message: String) -> Vec<u8> {
+        SignatureDomain::apply_optional(self.signature_domain.as_ref(), message).into_bytes()
+    }
+
+    /// Applies the shared state failure policy to the query of the counters shared by the API
+    /// replicas. The query not finished within the timeout (if it's set) is considered failed,
+    /// e.g. if the database is overloaded. A lagging database replica is not detected here:
+    /// its stale counters are used as is, unless the database timeouts fail the query.
+    /// Returns `None` if the failure is let through.
+    async fn query_shared_state<T>(
+        &self,
+        query: impl Future<Output = anyhow::Result<T>>,
+    ) -> Result<Option<T>, SubmitError> {
+        let result = match self.shared_state_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, query)
+                .await
+                .unwrap_or_else(|_| Err(anyhow::format_err!("Shared state query timed out"))),
+            None => query.await,
+        };
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(err) if self.shared_state_failure_policy.is_open() => {
+                vlog::warn!(
+                    "Failed to query the shared state, the check is skipped: {}",
+                    err
+                );
+                metrics::counter!("api.tx_sender.shared_state.failed_open", 1);
+                Ok(None)
+            }
+            Err(err) => Err(SubmitError::internal(err)),
+        }
+    }
+
+    /// Returns the subsidy which can still be paid in the token.
+    /// No subsidies are paid while the shared counters are not available.
+    async fn allowed_subsidy(
+        &self,
+        token_address: &Address,
+    ) -> Result<Ratio<BigUint>, SubmitError> {
+        let allowed_subsidy = self
+            .query_shared_state(self.subsidy_accumulator.get_allowed_subsidy(token_address))
+            .await?;
+        Ok(allowed_subsidy.unwrap_or_else(Ratio::zero))
+    }
+
+    /// Counts the submitted transactions against the per-account rate limit. Transactions
+    /// of a batch are counted for each of their initiators. Must be called only once the
+    /// signatures are verified. On the dry run the limit
+    /// is checked without counting the transactions. The limit is not enforced while
+    /// the shared counters are not available, if the failure policy allows it.
+    async fn check_rate_limit<'a>(
+        &self,
+        txs: impl Iterator<Item = &'a ZkSyncTx>,
+        dry_run: bool,
+    ) -> Result<(), SubmitError> {
+        if self.max_txs_per_account_per_minute == 0 {
+            return Ok(());
+        }
+
+        let mut txs_per_account = HashMap::<AccountId, u64>::new();
+        for tx in txs {
+            if let Ok(account_id) = tx.account_id() {
+                *txs_per_account.entry(account_id).or_default() += 1;
+            }
+        }
+
+        let limit = BigDecimal::from(self.max_txs_per_account_per_minute);
+        for (account_id, txs_count) in txs_per_account {
+            let key = format!("rate_limit:{}", *account_id);
+            let query = async {
+                if dry_run {
+                    self.submission_counters
+                        .load(&key)
+                        .await
+                        .map(|submitted| submitted + BigDecimal::from(txs_count))
+                } else {
+                    self.submission_counters
+                        .increment(
+                            &key,
+                            BigDecimal::from(txs_count),
+                            chrono::Duration::minutes(1),
+                        )
+                        .await
+                }
+            };
+            let submitted = match self.query_shared_state(query).await? {
+                Some(submitted) => submitted,
+                None => return Ok(()),
+            };
+            if submitted > limit {
+                metrics::counter!("api.tx_sender.rate_limit_exceeded", 1);
+                return Err(SubmitError::RateLimitExceeded);
+            }
+        }
+        Ok(())
+    }
+
     /// If `ForcedExit` has Ethereum siganture (e.g. it's a part of a batch), an actual signer
     /// is initiator, not the target, thus, this function will perform a database query to acquire
     /// the corresponding address.
     async fn get_tx_sender(&self, tx: &ZkSyncTx) -> Result<Address, anyhow::Error> {
         match tx {
             ZkSyncTx::ForcedExit(tx) => self
-                .pool
-                .access_storage()
-                .await?
-                .chain()
-                .account_schema()
-                .account_address_by_id(tx.initiator_account_id)
+                .accounts
+                .account_address_by_id(
+                    &mut self.pool.access_storage().await?,
+                    tx.initiator_account_id,
+                )
                 .await?
                 .ok_or_else(|| anyhow::anyhow!("Forced Exit account is not found in db")),
             _ => Ok(tx.account()),
@@ -265,19 +473,181 @@ impl TxSender {
     }
 
     pub async fn submit_tx(
+        &self,
+        tx: ZkSyncTx,
+        signature: Option<TxEthSignature>,
+        fast_processing: Option<bool>,
+    ) -> Result<TxHash, SubmitError> {
+        // Everything logged during the submission will carry the transaction hash.
+        let span = vlog::info_span!("submit_tx", tx_hash = %tx.hash().to_string());
+        let result = self
+            .submit_tx_inner(tx, signature, fast_processing, false)
+            .instrument(span.clone())
+            .await;
+
+        span.in_scope(|| match &result {
+            Ok(_) => vlog::debug!("Transaction was sent to the mempool"),
+            Err(err) => vlog::debug!("Transaction was rejected: {}", err),
+        });
+        result
+    }
+
+    /// Sends the transaction to the mempool unless it was already submitted with the same
+    /// idempotency key, in which case the hash of the submitted transaction is returned.
+    pub async fn submit_tx_idempotent(
+        &self,
+        idempotency_key: Option<String>,
+        tx: ZkSyncTx,
+        signature: Option<TxEthSignature>,
+        fast_processing: Option<bool>,
+    ) -> Result<TxHash, SubmitError> {
+        let tx_hashes = self
+            .submit_idempotent(idempotency_key, vec![tx.hash()], async {
+                self.submit_tx(tx, signature, fast_processing)
+                    .await
+                    .map(|tx_hash| vec![tx_hash])
+            })
+            .await?;
+        Ok(tx_hashes[0])
+    }
+
+    /// Sends the batch to the mempool unless it was already submitted with the same
+    /// idempotency key, in which case the hashes of the submitted transactions are returned.
+    pub async fn submit_txs_batch_idempotent(
+        &self,
+        idempotency_key: Option<String>,
+        txs: Vec<TxWithSignature>,
+        eth_signatures: Option<EthBatchSignatures>,
+    ) -> Result<Vec<TxHash>, SubmitError> {
+        let request_hashes = txs.iter().map(|tx| tx.tx.hash()).collect();
+        self.submit_idempotent(
+            idempotency_key,
+            request_hashes,
+            self.submit_txs_batch(txs, eth_signatures),
+        )
+        .await
+    }
+
+    /// Runs the submission of the transactions with the given hashes, remembering its result
+    /// for the idempotency key. If the key was already used for the same transactions,
+    /// the result of the previous submission is returned instead.
+    async fn submit_idempotent(
+        &self,
+        idempotency_key: Option<String>,
+        request_hashes: Vec<TxHash>,
+        submission: impl Future<Output = Result<Vec<TxHash>, SubmitError>>,
+    ) -> Result<Vec<TxHash>, SubmitError> {
+        let idempotency_key = match idempotency_key {
+            Some(idempotency_key) => idempotency_key,
+            None => return submission.await,
+        };
+        if idempotency_key.is_empty() || idempotency_key.len() > MAX_IDEMPOTENCY_KEY_LENGTH {
+            return Err(SubmitError::invalid_params(format!(
+                "idempotency key should be from 1 to {} characters long",
+                MAX_IDEMPOTENCY_KEY_LENGTH
+            )));
+        }
+
+        let valid_since = Utc::now() - self.idempotency_key_ttl;
+        let pending_valid_since =
+            Utc::now() - chrono::Duration::seconds(IDEMPOTENT_SUBMISSION_TIMEOUT_SECS);
+        let previous_submission = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?
+            .idempotency_schema()
+            .reserve_key(
+                &idempotency_key,
+                &request_hashes,
+                valid_since,
+                pending_valid_since,
+            )
+            .await
+            .map_err(SubmitError::internal)?;
+        if let Some(previous_submission) = previous_submission {
+            if previous_submission.request_hashes != request_hashes {
+                return Err(SubmitError::invalid_params(
+                    "idempotency key is already used for other transactions",
+                ));
+            }
+            return previous_submission.tx_hashes.ok_or_else(|| {
+                SubmitError::other("Submission with this idempotency key is in progress")
+            });
+        }
+
+        let result = submission.await;
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?;
+        let stored = match &result {
+            Ok(tx_hashes) => {
+                storage
+                    .idempotency_schema()
+                    .complete_submission(&idempotency_key, tx_hashes)
+                    .await
+            }
+            Err(_) => {
+                storage
+                    .idempotency_schema()
+                    .release_key(&idempotency_key)
+                    .await
+            }
+        };
+        // Transactions are already processed, so the result is returned anyway.
+        // The key reserved by the unfinished submission expires after the TTL.
+        if let Err(err) = stored {
+            vlog::error!(
+                "Failed to store the submission with the idempotency key {}: {}",
+                idempotency_key,
+                err
+            );
+        }
+        result
+    }
+
+    /// Checks the transaction and sends it to the mempool. On the dry run the transaction
+    /// goes through all the same checks, including the mempool ones, but is not added to the mempool.
+    async fn submit_tx_inner(
         &self,
         mut tx: ZkSyncTx,
         signature: Option<TxEthSignature>,
         fast_processing: Option<bool>,
+        dry_run: bool,
     ) -> Result<TxHash, SubmitError> {
         if tx.is_close() {
             return Err(SubmitError::AccountCloseDisabled);
         }
+        check_packable_amounts(&tx)?;
+        check_guardians_domain(&tx, &self.network_domain)?;
 
         if let ZkSyncTx::ForcedExit(forced_exit) = &tx {
             self.check_forced_exit(forced_exit).await?;
         }
 
+        // `ChangePubKey` without fee is accepted if its fee is prepaid by another account.
+        if let ZkSyncTx::ChangePubKey(change_pub_key) = &tx {
+            if change_pub_key.fee.is_zero() && fast_processing != Some(true) {
+                let recipient = change_pub_key.account;
+                let mut storage = self
+                    .pool
+                    .access_storage()
+                    .await
+                    .map_err(SubmitError::internal)?;
+                let prepaid_activation =
+                    Self::load_prepaid_activation(&mut storage, recipient).await?;
+                drop(storage);
+
+                if let Some((activation, _)) = prepaid_activation {
+                    return self
+                        .submit_prepaid_activation_batch(tx, signature, activation, dry_run)
+                        .await;
+                }
+            }
+        }
+
         let fast_processing = fast_processing.unwrap_or_default(); // `None` => false
         if fast_processing && !tx.is_withdraw() {
             return Err(SubmitError::UnsupportedFastProcessing);
@@ -299,11 +669,11 @@ impl TxSender {
 
         // Resolve the token.
         let token = self.token_info_from_id(tx.token_id()).await?;
-        let allowed_subsidy = self.subsidy_accumulator.get_allowed_subsidy(&token.address);
+        let allowed_subsidy = self.allowed_subsidy(&token.address).await?;
         let mut paid_subsidy = Ratio::from_integer(0u32.into());
         let msg_to_sign = tx
             .get_ethereum_sign_message(token.clone())
-            .map(String::into_bytes);
+            .map(|message| self.eth_sign_message(message));
 
         let is_whitelisted_initiator = tx
             .account_id()
@@ -318,6 +688,8 @@ impl TxSender {
 
         let sign_verify_channel = self.sign_verify_requests.clone();
         let ticker_request_sender = self.ticker_requests.clone();
+        // Signed and required fees of the transaction paying more than required.
+        let mut fee_refund = None;
 
         if let Some((tx_type, token, address, provided_fee)) = tx_fee_info {
             let should_enforce_fee = !matches!(tx_type, TxFeeTypes::ChangePubKey { .. })
@@ -332,7 +704,19 @@ impl TxSender {
 
             let required_fee_data =
                 Self::ticker_request(ticker_request_sender, tx_type, address, token.clone())
-                    .await?;
+                    .await?
+                    .with_markup(self.fee_markup_percent);
+
+            // The initiator of `ForcedExit` is not known by the address, so its fee is not refunded.
+            if self.fee_refunds_enabled
+                && !matches!(tx, ZkSyncTx::ForcedExit(_))
+                && provided_fee > required_fee_data.normal_fee.total_fee
+            {
+                fee_refund = Some((
+                    provided_fee.clone(),
+                    required_fee_data.normal_fee.total_fee.clone(),
+                ));
+            }
 
             // Converting `BitUint` to `BigInt` is safe.
             let required_fee: BigDecimal = required_fee_data
@@ -376,36 +760,58 @@ impl TxSender {
             self.get_tx_sender_type(&tx).await?,
             signature.clone(),
             msg_to_sign,
+            self.signature_domain.is_none(),
             sign_verify_channel,
         )
         .await?
         .unwrap_tx();
+        // Only the transactions signed by the account are counted against its limit,
+        // so nobody can exhaust the limit of another account.
+        self.check_rate_limit(std::iter::once(&verified_tx.tx), dry_run)
+            .await?;
 
         let tx_hash = verified_tx.tx.hash();
+        if dry_run {
+            self.core_api_client
+                .check_tx(verified_tx)
+                .await
+                .map_err(SubmitError::communication_core_server)?
+                .map_err(SubmitError::TxAdd)?;
+            return Ok(tx_hash);
+        }
         // Send verified transactions to the mempool.
         self.core_api_client
             .send_tx(verified_tx)
             .await
             .map_err(SubmitError::communication_core_server)?
             .map_err(SubmitError::TxAdd)?;
+        if let Some((signed_fee, actual_fee)) = fee_refund {
+            self.record_fee_refund(tx_hash, tx.account(), token.id, &signed_fee, &actual_fee)
+                .await;
+        }
         // if everything is OK, return the transactions hashes.
         if paid_subsidy > Ratio::from_integer(0u32.into()) {
             let paid_subsidy_dec = ratio_to_big_decimal(&paid_subsidy, 6).to_string();
-            let total_paid_subsidy = ratio_to_big_decimal(
-                &self
-                    .subsidy_accumulator
-                    .get_total_paid_subsidy(&token.address),
-                6,
-            );
-            vlog::info!(
-                "Paid subsidy for tx, tx: {}, token: {}, subsidy_tx: {} USD, subsidy_token_total: {} USD",
-                tx_hash.to_string(),
-                &token.address,
-                paid_subsidy_dec,
-                total_paid_subsidy
-            );
-            self.subsidy_accumulator
-                .add_used_subsidy(&token.address, paid_subsidy);
+            // The transaction is already sent to the mempool, so the failure to record
+            // the subsidy must not be reported to the user.
+            match self
+                .subsidy_accumulator
+                .add_used_subsidy(&token.address, paid_subsidy)
+                .await
+            {
+                Ok(total_paid_subsidy) => vlog::info!(
+                    "Paid subsidy for tx, tx: {}, token: {}, subsidy_tx: {} USD, subsidy_token_total: {} USD",
+                    tx_hash.to_string(),
+                    &token.address,
+                    paid_subsidy_dec,
+                    ratio_to_big_decimal(&total_paid_subsidy, 6)
+                ),
+                Err(err) => vlog::error!(
+                    "Failed to record the paid subsidy for tx {}: {}",
+                    tx_hash.to_string(),
+                    err
+                ),
+            }
         }
         Ok(tx.hash())
     }
@@ -414,6 +820,62 @@ impl TxSender {
         &self,
         txs: Vec<TxWithSignature>,
         eth_signatures: Option<EthBatchSignatures>,
+    ) -> Result<Vec<TxHash>, SubmitError> {
+        let tx_hashes = txs
+            .iter()
+            .map(|tx| tx.tx.hash().to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let span = vlog::info_span!("submit_txs_batch", tx_hashes = %tx_hashes);
+        let result = self
+            .submit_txs_batch_inner(txs, eth_signatures, HashMap::new(), false)
+            .instrument(span.clone())
+            .await;
+
+        span.in_scope(|| match &result {
+            Ok(_) => vlog::debug!("Batch was sent to the mempool"),
+            Err(err) => vlog::debug!("Batch was rejected: {}", err),
+        });
+        result
+    }
+
+    /// Runs all the checks the transaction is subject to on submission, including the mempool
+    /// ones, without submitting it. Returns the error the submission would currently fail with.
+    pub async fn would_accept_tx(
+        &self,
+        tx: ZkSyncTx,
+        signature: Option<TxEthSignature>,
+        fast_processing: Option<bool>,
+    ) -> Result<(), SubmitError> {
+        let span = vlog::info_span!("would_accept_tx", tx_hash = %tx.hash().to_string());
+        self.submit_tx_inner(tx, signature, fast_processing, true)
+            .instrument(span)
+            .await
+            .map(drop)
+    }
+
+    /// Same as `would_accept_tx`, but for the batch of transactions.
+    pub async fn would_accept_txs_batch(
+        &self,
+        txs: Vec<TxWithSignature>,
+        eth_signatures: Option<EthBatchSignatures>,
+    ) -> Result<(), SubmitError> {
+        self.submit_txs_batch_inner(txs, eth_signatures, HashMap::new(), true)
+            .instrument(vlog::info_span!("would_accept_txs_batch"))
+            .await
+            .map(drop)
+    }
+
+    /// Verifies and sends the batch to the mempool. Transactions which hashes are listed in
+    /// `custom_messages` are authorized by the Ethereum signatures of the provided messages
+    /// instead of the regular ones (e.g. withdrawals from the fast withdrawal intents).
+    /// On the dry run the batch is only checked, see `submit_tx_inner`.
+    async fn submit_txs_batch_inner(
+        &self,
+        txs: Vec<TxWithSignature>,
+        eth_signatures: Option<EthBatchSignatures>,
+        custom_messages: HashMap<TxHash, Vec<u8>>,
+        dry_run: bool,
     ) -> Result<Vec<TxHash>, SubmitError> {
         // Bring the received signatures into a vector for simplified work.
         let eth_signatures = EthBatchSignatures::api_arg_to_vec(eth_signatures);
@@ -435,6 +897,10 @@ impl TxSender {
         if txs.iter().any(|tx| tx.tx.is_close()) {
             return Err(SubmitError::AccountCloseDisabled);
         }
+        for tx in &txs {
+            check_packable_amounts(&tx.tx)?;
+            check_guardians_domain(&tx.tx, &self.network_domain)?;
+        }
 
         // Checking fees data
         let mut provided_total_usd_fee = BigDecimal::from(0);
@@ -443,14 +909,21 @@ impl TxSender {
         let eth_token = TokenLike::Id(TokenId(0));
 
         let mut token_fees = HashMap::<Address, BigUint>::new();
+        // Transaction paying the largest fee, the fee paid over the required one is refunded to it.
+        let mut fee_payer: Option<(TxHash, Address, TokenId, BigUint)> = None;
 
         for tx in &txs {
             let tx_fee_info = tx.tx.get_fee_info();
 
             if let Some((tx_type, token, address, provided_fee)) = tx_fee_info {
                 // Save the transaction type before moving on to the next one, otherwise
-                // the total fee won't get affected by it.
-                transaction_types.push((tx_type, address));
+                // the total fee won't get affected by it. The transactions of the same sender
+                // in the same token are charged for their marginal cost.
+                transaction_types.push(BatchFeeTx::new(
+                    tx_type,
+                    address,
+                    Some((tx.tx.account(), tx.tx.token_id())),
+                ));
 
                 if provided_fee == BigUint::zero() {
                     continue;
@@ -464,6 +937,18 @@ impl TxSender {
                 if !fee_allowed && provided_fee != 0u64.into() {
                     return Err(SubmitError::InappropriateFeeToken);
                 }
+                // The initiator of `ForcedExit` is not known by the address, so its fee is not refunded.
+                let larger_fee = fee_payer
+                    .as_ref()
+                    .map_or(true, |(.., payer_fee)| provided_fee > *payer_fee);
+                if larger_fee && !matches!(tx.tx, ZkSyncTx::ForcedExit(_)) {
+                    fee_payer = Some((
+                        tx.tx.hash(),
+                        tx.tx.account(),
+                        tx.tx.token_id(),
+                        provided_fee.clone(),
+                    ));
+                }
 
                 let check_token = if fee_allowed {
                     // For allowed tokens, we perform check in the transaction token (as expected).
@@ -493,6 +978,7 @@ impl TxSender {
         }
 
         let mut subsidy_paid = None;
+        let mut fee_refund = None;
         // Only one token in batch
         if token_fees.len() == 1 {
             let (batch_token, fee_paid) = token_fees.into_iter().next().unwrap();
@@ -501,7 +987,8 @@ impl TxSender {
                 transaction_types.clone(),
                 batch_token.into(),
             )
-            .await?;
+            .await?
+            .with_markup(self.fee_markup_percent);
             let user_provided_fee =
                 scale_user_fee_up(BigDecimal::from(fee_paid.to_bigint().unwrap()));
             let required_normal_fee =
@@ -509,7 +996,7 @@ impl TxSender {
 
             // Not enough fee
             if required_normal_fee >= user_provided_fee {
-                let allowed_subsidy = self.subsidy_accumulator.get_allowed_subsidy(&batch_token);
+                let allowed_subsidy = self.allowed_subsidy(&batch_token).await?;
                 let max_subsidy = batch_token_fee.get_max_subsidy(&allowed_subsidy);
                 let required_subsidy = &required_normal_fee - &user_provided_fee;
                 // check if subsidy can be used
@@ -525,6 +1012,17 @@ impl TxSender {
                     return Err(SubmitError::TxAdd(TxAddError::TxBatchFeeTooLow));
                 }
             }
+
+            // Fees of the batch are paid in a single token, so the excess is refunded to the transaction
+            // paying the largest fee, at most the fee it paid.
+            let required_fee = &batch_token_fee.normal_fee.total_fee;
+            if self.fee_refunds_enabled && fee_paid > *required_fee {
+                if let Some((tx_hash, address, token, payer_fee)) = fee_payer {
+                    let excess = std::cmp::min(&fee_paid - required_fee, payer_fee.clone());
+                    let actual_fee = &payer_fee - excess;
+                    fee_refund = Some((tx_hash, address, token, payer_fee, actual_fee));
+                }
+            }
         } else {
             // Calculate required fee for ethereum token
             let required_eth_fee = Self::ticker_batch_fee_request(
@@ -533,6 +1031,7 @@ impl TxSender {
                 eth_token.clone(),
             )
             .await?
+            .with_markup(self.fee_markup_percent)
             .normal_fee;
 
             let eth_price_in_usd = Self::ticker_price_request(
@@ -572,7 +1071,13 @@ impl TxSender {
             let token = self.token_info_from_id(tx.token_id()).await?;
             tokens.push(token.clone());
 
-            messages_to_sign.push(tx.get_ethereum_sign_message(token).map(String::into_bytes));
+            let message_to_sign = match custom_messages.get(&tx.hash()) {
+                Some(message) => Some(message.clone()),
+                None => tx
+                    .get_ethereum_sign_message(token)
+                    .map(|message| self.eth_sign_message(message)),
+            };
+            messages_to_sign.push(message_to_sign);
             tx_senders.push(
                 self.get_tx_sender(tx)
                     .await
@@ -599,7 +1104,11 @@ impl TxSender {
                 .map(|((tx, token), sender)| (tx.tx.clone(), token, sender))
                 .collect::<Vec<_>>();
             // Create batch signature data.
-            Some(EthBatchSignData::new(_txs, eth_signatures).map_err(SubmitError::other)?)
+            Some(
+                EthBatchSignData::new(_txs, eth_signatures)
+                    .map_err(SubmitError::other)?
+                    .with_domain(self.signature_domain.as_ref()),
+            )
         } else {
             None
         };
@@ -610,6 +1119,7 @@ impl TxSender {
             tx_sender_types,
             batch_sign_data,
             messages_to_sign,
+            self.signature_domain.is_none(),
             self.sign_verify_requests.clone(),
         )
         .await?
@@ -618,37 +1128,463 @@ impl TxSender {
             verified_signatures.extend(sign_data.signatures.into_iter());
         }
         verified_txs.extend(verified_batch.into_iter());
+        self.check_rate_limit(verified_txs.iter().map(|tx| &tx.tx), dry_run)
+            .await?;
+
+        let tx_hashes: Vec<TxHash> = verified_txs.iter().map(|tx| tx.tx.hash()).collect();
+        if dry_run {
+            self.core_api_client
+                .check_txs_batch(verified_txs, verified_signatures)
+                .await
+                .map_err(SubmitError::communication_core_server)?
+                .map_err(SubmitError::TxAdd)?;
+            return Ok(tx_hashes);
+        }
 
         if let Some((subsidy_token, subsidy_paid)) = subsidy_paid {
             let paid_subsidy_dec = ratio_to_big_decimal(&subsidy_paid, 6);
-            let total_paid_subsidy = ratio_to_big_decimal(
-                &self
-                    .subsidy_accumulator
-                    .get_total_paid_subsidy(&subsidy_token),
-                6,
-            );
+            let total_paid_subsidy = self
+                .subsidy_accumulator
+                .add_used_subsidy(&subsidy_token, subsidy_paid)
+                .await
+                .map_err(SubmitError::internal)?;
 
             vlog::info!(
                 "Paid subsidy for batch: token: {}, , subsidy_tx: {} USD, subsidy_token_total: {} USD",
                 subsidy_token,
                 paid_subsidy_dec,
-                total_paid_subsidy
+                ratio_to_big_decimal(&total_paid_subsidy, 6)
             );
-            self.subsidy_accumulator
-                .add_used_subsidy(&subsidy_token, subsidy_paid);
         }
 
-        let tx_hashes: Vec<TxHash> = verified_txs.iter().map(|tx| tx.tx.hash()).collect();
         // Send verified transactions to the mempool.
         self.core_api_client
             .send_txs_batch(verified_txs, verified_signatures)
             .await
             .map_err(SubmitError::communication_core_server)?
             .map_err(SubmitError::TxAdd)?;
+        if let Some((tx_hash, address, token, signed_fee, actual_fee)) = fee_refund {
+            self.record_fee_refund(tx_hash, address, token, &signed_fee, &actual_fee)
+                .await;
+        }
 
         Ok(tx_hashes)
     }
 
+    /// Records the refund of the fee paid over the required one for the transaction sent to the mempool.
+    async fn record_fee_refund(
+        &self,
+        tx_hash: TxHash,
+        address: Address,
+        token: TokenId,
+        signed_fee: &BigUint,
+        actual_fee: &BigUint,
+    ) {
+        // Same as the subsidy, the failure to record the refund is not reported to the user.
+        let result = match self.pool.access_storage().await {
+            Ok(mut storage) => storage
+                .fee_refunds_schema()
+                .store_refund(&tx_hash, address, token, signed_fee, actual_fee)
+                .await
+                .map(drop),
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            vlog::error!(
+                "Failed to record the fee refund for tx {}: {}",
+                tx_hash.to_string(),
+                err
+            );
+        }
+    }
+
+    /// Accepts the intent to sell the withdrawal to the liquidity provider.
+    /// The intent is not sent to the mempool until it's fulfilled by the liquidity provider.
+    pub async fn submit_fast_withdrawal_intent(
+        &self,
+        mut intent: FastWithdrawalIntent,
+    ) -> Result<FastWithdrawalIntentId, SubmitError> {
+        intent
+            .check_correctness()
+            .map_err(|err| SubmitError::IncorrectTx(err.to_string()))?;
+        let withdraw = ZkSyncTx::from(intent.withdraw.clone());
+
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?;
+        // Intent is fulfilled at unknown moment, so the withdrawal must be valid at least for now.
+        let account = storage
+            .chain()
+            .account_schema()
+            .last_committed_state_for_account(intent.withdraw.account_id)
+            .await
+            .map_err(SubmitError::internal)?
+            .ok_or_else(|| SubmitError::IncorrectTx("Account does not exist".to_string()))?;
+        if account.nonce != intent.withdraw.nonce {
+            return Err(SubmitError::TxAdd(TxAddError::NonceMismatch));
+        }
+
+        let token = self.token_info_from_id(intent.withdraw.token).await?;
+        let message =
+            self.eth_sign_message(intent.get_ethereum_sign_message(&token.symbol, token.decimals));
+        let tx_sender = self
+            .get_tx_sender(&withdraw)
+            .await
+            .or(Err(SubmitError::TxAdd(TxAddError::DbError)))?;
+        verify_tx_info_message_signature(
+            &withdraw,
+            tx_sender,
+            token,
+            self.get_tx_sender_type(&withdraw).await?,
+            intent.eth_signature.clone(),
+            Some(message),
+            self.signature_domain.is_none(),
+            self.sign_verify_requests.clone(),
+        )
+        .await?;
+
+        let now = Utc::now();
+        let removed = storage
+            .fast_withdrawals_schema()
+            .remove_expired_intents(now.timestamp() as u64)
+            .await
+            .map_err(SubmitError::internal)?;
+        metrics::counter!("api.fast_withdrawals.expired_intents", removed);
+        storage
+            .fast_withdrawals_schema()
+            .store_intent(&intent, now)
+            .await
+            .map_err(SubmitError::internal)?
+            .ok_or_else(|| SubmitError::other("Withdrawal is already offered"))
+    }
+
+    /// Returns the oldest intents the liquidity providers can fulfill, optionally filtered
+    /// by the token. Intents which withdrawal nonce has been used by another transaction
+    /// are removed.
+    pub async fn pending_fast_withdrawal_offers(
+        &self,
+        token: Option<TokenId>,
+        limit: u32,
+    ) -> Result<Vec<FastWithdrawalOffer>, SubmitError> {
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?;
+        let intents = storage
+            .fast_withdrawals_schema()
+            .load_pending_intents(token, Utc::now().timestamp() as u64, limit)
+            .await
+            .map_err(SubmitError::internal)?;
+
+        let mut offers = Vec::with_capacity(intents.len());
+        for stored in intents {
+            if Self::remove_cancelled_intent(&mut storage, &stored).await? {
+                continue;
+            }
+            offers.push(stored.intent.offer(stored.id, stored.created_at));
+        }
+        Ok(offers)
+    }
+
+    /// Removes the intent if the nonce of its withdrawal has been used by another transaction,
+    /// so the withdrawal can't be executed anymore. Returns whether the intent has been removed.
+    async fn remove_cancelled_intent(
+        storage: &mut StorageProcessor<'_>,
+        stored: &StoredFastWithdrawalIntent,
+    ) -> Result<bool, SubmitError> {
+        let withdraw = &stored.intent.withdraw;
+        let nonce = storage
+            .chain()
+            .account_schema()
+            .last_committed_state_for_account(withdraw.account_id)
+            .await
+            .map_err(SubmitError::internal)?
+            .map(|account| account.nonce);
+        if nonce.map_or(true, |nonce| nonce <= withdraw.nonce) {
+            return Ok(false);
+        }
+
+        storage
+            .fast_withdrawals_schema()
+            .remove_intent(stored.id)
+            .await
+            .map_err(SubmitError::internal)?;
+        metrics::counter!("api.fast_withdrawals.cancelled_intents", 1);
+        Ok(true)
+    }
+
+    /// Fulfills the fast withdrawal intent with the payout transfer of the liquidity provider.
+    /// The transfer and the withdrawal are sent to the mempool as a batch, so either both
+    /// or none of them are executed.
+    pub async fn fulfill_fast_withdrawal_intent(
+        &self,
+        id: FastWithdrawalIntentId,
+        transfer: TxWithSignature,
+    ) -> Result<Vec<TxHash>, SubmitError> {
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?;
+        let stored = storage
+            .fast_withdrawals_schema()
+            .get_intent(id)
+            .await
+            .map_err(SubmitError::internal)?
+            .ok_or_else(|| SubmitError::invalid_params("Fast withdrawal intent not found"))?;
+        if stored.fulfilled_at.is_none() && stored.valid_until <= Utc::now().timestamp() as u64 {
+            storage
+                .fast_withdrawals_schema()
+                .remove_intent(id)
+                .await
+                .map_err(SubmitError::internal)?;
+            return Err(SubmitError::invalid_params(
+                "Fast withdrawal intent is expired",
+            ));
+        }
+        if Self::remove_cancelled_intent(&mut storage, &stored).await? {
+            return Err(SubmitError::invalid_params(
+                "Fast withdrawal intent is cancelled by the user",
+            ));
+        }
+        drop(storage);
+        let intent = stored.intent;
+
+        let payout = match &transfer.tx {
+            ZkSyncTx::Transfer(payout) => payout,
+            _ => {
+                return Err(SubmitError::IncorrectTx(
+                    "Fast withdrawal payout must be a transfer".to_string(),
+                ))
+            }
+        };
+        if payout.from != intent.liquidity_provider()
+            || payout.to != intent.withdraw.from
+            || payout.token != intent.withdraw.token
+            || payout.amount < intent.payout
+        {
+            return Err(SubmitError::IncorrectTx(
+                "Transfer does not match the fast withdrawal intent".to_string(),
+            ));
+        }
+
+        let claimed = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?
+            .fast_withdrawals_schema()
+            .claim_intent(id, transfer.tx.hash(), Utc::now())
+            .await
+            .map_err(SubmitError::internal)?;
+        if !claimed {
+            return Err(SubmitError::other(
+                "Fast withdrawal intent is already fulfilled",
+            ));
+        }
+
+        let token = self.token_info_from_id(intent.withdraw.token).await?;
+        let message =
+            self.eth_sign_message(intent.get_ethereum_sign_message(&token.symbol, token.decimals));
+        let mut withdraw = intent.withdraw;
+        // Liquidity provider is interested in getting the funds on L1 as soon as possible.
+        withdraw.fast = true;
+        let withdraw = TxWithSignature {
+            tx: ZkSyncTx::from(withdraw),
+            signature: intent.eth_signature,
+        };
+        let custom_messages = HashMap::from_iter(vec![(withdraw.tx.hash(), message)]);
+
+        let result = self
+            .submit_txs_batch_inner(vec![transfer, withdraw], None, custom_messages, false)
+            .await;
+        if result.is_err() {
+            // Let another liquidity provider fulfill the intent.
+            self.pool
+                .access_storage()
+                .await
+                .map_err(SubmitError::internal)?
+                .fast_withdrawals_schema()
+                .release_intent(id)
+                .await
+                .map_err(SubmitError::internal)?;
+        }
+        result
+    }
+
+    /// Accepts the transfer prepaying the activation of the recipient account.
+    /// The transfer is not sent to the mempool until the recipient submits its `ChangePubKey`.
+    pub async fn submit_prepaid_activation(
+        &self,
+        mut activation: PrepaidActivation,
+    ) -> Result<(), SubmitError> {
+        activation
+            .check_correctness()
+            .map_err(|err| SubmitError::IncorrectTx(err.to_string()))?;
+
+        let hint = self.activation_hint(activation.recipient()).await?;
+        if hint.activated {
+            return Err(SubmitError::IncorrectTx(
+                "Account is already activated".to_string(),
+            ));
+        }
+
+        let fee_payment = ZkSyncTx::from(activation.fee_payment.clone());
+        let fee_allowed = Self::token_allowed_for_fees(
+            self.ticker_requests.clone(),
+            fee_payment.token_id().into(),
+        )
+        .await?;
+        if !fee_allowed {
+            return Err(SubmitError::InappropriateFeeToken);
+        }
+
+        let token = self.token_info_from_id(fee_payment.token_id()).await?;
+        let message = fee_payment
+            .get_ethereum_sign_message(token.clone())
+            .map(|message| self.eth_sign_message(message));
+        verify_tx_info_message_signature(
+            &fee_payment,
+            activation.payer(),
+            token,
+            self.get_tx_sender_type(&fee_payment).await?,
+            activation.eth_signature.clone(),
+            message,
+            self.signature_domain.is_none(),
+            self.sign_verify_requests.clone(),
+        )
+        .await?;
+
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?;
+        let removed = storage
+            .activations_schema()
+            .remove_expired_prepaid_activations()
+            .await
+            .map_err(SubmitError::internal)?;
+        metrics::counter!("api.activations.expired", removed);
+        // The previous activation may be reclaimed by its payer, so it can be replaced.
+        Self::load_prepaid_activation(&mut storage, activation.recipient()).await?;
+        let stored = storage
+            .activations_schema()
+            .store_prepaid_activation(&activation, Utc::now())
+            .await
+            .map_err(SubmitError::internal)?;
+        if !stored {
+            return Err(SubmitError::other("Account activation is already prepaid"));
+        }
+        Ok(())
+    }
+
+    /// Returns the activation status of the account.
+    pub async fn activation_hint(&self, address: Address) -> Result<ActivationHint, SubmitError> {
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?;
+        let committed = storage
+            .chain()
+            .account_schema()
+            .account_state_by_address(address)
+            .await
+            .map_err(SubmitError::internal)?
+            .committed;
+        let prepaid = Self::load_prepaid_activation(&mut storage, address).await?;
+
+        Ok(ActivationHint {
+            address,
+            account_id: committed.as_ref().map(|(id, _)| *id),
+            nonce: committed.as_ref().map(|(_, account)| account.nonce),
+            activated: committed
+                .map(|(_, account)| account.pub_key_hash != PubKeyHash::default())
+                .unwrap_or(false),
+            prepaid_by: prepaid.as_ref().map(|(activation, _)| activation.payer()),
+            prepaid_at: prepaid.as_ref().map(|(_, created_at)| *created_at),
+            prepaid_until: prepaid
+                .map(|(activation, created_at)| activation.expires_at(created_at)),
+        })
+    }
+
+    /// Loads the unexpired prepaid activation of the account. The activation is dropped if its
+    /// payer has reclaimed it, i.e. the nonce of the held transfer has been used by another transaction.
+    async fn load_prepaid_activation(
+        storage: &mut StorageProcessor<'_>,
+        recipient: Address,
+    ) -> Result<Option<(PrepaidActivation, DateTime<Utc>)>, SubmitError> {
+        let prepaid = storage
+            .activations_schema()
+            .get_prepaid_activation(recipient)
+            .await
+            .map_err(SubmitError::internal)?;
+        let (activation, created_at) = match prepaid {
+            Some(prepaid) => prepaid,
+            None => return Ok(None),
+        };
+
+        let payer_nonce = storage
+            .chain()
+            .account_schema()
+            .account_state_by_address(activation.payer())
+            .await
+            .map_err(SubmitError::internal)?
+            .committed
+            .map(|(_, account)| account.nonce);
+        if payer_nonce.map_or(false, |nonce| nonce > activation.fee_payment.nonce) {
+            storage
+                .activations_schema()
+                .remove_prepaid_activation(recipient)
+                .await
+                .map_err(SubmitError::internal)?;
+            metrics::counter!("api.activations.reclaimed", 1);
+            return Ok(None);
+        }
+        Ok(Some((activation, created_at)))
+    }
+
+    /// Sends the `ChangePubKey` along with the transfer prepaying its fee as a batch.
+    async fn submit_prepaid_activation_batch(
+        &self,
+        change_pub_key: ZkSyncTx,
+        signature: Option<TxEthSignature>,
+        activation: PrepaidActivation,
+        dry_run: bool,
+    ) -> Result<TxHash, SubmitError> {
+        let recipient = activation.recipient();
+        let tx_hash = change_pub_key.hash();
+        let txs = vec![
+            TxWithSignature {
+                tx: ZkSyncTx::from(activation.fee_payment),
+                signature: activation.eth_signature,
+            },
+            TxWithSignature {
+                tx: change_pub_key,
+                signature,
+            },
+        ];
+        self.submit_txs_batch_inner(txs, None, HashMap::new(), dry_run)
+            .await?;
+        if dry_run {
+            return Ok(tx_hash);
+        }
+
+        self.pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?
+            .activations_schema()
+            .remove_prepaid_activation(recipient)
+            .await
+            .map_err(SubmitError::internal)?;
+        Ok(tx_hash)
+    }
+
     pub async fn get_txs_fee_in_wei(
         &self,
         tx_type: TxFeeTypes,
@@ -661,7 +1597,8 @@ impl TxSender {
             address,
             token.clone(),
         )
-        .await?;
+        .await?
+        .with_markup(self.fee_markup_percent);
 
         if resp_fee.subsidy_fee.total_fee == resp_fee.normal_fee.total_fee {
             return Ok(resp_fee.normal_fee);
@@ -669,7 +1606,7 @@ impl TxSender {
 
         let token = self.token_info_from_id(token).await?;
 
-        let allowed_subsidy = self.subsidy_accumulator.get_allowed_subsidy(&token.address);
+        let allowed_subsidy = self.allowed_subsidy(&token.address).await?;
         if allowed_subsidy >= resp_fee.subsidy_size_usd {
             Ok(resp_fee.subsidy_fee)
         } else {
@@ -679,7 +1616,7 @@ impl TxSender {
 
     pub async fn get_txs_batch_fee_in_wei(
         &self,
-        transactions: Vec<(TxFeeTypes, Address)>,
+        transactions: Vec<BatchFeeTx>,
         token: TokenLike,
     ) -> Result<BatchFee, SubmitError> {
         let resp_fee = Self::ticker_batch_fee_request(
@@ -687,7 +1624,8 @@ impl TxSender {
             transactions,
             token.clone(),
         )
-        .await?;
+        .await?
+        .with_markup(self.fee_markup_percent);
 
         if resp_fee.normal_fee.total_fee == resp_fee.subsidy_fee.total_fee {
             return Ok(resp_fee.normal_fee);
@@ -695,7 +1633,7 @@ impl TxSender {
 
         let token = self.token_info_from_id(token).await?;
 
-        let allowed_subsidy = self.subsidy_accumulator.get_allowed_subsidy(&token.address);
+        let allowed_subsidy = self.allowed_subsidy(&token.address).await?;
         if allowed_subsidy >= resp_fee.subsidy_size_usd {
             Ok(resp_fee.subsidy_fee)
         } else {
@@ -732,17 +1670,15 @@ impl TxSender {
             ZkSyncTx::Transfer(tx) => {
                 let token = self.token_info_from_id(tx.token).await?;
 
-                let msg = tx
-                    .get_ethereum_sign_message(&token.symbol, token.decimals)
-                    .into_bytes();
+                let msg = self
+                    .eth_sign_message(tx.get_ethereum_sign_message(&token.symbol, token.decimals));
                 Some(msg)
             }
             ZkSyncTx::Withdraw(tx) => {
                 let token = self.token_info_from_id(tx.token).await?;
 
-                let msg = tx
-                    .get_ethereum_sign_message(&token.symbol, token.decimals)
-                    .into_bytes();
+                let msg = self
+                    .eth_sign_message(tx.get_ethereum_sign_message(&token.symbol, token.decimals));
                 Some(msg)
             }
             _ => None,
@@ -770,7 +1706,7 @@ impl TxSender {
 
     async fn ticker_batch_fee_request(
         mut ticker_request_sender: mpsc::Sender<TickerRequest>,
-        transactions: Vec<(TxFeeTypes, Address)>,
+        transactions: Vec<BatchFeeTx>,
         token: TokenLike,
     ) -> Result<ResponseBatchFee, SubmitError> {
         let req = oneshot::channel();
@@ -783,7 +1719,7 @@ impl TxSender {
             .await
             .map_err(SubmitError::internal)?;
         let resp = req.1.await.map_err(SubmitError::internal)?;
-        resp.map_err(|err| internal_error!(err))
+        resp.map_err(ticker_error)
     }
 
     async fn ticker_request(
@@ -804,7 +1740,7 @@ impl TxSender {
             .map_err(SubmitError::internal)?;
 
         let resp = req.1.await.map_err(SubmitError::internal)?;
-        resp.map_err(|err| internal_error!(err))
+        resp.map_err(ticker_error)
     }
 
     pub async fn token_allowed_for_fees(
@@ -840,7 +1776,10 @@ impl TxSender {
             .await
             .map_err(SubmitError::internal)?;
         let resp = req.1.await.map_err(SubmitError::internal)?;
-        resp.map_err(|err| internal_error!(err))
+        resp.map_err(|err| match err {
+            PriceError::UnreliablePrice(_) => SubmitError::FeeQuotingSuspended,
+            err => internal_error!(err),
+        })
     }
 }
 
@@ -863,6 +1802,8 @@ async fn send_verify_request_and_recv(
 
 /// Send a request for Ethereum signature verification and wait for the response.
 /// If `msg_to_sign` is not `None`, then the signature must be present.
+/// Old-format messages are accepted only if `allow_old_messages` is set.
+#[allow(clippy::too_many_arguments)]
 async fn verify_tx_info_message_signature(
     tx: &ZkSyncTx,
     tx_sender: Address,
@@ -870,6 +1811,7 @@ async fn verify_tx_info_message_signature(
     account_type: EthAccountType,
     signature: Option<TxEthSignature>,
     msg_to_sign: Option<Vec<u8>>,
+    allow_old_messages: bool,
     req_channel: mpsc::Sender<VerifySignatureRequest>,
 ) -> Result<VerifiedTx, SubmitError> {
     let eth_sign_data = match msg_to_sign {
@@ -903,6 +1845,7 @@ async fn verify_tx_info_message_signature(
             },
             sender: tx_sender,
             token,
+            allow_old_messages,
         }),
         response: sender,
     };
@@ -914,6 +1857,7 @@ async fn verify_tx_info_message_signature(
 /// Unlike in case of `verify_tx_info_message_signature`, we do not require
 /// every transaction from the batch to be signed. The signature must be obtained
 /// through signing a human-readable message with accordance to zkSync protocol.
+#[allow(clippy::too_many_arguments)]
 async fn verify_txs_batch_signature(
     batch: Vec<TxWithSignature>,
     senders: Vec<Address>,
@@ -921,6 +1865,7 @@ async fn verify_txs_batch_signature(
     sender_types: Vec<EthAccountType>,
     batch_sign_data: Option<EthBatchSignData>,
     msgs_to_sign: Vec<Option<Vec<u8>>>,
+    allow_old_messages: bool,
     req_channel: mpsc::Sender<VerifySignatureRequest>,
 ) -> Result<VerifiedTx, SubmitError> {
     // This hashset holds addresses that have performed a CREATE2 ChangePubKey
@@ -982,6 +1927,7 @@ async fn verify_txs_batch_signature(
             batch_sign_data,
             senders,
             tokens,
+            allow_old_messages,
         }),
         response: sender,
     };
@@ -989,6 +1935,49 @@ async fn verify_txs_batch_signature(
     send_verify_request_and_recv(request, req_channel, receiver).await
 }
 
+/// Checks that the amounts of the transaction can be packed, otherwise reports the closest
+/// packable values, so the user doesn't get an opaque signature error.
+fn check_packable_amounts(tx: &ZkSyncTx) -> Result<(), SubmitError> {
+    let check = |field: &str, amounts: Option<PackableAmounts>| match amounts {
+        Some(amounts) if amounts.lossless => Ok(()),
+        Some(amounts) => Err(SubmitError::IncorrectTx(format!(
+            "{} is not packable, closest packable values are {} and {}",
+            field, amounts.floor, amounts.ceil
+        ))),
+        None => Err(SubmitError::IncorrectTx(format!(
+            "{} is too large to be packed",
+            field
+        ))),
+    };
+
+    if let ZkSyncTx::Transfer(transfer) = tx {
+        check(
+            "Amount",
+            PackableAmounts::try_token_amount(&transfer.amount),
+        )?;
+    }
+    if let Some((_, _, _, fee)) = tx.get_fee_info() {
+        check("Fee", PackableAmounts::try_fee_amount(&fee))?;
+    }
+    Ok(())
+}
+
+/// Rejects the `ChangePubKey` authorized by the guardians who signed it for another network:
+/// such transaction would be accepted by the signature check, but rejected by the contract.
+fn check_guardians_domain(tx: &ZkSyncTx, domain: &SignatureDomain) -> Result<(), SubmitError> {
+    if let ZkSyncTx::ChangePubKey(change_pubkey) = tx {
+        if let Some(ChangePubKeyEthAuthData::Guardians(data)) = &change_pubkey.eth_auth_data {
+            if data.domain != *domain {
+                return Err(SubmitError::IncorrectTx(format!(
+                    "Guardian signatures must be made for chain ID {} and contract {:?}",
+                    domain.chain_id, domain.contract_address
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Scales the fee provided by user up to check whether the provided fee is enough to cover our expenses for
 /// maintaining the protocol.
 ///
@@ -0,0 +1,334 @@
+//! Simulated execution of the transactions (preflight).
+//!
+//! Transactions are executed against the last committed state of the accounts they touch,
+//! and nothing is persisted. This lets the wallets check the outcome of complex batches
+//! before submitting them.
+//!
+//! The simulation checks the zkSync signatures, nonces, balances and time ranges of
+//! the transactions. It does not check the Ethereum signatures or whether the fees are high enough.
+//! Transactions pending in the mempool are not taken into account.
+
+// Built-in uses
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    sync::Arc,
+    time::Instant,
+};
+
+// External uses
+use chrono::Utc;
+
+// Workspace uses
+use zksync_api_client::rest::v1::{SimulatedAccountState, SimulatedTx, TxSimulationResult};
+use zksync_crypto::{
+    priv_key_from_fs,
+    rand::{thread_rng, Rng},
+    PrivateKey,
+};
+use zksync_state::state::ZkSyncState;
+use zksync_storage::ConnectionPool;
+use zksync_types::{
+    tx::TxSignature, AccountId, AccountMap, AccountUpdate, Address, PubKeyHash, TokenId, ZkSyncTx,
+};
+
+// Local uses
+use crate::api_server::tx_sender::SubmitError;
+
+/// Executes the transactions without persisting the results.
+#[derive(Clone)]
+pub struct TxSimulator {
+    pool: ConnectionPool,
+    max_txs: usize,
+    /// Key signing the unsigned transactions. It replaces the signing keys of their senders
+    /// in the simulated state.
+    signing_key: Arc<PrivateKey>,
+}
+
+impl TxSimulator {
+    pub fn new(pool: ConnectionPool, max_txs: usize) -> Self {
+        Self {
+            pool,
+            max_txs,
+            signing_key: Arc::new(priv_key_from_fs(thread_rng().gen())),
+        }
+    }
+
+    /// Executes the transactions one after another, just like the batch.
+    /// Unsigned transactions are executed as if they were signed by the current signing keys
+    /// of their senders.
+    pub async fn simulate(
+        &self,
+        txs: Vec<ZkSyncTx>,
+        unsigned: bool,
+    ) -> Result<TxSimulationResult, SubmitError> {
+        if txs.is_empty() {
+            return Err(SubmitError::invalid_params("No transactions to simulate"));
+        }
+        if txs.len() > self.max_txs {
+            return Err(SubmitError::invalid_params(format!(
+                "Too many transactions to simulate, max {}",
+                self.max_txs
+            )));
+        }
+
+        let start = Instant::now();
+        let mut state = self.load_state(&txs).await.map_err(SubmitError::internal)?;
+        let signing_key = Some(self.signing_key.as_ref()).filter(|_| unsigned);
+        let result = execute_txs(&mut state, txs, signing_key);
+
+        metrics::histogram!("api.tx_simulator.simulate", start.elapsed());
+        Ok(result)
+    }
+
+    /// Loads the last committed state of the accounts touched by the transactions.
+    async fn load_state(&self, txs: &[ZkSyncTx]) -> anyhow::Result<ZkSyncState> {
+        let addresses: HashSet<Address> = txs.iter().flat_map(touched_addresses).collect();
+
+        let mut storage = self.pool.access_storage().await?;
+        let block_number = storage
+            .chain()
+            .block_schema()
+            .get_last_committed_block()
+            .await?;
+
+        let mut accounts = AccountMap::default();
+        for address in addresses {
+            let mut account_schema = storage.chain().account_schema();
+            let account_id = match account_schema.account_id_by_address(address).await? {
+                Some(account_id) => account_id,
+                None => continue,
+            };
+            if let Some(account) = account_schema
+                .last_committed_state_for_account(account_id)
+                .await?
+            {
+                accounts.insert(account_id, account);
+            }
+        }
+
+        Ok(ZkSyncState::from_acc_map(accounts, block_number))
+    }
+}
+
+/// Executes the transactions against the state. If the signing key is provided,
+/// the transactions are signed with it.
+fn execute_txs(
+    state: &mut ZkSyncState,
+    txs: Vec<ZkSyncTx>,
+    signing_key: Option<&PrivateKey>,
+) -> TxSimulationResult {
+    let now = Utc::now().timestamp() as u64;
+    let existing_accounts: HashSet<_> = state.get_account_addresses().values().copied().collect();
+
+    let mut simulated_txs = Vec::new();
+    let mut updated_accounts = BTreeMap::<AccountId, BTreeSet<TokenId>>::new();
+    for (index, mut tx) in txs.into_iter().enumerate() {
+        if let Some(signing_key) = signing_key {
+            if let Err(reason) = sign_tx(state, &mut tx, signing_key) {
+                return failed_simulation(index, reason);
+            }
+        }
+        if !tx.check_correctness() {
+            return failed_simulation(index, "Transaction is incorrect".to_owned());
+        }
+        if tx.valid_from() > now || tx.valid_until() < now {
+            return failed_simulation(
+                index,
+                "Transaction can't be executed now because of its time range".to_owned(),
+            );
+        }
+
+        let tx_hash = tx.hash();
+        let op_success = match state.execute_tx(tx) {
+            Ok(op_success) => op_success,
+            Err(err) => return failed_simulation(index, err.to_string()),
+        };
+
+        for (account_id, update) in &op_success.updates {
+            let tokens = updated_accounts.entry(*account_id).or_default();
+            if let AccountUpdate::UpdateBalance { balance_update, .. } = update {
+                tokens.insert(balance_update.0);
+            }
+        }
+        let (fee_token, fee) = op_success
+            .fee
+            .map(|fee| (fee.token, fee.amount))
+            .unwrap_or_default();
+        simulated_txs.push(SimulatedTx {
+            tx_hash,
+            fee_token,
+            fee: fee.into(),
+        });
+    }
+
+    let accounts = updated_accounts
+        .into_iter()
+        .filter_map(|(account_id, tokens)| {
+            let account = state.get_account(account_id)?;
+            Some(SimulatedAccountState {
+                address: account.address,
+                account_id: Some(account_id).filter(|id| existing_accounts.contains(id)),
+                nonce: account.nonce,
+                balances: tokens
+                    .into_iter()
+                    .map(|token| (token, account.get_balance(token).into()))
+                    .collect(),
+            })
+        })
+        .collect();
+
+    TxSimulationResult {
+        success: true,
+        failed_tx_index: None,
+        fail_reason: None,
+        txs: simulated_txs,
+        accounts,
+    }
+}
+
+/// Signs the transaction with the simulation key, replacing the signing key of its sender.
+fn sign_tx(state: &mut ZkSyncState, tx: &mut ZkSyncTx, key: &PrivateKey) -> Result<(), String> {
+    match tx {
+        ZkSyncTx::Transfer(tx) => tx.signature = TxSignature::sign_musig(key, &tx.get_bytes()),
+        ZkSyncTx::Withdraw(tx) => tx.signature = TxSignature::sign_musig(key, &tx.get_bytes()),
+        ZkSyncTx::ForcedExit(tx) => tx.signature = TxSignature::sign_musig(key, &tx.get_bytes()),
+        ZkSyncTx::Close(tx) => tx.signature = TxSignature::sign_musig(key, &tx.get_bytes()),
+        // The signature of `ChangePubKey` must correspond to the new key, so it can't be replaced.
+        ZkSyncTx::ChangePubKey(_) => {
+            return Err("ChangePubKey transaction must be signed to be simulated".to_owned())
+        }
+    }
+
+    // Accounts without the signing key can't send the transactions even if they're signed.
+    if let Some((account_id, mut account)) = state.get_account_by_address(&tx.account()) {
+        if account.pub_key_hash != PubKeyHash::default() {
+            account.pub_key_hash = PubKeyHash::from_privkey(key);
+            state.insert_account(account_id, account);
+        }
+    }
+    Ok(())
+}
+
+/// Returns the addresses of the accounts the transaction may change.
+fn touched_addresses(tx: &ZkSyncTx) -> Vec<Address> {
+    let mut addresses = vec![tx.account()];
+    match tx {
+        ZkSyncTx::Transfer(tx) => addresses.push(tx.to),
+        ZkSyncTx::ForcedExit(tx) => addresses.push(tx.target),
+        ZkSyncTx::Withdraw(_) | ZkSyncTx::Close(_) | ZkSyncTx::ChangePubKey(_) => {}
+    }
+    addresses
+}
+
+fn failed_simulation(index: usize, reason: String) -> TxSimulationResult {
+    TxSimulationResult {
+        success: false,
+        failed_tx_index: Some(index),
+        fail_reason: Some(reason),
+        txs: Vec::new(),
+        accounts: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::BigUint;
+    use zksync_test_account::ZkSyncAccount;
+    use zksync_types::{
+        tx::{TimeRange, Transfer},
+        Account, Nonce,
+    };
+
+    fn create_state(sender: &ZkSyncAccount) -> ZkSyncState {
+        let mut account = Account::default_with_address(&sender.address);
+        account.pub_key_hash = sender.pubkey_hash;
+        account.add_balance(TokenId(0), &BigUint::from(1_000u32));
+
+        let mut accounts = AccountMap::default();
+        accounts.insert(AccountId(1), account);
+        ZkSyncState::from_acc_map(accounts, Default::default())
+    }
+
+    fn unsigned_transfer(sender: &ZkSyncAccount, to: Address) -> ZkSyncTx {
+        let transfer = Transfer::new(
+            AccountId(1),
+            sender.address,
+            to,
+            TokenId(0),
+            100u32.into(),
+            10u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        );
+        ZkSyncTx::Transfer(Box::new(transfer))
+    }
+
+    /// Checks that the fees and the resulting balances of the executed transactions are returned.
+    #[test]
+    fn simulate_signed_transfer() {
+        let sender = ZkSyncAccount::rand();
+        sender.set_account_id(Some(AccountId(1)));
+        let recipient = Address::repeat_byte(7);
+        let mut state = create_state(&sender);
+
+        let (transfer, _) = sender.sign_transfer(
+            TokenId(0),
+            "ETH",
+            100u32.into(),
+            10u32.into(),
+            &recipient,
+            None,
+            true,
+            TimeRange::default(),
+        );
+        let tx = ZkSyncTx::Transfer(Box::new(transfer));
+        let result = execute_txs(&mut state, vec![tx.clone()], None);
+
+        assert!(result.success, "{:?}", result.fail_reason);
+        assert_eq!(result.txs.len(), 1);
+        assert_eq!(result.txs[0].tx_hash, tx.hash());
+        assert_eq!(result.txs[0].fee.0, BigUint::from(10u32));
+
+        assert_eq!(result.accounts.len(), 2);
+        let sender_state = &result.accounts[0];
+        assert_eq!(sender_state.account_id, Some(AccountId(1)));
+        assert_eq!(sender_state.nonce, Nonce(1));
+        assert_eq!(sender_state.balances[&TokenId(0)].0, BigUint::from(890u32));
+        let recipient_state = &result.accounts[1];
+        assert_eq!(recipient_state.address, recipient);
+        assert_eq!(recipient_state.account_id, None);
+        assert_eq!(
+            recipient_state.balances[&TokenId(0)].0,
+            BigUint::from(100u32)
+        );
+    }
+
+    /// Checks that the unsigned transactions are only executed in the unsigned mode.
+    #[test]
+    fn simulate_unsigned_transfer() {
+        let sender = ZkSyncAccount::rand();
+        let recipient = Address::repeat_byte(7);
+        let signing_key = priv_key_from_fs(thread_rng().gen());
+
+        let mut state = create_state(&sender);
+        let tx = unsigned_transfer(&sender, recipient);
+        let result = execute_txs(&mut state, vec![tx.clone()], None);
+        assert!(!result.success);
+        assert_eq!(result.failed_tx_index, Some(0));
+
+        let mut state = create_state(&sender);
+        let txs = vec![tx, unsigned_transfer(&sender, recipient)];
+        let result = execute_txs(&mut state, txs, Some(&signing_key));
+        // The second transfer has the same nonce as the first one.
+        assert!(!result.success);
+        assert_eq!(result.failed_tx_index, Some(1));
+
+        let mut state = create_state(&sender);
+        let tx = unsigned_transfer(&sender, recipient);
+        let result = execute_txs(&mut state, vec![tx], Some(&signing_key));
+        assert!(result.success, "{:?}", result.fail_reason);
+        assert_eq!(result.accounts[0].nonce, Nonce(1));
+    }
+}
@@ -1,5 +1,11 @@
 pub use zksync_types::EthBlockId;
-use zksync_types::{tx::TxEthSignature, Address, PriorityOp, SignedZkSyncTx, H256};
+use zksync_types::{
+    protocol_version::{
+        check_protocol_version_header, COMPONENTS_PROTOCOL_VERSION, PROTOCOL_VERSION_HEADER,
+    },
+    tx::TxEthSignature,
+    Address, PriorityOp, SignedZkSyncTx, H256,
+};
 
 use crate::tx_error::TxAddError;
 
@@ -36,6 +42,24 @@ impl CoreApiClient {
         self.post(&endpoint, data).await
     }
 
+    /// Runs the Core mempool checks of the transaction without adding it to the mempool.
+    pub async fn check_tx(&self, tx: SignedZkSyncTx) -> anyhow::Result<Result<(), TxAddError>> {
+        let endpoint = format!("{}/check_tx", self.addr);
+        self.post(&endpoint, tx).await
+    }
+
+    /// Runs the Core mempool checks of the transactions batch without adding it to the mempool.
+    pub async fn check_txs_batch(
+        &self,
+        txs: Vec<SignedZkSyncTx>,
+        eth_signatures: Vec<TxEthSignature>,
+    ) -> anyhow::Result<Result<(), TxAddError>> {
+        let endpoint = format!("{}/check_txs_batch", self.addr);
+        let data = (txs, eth_signatures);
+
+        self.post(&endpoint, data).await
+    }
+
     /// Queries information about unconfirmed deposit operations for a certain address from a Core.
     pub async fn get_unconfirmed_deposits(
         &self,
@@ -68,10 +92,22 @@ impl CoreApiClient {
         self.get(&endpoint).await
     }
 
+    /// Queries the priority operations pending inclusion, starting from the given serial ID, from a Core.
+    pub async fn get_priority_queue(
+        &self,
+        first_serial_id: u64,
+    ) -> anyhow::Result<Vec<PriorityOp>> {
+        let endpoint = format!("{}/priority_queue/{}", self.addr, first_serial_id);
+        self.get(&endpoint).await
+    }
+
     async fn get<T: serde::de::DeserializeOwned>(&self, url: &str) -> anyhow::Result<T> {
-        let response = self.client.get(url).send().await?.json().await?;
+        let request_builder = self
+            .client
+            .get(url)
+            .header(PROTOCOL_VERSION_HEADER, COMPONENTS_PROTOCOL_VERSION);
 
-        Ok(response)
+        Self::send(request_builder).await
     }
 
     async fn post<T: serde::de::DeserializeOwned>(
@@ -79,15 +115,40 @@ impl CoreApiClient {
         url: &str,
         request: impl serde::Serialize,
     ) -> anyhow::Result<T> {
-        let response = self
+        let mut request_builder = self
             .client
             .post(url)
-            .json(&request)
-            .send()
-            .await?
-            .json()
-            .await?;
+            .header(PROTOCOL_VERSION_HEADER, COMPONENTS_PROTOCOL_VERSION)
+            .json(&request);
+        // Link the spans of the Core with the span of the API request.
+        for (name, value) in vlog::trace_context_headers(&vlog::Span::current()) {
+            request_builder = request_builder.header(name.as_str(), value);
+        }
+
+        Self::send(request_builder).await
+    }
+
+    async fn send<T: serde::de::DeserializeOwned>(
+        request_builder: reqwest::RequestBuilder,
+    ) -> anyhow::Result<T> {
+        let response = request_builder.send().await?;
+        // The Core rejects the requests of the API servers of the incompatible versions.
+        if response.status() == reqwest::StatusCode::CONFLICT {
+            anyhow::bail!(
+                "Core doesn't support protocol version {}: {}",
+                COMPONENTS_PROTOCOL_VERSION,
+                response.text().await?
+            );
+        }
+        // And the API server rejects the Core of the incompatible version.
+        let core_version = response
+            .headers()
+            .get(PROTOCOL_VERSION_HEADER)
+            .map(|value| value.to_str().unwrap_or_default());
+        check_protocol_version_header(core_version).map_err(|err| {
+            anyhow::format_err!("Core protocol version is not supported: {}", err)
+        })?;
 
-        Ok(response)
+        Ok(response.json().await?)
     }
 }
@@ -2,14 +2,20 @@
 //! onchain `ChangePubKey` authorization or EIP1271 signature
 //! verification.
 
-use web3::{contract::Options, types::Address};
-use zksync_contracts::eip1271_contract;
+use std::collections::HashSet;
+use web3::{
+    contract::{tokens::Tokenize, Error as ContractError, Options},
+    types::{Address, BlockId, BlockNumber, U256},
+};
+use zksync_contracts::{eip1271_contract, token_restrictions_contract};
 use zksync_eth_client::ethereum_gateway::EthereumGateway;
 use zksync_types::{
-    tx::EIP1271Signature,
+    tx::{ChangePubKeyOnchainAuthStatus, EIP1271Signature},
     {Nonce, PubKeyHash},
 };
 
+use crate::tx_error::TxAddError;
+
 /// isValidSignature return value according to EIP1271 standard
 /// bytes4(keccak256("isValidSignature(bytes32,bytes)")
 pub const EIP1271_SUCCESS_RETURN_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
@@ -17,11 +23,24 @@ pub const EIP1271_SUCCESS_RETURN_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
 #[derive(Clone)]
 pub struct EthereumChecker {
     client: EthereumGateway,
+    /// Tokens which contracts are checked for the restrictions before accepting withdrawals.
+    withdrawal_checked_tokens: HashSet<Address>,
+    /// ABI of the token restriction functions, loaded only if there are tokens to check.
+    token_restrictions_abi: Option<ethabi::Contract>,
 }
 
 impl EthereumChecker {
-    pub fn new(client: EthereumGateway) -> Self {
-        Self { client }
+    pub fn new(client: EthereumGateway, withdrawal_checked_tokens: HashSet<Address>) -> Self {
+        let token_restrictions_abi = if withdrawal_checked_tokens.is_empty() {
+            None
+        } else {
+            Some(token_restrictions_contract())
+        };
+        Self {
+            client,
+            withdrawal_checked_tokens,
+            token_restrictions_abi,
+        }
     }
 
     /// Transforms the message into an array expected by EIP-1271 standard.
@@ -74,27 +93,157 @@ impl EthereumChecker {
         nonce: Nonce,
         pub_key_hash: &PubKeyHash,
     ) -> Result<bool, anyhow::Error> {
-        let auth_fact: Vec<u8> = self
+        let auth_fact = self.auth_fact(address, nonce, None).await?;
+        Ok(auth_fact.as_slice() == tiny_keccak::keccak256(&pub_key_hash.data[..]))
+    }
+
+    /// Returns the onchain `ChangePubKey` authorization status at the latest Ethereum block.
+    /// Authorizations with a requested reset are not valid, so their auth facts are not queried.
+    pub async fn pubkey_change_auth_status(
+        &self,
+        address: Address,
+        nonce: Nonce,
+        pub_key_hash: PubKeyHash,
+    ) -> Result<ChangePubKeyOnchainAuthStatus, anyhow::Error> {
+        let eth_block = self.client.block_number().await?;
+        let block = BlockId::Number(BlockNumber::Number(eth_block));
+
+        let reset_time: U256 = self
             .client
             .call_main_contract_function(
-                "authFacts",
+                "authFactsResetTimer",
                 (address, u64::from(*nonce)),
                 None,
                 Options::default(),
+                block,
+            )
+            .await
+            .map_err(|e| {
+                anyhow::format_err!("Failed to query contract authFactsResetTimer: {}", e)
+            })?;
+        let authorized = if reset_time.is_zero() {
+            let auth_fact = self.auth_fact(address, nonce, Some(block)).await?;
+            auth_fact.as_slice() == tiny_keccak::keccak256(&pub_key_hash.data[..])
+        } else {
+            false
+        };
+
+        Ok(ChangePubKeyOnchainAuthStatus {
+            address,
+            nonce,
+            pub_key_hash,
+            authorized,
+            eth_block: eth_block.as_u64(),
+        })
+    }
+
+    async fn auth_fact(
+        &self,
+        address: Address,
+        nonce: Nonce,
+        block: Option<BlockId>,
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        self.client
+            .call_main_contract_function(
+                "authFacts",
+                (address, u64::from(*nonce)),
                 None,
+                Options::default(),
+                block,
             )
             .await
-            .map_err(|e| anyhow::format_err!("Failed to query contract authFacts: {}", e))?;
-        Ok(auth_fact.as_slice() == tiny_keccak::keccak256(&pub_key_hash.data[..]))
+            .map_err(|e| anyhow::format_err!("Failed to query contract authFacts: {}", e))
+    }
+
+    /// Checks that the token contract will allow to complete the withdrawal to the recipient,
+    /// so the withdrawn funds won't get stuck in the zkSync contract.
+    /// Only the tokens from the `withdrawal_checked_tokens` list are checked.
+    pub async fn check_withdrawal_restrictions(
+        &self,
+        token: Address,
+        recipient: Address,
+    ) -> Result<(), TxAddError> {
+        if !self.withdrawal_checked_tokens.contains(&token) {
+            return Ok(());
+        }
+
+        if self.call_token_restriction(token, "paused", ()).await? {
+            return Err(TxAddError::WithdrawalTokenPaused);
+        }
+        // Different token implementations use different spelling.
+        for function in &["isBlacklisted", "isBlackListed"] {
+            if self
+                .call_token_restriction(token, function, recipient)
+                .await?
+            {
+                return Err(TxAddError::WithdrawalRecipientBlacklisted);
+            }
+        }
+        Ok(())
+    }
+
+    /// Calls the restriction function of the token contract. Tokens usually implement only
+    /// some of these functions, so the reverted call means that the restriction is not applied.
+    /// Any other failure (e.g. the Ethereum node is not available) rejects the withdrawal,
+    /// since the restriction can't be checked.
+    async fn call_token_restriction<P: Tokenize + Clone>(
+        &self,
+        token: Address,
+        function: &str,
+        params: P,
+    ) -> Result<bool, TxAddError> {
+        let abi = match &self.token_restrictions_abi {
+            Some(abi) => abi.clone(),
+            None => return Ok(false),
+        };
+        let call_result = self
+            .client
+            .call_contract_function(function, params, None, Options::default(), None, token, abi)
+            .await;
+
+        match call_result {
+            Ok(applied) => Ok(applied),
+            Err(error) if Self::is_function_missing(&error) => {
+                vlog::debug!(
+                    "Token {:?} doesn't implement the restriction `{}`: {}",
+                    token,
+                    function,
+                    error
+                );
+                Ok(false)
+            }
+            Err(error) => {
+                vlog::warn!(
+                    "Token {:?} restriction `{}` can't be checked: {}",
+                    token,
+                    function,
+                    error
+                );
+                metrics::counter!("api.eth_checker.token_restriction_unavailable", 1);
+                Err(TxAddError::EthereumUnavailable)
+            }
+        }
+    }
+
+    /// Returns `true` if the contract call failed because the contract doesn't implement
+    /// the function: the call is reverted, or the fallback function returned nothing.
+    fn is_function_missing(error: &anyhow::Error) -> bool {
+        match error.downcast_ref::<ContractError>() {
+            Some(ContractError::InvalidOutputType(_)) | Some(ContractError::Abi(_)) => true,
+            Some(ContractError::Api(web3::Error::Rpc(error))) => error.message.contains("revert"),
+            _ => false,
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::EthereumChecker;
+    use crate::tx_error::TxAddError;
     use std::str::FromStr;
     use zksync_config::test_config::TestConfig;
     use zksync_contracts::zksync_contract;
+    use zksync_eth_client::clients::mock::{MockCallResponse, MockEthereum};
     use zksync_eth_client::ethereum_gateway::EthereumGateway;
     use zksync_eth_client::ETHDirectClient;
     use zksync_eth_signer::PrivateKeySigner;
@@ -103,6 +252,87 @@ mod tests {
         Address,
     };
 
+    /// Sets the responses of the token restriction functions, the function which is not set
+    /// is not implemented by the token.
+    async fn token_restrictions(
+        paused: Option<MockCallResponse>,
+        blacklisted: Option<MockCallResponse>,
+    ) -> (EthereumChecker, Address) {
+        let token = Address::repeat_byte(0x01);
+        let client = MockEthereum::default();
+        let responses = [
+            ("paused", paused),
+            ("isBlacklisted", blacklisted),
+            ("isBlackListed", None),
+        ];
+        for (function, response) in responses.iter().cloned() {
+            let response = response.unwrap_or(MockCallResponse::Revert);
+            client.set_contract_call(token, function, response).await;
+        }
+
+        let checked_tokens = vec![token].into_iter().collect();
+        let checker = EthereumChecker::new(EthereumGateway::Mock(client), checked_tokens);
+        (checker, token)
+    }
+
+    #[tokio::test]
+    async fn withdrawal_restrictions() {
+        let recipient = Address::repeat_byte(0x02);
+        let applied = || Some(MockCallResponse::Output(vec![ethabi::Token::Bool(true)]));
+        let not_applied = || Some(MockCallResponse::Output(vec![ethabi::Token::Bool(false)]));
+
+        // Token doesn't implement any of the restrictions.
+        let (checker, token) = token_restrictions(None, None).await;
+        assert!(checker
+            .check_withdrawal_restrictions(token, recipient)
+            .await
+            .is_ok());
+        // Only the listed tokens are checked.
+        assert!(checker
+            .check_withdrawal_restrictions(Address::repeat_byte(0x03), recipient)
+            .await
+            .is_ok());
+
+        let (checker, token) = token_restrictions(not_applied(), not_applied()).await;
+        assert!(checker
+            .check_withdrawal_restrictions(token, recipient)
+            .await
+            .is_ok());
+
+        let (checker, token) = token_restrictions(applied(), None).await;
+        assert!(matches!(
+            checker
+                .check_withdrawal_restrictions(token, recipient)
+                .await,
+            Err(TxAddError::WithdrawalTokenPaused)
+        ));
+
+        let (checker, token) = token_restrictions(not_applied(), applied()).await;
+        assert!(matches!(
+            checker
+                .check_withdrawal_restrictions(token, recipient)
+                .await,
+            Err(TxAddError::WithdrawalRecipientBlacklisted)
+        ));
+
+        // Restrictions can't be checked while the node is not available.
+        let unavailable = || Some(MockCallResponse::Unavailable);
+        let (checker, token) = token_restrictions(unavailable(), None).await;
+        assert!(matches!(
+            checker
+                .check_withdrawal_restrictions(token, recipient)
+                .await,
+            Err(TxAddError::EthereumUnavailable)
+        ));
+        let (checker, token) = token_restrictions(not_applied(), unavailable()).await;
+        assert!(matches!(
+            checker
+                .check_withdrawal_restrictions(token, recipient)
+                .await,
+            Err(TxAddError::EthereumUnavailable)
+        ));
+    }
+
     #[tokio::test]
     async fn test_eip1271() {
         let config = TestConfig::load();
@@ -130,7 +360,7 @@ mod tests {
             1.0,
         ));
 
-        let eth_checker = EthereumChecker::new(client);
+        let eth_checker = EthereumChecker::new(client, Default::default());
 
         let result = eth_checker
             .is_eip1271_signature_correct(
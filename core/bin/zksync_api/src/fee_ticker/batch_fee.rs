@@ -0,0 +1,76 @@
+//! Marginal costs of the batched transactions.
+//!
+//! Transactions of a batch are not independent: the self-transfer of an account which sends
+//! other transactions of the batch in the same token is an internal transfer. It only exists
+//! to pay the fee of the batch, so it's only charged for the block space it takes rather than
+//! the cost of a standalone transfer. The rest of the transactions are charged as the standalone
+//! ones, since their public data doesn't depend on the other transactions of the batch.
+//!
+//! The origin of the transactions is only known if the sender is specified in the fee request,
+//! otherwise all the transactions are charged as the standalone ones.
+
+// Built-in deps
+use std::collections::HashSet;
+// Workspace deps
+use zksync_types::{Address, TokenId, TxFeeTypes};
+
+/// Transaction of the batch to calculate the fee for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatchFeeTx {
+    pub tx_type: TxFeeTypes,
+    pub recipient: Address,
+    /// The sender of the transaction and the token it's sent in, if known.
+    pub origin: Option<(Address, TokenId)>,
+}
+
+impl BatchFeeTx {
+    pub fn new(
+        tx_type: TxFeeTypes,
+        recipient: Address,
+        origin: Option<(Address, TokenId)>,
+    ) -> Self {
+        Self {
+            tx_type,
+            recipient,
+            origin,
+        }
+    }
+
+    fn is_self_transfer(&self) -> bool {
+        matches!(self.origin, Some((sender, _)) if sender == self.recipient)
+            && self.tx_type == TxFeeTypes::Transfer
+    }
+}
+
+impl From<(TxFeeTypes, Address)> for BatchFeeTx {
+    fn from((tx_type, recipient): (TxFeeTypes, Address)) -> Self {
+        Self::new(tx_type, recipient, None)
+    }
+}
+
+/// The way the transaction of the batch is charged.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BatchTxCharge {
+    /// The transaction costs as much as if it was sent alone.
+    Standalone,
+    /// Self-transfer paying the fee for the other transactions of the same origin.
+    Internal,
+}
+
+/// Determines how each transaction of the batch is charged.
+pub fn batch_tx_charges(txs: &[BatchFeeTx]) -> Vec<BatchTxCharge> {
+    let paid_origins: HashSet<_> = txs
+        .iter()
+        .filter(|tx| !tx.is_self_transfer())
+        .filter_map(|tx| tx.origin)
+        .collect();
+
+    txs.iter()
+        .map(|tx| match tx.origin {
+            Some(origin) if tx.is_self_transfer() && paid_origins.contains(&origin) => {
+                BatchTxCharge::Internal
+            }
+            _ => BatchTxCharge::Standalone,
+        })
+        .collect()
+}
@@ -27,6 +27,9 @@ pub(crate) const BASE_CHANGE_PUBKEY_OFFCHAIN_COST: u64 = CommitCost::CHANGE_PUBK
 pub(crate) const BASE_CHANGE_PUBKEY_CREATE2_COST: u64 = CommitCost::CHANGE_PUBKEY_COST_CREATE2
     + VerifyCost::CHANGE_PUBKEY_COST
     + AMORTIZED_COST_PER_CHUNK * (ChangePubKeyOp::CHUNKS as u64);
+pub(crate) const BASE_CHANGE_PUBKEY_GUARDIANS_COST: u64 = CommitCost::CHANGE_PUBKEY_COST_GUARDIANS
+    + VerifyCost::CHANGE_PUBKEY_COST
+    + AMORTIZED_COST_PER_CHUNK * (ChangePubKeyOp::CHUNKS as u64);
 pub(crate) const BASE_CHANGE_PUBKEY_ONCHAIN_COST: u64 = CommitCost::CHANGE_PUBKEY_COST_ONCHAIN
     + VerifyCost::CHANGE_PUBKEY_COST
     + AMORTIZED_COST_PER_CHUNK * (ChangePubKeyOp::CHUNKS as u64);
@@ -41,3 +44,9 @@ pub(crate) const SUBSIDY_CHANGE_PUBKEY_ONCHAIN_COST: u64 = BASE_CHANGE_PUBKEY_ON
 pub(crate) const SUBSIDY_OLD_CHANGE_PUBKEY_OFFCHAIN_COST: u64 =
     BASE_OLD_CHANGE_PUBKEY_OFFCHAIN_COST;
 pub(crate) const SUBSIDY_CHANGE_PUBKEY_CREATE2_COST: u64 = BASE_CHANGE_PUBKEY_CREATE2_COST;
+pub(crate) const SUBSIDY_CHANGE_PUBKEY_GUARDIANS_COST: u64 = BASE_CHANGE_PUBKEY_GUARDIANS_COST;
+
+// Self-transfers paying the fee for the other transactions of the batch are only charged
+// for the block space they take.
+pub(crate) const INTERNAL_TRANSFER_COST: u64 =
+    AMORTIZED_COST_PER_CHUNK * (TransferOp::CHUNKS as u64);
@@ -27,21 +27,27 @@ use tokio::task::JoinHandle;
 use tokio::time::Instant;
 // Workspace deps
 use zksync_balancer::{Balancer, BuildBalancedItem};
-use zksync_config::{configs::ticker::TokenPriceSource, ZkSyncConfig};
+use zksync_config::{configs::ticker::TokenPriceSource, ConfigReloader, Reloadable, ZkSyncConfig};
 use zksync_storage::ConnectionPool;
 use zksync_types::{
-    tokens::ChangePubKeyFeeTypeArg, tx::ChangePubKeyType, Address, BatchFee, ChangePubKeyOp, Fee,
-    OutputFeeType, Token, TokenId, TokenLike, TransferOp, TransferToNewOp, TxFeeTypes, WithdrawOp,
+    token_amount::token_unit,
+    tokens::{ChangePubKeyFeeTypeArg, FeeTokenIneligibility, TokenFeeStatus},
+    tx::ChangePubKeyType,
+    Address, BatchFee, ChangePubKeyOp, Fee, OutputFeeType, Token, TokenId, TokenLike, TransferOp,
+    TransferToNewOp, TxFeeTypes, WithdrawOp,
+};
+use zksync_utils::{
+    ratio_to_big_decimal,
+    supervisor::{SharedReceiver, Supervisor},
 };
-use zksync_utils::ratio_to_big_decimal;
 
 // Local deps
 use crate::fee_ticker::ticker_info::{FeeTickerInfo, TickerInfo};
 use crate::fee_ticker::validator::MarketUpdater;
 use crate::fee_ticker::{
     ticker_api::{
-        coingecko::CoinGeckoAPI, coinmarkercap::CoinMarketCapAPI, FeeTickerAPI, TickerApi,
-        CONNECTION_TIMEOUT,
+        coingecko::CoinGeckoAPI, coinmarkercap::CoinMarketCapAPI, is_price_expired,
+        price_checker::PriceSanityChecker, FeeTickerAPI, TickerApi, CONNECTION_TIMEOUT,
     },
     validator::{
         watcher::{TokenWatcher, UniswapTokenWatcher},
@@ -51,7 +57,13 @@ use crate::fee_ticker::{
 use crate::utils::token_db_cache::TokenDBCache;
 use num::bigint::ToBigInt;
 
+pub use self::batch_fee::BatchFeeTx;
+use self::batch_fee::{batch_tx_charges, BatchTxCharge};
+use self::quote_cache::{FeeQuoteCache, QuoteKey};
+
+mod batch_fee;
 mod constants;
+mod quote_cache;
 mod ticker_api;
 mod ticker_info;
 pub mod validator;
@@ -124,6 +136,12 @@ impl GasOperationsCost {
                 )),
                 constants::BASE_CHANGE_PUBKEY_CREATE2_COST.into(),
             ),
+            (
+                OutputFeeType::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
+                    ChangePubKeyType::Guardians,
+                )),
+                constants::BASE_CHANGE_PUBKEY_GUARDIANS_COST.into(),
+            ),
         ]
         .into_iter()
         .collect::<HashMap<_, _>>();
@@ -175,6 +193,12 @@ impl GasOperationsCost {
                 )),
                 constants::SUBSIDY_CHANGE_PUBKEY_CREATE2_COST.into(),
             ),
+            (
+                OutputFeeType::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
+                    ChangePubKeyType::Guardians,
+                )),
+                constants::SUBSIDY_CHANGE_PUBKEY_GUARDIANS_COST.into(),
+            ),
         ]
         .into_iter()
         .collect::<HashMap<_, _>>();
@@ -191,6 +215,8 @@ pub struct TickerConfig {
     zkp_cost_chunk_usd: Ratio<BigUint>,
     gas_cost_tx: GasOperationsCost,
     tokens_risk_factors: HashMap<TokenId, Ratio<BigUint>>,
+    /// Multipliers applied to the fees paid in the listed tokens.
+    tokens_fee_markups: HashMap<Address, Ratio<BigUint>>,
     not_subsidized_tokens: HashSet<Address>,
 }
 
@@ -200,7 +226,7 @@ pub enum TokenPriceRequestType {
     USDForOneToken,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ResponseFee {
     pub normal_fee: Fee,
     pub subsidy_fee: Fee,
@@ -236,6 +262,18 @@ fn get_max_subsidy(
 }
 
 impl ResponseFee {
+    /// Increases the fees by the markup of the API namespace, if any.
+    pub fn with_markup(self, markup_percent: Option<u32>) -> Self {
+        match markup_percent {
+            Some(markup_percent) => Self {
+                normal_fee: self.normal_fee.with_markup(markup_percent),
+                subsidy_fee: self.subsidy_fee.with_markup(markup_percent),
+                subsidy_size_usd: self.subsidy_size_usd,
+            },
+            None => self,
+        }
+    }
+
     pub fn get_max_subsidy(&self, allowed_subsidy: &Ratio<BigUint>) -> BigDecimal {
         get_max_subsidy(
             allowed_subsidy,
@@ -254,6 +292,18 @@ pub struct ResponseBatchFee {
 }
 
 impl ResponseBatchFee {
+    /// Increases the fees by the markup of the API namespace, if any.
+    pub fn with_markup(self, markup_percent: Option<u32>) -> Self {
+        match markup_percent {
+            Some(markup_percent) => Self {
+                normal_fee: self.normal_fee.with_markup(markup_percent),
+                subsidy_fee: self.subsidy_fee.with_markup(markup_percent),
+                subsidy_size_usd: self.subsidy_size_usd,
+            },
+            None => self,
+        }
+    }
+
     pub fn get_max_subsidy(&self, allowed_subsidy: &Ratio<BigUint>) -> BigDecimal {
         get_max_subsidy(
             allowed_subsidy,
@@ -273,7 +323,7 @@ pub enum TickerRequest {
         response: oneshot::Sender<Result<ResponseFee, anyhow::Error>>,
     },
     GetBatchTxFee {
-        transactions: Vec<(TxFeeTypes, Address)>,
+        transactions: Vec<BatchFeeTx>,
         token: TokenLike,
         response: oneshot::Sender<Result<ResponseBatchFee, anyhow::Error>>,
     },
@@ -286,6 +336,12 @@ pub enum TickerRequest {
         token: TokenLike,
         response: oneshot::Sender<Result<bool, anyhow::Error>>,
     },
+    /// Returns whether the token can be used to pay fees along with the reason if it can't,
+    /// or `None` if the token is unknown.
+    GetTokenFeeStatus {
+        token: TokenLike,
+        response: oneshot::Sender<Result<Option<TokenFeeStatus>, anyhow::Error>>,
+    },
 }
 
 #[derive(Debug, Error)]
@@ -296,6 +352,8 @@ pub enum PriceError {
     ApiError(String),
     #[error("Database error: {0}")]
     DBError(String),
+    #[error("Token price is unreliable: {0}")]
+    UnreliablePrice(String),
 }
 
 impl PriceError {
@@ -310,21 +368,28 @@ impl PriceError {
     pub fn db_error(msg: impl Display) -> Self {
         Self::DBError(msg.to_string())
     }
+
+    pub fn unreliable_price(msg: impl Display) -> Self {
+        Self::UnreliablePrice(msg.to_string())
+    }
 }
 
+#[derive(Clone)]
 struct FeeTicker<API, INFO, WATCHER> {
     api: API,
     info: INFO,
-    requests: Receiver<TickerRequest>,
-    config: TickerConfig,
+    requests: SharedReceiver<TickerRequest>,
+    config: Reloadable<TickerConfig>,
     validator: FeeTokenValidator<WATCHER>,
+    quote_cache: Option<FeeQuoteCache>,
 }
 
 struct FeeTickerBuilder<API, INFO, WATCHER> {
     api: API,
     info: INFO,
-    config: TickerConfig,
+    config: Reloadable<TickerConfig>,
     validator: FeeTokenValidator<WATCHER>,
+    quote_cache: Option<FeeQuoteCache>,
 }
 
 impl<API: Clone, INFO: Clone, WATCHER: Clone>
@@ -338,9 +403,10 @@ impl<API: Clone, INFO: Clone, WATCHER: Clone>
         FeeTicker {
             api: self.api.clone(),
             info: self.info.clone(),
-            requests: receiver,
+            requests: receiver.into(),
             config: self.config.clone(),
             validator: self.validator.clone(),
+            quote_cache: self.quote_cache.clone(),
         }
     }
 }
@@ -350,13 +416,23 @@ pub fn run_ticker_task(
     db_pool: ConnectionPool,
     tricker_requests: Receiver<TickerRequest>,
     config: &ZkSyncConfig,
+    config_reloader: &ConfigReloader,
+    supervisor: &Supervisor,
 ) -> JoinHandle<()> {
-    let ticker_config = TickerConfig {
+    let ticker_config = Reloadable::from(TickerConfig {
         zkp_cost_chunk_usd: Ratio::from_integer(BigUint::from(10u32).pow(3u32)).inv(),
         gas_cost_tx: GasOperationsCost::from_constants(config.ticker.fast_processing_coeff),
         tokens_risk_factors: HashMap::new(),
+        tokens_fee_markups: config.ticker.get_fee_markups(),
         not_subsidized_tokens: HashSet::from_iter(config.ticker.not_subsidized_tokens.clone()),
-    };
+    });
+    config_reloader.subscribe({
+        let ticker_config = ticker_config.clone();
+        move |params| {
+            let gas_cost_tx = GasOperationsCost::from_constants(params.fast_processing_coeff);
+            ticker_config.update(|config| config.gas_cost_tx = gas_cost_tx);
+        }
+    });
 
     let cache = (db_pool.clone(), TokenDBCache::new());
     let watcher = UniswapTokenWatcher::new(config.ticker.uniswap_url.clone());
@@ -376,34 +452,83 @@ pub fn run_ticker_task(
         .build()
         .expect("Failed to build reqwest::Client");
     let (price_source, base_url) = config.ticker.price_source();
+    let secondary_base_url: Option<reqwest::Url> = config
+        .ticker
+        .secondary_price_source()
+        .map(|(_, url)| url.parse().expect("Correct secondary price source url"));
+    let max_price_divergence = config.ticker.max_price_divergence();
+    let price_feed_failure_policy = config.failure_policy.price_feed;
+    // Quotes are shared between the ticker actors and refreshed by the separate one.
+    let quote_cache = if config.ticker.fee_quote_cache_enabled {
+        Some(FeeQuoteCache::new(
+            config.ticker.fee_quote_max_staleness(),
+            config.ticker.fee_quote_refresh_interval(),
+            config.ticker.fee_quote_cache_top_pairs,
+            config.ticker.fee_quote_warmup_tokens.clone(),
+        ))
+    } else {
+        None
+    };
     match price_source {
         TokenPriceSource::CoinMarketCap => {
-            let token_price_api =
-                CoinMarketCapAPI::new(client, base_url.parse().expect("Correct CoinMarketCap url"));
+            // The secondary source being unreachable on start doesn't stop the ticker,
+            // the prices are not cross-checked then.
+            let secondary_api = secondary_base_url.and_then(|url| {
+                CoinGeckoAPI::new(client.clone(), url)
+                    .map_err(|err| {
+                        vlog::error!(
+                            "Failed to init the secondary CoinGecko price source, \
+                             the prices are not cross-checked: {}",
+                            err
+                        )
+                    })
+                    .ok()
+            });
+            let token_price_api = PriceSanityChecker::new(
+                CoinMarketCapAPI::new(client, base_url.parse().expect("Correct CoinMarketCap url")),
+                secondary_api,
+                max_price_divergence,
+                price_feed_failure_policy,
+            );
 
-            let ticker_api = TickerApi::new(db_pool.clone(), token_price_api);
+            let ticker_api = TickerApi::new(db_pool.clone(), token_price_api)
+                .with_failure_policy(price_feed_failure_policy);
             let ticker_info = TickerInfo::new(db_pool);
-            let fee_ticker = FeeTicker::new(
+            let mut fee_ticker = FeeTicker::new(
                 ticker_api,
                 ticker_info,
                 tricker_requests,
                 ticker_config,
                 validator,
             );
+            if let Some(quote_cache) = quote_cache {
+                fee_ticker = fee_ticker.with_quote_cache(quote_cache);
+                let refresher = fee_ticker.clone();
+                supervisor.spawn("fee_quote_refresher", move || {
+                    refresher.clone().keep_quotes_updated()
+                });
+            }
 
-            tokio::spawn(fee_ticker.run())
+            supervisor.spawn("fee_ticker", move || fee_ticker.clone().run())
         }
 
         TokenPriceSource::CoinGecko => {
-            let token_price_api =
+            let secondary_api =
+                secondary_base_url.map(|url| CoinMarketCapAPI::new(client.clone(), url));
+            let token_price_api = PriceSanityChecker::new(
                 CoinGeckoAPI::new(client, base_url.parse().expect("Correct CoinGecko url"))
-                    .expect("failed to init CoinGecko client");
+                    .expect("failed to init CoinGecko client"),
+                secondary_api,
+                max_price_divergence,
+                price_feed_failure_policy,
+            );
             let ticker_info = TickerInfo::new(db_pool.clone());
 
             let token_db_cache = TokenDBCache::new();
             let price_cache = Arc::new(Mutex::new(HashMap::new()));
             let gas_price_cache = Arc::new(Mutex::new(None));
             let ticker_api = TickerApi::new(db_pool, token_price_api)
+                .with_failure_policy(price_feed_failure_policy)
                 .with_token_db_cache(token_db_cache)
                 .with_price_cache(price_cache)
                 .with_gas_price_cache(gas_price_cache);
@@ -414,13 +539,21 @@ pub fn run_ticker_task(
                     info: ticker_info,
                     config: ticker_config,
                     validator,
+                    quote_cache: quote_cache.clone(),
                 },
                 tricker_requests,
                 config.ticker.number_of_ticker_actors,
                 TICKER_CHANNEL_SIZE,
             );
-            for ticker in tickers.into_iter() {
-                tokio::spawn(ticker.run());
+            if let Some(refresher) = tickers.first().cloned().filter(|_| quote_cache.is_some()) {
+                supervisor.spawn("fee_quote_refresher", move || {
+                    refresher.clone().keep_quotes_updated()
+                });
+            }
+            for (index, ticker) in tickers.into_iter().enumerate() {
+                supervisor.spawn(format!("fee_ticker_{}", index), move || {
+                    ticker.clone().run()
+                });
             }
             tokio::spawn(ticker_balancer.run())
         }
@@ -432,15 +565,23 @@ impl<API: FeeTickerAPI, INFO: FeeTickerInfo, WATCHER: TokenWatcher> FeeTicker<AP
         api: API,
         info: INFO,
         requests: Receiver<TickerRequest>,
-        config: TickerConfig,
+        config: Reloadable<TickerConfig>,
         validator: FeeTokenValidator<WATCHER>,
     ) -> Self {
         Self {
             api,
             info,
-            requests,
+            requests: requests.into(),
             config,
             validator,
+            quote_cache: None,
+        }
+    }
+
+    fn with_quote_cache(self, quote_cache: FeeQuoteCache) -> Self {
+        Self {
+            quote_cache: Some(quote_cache),
+            ..self
         }
     }
 
@@ -481,6 +622,11 @@ impl<API: FeeTickerAPI, INFO: FeeTickerInfo, WATCHER: TokenWatcher> FeeTicker<AP
                     metrics::histogram!("ticker.is_token_allowed", start.elapsed());
                     response.send(allowed).unwrap_or_default();
                 }
+                TickerRequest::GetTokenFeeStatus { token, response } => {
+                    let status = self.get_token_fee_status(token).await;
+                    metrics::histogram!("ticker.get_token_fee_status", start.elapsed());
+                    response.send(status).unwrap_or_default();
+                }
                 TickerRequest::GetBatchTxFee {
                     transactions,
                     token,
@@ -507,7 +653,7 @@ impl<API: FeeTickerAPI, INFO: FeeTickerInfo, WATCHER: TokenWatcher> FeeTicker<AP
                     .await
                     .map_err(PriceError::db_error)?
                     .decimals;
-                BigUint::from(10u32).pow(u32::from(token_decimals))
+                token_unit(token_decimals)
             }
             TokenPriceRequestType::USDForOneToken => BigUint::from(1u32),
         };
@@ -518,22 +664,74 @@ impl<API: FeeTickerAPI, INFO: FeeTickerInfo, WATCHER: TokenWatcher> FeeTicker<AP
             .map(|price| ratio_to_big_decimal(&(price.usd_price / factor), 100))
     }
 
+    async fn get_token_fee_status(
+        &mut self,
+        token: TokenLike,
+    ) -> anyhow::Result<Option<TokenFeeStatus>> {
+        let token = match self.validator.resolve_token(token).await? {
+            Some(token) => token,
+            None => return Ok(None),
+        };
+        let token_id = token.id;
+        if let Some(reason) = self.validator.fee_ineligibility(token).await? {
+            return Ok(Some(TokenFeeStatus::ineligible(token_id, reason)));
+        }
+
+        // The fee can't be calculated without the token price, see `token_usd_risk`.
+        let status = match self.api.get_last_quote(TokenLike::Id(token_id)).await {
+            Ok(price) if price.usd_price.is_zero() => {
+                TokenFeeStatus::ineligible(token_id, FeeTokenIneligibility::PriceUnavailable)
+            }
+            Ok(price) => {
+                let price_stale = is_price_expired(&price);
+                TokenFeeStatus::eligible(token_id, &price, price_stale)
+            }
+            Err(PriceError::UnreliablePrice(_)) => {
+                TokenFeeStatus::ineligible(token_id, FeeTokenIneligibility::UnreliablePrice)
+            }
+            Err(PriceError::TokenNotFound(_)) | Err(PriceError::ApiError(_)) => {
+                TokenFeeStatus::ineligible(token_id, FeeTokenIneligibility::PriceUnavailable)
+            }
+            Err(PriceError::DBError(err)) => anyhow::bail!(err),
+        };
+        Ok(Some(status))
+    }
+
     async fn get_fee_from_ticker_in_wei(
         &mut self,
         tx_type: TxFeeTypes,
         token: TokenLike,
         recipient: Address,
     ) -> Result<ResponseFee, anyhow::Error> {
-        let zkp_cost_chunk = self.config.zkp_cost_chunk_usd.clone();
         let token = self.api.get_token(token).await?;
+        let fee_type = self.output_fee_type(tx_type, recipient).await;
+
+        let quote_cache = match self.quote_cache.clone() {
+            Some(quote_cache) => quote_cache,
+            None => return self.calculate_fee(fee_type, &token).await,
+        };
+        if let Some(fee) = quote_cache.get((fee_type, token.id)).await {
+            return Ok(fee);
+        }
+        let fee = self.calculate_fee(fee_type, &token).await?;
+        quote_cache.insert((fee_type, token.id), fee.clone()).await;
+        Ok(fee)
+    }
+
+    async fn calculate_fee(
+        &mut self,
+        fee_type: OutputFeeType,
+        token: &Token,
+    ) -> Result<ResponseFee, anyhow::Error> {
+        let zkp_cost_chunk = self.config.read().zkp_cost_chunk_usd.clone();
 
         let gas_price_wei = self.api.get_gas_price_wei().await?;
         let scale_gas_price = Self::risk_gas_price_estimate(gas_price_wei.clone());
         let wei_price_usd = self.wei_price_usd().await?;
-        let token_usd_risk = self.token_usd_risk(&token).await?;
+        let token_usd_risk = self.token_usd_risk(token).await?;
 
-        let (fee_type, (normal_gas_tx_amount, subsidy_gas_tx_amount), op_chunks) =
-            self.gas_tx_amount(tx_type, recipient).await;
+        let ((normal_gas_tx_amount, subsidy_gas_tx_amount), op_chunks) =
+            self.gas_tx_amount(fee_type, token).await?;
 
         let zkp_fee = (zkp_cost_chunk * op_chunks) * &token_usd_risk;
         let normal_gas_fee =
@@ -571,9 +769,9 @@ impl<API: FeeTickerAPI, INFO: FeeTickerInfo, WATCHER: TokenWatcher> FeeTicker<AP
     async fn get_batch_from_ticker_in_wei(
         &mut self,
         token: TokenLike,
-        txs: Vec<(TxFeeTypes, Address)>,
+        txs: Vec<BatchFeeTx>,
     ) -> anyhow::Result<ResponseBatchFee> {
-        let zkp_cost_chunk = self.config.zkp_cost_chunk_usd.clone();
+        let zkp_cost_chunk = self.config.read().zkp_cost_chunk_usd.clone();
         let token = self.api.get_token(token).await?;
 
         let gas_price_wei = self.api.get_gas_price_wei().await?;
@@ -585,9 +783,20 @@ impl<API: FeeTickerAPI, INFO: FeeTickerInfo, WATCHER: TokenWatcher> FeeTicker<AP
         let mut total_subsidy_gas_tx_amount = BigUint::zero();
         let mut total_op_chunks = BigUint::zero();
 
-        for (tx_type, recipient) in txs {
-            let (_, (normal_gas_tx_amount, subsidy_gas_tx_amount), op_chunks) =
-                self.gas_tx_amount(tx_type, recipient).await;
+        for (tx, charge) in txs.iter().zip(batch_tx_charges(&txs)) {
+            let fee_type = self.output_fee_type(tx.tx_type, tx.recipient).await;
+            let ((normal_gas_tx_amount, subsidy_gas_tx_amount), op_chunks) =
+                self.gas_tx_amount(fee_type, &token).await?;
+            let (normal_gas_tx_amount, subsidy_gas_tx_amount) = match charge {
+                BatchTxCharge::Internal => {
+                    let cost = BigUint::from(constants::INTERNAL_TRANSFER_COST);
+                    (
+                        normal_gas_tx_amount.min(cost.clone()),
+                        subsidy_gas_tx_amount.min(cost),
+                    )
+                }
+                BatchTxCharge::Standalone => (normal_gas_tx_amount, subsidy_gas_tx_amount),
+            };
             total_normal_gas_tx_amount += normal_gas_tx_amount;
             total_subsidy_gas_tx_amount += subsidy_gas_tx_amount;
             total_op_chunks += op_chunks;
@@ -620,19 +829,25 @@ impl<API: FeeTickerAPI, INFO: FeeTickerInfo, WATCHER: TokenWatcher> FeeTicker<AP
     }
 
     async fn token_usd_risk(&mut self, token: &Token) -> anyhow::Result<Ratio<BigUint>> {
-        let token_risk_factor = self
-            .config
-            .tokens_risk_factors
-            .get(&token.id)
-            .cloned()
-            .unwrap_or_else(|| Ratio::from_integer(1u32.into()));
+        let token_risk_factor = {
+            let config = self.config.read();
+            let risk_factor = config
+                .tokens_risk_factors
+                .get(&token.id)
+                .cloned()
+                .unwrap_or_else(|| Ratio::from_integer(1u32.into()));
+            match config.tokens_fee_markups.get(&token.address) {
+                Some(markup) => risk_factor * markup,
+                None => risk_factor,
+            }
+        };
 
         let token_price_usd = self
             .api
             .get_last_quote(TokenLike::Id(token.id))
             .await?
             .usd_price
-            / BigUint::from(10u32).pow(u32::from(token.decimals));
+            / token_unit(token.decimals);
         // TODO Check tokens fee allowance by non-zero price (ZKS-580)
         token_risk_factor
             .checked_div(&token_price_usd)
@@ -644,42 +859,124 @@ impl<API: FeeTickerAPI, INFO: FeeTickerInfo, WATCHER: TokenWatcher> FeeTicker<AP
         self.info.is_account_new(address).await
     }
 
-    async fn gas_tx_amount(
-        &mut self,
-        tx_type: TxFeeTypes,
-        recipient: Address,
-    ) -> (OutputFeeType, (BigUint, BigUint), BigUint) {
-        let (fee_type, op_chunks) = match tx_type {
-            TxFeeTypes::Withdraw => (OutputFeeType::Withdraw, WithdrawOp::CHUNKS),
-            TxFeeTypes::FastWithdraw => (OutputFeeType::FastWithdraw, WithdrawOp::CHUNKS),
+    /// Returns the fee type of the transaction, transfers to the new accounts are more expensive.
+    async fn output_fee_type(&mut self, tx_type: TxFeeTypes, recipient: Address) -> OutputFeeType {
+        match tx_type {
+            TxFeeTypes::Withdraw => OutputFeeType::Withdraw,
+            TxFeeTypes::FastWithdraw => OutputFeeType::FastWithdraw,
             TxFeeTypes::Transfer => {
                 if self.is_account_new(recipient).await {
-                    (OutputFeeType::TransferToNew, TransferToNewOp::CHUNKS)
+                    OutputFeeType::TransferToNew
                 } else {
-                    (OutputFeeType::Transfer, TransferOp::CHUNKS)
+                    OutputFeeType::Transfer
                 }
             }
-            TxFeeTypes::ChangePubKey(arg) => {
-                (OutputFeeType::ChangePubKey(arg), ChangePubKeyOp::CHUNKS)
-            }
+            TxFeeTypes::ChangePubKey(arg) => OutputFeeType::ChangePubKey(arg),
+        }
+    }
+
+    /// Returns the gas cost of the transaction, both standard and subsidized, and the number
+    /// of its chunks. Withdrawals of the token are charged for the gas measured from
+    /// the completed withdrawals if it exceeds the flat estimation. Batches are assumed to
+    /// withdraw the token their fee is paid in.
+    async fn gas_tx_amount(
+        &mut self,
+        fee_type: OutputFeeType,
+        token: &Token,
+    ) -> anyhow::Result<((BigUint, BigUint), BigUint)> {
+        let op_chunks = match fee_type {
+            OutputFeeType::Withdraw | OutputFeeType::FastWithdraw => WithdrawOp::CHUNKS,
+            OutputFeeType::Transfer => TransferOp::CHUNKS,
+            OutputFeeType::TransferToNew => TransferToNewOp::CHUNKS,
+            OutputFeeType::ChangePubKey(_) => ChangePubKeyOp::CHUNKS,
         };
         // Convert chunks amount to `BigUint`.
         let op_chunks = BigUint::from(op_chunks);
 
-        let gas_tx_amount = (
-            self.config
+        let extra_withdrawal_gas = match fee_type {
+            OutputFeeType::Withdraw | OutputFeeType::FastWithdraw => self
+                .info
+                .withdrawal_gas_cost(token.id)
+                .await?
+                .map(|cost| cost.extra_gas_cost())
+                .unwrap_or_default(),
+            _ => 0,
+        };
+
+        let config = self.config.read();
+        let (standard_cost, subsidy_cost): (BigUint, BigUint) = (
+            config
                 .gas_cost_tx
                 .standard_cost
                 .get(&fee_type)
                 .cloned()
                 .unwrap(),
-            self.config
+            config
                 .gas_cost_tx
                 .subsidize_cost
                 .get(&fee_type)
                 .cloned()
                 .unwrap(),
         );
-        (fee_type, gas_tx_amount, op_chunks)
+        let gas_tx_amount = (
+            standard_cost + extra_withdrawal_gas,
+            subsidy_cost + extra_withdrawal_gas,
+        );
+        Ok((gas_tx_amount, op_chunks))
+    }
+
+    /// Keeps the quotes of the most requested (fee type, token) pairs and the ones paid
+    /// in the warm-up tokens fresh, so the API requests are served from the cache.
+    async fn keep_quotes_updated(mut self) {
+        let quote_cache = match self.quote_cache.clone() {
+            Some(quote_cache) => quote_cache,
+            None => return,
+        };
+        // The first tick is immediate, so the warm-up quotes are calculated upon start.
+        let mut timer = tokio::time::interval(quote_cache.refresh_interval);
+        loop {
+            timer.tick().await;
+            let start = Instant::now();
+
+            let mut keys = self.warmup_quote_keys(&quote_cache.warmup_tokens).await;
+            keys.extend(quote_cache.take_most_requested(quote_cache.top_pairs).await);
+            for (fee_type, token_id) in keys {
+                let fee = match self.api.get_token(TokenLike::Id(token_id)).await {
+                    Ok(token) => self.calculate_fee(fee_type, &token).await,
+                    Err(err) => Err(err),
+                };
+                match fee {
+                    Ok(fee) => quote_cache.insert((fee_type, token_id), fee).await,
+                    Err(err) => vlog::debug!(
+                        "Failed to refresh the {:?} fee quote for token {}: {}",
+                        fee_type,
+                        token_id,
+                        err
+                    ),
+                }
+            }
+            metrics::histogram!("ticker.quote_cache.refresh", start.elapsed());
+        }
+    }
+
+    /// Returns all the fee types paid in the warm-up tokens.
+    async fn warmup_quote_keys(&mut self, warmup_tokens: &[Address]) -> HashSet<QuoteKey> {
+        let fee_types: Vec<_> = self
+            .config
+            .read()
+            .gas_cost_tx
+            .standard_cost
+            .keys()
+            .copied()
+            .collect();
+
+        let mut keys = HashSet::new();
+        for address in warmup_tokens {
+            match self.api.get_token(TokenLike::Address(*address)).await {
+                Ok(token) => keys.extend(fee_types.iter().map(|fee_type| (*fee_type, token.id))),
+                Err(err) => vlog::debug!("Failed to load the warm-up token {:?}: {}", address, err),
+            }
+        }
+        keys
     }
 }
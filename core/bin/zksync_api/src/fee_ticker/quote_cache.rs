@@ -0,0 +1,110 @@
+//! Cache of the transaction fee quotes.
+//!
+//! The fee calculation needs the token, the gas price and the token prices, so it's one of the
+//! main sources of the API latency. The quotes are cached per (fee type, token) pair and served
+//! while they are not older than the max staleness. The most requested pairs are refreshed in
+//! the background along with the ones paid in the warm-up tokens (see
+//! `FeeTicker::keep_quotes_updated`), so the requests for them rarely wait for the calculation.
+
+// Built-in deps
+use std::collections::HashMap;
+use std::sync::Arc;
+// External deps
+use tokio::sync::Mutex;
+use tokio::time::{Duration, Instant};
+// Workspace deps
+use zksync_types::{Address, OutputFeeType, TokenId};
+// Local deps
+use super::ResponseFee;
+
+/// Pair of the fee type and the token the fee is paid in.
+pub(super) type QuoteKey = (OutputFeeType, TokenId);
+
+#[derive(Debug, Default)]
+struct QuoteEntry {
+    quote: Option<(ResponseFee, Instant)>,
+    /// Number of the requests for the pair, halved upon every refresh,
+    /// so the recently requested pairs take precedence.
+    requests: u64,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct FeeQuoteCache {
+    entries: Arc<Mutex<HashMap<QuoteKey, QuoteEntry>>>,
+    max_staleness: Duration,
+    /// Interval between the refreshes of the quotes.
+    pub refresh_interval: Duration,
+    /// Number of the most requested pairs refreshed in the background.
+    pub top_pairs: usize,
+    /// Tokens the quotes of all fee types are refreshed for regardless of the requests.
+    pub warmup_tokens: Arc<[Address]>,
+}
+
+impl FeeQuoteCache {
+    pub fn new(
+        max_staleness: Duration,
+        refresh_interval: Duration,
+        top_pairs: usize,
+        warmup_tokens: Vec<Address>,
+    ) -> Self {
+        Self {
+            entries: Default::default(),
+            max_staleness,
+            refresh_interval,
+            top_pairs,
+            warmup_tokens: warmup_tokens.into(),
+        }
+    }
+
+    /// Returns the quote for the pair if it's not older than the max staleness.
+    /// The request is counted whether the quote is cached or not.
+    pub async fn get(&self, key: QuoteKey) -> Option<ResponseFee> {
+        let mut entries = self.entries.lock().await;
+        let entry = entries.entry(key).or_default();
+        entry.requests += 1;
+
+        match &entry.quote {
+            Some((quote, updated_at)) if updated_at.elapsed() < self.max_staleness => {
+                metrics::counter!("ticker.quote_cache.hit", 1);
+                Some(quote.clone())
+            }
+            _ => {
+                metrics::counter!("ticker.quote_cache.miss", 1);
+                None
+            }
+        }
+    }
+
+    pub async fn insert(&self, key: QuoteKey, quote: ResponseFee) {
+        let mut entries = self.entries.lock().await;
+        entries.entry(key).or_default().quote = Some((quote, Instant::now()));
+    }
+
+    /// Returns up to `limit` pairs most requested recently. Counters of the requests are halved,
+    /// and the pairs no longer requested are evicted once their quotes become stale.
+    pub async fn take_most_requested(&self, limit: usize) -> Vec<QuoteKey> {
+        let mut entries = self.entries.lock().await;
+
+        let mut requested: Vec<_> = entries
+            .iter()
+            .filter(|(_, entry)| entry.requests > 0)
+            .map(|(key, entry)| (*key, entry.requests))
+            .collect();
+        requested.sort_unstable_by(|(_, lhs), (_, rhs)| rhs.cmp(lhs));
+        requested.truncate(limit);
+
+        let max_staleness = self.max_staleness;
+        entries.retain(|_, entry| {
+            let is_fresh = matches!(
+                &entry.quote,
+                Some((_, updated_at)) if updated_at.elapsed() < max_staleness
+            );
+            entry.requests > 0 || is_fresh
+        });
+        for entry in entries.values_mut() {
+            entry.requests /= 2;
+        }
+
+        requested.into_iter().map(|(key, _)| key).collect()
+    }
+}
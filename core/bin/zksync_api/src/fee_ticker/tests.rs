@@ -8,12 +8,16 @@ use futures::{channel::mpsc, executor::block_on};
 use std::str::FromStr;
 use std::thread::sleep;
 use tokio::time::Duration;
-use zksync_types::{Address, Token, TokenId, TokenPrice};
+use zksync_config::configs::failure_policy::FailurePolicy;
+use zksync_types::{
+    gas_counter::VerifyCost, withdrawal_gas::WithdrawalGasCost, Address, Token, TokenId, TokenPrice,
+};
 use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal, UnsignedRatioSerializeAsDecimal};
 
 use crate::fee_ticker::{
     ticker_api::{
         coingecko::{CoinGeckoTokenInfo, CoinGeckoTokenList},
+        price_checker::PriceSanityChecker,
         TokenPriceAPI,
     },
     validator::{cache::TokenInMemoryCache, FeeTokenValidator},
@@ -118,6 +122,7 @@ fn get_test_ticker_config() -> TickerConfig {
                 t.risk_factor.map(|risk| (id, risk))
             })
             .collect(),
+        tokens_fee_markups: HashMap::new(),
         not_subsidized_tokens: vec![
             Address::from_str("34083bbd70d394110487feaa087da875a54624ec").unwrap(),
         ]
@@ -162,7 +167,10 @@ impl FeeTickerAPI for MockApiProvider {
     }
 }
 
-struct MockTickerInfo;
+#[derive(Default)]
+struct MockTickerInfo {
+    withdrawal_gas_costs: HashMap<TokenId, WithdrawalGasCost>,
+}
 
 #[async_trait]
 impl FeeTickerInfo for MockTickerInfo {
@@ -170,6 +178,13 @@ impl FeeTickerInfo for MockTickerInfo {
         // Always false for simplicity.
         false
     }
+
+    async fn withdrawal_gas_cost(
+        &mut self,
+        token_id: TokenId,
+    ) -> anyhow::Result<Option<WithdrawalGasCost>> {
+        Ok(self.withdrawal_gas_costs.get(&token_id).cloned())
+    }
 }
 
 fn format_with_dot(num: &Ratio<BigUint>, precision: usize) -> String {
@@ -195,6 +210,32 @@ impl TokenPriceAPI for ErrorTickerApi {
     }
 }
 
+/// Price source reporting the fixed USD price, `None` means the token is not listed.
+struct FixedPriceApi(Option<u32>);
+
+#[async_trait::async_trait]
+impl TokenPriceAPI for FixedPriceApi {
+    async fn get_price(&self, _token_symbol: &str) -> Result<TokenPrice, PriceError> {
+        let price = self
+            .0
+            .ok_or_else(|| PriceError::token_not_found("Wrong token"))?;
+        Ok(TokenPrice {
+            usd_price: Ratio::from_integer(price.into()),
+            last_updated: Utc::now(),
+        })
+    }
+}
+
+/// Price source which is never available.
+struct UnavailablePriceApi;
+
+#[async_trait::async_trait]
+impl TokenPriceAPI for UnavailablePriceApi {
+    async fn get_price(&self, _token_symbol: &str) -> Result<TokenPrice, PriceError> {
+        Err(PriceError::api_error("Service unavailable"))
+    }
+}
+
 fn run_server() -> (String, AbortHandle) {
     let mut url = None;
     let mut server = None;
@@ -245,9 +286,9 @@ fn test_ticker_formula() {
     let config = get_test_ticker_config();
     let mut ticker = FeeTicker::new(
         MockApiProvider,
-        MockTickerInfo,
+        MockTickerInfo::default(),
         mpsc::channel(1).1,
-        config,
+        config.into(),
         validator,
     );
 
@@ -260,7 +301,7 @@ fn test_ticker_formula() {
                 .unwrap()
                 .decimals;
             let batched_fee_in_token = block_on(
-                ticker.get_batch_from_ticker_in_wei(token.clone(), vec![(tx_type, address)]),
+                ticker.get_batch_from_ticker_in_wei(token.clone(), vec![(tx_type, address).into()]),
             )
             .expect("failed to get batched fee for token");
             assert_eq!(
@@ -345,6 +386,234 @@ fn test_ticker_formula() {
     }
 }
 
+#[test]
+fn test_fee_markup() {
+    let token = TestToken::hex();
+    let get_fee = |config: TickerConfig| -> BigUint {
+        let validator = FeeTokenValidator::new(
+            TokenInMemoryCache::new(),
+            chrono::Duration::seconds(100),
+            BigDecimal::from(100),
+            Default::default(),
+            FakeTokenWatcher,
+        );
+        let mut ticker = FeeTicker::new(
+            MockApiProvider,
+            MockTickerInfo::default(),
+            mpsc::channel(1).1,
+            config.into(),
+            validator,
+        );
+        block_on(ticker.get_fee_from_ticker_in_wei(
+            TxFeeTypes::Transfer,
+            token.id.into(),
+            Address::default(),
+        ))
+        .expect("failed to get fee in token")
+        .normal_fee
+        .total_fee
+    };
+
+    let fee = get_fee(get_test_ticker_config());
+    let mut config = get_test_ticker_config();
+    config.tokens_fee_markups.insert(
+        token.address,
+        Ratio::new(BigUint::from(150u32), BigUint::from(100u32)),
+    );
+    let fee_with_markup = get_fee(config);
+
+    // Fees are rounded, so the ratio may differ slightly.
+    let ratio = Ratio::new(fee_with_markup, fee.clone());
+    let diff = ratio_to_big_decimal(&ratio, 6) - BigDecimal::from_str("1.5").unwrap();
+    assert!(
+        diff.abs() < BigDecimal::from_str("0.01").unwrap(),
+        "markup is not applied: fee {}, diff {}",
+        fee,
+        diff
+    );
+}
+
+#[test]
+fn test_withdrawal_gas_cost() {
+    let token = TestToken::hex();
+    let get_fee = |info: MockTickerInfo| -> Fee {
+        let validator = FeeTokenValidator::new(
+            TokenInMemoryCache::new(),
+            chrono::Duration::seconds(100),
+            BigDecimal::from(100),
+            Default::default(),
+            FakeTokenWatcher,
+        );
+        let mut ticker = FeeTicker::new(
+            MockApiProvider,
+            info,
+            mpsc::channel(1).1,
+            get_test_ticker_config().into(),
+            validator,
+        );
+        block_on(ticker.get_fee_from_ticker_in_wei(
+            TxFeeTypes::Withdraw,
+            token.id.into(),
+            Address::default(),
+        ))
+        .expect("failed to get fee in token")
+        .normal_fee
+    };
+
+    let fee = get_fee(MockTickerInfo::default());
+
+    // Withdrawals of the token are measured to cost more than the flat estimation.
+    let mut info = MockTickerInfo::default();
+    let mut cost = WithdrawalGasCost::new(token.id);
+    cost.add_samples(VerifyCost::WITHDRAW_COST + 100_000, 1);
+    info.withdrawal_gas_costs.insert(token.id, cost);
+    let calibrated_fee = get_fee(info);
+    assert_eq!(
+        calibrated_fee.gas_tx_amount,
+        fee.gas_tx_amount.clone() + 100_000u32
+    );
+    assert!(calibrated_fee.total_fee > fee.total_fee);
+
+    // Cheaper withdrawals do not decrease the fee.
+    let mut info = MockTickerInfo::default();
+    let mut cost = WithdrawalGasCost::new(token.id);
+    cost.add_samples(VerifyCost::WITHDRAW_COST / 2, 1);
+    info.withdrawal_gas_costs.insert(token.id, cost);
+    assert_eq!(get_fee(info).gas_tx_amount, fee.gas_tx_amount);
+}
+
+/// Checks that the transactions of the same origin in the batch are charged for their marginal cost.
+#[test]
+fn test_batch_marginal_fee() {
+    let token = TestToken::eth();
+    let sender = Address::repeat_byte(1);
+    let recipient = Address::repeat_byte(2);
+    let transfer = |recipient: Address, origin: Option<(Address, TokenId)>| {
+        BatchFeeTx::new(TxFeeTypes::Transfer, recipient, origin)
+    };
+
+    let origin = Some((sender, token.id));
+    let other_origin = Some((sender, TokenId(token.id.0 + 1)));
+    let txs = vec![
+        transfer(recipient, origin),
+        transfer(recipient, origin),
+        transfer(sender, origin),
+        transfer(sender, other_origin),
+        transfer(recipient, None),
+    ];
+    assert_eq!(
+        batch_tx_charges(&txs),
+        vec![
+            BatchTxCharge::Standalone,
+            BatchTxCharge::Standalone,
+            BatchTxCharge::Internal,
+            // There are no other transactions of this origin to pay the fee for.
+            BatchTxCharge::Standalone,
+            BatchTxCharge::Standalone,
+        ]
+    );
+
+    let validator = FeeTokenValidator::new(
+        TokenInMemoryCache::new(),
+        chrono::Duration::seconds(100),
+        BigDecimal::from(100),
+        Default::default(),
+        FakeTokenWatcher,
+    );
+    // The proving cost is the same for all the transactions, so only the gas is charged.
+    let mut config = get_test_ticker_config();
+    config.zkp_cost_chunk_usd = Ratio::from_integer(BigUint::zero());
+    let mut ticker = FeeTicker::new(
+        MockApiProvider,
+        MockTickerInfo::default(),
+        mpsc::channel(1).1,
+        config.into(),
+        validator,
+    );
+    let mut get_fee = |txs: Vec<BatchFeeTx>| {
+        block_on(ticker.get_batch_from_ticker_in_wei(token.id.into(), txs))
+            .expect("failed to get batched fee for token")
+            .normal_fee
+            .total_fee
+    };
+
+    let standalone_fee = get_fee(vec![
+        transfer(recipient, None),
+        transfer(recipient, None),
+        transfer(sender, None),
+    ]);
+    let marginal_fee = get_fee(vec![
+        transfer(recipient, origin),
+        transfer(recipient, origin),
+        transfer(sender, origin),
+    ]);
+    assert!(marginal_fee < standalone_fee);
+
+    // Transactions in the different tokens are charged as the standalone ones.
+    let fee = get_fee(vec![
+        transfer(recipient, origin),
+        transfer(recipient, other_origin),
+        transfer(sender, Some((sender, TokenId(token.id.0 + 2)))),
+    ]);
+    assert_eq!(fee, standalone_fee);
+}
+
+/// Checks that the fee quotes are served from the cache while they are fresh.
+#[test]
+fn test_fee_quote_cache() {
+    let token = TestToken::hex();
+    let get_fee = |max_staleness: Duration| {
+        let validator = FeeTokenValidator::new(
+            TokenInMemoryCache::new(),
+            chrono::Duration::seconds(100),
+            BigDecimal::from(100),
+            Default::default(),
+            FakeTokenWatcher,
+        );
+        let config = Reloadable::from(get_test_ticker_config());
+        let quote_cache = FeeQuoteCache::new(max_staleness, Duration::from_secs(1), 1, Vec::new());
+        let mut ticker = FeeTicker::new(
+            MockApiProvider,
+            MockTickerInfo::default(),
+            mpsc::channel(1).1,
+            config.clone(),
+            validator,
+        )
+        .with_quote_cache(quote_cache.clone());
+        let mut get_fee = || {
+            block_on(ticker.get_fee_from_ticker_in_wei(
+                TxFeeTypes::Transfer,
+                token.id.into(),
+                Address::default(),
+            ))
+            .expect("failed to get fee in token")
+            .normal_fee
+            .total_fee
+        };
+
+        let fee = get_fee();
+        config.update(|config| {
+            config.tokens_fee_markups.insert(
+                token.address,
+                Ratio::new(BigUint::from(150u32), BigUint::from(100u32)),
+            );
+        });
+        let fee_after_update = get_fee();
+
+        // Both requests are counted for the pair.
+        let most_requested = block_on(quote_cache.take_most_requested(10));
+        assert_eq!(most_requested, vec![(OutputFeeType::Transfer, token.id)]);
+        (fee, fee_after_update)
+    };
+
+    // The fresh quote is not recalculated.
+    let (fee, cached_fee) = get_fee(Duration::from_secs(60));
+    assert_eq!(fee, cached_fee);
+    // The stale one is.
+    let (fee, recalculated_fee) = get_fee(Duration::from_secs(0));
+    assert!(recalculated_fee > fee);
+}
+
 // It's temporary solution while zero-price tokens marked as allowed for fee
 #[test]
 fn test_zero_price_token_fee() {
@@ -359,9 +628,9 @@ fn test_zero_price_token_fee() {
     let config = get_test_ticker_config();
     let mut ticker = FeeTicker::new(
         MockApiProvider,
-        MockTickerInfo,
+        MockTickerInfo::default(),
         mpsc::channel(1).1,
-        config,
+        config.into(),
         validator,
     );
 
@@ -377,11 +646,76 @@ fn test_zero_price_token_fee() {
 
     block_on(ticker.get_batch_from_ticker_in_wei(
         token.id.into(),
-        vec![(TxFeeTypes::Transfer, Address::default())],
+        vec![(TxFeeTypes::Transfer, Address::default()).into()],
     ))
     .unwrap_err();
 }
 
+/// Checks that the prices diverging from the secondary source are rejected.
+#[test]
+fn test_price_sanity_check() {
+    let max_divergence = Ratio::new(BigUint::from(10u32), BigUint::from(100u32));
+    let get_price = |primary: u32, secondary: Option<u32>| {
+        let checker = PriceSanityChecker::new(
+            FixedPriceApi(Some(primary)),
+            Some(FixedPriceApi(secondary)),
+            max_divergence.clone(),
+            FailurePolicy::Open,
+        );
+        block_on(checker.get_price("ETH"))
+    };
+
+    // Prices within the threshold are accepted.
+    assert_eq!(
+        get_price(100, Some(105)).unwrap().usd_price,
+        Ratio::from_integer(100u32.into())
+    );
+    assert!(get_price(100, Some(110)).is_ok());
+    assert!(get_price(110, Some(100)).is_ok());
+    assert!(get_price(0, Some(0)).is_ok());
+    // Diverged prices suspend the fee quoting.
+    assert!(matches!(
+        get_price(100, Some(120)),
+        Err(PriceError::UnreliablePrice(_))
+    ));
+    assert!(matches!(
+        get_price(100, Some(0)),
+        Err(PriceError::UnreliablePrice(_))
+    ));
+    // Tokens not listed by the secondary source are not checked.
+    assert!(get_price(100, None).is_ok());
+
+    // Prices are not checked without the secondary source.
+    let checker = PriceSanityChecker::<_, FixedPriceApi>::new(
+        FixedPriceApi(Some(100)),
+        None,
+        max_divergence,
+        FailurePolicy::Closed,
+    );
+    assert!(block_on(checker.get_price("ETH")).is_ok());
+}
+
+/// Checks that the prices are accepted unchecked while the secondary source is not available
+/// only if the price feed fails open.
+#[test]
+fn test_price_sanity_check_failure_policy() {
+    let get_price = |failure_policy| {
+        let checker = PriceSanityChecker::new(
+            FixedPriceApi(Some(100)),
+            Some(UnavailablePriceApi),
+            Ratio::new(BigUint::from(10u32), BigUint::from(100u32)),
+            failure_policy,
+        );
+        block_on(checker.get_price("ETH"))
+    };
+
+    assert!(get_price(FailurePolicy::Open).is_ok());
+    assert!(matches!(
+        get_price(FailurePolicy::Closed),
+        Err(PriceError::ApiError(_))
+    ));
+}
+
 #[actix_rt::test]
 #[ignore]
 // It's ignore because we can't initialize coingecko in current way with block
@@ -420,9 +754,9 @@ async fn test_error_coingecko_api() {
     let config = get_test_ticker_config();
     let mut ticker = FeeTicker::new(
         ticker_api,
-        MockTickerInfo,
+        MockTickerInfo::default(),
         mpsc::channel(1).1,
-        config,
+        config.into(),
         validator,
     );
     for _ in 0..1000 {
@@ -472,9 +806,9 @@ async fn test_error_api() {
     let config = get_test_ticker_config();
     let mut ticker = FeeTicker::new(
         ticker_api,
-        MockTickerInfo,
+        MockTickerInfo::default(),
         mpsc::channel(1).1,
-        config,
+        config.into(),
         validator,
     );
 
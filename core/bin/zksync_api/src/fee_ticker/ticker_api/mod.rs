@@ -9,11 +9,13 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
+use zksync_config::configs::failure_policy::FailurePolicy;
 use zksync_storage::ConnectionPool;
 use zksync_types::{Token, TokenId, TokenLike, TokenPrice};
 
 pub mod coingecko;
 pub mod coinmarkercap;
+pub mod price_checker;
 
 const API_PRICE_EXPIRATION_TIME_SECS: i64 = 300; // 5 mins
 const HISTORICAL_PRICE_EXPIRATION_TIME: Duration = Duration::from_secs(60);
@@ -23,6 +25,14 @@ pub const REQUEST_TIMEOUT: Duration = Duration::from_millis(700);
 /// Configuration parameter of the reqwest Client
 pub const CONNECTION_TIMEOUT: Duration = Duration::from_millis(700);
 
+/// Returns `true` if the price is too old to be used without trying to refresh it.
+pub(crate) fn is_price_expired(price: &TokenPrice) -> bool {
+    Utc::now()
+        .signed_duration_since(price.last_updated)
+        .num_seconds()
+        > API_PRICE_EXPIRATION_TIME_SECS
+}
+
 #[async_trait]
 pub trait TokenPriceAPI {
     async fn get_price(&self, token_symbol: &str) -> Result<TokenPrice, PriceError>;
@@ -57,10 +67,7 @@ impl TokenCacheEntry {
     }
 
     fn is_price_expired(&self) -> bool {
-        Utc::now()
-            .signed_duration_since(self.price.last_updated)
-            .num_seconds()
-            > API_PRICE_EXPIRATION_TIME_SECS
+        is_price_expired(&self.price)
     }
 
     fn is_cache_entry_expired(&self) -> bool {
@@ -82,6 +89,8 @@ pub(super) struct TickerApi<T: TokenPriceAPI> {
     gas_price_cache: Arc<Mutex<Option<(BigUint, Instant)>>>,
 
     token_price_api: T,
+    /// Whether the last known price is used once the price sources are not available.
+    failure_policy: FailurePolicy,
 }
 
 impl<T: TokenPriceAPI> TickerApi<T> {
@@ -93,6 +102,14 @@ impl<T: TokenPriceAPI> TickerApi<T> {
             price_cache: Default::default(),
             gas_price_cache: Default::default(),
             token_price_api,
+            failure_policy: FailurePolicy::Open,
+        }
+    }
+
+    pub fn with_failure_policy(self, failure_policy: FailurePolicy) -> Self {
+        Self {
+            failure_policy,
+            ..self
         }
     }
     pub fn with_token_db_cache(self, token_db_cache: TokenDBCache) -> Self {
@@ -248,8 +265,16 @@ impl<T: TokenPriceAPI + Send + Sync> FeeTickerAPI for TickerApi<T> {
                     last_updated: Utc::now(),
                 });
             }
+            // The price sources disagree, so the historical price can't be relied on either.
+            Err(err @ PriceError::UnreliablePrice(_)) => {
+                return Err(err);
+            }
             Err(e) => {
                 vlog::warn!("Failed to get price: {}", e);
+                if !self.failure_policy.is_open() {
+                    metrics::histogram!("ticker.get_last_quote", start.elapsed());
+                    return Err(e);
+                }
             }
         }
 
@@ -259,6 +284,7 @@ impl<T: TokenPriceAPI + Send + Sync> FeeTickerAPI for TickerApi<T> {
             .map_err(|e| vlog::warn!("Failed to get historical ticker price: {}", e));
 
         if let Ok(Some(historical_price)) = historical_price {
+            metrics::counter!("ticker.price_feed.failed_open", 1);
             self.update_stored_value(token.id, historical_price.clone(), true)
                 .await;
             metrics::histogram!("ticker.get_last_quote", start.elapsed());
@@ -0,0 +1,114 @@
+// External deps
+use async_trait::async_trait;
+use num::{rational::Ratio, BigUint, Zero};
+// Workspace deps
+use zksync_config::configs::failure_policy::FailurePolicy;
+use zksync_types::TokenPrice;
+use zksync_utils::ratio_to_big_decimal;
+// Local deps
+use super::TokenPriceAPI;
+use crate::fee_ticker::PriceError;
+
+/// Token price source cross-checking the prices with the secondary source.
+///
+/// Since the fees are calculated from the USD prices of both ETH and the fee token, checking every
+/// quote keeps the token/ETH ratio within the bounds as well. The price diverging from the secondary
+/// one by more than the allowed threshold is rejected with `PriceError::UnreliablePrice`, so the fee
+/// quoting for the token is suspended until the sources agree again. If the secondary source
+/// doesn't list the token, the price is accepted as is. If the secondary source is not available,
+/// the price is accepted unchecked or rejected depending on the price feed failure policy.
+/// If the secondary source fails to initialize on start, it's not set and the prices aren't checked.
+#[derive(Debug, Clone)]
+pub struct PriceSanityChecker<P, S> {
+    primary: P,
+    secondary: Option<S>,
+    max_divergence: Ratio<BigUint>,
+    failure_policy: FailurePolicy,
+}
+
+impl<P, S> PriceSanityChecker<P, S> {
+    /// Creates the price source, the prices are not checked if the secondary source is not set.
+    pub fn new(
+        primary: P,
+        secondary: Option<S>,
+        max_divergence: Ratio<BigUint>,
+        failure_policy: FailurePolicy,
+    ) -> Self {
+        Self {
+            primary,
+            secondary,
+            max_divergence,
+            failure_policy,
+        }
+    }
+}
+
+/// Returns the relative divergence of the prices, `None` if only one of them is zero.
+fn price_divergence(price: &Ratio<BigUint>, reference: &Ratio<BigUint>) -> Option<Ratio<BigUint>> {
+    let (lower, upper) = if price < reference {
+        (price, reference)
+    } else {
+        (reference, price)
+    };
+    if lower.is_zero() {
+        return if upper.is_zero() {
+            Some(Ratio::zero())
+        } else {
+            None
+        };
+    }
+
+    Some((upper - lower) / lower)
+}
+
+#[async_trait]
+impl<P, S> TokenPriceAPI for PriceSanityChecker<P, S>
+where
+    P: TokenPriceAPI + Send + Sync,
+    S: TokenPriceAPI + Send + Sync,
+{
+    async fn get_price(&self, token_symbol: &str) -> Result<TokenPrice, PriceError> {
+        let price = self.primary.get_price(token_symbol).await?;
+        let secondary = match &self.secondary {
+            Some(secondary) => secondary,
+            None => return Ok(price),
+        };
+
+        let reference = match secondary.get_price(token_symbol).await {
+            Ok(reference) => reference,
+            Err(PriceError::TokenNotFound(_)) => return Ok(price),
+            Err(err) => {
+                vlog::warn!(
+                    "Failed to cross-check the price of {} with the secondary source: {}",
+                    token_symbol,
+                    err
+                );
+                metrics::counter!("ticker.price_sanity_check.unavailable", 1);
+                if self.failure_policy.is_open() {
+                    return Ok(price);
+                }
+                return Err(PriceError::api_error(format!(
+                    "price of {} can't be cross-checked with the secondary source",
+                    token_symbol
+                )));
+            }
+        };
+
+        match price_divergence(&price.usd_price, &reference.usd_price) {
+            Some(divergence) if divergence <= self.max_divergence => Ok(price),
+            _ => {
+                vlog::error!(
+                    "Price of {} diverges from the secondary source: {} vs {} USD, fee quoting is suspended",
+                    token_symbol,
+                    ratio_to_big_decimal(&price.usd_price, 6),
+                    ratio_to_big_decimal(&reference.usd_price, 6)
+                );
+                metrics::counter!("ticker.price_sanity_check.diverged", 1);
+                Err(PriceError::unreliable_price(format!(
+                    "price of {} diverges from the secondary source",
+                    token_symbol
+                )))
+            }
+        }
+    }
+}
@@ -1,29 +1,49 @@
 //! Additional methods gathering the information required
 //! by ticker for operating.
 
+// Built-in deps
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 // External deps
 use async_trait::async_trait;
+use tokio::sync::Mutex;
 // Workspace deps
 use zksync_storage::ConnectionPool;
-use zksync_types::Address;
+use zksync_types::{withdrawal_gas::WithdrawalGasCost, Address, TokenId};
 // Local deps
 
+/// Measured withdrawal gas costs are updated once the `executeBlocks` transactions are confirmed,
+/// so they are loaded at most once per this period instead of on every fee quote.
+const WITHDRAWAL_GAS_COST_EXPIRATION_TIME: Duration = Duration::from_secs(300);
+
 /// Api responsible for querying for TokenPrices
 #[async_trait]
 pub trait FeeTickerInfo {
     /// Check whether account exists in the zkSync network or not.
     /// Returns `true` if account does not yet exist in the zkSync network.
     async fn is_account_new(&mut self, address: Address) -> bool;
+
+    /// Returns the withdrawal gas cost of the token measured from the completed withdrawals,
+    /// if any withdrawals of the token were measured.
+    async fn withdrawal_gas_cost(
+        &mut self,
+        token_id: TokenId,
+    ) -> anyhow::Result<Option<WithdrawalGasCost>>;
 }
 
 #[derive(Clone)]
 pub struct TickerInfo {
     db: ConnectionPool,
+    withdrawal_gas_cost_cache: Arc<Mutex<HashMap<TokenId, (Option<WithdrawalGasCost>, Instant)>>>,
 }
 
 impl TickerInfo {
     pub fn new(db: ConnectionPool) -> Self {
-        Self { db }
+        Self {
+            db,
+            withdrawal_gas_cost_cache: Default::default(),
+        }
     }
 }
 
@@ -46,4 +66,26 @@ impl FeeTickerInfo for TickerInfo {
         // If account is `Some(_)` then it's not new.
         account_state.committed.is_none()
     }
+
+    async fn withdrawal_gas_cost(
+        &mut self,
+        token_id: TokenId,
+    ) -> anyhow::Result<Option<WithdrawalGasCost>> {
+        let mut cache = self.withdrawal_gas_cost_cache.lock().await;
+        if let Some((cost, cache_time)) = cache.get(&token_id) {
+            if cache_time.elapsed() < WITHDRAWAL_GAS_COST_EXPIRATION_TIME {
+                return Ok(cost.clone());
+            }
+        }
+
+        let cost = self
+            .db
+            .access_storage()
+            .await?
+            .tokens_schema()
+            .get_withdrawal_gas_cost(token_id)
+            .await?;
+        cache.insert(token_id, (cost.clone(), Instant::now()));
+        Ok(cost)
+    }
 }
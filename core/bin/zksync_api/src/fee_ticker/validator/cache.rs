@@ -3,7 +3,10 @@ use std::{collections::HashMap, sync::Arc};
 use tokio::sync::Mutex;
 
 use zksync_storage::ConnectionPool;
-use zksync_types::{tokens::TokenMarketVolume, Token, TokenId, TokenLike};
+use zksync_types::{
+    tokens::{TokenFlags, TokenMarketVolume},
+    Token, TokenId, TokenLike,
+};
 
 use crate::utils::token_db_cache::TokenDBCache;
 
@@ -23,6 +26,7 @@ pub(crate) struct TokenInDBCache {
 pub(crate) struct TokenInMemoryCache {
     tokens: Arc<Mutex<HashMap<TokenLike, Token>>>,
     market: Arc<Mutex<HashMap<TokenId, TokenMarketVolume>>>,
+    flags: Arc<Mutex<HashMap<TokenId, TokenFlags>>>,
 }
 
 impl TokenInDBCache {
@@ -50,6 +54,13 @@ impl TokenInMemoryCache {
             ..self
         }
     }
+
+    pub fn with_flags(self, flags: HashMap<TokenId, TokenFlags>) -> Self {
+        Self {
+            flags: Arc::new(Mutex::new(flags)),
+            ..self
+        }
+    }
 }
 
 impl From<TokenInMemoryCache> for TokenCacheWrapper {
@@ -77,6 +88,24 @@ impl TokenCacheWrapper {
         }
     }
 
+    pub async fn get_token_flags(&self, token_id: TokenId) -> anyhow::Result<TokenFlags> {
+        match self {
+            Self::DB(cache) => {
+                cache
+                    .inner
+                    .get_token_flags(&mut cache.pool.access_storage().await?, token_id)
+                    .await
+            }
+            Self::Memory(cache) => Ok(cache
+                .flags
+                .lock()
+                .await
+                .get(&token_id)
+                .copied()
+                .unwrap_or_default()),
+        }
+    }
+
     pub async fn get_token_market_volume(
         &self,
         token_id: TokenId,
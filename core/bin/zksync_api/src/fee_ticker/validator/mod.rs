@@ -15,7 +15,7 @@ use chrono::Utc;
 
 // Workspace uses
 use zksync_types::{
-    tokens::{Token, TokenLike, TokenMarketVolume},
+    tokens::{FeeTokenIneligibility, Token, TokenLike, TokenMarketVolume},
     Address,
 };
 
@@ -134,17 +134,34 @@ impl<W: TokenWatcher> FeeTokenValidator<W> {
     pub(crate) async fn token_allowed(&mut self, token: TokenLike) -> anyhow::Result<bool> {
         let token = self.resolve_token(token).await?;
         if let Some(token) = token {
-            if self.unconditionally_valid.contains(&token.address) {
-                return Ok(true);
-            }
-            self.check_token(token).await
+            Ok(self.fee_ineligibility(token).await?.is_none())
         } else {
             // Unknown tokens aren't suitable for our needs, obviously.
             Ok(false)
         }
     }
 
-    async fn resolve_token(&self, token: TokenLike) -> anyhow::Result<Option<Token>> {
+    /// Returns the reason the token can't be used to pay fees, or `None` if it can.
+    pub(crate) async fn fee_ineligibility(
+        &mut self,
+        token: Token,
+    ) -> anyhow::Result<Option<FeeTokenIneligibility>> {
+        // Settings set by the operator take precedence over the market volume.
+        let flags = self.tokens_cache.get_token_flags(token.id).await?;
+        if flags.disabled {
+            return Ok(Some(FeeTokenIneligibility::OperatorDisabled));
+        }
+        if flags.fee_eligible || self.unconditionally_valid.contains(&token.address) {
+            return Ok(None);
+        }
+        if self.check_token(token).await? {
+            Ok(None)
+        } else {
+            Ok(Some(FeeTokenIneligibility::InsufficientLiquidity))
+        }
+    }
+
+    pub(crate) async fn resolve_token(&self, token: TokenLike) -> anyhow::Result<Option<Token>> {
         self.tokens_cache.get_token(token).await
     }
 
@@ -209,7 +226,7 @@ mod tests {
     use std::str::FromStr;
     use std::sync::Arc;
     use tokio::sync::Mutex;
-    use zksync_types::TokenId;
+    use zksync_types::{tokens::TokenFlags, TokenId};
 
     #[derive(Clone)]
     struct InMemoryTokenWatcher {
@@ -342,5 +359,69 @@ mod tests {
         assert_eq!(eth_allowed, true);
         assert!(validator.tokens.get(&dai_token_address).unwrap().allowed);
         assert!(!validator.tokens.get(&phnx_token_address).unwrap().allowed);
+        assert_eq!(
+            validator.fee_ineligibility(phnx_token).await.unwrap(),
+            Some(FeeTokenIneligibility::InsufficientLiquidity)
+        );
+    }
+
+    #[tokio::test]
+    async fn token_flags_take_precedence() {
+        let dai_token_address =
+            Address::from_str("6b175474e89094c44da98b954eedeac495271d0f").unwrap();
+        let dai_token = Token::new(TokenId(1), dai_token_address, "DAI", 18);
+        let phnx_token_address =
+            Address::from_str("38A2fDc11f526Ddd5a607C1F251C065f40fBF2f7").unwrap();
+        let phnx_token = Token::new(TokenId(2), phnx_token_address, "PHNX", 18);
+
+        let mut tokens = HashMap::new();
+        tokens.insert(TokenLike::Address(dai_token_address), dai_token.clone());
+        tokens.insert(TokenLike::Address(phnx_token_address), phnx_token.clone());
+        let mut flags = HashMap::new();
+        // Disabled token isn't allowed even if it's unconditionally valid.
+        flags.insert(
+            dai_token.id,
+            TokenFlags {
+                disabled: true,
+                fee_eligible: false,
+            },
+        );
+        // Fee eligible token is allowed without checking its market volume.
+        flags.insert(
+            phnx_token.id,
+            TokenFlags {
+                disabled: false,
+                fee_eligible: true,
+            },
+        );
+        let mut unconditionally_valid = HashSet::new();
+        unconditionally_valid.insert(dai_token_address);
+
+        let cache = TokenInMemoryCache::new()
+            .with_tokens(tokens)
+            .with_flags(flags);
+        let watcher = InMemoryTokenWatcher {
+            amounts: Arc::new(Mutex::new(HashMap::new())),
+        };
+        let mut validator = FeeTokenValidator::new(
+            cache,
+            chrono::Duration::seconds(100),
+            BigDecimal::from(100),
+            unconditionally_valid,
+            watcher,
+        );
+
+        assert!(!validator
+            .token_allowed(TokenLike::Address(dai_token_address))
+            .await
+            .unwrap());
+        assert!(validator
+            .token_allowed(TokenLike::Address(phnx_token_address))
+            .await
+            .unwrap());
+        assert_eq!(
+            validator.fee_ineligibility(dai_token).await.unwrap(),
+            Some(FeeTokenIneligibility::OperatorDisabled)
+        );
     }
 }
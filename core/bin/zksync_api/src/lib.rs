@@ -2,9 +2,10 @@
 
 use crate::{api_server::start_api_server, fee_ticker::run_ticker_task};
 use futures::channel::mpsc;
-use zksync_config::ZkSyncConfig;
+use zksync_config::{ConfigReloader, ZkSyncConfig};
 use zksync_eth_client::EthereumGateway;
 use zksync_storage::ConnectionPool;
+use zksync_utils::supervisor::Supervisor;
 
 pub mod api_server;
 pub mod core_api_client;
@@ -20,11 +21,19 @@ pub fn run_api(
     panic_notify: mpsc::Sender<bool>,
     eth_gateway: EthereumGateway,
     config: &ZkSyncConfig,
+    config_reloader: &ConfigReloader,
+    supervisor: &Supervisor,
 ) -> tokio::task::JoinHandle<()> {
     let channel_size = 32768;
     let (ticker_request_sender, ticker_request_receiver) = mpsc::channel(channel_size);
 
-    let ticker_task = run_ticker_task(connection_pool.clone(), ticker_request_receiver, config);
+    let ticker_task = run_ticker_task(
+        connection_pool.clone(),
+        ticker_request_receiver,
+        config,
+        config_reloader,
+        supervisor,
+    );
 
     start_api_server(
         connection_pool,
@@ -32,6 +41,8 @@ pub fn run_api(
         ticker_request_sender,
         eth_gateway,
         config,
+        config_reloader.clone(),
+        supervisor.clone(),
     );
 
     ticker_task
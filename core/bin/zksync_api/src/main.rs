@@ -1,17 +1,19 @@
 use futures::{channel::mpsc, executor::block_on, SinkExt, StreamExt};
 use std::cell::RefCell;
 use zksync_api::run_api;
-use zksync_config::ZkSyncConfig;
+use zksync_config::{ConfigReloader, ZkSyncConfig};
 use zksync_eth_client::EthereumGateway;
 use zksync_gateway_watcher::run_gateway_watcher_if_multiplexed;
 use zksync_prometheus_exporter::run_prometheus_exporter;
 use zksync_storage::ConnectionPool;
+use zksync_utils::supervisor::Supervisor;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let _sentry_guard = vlog::init();
     // handle ctrl+c
     let config = ZkSyncConfig::from_env();
+    config.validate()?;
     let (stop_signal_sender, mut stop_signal_receiver) = mpsc::channel(256);
     {
         let stop_signal_sender = RefCell::new(stop_signal_sender.clone());
@@ -30,7 +32,17 @@ async fn main() -> anyhow::Result<()> {
 
     let gateway_watcher_task_opt = run_gateway_watcher_if_multiplexed(eth_gateway.clone(), &config);
 
-    let task_handle = run_api(connection_pool, stop_signal_sender, eth_gateway, &config);
+    let config_reloader = ConfigReloader::new(&config);
+    config_reloader.clone().reload_on_sighup();
+
+    let task_handle = run_api(
+        connection_pool,
+        stop_signal_sender,
+        eth_gateway,
+        &config,
+        &config_reloader,
+        &Supervisor::default(),
+    );
 
     tokio::select! {
         _ = async { task_handle.await } => {
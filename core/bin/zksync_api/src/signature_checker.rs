@@ -3,6 +3,10 @@
 //! Main routine of this module operates a multithreaded event loop,
 //! which is used to spawn concurrent tasks to efficiently check the
 //! transactions signatures.
+//!
+//! zkSync signatures of the requests queued at the same moment are verified
+//! in one batch on the `rayon` thread pool, split into the chunks of transactions,
+//! and only Ethereum-related checks are performed by the concurrent tasks.
 
 // Built-in uses
 use std::collections::HashSet;
@@ -13,6 +17,7 @@ use futures::{
     channel::{mpsc, oneshot},
     StreamExt,
 };
+use rayon::prelude::*;
 use tokio::runtime::{Builder, Handle};
 // Workspace uses
 use zksync_types::{
@@ -32,6 +37,22 @@ pub enum TxVariant {
     Batch(Vec<SignedZkSyncTx>, Option<EthBatchSignData>),
 }
 
+impl TxVariant {
+    fn txs(&self) -> &[SignedZkSyncTx] {
+        match self {
+            TxVariant::Tx(tx) => std::slice::from_ref(tx),
+            TxVariant::Batch(txs, _) => txs,
+        }
+    }
+
+    fn txs_mut(&mut self) -> &mut [SignedZkSyncTx] {
+        match self {
+            TxVariant::Tx(tx) => std::slice::from_mut(tx),
+            TxVariant::Batch(txs, _) => txs,
+        }
+    }
+}
+
 /// Wrapper on a `TxVariant` which guarantees that (a batch of)
 /// transaction(s) was checked and signatures associated with
 /// this transactions are correct.
@@ -48,9 +69,23 @@ impl VerifiedTx {
         request_data: RequestData,
         eth_checker: &EthereumChecker,
     ) -> Result<Self, TxAddError> {
-        verify_eth_signature(&request_data, eth_checker).await?;
         let mut tx_variant = request_data.get_tx_variant();
-        verify_tx_correctness(&mut tx_variant)?;
+        let tx_correctness = verify_tx_correctness(&mut tx_variant);
+
+        Self::verify_checked(request_data, tx_variant, tx_correctness, eth_checker).await
+    }
+
+    /// Same as `verify`, but takes the result of the `ZKSync` correctness check
+    /// performed beforehand, e.g. in a batch with other requests.
+    async fn verify_checked(
+        request_data: RequestData,
+        tx_variant: TxVariant,
+        tx_correctness: Result<(), TxAddError>,
+        eth_checker: &EthereumChecker,
+    ) -> Result<Self, TxAddError> {
+        verify_eth_signature(&request_data, eth_checker).await?;
+        verify_withdrawals(&request_data, eth_checker).await?;
+        tx_correctness?;
 
         Ok(Self(tx_variant))
     }
@@ -89,6 +124,7 @@ async fn verify_eth_signature(
                 &request.tx,
                 request.sender,
                 request.token.clone(),
+                request.allow_old_messages,
                 eth_checker,
             )
             .await?;
@@ -102,14 +138,28 @@ async fn verify_eth_signature(
                 return Err(TxAddError::Other);
             }
             if let Some(batch_sign_data) = &request.batch_sign_data {
-                verify_eth_signature_txs_batch(txs, accounts, batch_sign_data, eth_checker).await?;
+                verify_eth_signature_txs_batch(
+                    txs,
+                    accounts,
+                    batch_sign_data,
+                    request.allow_old_messages,
+                    eth_checker,
+                )
+                .await?;
             }
             // In case there're signatures provided for some of transactions
             // we still verify them.
             for ((tx, &account), token) in
                 txs.iter().zip(accounts.iter()).zip(tokens.iter().cloned())
             {
-                verify_eth_signature_single_tx(tx, account, token, eth_checker).await?;
+                verify_eth_signature_single_tx(
+                    tx,
+                    account,
+                    token,
+                    request.allow_old_messages,
+                    eth_checker,
+                )
+                .await?;
             }
         }
     }
@@ -117,6 +167,29 @@ async fn verify_eth_signature(
     Ok(())
 }
 
+/// Checks that the token contracts won't block the withdrawals of the (batch of) transaction(s).
+async fn verify_withdrawals(
+    request_data: &RequestData,
+    eth_checker: &EthereumChecker,
+) -> Result<(), TxAddError> {
+    let txs: Vec<(&SignedZkSyncTx, &Token)> = match request_data {
+        RequestData::Tx(request) => vec![(&request.tx, &request.token)],
+        RequestData::Batch(request) => request.txs.iter().zip(request.tokens.iter()).collect(),
+    };
+    for (tx, token) in txs {
+        let recipient = match &tx.tx {
+            ZkSyncTx::Withdraw(tx) => tx.to,
+            ZkSyncTx::ForcedExit(tx) => tx.target,
+            _ => continue,
+        };
+        eth_checker
+            .check_withdrawal_restrictions(token.address, recipient)
+            .await?;
+    }
+
+    Ok(())
+}
+
 /// Given a single Ethereum signature and a message, checks that it
 /// was signed by an expected address.
 async fn verify_ethereum_signature(
@@ -146,6 +219,7 @@ async fn verify_eth_signature_single_tx(
     tx: &SignedZkSyncTx,
     sender_address: Address,
     token: Token,
+    allow_old_messages: bool,
     eth_checker: &EthereumChecker,
 ) -> Result<(), TxAddError> {
     let start = Instant::now();
@@ -174,7 +248,7 @@ async fn verify_eth_signature_single_tx(
         let mut signature_correct =
             verify_ethereum_signature(signature, &sign_data.message, sender_address, eth_checker)
                 .await;
-        if !signature_correct {
+        if !signature_correct && allow_old_messages {
             let old_message = tx.get_old_ethereum_sign_message(token);
             if let Some(message) = old_message {
                 signature_correct = verify_ethereum_signature(
@@ -202,6 +276,7 @@ async fn verify_eth_signature_txs_batch(
     txs: &[SignedZkSyncTx],
     senders: &[Address],
     batch_sign_data: &EthBatchSignData,
+    allow_old_messages: bool,
     eth_checker: &EthereumChecker,
 ) -> Result<(), TxAddError> {
     let start = Instant::now();
@@ -227,7 +302,7 @@ async fn verify_eth_signature_txs_batch(
                 eth_checker,
             )
             .await;
-            if !signature_correct {
+            if !signature_correct && allow_old_messages {
                 signature_correct = verify_ethereum_signature(
                     signature,
                     old_message.as_slice(),
@@ -254,22 +329,74 @@ async fn verify_eth_signature_txs_batch(
     Ok(())
 }
 
+/// Checks the correctness of the ZKSync transactions (including the signature check),
+/// returns whether each of them is correct. Verification of a single signature is short,
+/// so the transactions are split between the `rayon` tasks in chunks rather than one by one.
+fn check_txs_correctness(txs: &mut [&mut SignedZkSyncTx]) -> Vec<bool> {
+    txs.par_chunks_mut(VERIFY_CHUNK_SIZE)
+        .map(|chunk| {
+            chunk
+                .iter_mut()
+                .map(|tx| tx.tx.check_correctness())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// Converts the correctness of the transactions of the variant into the check result.
+fn tx_correctness(tx: &TxVariant, txs_correct: &[bool]) -> Result<(), TxAddError> {
+    match (tx, txs_correct.iter().position(|correct| !correct)) {
+        (_, None) => Ok(()),
+        (TxVariant::Tx(_), Some(_)) => Err(TxAddError::IncorrectTx),
+        (TxVariant::Batch(..), Some(position)) => Err(TxAddError::IncorrectBatchTx(position)),
+    }
+}
+
 /// Verifies the correctness of the ZKSync transaction(s) (including the
 /// signature check).
 fn verify_tx_correctness(tx: &mut TxVariant) -> Result<(), TxAddError> {
-    match tx {
-        TxVariant::Tx(tx) => {
-            if !tx.tx.check_correctness() {
-                return Err(TxAddError::IncorrectTx);
-            }
-        }
-        TxVariant::Batch(batch, _) => {
-            if batch.iter_mut().any(|tx| !tx.tx.check_correctness()) {
-                return Err(TxAddError::IncorrectTx);
-            }
-        }
-    }
-    Ok(())
+    let txs_correct = check_txs_correctness(&mut tx.txs_mut().iter_mut().collect::<Vec<_>>());
+    tx_correctness(tx, &txs_correct)
+}
+
+/// Verifies the correctness of the ZKSync transactions of several requests at once.
+/// Returns the checked transactions (with the cached signers) together with the check results.
+fn verify_txs_correctness_batch(
+    mut tx_variants: Vec<TxVariant>,
+) -> Vec<(TxVariant, Result<(), TxAddError>)> {
+    let start = Instant::now();
+    let batch_size = tx_variants.len();
+    // The transactions of all the requests are split into chunks together,
+    // so the small requests don't take a task each.
+    let txs_correct = {
+        let mut txs: Vec<_> = tx_variants
+            .iter_mut()
+            .flat_map(|tx_variant| tx_variant.txs_mut().iter_mut())
+            .collect();
+        check_txs_correctness(&mut txs)
+    };
+
+    let mut offset = 0;
+    let results = tx_variants
+        .into_iter()
+        .map(|tx_variant| {
+            let txs_count = tx_variant.txs().len();
+            let tx_correctness =
+                tx_correctness(&tx_variant, &txs_correct[offset..offset + txs_count]);
+            offset += txs_count;
+            (tx_variant, tx_correctness)
+        })
+        .collect();
+
+    metrics::histogram!(
+        "signature_checker.verify_txs_correctness_batch",
+        start.elapsed()
+    );
+    metrics::histogram!("signature_checker.batch_size", batch_size as u64);
+    results
 }
 
 #[derive(Debug)]
@@ -282,6 +409,9 @@ pub struct TxRequest {
     /// Resolved token might be used to obtain old-formatted 2-FA messages.
     /// Needed for backwards compatibility.
     pub token: Token,
+    /// Whether the old-formatted 2-FA messages are accepted. They're not bound
+    /// to the network, so they're rejected once the signature domain is required.
+    pub allow_old_messages: bool,
 }
 
 #[derive(Debug)]
@@ -290,6 +420,8 @@ pub struct BatchRequest {
     pub batch_sign_data: Option<EthBatchSignData>,
     pub senders: Vec<Address>,
     pub tokens: Vec<Token>,
+    /// See `TxRequest::allow_old_messages`.
+    pub allow_old_messages: bool,
 }
 
 /// Request for the signature check.
@@ -317,14 +449,20 @@ impl RequestData {
     }
 }
 
+/// Maximum number of requests which zkSync signatures are verified in one batch.
+const MAX_VERIFY_BATCH_SIZE: usize = 64;
+/// Number of the transactions verified sequentially by one task of the `rayon` thread pool.
+const VERIFY_CHUNK_SIZE: usize = 16;
+
 /// Main routine of the concurrent signature checker.
 /// See the module documentation for details.
 pub fn start_sign_checker_detached(
     client: EthereumGateway,
+    withdrawal_checked_tokens: HashSet<Address>,
     input: mpsc::Receiver<VerifySignatureRequest>,
     panic_notify: mpsc::Sender<bool>,
 ) {
-    let eth_checker = EthereumChecker::new(client);
+    let eth_checker = EthereumChecker::new(client, withdrawal_checked_tokens);
 
     /// Main signature check requests handler.
     /// Basically it receives the requests through the channel and verifies signatures,
@@ -334,13 +472,37 @@ pub fn start_sign_checker_detached(
         mut input: mpsc::Receiver<VerifySignatureRequest>,
         eth_checker: EthereumChecker,
     ) {
-        while let Some(VerifySignatureRequest { data, response }) = input.next().await {
-            let eth_checker = eth_checker.clone();
-            handle.spawn(async move {
-                let resp = VerifiedTx::verify(data, &eth_checker).await;
+        while let Some(request) = input.next().await {
+            // Take the requests that are already queued, so their zkSync signatures
+            // are verified in one batch.
+            let mut requests = vec![request];
+            while requests.len() < MAX_VERIFY_BATCH_SIZE {
+                match input.try_next() {
+                    Ok(Some(request)) => requests.push(request),
+                    _ => break,
+                }
+            }
+
+            let tx_variants = requests
+                .iter()
+                .map(|request| request.data.get_tx_variant())
+                .collect();
+            let checked_txs = handle
+                .spawn_blocking(move || verify_txs_correctness_batch(tx_variants))
+                .await
+                .expect("Signature verification task panicked");
+
+            for (request, (tx_variant, tx_correctness)) in requests.into_iter().zip(checked_txs) {
+                let VerifySignatureRequest { data, response } = request;
+                let eth_checker = eth_checker.clone();
+                handle.spawn(async move {
+                    let resp =
+                        VerifiedTx::verify_checked(data, tx_variant, tx_correctness, &eth_checker)
+                            .await;
 
-                response.send(resp).unwrap_or_default();
-            });
+                    response.send(resp).unwrap_or_default();
+                });
+            }
         }
     }
 
@@ -359,3 +521,96 @@ pub fn start_sign_checker_detached(
         })
         .expect("failed to start signature checker thread");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_test_account::ZkSyncAccount;
+    use zksync_types::{AccountId, TokenId};
+
+    fn signed_transfers(count: usize) -> Vec<SignedZkSyncTx> {
+        let from = ZkSyncAccount::rand();
+        from.set_account_id(Some(AccountId(1)));
+        let to = ZkSyncAccount::rand();
+
+        (0..count)
+            .map(|_| {
+                let (transfer, _) = from.sign_transfer(
+                    TokenId(0),
+                    "ETH",
+                    100u64.into(),
+                    10u64.into(),
+                    &to.address,
+                    None,
+                    true,
+                    Default::default(),
+                );
+                SignedZkSyncTx {
+                    tx: ZkSyncTx::Transfer(Box::new(transfer)),
+                    eth_sign_data: None,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn batch_verification_reports_incorrect_tx() {
+        let mut txs = signed_transfers(4);
+        if let ZkSyncTx::Transfer(transfer) = &mut txs[2].tx {
+            transfer.amount += 1u64;
+        }
+
+        let tx_variants = vec![
+            TxVariant::Tx(txs[0].clone()),
+            TxVariant::Batch(txs[..2].to_vec(), None),
+            TxVariant::Batch(txs, None),
+        ];
+        let results: Vec<_> = verify_txs_correctness_batch(tx_variants)
+            .into_iter()
+            .map(|(_, result)| result.map_err(|err| err.to_string()))
+            .collect();
+
+        assert_eq!(
+            results,
+            vec![
+                Ok(()),
+                Ok(()),
+                Err(TxAddError::IncorrectBatchTx(2).to_string())
+            ]
+        );
+    }
+
+    /// Checks that the results are matched with the requests when their transactions
+    /// are split into several chunks.
+    #[test]
+    fn batch_verification_across_chunks() {
+        let mut txs = signed_transfers(VERIFY_CHUNK_SIZE + 3);
+        let last = txs.len() - 1;
+        if let ZkSyncTx::Transfer(transfer) = &mut txs[last].tx {
+            transfer.amount += 1u64;
+        }
+
+        let tx_variants = vec![
+            TxVariant::Batch(txs[..VERIFY_CHUNK_SIZE - 1].to_vec(), None),
+            TxVariant::Tx(txs[VERIFY_CHUNK_SIZE - 1].clone()),
+            TxVariant::Tx(txs[VERIFY_CHUNK_SIZE].clone()),
+            TxVariant::Batch(txs[VERIFY_CHUNK_SIZE + 1..].to_vec(), None),
+            TxVariant::Tx(txs[last].clone()),
+        ];
+        let results: Vec<_> = verify_txs_correctness_batch(tx_variants)
+            .into_iter()
+            .map(|(_, result)| result.map_err(|err| err.to_string()))
+            .collect();
+
+        assert_eq!(
+            results,
+            vec![
+                Ok(()),
+                Ok(()),
+                Ok(()),
+                Err(TxAddError::IncorrectBatchTx(1).to_string()),
+                Err(TxAddError::IncorrectTx.to_string()),
+            ]
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use zksync_types::{api_error::ApiErrorCode, TokenId};
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Error)]
 pub enum TxAddError {
@@ -9,6 +10,9 @@ pub enum TxAddError {
     #[error("Tx is incorrect")]
     IncorrectTx,
 
+    #[error("Tx #{0} of the batch is incorrect")]
+    IncorrectBatchTx(usize),
+
     #[error("Transaction fee is too low")]
     TxFeeTooLow,
 
@@ -44,4 +48,70 @@ pub enum TxAddError {
 
     #[error("Too many Ethereum signatures provided")]
     EthSignaturesLimitExceeded,
+
+    #[error("Server is shutting down, try again later")]
+    ShuttingDown,
+
+    #[error("Token transfers are paused by the token contract, withdrawal can't be completed")]
+    WithdrawalTokenPaused,
+
+    #[error("Withdrawal recipient is blacklisted by the token contract")]
+    WithdrawalRecipientBlacklisted,
+
+    #[error("Ethereum node is unavailable, try again later")]
+    EthereumUnavailable,
+
+    #[error("Transfer recipient is screened by the operator")]
+    RecipientScreened,
+
+    #[error("Server is overloaded, try again in {0} seconds")]
+    Overloaded(u64),
+
+    #[error("Account public key is changed too often, try again in {0} seconds")]
+    ChangePubKeyRateLimited(u64),
+
+    #[error("Amount of token {0} exceeds the allowed maximum")]
+    AmountOutOfBounds(TokenId),
+
+    #[error("Fee in token {0} exceeds the allowed maximum")]
+    FeeOutOfBounds(TokenId),
+
+    #[error("Account recovery by the guardians is not initiated")]
+    GuardianRecoveryNotInitiated,
+
+    #[error("Timelock of the account recovery passes in {0} seconds")]
+    GuardianRecoveryTimelocked(u64),
+}
+
+impl From<TxAddError> for ApiErrorCode {
+    fn from(error: TxAddError) -> Self {
+        match error {
+            TxAddError::NonceMismatch => Self::NonceMismatch,
+            TxAddError::IncorrectTx => Self::IncorrectTx,
+            TxAddError::IncorrectBatchTx(_) => Self::IncorrectBatchTx,
+            TxAddError::TxFeeTooLow => Self::TxFeeTooLow,
+            TxAddError::TxBatchFeeTooLow => Self::TxBatchFeeTooLow,
+            TxAddError::MissingEthSignature => Self::MissingEthSignature,
+            TxAddError::EIP1271SignatureVerificationFail => Self::EIP1271SignatureVerificationFail,
+            TxAddError::IncorrectEthSignature => Self::IncorrectEthSignature,
+            TxAddError::ChangePkNotAuthorized => Self::ChangePkNotAuthorized,
+            TxAddError::Other => Self::Internal,
+            TxAddError::DbError => Self::StorageUnavailable,
+            TxAddError::EmptyBatch => Self::EmptyBatch,
+            TxAddError::BatchTooBig => Self::BatchTooBig,
+            TxAddError::BatchWithdrawalsOverload => Self::BatchWithdrawalsOverload,
+            TxAddError::EthSignaturesLimitExceeded => Self::EthSignaturesLimitExceeded,
+            TxAddError::ShuttingDown => Self::ShuttingDown,
+            TxAddError::WithdrawalTokenPaused => Self::WithdrawalTokenPaused,
+            TxAddError::WithdrawalRecipientBlacklisted => Self::WithdrawalRecipientBlacklisted,
+            TxAddError::EthereumUnavailable => Self::EthereumUnavailable,
+            TxAddError::RecipientScreened => Self::RecipientScreened,
+            TxAddError::Overloaded(_) => Self::ServerOverloaded,
+            TxAddError::ChangePubKeyRateLimited(_) => Self::ChangePubKeyRateLimited,
+            TxAddError::AmountOutOfBounds(_) => Self::AmountOutOfBounds,
+            TxAddError::FeeOutOfBounds(_) => Self::FeeOutOfBounds,
+            TxAddError::GuardianRecoveryNotInitiated => Self::GuardianRecoveryNotInitiated,
+            TxAddError::GuardianRecoveryTimelocked(_) => Self::GuardianRecoveryTimelocked,
+        }
+    }
 }
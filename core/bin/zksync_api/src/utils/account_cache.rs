@@ -0,0 +1,112 @@
+//! Cache for the account ID ↔ address lookups.
+//!
+//! The accounts are never removed from the tree and their IDs are never reused (the `Close`
+//! operation is disabled), so once the account is created, its ID and address never change and
+//! these mappings are cached without any expiration. Enabling `Close` would require evicting both
+//! directions of the closed accounts here. The addresses that don't have an account yet, however, may become
+//! outdated, since the account may be created in the next block.
+//!
+//! Such entries are tagged with the version of the accounts set, which is incremented every time
+//! the API server observes an operation creating an account. Entries with an older version are
+//! considered absent.
+
+// Built-in uses
+use std::sync::atomic::{AtomicU64, Ordering};
+// Workspace uses
+use zksync_storage::{QueryResult, StorageProcessor};
+use zksync_types::{block::ExecutedOperations, AccountId, Address, ZkSyncOp};
+// Local uses
+use super::shared_lru_cache::SharedLruCache;
+
+static ACCOUNTS_VERSION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone)]
+pub struct AccountIdCache {
+    ids: SharedLruCache<Address, AccountId>,
+    addresses: SharedLruCache<AccountId, Address>,
+    unknown_addresses: SharedLruCache<Address, u64>,
+}
+
+impl AccountIdCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            ids: SharedLruCache::new(capacity),
+            addresses: SharedLruCache::new(capacity),
+            unknown_addresses: SharedLruCache::new(capacity),
+        }
+    }
+
+    /// Invalidates the entries that may be affected by the executed operations.
+    pub fn handle_executed_operations<'a>(
+        operations: impl IntoIterator<Item = &'a ExecutedOperations>,
+    ) {
+        let affects_accounts = operations.into_iter().any(|operation| {
+            matches!(
+                operation.get_executed_op(),
+                Some(ZkSyncOp::Deposit(_)) | Some(ZkSyncOp::TransferToNew(_))
+            )
+        });
+        if affects_accounts {
+            Self::invalidate_all();
+        }
+    }
+
+    /// Invalidates the mutable entries of all the caches in the process.
+    pub fn invalidate_all() {
+        ACCOUNTS_VERSION.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn version() -> u64 {
+        ACCOUNTS_VERSION.load(Ordering::SeqCst)
+    }
+
+    pub async fn account_id_by_address(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        address: Address,
+    ) -> QueryResult<Option<AccountId>> {
+        if let Some(account_id) = self.ids.get(&address) {
+            return Ok(Some(account_id));
+        }
+        let version = Self::version();
+        if self.unknown_addresses.get(&address) == Some(version) {
+            return Ok(None);
+        }
+
+        let account_id = storage
+            .chain()
+            .account_schema()
+            .account_id_by_address(address)
+            .await?;
+        match account_id {
+            Some(account_id) => self.insert(account_id, address),
+            None => self.unknown_addresses.insert(address, version),
+        }
+        Ok(account_id)
+    }
+
+    pub async fn account_address_by_id(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        account_id: AccountId,
+    ) -> QueryResult<Option<Address>> {
+        if let Some(address) = self.addresses.get(&account_id) {
+            return Ok(Some(address));
+        }
+
+        let address = storage
+            .chain()
+            .account_schema()
+            .account_address_by_id(account_id)
+            .await?;
+        if let Some(address) = address {
+            self.insert(account_id, address);
+        }
+        Ok(address)
+    }
+
+    fn insert(&self, account_id: AccountId, address: Address) {
+        self.ids.insert(address, account_id);
+        self.addresses.insert(account_id, address);
+    }
+}
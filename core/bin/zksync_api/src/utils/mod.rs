@@ -1,3 +1,5 @@
+pub mod account_cache;
 pub mod block_details_cache;
+pub mod shared_counters;
 pub mod shared_lru_cache;
 pub mod token_db_cache;
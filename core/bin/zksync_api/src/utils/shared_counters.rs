@@ -0,0 +1,210 @@
+//! Counters limiting the actions of the API users, e.g. the paid fee subsidies or the number
+//! of submitted transactions.
+//!
+//! When several API server replicas are run behind a load balancer, the counters have to be
+//! shared between them: otherwise each replica would enforce the limits independently and the
+//! actual limits would be multiplied by the number of replicas. In the stateless mode the
+//! counters are kept in Redis if it's configured, since the counters are updated on every
+//! submission and don't have to be durable, or in the database otherwise. In the stateful
+//! mode they are kept in memory.
+//!
+//! The rest of the API server state can safely stay per-instance: the LRU caches only hold
+//! immutable data (e.g. finalized blocks and account IDs), and the subscriptions are bound to
+//! the connection and are notified based on the database state.
+
+// Built-in uses
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+// External uses
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Duration, Utc};
+use redis::aio::MultiplexedConnection;
+
+// Workspace uses
+use zksync_config::configs::api::Common;
+use zksync_storage::{ConnectionPool, QueryResult};
+
+// Local uses
+
+#[derive(Debug, Clone)]
+pub enum SharedCounters {
+    /// Counters are kept in memory and are not shared with the other replicas.
+    Local(Arc<Mutex<HashMap<String, (BigDecimal, DateTime<Utc>)>>>),
+    /// Counters are kept in the database shared by all the replicas.
+    Database(ConnectionPool),
+    /// Counters are kept in Redis shared by all the replicas.
+    Redis(RedisCounters),
+}
+
+impl SharedCounters {
+    pub fn new(pool: ConnectionPool, config: &Common) -> Self {
+        if !config.stateless {
+            return Self::Local(Arc::default());
+        }
+        match config.shared_counters_redis_url() {
+            Some(url) => {
+                Self::Redis(RedisCounters::new(url).expect("Incorrect shared counters Redis URL"))
+            }
+            None => Self::Database(pool),
+        }
+    }
+
+    /// Adds `amount` to the counter and returns its new value. The counter is reset
+    /// once the `period` since its first increment expires.
+    pub async fn increment(
+        &self,
+        key: &str,
+        amount: BigDecimal,
+        period: Duration,
+    ) -> QueryResult<BigDecimal> {
+        match self {
+            Self::Local(counters) => {
+                let now = Utc::now();
+                let mut counters = counters.lock().expect("shared counters lock");
+                let counter = counters
+                    .entry(key.to_owned())
+                    .or_insert_with(|| (BigDecimal::default(), now + period));
+                if counter.1 <= now {
+                    *counter = (BigDecimal::default(), now + period);
+                }
+                counter.0 += amount;
+                Ok(counter.0.clone())
+            }
+            Self::Database(pool) => {
+                let mut storage = pool.access_storage().await?;
+                storage
+                    .counters_schema()
+                    .increment(key, amount, period)
+                    .await
+            }
+            Self::Redis(counters) => counters.increment(key, amount, period).await,
+        }
+    }
+
+    /// Returns the current value of the counter.
+    pub async fn load(&self, key: &str) -> QueryResult<BigDecimal> {
+        match self {
+            Self::Local(counters) => {
+                let counters = counters.lock().expect("shared counters lock");
+                let value = counters
+                    .get(key)
+                    .filter(|(_, expires_at)| *expires_at > Utc::now())
+                    .map(|(value, _)| value.clone())
+                    .unwrap_or_default();
+                Ok(value)
+            }
+            Self::Database(pool) => {
+                let mut storage = pool.access_storage().await?;
+                storage.counters_schema().load(key).await
+            }
+            Self::Redis(counters) => counters.load(key).await,
+        }
+    }
+}
+
+/// Counters kept in Redis. The connection is established on the first use
+/// and is shared by all the clones.
+#[derive(Clone)]
+pub struct RedisCounters {
+    client: redis::Client,
+    connection: Arc<tokio::sync::Mutex<Option<MultiplexedConnection>>>,
+}
+
+impl std::fmt::Debug for RedisCounters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisCounters").finish()
+    }
+}
+
+impl RedisCounters {
+    /// Prefix of the keys, so the counters don't clash with the other data stored in Redis.
+    const KEY_PREFIX: &'static str = "zksync_api:counters:";
+
+    fn new(url: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            client: redis::Client::open(url)?,
+            connection: Arc::default(),
+        })
+    }
+
+    async fn connection(&self) -> QueryResult<MultiplexedConnection> {
+        let mut connection = self.connection.lock().await;
+        if let Some(connection) = connection.as_ref() {
+            return Ok(connection.clone());
+        }
+        let new_connection = self.client.get_multiplexed_tokio_connection().await?;
+        *connection = Some(new_connection.clone());
+        Ok(new_connection)
+    }
+
+    async fn increment(
+        &self,
+        key: &str,
+        amount: BigDecimal,
+        period: Duration,
+    ) -> QueryResult<BigDecimal> {
+        let key = format!("{}{}", Self::KEY_PREFIX, key);
+        let mut connection = self.connection().await?;
+        // The counter expires once the period since its first increment passes.
+        let (value,): (String,) = redis::pipe()
+            .atomic()
+            .cmd("SET")
+            .arg(&key)
+            .arg(0)
+            .arg("PX")
+            .arg(period.num_milliseconds().max(1))
+            .arg("NX")
+            .ignore()
+            .cmd("INCRBYFLOAT")
+            .arg(&key)
+            .arg(amount.to_string())
+            .query_async(&mut connection)
+            .await?;
+        Ok(BigDecimal::from_str(&value)?)
+    }
+
+    async fn load(&self, key: &str) -> QueryResult<BigDecimal> {
+        let key = format!("{}{}", Self::KEY_PREFIX, key);
+        let mut connection = self.connection().await?;
+        let value: Option<String> = redis::cmd("GET")
+            .arg(&key)
+            .query_async(&mut connection)
+            .await?;
+        match value {
+            Some(value) => Ok(BigDecimal::from_str(&value)?),
+            None => Ok(BigDecimal::default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn local_counters() {
+        let counters = SharedCounters::Local(Arc::default());
+        let period = Duration::minutes(1);
+
+        assert_eq!(counters.load("a").await.unwrap(), BigDecimal::from(0));
+        counters.increment("a", 2.into(), period).await.unwrap();
+        let value = counters.increment("a", 3.into(), period).await.unwrap();
+        assert_eq!(value, BigDecimal::from(5));
+        assert_eq!(counters.load("a").await.unwrap(), BigDecimal::from(5));
+        assert_eq!(counters.load("b").await.unwrap(), BigDecimal::from(0));
+
+        // Expired counter is started anew.
+        let value = counters
+            .increment("b", 1.into(), Duration::zero())
+            .await
+            .unwrap();
+        assert_eq!(value, BigDecimal::from(1));
+        assert_eq!(counters.load("b").await.unwrap(), BigDecimal::from(0));
+        let value = counters.increment("b", 4.into(), period).await.unwrap();
+        assert_eq!(value, BigDecimal::from(4));
+    }
+}
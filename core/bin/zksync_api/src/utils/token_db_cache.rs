@@ -1,15 +1,41 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 
 use zksync_storage::StorageProcessor;
-use zksync_types::tokens::TokenMarketVolume;
+use zksync_types::tokens::{TokenFlags, TokenMarketVolume};
 use zksync_types::{Token, TokenId, TokenLike};
 
+/// Version of the token data changed by this process, incremented every time the tokens are
+/// changed via the admin API. Each cache compares it with the version of its entries and drops them
+/// once they are outdated, so the changes are immediately visible to every component of the process.
+static TOKENS_VERSION: AtomicU64 = AtomicU64::new(0);
+
+/// Interval between the checks of the token data version stored in the database, which is
+/// incremented on every change of the tokens. The changes made by the other processes (e.g. by
+/// the admin API of another server instance) become visible after this interval.
+const STORED_VERSION_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Version of the cached entries.
+#[derive(Debug, Default)]
+struct CacheVersion {
+    local: u64,
+    stored: u64,
+    checked_at: Option<Instant>,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct TokenDBCache {
-    // TODO: handle stale entries, edge case when we rename token after adding it (ZKS-97)
     cache: Arc<RwLock<HashMap<TokenLike, Token>>>,
+    flags: Arc<RwLock<HashMap<TokenId, TokenFlags>>>,
+    version: Arc<Mutex<CacheVersion>>,
 }
 
 impl TokenDBCache {
@@ -17,6 +43,38 @@ impl TokenDBCache {
         Self::default()
     }
 
+    /// Drops the cached entries of all the caches in the process.
+    /// Must be called after the tokens are updated in the database.
+    pub fn invalidate_all() {
+        TOKENS_VERSION.fetch_add(1, Ordering::SeqCst);
+    }
+
+    async fn drop_outdated_entries(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<()> {
+        let mut version = self.version.lock().await;
+        let local_version = TOKENS_VERSION.load(Ordering::SeqCst);
+        let stored_version = match version.checked_at {
+            Some(checked_at) if checked_at.elapsed() < STORED_VERSION_CHECK_INTERVAL => {
+                version.stored
+            }
+            _ => {
+                version.checked_at = Some(Instant::now());
+                storage.tokens_schema().load_tokens_version().await?
+            }
+        };
+        if version.local == local_version && version.stored == stored_version {
+            return Ok(());
+        }
+
+        self.cache.write().await.clear();
+        self.flags.write().await.clear();
+        version.local = local_version;
+        version.stored = stored_version;
+        Ok(())
+    }
+
     pub async fn get_token(
         &self,
         storage: &mut StorageProcessor<'_>,
@@ -30,6 +88,7 @@ impl TokenDBCache {
         storage: &mut StorageProcessor<'_>,
         token_query: TokenLike,
     ) -> anyhow::Result<Option<Token>> {
+        self.drop_outdated_entries(storage).await?;
         // Just return token from cache.
         if let Some(token) = self.cache.read().await.get(&token_query) {
             return Ok(Some(token.clone()));
@@ -49,6 +108,22 @@ impl TokenDBCache {
         Ok(token)
     }
 
+    /// Returns the operator-controlled settings of the token.
+    pub async fn get_token_flags(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        token_id: TokenId,
+    ) -> anyhow::Result<TokenFlags> {
+        self.drop_outdated_entries(storage).await?;
+        if let Some(flags) = self.flags.read().await.get(&token_id) {
+            return Ok(*flags);
+        }
+
+        let flags = storage.tokens_schema().get_token_flags(token_id).await?;
+        self.flags.write().await.insert(token_id, flags);
+        Ok(flags)
+    }
+
     pub async fn token_symbol(
         &self,
         storage: &mut StorageProcessor<'_>,
@@ -0,0 +1,566 @@
+//! Audit replay re-executes all the stored blocks from genesis to check that the storage
+//! hasn't been silently corrupted.
+//!
+//! Every block is re-executed on top of the replayed state, and the resulting state root is
+//! checked against the root stored for the block and, if the block is committed, against
+//! the root committed to L1. Block commitments are recomputed from the replayed roots the
+//! same way the zkSync contract does. Once all the blocks are replayed, the replayed accounts
+//! are checked against the stored committed state.
+//!
+//! The replay stops at the first state root divergence, since all the following blocks
+//! would diverge as well. The report is signed by the operator key, so it can be attributed
+//! to the operator once it's published.
+
+// Built-in uses
+use std::collections::HashMap;
+// External uses
+use anyhow::format_err;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+// Workspace uses
+use zksync_config::ZkSyncConfig;
+use zksync_eth_client::EthereumGateway;
+use zksync_state::state::ZkSyncState;
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::{
+    aggregated_operations::AggregatedActionType,
+    block::{Block, ExecutedOperations},
+    tx::PackedEthSignature,
+    Account, AccountId, Address, BlockNumber, H256,
+};
+// Local uses
+use crate::l1_state_verifier::{decode_committed_blocks, L1CommittedBlock};
+
+/// Max amount of the diverged accounts listed in the report.
+const MAX_REPORTED_ACCOUNTS: usize = 100;
+
+/// Invariant violated by the stored data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DivergenceKind {
+    /// Genesis state doesn't match the stored genesis block.
+    GenesisRoot,
+    /// Operation that succeeded in the block fails on replay.
+    OperationReplay,
+    /// Replayed state root doesn't match the stored one.
+    StoredRoot,
+    /// Recomputed block commitment doesn't match the stored one.
+    StoredCommitment,
+    /// Replayed state root doesn't match the one committed to L1.
+    L1Root,
+    /// Recomputed block commitment doesn't match the one committed to L1.
+    L1Commitment,
+    /// Replayed account doesn't match the stored one.
+    AccountState,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Divergence {
+    pub block_number: BlockNumber,
+    pub kind: DivergenceKind,
+    pub expected: String,
+    pub actual: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditReportBody {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    /// Last stored block at the start of the replay.
+    pub last_block: BlockNumber,
+    pub replayed_blocks: u32,
+    /// Amount of the replayed blocks checked against L1.
+    pub l1_checked_blocks: u32,
+    /// State root after the last replayed block.
+    pub final_root_hash: H256,
+    pub divergences: Vec<Divergence>,
+    pub passed: bool,
+}
+
+/// Audit report signed by the operator.
+///
+/// `reportHash` is the keccak256 hash of the serialized `report`, and the `signature` is
+/// the Ethereum signature of the hash bytes made by the `signer`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditReport {
+    pub report: AuditReportBody,
+    pub report_hash: H256,
+    pub signer: Address,
+    pub signature: PackedEthSignature,
+}
+
+impl AuditReport {
+    fn sign(report: AuditReportBody, private_key: &H256) -> anyhow::Result<Self> {
+        let report_hash = H256::from(tiny_keccak::keccak256(&serde_json::to_vec(&report)?));
+        let signature = PackedEthSignature::sign(private_key, report_hash.as_bytes())
+            .map_err(|err| format_err!("Failed to sign the audit report: {}", err))?;
+        let signer = PackedEthSignature::address_from_private_key(private_key)
+            .map_err(|err| format_err!("Invalid operator private key: {}", err))?;
+
+        Ok(Self {
+            report,
+            report_hash,
+            signer,
+            signature,
+        })
+    }
+}
+
+struct AuditReplay {
+    eth_gateway: EthereumGateway,
+    state: ZkSyncState,
+    /// The last fetched commit transaction along with the blocks committed by it.
+    last_commit: Option<(H256, Vec<L1CommittedBlock>)>,
+    divergences: Vec<Divergence>,
+    l1_checked_blocks: u32,
+}
+
+impl AuditReplay {
+    fn diverge(
+        &mut self,
+        block_number: BlockNumber,
+        kind: DivergenceKind,
+        expected: impl std::fmt::Debug,
+        actual: impl std::fmt::Debug,
+    ) {
+        let divergence = Divergence {
+            block_number,
+            kind,
+            expected: format!("{:?}", expected),
+            actual: format!("{:?}", actual),
+        };
+        vlog::error!("Audit replay divergence: {:?}", divergence);
+        self.divergences.push(divergence);
+    }
+
+    /// Re-executes the operations of the block in their original order and collects the fees.
+    /// Returns `false` if any of the successful operations fails on replay.
+    fn replay_block(&mut self, block: &Block) -> bool {
+        let mut fees = Vec::new();
+        for operation in &block.block_transactions {
+            let result = match operation {
+                ExecutedOperations::Tx(tx) if tx.success => {
+                    self.state.execute_tx(tx.signed_tx.tx.clone())
+                }
+                // Failed transactions don't change the state.
+                ExecutedOperations::Tx(_) => continue,
+                ExecutedOperations::PriorityOp(op) => {
+                    Ok(self.state.execute_priority_op(op.priority_op.data.clone()))
+                }
+            };
+            match result {
+                Ok(success) => fees.extend(success.fee),
+                Err(error) => {
+                    let hash = operation
+                        .get_executed_tx()
+                        .map(|tx| tx.signed_tx.hash().to_string())
+                        .unwrap_or_default();
+                    self.diverge(
+                        block.block_number,
+                        DivergenceKind::OperationReplay,
+                        format!("tx {} succeeds", hash),
+                        error,
+                    );
+                    return false;
+                }
+            }
+        }
+        self.state.collect_fee(&fees, block.fee_account);
+        true
+    }
+
+    async fn l1_committed_block(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        block_number: BlockNumber,
+    ) -> anyhow::Result<Option<L1CommittedBlock>> {
+        let tx_hash = match storage
+            .ethereum_schema()
+            .aggregated_op_final_hash(block_number, AggregatedActionType::CommitBlocks)
+            .await?
+        {
+            Some(tx_hash) => tx_hash,
+            None => return Ok(None),
+        };
+        // Blocks are replayed in order, so only the last commit transaction has to be cached.
+        let is_cached = matches!(&self.last_commit, Some((hash, _)) if *hash == tx_hash);
+        if !is_cached {
+            let tx = self
+                .eth_gateway
+                .get_tx(tx_hash)
+                .await?
+                .ok_or_else(|| format_err!("Commit transaction {:?} is not found", tx_hash))?;
+            self.last_commit = Some((tx_hash, decode_committed_blocks(&tx.input.0)?));
+        }
+
+        Ok(self
+            .last_commit
+            .iter()
+            .flat_map(|(_, blocks)| blocks)
+            .find(|block| block.block_number == block_number)
+            .cloned())
+    }
+
+    /// Checks the replayed root and the recomputed commitment of the block against
+    /// the stored and L1-committed ones. Returns `false` if the state roots diverge.
+    async fn check_block(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        block: &Block,
+        previous_root: H256,
+    ) -> anyhow::Result<bool> {
+        let replayed_block = Block {
+            new_root_hash: self.state.root_hash(),
+            ..block.clone()
+        };
+        let replayed_root = replayed_block.get_eth_encoded_root();
+        let commitment = Block::get_commitment(
+            block.block_number,
+            block.fee_account,
+            previous_root,
+            replayed_root,
+            block.timestamp,
+            &replayed_block.get_onchain_op_commitment(),
+            &replayed_block.get_eth_public_data(),
+        );
+
+        let stored_root = block.get_eth_encoded_root();
+        if stored_root != replayed_root {
+            self.diverge(
+                block.block_number,
+                DivergenceKind::StoredRoot,
+                replayed_root,
+                stored_root,
+            );
+            return Ok(false);
+        }
+        if block.block_commitment != commitment {
+            self.diverge(
+                block.block_number,
+                DivergenceKind::StoredCommitment,
+                commitment,
+                block.block_commitment,
+            );
+        }
+
+        if let Some(l1_block) = self.l1_committed_block(storage, block.block_number).await? {
+            self.l1_checked_blocks += 1;
+            if l1_block.state_hash != replayed_root {
+                self.diverge(
+                    block.block_number,
+                    DivergenceKind::L1Root,
+                    replayed_root,
+                    l1_block.state_hash,
+                );
+                return Ok(false);
+            }
+            if l1_block.commitment != commitment {
+                self.diverge(
+                    block.block_number,
+                    DivergenceKind::L1Commitment,
+                    commitment,
+                    l1_block.commitment,
+                );
+            }
+        }
+        Ok(true)
+    }
+
+    /// Checks the replayed accounts against the stored committed state.
+    async fn check_accounts(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        last_block: BlockNumber,
+    ) -> anyhow::Result<()> {
+        let (_, stored_accounts) = storage
+            .chain()
+            .state_schema()
+            .load_committed_state(Some(last_block))
+            .await?;
+        let replayed_accounts: HashMap<AccountId, Account> = self
+            .state
+            .get_accounts()
+            .into_iter()
+            .map(|(id, account)| (AccountId(id), account))
+            .collect();
+
+        let mut account_ids: Vec<_> = stored_accounts
+            .keys()
+            .chain(replayed_accounts.keys())
+            .copied()
+            .collect();
+        account_ids.sort_unstable();
+        account_ids.dedup();
+        let diverged_accounts = account_ids.into_iter().filter_map(|id| {
+            let replayed = replayed_accounts.get(&id);
+            let stored = stored_accounts.get(&id);
+            if replayed == stored {
+                None
+            } else {
+                Some((id, replayed.cloned(), stored.cloned()))
+            }
+        });
+        for (id, replayed, stored) in diverged_accounts.take(MAX_REPORTED_ACCOUNTS) {
+            self.diverge(
+                last_block,
+                DivergenceKind::AccountState,
+                (id, replayed),
+                (id, stored),
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Replays all the stored blocks from genesis and returns the report signed by the operator key.
+pub async fn run_audit_replay(config: &ZkSyncConfig) -> anyhow::Result<AuditReport> {
+    let started_at = Utc::now();
+    let pool = ConnectionPool::new(Some(1));
+    let mut storage = pool.access_storage().await?;
+    let last_block = storage
+        .chain()
+        .block_schema()
+        .get_last_committed_block()
+        .await?;
+    vlog::info!("Replaying blocks from genesis up to #{}", *last_block);
+
+    // Genesis state contains only the fee account, see `ZkSyncStateKeeper::create_genesis_block`.
+    let mut genesis_accounts = zksync_types::AccountMap::default();
+    genesis_accounts.insert(
+        AccountId(0),
+        Account::default_with_address(&config.chain.state_keeper.fee_account_addr),
+    );
+    let mut replay = AuditReplay {
+        eth_gateway: EthereumGateway::from_config(config),
+        state: ZkSyncState::from_acc_map(genesis_accounts, BlockNumber(1)),
+        last_commit: None,
+        divergences: Vec::new(),
+        l1_checked_blocks: 0,
+    };
+
+    let genesis_block = storage
+        .chain()
+        .block_schema()
+        .get_block(BlockNumber(0))
+        .await?
+        .ok_or_else(|| format_err!("Genesis block is not found"))?;
+    let mut previous_root = genesis_block.get_eth_encoded_root();
+    let genesis_root = Block {
+        new_root_hash: replay.state.root_hash(),
+        ..genesis_block
+    }
+    .get_eth_encoded_root();
+    if genesis_root != previous_root {
+        replay.diverge(
+            BlockNumber(0),
+            DivergenceKind::GenesisRoot,
+            genesis_root,
+            previous_root,
+        );
+    }
+
+    let mut replayed_blocks = 0;
+    if replay.divergences.is_empty() {
+        for block_number in 1..=*last_block {
+            let block_number = BlockNumber(block_number);
+            let block = storage
+                .chain()
+                .block_schema()
+                .get_block(block_number)
+                .await?
+                .ok_or_else(|| format_err!("Block #{} is not found", *block_number))?;
+
+            if !replay.replay_block(&block)
+                || !replay
+                    .check_block(&mut storage, &block, previous_root)
+                    .await?
+            {
+                break;
+            }
+            previous_root = block.get_eth_encoded_root();
+            replayed_blocks += 1;
+
+            if *block_number % 1000 == 0 {
+                vlog::info!("Replayed blocks up to #{}", *block_number);
+            }
+        }
+    }
+    if replayed_blocks == *last_block {
+        replay.check_accounts(&mut storage, last_block).await?;
+    }
+
+    let report = AuditReportBody {
+        started_at,
+        finished_at: Utc::now(),
+        last_block,
+        replayed_blocks,
+        l1_checked_blocks: replay.l1_checked_blocks,
+        final_root_hash: previous_root,
+        passed: replay.divergences.is_empty(),
+        divergences: replay.divergences,
+    };
+    AuditReport::sign(report, &config.eth_sender.sender.operator_private_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::BigUint;
+    use zksync_crypto::{ff::PrimeField, Fr};
+    use zksync_eth_client::clients::mock::MockEthereum;
+    use zksync_types::{
+        block::{ExecutedPriorityOp, ExecutedTx},
+        tx::TimeRange,
+        Deposit, DepositOp, Nonce, PriorityOp, TokenId, Transfer, ZkSyncOp, ZkSyncPriorityOp,
+        ZkSyncTx,
+    };
+
+    const DEPOSIT_ADDRESS: [u8; 20] = [1; 20];
+
+    fn audit_replay() -> AuditReplay {
+        let mut genesis_accounts = zksync_types::AccountMap::default();
+        genesis_accounts.insert(
+            AccountId(0),
+            Account::default_with_address(&Address::repeat_byte(0xfe)),
+        );
+        AuditReplay {
+            eth_gateway: EthereumGateway::Mock(MockEthereum::default()),
+            state: ZkSyncState::from_acc_map(genesis_accounts, BlockNumber(1)),
+            last_commit: None,
+            divergences: Vec::new(),
+            l1_checked_blocks: 0,
+        }
+    }
+
+    fn deposit_op() -> ExecutedOperations {
+        let deposit = Deposit {
+            from: DEPOSIT_ADDRESS.into(),
+            to: DEPOSIT_ADDRESS.into(),
+            amount: BigUint::from(100u32),
+            token: TokenId(0),
+        };
+        ExecutedOperations::PriorityOp(Box::new(ExecutedPriorityOp {
+            priority_op: PriorityOp {
+                serial_id: 0,
+                data: ZkSyncPriorityOp::Deposit(deposit.clone()),
+                deadline_block: 0,
+                eth_hash: H256::zero(),
+                eth_block: 0,
+            },
+            op: ZkSyncOp::Deposit(Box::new(DepositOp {
+                priority_op: deposit,
+                account_id: AccountId(1),
+            })),
+            block_index: 0,
+            created_at: Utc::now(),
+        }))
+    }
+
+    /// Unsigned transfer from the deposited account, it can't be executed.
+    fn transfer_tx(success: bool) -> ExecutedOperations {
+        let transfer = Transfer::new(
+            AccountId(1),
+            DEPOSIT_ADDRESS.into(),
+            Address::repeat_byte(2),
+            TokenId(0),
+            BigUint::from(10u32),
+            BigUint::from(0u32),
+            Nonce(0),
+            TimeRange::default(),
+            None,
+        );
+        ExecutedOperations::Tx(Box::new(ExecutedTx {
+            signed_tx: ZkSyncTx::Transfer(Box::new(transfer)).into(),
+            success,
+            op: None,
+            fail_reason: None,
+            block_index: Some(1),
+            created_at: Utc::now(),
+            batch_id: None,
+        }))
+    }
+
+    fn block(transactions: Vec<ExecutedOperations>) -> Block {
+        Block::new(
+            BlockNumber(1),
+            Fr::from_str("1").unwrap(),
+            AccountId(0),
+            transactions,
+            (0, 1),
+            10,
+            1_000_000.into(),
+            1_000_000.into(),
+            H256::zero(),
+            0,
+        )
+    }
+
+    fn report() -> AuditReportBody {
+        AuditReportBody {
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+            last_block: BlockNumber(1),
+            replayed_blocks: 1,
+            l1_checked_blocks: 0,
+            final_root_hash: H256::repeat_byte(1),
+            divergences: Vec::new(),
+            passed: true,
+        }
+    }
+
+    /// Checks that the report is attributed to the operator key and that the hash
+    /// covers the serialized report.
+    #[test]
+    fn signed_report() {
+        let private_key = H256::repeat_byte(0x42);
+        let report = AuditReport::sign(report(), &private_key).unwrap();
+
+        let expected_hash = H256::from(tiny_keccak::keccak256(
+            &serde_json::to_vec(&report.report).unwrap(),
+        ));
+        assert_eq!(report.report_hash, expected_hash);
+        assert_eq!(
+            report.signer,
+            PackedEthSignature::address_from_private_key(&private_key).unwrap()
+        );
+        assert_eq!(
+            report
+                .signature
+                .signature_recover_signer(report.report_hash.as_bytes())
+                .unwrap(),
+            report.signer
+        );
+    }
+
+    /// Checks that the operations of the block are applied to the replayed state,
+    /// while the failed transactions are skipped.
+    #[test]
+    fn replay_block_applies_operations() {
+        let mut replay = audit_replay();
+        let genesis_root = replay.state.root_hash();
+
+        assert!(replay.replay_block(&block(vec![deposit_op(), transfer_tx(false)])));
+        assert!(replay.divergences.is_empty());
+        assert_ne!(replay.state.root_hash(), genesis_root);
+        let (_, account) = replay
+            .state
+            .get_account_by_address(&DEPOSIT_ADDRESS.into())
+            .unwrap();
+        assert_eq!(account.get_balance(TokenId(0)), BigUint::from(100u32));
+    }
+
+    /// Checks that the successful operation failing on replay is reported.
+    #[test]
+    fn replay_block_reports_failed_operations() {
+        let mut replay = audit_replay();
+
+        assert!(!replay.replay_block(&block(vec![deposit_op(), transfer_tx(true)])));
+        assert_eq!(replay.divergences.len(), 1);
+        let divergence = &replay.divergences[0];
+        assert_eq!(divergence.block_number, BlockNumber(1));
+        assert_eq!(divergence.kind, DivergenceKind::OperationReplay);
+    }
+}
@@ -0,0 +1,217 @@
+//! Backpressure of the blocks processing on the transactions submission.
+//!
+//! The state keeper creates blocks regardless of whether they are committed on L1 and proven in time.
+//! If the Ethereum sender or the provers lag behind, the backlog of the unprocessed blocks keeps growing,
+//! and so does the time until the users' transactions are finalized. To prevent that, the backpressure
+//! monitor periodically measures the backlog, and while it exceeds the configured limits the mempool
+//! rejects the new transactions, asking the clients to retry after a while. Priority operations
+//! are not affected, since they are submitted on L1.
+
+// Built-in deps
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+// External uses
+use tokio::{task::JoinHandle, time};
+// Workspace uses
+use zksync_config::{configs::chain::Backpressure as BackpressureConfig, ZkSyncConfig};
+use zksync_storage::ConnectionPool;
+use zksync_types::aggregated_operations::AggregatedActionType;
+
+/// Shared signal of the backpressure, raised while the blocks processing lags behind.
+#[derive(Debug, Clone, Default)]
+pub struct Backpressure {
+    active: Arc<AtomicBool>,
+    retry_after: u64,
+}
+
+impl Backpressure {
+    pub fn new(retry_after: u64) -> Self {
+        Self {
+            active: Default::default(),
+            retry_after,
+        }
+    }
+
+    /// Returns the time the clients should wait before resubmitting the transactions (in seconds),
+    /// or `None` if the new transactions can be accepted.
+    pub fn retry_after(&self) -> Option<u64> {
+        if self.active.load(Ordering::SeqCst) {
+            Some(self.retry_after)
+        } else {
+            None
+        }
+    }
+
+    fn set_active(&self, active: bool) -> bool {
+        self.active.swap(active, Ordering::SeqCst)
+    }
+}
+
+/// Blocks which are created by the state keeper but not yet processed.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct BlocksBacklog {
+    /// Blocks which are not yet committed on L1.
+    uncommitted_blocks: u32,
+    /// Prover jobs which are not completed yet.
+    pending_prover_jobs: u32,
+}
+
+impl BlocksBacklog {
+    async fn load(db_pool: &ConnectionPool) -> anyhow::Result<Self> {
+        let mut storage = db_pool.access_storage().await?;
+        let last_saved_block = storage
+            .chain()
+            .block_schema()
+            .get_last_saved_block()
+            .await?;
+        let last_committed_block = storage
+            .chain()
+            .operations_schema()
+            .get_last_block_by_aggregated_action(AggregatedActionType::CommitBlocks, Some(true))
+            .await?;
+        let pending_prover_jobs = storage.prover_schema().pending_jobs_count().await?;
+
+        Ok(Self {
+            uncommitted_blocks: (*last_saved_block).saturating_sub(*last_committed_block),
+            pending_prover_jobs,
+        })
+    }
+
+    /// Returns the description of the exceeded limit, if any.
+    fn exceeded_limit(&self, config: &BackpressureConfig) -> Option<String> {
+        if self.uncommitted_blocks > config.max_uncommitted_blocks {
+            Some(format!(
+                "{} blocks are not committed on L1",
+                self.uncommitted_blocks
+            ))
+        } else if self.pending_prover_jobs > config.max_pending_prover_jobs {
+            Some(format!(
+                "{} prover jobs are not completed",
+                self.pending_prover_jobs
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+struct BackpressureMonitor {
+    db_pool: ConnectionPool,
+    backpressure: Backpressure,
+    config: BackpressureConfig,
+}
+
+impl BackpressureMonitor {
+    async fn check(&self) -> anyhow::Result<()> {
+        let backlog = BlocksBacklog::load(&self.db_pool).await?;
+        metrics::gauge!(
+            "backpressure.uncommitted_blocks",
+            backlog.uncommitted_blocks as f64
+        );
+        metrics::gauge!(
+            "backpressure.pending_prover_jobs",
+            backlog.pending_prover_jobs as f64
+        );
+
+        let exceeded_limit = backlog.exceeded_limit(&self.config);
+        let was_active = self.backpressure.set_active(exceeded_limit.is_some());
+        match (exceeded_limit, was_active) {
+            (Some(reason), false) => {
+                vlog::warn!(
+                    "Blocks processing lags behind, rejecting new transactions: {}",
+                    reason
+                );
+            }
+            (None, true) => {
+                vlog::info!("Blocks processing caught up, accepting new transactions");
+            }
+            _ => {}
+        }
+        metrics::gauge!(
+            "backpressure.active",
+            if self.backpressure.retry_after().is_some() {
+                1.0
+            } else {
+                0.0
+            }
+        );
+        Ok(())
+    }
+}
+
+/// Runs the monitor of the blocks processing backlog, if the backpressure is enabled.
+#[must_use]
+pub fn run_backpressure_monitor(
+    config: &ZkSyncConfig,
+    db_pool: ConnectionPool,
+    backpressure: Backpressure,
+) -> Option<JoinHandle<()>> {
+    let config = config.chain.backpressure.clone();
+    if !config.enabled {
+        return None;
+    }
+
+    let mut timer = time::interval(config.check_interval());
+    let monitor = BackpressureMonitor {
+        db_pool,
+        backpressure,
+        config,
+    };
+    Some(tokio::spawn(async move {
+        loop {
+            timer.tick().await;
+
+            if let Err(err) = monitor.check().await {
+                vlog::warn!("Failed to check the blocks processing backlog: {}", err);
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backlog_limits() {
+        let config = BackpressureConfig {
+            enabled: true,
+            check_interval: 10,
+            max_uncommitted_blocks: 10,
+            max_pending_prover_jobs: 20,
+            retry_after: 30,
+        };
+
+        let backlog = BlocksBacklog {
+            uncommitted_blocks: 10,
+            pending_prover_jobs: 20,
+        };
+        assert_eq!(backlog.exceeded_limit(&config), None);
+
+        let backlog = BlocksBacklog {
+            uncommitted_blocks: 11,
+            ..backlog
+        };
+        assert!(backlog.exceeded_limit(&config).is_some());
+
+        let backlog = BlocksBacklog {
+            uncommitted_blocks: 0,
+            pending_prover_jobs: 21,
+        };
+        assert!(backlog.exceeded_limit(&config).is_some());
+    }
+
+    #[test]
+    fn backpressure_signal() {
+        let backpressure = Backpressure::new(30);
+        assert_eq!(backpressure.retry_after(), None);
+
+        assert!(!backpressure.set_active(true));
+        assert_eq!(backpressure.clone().retry_after(), Some(30));
+
+        assert!(backpressure.set_active(false));
+        assert_eq!(backpressure.retry_after(), None);
+    }
+}
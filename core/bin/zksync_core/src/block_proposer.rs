@@ -13,20 +13,25 @@ use futures::{
 use tokio::{task::JoinHandle, time};
 // Workspace deps
 use zksync_config::ZkSyncConfig;
+use zksync_types::BlockNumber;
 // Local deps
 use crate::{
     mempool::{GetBlockRequest, MempoolBlocksRequest, ProposedBlock},
-    state_keeper::StateKeeperRequest,
+    state_keeper::{PendingBlockInfo, StateKeeperRequest},
 };
 
 fn create_mempool_req(
     last_priority_op_number: u64,
+    last_l1_message_number: u64,
+    block_number: BlockNumber,
     block_timestamp: u64,
 ) -> (MempoolBlocksRequest, oneshot::Receiver<ProposedBlock>) {
     let (response_sender, receiver) = oneshot::channel();
     (
         MempoolBlocksRequest::GetBlock(GetBlockRequest {
             last_priority_op_number,
+            last_l1_message_number,
+            block_number,
             block_timestamp,
             response_sender,
         }),
@@ -36,15 +41,20 @@ fn create_mempool_req(
 
 struct BlockProposer {
     current_priority_op_number: u64,
+    current_l1_message_number: u64,
 
     mempool_requests: mpsc::Sender<MempoolBlocksRequest>,
     statekeeper_requests: mpsc::Sender<StateKeeperRequest>,
 }
 
 impl BlockProposer {
-    async fn propose_new_block(&mut self, block_timestamp: u64) -> ProposedBlock {
-        let (mempool_req, resp) =
-            create_mempool_req(self.current_priority_op_number, block_timestamp);
+    async fn propose_new_block(&mut self, pending_block: PendingBlockInfo) -> ProposedBlock {
+        let (mempool_req, resp) = create_mempool_req(
+            self.current_priority_op_number,
+            self.current_l1_message_number,
+            pending_block.number,
+            pending_block.timestamp,
+        );
         self.mempool_requests
             .send(mempool_req)
             .await
@@ -53,25 +63,26 @@ impl BlockProposer {
         resp.await.expect("Mempool new block request failed")
     }
 
-    async fn get_pending_block_timestamp(&mut self) -> u64 {
-        let (block_timestamp_sender, block_timestamp_receiver) = oneshot::channel();
+    async fn get_pending_block_info(&mut self) -> PendingBlockInfo {
+        let (pending_block_sender, pending_block_receiver) = oneshot::channel();
         self.statekeeper_requests
-            .send(StateKeeperRequest::GetPendingBlockTimestamp(
-                block_timestamp_sender,
+            .send(StateKeeperRequest::GetPendingBlockInfo(
+                pending_block_sender,
             ))
             .await
             .expect("state keeper receiver dropped");
 
-        block_timestamp_receiver
+        pending_block_receiver
             .await
-            .expect("State keeper pending block timestamp request failed")
+            .expect("State keeper pending block info request failed")
     }
 
     async fn commit_new_tx_mini_batch(&mut self) {
-        let block_timestamp = self.get_pending_block_timestamp().await;
-        let proposed_block = self.propose_new_block(block_timestamp).await;
+        let pending_block = self.get_pending_block_info().await;
+        let proposed_block = self.propose_new_block(pending_block).await;
 
         self.current_priority_op_number += proposed_block.priority_ops.len() as u64;
+        self.current_l1_message_number += proposed_block.l1_messages.len() as u64;
         self.statekeeper_requests
             .send(StateKeeperRequest::ExecuteMiniBlock(proposed_block))
             .await
@@ -102,8 +113,21 @@ pub fn run_block_proposer_task(
             .await
             .expect("Unprocessed priority op initialization");
 
+        let next_l1_message_chan = oneshot::channel();
+        statekeeper_requests
+            .send(StateKeeperRequest::GetNextL1MessageId(
+                next_l1_message_chan.0,
+            ))
+            .await
+            .expect("state keeper receiver dropped");
+        let current_l1_message_number = next_l1_message_chan
+            .1
+            .await
+            .expect("Next L1 message initialization");
+
         let mut block_proposer = BlockProposer {
             current_priority_op_number,
+            current_l1_message_number,
             mempool_requests,
             statekeeper_requests,
         };
@@ -1,25 +1,41 @@
 // Built-in uses
-use std::time::{Duration, Instant};
+use std::{
+    ops::Range,
+    time::{Duration, Instant},
+};
 // External uses
-use futures::channel::mpsc::{Receiver, Sender};
+use futures::channel::{
+    mpsc::{Receiver, Sender},
+    oneshot,
+};
 use futures::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use tokio::{task::JoinHandle, time};
 // Workspace uses
 use crate::mempool::MempoolBlocksRequest;
+use vlog::Instrument;
 use zksync_config::ZkSyncConfig;
-use zksync_storage::ConnectionPool;
+use zksync_storage::{
+    leader_election::FencingToken, ConnectionPool, QueryResult, StorageProcessor,
+};
 use zksync_types::{
     block::{Block, BlockMetadata, ExecutedOperations, PendingBlock},
+    l1_message::L1Message,
+    tx::TxHash,
     AccountUpdates, BlockNumber,
 };
+use zksync_utils::shutdown::DrainGuard;
 
 mod aggregated_committer;
+#[cfg(test)]
+mod tests;
 
 #[derive(Debug)]
 pub enum CommitRequest {
     PendingBlock((PendingBlock, AppliedUpdatesRequest)),
     Block((BlockCommitRequest, AppliedUpdatesRequest)),
+    /// Notifies the sender once all the previous requests are stored.
+    Flush(oneshot::Sender<()>),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -33,6 +49,10 @@ pub struct BlockCommitRequest {
 pub struct AppliedUpdatesRequest {
     pub account_updates: AccountUpdates,
     pub first_update_order_id: usize,
+    /// Ranges of the account updates made by the executed transactions.
+    pub tx_updates: Vec<(TxHash, Range<usize>)>,
+    /// Messages sent from L1 that were delivered since the previous request.
+    pub l1_messages: Vec<L1Message>,
 }
 
 pub struct ExecutedOpsNotify {
@@ -41,160 +61,254 @@ pub struct ExecutedOpsNotify {
 }
 
 const PROOF_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Max number of the commit requests persisted in a single DB transaction.
+const MAX_COALESCED_REQUESTS: usize = 64;
 
-async fn handle_new_commit_task(
-    mut rx_for_ops: Receiver<CommitRequest>,
-    mut mempool_req_sender: Sender<MempoolBlocksRequest>,
-    pool: ConnectionPool,
-) {
-    while let Some(request) = rx_for_ops.next().await {
-        match request {
-            CommitRequest::Block((block_commit_request, applied_updates_req)) => {
-                commit_block(
-                    block_commit_request,
-                    applied_updates_req,
-                    &pool,
-                    &mut mempool_req_sender,
-                )
-                .await;
+/// Commit requests of the same block, persisted in a single DB transaction.
+///
+/// At high TPS the state keeper sends the pending block updates faster than they are stored,
+/// and every pending block update stores all the block transactions executed so far. Instead
+/// of storing the queued requests one by one, the committer merges the requests of the same block:
+/// the state updates are concatenated, and only the last state of the block is stored.
+/// Requests of the different blocks are never merged, so every block is still stored atomically
+/// and the blocks are stored in order.
+#[derive(Debug)]
+struct BlockPersistRequest {
+    block_number: BlockNumber,
+    /// The latest pending state of the block, unless the block is sealed.
+    pending_block: Option<PendingBlock>,
+    /// The sealed block.
+    block: Option<BlockCommitRequest>,
+    applied_updates: AppliedUpdatesRequest,
+    requests_count: usize,
+}
+
+impl BlockPersistRequest {
+    fn new(request: CommitRequest) -> Self {
+        let (block_number, pending_block, block, applied_updates) = match request {
+            CommitRequest::PendingBlock((pending_block, applied_updates)) => (
+                pending_block.number,
+                Some(pending_block),
+                None,
+                applied_updates,
+            ),
+            CommitRequest::Block((block, applied_updates)) => {
+                (block.block.block_number, None, Some(block), applied_updates)
+            }
+            CommitRequest::Flush(_) => unreachable!("Flush requests are not persisted"),
+        };
+        Self {
+            block_number,
+            pending_block,
+            block,
+            applied_updates,
+            requests_count: 1,
+        }
+    }
+
+    /// Merges the next request of the same block. Returns the request back if it can't be merged.
+    fn merge(&mut self, request: CommitRequest) -> Result<(), CommitRequest> {
+        let (block_number, applied_updates) = match &request {
+            CommitRequest::PendingBlock((pending_block, applied_updates)) => {
+                (pending_block.number, applied_updates)
             }
-            CommitRequest::PendingBlock((pending_block, applied_updates_req)) => {
-                let mut operations = pending_block.success_operations.clone();
-                operations.extend(
-                    pending_block
-                        .failed_txs
-                        .clone()
-                        .into_iter()
-                        .map(|tx| ExecutedOperations::Tx(Box::new(tx))),
-                );
-                save_pending_block(pending_block, applied_updates_req, &pool).await;
+            CommitRequest::Block((block, applied_updates)) => {
+                (block.block.block_number, applied_updates)
             }
+            CommitRequest::Flush(_) => return Err(request),
+        };
+        let next_update_order_id =
+            self.applied_updates.first_update_order_id + self.applied_updates.account_updates.len();
+        if block_number != self.block_number
+            || self.block.is_some()
+            || applied_updates.first_update_order_id != next_update_order_id
+        {
+            return Err(request);
         }
+
+        let applied_updates = match request {
+            CommitRequest::PendingBlock((pending_block, applied_updates)) => {
+                self.pending_block = Some(pending_block);
+                applied_updates
+            }
+            CommitRequest::Block((block, applied_updates)) => {
+                // The sealed block contains all the transactions of the pending one.
+                self.pending_block = None;
+                self.block = Some(block);
+                applied_updates
+            }
+            CommitRequest::Flush(_) => unreachable!("Flush requests are not merged"),
+        };
+        self.applied_updates
+            .account_updates
+            .extend(applied_updates.account_updates);
+        self.applied_updates
+            .tx_updates
+            .extend(applied_updates.tx_updates);
+        self.applied_updates
+            .l1_messages
+            .extend(applied_updates.l1_messages);
+        self.requests_count += 1;
+        Ok(())
     }
 }
 
-async fn save_pending_block(
-    pending_block: PendingBlock,
-    applied_updates_request: AppliedUpdatesRequest,
-    pool: &ConnectionPool,
+async fn handle_new_commit_task(
+    mut rx_for_ops: Receiver<CommitRequest>,
+    mut mempool_req_sender: Sender<MempoolBlocksRequest>,
+    pool: ConnectionPool,
+    fencing_token: Option<FencingToken>,
+    drain_guard: DrainGuard,
 ) {
-    let start = Instant::now();
-    let mut storage = pool
-        .access_storage()
-        .await
-        .expect("db connection fail for committer");
-
-    let mut transaction = storage
-        .start_transaction()
-        .await
-        .expect("Failed initializing a DB transaction");
-
-    let block_number = pending_block.number;
-
-    vlog::trace!("persist pending block #{}", block_number);
-
-    transaction
-        .chain()
-        .block_schema()
-        .save_pending_block(pending_block)
-        .await
-        .expect("committer must commit the pending block into db");
+    let mut next_request = None;
+    loop {
+        let request = match next_request.take() {
+            Some(request) => request,
+            None => match rx_for_ops.next().await {
+                Some(request) => request,
+                None => break,
+            },
+        };
+        if let CommitRequest::Flush(notify) = request {
+            // The requests are handled in order, so all the previous ones are already stored.
+            notify.send(()).unwrap_or_default();
+            continue;
+        }
 
-    transaction
-        .chain()
-        .state_schema()
-        .commit_state_update(
-            block_number,
-            &applied_updates_request.account_updates,
-            applied_updates_request.first_update_order_id,
-        )
-        .await
-        .expect("committer must commit the pending block into db");
+        // Merge the requests queued while the previous ones were being stored.
+        let mut persist_request = BlockPersistRequest::new(request);
+        while persist_request.requests_count < MAX_COALESCED_REQUESTS {
+            let request = match rx_for_ops.try_next() {
+                Ok(Some(request)) => request,
+                _ => break,
+            };
+            if let Err(request) = persist_request.merge(request) {
+                next_request = Some(request);
+                break;
+            }
+        }
+        metrics::histogram!(
+            "committer.coalesced_requests",
+            persist_request.requests_count as u64
+        );
 
-    transaction
-        .commit()
-        .await
-        .expect("Unable to commit DB transaction");
+        let span = vlog::info_span!("commit_block", block_number = *persist_request.block_number);
+        let mut storage = pool
+            .access_storage()
+            .await
+            .expect("db connection fail for committer");
+        let accounts_updated = persist_block(&mut storage, persist_request, fencing_token)
+            .instrument(span)
+            .await
+            .expect("committer must commit the block into db");
 
-    metrics::histogram!("committer.save_pending_block", start.elapsed());
+        if let Some(accounts_updated) = accounts_updated {
+            mempool_req_sender
+                .send(MempoolBlocksRequest::UpdateNonces(accounts_updated))
+                .await
+                .map_err(|e| vlog::warn!("Failed notify mempool about account updates: {}", e))
+                .unwrap_or_default();
+        }
+    }
+    // The state keeper closes the channel on shutdown, once all the requests are handled
+    // every executed operation is stored in the database.
+    drop(drain_guard);
 }
 
-async fn commit_block(
-    block_commit_request: BlockCommitRequest,
-    applied_updates_request: AppliedUpdatesRequest,
-    pool: &ConnectionPool,
-    mempool_req_sender: &mut Sender<MempoolBlocksRequest>,
-) {
+/// Stores the block in a single DB transaction.
+/// Returns the accounts updated in the block if the block is sealed.
+///
+/// In the active/standby mode, the transaction is fenced with the token of the leadership,
+/// so the instance which lost it can't store the block once another one takes over.
+async fn persist_block(
+    storage: &mut StorageProcessor<'_>,
+    request: BlockPersistRequest,
+    fencing_token: Option<FencingToken>,
+) -> QueryResult<Option<AccountUpdates>> {
     let start = Instant::now();
-    let BlockCommitRequest {
+    let BlockPersistRequest {
+        block_number,
+        pending_block,
         block,
-        block_metadata,
-        accounts_updated,
-    } = block_commit_request;
-
-    let mut storage = pool
-        .access_storage()
-        .await
-        .expect("db connection fail for committer");
-
-    let mut transaction = storage
-        .start_transaction()
-        .await
-        .expect("Failed initializing a DB transaction");
-
-    // This is needed to keep track of how many priority ops are in each block
-    // and trigger grafana alerts if there are suspiciously few
-    let total_priority_ops = block
-        .block_transactions
-        .iter()
-        .filter(|tx| matches!(tx, ExecutedOperations::PriorityOp(_)))
-        .count();
-    metrics::histogram!(
-        "committer.priority_ops_per_block",
-        total_priority_ops as u64
-    );
+        applied_updates,
+        ..
+    } = request;
+    let mut transaction = storage.start_transaction().await?;
+    if let Some(fencing_token) = fencing_token {
+        transaction
+            .leader_election_schema()
+            .check_fencing_token(fencing_token)
+            .await?;
+    }
 
     transaction
         .chain()
         .state_schema()
         .commit_state_update(
-            block.block_number,
-            &applied_updates_request.account_updates,
-            applied_updates_request.first_update_order_id,
+            block_number,
+            &applied_updates.account_updates,
+            applied_updates.first_update_order_id,
         )
-        .await
-        .expect("committer must commit the pending block into db");
-
-    vlog::info!("commit block #{}", block.block_number);
-
-    let block_number = block.block_number;
-
+        .await?;
     transaction
         .chain()
-        .block_schema()
-        .save_block(block)
-        .await
-        .expect("committer must commit the op into db");
+        .state_schema()
+        .store_tx_account_updates(block_number, &applied_updates.tx_updates)
+        .await?;
+    if !applied_updates.l1_messages.is_empty() {
+        transaction
+            .l1_messages_schema()
+            .store_messages(block_number, &applied_updates.l1_messages)
+            .await?;
+    }
 
-    transaction
-        .chain()
-        .block_schema()
-        .save_block_metadata(block_number, block_metadata)
-        .await
-        .expect("committer must commit block block metadata into db");
+    let accounts_updated = if let Some(block_commit_request) = block {
+        let BlockCommitRequest {
+            block,
+            block_metadata,
+            accounts_updated,
+        } = block_commit_request;
 
-    mempool_req_sender
-        .send(MempoolBlocksRequest::UpdateNonces(accounts_updated))
-        .await
-        .map_err(|e| vlog::warn!("Failed notify mempool about account updates: {}", e))
-        .unwrap_or_default();
+        // This is needed to keep track of how many priority ops are in each block
+        // and trigger grafana alerts if there are suspiciously few
+        let total_priority_ops = block
+            .block_transactions
+            .iter()
+            .filter(|tx| matches!(tx, ExecutedOperations::PriorityOp(_)))
+            .count();
+        metrics::histogram!(
+            "committer.priority_ops_per_block",
+            total_priority_ops as u64
+        );
 
-    transaction
-        .commit()
-        .await
-        .expect("Unable to commit DB transaction");
+        vlog::info!("commit block #{}", block_number);
+        transaction.chain().block_schema().save_block(block).await?;
+        transaction
+            .chain()
+            .block_schema()
+            .save_block_metadata(block_number, block_metadata)
+            .await?;
+        Some(accounts_updated)
+    } else if let Some(pending_block) = pending_block {
+        vlog::trace!("persist pending block #{}", block_number);
+        transaction
+            .chain()
+            .block_schema()
+            .save_pending_block(pending_block)
+            .await?;
+        None
+    } else {
+        None
+    };
+
+    transaction.commit().await?;
 
-    metrics::histogram!("committer.commit_block", start.elapsed());
+    if accounts_updated.is_some() {
+        metrics::histogram!("committer.commit_block", start.elapsed());
+    } else {
+        metrics::histogram!("committer.save_pending_block", start.elapsed());
+    }
+    Ok(accounts_updated)
 }
 
 async fn poll_for_new_proofs_task(pool: ConnectionPool, config: ZkSyncConfig) {
@@ -220,11 +334,15 @@ pub fn run_committer(
     mempool_req_sender: Sender<MempoolBlocksRequest>,
     pool: ConnectionPool,
     config: &ZkSyncConfig,
+    fencing_token: Option<FencingToken>,
+    drain_guard: DrainGuard,
 ) -> JoinHandle<()> {
     tokio::spawn(handle_new_commit_task(
         rx_for_ops,
         mempool_req_sender,
         pool.clone(),
+        fencing_token,
+        drain_guard,
     ));
     tokio::spawn(poll_for_new_proofs_task(pool, config.clone()))
 }
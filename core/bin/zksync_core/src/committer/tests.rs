@@ -0,0 +1,244 @@
+// External uses
+use num::BigUint;
+// Workspace uses
+use zksync_storage::test_data::{gen_sample_block, BLOCK_SIZE_CHUNKS};
+use zksync_types::{
+    block::{BlockMetadata, ExecutedTx},
+    tx::{TimeRange, Transfer},
+    AccountId, AccountUpdate, Address, Nonce, TokenId, TransferOp, ZkSyncOp, ZkSyncTx, H256,
+};
+// Local uses
+use super::*;
+
+fn gen_transfer(block_number: BlockNumber, nonce: u32) -> ExecutedOperations {
+    let transfer = Transfer::new(
+        AccountId(1),
+        Address::repeat_byte(1),
+        Address::repeat_byte(2),
+        TokenId(0),
+        BigUint::from(*block_number),
+        BigUint::from(0u32),
+        Nonce(nonce),
+        TimeRange::default(),
+        None,
+    );
+    let op = ZkSyncOp::Transfer(Box::new(TransferOp {
+        tx: transfer.clone(),
+        from: AccountId(1),
+        to: AccountId(2),
+    }));
+    ExecutedOperations::Tx(Box::new(ExecutedTx {
+        signed_tx: ZkSyncTx::Transfer(Box::new(transfer)).into(),
+        success: true,
+        op: Some(op),
+        fail_reason: None,
+        block_index: None,
+        created_at: chrono::Utc::now(),
+        batch_id: None,
+    }))
+}
+
+/// Generates the requests the state keeper sends for the block: the pending block updates
+/// with the given number of transactions each, followed by the sealed block.
+fn gen_block_requests(
+    block_number: BlockNumber,
+    iterations: usize,
+    txs_per_iteration: usize,
+) -> Vec<CommitRequest> {
+    let mut requests = Vec::new();
+    let mut operations = Vec::new();
+    let mut account_updates = Vec::new();
+    let mut tx_updates = Vec::new();
+    for iteration in 0..iterations {
+        let first_update_order_id = account_updates.len();
+        for _ in 0..txs_per_iteration {
+            let nonce = account_updates.len() as u32;
+            let operation = gen_transfer(block_number, nonce);
+            let tx_hash = operation.get_executed_tx().unwrap().signed_tx.hash();
+            tx_updates.push((tx_hash, account_updates.len()..account_updates.len() + 1));
+            account_updates.push((
+                AccountId(1),
+                AccountUpdate::UpdateBalance {
+                    old_nonce: Nonce(nonce),
+                    new_nonce: Nonce(nonce + 1),
+                    balance_update: (TokenId(0), BigUint::from(100u32), BigUint::from(99u32)),
+                },
+            ));
+            operations.push(operation);
+        }
+
+        let pending_block = PendingBlock {
+            number: block_number,
+            chunks_left: 0,
+            unprocessed_priority_op_before: 0,
+            pending_block_iteration: iteration,
+            success_operations: operations.clone(),
+            failed_txs: Vec::new(),
+            previous_block_root_hash: H256::zero(),
+            timestamp: 0,
+        };
+        let applied_updates = AppliedUpdatesRequest {
+            account_updates: account_updates[first_update_order_id..].to_vec(),
+            first_update_order_id,
+            tx_updates: tx_updates[first_update_order_id..].to_vec(),
+            l1_messages: Vec::new(),
+        };
+        requests.push(CommitRequest::PendingBlock((
+            pending_block,
+            applied_updates,
+        )));
+    }
+
+    let block = BlockCommitRequest {
+        block: gen_sample_block(block_number, BLOCK_SIZE_CHUNKS, operations),
+        block_metadata: BlockMetadata {
+            fast_processing: false,
+        },
+        accounts_updated: account_updates.clone(),
+    };
+    let applied_updates = AppliedUpdatesRequest {
+        account_updates: Vec::new(),
+        first_update_order_id: account_updates.len(),
+        tx_updates: Vec::new(),
+        l1_messages: Vec::new(),
+    };
+    requests.push(CommitRequest::Block((block, applied_updates)));
+    requests
+}
+
+/// Checks that only the requests of the same block are merged.
+#[test]
+fn merge_requests_of_the_same_block() {
+    let mut requests = gen_block_requests(BlockNumber(1), 3, 2).into_iter();
+    let mut persist_request = BlockPersistRequest::new(requests.next().unwrap());
+    for request in requests {
+        persist_request.merge(request).unwrap();
+    }
+    assert_eq!(persist_request.requests_count, 4);
+    assert!(persist_request.pending_block.is_none());
+    assert!(persist_request.block.is_some());
+    assert_eq!(persist_request.applied_updates.first_update_order_id, 0);
+    assert_eq!(persist_request.applied_updates.account_updates.len(), 6);
+    assert_eq!(persist_request.applied_updates.tx_updates.len(), 6);
+
+    // The sealed block is not merged with the next block.
+    let next_block_request = gen_block_requests(BlockNumber(2), 1, 1).remove(0);
+    assert!(persist_request.merge(next_block_request).is_err());
+
+    // The pending block is not merged with the next block either.
+    let mut requests = gen_block_requests(BlockNumber(1), 2, 2);
+    let mut persist_request = BlockPersistRequest::new(requests.remove(0));
+    persist_request.merge(requests.remove(0)).unwrap();
+    assert_eq!(
+        persist_request
+            .pending_block
+            .as_ref()
+            .unwrap()
+            .success_operations
+            .len(),
+        4
+    );
+    let next_block_request = gen_block_requests(BlockNumber(2), 1, 1).remove(0);
+    assert!(persist_request.merge(next_block_request).is_err());
+
+    // Non-contiguous state updates are not merged.
+    let mut requests = gen_block_requests(BlockNumber(1), 3, 1);
+    let mut persist_request = BlockPersistRequest::new(requests.remove(0));
+    assert!(persist_request.merge(requests.remove(1)).is_err());
+}
+
+/// Checks that the flush requests are never merged, so they are answered only once
+/// the previous requests are stored.
+#[test]
+fn flush_requests_are_not_merged() {
+    let mut requests = gen_block_requests(BlockNumber(1), 2, 1);
+    let mut persist_request = BlockPersistRequest::new(requests.remove(0));
+    let (notify, _) = oneshot::channel();
+    assert!(matches!(
+        persist_request.merge(CommitRequest::Flush(notify)),
+        Err(CommitRequest::Flush(_))
+    ));
+    assert_eq!(persist_request.requests_count, 1);
+}
+
+/// Stores the requests, merging the consecutive ones if `coalesce` is set.
+/// Returns the number of the stored persist requests.
+async fn persist_requests(
+    storage: &mut StorageProcessor<'_>,
+    requests: Vec<CommitRequest>,
+    coalesce: bool,
+) -> QueryResult<usize> {
+    let mut persist_requests: Vec<BlockPersistRequest> = Vec::new();
+    for request in requests {
+        let request = match persist_requests.last_mut() {
+            Some(last) if coalesce => match last.merge(request) {
+                Ok(()) => continue,
+                Err(request) => request,
+            },
+            _ => request,
+        };
+        persist_requests.push(BlockPersistRequest::new(request));
+    }
+
+    let persisted = persist_requests.len();
+    for persist_request in persist_requests {
+        persist_block(storage, persist_request, None).await?;
+    }
+    Ok(persisted)
+}
+
+/// Checks that the coalesced requests store the same blocks as the requests stored one by one,
+/// with one write per block. Requires the database, run with
+/// `cargo test -p zksync_core coalesced_requests_are_stored -- --ignored`.
+/// Nothing is stored, since all the changes are rolled back.
+#[tokio::test]
+#[ignore]
+async fn coalesced_requests_are_stored() -> anyhow::Result<()> {
+    const BLOCKS: u32 = 3;
+    const ITERATIONS: usize = 5;
+    const TXS_PER_ITERATION: usize = 4;
+
+    let mut storage = StorageProcessor::establish_connection().await?;
+    let mut transaction = storage.start_transaction().await?;
+    let last_block = transaction
+        .chain()
+        .block_schema()
+        .get_last_saved_block()
+        .await?;
+
+    for (run, coalesce) in vec![false, true].into_iter().enumerate() {
+        let first_block = *last_block + 1 + run as u32 * BLOCKS;
+        let requests = (first_block..first_block + BLOCKS)
+            .flat_map(|block| gen_block_requests(BlockNumber(block), ITERATIONS, TXS_PER_ITERATION))
+            .collect();
+        let persisted = persist_requests(&mut transaction, requests, coalesce).await?;
+        let expected_persisted = if coalesce {
+            BLOCKS as usize
+        } else {
+            BLOCKS as usize * (ITERATIONS + 1)
+        };
+        assert_eq!(persisted, expected_persisted);
+
+        for block_number in first_block..first_block + BLOCKS {
+            let block = transaction
+                .chain()
+                .block_schema()
+                .get_block(BlockNumber(block_number))
+                .await?
+                .expect("block is not stored");
+            assert_eq!(
+                block.block_transactions.len(),
+                ITERATIONS * TXS_PER_ITERATION
+            );
+        }
+    }
+    assert_eq!(
+        transaction
+            .chain()
+            .block_schema()
+            .get_last_saved_block()
+            .await?,
+        last_block + 2 * BLOCKS
+    );
+    Ok(())
+}
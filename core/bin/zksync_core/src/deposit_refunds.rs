@@ -0,0 +1,235 @@
+//! Deposit refunds sender sends the refunds of the deposits which funds got stuck on L2.
+//!
+//! Unprocessable deposits are flagged by `eth_watch` and refunded only once the operator requests
+//! it via the admin API. The refund sends the deposited amount back to the depositor on L1 from the
+//! dedicated refund account, independently of the `eth_sender`. The deposit itself is still
+//! processed on L2, so the refund is a compensation paid by the operator.
+//!
+//! The sender keeps no state in memory: every refund moves through the `requested`, `sent`
+//! and `confirmed` (or `failed`) statuses in the database. The refund transaction is signed and
+//! stored along with its nonce before it's broadcast, and the stored transaction is broadcast
+//! again while it's not mined, so neither a restart nor a transaction dropped by the Ethereum
+//! nodes leaves the refund stuck or makes it sent twice.
+
+// Built-in uses
+use std::{cmp, time::Duration};
+// External uses
+use ethabi::Token;
+use tokio::{task::JoinHandle, time};
+use web3::contract::Options;
+// Workspace uses
+use zksync_config::ZkSyncConfig;
+use zksync_contracts::erc20_contract;
+use zksync_eth_client::{EthereumGateway, SignedCallResult};
+use zksync_storage::ConnectionPool;
+use zksync_types::{
+    deposit_refund::{DepositRefund, DepositRefundStatus},
+    tx::PackedEthSignature,
+    TokenLike, U256,
+};
+
+const REFUNDS_INTERVAL: Duration = Duration::from_secs(30);
+/// Max amount of the refunds sent or checked within one iteration.
+const REFUNDS_BATCH_SIZE: u32 = 10;
+const ETH_TRANSFER_GAS_LIMIT: u64 = 21_000;
+const ERC20_TRANSFER_GAS_LIMIT: u64 = 150_000;
+
+struct DepositRefundsSender {
+    db_pool: ConnectionPool,
+    eth_gateway: EthereumGateway,
+    wait_confirmations: u64,
+}
+
+impl DepositRefundsSender {
+    async fn process_refunds(&self) -> anyhow::Result<()> {
+        let mut storage = self.db_pool.access_storage().await?;
+        let requested = storage
+            .deposit_refunds_schema()
+            .load_refunds(DepositRefundStatus::Requested, REFUNDS_BATCH_SIZE)
+            .await?;
+        for refund in requested {
+            // The nonces of the stored transactions are taken into account, since some of them
+            // may be not broadcast yet and thus unknown to the Ethereum node.
+            let stored_nonce = storage.deposit_refunds_schema().next_refund_nonce().await?;
+            let pending_nonce = self.eth_gateway.pending_nonce().await?;
+            let nonce = cmp::max(pending_nonce, U256::from(stored_nonce.unwrap_or_default()));
+
+            let signed_tx = self.sign_refund(&refund, nonce).await?;
+            let stored = storage
+                .deposit_refunds_schema()
+                .store_refund_tx(
+                    refund.serial_id,
+                    signed_tx.hash,
+                    signed_tx.nonce.as_u64(),
+                    &signed_tx.raw_tx,
+                )
+                .await?;
+            // The refund was dismissed or handled concurrently.
+            if !stored {
+                continue;
+            }
+
+            // If the transaction isn't broadcast, it will be resent along with the dropped ones.
+            match self.eth_gateway.send_raw_tx(signed_tx.raw_tx).await {
+                Ok(tx_hash) => vlog::info!(
+                    "Refund of the deposit #{} is sent in tx {:?}",
+                    refund.serial_id,
+                    tx_hash
+                ),
+                Err(err) => vlog::warn!(
+                    "Failed to send the refund tx {:?} of the deposit #{}, it will be resent: {}",
+                    signed_tx.hash,
+                    refund.serial_id,
+                    err
+                ),
+            }
+        }
+
+        let sent = storage
+            .deposit_refunds_schema()
+            .load_refunds(DepositRefundStatus::Sent, REFUNDS_BATCH_SIZE)
+            .await?;
+        for refund in sent {
+            let tx_hash = refund
+                .refund_tx_hash
+                .expect("Sent refund must have the transaction hash");
+            let status = match self.eth_gateway.get_tx_status(tx_hash).await? {
+                Some(status) if !status.success => DepositRefundStatus::Failed,
+                Some(status) if status.confirmations >= self.wait_confirmations => {
+                    DepositRefundStatus::Confirmed
+                }
+                Some(_) => continue,
+                None => {
+                    if self.resend_refund(&refund).await? {
+                        continue;
+                    }
+                    DepositRefundStatus::Failed
+                }
+            };
+            storage
+                .deposit_refunds_schema()
+                .update_status(refund.serial_id, &[DepositRefundStatus::Sent], status, None)
+                .await?;
+            if status == DepositRefundStatus::Failed {
+                vlog::warn!(
+                    "Refund tx {:?} of the deposit #{} failed, the refund can be requested again",
+                    tx_hash,
+                    refund.serial_id
+                );
+            }
+            metrics::counter!("deposit_refunds.processed", 1, "status" => status.as_str());
+        }
+        Ok(())
+    }
+
+    /// Broadcasts the stored refund transaction which is not mined, e.g. because it was dropped
+    /// by the Ethereum nodes or was never sent. Returns `false` if the transaction can't be mined
+    /// anymore, since its nonce is used by another transaction of the refund account.
+    async fn resend_refund(&self, refund: &DepositRefund) -> anyhow::Result<bool> {
+        let nonce = refund
+            .refund_tx_nonce
+            .expect("Sent refund must have the transaction nonce");
+        if self.eth_gateway.current_nonce().await? > U256::from(nonce) {
+            // The transaction could have been mined right after its status was checked.
+            let tx_hash = refund
+                .refund_tx_hash
+                .expect("Sent refund must have the transaction hash");
+            let is_mined = self.eth_gateway.get_tx_status(tx_hash).await?.is_some();
+            return Ok(is_mined);
+        }
+
+        let raw_tx = self
+            .db_pool
+            .access_storage()
+            .await?
+            .deposit_refunds_schema()
+            .get_refund_raw_tx(refund.serial_id)
+            .await?
+            .expect("Sent refund must have the signed transaction");
+        // The node rejects the transaction it already knows, that's not an error for us.
+        if let Err(err) = self.eth_gateway.send_raw_tx(raw_tx).await {
+            vlog::debug!(
+                "Refund tx {:?} of the deposit #{} is not resent: {}",
+                refund.refund_tx_hash,
+                refund.serial_id,
+                err
+            );
+        }
+        metrics::counter!("deposit_refunds.resent", 1);
+        Ok(true)
+    }
+
+    /// Signs the transaction sending the deposited amount back to the depositor.
+    async fn sign_refund(
+        &self,
+        refund: &DepositRefund,
+        nonce: U256,
+    ) -> anyhow::Result<SignedCallResult> {
+        let amount = U256::from_dec_str(&refund.amount.to_string())?;
+        let signed_tx = if refund.token.0 == 0 {
+            let options = Options {
+                value: Some(amount),
+                gas: Some(ETH_TRANSFER_GAS_LIMIT.into()),
+                nonce: Some(nonce),
+                ..Default::default()
+            };
+            self.eth_gateway
+                .sign_prepared_tx_for_addr(Vec::new(), refund.from, options)
+                .await?
+        } else {
+            let mut storage = self.db_pool.access_storage().await?;
+            let token = storage
+                .tokens_schema()
+                .get_token(TokenLike::Id(refund.token))
+                .await?
+                .ok_or_else(|| anyhow::format_err!("Unknown token {}", refund.token))?;
+            let data = erc20_contract()
+                .function("transfer")?
+                .encode_input(&[Token::Address(refund.from), Token::Uint(amount)])?;
+            let options = Options {
+                gas: Some(ERC20_TRANSFER_GAS_LIMIT.into()),
+                nonce: Some(nonce),
+                ..Default::default()
+            };
+            self.eth_gateway
+                .sign_prepared_tx_for_addr(data, token.address, options)
+                .await?
+        };
+        Ok(signed_tx)
+    }
+}
+
+#[must_use]
+pub fn run_deposit_refunds_sender(
+    config: &ZkSyncConfig,
+    db_pool: ConnectionPool,
+) -> Option<JoinHandle<()>> {
+    if !config.eth_watch.deposit_refunds_enabled {
+        return None;
+    }
+
+    // The refunds are sent from the dedicated account, so they don't interfere
+    // with the nonces of the operator transactions.
+    let private_key = config.eth_watch.refund_account_private_key;
+    let mut refund_config = config.clone();
+    refund_config.eth_sender.sender.operator_private_key = private_key;
+    refund_config.eth_sender.sender.operator_commit_eth_addr =
+        PackedEthSignature::address_from_private_key(&private_key)
+            .expect("Invalid refund account private key");
+    let sender = DepositRefundsSender {
+        db_pool,
+        eth_gateway: EthereumGateway::from_config(&refund_config),
+        wait_confirmations: config.eth_sender.sender.wait_confirmations,
+    };
+
+    let mut timer = time::interval(REFUNDS_INTERVAL);
+    Some(tokio::spawn(async move {
+        loop {
+            timer.tick().await;
+
+            if let Err(err) = sender.process_refunds().await {
+                vlog::error!("Failed to process the deposit refunds: {}", err);
+            }
+        }
+    }))
+}
@@ -1,21 +1,35 @@
-use std::{convert::TryFrom, time::Instant};
+use std::{
+    convert::TryFrom,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
 
 use anyhow::format_err;
 use ethabi::Hash;
+use futures::future;
 use std::fmt::Debug;
+use tokio::time;
 use web3::{
     contract::Options,
-    transports::http,
     types::{BlockNumber, FilterBuilder, Log},
-    Web3,
 };
 
+use zksync_config::ETHWatchConfig;
 use zksync_contracts::zksync_contract;
 use zksync_eth_client::ethereum_gateway::EthereumGateway;
-use zksync_types::{Address, Nonce, PriorityOp, H160, U256};
+use zksync_types::{l1_message::L1Message, Address, Nonce, PriorityOp, H160, U256};
+
+use super::logs_range::{dedup_logs, LogsRange};
+
+/// Checks whether the Ethereum node asks to reduce the amount of the requests.
+pub fn is_rate_limit_error(error: &anyhow::Error) -> bool {
+    error.to_string().contains("429 Too Many Requests")
+}
 
 struct ContractTopics {
     new_priority_request: Hash,
+    /// Contracts deployed before the message relay was introduced don't have the event.
+    message_sent: Option<Hash>,
 }
 
 impl ContractTopics {
@@ -25,6 +39,10 @@ impl ContractTopics {
                 .event("NewPriorityRequest")
                 .expect("main contract abi error")
                 .signature(),
+            message_sent: zksync_contract
+                .event("MessageSent")
+                .ok()
+                .map(|event| event.signature()),
         }
     }
 }
@@ -36,28 +54,105 @@ pub trait EthClient {
         from: BlockNumber,
         to: BlockNumber,
     ) -> anyhow::Result<Vec<PriorityOp>>;
+    async fn get_message_events(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> anyhow::Result<Vec<L1Message>>;
     async fn block_number(&self) -> anyhow::Result<u64>;
     async fn get_auth_fact(&self, address: Address, nonce: Nonce) -> anyhow::Result<Vec<u8>>;
     async fn get_auth_fact_reset_time(&self, address: Address, nonce: Nonce)
         -> anyhow::Result<u64>;
 }
 
+/// Client querying the events of the zkSync contract.
+///
+/// Logs are queried in the block ranges adjusted to the Ethereum node capabilities
+/// (see `logs_range`), and the logs of the different topics are queried in parallel.
 pub struct EthHttpClient {
     client: EthereumGateway,
     topics: ContractTopics,
     zksync_contract_addr: H160,
+    logs_range: Mutex<LogsRange>,
+    logs_request_timeout: Duration,
 }
 
 impl EthHttpClient {
-    pub fn new(client: EthereumGateway, zksync_contract_addr: H160) -> Self {
+    pub fn new(
+        client: EthereumGateway,
+        zksync_contract_addr: H160,
+        config: &ETHWatchConfig,
+    ) -> Self {
         let topics = ContractTopics::new(&zksync_contract());
         Self {
             client,
             topics,
             zksync_contract_addr,
+            logs_range: Mutex::new(LogsRange::from_config(config)),
+            logs_request_timeout: config.logs_request_timeout(),
+        }
+    }
+
+    async fn resolve_block_number(&self, block: BlockNumber) -> anyhow::Result<u64> {
+        match block {
+            BlockNumber::Number(number) => Ok(number.as_u64()),
+            BlockNumber::Earliest => Ok(0),
+            BlockNumber::Latest | BlockNumber::Pending => self.block_number().await,
+        }
+    }
+
+    async fn query_logs(&self, from: u64, to: u64, topic: Hash) -> anyhow::Result<Vec<Log>> {
+        let filter = FilterBuilder::default()
+            .address(vec![self.zksync_contract_addr])
+            .from_block(BlockNumber::Number(from.into()))
+            .to_block(BlockNumber::Number(to.into()))
+            .topics(Some(vec![topic]), None, None, None)
+            .build();
+
+        match time::timeout(self.logs_request_timeout, self.client.logs(filter)).await {
+            Ok(logs) => logs,
+            Err(_) => Err(format_err!(
+                "Logs request timed out after {:?}",
+                self.logs_request_timeout
+            )),
         }
     }
 
+    /// Queries the logs of the topic, splitting the blocks into the ranges the Ethereum node is able
+    /// to serve. The failed range is retried with the smaller size, until the min size is reached.
+    async fn get_topic_logs(&self, from: u64, to: u64, topic: Hash) -> anyhow::Result<Vec<Log>> {
+        let mut logs = Vec::new();
+        let mut range_start = from;
+        while range_start <= to {
+            let range_size = self.logs_range.lock().unwrap().size();
+            let range_end = std::cmp::min(range_start.saturating_add(range_size - 1), to);
+            match self.query_logs(range_start, range_end, topic).await {
+                Ok(range_logs) => {
+                    self.logs_range.lock().unwrap().on_success();
+                    logs.extend(range_logs);
+                    range_start = range_end + 1;
+                }
+                Err(error) => {
+                    // Rate limiting is handled by the watcher backoff, smaller ranges won't help.
+                    let failed_size = range_end - range_start + 1;
+                    if is_rate_limit_error(&error)
+                        || !self.logs_range.lock().unwrap().on_failure(failed_size)
+                    {
+                        return Err(error);
+                    }
+                    vlog::warn!(
+                        "Failed to query the logs for the blocks {}..={}, retrying with the smaller range: {}",
+                        range_start,
+                        range_end,
+                        error
+                    );
+                    metrics::counter!("eth_watcher.logs_range_failures", 1);
+                }
+            }
+        }
+        Ok(logs)
+    }
+
     async fn get_events<T>(
         &self,
         from: BlockNumber,
@@ -68,22 +163,31 @@ impl EthHttpClient {
         T: TryFrom<Log>,
         T::Error: Debug,
     {
-        let filter = FilterBuilder::default()
-            .address(vec![self.zksync_contract_addr])
-            .from_block(from)
-            .to_block(to)
-            .topics(Some(topics), None, None, None)
-            .build();
+        let from = self.resolve_block_number(from).await?;
+        let to = self.resolve_block_number(to).await?;
+        let topic_logs = future::try_join_all(
+            topics
+                .into_iter()
+                .map(|topic| self.get_topic_logs(from, to, topic)),
+        )
+        .await?;
 
-        let mut logs = self.client.logs(filter).await?;
-        let is_possible_to_sort_logs = logs.iter().all(|log| log.log_index.is_some());
+        let mut logs = dedup_logs(topic_logs.into_iter().flatten());
+        let is_possible_to_sort_logs = logs
+            .iter()
+            .all(|log| log.block_number.is_some() && log.log_index.is_some());
         if is_possible_to_sort_logs {
+            // Log index is unique within the block only.
             logs.sort_by_key(|log| {
-                log.log_index
-                    .expect("all logs log_index should have values")
+                (
+                    log.block_number
+                        .expect("all logs block_number should have values"),
+                    log.log_index
+                        .expect("all logs log_index should have values"),
+                )
             });
         } else {
-            vlog::warn!("Some of the log entries does not have log_index, we rely on the provided logs order");
+            vlog::warn!("Some of the log entries does not have block_number or log_index, we rely on the provided logs order");
         }
 
         logs.into_iter()
@@ -111,6 +215,22 @@ impl EthClient for EthHttpClient {
         result
     }
 
+    async fn get_message_events(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> anyhow::Result<Vec<L1Message>> {
+        let topic = match self.topics.message_sent {
+            Some(topic) => topic,
+            None => return Ok(Vec::new()),
+        };
+        let start = Instant::now();
+
+        let result = self.get_events(from, to, vec![topic]).await;
+        metrics::histogram!("eth_watcher.get_message_events", start.elapsed());
+        result
+    }
+
     async fn block_number(&self) -> anyhow::Result<u64> {
         Ok(self.client.block_number().await?.as_u64())
     }
@@ -146,9 +266,3 @@ impl EthClient for EthHttpClient {
             .map(|res: U256| res.as_u64())
     }
 }
-
-pub async fn get_web3_block_number(web3: &Web3<http::Http>) -> anyhow::Result<u64> {
-    let block_number = web3.eth().block_number().await?.as_u64();
-
-    Ok(block_number)
-}
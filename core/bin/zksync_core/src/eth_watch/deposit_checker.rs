@@ -0,0 +1,68 @@
+// External uses
+use web3::types::Address;
+// Workspace deps
+use zksync_storage::ConnectionPool;
+use zksync_types::{deposit_refund::DepositFailureReason, Deposit, PriorityOp};
+
+/// Checks the received deposits for the ones which funds would get stuck on L2.
+///
+/// Priority operations can't be skipped, so the unprocessable deposits are only flagged
+/// in the database: the operator decides whether the depositor should be refunded.
+///
+/// Deposits of the paused tokens are not flagged: the recipient owns the credited funds
+/// and can withdraw them once the token is unpaused, so a refund would pay the deposit twice.
+pub struct DepositChecker {
+    pool: ConnectionPool,
+    contract_addr: Address,
+}
+
+impl DepositChecker {
+    pub fn new(pool: ConnectionPool, contract_addr: Address) -> Self {
+        Self {
+            pool,
+            contract_addr,
+        }
+    }
+
+    /// Flags the unprocessable deposits among the received priority operations.
+    pub async fn flag_unprocessable(&self, ops: &[PriorityOp]) -> anyhow::Result<()> {
+        for op in ops {
+            let deposit = match op.data.try_get_deposit() {
+                Some(deposit) => deposit,
+                None => continue,
+            };
+            if let Some(reason) =
+                DepositFailureReason::check_recipient(&deposit, self.contract_addr)
+            {
+                self.flag_deposit(op, &deposit, reason).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn flag_deposit(
+        &self,
+        op: &PriorityOp,
+        deposit: &Deposit,
+        reason: DepositFailureReason,
+    ) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage().await?;
+        let flagged = storage
+            .deposit_refunds_schema()
+            .flag_deposit(op.serial_id, op.eth_hash, deposit, reason)
+            .await?;
+        // The operations are received again on restart, the action item is reported only once.
+        if flagged {
+            vlog::warn!(
+                "Action required: deposit #{} (tx {:?}) from {:?} can't be used on L2 ({}), \
+                 its refund can be requested or dismissed via the admin API",
+                op.serial_id,
+                op.eth_hash,
+                deposit.from,
+                reason
+            );
+            metrics::counter!("eth_watch.flagged_deposits", 1, "reason" => reason.as_str());
+        }
+        Ok(())
+    }
+}
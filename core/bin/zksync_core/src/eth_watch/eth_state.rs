@@ -2,7 +2,7 @@
 use std::collections::HashMap;
 // External uses
 // Workspace deps
-use zksync_types::{PriorityOp, SerialId};
+use zksync_types::{l1_message::L1Message, PriorityOp, SerialId};
 // Local deps
 use super::received_ops::ReceivedPriorityOp;
 
@@ -29,6 +29,9 @@ pub struct ETHState {
     /// Queue of priority operations that passed the confirmation
     /// threshold and are waiting to be executed.
     priority_queue: HashMap<u64, ReceivedPriorityOp>,
+    /// Messages sent to the zkSync accounts that passed the confirmation
+    /// threshold and are waiting to be delivered, keyed by their serial IDs.
+    l1_messages: HashMap<u64, L1Message>,
 }
 
 impl ETHState {
@@ -36,11 +39,13 @@ impl ETHState {
         last_ethereum_block: u64,
         unconfirmed_queue: Vec<PriorityOp>,
         priority_queue: HashMap<SerialId, ReceivedPriorityOp>,
+        l1_messages: HashMap<u64, L1Message>,
     ) -> Self {
         Self {
             last_ethereum_block,
             unconfirmed_queue,
             priority_queue,
+            l1_messages,
         }
     }
 
@@ -55,4 +60,8 @@ impl ETHState {
     pub fn unconfirmed_queue(&self) -> &[PriorityOp] {
         &self.unconfirmed_queue
     }
+
+    pub fn l1_messages(&self) -> &HashMap<u64, L1Message> {
+        &self.l1_messages
+    }
 }
@@ -0,0 +1,176 @@
+//! Sizing of the block ranges the logs are queried for.
+//!
+//! Public Ethereum providers limit the amount of the blocks or of the logs `eth_getLogs` may cover,
+//! and time out on the large ranges, so the large ranges are split into the smaller ones. The size
+//! of the range shrinks twice on every failed request and grows twice after a series of the
+//! successful ones, so the watcher settles on the largest range the provider is able to serve.
+
+// Built-in deps
+use std::collections::HashSet;
+// External uses
+use web3::types::Log;
+// Workspace deps
+use zksync_config::ETHWatchConfig;
+
+/// Amount of the consecutive successful requests after which the range grows.
+const GROW_AFTER_SUCCESSES: u32 = 4;
+
+#[derive(Debug, Clone)]
+pub struct LogsRange {
+    min_size: u64,
+    max_size: u64,
+    size: u64,
+    successes: u32,
+}
+
+impl LogsRange {
+    pub fn new(min_size: u64, max_size: u64) -> Self {
+        let min_size = min_size.max(1);
+        let max_size = max_size.max(min_size);
+        Self {
+            min_size,
+            max_size,
+            size: max_size,
+            successes: 0,
+        }
+    }
+
+    pub fn from_config(config: &ETHWatchConfig) -> Self {
+        Self::new(config.logs_min_block_range, config.logs_max_block_range)
+    }
+
+    /// Amount of the blocks the next request should cover.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Reports the successful request.
+    pub fn on_success(&mut self) {
+        self.successes += 1;
+        if self.successes >= GROW_AFTER_SUCCESSES && self.size < self.max_size {
+            self.size = self.size.saturating_mul(2).min(self.max_size);
+            self.successes = 0;
+            metrics::gauge!("eth_watcher.logs_range_size", self.size as f64);
+        }
+    }
+
+    /// Reports the failed request for the range of `failed_size` blocks. Returns `false` if the range
+    /// can't be shrunk anymore, so the request shouldn't be retried.
+    pub fn on_failure(&mut self, failed_size: u64) -> bool {
+        self.successes = 0;
+        if failed_size <= self.min_size {
+            return false;
+        }
+        self.size = (failed_size / 2).max(self.min_size);
+        metrics::gauge!("eth_watcher.logs_range_size", self.size as f64);
+        true
+    }
+}
+
+/// Removes the logs with the same transaction hash and log index, which are returned more than once
+/// by some providers. Logs without the hash or the index are kept as is.
+pub fn dedup_logs(logs: impl IntoIterator<Item = Log>) -> Vec<Log> {
+    let mut seen = HashSet::new();
+    let mut duplicates = 0;
+    let logs: Vec<_> = logs
+        .into_iter()
+        .filter(|log| match (log.transaction_hash, log.log_index) {
+            (Some(tx_hash), Some(log_index)) => {
+                let unique = seen.insert((tx_hash, log_index));
+                if !unique {
+                    duplicates += 1;
+                }
+                unique
+            }
+            _ => true,
+        })
+        .collect();
+    if duplicates > 0 {
+        vlog::debug!("{} duplicated logs were received", duplicates);
+        metrics::counter!("eth_watcher.duplicated_logs", duplicates);
+    }
+    logs
+}
+
+#[cfg(test)]
+mod tests {
+    use web3::types::{Bytes, H256, U256};
+
+    use super::*;
+
+    #[test]
+    fn range_sizing() {
+        let mut range = LogsRange::new(10, 1000);
+        assert_eq!(range.size(), 1000);
+
+        assert!(range.on_failure(1000));
+        assert_eq!(range.size(), 500);
+        // Size is derived from the failed request, not from the current size.
+        assert!(range.on_failure(300));
+        assert_eq!(range.size(), 150);
+        assert!(range.on_failure(15));
+        assert_eq!(range.size(), 10);
+        assert!(!range.on_failure(10));
+        assert_eq!(range.size(), 10);
+
+        for _ in 0..GROW_AFTER_SUCCESSES - 1 {
+            range.on_success();
+        }
+        assert_eq!(range.size(), 10);
+        range.on_success();
+        assert_eq!(range.size(), 20);
+
+        // Failure resets the series of the successful requests.
+        for _ in 0..GROW_AFTER_SUCCESSES - 1 {
+            range.on_success();
+        }
+        assert!(range.on_failure(20));
+        range.on_success();
+        assert_eq!(range.size(), 10);
+
+        for _ in 0..10 * GROW_AFTER_SUCCESSES {
+            range.on_success();
+        }
+        assert_eq!(range.size(), 1000);
+    }
+
+    #[test]
+    fn logs_dedup() {
+        let log = |tx_hash: Option<u64>, log_index: Option<u64>| Log {
+            address: Default::default(),
+            topics: Vec::new(),
+            data: Bytes(Vec::new()),
+            block_hash: None,
+            block_number: None,
+            transaction_hash: tx_hash.map(H256::from_low_u64_be),
+            transaction_index: None,
+            log_index: log_index.map(U256::from),
+            transaction_log_index: None,
+            log_type: None,
+            removed: None,
+        };
+
+        let logs = dedup_logs(vec![
+            log(Some(1), Some(0)),
+            log(Some(1), Some(1)),
+            log(Some(2), Some(0)),
+            log(Some(1), Some(0)),
+            log(None, Some(0)),
+            log(None, Some(0)),
+        ]);
+        let keys: Vec<_> = logs
+            .iter()
+            .map(|log| (log.transaction_hash, log.log_index))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                (Some(H256::from_low_u64_be(1)), Some(U256::from(0))),
+                (Some(H256::from_low_u64_be(1)), Some(U256::from(1))),
+                (Some(H256::from_low_u64_be(2)), Some(U256::from(0))),
+                (None, Some(U256::from(0))),
+                (None, Some(U256::from(0))),
+            ]
+        );
+    }
+}
@@ -4,6 +4,20 @@
 //!
 //! Poll interval is configured using the `ETH_POLL_INTERVAL` constant.
 //! Number of confirmations is configured using the `CONFIRMATIONS_FOR_ETH_EVENT` environment variable.
+//!
+//! By default the state is restored from the Ethereum node on each restart, which requires the node
+//! to provide the logs for the whole priority operation expiration period. If `ETH_WATCH_PERSIST_EVENTS`
+//! is set, received operations are persisted in the database and only the blocks that were not processed
+//! yet are queried from the node.
+//!
+//! Along with the priority operations, the watcher collects the messages sent to the zkSync accounts
+//! via the `MessageSent` event. Messages are not persisted: upon restart only the messages sent within
+//! the priority operation expiration period (or the recent blocks window, if events are persisted)
+//! are queried, so they're expected to be delivered long before that.
+//!
+//! Logs are queried in the block ranges adjusted to what the Ethereum node is able to serve
+//! (see `logs_range`), the priority operations and the messages are queried in parallel,
+//! and the logs returned more than once are deduplicated.
 
 // Built-in deps
 use std::{
@@ -14,7 +28,7 @@ use std::{
 // External uses
 use futures::{
     channel::{mpsc, oneshot},
-    SinkExt, StreamExt,
+    SinkExt, Stream, StreamExt,
 };
 
 use tokio::{task::JoinHandle, time};
@@ -22,23 +36,32 @@ use web3::types::{Address, BlockNumber};
 
 // Workspace deps
 use zksync_crypto::params::PRIORITY_EXPIRATION;
-use zksync_types::{Nonce, PriorityOp, PubKeyHash, ZkSyncPriorityOp};
+use zksync_types::{l1_message::L1Message, Nonce, PriorityOp, PubKeyHash, ZkSyncPriorityOp};
 
 // Local deps
 use self::{
-    client::EthClient,
+    client::{is_rate_limit_error, EthClient},
+    deposit_checker::DepositChecker,
     eth_state::ETHState,
-    received_ops::{sift_outdated_ops, ReceivedPriorityOp},
+    received_ops::{
+        check_queue_continuity, merge_received_ops, sift_outdated_ops, ReceivedPriorityOp,
+    },
 };
 
-pub use client::{get_web3_block_number, EthHttpClient};
+pub use client::EthHttpClient;
+pub use storage::{DatabaseEventsStorage, EventsStorage};
 use zksync_config::ZkSyncConfig;
 
 use zksync_eth_client::ethereum_gateway::EthereumGateway;
+use zksync_storage::ConnectionPool;
+use zksync_utils::supervisor::{SharedReceiver, Supervisor};
 
 mod client;
+mod deposit_checker;
 mod eth_state;
+mod logs_range;
 mod received_ops;
+mod storage;
 
 #[cfg(test)]
 mod tests;
@@ -88,6 +111,13 @@ pub enum EthWatchRequest {
         eth_hash: Vec<u8>,
         resp: oneshot::Sender<Option<PriorityOp>>,
     },
+    /// Requests the consecutive confirmed messages starting from the provided serial ID.
+    /// Messages with the lesser IDs are considered delivered and are no longer kept.
+    GetL1Messages {
+        first_serial_id: u64,
+        max_messages: usize,
+        resp: oneshot::Sender<Vec<L1Message>>,
+    },
 }
 
 pub struct EthWatch<W: EthClient> {
@@ -96,6 +126,15 @@ pub struct EthWatch<W: EthClient> {
     /// All ethereum events are accepted after sufficient confirmations to eliminate risk of block reorg.
     number_of_confirmations_for_event: u64,
     mode: WatcherMode,
+    /// Storage for the received priority operations, if they have to be persisted.
+    events_storage: Option<Box<dyn EventsStorage>>,
+    /// Amount of blocks to query if there is no persisted state to start from.
+    /// Never less than `PRIORITY_EXPIRATION`, so no unexpired priority operation is missed.
+    recent_blocks_window: u64,
+    /// Checker flagging the received deposits which funds would get stuck on L2.
+    deposit_checker: Option<DepositChecker>,
+    /// Serial ID of the first message not delivered yet, as reported by the block proposer.
+    next_l1_message_id: u64,
 }
 
 impl<W: EthClient> EthWatch<W> {
@@ -105,16 +144,46 @@ impl<W: EthClient> EthWatch<W> {
             eth_state: ETHState::default(),
             mode: WatcherMode::Working,
             number_of_confirmations_for_event,
+            events_storage: None,
+            recent_blocks_window: 0,
+            deposit_checker: None,
+            next_l1_message_id: 0,
         }
     }
 
+    /// Enables persisting of the received priority operations in the provided storage.
+    /// The window of the recent blocks is extended to `PRIORITY_EXPIRATION` if it's shorter.
+    pub fn with_events_storage(
+        mut self,
+        events_storage: Box<dyn EventsStorage>,
+        recent_blocks_window: u64,
+    ) -> Self {
+        if recent_blocks_window < PRIORITY_EXPIRATION {
+            vlog::warn!(
+                "Window of the recent blocks ({}) is shorter than the priority operations expiration, \
+                 {} blocks will be queried instead",
+                recent_blocks_window,
+                PRIORITY_EXPIRATION
+            );
+        }
+        self.events_storage = Some(events_storage);
+        self.recent_blocks_window = std::cmp::max(recent_blocks_window, PRIORITY_EXPIRATION);
+        self
+    }
+
+    /// Enables flagging of the unprocessable deposits, so they can be refunded by the operator.
+    pub fn with_deposit_checker(mut self, deposit_checker: DepositChecker) -> Self {
+        self.deposit_checker = Some(deposit_checker);
+        self
+    }
+
     /// Atomically replaces the stored Ethereum state.
     fn set_new_state(&mut self, new_state: ETHState) {
         self.eth_state = new_state;
     }
 
     async fn get_unconfirmed_ops(
-        &mut self,
+        &self,
         current_ethereum_block: u64,
     ) -> anyhow::Result<Vec<PriorityOp>> {
         // We want to scan the interval of blocks from the latest one up to the oldest one which may
@@ -143,56 +212,175 @@ impl<W: EthClient> EthWatch<W> {
         let block_difference =
             last_ethereum_block.saturating_sub(self.eth_state.last_ethereum_block());
 
-        let (unconfirmed_queue, received_priority_queue) = self
+        let (unconfirmed_queue, received_priority_queue, received_messages) = self
             .update_eth_state(last_ethereum_block, block_difference)
             .await?;
 
         // Extend the existing priority operations with the new ones.
         let mut priority_queue = sift_outdated_ops(self.eth_state.priority_queue());
-        for (serial_id, op) in received_priority_queue {
-            priority_queue.insert(serial_id, op);
-        }
+        let conflicts = merge_received_ops(&mut priority_queue, received_priority_queue);
+        report_queue_violations(&priority_queue, &conflicts);
 
-        let new_state = ETHState::new(last_ethereum_block, unconfirmed_queue, priority_queue);
+        // Keep the messages that are not delivered yet along with the new ones.
+        let mut l1_messages: HashMap<_, _> = self
+            .eth_state
+            .l1_messages()
+            .iter()
+            .filter(|(serial_id, _)| **serial_id >= self.next_l1_message_id)
+            .map(|(serial_id, message)| (*serial_id, message.clone()))
+            .collect();
+        l1_messages.extend(received_messages);
+
+        let new_state = ETHState::new(
+            last_ethereum_block,
+            unconfirmed_queue,
+            priority_queue,
+            l1_messages,
+        );
         self.set_new_state(new_state);
         Ok(())
     }
 
     async fn restore_state_from_eth(&mut self, last_ethereum_block: u64) -> anyhow::Result<()> {
-        let (unconfirmed_queue, priority_queue) = self
-            .update_eth_state(last_ethereum_block, PRIORITY_EXPIRATION)
-            .await?;
+        let (unconfirmed_queue, priority_queue, l1_messages) = if self.events_storage.is_some() {
+            self.restore_persisted_eth_state(last_ethereum_block)
+                .await?
+        } else {
+            self.update_eth_state(last_ethereum_block, PRIORITY_EXPIRATION)
+                .await?
+        };
 
-        let new_state = ETHState::new(last_ethereum_block, unconfirmed_queue, priority_queue);
+        let new_state = ETHState::new(
+            last_ethereum_block,
+            unconfirmed_queue,
+            priority_queue,
+            l1_messages,
+        );
 
         self.set_new_state(new_state);
         vlog::debug!("ETH state: {:#?}", self.eth_state);
         Ok(())
     }
 
-    async fn update_eth_state(
+    /// Restores the priority operations and the undelivered messages from the events storage,
+    /// querying from the Ethereum node only the blocks that were not processed before.
+    async fn restore_persisted_eth_state(
         &mut self,
         current_ethereum_block: u64,
+    ) -> anyhow::Result<(
+        Vec<PriorityOp>,
+        HashMap<u64, ReceivedPriorityOp>,
+        HashMap<u64, L1Message>,
+    )> {
+        let new_block_with_accepted_events =
+            current_ethereum_block.saturating_sub(self.number_of_confirmations_for_event);
+        let events_storage = self
+            .events_storage
+            .as_ref()
+            .expect("Events storage must be set");
+
+        let last_processed_block = match events_storage.last_processed_block().await? {
+            Some(block) => block,
+            None => match events_storage.backfill_block().await? {
+                Some(block) => {
+                    vlog::info!(
+                        "No persisted priority operations, starting from the block {} processed by data restore",
+                        block
+                    );
+                    block
+                }
+                None => {
+                    vlog::warn!(
+                        "No persisted priority operations and no data restore state, \
+                         only the last {} blocks will be queried",
+                        self.recent_blocks_window
+                    );
+                    new_block_with_accepted_events.saturating_sub(self.recent_blocks_window)
+                }
+            },
+        };
+        let persisted_ops = events_storage
+            .load_priority_ops(new_block_with_accepted_events.saturating_sub(PRIORITY_EXPIRATION))
+            .await?;
+        let persisted_messages = events_storage.load_undelivered_l1_messages().await?;
+
+        let unprocessed_blocks_amount =
+            new_block_with_accepted_events.saturating_sub(last_processed_block);
+        let (unconfirmed_queue, received_priority_queue, received_messages) = self
+            .update_eth_state(current_ethereum_block, unprocessed_blocks_amount)
+            .await?;
+
+        let mut priority_queue: HashMap<_, ReceivedPriorityOp> = persisted_ops
+            .into_iter()
+            .map(|priority_op| (priority_op.serial_id, priority_op.into()))
+            .collect();
+        let conflicts = merge_received_ops(&mut priority_queue, received_priority_queue);
+        report_queue_violations(&priority_queue, &conflicts);
+
+        let mut l1_messages: HashMap<_, _> = persisted_messages
+            .into_iter()
+            .map(|message| (message.serial_id, message))
+            .collect();
+        l1_messages.extend(received_messages);
+
+        Ok((unconfirmed_queue, priority_queue, l1_messages))
+    }
+
+    /// Queries the events emitted within the `unprocessed_blocks_amount` blocks that have enough
+    /// confirmations and persists them. Returns the unconfirmed operations, the confirmed ones and
+    /// the messages that are not delivered yet.
+    async fn update_eth_state(
+        &self,
+        current_ethereum_block: u64,
         unprocessed_blocks_amount: u64,
-    ) -> anyhow::Result<(Vec<PriorityOp>, HashMap<u64, ReceivedPriorityOp>)> {
+    ) -> anyhow::Result<(
+        Vec<PriorityOp>,
+        HashMap<u64, ReceivedPriorityOp>,
+        HashMap<u64, L1Message>,
+    )> {
         let new_block_with_accepted_events =
             current_ethereum_block.saturating_sub(self.number_of_confirmations_for_event);
         let previous_block_with_accepted_events =
             new_block_with_accepted_events.saturating_sub(unprocessed_blocks_amount);
 
-        let unconfirmed_queue = self.get_unconfirmed_ops(current_ethereum_block).await?;
-        let priority_queue = self
-            .client
-            .get_priority_op_events(
+        let (unconfirmed_queue, received_ops, received_messages) = futures::try_join!(
+            self.get_unconfirmed_ops(current_ethereum_block),
+            self.client.get_priority_op_events(
                 BlockNumber::Number(previous_block_with_accepted_events.into()),
                 BlockNumber::Number(new_block_with_accepted_events.into()),
-            )
-            .await?
+            ),
+            self.client.get_message_events(
+                BlockNumber::Number(previous_block_with_accepted_events.into()),
+                BlockNumber::Number(new_block_with_accepted_events.into()),
+            ),
+        )?;
+
+        // Operations and messages are stored together, so that the messages of the processed
+        // blocks are not lost on restart.
+        if let Some(events_storage) = &self.events_storage {
+            events_storage
+                .store_events(
+                    &received_ops,
+                    &received_messages,
+                    new_block_with_accepted_events,
+                )
+                .await?;
+        }
+        if let Some(deposit_checker) = &self.deposit_checker {
+            deposit_checker.flag_unprocessable(&received_ops).await?;
+        }
+
+        let priority_queue = received_ops
             .into_iter()
             .map(|priority_op| (priority_op.serial_id, priority_op.into()))
             .collect();
+        let l1_messages = received_messages
+            .into_iter()
+            .filter(|message| message.serial_id >= self.next_l1_message_id)
+            .map(|message| (message.serial_id, message))
+            .collect();
 
-        Ok((unconfirmed_queue, priority_queue))
+        Ok((unconfirmed_queue, priority_queue, l1_messages))
     }
 
     fn get_priority_requests(&self, first_serial_id: u64, max_chunks: usize) -> Vec<PriorityOp> {
@@ -228,6 +416,21 @@ impl<W: EthClient> EthWatch<W> {
         Ok(auth_fact.as_slice() == tiny_keccak::keccak256(&pub_key_hash.data[..]))
     }
 
+    fn get_l1_messages(&mut self, first_serial_id: u64, max_messages: usize) -> Vec<L1Message> {
+        self.next_l1_message_id = self.next_l1_message_id.max(first_serial_id);
+
+        let mut result = Vec::new();
+        let mut current_message = first_serial_id;
+        while let Some(message) = self.eth_state.l1_messages().get(&current_message) {
+            if result.len() >= max_messages {
+                break;
+            }
+            result.push(message.clone());
+            current_message += 1;
+        }
+        result
+    }
+
     fn find_ongoing_op_by_hash(&self, eth_hash: &[u8]) -> Option<PriorityOp> {
         self.eth_state
             .unconfirmed_queue()
@@ -278,9 +481,8 @@ impl<W: EthClient> EthWatch<W> {
         Ok(())
     }
 
-    // TODO try to move it to eth client
     fn is_backoff_requested(&self, error: &anyhow::Error) -> bool {
-        error.to_string().contains("429 Too Many Requests")
+        is_rate_limit_error(error)
     }
 
     fn enter_backoff_mode(&mut self) {
@@ -307,7 +509,7 @@ impl<W: EthClient> EthWatch<W> {
         }
     }
 
-    pub async fn run(mut self, mut eth_watch_req: mpsc::Receiver<EthWatchRequest>) {
+    pub async fn run(mut self, mut eth_watch_req: impl Stream<Item = EthWatchRequest> + Unpin) {
         // As infura may be not responsive, we want to retry the query until we've actually got the
         // block number.
         // Normally, however, this loop is not expected to last more than one iteration.
@@ -370,6 +572,14 @@ impl<W: EthClient> EthWatch<W> {
                     resp.send(self.get_priority_requests(op_start_id, max_chunks))
                         .unwrap_or_default();
                 }
+                EthWatchRequest::GetL1Messages {
+                    first_serial_id,
+                    max_messages,
+                    resp,
+                } => {
+                    resp.send(self.get_l1_messages(first_serial_id, max_messages))
+                        .unwrap_or_default();
+                }
                 EthWatchRequest::GetUnconfirmedDeposits { address, resp } => {
                     let deposits_for_address = self.get_ongoing_deposits_for(address);
                     resp.send(deposits_for_address).ok();
@@ -400,20 +610,50 @@ impl<W: EthClient> EthWatch<W> {
 }
 
 #[must_use]
+/// Reports the operations conflicting with the known ones and the gaps in the priority queue.
+/// Operations after a gap are not provided to the mempool until the missing one is received.
+fn report_queue_violations(priority_queue: &HashMap<u64, ReceivedPriorityOp>, conflicts: &[u64]) {
+    if !conflicts.is_empty() {
+        vlog::error!(
+            "Received priority operations conflicting with the known ones, the known ones are replaced: {:?}",
+            conflicts
+        );
+        metrics::counter!("eth_watch.priority_op_conflicts", conflicts.len() as u64);
+    }
+    if let Err(err) = check_queue_continuity(priority_queue) {
+        vlog::error!("Priority queue is not continuous: {}", err);
+        metrics::counter!("eth_watch.priority_op_gaps", 1);
+    }
+}
+
 pub fn start_eth_watch(
     eth_req_sender: mpsc::Sender<EthWatchRequest>,
     eth_req_receiver: mpsc::Receiver<EthWatchRequest>,
     eth_gateway: EthereumGateway,
+    connection_pool: ConnectionPool,
     config_options: &ZkSyncConfig,
+    supervisor: &Supervisor,
 ) -> JoinHandle<()> {
-    let eth_client = EthHttpClient::new(eth_gateway, config_options.contracts.contract_addr);
-
-    let eth_watch = EthWatch::new(
-        eth_client,
-        config_options.eth_watch.confirmations_for_eth_event,
-    );
-
-    tokio::spawn(eth_watch.run(eth_req_receiver));
+    let contract_addr = config_options.contracts.contract_addr;
+    let confirmations_for_eth_event = config_options.eth_watch.confirmations_for_eth_event;
+    let persist_events = config_options.eth_watch.persist_events;
+    let recent_blocks_window = config_options.eth_watch.recent_blocks_window;
+    let eth_watch_config = config_options.eth_watch.clone();
+    let eth_req_receiver = SharedReceiver::from(eth_req_receiver);
+    // Watcher state is restored on each restart, either from the Ethereum node or
+    // from the persisted events.
+    supervisor.spawn("eth_watch", move || {
+        let eth_client = EthHttpClient::new(eth_gateway.clone(), contract_addr, &eth_watch_config);
+        let mut eth_watch = EthWatch::new(eth_client, confirmations_for_eth_event);
+        if persist_events {
+            let events_storage = DatabaseEventsStorage::new(connection_pool.clone());
+            eth_watch =
+                eth_watch.with_events_storage(Box::new(events_storage), recent_blocks_window);
+        }
+        let deposit_checker = DepositChecker::new(connection_pool.clone(), contract_addr);
+        eth_watch = eth_watch.with_deposit_checker(deposit_checker);
+        eth_watch.run(eth_req_receiver.clone())
+    });
 
     let poll_interval = config_options.eth_watch.poll_interval();
     tokio::spawn(async move {
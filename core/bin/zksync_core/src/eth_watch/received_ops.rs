@@ -4,7 +4,10 @@ use std::{
     time::{Duration, Instant},
 };
 // Workspace deps
-use zksync_types::PriorityOp;
+use zksync_types::{
+    priority_ops::{check_serial_id_continuity, SerialIdContinuityError},
+    PriorityOp,
+};
 
 pub const SECS_IN_HOUR: u64 = 3600;
 
@@ -64,3 +67,39 @@ pub fn sift_outdated_ops(
         })
         .collect()
 }
+
+/// Adds the received operations to the queue.
+///
+/// Serial IDs are assigned by the contract, so the same serial ID can only be received again for
+/// the same operation. A different operation with the known serial ID means that the chain was
+/// reorganized deeper than the confirmations the watcher waits for, or the node served the events
+/// of another chain before. The freshly received operation reflects the current chain, so it replaces
+/// the known one. Returns the serial IDs of the replaced operations, which must be reported, since
+/// the replaced operations may have been executed already.
+pub fn merge_received_ops(
+    ops: &mut HashMap<u64, ReceivedPriorityOp>,
+    received: HashMap<u64, ReceivedPriorityOp>,
+) -> Vec<u64> {
+    let mut conflicts = Vec::new();
+    for (serial_id, op) in received {
+        if let Some(known) = ops.insert(serial_id, op) {
+            if known.op.eth_hash != ops[&serial_id].op.eth_hash {
+                conflicts.push(serial_id);
+            }
+        }
+    }
+    conflicts.sort_unstable();
+    conflicts
+}
+
+/// Checks that there are no gaps between the serial IDs of the queued operations.
+pub fn check_queue_continuity(
+    ops: &HashMap<u64, ReceivedPriorityOp>,
+) -> Result<(), SerialIdContinuityError> {
+    let mut serial_ids: Vec<_> = ops.keys().copied().collect();
+    serial_ids.sort_unstable();
+    if let Some(&first_serial_id) = serial_ids.first() {
+        check_serial_id_continuity(first_serial_id, serial_ids)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,94 @@
+// Workspace deps
+use zksync_storage::ConnectionPool;
+use zksync_types::{l1_message::L1Message, PriorityOp};
+
+/// Storage of the priority operations and the L1 messages received by `eth_watch`.
+///
+/// Persisted events allow `eth_watch` to restore its state after restart by querying
+/// only the recent blocks from the Ethereum node, which is required for the nodes that
+/// don't keep the old logs.
+#[async_trait::async_trait]
+pub trait EventsStorage: Send + Sync {
+    /// Returns the last Ethereum block for which all the priority operations are persisted.
+    async fn last_processed_block(&self) -> anyhow::Result<Option<u64>>;
+    /// Returns the block to start from if no operations were persisted yet.
+    async fn backfill_block(&self) -> anyhow::Result<Option<u64>>;
+    /// Loads the priority operations emitted in the blocks starting from `from_block`.
+    async fn load_priority_ops(&self, from_block: u64) -> anyhow::Result<Vec<PriorityOp>>;
+    /// Loads the persisted messages that are not delivered yet.
+    async fn load_undelivered_l1_messages(&self) -> anyhow::Result<Vec<L1Message>>;
+    /// Stores the priority operations and the messages emitted in the blocks up to
+    /// `last_processed_block`.
+    async fn store_events(
+        &self,
+        ops: &[PriorityOp],
+        messages: &[L1Message],
+        last_processed_block: u64,
+    ) -> anyhow::Result<()>;
+}
+
+/// Events storage backed by the server database.
+///
+/// If no operations were persisted, the last Ethereum block processed by the data restore
+/// is used as a starting point: all the operations before it are already included in the
+/// restored blocks.
+pub struct DatabaseEventsStorage {
+    pool: ConnectionPool,
+}
+
+impl DatabaseEventsStorage {
+    pub fn new(pool: ConnectionPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventsStorage for DatabaseEventsStorage {
+    async fn last_processed_block(&self) -> anyhow::Result<Option<u64>> {
+        let mut storage = self.pool.access_storage().await?;
+        storage.eth_watch_schema().load_last_processed_block().await
+    }
+
+    async fn backfill_block(&self) -> anyhow::Result<Option<u64>> {
+        let mut storage = self.pool.access_storage().await?;
+        let last_watched_block = storage
+            .data_restore_schema()
+            .try_load_last_watched_block_number()
+            .await?;
+
+        match last_watched_block {
+            Some(block) => Ok(Some(block.block_number.parse()?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn load_priority_ops(&self, from_block: u64) -> anyhow::Result<Vec<PriorityOp>> {
+        let mut storage = self.pool.access_storage().await?;
+        storage
+            .eth_watch_schema()
+            .load_priority_ops(from_block)
+            .await
+    }
+
+    async fn load_undelivered_l1_messages(&self) -> anyhow::Result<Vec<L1Message>> {
+        let mut storage = self.pool.access_storage().await?;
+        let next_message_id = storage.l1_messages_schema().next_message_id().await?;
+        storage
+            .eth_watch_schema()
+            .load_l1_messages(next_message_id)
+            .await
+    }
+
+    async fn store_events(
+        &self,
+        ops: &[PriorityOp],
+        messages: &[L1Message],
+        last_processed_block: u64,
+    ) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage().await?;
+        storage
+            .eth_watch_schema()
+            .store_events(ops, messages, last_processed_block)
+            .await
+    }
+}
@@ -3,22 +3,37 @@ use std::collections::HashMap;
 
 use web3::types::{Address, BlockNumber};
 
-use zksync_types::{AccountId, Deposit, FullExit, Nonce, PriorityOp, TokenId, ZkSyncPriorityOp};
+use zksync_types::{
+    l1_message::L1Message, priority_ops::SerialIdContinuityError, AccountId, Deposit, FullExit,
+    Nonce, PriorityOp, PubKeyHash, TokenId, ZkSyncPriorityOp, H256,
+};
 
-use crate::eth_watch::{client::EthClient, EthWatch};
+use crate::eth_watch::{
+    client::EthClient,
+    received_ops::{check_queue_continuity, merge_received_ops, ReceivedPriorityOp},
+    EthWatch, EventsStorage,
+};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 struct FakeEthClientData {
     priority_ops: HashMap<u64, Vec<PriorityOp>>,
+    messages: HashMap<u64, Vec<L1Message>>,
     last_block_number: u64,
+    auth_facts: HashMap<(Address, Nonce), Vec<u8>>,
+    auth_fact_reset_times: HashMap<(Address, Nonce), u64>,
+    auth_fact_requests: usize,
 }
 
 impl FakeEthClientData {
     fn new() -> Self {
         Self {
             priority_ops: Default::default(),
+            messages: Default::default(),
             last_block_number: 0,
+            auth_facts: Default::default(),
+            auth_fact_reset_times: Default::default(),
+            auth_fact_requests: 0,
         }
     }
 
@@ -49,6 +64,35 @@ impl FakeEthClient {
         self.inner.write().await.add_operations(ops);
     }
 
+    async fn add_messages(&mut self, messages: &[L1Message]) {
+        let mut inner = self.inner.write().await;
+        for message in messages {
+            inner.last_block_number = max(message.eth_block, inner.last_block_number);
+            inner
+                .messages
+                .entry(message.eth_block)
+                .or_insert_with(Vec::new)
+                .push(message.clone());
+        }
+    }
+
+    async fn set_auth_fact(&mut self, address: Address, nonce: Nonce) {
+        let fact = tiny_keccak::keccak256(&PubKeyHash::zero().data[..]).to_vec();
+        self.inner
+            .write()
+            .await
+            .auth_facts
+            .insert((address, nonce), fact);
+    }
+
+    async fn reset_auth_fact(&mut self, address: Address, nonce: Nonce, reset_time: u64) {
+        self.inner
+            .write()
+            .await
+            .auth_fact_reset_times
+            .insert((address, nonce), reset_time);
+    }
+
     async fn block_to_number(&self, block: &BlockNumber) -> u64 {
         match block {
             BlockNumber::Latest => self.inner.read().await.last_block_number,
@@ -77,24 +121,53 @@ impl EthClient for FakeEthClient {
         Ok(operations)
     }
 
+    async fn get_message_events(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> Result<Vec<L1Message>, anyhow::Error> {
+        let from = self.block_to_number(&from).await;
+        let to = self.block_to_number(&to).await;
+        let mut messages = vec![];
+        for number in from..=to {
+            if let Some(block_messages) = self.inner.read().await.messages.get(&number) {
+                messages.extend_from_slice(block_messages);
+            }
+        }
+        Ok(messages)
+    }
+
     async fn block_number(&self) -> Result<u64, anyhow::Error> {
         Ok(self.inner.read().await.last_block_number)
     }
 
     async fn get_auth_fact(
         &self,
-        _address: Address,
-        _nonce: Nonce,
+        address: Address,
+        nonce: Nonce,
     ) -> Result<Vec<u8>, anyhow::Error> {
-        unreachable!()
+        let mut inner = self.inner.write().await;
+        inner.auth_fact_requests += 1;
+        Ok(inner
+            .auth_facts
+            .get(&(address, nonce))
+            .cloned()
+            .unwrap_or_default())
     }
 
     async fn get_auth_fact_reset_time(
         &self,
-        _address: Address,
-        _nonce: Nonce,
+        address: Address,
+        nonce: Nonce,
     ) -> Result<u64, anyhow::Error> {
-        unreachable!()
+        Ok(self
+            .inner
+            .read()
+            .await
+            .auth_fact_reset_times
+            .get(&(address, nonce))
+            .copied()
+            .unwrap_or_default())
     }
 }
 
@@ -102,6 +175,69 @@ fn create_watcher<T: EthClient>(client: T) -> EthWatch<T> {
     EthWatch::new(client, 1)
 }
 
+#[derive(Default)]
+struct FakeEventsStorageData {
+    priority_ops: HashMap<u64, PriorityOp>,
+    messages: HashMap<u64, L1Message>,
+    next_message_id: u64,
+    last_processed_block: Option<u64>,
+}
+
+#[derive(Clone, Default)]
+struct FakeEventsStorage {
+    inner: Arc<RwLock<FakeEventsStorageData>>,
+}
+
+#[async_trait::async_trait]
+impl EventsStorage for FakeEventsStorage {
+    async fn last_processed_block(&self) -> anyhow::Result<Option<u64>> {
+        Ok(self.inner.read().await.last_processed_block)
+    }
+
+    async fn backfill_block(&self) -> anyhow::Result<Option<u64>> {
+        Ok(None)
+    }
+
+    async fn load_priority_ops(&self, from_block: u64) -> anyhow::Result<Vec<PriorityOp>> {
+        Ok(self
+            .inner
+            .read()
+            .await
+            .priority_ops
+            .values()
+            .filter(|op| op.eth_block >= from_block)
+            .cloned()
+            .collect())
+    }
+
+    async fn load_undelivered_l1_messages(&self) -> anyhow::Result<Vec<L1Message>> {
+        let inner = self.inner.read().await;
+        Ok(inner
+            .messages
+            .values()
+            .filter(|message| message.serial_id >= inner.next_message_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn store_events(
+        &self,
+        ops: &[PriorityOp],
+        messages: &[L1Message],
+        last_processed_block: u64,
+    ) -> anyhow::Result<()> {
+        let mut inner = self.inner.write().await;
+        for op in ops {
+            inner.priority_ops.insert(op.serial_id, op.clone());
+        }
+        for message in messages {
+            inner.messages.insert(message.serial_id, message.clone());
+        }
+        inner.last_processed_block = Some(last_processed_block);
+        Ok(())
+    }
+}
+
 #[tokio::test]
 async fn test_operation_queues() {
     let mut client = FakeEthClient::new();
@@ -364,3 +500,209 @@ async fn test_restore_and_poll_time_lag() {
     priority_queues.get(&0).unwrap();
     priority_queues.get(&1).unwrap();
 }
+
+fn message(serial_id: u64, eth_block: u64) -> L1Message {
+    L1Message {
+        serial_id,
+        sender: Address::repeat_byte(1),
+        recipient: Address::repeat_byte(2),
+        payload: vec![serial_id as u8],
+        eth_hash: [serial_id as u8; 32].into(),
+        eth_block,
+    }
+}
+
+/// Checks that only the confirmed messages are served, and that the delivered ones are dropped.
+#[tokio::test]
+async fn test_l1_messages() {
+    let mut client = FakeEthClient::new();
+    client
+        .add_messages(&[message(0, 1), message(1, 1), message(2, 3)])
+        .await;
+
+    let mut watcher = create_watcher(client.clone());
+    watcher.restore_state_from_eth(3).await.unwrap();
+    // The last message is not confirmed yet.
+    let messages = watcher.get_l1_messages(0, 10);
+    assert_eq!(messages, vec![message(0, 1), message(1, 1)]);
+    assert_eq!(watcher.get_l1_messages(0, 1), vec![message(0, 1)]);
+
+    // The first message is delivered, so it's dropped once the new block is processed.
+    assert_eq!(watcher.get_l1_messages(1, 10), vec![message(1, 1)]);
+    client.add_messages(&[message(3, 5)]).await;
+    watcher.poll_eth_node().await.unwrap();
+    assert!(!watcher.eth_state.l1_messages().contains_key(&0));
+    assert_eq!(
+        watcher.get_l1_messages(1, 10),
+        vec![message(1, 1), message(2, 3)]
+    );
+}
+
+/// Checks that the onchain `ChangePubKey` authorizations are checked against the auth facts,
+/// and the reset ones are rejected without querying the auth fact.
+#[tokio::test]
+async fn test_pubkey_change_authorization() {
+    let mut client = FakeEthClient::new();
+    let address = Address::repeat_byte(1);
+    client.set_auth_fact(address, Nonce(0)).await;
+    client.set_auth_fact(address, Nonce(1)).await;
+    client.reset_auth_fact(address, Nonce(1), 1_000).await;
+    let watcher = create_watcher(client.clone());
+
+    assert!(watcher
+        .is_new_pubkey_hash_authorized(address, Nonce(0), &PubKeyHash::zero())
+        .await
+        .unwrap());
+    // Authorization of another public key hash or nonce is not set.
+    assert!(!watcher
+        .is_new_pubkey_hash_authorized(address, Nonce(0), &PubKeyHash { data: [1; 20] })
+        .await
+        .unwrap());
+    assert!(!watcher
+        .is_new_pubkey_hash_authorized(address, Nonce(2), &PubKeyHash::zero())
+        .await
+        .unwrap());
+    assert_eq!(client.inner.read().await.auth_fact_requests, 3);
+
+    assert!(!watcher
+        .is_new_pubkey_hash_authorized(address, Nonce(1), &PubKeyHash::zero())
+        .await
+        .unwrap());
+    assert_eq!(client.inner.read().await.auth_fact_requests, 3);
+}
+
+/// Checks that with the persisted events only the blocks that were not processed yet
+/// are queried from the Ethereum node.
+#[tokio::test]
+async fn test_restore_from_persisted_events() {
+    let deposit = |serial_id: u64, eth_block: u64| PriorityOp {
+        serial_id,
+        data: ZkSyncPriorityOp::Deposit(Deposit {
+            from: Default::default(),
+            token: TokenId(0),
+            amount: Default::default(),
+            to: Default::default(),
+        }),
+        deadline_block: 0,
+        eth_hash: [serial_id as u8; 32].into(),
+        eth_block,
+    };
+
+    let events_storage = FakeEventsStorage::default();
+    events_storage
+        .store_events(&[deposit(0, 2)], &[], 3)
+        .await
+        .unwrap();
+
+    // Logs of the old blocks are not available on the node, except for the operation
+    // that must not be queried since its block is already processed.
+    let mut client = FakeEthClient::new();
+    client.add_operations(&[deposit(5, 2), deposit(1, 5)]).await;
+
+    let mut watcher =
+        create_watcher(client).with_events_storage(Box::new(events_storage.clone()), 100);
+    watcher.restore_state_from_eth(6).await.unwrap();
+
+    let priority_queue = watcher.eth_state.priority_queue();
+    assert_eq!(priority_queue.len(), 2);
+    assert!(priority_queue.contains_key(&0));
+    assert!(priority_queue.contains_key(&1));
+
+    let stored = events_storage.inner.read().await;
+    assert_eq!(stored.last_processed_block, Some(5));
+    assert!(stored.priority_ops.contains_key(&1));
+    assert!(!stored.priority_ops.contains_key(&5));
+}
+
+/// Checks that the window of the recent blocks queried on the first start covers all
+/// the unexpired priority operations, even if it's configured shorter.
+#[tokio::test]
+async fn test_recent_blocks_window_covers_expiration() {
+    let deposit = PriorityOp {
+        serial_id: 0,
+        data: ZkSyncPriorityOp::Deposit(Deposit {
+            from: Default::default(),
+            token: TokenId(0),
+            amount: Default::default(),
+            to: Default::default(),
+        }),
+        deadline_block: 0,
+        eth_hash: [0; 32].into(),
+        eth_block: 10,
+    };
+    let mut client = FakeEthClient::new();
+    client.add_operations(&[deposit]).await;
+
+    let mut watcher =
+        create_watcher(client).with_events_storage(Box::new(FakeEventsStorage::default()), 100);
+    watcher.restore_state_from_eth(1_000).await.unwrap();
+
+    assert!(watcher.eth_state.priority_queue().contains_key(&0));
+}
+
+/// Checks that the messages received before restart are restored from the events storage
+/// even if their blocks are out of the queried range, while the delivered ones are skipped.
+#[tokio::test]
+async fn test_restore_persisted_l1_messages() {
+    let mut client = FakeEthClient::new();
+    client.add_messages(&[message(0, 1), message(1, 2)]).await;
+
+    let events_storage = FakeEventsStorage::default();
+    let mut watcher =
+        create_watcher(client.clone()).with_events_storage(Box::new(events_storage.clone()), 100);
+    watcher.restore_state_from_eth(3).await.unwrap();
+    assert_eq!(events_storage.inner.read().await.messages.len(), 2);
+
+    // Logs of the old blocks are pruned by the node, and the first message is delivered.
+    client.inner.write().await.messages.clear();
+    client.add_messages(&[message(2, 10)]).await;
+    events_storage.inner.write().await.next_message_id = 1;
+
+    let mut watcher = create_watcher(client).with_events_storage(Box::new(events_storage), 100);
+    watcher.restore_state_from_eth(11).await.unwrap();
+    assert_eq!(
+        watcher.get_l1_messages(1, 10),
+        vec![message(1, 2), message(2, 10)]
+    );
+    assert!(!watcher.eth_state.l1_messages().contains_key(&0));
+}
+
+#[test]
+fn test_priority_queue_continuity() {
+    let op = |serial_id: u64, eth_hash: u8| -> (u64, ReceivedPriorityOp) {
+        (
+            serial_id,
+            PriorityOp {
+                serial_id,
+                data: ZkSyncPriorityOp::FullExit(FullExit {
+                    account_id: AccountId(1),
+                    eth_address: Address::default(),
+                    token: TokenId(0),
+                }),
+                deadline_block: 0,
+                eth_hash: H256::repeat_byte(eth_hash),
+                eth_block: serial_id,
+            }
+            .into(),
+        )
+    };
+
+    let mut queue = vec![op(0, 0), op(1, 1)].into_iter().collect();
+    // The same operation can be received again, e.g. because of the overlapping block ranges.
+    let conflicts = merge_received_ops(&mut queue, vec![op(1, 1), op(2, 2)].into_iter().collect());
+    assert!(conflicts.is_empty());
+    assert_eq!(queue.len(), 3);
+    assert_eq!(check_queue_continuity(&queue), Ok(()));
+
+    // Another operation with the known serial ID replaces the known one and is reported.
+    let conflicts = merge_received_ops(&mut queue, vec![op(2, 5), op(4, 4)].into_iter().collect());
+    assert_eq!(conflicts, vec![2]);
+    assert_eq!(queue[&2].as_ref().eth_hash, H256::repeat_byte(5));
+    assert_eq!(
+        check_queue_continuity(&queue),
+        Err(SerialIdContinuityError::Gap {
+            expected: 3,
+            found: 4
+        })
+    );
+}
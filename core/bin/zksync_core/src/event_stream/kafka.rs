@@ -0,0 +1,56 @@
+// Built-in uses
+// External uses
+use async_trait::async_trait;
+use futures::future::try_join_all;
+use rdkafka::{
+    config::ClientConfig,
+    producer::{FutureProducer, FutureRecord},
+    util::Timeout,
+};
+// Workspace uses
+use zksync_config::EventStreamConfig;
+use zksync_types::event::EventOffset;
+// Local uses
+use super::EventSink;
+
+/// Publishes the events to the Kafka topic. Offset of the event is used as the message key.
+pub struct KafkaSink {
+    producer: FutureProducer,
+    topic: String,
+    timeout: Timeout,
+}
+
+impl KafkaSink {
+    pub fn new(config: &EventStreamConfig) -> anyhow::Result<Self> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", &config.servers)
+            // Keep the order of the events in case of retries.
+            .set("enable.idempotence", "true")
+            .create()?;
+
+        Ok(Self {
+            producer,
+            topic: config.topic.clone(),
+            timeout: Timeout::After(config.publish_timeout()),
+        })
+    }
+}
+
+#[async_trait]
+impl EventSink for KafkaSink {
+    async fn publish(&self, events: Vec<(EventOffset, Vec<u8>)>) -> anyhow::Result<()> {
+        let keys = events
+            .iter()
+            .map(|(offset, _)| offset.to_string())
+            .collect::<Vec<_>>();
+        let deliveries = events.iter().zip(&keys).map(|((_, payload), key)| {
+            let record = FutureRecord::to(&self.topic).key(key).payload(payload);
+            self.producer.send(record, self.timeout)
+        });
+
+        try_join_all(deliveries)
+            .await
+            .map_err(|(err, _)| anyhow::anyhow!("Kafka delivery failed: {}", err))?;
+        Ok(())
+    }
+}
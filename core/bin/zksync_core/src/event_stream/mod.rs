@@ -0,0 +1,138 @@
+//! Event stream publishes the chain events to a message broker (Kafka or NATS), so the analytics
+//! pipelines can follow the chain without polling the API.
+//!
+//! The publisher follows the event log (see `zksync_types::event`) and publishes every event as
+//! a JSON-serialized `StreamedEvent`, in the offset order. The progress is stored in the database
+//! only after the sink has confirmed the events, and the guarantee depends on the broker:
+//!
+//! - Kafka acknowledges the events once they're written to the topic, so they're delivered
+//!   at least once: after a restart some of the events may be published again, and the consumers
+//!   should skip the events with the offsets they have already processed.
+//! - NATS (core, without JetStream) only confirms that the server has received the events. They're
+//!   not persisted, so the events published while a consumer is disconnected are lost for it.
+//!   Consumers requiring every event should use Kafka or catch up from the API.
+//!
+//! Brokers are supported via the optional features (`kafka` and `nats`) to not pull the client
+//! libraries into the builds which don't need them.
+
+// Built-in uses
+// External uses
+use async_trait::async_trait;
+use tokio::{task::JoinHandle, time};
+// Workspace uses
+use zksync_config::{configs::event_stream::EventStreamBackend, EventStreamConfig, ZkSyncConfig};
+use zksync_storage::ConnectionPool;
+use zksync_types::event::{EventOffset, StreamedEvent};
+
+#[cfg(feature = "kafka")]
+mod kafka;
+#[cfg(feature = "nats")]
+mod nats;
+
+/// Name of the publisher in the list of the event log consumers.
+const CONSUMER_NAME: &str = "event_stream";
+
+/// Message broker client.
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    /// Publishes the serialized events in the given order.
+    /// Returns once all of them are confirmed by the broker, see the module docs for what
+    /// the confirmation guarantees.
+    async fn publish(&self, events: Vec<(EventOffset, Vec<u8>)>) -> anyhow::Result<()>;
+}
+
+fn create_sink(config: &EventStreamConfig) -> anyhow::Result<Box<dyn EventSink>> {
+    match config.backend {
+        #[cfg(feature = "kafka")]
+        EventStreamBackend::Kafka => Ok(Box::new(kafka::KafkaSink::new(config)?)),
+        #[cfg(feature = "nats")]
+        EventStreamBackend::Nats => Ok(Box::new(nats::NatsSink::new(config)?)),
+        #[allow(unreachable_patterns)]
+        backend => anyhow::bail!(
+            "Event stream backend {:?} requires the server to be built with its feature",
+            backend
+        ),
+    }
+}
+
+struct EventPublisher {
+    config: EventStreamConfig,
+    db_pool: ConnectionPool,
+    sink: Box<dyn EventSink>,
+}
+
+impl EventPublisher {
+    /// Publishes the next batch of the events. Returns `false` if there are no new events.
+    async fn publish_next_batch(&self) -> anyhow::Result<bool> {
+        let mut storage = self.db_pool.access_storage().await?;
+
+        let from_offset = match storage
+            .event_schema()
+            .get_consumer_offset(CONSUMER_NAME)
+            .await?
+        {
+            Some(offset) => offset,
+            // Event log offsets start from 1, so the whole log is published.
+            None => 0,
+        };
+        let records = storage
+            .event_schema()
+            .load_events(from_offset, self.config.batch_size)
+            .await?;
+        let next_offset = match records.last() {
+            Some(record) => record.offset + 1,
+            None => return Ok(false),
+        };
+
+        let events = records
+            .into_iter()
+            .map(|record| {
+                let offset = record.offset;
+                serde_json::to_vec(&StreamedEvent::from(record)).map(|event| (offset, event))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.sink.publish(events).await?;
+
+        storage
+            .event_schema()
+            .update_consumer_offset(CONSUMER_NAME, next_offset)
+            .await?;
+        Ok(true)
+    }
+}
+
+#[must_use]
+pub fn run_event_stream_publisher(
+    config: &ZkSyncConfig,
+    db_pool: ConnectionPool,
+) -> Option<JoinHandle<()>> {
+    if !config.event_stream.enabled {
+        return None;
+    }
+
+    let sink = create_sink(&config.event_stream).expect("failed to create event stream sink");
+    let publisher = EventPublisher {
+        config: config.event_stream.clone(),
+        db_pool,
+        sink,
+    };
+    let mut timer = time::interval(publisher.config.publish_interval());
+
+    Some(tokio::spawn(async move {
+        loop {
+            timer.tick().await;
+
+            // Catch up with the event log before waiting for the next tick.
+            loop {
+                match publisher.publish_next_batch().await {
+                    Ok(true) => {}
+                    Ok(false) => break,
+                    Err(err) => {
+                        vlog::error!("Failed to publish events to the event stream: {}", err);
+                        break;
+                    }
+                }
+            }
+        }
+    }))
+}
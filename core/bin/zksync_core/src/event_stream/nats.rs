@@ -0,0 +1,49 @@
+// Built-in uses
+// External uses
+use async_trait::async_trait;
+// Workspace uses
+use zksync_config::EventStreamConfig;
+use zksync_types::event::EventOffset;
+// Local uses
+use super::EventSink;
+
+/// Publishes the events to the NATS subject. Core NATS doesn't persist the messages, so
+/// the events are delivered only to the subscribers connected at the time of publishing.
+pub struct NatsSink {
+    connection: nats_client::Connection,
+    subject: String,
+    timeout: std::time::Duration,
+}
+
+impl NatsSink {
+    pub fn new(config: &EventStreamConfig) -> anyhow::Result<Self> {
+        let connection = nats_client::connect(&config.servers)?;
+
+        Ok(Self {
+            connection,
+            subject: config.topic.clone(),
+            timeout: config.publish_timeout(),
+        })
+    }
+}
+
+#[async_trait]
+impl EventSink for NatsSink {
+    async fn publish(&self, events: Vec<(EventOffset, Vec<u8>)>) -> anyhow::Result<()> {
+        let connection = self.connection.clone();
+        let subject = self.subject.clone();
+        let timeout = self.timeout;
+
+        // The client is blocking, so it's used outside of the async runtime threads.
+        tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+            for (_, payload) in events {
+                connection.publish(&subject, payload)?;
+            }
+            // Flush ensures that the server has received all the published events, but not that
+            // they're delivered to the subscribers.
+            connection.flush_timeout(timeout)?;
+            Ok(())
+        })
+        .await?
+    }
+}
@@ -0,0 +1,604 @@
+//! Fee sweeper moves the fees collected by the fee account to the treasury.
+//!
+//! The fees of every block are credited to the account configured as `state_keeper.fee_account_addr`,
+//! which doesn't have to be the account committing the blocks on L1. Once the balance of the token
+//! reaches the configured threshold, the sweeper signs a zero-fee transfer (or withdrawal) of the
+//! balance to the treasury with the fee account key and adds it to the mempool directly.
+//!
+//! Every sweep is recorded in the database before it's sent, and its outcome is tracked until the
+//! transaction is executed in a sealed block. New sweeps are sent only once all the previous ones
+//! are resolved, so the balances and the nonce of the fee account are always up to date. A sent
+//! transaction which is neither executed nor queued in the mempool (e.g. the server stopped before
+//! it was added to the mempool) will never be executed, so it's considered failed.
+//!
+//! With the fee refunds enabled, the sweeper also refunds the part of the signed fee exceeding the
+//! fee computed by the server on the transaction submission. Refunds are sent once the transaction
+//! is executed in a sealed block, and the refunded amounts are excluded from the swept balances.
+//!
+//! Refunds are deliberately regular transfers from the fee account: the fee is a part of the signed
+//! transaction data and the circuit always charges the signed fee to the fee account, so there is
+//! no way to charge less within the transaction itself. The refund transfer is recorded before it's
+//! added to the mempool, but the refund is marked sent only once the mempool accepts it. If the server
+//! stops in between, the recorded transfer is looked up on the next iteration, so the refund is never
+//! sent twice.
+
+// Built-in uses
+use std::collections::HashMap;
+// External uses
+use futures::{
+    channel::{mpsc, oneshot},
+    SinkExt,
+};
+use num::BigUint;
+use tokio::{task::JoinHandle, time};
+// Workspace uses
+use zksync_config::ZkSyncConfig;
+use zksync_crypto::PrivateKey;
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::{
+    fee_refund::FeeRefundStatus,
+    fee_sweep::{FeeSweepKind, FeeSweepStatus, SweepThresholds},
+    helpers::closest_packable_token_amount,
+    key_audit::{KeyUsage, OperatorKey},
+    tx::{TimeRange, TxHash},
+    Account, AccountId, Address, BlockNumber, Nonce, PubKeyHash, SignedZkSyncTx, TokenId, Transfer,
+    Withdraw, ZkSyncTx,
+};
+// Local uses
+use crate::mempool::MempoolTransactionRequest;
+
+/// Max amount of the sent sweeps checked within one iteration.
+const SWEEPS_BATCH_SIZE: u32 = 100;
+/// Max amount of the refunds processed within one iteration.
+const REFUNDS_BATCH_SIZE: u32 = 100;
+/// Fail reason of the sent transactions that are neither executed nor queued in the mempool.
+const TX_NOT_FOUND_REASON: &str = "Transaction is not found in the mempool";
+
+struct FeeSweeper {
+    db_pool: ConnectionPool,
+    mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+    fee_account_address: Address,
+    private_key: PrivateKey,
+    treasury: Address,
+    kind: FeeSweepKind,
+    thresholds: SweepThresholds,
+    sweeps_enabled: bool,
+    refunds_enabled: bool,
+}
+
+impl FeeSweeper {
+    async fn sweep_fees(&self) -> anyhow::Result<()> {
+        let mut storage = self.db_pool.access_storage().await?;
+        if !self.check_sent_sweeps(&mut storage).await?
+            || !self.check_sent_refunds(&mut storage).await?
+        {
+            return Ok(());
+        }
+
+        let (account_id, account) = match storage
+            .chain()
+            .account_schema()
+            .account_state_by_address(self.fee_account_address)
+            .await?
+            .committed
+        {
+            Some(state) => state,
+            None => {
+                vlog::warn!(
+                    "Fee account {:?} is not created yet, no fees to sweep or refund",
+                    self.fee_account_address
+                );
+                return Ok(());
+            }
+        };
+        if account.pub_key_hash != PubKeyHash::from_privkey(&self.private_key) {
+            vlog::warn!(
+                "Public key hash of the fee account {:?} doesn't match the configured key, \
+                 fees can't be swept or refunded until it's set via ChangePubKey",
+                self.fee_account_address
+            );
+            return Ok(());
+        }
+
+        let mut nonce = account.nonce;
+        // Balances of the fee account reserved by the refunds sent within this iteration.
+        let mut refunded = HashMap::new();
+        if self.refunds_enabled
+            && !self
+                .send_refunds(
+                    &mut storage,
+                    account_id,
+                    &account,
+                    &mut nonce,
+                    &mut refunded,
+                )
+                .await?
+        {
+            return Ok(());
+        }
+        if !self.sweeps_enabled {
+            return Ok(());
+        }
+
+        let tokens: Vec<_> = self.thresholds.iter().map(|(token, _)| token).collect();
+        for token in tokens {
+            let balance = account.get_balance(token)
+                - refunded
+                    .get(&token)
+                    .cloned()
+                    .unwrap_or_else(|| BigUint::from(0u32));
+            if !self.thresholds.is_reached(token, &balance) {
+                continue;
+            }
+
+            let tx = self.sign_sweep(account_id, token, balance, nonce)?;
+            let (amount, operation) = match &tx {
+                ZkSyncTx::Transfer(tx) => (tx.amount.clone(), "Transfer"),
+                ZkSyncTx::Withdraw(tx) => (tx.amount.clone(), "Withdraw"),
+                _ => unreachable!("Sweeps are either transfers or withdrawals"),
+            };
+            if amount == BigUint::from(0u32) {
+                continue;
+            }
+
+            // The sweep is recorded before it's sent, so every transaction signed with the fee
+            // account key gets to the audit trail, even if the server stops in the meantime.
+            let tx_hash = tx.hash();
+            let id = storage
+                .fee_sweeps_schema()
+                .store_sweep(token, &amount, self.treasury, self.kind, &tx_hash)
+                .await?;
+            storage
+                .key_audit_schema()
+                .record_key_usage(&KeyUsage::new(
+                    OperatorKey::FeeAccount,
+                    operation,
+                    &tx.get_bytes(),
+                    Ok(()),
+                ))
+                .await?;
+
+            if let Err(reason) = self.add_to_mempool(tx).await? {
+                let status = FeeSweepStatus::Failed;
+                storage
+                    .fee_sweeps_schema()
+                    .update_status(id, status, Some(reason.clone()))
+                    .await?;
+                metrics::counter!("fee_sweeper.processed", 1, "status" => status.as_str());
+                vlog::warn!(
+                    "Sweep of the token {} is rejected by the mempool: {}",
+                    token,
+                    reason
+                );
+                break;
+            }
+
+            vlog::info!(
+                "Sweep of {} of the token {} to the treasury {:?} is sent in tx {}",
+                amount,
+                token,
+                self.treasury,
+                tx_hash.to_string()
+            );
+            *nonce += 1;
+        }
+        Ok(())
+    }
+
+    /// Updates the statuses of the sent sweeps, returns `true` if all of them are resolved.
+    async fn check_sent_sweeps(&self, storage: &mut StorageProcessor<'_>) -> anyhow::Result<bool> {
+        let sent = storage
+            .fee_sweeps_schema()
+            .load_sweeps(Some(FeeSweepStatus::Sent), SWEEPS_BATCH_SIZE)
+            .await?;
+        if sent.is_empty() {
+            return Ok(true);
+        }
+
+        let last_saved_block = storage
+            .chain()
+            .block_schema()
+            .get_last_saved_block()
+            .await?;
+        let mut resolved = true;
+        for sweep in sent {
+            let (status, fail_reason) =
+                match Self::tx_outcome(storage, sweep.tx_hash, last_saved_block).await? {
+                    Some(Ok(())) => (FeeSweepStatus::Executed, None),
+                    Some(Err(fail_reason)) => (FeeSweepStatus::Failed, fail_reason),
+                    None if !Self::tx_in_flight(storage, sweep.tx_hash).await? => {
+                        (FeeSweepStatus::Failed, Some(TX_NOT_FOUND_REASON.to_owned()))
+                    }
+                    None => {
+                        resolved = false;
+                        continue;
+                    }
+                };
+
+            storage
+                .fee_sweeps_schema()
+                .update_status(sweep.id, status, fail_reason.clone())
+                .await?;
+            metrics::counter!("fee_sweeper.processed", 1, "status" => status.as_str());
+            if status == FeeSweepStatus::Failed {
+                vlog::warn!(
+                    "Sweep #{} of the token {} failed: {}",
+                    sweep.id,
+                    sweep.token,
+                    fail_reason.unwrap_or_default()
+                );
+            }
+        }
+        Ok(resolved)
+    }
+
+    /// Returns the outcome of the transaction once it's final, i.e. it's either executed in a sealed
+    /// block or expired. The failed transactions are reported with the failure reason.
+    async fn tx_outcome(
+        storage: &mut StorageProcessor<'_>,
+        tx_hash: TxHash,
+        last_saved_block: BlockNumber,
+    ) -> anyhow::Result<Option<Result<(), Option<String>>>> {
+        // Transactions of the pending block are stored too, but they're final only once the block is sealed.
+        let receipt = storage
+            .chain()
+            .operations_ext_schema()
+            .tx_receipt(tx_hash.as_ref())
+            .await?
+            .filter(|receipt| receipt.block_number <= i64::from(*last_saved_block));
+        if let Some(receipt) = receipt {
+            return Ok(Some(if receipt.success {
+                Ok(())
+            } else {
+                Err(receipt.fail_reason)
+            }));
+        }
+
+        let tx_hash = storage
+            .chain()
+            .operations_ext_schema()
+            .resolve_tx_hash(tx_hash)
+            .await?;
+        let expired = storage
+            .chain()
+            .mempool_schema()
+            .get_expired_tx(tx_hash)
+            .await?;
+        Ok(expired.map(|expired| Err(Some(expired.reason))))
+    }
+
+    /// Returns `true` if the transaction is queued in the mempool or is being executed in the pending block.
+    /// The executed transactions are removed from the mempool atomically with storing them, so
+    /// the transaction which is neither queued nor executed will never be executed.
+    async fn tx_in_flight(
+        storage: &mut StorageProcessor<'_>,
+        tx_hash: TxHash,
+    ) -> anyhow::Result<bool> {
+        let executing = storage
+            .chain()
+            .operations_ext_schema()
+            .tx_receipt(tx_hash.as_ref())
+            .await?
+            .is_some();
+        if executing {
+            return Ok(true);
+        }
+        let queued = storage
+            .chain()
+            .mempool_schema()
+            .contains_tx(tx_hash)
+            .await?;
+        Ok(queued)
+    }
+
+    /// Updates the statuses of the sent refunds, returns `true` if all of them are resolved.
+    async fn check_sent_refunds(&self, storage: &mut StorageProcessor<'_>) -> anyhow::Result<bool> {
+        if !self.refunds_enabled {
+            return Ok(true);
+        }
+        let sent = storage
+            .fee_refunds_schema()
+            .load_oldest_refunds(FeeRefundStatus::Sent, REFUNDS_BATCH_SIZE)
+            .await?;
+        if sent.is_empty() {
+            return Ok(true);
+        }
+
+        let last_saved_block = storage
+            .chain()
+            .block_schema()
+            .get_last_saved_block()
+            .await?;
+        let mut resolved = true;
+        for refund in sent {
+            let refund_tx_hash = refund
+                .refund_tx_hash
+                .expect("Sent refund must have the transaction hash");
+            let (status, fail_reason) =
+                match Self::tx_outcome(storage, refund_tx_hash, last_saved_block).await? {
+                    Some(Ok(())) => (FeeRefundStatus::Refunded, None),
+                    Some(Err(fail_reason)) => (FeeRefundStatus::Failed, fail_reason),
+                    None if !Self::tx_in_flight(storage, refund_tx_hash).await? => (
+                        FeeRefundStatus::Failed,
+                        Some(TX_NOT_FOUND_REASON.to_owned()),
+                    ),
+                    None => {
+                        resolved = false;
+                        continue;
+                    }
+                };
+
+            storage
+                .fee_refunds_schema()
+                .update_status(refund.id, status, fail_reason.clone())
+                .await?;
+            metrics::counter!("fee_sweeper.refunds", 1, "status" => status.as_str());
+            if status == FeeRefundStatus::Failed {
+                vlog::warn!(
+                    "Fee refund of the tx {} failed: {}",
+                    refund.tx_hash.to_string(),
+                    fail_reason.unwrap_or_default()
+                );
+            }
+        }
+        Ok(resolved)
+    }
+
+    /// Sends the refunds of the executed transactions, adding the refunded amounts to `refunded`.
+    /// Returns `false` if the refund is rejected by the mempool, so nothing else should be sent.
+    async fn send_refunds(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        account_id: AccountId,
+        account: &Account,
+        nonce: &mut Nonce,
+        refunded: &mut HashMap<TokenId, BigUint>,
+    ) -> anyhow::Result<bool> {
+        let pending = storage
+            .fee_refunds_schema()
+            .load_oldest_refunds(FeeRefundStatus::Pending, REFUNDS_BATCH_SIZE)
+            .await?;
+        if pending.is_empty() {
+            return Ok(true);
+        }
+
+        let last_saved_block = storage
+            .chain()
+            .block_schema()
+            .get_last_saved_block()
+            .await?;
+        for refund in pending {
+            if let Some(refund_tx_hash) = refund.refund_tx_hash {
+                if self
+                    .resolve_recorded_refund(storage, refund.id, refund_tx_hash, last_saved_block)
+                    .await?
+                {
+                    // The nonce and the balances of the fee account loaded above may not account
+                    // for the transfer yet, so nothing else is sent within this iteration.
+                    return Ok(false);
+                }
+            }
+            let cancel_reason =
+                match Self::tx_outcome(storage, refund.tx_hash, last_saved_block).await? {
+                    None => continue,
+                    Some(Err(fail_reason)) => Some(format!(
+                        "Transaction failed: {}",
+                        fail_reason.unwrap_or_default()
+                    )),
+                    Some(Ok(())) if refund.address == self.fee_account_address => {
+                        Some("Fee is paid by the fee account".to_owned())
+                    }
+                    Some(Ok(())) => None,
+                };
+            // Unlike withdrawals, transfer amounts must be packable.
+            let amount = closest_packable_token_amount(&refund.differential());
+            let cancel_reason = cancel_reason.or_else(|| {
+                if amount == BigUint::from(0u32) {
+                    Some("Refund amount is too small".to_owned())
+                } else {
+                    None
+                }
+            });
+            if let Some(reason) = cancel_reason {
+                let status = FeeRefundStatus::Cancelled;
+                storage
+                    .fee_refunds_schema()
+                    .update_status(refund.id, status, Some(reason))
+                    .await?;
+                metrics::counter!("fee_sweeper.refunds", 1, "status" => status.as_str());
+                continue;
+            }
+
+            let token_refunded = refunded
+                .entry(refund.token)
+                .or_insert_with(|| BigUint::from(0u32));
+            if account.get_balance(refund.token) < &*token_refunded + &amount {
+                // The refund is sent once the fee account collects enough fees.
+                vlog::warn!(
+                    "Balance of the token {} of the fee account is not enough to refund the fee of the tx {}",
+                    refund.token,
+                    refund.tx_hash.to_string()
+                );
+                continue;
+            }
+
+            let tx: ZkSyncTx = Transfer::new_signed(
+                account_id,
+                self.fee_account_address,
+                refund.address,
+                refund.token,
+                amount.clone(),
+                BigUint::from(0u32),
+                *nonce,
+                TimeRange::default(),
+                &self.private_key,
+            )?
+            .into();
+            // Same as the sweeps, the refund transfer is recorded before it's sent.
+            let tx_hash = tx.hash();
+            storage
+                .fee_refunds_schema()
+                .record_refund_tx(refund.id, &amount, &tx_hash)
+                .await?;
+            storage
+                .key_audit_schema()
+                .record_key_usage(&KeyUsage::new(
+                    OperatorKey::FeeAccount,
+                    "Transfer",
+                    &tx.get_bytes(),
+                    Ok(()),
+                ))
+                .await?;
+
+            if let Err(reason) = self.add_to_mempool(tx).await? {
+                let status = FeeRefundStatus::Failed;
+                storage
+                    .fee_refunds_schema()
+                    .update_status(refund.id, status, Some(reason.clone()))
+                    .await?;
+                metrics::counter!("fee_sweeper.refunds", 1, "status" => status.as_str());
+                vlog::warn!(
+                    "Fee refund of the tx {} is rejected by the mempool: {}",
+                    refund.tx_hash.to_string(),
+                    reason
+                );
+                return Ok(false);
+            }
+            storage.fee_refunds_schema().mark_sent(refund.id).await?;
+
+            vlog::info!(
+                "Fee refund of {} of the token {} for the tx {} is sent in tx {}",
+                amount,
+                refund.token,
+                refund.tx_hash.to_string(),
+                tx_hash.to_string()
+            );
+            *token_refunded += amount;
+            **nonce += 1;
+        }
+        Ok(true)
+    }
+
+    /// Checks the refund transfer recorded without being marked sent, i.e. the server stopped
+    /// before the transfer was added to the mempool or right after that. Returns `true` if the
+    /// transfer is known to the mempool or executed, so the refund must not be sent again.
+    async fn resolve_recorded_refund(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        id: i64,
+        refund_tx_hash: TxHash,
+        last_saved_block: BlockNumber,
+    ) -> anyhow::Result<bool> {
+        let (status, fail_reason) =
+            match Self::tx_outcome(storage, refund_tx_hash, last_saved_block).await? {
+                Some(Ok(())) => (FeeRefundStatus::Refunded, None),
+                Some(Err(fail_reason)) => (FeeRefundStatus::Failed, fail_reason),
+                None => {
+                    if !Self::tx_in_flight(storage, refund_tx_hash).await? {
+                        // The transfer never reached the mempool, so it's signed again.
+                        return Ok(false);
+                    }
+                    storage.fee_refunds_schema().mark_sent(id).await?;
+                    return Ok(true);
+                }
+            };
+
+        storage
+            .fee_refunds_schema()
+            .update_status(id, status, fail_reason)
+            .await?;
+        metrics::counter!("fee_sweeper.refunds", 1, "status" => status.as_str());
+        Ok(true)
+    }
+
+    fn sign_sweep(
+        &self,
+        account_id: AccountId,
+        token: TokenId,
+        balance: BigUint,
+        nonce: Nonce,
+    ) -> anyhow::Result<ZkSyncTx> {
+        let fee = BigUint::from(0u32);
+        let tx = match self.kind {
+            FeeSweepKind::Transfer => Transfer::new_signed(
+                account_id,
+                self.fee_account_address,
+                self.treasury,
+                token,
+                // Unlike withdrawals, transfer amounts must be packable.
+                closest_packable_token_amount(&balance),
+                fee,
+                nonce,
+                TimeRange::default(),
+                &self.private_key,
+            )?
+            .into(),
+            FeeSweepKind::Withdraw => Withdraw::new_signed(
+                account_id,
+                self.fee_account_address,
+                self.treasury,
+                token,
+                balance,
+                fee,
+                nonce,
+                TimeRange::default(),
+                &self.private_key,
+            )?
+            .into(),
+        };
+        Ok(tx)
+    }
+
+    /// Adds the transaction to the mempool, returns the rejection reason if it's not accepted.
+    async fn add_to_mempool(&self, tx: ZkSyncTx) -> anyhow::Result<Result<(), String>> {
+        let span = vlog::info_span!("fee_sweep", tx_hash = %tx.hash().to_string());
+        let (sender, receiver) = oneshot::channel();
+        let request =
+            MempoolTransactionRequest::NewTx(Box::new(SignedZkSyncTx::from(tx)), sender, span);
+        self.mempool_tx_sender.clone().send(request).await?;
+        let response = receiver.await?;
+        Ok(response.map_err(|err| err.to_string()))
+    }
+}
+
+#[must_use]
+pub fn run_fee_sweeper(
+    config: &ZkSyncConfig,
+    db_pool: ConnectionPool,
+    mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+) -> Option<JoinHandle<()>> {
+    let sweep_config = &config.chain.fee_sweep;
+    let refunds_enabled = config.chain.fee_refund.enabled;
+    if !sweep_config.enabled && !refunds_enabled {
+        return None;
+    }
+
+    let sweeper = FeeSweeper {
+        db_pool,
+        mempool_tx_sender,
+        fee_account_address: config.chain.state_keeper.fee_account_addr,
+        private_key: sweep_config.fee_account_private_key(),
+        treasury: sweep_config.treasury_address,
+        kind: if sweep_config.withdraw {
+            FeeSweepKind::Withdraw
+        } else {
+            FeeSweepKind::Transfer
+        },
+        thresholds: sweep_config
+            .thresholds()
+            .expect("Invalid fee sweep thresholds"),
+        sweeps_enabled: sweep_config.enabled,
+        refunds_enabled,
+    };
+
+    let mut timer = time::interval(sweep_config.interval());
+    Some(tokio::spawn(async move {
+        loop {
+            timer.tick().await;
+
+            if let Err(err) = sweeper.sweep_fees().await {
+                vlog::error!("Failed to sweep or refund the collected fees: {}", err);
+            }
+        }
+    }))
+}
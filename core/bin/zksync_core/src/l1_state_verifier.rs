@@ -0,0 +1,315 @@
+//! L1 state verifier cross-checks the blocks committed to L1 against the local ones.
+//!
+//! Once the commit transaction is confirmed, the verifier fetches it from the Ethereum node,
+//! decodes the committed blocks and recomputes their commitments the same way the zkSync
+//! contract does. If the state root or the commitment of any block differs from the locally
+//! computed one, the local state has diverged from the state accepted by L1: the block
+//! production is halted and the divergence is reported on every check until it's resolved.
+//! The halt is persisted, so it's kept after the restarts until the blocks are reverted.
+//!
+//! Only the blocks that are committed but not executed are checked: the execution on L1
+//! requires a proof for the committed block, so the executed blocks can't diverge. Thus the
+//! verifier doesn't have to keep any state between restarts.
+
+// Built-in deps
+use std::time::Instant;
+// External uses
+use anyhow::{bail, format_err};
+use tokio::{task::JoinHandle, time};
+// Workspace uses
+use zksync_config::ZkSyncConfig;
+use zksync_contracts::zksync_contract;
+use zksync_eth_client::EthereumGateway;
+use zksync_storage::ConnectionPool;
+use zksync_types::{
+    aggregated_operations::AggregatedActionType,
+    block_commitment::{compute_block_commitment, decode_commit_blocks},
+    BlockNumber, H256,
+};
+// Local uses
+use crate::state_keeper::BlockProductionHalt;
+
+/// Block data as it was committed to L1.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct L1CommittedBlock {
+    pub block_number: BlockNumber,
+    pub previous_state_hash: H256,
+    pub state_hash: H256,
+    pub commitment: H256,
+}
+
+/// Decodes the input of the `commitBlocks` call and recomputes the commitments of the
+/// committed blocks.
+pub(crate) fn decode_committed_blocks(input: &[u8]) -> anyhow::Result<Vec<L1CommittedBlock>> {
+    let contract = zksync_contract();
+    let function = contract.function("commitBlocks")?;
+    if input.len() < 4 || input[..4] != function.short_signature() {
+        bail!("Transaction is not a blocks commit");
+    }
+
+    let tokens = function.decode_input(&input[4..])?;
+    decode_commit_blocks(&tokens)?
+        .iter()
+        .map(|block| {
+            let stored_block = compute_block_commitment(block)?;
+            Ok(L1CommittedBlock {
+                block_number: block.block_number,
+                previous_state_hash: block.previous_state_hash,
+                state_hash: block.state_hash,
+                commitment: stored_block.commitment,
+            })
+        })
+        .collect()
+}
+
+struct L1StateVerifier {
+    db_pool: ConnectionPool,
+    eth_gateway: EthereumGateway,
+    production_halt: BlockProductionHalt,
+    /// The last block that was found consistent with L1.
+    last_checked_block: BlockNumber,
+    /// The last fetched commit transaction along with the blocks committed by it.
+    /// Several blocks are usually committed by the same transaction.
+    last_commit: Option<(H256, Vec<L1CommittedBlock>)>,
+}
+
+impl L1StateVerifier {
+    async fn l1_committed_block(
+        &mut self,
+        tx_hash: H256,
+        block_number: BlockNumber,
+    ) -> anyhow::Result<L1CommittedBlock> {
+        let is_cached = matches!(&self.last_commit, Some((hash, _)) if *hash == tx_hash);
+        if !is_cached {
+            let tx = self
+                .eth_gateway
+                .get_tx(tx_hash)
+                .await?
+                .ok_or_else(|| format_err!("Commit transaction {:?} is not found", tx_hash))?;
+            let committed_blocks = decode_committed_blocks(&tx.input.0)?;
+            self.last_commit = Some((tx_hash, committed_blocks));
+        }
+
+        self.last_commit
+            .iter()
+            .flat_map(|(_, blocks)| blocks)
+            .find(|block| block.block_number == block_number)
+            .cloned()
+            .ok_or_else(|| {
+                format_err!(
+                    "Block #{} is not committed by the transaction {:?}",
+                    *block_number,
+                    tx_hash
+                )
+            })
+    }
+
+    async fn check(&mut self) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let mut storage = self.db_pool.access_storage().await?;
+
+        let last_executed_block = storage
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await?;
+        let last_committed_block = storage
+            .chain()
+            .operations_schema()
+            .get_last_block_by_aggregated_action(AggregatedActionType::CommitBlocks, Some(true))
+            .await?;
+        // Committed blocks may be reverted, in that case the new blocks with the same numbers
+        // have to be checked again.
+        self.last_checked_block = self
+            .last_checked_block
+            .max(last_executed_block)
+            .min(last_committed_block);
+
+        for block_number in (*self.last_checked_block + 1)..=*last_committed_block {
+            let block_number = BlockNumber(block_number);
+            let block = storage
+                .chain()
+                .block_schema()
+                .get_block(block_number)
+                .await?
+                .ok_or_else(|| format_err!("Block #{} is not found", *block_number))?;
+            let tx_hash = storage
+                .ethereum_schema()
+                .aggregated_op_final_hash(block_number, AggregatedActionType::CommitBlocks)
+                .await?
+                .ok_or_else(|| {
+                    format_err!(
+                        "Commit transaction for block #{} is not found",
+                        *block_number
+                    )
+                })?;
+            let l1_block = self.l1_committed_block(tx_hash, block_number).await?;
+
+            let local_state_hash = block.get_eth_encoded_root();
+            if l1_block.state_hash != local_state_hash
+                || l1_block.commitment != block.block_commitment
+            {
+                metrics::counter!("l1_state_verifier.divergences", 1);
+                vlog::error!(
+                    "Local state diverges from L1 at block #{}: local state hash {:?}, \
+                     commitment {:?}; committed state hash {:?}, commitment {:?}. \
+                     Block production is halted",
+                    *block_number,
+                    local_state_hash,
+                    block.block_commitment,
+                    l1_block.state_hash,
+                    l1_block.commitment
+                );
+                self.production_halt
+                    .halt(&format!(
+                        "local state diverges from L1 at block #{}",
+                        *block_number
+                    ))
+                    .await;
+                return Ok(());
+            }
+
+            self.last_checked_block = block_number;
+        }
+
+        metrics::gauge!(
+            "l1_state_verifier.last_checked_block",
+            *self.last_checked_block as f64
+        );
+        metrics::histogram!("l1_state_verifier.check", start.elapsed());
+        Ok(())
+    }
+}
+
+/// Runs the task checking the committed blocks against L1 if it's enabled in the config.
+#[must_use]
+pub fn run_l1_state_verifier(
+    config: &ZkSyncConfig,
+    db_pool: ConnectionPool,
+    eth_gateway: EthereumGateway,
+    production_halt: BlockProductionHalt,
+) -> Option<JoinHandle<()>> {
+    if !config.eth_watch.verify_committed_state {
+        return None;
+    }
+
+    let mut verifier = L1StateVerifier {
+        db_pool,
+        eth_gateway,
+        production_halt,
+        last_checked_block: BlockNumber(0),
+        last_commit: None,
+    };
+    let mut timer = time::interval(config.eth_watch.state_verification_interval());
+
+    Some(tokio::spawn(async move {
+        loop {
+            timer.tick().await;
+
+            if let Err(err) = verifier.check().await {
+                vlog::warn!("Failed to check the committed blocks against L1: {}", err);
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use num::BigUint;
+    use zksync_crypto::{ff::PrimeField, Fr};
+    use zksync_types::{
+        aggregated_operations::BlocksCommitOperation,
+        block::{Block, ExecutedOperations, ExecutedPriorityOp},
+        AccountId, Deposit, DepositOp, PriorityOp, TokenId, ZkSyncOp, ZkSyncPriorityOp,
+    };
+
+    fn deposit_op() -> ExecutedOperations {
+        let deposit = Deposit {
+            from: [1u8; 20].into(),
+            to: [1u8; 20].into(),
+            amount: BigUint::from(100u32),
+            token: TokenId(0),
+        };
+        ExecutedOperations::PriorityOp(Box::new(ExecutedPriorityOp {
+            priority_op: PriorityOp {
+                serial_id: 0,
+                data: ZkSyncPriorityOp::Deposit(deposit.clone()),
+                deadline_block: 0,
+                eth_hash: H256::zero(),
+                eth_block: 0,
+            },
+            op: ZkSyncOp::Deposit(Box::new(DepositOp {
+                priority_op: deposit,
+                account_id: AccountId(1),
+            })),
+            block_index: 0,
+            created_at: Utc::now(),
+        }))
+    }
+
+    fn block(number: u32, transactions: Vec<ExecutedOperations>, previous: &Block) -> Block {
+        Block::new_from_available_block_sizes(
+            BlockNumber(number),
+            Fr::from_str(&number.to_string()).unwrap(),
+            AccountId(0),
+            transactions,
+            (0, 0),
+            &[10],
+            1_000_000.into(),
+            1_000_000.into(),
+            previous.get_eth_encoded_root(),
+            number as u64,
+        )
+    }
+
+    /// Checks that the commitments recomputed from the commit transaction input
+    /// match the ones computed by the server.
+    #[test]
+    fn decode_commit_input() {
+        let genesis = Block::new_from_available_block_sizes(
+            BlockNumber(0),
+            Fr::from_str("0").unwrap(),
+            AccountId(0),
+            Vec::new(),
+            (0, 0),
+            &[10],
+            1_000_000.into(),
+            1_000_000.into(),
+            H256::zero(),
+            0,
+        );
+        let first = block(1, vec![deposit_op()], &genesis);
+        let second = block(2, Vec::new(), &first);
+
+        let operation = BlocksCommitOperation {
+            last_committed_block: genesis,
+            blocks: vec![first, second],
+        };
+        let input = zksync_contract()
+            .function("commitBlocks")
+            .unwrap()
+            .encode_input(&operation.get_eth_tx_args())
+            .unwrap();
+
+        let committed_blocks = decode_committed_blocks(&input).unwrap();
+        let previous_blocks =
+            std::iter::once(&operation.last_committed_block).chain(operation.blocks.iter());
+        let expected: Vec<_> = operation
+            .blocks
+            .iter()
+            .zip(previous_blocks)
+            .map(|(block, previous_block)| L1CommittedBlock {
+                block_number: block.block_number,
+                previous_state_hash: previous_block.get_eth_encoded_root(),
+                state_hash: block.get_eth_encoded_root(),
+                commitment: block.block_commitment,
+            })
+            .collect();
+        assert_eq!(committed_blocks, expected);
+
+        // Input of the other contract calls is rejected.
+        assert!(decode_committed_blocks(&input[1..]).is_err());
+    }
+}
@@ -0,0 +1,167 @@
+//! Leader election of the server instances running in the active/standby mode.
+//!
+//! Only the leader instance runs the components changing the state (state keeper, Ethereum sender,
+//! prover server, etc.), while the standby ones serve the read API only. The leadership is represented
+//! by the Postgres advisory lock held by the dedicated database connection, thus it's released
+//! automatically once the leader dies or loses the connection to the database.
+//!
+//! The standby instance taking over the leadership starts the components the same way as the server
+//! does on restart, so their state is resynced from the database. The leader which lost the lock
+//! must stop right away to not compete with the new one. Until it notices the loss, its blocks are
+//! rejected by the database, since the committer fences them with the token of the leadership.
+//! The leader shutting down gracefully gives up the leadership once its components are stopped.
+
+// Built-in deps
+use std::time::Duration;
+// External uses
+use futures::channel::oneshot;
+use tokio::{
+    task::JoinHandle,
+    time::{self, delay_for},
+};
+// Workspace deps
+use zksync_config::configs::FailoverConfig;
+use zksync_storage::{leader_election::FencingToken, StorageProcessor};
+
+/// Leadership held by the server instance.
+#[derive(Debug)]
+pub struct Leadership {
+    storage: StorageProcessor<'static>,
+    fencing_token: FencingToken,
+    check_interval: Duration,
+}
+
+impl Leadership {
+    /// Waits until the instance becomes the leader.
+    pub async fn acquire(config: &FailoverConfig) -> Self {
+        metrics::gauge!("leader_election.is_leader", 0.0);
+
+        let mut storage = None;
+        let fencing_token = loop {
+            match Self::try_acquire(&mut storage, config.lock_id).await {
+                Ok(Some(fencing_token)) => break fencing_token,
+                Ok(None) => {}
+                Err(err) => {
+                    vlog::warn!("Failed to acquire the leadership: {}", err);
+                    // The connection may be broken, so it's re-established on the next attempt.
+                    // Dropping the connection also releases the lock if it was acquired.
+                    storage = None;
+                }
+            }
+            delay_for(config.check_interval()).await;
+        };
+
+        vlog::info!(
+            "The server instance became the leader, fencing token {}",
+            fencing_token.token
+        );
+        metrics::gauge!("leader_election.is_leader", 1.0);
+        Self {
+            storage: storage.expect("Leadership is acquired without the connection"),
+            fencing_token,
+            check_interval: config.check_interval(),
+        }
+    }
+
+    async fn try_acquire(
+        storage: &mut Option<StorageProcessor<'static>>,
+        lock_id: i64,
+    ) -> anyhow::Result<Option<FencingToken>> {
+        if storage.is_none() {
+            *storage = Some(StorageProcessor::establish_connection().await?);
+        }
+
+        let mut schema = storage.as_mut().unwrap().leader_election_schema();
+        if !schema.try_acquire_leadership(lock_id).await? {
+            return Ok(None);
+        }
+        let fencing_token = schema.issue_fencing_token(lock_id).await?;
+        Ok(Some(fencing_token))
+    }
+
+    /// Returns the token the writes of the leader must be fenced with,
+    /// see `zksync_storage::leader_election`.
+    pub fn fencing_token(&self) -> FencingToken {
+        self.fencing_token
+    }
+
+    /// Keeps checking that the leadership is still held until it's lost or released.
+    #[must_use]
+    pub fn watch(mut self) -> LeadershipWatch {
+        let (release_sender, mut release_receiver) = oneshot::channel();
+        let task = tokio::spawn(async move {
+            let mut timer = time::interval(self.check_interval);
+            loop {
+                tokio::select! {
+                    _ = timer.tick() => {}
+                    _ = &mut release_receiver => {
+                        self.release().await;
+                        return;
+                    }
+                }
+
+                let held = self
+                    .storage
+                    .leader_election_schema()
+                    .check_leadership(self.fencing_token)
+                    .await;
+                match held {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        vlog::error!(
+                            "The server instance lost the leadership: the lock is not held"
+                        );
+                        break;
+                    }
+                    Err(err) => {
+                        vlog::error!("The server instance lost the leadership: {}", err);
+                        break;
+                    }
+                }
+            }
+            metrics::gauge!("leader_election.is_leader", 0.0);
+        });
+
+        LeadershipWatch {
+            task,
+            release_sender,
+        }
+    }
+
+    async fn release(&mut self) {
+        let released = self
+            .storage
+            .leader_election_schema()
+            .release_leadership(self.fencing_token.lock_id)
+            .await;
+        match released {
+            Ok(true) => vlog::info!("The server instance gave up the leadership"),
+            Ok(false) => vlog::warn!("The leadership was not held when giving it up"),
+            // The lock is released anyway once the connection is closed.
+            Err(err) => vlog::warn!("Failed to give up the leadership: {}", err),
+        }
+        metrics::gauge!("leader_election.is_leader", 0.0);
+    }
+}
+
+/// Leadership checked in the background, see `Leadership::watch`.
+#[derive(Debug)]
+pub struct LeadershipWatch {
+    task: JoinHandle<()>,
+    release_sender: oneshot::Sender<()>,
+}
+
+impl LeadershipWatch {
+    /// Resolves once the leadership is lost.
+    pub async fn lost(&mut self) {
+        (&mut self.task).await.unwrap_or_default();
+    }
+
+    /// Gives up the leadership, so the standby instance takes over right away rather than once
+    /// the connection of this one is closed. Must be called after the components changing
+    /// the state are stopped.
+    pub async fn release(self) {
+        self.release_sender.send(()).unwrap_or_default();
+        self.task.await.unwrap_or_default();
+    }
+}
@@ -2,30 +2,57 @@ use zksync_types::{tokens::get_genesis_token_list, Token, TokenId};
 
 use crate::state_keeper::ZkSyncStateInitParams;
 use crate::{
+    backpressure::{run_backpressure_monitor, Backpressure},
     block_proposer::run_block_proposer_task,
-    committer::run_committer,
+    committer::{run_committer, CommitRequest},
+    deposit_refunds::run_deposit_refunds_sender,
     eth_watch::start_eth_watch,
+    event_stream::run_event_stream_publisher,
+    fee_sweeper::run_fee_sweeper,
+    l1_state_verifier::run_l1_state_verifier,
     mempool::run_mempool_tasks,
     private_api::start_private_core_api,
     rejected_tx_cleaner::run_rejected_tx_cleaner,
-    state_keeper::{start_state_keeper, ZkSyncStateKeeper},
+    revenue_reporter::run_revenue_reporter,
+    state_keeper::{
+        BlockProductionHalt, BlockTimeController, StateKeeperRequest, ZkSyncStateKeeper,
+    },
+    webhook_dispatcher::run_webhook_dispatcher,
+};
+use futures::{
+    channel::{mpsc, oneshot},
+    future, SinkExt,
 };
-use futures::{channel::mpsc, future};
 use tokio::task::JoinHandle;
-use zksync_config::ZkSyncConfig;
+use zksync_config::{configs::chain::StateKeeper as StateKeeperConfig, ZkSyncConfig};
 use zksync_eth_client::EthereumGateway;
 use zksync_gateway_watcher::run_gateway_watcher_if_multiplexed;
-use zksync_storage::ConnectionPool;
+use zksync_storage::{leader_election::FencingToken, ConnectionPool};
+use zksync_tree_cache::TreeCacheStorage;
+use zksync_utils::{
+    shutdown::{DrainGuard, ShutdownCoordinator, ShutdownSignal},
+    supervisor::{SharedReceiver, Supervisor},
+};
 
 const DEFAULT_CHANNEL_CAPACITY: usize = 32_768;
 
+pub mod audit_replay;
+pub mod backpressure;
 pub mod block_proposer;
 pub mod committer;
+pub mod deposit_refunds;
 pub mod eth_watch;
+pub mod event_stream;
+pub mod fee_sweeper;
+pub mod l1_state_verifier;
+pub mod leader_election;
 pub mod mempool;
 pub mod private_api;
 pub mod rejected_tx_cleaner;
+pub mod revenue_reporter;
+pub mod snapshot_sync;
 pub mod state_keeper;
+pub mod webhook_dispatcher;
 
 /// Waits for *any* of the tokio tasks to be finished.
 /// Since the main tokio tasks are used as actors which should live as long
@@ -84,6 +111,69 @@ pub async fn genesis_init(config: &ZkSyncConfig) {
     }
 }
 
+/// Restores the state keeper from the database and runs it.
+/// State is restored on each (re)start, so the restarted state keeper continues from the last stored block.
+/// The failed state keeper may leave the requests queued in the committer, so the state is restored
+/// only once the committer stores them, otherwise the restarted state keeper would create the queued
+/// blocks once again.
+async fn run_state_keeper(
+    connection_pool: ConnectionPool,
+    requests: SharedReceiver<StateKeeperRequest>,
+    mut commit_requests: mpsc::Sender<CommitRequest>,
+    config: StateKeeperConfig,
+    shutdown: ShutdownSignal,
+    drain_guard: Option<DrainGuard>,
+    production_halt: BlockProductionHalt,
+    tree_cache: TreeCacheStorage,
+) {
+    let (flushed_sender, flushed_receiver) = oneshot::channel();
+    commit_requests
+        .send(CommitRequest::Flush(flushed_sender))
+        .await
+        .expect("Committer receiver dropped");
+    flushed_receiver
+        .await
+        .expect("Committer stopped before storing the queued requests");
+
+    let mut storage_processor = connection_pool
+        .access_storage()
+        .await
+        .expect("Unable to access the database");
+    let state_keeper_init =
+        ZkSyncStateInitParams::restore_from_db(&mut storage_processor, &tree_cache)
+            .await
+            .expect("Unable to restore the state keeper state");
+    let pending_block = state_keeper_init
+        .get_pending_block(&mut storage_processor)
+        .await;
+    drop(storage_processor);
+
+    let mut state_keeper = ZkSyncStateKeeper::new(
+        state_keeper_init,
+        config.fee_account_addr,
+        requests,
+        commit_requests,
+        config.block_chunk_sizes.clone(),
+        config.miniblock_iterations as usize,
+        config.fast_block_miniblock_iterations as usize,
+        config.last_tx_signer_data(),
+    )
+    .with_shutdown(shutdown, drain_guard)
+    .with_production_halt(production_halt);
+    if config.adaptive_block_size {
+        state_keeper = state_keeper.with_adaptive_block_size();
+    }
+    if config.adaptive_block_time {
+        state_keeper = state_keeper.with_block_time_controller(BlockTimeController::new(
+            config.target_block_time(),
+            config.miniblock_iterations as usize,
+            config.min_miniblock_iterations as usize,
+            config.max_miniblock_iterations as usize,
+        ));
+    }
+    state_keeper.run(pending_block).await
+}
+
 /// Starts the core application, which has the following sub-modules:
 ///
 /// - Ethereum Watcher, module to monitor on-chain operations.
@@ -92,11 +182,25 @@ pub async fn genesis_init(config: &ZkSyncConfig) {
 /// - block proposer, module to create block proposals for state keeper.
 /// - committer, module to store pending and completed blocks into the database.
 /// - private Core API server.
+/// - revenue reporter, module to aggregate the collected fees.
+/// - webhook dispatcher (if enabled).
+/// - event stream publisher (if enabled).
+/// - L1 state verifier, module to cross-check the committed blocks against L1 (if enabled).
+/// - backpressure monitor, module to throttle the new transactions while the blocks processing lags (if enabled).
+/// - fee sweeper, module to move the collected fees to the treasury (if enabled).
+///
+/// Ethereum Watcher and state keeper are supervised, i.e. restarted if they fail.
+///
+/// On shutdown, the private API stops accepting transactions and the state keeper seals the pending block,
+/// which is then stored by the committer.
 pub async fn run_core(
     connection_pool: ConnectionPool,
     panic_notify: mpsc::Sender<bool>,
     eth_gateway: EthereumGateway,
     config: &ZkSyncConfig,
+    fencing_token: Option<FencingToken>,
+    shutdown: &ShutdownCoordinator,
+    supervisor: &Supervisor,
 ) -> anyhow::Result<Vec<JoinHandle<()>>> {
     let (proposed_blocks_sender, proposed_blocks_receiver) =
         mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
@@ -113,29 +217,42 @@ pub async fn run_core(
         eth_watch_req_sender.clone(),
         eth_watch_req_receiver,
         eth_gateway.clone(),
+        connection_pool.clone(),
         &config,
+        supervisor,
     );
 
-    // Insert pending withdrawals into database (if required)
-    let mut storage_processor = connection_pool.access_storage().await?;
-
     // Start State Keeper.
-    let state_keeper_init = ZkSyncStateInitParams::restore_from_db(&mut storage_processor).await?;
-    let pending_block = state_keeper_init
-        .get_pending_block(&mut storage_processor)
-        .await;
-
-    let state_keeper = ZkSyncStateKeeper::new(
-        state_keeper_init,
-        config.chain.state_keeper.fee_account_addr,
-        state_keeper_req_receiver,
-        proposed_blocks_sender,
-        config.chain.state_keeper.block_chunk_sizes.clone(),
-        config.chain.state_keeper.miniblock_iterations as usize,
-        config.chain.state_keeper.fast_block_miniblock_iterations as usize,
-        config.chain.state_keeper.last_tx_signer_data(),
-    );
-    let state_keeper_task = start_state_keeper(state_keeper, pending_block);
+    let production_halt = BlockProductionHalt::restore(connection_pool.clone()).await?;
+    let state_keeper_req_receiver = SharedReceiver::from(state_keeper_req_receiver);
+    // Each restart of the state keeper has to be drained, so it obtains a new guard.
+    let mut state_keeper_drain_guard = Some(shutdown.register("state_keeper"));
+    let drain_registry = shutdown.registry();
+    let state_keeper_tree_cache =
+        TreeCacheStorage::open(&config.db, zksync_tree_cache::STATE_KEEPER)
+            .expect("Unable to open the account tree cache storage");
+    let state_keeper_task = supervisor.spawn("state_keeper", {
+        let connection_pool = connection_pool.clone();
+        let state_keeper_config = config.chain.state_keeper.clone();
+        let shutdown_signal = shutdown.signal();
+        let production_halt = production_halt.clone();
+        move || {
+            run_state_keeper(
+                connection_pool.clone(),
+                state_keeper_req_receiver.clone(),
+                proposed_blocks_sender.clone(),
+                state_keeper_config.clone(),
+                shutdown_signal.clone(),
+                Some(
+                    state_keeper_drain_guard
+                        .take()
+                        .unwrap_or_else(|| drain_registry.register("state_keeper")),
+                ),
+                production_halt.clone(),
+                state_keeper_tree_cache.clone(),
+            )
+        }
+    });
 
     // Start committer.
     let committer_task = run_committer(
@@ -143,14 +260,22 @@ pub async fn run_core(
         mempool_block_request_sender.clone(),
         connection_pool.clone(),
         &config,
+        fencing_token,
+        shutdown.register("committer"),
     );
 
+    // Start backpressure monitor.
+    let backpressure = Backpressure::new(config.chain.backpressure.retry_after);
+    let backpressure_task_opt =
+        run_backpressure_monitor(&config, connection_pool.clone(), backpressure.clone());
+
     // Start mempool.
     let mempool_task = run_mempool_tasks(
         connection_pool.clone(),
         mempool_tx_request_receiver,
         mempool_block_request_receiver,
         eth_watch_req_sender.clone(),
+        backpressure,
         &config,
         4,
         DEFAULT_CHANNEL_CAPACITY,
@@ -161,6 +286,33 @@ pub async fn run_core(
     // Start rejected transactions cleaner task.
     let rejected_tx_cleaner_task = run_rejected_tx_cleaner(&config, connection_pool.clone());
 
+    // Start operator revenue reporter.
+    let revenue_reporter_task = run_revenue_reporter(connection_pool.clone());
+
+    // Start webhook dispatcher.
+    let webhook_dispatcher_task_opt = run_webhook_dispatcher(&config, connection_pool.clone());
+
+    // Start event stream publisher.
+    let event_stream_task_opt = run_event_stream_publisher(&config, connection_pool.clone());
+
+    // Start deposit refunds sender.
+    let deposit_refunds_task_opt = run_deposit_refunds_sender(&config, connection_pool.clone());
+
+    // Start fee sweeper.
+    let fee_sweeper_task_opt = run_fee_sweeper(
+        &config,
+        connection_pool.clone(),
+        mempool_tx_request_sender.clone(),
+    );
+
+    // Start L1 state verifier.
+    let l1_state_verifier_task_opt = run_l1_state_verifier(
+        &config,
+        connection_pool.clone(),
+        eth_gateway.clone(),
+        production_halt,
+    );
+
     // Start block proposer.
     let proposer_task = run_block_proposer_task(
         &config,
@@ -174,6 +326,8 @@ pub async fn run_core(
         mempool_tx_request_sender,
         eth_watch_req_sender,
         config.api.private.clone(),
+        shutdown.signal(),
+        supervisor.clone(),
     );
 
     let mut task_futures = vec![
@@ -183,11 +337,30 @@ pub async fn run_core(
         mempool_task,
         proposer_task,
         rejected_tx_cleaner_task,
+        revenue_reporter_task,
     ];
 
     if let Some(task) = gateway_watcher_task_opt {
         task_futures.push(task);
     }
+    if let Some(task) = webhook_dispatcher_task_opt {
+        task_futures.push(task);
+    }
+    if let Some(task) = event_stream_task_opt {
+        task_futures.push(task);
+    }
+    if let Some(task) = l1_state_verifier_task_opt {
+        task_futures.push(task);
+    }
+    if let Some(task) = backpressure_task_opt {
+        task_futures.push(task);
+    }
+    if let Some(task) = deposit_refunds_task_opt {
+        task_futures.push(task);
+    }
+    if let Some(task) = fee_sweeper_task_opt {
+        task_futures.push(task);
+    }
 
     Ok(task_futures)
 }
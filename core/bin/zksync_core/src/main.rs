@@ -1,25 +1,29 @@
-use futures::{channel::mpsc, executor::block_on, SinkExt, StreamExt};
-use std::cell::RefCell;
+use futures::{channel::mpsc, StreamExt};
+use std::time::Duration;
 use zksync_config::ZkSyncConfig;
 use zksync_core::{run_core, wait_for_tasks};
 use zksync_eth_client::EthereumGateway;
 use zksync_prometheus_exporter::run_prometheus_exporter;
 use zksync_storage::ConnectionPool;
+use zksync_utils::{shutdown::ShutdownCoordinator, supervisor::Supervisor};
+
+/// Time given to the actors to finish their work after the shutdown is requested.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(60);
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let _sentry_guard = vlog::init();
-    // handle ctrl+c
     let config = ZkSyncConfig::from_env();
     let eth_gateway = EthereumGateway::from_config(&config);
+    // Panicked actors notify about themselves via the stop signal channel,
+    // while Ctrl+C and SIGTERM start the graceful shutdown.
     let (stop_signal_sender, mut stop_signal_receiver) = mpsc::channel(256);
+    let shutdown = ShutdownCoordinator::new();
+    let mut shutdown_signal = shutdown.signal();
     {
-        let stop_signal_sender = RefCell::new(stop_signal_sender.clone());
-        ctrlc::set_handler(move || {
-            let mut sender = stop_signal_sender.borrow_mut();
-            block_on(sender.send(true)).expect("Ctrl+C signal send");
-        })
-        .expect("Error setting Ctrl+C handler");
+        let shutdown_trigger = shutdown.trigger();
+        ctrlc::set_handler(move || shutdown_trigger.trigger())
+            .expect("Error setting Ctrl+C handler");
     }
     let connection_pool = ConnectionPool::new(None);
 
@@ -27,9 +31,17 @@ async fn main() -> anyhow::Result<()> {
     let (prometheus_task_handle, counter_task_handle) =
         run_prometheus_exporter(connection_pool.clone(), config.api.prometheus.port, true);
 
-    let task_handles = run_core(connection_pool, stop_signal_sender, eth_gateway, &config)
-        .await
-        .expect("Unable to start Core actors");
+    let task_handles = run_core(
+        connection_pool,
+        stop_signal_sender,
+        eth_gateway,
+        &config,
+        None,
+        &shutdown,
+        &Supervisor::default(),
+    )
+    .await
+    .expect("Unable to start Core actors");
 
     tokio::select! {
         _ = async { wait_for_tasks(task_handles).await } => {
@@ -44,6 +56,13 @@ async fn main() -> anyhow::Result<()> {
         _ = async { stop_signal_receiver.next().await } => {
             vlog::warn!("Stop signal received, shutting down");
         }
+        _ = shutdown_signal.recv() => {
+            vlog::warn!("Termination signal received, waiting for the actors to finish their work");
+            let not_drained = shutdown.wait_drained(SHUTDOWN_TIMEOUT).await;
+            if !not_drained.is_empty() {
+                vlog::error!("Actors {:?} did not finish their work in time", not_drained);
+            }
+        }
     };
 
     Ok(())
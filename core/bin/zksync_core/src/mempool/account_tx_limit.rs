@@ -0,0 +1,164 @@
+//! Cap on the amount of the transactions of a single account in a block.
+//!
+//! The mempool proposes the transactions in the order of their arrival, so an account flooding
+//! the mempool can fill the blocks with its transactions and delay everyone else's. The limit caps
+//! the amount of the transactions of the account proposed for the same block. Transactions over
+//! the limit stay in the queue in their order and are proposed for the next blocks.
+//!
+//! The transactions are counted as they're proposed, across all the miniblocks of the block.
+//! A batch is proposed if all its senders have a slot left, so the batches larger than the limit
+//! are not held forever. Once a transaction is held back, the later transactions of all its senders
+//! are held as well, so they don't follow a nonce hole.
+
+// Built-in deps
+use std::collections::{HashMap, HashSet};
+// Workspace uses
+use zksync_config::configs::chain::Mempool as MempoolConfig;
+use zksync_types::{mempool::SignedTxVariant, AccountId, BlockNumber};
+
+#[derive(Debug, Clone)]
+pub struct AccountTxLimit {
+    limit: usize,
+    block_number: BlockNumber,
+    /// Amount of the transactions of the accounts proposed for the block.
+    proposed_txs: HashMap<AccountId, usize>,
+    /// Accounts the transactions of which are held back in the current proposal.
+    held_accounts: HashSet<AccountId>,
+}
+
+impl AccountTxLimit {
+    /// Creates the limit from the config, returns `None` if the limit is disabled.
+    pub fn from_config(config: &MempoolConfig) -> Option<Self> {
+        if config.max_account_txs_per_block == 0 {
+            return None;
+        }
+
+        Some(Self::new(config.max_account_txs_per_block as usize))
+    }
+
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            block_number: BlockNumber(0),
+            proposed_txs: HashMap::new(),
+            held_accounts: HashSet::new(),
+        }
+    }
+
+    /// Starts the proposal of the transactions for the block. The transactions of the block
+    /// are counted from scratch, unless they're proposed for the same block as the previous ones.
+    pub fn start_proposal(&mut self, block_number: BlockNumber) {
+        self.held_accounts.clear();
+        if self.block_number != block_number {
+            self.block_number = block_number;
+            self.proposed_txs.clear();
+        }
+    }
+
+    /// Returns the accounts sending the transactions of the element, with the amount
+    /// of the transactions of every account.
+    fn senders(element: &SignedTxVariant) -> HashMap<AccountId, usize> {
+        let txs = match element {
+            SignedTxVariant::Tx(tx) => std::slice::from_ref(tx),
+            SignedTxVariant::Batch(batch) => batch.txs.as_slice(),
+        };
+        let mut senders = HashMap::new();
+        for tx in txs {
+            // `Close` transactions are disabled, so they're not counted.
+            if let Ok(account_id) = tx.account_id() {
+                *senders.entry(account_id).or_default() += 1;
+            }
+        }
+        senders
+    }
+
+    /// Checks whether the element must be held back from the current proposal: either one of its
+    /// senders has reached the limit, or the preceding transactions of the sender are held back.
+    pub fn holds_back(&mut self, element: &SignedTxVariant) -> bool {
+        let senders = Self::senders(element);
+        let held = senders.keys().any(|account_id| {
+            self.held_accounts.contains(account_id)
+                || self
+                    .proposed_txs
+                    .get(account_id)
+                    .map_or(false, |proposed| *proposed >= self.limit)
+        });
+        if held {
+            self.held_accounts.extend(senders.keys());
+        }
+        held
+    }
+
+    /// Counts the transactions of the element proposed for the current block.
+    pub fn record(&mut self, element: &SignedTxVariant) {
+        for (account_id, txs) in Self::senders(element) {
+            *self.proposed_txs.entry(account_id).or_default() += txs;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigUint;
+    use zksync_types::{
+        mempool::SignedTxsBatch, Address, Nonce, SignedZkSyncTx, TokenId, Transfer, ZkSyncTx,
+    };
+
+    use super::*;
+
+    fn transfer(account_id: u32, nonce: u32) -> SignedZkSyncTx {
+        let transfer = Transfer::new(
+            AccountId(account_id),
+            Address::repeat_byte(account_id as u8),
+            Address::repeat_byte(0xff),
+            TokenId(0),
+            BigUint::from(1_u32),
+            BigUint::from(1_u32),
+            Nonce(nonce),
+            Default::default(),
+            None,
+        );
+        SignedZkSyncTx {
+            tx: ZkSyncTx::Transfer(Box::new(transfer)),
+            eth_sign_data: None,
+        }
+    }
+
+    #[test]
+    fn account_tx_limit() {
+        let mut limit = AccountTxLimit::new(2);
+        limit.start_proposal(BlockNumber(1));
+
+        limit.record(&transfer(1, 0).into());
+        assert!(!limit.holds_back(&transfer(1, 1).into()));
+        limit.record(&transfer(1, 1).into());
+        assert!(limit.holds_back(&transfer(1, 2).into()));
+        assert!(!limit.holds_back(&transfer(2, 0).into()));
+
+        // Batch is proposed if all its senders have a slot left, and counted as a whole.
+        let batch = |txs| {
+            SignedTxVariant::Batch(SignedTxsBatch {
+                txs,
+                batch_id: 1,
+                eth_signatures: Vec::new(),
+            })
+        };
+        let large_batch = batch(vec![transfer(2, 0), transfer(2, 1), transfer(3, 0)]);
+        assert!(!limit.holds_back(&large_batch));
+        limit.record(&large_batch);
+        assert!(limit.holds_back(&transfer(2, 2).into()));
+        assert!(!limit.holds_back(&transfer(3, 1).into()));
+
+        // Batch with the limited sender is held back, and so are the later transactions
+        // of its other senders.
+        assert!(limit.holds_back(&batch(vec![transfer(1, 2), transfer(4, 0)])));
+        assert!(limit.holds_back(&transfer(4, 1).into()));
+
+        // Miniblocks of the same block share the counters, the next block starts from scratch.
+        limit.start_proposal(BlockNumber(1));
+        assert!(limit.holds_back(&transfer(1, 2).into()));
+        assert!(!limit.holds_back(&transfer(4, 0).into()));
+        limit.start_proposal(BlockNumber(2));
+        assert!(!limit.holds_back(&transfer(1, 2).into()));
+    }
+}
@@ -0,0 +1,132 @@
+//! Rate limit of the `ChangePubKey` transactions.
+//!
+//! Every `ChangePubKey` transaction takes several chunks of the block pubdata, so the account
+//! rotating its key over and over again bloats the pubdata at the cost of the fee only.
+//! The limit caps the amount of the `ChangePubKey` transactions an account can make within
+//! the sliding window. Both the executed transactions and the ones awaiting in the mempool
+//! are counted, so the limit holds across the restarts and the mempool handlers.
+
+// Built-in deps
+use std::collections::{HashMap, HashSet};
+// External uses
+use chrono::{DateTime, Duration, Utc};
+// Workspace uses
+use zksync_config::configs::chain::Mempool as MempoolConfig;
+use zksync_storage::StorageProcessor;
+use zksync_types::{Address, SignedZkSyncTx, ZkSyncTx};
+// Local uses
+use super::TxAddError;
+
+#[derive(Debug, Clone)]
+pub struct ChangePubKeyLimit {
+    limit: usize,
+    window: Duration,
+    exempt_accounts: HashSet<Address>,
+}
+
+impl ChangePubKeyLimit {
+    /// Creates the limit from the config, returns `None` if the limit is disabled.
+    pub fn from_config(config: &MempoolConfig) -> Option<Self> {
+        if config.change_pubkey_limit == 0 {
+            return None;
+        }
+
+        Some(Self {
+            limit: config.change_pubkey_limit as usize,
+            window: Duration::from_std(config.change_pubkey_limit_window())
+                .expect("Invalid ChangePubKey limit window"),
+            exempt_accounts: config
+                .change_pubkey_limit_exempt_accounts
+                .iter()
+                .copied()
+                .collect(),
+        })
+    }
+
+    /// Checks that the accounts changing their keys in the transactions don't exceed the limit.
+    pub async fn check(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        txs: &[SignedZkSyncTx],
+    ) -> Result<(), TxAddError> {
+        let mut new_changes: HashMap<Address, usize> = HashMap::new();
+        for tx in txs {
+            if let ZkSyncTx::ChangePubKey(tx) = &tx.tx {
+                if !self.exempt_accounts.contains(&tx.account) {
+                    *new_changes.entry(tx.account).or_default() += 1;
+                }
+            }
+        }
+
+        let now = Utc::now();
+        for (address, new_changes) in new_changes {
+            let recent_changes = storage
+                .chain()
+                .mempool_schema()
+                .load_change_pubkey_times(address, now - self.window)
+                .await
+                .map_err(|err| {
+                    vlog::warn!("Mempool storage access error: {}", err);
+                    TxAddError::DbError
+                })?;
+
+            if let Some(retry_after) = self.retry_after(&recent_changes, new_changes, now) {
+                vlog::info!(
+                    "ChangePubKey of the account {:#x} is rejected by the rate limit, \
+                     {} changes were made within the window",
+                    address,
+                    recent_changes.len()
+                );
+                metrics::counter!("mempool.change_pubkey_rate_limited", 1);
+                return Err(TxAddError::ChangePubKeyRateLimited(retry_after));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the amount of seconds after which the new changes fit into the limit,
+    /// or `None` if they already fit. `recent_changes` must be sorted from the oldest one.
+    fn retry_after(
+        &self,
+        recent_changes: &[DateTime<Utc>],
+        new_changes: usize,
+        now: DateTime<Utc>,
+    ) -> Option<u64> {
+        let excess = (recent_changes.len() + new_changes).checked_sub(self.limit)?;
+        if excess == 0 {
+            return None;
+        }
+
+        // The new changes fit once the `excess` oldest changes leave the window.
+        // If there are too many new changes, they never fit, so the whole window is reported.
+        let released_at = match recent_changes.get(excess - 1) {
+            Some(change) => *change + self.window,
+            None => now + self.window,
+        };
+        Some((released_at - now).num_seconds().max(1) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn change_pubkey_retry_after() {
+        let limit = ChangePubKeyLimit {
+            limit: 2,
+            window: Duration::hours(1),
+            exempt_accounts: HashSet::new(),
+        };
+        let now = Utc::now();
+        let changes = [now - Duration::minutes(50), now - Duration::minutes(20)];
+
+        assert_eq!(limit.retry_after(&changes[..1], 1, now), None);
+        // The oldest change leaves the window in 10 minutes.
+        assert_eq!(limit.retry_after(&changes, 1, now), Some(600));
+        // Both changes have to leave the window.
+        assert_eq!(limit.retry_after(&changes, 2, now), Some(2400));
+        // The batch exceeding the limit on its own never fits.
+        assert_eq!(limit.retry_after(&[], 3, now), Some(3600));
+    }
+}
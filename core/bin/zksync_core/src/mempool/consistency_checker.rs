@@ -0,0 +1,493 @@
+//! Consistency checker periodically cross-verifies the in-memory mempool state against
+//! the database: the committed nonces of the accounts, the persistent mempool and the
+//! pending block.
+//!
+//! Divergences may appear e.g. after a block revert, when the mempool keeps transactions
+//! referencing the nonces that are already used. Found divergences are reported via logs
+//! and metrics and, if configured, corrected.
+//!
+//! Some divergences (nonce mismatches and transactions missing in the database) may be
+//! observed for a short time while a block is being committed. Such divergences are only
+//! reported if they are found by two consecutive checks.
+//!
+//! The committed state is loaded in full only by the first check and after the blocks revert,
+//! the other checks apply the state diffs of the blocks committed since the previous check.
+
+// Built-in deps
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+// External uses
+use tokio::{sync::RwLock, time};
+// Workspace uses
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::{tx::TxHash, AccountId, AccountMap, AccountUpdate, Address, BlockNumber, Nonce};
+// Local uses
+use super::MempoolState;
+
+/// Divergences between the mempool and the database found by a single check.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct ConsistencyReport {
+    /// Accounts for which the mempool nonce differs from the committed one,
+    /// along with the committed account ID and nonce.
+    nonce_mismatches: HashMap<Address, (AccountId, Nonce)>,
+    /// Queued transactions which can't be executed since their nonces are already used.
+    /// For batches, hashes of all the batch transactions are included.
+    stale_txs: HashSet<TxHash>,
+    /// Queued transactions which are already executed in the pending block.
+    executed_txs: HashSet<TxHash>,
+    /// Queued transactions which are not present in the persistent mempool.
+    unpersisted_txs: HashSet<TxHash>,
+}
+
+impl ConsistencyReport {
+    fn collect(
+        mempool_state: &MempoolState,
+        committed_accounts: &HashMap<Address, (AccountId, Nonce)>,
+        persisted_txs: &HashSet<TxHash>,
+        pending_block_txs: &HashSet<TxHash>,
+    ) -> Self {
+        let mut report = Self::default();
+
+        for (address, (account_id, nonce)) in committed_accounts {
+            if mempool_state.account_nonces.get(address) != Some(nonce) {
+                report
+                    .nonce_mismatches
+                    .insert(*address, (*account_id, *nonce));
+            }
+        }
+
+        for tx_variant in mempool_state.transactions_queue.iter() {
+            let hashes = tx_variant.hashes();
+            let is_stale = tx_variant.get_transactions().iter().any(|tx| {
+                committed_accounts
+                    .get(&tx.account())
+                    .map_or(false, |(_, nonce)| tx.nonce() < *nonce)
+            });
+            if is_stale {
+                report.stale_txs.extend(hashes.iter().cloned());
+            }
+
+            for hash in hashes {
+                if pending_block_txs.contains(&hash) {
+                    report.executed_txs.insert(hash);
+                }
+                if !persisted_txs.contains(&hash) {
+                    report.unpersisted_txs.insert(hash);
+                }
+            }
+        }
+
+        report
+    }
+
+    /// Leaves only the divergences that can't be caused by the concurrent block commit,
+    /// i.e. ones that are definitive or were also found by the previous check.
+    fn confirmed(&self, previous: &Self) -> Self {
+        Self {
+            nonce_mismatches: self
+                .nonce_mismatches
+                .iter()
+                .filter(|(address, expected)| {
+                    previous.nonce_mismatches.get(address) == Some(expected)
+                })
+                .map(|(address, expected)| (*address, *expected))
+                .collect(),
+            stale_txs: self.stale_txs.clone(),
+            executed_txs: self.executed_txs.clone(),
+            unpersisted_txs: self
+                .unpersisted_txs
+                .intersection(&previous.unpersisted_txs)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.nonce_mismatches.is_empty()
+            && self.stale_txs.is_empty()
+            && self.executed_txs.is_empty()
+            && self.unpersisted_txs.is_empty()
+    }
+
+    fn report_metrics(&self) {
+        let divergences = [
+            ("nonce_mismatch", self.nonce_mismatches.len()),
+            ("stale_tx", self.stale_txs.len()),
+            ("executed_tx", self.executed_txs.len()),
+            ("unpersisted_tx", self.unpersisted_txs.len()),
+        ];
+        for (kind, count) in divergences.iter() {
+            metrics::counter!(
+                "mempool.consistency_check.divergences",
+                *count as u64,
+                "kind" => *kind
+            );
+        }
+    }
+
+    /// Brings the mempool state in line with the database.
+    fn apply(&self, mempool_state: &mut MempoolState) {
+        for (address, (account_id, nonce)) in &self.nonce_mismatches {
+            mempool_state.account_ids.insert(*account_id, *address);
+            mempool_state.account_nonces.insert(*address, *nonce);
+        }
+
+        mempool_state.transactions_queue.retain(|tx_variant| {
+            tx_variant.hashes().iter().all(|hash| {
+                !self.stale_txs.contains(hash)
+                    && !self.executed_txs.contains(hash)
+                    && !self.unpersisted_txs.contains(hash)
+            })
+        });
+        mempool_state.report_size();
+    }
+}
+
+/// Committed nonces of the accounts, kept up to date by applying the state diffs.
+#[derive(Debug, Default)]
+struct CommittedAccounts {
+    block_number: BlockNumber,
+    /// ID of the latest blocks revert. The updates of the reverted blocks are removed,
+    /// so the state can't be restored from the diffs once the blocks are reverted.
+    last_revert_id: Option<i64>,
+    addresses: HashMap<AccountId, Address>,
+    accounts: HashMap<Address, (AccountId, Nonce)>,
+}
+
+impl CommittedAccounts {
+    fn new(block_number: BlockNumber, last_revert_id: Option<i64>, accounts: AccountMap) -> Self {
+        let mut committed = Self {
+            block_number,
+            last_revert_id,
+            ..Self::default()
+        };
+        for (id, account) in accounts {
+            committed.addresses.insert(id, account.address);
+            committed
+                .accounts
+                .insert(account.address, (id, account.nonce));
+        }
+        committed
+    }
+
+    fn apply_updates(&mut self, block_number: BlockNumber, updates: &[(AccountId, AccountUpdate)]) {
+        for (id, update) in updates {
+            match update {
+                AccountUpdate::Create { address, nonce } => {
+                    self.addresses.insert(*id, *address);
+                    self.accounts.insert(*address, (*id, *nonce));
+                }
+                AccountUpdate::Delete { address, .. } => {
+                    self.addresses.remove(id);
+                    self.accounts.remove(address);
+                }
+                AccountUpdate::UpdateBalance { new_nonce, .. }
+                | AccountUpdate::ChangePubKeyHash { new_nonce, .. } => {
+                    if let Some(address) = self.addresses.get(id) {
+                        self.accounts.insert(*address, (*id, *new_nonce));
+                    }
+                }
+            }
+        }
+        self.block_number = block_number;
+    }
+}
+
+pub(super) struct MempoolConsistencyChecker {
+    db_pool: ConnectionPool,
+    mempool_state: Arc<RwLock<MempoolState>>,
+    auto_correct: bool,
+    /// Divergences found by the previous check.
+    previous_report: ConsistencyReport,
+    /// Committed state as of the previous check.
+    committed: Option<CommittedAccounts>,
+}
+
+impl MempoolConsistencyChecker {
+    pub fn new(
+        db_pool: ConnectionPool,
+        mempool_state: Arc<RwLock<MempoolState>>,
+        auto_correct: bool,
+    ) -> Self {
+        Self {
+            db_pool,
+            mempool_state,
+            auto_correct,
+            previous_report: ConsistencyReport::default(),
+            committed: None,
+        }
+    }
+
+    /// Brings the committed state up to date, loading it in full if it's not loaded yet
+    /// or the blocks were reverted since the previous check.
+    async fn update_committed_accounts(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<()> {
+        let last_revert_id = storage
+            .chain()
+            .block_schema()
+            .load_block_reverts(1)
+            .await?
+            .first()
+            .map(|revert| revert.id);
+
+        match &mut self.committed {
+            Some(committed) if committed.last_revert_id == last_revert_id => {
+                let state_diff = storage
+                    .chain()
+                    .state_schema()
+                    .load_state_diff(committed.block_number, None)
+                    .await?;
+                if let Some((block_number, updates)) = state_diff {
+                    committed.apply_updates(block_number, &updates);
+                }
+            }
+            _ => {
+                let (block_number, accounts) = storage
+                    .chain()
+                    .state_schema()
+                    .load_committed_state(None)
+                    .await?;
+                self.committed = Some(CommittedAccounts::new(
+                    block_number,
+                    last_revert_id,
+                    accounts,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    async fn check(&mut self) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let mut storage = self.db_pool.access_storage().await?;
+
+        self.update_committed_accounts(&mut storage).await?;
+        let committed_accounts = &self
+            .committed
+            .as_ref()
+            .expect("Committed state is loaded above")
+            .accounts;
+
+        let persisted_txs: HashSet<_> = storage
+            .chain()
+            .mempool_schema()
+            .load_txs()
+            .await?
+            .iter()
+            .flat_map(|tx_variant| tx_variant.hashes())
+            .collect();
+
+        let pending_block_txs: HashSet<_> = storage
+            .chain()
+            .block_schema()
+            .load_pending_block()
+            .await?
+            .map(|block| {
+                let executed_txs = block
+                    .success_operations
+                    .iter()
+                    .filter_map(|op| op.get_executed_tx())
+                    .map(|tx| tx.signed_tx.hash());
+                let failed_txs = block.failed_txs.iter().map(|tx| tx.signed_tx.hash());
+                executed_txs.chain(failed_txs).collect()
+            })
+            .unwrap_or_default();
+
+        let mut mempool_state = self.mempool_state.write().await;
+        let report = ConsistencyReport::collect(
+            &mempool_state,
+            committed_accounts,
+            &persisted_txs,
+            &pending_block_txs,
+        );
+        let confirmed = report.confirmed(&self.previous_report);
+        self.previous_report = report;
+
+        confirmed.report_metrics();
+        metrics::histogram!("mempool.consistency_check", start.elapsed());
+        if confirmed.is_empty() {
+            return Ok(());
+        }
+
+        vlog::warn!(
+            "Mempool is inconsistent with the database: {} nonce mismatches, {} stale txs, \
+             {} txs executed in the pending block, {} txs missing in the database",
+            confirmed.nonce_mismatches.len(),
+            confirmed.stale_txs.len(),
+            confirmed.executed_txs.len(),
+            confirmed.unpersisted_txs.len()
+        );
+        vlog::debug!("Mempool divergences: {:?}", confirmed);
+        if !self.auto_correct {
+            return Ok(());
+        }
+
+        confirmed.apply(&mut mempool_state);
+        drop(mempool_state);
+
+        // Stale transactions can never be executed, so they are removed from the persistent
+        // mempool as well. Otherwise they would be restored into the mempool on restart.
+        let stale_txs: Vec<_> = confirmed.stale_txs.into_iter().collect();
+        storage
+            .chain()
+            .mempool_schema()
+            .remove_txs(&stale_txs)
+            .await?;
+
+        vlog::info!("Mempool divergences were corrected");
+        Ok(())
+    }
+
+    pub async fn run(mut self, check_interval: Duration) {
+        let mut timer = time::interval(check_interval);
+        loop {
+            timer.tick().await;
+
+            if let Err(err) = self.check().await {
+                vlog::warn!("Failed to check the mempool consistency: {}", err);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mempool::mempool_transactions_queue::MempoolTransactionsQueue;
+    use zksync_types::{
+        mempool::SignedTxVariant, tx::Withdraw, Account, SignedZkSyncTx, TokenId, ZkSyncTx,
+    };
+
+    fn get_withdraw(from: Address, nonce: Nonce) -> SignedTxVariant {
+        let withdraw = Withdraw::new(
+            AccountId(3),
+            from,
+            [9u8; 20].into(),
+            TokenId(1),
+            20u32.into(),
+            10u32.into(),
+            nonce,
+            Default::default(),
+            None,
+        );
+
+        SignedTxVariant::Tx(SignedZkSyncTx {
+            tx: ZkSyncTx::Withdraw(Box::new(withdraw)),
+            eth_sign_data: None,
+        })
+    }
+
+    /// Checks that the divergences are found, confirmed and corrected.
+    #[test]
+    fn mempool_divergences() {
+        let address = Address::repeat_byte(1);
+        let stale_tx = get_withdraw(address, Nonce(1));
+        let executed_tx = get_withdraw(address, Nonce(2));
+        let valid_tx = get_withdraw(address, Nonce(3));
+
+        let mut transactions_queue = MempoolTransactionsQueue::new();
+        for tx in &[stale_tx.clone(), executed_tx.clone(), valid_tx.clone()] {
+            transactions_queue.add_tx_variant(tx.clone());
+        }
+        let mut mempool_state = MempoolState {
+            account_nonces: vec![(address, Nonce(5))].into_iter().collect(),
+            account_ids: vec![(AccountId(3), address)].into_iter().collect(),
+            transactions_queue,
+            nonce_reservations: HashMap::new(),
+            proposed_nonces: HashMap::new(),
+            delayed_accounts: HashMap::new(),
+            release_times: HashMap::new(),
+        };
+
+        // Nonce was reverted in the database.
+        let committed_accounts = vec![(address, (AccountId(3), Nonce(2)))]
+            .into_iter()
+            .collect();
+        let persisted_txs = vec![stale_tx.hashes()[0], valid_tx.hashes()[0]]
+            .into_iter()
+            .collect();
+        let pending_block_txs = vec![executed_tx.hashes()[0]].into_iter().collect();
+
+        let report = ConsistencyReport::collect(
+            &mempool_state,
+            &committed_accounts,
+            &persisted_txs,
+            &pending_block_txs,
+        );
+        assert_eq!(report.nonce_mismatches.len(), 1);
+        assert!(report.stale_txs.contains(&stale_tx.hashes()[0]));
+        assert!(report.executed_txs.contains(&executed_tx.hashes()[0]));
+        assert!(report.unpersisted_txs.contains(&executed_tx.hashes()[0]));
+
+        // Divergences that may be caused by the concurrent block commit are not confirmed
+        // by the first check.
+        let confirmed = report.confirmed(&ConsistencyReport::default());
+        assert!(confirmed.nonce_mismatches.is_empty());
+        assert!(confirmed.unpersisted_txs.is_empty());
+        assert_eq!(confirmed.stale_txs, report.stale_txs);
+
+        let confirmed = report.confirmed(&report);
+        assert_eq!(confirmed, report);
+
+        confirmed.apply(&mut mempool_state);
+        assert_eq!(mempool_state.nonce(&address), Nonce(2));
+        let queued_hashes: Vec<_> = mempool_state
+            .transactions_queue
+            .iter()
+            .flat_map(|tx| tx.hashes())
+            .collect();
+        assert_eq!(queued_hashes, valid_tx.hashes());
+    }
+
+    /// Checks that the committed nonces are kept up to date by the state diffs.
+    #[test]
+    fn committed_accounts_updates() {
+        let address = Address::repeat_byte(1);
+        let new_address = Address::repeat_byte(2);
+        let mut account = Account::default_with_address(&address);
+        account.nonce = Nonce(3);
+        let accounts = vec![(AccountId(1), account)].into_iter().collect();
+        let mut committed = CommittedAccounts::new(BlockNumber(1), None, accounts);
+
+        let updates = vec![
+            (
+                AccountId(1),
+                AccountUpdate::UpdateBalance {
+                    old_nonce: Nonce(3),
+                    new_nonce: Nonce(4),
+                    balance_update: (TokenId(0), 10u32.into(), 5u32.into()),
+                },
+            ),
+            (
+                AccountId(2),
+                AccountUpdate::Create {
+                    address: new_address,
+                    nonce: Nonce(0),
+                },
+            ),
+        ];
+        committed.apply_updates(BlockNumber(3), &updates);
+
+        assert_eq!(committed.block_number, BlockNumber(3));
+        assert_eq!(committed.accounts[&address], (AccountId(1), Nonce(4)));
+        assert_eq!(committed.accounts[&new_address], (AccountId(2), Nonce(0)));
+
+        committed.apply_updates(
+            BlockNumber(2),
+            &[(
+                AccountId(2),
+                AccountUpdate::Delete {
+                    address: new_address,
+                    nonce: Nonce(0),
+                },
+            )],
+        );
+        assert!(!committed.accounts.contains_key(&new_address));
+        assert!(!committed.addresses.contains_key(&AccountId(2)));
+    }
+}
@@ -0,0 +1,248 @@
+//! Operator-side checks of the account recoveries by the guardians.
+//!
+//! The contract only checks that the `ChangePubKey` is authorized by the quorum of the guardian set
+//! signed by the owner. Whether the set is the latest one registered by the account and whether
+//! the timelock of the recovery has passed is checked here, see `zksync_types::guardians` for
+//! the trust model. The recovery is checked when it's added to the mempool, and again before it's
+//! proposed for the block, since the owner may register another set in between: the revoked
+//! recoveries are removed from the mempool.
+
+// External uses
+use chrono::{DateTime, Utc};
+// Workspace uses
+use zksync_storage::StorageProcessor;
+use zksync_types::{
+    guardians::{ChangePubKeyGuardiansData, GuardianRecovery, GuardianRegistration},
+    mempool::SignedTxVariant,
+    tx::{ChangePubKey, ChangePubKeyEthAuthData},
+    SignedZkSyncTx, ZkSyncTx,
+};
+// Local uses
+use super::TxAddError;
+
+/// Returns the `ChangePubKey` transaction along with its guardians authorization,
+/// if the transaction is a recovery by the guardians.
+fn as_guardian_recovery(
+    tx: &SignedZkSyncTx,
+) -> Option<(&ChangePubKey, &ChangePubKeyGuardiansData)> {
+    match &tx.tx {
+        ZkSyncTx::ChangePubKey(tx) => match &tx.eth_auth_data {
+            Some(ChangePubKeyEthAuthData::Guardians(data)) => Some((tx, data)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Checks whether the transaction variant contains a recovery by the guardians.
+pub fn contains_guardian_recovery(tx_variant: &SignedTxVariant) -> bool {
+    match tx_variant {
+        SignedTxVariant::Tx(tx) => as_guardian_recovery(tx).is_some(),
+        SignedTxVariant::Batch(batch) => batch
+            .txs
+            .iter()
+            .any(|tx| as_guardian_recovery(tx).is_some()),
+    }
+}
+
+/// Checks the recovery against the guardian set registered by the account and the recovery
+/// initiated by its guardians.
+fn check_recovery(
+    guardians_data: &ChangePubKeyGuardiansData,
+    registration: Option<GuardianRegistration>,
+    recovery: Option<GuardianRecovery>,
+    now: DateTime<Utc>,
+) -> Result<(), TxAddError> {
+    // The replaced guardian sets are revoked.
+    if registration.map(|registration| registration.guardian_set)
+        != Some(guardians_data.guardian_set.clone())
+    {
+        return Err(TxAddError::GuardianRecoveryNotInitiated);
+    }
+
+    let recovery = recovery.ok_or(TxAddError::GuardianRecoveryNotInitiated)?;
+    let remaining = recovery.executable_at - now;
+    if remaining.num_seconds() > 0 {
+        return Err(TxAddError::GuardianRecoveryTimelocked(
+            remaining.num_seconds() as u64,
+        ));
+    }
+    Ok(())
+}
+
+/// Rejects the `ChangePubKey` transactions authorized by the guardians unless the recovery
+/// was initiated for the registered guardian set and its timelock has passed.
+pub async fn check_guardian_recoveries(
+    storage: &mut StorageProcessor<'_>,
+    txs: &[SignedZkSyncTx],
+) -> Result<(), TxAddError> {
+    for tx in txs {
+        let (change_pubkey, guardians_data) = match as_guardian_recovery(tx) {
+            Some(recovery) => recovery,
+            None => continue,
+        };
+
+        let mut schema = storage.guardians_schema();
+        let registration = schema
+            .get_guardians(change_pubkey.account_id)
+            .await
+            .map_err(|err| {
+                vlog::warn!("Mempool storage access error: {}", err);
+                TxAddError::DbError
+            })?;
+        let recovery = schema
+            .get_recovery(
+                change_pubkey.account_id,
+                &change_pubkey.new_pk_hash,
+                change_pubkey.nonce,
+            )
+            .await
+            .map_err(|err| {
+                vlog::warn!("Mempool storage access error: {}", err);
+                TxAddError::DbError
+            })?;
+
+        let result = check_recovery(guardians_data, registration, recovery, Utc::now());
+        if let Err(TxAddError::GuardianRecoveryTimelocked(_)) = result {
+            metrics::counter!("mempool.guardian_recovery_timelocked", 1);
+        }
+        result?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use zksync_types::{
+        account::PubKeyHash,
+        guardians::GuardianSet,
+        mempool::SignedTxsBatch,
+        tx::{PackedEthSignature, SignatureDomain, TimeRange},
+        AccountId, Address, Nonce, TokenId,
+    };
+
+    fn guardian_set(version: u32) -> GuardianSet {
+        GuardianSet {
+            version,
+            guardians: vec![Address::repeat_byte(1), Address::repeat_byte(2)],
+            quorum: 1,
+            timelock: 3600,
+        }
+    }
+
+    fn signature() -> PackedEthSignature {
+        PackedEthSignature::deserialize_packed(&[0; 65]).unwrap()
+    }
+
+    fn guardians_data(version: u32) -> ChangePubKeyGuardiansData {
+        ChangePubKeyGuardiansData {
+            domain: SignatureDomain::new(1, Address::repeat_byte(9)),
+            guardian_set: guardian_set(version),
+            owner_signature: signature(),
+            signatures: Vec::new(),
+        }
+    }
+
+    fn registration(version: u32) -> GuardianRegistration {
+        GuardianRegistration {
+            account_id: AccountId(1),
+            address: Address::repeat_byte(3),
+            guardian_set: guardian_set(version),
+            owner_signature: signature(),
+            registered_at: Utc::now(),
+        }
+    }
+
+    fn recovery(executable_at: DateTime<Utc>) -> GuardianRecovery {
+        GuardianRecovery {
+            account_id: AccountId(1),
+            new_pk_hash: PubKeyHash::default(),
+            nonce: Nonce(0),
+            signatures: Vec::new(),
+            initiated_at: executable_at - Duration::hours(1),
+            executable_at,
+        }
+    }
+
+    fn change_pubkey(eth_auth_data: Option<ChangePubKeyEthAuthData>) -> SignedZkSyncTx {
+        let mut tx = ChangePubKey::new(
+            AccountId(1),
+            Address::repeat_byte(3),
+            PubKeyHash::default(),
+            TokenId(0),
+            0u32.into(),
+            Nonce(0),
+            TimeRange::default(),
+            None,
+            None,
+        );
+        tx.eth_auth_data = eth_auth_data;
+        ZkSyncTx::ChangePubKey(Box::new(tx)).into()
+    }
+
+    /// Checks that the recovery is accepted only for the registered set once the timelock passes.
+    #[test]
+    fn recovery_checks() {
+        let now = Utc::now();
+        let executable = Some(recovery(now - Duration::seconds(1)));
+
+        assert!(check_recovery(
+            &guardians_data(2),
+            Some(registration(2)),
+            executable.clone(),
+            now
+        )
+        .is_ok());
+        // The set is replaced after the recovery was initiated.
+        assert!(matches!(
+            check_recovery(
+                &guardians_data(1),
+                Some(registration(2)),
+                executable.clone(),
+                now
+            ),
+            Err(TxAddError::GuardianRecoveryNotInitiated)
+        ));
+        assert!(matches!(
+            check_recovery(&guardians_data(1), None, executable, now),
+            Err(TxAddError::GuardianRecoveryNotInitiated)
+        ));
+        assert!(matches!(
+            check_recovery(&guardians_data(2), Some(registration(2)), None, now),
+            Err(TxAddError::GuardianRecoveryNotInitiated)
+        ));
+        assert!(matches!(
+            check_recovery(
+                &guardians_data(2),
+                Some(registration(2)),
+                Some(recovery(now + Duration::seconds(60))),
+                now
+            ),
+            Err(TxAddError::GuardianRecoveryTimelocked(60))
+        ));
+    }
+
+    /// Checks that the recoveries are found among the single transactions and the batches.
+    #[test]
+    fn guardian_recoveries_are_detected() {
+        let recovery_tx =
+            change_pubkey(Some(ChangePubKeyEthAuthData::Guardians(guardians_data(1))));
+        let plain_tx = change_pubkey(None);
+
+        assert!(contains_guardian_recovery(&SignedTxVariant::Tx(
+            recovery_tx.clone()
+        )));
+        assert!(!contains_guardian_recovery(&SignedTxVariant::Tx(
+            plain_tx.clone()
+        )));
+        assert!(contains_guardian_recovery(&SignedTxVariant::Batch(
+            SignedTxsBatch {
+                txs: vec![plain_tx, recovery_tx],
+                batch_id: 1,
+                eth_signatures: Vec::new(),
+            }
+        )));
+    }
+}
@@ -48,6 +48,28 @@ impl MempoolTransactionsQueue {
         }
     }
 
+    /// Returns the total amount of queued transactions (both ready and pending).
+    pub fn len(&self) -> usize {
+        self.ready_txs.len() + self.pending_txs.len()
+    }
+
+    /// Returns an iterator over all the queued transactions (both ready and pending).
+    pub fn iter(&self) -> impl Iterator<Item = &SignedTxVariant> {
+        self.ready_txs
+            .iter()
+            .chain(self.pending_txs.iter().map(|pending_tx| &pending_tx.tx))
+    }
+
+    /// Retains only the transactions for which the predicate returns `true`.
+    pub fn retain(&mut self, mut predicate: impl FnMut(&SignedTxVariant) -> bool) {
+        self.ready_txs.retain(|tx| predicate(tx));
+        self.pending_txs = self
+            .pending_txs
+            .drain()
+            .filter(|pending_tx| predicate(&pending_tx.tx))
+            .collect();
+    }
+
     pub fn pop_front(&mut self) -> Option<SignedTxVariant> {
         self.ready_txs.pop_front()
     }
@@ -57,13 +79,20 @@ impl MempoolTransactionsQueue {
     }
 
     pub fn add_tx_variant(&mut self, tx: SignedTxVariant) {
+        self.add_tx_variant_not_before(tx, 0);
+    }
+
+    /// Adds the transaction which doesn't become ready until the given block timestamp
+    /// even if its `valid_from` is earlier.
+    pub fn add_tx_variant_not_before(&mut self, tx: SignedTxVariant, timestamp: u64) {
         self.pending_txs.push(MempoolPendingTransaction {
             valid_from: tx
                 .get_transactions()
                 .into_iter()
                 .map(|tx| tx.tx.valid_from())
                 .max()
-                .unwrap_or(0),
+                .unwrap_or(0)
+                .max(timestamp),
             tx,
         });
     }
@@ -13,10 +13,28 @@
 //!
 //! Communication with db:
 //! on restart mempool restores nonces of the accounts that are stored in the account tree.
+//! Transactions from the nonce ranges reserved by the accounts (see `zksync_types::nonce_reservation`)
+//! may arrive out of order: such transactions are held in the queue until the preceding ones arrive.
+//! Transfers to the addresses screened by the operator may be rejected or held in the queue
+//! for the review (see `screening`).
+//! While running, the mempool state is periodically cross-verified against the database
+//! (see `consistency_checker`), and the transactions which weren't executed in time are expired
+//! (see `tx_expiry`). Accounts changing their public keys too often are rate limited
+//! (see `change_pubkey_limit`). Transactions with the amounts or fees exceeding the sanity bounds
+//! configured by the operator are rejected (see `zksync_types::amount_bounds`).
+//! Proposed transactions are ordered by their dependencies, so the transactions of the same account
+//! are executed in the nonce order (see `tx_dependencies`). The amount of the transactions of a single
+//! account proposed for a block may be capped (see `account_tx_limit`).
 
 // Built-in deps
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    cmp::max,
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 // External uses
+use chrono::{DateTime, Utc};
 use futures::{
     channel::{
         mpsc::{self, Receiver},
@@ -31,21 +49,49 @@ use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
 
 // Workspace uses
+use vlog::Instrument;
 use zksync_balancer::{Balancer, BuildBalancedItem};
 use zksync_config::ZkSyncConfig;
-use zksync_storage::ConnectionPool;
+use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_types::{
+    amount_bounds::{AmountBounds, TxAmountBoundsError},
+    l1_message::L1Message,
     mempool::{SignedTxVariant, SignedTxsBatch},
-    tx::TxEthSignature,
-    AccountId, AccountUpdate, AccountUpdates, Address, Nonce, PriorityOp, SignedZkSyncTx,
-    TransferOp, TransferToNewOp, ZkSyncTx,
+    nonce_reservation::NonceReservation,
+    priority_ops::check_serial_id_continuity,
+    screening::{ScreeningAction, ScreeningRecord},
+    tx::{TxEthSignature, TxHash},
+    AccountId, AccountUpdate, AccountUpdates, Address, BlockNumber, Nonce, PriorityOp,
+    SignedZkSyncTx, TokenId, TransferOp, TransferToNewOp, ZkSyncTx,
 };
 
 // Local uses
-use crate::mempool::mempool_transactions_queue::MempoolTransactionsQueue;
-use crate::{eth_watch::EthWatchRequest, wait_for_tasks};
+use crate::mempool::{
+    account_tx_limit::AccountTxLimit,
+    change_pubkey_limit::ChangePubKeyLimit,
+    consistency_checker::MempoolConsistencyChecker,
+    guardian_recovery::{check_guardian_recoveries, contains_guardian_recovery},
+    mempool_transactions_queue::MempoolTransactionsQueue,
+    screening::{AddressScreener, DepositScreeningQueue},
+    tx_dependencies::order_by_dependencies,
+    tx_expiry::MempoolTxExpiry,
+};
+use crate::{backpressure::Backpressure, eth_watch::EthWatchRequest, wait_for_tasks};
 
+mod account_tx_limit;
+mod change_pubkey_limit;
+mod consistency_checker;
+mod guardian_recovery;
 mod mempool_transactions_queue;
+mod screening;
+pub mod tx_dependencies;
+mod tx_expiry;
+
+#[cfg(test)]
+mod tests;
+
+/// Maximum number of the messages sent from L1 delivered in one miniblock.
+const MAX_L1_MESSAGES_PER_MINIBLOCK: usize = 100;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Error)]
 pub enum TxAddError {
@@ -87,23 +133,51 @@ pub enum TxAddError {
 
     #[error("The number of withdrawals in the batch is too big")]
     BatchWithdrawalsOverload,
+
+    #[error("Server is shutting down, try again later")]
+    ShuttingDown,
+
+    #[error("Transfer recipient is screened by the operator")]
+    RecipientScreened,
+
+    #[error("Server is overloaded, try again in {0} seconds")]
+    Overloaded(u64),
+
+    #[error("Account public key is changed too often, try again in {0} seconds")]
+    ChangePubKeyRateLimited(u64),
+
+    #[error("Amount of token {0} exceeds the allowed maximum")]
+    AmountOutOfBounds(TokenId),
+
+    #[error("Fee in token {0} exceeds the allowed maximum")]
+    FeeOutOfBounds(TokenId),
+
+    #[error("Account recovery by the guardians is not initiated")]
+    GuardianRecoveryNotInitiated,
+
+    #[error("Timelock of the account recovery passes in {0} seconds")]
+    GuardianRecoveryTimelocked(u64),
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct ProposedBlock {
     pub priority_ops: Vec<PriorityOp>,
     pub txs: Vec<SignedTxVariant>,
+    /// Messages sent from L1 that are delivered in the block. They don't affect the state.
+    pub l1_messages: Vec<L1Message>,
 }
 
 impl ProposedBlock {
     pub fn is_empty(&self) -> bool {
-        self.priority_ops.is_empty() && self.txs.is_empty()
+        self.priority_ops.is_empty() && self.txs.is_empty() && self.l1_messages.is_empty()
     }
 }
 
 #[derive(Debug)]
 pub struct GetBlockRequest {
     pub last_priority_op_number: u64,
+    pub last_l1_message_number: u64,
+    pub block_number: BlockNumber,
     pub block_timestamp: u64,
     pub response_sender: oneshot::Sender<ProposedBlock>,
 }
@@ -113,7 +187,12 @@ pub enum MempoolTransactionRequest {
     /// Add new transaction to mempool, transaction should be previously checked
     /// for correctness (including its Ethereum and ZKSync signatures).
     /// oneshot is used to receive tx add result.
-    NewTx(Box<SignedZkSyncTx>, oneshot::Sender<Result<(), TxAddError>>),
+    /// Handling of the request is traced within the provided span.
+    NewTx(
+        Box<SignedZkSyncTx>,
+        oneshot::Sender<Result<(), TxAddError>>,
+        vlog::Span,
+    ),
     /// Add a new batch of transactions to the mempool. All transactions in batch must
     /// be either executed successfully, or otherwise fail all together.
     /// Invariants for each individual transaction in the batch are the same as in
@@ -122,6 +201,20 @@ pub enum MempoolTransactionRequest {
         Vec<SignedZkSyncTx>,
         Vec<TxEthSignature>,
         oneshot::Sender<Result<(), TxAddError>>,
+        vlog::Span,
+    ),
+    /// Run the same checks as for the `NewTx` request, without adding the transaction to mempool.
+    CheckTx(
+        Box<SignedZkSyncTx>,
+        oneshot::Sender<Result<(), TxAddError>>,
+        vlog::Span,
+    ),
+    /// Run the same checks as for the `NewTxsBatch` request, without adding the batch to mempool.
+    CheckTxsBatch(
+        Vec<SignedZkSyncTx>,
+        Vec<TxEthSignature>,
+        oneshot::Sender<Result<(), TxAddError>>,
+        vlog::Span,
     ),
 }
 
@@ -138,6 +231,14 @@ struct MempoolState {
     account_nonces: HashMap<Address, Nonce>,
     account_ids: HashMap<AccountId, Address>,
     transactions_queue: MempoolTransactionsQueue,
+    // active nonce reservations of the accounts
+    nonce_reservations: HashMap<Address, Vec<NonceReservation>>,
+    // next nonce of the reserving accounts after the proposed but not yet committed transactions
+    proposed_nonces: HashMap<Address, Nonce>,
+    // release times of the accounts having the transactions delayed by the screening
+    delayed_accounts: HashMap<Address, DateTime<Utc>>,
+    // release times of the delayed transactions, the expiry TTL is counted since them
+    release_times: HashMap<TxHash, DateTime<Utc>>,
 }
 
 impl MempoolState {
@@ -165,7 +266,7 @@ impl MempoolState {
         }
     }
 
-    async fn restore_from_db(db_pool: &ConnectionPool) -> Self {
+    async fn restore_from_db(db_pool: &ConnectionPool, tx_ttl: Duration) -> Self {
         let mut storage = db_pool.access_storage().await.expect("mempool db restore");
         let mut transaction = storage
             .start_transaction()
@@ -187,6 +288,19 @@ impl MempoolState {
             account_nonces.insert(account.address, account.nonce);
         }
 
+        let mut nonce_reservations: HashMap<_, Vec<_>> = HashMap::new();
+        for reservation in transaction
+            .nonce_reservations_schema()
+            .load_all_active_reservations()
+            .await
+            .expect("mempool nonce reservations load")
+        {
+            nonce_reservations
+                .entry(reservation.address)
+                .or_default()
+                .push(reservation);
+        }
+
         // Remove any possible duplicates of already executed transactions
         // from the database.
         transaction
@@ -205,12 +319,18 @@ impl MempoolState {
             .await
             .expect("Attempt to restore mempool txs from DB failed");
 
-        // Transactions can become ready when knowing the block timestamp
-        let mut transactions_queue = MempoolTransactionsQueue::new();
-
-        for tx in all_mempool_txs.clone() {
-            transactions_queue.add_tx_variant(tx);
-        }
+        // Transactions delayed by the screening are held until their release times.
+        // The ones released recently are loaded as well, since they expire counting from
+        // the release rather than from the submission.
+        let released_after = Utc::now()
+            - chrono::Duration::from_std(tx_ttl).expect("mempool transactions TTL is too big");
+        let delays: HashMap<_, _> = transaction
+            .screening_schema()
+            .load_delays(released_after)
+            .await
+            .expect("mempool screening delays load")
+            .into_iter()
+            .collect();
 
         transaction
             .commit()
@@ -222,48 +342,180 @@ impl MempoolState {
             all_mempool_txs.len()
         );
 
-        Self {
+        let mut mempool_state = Self {
             account_nonces,
             account_ids,
-            transactions_queue,
+            // Transactions can become ready when knowing the block timestamp
+            transactions_queue: MempoolTransactionsQueue::new(),
+            nonce_reservations,
+            proposed_nonces: HashMap::new(),
+            delayed_accounts: HashMap::new(),
+            release_times: HashMap::new(),
+        };
+        for tx in all_mempool_txs {
+            let release_at = tx
+                .hashes()
+                .iter()
+                .filter_map(|hash| delays.get(hash))
+                .max()
+                .copied();
+            mempool_state.add_tx_variant(tx, release_at);
         }
+        mempool_state
     }
 
     fn nonce(&self, address: &Address) -> Nonce {
         *self.account_nonces.get(address).unwrap_or(&Nonce(0))
     }
 
-    fn add_tx(&mut self, tx: SignedZkSyncTx) {
-        self.transactions_queue.add_tx_variant(tx.into());
+    /// Returns the nonce of the next transaction of the account, taking the proposed
+    /// but not yet committed transactions into account.
+    fn expected_nonce(&self, address: &Address) -> Nonce {
+        let committed_nonce = self.nonce(address);
+        self.proposed_nonces
+            .get(address)
+            .map_or(committed_nonce, |nonce| max(*nonce, committed_nonce))
+    }
+
+    fn is_nonce_reserved(&self, address: &Address, nonce: Nonce, now: DateTime<Utc>) -> bool {
+        self.nonce_reservations
+            .get(address)
+            .map_or(false, |reservations| {
+                reservations
+                    .iter()
+                    .any(|reservation| reservation.is_active(now) && reservation.contains(nonce))
+            })
     }
 
-    fn add_batch(&mut self, batch: SignedTxsBatch) {
+    /// Checks whether the transaction from the reserved nonce range arrived before
+    /// the preceding transactions of the account, so it can't be executed yet.
+    fn awaits_reserved_nonces(&self, tx: &SignedZkSyncTx, now: DateTime<Utc>) -> bool {
+        let address = tx.account();
+        tx.nonce() > self.expected_nonce(&address)
+            && self.is_nonce_reserved(&address, tx.nonce(), now)
+    }
+
+    fn record_proposed_tx(&mut self, tx: &SignedZkSyncTx) {
+        let address = tx.account();
+        if self.nonce_reservations.contains_key(&address) {
+            let next_nonce = tx.nonce() + 1;
+            let nonce = self.proposed_nonces.entry(address).or_insert(next_nonce);
+            *nonce = max(*nonce, next_nonce);
+        }
+    }
+
+    fn record_proposed(&mut self, tx: &SignedTxVariant) {
+        match tx {
+            SignedTxVariant::Tx(tx) => self.record_proposed_tx(tx),
+            SignedTxVariant::Batch(batch) => {
+                for tx in &batch.txs {
+                    self.record_proposed_tx(tx);
+                }
+            }
+        }
+    }
+
+    /// Removes the expired nonce reservations and the proposed nonces which were committed.
+    fn prune_nonce_reservations(&mut self, now: DateTime<Utc>) {
+        self.nonce_reservations.retain(|_, reservations| {
+            reservations.retain(|reservation| reservation.is_active(now));
+            !reservations.is_empty()
+        });
+
+        let (account_nonces, nonce_reservations) = (&self.account_nonces, &self.nonce_reservations);
+        self.proposed_nonces.retain(|address, nonce| {
+            nonce_reservations.contains_key(address)
+                && *nonce > *account_nonces.get(address).unwrap_or(&Nonce(0))
+        });
+    }
+
+    /// Removes the release times of the accounts which delayed transactions were released.
+    fn prune_delays(&mut self, now: DateTime<Utc>) {
+        self.delayed_accounts
+            .retain(|_, release_at| *release_at > now);
+    }
+
+    /// Adds the transaction to the queue, holding it until the release time if it's delayed
+    /// by the screening. Later transactions of the accounts having the delayed transactions
+    /// are held as well, so they're not executed out of order.
+    fn add_tx_variant(&mut self, tx: SignedTxVariant, release_at: Option<DateTime<Utc>>) {
+        let accounts: Vec<_> = tx
+            .get_transactions()
+            .iter()
+            .map(|tx| tx.account())
+            .collect();
+        let release_at = accounts
+            .iter()
+            .filter_map(|account| self.delayed_accounts.get(account))
+            .copied()
+            .chain(release_at)
+            .max();
+
+        match release_at {
+            Some(release_at) => {
+                for account in accounts {
+                    let account_release_at =
+                        self.delayed_accounts.entry(account).or_insert(release_at);
+                    *account_release_at = max(*account_release_at, release_at);
+                }
+                self.release_times
+                    .extend(tx.hashes().into_iter().map(|hash| (hash, release_at)));
+                self.transactions_queue
+                    .add_tx_variant_not_before(tx, release_at.timestamp() as u64);
+            }
+            None => self.transactions_queue.add_tx_variant(tx),
+        }
+        self.report_size();
+    }
+
+    fn add_tx(&mut self, tx: SignedZkSyncTx, release_at: Option<DateTime<Utc>>) {
+        self.add_tx_variant(tx.into(), release_at);
+    }
+
+    fn add_batch(&mut self, batch: SignedTxsBatch, release_at: Option<DateTime<Utc>>) {
         assert_ne!(batch.batch_id, 0, "Batch ID was not set");
 
-        self.transactions_queue
-            .add_tx_variant(SignedTxVariant::Batch(batch));
+        self.add_tx_variant(SignedTxVariant::Batch(batch), release_at);
+    }
+
+    fn report_size(&self) {
+        metrics::gauge!("mempool.size", self.transactions_queue.len() as f64);
     }
 }
 
 struct MempoolBlocksHandler {
+    db_pool: ConnectionPool,
     mempool_state: Arc<RwLock<MempoolState>>,
+    deposit_screening: Option<DepositScreeningQueue>,
     requests: mpsc::Receiver<MempoolBlocksRequest>,
     eth_watch_req: mpsc::Sender<EthWatchRequest>,
     max_block_size_chunks: usize,
+    account_tx_limit: Option<AccountTxLimit>,
 }
 
 impl MempoolBlocksHandler {
     async fn propose_new_block(
         &mut self,
         current_unprocessed_priority_op: u64,
+        current_undelivered_l1_message: u64,
+        block_number: BlockNumber,
         block_timestamp: u64,
     ) -> ProposedBlock {
         let start = std::time::Instant::now();
         let (chunks_left, priority_ops) = self
             .select_priority_ops(current_unprocessed_priority_op)
             .await;
+        if let Some(deposit_screening) = &mut self.deposit_screening {
+            deposit_screening.push(&priority_ops);
+        }
+        let held_back_recoveries = self.recheck_guardian_recoveries().await;
         let (_chunks_left, txs) = self
-            .prepare_tx_for_block(chunks_left, block_timestamp)
+            .prepare_tx_for_block(
+                chunks_left,
+                block_number,
+                block_timestamp,
+                &held_back_recoveries,
+            )
             .await;
 
         if !priority_ops.is_empty() {
@@ -272,8 +524,31 @@ impl MempoolBlocksHandler {
         if !txs.is_empty() {
             vlog::debug!("Proposed txs for block: {:?}", txs);
         }
+        let l1_messages = self
+            .select_l1_messages(current_undelivered_l1_message)
+            .await;
         metrics::histogram!("mempool.propose_new_block", start.elapsed());
-        ProposedBlock { priority_ops, txs }
+        ProposedBlock {
+            priority_ops,
+            txs,
+            l1_messages,
+        }
+    }
+
+    /// Returns the messages sent from L1 which are not delivered yet.
+    async fn select_l1_messages(&self, current_undelivered_l1_message: u64) -> Vec<L1Message> {
+        let eth_watch_resp = oneshot::channel();
+        self.eth_watch_req
+            .clone()
+            .send(EthWatchRequest::GetL1Messages {
+                first_serial_id: current_undelivered_l1_message,
+                max_messages: MAX_L1_MESSAGES_PER_MINIBLOCK,
+                resp: eth_watch_resp.0,
+            })
+            .await
+            .expect("ETH watch req receiver dropped");
+
+        eth_watch_resp.1.await.expect("Err response from eth watch")
     }
 
     /// Returns: chunks left from max amount of chunks, ops selected
@@ -292,7 +567,25 @@ impl MempoolBlocksHandler {
             .await
             .expect("ETH watch req receiver dropped");
 
-        let priority_ops = eth_watch_resp.1.await.expect("Err response from eth watch");
+        let mut priority_ops = eth_watch_resp.1.await.expect("Err response from eth watch");
+        // Only the operations following the processed ones can be proposed, the rest
+        // would be rejected by the state keeper anyway.
+        if let Err(err) = check_serial_id_continuity(
+            current_unprocessed_priority_op,
+            priority_ops.iter().map(|op| op.serial_id),
+        ) {
+            vlog::error!(
+                "Priority operations received from eth_watch are not continuous: {}",
+                err
+            );
+            metrics::counter!("mempool.priority_op_continuity_violations", 1);
+            let continuous = priority_ops
+                .iter()
+                .zip(current_unprocessed_priority_op..)
+                .take_while(|(op, serial_id)| op.serial_id == *serial_id)
+                .count();
+            priority_ops.truncate(continuous);
+        }
 
         (
             self.max_block_size_chunks
@@ -304,22 +597,127 @@ impl MempoolBlocksHandler {
         )
     }
 
+    /// Checks the guardian recoveries in the mempool again before they're proposed, since
+    /// the account may have registered another guardian set after the recovery was accepted,
+    /// and the contract doesn't check it. The revoked recoveries are removed from the mempool.
+    /// Returns the hashes of the recoveries that can't be proposed yet, e.g. on the storage errors.
+    async fn recheck_guardian_recoveries(&self) -> HashSet<TxHash> {
+        let recoveries: Vec<_> = self
+            .mempool_state
+            .read()
+            .await
+            .transactions_queue
+            .iter()
+            .filter(|tx| contains_guardian_recovery(tx))
+            .cloned()
+            .collect();
+        if recoveries.is_empty() {
+            return HashSet::new();
+        }
+
+        let mut storage = match self.db_pool.access_storage().await {
+            Ok(storage) => storage,
+            Err(err) => {
+                vlog::warn!("Failed to check the guardian recoveries: {}", err);
+                return recoveries
+                    .iter()
+                    .flat_map(SignedTxVariant::hashes)
+                    .collect();
+            }
+        };
+        let mut held_back = HashSet::new();
+        let mut revoked = Vec::new();
+        for tx in &recoveries {
+            match check_guardian_recoveries(&mut storage, &tx.get_transactions()).await {
+                Ok(()) => {}
+                Err(TxAddError::DbError) | Err(TxAddError::GuardianRecoveryTimelocked(_)) => {
+                    held_back.extend(tx.hashes());
+                }
+                Err(err) => {
+                    let reason = format!("Transaction rejected: {}", err);
+                    revoked.extend(tx.hashes().into_iter().map(|hash| (hash, reason.clone())));
+                }
+            }
+        }
+        if revoked.is_empty() {
+            return held_back;
+        }
+
+        let revoked_hashes: HashSet<_> = revoked.iter().map(|(hash, _)| *hash).collect();
+        self.mempool_state
+            .write()
+            .await
+            .transactions_queue
+            .retain(|tx| !tx.hashes().iter().any(|hash| revoked_hashes.contains(hash)));
+        vlog::info!(
+            "{} revoked guardian recoveries are removed from the mempool",
+            revoked.len()
+        );
+        metrics::counter!("mempool.revoked_guardian_recoveries", revoked.len() as u64);
+        if let Err(err) = storage
+            .chain()
+            .mempool_schema()
+            .expire_txs(&revoked, Utc::now())
+            .await
+        {
+            vlog::warn!("Failed to remove the revoked guardian recoveries: {}", err);
+        }
+        held_back
+    }
+
     async fn prepare_tx_for_block(
         &mut self,
         mut chunks_left: usize,
+        block_number: BlockNumber,
         block_timestamp: u64,
+        held_back_txs: &HashSet<TxHash>,
     ) -> (usize, Vec<SignedTxVariant>) {
         let mut mempool_state = self.mempool_state.write().await;
+        let now = Utc::now();
+        mempool_state.prune_nonce_reservations(now);
+        mempool_state.prune_delays(now);
 
         mempool_state
             .transactions_queue
             .prepare_new_ready_transactions(block_timestamp);
 
+        let account_tx_limit = &mut self.account_tx_limit;
+        if let Some(account_tx_limit) = account_tx_limit {
+            account_tx_limit.start_proposal(block_number);
+        }
+        // Transactions held back by the limit of the account transactions in the block,
+        // or by the caller.
+        let mut limited_txs = Vec::new();
+
         let mut txs_for_commit = Vec::new();
+        // Transactions from the reserved nonce ranges which arrived before the preceding ones.
+        let mut deferred_txs = Vec::new();
 
         while let Some(tx) = mempool_state.transactions_queue.pop_front() {
+            let tx = match tx {
+                SignedTxVariant::Tx(tx) if mempool_state.awaits_reserved_nonces(&tx, now) => {
+                    deferred_txs.push(tx);
+                    continue;
+                }
+                tx => tx,
+            };
+            if tx.hashes().iter().any(|hash| held_back_txs.contains(hash)) {
+                limited_txs.push(tx);
+                continue;
+            }
+            if let Some(account_tx_limit) = account_tx_limit {
+                if account_tx_limit.holds_back(&tx) {
+                    limited_txs.push(tx);
+                    continue;
+                }
+            }
+
             let chunks_for_tx = mempool_state.required_chunks(&tx);
             if chunks_left >= chunks_for_tx {
+                mempool_state.record_proposed(&tx);
+                if let Some(account_tx_limit) = account_tx_limit {
+                    account_tx_limit.record(&tx);
+                }
                 txs_for_commit.push(tx);
                 chunks_left -= chunks_for_tx;
             } else {
@@ -329,6 +727,46 @@ impl MempoolBlocksHandler {
             }
         }
 
+        // Gaps before the deferred transactions may have been filled by the selected ones.
+        deferred_txs.sort_by_key(|tx| tx.nonce());
+        let mut still_deferred_txs = Vec::new();
+        for tx in deferred_txs {
+            let chunks_for_tx = mempool_state.chunks_for_tx(&tx.tx);
+            let ready = !mempool_state.awaits_reserved_nonces(&tx, now)
+                && !held_back_txs.contains(&tx.hash())
+                && chunks_left >= chunks_for_tx;
+            let tx = SignedTxVariant::from(tx);
+            let held_back = ready
+                && account_tx_limit
+                    .as_mut()
+                    .map_or(false, |account_tx_limit| account_tx_limit.holds_back(&tx));
+            if ready && !held_back {
+                mempool_state.record_proposed(&tx);
+                if let Some(account_tx_limit) = account_tx_limit {
+                    account_tx_limit.record(&tx);
+                }
+                txs_for_commit.push(tx);
+                chunks_left -= chunks_for_tx;
+            } else {
+                still_deferred_txs.push(tx);
+            }
+        }
+        metrics::gauge!("mempool.deferred_txs", still_deferred_txs.len() as f64);
+        metrics::gauge!("mempool.account_limited_txs", limited_txs.len() as f64);
+        // Held transactions are returned to the front of the queue in their order.
+        for tx in still_deferred_txs
+            .into_iter()
+            .rev()
+            .chain(limited_txs.into_iter().rev())
+        {
+            mempool_state.transactions_queue.push_front(tx);
+        }
+        mempool_state.report_size();
+
+        // Transactions may arrive out of the nonce order, so the later ones are moved after
+        // the ones they depend on.
+        let txs_for_commit = order_by_dependencies(txs_for_commit);
+
         (chunks_left, txs_for_commit)
     }
 
@@ -339,7 +777,12 @@ impl MempoolBlocksHandler {
                 MempoolBlocksRequest::GetBlock(block) => {
                     // Generate proposed block.
                     let proposed_block = self
-                        .propose_new_block(block.last_priority_op_number, block.block_timestamp)
+                        .propose_new_block(
+                            block.last_priority_op_number,
+                            block.last_l1_message_number,
+                            block.block_number,
+                            block.block_timestamp,
+                        )
                         .await;
 
                     // Send the proposed block to the request initiator.
@@ -413,6 +856,10 @@ impl MempoolBlocksHandler {
 struct MempoolTransactionsHandler {
     db_pool: ConnectionPool,
     mempool_state: Arc<RwLock<MempoolState>>,
+    screener: Option<Arc<AddressScreener>>,
+    change_pubkey_limit: Option<ChangePubKeyLimit>,
+    amount_bounds: Arc<AmountBounds>,
+    backpressure: Backpressure,
     requests: mpsc::Receiver<MempoolTransactionRequest>,
     max_block_size_chunks: usize,
 }
@@ -420,6 +867,10 @@ struct MempoolTransactionsHandler {
 struct MempoolTransactionsHandlerBuilder {
     db_pool: ConnectionPool,
     mempool_state: Arc<RwLock<MempoolState>>,
+    screener: Option<Arc<AddressScreener>>,
+    change_pubkey_limit: Option<ChangePubKeyLimit>,
+    amount_bounds: Arc<AmountBounds>,
+    backpressure: Backpressure,
     max_block_size_chunks: usize,
 }
 
@@ -433,6 +884,10 @@ impl BuildBalancedItem<MempoolTransactionRequest, MempoolTransactionsHandler>
         MempoolTransactionsHandler {
             db_pool: self.db_pool.clone(),
             mempool_state: self.mempool_state.clone(),
+            screener: self.screener.clone(),
+            change_pubkey_limit: self.change_pubkey_limit.clone(),
+            amount_bounds: self.amount_bounds.clone(),
+            backpressure: self.backpressure.clone(),
             requests: receiver,
             max_block_size_chunks: self.max_block_size_chunks,
         }
@@ -440,17 +895,137 @@ impl BuildBalancedItem<MempoolTransactionRequest, MempoolTransactionsHandler>
 }
 
 impl MempoolTransactionsHandler {
-    async fn add_tx(&mut self, tx: SignedZkSyncTx) -> Result<(), TxAddError> {
+    /// Screens the transfer recipients. The screening API may be slow, so it's done before
+    /// the storage connection is taken.
+    async fn screen_txs(&self, txs: &[SignedZkSyncTx]) -> Option<ScreeningRecord> {
+        match &self.screener {
+            Some(screener) => screener.screen_txs(txs, Utc::now()).await,
+            None => None,
+        }
+    }
+
+    /// Records the screened transaction to the audit trail and applies the screening action.
+    /// Returns the time the delayed transactions are released at. On the dry run the screened
+    /// transactions are not recorded.
+    async fn apply_screening(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        record: Option<ScreeningRecord>,
+        dry_run: bool,
+    ) -> Result<Option<DateTime<Utc>>, TxAddError> {
+        let record = match record {
+            Some(record) => record,
+            None => return Ok(None),
+        };
+        if dry_run {
+            return match record.action {
+                ScreeningAction::Reject => Err(TxAddError::RecipientScreened),
+                ScreeningAction::Flag | ScreeningAction::Delay => Ok(record.release_at),
+            };
+        }
+        vlog::info!(
+            "Transfer {} to the screened address {:#x} ({}), action: {}",
+            record
+                .tx_hash
+                .map(|tx_hash| tx_hash.to_string())
+                .unwrap_or_default(),
+            record.address,
+            record.reason,
+            record.action
+        );
+        metrics::counter!("mempool.screened_txs", 1, "action" => record.action.as_str());
+        storage
+            .screening_schema()
+            .record_screening(&record)
+            .await
+            .map_err(|err| {
+                vlog::warn!("Mempool storage access error: {}", err);
+                TxAddError::DbError
+            })?;
+
+        match record.action {
+            ScreeningAction::Reject => Err(TxAddError::RecipientScreened),
+            ScreeningAction::Flag | ScreeningAction::Delay => Ok(record.release_at),
+        }
+    }
+
+    /// Rejects the `ChangePubKey` transactions of the accounts exceeding the rate limit.
+    async fn check_change_pubkey_limit(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        txs: &[SignedZkSyncTx],
+    ) -> Result<(), TxAddError> {
+        match &self.change_pubkey_limit {
+            Some(limit) => limit.check(storage, txs).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Rejects the transactions with the amounts or fees exceeding the sanity bounds.
+    fn check_amount_bounds(&self, txs: &[SignedZkSyncTx]) -> Result<(), TxAddError> {
+        for tx in txs {
+            if let Err(err) = self.amount_bounds.check_tx(&tx.tx) {
+                vlog::warn!(
+                    "Transaction {} is out of the amount bounds: {}",
+                    tx.hash().to_string(),
+                    err
+                );
+                metrics::counter!("mempool.amount_bounds_rejected_txs", 1);
+                return Err(match err {
+                    TxAmountBoundsError::AmountOutOfBounds { token, .. } => {
+                        TxAddError::AmountOutOfBounds(token)
+                    }
+                    TxAmountBoundsError::FeeOutOfBounds { token, .. } => {
+                        TxAddError::FeeOutOfBounds(token)
+                    }
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Rejects the new transactions while the blocks processing lags behind.
+    fn check_backpressure(&self) -> Result<(), TxAddError> {
+        match self.backpressure.retry_after() {
+            Some(retry_after) => {
+                metrics::counter!("mempool.backpressure_rejected_requests", 1);
+                Err(TxAddError::Overloaded(retry_after))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Adds the transaction to the mempool. On the dry run the transaction is only checked.
+    async fn add_tx(&mut self, tx: SignedZkSyncTx, dry_run: bool) -> Result<(), TxAddError> {
+        self.check_backpressure()?;
+        self.check_amount_bounds(std::slice::from_ref(&tx))?;
         // Correctness should be checked by `signature_checker`, thus
         // `tx.check_correctness()` is not invoked here.
-        if tx.nonce() < self.mempool_state.read().await.nonce(&tx.account()) {
+        let (committed_nonce, is_nonce_reserved) = {
+            let mempool_state = self.mempool_state.read().await;
+            (
+                mempool_state.nonce(&tx.account()),
+                mempool_state.is_nonce_reserved(&tx.account(), tx.nonce(), Utc::now()),
+            )
+        };
+        if tx.nonce() < committed_nonce {
             return Err(TxAddError::NonceMismatch);
         }
+        let screening = self.screen_txs(std::slice::from_ref(&tx)).await;
 
         let mut storage = self.db_pool.access_storage().await.map_err(|err| {
             vlog::warn!("Mempool storage access error: {}", err);
             TxAddError::DbError
         })?;
+        self.check_change_pubkey_limit(&mut storage, std::slice::from_ref(&tx))
+            .await?;
+        check_guardian_recoveries(&mut storage, std::slice::from_ref(&tx)).await?;
+        let release_at = self
+            .apply_screening(&mut storage, screening, dry_run)
+            .await?;
+        if dry_run {
+            return Ok(());
+        }
 
         storage
             .chain()
@@ -462,15 +1037,42 @@ impl MempoolTransactionsHandler {
                 TxAddError::DbError
             })?;
 
-        self.mempool_state.write().await.add_tx(tx);
+        if tx.nonce() > committed_nonce && !is_nonce_reserved {
+            // The nonce may belong to the range reserved after the reservations were loaded.
+            let reservations = storage
+                .nonce_reservations_schema()
+                .load_active_reservations(tx.account())
+                .await
+                .map_err(|err| {
+                    vlog::warn!("Mempool storage access error: {}", err);
+                    TxAddError::DbError
+                })?;
+            if !reservations.is_empty() {
+                self.mempool_state
+                    .write()
+                    .await
+                    .nonce_reservations
+                    .insert(tx.account(), reservations);
+            }
+        }
+
+        vlog::debug!(
+            tx_hash = %tx.hash().to_string(),
+            "Transaction was added to the mempool"
+        );
+        self.mempool_state.write().await.add_tx(tx, release_at);
         Ok(())
     }
 
+    /// Adds the batch to the mempool. On the dry run the batch is only checked.
     async fn add_batch(
         &mut self,
         txs: Vec<SignedZkSyncTx>,
         eth_signatures: Vec<TxEthSignature>,
+        dry_run: bool,
     ) -> Result<(), TxAddError> {
+        self.check_backpressure()?;
+        self.check_amount_bounds(&txs)?;
         for tx in txs.iter() {
             // Correctness should be checked by `signature_checker`, thus
             // `tx.check_correctness()` is not invoked here.
@@ -488,11 +1090,21 @@ impl MempoolTransactionsHandler {
         if self.mempool_state.read().await.chunks_for_batch(&batch) > self.max_block_size_chunks {
             return Err(TxAddError::BatchTooBig);
         }
+        let screening = self.screen_txs(&batch.txs).await;
 
         let mut storage = self.db_pool.access_storage().await.map_err(|err| {
             vlog::warn!("Mempool storage access error: {}", err);
             TxAddError::DbError
         })?;
+        self.check_change_pubkey_limit(&mut storage, &batch.txs)
+            .await?;
+        check_guardian_recoveries(&mut storage, &batch.txs).await?;
+        let release_at = self
+            .apply_screening(&mut storage, screening, dry_run)
+            .await?;
+        if dry_run {
+            return Ok(());
+        }
 
         let batch_id = storage
             .chain()
@@ -505,8 +1117,21 @@ impl MempoolTransactionsHandler {
             })?;
 
         batch.batch_id = batch_id;
+        let tx_hashes = batch
+            .txs
+            .iter()
+            .map(|tx| tx.hash().to_string())
+            .collect::<Vec<_>>();
+        vlog::debug!(
+            batch_id,
+            tx_hashes = %tx_hashes.join(","),
+            "Batch was added to the mempool"
+        );
 
-        self.mempool_state.write().await.add_batch(batch);
+        self.mempool_state
+            .write()
+            .await
+            .add_batch(batch, release_at);
         Ok(())
     }
 
@@ -514,14 +1139,28 @@ impl MempoolTransactionsHandler {
         vlog::info!("Transaction mempool handler is running");
         while let Some(request) = self.requests.next().await {
             match request {
-                MempoolTransactionRequest::NewTx(tx, resp) => {
-                    let tx_add_result = self.add_tx(*tx).await;
+                MempoolTransactionRequest::NewTx(tx, resp, span) => {
+                    let tx_add_result = self.add_tx(*tx, false).instrument(span).await;
                     resp.send(tx_add_result).unwrap_or_default();
                 }
-                MempoolTransactionRequest::NewTxsBatch(txs, eth_signatures, resp) => {
-                    let tx_add_result = self.add_batch(txs, eth_signatures).await;
+                MempoolTransactionRequest::NewTxsBatch(txs, eth_signatures, resp, span) => {
+                    let tx_add_result = self
+                        .add_batch(txs, eth_signatures, false)
+                        .instrument(span)
+                        .await;
                     resp.send(tx_add_result).unwrap_or_default();
                 }
+                MempoolTransactionRequest::CheckTx(tx, resp, span) => {
+                    let tx_check_result = self.add_tx(*tx, true).instrument(span).await;
+                    resp.send(tx_check_result).unwrap_or_default();
+                }
+                MempoolTransactionRequest::CheckTxsBatch(txs, eth_signatures, resp, span) => {
+                    let tx_check_result = self
+                        .add_batch(txs, eth_signatures, true)
+                        .instrument(span)
+                        .await;
+                    resp.send(tx_check_result).unwrap_or_default();
+                }
             }
         }
     }
@@ -533,13 +1172,24 @@ pub fn run_mempool_tasks(
     tx_requests: mpsc::Receiver<MempoolTransactionRequest>,
     block_requests: mpsc::Receiver<MempoolBlocksRequest>,
     eth_watch_req: mpsc::Sender<EthWatchRequest>,
+    backpressure: Backpressure,
     config: &ZkSyncConfig,
     number_of_mempool_transaction_handlers: u8,
     channel_capacity: usize,
 ) -> JoinHandle<()> {
     let config = config.clone();
     tokio::spawn(async move {
-        let mempool_state = Arc::new(RwLock::new(MempoolState::restore_from_db(&db_pool).await));
+        let mempool_state = Arc::new(RwLock::new(
+            MempoolState::restore_from_db(&db_pool, config.chain.mempool.tx_ttl()).await,
+        ));
+        let screener =
+            AddressScreener::from_config(&config.screening, config.failure_policy.screening)
+                .map(Arc::new);
+        let amount_bounds = config
+            .chain
+            .mempool
+            .amount_bounds()
+            .expect("Invalid CHAIN_MEMPOOL_AMOUNT_BOUNDS");
         let max_block_size_chunks = *config
             .chain
             .state_keeper
@@ -552,6 +1202,10 @@ pub fn run_mempool_tasks(
             MempoolTransactionsHandlerBuilder {
                 db_pool: db_pool.clone(),
                 mempool_state: mempool_state.clone(),
+                screener: screener.clone(),
+                change_pubkey_limit: ChangePubKeyLimit::from_config(&config.chain.mempool),
+                amount_bounds: Arc::new(amount_bounds),
+                backpressure,
                 max_block_size_chunks,
             },
             tx_requests,
@@ -565,11 +1219,37 @@ pub fn run_mempool_tasks(
 
         tasks.push(tokio::spawn(balancer.run()));
 
+        let consistency_checker = MempoolConsistencyChecker::new(
+            db_pool.clone(),
+            mempool_state.clone(),
+            config.chain.mempool.consistency_auto_correct,
+        );
+        tasks.push(tokio::spawn(
+            consistency_checker.run(config.chain.mempool.consistency_check_interval()),
+        ));
+
+        let tx_expiry = MempoolTxExpiry::new(
+            db_pool.clone(),
+            mempool_state.clone(),
+            config.chain.mempool.tx_ttl(),
+        );
+        tasks.push(tokio::spawn(
+            tx_expiry.run(config.chain.mempool.tx_expiry_check_interval()),
+        ));
+
+        let deposit_screening = screener.map(|screener| {
+            let (queue, task) = DepositScreeningQueue::run(screener, db_pool.clone());
+            tasks.push(task);
+            queue
+        });
         let blocks_handler = MempoolBlocksHandler {
+            db_pool,
             mempool_state,
+            deposit_screening,
             requests: block_requests,
             eth_watch_req,
             max_block_size_chunks,
+            account_tx_limit: AccountTxLimit::from_config(&config.chain.mempool),
         };
         tasks.push(tokio::spawn(blocks_handler.run()));
         wait_for_tasks(tasks).await
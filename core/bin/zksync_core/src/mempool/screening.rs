@@ -0,0 +1,516 @@
+//! Screening of the deposits and transfers recipients.
+//!
+//! The recipients are checked by the screening hooks: the local list of the screened addresses
+//! and the external screening API. The external API responses are cached, and after its failures
+//! the API isn't requested for a backoff period, so the unavailable API doesn't slow down every
+//! transfer by the request timeout. The API failures are handled according to the failure policy:
+//! with the closed one they're treated as the positive screening results, so the transfers aren't
+//! let through unchecked, while with the open one the address is considered not screened by
+//! the failed hook.
+//!
+//! The deposits are enforced by the contract and are only flagged, so they're screened in the
+//! background rather than when the block is proposed (see `DepositScreeningQueue`).
+//! See `zksync_types::screening` for the actions taken on the screened transactions.
+
+// Built-in deps
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+// External uses
+use anyhow::format_err;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::{channel::mpsc, StreamExt};
+use serde::Deserialize;
+use tokio::{sync::RwLock, task::JoinHandle};
+// Workspace uses
+use zksync_config::configs::{failure_policy::FailurePolicy, ScreeningConfig};
+use zksync_storage::ConnectionPool;
+use zksync_types::{
+    screening::{ScreeningAction, ScreeningRecord},
+    Address, PriorityOp, SignedZkSyncTx, ZkSyncPriorityOp, ZkSyncTx,
+};
+
+/// Check of the recipient addresses, e.g. against the sanctions list.
+#[async_trait]
+pub trait ScreeningHook: Send + Sync {
+    /// Returns the reason the address is screened for, or `None` if it's not screened.
+    async fn screen(&self, address: Address) -> anyhow::Result<Option<String>>;
+}
+
+/// Local list of the screened addresses.
+#[derive(Debug)]
+pub struct LocalListScreening {
+    addresses: HashSet<Address>,
+}
+
+impl LocalListScreening {
+    pub fn new(addresses: impl IntoIterator<Item = Address>) -> Self {
+        Self {
+            addresses: addresses.into_iter().collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl ScreeningHook for LocalListScreening {
+    async fn screen(&self, address: Address) -> anyhow::Result<Option<String>> {
+        Ok(Some("listed in the local screening list".to_owned())
+            .filter(|_| self.addresses.contains(&address)))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ScreeningApiResponse {
+    screened: bool,
+}
+
+/// Time the screening API isn't requested for after the first failure,
+/// doubled with every consecutive failure.
+const MIN_FAILURE_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_FAILURE_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Consecutive failures of the screening API.
+#[derive(Debug, Default)]
+struct FailureBackoff {
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl FailureBackoff {
+    fn check(&self, now: Instant) -> anyhow::Result<()> {
+        match self.retry_at {
+            Some(retry_at) if now < retry_at => Err(format_err!(
+                "Screening API is not requested after {} consecutive failures",
+                self.failures
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn on_failure(&mut self, now: Instant) {
+        self.failures = self.failures.saturating_add(1);
+        let backoff = MIN_FAILURE_BACKOFF
+            .checked_mul(2u32.saturating_pow(self.failures - 1))
+            .map_or(MAX_FAILURE_BACKOFF, |backoff| {
+                backoff.min(MAX_FAILURE_BACKOFF)
+            });
+        self.retry_at = Some(now + backoff);
+    }
+
+    fn on_success(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// External screening API requested as `GET {url}/{address}`.
+#[derive(Debug)]
+pub struct ExternalApiScreening {
+    client: reqwest::Client,
+    url: String,
+    cache_ttl: Duration,
+    cache: RwLock<HashMap<Address, (bool, Instant)>>,
+    backoff: Mutex<FailureBackoff>,
+}
+
+impl ExternalApiScreening {
+    pub fn new(url: &str, timeout: Duration, cache_ttl: Duration) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(timeout)
+                .build()
+                .expect("Failed to build the screening API client"),
+            url: url.trim_end_matches('/').to_owned(),
+            cache_ttl,
+            cache: RwLock::new(HashMap::new()),
+            backoff: Mutex::default(),
+        }
+    }
+
+    async fn request(&self, address: Address) -> anyhow::Result<bool> {
+        let response = self
+            .client
+            .get(&format!("{}/{:#x}", self.url, address))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(format_err!(
+                "Screening API responded with {}",
+                response.status()
+            ));
+        }
+
+        Ok(response.json::<ScreeningApiResponse>().await?.screened)
+    }
+}
+
+#[async_trait]
+impl ScreeningHook for ExternalApiScreening {
+    async fn screen(&self, address: Address) -> anyhow::Result<Option<String>> {
+        let cached = self.cache.read().await.get(&address).cloned();
+        let screened = match cached {
+            Some((screened, cached_at)) if cached_at.elapsed() < self.cache_ttl => screened,
+            _ => {
+                self.backoff.lock().unwrap().check(Instant::now())?;
+                let screened = match self.request(address).await {
+                    Ok(screened) => {
+                        self.backoff.lock().unwrap().on_success();
+                        screened
+                    }
+                    Err(err) => {
+                        self.backoff.lock().unwrap().on_failure(Instant::now());
+                        return Err(err);
+                    }
+                };
+                let mut cache = self.cache.write().await;
+                let cache_ttl = self.cache_ttl;
+                cache.retain(|_, (_, cached_at)| cached_at.elapsed() < cache_ttl);
+                cache.insert(address, (screened, Instant::now()));
+                screened
+            }
+        };
+
+        Ok(Some("flagged by the screening API".to_owned()).filter(|_| screened))
+    }
+}
+
+/// Capacity of the queue of the deposits waiting for the screening.
+const DEPOSIT_SCREENING_QUEUE_SIZE: usize = 128;
+
+/// Queue of the deposits screened in the background, so the block proposal doesn't wait
+/// for the screening API.
+#[derive(Debug, Clone)]
+pub struct DepositScreeningQueue {
+    sender: mpsc::Sender<Vec<PriorityOp>>,
+}
+
+impl DepositScreeningQueue {
+    /// Runs the task screening the queued deposits and recording the screened ones
+    /// to the audit trail.
+    pub fn run(screener: Arc<AddressScreener>, db_pool: ConnectionPool) -> (Self, JoinHandle<()>) {
+        let (sender, receiver) = mpsc::channel(DEPOSIT_SCREENING_QUEUE_SIZE);
+        let task = tokio::spawn(screen_deposits_task(screener, db_pool, receiver));
+        (Self { sender }, task)
+    }
+
+    /// Queues the deposits among the proposed priority operations for the screening.
+    /// If the queue is full, i.e. the screening falls behind, the deposits are not screened.
+    pub fn push(&mut self, priority_ops: &[PriorityOp]) {
+        let deposits: Vec<_> = priority_ops
+            .iter()
+            .filter(|op| matches!(op.data, ZkSyncPriorityOp::Deposit(_)))
+            .cloned()
+            .collect();
+        if deposits.is_empty() {
+            return;
+        }
+
+        let deposits_count = deposits.len();
+        if let Err(err) = self.sender.try_send(deposits) {
+            vlog::warn!("Failed to queue the deposits for the screening: {}", err);
+            metrics::counter!("mempool.screening.skipped_deposits", deposits_count as u64);
+        }
+    }
+}
+
+async fn screen_deposits_task(
+    screener: Arc<AddressScreener>,
+    db_pool: ConnectionPool,
+    mut receiver: mpsc::Receiver<Vec<PriorityOp>>,
+) {
+    // The operations are proposed again if the block they were proposed for isn't executed,
+    // so the ones already screened are skipped.
+    let mut next_serial_id = 0;
+    while let Some(deposits) = receiver.next().await {
+        let deposits: Vec<_> = deposits
+            .into_iter()
+            .filter(|op| op.serial_id >= next_serial_id)
+            .collect();
+        next_serial_id = match deposits.last() {
+            Some(op) => op.serial_id + 1,
+            None => continue,
+        };
+
+        let records = screener.screen_deposits(&deposits).await;
+        if records.is_empty() {
+            continue;
+        }
+        metrics::counter!("mempool.screened_deposits", records.len() as u64);
+        for record in &records {
+            vlog::info!(
+                "Deposit #{} to the screened address {:#x} was flagged: {}",
+                record.priority_op_serial_id.unwrap_or_default(),
+                record.address,
+                record.reason
+            );
+        }
+
+        let result = async {
+            let mut storage = db_pool.access_storage().await?;
+            for record in &records {
+                storage.screening_schema().record_screening(record).await?;
+            }
+            Ok::<_, anyhow::Error>(())
+        }
+        .await;
+        if let Err(err) = result {
+            vlog::warn!("Failed to record the screened deposits: {}", err);
+        }
+    }
+}
+
+/// Screened recipient of the transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScreeningHit {
+    pub address: Address,
+    pub reason: String,
+}
+
+/// Screens the recipients with the configured hooks.
+pub struct AddressScreener {
+    hooks: Vec<Box<dyn ScreeningHook>>,
+    action: ScreeningAction,
+    delay: chrono::Duration,
+    failure_policy: FailurePolicy,
+}
+
+impl std::fmt::Debug for AddressScreener {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AddressScreener")
+            .field("hooks", &self.hooks.len())
+            .field("action", &self.action)
+            .field("delay", &self.delay)
+            .field("failure_policy", &self.failure_policy)
+            .finish()
+    }
+}
+
+impl AddressScreener {
+    pub fn new(
+        hooks: Vec<Box<dyn ScreeningHook>>,
+        action: ScreeningAction,
+        delay: chrono::Duration,
+        failure_policy: FailurePolicy,
+    ) -> Self {
+        Self {
+            hooks,
+            action,
+            delay,
+            failure_policy,
+        }
+    }
+
+    /// Creates the screener if the screening is enabled.
+    pub fn from_config(config: &ScreeningConfig, failure_policy: FailurePolicy) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+
+        let mut hooks: Vec<Box<dyn ScreeningHook>> = vec![Box::new(LocalListScreening::new(
+            config.screened_addresses.iter().copied(),
+        ))];
+        if let Some(url) = config.api_url() {
+            hooks.push(Box::new(ExternalApiScreening::new(
+                url,
+                config.api_timeout(),
+                config.api_cache_ttl(),
+            )));
+        }
+        Some(Self::new(
+            hooks,
+            config.action,
+            config.delay(),
+            failure_policy,
+        ))
+    }
+
+    pub fn action(&self) -> ScreeningAction {
+        self.action
+    }
+
+    async fn screen_address(&self, address: Address) -> Option<ScreeningHit> {
+        for hook in &self.hooks {
+            let reason = match hook.screen(address).await {
+                Ok(Some(reason)) => reason,
+                Ok(None) => continue,
+                Err(err) => {
+                    vlog::warn!("Failed to screen the address {:#x}: {}", address, err);
+                    metrics::counter!("mempool.screening.errors", 1);
+                    if self.failure_policy.is_open() {
+                        metrics::counter!("mempool.screening.failed_open", 1);
+                        continue;
+                    }
+                    format!("screening is unavailable: {}", err)
+                }
+            };
+            return Some(ScreeningHit { address, reason });
+        }
+        None
+    }
+
+    /// Returns the audit record of the first transfer to the screened recipient.
+    /// The delayed transactions are released after the configured delay.
+    pub async fn screen_txs(
+        &self,
+        txs: &[SignedZkSyncTx],
+        now: DateTime<Utc>,
+    ) -> Option<ScreeningRecord> {
+        for tx in txs {
+            if let ZkSyncTx::Transfer(transfer) = &tx.tx {
+                if let Some(hit) = self.screen_address(transfer.to).await {
+                    return Some(self.tx_record(tx, hit, now));
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns the audit records of the deposits to the screened addresses.
+    pub async fn screen_deposits(&self, priority_ops: &[PriorityOp]) -> Vec<ScreeningRecord> {
+        let mut records = Vec::new();
+        for op in priority_ops {
+            if let ZkSyncPriorityOp::Deposit(deposit) = &op.data {
+                if let Some(hit) = self.screen_address(deposit.to).await {
+                    records.push(ScreeningRecord {
+                        address: hit.address,
+                        tx_hash: None,
+                        priority_op_serial_id: Some(op.serial_id),
+                        action: ScreeningAction::Flag,
+                        reason: hit.reason,
+                        release_at: None,
+                    });
+                }
+            }
+        }
+        records
+    }
+
+    fn tx_record(
+        &self,
+        tx: &SignedZkSyncTx,
+        hit: ScreeningHit,
+        now: DateTime<Utc>,
+    ) -> ScreeningRecord {
+        ScreeningRecord {
+            address: hit.address,
+            tx_hash: Some(tx.hash()),
+            priority_op_serial_id: None,
+            action: self.action,
+            reason: hit.reason,
+            release_at: Some(now + self.delay).filter(|_| self.action == ScreeningAction::Delay),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::{tx::Transfer, AccountId, Nonce, TokenId};
+
+    struct FailingScreening;
+
+    #[async_trait]
+    impl ScreeningHook for FailingScreening {
+        async fn screen(&self, _address: Address) -> anyhow::Result<Option<String>> {
+            Err(format_err!("timeout"))
+        }
+    }
+
+    fn transfer(to: Address) -> SignedZkSyncTx {
+        let transfer = Transfer::new(
+            AccountId(1),
+            Address::repeat_byte(1),
+            to,
+            TokenId(0),
+            100u32.into(),
+            10u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        );
+        SignedZkSyncTx {
+            tx: ZkSyncTx::Transfer(Box::new(transfer)),
+            eth_sign_data: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn transfers_to_screened_addresses_are_found() {
+        let screened = Address::repeat_byte(0xaa);
+        let screener = AddressScreener::new(
+            vec![Box::new(LocalListScreening::new(vec![screened]))],
+            ScreeningAction::Delay,
+            chrono::Duration::hours(1),
+            FailurePolicy::Closed,
+        );
+
+        let txs = vec![transfer(Address::repeat_byte(2)), transfer(screened)];
+        let now = Utc::now();
+        let record = screener.screen_txs(&txs, now).await.unwrap();
+        assert_eq!(record.address, screened);
+        assert_eq!(record.tx_hash, Some(txs[1].hash()));
+        assert_eq!(record.release_at, Some(now + chrono::Duration::hours(1)));
+
+        assert!(screener.screen_txs(&txs[..1], now).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn screening_failures_are_treated_as_hits() {
+        let screener = AddressScreener::new(
+            vec![Box::new(FailingScreening)],
+            ScreeningAction::Reject,
+            chrono::Duration::hours(1),
+            FailurePolicy::Closed,
+        );
+
+        let txs = vec![transfer(Address::repeat_byte(2))];
+        let record = screener.screen_txs(&txs, Utc::now()).await.unwrap();
+        assert!(record.reason.contains("timeout"));
+        assert_eq!(record.release_at, None);
+    }
+
+    #[tokio::test]
+    async fn screening_failures_are_let_through_when_failing_open() {
+        let screened = Address::repeat_byte(0xaa);
+        let screener = AddressScreener::new(
+            vec![
+                Box::new(FailingScreening),
+                Box::new(LocalListScreening::new(vec![screened])),
+            ],
+            ScreeningAction::Reject,
+            chrono::Duration::hours(1),
+            FailurePolicy::Open,
+        );
+
+        let txs = vec![transfer(Address::repeat_byte(2))];
+        assert!(screener.screen_txs(&txs, Utc::now()).await.is_none());
+        // The other hooks are still applied.
+        let txs = vec![transfer(screened)];
+        let record = screener.screen_txs(&txs, Utc::now()).await.unwrap();
+        assert_eq!(record.address, screened);
+    }
+
+    #[test]
+    fn failure_backoff() {
+        let now = Instant::now();
+        let mut backoff = FailureBackoff::default();
+        assert!(backoff.check(now).is_ok());
+
+        backoff.on_failure(now);
+        assert!(backoff.check(now).is_err());
+        assert!(backoff.check(now + MIN_FAILURE_BACKOFF).is_ok());
+        // The backoff doubles with every consecutive failure up to the limit.
+        backoff.on_failure(now);
+        assert!(backoff.check(now + MIN_FAILURE_BACKOFF).is_err());
+        assert!(backoff.check(now + MIN_FAILURE_BACKOFF * 2).is_ok());
+        for _ in 0..100 {
+            backoff.on_failure(now);
+        }
+        assert!(backoff.check(now + MAX_FAILURE_BACKOFF).is_ok());
+
+        backoff.on_success();
+        assert!(backoff.check(now).is_ok());
+    }
+}
@@ -0,0 +1,115 @@
+//! Tests of the mempool transactions handler. Require the database, run with
+//! `cargo test -p zksync_core mempool::tests -- --ignored`.
+
+// External uses
+use num::BigUint;
+// Workspace uses
+use zksync_types::{
+    amount_bounds::AmountBounds,
+    tx::{TimeRange, Transfer},
+    Address, Nonce, TokenId, ZkSyncTx,
+};
+// Local uses
+use super::*;
+
+fn sender() -> Address {
+    Address::repeat_byte(0x11)
+}
+
+fn transfer(nonce: u32) -> SignedZkSyncTx {
+    let transfer = Transfer::new(
+        AccountId(0xdead),
+        sender(),
+        Address::repeat_byte(0x22),
+        TokenId(0),
+        BigUint::from(100u32),
+        BigUint::from(1u32),
+        Nonce(nonce),
+        TimeRange::default(),
+        None,
+    );
+    ZkSyncTx::Transfer(Box::new(transfer)).into()
+}
+
+/// Creates the handler with the sender account having the committed nonce 1.
+fn transactions_handler() -> MempoolTransactionsHandler {
+    let mut account_nonces = HashMap::new();
+    account_nonces.insert(sender(), Nonce(1));
+    let mempool_state = MempoolState {
+        account_nonces,
+        account_ids: HashMap::new(),
+        transactions_queue: MempoolTransactionsQueue::new(),
+        nonce_reservations: HashMap::new(),
+        proposed_nonces: HashMap::new(),
+        delayed_accounts: HashMap::new(),
+        release_times: HashMap::new(),
+    };
+    let (_, requests) = mpsc::channel(1);
+
+    MempoolTransactionsHandler {
+        db_pool: ConnectionPool::new(Some(1)),
+        mempool_state: Arc::new(RwLock::new(mempool_state)),
+        screener: None,
+        change_pubkey_limit: None,
+        amount_bounds: Arc::new(AmountBounds::default()),
+        backpressure: Backpressure::new(1),
+        requests,
+        max_block_size_chunks: 100,
+    }
+}
+
+async fn is_stored(handler: &MempoolTransactionsHandler, tx: &SignedZkSyncTx) -> bool {
+    handler
+        .db_pool
+        .access_storage()
+        .await
+        .unwrap()
+        .chain()
+        .mempool_schema()
+        .contains_tx(tx.hash())
+        .await
+        .unwrap()
+}
+
+/// Checks that the transaction is checked but not added to the mempool on the dry run.
+#[tokio::test]
+#[ignore]
+async fn dry_run_tx() {
+    let mut handler = transactions_handler();
+
+    let result = handler.add_tx(transfer(0), true).await;
+    assert!(matches!(result, Err(TxAddError::NonceMismatch)));
+
+    let tx = transfer(1);
+    handler.add_tx(tx.clone(), true).await.unwrap();
+    assert_eq!(
+        handler.mempool_state.read().await.transactions_queue.len(),
+        0
+    );
+    assert!(!is_stored(&handler, &tx).await);
+}
+
+/// Checks that the batch is checked but not added to the mempool on the dry run.
+#[tokio::test]
+#[ignore]
+async fn dry_run_batch() {
+    let mut handler = transactions_handler();
+
+    let result = handler
+        .add_batch(vec![transfer(0), transfer(1)], Vec::new(), true)
+        .await;
+    assert!(matches!(result, Err(TxAddError::NonceMismatch)));
+
+    let txs = vec![transfer(1), transfer(2)];
+    handler
+        .add_batch(txs.clone(), Vec::new(), true)
+        .await
+        .unwrap();
+    assert_eq!(
+        handler.mempool_state.read().await.transactions_queue.len(),
+        0
+    );
+    for tx in &txs {
+        assert!(!is_stored(&handler, tx).await);
+    }
+}
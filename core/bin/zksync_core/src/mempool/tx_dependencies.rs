@@ -0,0 +1,271 @@
+//! Dependencies between the queued transactions.
+//!
+//! Transactions of the same account must be executed in the nonce order, and the transactions
+//! of a batch are executed all together. A transaction executed before the preceding transaction
+//! of its account fails with the nonce mismatch, and so do the later transactions of the account
+//! if the preceding one is rejected, each in the block it happened to be proposed for.
+//!
+//! To keep such rejections coherent, the block proposer orders the proposed transactions by their
+//! dependencies, and the state keeper defers the transactions following a nonce hole instead of
+//! executing them: they're executed once the hole is filled, or rejected all together otherwise.
+
+// Built-in deps
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+// Workspace uses
+use zksync_types::{mempool::SignedTxVariant, AccountId, Nonce, SignedZkSyncTx};
+
+/// Transaction which can't be executed until the preceding transaction of its account is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonceHole {
+    pub account_id: AccountId,
+    /// Nonce of the missing transaction.
+    pub expected: Nonce,
+    /// Nonce of the transaction following the hole.
+    pub nonce: Nonce,
+}
+
+fn transactions(element: &SignedTxVariant) -> &[SignedZkSyncTx] {
+    match element {
+        SignedTxVariant::Tx(tx) => std::slice::from_ref(tx),
+        SignedTxVariant::Batch(batch) => &batch.txs,
+    }
+}
+
+/// Returns the accounts sending the transactions of the element.
+pub fn senders(element: &SignedTxVariant) -> HashSet<AccountId> {
+    transactions(element)
+        .iter()
+        .filter_map(|tx| tx.account_id().ok())
+        .collect()
+}
+
+/// Returns the lowest nonce of every account sending the transactions of the element.
+fn lowest_nonces(element: &SignedTxVariant) -> HashMap<AccountId, Nonce> {
+    let mut nonces = HashMap::new();
+    for tx in transactions(element) {
+        // `Close` transactions are disabled, so they don't depend on anything.
+        if let Ok(account_id) = tx.account_id() {
+            let nonce = nonces.entry(account_id).or_insert_with(|| tx.nonce());
+            *nonce = std::cmp::min(*nonce, tx.nonce());
+        }
+    }
+    nonces
+}
+
+/// Orders the elements so that every element goes after the elements containing the transactions
+/// of the same accounts with the lesser nonces. The relative order of the independent elements
+/// is preserved. Elements forming a cycle (possible only with the batches) are kept in the original
+/// order, since they fail anyway.
+pub fn order_by_dependencies(elements: Vec<SignedTxVariant>) -> Vec<SignedTxVariant> {
+    // Chain the elements of every account in the nonce order.
+    let mut account_chains: HashMap<AccountId, Vec<(Nonce, usize)>> = HashMap::new();
+    for (idx, element) in elements.iter().enumerate() {
+        for (account_id, nonce) in lowest_nonces(element) {
+            account_chains
+                .entry(account_id)
+                .or_default()
+                .push((nonce, idx));
+        }
+    }
+
+    let mut dependents = vec![Vec::new(); elements.len()];
+    let mut dependencies_count = vec![0_usize; elements.len()];
+    for chain in account_chains.values_mut() {
+        chain.sort_unstable();
+        for pair in chain.windows(2) {
+            let (dependency, dependent) = (pair[0].1, pair[1].1);
+            if dependency != dependent {
+                dependents[dependency].push(dependent);
+                dependencies_count[dependent] += 1;
+            }
+        }
+    }
+    if dependencies_count.iter().all(|&count| count == 0) {
+        return elements;
+    }
+
+    // Topological sort, taking the earliest element among the ready ones.
+    let mut ready: BinaryHeap<_> = dependencies_count
+        .iter()
+        .enumerate()
+        .filter(|(_, &count)| count == 0)
+        .map(|(idx, _)| Reverse(idx))
+        .collect();
+    let mut order = Vec::with_capacity(elements.len());
+    while let Some(Reverse(idx)) = ready.pop() {
+        order.push(idx);
+        for &dependent in &dependents[idx] {
+            dependencies_count[dependent] -= 1;
+            if dependencies_count[dependent] == 0 {
+                ready.push(Reverse(dependent));
+            }
+        }
+    }
+    let mut ordered = vec![false; elements.len()];
+    for &idx in &order {
+        ordered[idx] = true;
+    }
+    order.extend((0..elements.len()).filter(|&idx| !ordered[idx]));
+
+    let mut elements: Vec<_> = elements.into_iter().map(Some).collect();
+    order
+        .into_iter()
+        .map(|idx| elements[idx].take().expect("Element is taken twice"))
+        .collect()
+}
+
+/// Finds the transaction of the element which can't be executed because the preceding transaction
+/// of its account is not executed yet. `next_nonce` returns the nonce of the account in the state,
+/// if the account exists.
+pub fn find_nonce_hole(
+    element: &SignedTxVariant,
+    mut next_nonce: impl FnMut(AccountId) -> Option<Nonce>,
+) -> Option<NonceHole> {
+    let mut expected_nonces = HashMap::new();
+    for tx in transactions(element) {
+        let account_id = match tx.account_id() {
+            Ok(account_id) => account_id,
+            Err(_) => continue,
+        };
+        let expected = match expected_nonces.get(&account_id) {
+            Some(expected) => *expected,
+            None => match next_nonce(account_id) {
+                Some(nonce) => nonce,
+                // Transaction of the nonexistent account fails regardless of its nonce.
+                None => continue,
+            },
+        };
+        if tx.nonce() > expected {
+            return Some(NonceHole {
+                account_id,
+                expected,
+                nonce: tx.nonce(),
+            });
+        }
+        // A transaction with the used nonce fails, but doesn't create a hole.
+        expected_nonces.insert(account_id, std::cmp::max(expected, tx.nonce() + 1));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigUint;
+    use zksync_types::{mempool::SignedTxsBatch, Address, TokenId, Transfer, ZkSyncTx};
+
+    use super::*;
+
+    fn transfer(account_id: u32, nonce: u32) -> SignedZkSyncTx {
+        let transfer = Transfer::new(
+            AccountId(account_id),
+            Address::repeat_byte(account_id as u8),
+            Address::repeat_byte(0xff),
+            TokenId(0),
+            BigUint::from(1_u32),
+            BigUint::from(1_u32),
+            Nonce(nonce),
+            Default::default(),
+            None,
+        );
+        SignedZkSyncTx {
+            tx: ZkSyncTx::Transfer(Box::new(transfer)),
+            eth_sign_data: None,
+        }
+    }
+
+    fn batch(txs: Vec<SignedZkSyncTx>) -> SignedTxVariant {
+        SignedTxVariant::Batch(SignedTxsBatch {
+            txs,
+            batch_id: 1,
+            eth_signatures: Vec::new(),
+        })
+    }
+
+    fn nonces(elements: &[SignedTxVariant]) -> Vec<Vec<(u32, u32)>> {
+        elements
+            .iter()
+            .map(|element| {
+                transactions(element)
+                    .iter()
+                    .map(|tx| (*tx.account_id().unwrap(), *tx.nonce()))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ordering() {
+        // Independent elements keep their order.
+        let elements = vec![transfer(1, 0).into(), transfer(2, 5).into()];
+        assert_eq!(
+            nonces(&order_by_dependencies(elements)),
+            vec![vec![(1, 0)], vec![(2, 5)]]
+        );
+
+        // Transactions of the same account are ordered by nonces, the others are not moved.
+        let elements = vec![
+            transfer(1, 2).into(),
+            transfer(2, 0).into(),
+            transfer(1, 1).into(),
+            batch(vec![transfer(3, 0), transfer(1, 0)]),
+        ];
+        assert_eq!(
+            nonces(&order_by_dependencies(elements)),
+            vec![
+                vec![(2, 0)],
+                vec![(3, 0), (1, 0)],
+                vec![(1, 1)],
+                vec![(1, 2)]
+            ]
+        );
+
+        // Cycles are kept as is.
+        let elements = vec![
+            batch(vec![transfer(1, 1), transfer(2, 0)]),
+            batch(vec![transfer(1, 0), transfer(2, 1)]),
+            transfer(3, 0).into(),
+        ];
+        assert_eq!(
+            nonces(&order_by_dependencies(elements)),
+            vec![vec![(3, 0)], vec![(1, 1), (2, 0)], vec![(1, 0), (2, 1)]]
+        );
+    }
+
+    #[test]
+    fn nonce_holes() {
+        let state = |account_id: AccountId| match *account_id {
+            1 => Some(Nonce(3)),
+            2 => Some(Nonce(0)),
+            _ => None,
+        };
+
+        assert_eq!(find_nonce_hole(&transfer(1, 3).into(), state), None);
+        // Used nonces and nonexistent accounts fail on their own.
+        assert_eq!(find_nonce_hole(&transfer(1, 2).into(), state), None);
+        assert_eq!(find_nonce_hole(&transfer(5, 2).into(), state), None);
+        assert_eq!(
+            find_nonce_hole(&transfer(1, 4).into(), state),
+            Some(NonceHole {
+                account_id: AccountId(1),
+                expected: Nonce(3),
+                nonce: Nonce(4),
+            })
+        );
+
+        // Batch transactions are executed one after another.
+        let element = batch(vec![transfer(1, 3), transfer(2, 0), transfer(1, 4)]);
+        assert_eq!(find_nonce_hole(&element, state), None);
+        let element = batch(vec![transfer(1, 3), transfer(2, 1)]);
+        assert_eq!(
+            find_nonce_hole(&element, state),
+            Some(NonceHole {
+                account_id: AccountId(2),
+                expected: Nonce(0),
+                nonce: Nonce(1),
+            })
+        );
+    }
+}
@@ -0,0 +1,288 @@
+//! Expiry of the transactions which are stuck in the mempool.
+//!
+//! A transaction expires once its `valid_until` timestamp passes, or if it wasn't executed
+//! within the configured TTL since it was added to the mempool. The transactions delayed by
+//! the screening can't be executed before their release, so their TTL counts since the release
+//! time instead. Expired transactions are removed
+//! from the mempool, and the reasons they expired for are stored in the database, so the API
+//! reports them as rejected rather than pending forever.
+//!
+//! Batches expire as a whole: if any transaction of the batch expires, the entire batch is removed.
+
+// Built-in deps
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+// External uses
+use chrono::{DateTime, Utc};
+use tokio::{sync::RwLock, time};
+// Workspace uses
+use zksync_storage::ConnectionPool;
+use zksync_types::{mempool::SignedTxVariant, tx::TxHash};
+// Local uses
+use super::MempoolState;
+
+/// Returns the reason the transaction variant expired for, or `None` if it's still valid.
+fn expiry_reason(
+    tx_variant: &SignedTxVariant,
+    stale_txs: &HashSet<TxHash>,
+    now: u64,
+    tx_ttl: Duration,
+) -> Option<String> {
+    let txs = tx_variant.get_transactions();
+    if txs.iter().any(|tx| tx.tx.valid_until() < now) {
+        Some("Transaction expired: its valid_until timestamp has passed".to_owned())
+    } else if txs.iter().any(|tx| stale_txs.contains(&tx.hash())) {
+        Some(format!(
+            "Transaction expired: it was not executed within {} seconds",
+            tx_ttl.as_secs()
+        ))
+    } else {
+        None
+    }
+}
+
+/// Removes the expired transactions from the mempool state. The transactions created before
+/// the TTL cutoff are stale unless they were released by the screening after the cutoff.
+/// Returns the hashes of the removed transactions along with the expiry reasons.
+fn remove_expired_txs(
+    mempool_state: &mut MempoolState,
+    created_before_cutoff: HashSet<TxHash>,
+    now: DateTime<Utc>,
+    tx_ttl: Duration,
+) -> anyhow::Result<Vec<(TxHash, String)>> {
+    let cutoff = now - chrono::Duration::from_std(tx_ttl)?;
+    let release_times = &mempool_state.release_times;
+    let stale_txs: HashSet<_> = created_before_cutoff
+        .into_iter()
+        .filter(|hash| {
+            release_times
+                .get(hash)
+                .map_or(true, |release_at| *release_at < cutoff)
+        })
+        .collect();
+
+    let mut expired_txs = Vec::new();
+    let now = now.timestamp() as u64;
+    mempool_state.transactions_queue.retain(|tx_variant| {
+        match expiry_reason(tx_variant, &stale_txs, now, tx_ttl) {
+            Some(reason) => {
+                expired_txs.extend(
+                    tx_variant
+                        .hashes()
+                        .into_iter()
+                        .map(|hash| (hash, reason.clone())),
+                );
+                false
+            }
+            None => true,
+        }
+    });
+    // The release times of the transactions which left the mempool are not needed anymore.
+    let queued_txs: HashSet<_> = mempool_state
+        .transactions_queue
+        .iter()
+        .flat_map(|tx_variant| tx_variant.hashes())
+        .collect();
+    mempool_state
+        .release_times
+        .retain(|hash, _| queued_txs.contains(hash));
+    mempool_state.report_size();
+    Ok(expired_txs)
+}
+
+pub(super) struct MempoolTxExpiry {
+    db_pool: ConnectionPool,
+    mempool_state: Arc<RwLock<MempoolState>>,
+    tx_ttl: Duration,
+}
+
+impl MempoolTxExpiry {
+    pub fn new(
+        db_pool: ConnectionPool,
+        mempool_state: Arc<RwLock<MempoolState>>,
+        tx_ttl: Duration,
+    ) -> Self {
+        Self {
+            db_pool,
+            mempool_state,
+            tx_ttl,
+        }
+    }
+
+    async fn expire_txs(&self) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let mut storage = self.db_pool.access_storage().await?;
+
+        let now = Utc::now();
+        let cutoff = now - chrono::Duration::from_std(self.tx_ttl)?;
+        let created_before_cutoff: HashSet<_> = storage
+            .chain()
+            .mempool_schema()
+            .load_txs_created_before(cutoff)
+            .await?
+            .into_iter()
+            .collect();
+
+        let mut mempool_state = self.mempool_state.write().await;
+        let expired_txs =
+            remove_expired_txs(&mut mempool_state, created_before_cutoff, now, self.tx_ttl)?;
+        drop(mempool_state);
+
+        if !expired_txs.is_empty() {
+            storage
+                .chain()
+                .mempool_schema()
+                .expire_txs(&expired_txs, now)
+                .await?;
+            vlog::info!("{} transactions expired in the mempool", expired_txs.len());
+        }
+
+        metrics::counter!("mempool.expired_txs", expired_txs.len() as u64);
+        metrics::histogram!("mempool.expire_txs", start.elapsed());
+        Ok(())
+    }
+
+    pub async fn run(self, check_interval: Duration) {
+        let mut timer = time::interval(check_interval);
+        loop {
+            timer.tick().await;
+
+            if let Err(err) = self.expire_txs().await {
+                vlog::warn!("Failed to expire the mempool transactions: {}", err);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mempool::mempool_transactions_queue::MempoolTransactionsQueue;
+    use chrono::TimeZone;
+    use zksync_types::{
+        mempool::SignedTxsBatch,
+        tx::{TimeRange, Withdraw},
+        AccountId, Address, Nonce, SignedZkSyncTx, TokenId, ZkSyncTx,
+    };
+
+    fn get_withdraw(nonce: u32, valid_until: u64) -> SignedZkSyncTx {
+        let withdraw = Withdraw::new(
+            AccountId(3),
+            Address::repeat_byte(1),
+            Address::repeat_byte(9),
+            TokenId(1),
+            20u32.into(),
+            10u32.into(),
+            Nonce(nonce),
+            TimeRange::new(0, valid_until),
+            None,
+        );
+
+        SignedZkSyncTx {
+            tx: ZkSyncTx::Withdraw(Box::new(withdraw)),
+            eth_sign_data: None,
+        }
+    }
+
+    fn mempool_state() -> MempoolState {
+        MempoolState {
+            account_nonces: Default::default(),
+            account_ids: Default::default(),
+            transactions_queue: MempoolTransactionsQueue::new(),
+            nonce_reservations: Default::default(),
+            proposed_nonces: Default::default(),
+            delayed_accounts: Default::default(),
+            release_times: Default::default(),
+        }
+    }
+
+    /// Checks that the txs past `valid_until` and the stale txs are removed from the mempool.
+    #[test]
+    fn expired_txs_are_removed() {
+        let tx_ttl = Duration::from_secs(60);
+        let now = Utc.timestamp(1_000, 0);
+
+        let valid_tx = get_withdraw(0, u64::MAX);
+        let outdated_tx = get_withdraw(1, 999);
+        let stale_tx = get_withdraw(2, u64::MAX);
+        let batch = SignedTxsBatch {
+            txs: vec![get_withdraw(3, u64::MAX), get_withdraw(4, 999)],
+            batch_id: 1,
+            eth_signatures: Vec::new(),
+        };
+
+        let mut mempool_state = mempool_state();
+        for tx in vec![valid_tx.clone(), outdated_tx.clone(), stale_tx.clone()] {
+            mempool_state
+                .transactions_queue
+                .add_tx_variant(SignedTxVariant::Tx(tx));
+        }
+        mempool_state
+            .transactions_queue
+            .add_tx_variant(SignedTxVariant::Batch(batch.clone()));
+
+        let stale_txs = vec![stale_tx.hash()].into_iter().collect();
+        let expired_txs = remove_expired_txs(&mut mempool_state, stale_txs, now, tx_ttl).unwrap();
+
+        let expired_hashes: HashSet<_> = expired_txs.iter().map(|(hash, _)| *hash).collect();
+        let expected_hashes: HashSet<_> = vec![
+            outdated_tx.hash(),
+            stale_tx.hash(),
+            batch.txs[0].hash(),
+            batch.txs[1].hash(),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(expired_hashes, expected_hashes);
+        assert!(expired_txs
+            .iter()
+            .any(|(hash, reason)| *hash == stale_tx.hash() && reason.contains("60 seconds")));
+
+        let retained: Vec<_> = mempool_state
+            .transactions_queue
+            .iter()
+            .flat_map(|tx_variant| tx_variant.hashes())
+            .collect();
+        assert_eq!(retained, vec![valid_tx.hash()]);
+    }
+
+    /// Checks that the TTL of the txs delayed by the screening counts since their release.
+    #[test]
+    fn delayed_txs_expire_after_release() {
+        let tx_ttl = Duration::from_secs(60);
+        let now = Utc.timestamp(1_000, 0);
+
+        let released_tx = get_withdraw(0, u64::MAX);
+        let stale_tx = get_withdraw(1, u64::MAX);
+        let mut mempool_state = mempool_state();
+        mempool_state.add_tx(stale_tx.clone(), Some(now - chrono::Duration::seconds(120)));
+        mempool_state.add_tx(
+            released_tx.clone(),
+            Some(now - chrono::Duration::seconds(30)),
+        );
+
+        // Both transactions were created long before the cutoff.
+        let created_before_cutoff = vec![released_tx.hash(), stale_tx.hash()]
+            .into_iter()
+            .collect();
+        let expired_txs =
+            remove_expired_txs(&mut mempool_state, created_before_cutoff, now, tx_ttl).unwrap();
+        assert_eq!(expired_txs.len(), 1);
+        assert_eq!(expired_txs[0].0, stale_tx.hash());
+
+        let retained: Vec<_> = mempool_state
+            .transactions_queue
+            .iter()
+            .flat_map(|tx_variant| tx_variant.hashes())
+            .collect();
+        assert_eq!(retained, vec![released_tx.hash()]);
+        // The release time of the expired transaction is pruned.
+        assert_eq!(
+            mempool_state.release_times.keys().collect::<Vec<_>>(),
+            vec![&released_tx.hash()]
+        );
+    }
+}
@@ -7,33 +7,66 @@
 //! All the incoming data is assumed to be correct and not double-checked
 //! for correctness.
 
-use crate::{eth_watch::EthWatchRequest, mempool::MempoolTransactionRequest};
-use actix_web::{web, App, HttpResponse, HttpServer};
+use crate::{
+    eth_watch::EthWatchRequest,
+    mempool::{MempoolTransactionRequest, TxAddError},
+};
+use actix_web::{dev::Service, web, App, HttpRequest, HttpResponse, HttpServer};
 use futures::{
     channel::{mpsc, oneshot},
+    future,
     sink::SinkExt,
+    FutureExt,
 };
 use std::thread;
 use zksync_config::configs::api::PrivateApi;
-use zksync_types::{tx::TxEthSignature, Address, SignedZkSyncTx, H256};
-use zksync_utils::panic_notify::ThreadPanicNotify;
+use zksync_types::{
+    protocol_version::{
+        check_protocol_version_header, COMPONENTS_PROTOCOL_VERSION, PROTOCOL_VERSION_HEADER,
+    },
+    tx::TxEthSignature,
+    Address, SignedZkSyncTx, H256,
+};
+use zksync_utils::{
+    panic_notify::ThreadPanicNotify, shutdown::ShutdownSignal, supervisor::Supervisor,
+};
+
+/// Creates a span for the request handler, linked to the span of the API server (if any).
+fn request_span(req: &HttpRequest, span: vlog::Span) -> vlog::Span {
+    let headers = req
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)));
+    vlog::set_remote_parent(&span, headers);
+    span
+}
 
 #[derive(Debug, Clone)]
 struct AppState {
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
     eth_watch_req_sender: mpsc::Sender<EthWatchRequest>,
+    shutdown: ShutdownSignal,
+    supervisor: Supervisor,
 }
 
-/// Adds a new transaction into the mempool.
-/// Returns a JSON representation of `Result<(), TxAddError>`.
-/// Expects transaction to be checked on the API side.
-#[actix_web::post("/new_tx")]
-async fn new_tx(
-    data: web::Data<AppState>,
-    web::Json(tx): web::Json<SignedZkSyncTx>,
+impl AppState {
+    /// Response rejecting the transaction if the server is shutting down, so
+    /// the block being sealed on shutdown is not extended with the new transactions.
+    fn reject_if_shutting_down(&self) -> Option<HttpResponse> {
+        if self.shutdown.is_triggered() {
+            Some(HttpResponse::Ok().json(Err::<(), _>(TxAddError::ShuttingDown)))
+        } else {
+            None
+        }
+    }
+}
+
+/// Sends the request to the mempool and returns a JSON representation of its `Result<(), TxAddError>`.
+async fn mempool_request(
+    data: &AppState,
+    item: MempoolTransactionRequest,
+    receiver: oneshot::Receiver<Result<(), TxAddError>>,
 ) -> actix_web::Result<HttpResponse> {
-    let (sender, receiver) = oneshot::channel();
-    let item = MempoolTransactionRequest::NewTx(Box::new(tx), sender);
     let mut mempool_sender = data.mempool_tx_sender.clone();
     mempool_sender
         .send(item)
@@ -47,27 +80,82 @@ async fn new_tx(
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// Adds a new transaction into the mempool.
+/// Returns a JSON representation of `Result<(), TxAddError>`.
+/// Expects transaction to be checked on the API side.
+#[actix_web::post("/new_tx")]
+async fn new_tx(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    web::Json(tx): web::Json<SignedZkSyncTx>,
+) -> actix_web::Result<HttpResponse> {
+    if let Some(response) = data.reject_if_shutting_down() {
+        return Ok(response);
+    }
+    let span = request_span(
+        &req,
+        vlog::info_span!("new_tx", tx_hash = %tx.hash().to_string()),
+    );
+    let (sender, receiver) = oneshot::channel();
+    let item = MempoolTransactionRequest::NewTx(Box::new(tx), sender, span);
+    mempool_request(&data, item, receiver).await
+}
+
 /// Adds a new transactions batch into the mempool.
 /// Returns a JSON representation of `Result<(), TxAddError>`.
 /// Expects transaction to be checked on the API side.
 #[actix_web::post("/new_txs_batch")]
 async fn new_txs_batch(
+    req: HttpRequest,
     data: web::Data<AppState>,
     web::Json((txs, eth_signatures)): web::Json<(Vec<SignedZkSyncTx>, Vec<TxEthSignature>)>,
 ) -> actix_web::Result<HttpResponse> {
+    if let Some(response) = data.reject_if_shutting_down() {
+        return Ok(response);
+    }
+    let span = request_span(&req, vlog::info_span!("new_txs_batch"));
     let (sender, receiver) = oneshot::channel();
-    let item = MempoolTransactionRequest::NewTxsBatch(txs, eth_signatures, sender);
-    let mut mempool_sender = data.mempool_tx_sender.clone();
-    mempool_sender
-        .send(item)
-        .await
-        .map_err(|_err| HttpResponse::InternalServerError().finish())?;
+    let item = MempoolTransactionRequest::NewTxsBatch(txs, eth_signatures, sender, span);
+    mempool_request(&data, item, receiver).await
+}
 
-    let response = receiver
-        .await
-        .map_err(|_err| HttpResponse::InternalServerError().finish())?;
+/// Checks whether the transaction would be accepted by the mempool, without adding it.
+/// Returns a JSON representation of `Result<(), TxAddError>`.
+/// Expects transaction to be checked on the API side.
+#[actix_web::post("/check_tx")]
+async fn check_tx(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    web::Json(tx): web::Json<SignedZkSyncTx>,
+) -> actix_web::Result<HttpResponse> {
+    if let Some(response) = data.reject_if_shutting_down() {
+        return Ok(response);
+    }
+    let span = request_span(
+        &req,
+        vlog::info_span!("check_tx", tx_hash = %tx.hash().to_string()),
+    );
+    let (sender, receiver) = oneshot::channel();
+    let item = MempoolTransactionRequest::CheckTx(Box::new(tx), sender, span);
+    mempool_request(&data, item, receiver).await
+}
 
-    Ok(HttpResponse::Ok().json(response))
+/// Checks whether the transactions batch would be accepted by the mempool, without adding it.
+/// Returns a JSON representation of `Result<(), TxAddError>`.
+/// Expects transaction to be checked on the API side.
+#[actix_web::post("/check_txs_batch")]
+async fn check_txs_batch(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    web::Json((txs, eth_signatures)): web::Json<(Vec<SignedZkSyncTx>, Vec<TxEthSignature>)>,
+) -> actix_web::Result<HttpResponse> {
+    if let Some(response) = data.reject_if_shutting_down() {
+        return Ok(response);
+    }
+    let span = request_span(&req, vlog::info_span!("check_txs_batch"));
+    let (sender, receiver) = oneshot::channel();
+    let item = MempoolTransactionRequest::CheckTxsBatch(txs, eth_signatures, sender, span);
+    mempool_request(&data, item, receiver).await
 }
 
 /// Obtains information about unconfirmed deposits known for a certain address.
@@ -142,12 +230,54 @@ async fn unconfirmed_op(
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// Obtains the priority operations confirmed on L1 and not yet included into a block,
+/// starting from the given serial ID.
+#[actix_web::get("/priority_queue/{first_serial_id}")]
+async fn priority_queue(
+    data: web::Data<AppState>,
+    web::Path(first_serial_id): web::Path<u64>,
+) -> actix_web::Result<HttpResponse> {
+    let (sender, receiver) = oneshot::channel();
+    let item = EthWatchRequest::GetPriorityQueueOps {
+        op_start_id: first_serial_id,
+        max_chunks: usize::MAX,
+        resp: sender,
+    };
+    let mut eth_watch_sender = data.eth_watch_req_sender.clone();
+    eth_watch_sender
+        .send(item)
+        .await
+        .map_err(|_err| HttpResponse::InternalServerError().finish())?;
+
+    let response = receiver
+        .await
+        .map_err(|_err| HttpResponse::InternalServerError().finish())?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Returns the status of the supervised components.
+/// Responds with `503 Service Unavailable` if any of them is failed.
+#[actix_web::get("/health")]
+async fn health(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let report = data.supervisor.health();
+    let response = if report.healthy {
+        HttpResponse::Ok().json(report)
+    } else {
+        HttpResponse::ServiceUnavailable().json(report)
+    };
+
+    Ok(response)
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn start_private_core_api(
     panic_notify: mpsc::Sender<bool>,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
     eth_watch_req_sender: mpsc::Sender<EthWatchRequest>,
     config: PrivateApi,
+    shutdown: ShutdownSignal,
+    supervisor: Supervisor,
 ) {
     thread::Builder::new()
         .name("core-private-api".to_string())
@@ -161,6 +291,8 @@ pub fn start_private_core_api(
                     let app_state = AppState {
                         mempool_tx_sender: mempool_tx_sender.clone(),
                         eth_watch_req_sender: eth_watch_req_sender.clone(),
+                        shutdown: shutdown.clone(),
+                        supervisor: supervisor.clone(),
                     };
 
                     // By calling `register_data` instead of `data` we're avoiding double
@@ -168,12 +300,38 @@ pub fn start_private_core_api(
                     App::new()
                         .wrap(actix_web::middleware::Logger::default())
                         .wrap(vlog::actix_middleware())
+                        // The API servers check the version of the Core in turn,
+                        // see `zksync_types::protocol_version`.
+                        .wrap(actix_web::middleware::DefaultHeaders::new().header(
+                            PROTOCOL_VERSION_HEADER,
+                            COMPONENTS_PROTOCOL_VERSION.to_string(),
+                        ))
+                        // Requests of the API servers of the incompatible versions are rejected,
+                        // so the mixed-version deployments fail fast.
+                        .wrap_fn(|req, srv| {
+                            if req.path() != "/health" {
+                                let version = req
+                                    .headers()
+                                    .get(PROTOCOL_VERSION_HEADER)
+                                    .map(|value| value.to_str().unwrap_or_default());
+                                if let Err(err) = check_protocol_version_header(version) {
+                                    vlog::warn!("Rejected the request to {}: {}", req.path(), err);
+                                    let response = actix_web::error::ErrorConflict(err);
+                                    return future::err(response).left_future();
+                                }
+                            }
+                            srv.call(req).right_future()
+                        })
                         .app_data(web::Data::new(app_state))
                         .service(new_tx)
                         .service(new_txs_batch)
+                        .service(check_tx)
+                        .service(check_txs_batch)
                         .service(unconfirmed_op)
                         .service(unconfirmed_ops)
                         .service(unconfirmed_deposits)
+                        .service(priority_queue)
+                        .service(health)
                 })
                 .bind(&config.bind_addr())
                 .expect("failed to bind")
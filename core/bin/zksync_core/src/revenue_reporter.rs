@@ -0,0 +1,125 @@
+//! Revenue reporter aggregates the fees collected by the operator for the revenue reports.
+//!
+//! The reporter follows the event log and, once a block is verified, sums the fees of its
+//! successfully executed transactions by the token and the hour (see `zksync_types::revenue`).
+//! Every aggregate is valued in USD using the token price known at the moment of aggregation.
+//! Aggregates are stored in the same database transaction which moves the reporter to the next
+//! event, so the fees of every block are counted exactly once.
+
+// Built-in uses
+use std::time::Duration;
+// External uses
+use num::{rational::Ratio, BigUint};
+use tokio::{task::JoinHandle, time};
+// Workspace uses
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::{
+    event::ChainEvent, revenue::CollectedFees, token_amount::to_token_units, TokenLike,
+};
+
+/// Name of the reporter in the list of the event log consumers.
+const CONSUMER_NAME: &str = "operator_revenue";
+/// Max amount of the events processed within one iteration.
+const EVENTS_BATCH_SIZE: u32 = 100;
+const REPORT_INTERVAL: Duration = Duration::from_secs(60);
+
+struct RevenueReporter {
+    db_pool: ConnectionPool,
+}
+
+impl RevenueReporter {
+    async fn process_new_events(&self) -> anyhow::Result<()> {
+        let mut storage = self.db_pool.access_storage().await?;
+
+        let from_offset = match storage
+            .event_schema()
+            .get_consumer_offset(CONSUMER_NAME)
+            .await?
+        {
+            Some(offset) => offset,
+            // Only the fees collected after the first start of the reporter are counted.
+            None => storage
+                .event_schema()
+                .get_last_offset()
+                .await?
+                .map(|offset| offset + 1)
+                .unwrap_or_default(),
+        };
+        let events = storage
+            .event_schema()
+            .load_events(from_offset, EVENTS_BATCH_SIZE)
+            .await?;
+        let next_offset = match events.last() {
+            Some(event) => event.offset + 1,
+            None => return Ok(()),
+        };
+
+        let mut fees = Vec::new();
+        for record in events {
+            let block_number = match record.event {
+                ChainEvent::BlockVerified { block_number } => block_number,
+                _ => continue,
+            };
+            let operations = storage
+                .chain()
+                .block_schema()
+                .get_block_executed_ops(block_number)
+                .await?;
+            fees.extend(CollectedFees::aggregate(&operations));
+        }
+        for entry in &mut fees {
+            entry.amount_usd = Self::value_in_usd(&mut storage, entry).await?;
+        }
+
+        storage
+            .revenue_schema()
+            .store_collected_fees(CONSUMER_NAME, next_offset, &fees)
+            .await?;
+        Ok(())
+    }
+
+    /// Values the fees using the last known token price.
+    /// Fees in the tokens without the known price are valued at zero.
+    async fn value_in_usd(
+        storage: &mut StorageProcessor<'_>,
+        fees: &CollectedFees,
+    ) -> anyhow::Result<Ratio<BigUint>> {
+        let token = storage
+            .tokens_schema()
+            .get_token(TokenLike::Id(fees.token_id))
+            .await?;
+        let price = storage
+            .tokens_schema()
+            .get_historical_ticker_price(fees.token_id)
+            .await?;
+
+        match (token, price) {
+            (Some(token), Some(price)) => {
+                Ok(price.usd_price * to_token_units(&fees.amount, token.decimals))
+            }
+            _ => {
+                vlog::warn!(
+                    "USD price of the token {} is unknown, its fees are valued at zero",
+                    *fees.token_id
+                );
+                Ok(Ratio::from_integer(BigUint::from(0u32)))
+            }
+        }
+    }
+}
+
+#[must_use]
+pub fn run_revenue_reporter(db_pool: ConnectionPool) -> JoinHandle<()> {
+    let reporter = RevenueReporter { db_pool };
+    let mut timer = time::interval(REPORT_INTERVAL);
+
+    tokio::spawn(async move {
+        loop {
+            timer.tick().await;
+
+            if let Err(err) = reporter.process_new_events().await {
+                vlog::error!("Failed to aggregate the operator revenue: {}", err);
+            }
+        }
+    })
+}
@@ -0,0 +1,564 @@
+//! Snapshot sync bootstraps a new server instance from the state snapshot served by a peer,
+//! instead of restoring the state from L1 or replaying the history.
+//!
+//! The snapshot block is checked against its commit transaction on L1, and the account tree
+//! rebuilt from the downloaded chunks is checked against the root of the block, so the peer
+//! doesn't have to be trusted. The tokens aren't a part of the tree, so their addresses are
+//! checked against the governance contract instead, and the accounts may only hold the checked
+//! tokens. The symbols and decimals of the tokens are informational and are taken as is.
+//!
+//! The chunks are saved to the local directory as they're downloaded, so an interrupted sync
+//! resumes from the last saved chunk as long as the peer still serves the snapshot. Otherwise,
+//! the sync starts over with the latest snapshot. The chunks are imported one by one in a single
+//! database transaction, which is committed only if the root matches, so only the account tree
+//! is kept in memory.
+//!
+//! The imported database contains the state at the snapshot block, the block itself and
+//! the tokens, but not the blocks and transactions preceding the snapshot block.
+
+// Built-in uses
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, UNIX_EPOCH},
+};
+// External uses
+use anyhow::{bail, ensure, format_err};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde::{Deserialize, Serialize};
+use web3::{contract::Options, types::U256};
+// Workspace uses
+use zksync_api_client::rest::v1::Client;
+use zksync_config::ZkSyncConfig;
+use zksync_contracts::governance_contract;
+use zksync_crypto::params;
+use zksync_eth_client::EthereumGateway;
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::{
+    aggregated_operations::{BlocksCommitOperation, BlocksExecuteOperation},
+    block::Block,
+    snapshot::{account_creation_updates, SnapshotChunk, SnapshotMetadata},
+    Account, AccountId, AccountTree, AccountUpdates, Address, BlockNumber, Token, TokenId,
+};
+// Local uses
+use crate::l1_state_verifier::decode_committed_blocks;
+
+/// File keeping the number of the block the saved chunks belong to.
+const BLOCK_NUMBER_FILE: &str = "block_number.json";
+/// Time the authorization token of the snapshot requests is valid for.
+const AUTH_TOKEN_VALIDITY: Duration = Duration::from_secs(24 * 3600);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PayloadAuthToken {
+    /// Subject (whom auth token refers to).
+    sub: String,
+    /// Expiration time (as UTC timestamp).
+    exp: usize,
+}
+
+/// Encodes the token authorizing the snapshot requests to the peer.
+fn auth_token(secret: &str) -> anyhow::Result<String> {
+    let exp = UNIX_EPOCH.elapsed()? + AUTH_TOKEN_VALIDITY;
+    let payload = PayloadAuthToken {
+        sub: "snapshot-sync".to_string(),
+        exp: exp.as_secs() as usize,
+    };
+    Ok(encode(
+        &Header::default(),
+        &payload,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )?)
+}
+
+/// Local directory keeping the downloaded chunks of the snapshot.
+struct SnapshotDir {
+    path: PathBuf,
+}
+
+impl SnapshotDir {
+    /// Opens the directory for the snapshot. The chunks of the other snapshot are removed.
+    fn open(path: &Path, block_number: BlockNumber) -> anyhow::Result<Self> {
+        let block_number_path = path.join(BLOCK_NUMBER_FILE);
+        let saved_block = fs::read(&block_number_path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<BlockNumber>(&bytes).ok());
+        if saved_block != Some(block_number) {
+            if path.exists() {
+                fs::remove_dir_all(path)?;
+            }
+            fs::create_dir_all(path)?;
+            fs::write(&block_number_path, serde_json::to_vec(&block_number)?)?;
+        }
+
+        Ok(Self {
+            path: path.to_owned(),
+        })
+    }
+
+    fn chunk_path(&self, index: usize) -> PathBuf {
+        self.path.join(format!("chunk_{}.json", index))
+    }
+
+    fn load_chunk(&self, index: usize) -> anyhow::Result<Option<SnapshotChunk>> {
+        match fs::read(self.chunk_path(index)) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Saves the chunk, a partially written chunk is never picked up on resume.
+    fn save_chunk(&self, chunk: &SnapshotChunk) -> anyhow::Result<()> {
+        let path = self.chunk_path(chunk.index);
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_vec(chunk)?)?;
+        fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+
+    fn remove(&self) -> anyhow::Result<()> {
+        fs::remove_dir_all(&self.path)?;
+        Ok(())
+    }
+}
+
+/// Checks that the snapshot block is committed to L1 by the zkSync contract.
+async fn check_l1_commitment(
+    eth_gateway: &EthereumGateway,
+    config: &ZkSyncConfig,
+    metadata: &SnapshotMetadata,
+) -> anyhow::Result<()> {
+    let block = &metadata.block;
+    let tx_hash = metadata
+        .commit_tx_hash
+        .ok_or_else(|| format_err!("Block #{} is not committed to L1", *block.block_number))?;
+
+    let status = eth_gateway
+        .get_tx_status(tx_hash)
+        .await?
+        .ok_or_else(|| format_err!("Commit transaction {:?} is not found", tx_hash))?;
+    ensure!(
+        status.success,
+        "Commit transaction {:?} has failed",
+        tx_hash
+    );
+    let tx = eth_gateway
+        .get_tx(tx_hash)
+        .await?
+        .ok_or_else(|| format_err!("Commit transaction {:?} is not found", tx_hash))?;
+    ensure!(
+        tx.to == Some(config.contracts.contract_addr),
+        "Transaction {:?} is not sent to the zkSync contract",
+        tx_hash
+    );
+
+    let l1_block = decode_committed_blocks(&tx.input.0)?
+        .into_iter()
+        .find(|l1_block| l1_block.block_number == block.block_number)
+        .ok_or_else(|| {
+            format_err!(
+                "Block #{} is not committed by the transaction {:?}",
+                *block.block_number,
+                tx_hash
+            )
+        })?;
+    // The commitment covers the public data of the block,
+    // so the operations of the block are checked as well.
+    let commitment = Block::get_commitment(
+        block.block_number,
+        block.fee_account,
+        l1_block.previous_state_hash,
+        block.get_eth_encoded_root(),
+        block.timestamp,
+        &block.get_onchain_op_commitment(),
+        &block.get_eth_public_data(),
+    );
+    ensure!(
+        l1_block.state_hash == block.get_eth_encoded_root() && l1_block.commitment == commitment,
+        "Block #{} doesn't match the one committed to L1",
+        *block.block_number
+    );
+    Ok(())
+}
+
+/// Checks the addresses of the snapshot tokens against the ones registered by the governance
+/// contract. Returns the IDs of the checked tokens.
+async fn check_tokens(
+    eth_gateway: &EthereumGateway,
+    config: &ZkSyncConfig,
+    tokens: &[Token],
+) -> anyhow::Result<HashSet<TokenId>> {
+    let mut token_ids = HashSet::new();
+    for token in tokens {
+        ensure!(
+            token.id <= params::max_token_id() && token_ids.insert(token.id),
+            "Token #{} is invalid or duplicated",
+            *token.id
+        );
+        // ETH is not registered by the governance contract.
+        let registered_address = if token.id == TokenId(0) {
+            Address::zero()
+        } else {
+            eth_gateway
+                .call_contract_function(
+                    "tokenAddresses",
+                    (U256::from(*token.id),),
+                    None,
+                    Options::default(),
+                    None,
+                    config.contracts.governance_addr,
+                    governance_contract(),
+                )
+                .await?
+        };
+        ensure!(
+            registered_address == token.address,
+            "Token #{} has the address {:?}, while {:?} is registered on L1",
+            *token.id,
+            token.address,
+            registered_address
+        );
+    }
+    Ok(token_ids)
+}
+
+/// Rebuilds the account tree from the snapshot chunks.
+struct SnapshotImporter {
+    tree: AccountTree,
+    /// Tokens the accounts may hold.
+    token_ids: HashSet<TokenId>,
+    accounts_count: usize,
+    last_account_id: Option<AccountId>,
+}
+
+impl SnapshotImporter {
+    fn new(token_ids: HashSet<TokenId>) -> Self {
+        Self {
+            tree: AccountTree::new(params::account_tree_depth()),
+            token_ids,
+            accounts_count: 0,
+            last_account_id: None,
+        }
+    }
+
+    /// Adds the accounts of the chunk to the tree and returns the updates creating them.
+    /// The chunks must be added in order.
+    fn add_chunk(&mut self, chunk: SnapshotChunk) -> anyhow::Result<AccountUpdates> {
+        let mut updates = AccountUpdates::new();
+        let mut accounts = Vec::with_capacity(chunk.accounts.len());
+        for (id, account) in chunk.accounts {
+            // The accounts are checked before being inserted, since the tree panics on
+            // the invalid IDs, tokens and balances.
+            ensure!(
+                self.last_account_id < Some(id) && id <= params::max_account_id(),
+                "Account #{} is out of order or invalid",
+                *id
+            );
+            for (token, balance) in account.get_nonzero_balances() {
+                ensure!(
+                    self.token_ids.contains(&token),
+                    "Account #{} holds the unknown token #{}",
+                    *id,
+                    *token
+                );
+                ensure!(
+                    balance.0.bits() <= params::BALANCE_BIT_WIDTH as u64,
+                    "Account #{} has the invalid balance of the token #{}",
+                    *id,
+                    *token
+                );
+            }
+            self.last_account_id = Some(id);
+
+            // The account is inserted as it's restored by the updates, i.e. without
+            // the zero balances.
+            let account_updates = account_creation_updates(id, &account);
+            let account = Account::apply_updates(
+                None,
+                &account_updates
+                    .iter()
+                    .map(|(_, update)| update.clone())
+                    .collect::<Vec<_>>(),
+            )
+            .ok_or_else(|| format_err!("Account #{} can't be restored", *id))?;
+            updates.extend(account_updates);
+            accounts.push((*id, account));
+        }
+        self.accounts_count += accounts.len();
+        // Leaf hashes of the chunk are calculated in one batch.
+        self.tree.insert_many(accounts);
+        Ok(updates)
+    }
+
+    /// Checks the rebuilt tree against the snapshot block.
+    fn check(&self, metadata: &SnapshotMetadata) -> anyhow::Result<()> {
+        ensure!(
+            self.accounts_count == metadata.accounts_count
+                && self.tree.root_hash() == metadata.block.new_root_hash,
+            "Snapshot accounts don't match the root hash of block #{}",
+            *metadata.block_number()
+        );
+        Ok(())
+    }
+}
+
+/// Downloads the missing chunks of the snapshot and imports all the snapshot accounts.
+async fn import_accounts(
+    storage: &mut StorageProcessor<'_>,
+    client: &Client,
+    auth_token: &str,
+    snapshot_dir: &SnapshotDir,
+    metadata: &SnapshotMetadata,
+    importer: &mut SnapshotImporter,
+) -> anyhow::Result<()> {
+    let block_number = metadata.block_number();
+    let mut update_order_id = 0;
+    for index in 0..metadata.chunks_count {
+        let chunk = match snapshot_dir.load_chunk(index)? {
+            Some(chunk) => chunk,
+            None => {
+                let chunk = client
+                    .snapshot_chunk(auth_token, block_number, index)
+                    .await?
+                    .ok_or_else(|| {
+                        format_err!(
+                            "Snapshot at block #{} is not served anymore, restart the sync",
+                            *block_number
+                        )
+                    })?;
+                ensure!(
+                    chunk.block_number == block_number && chunk.index == index,
+                    "Peer returned the chunk #{} of block #{} instead of the chunk #{} of block #{}",
+                    chunk.index,
+                    *chunk.block_number,
+                    index,
+                    *block_number
+                );
+                snapshot_dir.save_chunk(&chunk)?;
+                metrics::counter!("snapshot_sync.chunks_downloaded", 1);
+                chunk
+            }
+        };
+        let account_updates = match importer.add_chunk(chunk) {
+            Ok(account_updates) => account_updates,
+            Err(err) => {
+                // The chunks are inconsistent with the verified block, so none of them can be trusted.
+                snapshot_dir.remove()?;
+                return Err(err);
+            }
+        };
+        storage
+            .chain()
+            .state_schema()
+            .commit_state_update(block_number, &account_updates, update_order_id)
+            .await?;
+        update_order_id += account_updates.len();
+
+        if (index + 1) % 10 == 0 {
+            vlog::info!(
+                "Imported {} of {} snapshot chunks",
+                index + 1,
+                metadata.chunks_count
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Imports the snapshot served by the peer into the empty database.
+/// Returns the number of the block the snapshot is made at.
+pub async fn run_snapshot_sync(
+    config: &ZkSyncConfig,
+    peer_url: &str,
+    snapshot_dir: &Path,
+) -> anyhow::Result<BlockNumber> {
+    let pool = ConnectionPool::new(Some(1));
+    let mut storage = pool.access_storage().await?;
+    let (_, stored_accounts) = storage
+        .chain()
+        .state_schema()
+        .load_committed_state(None)
+        .await?;
+    ensure!(
+        stored_accounts.is_empty(),
+        "Snapshot can only be imported into the empty database"
+    );
+
+    let client = Client::new(peer_url.to_owned());
+    let auth_token = auth_token(&config.api.rest.snapshots_secret_auth)?;
+    let metadata = client.latest_snapshot(&auth_token).await?;
+    let block_number = metadata.block_number();
+    vlog::info!(
+        "Syncing the snapshot at block #{}: {} accounts in {} chunks",
+        *block_number,
+        metadata.accounts_count,
+        metadata.chunks_count
+    );
+    let eth_gateway = EthereumGateway::from_config(config);
+    check_l1_commitment(&eth_gateway, config, &metadata).await?;
+    let token_ids = check_tokens(&eth_gateway, config, &metadata.tokens).await?;
+
+    let snapshot_dir = SnapshotDir::open(snapshot_dir, block_number)?;
+    let mut importer = SnapshotImporter::new(token_ids);
+    // Nothing is committed unless the whole snapshot matches the block.
+    let mut transaction = storage.start_transaction().await?;
+    import_accounts(
+        &mut transaction,
+        &client,
+        &auth_token,
+        &snapshot_dir,
+        &metadata,
+        &mut importer,
+    )
+    .await?;
+    if let Err(err) = importer.check(&metadata) {
+        snapshot_dir.remove()?;
+        return Err(err);
+    }
+    drop(importer);
+
+    let block = metadata.block;
+    for token in metadata.tokens {
+        transaction.tokens_schema().store_token(token).await?;
+    }
+    transaction
+        .chain()
+        .block_schema()
+        .save_block(block.clone())
+        .await?;
+    // The snapshot block is verified, so it's stored as executed on L1 the same way
+    // the data restore does, and the eth sender continues from the next block.
+    transaction
+        .data_restore_schema()
+        .save_block_operations(
+            BlocksCommitOperation {
+                last_committed_block: block.clone(),
+                blocks: vec![block.clone()],
+            },
+            BlocksExecuteOperation {
+                blocks: vec![block],
+            },
+        )
+        .await?;
+    transaction
+        .data_restore_schema()
+        .initialize_eth_stats(block_number, block_number, block_number)
+        .await?;
+    transaction.commit().await?;
+
+    snapshot_dir.remove()?;
+    vlog::info!("Snapshot at block #{} is imported", *block_number);
+    Ok(block_number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::BigUint;
+    use zksync_types::Nonce;
+
+    fn account(address: u8, balances: &[(u32, u64)]) -> Account {
+        let mut account = Account::default_with_address(&Address::repeat_byte(address));
+        account.nonce = Nonce(u32::from(address));
+        for &(token, balance) in balances {
+            account.set_balance(TokenId(token), BigUint::from(balance));
+        }
+        account
+    }
+
+    fn chunk(index: usize, accounts: Vec<(AccountId, Account)>) -> SnapshotChunk {
+        SnapshotChunk {
+            block_number: BlockNumber(5),
+            index,
+            accounts,
+        }
+    }
+
+    fn importer() -> SnapshotImporter {
+        SnapshotImporter::new(vec![TokenId(0), TokenId(1)].into_iter().collect())
+    }
+
+    /// Checks that the tree rebuilt from the chunks matches the original one, and the updates
+    /// restore the accounts.
+    #[test]
+    fn importer_rebuilds_tree() {
+        let accounts = vec![
+            (AccountId(0), account(1, &[(0, 100)])),
+            (AccountId(1), account(2, &[(0, 5), (1, 7)])),
+            (AccountId(3), account(3, &[])),
+        ];
+        let mut expected_tree = AccountTree::new(params::account_tree_depth());
+        for (id, account) in &accounts {
+            expected_tree.insert(**id, account.clone());
+        }
+
+        let mut importer = importer();
+        let mut updates = importer
+            .add_chunk(chunk(0, accounts[..2].to_vec()))
+            .unwrap();
+        updates.extend(
+            importer
+                .add_chunk(chunk(1, accounts[2..].to_vec()))
+                .unwrap(),
+        );
+        assert_eq!(importer.accounts_count, accounts.len());
+        assert_eq!(importer.tree.root_hash(), expected_tree.root_hash());
+
+        for (id, account) in accounts {
+            let account_updates: Vec<_> = updates
+                .iter()
+                .filter(|(update_id, _)| *update_id == id)
+                .map(|(_, update)| update.clone())
+                .collect();
+            assert_eq!(
+                Account::apply_updates(None, &account_updates),
+                Some(account)
+            );
+        }
+    }
+
+    #[test]
+    fn importer_rejects_invalid_accounts() {
+        // The accounts must be ordered across the chunks.
+        let mut importer = importer();
+        importer
+            .add_chunk(chunk(0, vec![(AccountId(2), account(1, &[]))]))
+            .unwrap();
+        assert!(importer
+            .add_chunk(chunk(1, vec![(AccountId(2), account(2, &[]))]))
+            .is_err());
+        assert!(importer
+            .add_chunk(chunk(1, vec![(AccountId(1), account(2, &[]))]))
+            .is_err());
+
+        // The tokens must be checked on L1.
+        assert!(importer()
+            .add_chunk(chunk(0, vec![(AccountId(0), account(1, &[(2, 1)]))]))
+            .is_err());
+        // The ID must fit into the tree.
+        let invalid_id = AccountId(*params::max_account_id() + 1);
+        assert!(importer()
+            .add_chunk(chunk(0, vec![(invalid_id, account(1, &[]))]))
+            .is_err());
+    }
+
+    /// Checks that the saved chunks are kept for the same snapshot only.
+    #[test]
+    fn snapshot_dir_resume() {
+        let path = std::env::temp_dir().join(format!("snapshot_sync_test_{}", std::process::id()));
+        let saved_chunk = chunk(0, vec![(AccountId(0), account(1, &[(0, 1)]))]);
+
+        let snapshot_dir = SnapshotDir::open(&path, BlockNumber(5)).unwrap();
+        assert!(snapshot_dir.load_chunk(0).unwrap().is_none());
+        snapshot_dir.save_chunk(&saved_chunk).unwrap();
+
+        let snapshot_dir = SnapshotDir::open(&path, BlockNumber(5)).unwrap();
+        let loaded_chunk = snapshot_dir.load_chunk(0).unwrap().unwrap();
+        assert_eq!(loaded_chunk.accounts, saved_chunk.accounts);
+
+        let snapshot_dir = SnapshotDir::open(&path, BlockNumber(6)).unwrap();
+        assert!(snapshot_dir.load_chunk(0).unwrap().is_none());
+        snapshot_dir.remove().unwrap();
+    }
+}
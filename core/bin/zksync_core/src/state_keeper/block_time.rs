@@ -0,0 +1,171 @@
+//! Block time controller keeps the average time the blocks take at the target.
+//!
+//! The block is sealed either once it's full or once the limit of the miniblock iterations
+//! is reached. The block time is measured from the execution of its first transaction to its
+//! sealing, so the idle time without any transactions, which the iterations limit doesn't affect,
+//! isn't counted. The controller tracks the moving average of the block times and scales
+//! the iterations limit by the ratio of the target time to the average one, keeping the limit
+//! within the configured bounds. The blocks sealed without any transactions are not counted.
+//!
+//! Note that the iterations are only counted for the miniblocks executing transactions, so under
+//! the low load the block time still depends on how often the transactions arrive.
+
+// Built-in deps
+use std::time::{Duration, Instant};
+
+/// Weight of the latest interval in the moving average.
+const SMOOTHING_FACTOR: f64 = 0.2;
+/// A single block time is counted as at most this many target times,
+/// so a single slow block doesn't collapse the limit.
+const MAX_INTERVAL_RATIO: f64 = 2.0;
+
+#[derive(Debug)]
+pub struct BlockTimeController {
+    target: Duration,
+    min_iterations: usize,
+    max_iterations: usize,
+    /// Current limit, kept fractional so that the small adjustments accumulate.
+    iterations: f64,
+    /// Moving average of the block times, in seconds.
+    average_interval: Option<f64>,
+    /// Time the first transaction of the pending block was executed at.
+    block_started_at: Option<Instant>,
+}
+
+impl BlockTimeController {
+    pub fn new(
+        target: Duration,
+        initial_iterations: usize,
+        min_iterations: usize,
+        max_iterations: usize,
+    ) -> Self {
+        let iterations = initial_iterations.max(min_iterations).min(max_iterations);
+        Self {
+            target,
+            min_iterations,
+            max_iterations,
+            iterations: iterations as f64,
+            average_interval: None,
+            block_started_at: None,
+        }
+    }
+
+    /// Current limit of the miniblock iterations before sealing the block.
+    pub fn iterations_limit(&self) -> usize {
+        self.iterations.round() as usize
+    }
+
+    /// Marks the pending block as having the transactions executed since the given moment.
+    /// Only the first call for the block has an effect.
+    pub fn txs_executed(&mut self, executed_at: Instant) {
+        self.block_started_at.get_or_insert(executed_at);
+    }
+
+    /// Accounts the block sealed at the given moment and adjusts the iterations limit.
+    /// Returns the new limit.
+    pub fn block_sealed(&mut self, sealed_at: Instant) -> usize {
+        match self.block_started_at.take() {
+            Some(started_at) => self.update(sealed_at.saturating_duration_since(started_at)),
+            None => self.iterations_limit(),
+        }
+    }
+
+    fn update(&mut self, interval: Duration) -> usize {
+        let target = self.target.as_secs_f64();
+        let interval = interval.as_secs_f64().min(target * MAX_INTERVAL_RATIO);
+        let average = match self.average_interval {
+            Some(average) => average + SMOOTHING_FACTOR * (interval - average),
+            None => interval,
+        };
+        self.average_interval = Some(average);
+
+        if average > 0.0 {
+            self.iterations = (self.iterations * target / average)
+                .max(self.min_iterations as f64)
+                .min(self.max_iterations as f64);
+        }
+
+        metrics::histogram!(
+            "state_keeper.block_interval",
+            Duration::from_secs_f64(interval)
+        );
+        metrics::gauge!("state_keeper.block_interval_average", average);
+        metrics::gauge!("state_keeper.block_interval_target", target);
+        metrics::gauge!(
+            "state_keeper.miniblock_iterations_limit",
+            self.iterations_limit() as f64
+        );
+        self.iterations_limit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn controller() -> BlockTimeController {
+        BlockTimeController::new(Duration::from_secs(10), 10, 2, 40)
+    }
+
+    #[test]
+    fn slow_blocks_decrease_limit() {
+        let mut controller = controller();
+        let limit = controller.update(Duration::from_secs(20));
+        assert_eq!(limit, 5);
+
+        // The limit never drops below the lower bound.
+        for _ in 0..20 {
+            controller.update(Duration::from_secs(20));
+        }
+        assert_eq!(controller.iterations_limit(), 2);
+    }
+
+    #[test]
+    fn fast_blocks_increase_limit() {
+        let mut controller = controller();
+        let limit = controller.update(Duration::from_secs(5));
+        assert_eq!(limit, 20);
+
+        // The limit never exceeds the upper bound.
+        for _ in 0..20 {
+            controller.update(Duration::from_secs(5));
+        }
+        assert_eq!(controller.iterations_limit(), 40);
+    }
+
+    #[test]
+    fn on_target_blocks_keep_limit() {
+        let mut controller = controller();
+        for _ in 0..10 {
+            assert_eq!(controller.update(Duration::from_secs(10)), 10);
+        }
+    }
+
+    #[test]
+    fn slow_block_is_capped() {
+        let mut controller = controller();
+        // An hour-long block counts as twice the target time.
+        assert_eq!(controller.update(Duration::from_secs(3600)), 5);
+    }
+
+    #[test]
+    fn block_time_is_measured_from_first_tx() {
+        let mut controller = controller();
+        let sealed_at = Instant::now();
+        // The empty blocks are not counted.
+        assert_eq!(controller.block_sealed(sealed_at), 10);
+
+        // The idle time before the first transaction is not counted.
+        let started_at = sealed_at + Duration::from_secs(3600);
+        controller.txs_executed(started_at);
+        controller.txs_executed(started_at + Duration::from_secs(4));
+        assert_eq!(
+            controller.block_sealed(started_at + Duration::from_secs(5)),
+            20
+        );
+        assert_eq!(
+            controller.block_sealed(started_at + Duration::from_secs(3600)),
+            20
+        );
+    }
+}
@@ -1,5 +1,10 @@
-use std::collections::{HashMap, VecDeque};
-use std::time::Instant;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ops::Range;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::time::{Duration, Instant};
 // External uses
 use futures::{
     channel::{mpsc, oneshot},
@@ -9,6 +14,7 @@ use futures::{
 use itertools::Itertools;
 use tokio::task::JoinHandle;
 // Workspace uses
+use vlog::Instrument;
 use zksync_crypto::{
     convert::FeConvert,
     ff::{self, PrimeField, PrimeFieldRepr},
@@ -17,38 +23,126 @@ use zksync_crypto::{
 };
 use zksync_state::state::{CollectedFee, OpSuccess, ZkSyncState};
 use zksync_storage::ConnectionPool;
+use zksync_tree_cache::TreeCacheStorage;
 use zksync_types::{
     block::{
-        Block, BlockMetadata, ExecutedOperations, ExecutedPriorityOp, ExecutedTx,
-        PendingBlock as SendablePendingBlock,
+        smallest_block_size_for_chunks, Block, BlockMetadata, ExecutedOperations,
+        ExecutedPriorityOp, ExecutedTx, PendingBlock as SendablePendingBlock,
     },
     gas_counter::GasCounter,
     helpers::reverse_updates,
+    l1_message::L1Message,
     mempool::SignedTxVariant,
+    priority_ops::{check_serial_id_continuity, SerialIdContinuityError},
     tx::{TxHash, ZkSyncTx},
     Account, AccountId, AccountTree, AccountUpdate, AccountUpdates, Address, BlockNumber,
     PriorityOp, SignedZkSyncTx, Transfer, TransferOp, H256,
 };
+use zksync_utils::{
+    shutdown::{DrainGuard, ShutdownSignal},
+    supervisor::SharedReceiver,
+};
 // Local uses
 use crate::{
     committer::{AppliedUpdatesRequest, BlockCommitRequest, CommitRequest},
-    mempool::ProposedBlock,
+    mempool::{
+        tx_dependencies::{find_nonce_hole, order_by_dependencies, senders, NonceHole},
+        ProposedBlock,
+    },
 };
 use std::time::{SystemTime, UNIX_EPOCH};
 use zksync_state::error::{OpError, TxBatchError};
 
+pub use self::block_time::BlockTimeController;
+
+mod block_time;
 #[cfg(test)]
 mod tests;
 
+/// ID of the fee account created in the genesis block.
+const GENESIS_FEE_ACCOUNT_ID: AccountId = AccountId(0);
+
 pub enum ExecutedOpId {
     Transaction(TxHash),
     PriorityOp(u64),
 }
 
+/// Switch that stops the block production once triggered.
+/// It is shared between the state keeper and the components detecting the conditions
+/// under which no new blocks may be created, e.g. the divergence of the local state from L1.
+///
+/// The halt is persisted, so the block production stays halted after the server restarts
+/// until the blocks are reverted with the `block_revert` tool.
+#[derive(Clone, Default)]
+pub struct BlockProductionHalt {
+    halted: Arc<AtomicBool>,
+    /// Pool to persist the halt with, the halt is kept in memory only if it's not set.
+    db_pool: Option<ConnectionPool>,
+}
+
+impl BlockProductionHalt {
+    /// Loads the persisted halt from the database.
+    pub async fn restore(db_pool: ConnectionPool) -> anyhow::Result<Self> {
+        let mut storage = db_pool.access_storage().await?;
+        let reason = storage
+            .chain()
+            .block_schema()
+            .load_block_production_halt()
+            .await?;
+        drop(storage);
+
+        if let Some(reason) = &reason {
+            vlog::error!(
+                "Block production was halted before the restart: {}. \
+                 Revert the blocks to resume it",
+                reason
+            );
+        }
+        Ok(Self {
+            halted: Arc::new(AtomicBool::new(reason.is_some())),
+            db_pool: Some(db_pool),
+        })
+    }
+
+    /// Halts the block production. The halt applies immediately, even if it can't be persisted.
+    pub async fn halt(&self, reason: &str) {
+        self.halted.store(true, Ordering::SeqCst);
+
+        let db_pool = match &self.db_pool {
+            Some(db_pool) => db_pool,
+            None => return,
+        };
+        let result = async {
+            let mut storage = db_pool.access_storage().await?;
+            storage
+                .chain()
+                .block_schema()
+                .halt_block_production(reason)
+                .await
+        }
+        .await;
+        if let Err(err) = result {
+            vlog::error!("Unable to persist the block production halt: {}", err);
+        }
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted.load(Ordering::SeqCst)
+    }
+}
+
+/// Block the proposed transactions are executed in.
+#[derive(Debug, Clone, Copy)]
+pub struct PendingBlockInfo {
+    pub number: BlockNumber,
+    pub timestamp: u64,
+}
+
 pub enum StateKeeperRequest {
     GetAccount(Address, oneshot::Sender<Option<(AccountId, Account)>>),
-    GetPendingBlockTimestamp(oneshot::Sender<u64>),
+    GetPendingBlockInfo(oneshot::Sender<PendingBlockInfo>),
     GetLastUnprocessedPriorityOp(oneshot::Sender<u64>),
+    GetNextL1MessageId(oneshot::Sender<u64>),
     ExecuteMiniBlock(ProposedBlock),
     SealBlock,
     GetCurrentState(oneshot::Sender<ZkSyncStateInitParams>),
@@ -59,7 +153,11 @@ struct PendingBlock {
     success_operations: Vec<ExecutedOperations>,
     failed_txs: Vec<ExecutedTx>,
     account_updates: AccountUpdates,
+    /// Size of the block (in chunks) the pending block is expected to fit into.
+    block_size: usize,
     chunks_left: usize,
+    /// Whether an operation was rejected because it didn't fit into the block.
+    overflowed: bool,
     pending_op_block_index: u32,
     unprocessed_priority_op_before: u64,
     pending_block_iteration: usize,
@@ -70,23 +168,26 @@ struct PendingBlock {
     collected_fees: Vec<CollectedFee>,
     /// Number of stored account updates in the db (from `account_updates` field)
     stored_account_updates: usize,
+    /// Ranges of `account_updates` made by the executed transactions.
+    tx_updates: Vec<(TxHash, Range<usize>)>,
+    /// Number of stored ranges in the db (from `tx_updates` field)
+    stored_tx_updates: usize,
     previous_block_root_hash: H256,
     timestamp: u64,
+    /// Messages sent from L1 delivered in the block that are not sent to the committer yet.
+    l1_messages: Vec<L1Message>,
 }
 
 impl PendingBlock {
     fn new(
         unprocessed_priority_op_before: u64,
-        available_chunks_sizes: &[usize],
+        block_size: usize,
         previous_block_root_hash: H256,
         timestamp: u64,
         should_include_last_transfer: bool,
     ) -> Self {
         // TransferOp chunks are subtracted to reserve space for last transfer.
-        let mut chunks_left = *available_chunks_sizes
-            .iter()
-            .max()
-            .expect("Expected at least one block chunks size");
+        let mut chunks_left = block_size;
         if should_include_last_transfer {
             chunks_left -= TransferOp::CHUNKS;
         }
@@ -94,7 +195,9 @@ impl PendingBlock {
             success_operations: Vec::new(),
             failed_txs: Vec::new(),
             account_updates: Vec::new(),
+            block_size,
             chunks_left,
+            overflowed: false,
             pending_op_block_index: 0,
             unprocessed_priority_op_before,
             pending_block_iteration: 0,
@@ -102,12 +205,24 @@ impl PendingBlock {
             fast_processing_required: false,
             collected_fees: Vec::new(),
             stored_account_updates: 0,
+            tx_updates: Vec::new(),
+            stored_tx_updates: 0,
             previous_block_root_hash,
             timestamp,
+            l1_messages: Vec::new(),
         }
     }
 }
 
+/// Transaction deferred by the state keeper because it follows a nonce hole.
+#[derive(Debug, Clone)]
+struct DeferredTx {
+    variant: SignedTxVariant,
+    hole: NonceHole,
+    /// Number of the miniblocks the transaction has been deferred for.
+    deferrals: usize,
+}
+
 pub fn system_time_timestamp() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -120,17 +235,34 @@ pub struct ZkSyncStateKeeper {
     /// Current plasma state
     state: ZkSyncState,
 
+    /// Address of the account the fees are collected to.
+    fee_account_address: Address,
+    /// ID of the account the fees are collected to. Until the configured fee account is created,
+    /// fees are collected to the genesis fee account.
     fee_account_id: AccountId,
     current_unprocessed_priority_op: u64,
+    /// Serial ID of the first message sent from L1 which is not delivered yet.
+    next_l1_message_id: u64,
 
     pending_block: PendingBlock,
 
-    rx_for_blocks: mpsc::Receiver<StateKeeperRequest>,
+    rx_for_blocks: SharedReceiver<StateKeeperRequest>,
     tx_for_commitments: mpsc::Sender<CommitRequest>,
 
     available_block_chunk_sizes: Vec<usize>,
     max_miniblock_iterations: usize,
     fast_miniblock_iterations: usize,
+    /// Whether the size of each block is chosen based on the load instead of always
+    /// using the maximum block size.
+    adaptive_block_size: bool,
+    /// Adjusts `max_miniblock_iterations` to keep the target interval between the blocks.
+    block_time: Option<BlockTimeController>,
+    /// Transactions waiting for the preceding transactions of their accounts to be executed.
+    deferred_txs: Vec<DeferredTx>,
+    /// Number of the miniblocks the transactions may wait for the preceding ones for.
+    /// Fixed at the configured iterations limit, so it doesn't follow the limit adjusted by
+    /// the block time controller.
+    max_deferrals: usize,
 
     // Two fields below are for optimization: we don't want to overwrite all the block contents over and over.
     // With these fields we'll be able save the diff between two pending block states only.
@@ -141,6 +273,13 @@ pub struct ZkSyncStateKeeper {
 
     /// ZK sync account that is used to create last transfer before sealing block (e.g. to change block hash)
     tx_signer: Option<(Address, PrivateKey)>,
+
+    /// Signal to seal the pending block and stop producing new blocks.
+    shutdown: Option<ShutdownSignal>,
+    /// Guard dropped once the state keeper has sent its last block to the committer.
+    drain_guard: Option<DrainGuard>,
+    /// Switch to stop producing new blocks without shutting down the server.
+    production_halt: BlockProductionHalt,
 }
 
 #[derive(Debug, Clone)]
@@ -149,6 +288,7 @@ pub struct ZkSyncStateInitParams {
     pub acc_id_by_addr: HashMap<Address, AccountId>,
     pub last_block_number: BlockNumber,
     pub unprocessed_priority_op: u64,
+    pub next_l1_message_id: u64,
 }
 
 impl Default for ZkSyncStateInitParams {
@@ -164,6 +304,7 @@ impl ZkSyncStateInitParams {
             acc_id_by_addr: HashMap::new(),
             last_block_number: BlockNumber(0),
             unprocessed_priority_op: 0,
+            next_l1_message_id: 0,
         }
     }
 
@@ -196,9 +337,10 @@ impl ZkSyncStateInitParams {
 
     pub async fn restore_from_db(
         storage: &mut zksync_storage::StorageProcessor<'_>,
+        tree_cache: &TreeCacheStorage,
     ) -> Result<Self, anyhow::Error> {
         let mut init_params = Self::new();
-        init_params.load_from_db(storage).await?;
+        init_params.load_from_db(storage, tree_cache).await?;
 
         Ok(init_params)
     }
@@ -206,75 +348,99 @@ impl ZkSyncStateInitParams {
     async fn load_account_tree(
         &mut self,
         storage: &mut zksync_storage::StorageProcessor<'_>,
+        tree_cache: &TreeCacheStorage,
     ) -> Result<BlockNumber, anyhow::Error> {
-        let (last_cached_block_number, accounts) = if let Some((block, _)) = storage
-            .chain()
-            .block_schema()
-            .get_account_tree_cache()
-            .await?
-        {
-            storage
-                .chain()
-                .state_schema()
-                .load_committed_state(Some(block))
-                .await?
-        } else {
-            storage.chain().state_schema().load_verified_state().await?
+        let last_cached_block_number = match tree_cache.load_latest(storage).await? {
+            Some((block, account_tree_cache)) => {
+                // The whole tree is restored from the cache storage if it keeps the accounts,
+                // otherwise the accounts are loaded from the database.
+                let accounts = match tree_cache.load_accounts(block)? {
+                    Some(accounts) => accounts,
+                    None => storage
+                        .chain()
+                        .state_schema()
+                        .load_committed_state(Some(block))
+                        .await?
+                        .1
+                        .into_iter()
+                        .collect(),
+                };
+                for (id, account) in accounts {
+                    self.insert_account(id, account);
+                }
+                self.tree
+                    .set_internals(serde_json::from_value(account_tree_cache)?);
+                Some(block)
+            }
+            None => None,
+        };
+        let restored_block_number = match last_cached_block_number {
+            Some(block) => block,
+            None => {
+                let (block, accounts) =
+                    storage.chain().state_schema().load_verified_state().await?;
+                for (id, account) in accounts {
+                    self.insert_account(id, account);
+                }
+                block
+            }
         };
 
-        for (id, account) in accounts {
-            self.insert_account(id, account);
-        }
-
-        if let Some(account_tree_cache) = storage
+        // Only the accounts updated since the restored block are loaded from the database.
+        let block_number = match storage
             .chain()
-            .block_schema()
-            .get_account_tree_cache_block(last_cached_block_number)
-            .await?
+            .state_schema()
+            .load_state_diff(restored_block_number, None)
+            .await
+            .map_err(|e| anyhow::format_err!("couldn't load committed state diff: {}", e))?
         {
-            self.tree
-                .set_internals(serde_json::from_value(account_tree_cache)?);
-        } else {
+            Some((block_number, account_updates)) => {
+                let mut updated_accounts = HashMap::new();
+                for (id, update) in account_updates {
+                    let account = updated_accounts
+                        .remove(&id)
+                        .unwrap_or_else(|| self.tree.get(*id).cloned());
+                    updated_accounts.insert(id, Account::apply_update(account, update));
+                }
+                for (id, account) in updated_accounts {
+                    match account {
+                        Some(account) => {
+                            self.remove_account(id);
+                            self.insert_account(id, account);
+                        }
+                        None => {
+                            self.remove_account(id);
+                        }
+                    }
+                }
+                block_number
+            }
+            None => restored_block_number,
+        };
+
+        // The cache is refreshed if it's missing, or if the storage keeps the accounts of the tree,
+        // so the next restore doesn't need to apply the updates again.
+        let refresh_cache = match last_cached_block_number {
+            Some(block) => tree_cache.stores_accounts() && block != block_number,
+            None => true,
+        };
+        if refresh_cache {
             self.tree.root_hash();
             let account_tree_cache = self.tree.get_internals();
-            storage
-                .chain()
-                .block_schema()
-                .store_account_tree_cache(
-                    last_cached_block_number,
+            tree_cache
+                .store(
+                    storage,
+                    block_number,
                     serde_json::to_value(account_tree_cache)?,
                 )
                 .await?;
-        }
-
-        let (block_number, accounts) = storage
-            .chain()
-            .state_schema()
-            .load_committed_state(None)
-            .await
-            .map_err(|e| anyhow::format_err!("couldn't load committed state: {}", e))?;
-
-        if block_number != last_cached_block_number {
-            if let Some((_, account_updates)) = storage
-                .chain()
-                .state_schema()
-                .load_state_diff(last_cached_block_number, Some(block_number))
-                .await?
-            {
-                let mut updated_accounts = account_updates
-                    .into_iter()
-                    .map(|(id, _)| id)
-                    .collect::<Vec<_>>();
-                updated_accounts.sort_unstable();
-                updated_accounts.dedup();
-                for idx in updated_accounts {
-                    if let Some(acc) = accounts.get(&idx).cloned() {
-                        self.insert_account(idx, acc);
-                    } else {
-                        self.remove_account(idx);
-                    }
-                }
-            }
+            tree_cache.store_accounts(
+                block_number,
+                self.tree
+                    .items
+                    .iter()
+                    .map(|(id, account)| (AccountId(*id as u32), account)),
+            )?;
         }
 
         // We have to load actual number of the last committed block, since above we load the block number from state,
@@ -309,16 +475,21 @@ impl ZkSyncStateInitParams {
     async fn load_from_db(
         &mut self,
         storage: &mut zksync_storage::StorageProcessor<'_>,
+        tree_cache: &TreeCacheStorage,
     ) -> Result<(), anyhow::Error> {
-        let block_number = self.load_account_tree(storage).await?;
+        let block_number = self.load_account_tree(storage, tree_cache).await?;
         self.last_block_number = block_number;
         self.unprocessed_priority_op =
             Self::unprocessed_priority_op_id(storage, block_number).await?;
+        // Messages of the pending block are stored as well, so they're not delivered twice.
+        self.next_l1_message_id = storage.l1_messages_schema().next_message_id().await?;
 
         vlog::info!(
-            "Loaded committed state: last block number: {}, unprocessed priority op: {}",
+            "Loaded committed state: last block number: {}, unprocessed priority op: {}, \
+             next L1 message: {}",
             *self.last_block_number,
-            self.unprocessed_priority_op
+            self.unprocessed_priority_op,
+            self.next_l1_message_id
         );
         Ok(())
     }
@@ -385,7 +556,7 @@ impl ZkSyncStateKeeper {
     pub fn new(
         initial_state: ZkSyncStateInitParams,
         fee_account_address: Address,
-        rx_for_blocks: mpsc::Receiver<StateKeeperRequest>,
+        rx_for_blocks: impl Into<SharedReceiver<StateKeeperRequest>>,
         tx_for_commitments: mpsc::Sender<CommitRequest>,
         available_block_chunk_sizes: Vec<usize>,
         max_miniblock_iterations: usize,
@@ -406,9 +577,19 @@ impl ZkSyncStateKeeper {
             initial_state.last_block_number + 1,
         );
 
-        let (fee_account_id, _) = state
-            .get_account_by_address(&fee_account_address)
-            .expect("Fee account should be present in the account tree");
+        // The fee account can be changed after genesis, e.g. to separate it from the operator account.
+        // Such an account has to be created with a deposit or a transfer first.
+        let fee_account_id = match state.get_account_by_address(&fee_account_address) {
+            Some((id, _)) => id,
+            None => {
+                vlog::warn!(
+                    "Fee account {:?} is not in the account tree yet, fees are collected to the genesis \
+                     fee account until it's created",
+                    fee_account_address
+                );
+                GENESIS_FEE_ACCOUNT_ID
+            }
+        };
         // Keeper starts with the NEXT block
         // we leave space for last tx
         let mut be_bytes = [0u8; 32];
@@ -420,13 +601,15 @@ impl ZkSyncStateKeeper {
         let previous_root_hash = H256::from(be_bytes);
         let keeper = ZkSyncStateKeeper {
             state,
+            fee_account_address,
             fee_account_id,
             current_unprocessed_priority_op: initial_state.unprocessed_priority_op,
-            rx_for_blocks,
+            next_l1_message_id: initial_state.next_l1_message_id,
+            rx_for_blocks: rx_for_blocks.into(),
             tx_for_commitments,
             pending_block: PendingBlock::new(
                 initial_state.unprocessed_priority_op,
-                &available_block_chunk_sizes,
+                *available_block_chunk_sizes.last().unwrap(),
                 previous_root_hash,
                 system_time_timestamp(),
                 tx_signer.is_some(),
@@ -434,10 +617,18 @@ impl ZkSyncStateKeeper {
             available_block_chunk_sizes,
             max_miniblock_iterations,
             fast_miniblock_iterations,
+            adaptive_block_size: false,
+            block_time: None,
+            deferred_txs: Vec::new(),
+            max_deferrals: max_miniblock_iterations,
 
             success_txs_pending_len: 0,
             failed_txs_pending_len: 0,
             tx_signer,
+
+            shutdown: None,
+            drain_guard: None,
+            production_halt: BlockProductionHalt::default(),
         };
 
         let root = keeper.state.root_hash();
@@ -446,6 +637,44 @@ impl ZkSyncStateKeeper {
         keeper
     }
 
+    /// Makes the state keeper seal the pending block once the shutdown is requested,
+    /// so the executed operations are persisted before the process exits.
+    /// The drain guard is absent if the state keeper is restarted after a failure:
+    /// the guard is already released by the failed instance.
+    pub fn with_shutdown(
+        mut self,
+        signal: ShutdownSignal,
+        drain_guard: Option<DrainGuard>,
+    ) -> Self {
+        self.shutdown = Some(signal);
+        self.drain_guard = drain_guard;
+        self
+    }
+
+    /// Makes the state keeper stop executing transactions and sealing blocks
+    /// once the switch is triggered. Read requests are still served.
+    pub fn with_production_halt(mut self, production_halt: BlockProductionHalt) -> Self {
+        self.production_halt = production_halt;
+        self
+    }
+
+    /// Makes the state keeper choose the size of each block based on the load observed in the
+    /// previous one, so the blocks are not padded to the maximum size when the load is low.
+    /// The block that is currently pending still uses the maximum size.
+    pub fn with_adaptive_block_size(mut self) -> Self {
+        self.adaptive_block_size = true;
+        self
+    }
+
+    /// Makes the state keeper adjust the miniblock iterations limit after each sealed block,
+    /// so the average interval between the blocks stays at the target of the controller.
+    /// Blocks requiring the fast processing are still sealed within `fast_miniblock_iterations`.
+    pub fn with_block_time_controller(mut self, controller: BlockTimeController) -> Self {
+        self.max_miniblock_iterations = controller.iterations_limit();
+        self.block_time = Some(controller);
+        self
+    }
+
     pub async fn initialize(&mut self, pending_block: Option<SendablePendingBlock>) {
         let start = Instant::now();
         if let Some(pending_block) = pending_block {
@@ -474,6 +703,7 @@ impl ZkSyncStateKeeper {
                 }
             }
             self.pending_block.stored_account_updates = self.pending_block.account_updates.len();
+            self.pending_block.stored_tx_updates = self.pending_block.tx_updates.len();
 
             vlog::info!(
                 "Executed restored proposed block: {} transactions, {} priority operations, {} failed transactions",
@@ -517,11 +747,15 @@ impl ZkSyncStateKeeper {
             address: *fee_account_address,
             nonce: fee_account.nonce,
         };
-        accounts.insert(AccountId(0), fee_account);
+        accounts.insert(GENESIS_FEE_ACCOUNT_ID, fee_account);
         transaction
             .chain()
             .state_schema()
-            .commit_state_update(BlockNumber(0), &[(AccountId(0), db_account_update)], 0)
+            .commit_state_update(
+                BlockNumber(0),
+                &[(GENESIS_FEE_ACCOUNT_ID, db_account_update)],
+                0,
+            )
             .await
             .expect("db fail");
         transaction
@@ -549,17 +783,39 @@ impl ZkSyncStateKeeper {
         metrics::histogram!("state_keeper.create_genesis_block", start.elapsed());
     }
 
-    async fn run(mut self, pending_block: Option<SendablePendingBlock>) {
+    pub async fn run(mut self, pending_block: Option<SendablePendingBlock>) {
         self.initialize(pending_block).await;
 
-        while let Some(req) = self.rx_for_blocks.next().await {
+        let mut shutdown = self.shutdown.take();
+        loop {
+            let mut shutdown_requested = false;
+            let req = tokio::select! {
+                req = self.rx_for_blocks.next() => req,
+                _ = wait_for_shutdown(&mut shutdown) => {
+                    shutdown_requested = true;
+                    None
+                }
+            };
+            if shutdown_requested {
+                shutdown = None;
+                self.seal_for_shutdown().await;
+                continue;
+            }
+            let req = match req {
+                Some(req) => req,
+                None => break,
+            };
+
             match req {
                 StateKeeperRequest::GetAccount(addr, sender) => {
                     sender.send(self.account(&addr)).unwrap_or_default();
                 }
-                StateKeeperRequest::GetPendingBlockTimestamp(sender) => {
+                StateKeeperRequest::GetPendingBlockInfo(sender) => {
                     sender
-                        .send(self.pending_block.timestamp)
+                        .send(PendingBlockInfo {
+                            number: self.state.block_number,
+                            timestamp: self.pending_block.timestamp,
+                        })
                         .unwrap_or_default();
                 }
                 StateKeeperRequest::GetLastUnprocessedPriorityOp(sender) => {
@@ -567,11 +823,32 @@ impl ZkSyncStateKeeper {
                         .send(self.current_unprocessed_priority_op)
                         .unwrap_or_default();
                 }
+                StateKeeperRequest::GetNextL1MessageId(sender) => {
+                    sender.send(self.next_l1_message_id).unwrap_or_default();
+                }
+                StateKeeperRequest::ExecuteMiniBlock(_) | StateKeeperRequest::SealBlock
+                    if self.tx_for_commitments.is_closed() =>
+                {
+                    vlog::debug!("Server is shutting down, new blocks are not produced");
+                }
+                StateKeeperRequest::ExecuteMiniBlock(_) | StateKeeperRequest::SealBlock
+                    if self.production_halt.is_halted() =>
+                {
+                    vlog::warn!("Block production is halted, new blocks are not produced");
+                }
                 StateKeeperRequest::ExecuteMiniBlock(proposed_block) => {
-                    self.execute_proposed_block(proposed_block).await;
+                    let span = vlog::info_span!(
+                        "execute_miniblock",
+                        block_number = *self.state.block_number
+                    );
+                    self.execute_proposed_block(proposed_block)
+                        .instrument(span)
+                        .await;
                 }
                 StateKeeperRequest::SealBlock => {
-                    self.seal_pending_block().await;
+                    let span =
+                        vlog::info_span!("seal_block", block_number = *self.state.block_number);
+                    self.seal_pending_block().instrument(span).await;
                 }
                 StateKeeperRequest::GetCurrentState(sender) => {
                     sender.send(self.get_current_state()).unwrap_or_default();
@@ -580,6 +857,21 @@ impl ZkSyncStateKeeper {
         }
     }
 
+    /// Seals the pending block and closes the channel to the committer,
+    /// so the committer finishes once all the sent blocks are stored.
+    async fn seal_for_shutdown(&mut self) {
+        if !self.pending_block.success_operations.is_empty() {
+            vlog::info!(
+                "Sealing the pending block #{} before shutdown",
+                *self.state.block_number
+            );
+            let span = vlog::info_span!("seal_block", block_number = *self.state.block_number);
+            self.seal_pending_block().instrument(span).await;
+        }
+        self.tx_for_commitments.close_channel();
+        self.drain_guard = None;
+    }
+
     async fn execute_proposed_block(&mut self, proposed_block: ProposedBlock) {
         let start = Instant::now();
         let mut executed_ops = Vec::new();
@@ -590,13 +882,33 @@ impl ZkSyncStateKeeper {
         }
 
         // We want to store this variable before moving anything from the pending block.
-        let empty_proposed_block = proposed_block.is_empty();
+        let empty_proposed_block = proposed_block.is_empty() && self.deferred_txs.is_empty();
+        let mut l1_messages = proposed_block.l1_messages;
 
         let mut priority_op_queue = proposed_block
             .priority_ops
             .into_iter()
             .collect::<VecDeque<_>>();
         while let Some(priority_op) = priority_op_queue.pop_front() {
+            if let Err(err) = check_serial_id_continuity(
+                self.current_unprocessed_priority_op,
+                std::iter::once(priority_op.serial_id),
+            ) {
+                // The proposer must provide the operations right after the processed ones, so
+                // this is a bug either in the mempool or in the `eth_watch`. Executing such
+                // operations would produce a block rejected by the contract.
+                vlog::error!(
+                    serial_id = priority_op.serial_id,
+                    "Priority operation is not executed: {}",
+                    err
+                );
+                metrics::counter!("state_keeper.priority_op_continuity_violations", 1);
+                if let SerialIdContinuityError::Gap { .. } = err {
+                    // The rest of the operations can't be executed before the missing one.
+                    break;
+                }
+                continue;
+            }
             match self.apply_priority_op(priority_op) {
                 Ok(exec_op) => {
                     executed_ops.push(exec_op);
@@ -609,8 +921,40 @@ impl ZkSyncStateKeeper {
             }
         }
 
-        let mut tx_queue = proposed_block.txs.into_iter().collect::<VecDeque<_>>();
+        // Transactions deferred previously go together with the proposed ones, so the transactions
+        // filling the nonce holes are executed first.
+        let deferred_txs = std::mem::take(&mut self.deferred_txs);
+        let mut deferrals: HashMap<_, _> = deferred_txs
+            .iter()
+            .map(|deferred| (deferred.variant.hashes(), deferred.deferrals))
+            .collect();
+        let txs = deferred_txs
+            .into_iter()
+            .map(|deferred| deferred.variant)
+            .chain(proposed_block.txs)
+            .collect();
+
+        let mut tx_queue = order_by_dependencies(txs)
+            .into_iter()
+            .collect::<VecDeque<_>>();
         while let Some(variant) = tx_queue.pop_front() {
+            let hole = find_nonce_hole(&variant, |account_id| {
+                self.state
+                    .get_account(account_id)
+                    .map(|account| account.nonce)
+            });
+            if let Some(hole) = hole {
+                // Executing the transaction would fail with the nonce mismatch, while the missing
+                // transaction may still be proposed, so the transaction waits for it.
+                let deferrals = deferrals.remove(&variant.hashes()).unwrap_or_default() + 1;
+                self.deferred_txs.push(DeferredTx {
+                    variant,
+                    hole,
+                    deferrals,
+                });
+                continue;
+            }
+
             match &variant {
                 SignedTxVariant::Tx(tx) => {
                     match self.apply_tx(tx) {
@@ -645,13 +989,27 @@ impl ZkSyncStateKeeper {
             }
         }
 
+        executed_ops.append(&mut self.reject_stale_deferred_txs());
+        if let Some(block_time) = &mut self.block_time {
+            if !self.pending_block.success_operations.is_empty() {
+                block_time.txs_executed(start);
+            }
+        }
+
+        // Messages don't change the state, they're recorded in the block
+        // that is pending once the miniblock is executed.
+        self.next_l1_message_id += l1_messages.len() as u64;
+        self.pending_block.l1_messages.append(&mut l1_messages);
+
         if !self.pending_block.success_operations.is_empty() {
             self.pending_block.pending_block_iteration += 1;
         }
 
         // If pending block contains withdrawals we seal it faster
         let max_miniblock_iterations = if self.pending_block.fast_processing_required {
+            // The adjusted limit may be lower than the fast one.
             self.fast_miniblock_iterations
+                .min(self.max_miniblock_iterations)
         } else {
             self.max_miniblock_iterations
         };
@@ -668,9 +1026,77 @@ impl ZkSyncStateKeeper {
             }
         }
 
+        // Rate of this counter is the throughput of the state keeper (TPS).
+        metrics::counter!(
+            "state_keeper.executed_operations",
+            executed_ops.len() as u64
+        );
         metrics::histogram!("state_keeper.execute_proposed_block", start.elapsed());
     }
 
+    /// Rejects the deferred transactions whose nonce holes haven't been filled for a whole block,
+    /// together with the rest of the deferred transactions of the same accounts, which can't be
+    /// executed either. The rest of the deferred transactions keep waiting.
+    fn reject_stale_deferred_txs(&mut self) -> Vec<ExecutedOperations> {
+        let max_deferrals = self.max_deferrals;
+        let mut rejected_accounts: HashSet<_> = self
+            .deferred_txs
+            .iter()
+            .filter(|deferred| deferred.deferrals > max_deferrals)
+            .flat_map(|deferred| senders(&deferred.variant))
+            .collect();
+        if rejected_accounts.is_empty() {
+            metrics::gauge!("state_keeper.deferred_txs", self.deferred_txs.len() as f64);
+            return Vec::new();
+        }
+
+        // Batches may tie the transactions of several accounts together.
+        let mut rejected = Vec::new();
+        loop {
+            let (newly_rejected, deferred): (Vec<_>, Vec<_>) = self
+                .deferred_txs
+                .drain(..)
+                .partition(|deferred| !senders(&deferred.variant).is_disjoint(&rejected_accounts));
+            self.deferred_txs = deferred;
+            if newly_rejected.is_empty() {
+                break;
+            }
+            for deferred in &newly_rejected {
+                rejected_accounts.extend(senders(&deferred.variant));
+            }
+            rejected.extend(newly_rejected);
+        }
+
+        let mut executed_operations = Vec::new();
+        for DeferredTx { variant, hole, .. } in rejected {
+            let fail_reason = format!(
+                "Nonce mismatch: transaction with nonce {} of the account {} is not executed",
+                *hole.expected, *hole.account_id
+            );
+            let (txs, batch_id) = match variant {
+                SignedTxVariant::Tx(tx) => (vec![tx], None),
+                SignedTxVariant::Batch(batch) => (batch.txs, Some(batch.batch_id)),
+            };
+            metrics::counter!("state_keeper.rejected_dependent_txs", txs.len() as u64);
+            for tx in txs {
+                vlog::warn!("Failed to execute transaction: {:?}, {}", tx, fail_reason);
+                let failed_tx = ExecutedTx {
+                    signed_tx: tx,
+                    success: false,
+                    op: None,
+                    fail_reason: Some(fail_reason.clone()),
+                    block_index: None,
+                    created_at: chrono::Utc::now(),
+                    batch_id,
+                };
+                self.pending_block.failed_txs.push(failed_tx.clone());
+                executed_operations.push(ExecutedOperations::Tx(Box::new(failed_tx)));
+            }
+        }
+        metrics::gauge!("state_keeper.deferred_txs", self.deferred_txs.len() as f64);
+        executed_operations
+    }
+
     // Err if there is no space in current block
     fn apply_priority_op(
         &mut self,
@@ -678,7 +1104,7 @@ impl ZkSyncStateKeeper {
     ) -> Result<ExecutedOperations, PriorityOp> {
         let start = Instant::now();
         let chunks_needed = priority_op.data.chunks();
-        if self.pending_block.chunks_left < chunks_needed {
+        if !self.fits_into_pending_block(chunks_needed) {
             return Err(priority_op);
         }
 
@@ -712,6 +1138,13 @@ impl ZkSyncStateKeeper {
         let block_index = self.pending_block.pending_op_block_index;
         self.pending_block.pending_op_block_index += 1;
 
+        vlog::debug!(
+            serial_id = priority_op.serial_id,
+            eth_hash = %format!("{:#x}", priority_op.eth_hash),
+            block_number = *self.state.block_number,
+            block_index,
+            "Priority operation was executed"
+        );
         let exec_result = ExecutedOperations::PriorityOp(Box::new(ExecutedPriorityOp {
             op: executed_op,
             priority_op,
@@ -790,7 +1223,7 @@ impl ZkSyncStateKeeper {
 
         // If we can't add the tx to the block due to the size limit, we return this tx,
         // seal the block and execute it again.
-        if self.pending_block.chunks_left < chunks_needed {
+        if !self.fits_into_pending_block(chunks_needed) {
             return Err(());
         }
 
@@ -829,6 +1262,15 @@ impl ZkSyncStateKeeper {
             return Err(());
         }
 
+        // Check if we should mark this block as requiring fast processing.
+        // Fast withdrawals are sent in batches e.g. when fulfilling the fast withdrawal intents.
+        if txs
+            .iter()
+            .any(|tx| matches!(&tx.tx, ZkSyncTx::Withdraw(tx) if tx.fast))
+        {
+            self.pending_block.fast_processing_required = true;
+        }
+
         let all_updates = self.execute_txs_batch(txs, self.pending_block.timestamp);
 
         for (tx, tx_updates) in txs.iter().zip(all_updates) {
@@ -844,7 +1286,12 @@ impl ZkSyncStateKeeper {
                         .expect("We have already checked that we can include this tx");
 
                     self.pending_block.chunks_left -= executed_op.chunks();
+                    let first_update = self.pending_block.account_updates.len();
                     self.pending_block.account_updates.append(&mut updates);
+                    self.pending_block.tx_updates.push((
+                        tx.hash(),
+                        first_update..self.pending_block.account_updates.len(),
+                    ));
                     if let Some(fee) = fee {
                         self.pending_block.collected_fees.push(fee);
                     }
@@ -893,7 +1340,7 @@ impl ZkSyncStateKeeper {
 
         // If we can't add the tx to the block due to the size limit, we return this tx,
         // seal the block and execute it again.
-        if self.pending_block.chunks_left < chunks_needed {
+        if !self.fits_into_pending_block(chunks_needed) {
             return Err(());
         }
 
@@ -935,7 +1382,12 @@ impl ZkSyncStateKeeper {
                     .expect("We have already checked that we can include this tx");
 
                 self.pending_block.chunks_left -= chunks_needed;
+                let first_update = self.pending_block.account_updates.len();
                 self.pending_block.account_updates.append(&mut updates);
+                self.pending_block.tx_updates.push((
+                    tx.hash(),
+                    first_update..self.pending_block.account_updates.len(),
+                ));
                 if let Some(fee) = fee {
                     self.pending_block.collected_fees.push(fee);
                 }
@@ -972,15 +1424,80 @@ impl ZkSyncStateKeeper {
             }
         };
 
+        vlog::debug!(
+            tx_hash = %tx.hash().to_string(),
+            block_number = *self.state.block_number,
+            success = exec_result.get_executed_op().is_some(),
+            "Transaction was executed"
+        );
         metrics::histogram!("state_keeper.apply_tx", start.elapsed());
         Ok(exec_result)
     }
 
+    /// Checks whether the operation fits into the pending block.
+    /// An empty pending block is extended to the maximum size if needed, so any operation
+    /// that fits into the maximum block can be executed regardless of the chosen block size.
+    fn fits_into_pending_block(&mut self, chunks_needed: usize) -> bool {
+        let max_block_size = *self.available_block_chunk_sizes.last().unwrap();
+        if self.pending_block.chunks_left < chunks_needed
+            && self.pending_block.success_operations.is_empty()
+            && self.pending_block.block_size < max_block_size
+        {
+            self.pending_block.chunks_left += max_block_size - self.pending_block.block_size;
+            self.pending_block.block_size = max_block_size;
+        }
+
+        if self.pending_block.chunks_left < chunks_needed {
+            self.pending_block.overflowed = true;
+            return false;
+        }
+        true
+    }
+
+    /// Chooses the size of the block following the sealed one.
+    ///
+    /// With the adaptive block size, the block following the one that got full may use
+    /// the next larger size. Otherwise, the smallest size fitting all the chunks used by
+    /// the sealed block is chosen, so the low load doesn't result in the mostly empty blocks
+    /// of the maximum size, which take the same time to prove as the full ones.
+    fn next_block_size(&self, sealed_block: &PendingBlock) -> usize {
+        let max_block_size = *self.available_block_chunk_sizes.last().unwrap();
+        if !self.adaptive_block_size {
+            return max_block_size;
+        }
+
+        if sealed_block.overflowed || sealed_block.chunks_left == 0 {
+            self.available_block_chunk_sizes
+                .iter()
+                .copied()
+                .find(|&size| size > sealed_block.block_size)
+                .unwrap_or(max_block_size)
+        } else {
+            let used_chunks = sealed_block.block_size - sealed_block.chunks_left;
+            smallest_block_size_for_chunks(used_chunks, &self.available_block_chunk_sizes)
+        }
+    }
+
+    /// Switches to the configured fee account once it's created.
+    fn update_fee_account(&mut self) {
+        if let Some((id, _)) = self.state.get_account_by_address(&self.fee_account_address) {
+            if id != self.fee_account_id {
+                vlog::info!(
+                    "Fee account {:?} is created, fees are collected to the account #{}",
+                    self.fee_account_address,
+                    *id
+                );
+                self.fee_account_id = id;
+            }
+        }
+    }
+
     /// Finalizes the pending block, transforming it into a full block.
     async fn seal_pending_block(&mut self) {
         let start = Instant::now();
 
         // Apply fees of pending block
+        self.update_fee_account();
         let fee_updates = self
             .state
             .collect_fee(&self.pending_block.collected_fees, self.fee_account_id);
@@ -992,11 +1509,13 @@ impl ZkSyncStateKeeper {
         if let Err(e) = self.execute_transfer_to_change_block_hash() {
             vlog::error!("Failed to execute transfer to change block hash: {}", e);
         }
+        let next_block_size = self.next_block_size(&self.pending_block);
+        metrics::gauge!("state_keeper.next_block_size", next_block_size as f64);
         let mut pending_block = std::mem::replace(
             &mut self.pending_block,
             PendingBlock::new(
                 self.current_unprocessed_priority_op,
-                &self.available_block_chunk_sizes,
+                next_block_size,
                 H256::default(),
                 system_time_timestamp(),
                 self.tx_signer.is_some(),
@@ -1033,6 +1552,23 @@ impl ZkSyncStateKeeper {
             pending_block.timestamp,
         );
 
+        if let Err(err) = block.check_priority_ops_continuity() {
+            // Such block would be rejected by the contract. Restarting the state keeper would
+            // only execute the same operations again, so the block production is stopped until
+            // the operator investigates the issue.
+            vlog::error!(
+                block_number = *block.block_number,
+                "Refusing to seal the block violating the priority operations continuity, \
+                 block production is halted: {}",
+                err
+            );
+            metrics::counter!("state_keeper.halted_on_continuity_violation", 1);
+            self.production_halt
+                .halt("priority operations continuity violation")
+                .await;
+            return;
+        }
+
         self.pending_block.previous_block_root_hash = block.get_eth_encoded_root();
 
         let block_metadata = BlockMetadata {
@@ -1046,14 +1582,19 @@ impl ZkSyncStateKeeper {
         };
         let first_update_order_id = pending_block.stored_account_updates;
         let account_updates = pending_block.account_updates[first_update_order_id..].to_vec();
+        let tx_updates = pending_block.tx_updates[pending_block.stored_tx_updates..].to_vec();
         let applied_updates_request = AppliedUpdatesRequest {
             account_updates,
             first_update_order_id,
+            tx_updates,
+            l1_messages: std::mem::take(&mut pending_block.l1_messages),
         };
         pending_block.stored_account_updates = pending_block.account_updates.len();
+        pending_block.stored_tx_updates = pending_block.tx_updates.len();
         *self.state.block_number += 1;
 
         vlog::info!(
+            block_number = *block_commit_request.block.block_number,
             "Creating full block: {}, operations: {}, chunks_left: {}, miniblock iterations: {}",
             *block_commit_request.block.block_number,
             block_commit_request.block.block_transactions.len(),
@@ -1061,6 +1602,17 @@ impl ZkSyncStateKeeper {
             pending_block.pending_block_iteration
         );
 
+        // Time passed since the first transaction was included into the block.
+        let block_seal_latency = system_time_timestamp().saturating_sub(pending_block.timestamp);
+        metrics::histogram!(
+            "state_keeper.block_seal_latency",
+            Duration::from_secs(block_seal_latency)
+        );
+        metrics::counter!("state_keeper.sealed_blocks", 1);
+        if let Some(block_time) = &mut self.block_time {
+            self.max_miniblock_iterations = block_time.block_sealed(Instant::now());
+        }
+
         let commit_request = CommitRequest::Block((block_commit_request, applied_updates_request));
         self.tx_for_commitments
             .send(commit_request)
@@ -1103,11 +1655,16 @@ impl ZkSyncStateKeeper {
         };
         let first_update_order_id = self.pending_block.stored_account_updates;
         let account_updates = self.pending_block.account_updates[first_update_order_id..].to_vec();
+        let tx_updates =
+            self.pending_block.tx_updates[self.pending_block.stored_tx_updates..].to_vec();
         let applied_updates_request = AppliedUpdatesRequest {
             account_updates,
             first_update_order_id,
+            tx_updates,
+            l1_messages: std::mem::take(&mut self.pending_block.l1_messages),
         };
         self.pending_block.stored_account_updates = self.pending_block.account_updates.len();
+        self.pending_block.stored_tx_updates = self.pending_block.tx_updates.len();
 
         vlog::debug!(
             "Persisting mini block: {}, operations: {}, failed_txs: {}, chunks_left: {}, miniblock iterations: {}",
@@ -1135,6 +1692,7 @@ impl ZkSyncStateKeeper {
             acc_id_by_addr: self.state.get_account_addresses(),
             last_block_number: self.state.block_number - 1,
             unprocessed_priority_op: self.current_unprocessed_priority_op,
+            next_l1_message_id: self.next_l1_message_id,
         }
     }
 
@@ -1233,6 +1791,14 @@ impl ZkSyncStateKeeper {
     }
 }
 
+/// Resolves once the shutdown is requested, never resolves if there is no shutdown signal.
+async fn wait_for_shutdown(signal: &mut Option<ShutdownSignal>) {
+    match signal {
+        Some(signal) => signal.recv().await,
+        None => futures::future::pending().await,
+    }
+}
+
 #[must_use]
 pub fn start_state_keeper(
     sk: ZkSyncStateKeeper,
@@ -0,0 +1,571 @@
+//! Differential testing of the state keeper against the reference state machine.
+//!
+//! Random sequences of deposits, transfers and withdrawals, including the failing ones, are executed
+//! by the state keeper and by the naive reference implementation below, which keeps the accounts in
+//! a plain map and checks the packability of the amounts without the `zksync_types` helpers. After
+//! every round of operations the following must match:
+//!
+//! - the transactions admitted by the correctness check and by the reference one;
+//! - the accounts of the state keeper and of the reference;
+//! - the root hash of the state keeper tree and the one of the tree built from scratch out of the
+//!   reference accounts, so the incremental updates of the tree are checked;
+//! - the reference balances and the ones obtained by replaying the public data of the sealed blocks,
+//!   so the amounts changed by the packing are caught.
+//!
+//! The seed of the diverged run is reported in the assertion messages.
+
+// Built-in deps
+use std::collections::{BTreeMap, HashSet};
+// External uses
+use num::{CheckedSub, One, Zero};
+// Workspace uses
+use zksync_crypto::params::{
+    account_tree_depth, AMOUNT_EXPONENT_BIT_WIDTH, AMOUNT_MANTISSA_BIT_WIDTH,
+    FEE_EXPONENT_BIT_WIDTH, FEE_MANTISSA_BIT_WIDTH,
+};
+use zksync_types::{block::Block, tx::TxSignature};
+// Local uses
+use super::*;
+
+const SEEDS: [u32; 3] = [1, 2, 3];
+const ROUNDS: usize = 6;
+const DEPOSITS_PER_ROUND: usize = 3;
+const TXS_PER_ROUND: usize = 12;
+/// Number of the accounts with the signing keys, the only ones sending the transactions.
+const SIGNERS: usize = 4;
+/// Number of the addresses receiving the funds besides the signers.
+const RECIPIENTS: usize = 4;
+const TOKENS: [TokenId; 3] = [TokenId(0), TokenId(1), TokenId(2)];
+
+/// Checks that the amount can be represented as `mantissa * 10^exponent`
+/// with the given bit widths of the mantissa and the exponent.
+fn is_packable(amount: &BigUint, mantissa_bits: usize, exponent_bits: usize) -> bool {
+    let mantissa_limit = BigUint::one() << mantissa_bits;
+    let mut power = BigUint::one();
+    for _ in 0..(1 << exponent_bits) {
+        if (amount % &power).is_zero() && amount / &power < mantissa_limit {
+            return true;
+        }
+        power *= 10u32;
+    }
+    false
+}
+
+#[derive(Debug, Clone)]
+struct ReferenceAccount {
+    address: Address,
+    pub_key_hash: PubKeyHash,
+    nonce: u32,
+    balances: BTreeMap<TokenId, BigUint>,
+}
+
+impl ReferenceAccount {
+    fn new(address: Address) -> Self {
+        Self {
+            address,
+            pub_key_hash: PubKeyHash::default(),
+            nonce: 0,
+            balances: BTreeMap::new(),
+        }
+    }
+
+    fn balance(&self, token: TokenId) -> BigUint {
+        self.balances.get(&token).cloned().unwrap_or_default()
+    }
+
+    fn to_account(&self) -> Account {
+        let mut account = Account::default_with_address(&self.address);
+        account.pub_key_hash = self.pub_key_hash;
+        account.nonce = Nonce(self.nonce);
+        for (token, balance) in &self.balances {
+            account.set_balance(*token, balance.clone());
+        }
+        account
+    }
+}
+
+/// Transfer or withdrawal sent by the test.
+#[derive(Debug, Clone)]
+struct TestTx {
+    withdrawal: bool,
+    account_id: AccountId,
+    from: Address,
+    to: Address,
+    token: TokenId,
+    amount: BigUint,
+    fee: BigUint,
+    nonce: u32,
+    /// Index of the key the transaction is signed with.
+    signer: usize,
+}
+
+impl TestTx {
+    fn sign(&self, key: &PrivateKey) -> SignedZkSyncTx {
+        // The transactions are signed regardless of their correctness,
+        // so they're checked by the state keeper the same way as the correct ones.
+        let tx = if self.withdrawal {
+            let mut withdraw = Withdraw::new(
+                self.account_id,
+                self.from,
+                self.to,
+                self.token,
+                self.amount.clone(),
+                self.fee.clone(),
+                Nonce(self.nonce),
+                Default::default(),
+                None,
+            );
+            withdraw.signature = TxSignature::sign_musig(key, &withdraw.get_bytes());
+            ZkSyncTx::Withdraw(Box::new(withdraw))
+        } else {
+            let mut transfer = Transfer::new(
+                self.account_id,
+                self.from,
+                self.to,
+                self.token,
+                self.amount.clone(),
+                self.fee.clone(),
+                Nonce(self.nonce),
+                Default::default(),
+                None,
+            );
+            transfer.signature = TxSignature::sign_musig(key, &transfer.get_bytes());
+            ZkSyncTx::Transfer(Box::new(transfer))
+        };
+        SignedZkSyncTx {
+            tx,
+            eth_sign_data: None,
+        }
+    }
+}
+
+/// Naive implementation of the zkSync state: the accounts are kept in the map,
+/// and every operation is checked and applied step by step.
+#[derive(Debug, Default)]
+struct ReferenceState {
+    accounts: BTreeMap<AccountId, ReferenceAccount>,
+    /// Fees collected since the last sealed block.
+    pending_fees: BTreeMap<TokenId, BigUint>,
+}
+
+impl ReferenceState {
+    fn account_id(&self, address: &Address) -> Option<AccountId> {
+        self.accounts
+            .iter()
+            .find(|(_, account)| account.address == *address)
+            .map(|(id, _)| *id)
+    }
+
+    /// Returns the account with the address, the account is created with the next ID if needed.
+    fn account_mut(&mut self, address: Address) -> &mut ReferenceAccount {
+        let id = match self.account_id(&address) {
+            Some(id) => id,
+            None => {
+                let id = self
+                    .accounts
+                    .keys()
+                    .next_back()
+                    .map(|id| *id + 1)
+                    .unwrap_or(AccountId(0));
+                self.accounts.insert(id, ReferenceAccount::new(address));
+                id
+            }
+        };
+        self.accounts.get_mut(&id).unwrap()
+    }
+
+    /// Checks the transaction the way the mempool does before accepting it.
+    fn admits(&self, tx: &TestTx) -> bool {
+        let fee_packable = is_packable(&tx.fee, FEE_MANTISSA_BIT_WIDTH, FEE_EXPONENT_BIT_WIDTH);
+        if tx.withdrawal {
+            fee_packable
+        } else {
+            fee_packable
+                && is_packable(
+                    &tx.amount,
+                    AMOUNT_MANTISSA_BIT_WIDTH,
+                    AMOUNT_EXPONENT_BIT_WIDTH,
+                )
+                && tx.to != Address::zero()
+        }
+    }
+
+    fn deposit(&mut self, to: Address, token: TokenId, amount: &BigUint) {
+        *self.account_mut(to).balances.entry(token).or_default() += amount;
+    }
+
+    /// Executes the admitted transaction, returns whether it has succeeded.
+    fn execute_tx(&mut self, tx: &TestTx, signer_pub_key_hash: PubKeyHash) -> bool {
+        let account = match self.accounts.get_mut(&tx.account_id) {
+            Some(account) => account,
+            None => return false,
+        };
+        let total = &tx.amount + &tx.fee;
+        let new_balance = match account.balance(tx.token).checked_sub(&total) {
+            Some(new_balance) => new_balance,
+            None => return false,
+        };
+        if account.address != tx.from
+            || account.pub_key_hash == PubKeyHash::default()
+            || account.pub_key_hash != signer_pub_key_hash
+            || account.nonce != tx.nonce
+        {
+            return false;
+        }
+
+        account.balances.insert(tx.token, new_balance);
+        account.nonce += 1;
+        if !tx.withdrawal {
+            *self
+                .account_mut(tx.to)
+                .balances
+                .entry(tx.token)
+                .or_default() += &tx.amount;
+        }
+        *self.pending_fees.entry(tx.token).or_default() += &tx.fee;
+        true
+    }
+
+    fn seal_block(&mut self, fee_account_id: AccountId) {
+        let fee_account = self.accounts.get_mut(&fee_account_id).unwrap();
+        for (token, fee) in std::mem::take(&mut self.pending_fees) {
+            *fee_account.balances.entry(token).or_default() += fee;
+        }
+    }
+
+    fn root_hash(&self) -> Fr {
+        let mut tree = AccountTree::new(account_tree_depth());
+        for (id, account) in &self.accounts {
+            tree.insert(**id, account.to_account());
+        }
+        tree.root_hash()
+    }
+}
+
+/// Balances obtained by replaying the public data of the sealed blocks,
+/// the same way the data is interpreted on L1.
+#[derive(Debug, Default)]
+struct PubdataReplay {
+    balances: BTreeMap<(AccountId, TokenId), BigUint>,
+}
+
+impl PubdataReplay {
+    fn credit(&mut self, account_id: AccountId, token: TokenId, amount: &BigUint) {
+        *self.balances.entry((account_id, token)).or_default() += amount;
+    }
+
+    fn debit(&mut self, account_id: AccountId, token: TokenId, amount: &BigUint) {
+        let balance = self.balances.entry((account_id, token)).or_default();
+        *balance = balance.checked_sub(amount).unwrap_or_else(|| {
+            panic!(
+                "Account #{} can't pay {} of the token {}",
+                *account_id, amount, *token
+            )
+        });
+    }
+
+    fn replay_block(&mut self, block: &Block) {
+        let mut fees = BTreeMap::<TokenId, BigUint>::new();
+        for op in &block.block_transactions {
+            let pubdata = op.get_eth_public_data();
+            if pubdata.is_empty() {
+                // Failed transaction.
+                continue;
+            }
+            match ZkSyncOp::from_public_data(&pubdata).expect("Public data can't be decoded") {
+                ZkSyncOp::Deposit(op) => {
+                    let deposit = &op.priority_op;
+                    self.credit(op.account_id, deposit.token, &deposit.amount);
+                }
+                ZkSyncOp::Transfer(op) => {
+                    self.debit(op.from, op.tx.token, &(&op.tx.amount + &op.tx.fee));
+                    self.credit(op.to, op.tx.token, &op.tx.amount);
+                    *fees.entry(op.tx.token).or_default() += &op.tx.fee;
+                }
+                ZkSyncOp::TransferToNew(op) => {
+                    self.debit(op.from, op.tx.token, &(&op.tx.amount + &op.tx.fee));
+                    self.credit(op.to, op.tx.token, &op.tx.amount);
+                    *fees.entry(op.tx.token).or_default() += &op.tx.fee;
+                }
+                ZkSyncOp::Withdraw(op) => {
+                    self.debit(op.account_id, op.tx.token, &(&op.tx.amount + &op.tx.fee));
+                    *fees.entry(op.tx.token).or_default() += &op.tx.fee;
+                }
+                op => panic!("Unexpected operation in the block: {:?}", op),
+            }
+        }
+        for (token, fee) in fees {
+            self.credit(block.fee_account, token, &fee);
+        }
+    }
+
+    fn balance(&self, account_id: AccountId, token: TokenId) -> BigUint {
+        self.balances
+            .get(&(account_id, token))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Returns the random amount: either a small one, a packable one, an arbitrary one
+/// (likely not packable), or the whole balance.
+fn random_amount(rng: &mut XorShiftRng, balance: &BigUint) -> BigUint {
+    match rng.gen_range(0, 4) {
+        0 => BigUint::from(rng.gen_range(0u64, 1_000_000)),
+        1 => {
+            BigUint::from(rng.gen_range(1u64, 1u64 << AMOUNT_MANTISSA_BIT_WIDTH))
+                * BigUint::from(10u32).pow(rng.gen_range(0, 10))
+        }
+        2 => BigUint::from(rng.gen::<u64>()),
+        _ => balance.clone(),
+    }
+}
+
+/// Returns the random fee: either a small one, a packable one, or an arbitrary one
+/// (likely not packable).
+fn random_fee(rng: &mut XorShiftRng) -> BigUint {
+    match rng.gen_range(0, 3) {
+        0 => BigUint::from(rng.gen_range(0u64, 1u64 << FEE_MANTISSA_BIT_WIDTH)),
+        1 => {
+            BigUint::from(rng.gen_range(1u64, 1u64 << FEE_MANTISSA_BIT_WIDTH))
+                * BigUint::from(10u32).pow(rng.gen_range(0, 10))
+        }
+        _ => BigUint::from(rng.gen::<u32>()),
+    }
+}
+
+fn random_address(rng: &mut XorShiftRng) -> Address {
+    Address::from_low_u64_be(rng.gen_range(1, u64::max_value()))
+}
+
+/// Checks that the state keeper state, the reference one and the replayed one match.
+fn check_states(
+    seed: u32,
+    round: usize,
+    tester: &StateKeeperTester,
+    reference: &ReferenceState,
+    replay: &PubdataReplay,
+) {
+    let state = &tester.state_keeper.state;
+    assert_eq!(
+        state.get_accounts().len(),
+        reference.accounts.len(),
+        "Seed {}, round {}: number of accounts diverged",
+        seed,
+        round
+    );
+    for (id, expected) in &reference.accounts {
+        let account = state.get_account(*id).unwrap_or_else(|| {
+            panic!(
+                "Seed {}, round {}: account #{} doesn't exist",
+                seed, round, **id
+            )
+        });
+        assert_eq!(
+            (account.address, account.pub_key_hash, *account.nonce),
+            (expected.address, expected.pub_key_hash, expected.nonce),
+            "Seed {}, round {}: account #{} diverged",
+            seed,
+            round,
+            **id
+        );
+        for &token in &TOKENS {
+            assert_eq!(
+                account.get_balance(token),
+                expected.balance(token),
+                "Seed {}, round {}: balance of the token {} of account #{} diverged",
+                seed,
+                round,
+                *token,
+                **id
+            );
+            assert_eq!(
+                replay.balance(*id, token),
+                expected.balance(token),
+                "Seed {}, round {}: balance of the token {} of account #{} diverged from the public data",
+                seed,
+                round,
+                *token,
+                **id
+            );
+        }
+    }
+    assert_eq!(
+        state.root_hash(),
+        reference.root_hash(),
+        "Seed {}, round {}: root hash diverged",
+        seed,
+        round
+    );
+}
+
+async fn run_differential_test(seed: u32) {
+    let mut rng = XorShiftRng::from_seed([seed, 0x5eed, 0xd1ff, 0x7e57]);
+    let mut tester = StateKeeperTester::new(100, 1000, 1000);
+    let mut reference = ReferenceState::default();
+    let mut replay = PubdataReplay::default();
+
+    let fee_account = tester
+        .state_keeper
+        .state
+        .get_account(tester.fee_collector)
+        .unwrap();
+    reference.account_mut(fee_account.address);
+
+    // Accounts with the signing keys are funded directly, as if they were restored from the database.
+    let mut keys = Vec::new();
+    let mut signers = Vec::new();
+    for _ in 0..SIGNERS {
+        let key = priv_key_from_fs(rng.gen());
+        let address = random_address(&mut rng);
+        let reference_account = reference.account_mut(address);
+        reference_account.pub_key_hash = PubKeyHash::from_privkey(&key);
+        for &token in &TOKENS {
+            reference_account
+                .balances
+                .insert(token, BigUint::from(10u32).pow(18));
+        }
+        let account_id = reference.account_id(&address).unwrap();
+        let account = reference.accounts[&account_id].to_account();
+        tester
+            .state_keeper
+            .state
+            .insert_account(account_id, account);
+        for &token in &TOKENS {
+            replay.credit(account_id, token, &BigUint::from(10u32).pow(18));
+        }
+        keys.push(key);
+        signers.push((account_id, address));
+    }
+    let pub_key_hashes: Vec<_> = keys.iter().map(PubKeyHash::from_privkey).collect();
+    let mut recipients: Vec<_> = signers.iter().map(|(_, address)| *address).collect();
+    recipients.push(fee_account.address);
+    recipients.extend((0..RECIPIENTS).map(|_| random_address(&mut rng)));
+    check_states(seed, 0, &tester, &reference, &replay);
+
+    let mut serial_id = 0;
+    for round in 1..=ROUNDS {
+        // Priority operations are executed before the transactions.
+        let mut priority_ops = Vec::new();
+        for _ in 0..DEPOSITS_PER_ROUND {
+            let to = if rng.gen_range(0, 4) == 0 {
+                random_address(&mut rng)
+            } else {
+                recipients[rng.gen_range(0, recipients.len())]
+            };
+            let deposit = Deposit {
+                from: random_address(&mut rng),
+                to,
+                token: TOKENS[rng.gen_range(0, TOKENS.len())],
+                amount: BigUint::from(rng.gen::<u64>()),
+            };
+            reference.deposit(deposit.to, deposit.token, &deposit.amount);
+            priority_ops.push(PriorityOp {
+                data: ZkSyncPriorityOp::Deposit(deposit),
+                serial_id,
+                deadline_block: 0,
+                eth_hash: H256::zero(),
+                eth_block: 0,
+            });
+            serial_id += 1;
+        }
+
+        let mut txs = Vec::new();
+        let mut senders = HashSet::new();
+        for _ in 0..TXS_PER_ROUND {
+            let signer = rng.gen_range(0, SIGNERS);
+            let (account_id, from) = signers[signer];
+            let account = &reference.accounts[&account_id];
+            let token = TOKENS[rng.gen_range(0, TOKENS.len())];
+            // The used nonce is sent only before the other transactions of the account,
+            // so the proposed transactions don't have to be reordered by their nonces.
+            let nonce =
+                if account.nonce > 0 && !senders.contains(&account_id) && rng.gen_range(0, 10) == 0
+                {
+                    account.nonce - 1
+                } else {
+                    account.nonce
+                };
+            let to = if rng.gen_range(0, 5) == 0 {
+                random_address(&mut rng)
+            } else {
+                recipients[rng.gen_range(0, recipients.len())]
+            };
+            let tx = TestTx {
+                withdrawal: rng.gen_range(0, 4) == 0,
+                account_id,
+                from,
+                to,
+                token,
+                amount: random_amount(&mut rng, &account.balance(token)),
+                fee: random_fee(&mut rng),
+                nonce,
+                // Some transactions are signed by the other accounts.
+                signer: if rng.gen_range(0, 10) == 0 {
+                    (signer + 1) % SIGNERS
+                } else {
+                    signer
+                },
+            };
+
+            let mut signed_tx = tx.sign(&keys[tx.signer]);
+            let admitted = signed_tx.tx.check_correctness();
+            assert_eq!(
+                admitted,
+                reference.admits(&tx),
+                "Seed {}, round {}: correctness check of {:?} diverged",
+                seed,
+                round,
+                tx
+            );
+            if admitted {
+                reference.execute_tx(&tx, pub_key_hashes[tx.signer]);
+                senders.insert(account_id);
+                txs.push(SignedTxVariant::Tx(signed_tx));
+            }
+        }
+
+        tester
+            .state_keeper
+            .execute_proposed_block(ProposedBlock {
+                priority_ops,
+                txs,
+                l1_messages: Vec::new(),
+            })
+            .await;
+        assert!(
+            tester.state_keeper.deferred_txs.is_empty(),
+            "Seed {}, round {}: transactions are deferred",
+            seed,
+            round
+        );
+        tester.state_keeper.seal_pending_block().await;
+        reference.seal_block(tester.fee_collector);
+
+        let mut last_block = None;
+        while let Ok(Some(request)) = tester.response_rx.try_next() {
+            if let CommitRequest::Block((request, _)) = request {
+                replay.replay_block(&request.block);
+                last_block = Some(request.block);
+            }
+        }
+        let last_block = last_block.expect("Block is not sealed");
+        assert_eq!(
+            last_block.new_root_hash,
+            reference.root_hash(),
+            "Seed {}, round {}: root hash of the block diverged",
+            seed,
+            round
+        );
+        check_states(seed, round, &tester, &reference, &replay);
+    }
+}
+
+/// Executes the random operations by the state keeper and by the reference implementation,
+/// and checks that the resulting states match.
+#[tokio::test]
+async fn state_keeper_matches_reference() {
+    for &seed in &SEEDS {
+        run_differential_test(seed).await;
+    }
+}
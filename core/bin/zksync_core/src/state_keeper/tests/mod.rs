@@ -1,4 +1,6 @@
-use super::{CommitRequest, ZkSyncStateInitParams, ZkSyncStateKeeper};
+mod differential;
+
+use super::{BlockTimeController, CommitRequest, ZkSyncStateInitParams, ZkSyncStateKeeper};
 use crate::mempool::ProposedBlock;
 use futures::{channel::mpsc, stream::StreamExt};
 use num::BigUint;
@@ -20,6 +22,14 @@ struct StateKeeperTester {
 
 impl StateKeeperTester {
     fn new(available_chunk_size: usize, max_iterations: usize, fast_iterations: usize) -> Self {
+        Self::with_block_sizes(vec![available_chunk_size], max_iterations, fast_iterations)
+    }
+
+    fn with_block_sizes(
+        available_chunk_sizes: Vec<usize>,
+        max_iterations: usize,
+        fast_iterations: usize,
+    ) -> Self {
         const CHANNEL_SIZE: usize = 32768;
         let (_request_tx, request_rx) = mpsc::channel(CHANNEL_SIZE);
         let (response_tx, response_rx) = mpsc::channel(CHANNEL_SIZE);
@@ -34,7 +44,7 @@ impl StateKeeperTester {
             fee_collector.address,
             request_rx,
             response_tx,
-            vec![available_chunk_size],
+            available_chunk_sizes,
             max_iterations,
             fast_iterations,
             None,
@@ -202,6 +212,7 @@ async fn apply_single_transfer(tester: &mut StateKeeperTester) {
     let proposed_block = ProposedBlock {
         txs: vec![SignedTxVariant::Tx(transfer)],
         priority_ops: Vec::new(),
+        l1_messages: Vec::new(),
     };
     tester
         .state_keeper
@@ -221,6 +232,7 @@ async fn apply_batch_with_two_transfers(tester: &mut StateKeeperTester) {
             eth_signatures: Vec::new(),
         })],
         priority_ops: Vec::new(),
+        l1_messages: Vec::new(),
     };
     tester
         .state_keeper
@@ -343,6 +355,27 @@ mod apply_tx {
         assert_eq!(pending_block.fast_processing_required, true);
     }
 
+    /// Checks if fast withdrawal in a batch makes fast processing required
+    #[test]
+    fn fast_withdrawal_in_batch() {
+        let mut tester = StateKeeperTester::new(6, 1, 1);
+        let withdraw = create_account_and_fast_withdrawal(
+            &mut tester,
+            TokenId(0),
+            AccountId(1),
+            200u32,
+            145u32,
+            Default::default(),
+        );
+        let result = tester.state_keeper.apply_batch(&[withdraw], 1);
+
+        assert!(result.is_ok());
+        assert_eq!(
+            tester.state_keeper.pending_block.fast_processing_required,
+            true
+        );
+    }
+
     /// Checks if withdrawal that will fail is processed correctly
     #[test]
     fn failure() {
@@ -483,6 +516,11 @@ async fn seal_pending_block() {
             // + 1 here is for the update corresponding to collected fee
             old_updates_len - updates.first_update_order_id + 1
         );
+        // Updates are tracked only for the successfully executed transactions.
+        assert_eq!(updates.tx_updates.len(), 1);
+        let (tx_hash, tx_updates) = &updates.tx_updates[0];
+        assert_eq!(*tx_hash, good_withdraw.hash());
+        assert!(!tx_updates.is_empty());
     } else {
         panic!("Block is not received!");
     }
@@ -547,6 +585,81 @@ async fn store_pending_block() {
     }
 }
 
+/// Checks that with the adaptive block size the next block size is chosen
+/// based on the chunks used by the sealed block.
+#[tokio::test]
+async fn adaptive_block_size() {
+    let mut tester = StateKeeperTester::with_block_sizes(vec![6, 10], 3, 3);
+    tester.state_keeper.adaptive_block_size = true;
+    assert_eq!(tester.state_keeper.pending_block.block_size, 10);
+
+    // Low load: the next block uses the smallest size fitting the sealed one.
+    let transfer =
+        create_account_and_transfer(&mut tester, TokenId(0), AccountId(1), 200u32, 100u32);
+    assert!(tester.state_keeper.apply_tx(&transfer).is_ok());
+    tester.state_keeper.seal_pending_block().await;
+    assert_eq!(tester.state_keeper.pending_block.block_size, 6);
+    assert_eq!(tester.state_keeper.pending_block.chunks_left, 6);
+
+    // The block got full: the next block may use the larger size.
+    for account_id in 2..5 {
+        let transfer = create_account_and_transfer(
+            &mut tester,
+            TokenId(0),
+            AccountId(account_id),
+            200u32,
+            100u32,
+        );
+        assert!(tester.state_keeper.apply_tx(&transfer).is_ok());
+    }
+    let transfer =
+        create_account_and_transfer(&mut tester, TokenId(0), AccountId(5), 200u32, 100u32);
+    assert!(tester.state_keeper.apply_tx(&transfer).is_err());
+    tester.state_keeper.seal_pending_block().await;
+    assert_eq!(tester.state_keeper.pending_block.block_size, 10);
+
+    // An empty block is extended to fit any operation.
+    tester.state_keeper.seal_pending_block().await;
+    assert_eq!(tester.state_keeper.pending_block.block_size, 6);
+    assert!(tester.state_keeper.fits_into_pending_block(8));
+    assert_eq!(tester.state_keeper.pending_block.block_size, 10);
+    assert_eq!(tester.state_keeper.pending_block.chunks_left, 10);
+}
+
+/// Checks that the fees are collected to the genesis fee account until the configured
+/// fee account is created.
+#[tokio::test]
+async fn fee_account_switch() {
+    let mut tester = StateKeeperTester::new(20, 3, 3);
+    let fee_account = Account::default_with_address(&H160::random());
+    tester.state_keeper.fee_account_address = fee_account.address;
+
+    let transfer =
+        create_account_and_transfer(&mut tester, TokenId(0), AccountId(1), 200u32, 100u32);
+    assert!(tester.state_keeper.apply_tx(&transfer).is_ok());
+    tester.state_keeper.seal_pending_block().await;
+    assert_eq!(tester.state_keeper.fee_account_id, tester.fee_collector);
+    let genesis_fee_account = tester
+        .state_keeper
+        .state
+        .get_account(tester.fee_collector)
+        .unwrap();
+    assert_eq!(genesis_fee_account.get_balance(TokenId(0)), 1u32.into());
+
+    // Once the fee account is created, fees are collected to it.
+    tester
+        .state_keeper
+        .state
+        .insert_account(AccountId(2), fee_account);
+    let transfer =
+        create_account_and_transfer(&mut tester, TokenId(0), AccountId(3), 200u32, 100u32);
+    assert!(tester.state_keeper.apply_tx(&transfer).is_ok());
+    tester.state_keeper.seal_pending_block().await;
+    assert_eq!(tester.state_keeper.fee_account_id, AccountId(2));
+    let fee_account = tester.state_keeper.state.get_account(AccountId(2)).unwrap();
+    assert_eq!(fee_account.get_balance(TokenId(0)), 1u32.into());
+}
+
 mod execute_proposed_block {
 
     use super::*;
@@ -690,6 +803,7 @@ mod execute_proposed_block {
                 SignedTxVariant::Tx(bad_withdraw),
             ],
             priority_ops: vec![deposit],
+            l1_messages: Vec::new(),
         };
         let pending_block_iteration = tester.state_keeper.pending_block.pending_block_iteration;
         tester
@@ -735,6 +849,7 @@ mod execute_proposed_block {
                 SignedTxVariant::Tx(bad_withdraw),
             ],
             priority_ops: vec![deposit],
+            l1_messages: Vec::new(),
         };
         tester
             .state_keeper
@@ -778,6 +893,7 @@ mod execute_proposed_block {
                 SignedTxVariant::Tx(bad_withdraw),
             ],
             priority_ops: vec![deposit],
+            l1_messages: Vec::new(),
         };
         tester
             .state_keeper
@@ -789,6 +905,78 @@ mod execute_proposed_block {
         ));
     }
 
+    /// Checks that the priority operations are executed only in the serial ID order:
+    /// duplicates are skipped and nothing is executed after a gap.
+    #[tokio::test]
+    async fn priority_ops_continuity() {
+        let mut tester = StateKeeperTester::new(20, 3, 3);
+        let deposit = |serial_id| PriorityOp {
+            serial_id,
+            ..create_deposit(TokenId(0), 12u32)
+        };
+        let proposed_block = ProposedBlock {
+            txs: Vec::new(),
+            priority_ops: vec![deposit(0), deposit(0), deposit(2), deposit(1)],
+            l1_messages: Vec::new(),
+        };
+        tester
+            .state_keeper
+            .execute_proposed_block(proposed_block)
+            .await;
+        assert_eq!(tester.state_keeper.current_unprocessed_priority_op, 1);
+        assert_eq!(
+            tester.state_keeper.pending_block.success_operations.len(),
+            1
+        );
+
+        // The skipped operations are executed once proposed in order.
+        let proposed_block = ProposedBlock {
+            txs: Vec::new(),
+            priority_ops: vec![deposit(1), deposit(2)],
+            l1_messages: Vec::new(),
+        };
+        tester
+            .state_keeper
+            .execute_proposed_block(proposed_block)
+            .await;
+        assert_eq!(tester.state_keeper.current_unprocessed_priority_op, 3);
+        tester.state_keeper.seal_pending_block().await;
+        loop {
+            match tester.response_rx.next().await {
+                Some(CommitRequest::Block((block, _))) => {
+                    assert_eq!(block.block.processed_priority_ops, (0, 3));
+                    break;
+                }
+                Some(_) => continue,
+                None => panic!("Block is not received!"),
+            }
+        }
+    }
+
+    /// Checks that the block violating the priority operations continuity is not sealed,
+    /// and the block production is halted instead.
+    #[tokio::test]
+    async fn continuity_violation_halts_production() {
+        let mut tester = StateKeeperTester::new(20, 3, 3);
+        let proposed_block = ProposedBlock {
+            txs: Vec::new(),
+            priority_ops: vec![create_deposit(TokenId(0), 12u32)],
+            l1_messages: Vec::new(),
+        };
+        tester
+            .state_keeper
+            .execute_proposed_block(proposed_block)
+            .await;
+        // The block claims the operations it doesn't include.
+        tester.state_keeper.current_unprocessed_priority_op = 5;
+        tester.state_keeper.seal_pending_block().await;
+
+        assert!(tester.state_keeper.production_halt.is_halted());
+        while let Ok(Some(request)) = tester.response_rx.try_next() {
+            assert!(!matches!(request, CommitRequest::Block(_)));
+        }
+    }
+
     /// Checks that fast withdrawal causes block to be sealed faster.
     #[tokio::test]
     async fn fast_withdrawal() {
@@ -808,6 +996,7 @@ mod execute_proposed_block {
         let proposed_block = ProposedBlock {
             priority_ops: Vec::new(),
             txs: vec![withdraw.into()],
+            l1_messages: Vec::new(),
         };
 
         tester
@@ -836,6 +1025,7 @@ mod execute_proposed_block {
         let proposed_block = ProposedBlock {
             txs: vec![],
             priority_ops: vec![],
+            l1_messages: Vec::new(),
         };
 
         tester
@@ -865,6 +1055,7 @@ mod execute_proposed_block {
         let proposed_block = ProposedBlock {
             txs: vec![SignedTxVariant::Tx(bad_withdraw)],
             priority_ops: vec![],
+            l1_messages: Vec::new(),
         };
 
         tester
@@ -894,6 +1085,7 @@ mod execute_proposed_block {
         let proposed_block = ProposedBlock {
             txs: vec![SignedTxVariant::Tx(good_withdraw)],
             priority_ops: vec![],
+            l1_messages: Vec::new(),
         };
 
         let pending_block_iteration = tester.state_keeper.pending_block.pending_block_iteration;
@@ -924,6 +1116,7 @@ mod execute_proposed_block {
         let proposed_block = ProposedBlock {
             txs: vec![SignedTxVariant::Tx(bad_withdraw)],
             priority_ops: vec![],
+            l1_messages: Vec::new(),
         };
 
         let pending_block_iteration = tester.state_keeper.pending_block.pending_block_iteration;
@@ -946,6 +1139,7 @@ mod execute_proposed_block {
         let proposed_block = ProposedBlock {
             txs: vec![],
             priority_ops: vec![],
+            l1_messages: Vec::new(),
         };
 
         let pending_block_iteration = tester.state_keeper.pending_block.pending_block_iteration;
@@ -991,6 +1185,7 @@ mod execute_proposed_block {
                 SignedTxVariant::Tx(bad_withdraw_1.clone()),
             ],
             priority_ops: vec![],
+            l1_messages: Vec::new(),
         };
 
         let good_withdraw_2 = create_account_and_withdrawal(
@@ -1015,6 +1210,7 @@ mod execute_proposed_block {
                 SignedTxVariant::Tx(bad_withdraw_2.clone()),
             ],
             priority_ops: vec![],
+            l1_messages: Vec::new(),
         };
 
         tester
@@ -1121,6 +1317,7 @@ mod execute_proposed_block {
                 SignedTxVariant::Tx(correct_transfer.clone()),
             ],
             priority_ops: vec![],
+            l1_messages: Vec::new(),
         };
 
         tester
@@ -1154,6 +1351,151 @@ mod execute_proposed_block {
         }
     }
 
+    /// Checks that the transactions following a nonce hole are deferred until the hole is filled,
+    /// and are rejected all together if it's not filled for a whole block.
+    #[tokio::test]
+    async fn nonce_holes() {
+        const MAX_ITERATIONS: usize = 3;
+
+        let mut tester = StateKeeperTester::new(50, MAX_ITERATIONS, MAX_ITERATIONS);
+        let account_id = AccountId(1);
+        let (account, sk) = tester.add_account(account_id);
+        tester.set_balance(account_id, TokenId(0), 999u32);
+        let transfer = |nonce| {
+            let transfer = Transfer::new_signed(
+                account_id,
+                account.address,
+                account.address,
+                TokenId(0),
+                1u32.into(),
+                0u32.into(),
+                Nonce(nonce),
+                Default::default(),
+                &sk,
+            )
+            .unwrap();
+            SignedTxVariant::Tx(SignedZkSyncTx {
+                tx: ZkSyncTx::Transfer(Box::new(transfer)),
+                eth_sign_data: None,
+            })
+        };
+        let proposed_block = |txs| ProposedBlock {
+            txs,
+            priority_ops: Vec::new(),
+            l1_messages: Vec::new(),
+        };
+
+        // Transactions are executed in the nonce order, the one following the hole waits.
+        tester
+            .state_keeper
+            .execute_proposed_block(proposed_block(vec![transfer(1), transfer(0), transfer(3)]))
+            .await;
+        assert_eq!(
+            tester.state_keeper.pending_block.success_operations.len(),
+            2
+        );
+        assert_eq!(tester.state_keeper.deferred_txs.len(), 1);
+
+        // Once the hole is filled, the deferred transaction is executed.
+        tester
+            .state_keeper
+            .execute_proposed_block(proposed_block(vec![transfer(2)]))
+            .await;
+        assert_eq!(
+            tester.state_keeper.pending_block.success_operations.len(),
+            4
+        );
+        assert!(tester.state_keeper.pending_block.failed_txs.is_empty());
+        assert!(tester.state_keeper.deferred_txs.is_empty());
+        tester.state_keeper.seal_pending_block().await;
+
+        // The hole is not filled, so the transactions of the account are rejected together,
+        // regardless of how long each of them has been deferred.
+        tester
+            .state_keeper
+            .execute_proposed_block(proposed_block(vec![transfer(5)]))
+            .await;
+        for _ in 0..MAX_ITERATIONS - 1 {
+            tester
+                .state_keeper
+                .execute_proposed_block(proposed_block(vec![]))
+                .await;
+        }
+        tester
+            .state_keeper
+            .execute_proposed_block(proposed_block(vec![transfer(6)]))
+            .await;
+        assert!(tester.state_keeper.deferred_txs.is_empty());
+        let failed_txs = &tester.state_keeper.pending_block.failed_txs;
+        assert_eq!(failed_txs.len(), 2);
+        for failed_tx in failed_txs {
+            assert!(failed_tx
+                .fail_reason
+                .as_ref()
+                .unwrap()
+                .starts_with("Nonce mismatch"));
+        }
+        assert!(tester
+            .state_keeper
+            .pending_block
+            .success_operations
+            .is_empty());
+    }
+
+    /// Checks that the deferred transactions wait for the configured number of the miniblocks
+    /// even if the block time controller lowers the iterations limit.
+    #[tokio::test]
+    async fn deferrals_limit_is_fixed() {
+        const MAX_ITERATIONS: usize = 4;
+
+        let mut tester = StateKeeperTester::new(50, MAX_ITERATIONS, MAX_ITERATIONS);
+        let controller =
+            BlockTimeController::new(std::time::Duration::from_secs(10), 1, 1, MAX_ITERATIONS);
+        tester.state_keeper = tester.state_keeper.with_block_time_controller(controller);
+        assert_eq!(tester.state_keeper.max_miniblock_iterations, 1);
+
+        let account_id = AccountId(1);
+        let (account, sk) = tester.add_account(account_id);
+        tester.set_balance(account_id, TokenId(0), 999u32);
+        let transfer = Transfer::new_signed(
+            account_id,
+            account.address,
+            account.address,
+            TokenId(0),
+            1u32.into(),
+            0u32.into(),
+            Nonce(1),
+            Default::default(),
+            &sk,
+        )
+        .unwrap();
+        let mut txs = vec![SignedTxVariant::Tx(SignedZkSyncTx {
+            tx: ZkSyncTx::Transfer(Box::new(transfer)),
+            eth_sign_data: None,
+        })];
+
+        for _ in 0..MAX_ITERATIONS {
+            tester
+                .state_keeper
+                .execute_proposed_block(ProposedBlock {
+                    txs: std::mem::take(&mut txs),
+                    priority_ops: Vec::new(),
+                    l1_messages: Vec::new(),
+                })
+                .await;
+            assert_eq!(tester.state_keeper.deferred_txs.len(), 1);
+        }
+        tester
+            .state_keeper
+            .execute_proposed_block(ProposedBlock {
+                txs: Vec::new(),
+                priority_ops: Vec::new(),
+                l1_messages: Vec::new(),
+            })
+            .await;
+        assert!(tester.state_keeper.deferred_txs.is_empty());
+    }
+
     /// Checks that execution of failed transaction shouldn't change gas count.
     #[tokio::test]
     async fn gas_count_change() {
@@ -1198,6 +1540,7 @@ mod execute_proposed_block {
                 eth_signatures: Vec::new(),
             })],
             priority_ops: Vec::new(),
+            l1_messages: Vec::new(),
         };
         tester
             .state_keeper
@@ -1285,6 +1628,7 @@ mod execute_proposed_block {
         let proposed_block = ProposedBlock {
             txs,
             priority_ops: Vec::new(),
+            l1_messages: Vec::new(),
         };
         tester
             .state_keeper
@@ -1341,6 +1685,7 @@ mod execute_proposed_block {
                 eth_signatures: Vec::new(),
             })],
             priority_ops: Vec::new(),
+            l1_messages: Vec::new(),
         };
         // Execute big batch.
         tester
@@ -1358,4 +1703,38 @@ mod execute_proposed_block {
             panic!("Pending block is not received");
         }
     }
+
+    /// Checks that the messages sent from L1 are passed to the committer along with the
+    /// pending block, even if the proposed block contains no operations.
+    #[tokio::test]
+    async fn l1_messages() {
+        let mut tester = StateKeeperTester::new(20, 3, 3);
+        let message = l1_message::L1Message {
+            serial_id: 0,
+            sender: Address::repeat_byte(1),
+            recipient: Address::repeat_byte(2),
+            payload: vec![1, 2, 3],
+            eth_hash: H256::repeat_byte(3),
+            eth_block: 10,
+        };
+        let proposed_block = ProposedBlock {
+            priority_ops: Vec::new(),
+            txs: Vec::new(),
+            l1_messages: vec![message.clone()],
+        };
+        tester
+            .state_keeper
+            .execute_proposed_block(proposed_block)
+            .await;
+
+        if let Some(CommitRequest::PendingBlock((block, updates))) = tester.response_rx.next().await
+        {
+            assert!(block.success_operations.is_empty());
+            assert_eq!(updates.l1_messages, vec![message]);
+        } else {
+            panic!("Pending block is not received");
+        }
+        assert!(tester.state_keeper.pending_block.l1_messages.is_empty());
+        assert_eq!(tester.state_keeper.next_l1_message_id, 1);
+    }
 }
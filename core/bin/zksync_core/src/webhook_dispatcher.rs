@@ -0,0 +1,207 @@
+//! Webhook dispatcher sends the notifications about the chain events to the subscribed URLs.
+//!
+//! The dispatcher follows the event log and, once a block is verified, derives the notifications
+//! (see `zksync_types::webhooks`) for the matching subscriptions. The notifications are stored in
+//! the same database transaction which moves the dispatcher to the next event, so every event is
+//! handled exactly once even if the server is restarted.
+//!
+//! Deliveries are POST requests with the JSON payload. The payload is signed with HMAC-SHA256
+//! using the secret of the subscription, the hex-encoded signature is sent in the
+//! `X-Zksync-Signature` header. Failed deliveries are retried with the exponential backoff until
+//! the maximum amount of attempts is reached. Since the delivery may succeed without the response
+//! being received, the receivers should deduplicate the requests using the delivery ID sent in the
+//! `X-Zksync-Delivery` header.
+
+// External uses
+use chrono::Utc;
+use hmac::{Hmac, Mac, NewMac};
+use sha2::Sha256;
+use tokio::{task::JoinHandle, time};
+// Workspace uses
+use zksync_config::{WebhooksConfig, ZkSyncConfig};
+use zksync_storage::{webhooks::records::PendingWebhookDelivery, ConnectionPool};
+use zksync_types::{event::ChainEvent, webhooks::WebhookNotification};
+
+/// Max amount of the events processed within one iteration.
+const EVENTS_BATCH_SIZE: u32 = 100;
+/// Max amount of the deliveries attempted within one iteration.
+const DELIVERIES_BATCH_SIZE: u32 = 100;
+
+/// Returns the hex-encoded HMAC-SHA256 signature of the payload.
+fn sign_payload(secret: &str, payload: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_varkey(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(payload);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+struct WebhookDispatcher {
+    config: WebhooksConfig,
+    db_pool: ConnectionPool,
+    client: reqwest::Client,
+}
+
+impl WebhookDispatcher {
+    /// Derives the notifications from the new events in the event log.
+    async fn process_new_events(&self) -> anyhow::Result<()> {
+        let mut storage = self.db_pool.access_storage().await?;
+
+        let from_offset = match storage.webhooks_schema().get_dispatcher_offset().await? {
+            Some(offset) => offset,
+            // Only the events happened after the first start of the dispatcher are handled.
+            None => storage
+                .event_schema()
+                .get_last_offset()
+                .await?
+                .map(|offset| offset + 1)
+                .unwrap_or_default(),
+        };
+        let events = storage
+            .event_schema()
+            .load_events(from_offset, EVENTS_BATCH_SIZE)
+            .await?;
+        let next_offset = match events.last() {
+            Some(event) => event.offset + 1,
+            None => return Ok(()),
+        };
+
+        let subscriptions = storage.webhooks_schema().load_subscriptions().await?;
+        let mut deliveries = Vec::new();
+        for record in events {
+            let block_number = match record.event {
+                ChainEvent::BlockVerified { block_number } => block_number,
+                _ => continue,
+            };
+            if subscriptions.is_empty() {
+                continue;
+            }
+
+            let operations = storage
+                .chain()
+                .block_schema()
+                .get_block_executed_ops(block_number)
+                .await?;
+            let notifications = WebhookNotification::for_verified_block(block_number, &operations);
+            for notification in notifications {
+                for subscription in &subscriptions {
+                    if notification.matches(subscription) {
+                        deliveries.push((subscription.id, record.offset, notification.clone()));
+                    }
+                }
+            }
+        }
+
+        storage
+            .webhooks_schema()
+            .enqueue_deliveries(next_offset, &deliveries)
+            .await?;
+        Ok(())
+    }
+
+    /// Sends the deliveries which next attempt is due.
+    async fn send_due_deliveries(&self) -> anyhow::Result<()> {
+        let mut storage = self.db_pool.access_storage().await?;
+        let deliveries = storage
+            .webhooks_schema()
+            .load_due_deliveries(DELIVERIES_BATCH_SIZE)
+            .await?;
+
+        for pending in deliveries {
+            let id = pending.delivery.id;
+            match self.send(&pending).await {
+                Ok(()) => storage.webhooks_schema().mark_delivered(id).await?,
+                Err(err) => {
+                    let attempts = pending.delivery.attempts + 1;
+                    let next_attempt_at = if attempts < self.config.max_attempts {
+                        let delay = chrono::Duration::from_std(self.config.retry_delay(attempts))?;
+                        Some(Utc::now() + delay)
+                    } else {
+                        vlog::warn!(
+                            "Webhook delivery {} to {} failed after {} attempts: {}",
+                            id,
+                            pending.url,
+                            attempts,
+                            err
+                        );
+                        None
+                    };
+                    storage
+                        .webhooks_schema()
+                        .record_failed_attempt(id, &err.to_string(), next_attempt_at)
+                        .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn send(&self, pending: &PendingWebhookDelivery) -> anyhow::Result<()> {
+        let payload = serde_json::to_vec(&pending.delivery.payload)?;
+        let response = self
+            .client
+            .post(&pending.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header("X-Zksync-Delivery", pending.delivery.id)
+            .header(
+                "X-Zksync-Signature",
+                sign_payload(&pending.secret, &payload),
+            )
+            .body(payload)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("Subscriber responded with {}", status);
+        }
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn run_webhook_dispatcher(
+    config: &ZkSyncConfig,
+    db_pool: ConnectionPool,
+) -> Option<JoinHandle<()>> {
+    if !config.webhooks.enabled {
+        return None;
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(config.webhooks.request_timeout())
+        .build()
+        .expect("failed to build webhooks HTTP client");
+    let dispatcher = WebhookDispatcher {
+        config: config.webhooks.clone(),
+        db_pool,
+        client,
+    };
+    let mut timer = time::interval(dispatcher.config.dispatch_interval());
+
+    Some(tokio::spawn(async move {
+        loop {
+            timer.tick().await;
+
+            if let Err(err) = dispatcher.process_new_events().await {
+                vlog::error!("Failed to process events for webhooks: {}", err);
+            }
+            if let Err(err) = dispatcher.send_due_deliveries().await {
+                vlog::error!("Failed to send webhook deliveries: {}", err);
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_signature() {
+        // Test vector from RFC 4231, test case 2.
+        assert_eq!(
+            sign_payload("Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
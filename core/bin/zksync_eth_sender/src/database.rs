@@ -6,7 +6,13 @@ use num::BigUint;
 use zksync_basic_types::{H256, U256};
 // Workspace uses
 use zksync_storage::{ConnectionPool, StorageProcessor};
-use zksync_types::ethereum::{ETHOperation, EthOpId, InsertedOperationResponse};
+use zksync_types::{
+    ethereum::{ETHOperation, EthOpId, InsertedOperationResponse},
+    key_audit::KeyUsage,
+    priority_op_cost::PriorityOpGas,
+    withdrawal_execution::WithdrawalExecution,
+    withdrawal_gas::WithdrawalGasCost,
+};
 // Local uses
 use super::transactions::ETHStats;
 use zksync_types::aggregated_operations::{AggregatedActionType, AggregatedOperation};
@@ -104,11 +110,52 @@ pub(super) trait DatabaseInterface {
         connection: &mut StorageProcessor<'_>,
         op: &ETHOperation,
     ) -> anyhow::Result<bool>;
+
+    /// Appends the usage of the operator key to the audit log.
+    async fn record_key_usage(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        usage: &KeyUsage,
+    ) -> anyhow::Result<()>;
+
+    /// Loads the calibrated withdrawal gas costs of all the tokens.
+    async fn load_withdrawal_gas_costs(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<WithdrawalGasCost>>;
+
+    /// Stores the calibrated withdrawal gas costs.
+    async fn store_withdrawal_gas_costs(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        costs: &[WithdrawalGasCost],
+    ) -> anyhow::Result<()>;
+
+    /// Stores the L1 transactions executing the withdrawals.
+    async fn store_withdrawal_executions(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        executions: &[WithdrawalExecution],
+    ) -> anyhow::Result<()>;
+
+    /// Stores the gas attributed to the priority operations by the confirmed transaction.
+    async fn store_priority_op_gas(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        action: AggregatedActionType,
+        costs: &[PriorityOpGas],
+    ) -> anyhow::Result<()>;
+
+    /// Returns the identifier of the latest block revert, if the blocks were ever reverted.
+    async fn last_block_revert_id(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Option<i64>>;
 }
 
 /// The actual database wrapper.
 /// This structure uses `StorageProcessor` to interact with an existing database.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Database {
     /// Connection to the database.
     db_pool: ConnectionPool,
@@ -337,4 +384,77 @@ impl DatabaseInterface for Database {
             .await?;
         Ok(())
     }
+
+    async fn record_key_usage(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        usage: &KeyUsage,
+    ) -> anyhow::Result<()> {
+        connection
+            .key_audit_schema()
+            .record_key_usage(usage)
+            .await?;
+        Ok(())
+    }
+
+    async fn load_withdrawal_gas_costs(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<WithdrawalGasCost>> {
+        let costs = connection
+            .tokens_schema()
+            .load_withdrawal_gas_costs()
+            .await?;
+        Ok(costs)
+    }
+
+    async fn store_withdrawal_gas_costs(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        costs: &[WithdrawalGasCost],
+    ) -> anyhow::Result<()> {
+        connection
+            .tokens_schema()
+            .store_withdrawal_gas_costs(costs)
+            .await?;
+        Ok(())
+    }
+
+    async fn store_withdrawal_executions(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        executions: &[WithdrawalExecution],
+    ) -> anyhow::Result<()> {
+        connection
+            .chain()
+            .operations_schema()
+            .store_withdrawal_executions(executions)
+            .await?;
+        Ok(())
+    }
+
+    async fn store_priority_op_gas(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        action: AggregatedActionType,
+        costs: &[PriorityOpGas],
+    ) -> anyhow::Result<()> {
+        connection
+            .priority_op_costs_schema()
+            .record_priority_op_gas(action, costs)
+            .await?;
+        Ok(())
+    }
+
+    async fn last_block_revert_id(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Option<i64>> {
+        let reverts = connection
+            .chain()
+            .block_schema()
+            .load_block_reverts(1)
+            .await?;
+        Ok(reverts.first().map(|revert| revert.id))
+    }
 }
@@ -20,6 +20,11 @@ mod tests;
 /// transactions only), which guarantees that we will increase the
 /// gas price for transactions that were not mined by the network
 /// within a reasonable time.
+///
+/// Since the average gas price includes the prices of the sent transactions,
+/// it may stay inflated for a long time after the network price drops sharply.
+/// To handle it, the limit decays toward the scaled average network price once
+/// the network samples are consistently far below the limit.
 #[derive(Debug)]
 pub(super) struct GasAdjuster<DB: DatabaseInterface> {
     /// Collected statistics about recently used gas prices.
@@ -101,7 +106,7 @@ impl<DB: DatabaseInterface> GasAdjuster<DB> {
             // Report the current price to be gathered by the statistics module.
             match ethereum.get_gas_price().await {
                 Ok(network_price) => {
-                    self.statistics.add_sample(network_price);
+                    self.statistics.add_network_sample(network_price);
 
                     self.last_sample_added = Instant::now();
                 }
@@ -115,6 +120,19 @@ impl<DB: DatabaseInterface> GasAdjuster<DB> {
             // It's time to update the maximum price.
             let scale_factor = parameters::limit_scale_factor();
             self.statistics.update_limit(scale_factor);
+            // Lower the limit if it's stale.
+            let stale_limit = self.statistics.decay_limit(
+                scale_factor,
+                parameters::limit_decay_rate(),
+                parameters::limit_decay_floor(),
+            );
+            if let Some(stale_limit) = stale_limit {
+                vlog::info!(
+                    "Gas price limit is stale, decaying it from <{}> to <{}>",
+                    stale_limit,
+                    self.statistics.get_limit()
+                );
+            }
             self.last_price_renewal = Instant::now();
 
             // Update the value in the database as well.
@@ -173,18 +191,27 @@ impl<DB: DatabaseInterface> GasAdjuster<DB> {
 pub(super) struct GasStatistics {
     samples: VecDeque<U256>,
     current_sum: U256,
+    /// Recent gas prices suggested by the Ethereum node, without the prices of the sent transactions.
+    network_samples: VecDeque<U256>,
     current_max_price: U256,
+    /// Whether the limit has been decayed since the network price dropped. While the price stays low,
+    /// the decayed limit is kept across the updates, so the inflated average doesn't restore it.
+    limit_decayed: bool,
 }
 
 impl GasStatistics {
     /// Amount of entries in the gas price statistics pool.
     pub(crate) const GAS_PRICE_SAMPLES_AMOUNT: usize = 10;
+    /// The limit is considered stale if every network sample is this many times lower than the limit.
+    pub(crate) const STALE_LIMIT_RATIO: u64 = 2;
 
     pub fn new(initial_max_price: U256) -> Self {
         Self {
             samples: VecDeque::with_capacity(Self::GAS_PRICE_SAMPLES_AMOUNT),
             current_sum: 0.into(),
+            network_samples: VecDeque::with_capacity(Self::GAS_PRICE_SAMPLES_AMOUNT),
             current_max_price: initial_max_price,
+            limit_decayed: false,
         }
     }
 
@@ -199,26 +226,86 @@ impl GasStatistics {
         self.current_sum += price;
     }
 
+    /// Adds the gas price suggested by the Ethereum node. Unlike the prices of the sent transactions,
+    /// these samples are also used to detect the stale limit.
+    pub fn add_network_sample(&mut self, price: U256) {
+        if self.network_samples.len() >= Self::GAS_PRICE_SAMPLES_AMOUNT {
+            self.network_samples.pop_front();
+        }
+        self.network_samples.push_back(price);
+
+        self.add_sample(price);
+    }
+
     pub fn update_limit(&mut self, scale_factor: f64) {
         if self.samples.len() < Self::GAS_PRICE_SAMPLES_AMOUNT {
             // Not enough data, do nothing.
             return;
         }
 
+        let average_price = self.current_sum / self.samples.len();
+        let limit = Self::scale(average_price, scale_factor);
+
+        if self.limit_decayed && self.is_stale(limit) {
+            // The network price is still low, keep decaying from the already lowered limit.
+            self.current_max_price = std::cmp::min(self.current_max_price, limit);
+        } else {
+            self.limit_decayed = false;
+            self.current_max_price = limit;
+        }
+    }
+
+    /// Checks whether every recent network sample is far below the limit.
+    fn is_stale(&self, limit: U256) -> bool {
+        self.network_samples.len() >= Self::GAS_PRICE_SAMPLES_AMOUNT
+            && self
+                .network_samples
+                .iter()
+                .all(|sample| *sample * Self::STALE_LIMIT_RATIO < limit)
+    }
+
+    /// Lowers the limit toward the average network price multiplied by the scale factor
+    /// (but not below the floor) if all the recent network samples are far below the limit.
+    /// The decay rate is the share of the difference between the limit and its target removed
+    /// at once, so `1.0` sets the limit to the target right away, and `0.0` disables the decay.
+    /// The decayed limit is carried across the `update_limit` calls until the network price rises.
+    ///
+    /// Returns the previous limit if it has been decayed.
+    pub fn decay_limit(&mut self, scale_factor: f64, decay_rate: f64, floor: U256) -> Option<U256> {
+        let limit = self.current_max_price;
+        if decay_rate <= 0.0 || !self.is_stale(limit) {
+            return None;
+        }
+
+        let network_sum = self
+            .network_samples
+            .iter()
+            .fold(U256::zero(), |sum, sample| sum + *sample);
+        let network_average = network_sum / self.network_samples.len();
+        let target = std::cmp::max(Self::scale(network_average, scale_factor), floor);
+        if target >= limit {
+            return None;
+        }
+
+        let decay = Self::scale(limit - target, decay_rate.min(1.0));
+        self.current_max_price = limit - decay;
+        self.limit_decayed = true;
+        Some(limit)
+    }
+
+    fn scale(value: U256, factor: f64) -> U256 {
         // Since `U256` cannot be multiplied by `f64`, we replace this operation
         // with two:
         // Instead of `a` * `b`, we do `a` * `U256::from(b * 100)` / `U256::from(100)`.
         //
-        // This approach assumes that the scale factor is not too precise, e.g. `1.5` or `5.0`,
+        // This approach assumes that the factor is not too precise, e.g. `1.5` or `5.0`,
         // but not `3.14159265`.
-        let multiplier = (scale_factor * 100.0f64).round() as u64;
+        let multiplier = (factor * 100.0f64).round() as u64;
         let multiplier = U256::from(multiplier);
 
         let divider = U256::from(100);
 
-        let average_price = self.current_sum / self.samples.len();
-
-        self.current_max_price = average_price * multiplier / divider;
+        value * multiplier / divider
     }
 
     pub fn get_average_price(&self) -> Option<U256> {
@@ -5,6 +5,8 @@
 //!   gas price suggested by `GasAdjuster`.
 //! - Maximum gas price scale: multiplier to be applied to the average gas price to
 //!   calculate the upper limit for gas price in `GasAdjuster`.
+//! - Maximum gas price decay rate and floor: how fast the stale upper limit is lowered
+//!   toward the scaled average network price, and the value it's never lowered below.
 //!
 //! The module uses a child module `parameters_impl` which contains two implementations
 //! for functions declared in module: one for the actual usage, and one for tests.
@@ -13,6 +15,8 @@
 
 // Built-in deps.
 use std::time::Duration;
+// External deps
+use zksync_basic_types::U256;
 
 /// Obtains the interval for renewing the maximum gas price.
 ///
@@ -41,12 +45,32 @@ pub fn sample_adding_interval() -> Duration {
     parameters_impl::sample_adding_interval()
 }
 
+/// Obtains the share of the difference between the stale maximum gas price and its target
+/// removed on every update.
+///
+/// This value is not cached internally, as it may be changed for the already running
+/// server by an administrator. This may be required if existing settings aren't flexible
+/// enough to match the current network price.
+pub fn limit_decay_rate() -> f64 {
+    parameters_impl::limit_decay_rate()
+}
+
+/// Obtains the value the stale maximum gas price is never decayed below.
+///
+/// This value is not cached internally, as it may be changed for the already running
+/// server by an administrator. This may be required if existing settings aren't flexible
+/// enough to match the current network price.
+pub fn limit_decay_floor() -> U256 {
+    parameters_impl::limit_decay_floor()
+}
+
 // Actual methods implementation for non-test purposes.
 #[cfg(not(test))]
 mod parameters_impl {
     // Built-in deps.
     use std::time::Duration;
     // Workspace deps
+    use zksync_basic_types::U256;
     use zksync_config::configs::eth_sender::ETHSenderConfig;
 
     /// Obtains the interval for renewing the maximum gas price.
@@ -78,6 +102,19 @@ mod parameters_impl {
         let config = ETHSenderConfig::from_env();
         config.gas_price_limit.sample_interval()
     }
+
+    /// Obtains the share of the difference between the stale maximum gas price and its target
+    /// removed on every update.
+    pub fn limit_decay_rate() -> f64 {
+        let config = ETHSenderConfig::from_env();
+        config.gas_price_limit.decay_rate
+    }
+
+    /// Obtains the value the stale maximum gas price is never decayed below.
+    pub fn limit_decay_floor() -> U256 {
+        let config = ETHSenderConfig::from_env();
+        config.gas_price_limit.decay_floor.into()
+    }
 }
 
 // Hard-coded implementation for tests.
@@ -85,6 +122,8 @@ mod parameters_impl {
 mod parameters_impl {
     // Built-in deps.
     use std::time::Duration;
+    // Workspace deps
+    use zksync_basic_types::U256;
 
     /// `limit_update_interval` version for tests not looking for an environment variable value
     /// but using a zero interval instead.
@@ -103,4 +142,16 @@ mod parameters_impl {
     pub fn sample_adding_interval() -> Duration {
         Duration::from_secs(0)
     }
+
+    /// `limit_decay_rate` version for tests not looking for an environment variable value
+    /// but using a fixed decay rate (0.5) instead.
+    pub fn limit_decay_rate() -> f64 {
+        0.5f64
+    }
+
+    /// `limit_decay_floor` version for tests not looking for an environment variable value
+    /// but using a zero floor instead.
+    pub fn limit_decay_floor() -> U256 {
+        U256::zero()
+    }
 }
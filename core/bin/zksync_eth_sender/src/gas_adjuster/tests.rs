@@ -378,3 +378,70 @@ async fn gas_price_limit_preservation() {
         assert_eq!(new_limit, price_limit.into());
     }
 }
+
+/// Checks that the limit inflated by the prices of the sent transactions decays toward
+/// the scaled average network price once the network price drops.
+#[test]
+fn stale_gas_price_limit_decay() {
+    // Amount of samples to gather statistics.
+    const N_SAMPLES: usize = GasStatistics::GAS_PRICE_SAMPLES_AMOUNT;
+    // Price of the sent transactions.
+    const TX_PRICE: u64 = 1000;
+    // Price suggested by Ethereum client after the drop.
+    const NETWORK_PRICE: u64 = 10;
+
+    let mut statistics = GasStatistics::new(TX_PRICE.into());
+    for _ in 0..N_SAMPLES {
+        statistics.add_sample(TX_PRICE.into());
+    }
+    // Network samples are not enough to detect the stale limit.
+    statistics.add_network_sample(NETWORK_PRICE.into());
+    assert_eq!(statistics.decay_limit(1.0, 0.5, U256::zero()), None);
+
+    // Most of the samples are still the prices of the sent transactions.
+    for _ in 1..N_SAMPLES {
+        for _ in 1..N_SAMPLES {
+            statistics.add_sample(TX_PRICE.into());
+        }
+        statistics.add_network_sample(NETWORK_PRICE.into());
+    }
+    statistics.update_limit(1.0);
+    let inflated_limit = statistics.get_limit();
+    assert_eq!(inflated_limit, 901.into());
+
+    // Zero decay rate disables the decay.
+    assert_eq!(statistics.decay_limit(1.0, 0.0, U256::zero()), None);
+    assert_eq!(statistics.get_limit(), inflated_limit);
+
+    // The limit is lowered by half of the difference with the target.
+    assert_eq!(
+        statistics.decay_limit(1.0, 0.5, U256::zero()),
+        Some(inflated_limit)
+    );
+    assert_eq!(statistics.get_limit(), 456.into());
+
+    // The decayed limit is not restored by the update while the network price is low.
+    statistics.update_limit(1.0);
+    assert_eq!(statistics.get_limit(), 456.into());
+
+    // The limit is never lowered below the floor.
+    statistics.decay_limit(1.0, 1.0, 100.into());
+    assert_eq!(statistics.get_limit(), 100.into());
+    statistics.update_limit(1.0);
+    assert_eq!(statistics.decay_limit(1.0, 1.0, 100.into()), None);
+    assert_eq!(statistics.get_limit(), 100.into());
+
+    // The limit close to the network price is not stale.
+    statistics.decay_limit(1.0, 1.0, U256::zero());
+    assert_eq!(statistics.get_limit(), NETWORK_PRICE.into());
+    statistics.update_limit(1.0);
+    assert_eq!(statistics.decay_limit(1.0, 1.0, U256::zero()), None);
+    assert_eq!(statistics.get_limit(), NETWORK_PRICE.into());
+
+    // Once the network price rises, the limit follows the average price again.
+    for _ in 0..N_SAMPLES {
+        statistics.add_network_sample(TX_PRICE.into());
+    }
+    statistics.update_limit(1.0);
+    assert_eq!(statistics.get_limit(), TX_PRICE.into());
+}
@@ -4,24 +4,43 @@
 //! every transaction is executed successfully and confirmed.
 
 // Built-in deps
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
 use std::time::{Duration, Instant};
 // External uses
 use anyhow::format_err;
+use num::BigUint;
 use tokio::{task::JoinHandle, time};
 use web3::{
     contract::Options,
     types::{TransactionReceipt, H256, U256},
 };
 // Workspace uses
-use zksync_config::{ETHSenderConfig, ZkSyncConfig};
+use vlog::Instrument;
+use zksync_config::{ConfigReloader, ETHSenderConfig, Reloadable, ZkSyncConfig};
 use zksync_eth_client::{EthereumGateway, SignedCallResult};
 use zksync_storage::ConnectionPool;
-use zksync_types::ethereum::ETHOperation;
+use zksync_types::{
+    block::ExecutedOperations,
+    ethereum::ETHOperation,
+    key_audit::{KeyUsage, OperatorKey},
+    priority_op_cost::attribute_priority_op_gas,
+    withdrawal_execution::{
+        block_withdrawals, match_withdrawal_events, parse_withdrawal_events,
+        WithdrawalExecutionStatus,
+    },
+    withdrawal_gas::{attribute_withdrawal_gas, WithdrawalGasCost},
+    TokenId,
+};
+use zksync_utils::{
+    shutdown::{DrainGuard, ShutdownCoordinator, ShutdownSignal},
+    supervisor::Supervisor,
+};
 // Local uses
 use self::{
     database::{Database, DatabaseInterface},
     gas_adjuster::GasAdjuster,
+    shadow::{initialize_shadow_network, shadow_config, ShadowDatabase},
     transactions::*,
     tx_queue::{TxData, TxQueue, TxQueueBuilder},
 };
@@ -32,9 +51,13 @@ use zksync_types::{
 
 mod database;
 mod gas_adjuster;
+mod shadow;
 mod transactions;
 mod tx_queue;
 
+/// Network label of the metrics of the `eth_sender` working with the actual network.
+const MAIN_NETWORK: &str = "main";
+
 #[cfg(test)]
 mod tests;
 
@@ -119,29 +142,50 @@ struct ETHSender<DB: DatabaseInterface> {
     tx_queue: TxQueue,
     /// Utility for managing the gas price for transactions.
     gas_adjuster: GasAdjuster<DB>,
-    /// Settings for the `ETHSender`.
-    options: ETHSenderConfig,
+    /// Settings for the `ETHSender`, some of which can be changed while the sender is running.
+    options: Reloadable<ETHSenderConfig>,
+    /// Identifier of the latest block revert the state was loaded after.
+    last_revert_id: Option<i64>,
+    /// Network the operations are sent to, the metrics are labeled with it.
+    network: String,
 }
 
 impl<DB: DatabaseInterface> ETHSender<DB> {
-    pub async fn new(options: ETHSenderConfig, db: DB, ethereum: EthereumGateway) -> Self {
-        let mut connection = db
-            .acquire_connection()
+    pub async fn new(
+        options: Reloadable<ETHSenderConfig>,
+        db: DB,
+        ethereum: EthereumGateway,
+        network: String,
+    ) -> Self {
+        let (ongoing_ops, tx_queue, last_revert_id) = Self::restore_state(&db, &options)
             .await
-            .expect("Unable to connect to DB");
-        let mut transaction = connection
-            .start_transaction()
-            .await
-            .expect("Unable create database transaction");
+            .expect("Can't restore state");
+        let gas_adjuster = GasAdjuster::new(&db).await;
 
-        db.restore_unprocessed_operations(&mut transaction)
-            .await
-            .expect("Can't restore unprocessed operations");
+        Self {
+            ethereum,
+            ongoing_ops,
+            db,
+            tx_queue,
+            gas_adjuster,
+            options,
+            last_revert_id,
+            network,
+        }
+    }
 
-        let ongoing_ops = db
-            .load_unconfirmed_operations(&mut transaction)
-            .await
-            .expect("Can't restore state");
+    /// Loads the unconfirmed operations and the queue of the operations to send from the database.
+    /// Returns them along with the identifier of the latest block revert.
+    async fn restore_state(
+        db: &DB,
+        options: &Reloadable<ETHSenderConfig>,
+    ) -> anyhow::Result<(VecDeque<ETHOperation>, TxQueue, Option<i64>)> {
+        let mut connection = db.acquire_connection().await?;
+        let mut transaction = connection.start_transaction().await?;
+
+        db.restore_unprocessed_operations(&mut transaction).await?;
+
+        let ongoing_ops = db.load_unconfirmed_operations(&mut transaction).await?;
 
         let operations_id = ongoing_ops
             .iter()
@@ -149,51 +193,66 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             .map(|aggregated_op| aggregated_op.0)
             .collect::<Vec<_>>();
         db.remove_unprocessed_operations(&mut transaction, operations_id)
-            .await
-            .expect("Failed remove unprocessed operations");
+            .await?;
 
-        let stats = db
-            .load_stats(&mut transaction)
-            .await
-            .expect("Failed loading ETH operations stats");
+        let stats = db.load_stats(&mut transaction).await?;
+        let last_revert_id = db.last_block_revert_id(&mut transaction).await?;
 
-        let tx_queue = TxQueueBuilder::new(options.sender.max_txs_in_flight as usize)
+        let tx_queue = TxQueueBuilder::new(options.get().sender.max_txs_in_flight as usize)
             .with_sent_pending_txs(ongoing_ops.len())
             .with_commit_operations_count(stats.last_committed_block)
             .with_verify_operations_count(stats.last_verified_block)
             .with_execute_operations_count(stats.last_executed_block)
             .build();
 
-        let gas_adjuster = GasAdjuster::new(&db).await;
+        transaction.commit().await?;
+        Ok((ongoing_ops, tx_queue, last_revert_id))
+    }
 
-        transaction
-            .commit()
-            .await
-            .expect("Failed commit database transaction");
+    /// Reloads the state from the database if the blocks were reverted since it was loaded.
+    ///
+    /// The revert removes the operations of the reverted blocks and rewinds the stats,
+    /// so the queue built before it would send the removed operations.
+    async fn resync_after_revert(&mut self) -> anyhow::Result<()> {
+        let mut connection = self.db.acquire_connection().await?;
+        let last_revert_id = self.db.last_block_revert_id(&mut connection).await?;
         drop(connection);
-
-        Self {
-            ethereum,
-            ongoing_ops,
-            db,
-            tx_queue,
-            gas_adjuster,
-            options,
+        if last_revert_id == self.last_revert_id {
+            return Ok(());
         }
+
+        vlog::warn!(
+            "Blocks were reverted (revert id {:?}), reloading the state of the Ethereum sender",
+            last_revert_id
+        );
+        let (ongoing_ops, tx_queue, last_revert_id) =
+            Self::restore_state(&self.db, &self.options).await?;
+        self.ongoing_ops = ongoing_ops;
+        self.tx_queue = tx_queue;
+        self.last_revert_id = last_revert_id;
+        Ok(())
     }
 
     /// Main routine of `ETHSender`.
-    pub async fn run(mut self) {
+    /// Runs the main loop until the shutdown is requested.
+    /// Shutdown is only observed between iterations, so the sent transactions are always persisted
+    /// and no new operations are taken into processing after that.
+    /// The drain guard is absent if `eth_sender` is restarted after a failure.
+    pub async fn run(mut self, mut shutdown: ShutdownSignal, drain_guard: Option<DrainGuard>) {
         loop {
             // We perform a loading routine every X seconds.
-            tokio::time::delay_for(self.options.sender.tx_poll_period()).await;
+            let poll_period = self.options.get().sender.tx_poll_period();
+            tokio::select! {
+                _ = tokio::time::delay_for(poll_period) => {}
+                _ = shutdown.recv() => break,
+            }
             // If we received an error when loading a new operation, we can't do anything about it and should panic.
             if let Err(error) = self.load_new_operations().await {
                 vlog::error!("Unable to restore operations from the database: {}", error);
                 panic!("Unable to restore operations from the database: {}", error);
             }
 
-            if self.options.sender.is_enabled {
+            if self.options.get().sender.is_enabled {
                 // ...and proceed them.
                 self.proceed_next_operations().await;
                 // Update the gas adjuster to maintain the up-to-date max gas price limit.
@@ -202,12 +261,20 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                     .await;
             }
         }
+
+        vlog::info!(
+            "Ethereum sender is stopped with {} operations in flight",
+            self.ongoing_ops.len()
+        );
+        drop(drain_guard);
     }
 
     /// Gets the incoming operations from the database and adds them to the
-    /// transactions queue.
+    /// transactions queue. The state is reloaded first if the blocks were reverted.
     async fn load_new_operations(&mut self) -> anyhow::Result<()> {
         let start = Instant::now();
+        self.resync_after_revert().await?;
+
         let mut connection = self.db.acquire_connection().await?;
         let mut transaction = connection.start_transaction().await?;
 
@@ -233,7 +300,11 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             self.add_operation_to_queue(operation.clone())?;
         }
 
-        metrics::histogram!("eth_sender.load_new_operations", start.elapsed());
+        metrics::histogram!(
+            "eth_sender.load_new_operations",
+            start.elapsed(),
+            "network" => self.network.clone()
+        );
         Ok(())
     }
 
@@ -249,7 +320,7 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
 
         while let Some(tx) = self.tx_queue.pop_front() {
             if let Err(e) = self.initialize_operation(tx.clone()).await {
-                Self::process_error(e).await;
+                self.process_error(e).await;
                 // Return the unperformed operation to the queue, since failing the
                 // operation initialization means that it was not stored in the database.
                 if let Err(err_message) = self.tx_queue.return_popped(tx) {
@@ -267,10 +338,15 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             // network issue which won't appear the next time, so we report the situation to the
             // log and consider the operation pending (meaning that we won't process it on this
             // step, but will try to do so on the next one).
-            let commitment = match self.perform_commitment_step(&mut current_op).await {
+            let span = Self::operation_span(&current_op);
+            let commitment = match self
+                .perform_commitment_step(&mut current_op)
+                .instrument(span)
+                .await
+            {
                 Ok(commitment) => commitment,
                 Err(e) => {
-                    Self::process_error(e).await;
+                    self.process_error(e).await;
                     OperationCommitment::Pending
                 }
             };
@@ -294,10 +370,24 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
 
         // Store the ongoing operations for the next round.
         self.ongoing_ops = new_ongoing_ops;
-        metrics::histogram!("eth_sender.proceed_next_operations", start.elapsed());
+        metrics::gauge!(
+            "eth_sender.pending_operations",
+            self.ongoing_ops.len() as f64,
+            "network" => self.network.clone()
+        );
+        metrics::gauge!(
+            "eth_sender.queued_operations",
+            self.tx_queue.len() as f64,
+            "network" => self.network.clone()
+        );
+        metrics::histogram!(
+            "eth_sender.proceed_next_operations",
+            start.elapsed(),
+            "network" => self.network.clone()
+        );
     }
 
-    async fn process_error(err: anyhow::Error) {
+    async fn process_error(&self, err: anyhow::Error) {
         vlog::warn!("Error while trying to complete uncommitted op: {}", err);
         if err.to_string().contains(RATE_LIMIT_HTTP_CODE) {
             vlog::warn!(
@@ -306,7 +396,11 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             );
             // This metric is needed to track how much time is spent in backoff mode
             // and trigger grafana alerts
-            metrics::histogram!("eth_sender.backoff_mode", RATE_LIMIT_BACKOFF_PERIOD);
+            metrics::histogram!(
+                "eth_sender.backoff_mode",
+                RATE_LIMIT_BACKOFF_PERIOD,
+                "network" => self.network.clone()
+            );
             time::delay_for(RATE_LIMIT_BACKOFF_PERIOD).await;
         }
     }
@@ -353,7 +447,9 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             };
 
             // Sign the transaction.
-            let signed_tx = Self::sign_new_tx(&self.ethereum, &new_op).await?;
+            let signed_tx = Self::sign_new_tx(&self.ethereum, &new_op).await;
+            self.audit_key_usage(&new_op, &signed_tx).await?;
+            let signed_tx = signed_tx?;
 
             // With signed tx, update the hash in the operation entry and in the db.
             new_op.used_tx_hashes.push(signed_tx.hash);
@@ -369,16 +465,19 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         self.ongoing_ops.push_back(new_op.clone());
 
         // After storing all the tx data in the database, we can finally send the tx.
-        vlog::info!(
-            "Sending new tx: [ETH Operation <id: {}, type: {:?}>. ETH tx: {}. ZKSync operation: {}]",
-            new_op.id, new_op.op_type, self.eth_tx_description(&signed_tx), self.zksync_operation_description(&new_op),
-        );
+        let span = Self::operation_span(&new_op);
+        span.in_scope(|| {
+            vlog::info!(
+                "Sending new tx: [ETH Operation <id: {}, type: {:?}>. ETH tx: {}. ZKSync operation: {}]",
+                new_op.id, new_op.op_type, self.eth_tx_description(&signed_tx), self.zksync_operation_description(&new_op),
+            )
+        });
         if let Err(e) = self.ethereum.send_raw_tx(signed_tx.raw_tx).await {
             // Sending tx error is not critical: this will result in transaction being considered stuck,
             // and resent. We can't do anything about this failure either, since it's most probably is not
             // related to the node logic, so we just log this error and pretend to have this operation
             // processed.
-            vlog::warn!("Error while sending the operation: {}", e);
+            span.in_scope(|| vlog::warn!("Error while sending the operation: {}", e));
         }
 
         transaction.commit().await?;
@@ -386,6 +485,22 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         Ok(())
     }
 
+    /// Creates a span carrying the correlation fields of the operation, so the logs
+    /// of the `eth_sender` can be matched with the logs of the blocks being sent.
+    fn operation_span(operation: &ETHOperation) -> vlog::Span {
+        let (block_from, block_to) = operation
+            .op
+            .as_ref()
+            .map(|(_, op)| op.get_block_range())
+            .unwrap_or_default();
+        vlog::info_span!(
+            "eth_operation",
+            eth_op_id = operation.id,
+            block_from = *block_from,
+            block_to = *block_to
+        )
+    }
+
     /// Helper method to obtain the string representation of the Ethereum transaction.
     /// Intended to be used for log entries.
     fn eth_tx_description(&self, tx: &SignedCallResult) -> String {
@@ -450,7 +565,7 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                     // Transaction is pending, nothing to do yet.
                     return Ok(OperationCommitment::Pending);
                 }
-                TxCheckOutcome::Committed => {
+                TxCheckOutcome::Committed(gas_used) => {
                     let mut connection = self.db.acquire_connection().await?;
                     let mut transaction = connection.start_transaction().await?;
 
@@ -487,6 +602,28 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                         .confirm_operation(&mut transaction, tx_hash, op)
                         .await?;
                     transaction.commit().await?;
+
+                    if let Some(gas_used) = gas_used {
+                        metrics::counter!(
+                            "eth_sender.gas_spent",
+                            gas_used.low_u64(),
+                            "network" => self.network.clone()
+                        );
+
+                        if let Err(err) =
+                            self.calibrate_withdrawal_gas(op, gas_used.low_u64()).await
+                        {
+                            vlog::warn!("Failed to calibrate the withdrawal gas costs: {}", err);
+                        }
+                        if let Err(err) =
+                            self.account_priority_op_costs(op, gas_used.low_u64()).await
+                        {
+                            vlog::warn!("Failed to account the priority operations costs: {}", err);
+                        }
+                    }
+                    if let Err(err) = self.link_withdrawals(op, *tx_hash).await {
+                        vlog::warn!("Failed to link the withdrawals to the L1 tx: {}", err);
+                    }
                     return Ok(OperationCommitment::Committed);
                 }
                 TxCheckOutcome::Stuck => {
@@ -537,7 +674,11 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         self.ethereum.send_raw_tx(new_tx.raw_tx).await?;
         transaction.commit().await?;
 
-        metrics::histogram!("eth_sender.perform_commitment_step", start.elapsed());
+        metrics::histogram!(
+            "eth_sender.perform_commitment_step",
+            start.elapsed(),
+            "network" => self.network.clone()
+        );
         Ok(OperationCommitment::Pending)
     }
 
@@ -558,7 +699,7 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
 
     /// Helper method encapsulating the logic of determining the next deadline block.
     fn get_deadline_block(&self, current_block: u64) -> u64 {
-        current_block + self.options.sender.expected_wait_time_block
+        current_block + self.options.get().sender.expected_wait_time_block
     }
 
     /// Looks up for a transaction state on the Ethereum chain
@@ -576,8 +717,8 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             // Successful execution.
             Some(status) if status.success => {
                 // Check if transaction has enough confirmations.
-                if status.confirmations >= self.options.sender.wait_confirmations {
-                    TxCheckOutcome::Committed
+                if status.confirmations >= self.options.get().sender.wait_confirmations {
+                    TxCheckOutcome::Committed(status.gas_used)
                 } else {
                     TxCheckOutcome::Pending
                 }
@@ -585,7 +726,7 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             // Non-successful execution, report the failure with details.
             Some(status) => {
                 // Check if transaction has enough confirmations.
-                if status.confirmations >= self.options.sender.wait_confirmations {
+                if status.confirmations >= self.options.get().sender.wait_confirmations {
                     assert!(
                         status.receipt.is_some(),
                         "Receipt should exist for a failed transaction"
@@ -646,6 +787,124 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         Ok(signed_tx)
     }
 
+    /// Attributes the gas used by the confirmed `executeBlocks` transaction to the performed
+    /// withdrawals and updates the calibrated withdrawal gas costs of their tokens.
+    async fn calibrate_withdrawal_gas(
+        &self,
+        op: &ETHOperation,
+        gas_used: u64,
+    ) -> anyhow::Result<()> {
+        let blocks = match &op.op {
+            Some((_, AggregatedOperation::ExecuteBlocks(operation))) => &operation.blocks,
+            _ => return Ok(()),
+        };
+        let ops = blocks
+            .iter()
+            .flat_map(|block| &block.block_transactions)
+            .filter_map(ExecutedOperations::get_executed_op);
+
+        let mut connection = self.db.acquire_connection().await?;
+        let mut costs: HashMap<TokenId, WithdrawalGasCost> = self
+            .db
+            .load_withdrawal_gas_costs(&mut connection)
+            .await?
+            .into_iter()
+            .map(|cost| (cost.token_id, cost))
+            .collect();
+        let known_costs = costs
+            .iter()
+            .map(|(token_id, cost)| (*token_id, cost.gas_cost))
+            .collect();
+
+        let samples = attribute_withdrawal_gas(ops, blocks.len(), gas_used, &known_costs);
+        if samples.is_empty() {
+            return Ok(());
+        }
+        let updated_costs: Vec<_> = samples
+            .into_iter()
+            .map(|sample| {
+                let mut cost = costs
+                    .remove(&sample.token_id)
+                    .unwrap_or_else(|| WithdrawalGasCost::new(sample.token_id));
+                cost.add_samples(sample.gas_cost, sample.count);
+                cost
+            })
+            .collect();
+        self.db
+            .store_withdrawal_gas_costs(&mut connection, &updated_costs)
+            .await
+    }
+
+    /// Attributes the gas used by the confirmed `commitBlocks` or `executeBlocks` transaction
+    /// to the priority operations of its blocks.
+    async fn account_priority_op_costs(
+        &self,
+        op: &ETHOperation,
+        gas_used: u64,
+    ) -> anyhow::Result<()> {
+        let (action, blocks) = match &op.op {
+            Some((_, AggregatedOperation::CommitBlocks(operation))) => {
+                (AggregatedActionType::CommitBlocks, &operation.blocks)
+            }
+            Some((_, AggregatedOperation::ExecuteBlocks(operation))) => {
+                (AggregatedActionType::ExecuteBlocks, &operation.blocks)
+            }
+            _ => return Ok(()),
+        };
+        let gas_price = BigUint::from_str(&op.last_used_gas_price.to_string())?;
+        let costs = attribute_priority_op_gas(blocks, action, gas_used, &gas_price);
+        if costs.is_empty() {
+            return Ok(());
+        }
+
+        let mut connection = self.db.acquire_connection().await?;
+        self.db
+            .store_priority_op_gas(&mut connection, action, &costs)
+            .await
+    }
+
+    /// Links the withdrawals of the executed blocks to the confirmed `executeBlocks` transaction.
+    /// Its receipt is parsed to find the withdrawals stored in the pending balances of the recipients.
+    async fn link_withdrawals(&self, op: &ETHOperation, tx_hash: H256) -> anyhow::Result<()> {
+        let blocks = match &op.op {
+            Some((_, AggregatedOperation::ExecuteBlocks(operation))) => &operation.blocks,
+            _ => return Ok(()),
+        };
+        let withdrawals: Vec<_> = blocks.iter().flat_map(block_withdrawals).collect();
+        if withdrawals.is_empty() {
+            return Ok(());
+        }
+
+        let receipt = self
+            .ethereum
+            .tx_receipt(tx_hash)
+            .await?
+            .ok_or_else(|| format_err!("Receipt of the tx {:#x} is missing", tx_hash))?;
+        let events = parse_withdrawal_events(&receipt.logs);
+        let executions = match_withdrawal_events(withdrawals, &events, tx_hash);
+        let pending = executions
+            .iter()
+            .filter(|execution| execution.status == WithdrawalExecutionStatus::Pending)
+            .count();
+        if pending > 0 {
+            vlog::info!(
+                "{} withdrawals executed in tx {:#x} were stored in the pending balances",
+                pending,
+                tx_hash
+            );
+            metrics::counter!(
+                "eth_sender.pending_balance_withdrawals",
+                pending as u64,
+                "network" => self.network.clone()
+            );
+        }
+
+        let mut connection = self.db.acquire_connection().await?;
+        self.db
+            .store_withdrawal_executions(&mut connection, &executions)
+            .await
+    }
+
     /// Calculates the gas limit for transaction to be send, depending on the type of operation.
     fn gas_limit_for_op(op: &ETHOperation) -> U256 {
         let (_, op) = op
@@ -678,7 +937,9 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         let tx_options = self.tx_options_from_stuck_tx(stuck_tx).await?;
 
         let raw_tx = stuck_tx.encoded_tx_data.clone();
-        let signed_tx = self.ethereum.sign_prepared_tx(raw_tx, tx_options).await?;
+        let signed_tx = self.ethereum.sign_prepared_tx(raw_tx, tx_options).await;
+        self.audit_key_usage(stuck_tx, &signed_tx).await?;
+        let signed_tx = signed_tx?;
 
         stuck_tx.last_deadline_block = deadline_block;
         stuck_tx.last_used_gas_price = signed_tx.gas_price;
@@ -687,6 +948,28 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         Ok(signed_tx)
     }
 
+    /// Records the signing of the operation transaction to the audit log of the operator key.
+    /// Transactions which can't be recorded are not sent.
+    async fn audit_key_usage(
+        &self,
+        op: &ETHOperation,
+        signed_tx: &anyhow::Result<SignedCallResult>,
+    ) -> anyhow::Result<()> {
+        let usage = KeyUsage::new(
+            OperatorKey::OperatorEth,
+            op.op_type.to_string(),
+            &op.encoded_tx_data,
+            signed_tx
+                .as_ref()
+                .map(|_| ())
+                .map_err(|err| err.to_string()),
+        );
+        // The record is stored outside of the operation database transaction,
+        // so the failed signing attempts are recorded as well.
+        let mut connection = self.db.acquire_connection().await?;
+        self.db.record_key_usage(&mut connection, &usage).await
+    }
+
     /// Creates a new tx options from a stuck transaction, with updated gas amount
     /// and nonce.
     async fn tx_options_from_stuck_tx(
@@ -774,12 +1057,92 @@ pub fn run_eth_sender(
     pool: ConnectionPool,
     eth_gateway: EthereumGateway,
     options: ZkSyncConfig,
+    config_reloader: &ConfigReloader,
+    shutdown: &ShutdownCoordinator,
+    supervisor: &Supervisor,
 ) -> JoinHandle<()> {
     let db = Database::new(pool);
+    spawn_eth_sender(
+        "eth_sender",
+        MAIN_NETWORK.to_string(),
+        db,
+        eth_gateway,
+        options,
+        config_reloader,
+        shutdown,
+        supervisor,
+    )
+}
 
-    tokio::spawn(async move {
-        let eth_sender = ETHSender::new(options.eth_sender, db, eth_gateway).await;
+/// Runs the `eth_sender` sending the operations to the shadow network alongside the actual one
+/// if the shadow network is enabled in `eth_sender.shadow`, see the `shadow` module.
+pub async fn run_shadow_eth_sender_if_enabled(
+    pool: ConnectionPool,
+    options: &ZkSyncConfig,
+    config_reloader: &ConfigReloader,
+    shutdown: &ShutdownCoordinator,
+    supervisor: &Supervisor,
+) -> anyhow::Result<Option<JoinHandle<()>>> {
+    if !options.eth_sender.shadow.is_enabled {
+        return Ok(None);
+    }
+
+    let options = shadow_config(options);
+    let eth_gateway = EthereumGateway::from_config(&options);
+    initialize_shadow_network(&pool, &eth_gateway, &options).await?;
+
+    let network = options.eth_sender.shadow.network.clone();
+    let db = ShadowDatabase::new(pool, network.clone());
+    Ok(Some(spawn_eth_sender(
+        "shadow_eth_sender",
+        network,
+        db,
+        eth_gateway,
+        options,
+        config_reloader,
+        shutdown,
+        supervisor,
+    )))
+}
 
-        eth_sender.run().await
+#[allow(clippy::too_many_arguments)]
+fn spawn_eth_sender<DB>(
+    name: &'static str,
+    network: String,
+    db: DB,
+    eth_gateway: EthereumGateway,
+    options: ZkSyncConfig,
+    config_reloader: &ConfigReloader,
+    shutdown: &ShutdownCoordinator,
+    supervisor: &Supervisor,
+) -> JoinHandle<()>
+where
+    DB: DatabaseInterface + Clone + Send + Sync + 'static,
+{
+    let eth_sender_options = Reloadable::from(options.eth_sender);
+    config_reloader.subscribe({
+        let eth_sender_options = eth_sender_options.clone();
+        move |params| eth_sender_options.update(|options| params.apply_to_eth_sender(options))
+    });
+
+    // State of the sender is restored from the database on each restart, and each restart
+    // has to be drained, so it obtains a new guard.
+    let mut drain_guard = Some(shutdown.register(name));
+    let drain_registry = shutdown.registry();
+    let shutdown = shutdown.signal();
+    supervisor.spawn(name, move || {
+        let eth_sender_options = eth_sender_options.clone();
+        let db = db.clone();
+        let eth_gateway = eth_gateway.clone();
+        let shutdown = shutdown.clone();
+        let drain_guard = drain_guard
+            .take()
+            .unwrap_or_else(|| drain_registry.register(name));
+        let network = network.clone();
+        async move {
+            let eth_sender = ETHSender::new(eth_sender_options, db, eth_gateway, network).await;
+
+            eth_sender.run(shutdown, Some(drain_guard)).await
+        }
     })
 }
@@ -1,11 +1,14 @@
-use futures::{channel::mpsc, executor::block_on, SinkExt, StreamExt};
-use std::cell::RefCell;
-use zksync_config::ZkSyncConfig;
+use std::time::Duration;
+use zksync_config::{ConfigReloader, ZkSyncConfig};
 use zksync_eth_client::EthereumGateway;
-use zksync_eth_sender::run_eth_sender;
+use zksync_eth_sender::{run_eth_sender, run_shadow_eth_sender_if_enabled};
 use zksync_gateway_watcher::run_gateway_watcher_if_multiplexed;
 use zksync_prometheus_exporter::run_prometheus_exporter;
 use zksync_storage::ConnectionPool;
+use zksync_utils::{shutdown::ShutdownCoordinator, supervisor::Supervisor};
+
+/// Time given to `eth_sender` to finish its work after the shutdown is requested.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(60);
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -14,19 +17,18 @@ async fn main() -> anyhow::Result<()> {
 
     let _sentry_guard = vlog::init();
 
-    // handle ctrl+c
-    let (stop_signal_sender, mut stop_signal_receiver) = mpsc::channel(256);
+    // Ctrl+C and SIGTERM start the graceful shutdown.
+    let shutdown = ShutdownCoordinator::new();
+    let mut shutdown_signal = shutdown.signal();
     {
-        let stop_signal_sender = RefCell::new(stop_signal_sender.clone());
-        ctrlc::set_handler(move || {
-            let mut sender = stop_signal_sender.borrow_mut();
-            block_on(sender.send(true)).expect("crtlc signal send");
-        })
-        .expect("Error setting Ctrl-C handler");
+        let shutdown_trigger = shutdown.trigger();
+        ctrlc::set_handler(move || shutdown_trigger.trigger())
+            .expect("Error setting Ctrl-C handler");
     }
 
     let pool = ConnectionPool::new(Some(ETH_SENDER_CONNECTION_POOL_SIZE));
     let config = ZkSyncConfig::from_env();
+    config.validate()?;
     let eth_gateway = EthereumGateway::from_config(&config);
     let gateway_watcher_task_opt = run_gateway_watcher_if_multiplexed(eth_gateway.clone(), &config);
 
@@ -34,20 +36,47 @@ async fn main() -> anyhow::Result<()> {
     let (prometheus_task_handle, _) =
         run_prometheus_exporter(pool.clone(), config.api.prometheus.port, false);
 
-    let task_handle = run_eth_sender(pool, eth_gateway, config);
+    let config_reloader = ConfigReloader::new(&config);
+    config_reloader.clone().reload_on_sighup();
+
+    // Failed actors are restarted by the supervisor.
+    let supervisor = Supervisor::default();
+    let shadow_task_opt = run_shadow_eth_sender_if_enabled(
+        pool.clone(),
+        &config,
+        &config_reloader,
+        &shutdown,
+        &supervisor,
+    )
+    .await?;
+    let task_handle = run_eth_sender(
+        pool,
+        eth_gateway,
+        config,
+        &config_reloader,
+        &shutdown,
+        &supervisor,
+    );
 
     tokio::select! {
         _ = async { task_handle.await } => {
             panic!("Ethereum sender actors aren't supposed to finish their execution")
         },
+        _ = async { shadow_task_opt.unwrap().await }, if shadow_task_opt.is_some() => {
+            panic!("Shadow Ethereum sender actors aren't supposed to finish their execution")
+        },
         _ = async { gateway_watcher_task_opt.unwrap().await }, if gateway_watcher_task_opt.is_some() => {
             panic!("Gateway Watcher actors aren't supposed to finish their execution")
         },
         _ = async { prometheus_task_handle.await } => {
             panic!("Prometheus exporter actors aren't supposed to finish their execution")
         },
-        _ = async { stop_signal_receiver.next().await } => {
-            vlog::warn!("Stop signal received, shutting down");
+        _ = shutdown_signal.recv() => {
+            vlog::warn!("Termination signal received, waiting for the in-flight transactions");
+            let not_drained = shutdown.wait_drained(SHUTDOWN_TIMEOUT).await;
+            if !not_drained.is_empty() {
+                vlog::error!("Ethereum sender did not finish its work in time");
+            }
         }
     };
 
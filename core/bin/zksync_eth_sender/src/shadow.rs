@@ -0,0 +1,356 @@
+//! Shadow network support.
+//!
+//! If enabled in `eth_sender.shadow`, the aggregated operations of the actual network are also sent
+//! to the shadow one, e.g. to a mainnet fork with the upgraded contract, so the upgrade can be
+//! rehearsed on the realistic data before the mainnet.
+//!
+//! The shadow instance is the same `ETHSender` running in the same process as the actual one, with
+//! its own Ethereum gateway and on top of the separate database state: it has its own nonce, gas
+//! price limit and the list of the sent transactions, and the confirmation of its transactions
+//! doesn't affect the state of the blocks. It's supervised as a separate component, so its panics
+//! restart only the shadow instance, and its metrics are labeled with the shadow network name.
+//! The operations are sent starting with the blocks the shadow network contract hasn't processed
+//! at the moment of the first start, and regardless of whether they're confirmed in the actual
+//! network.
+
+// Built-in deps
+use std::collections::VecDeque;
+use std::str::FromStr;
+// External uses
+use anyhow::format_err;
+use num::BigUint;
+use web3::contract::Options;
+use zksync_basic_types::{H256, U256};
+// Workspace uses
+use zksync_config::ZkSyncConfig;
+use zksync_eth_client::EthereumGateway;
+use zksync_storage::{
+    ethereum::records::ETHStats as StorageETHStats, ConnectionPool, StorageProcessor,
+};
+use zksync_types::{
+    aggregated_operations::{AggregatedActionType, AggregatedOperation},
+    ethereum::{ETHOperation, EthOpId, InsertedOperationResponse},
+    key_audit::{KeyUsage, OperatorKey},
+    priority_op_cost::PriorityOpGas,
+    withdrawal_execution::WithdrawalExecution,
+    withdrawal_gas::WithdrawalGasCost,
+};
+// Local uses
+use crate::{database::DatabaseInterface, transactions::ETHStats};
+
+/// Returns the config with the Ethereum node, the contract and the operator account
+/// of the shadow network in place of the actual ones.
+pub fn shadow_config(config: &ZkSyncConfig) -> ZkSyncConfig {
+    let shadow = &config.eth_sender.shadow;
+    let mut config = config.clone();
+    config.eth_client.web3_url = shadow.web3_url.clone();
+    config.eth_client.chain_id = shadow.chain_id;
+    config.contracts.contract_addr = shadow.contract_addr;
+    config.eth_sender.sender.operator_private_key = shadow.operator_private_key;
+    config.eth_sender.sender.operator_commit_eth_addr = shadow.operator_commit_eth_addr;
+    config
+}
+
+/// Initializes the state of the shadow network on the first start. The operations will be sent
+/// starting with the blocks the shadow network contract hasn't committed, proven or executed yet.
+///
+/// `eth_gateway` must be connected to the shadow network, see [`shadow_config`].
+pub async fn initialize_shadow_network(
+    pool: &ConnectionPool,
+    eth_gateway: &EthereumGateway,
+    config: &ZkSyncConfig,
+) -> anyhow::Result<()> {
+    let network = &config.eth_sender.shadow.network;
+    let mut storage = pool.access_storage().await?;
+    if storage
+        .shadow_ethereum_schema()
+        .is_network_initialized(network)
+        .await?
+    {
+        return Ok(());
+    }
+
+    let stats = StorageETHStats {
+        last_committed_block: total_blocks(eth_gateway, "totalBlocksCommitted").await?,
+        last_verified_block: total_blocks(eth_gateway, "totalBlocksProven").await?,
+        last_executed_block: total_blocks(eth_gateway, "totalBlocksExecuted").await?,
+    };
+    let nonce = eth_gateway.pending_nonce().await?;
+    vlog::info!(
+        "Initializing the shadow network {}: nonce {}, last committed block {}, last proven block {}, last executed block {}",
+        network,
+        nonce,
+        stats.last_committed_block,
+        stats.last_verified_block,
+        stats.last_executed_block
+    );
+    storage
+        .shadow_ethereum_schema()
+        .initialize_network(
+            network,
+            nonce,
+            config.eth_sender.gas_price_limit.default.into(),
+            stats,
+        )
+        .await?;
+    Ok(())
+}
+
+async fn total_blocks(eth_gateway: &EthereumGateway, function: &str) -> anyhow::Result<i64> {
+    let total: U256 = eth_gateway
+        .call_main_contract_function(function, (), None, Options::default(), None)
+        .await
+        .map_err(|err| format_err!("Failed to query contract {}: {}", function, err))?;
+    Ok(total.as_u64() as i64)
+}
+
+/// Database access of the `ETHSender` working with the shadow network.
+#[derive(Debug, Clone)]
+pub struct ShadowDatabase {
+    db_pool: ConnectionPool,
+    /// Name of the shadow network.
+    network: String,
+}
+
+impl ShadowDatabase {
+    pub fn new(db_pool: ConnectionPool, network: String) -> Self {
+        Self { db_pool, network }
+    }
+}
+
+#[async_trait::async_trait]
+impl DatabaseInterface for ShadowDatabase {
+    async fn acquire_connection(&self) -> anyhow::Result<StorageProcessor<'_>> {
+        let connection = self.db_pool.access_storage().await?;
+
+        Ok(connection)
+    }
+
+    async fn load_unconfirmed_operations(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<VecDeque<ETHOperation>> {
+        let unconfirmed_ops = connection
+            .shadow_ethereum_schema()
+            .load_unconfirmed_operations(&self.network)
+            .await?;
+
+        Ok(unconfirmed_ops)
+    }
+
+    async fn restore_unprocessed_operations(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<()> {
+        connection
+            .shadow_ethereum_schema()
+            .restore_unprocessed_operations(&self.network)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn load_new_operations(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<(i64, AggregatedOperation)>> {
+        let unprocessed_ops = connection
+            .shadow_ethereum_schema()
+            .load_unprocessed_operations(&self.network)
+            .await?;
+
+        Ok(unprocessed_ops)
+    }
+
+    async fn remove_unprocessed_operations(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        operations_id: Vec<i64>,
+    ) -> anyhow::Result<()> {
+        connection
+            .shadow_ethereum_schema()
+            .remove_unprocessed_operations(&self.network, operations_id)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn save_new_eth_tx(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        _op_type: AggregatedActionType,
+        op: Option<(i64, AggregatedOperation)>,
+        deadline_block: i64,
+        used_gas_price: U256,
+        raw_tx: Vec<u8>,
+    ) -> anyhow::Result<InsertedOperationResponse> {
+        let op = op.ok_or_else(|| {
+            format_err!("Only the aggregated operations are sent to the shadow network")
+        })?;
+        let result = connection
+            .shadow_ethereum_schema()
+            .save_new_eth_tx(
+                &self.network,
+                op,
+                deadline_block,
+                BigUint::from_str(&used_gas_price.to_string()).unwrap(),
+                raw_tx,
+            )
+            .await?;
+
+        Ok(result)
+    }
+
+    async fn add_hash_entry(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: i64,
+        hash: &H256,
+    ) -> anyhow::Result<()> {
+        Ok(connection
+            .shadow_ethereum_schema()
+            .add_hash_entry(eth_op_id, hash)
+            .await?)
+    }
+
+    async fn update_eth_tx(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        eth_op_id: EthOpId,
+        new_deadline_block: i64,
+        new_gas_value: U256,
+    ) -> anyhow::Result<()> {
+        Ok(connection
+            .shadow_ethereum_schema()
+            .update_eth_tx(
+                eth_op_id,
+                new_deadline_block,
+                BigUint::from_str(&new_gas_value.to_string()).unwrap(),
+            )
+            .await?)
+    }
+
+    async fn confirm_operation(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        hash: &H256,
+        _op: &ETHOperation,
+    ) -> anyhow::Result<()> {
+        // The state of the blocks follows the actual network only.
+        connection
+            .shadow_ethereum_schema()
+            .confirm_eth_tx(&self.network, hash)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn load_stats(&self, connection: &mut StorageProcessor<'_>) -> anyhow::Result<ETHStats> {
+        let stats = connection
+            .shadow_ethereum_schema()
+            .load_stats(&self.network)
+            .await?;
+        Ok(stats.into())
+    }
+
+    async fn load_gas_price_limit(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<U256> {
+        let limit = connection
+            .shadow_ethereum_schema()
+            .load_gas_price_limit(&self.network)
+            .await?;
+        Ok(limit)
+    }
+
+    async fn update_gas_price_params(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        gas_price_limit: U256,
+        average_gas_price: U256,
+    ) -> anyhow::Result<()> {
+        connection
+            .shadow_ethereum_schema()
+            .update_gas_price(&self.network, gas_price_limit, average_gas_price)
+            .await?;
+        Ok(())
+    }
+
+    async fn is_previous_operation_confirmed(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        op: &ETHOperation,
+    ) -> anyhow::Result<bool> {
+        let confirmed = connection
+            .shadow_ethereum_schema()
+            .is_previous_operation_confirmed(&self.network, op.id)
+            .await?;
+
+        Ok(confirmed)
+    }
+
+    async fn record_key_usage(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        usage: &KeyUsage,
+    ) -> anyhow::Result<()> {
+        // The transactions are signed by the operator key of the shadow network.
+        let usage = KeyUsage {
+            key: OperatorKey::ShadowOperatorEth,
+            ..usage.clone()
+        };
+        connection
+            .key_audit_schema()
+            .record_key_usage(&usage)
+            .await?;
+        Ok(())
+    }
+
+    // The gas statistics and the withdrawal executions are collected in the actual network only,
+    // so the shadow network doesn't affect the fees and the withdrawal reports.
+
+    async fn load_withdrawal_gas_costs(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<WithdrawalGasCost>> {
+        Ok(Vec::new())
+    }
+
+    async fn store_withdrawal_gas_costs(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        _costs: &[WithdrawalGasCost],
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn store_withdrawal_executions(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        _executions: &[WithdrawalExecution],
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn store_priority_op_gas(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        _action: AggregatedActionType,
+        _costs: &[PriorityOpGas],
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn last_block_revert_id(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Option<i64>> {
+        let reverts = connection
+            .chain()
+            .block_schema()
+            .load_block_reverts(1)
+            .await?;
+        Ok(reverts.first().map(|revert| revert.id))
+    }
+}
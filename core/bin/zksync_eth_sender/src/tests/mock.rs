@@ -1,22 +1,27 @@
 //! Mocking utilities for tests.
 
 // Built-in deps
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::convert::TryFrom;
 // External uses
 use tokio::sync::RwLock;
 use web3::contract::Options;
-use zksync_basic_types::{BlockNumber, H256, U256};
+use zksync_basic_types::{BlockNumber, TokenId, H256, U256};
 // Workspace uses
-use zksync_config::configs::eth_sender::{ETHSenderConfig, GasLimit, Sender};
+use zksync_config::configs::eth_sender::{ETHSenderConfig, GasLimit, Sender, Shadow};
 use zksync_eth_client::EthereumGateway;
 use zksync_storage::{ethereum::records::ETHParams, StorageProcessor};
 use zksync_types::aggregated_operations::{AggregatedActionType, AggregatedOperation};
 use zksync_types::ethereum::{ETHOperation, EthOpId, InsertedOperationResponse};
+use zksync_types::key_audit::KeyUsage;
+use zksync_types::priority_op_cost::PriorityOpGas;
+use zksync_types::withdrawal_execution::WithdrawalExecution;
+use zksync_types::withdrawal_gas::WithdrawalGasCost;
 // Local uses
 use super::ETHSender;
 use crate::database::DatabaseInterface;
 use crate::transactions::ETHStats;
+use crate::MAIN_NETWORK;
 use zksync_eth_client::clients::mock::MockEthereum;
 
 /// Mock database is capable of recording all the incoming requests for the further analysis.
@@ -26,6 +31,9 @@ pub(in crate) struct MockDatabase {
     aggregated_operations: RwLock<Vec<(i64, AggregatedOperation)>>,
     unprocessed_operations: RwLock<Vec<(i64, AggregatedOperation)>>,
     eth_parameters: RwLock<ETHParams>,
+    key_usages: RwLock<Vec<KeyUsage>>,
+    withdrawal_gas_costs: RwLock<HashMap<TokenId, WithdrawalGasCost>>,
+    last_block_revert_id: RwLock<Option<i64>>,
 }
 
 impl MockDatabase {
@@ -41,6 +49,9 @@ impl MockDatabase {
             aggregated_operations: RwLock::new(aggregated_operations),
             unprocessed_operations: RwLock::new(unprocessed_operations),
             eth_parameters: RwLock::new(eth_parameters),
+            key_usages: RwLock::new(Vec::new()),
+            withdrawal_gas_costs: RwLock::new(HashMap::new()),
+            last_block_revert_id: RwLock::new(None),
         }
     }
 
@@ -68,6 +79,15 @@ impl MockDatabase {
         Ok(())
     }
 
+    /// Simulates the block revert tool, which removes the operations of the reverted blocks
+    /// and records the revert.
+    pub async fn revert_blocks(&self, revert_id: i64) {
+        self.eth_operations.write().await.clear();
+        self.aggregated_operations.write().await.clear();
+        self.unprocessed_operations.write().await.clear();
+        *self.last_block_revert_id.write().await = Some(revert_id);
+    }
+
     /// Ensures that the provided transaction is stored in the database and not confirmed yet.
     pub async fn assert_stored(&self, tx: &ETHOperation) {
         let eth_operations = self.eth_operations.read().await;
@@ -88,6 +108,20 @@ impl MockDatabase {
         assert!(is_confirmed);
     }
 
+    /// Returns the recorded usages of the operator key.
+    pub async fn key_usages(&self) -> Vec<KeyUsage> {
+        self.key_usages.read().await.clone()
+    }
+
+    /// Returns the stored withdrawal gas cost of the token.
+    pub async fn withdrawal_gas_cost(&self, token_id: TokenId) -> Option<WithdrawalGasCost> {
+        self.withdrawal_gas_costs
+            .read()
+            .await
+            .get(&token_id)
+            .cloned()
+    }
+
     /// Returns the stored average gas price.
     pub async fn average_gas_price(&self) -> U256 {
         let eth_parameters = self.eth_parameters.read().await;
@@ -355,6 +389,66 @@ impl DatabaseInterface for MockDatabase {
 
         Ok(confirmed)
     }
+
+    async fn record_key_usage(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        usage: &KeyUsage,
+    ) -> anyhow::Result<()> {
+        self.key_usages.write().await.push(usage.clone());
+
+        Ok(())
+    }
+
+    async fn load_withdrawal_gas_costs(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<WithdrawalGasCost>> {
+        Ok(self
+            .withdrawal_gas_costs
+            .read()
+            .await
+            .values()
+            .cloned()
+            .collect())
+    }
+
+    async fn store_withdrawal_gas_costs(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        costs: &[WithdrawalGasCost],
+    ) -> anyhow::Result<()> {
+        let mut withdrawal_gas_costs = self.withdrawal_gas_costs.write().await;
+        for cost in costs {
+            withdrawal_gas_costs.insert(cost.token_id, cost.clone());
+        }
+
+        Ok(())
+    }
+
+    async fn store_withdrawal_executions(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        _executions: &[WithdrawalExecution],
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn store_priority_op_gas(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+        _action: AggregatedActionType,
+        _costs: &[PriorityOpGas],
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn last_block_revert_id(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Option<i64>> {
+        Ok(*self.last_block_revert_id.read().await)
+    }
 }
 
 /// Creates a default `ETHParams` for use by mock `ETHSender` .
@@ -440,6 +534,7 @@ async fn build_eth_sender(
             wait_confirmations: super::WAIT_CONFIRMATIONS,
             tx_poll_period: 0,
             is_enabled: true,
+            protocol_version: 4,
             operator_commit_eth_addr: Default::default(),
             operator_private_key: Default::default(),
         },
@@ -448,10 +543,21 @@ async fn build_eth_sender(
             sample_interval: 15,
             update_interval: 15,
             scale_factor: 1.0f64,
+            decay_rate: 0.5f64,
+            decay_floor: 0,
+        },
+        shadow: Shadow {
+            network: "shadow".into(),
+            web3_url: Vec::new(),
+            chain_id: 9,
+            contract_addr: Default::default(),
+            operator_private_key: Default::default(),
+            operator_commit_eth_addr: Default::default(),
+            is_enabled: false,
         },
     };
 
-    ETHSender::new(options, db, ethereum).await
+    ETHSender::new(options.into(), db, ethereum, MAIN_NETWORK.to_string()).await
 }
 
 /// Behaves the same as `ETHSender::sign_new_tx`, but does not affect nonce.
@@ -6,6 +6,7 @@ use self::mock::{
 use super::{transactions::TxCheckOutcome, ETHSender, TxCheckMode};
 use web3::types::U64;
 use zksync_eth_client::ethereum_gateway::ExecutedTxStatus;
+use zksync_types::key_audit::OperatorKey;
 
 const EXPECTED_WAIT_TIME_BLOCKS: u64 = 30;
 const WAIT_CONFIRMATIONS: u64 = 3;
@@ -78,6 +79,7 @@ async fn transaction_state() {
         confirmations: WAIT_CONFIRMATIONS,
         success: true,
         receipt: None,
+        gas_used: None,
     };
     eth_sender
         .ethereum
@@ -91,6 +93,7 @@ async fn transaction_state() {
         confirmations: WAIT_CONFIRMATIONS - 1,
         success: true,
         receipt: None,
+        gas_used: None,
     };
     eth_sender
         .ethereum
@@ -104,6 +107,7 @@ async fn transaction_state() {
         confirmations: WAIT_CONFIRMATIONS,
         success: false,
         receipt: Some(Default::default()),
+        gas_used: None,
     };
     eth_sender
         .ethereum
@@ -117,6 +121,7 @@ async fn transaction_state() {
         confirmations: WAIT_CONFIRMATIONS - 1,
         success: false,
         receipt: Some(Default::default()),
+        gas_used: None,
     };
     eth_sender
         .ethereum
@@ -139,7 +144,7 @@ async fn transaction_state() {
             )
             .await
             .unwrap(),
-        TxCheckOutcome::Committed
+        TxCheckOutcome::Committed(None)
     );
 
     // Pending operation (no enough confirmations).
@@ -306,11 +311,92 @@ async fn operation_commitment_workflow() {
     }
 }
 
+/// Checks that every transaction signed with the operator key is recorded to the audit log.
+#[tokio::test]
+async fn key_usages_are_recorded() {
+    let mut eth_sender = default_eth_sender().await;
+
+    let aggregated_operations = vec![
+        test_data::commit_blocks_operation(0),
+        test_data::publish_proof_blocks_onchain_operations(0),
+        test_data::execute_blocks_operations(0),
+    ];
+
+    for (eth_op_id, aggregated_operation) in aggregated_operations.iter().enumerate() {
+        eth_sender
+            .db
+            .send_aggregated_operation(aggregated_operation.clone())
+            .await
+            .unwrap();
+        eth_sender.load_new_operations().await.unwrap();
+        eth_sender.proceed_next_operations().await;
+
+        let deadline_block = eth_sender.get_deadline_block(
+            eth_sender
+                .ethereum
+                .get_mock()
+                .unwrap()
+                .block_number()
+                .await
+                .unwrap()
+                .as_u64(),
+        );
+        let expected_tx = create_signed_tx(
+            eth_op_id as i64,
+            &eth_sender,
+            aggregated_operation.clone(),
+            deadline_block,
+            eth_op_id as i64,
+        )
+        .await;
+
+        // Confirm the transaction, so that the next operation is sent.
+        eth_sender
+            .ethereum
+            .get_mut_mock()
+            .unwrap()
+            .add_successfull_execution(expected_tx.used_tx_hashes[0], WAIT_CONFIRMATIONS)
+            .await;
+        eth_sender.proceed_next_operations().await;
+    }
+
+    let key_usages = eth_sender.db.key_usages().await;
+    assert_eq!(key_usages.len(), aggregated_operations.len());
+    assert!(key_usages
+        .iter()
+        .all(|usage| usage.key == OperatorKey::OperatorEth && usage.success));
+}
+
 /// A simple scenario for a stuck transaction:
 /// - A transaction is sent to the Ethereum.
 /// - It is not processed after some blocks.
 /// - `ETHSender` creates a new transaction with increased gas.
 /// - This transaction is completed successfully.
+/// Checks that the state is reloaded from the database after the blocks are reverted,
+/// so the operations of the reverted blocks are no longer tracked.
+#[tokio::test]
+async fn resync_after_revert() {
+    let mut eth_sender = default_eth_sender().await;
+
+    eth_sender
+        .db
+        .send_aggregated_operation(test_data::commit_blocks_operation(0))
+        .await
+        .unwrap();
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender.proceed_next_operations().await;
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+
+    // Nothing changes while there are no new reverts.
+    eth_sender.load_new_operations().await.unwrap();
+    assert_eq!(eth_sender.ongoing_ops.len(), 1);
+
+    eth_sender.db.revert_blocks(1).await;
+    eth_sender.load_new_operations().await.unwrap();
+    assert!(eth_sender.ongoing_ops.is_empty());
+    assert_eq!(eth_sender.last_revert_id, Some(1));
+}
+
 #[tokio::test]
 async fn stuck_transaction() {
     let mut eth_sender = default_eth_sender().await;
@@ -136,6 +136,11 @@ impl TxQueueBuilder {
 ///     for corresponding blocks is committed, the `verify` operation is yielded.
 ///   - Otherwise, if `commit` queue is not empty, a `commit` operation is yielded.
 /// 3. If all the queues are empty, no operation is returned.
+///
+/// Commit operations don't depend on the other queues, so blocks are committed as soon
+/// as they are sealed, while the proofs for the previous blocks are still being generated.
+/// The `verify` operation is never yielded before the `commit` operations for all its blocks,
+/// and each queue yields operations strictly in the order of blocks.
 #[derive(Debug)]
 pub struct TxQueue {
     max_pending_txs: usize,
@@ -242,6 +247,11 @@ impl TxQueue {
         }
     }
 
+    /// Returns the amount of operations waiting to be sent.
+    pub fn len(&self) -> usize {
+        self.commit_operations.len() + self.verify_operations.len() + self.execute_operations.len()
+    }
+
     /// Obtains the next operation from the underlying queues.
     /// This method does not use/affect `sent_pending_tx` counter.
     fn get_next_operation(&mut self) -> Option<TxData> {
@@ -408,6 +418,59 @@ mod tests {
         assert_eq!(queue.sent_pending_txs, pending_count);
     }
 
+    /// Checks that the blocks are committed without waiting for the proofs of the previous
+    /// blocks, and the proofs are never sent before the corresponding commits.
+    #[test]
+    fn commits_are_pipelined() {
+        const MAX_IN_FLY: usize = 10;
+        const COMMIT_MARK: u8 = 0;
+        const VERIFY_MARK: u8 = 1;
+
+        let mut queue = TxQueueBuilder::new(MAX_IN_FLY).build();
+
+        // Proof for the first block is ready before the block is committed.
+        queue
+            .add_verify_operation(get_tx_data(
+                AggregatedActionType::PublishProofBlocksOnchain,
+                BlockNumber(1),
+                vec![VERIFY_MARK, 0],
+            ))
+            .unwrap();
+        for block in 1..=3u8 {
+            queue
+                .add_commit_operation(get_tx_data(
+                    AggregatedActionType::CommitBlocks,
+                    BlockNumber(block as u32),
+                    vec![COMMIT_MARK, block],
+                ))
+                .unwrap();
+        }
+
+        // `verify` can't be sent before the `commit` for the same block.
+        let op_1 = queue.pop_front().unwrap();
+        assert_eq!(op_1.raw, vec![COMMIT_MARK, 1]);
+        let op_2 = queue.pop_front().unwrap();
+        assert_eq!(op_2.raw, vec![VERIFY_MARK, 0]);
+
+        // The next blocks are committed though there are no proofs for them yet.
+        let op_3 = queue.pop_front().unwrap();
+        assert_eq!(op_3.raw, vec![COMMIT_MARK, 2]);
+        let op_4 = queue.pop_front().unwrap();
+        assert_eq!(op_4.raw, vec![COMMIT_MARK, 3]);
+        assert_eq!(queue.pop_front(), None);
+
+        // Proofs are sent in the order of blocks once they are ready.
+        queue
+            .add_verify_operation(get_tx_data(
+                AggregatedActionType::PublishProofBlocksOnchain,
+                BlockNumber(2),
+                vec![VERIFY_MARK, 1],
+            ))
+            .unwrap();
+        let op_5 = queue.pop_front().unwrap();
+        assert_eq!(op_5.raw, vec![VERIFY_MARK, 1]);
+    }
+
     #[test]
     #[should_panic(expected = "No transactions are expected to be returned")]
     fn return_popped_empty() {
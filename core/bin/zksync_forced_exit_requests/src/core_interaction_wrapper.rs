@@ -3,7 +3,10 @@ use num::Zero;
 use zksync_config::ZkSyncConfig;
 use zksync_storage::{chain::operations_ext::records::TxReceiptResponse, ConnectionPool};
 use zksync_types::{
-    forced_exit_requests::{ForcedExitRequest, ForcedExitRequestId},
+    forced_exit_requests::{
+        ForcedExitL2Payment, ForcedExitPaymentStatus, ForcedExitRequest, ForcedExitRequestId,
+    },
+    key_audit::KeyUsage,
     tx::TxHash,
     AccountId, Nonce,
 };
@@ -41,6 +44,17 @@ pub trait CoreInteractionWrapper {
         deleting_threshold: chrono::Duration,
     ) -> anyhow::Result<()>;
     async fn check_forced_exit_request(&self, request: &ForcedExitRequest) -> anyhow::Result<bool>;
+    async fn send_tx(&self, tx: SignedZkSyncTx) -> anyhow::Result<TxHash>;
+    async fn send_txs_batch(&self, txs: Vec<SignedZkSyncTx>) -> anyhow::Result<Vec<TxHash>>;
+    async fn get_l2_payment(&self, tx_hash: TxHash) -> anyhow::Result<Option<ForcedExitL2Payment>>;
+    async fn store_l2_payment(&self, payment: &ForcedExitL2Payment) -> anyhow::Result<bool>;
+    async fn update_l2_payment_status(
+        &self,
+        tx_hash: TxHash,
+        status: ForcedExitPaymentStatus,
+    ) -> anyhow::Result<()>;
+    async fn remove_l2_payment(&self, tx_hash: TxHash) -> anyhow::Result<()>;
+    async fn record_key_usage(&self, usages: &[KeyUsage]) -> anyhow::Result<()>;
 }
 
 #[derive(Clone)]
@@ -197,4 +211,71 @@ impl CoreInteractionWrapper for MempoolCoreInteractionWrapper {
             Ok(false)
         }
     }
+
+    async fn send_tx(&self, tx: SignedZkSyncTx) -> anyhow::Result<TxHash> {
+        let hash = tx.hash();
+        self.core_api_client.send_tx(tx).await??;
+
+        Ok(hash)
+    }
+
+    async fn send_txs_batch(&self, txs: Vec<SignedZkSyncTx>) -> anyhow::Result<Vec<TxHash>> {
+        let hashes: Vec<TxHash> = txs.iter().map(|tx| tx.hash()).collect();
+        self.core_api_client.send_txs_batch(txs, vec![]).await??;
+
+        Ok(hashes)
+    }
+
+    async fn get_l2_payment(&self, tx_hash: TxHash) -> anyhow::Result<Option<ForcedExitL2Payment>> {
+        let mut storage = self.connection_pool.access_storage().await?;
+        let payment = storage
+            .forced_exit_requests_schema()
+            .get_l2_payment(tx_hash)
+            .await?;
+
+        Ok(payment)
+    }
+
+    async fn store_l2_payment(&self, payment: &ForcedExitL2Payment) -> anyhow::Result<bool> {
+        let mut storage = self.connection_pool.access_storage().await?;
+        let stored = storage
+            .forced_exit_requests_schema()
+            .store_l2_payment(payment)
+            .await?;
+
+        Ok(stored)
+    }
+
+    async fn update_l2_payment_status(
+        &self,
+        tx_hash: TxHash,
+        status: ForcedExitPaymentStatus,
+    ) -> anyhow::Result<()> {
+        let mut storage = self.connection_pool.access_storage().await?;
+        storage
+            .forced_exit_requests_schema()
+            .update_l2_payment_status(tx_hash, status)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove_l2_payment(&self, tx_hash: TxHash) -> anyhow::Result<()> {
+        let mut storage = self.connection_pool.access_storage().await?;
+        storage
+            .forced_exit_requests_schema()
+            .remove_l2_payment(tx_hash)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn record_key_usage(&self, usages: &[KeyUsage]) -> anyhow::Result<()> {
+        let mut storage = self.connection_pool.access_storage().await?;
+        for usage in usages {
+            storage.key_audit_schema().record_key_usage(usage).await?;
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,158 @@
+//! Collector of the dust accounts.
+//!
+//! Accounts without a signing key which hold less than `DUST_COLLECTOR_THRESHOLD_USD` in total and
+//! were not updated for `DUST_COLLECTOR_INACTIVITY_PERIOD` days are emptied by the ForcedExit
+//! transactions sent from the ForcedExit sender account, so the funds are withdrawn to the account
+//! addresses on L1. Accounts opted out by their owners (see `zksync_types::dust_collection`) and
+//! accounts holding tokens without the known price are never collected.
+//!
+//! Collected accounts stay in the tree and their IDs are never reused. Reclaiming the tree slots
+//! requires enabling the `Close` operation in the circuit, which means new verification keys, the
+//! `Verifier` and `Operations.sol` changes and an upgrade path, so it's left for a separate
+//! protocol upgrade.
+
+use std::sync::Arc;
+
+use num::{rational::Ratio, BigUint};
+use tokio::time;
+use zksync_config::ZkSyncConfig;
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::{dust_collection::DustCandidate, AccountId, PubKeyHash, TokenId, TokenLike};
+
+use crate::{
+    core_interaction_wrapper::CoreInteractionWrapper, forced_exit_sender::MempoolForcedExitSender,
+};
+
+pub struct DustCollector<T: CoreInteractionWrapper> {
+    connection_pool: ConnectionPool,
+    config: ZkSyncConfig,
+    forced_exit_sender: Arc<MempoolForcedExitSender<T>>,
+    // Candidates are checked in the order of the account IDs, starting over once all
+    // the accounts are checked
+    next_account_id: AccountId,
+}
+
+impl<T: CoreInteractionWrapper + Sync + Send> DustCollector<T> {
+    pub fn new(
+        connection_pool: ConnectionPool,
+        config: ZkSyncConfig,
+        forced_exit_sender: Arc<MempoolForcedExitSender<T>>,
+    ) -> Self {
+        Self {
+            connection_pool,
+            config,
+            forced_exit_sender,
+            next_account_id: AccountId(0),
+        }
+    }
+
+    pub async fn collect(&mut self) -> anyhow::Result<()> {
+        let config = &self.config.dust_collector;
+        let mut storage = self.connection_pool.access_storage().await?;
+        let inactive_since = chrono::Utc::now() - config.inactivity_period();
+        let candidates = storage
+            .dust_collection_schema()
+            .load_candidates(
+                inactive_since,
+                self.next_account_id,
+                config.max_accounts_per_iteration,
+            )
+            .await?;
+
+        self.next_account_id = match candidates.last() {
+            Some(candidate) if candidates.len() as u32 == config.max_accounts_per_iteration => {
+                candidate.account_id + 1
+            }
+            _ => AccountId(0),
+        };
+
+        for candidate in candidates {
+            let tokens = match self.dust_tokens(&mut storage, &candidate).await? {
+                Some(tokens) => tokens,
+                None => continue,
+            };
+
+            let hashes = self
+                .forced_exit_sender
+                .collect_dust(candidate.address, &tokens)
+                .await?;
+            storage
+                .dust_collection_schema()
+                .record_collection(candidate.account_id, &hashes)
+                .await?;
+            vlog::info!(
+                "Dust account {} is collected, {} ForcedExit transactions are sent",
+                *candidate.account_id,
+                hashes.len()
+            );
+        }
+
+        Ok(())
+    }
+
+    // Returns the tokens to be withdrawn if the account is still a dust account
+    // in the latest committed state
+    async fn dust_tokens(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        candidate: &DustCandidate,
+    ) -> anyhow::Result<Option<Vec<TokenId>>> {
+        let account = storage
+            .chain()
+            .account_schema()
+            .last_committed_state_for_account(candidate.account_id)
+            .await?;
+        let account = match account {
+            // The account could be activated after the last verified block
+            Some(account) if account.pub_key_hash == PubKeyHash::zero() => account,
+            _ => return Ok(None),
+        };
+
+        let threshold_usd = Ratio::new(
+            BigUint::from((self.config.dust_collector.threshold_usd * 1e6).round() as u64),
+            BigUint::from(1_000_000u32),
+        );
+        let mut value_usd = Ratio::from_integer(BigUint::from(0u32));
+        let mut tokens = Vec::new();
+        for (token_id, balance) in account.get_nonzero_balances() {
+            let token = storage
+                .tokens_schema()
+                .get_token(TokenLike::Id(token_id))
+                .await?;
+            let price = storage
+                .tokens_schema()
+                .get_historical_ticker_price(token_id)
+                .await?;
+            let (token, price) = match (token, price) {
+                (Some(token), Some(price)) => (token, price),
+                // The value of the account is unknown
+                _ => return Ok(None),
+            };
+
+            value_usd += price.usd_price
+                * Ratio::new(
+                    balance.0,
+                    BigUint::from(10u32).pow(u32::from(token.decimals)),
+                );
+            tokens.push(token_id);
+        }
+
+        if tokens.is_empty() || value_usd >= threshold_usd {
+            return Ok(None);
+        }
+        tokens.sort();
+        Ok(Some(tokens))
+    }
+
+    pub async fn run(mut self) {
+        let mut timer = time::interval(self.config.dust_collector.collection_interval());
+
+        loop {
+            timer.tick().await;
+
+            if let Err(err) = self.collect().await {
+                vlog::error!("Failed to collect the dust accounts: {}", err);
+            }
+        }
+    }
+}
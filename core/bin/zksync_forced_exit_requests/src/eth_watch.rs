@@ -3,31 +3,30 @@ use ethabi::{Address, Hash};
 use std::{
     convert::TryFrom,
     ops::Sub,
+    sync::Arc,
     time::{Duration, Instant},
 };
 use std::{convert::TryInto, fmt::Debug};
 use tokio::task::JoinHandle;
 use tokio::time;
-use web3::{
-    contract::Contract,
-    transports::Http,
-    types::{BlockNumber, FilterBuilder, Log},
-    Web3,
-};
+use web3::types::{BlockNumber, FilterBuilder, Log};
 use zksync_config::ZkSyncConfig;
+use zksync_eth_client::ethereum_gateway::EthereumGateway;
 use zksync_storage::ConnectionPool;
 
 use zksync_contracts::forced_exit_contract;
 use zksync_types::H160;
 
 use zksync_api::core_api_client::CoreApiClient;
-use zksync_core::eth_watch::{get_web3_block_number, WatcherMode};
+use zksync_core::eth_watch::WatcherMode;
 use zksync_types::forced_exit_requests::FundsReceivedEvent;
 
 use super::prepare_forced_exit_sender::prepare_forced_exit_sender_account;
 use crate::{
     core_interaction_wrapper::{CoreInteractionWrapper, MempoolCoreInteractionWrapper},
+    dust_collector::DustCollector,
     forced_exit_sender::MempoolForcedExitSender,
+    l2_watch::ForcedExitPaymentWatcher,
 };
 
 use super::ForcedExitSender;
@@ -62,20 +61,17 @@ pub trait EthClient {
 }
 
 pub struct EthHttpClient {
-    web3: Web3<Http>,
-    forced_exit_contract: Contract<Http>,
+    client: EthereumGateway,
+    forced_exit_contract_addr: H160,
     topics: ContractTopics,
 }
 
 impl EthHttpClient {
-    pub fn new(web3: Web3<Http>, zksync_contract_addr: H160) -> Self {
-        let forced_exit_contract =
-            Contract::new(web3.eth(), zksync_contract_addr, forced_exit_contract());
-
-        let topics = ContractTopics::new(forced_exit_contract.abi());
+    pub fn new(client: EthereumGateway, forced_exit_contract_addr: H160) -> Self {
+        let topics = ContractTopics::new(&forced_exit_contract());
         Self {
-            forced_exit_contract,
-            web3,
+            client,
+            forced_exit_contract_addr,
             topics,
         }
     }
@@ -88,8 +84,8 @@ impl EthHttpClient {
         let from = BlockNumber::from(from);
         let to = BlockNumber::from(to);
         get_contract_events(
-            &self.web3,
-            self.forced_exit_contract.address(),
+            &self.client,
+            self.forced_exit_contract_addr,
             from,
             to,
             topics,
@@ -118,7 +114,7 @@ impl EthClient for EthHttpClient {
     }
 
     async fn block_number(&self) -> anyhow::Result<u64> {
-        get_web3_block_number(&self.web3).await
+        Ok(self.client.block_number().await?.as_u64())
     }
 }
 
@@ -388,9 +384,10 @@ pub fn run_forced_exit_contract_watcher(
     connection_pool: ConnectionPool,
     config: ZkSyncConfig,
 ) -> JoinHandle<()> {
-    let transport = web3::transports::Http::new(&config.eth_client.web3_url[0]).unwrap();
-    let web3 = web3::Web3::new(transport);
-    let eth_client = EthHttpClient::new(web3, config.contracts.forced_exit_addr);
+    let eth_client = EthHttpClient::new(
+        EthereumGateway::from_config(&config),
+        config.contracts.forced_exit_addr,
+    );
 
     tokio::spawn(async move {
         // We should not proceed if the feature is disabled
@@ -424,6 +421,22 @@ pub fn run_forced_exit_contract_watcher(
             "Unexpected error while trying to wait for unconfirmed forced_exit transactions",
         );
 
+        // Requests may be paid both on L1 and L2, the sender is shared by the watchers
+        let forced_exit_sender = Arc::new(forced_exit_sender);
+        let payment_watcher = ForcedExitPaymentWatcher::new(
+            connection_pool.clone(),
+            config.clone(),
+            forced_exit_sender.clone(),
+        );
+        tokio::spawn(payment_watcher.run());
+
+        // Dust accounts are emptied by the ForcedExit transactions of the same sender
+        if config.dust_collector.enabled {
+            let dust_collector =
+                DustCollector::new(connection_pool, config.clone(), forced_exit_sender.clone());
+            tokio::spawn(dust_collector.run());
+        }
+
         let contract_watcher = ForcedExitContractWatcher::new(
             core_interaction_wrapper,
             config,
@@ -437,7 +450,7 @@ pub fn run_forced_exit_contract_watcher(
 }
 
 pub async fn get_contract_events<T>(
-    web3: &Web3<Http>,
+    client: &EthereumGateway,
     contract_address: Address,
     from: BlockNumber,
     to: BlockNumber,
@@ -454,7 +467,7 @@ where
         .topics(Some(topics), None, None, None)
         .build();
 
-    web3.eth()
+    client
         .logs(filter)
         .await?
         .into_iter()
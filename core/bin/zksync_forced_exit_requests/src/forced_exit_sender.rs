@@ -1,18 +1,23 @@
-use std::ops::AddAssign;
+use std::{ops::AddAssign, sync::Arc};
 
 use chrono::{DateTime, Utc};
 use num::BigUint;
-use tokio::time;
+use tokio::{sync::Mutex, time};
 
 use zksync_config::ZkSyncConfig;
 
 use zksync_types::{
-    forced_exit_requests::ForcedExitRequest, tx::TimeRange, tx::TxHash, AccountId, Address, Nonce,
-    TokenId, ZkSyncTx,
+    forced_exit_requests::{
+        ForcedExitL2Payment, ForcedExitPaymentStatus, ForcedExitRequest, ForcedExitRequestId,
+    },
+    key_audit::{KeyUsage, OperatorKey},
+    tx::TimeRange,
+    tx::TxHash,
+    AccountId, Address, Nonce, TokenId, ZkSyncTx,
 };
 
-use zksync_types::ForcedExit;
 use zksync_types::SignedZkSyncTx;
+use zksync_types::{ForcedExit, Transfer};
 
 use crate::{core_interaction_wrapper::CoreInteractionWrapper, utils};
 
@@ -27,16 +32,28 @@ pub trait ForcedExitSender {
     async fn process_request(&self, amount: BigUint, submission_time: DateTime<Utc>);
 }
 
+#[async_trait::async_trait]
+impl<S: ForcedExitSender + Sync + Send> ForcedExitSender for Arc<S> {
+    async fn process_request(&self, amount: BigUint, submission_time: DateTime<Utc>) {
+        self.as_ref().process_request(amount, submission_time).await
+    }
+}
+
 pub struct MempoolForcedExitSender<T: CoreInteractionWrapper> {
     core_interaction_wrapper: T,
     config: ZkSyncConfig,
     forced_exit_sender_account_id: AccountId,
     sender_private_key: PrivateKey<Engine>,
+    // Requests paid on L1 and on L2 are processed concurrently, but the transactions
+    // of the sender account must be submitted one by one to keep the nonces correct
+    processing_lock: Mutex<()>,
 }
 
 #[async_trait::async_trait]
 impl<T: CoreInteractionWrapper + Sync + Send> ForcedExitSender for MempoolForcedExitSender<T> {
     async fn process_request(&self, amount: BigUint, submission_time: DateTime<Utc>) {
+        let _lock = self.processing_lock.lock().await;
+
         let mut attempts: u32 = 0;
         // Typically this should not run any longer than 1 iteration
         // In case something bad happens we do not want the server crush because
@@ -76,6 +93,7 @@ impl<T: CoreInteractionWrapper> MempoolForcedExitSender<T> {
             forced_exit_sender_account_id,
             config,
             sender_private_key,
+            processing_lock: Mutex::new(()),
         }
     }
 
@@ -102,6 +120,32 @@ impl<T: CoreInteractionWrapper> MempoolForcedExitSender<T> {
         }
     }
 
+    pub fn build_refund(
+        &self,
+        nonce: Nonce,
+        to: Address,
+        token: TokenId,
+        amount: BigUint,
+    ) -> SignedZkSyncTx {
+        let tx = Transfer::new_signed(
+            self.forced_exit_sender_account_id,
+            self.config.forced_exit_requests.sender_account_address,
+            to,
+            token,
+            amount,
+            BigUint::from(0u32),
+            nonce,
+            TimeRange::default(),
+            &self.sender_private_key,
+        )
+        .expect("Failed to create signed refund Transfer transaction");
+
+        SignedZkSyncTx {
+            tx: ZkSyncTx::Transfer(Box::new(tx)),
+            eth_sign_data: None,
+        }
+    }
+
     pub async fn build_transactions(
         &self,
         // storage: &mut StorageProcessor<'_>,
@@ -119,10 +163,30 @@ impl<T: CoreInteractionWrapper> MempoolForcedExitSender<T> {
             transactions.push(self.build_forced_exit(sender_nonce, fe_request.target, token));
             sender_nonce.add_assign(1);
         }
+        self.audit_key_usage("ForcedExit", &transactions).await?;
 
         Ok(transactions)
     }
 
+    // Records the transactions signed with the sender key to the audit log.
+    // Signing failures are not recorded, since they abort the sender
+    async fn audit_key_usage(&self, operation: &str, txs: &[SignedZkSyncTx]) -> anyhow::Result<()> {
+        let usages: Vec<_> = txs
+            .iter()
+            .map(|tx| {
+                KeyUsage::new(
+                    OperatorKey::ForcedExitSender,
+                    operation,
+                    &tx.tx.get_bytes(),
+                    Ok(()),
+                )
+            })
+            .collect();
+        self.core_interaction_wrapper
+            .record_key_usage(&usages)
+            .await
+    }
+
     // Returns the id the request if it should be fulfilled,
     // error otherwise
     pub fn check_request(
@@ -225,6 +289,18 @@ impl<T: CoreInteractionWrapper> MempoolForcedExitSender<T> {
         amount: BigUint,
         submission_time: DateTime<Utc>,
     ) -> anyhow::Result<()> {
+        self.fulfill_request(amount, submission_time).await?;
+        Ok(())
+    }
+
+    // Sends the ForcedExit transactions for the request paid with the given amount.
+    // Returns the id of the fulfilled request or `None` if the payment does not
+    // correspond to any request that can be fulfilled
+    pub async fn fulfill_request(
+        &self,
+        amount: BigUint,
+        submission_time: DateTime<Utc>,
+    ) -> anyhow::Result<Option<ForcedExitRequestId>> {
         let (id, amount) = utils::extract_id_from_amount(
             amount,
             self.config.forced_exit_requests.digits_in_id as u32,
@@ -237,7 +313,7 @@ impl<T: CoreInteractionWrapper> MempoolForcedExitSender<T> {
             fe_request.unwrap()
         } else {
             // The request was not valid, that's fine
-            return Ok(());
+            return Ok(None);
         };
 
         let txs = self.build_transactions(fe_request.clone()).await?;
@@ -249,7 +325,7 @@ impl<T: CoreInteractionWrapper> MempoolForcedExitSender<T> {
             .await?;
         if !is_request_possible {
             // If not possible at all, return without sending any transactions
-            return Ok(());
+            return Ok(None);
         }
         let hashes = self
             .core_interaction_wrapper
@@ -261,7 +337,177 @@ impl<T: CoreInteractionWrapper> MempoolForcedExitSender<T> {
         self.wait_until_comitted(hashes[0]).await?;
         self.core_interaction_wrapper.set_fulfilled_at(id).await?;
 
-        Ok(())
+        Ok(Some(id))
+    }
+
+    // Processes the ForcedExit request paid with the L2 transfer to the sender account.
+    // Transfers that do not pay for any request which can be fulfilled are sent back.
+    //
+    // The payment is stored as pending before the transactions are submitted, so it's never
+    // processed twice, and is marked as fulfilled or refunded once they are committed. If the
+    // transactions fail, the payment is removed to be processed again. Should the server crash
+    // in between, the pending payment with its `refund_tx_hash` (or the `fulfilled_by` of the
+    // request) points to the transactions to be checked manually.
+    pub async fn process_l2_payment(
+        &self,
+        tx_hash: TxHash,
+        transfer: &Transfer,
+        submission_time: DateTime<Utc>,
+    ) -> anyhow::Result<ForcedExitPaymentStatus> {
+        let _lock = self.processing_lock.lock().await;
+
+        if let Some(payment) = self
+            .core_interaction_wrapper
+            .get_l2_payment(tx_hash)
+            .await?
+        {
+            return Ok(payment.status);
+        }
+
+        // The fee for the ForcedExit request is set in wei
+        let is_eth = transfer.token == TokenId(0);
+        let (request_id, price) = utils::extract_id_from_amount(
+            transfer.amount.clone(),
+            self.config.forced_exit_requests.digits_in_id as u32,
+        );
+
+        let mut payment = ForcedExitL2Payment {
+            tx_hash,
+            from: transfer.from,
+            amount: transfer.amount.clone(),
+            request_id: if is_eth { Some(request_id) } else { None },
+            status: ForcedExitPaymentStatus::Pending,
+            refund_tx_hash: None,
+            created_at: Utc::now(),
+        };
+
+        if is_eth {
+            let request = self
+                .core_interaction_wrapper
+                .get_request_by_id(request_id)
+                .await?;
+            if self.check_request(price, submission_time, request.clone()) {
+                // `check_request` already checked that the request exists
+                let request = request.unwrap();
+                if self
+                    .core_interaction_wrapper
+                    .check_forced_exit_request(&request)
+                    .await?
+                {
+                    let txs = self.build_transactions(request.clone()).await?;
+                    self.core_interaction_wrapper
+                        .store_l2_payment(&payment)
+                        .await?;
+
+                    let result = self.send_request_transactions(&request, txs).await;
+                    return self
+                        .complete_l2_payment(tx_hash, ForcedExitPaymentStatus::Fulfilled, result)
+                        .await;
+                }
+            }
+        }
+
+        let nonce = self
+            .core_interaction_wrapper
+            .get_nonce(self.forced_exit_sender_account_id)
+            .await?
+            .expect("Forced Exit sender account does not have nonce");
+        let refund = self.build_refund(
+            nonce,
+            transfer.from,
+            transfer.token,
+            transfer.amount.clone(),
+        );
+
+        self.audit_key_usage("Transfer", std::slice::from_ref(&refund))
+            .await?;
+
+        payment.refund_tx_hash = Some(refund.hash());
+        self.core_interaction_wrapper
+            .store_l2_payment(&payment)
+            .await?;
+
+        let result = self.send_refund(refund).await;
+        if let Ok(refund_hash) = &result {
+            vlog::info!(
+                "ForcedExit payment {} was refunded with {}",
+                tx_hash.to_string(),
+                refund_hash.to_string()
+            );
+        }
+        self.complete_l2_payment(tx_hash, ForcedExitPaymentStatus::Refunded, result)
+            .await
+    }
+
+    // Sends the ForcedExit transactions of the request and waits until they are committed
+    async fn send_request_transactions(
+        &self,
+        request: &ForcedExitRequest,
+        txs: Vec<SignedZkSyncTx>,
+    ) -> anyhow::Result<()> {
+        let hashes = self
+            .core_interaction_wrapper
+            .send_and_save_txs_batch(request, txs)
+            .await?;
+        self.wait_until_comitted(hashes[0]).await?;
+        self.core_interaction_wrapper
+            .set_fulfilled_at(request.id)
+            .await
+    }
+
+    // Sends the refund transaction and waits until it is committed
+    async fn send_refund(&self, refund: SignedZkSyncTx) -> anyhow::Result<TxHash> {
+        let refund_hash = self.core_interaction_wrapper.send_tx(refund).await?;
+        self.wait_until_comitted(refund_hash).await?;
+        Ok(refund_hash)
+    }
+
+    // Stores the outcome of the pending payment once its transactions are committed,
+    // or removes the payment if they failed
+    async fn complete_l2_payment<T>(
+        &self,
+        tx_hash: TxHash,
+        status: ForcedExitPaymentStatus,
+        result: anyhow::Result<T>,
+    ) -> anyhow::Result<ForcedExitPaymentStatus> {
+        if let Err(err) = result {
+            self.core_interaction_wrapper
+                .remove_l2_payment(tx_hash)
+                .await?;
+            return Err(err);
+        }
+
+        self.core_interaction_wrapper
+            .update_l2_payment_status(tx_hash, status)
+            .await?;
+        Ok(status)
+    }
+
+    // Withdraws the given balances of the dust account to its address on L1.
+    // Returns the hashes of the sent ForcedExit transactions once they are committed
+    pub async fn collect_dust(
+        &self,
+        target: Address,
+        tokens: &[TokenId],
+    ) -> anyhow::Result<Vec<TxHash>> {
+        let _lock = self.processing_lock.lock().await;
+
+        let mut sender_nonce = self
+            .core_interaction_wrapper
+            .get_nonce(self.forced_exit_sender_account_id)
+            .await?
+            .expect("Forced Exit sender account does not have nonce");
+        let mut txs = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            txs.push(self.build_forced_exit(sender_nonce, target, *token));
+            sender_nonce.add_assign(1);
+        }
+        self.audit_key_usage("ForcedExit", &txs).await?;
+
+        let hashes = self.core_interaction_wrapper.send_txs_batch(txs).await?;
+        self.wait_until_comitted(hashes[0]).await?;
+
+        Ok(hashes)
     }
 }
 #[cfg(test)]
@@ -385,4 +631,190 @@ mod test {
             1
         );
     }
+
+    #[tokio::test]
+    async fn test_l2_payment() {
+        let day = chrono::Duration::days(1);
+
+        let config = ZkSyncConfig::from_env();
+        let forced_exit_requests = ForcedExitRequestsConfig {
+            digits_in_id: 10,
+            ..config.forced_exit_requests
+        };
+        let config = ZkSyncConfig {
+            forced_exit_requests,
+            ..config
+        };
+        let sender_address = config.forced_exit_requests.sender_account_address;
+
+        let forced_exit_sender = get_test_forced_exit_sender(Some(config));
+        let wrapper = &forced_exit_sender.core_interaction_wrapper;
+
+        add_request(
+            &wrapper.requests,
+            ForcedExitRequest {
+                id: 12,
+                target: Address::random(),
+                tokens: vec![TokenId(1), TokenId(2)],
+                price_in_wei: BigUint::from_str("10000000000").unwrap(),
+                valid_until: Utc::now().add(day),
+                created_at: Utc::now(),
+                fulfilled_by: None,
+                fulfilled_at: None,
+            },
+        );
+
+        let payer = Address::random();
+        let payment = |token: u16, amount: &str| {
+            let transfer = Transfer::new(
+                AccountId(1),
+                payer,
+                sender_address,
+                TokenId(token),
+                BigUint::from_str(amount).unwrap(),
+                BigUint::from(0u32),
+                Nonce(0),
+                TimeRange::default(),
+                None,
+            );
+            let hash = ZkSyncTx::Transfer(Box::new(transfer.clone())).hash();
+            (hash, transfer)
+        };
+        let sent_txs = || wrapper.sent_txs.lock().unwrap().clone();
+
+        // Unknown request id, the payment is sent back
+        let (hash, transfer) = payment(0, "10000000011");
+        let status = forced_exit_sender
+            .process_l2_payment(hash, &transfer, Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(status, ForcedExitPaymentStatus::Refunded);
+        let txs = sent_txs();
+        assert_eq!(txs.len(), 1);
+        match &txs[0].tx {
+            ZkSyncTx::Transfer(refund) => {
+                assert_eq!(refund.to, payer);
+                assert_eq!(refund.amount, transfer.amount);
+            }
+            tx => panic!("Unexpected refund transaction: {:?}", tx),
+        }
+
+        // The price is paid in the wrong token, the payment is sent back
+        let (hash, transfer) = payment(1, "10000000012");
+        let status = forced_exit_sender
+            .process_l2_payment(hash, &transfer, Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(status, ForcedExitPaymentStatus::Refunded);
+        assert_eq!(sent_txs().len(), 2);
+
+        // The payment is correct, a ForcedExit is sent for every token
+        let (hash, transfer) = payment(0, "10000000012");
+        let status = forced_exit_sender
+            .process_l2_payment(hash, &transfer, Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(status, ForcedExitPaymentStatus::Fulfilled);
+        let txs = sent_txs();
+        assert_eq!(txs.len(), 4);
+        assert!(txs[2..]
+            .iter()
+            .all(|tx| matches!(tx.tx, ZkSyncTx::ForcedExit(_))));
+
+        // The same payment is not processed twice
+        let status = forced_exit_sender
+            .process_l2_payment(hash, &transfer, Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(status, ForcedExitPaymentStatus::Fulfilled);
+        assert_eq!(sent_txs().len(), 4);
+        assert_eq!(wrapper.l2_payments.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_failed_l2_payment() {
+        let config = ZkSyncConfig::from_env();
+        let mut core_interaction_wrapper = MockCoreInteractionWrapper::default();
+        core_interaction_wrapper
+            .tx_receipt
+            .as_mut()
+            .unwrap()
+            .success = false;
+        let forced_exit_sender = MempoolForcedExitSender::new(
+            core_interaction_wrapper,
+            config.clone(),
+            AccountId(TEST_ACCOUNT_FORCED_EXIT_SENDER_ID),
+        );
+
+        let transfer = Transfer::new(
+            AccountId(1),
+            Address::random(),
+            config.forced_exit_requests.sender_account_address,
+            TokenId(1),
+            BigUint::from(1000u32),
+            BigUint::from(0u32),
+            Nonce(0),
+            TimeRange::default(),
+            None,
+        );
+        let hash = ZkSyncTx::Transfer(Box::new(transfer.clone())).hash();
+
+        // The refund fails, so the payment is not marked as refunded and will be processed again
+        assert!(forced_exit_sender
+            .process_l2_payment(hash, &transfer, Utc::now())
+            .await
+            .is_err());
+        assert!(forced_exit_sender
+            .core_interaction_wrapper
+            .l2_payments
+            .lock()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_collect_dust() {
+        let forced_exit_sender = get_test_forced_exit_sender(None);
+        let target = Address::random();
+
+        let hashes = forced_exit_sender
+            .collect_dust(target, &[TokenId(0), TokenId(3)])
+            .await
+            .unwrap();
+        assert_eq!(hashes.len(), 2);
+
+        let txs = forced_exit_sender
+            .core_interaction_wrapper
+            .sent_txs
+            .lock()
+            .unwrap()
+            .clone();
+        let forced_exits: Vec<_> = txs
+            .iter()
+            .map(|tx| match &tx.tx {
+                ZkSyncTx::ForcedExit(forced_exit) => {
+                    (forced_exit.target, forced_exit.token, forced_exit.nonce)
+                }
+                tx => panic!("Unexpected transaction: {:?}", tx),
+            })
+            .collect();
+        assert_eq!(
+            forced_exits,
+            vec![
+                (target, TokenId(0), Nonce(0)),
+                (target, TokenId(3), Nonce(1))
+            ]
+        );
+
+        let key_usages = forced_exit_sender
+            .core_interaction_wrapper
+            .key_usages
+            .lock()
+            .unwrap()
+            .clone();
+        assert_eq!(key_usages.len(), 2);
+        assert!(key_usages
+            .iter()
+            .all(|usage| usage.key == OperatorKey::ForcedExitSender && usage.success));
+    }
 }
@@ -0,0 +1,121 @@
+//! Watcher of the ForcedExit requests paid with L2 transfers.
+//!
+//! Instead of sending the fee to the ForcedExit contract on L1, users may transfer it to
+//! the ForcedExit sender account on L2. The request ID is encoded in the lower digits of
+//! the transferred amount the same way as for L1 payments. The watcher follows the event log,
+//! and for every transfer to the sender account either submits the ForcedExit transactions
+//! for the paid request or sends the transfer back if it does not pay for any request which
+//! can be fulfilled.
+
+use std::sync::Arc;
+
+use num::Zero;
+use tokio::time;
+use zksync_config::ZkSyncConfig;
+use zksync_storage::ConnectionPool;
+use zksync_types::{event::ChainEvent, ZkSyncTx};
+
+use crate::{
+    core_interaction_wrapper::CoreInteractionWrapper, forced_exit_sender::MempoolForcedExitSender,
+};
+
+/// Name of the watcher in the `event_log_consumers` table.
+const CONSUMER_NAME: &str = "forced_exit_l2_payments";
+/// Max amount of the events processed within one iteration.
+const EVENTS_BATCH_SIZE: u32 = 100;
+
+pub struct ForcedExitPaymentWatcher<T: CoreInteractionWrapper> {
+    connection_pool: ConnectionPool,
+    config: ZkSyncConfig,
+    forced_exit_sender: Arc<MempoolForcedExitSender<T>>,
+}
+
+impl<T: CoreInteractionWrapper + Sync + Send> ForcedExitPaymentWatcher<T> {
+    pub fn new(
+        connection_pool: ConnectionPool,
+        config: ZkSyncConfig,
+        forced_exit_sender: Arc<MempoolForcedExitSender<T>>,
+    ) -> Self {
+        Self {
+            connection_pool,
+            config,
+            forced_exit_sender,
+        }
+    }
+
+    pub async fn poll(&self) -> anyhow::Result<()> {
+        let mut storage = self.connection_pool.access_storage().await?;
+        let from_offset = match storage
+            .event_schema()
+            .get_consumer_offset(CONSUMER_NAME)
+            .await?
+        {
+            Some(offset) => offset,
+            // Only the payments made after the first start of the watcher are handled.
+            None => storage
+                .event_schema()
+                .get_last_offset()
+                .await?
+                .map(|offset| offset + 1)
+                .unwrap_or_default(),
+        };
+        let events = storage
+            .event_schema()
+            .load_events(from_offset, EVENTS_BATCH_SIZE)
+            .await?;
+        drop(storage);
+        let next_offset = match events.last() {
+            Some(record) => record.offset + 1,
+            None => return Ok(()),
+        };
+
+        // Processed payments are stored, so if the batch fails midway, the payments
+        // handled before the failure are skipped on retry.
+        let sender_address = self.config.forced_exit_requests.sender_account_address;
+        for record in events {
+            if let ChainEvent::TxExecuted {
+                tx_hash,
+                tx: ZkSyncTx::Transfer(transfer),
+                success: true,
+                ..
+            } = &record.event
+            {
+                let is_payment = transfer.to == sender_address
+                    && transfer.from != sender_address
+                    && !transfer.amount.is_zero();
+                if is_payment {
+                    let status = self
+                        .forced_exit_sender
+                        .process_l2_payment(*tx_hash, transfer, record.created_at)
+                        .await?;
+                    vlog::info!(
+                        "ForcedExit payment {} processed: {}",
+                        tx_hash.to_string(),
+                        status.as_str()
+                    );
+                }
+            }
+        }
+
+        self.connection_pool
+            .access_storage()
+            .await?
+            .event_schema()
+            .update_consumer_offset(CONSUMER_NAME, next_offset)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn run(self) {
+        let mut timer = time::interval(self.config.forced_exit_requests.poll_interval());
+
+        loop {
+            timer.tick().await;
+
+            if let Err(err) = self.poll().await {
+                vlog::error!("Failed to process ForcedExit L2 payments: {}", err);
+            }
+        }
+    }
+}
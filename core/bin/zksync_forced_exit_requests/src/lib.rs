@@ -7,8 +7,10 @@ use zksync_api::core_api_client::CoreApiClient;
 use forced_exit_sender::ForcedExitSender;
 
 mod core_interaction_wrapper;
+pub mod dust_collector;
 pub mod eth_watch;
 pub mod forced_exit_sender;
+pub mod l2_watch;
 pub mod prepare_forced_exit_sender;
 mod utils;
 
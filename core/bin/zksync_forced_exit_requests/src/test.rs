@@ -4,7 +4,10 @@ use chrono::Utc;
 use zksync_storage::chain::operations_ext::records::TxReceiptResponse;
 use zksync_types::Nonce;
 use zksync_types::{
-    forced_exit_requests::{ForcedExitRequest, ForcedExitRequestId},
+    forced_exit_requests::{
+        ForcedExitL2Payment, ForcedExitPaymentStatus, ForcedExitRequest, ForcedExitRequestId,
+    },
+    key_audit::KeyUsage,
     tx::TxHash,
     AccountId, SignedZkSyncTx,
 };
@@ -18,6 +21,8 @@ pub struct MockCoreInteractionWrapper {
     pub sent_txs: Mutex<Vec<SignedZkSyncTx>>,
     // It is easier when keeping track of the deleted txs
     pub deleted_requests: Mutex<Vec<ForcedExitRequest>>,
+    pub l2_payments: Mutex<Vec<ForcedExitL2Payment>>,
+    pub key_usages: Mutex<Vec<KeyUsage>>,
 }
 
 impl Default for MockCoreInteractionWrapper {
@@ -36,6 +41,8 @@ impl Default for MockCoreInteractionWrapper {
             }),
             sent_txs: Mutex::new(vec![]),
             deleted_requests: Mutex::new(vec![]),
+            l2_payments: Mutex::new(vec![]),
+            key_usages: Mutex::new(vec![]),
         }
     }
 }
@@ -168,6 +175,64 @@ impl CoreInteractionWrapper for MockCoreInteractionWrapper {
         // For tests it is better to just return true all the time
         Ok(true)
     }
+
+    async fn send_tx(&self, tx: SignedZkSyncTx) -> anyhow::Result<TxHash> {
+        let hash = tx.hash();
+        self.lock_sent_txs().push(tx);
+
+        Ok(hash)
+    }
+
+    async fn send_txs_batch(&self, mut txs: Vec<SignedZkSyncTx>) -> anyhow::Result<Vec<TxHash>> {
+        let hashes: Vec<TxHash> = txs.iter().map(|tx| tx.hash()).collect();
+        self.lock_sent_txs().append(&mut txs);
+
+        Ok(hashes)
+    }
+
+    async fn get_l2_payment(&self, tx_hash: TxHash) -> anyhow::Result<Option<ForcedExitL2Payment>> {
+        let payments = self.l2_payments.lock().unwrap();
+
+        Ok(payments.iter().find(|p| p.tx_hash == tx_hash).cloned())
+    }
+
+    async fn store_l2_payment(&self, payment: &ForcedExitL2Payment) -> anyhow::Result<bool> {
+        let mut payments = self.l2_payments.lock().unwrap();
+        if payments.iter().any(|p| p.tx_hash == payment.tx_hash) {
+            return Ok(false);
+        }
+        payments.push(payment.clone());
+
+        Ok(true)
+    }
+
+    async fn update_l2_payment_status(
+        &self,
+        tx_hash: TxHash,
+        status: ForcedExitPaymentStatus,
+    ) -> anyhow::Result<()> {
+        let mut payments = self.l2_payments.lock().unwrap();
+        if let Some(payment) = payments.iter_mut().find(|p| p.tx_hash == tx_hash) {
+            payment.status = status;
+        }
+
+        Ok(())
+    }
+
+    async fn remove_l2_payment(&self, tx_hash: TxHash) -> anyhow::Result<()> {
+        self.l2_payments
+            .lock()
+            .unwrap()
+            .retain(|p| p.tx_hash != tx_hash);
+
+        Ok(())
+    }
+
+    async fn record_key_usage(&self, usages: &[KeyUsage]) -> anyhow::Result<()> {
+        self.key_usages.lock().unwrap().extend_from_slice(usages);
+
+        Ok(())
+    }
 }
 
 pub fn add_request(requests: &Mutex<Vec<ForcedExitRequest>>, new_request: ForcedExitRequest) {
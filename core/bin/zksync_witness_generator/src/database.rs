@@ -5,14 +5,22 @@
 
 // Built-in
 use std::clone::Clone;
+// External uses
+use bigdecimal::BigDecimal;
 // Workspace uses
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
+use zksync_storage::external_provers::{
+    records::{StorageExternalProver, StorageProverLease},
+    LeaseTerms,
+};
+use zksync_storage::prover::records::StorageProverJobProgress;
 use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_tree_cache::TreeCacheStorage;
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
     block::Block,
-    prover::{ProverJob, ProverJobType},
-    AccountMap, AccountUpdates, BlockNumber,
+    prover::{ProofProgress, ProverJob, ProverJobType},
+    AccountMap, AccountUpdates, Address, BlockNumber,
 };
 // Local uses
 use crate::DatabaseInterface;
@@ -23,11 +31,16 @@ use crate::DatabaseInterface;
 pub struct Database {
     /// Connection to the database.
     db_pool: ConnectionPool,
+    /// Storage of the account tree caches, which may be kept outside of the database.
+    tree_cache: TreeCacheStorage,
 }
 
 impl Database {
-    pub fn new(db_pool: ConnectionPool) -> Self {
-        Self { db_pool }
+    pub fn new(db_pool: ConnectionPool, tree_cache: TreeCacheStorage) -> Self {
+        Self {
+            db_pool,
+            tree_cache,
+        }
     }
 }
 
@@ -140,11 +153,7 @@ impl DatabaseInterface for Database {
         &self,
         connection: &mut StorageProcessor<'_>,
     ) -> anyhow::Result<Option<(BlockNumber, serde_json::Value)>> {
-        let tree_cache = connection
-            .chain()
-            .block_schema()
-            .get_account_tree_cache()
-            .await?;
+        let tree_cache = self.tree_cache.load_latest(connection).await?;
 
         Ok(tree_cache)
     }
@@ -175,6 +184,34 @@ impl DatabaseInterface for Database {
         Ok(())
     }
 
+    async fn record_proof_progress(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        job_id: i32,
+        prover_name: &str,
+        progress: ProofProgress,
+    ) -> anyhow::Result<()> {
+        connection
+            .prover_schema()
+            .record_proof_progress(job_id, prover_name, progress)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn load_block_proof_progress(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        block_number: BlockNumber,
+    ) -> anyhow::Result<Vec<StorageProverJobProgress>> {
+        let jobs = connection
+            .prover_schema()
+            .load_block_proof_progress(block_number)
+            .await?;
+
+        Ok(jobs)
+    }
+
     async fn store_proof(
         &self,
         connection: &mut StorageProcessor<'_>,
@@ -219,6 +256,19 @@ impl DatabaseInterface for Database {
         Ok(())
     }
 
+    async fn release_prover_job(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        job_id: i32,
+    ) -> anyhow::Result<()> {
+        connection
+            .prover_schema()
+            .release_prover_job(job_id)
+            .await?;
+
+        Ok(())
+    }
+
     async fn load_committed_state(
         &self,
         connection: &mut StorageProcessor<'_>,
@@ -254,11 +304,7 @@ impl DatabaseInterface for Database {
         block: BlockNumber,
         tree_cache: serde_json::Value,
     ) -> anyhow::Result<()> {
-        connection
-            .chain()
-            .block_schema()
-            .store_account_tree_cache(block, tree_cache)
-            .await?;
+        self.tree_cache.store(connection, block, tree_cache).await?;
 
         Ok(())
     }
@@ -285,4 +331,114 @@ impl DatabaseInterface for Database {
 
         Ok(count)
     }
+
+    async fn register_external_prover(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        name: &str,
+        api_key: &str,
+        reward_address: Address,
+        stake: BigDecimal,
+    ) -> anyhow::Result<i64> {
+        let prover_id = connection
+            .external_provers_schema()
+            .register_prover(name, api_key, reward_address, stake)
+            .await?;
+
+        Ok(prover_id)
+    }
+
+    async fn load_external_prover(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        api_key: &str,
+    ) -> anyhow::Result<Option<StorageExternalProver>> {
+        let prover = connection
+            .external_provers_schema()
+            .prover_by_api_key(api_key)
+            .await?;
+
+        Ok(prover)
+    }
+
+    async fn lease_job_to_external_prover(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        prover: &StorageExternalProver,
+        terms: &LeaseTerms,
+    ) -> anyhow::Result<Option<(ProverJob, StorageProverLease)>> {
+        let lease = connection
+            .external_provers_schema()
+            .lease_job(prover, terms)
+            .await?;
+
+        Ok(lease)
+    }
+
+    async fn load_external_prover_lease(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        prover_id: i64,
+        lease_id: i64,
+    ) -> anyhow::Result<Option<StorageProverLease>> {
+        let lease = connection
+            .external_provers_schema()
+            .load_active_lease(prover_id, lease_id)
+            .await?;
+
+        Ok(lease)
+    }
+
+    async fn load_external_prover_leases(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        prover_id: i64,
+        limit: u32,
+    ) -> anyhow::Result<Vec<StorageProverLease>> {
+        let leases = connection
+            .external_provers_schema()
+            .load_leases(prover_id, limit)
+            .await?;
+
+        Ok(leases)
+    }
+
+    async fn complete_external_prover_lease(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        lease_id: i64,
+    ) -> anyhow::Result<bool> {
+        let completed = connection
+            .external_provers_schema()
+            .complete_lease(lease_id)
+            .await?;
+
+        Ok(completed)
+    }
+
+    async fn slash_external_prover_lease(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        lease_id: i64,
+        reason: &str,
+    ) -> anyhow::Result<bool> {
+        let slashed = connection
+            .external_provers_schema()
+            .slash_lease(lease_id, reason)
+            .await?;
+
+        Ok(slashed)
+    }
+
+    async fn load_expired_external_prover_leases(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<i64>> {
+        let leases = connection
+            .external_provers_schema()
+            .expired_leases()
+            .await?;
+
+        Ok(leases)
+    }
 }
@@ -6,14 +6,21 @@
 // Built-in
 use std::clone::Clone;
 use std::marker::{Send, Sync};
+// External uses
+use bigdecimal::BigDecimal;
 // Workspace uses
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
+use zksync_storage::external_provers::{
+    records::{StorageExternalProver, StorageProverLease},
+    LeaseTerms,
+};
+use zksync_storage::prover::records::StorageProverJobProgress;
 use zksync_storage::StorageProcessor;
 use zksync_types::aggregated_operations::{AggregatedActionType, AggregatedOperation};
 use zksync_types::{
     block::Block,
-    prover::{ProverJob, ProverJobType},
-    AccountMap, AccountUpdates, BlockNumber,
+    prover::{ProofProgress, ProverJob, ProverJobType},
+    AccountMap, AccountUpdates, Address, BlockNumber,
 };
 
 /// Abstract database access trait.
@@ -92,6 +99,22 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
         prover_name: &str,
     ) -> anyhow::Result<()>;
 
+    /// Stores the stage of the proof generation reported by the prover working on the job.
+    async fn record_proof_progress(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        job_id: i32,
+        prover_name: &str,
+        progress: ProofProgress,
+    ) -> anyhow::Result<()>;
+
+    /// Returns the prover jobs covering the block along with the progress reported by the provers.
+    async fn load_block_proof_progress(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        block_number: BlockNumber,
+    ) -> anyhow::Result<Vec<StorageProverJobProgress>>;
+
     async fn store_proof(
         &self,
         connection: &mut StorageProcessor<'_>,
@@ -115,6 +138,13 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
         prover_name: &str,
     ) -> anyhow::Result<()>;
 
+    /// Returns the job refused by the prover to the queue.
+    async fn release_prover_job(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        job_id: i32,
+    ) -> anyhow::Result<()>;
+
     async fn load_committed_state(
         &self,
         connection: &mut StorageProcessor<'_>,
@@ -146,4 +176,61 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
         &self,
         connection: &mut StorageProcessor<'_>,
     ) -> anyhow::Result<u32>;
+
+    /// Registers the external prover, returns its ID.
+    async fn register_external_prover(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        name: &str,
+        api_key: &str,
+        reward_address: Address,
+        stake: BigDecimal,
+    ) -> anyhow::Result<i64>;
+
+    /// Returns the active external prover authenticated by the API key.
+    async fn load_external_prover(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        api_key: &str,
+    ) -> anyhow::Result<Option<StorageExternalProver>>;
+
+    async fn lease_job_to_external_prover(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        prover: &StorageExternalProver,
+        terms: &LeaseTerms,
+    ) -> anyhow::Result<Option<(ProverJob, StorageProverLease)>>;
+
+    /// Returns the active lease of the external prover.
+    async fn load_external_prover_lease(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        prover_id: i64,
+        lease_id: i64,
+    ) -> anyhow::Result<Option<StorageProverLease>>;
+
+    async fn load_external_prover_leases(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        prover_id: i64,
+        limit: u32,
+    ) -> anyhow::Result<Vec<StorageProverLease>>;
+
+    async fn complete_external_prover_lease(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        lease_id: i64,
+    ) -> anyhow::Result<bool>;
+
+    async fn slash_external_prover_lease(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        lease_id: i64,
+        reason: &str,
+    ) -> anyhow::Result<bool>;
+
+    async fn load_expired_external_prover_leases(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<i64>>;
 }
@@ -0,0 +1,455 @@
+//! API for the third-party provers.
+//!
+//! External provers are authenticated by the API keys issued on their registration.
+//! Every leased job locks a part of the prover stake as the collateral. The collateral
+//! is unlocked together with the payment once the proof is submitted, and slashed if
+//! the lease expires or the proof is rejected by the operator.
+
+// Built-in
+use std::str::FromStr;
+// External
+use actix_web::{web, HttpResponse, Scope};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use bigdecimal::BigDecimal;
+use chrono::Utc;
+use num::{bigint::ToBigInt, BigInt, BigUint};
+// Workspace deps
+use zksync_crypto::rand::{thread_rng, Rng};
+use zksync_prover_utils::{
+    aggregated_proofs::verify_aggregated_proof,
+    api::{
+        ExternalHeartbeat, ExternalJobLease, ExternalLeaseInfo, ExternalLeaseSlashing,
+        ExternalProofSubmission, ExternalProverAccount, ExternalProverCredentials,
+        ExternalProverRegistration, JobResultData,
+    },
+    verify_block_proof, PlonkVerificationKey,
+};
+use zksync_storage::external_provers::{
+    external_worker_name,
+    records::{StorageExternalProver, StorageProverLease},
+    LeaseTerms,
+};
+use zksync_storage::StorageProcessor;
+use zksync_types::{
+    proof_archive::commitment_public_input,
+    protocol_version::{check_protocol_version, COMPONENTS_PROTOCOL_VERSION},
+    prover::{ProverJobType, ProverLeaseStatus},
+    Address, BlockNumber,
+};
+// Local deps
+use crate::{AppState, DatabaseInterface};
+
+/// Amount of the latest leases returned with the prover account.
+const ACCOUNT_LEASES_LIMIT: u32 = 20;
+/// Slash reason of the leases which proofs were not submitted in time.
+const LEASE_EXPIRED_REASON: &str = "Lease expired";
+/// Slash reason of the leases which proofs didn't pass the verification.
+const INVALID_PROOF_REASON: &str = "Proof is invalid";
+
+fn to_big_decimal(amount: &BigUint) -> BigDecimal {
+    BigDecimal::from(BigInt::from(amount.clone()))
+}
+
+fn to_biguint(amount: &BigDecimal) -> BigUint {
+    amount
+        .to_bigint()
+        .and_then(|int| int.to_biguint())
+        .expect("Negative external prover amount stored in the database")
+}
+
+fn storage_error(err: anyhow::Error) -> actix_web::Error {
+    vlog::warn!("External provers API storage error: {}", err);
+    actix_web::error::ErrorInternalServerError("storage layer error")
+}
+
+impl From<StorageProverLease> for ExternalLeaseInfo {
+    fn from(lease: StorageProverLease) -> Self {
+        Self {
+            lease_id: lease.id,
+            job_id: lease.job_id,
+            status: ProverLeaseStatus::from_str(&lease.status)
+                .expect("Incorrect lease status stored in the database"),
+            collateral: to_biguint(&lease.collateral),
+            payment: to_biguint(&lease.payment),
+            deadline: lease.deadline.timestamp(),
+            slash_reason: lease.slash_reason,
+        }
+    }
+}
+
+impl<DB: DatabaseInterface> AppState<DB> {
+    fn lease_terms(&self) -> actix_web::Result<&LeaseTerms> {
+        self.lease_terms
+            .as_ref()
+            .ok_or_else(|| actix_web::error::ErrorNotFound("external provers API is disabled"))
+    }
+
+    async fn authenticate_external_prover(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        credentials: &BearerAuth,
+    ) -> actix_web::Result<StorageExternalProver> {
+        self.database
+            .load_external_prover(storage, credentials.token())
+            .await
+            .map_err(storage_error)?
+            .ok_or_else(|| actix_web::error::ErrorUnauthorized("invalid API key"))
+    }
+
+    /// Loads the active lease of the prover which deadline hasn't passed yet.
+    async fn load_active_lease(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        prover: &StorageExternalProver,
+        lease_id: i64,
+    ) -> actix_web::Result<StorageProverLease> {
+        let lease = self
+            .database
+            .load_external_prover_lease(storage, prover.id, lease_id)
+            .await
+            .map_err(storage_error)?
+            .ok_or_else(|| actix_web::error::ErrorNotFound("no active lease with such id"))?;
+        if lease.deadline <= Utc::now() {
+            return Err(actix_web::error::ErrorBadRequest("lease expired"));
+        }
+
+        Ok(lease)
+    }
+
+    /// Checks that the submitted proof is made for the commitments of the leased blocks
+    /// and is valid against the verification key. Returns the reason of the rejection
+    /// if the proof is not acceptable.
+    async fn verify_external_proof(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        first_block: BlockNumber,
+        last_block: BlockNumber,
+        proof: JobResultData,
+    ) -> actix_web::Result<Option<&'static str>> {
+        let mut blocks = Vec::new();
+        for block_number in *first_block..=*last_block {
+            let block = self
+                .database
+                .load_block(storage, BlockNumber(block_number))
+                .await
+                .map_err(storage_error)?
+                .ok_or_else(|| {
+                    storage_error(anyhow::format_err!(
+                        "Leased block {} is not stored",
+                        block_number
+                    ))
+                })?;
+            blocks.push((
+                commitment_public_input(block.block_commitment),
+                block.block_chunks_size,
+            ));
+        }
+
+        let verified = match proof {
+            JobResultData::BlockProof(proof) => {
+                let (commitment, block_size) = blocks[0];
+                if proof.serialize_single_proof().inputs != [commitment] {
+                    return Ok(Some("proof is not made for the block commitment"));
+                }
+                web::block(move || {
+                    let vk =
+                        PlonkVerificationKey::read_verification_key_for_main_circuit(block_size)?;
+                    verify_block_proof(&proof, &vk)
+                })
+                .await
+            }
+            JobResultData::AggregatedBlockProof(proof) => {
+                let commitments = blocks
+                    .iter()
+                    .map(|(commitment, _)| *commitment)
+                    .collect::<Vec<_>>();
+                if proof.serialize_aggregated_proof().individual_vk_inputs != commitments {
+                    return Ok(Some("proof is not made for the blocks commitments"));
+                }
+                web::block(move || verify_aggregated_proof(&proof)).await
+            }
+        };
+        let verified = verified.map_err(|err| {
+            vlog::warn!("External proof can't be verified: {}", err);
+            actix_web::error::ErrorInternalServerError("proof verification error")
+        })?;
+
+        Ok(if verified {
+            None
+        } else {
+            Some("proof verification failed")
+        })
+    }
+}
+
+async fn lease<DB: DatabaseInterface>(
+    data: web::Data<AppState<DB>>,
+    credentials: BearerAuth,
+) -> actix_web::Result<HttpResponse> {
+    let terms = data.lease_terms()?;
+    let mut storage = data.access_storage().await?;
+    let prover = data
+        .authenticate_external_prover(&mut storage, &credentials)
+        .await?;
+    if prover.free_stake() < terms.collateral {
+        return Err(actix_web::error::ErrorForbidden(
+            "free stake doesn't cover the job collateral",
+        ));
+    }
+
+    let leased = data
+        .database
+        .lease_job_to_external_prover(&mut storage, &prover, terms)
+        .await
+        .map_err(storage_error)?;
+    let response = leased.map(|(job, lease)| {
+        vlog::info!(
+            "Leased job {} to the external prover '{}'",
+            job.job_id,
+            prover.name
+        );
+        ExternalJobLease {
+            lease_id: lease.id,
+            job_id: job.job_id,
+            first_block: job.first_block,
+            last_block: job.last_block,
+            deadline: lease.deadline.timestamp(),
+            collateral: to_biguint(&lease.collateral),
+            payment: to_biguint(&lease.payment),
+            data: serde_json::from_value(job.job_data).expect("Failed to parse prover job from db"),
+            protocol_version: Some(COMPONENTS_PROTOCOL_VERSION),
+        }
+    });
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+async fn working_on<DB: DatabaseInterface>(
+    data: web::Data<AppState<DB>>,
+    credentials: BearerAuth,
+    r: web::Json<ExternalHeartbeat>,
+) -> actix_web::Result<HttpResponse> {
+    data.lease_terms()?;
+    let mut storage = data.access_storage().await?;
+    let prover = data
+        .authenticate_external_prover(&mut storage, &credentials)
+        .await?;
+    let lease = data
+        .load_active_lease(&mut storage, &prover, r.lease_id)
+        .await?;
+
+    data.database
+        .record_prover_is_working(
+            &mut storage,
+            lease.job_id,
+            &external_worker_name(&prover.name),
+        )
+        .await
+        .map_err(storage_error)?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+async fn publish<DB: DatabaseInterface>(
+    data: web::Data<AppState<DB>>,
+    credentials: BearerAuth,
+    r: web::Json<ExternalProofSubmission>,
+) -> actix_web::Result<HttpResponse> {
+    data.lease_terms()?;
+    // Same as for the operator provers, proofs created by the incompatible versions are rejected.
+    check_protocol_version(r.protocol_version).map_err(|err| {
+        vlog::warn!("Rejected the proof for lease {}: {}", r.lease_id, err);
+        actix_web::error::ErrorConflict(err)
+    })?;
+    let mut storage = data.access_storage().await?;
+    let prover = data
+        .authenticate_external_prover(&mut storage, &credentials)
+        .await?;
+    let mut transaction = storage.start_transaction().await.map_err(storage_error)?;
+    let lease = data
+        .load_active_lease(&mut transaction, &prover, r.lease_id)
+        .await?;
+    let first_block = BlockNumber(lease.first_block as u32);
+    let last_block = BlockNumber(lease.last_block as u32);
+
+    let proof_type_matches = match &r.data {
+        JobResultData::BlockProof(_) => lease.job_type == ProverJobType::SingleProof.to_string(),
+        JobResultData::AggregatedBlockProof(_) => {
+            lease.job_type == ProverJobType::AggregatedProof.to_string()
+        }
+    };
+    if !proof_type_matches {
+        return Err(actix_web::error::ErrorBadRequest(
+            "proof type doesn't match the leased job",
+        ));
+    }
+    // The proofs are checked before storing, since the stored proofs are sent to the
+    // contract as is, and an invalid one would stall the verification of the blocks.
+    let rejection = data
+        .verify_external_proof(&mut transaction, first_block, last_block, r.data.clone())
+        .await?;
+    if let Some(rejection) = rejection {
+        data.database
+            .slash_external_prover_lease(&mut transaction, lease.id, INVALID_PROOF_REASON)
+            .await
+            .map_err(storage_error)?;
+        transaction.commit().await.map_err(storage_error)?;
+
+        vlog::warn!(
+            "Rejected the proof of the external prover '{}' for job {}: {}",
+            prover.name,
+            lease.job_id,
+            rejection
+        );
+        metrics::counter!("external_provers.rejected_proofs", 1);
+        return Err(actix_web::error::ErrorBadRequest(rejection));
+    }
+
+    let storage_result = match &r.data {
+        JobResultData::BlockProof(single_proof) => {
+            data.database
+                .store_proof(&mut transaction, lease.job_id, first_block, single_proof)
+                .await
+        }
+        JobResultData::AggregatedBlockProof(aggregated_proof) => {
+            data.database
+                .store_aggregated_proof(
+                    &mut transaction,
+                    lease.job_id,
+                    first_block,
+                    last_block,
+                    aggregated_proof,
+                )
+                .await
+        }
+    };
+    storage_result.map_err(storage_error)?;
+    data.database
+        .complete_external_prover_lease(&mut transaction, lease.id)
+        .await
+        .map_err(storage_error)?;
+    transaction.commit().await.map_err(storage_error)?;
+
+    vlog::info!(
+        "Received a proof from the external prover '{}' for job: {}, blocks: [{},{}]",
+        prover.name,
+        lease.job_id,
+        first_block,
+        last_block
+    );
+    metrics::counter!("external_provers.completed_leases", 1);
+    Ok(HttpResponse::Ok().finish())
+}
+
+async fn account<DB: DatabaseInterface>(
+    data: web::Data<AppState<DB>>,
+    credentials: BearerAuth,
+) -> actix_web::Result<HttpResponse> {
+    data.lease_terms()?;
+    let mut storage = data.access_storage().await?;
+    let prover = data
+        .authenticate_external_prover(&mut storage, &credentials)
+        .await?;
+    let leases = data
+        .database
+        .load_external_prover_leases(&mut storage, prover.id, ACCOUNT_LEASES_LIMIT)
+        .await
+        .map_err(storage_error)?;
+
+    Ok(HttpResponse::Ok().json(ExternalProverAccount {
+        name: prover.name,
+        reward_address: Address::from_slice(&prover.reward_address),
+        stake: to_biguint(&prover.stake),
+        locked_stake: to_biguint(&prover.locked_stake),
+        slashed_amount: to_biguint(&prover.slashed_amount),
+        earned_amount: to_biguint(&prover.earned_amount),
+        leases: leases.into_iter().map(ExternalLeaseInfo::from).collect(),
+    }))
+}
+
+/// Registers the external prover and issues its API key. Protected by the operator token.
+pub(crate) async fn register<DB: DatabaseInterface>(
+    data: web::Data<AppState<DB>>,
+    r: web::Json<ExternalProverRegistration>,
+) -> actix_web::Result<HttpResponse> {
+    data.lease_terms()?;
+    if r.name.is_empty() {
+        return Err(actix_web::error::ErrorBadRequest("empty name"));
+    }
+
+    let api_key: String = thread_rng()
+        .gen::<[u8; 32]>()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    let mut storage = data.access_storage().await?;
+    let prover_id = data
+        .database
+        .register_external_prover(
+            &mut storage,
+            &r.name,
+            &api_key,
+            r.reward_address,
+            to_big_decimal(&r.stake),
+        )
+        .await
+        .map_err(storage_error)?;
+
+    vlog::info!("Registered the external prover '{}'", r.name);
+    Ok(HttpResponse::Ok().json(ExternalProverCredentials { prover_id, api_key }))
+}
+
+/// Slashes the collateral of the lease, e.g. once its proof is rejected. Protected by the operator token.
+pub(crate) async fn slash<DB: DatabaseInterface>(
+    data: web::Data<AppState<DB>>,
+    r: web::Json<ExternalLeaseSlashing>,
+) -> actix_web::Result<HttpResponse> {
+    data.lease_terms()?;
+    let mut storage = data.access_storage().await?;
+    let slashed = data
+        .database
+        .slash_external_prover_lease(&mut storage, r.lease_id, &r.reason)
+        .await
+        .map_err(storage_error)?;
+    if !slashed {
+        return Err(actix_web::error::ErrorNotFound(
+            "no active lease with such id",
+        ));
+    }
+
+    vlog::warn!(
+        "Slashed the external prover lease {}: {}",
+        r.lease_id,
+        r.reason
+    );
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Slashes the leases which proofs were not submitted in time.
+pub(crate) async fn slash_expired_leases<DB: DatabaseInterface>(
+    database: &DB,
+    connection: &mut StorageProcessor<'_>,
+) -> anyhow::Result<()> {
+    for lease_id in database
+        .load_expired_external_prover_leases(connection)
+        .await?
+    {
+        if database
+            .slash_external_prover_lease(connection, lease_id, LEASE_EXPIRED_REASON)
+            .await?
+        {
+            vlog::warn!("Slashed the expired external prover lease {}", lease_id);
+        }
+    }
+
+    Ok(())
+}
+
+/// Routes of the API for the external provers authenticated by their API keys.
+pub(crate) fn api_scope<DB: DatabaseInterface>() -> Scope {
+    web::scope("/external/v1")
+        .route("/lease", web::post().to(lease::<DB>))
+        .route("/working_on", web::post().to(working_on::<DB>))
+        .route("/publish", web::post().to(publish::<DB>))
+        .route("/account", web::get().to(account::<DB>))
+}
@@ -0,0 +1,301 @@
+//! gRPC interface of the prover server.
+//!
+//! Provides the same work distribution protocol as the HTTP API, but the job data and the proofs
+//! are streamed in chunks (see `zksync_prover_utils::grpc`), so the provers don't hit the
+//! request timeouts while transferring the witnesses of the big blocks. The job assignment
+//! is a long poll: the server waits for an idle job for a while instead of responding at once.
+
+// Built-in deps
+use std::{
+    net::SocketAddr,
+    pin::Pin,
+    time::{Duration, Instant},
+};
+// External deps
+use futures::{stream, Stream};
+use tonic::{transport::Server, Request, Response, Status, Streaming};
+// Workspace deps
+use zksync_prover_utils::{
+    api::{JobRequestData, JobResultData, ProverOutputRequest, WorkingOn},
+    encoding::PayloadEncoding,
+    grpc::{
+        job_parts,
+        proto::{
+            prover_server::{Prover, ProverServer},
+            Empty, JobHeader, JobPart, JobRequest, ProofPart, ReleaseJobRequest, StoppedRequest,
+            WorkingOnRequest,
+        },
+        receive_parts, AUTHORIZATION_METADATA,
+    },
+};
+use zksync_types::{
+    protocol_version::{
+        check_protocol_version, check_protocol_version_header, COMPONENTS_PROTOCOL_VERSION,
+        PROTOCOL_VERSION_HEADER,
+    },
+    BlockNumber,
+};
+// Local deps
+use super::{
+    is_duplicate_proof_error, record_heartbeat, store_job_result, AuthTokenValidator,
+    DatabaseInterface,
+};
+
+/// Maximum time the job assignment request waits for an idle job.
+const JOB_WAIT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Interval between the checks of the job queue while waiting for a job.
+const JOB_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+fn storage_error(err: anyhow::Error) -> Status {
+    vlog::warn!("Prover gRPC storage layer error: {}", err);
+    Status::internal("storage layer error")
+}
+
+struct ProverService<DB: DatabaseInterface> {
+    database: DB,
+}
+
+impl<DB: DatabaseInterface> ProverService<DB> {
+    /// Waits for an idle job, returns `None` if none appears within `JOB_WAIT_TIMEOUT`.
+    async fn wait_for_job(
+        &self,
+        encoding: PayloadEncoding,
+    ) -> Result<Option<(JobHeader, Vec<u8>)>, Status> {
+        let started_at = Instant::now();
+        loop {
+            let mut storage = self
+                .database
+                .acquire_connection()
+                .await
+                .map_err(storage_error)?;
+            let job = self
+                .database
+                .load_idle_prover_job_from_job_queue(&mut storage)
+                .await
+                .map_err(storage_error)?;
+            if let Some(job) = job {
+                let header = JobHeader {
+                    job_id: job.job_id,
+                    first_block: *job.first_block,
+                    last_block: *job.last_block,
+                    protocol_version: COMPONENTS_PROTOCOL_VERSION,
+                    content_type: encoding.content_type().to_owned(),
+                };
+                // The job data is stored as JSON, so it is only re-encoded for the binary formats.
+                let data = if encoding == PayloadEncoding::Json {
+                    serde_json::to_vec(&job.job_data).map_err(anyhow::Error::from)
+                } else {
+                    serde_json::from_value::<JobRequestData>(job.job_data)
+                        .map_err(anyhow::Error::from)
+                        .and_then(|job_data| encoding.encode(&job_data))
+                }
+                .expect("Failed to serialize prover job data");
+                return Ok(Some((header, data)));
+            }
+            drop(storage);
+
+            if started_at.elapsed() >= JOB_WAIT_TIMEOUT {
+                return Ok(None);
+            }
+            tokio::time::delay_for(JOB_POLL_INTERVAL).await;
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl<DB: DatabaseInterface> Prover for ProverService<DB> {
+    type AssignJobStream =
+        Pin<Box<dyn Stream<Item = Result<JobPart, Status>> + Send + Sync + 'static>>;
+
+    async fn assign_job(
+        &self,
+        request: Request<JobRequest>,
+    ) -> Result<Response<Self::AssignJobStream>, Status> {
+        let request = request.into_inner();
+        vlog::trace!(
+            "request block to prove from worker: {}",
+            request.prover_name
+        );
+        if request.prover_name.is_empty() {
+            return Err(Status::invalid_argument("empty name"));
+        }
+
+        let accepted_content_types = request.accepted_content_types.join(",");
+        let encoding = PayloadEncoding::negotiate(Some(&accepted_content_types));
+        let parts: Self::AssignJobStream = match self.wait_for_job(encoding).await? {
+            Some((header, data)) => {
+                vlog::info!(
+                    "satisfied request to prove from worker {} over gRPC: job {}",
+                    request.prover_name,
+                    header.job_id
+                );
+                metrics::histogram!("prover_server.grpc.job_size", data.len() as f64);
+                Box::pin(stream::iter(job_parts(header, data).map(Ok::<_, Status>)))
+            }
+            None => Box::pin(stream::empty::<Result<JobPart, Status>>()),
+        };
+        Ok(Response::new(parts))
+    }
+
+    async fn working_on(
+        &self,
+        request: Request<WorkingOnRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let request = request.into_inner();
+        vlog::trace!(
+            "Received heartbeat for prover_run with id: {}",
+            request.job_id
+        );
+        // The provers unaware of the progress reporting leave it empty.
+        let progress = if request.progress.is_empty() {
+            None
+        } else {
+            let progress = serde_json::from_slice(&request.progress)
+                .map_err(|err| Status::invalid_argument(format!("incorrect progress: {}", err)))?;
+            Some(progress)
+        };
+        let heartbeat = WorkingOn {
+            prover_name: request.prover_name,
+            job_id: request.job_id,
+            progress,
+        };
+
+        let mut storage = self
+            .database
+            .acquire_connection()
+            .await
+            .map_err(storage_error)?;
+        record_heartbeat(&self.database, &mut storage, &heartbeat)
+            .await
+            .map_err(storage_error)?;
+
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn publish_proof(
+        &self,
+        request: Request<Streaming<ProofPart>>,
+    ) -> Result<Response<Empty>, Status> {
+        let (header, data) = receive_parts(request.into_inner())
+            .await?
+            .ok_or_else(|| Status::invalid_argument("empty proof"))?;
+        // Proofs created by the provers of other versions may be invalid.
+        check_protocol_version(Some(header.protocol_version)).map_err(|err| {
+            vlog::warn!("Rejected the proof for job {}: {}", header.job_id, err);
+            Status::failed_precondition(err.to_string())
+        })?;
+        let content_type = Some(header.content_type.as_str()).filter(|value| !value.is_empty());
+        let data: JobResultData = PayloadEncoding::from_content_type(content_type)
+            .and_then(|encoding| encoding.decode(&data))
+            .map_err(|err| Status::invalid_argument(format!("incorrect proof: {}", err)))?;
+        let request = ProverOutputRequest {
+            job_id: header.job_id,
+            first_block: BlockNumber(header.first_block),
+            last_block: BlockNumber(header.last_block),
+            data,
+            protocol_version: Some(header.protocol_version),
+        };
+
+        let mut storage = self
+            .database
+            .acquire_connection()
+            .await
+            .map_err(storage_error)?;
+        store_job_result(&self.database, &mut storage, &request)
+            .await
+            .map_err(|err| {
+                vlog::error!("failed to store received proof: {}", err);
+                if is_duplicate_proof_error(&err) {
+                    Status::already_exists("duplicate key")
+                } else {
+                    Status::internal("storage layer error")
+                }
+            })?;
+
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn stopped(&self, request: Request<StoppedRequest>) -> Result<Response<Empty>, Status> {
+        let prover_name = request.into_inner().prover_name;
+        vlog::info!(
+            "Prover instance '{}' send a stopping notification",
+            prover_name
+        );
+        let mut storage = self
+            .database
+            .acquire_connection()
+            .await
+            .map_err(storage_error)?;
+        self.database
+            .record_prover_stop(&mut storage, &prover_name)
+            .await
+            .map_err(storage_error)?;
+
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn release_job(
+        &self,
+        request: Request<ReleaseJobRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let request = request.into_inner();
+        vlog::info!(
+            "Prover '{}' released job {}",
+            request.prover_name,
+            request.job_id
+        );
+        let mut storage = self
+            .database
+            .acquire_connection()
+            .await
+            .map_err(storage_error)?;
+        self.database
+            .release_prover_job(&mut storage, request.job_id)
+            .await
+            .map_err(storage_error)?;
+
+        Ok(Response::new(Empty {}))
+    }
+}
+
+/// Checks the authentication token and the protocol version of the request.
+fn check_request(secret_auth: &str, request: &Request<()>) -> Result<(), Status> {
+    let metadata = request.metadata();
+    let token = metadata
+        .get(AUTHORIZATION_METADATA)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or_else(|| Status::unauthenticated("authorization token is missing"))?;
+    AuthTokenValidator::new(secret_auth)
+        .validate_auth_token(token)
+        .map_err(|_| Status::unauthenticated("authorization error"))?;
+
+    // Reject the provers of other versions before they take the jobs.
+    let version = metadata
+        .get(PROTOCOL_VERSION_HEADER)
+        .map(|value| value.to_str().unwrap_or_default());
+    check_protocol_version_header(version).map_err(|err| {
+        vlog::warn!("Rejected the prover gRPC request: {}", err);
+        Status::failed_precondition(err.to_string())
+    })
+}
+
+/// Runs the gRPC interface of the prover server, panics if the server fails.
+pub async fn run_grpc_server<DB: DatabaseInterface>(
+    database: DB,
+    secret_auth: String,
+    bind_addr: SocketAddr,
+) {
+    let service =
+        ProverServer::with_interceptor(ProverService { database }, move |request: Request<()>| {
+            check_request(&secret_auth, &request)?;
+            Ok(request)
+        });
+
+    vlog::info!("Starting the prover gRPC server on {}", bind_addr);
+    Server::builder()
+        .add_service(service)
+        .serve(bind_addr)
+        .await
+        .expect("Prover gRPC server failed");
+}
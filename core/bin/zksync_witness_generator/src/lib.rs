@@ -3,33 +3,44 @@ use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 // External
-use actix_web::dev::ServiceRequest;
-use actix_web::{web, App, HttpResponse, HttpServer};
+use actix_web::dev::{Service, ServiceRequest};
+use actix_web::{http::header, web, App, HttpRequest, HttpResponse, HttpServer};
 use actix_web_httpauth::extractors::{
     bearer::{BearerAuth, Config},
     AuthenticationError,
 };
 use actix_web_httpauth::middleware::HttpAuthentication;
-use futures::channel::mpsc;
+use bigdecimal::BigDecimal;
+use futures::{channel::mpsc, future, FutureExt};
 use jsonwebtoken::errors::Error as JwtError;
 use jsonwebtoken::{decode, DecodingKey, Validation};
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 // Workspace deps
+use vlog::Instrument;
 use zksync_config::ZkSyncConfig;
 // Local deps
 use self::database_interface::DatabaseInterface;
 use self::scaler::ScalerOracle;
 use zksync_circuit::serialization::ProverData;
+use zksync_config::configs::prover::ExternalProvers;
 use zksync_prover_utils::api::{
-    JobRequestData, JobResultData, ProverInputRequest, ProverInputResponse, ProverOutputRequest,
-    WorkingOn,
+    BlockProofProgress, JobRequestData, JobResultData, ProverInputRequest, ProverInputResponse,
+    ProverJobProgress, ProverOutputRequest, ReleasedJob, WorkingOn,
 };
+use zksync_prover_utils::encoding::PayloadEncoding;
+use zksync_storage::external_provers::LeaseTerms;
+use zksync_storage::prover::records::StorageProverJobProgress;
 use zksync_types::aggregated_operations::{
     AggregatedActionType, AggregatedOperation, BlocksCreateProofOperation,
 };
+use zksync_types::protocol_version::{
+    check_protocol_version, check_protocol_version_header, ProtocolVersionError,
+    COMPONENTS_PROTOCOL_VERSION, PROTOCOL_VERSION_HEADER,
+};
 use zksync_types::prover::{
-    ProverJobType, AGGREGATED_PROOF_JOB_PRIORITY, SINGLE_PROOF_JOB_PRIORITY,
+    ProofProgress, ProverJobStatus, ProverJobType, AGGREGATED_PROOF_JOB_PRIORITY,
+    SINGLE_PROOF_JOB_PRIORITY,
 };
 use zksync_types::BlockNumber;
 use zksync_utils::panic_notify::ThreadPanicNotify;
@@ -39,6 +50,8 @@ mod tests;
 
 pub mod database;
 mod database_interface;
+mod external_provers;
+mod grpc;
 mod scaler;
 mod witness_generator;
 
@@ -55,10 +68,17 @@ struct AppState<DB: DatabaseInterface> {
     secret_auth: String,
     database: DB,
     scaler_oracle: Arc<RwLock<ScalerOracle<DB>>>,
+    /// Terms of the job leases for the external provers, `None` if their API is disabled.
+    lease_terms: Option<LeaseTerms>,
 }
 
 impl<DB: DatabaseInterface> AppState<DB> {
-    pub fn new(secret_auth: String, database: DB, idle_provers: u32) -> Self {
+    pub fn new(
+        secret_auth: String,
+        database: DB,
+        idle_provers: u32,
+        lease_terms: Option<LeaseTerms>,
+    ) -> Self {
         let scaler_oracle = Arc::new(RwLock::new(ScalerOracle::new(
             database.clone(),
             idle_provers,
@@ -68,6 +88,7 @@ impl<DB: DatabaseInterface> AppState<DB> {
             secret_auth,
             database,
             scaler_oracle,
+            lease_terms,
         }
     }
 
@@ -116,8 +137,29 @@ async fn status() -> actix_web::Result<String> {
     Ok("alive".into())
 }
 
+/// Encodes the response with the encoding accepted by the prover.
+fn encoded_response<T: Serialize>(
+    req: &HttpRequest,
+    response: &T,
+) -> actix_web::Result<HttpResponse> {
+    let accept = req
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok());
+    let encoding = PayloadEncoding::negotiate(accept);
+    let body = encoding.encode(response).map_err(|e| {
+        vlog::error!("failed to encode the prover response: {}", e);
+        actix_web::error::ErrorInternalServerError("encoding error")
+    })?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(encoding.content_type())
+        .body(body))
+}
+
 async fn get_job<DB: DatabaseInterface>(
     data: web::Data<AppState<DB>>,
+    req: HttpRequest,
     r: web::Json<ProverInputRequest>,
 ) -> actix_web::Result<HttpResponse> {
     vlog::trace!("request block to prove from worker: {}", r.prover_name);
@@ -135,22 +177,30 @@ async fn get_job<DB: DatabaseInterface>(
         })?;
     if let Some(prover_job) = ret {
         vlog::info!("satisfied request to prove from worker");
-        Ok(HttpResponse::Ok().json(ProverInputResponse {
-            job_id: prover_job.job_id,
-            first_block: prover_job.first_block,
-            last_block: prover_job.last_block,
-            data: Some(
-                serde_json::from_value(prover_job.job_data)
-                    .expect("Failed to parse prover job from db"),
-            ),
-        }))
+        encoded_response(
+            &req,
+            &ProverInputResponse {
+                job_id: prover_job.job_id,
+                first_block: prover_job.first_block,
+                last_block: prover_job.last_block,
+                data: Some(
+                    serde_json::from_value(prover_job.job_data)
+                        .expect("Failed to parse prover job from db"),
+                ),
+                protocol_version: Some(COMPONENTS_PROTOCOL_VERSION),
+            },
+        )
     } else {
-        Ok(HttpResponse::Ok().json(ProverInputResponse {
-            job_id: 0,
-            first_block: BlockNumber(0),
-            last_block: BlockNumber(0),
-            data: None,
-        }))
+        encoded_response(
+            &req,
+            &ProverInputResponse {
+                job_id: 0,
+                first_block: BlockNumber(0),
+                last_block: BlockNumber(0),
+                data: None,
+                protocol_version: Some(COMPONENTS_PROTOCOL_VERSION),
+            },
+        )
     }
 }
 
@@ -165,8 +215,7 @@ async fn working_on<DB: DatabaseInterface>(
         .access_storage()
         .await
         .map_err(actix_web::error::ErrorInternalServerError)?;
-    data.database
-        .record_prover_is_working(&mut storage, r.job_id, &r.prover_name)
+    record_heartbeat(&data.database, &mut storage, &r)
         .await
         .map_err(|e| {
             vlog::warn!("failed to record prover work in progress request: {}", e);
@@ -176,23 +225,130 @@ async fn working_on<DB: DatabaseInterface>(
     Ok(HttpResponse::Ok().finish())
 }
 
+/// Records the heartbeat of the prover along with the reported progress of the job.
+async fn record_heartbeat<DB: DatabaseInterface>(
+    database: &DB,
+    storage: &mut zksync_storage::StorageProcessor<'_>,
+    r: &WorkingOn,
+) -> anyhow::Result<()> {
+    database
+        .record_prover_is_working(storage, r.job_id, &r.prover_name)
+        .await?;
+    if let Some(progress) = r.progress {
+        vlog::debug!(
+            "Prover {} reported the progress of job {}: {:?}",
+            r.prover_name,
+            r.job_id,
+            progress
+        );
+        database
+            .record_proof_progress(storage, r.job_id, &r.prover_name, progress)
+            .await?;
+    }
+    Ok(())
+}
+
+fn job_progress(job: StorageProverJobProgress) -> ProverJobProgress {
+    let progress = job.stage.as_deref().and_then(|stage| {
+        ProofProgress::from_stage(stage)
+            .map_err(|e| vlog::warn!("Incorrect progress of job {}: {}", job.job_id, e))
+            .ok()
+    });
+    let job_status = ProverJobStatus::from_number(job.job_status)
+        .map(|status| format!("{:?}", status))
+        .unwrap_or_else(|_| job.job_status.to_string());
+
+    ProverJobProgress {
+        job_id: job.job_id,
+        job_type: job.job_type,
+        job_status,
+        first_block: BlockNumber(job.first_block as u32),
+        last_block: BlockNumber(job.last_block as u32),
+        updated_by: job.updated_by,
+        updated_at: job.updated_at.timestamp(),
+        prover_name: job.prover_name,
+        progress,
+        stage_started_at: job.stage_started_at.map(|time| time.timestamp()),
+        progress_updated_at: job.progress_updated_at.map(|time| time.timestamp()),
+    }
+}
+
+/// Returns the progress of the proof generation for the block, so it's visible
+/// whether the prover is stuck or the proof just takes long.
+async fn block_proof_progress<DB: DatabaseInterface>(
+    data: web::Data<AppState<DB>>,
+    block_number: web::Path<u32>,
+) -> actix_web::Result<HttpResponse> {
+    let block_number = BlockNumber(block_number.into_inner());
+    let mut storage = data.access_storage().await?;
+    let jobs = data
+        .database
+        .load_block_proof_progress(&mut storage, block_number)
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed to load the proof progress: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+
+    Ok(HttpResponse::Ok().json(BlockProofProgress {
+        block_number,
+        jobs: jobs.into_iter().map(job_progress).collect(),
+    }))
+}
+
 async fn publish<DB: DatabaseInterface>(
     data: web::Data<AppState<DB>>,
-    r: web::Json<ProverOutputRequest>,
+    req: HttpRequest,
+    body: web::Bytes,
 ) -> actix_web::Result<HttpResponse> {
+    // The proof is encoded the same way the prover has received the job.
+    let content_type = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok());
+    let r: ProverOutputRequest = PayloadEncoding::from_content_type(content_type)
+        .and_then(|encoding| encoding.decode(&body))
+        .map_err(|e| {
+            vlog::warn!("Rejected the incorrect proof: {}", e);
+            actix_web::error::ErrorBadRequest(e)
+        })?;
+    // Proofs created by the provers of other versions may be invalid.
+    check_protocol_version(r.protocol_version).map_err(|err| {
+        vlog::warn!("Rejected the proof for job {}: {}", r.job_id, err);
+        actix_web::error::ErrorConflict(err)
+    })?;
     let mut storage = data
         .access_storage()
         .await
         .map_err(actix_web::error::ErrorInternalServerError)?;
-    let storage_result = match &r.data {
+    if let Err(e) = store_job_result(&data.database, &mut storage, &r).await {
+        vlog::error!("failed to store received proof: {}", e);
+        let message = if is_duplicate_proof_error(&e) {
+            "duplicate key"
+        } else {
+            "storage layer error"
+        };
+        return Err(actix_web::error::ErrorInternalServerError(message));
+    }
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Stores the proof published by the prover.
+async fn store_job_result<DB: DatabaseInterface>(
+    database: &DB,
+    storage: &mut zksync_storage::StorageProcessor<'_>,
+    r: &ProverOutputRequest,
+) -> anyhow::Result<()> {
+    match &r.data {
         JobResultData::BlockProof(single_proof) => {
             vlog::info!(
                 "Received a proof for job: {}, single block: {}",
                 r.job_id,
                 r.first_block
             );
-            data.database
-                .store_proof(&mut storage, r.job_id, r.first_block, single_proof)
+            database
+                .store_proof(storage, r.job_id, r.first_block, single_proof)
                 .await
         }
         JobResultData::AggregatedBlockProof(aggregated_proof) => {
@@ -202,9 +358,9 @@ async fn publish<DB: DatabaseInterface>(
                 r.first_block,
                 r.last_block
             );
-            data.database
+            database
                 .store_aggregated_proof(
-                    &mut storage,
+                    storage,
                     r.job_id,
                     r.first_block,
                     r.last_block,
@@ -212,18 +368,12 @@ async fn publish<DB: DatabaseInterface>(
                 )
                 .await
         }
-    };
-    if let Err(e) = storage_result {
-        vlog::error!("failed to store received proof: {}", e);
-        let message = if e.to_string().contains("duplicate key") {
-            "duplicate key"
-        } else {
-            "storage layer error"
-        };
-        return Err(actix_web::error::ErrorInternalServerError(message));
     }
+}
 
-    Ok(HttpResponse::Ok().finish())
+/// Checks whether the proof is rejected because it's already stored.
+fn is_duplicate_proof_error(err: &anyhow::Error) -> bool {
+    err.to_string().contains("duplicate key")
 }
 
 async fn stopped<DB: DatabaseInterface>(
@@ -251,6 +401,28 @@ async fn stopped<DB: DatabaseInterface>(
     Ok(HttpResponse::Ok().finish())
 }
 
+async fn release_job<DB: DatabaseInterface>(
+    data: web::Data<AppState<DB>>,
+    r: web::Json<ReleasedJob>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    vlog::info!("Prover '{}' released job {}", r.prover_name, r.job_id);
+
+    data.database
+        .release_prover_job(&mut storage, r.job_id)
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed to release prover job: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
 /// Input of the `/scaler/replicas` endpoint.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RequiredReplicasInput {
@@ -374,10 +546,45 @@ async fn update_prover_job_queue<DB: DatabaseInterface>(database: DB) -> anyhow:
         }
     }
     database.mark_stale_jobs_as_idle(&mut connection).await?;
+    external_provers::slash_expired_leases(&database, &mut connection).await?;
 
     Ok(())
 }
 
+/// Checks the protocol version of the requests sent by the provers.
+fn check_prover_protocol_version(req: &ServiceRequest) -> Result<(), ProtocolVersionError> {
+    let prover_paths = [
+        "/get_job",
+        "/working_on",
+        "/publish",
+        "/stopped",
+        "/release_job",
+    ];
+    if !prover_paths.contains(&req.path()) {
+        return Ok(());
+    }
+
+    let version = req
+        .headers()
+        .get(PROTOCOL_VERSION_HEADER)
+        .map(|value| value.to_str().unwrap_or_default());
+    check_protocol_version_header(version)
+}
+
+fn lease_terms(config: &ExternalProvers) -> Option<LeaseTerms> {
+    if !config.enabled {
+        return None;
+    }
+
+    Some(LeaseTerms {
+        collateral: BigDecimal::from(config.job_collateral),
+        single_proof_payment: BigDecimal::from(config.single_proof_payment),
+        aggregated_proof_payment: BigDecimal::from(config.aggregated_proof_payment),
+        duration: chrono::Duration::from_std(config.lease_timeout())
+            .expect("Incorrect external prover lease timeout"),
+    })
+}
+
 pub fn run_prover_server<DB: DatabaseInterface>(
     database: DB,
     panic_notify: mpsc::Sender<bool>,
@@ -386,6 +593,7 @@ pub fn run_prover_server<DB: DatabaseInterface>(
     let witness_generator_opts = config.prover.witness_generator;
     let core_opts = config.prover.core;
     let prover_api_opts = config.api.prover;
+    let lease_terms = lease_terms(&config.prover.external);
 
     thread::Builder::new()
         .name("prover_server".to_string())
@@ -426,12 +634,27 @@ pub fn run_prover_server<DB: DatabaseInterface>(
                     );
                     pool_maintainer.start(panic_notify.clone());
                 }
+                // Start gRPC server if enabled, it serves the same provers as the HTTP one.
+                let grpc_server = if prover_api_opts.grpc_enabled {
+                    grpc::run_grpc_server(
+                        database.clone(),
+                        prover_api_opts.secret_auth.clone(),
+                        prover_api_opts.grpc_bind_addr(),
+                    )
+                    .left_future()
+                } else {
+                    future::ready(()).right_future()
+                };
                 // Start HTTP server.
                 let secret_auth = prover_api_opts.secret_auth.clone();
                 let idle_provers = core_opts.idle_provers;
-                HttpServer::new(move || {
-                    let app_state =
-                        AppState::new(secret_auth.clone(), database.clone(), idle_provers);
+                let http_server = HttpServer::new(move || {
+                    let app_state = AppState::new(
+                        secret_auth.clone(),
+                        database.clone(),
+                        idle_provers,
+                        lease_terms.clone(),
+                    );
 
                     let auth = HttpAuthentication::bearer(move |req, credentials| async {
                         let secret_auth = req
@@ -447,23 +670,59 @@ pub fn run_prover_server<DB: DatabaseInterface>(
                     // By calling `register_data` instead of `data` we're avoiding double
                     // `Arc` wrapping of the object.
                     App::new()
-                        .wrap(auth)
                         .wrap(vlog::actix_middleware())
+                        // Attach the request handling to the trace of the prover job.
+                        .wrap_fn(|req, srv| {
+                            let span = vlog::info_span!("prover_api_request", path = %req.path());
+                            let headers = req.headers().iter().filter_map(|(name, value)| {
+                                Some((name.as_str(), value.to_str().ok()?))
+                            });
+                            vlog::set_remote_parent(&span, headers);
+                            // Reject the provers of other versions before they take the jobs.
+                            // External provers check the version of the leased jobs themselves.
+                            if let Err(err) = check_prover_protocol_version(&req) {
+                                vlog::warn!("Rejected the request to {}: {}", req.path(), err);
+                                let response = actix_web::error::ErrorConflict(err);
+                                return future::err(response).left_future();
+                            }
+                            srv.call(req).instrument(span).right_future()
+                        })
                         .app_data(web::Data::new(app_state))
-                        .route("/status", web::get().to(status))
-                        .route("/get_job", web::get().to(get_job::<DB>))
-                        .route("/working_on", web::post().to(working_on::<DB>))
-                        .route("/publish", web::post().to(publish::<DB>))
-                        .route("/stopped", web::post().to(stopped::<DB>))
-                        .route(
-                            "/api/internal/prover/replicas",
-                            web::post().to(required_replicas::<DB>),
+                        // External provers are authenticated by their API keys
+                        // rather than by the operator token.
+                        .service(external_provers::api_scope::<DB>())
+                        .service(
+                            web::scope("")
+                                .wrap(auth)
+                                .route("/status", web::get().to(status))
+                                .route("/get_job", web::get().to(get_job::<DB>))
+                                .route("/working_on", web::post().to(working_on::<DB>))
+                                .route("/publish", web::post().to(publish::<DB>))
+                                .route("/stopped", web::post().to(stopped::<DB>))
+                                .route("/release_job", web::post().to(release_job::<DB>))
+                                .route(
+                                    "/api/internal/prover/replicas",
+                                    web::post().to(required_replicas::<DB>),
+                                )
+                                .route(
+                                    "/api/internal/prover/blocks/{block}/progress",
+                                    web::get().to(block_proof_progress::<DB>),
+                                )
+                                .route(
+                                    "/api/internal/prover/external/register",
+                                    web::post().to(external_provers::register::<DB>),
+                                )
+                                .route(
+                                    "/api/internal/prover/external/slash",
+                                    web::post().to(external_provers::slash::<DB>),
+                                ),
                         )
                 })
                 .bind(&prover_api_opts.bind_addr())
                 .expect("failed to bind")
-                .run()
-                .await
+                .run();
+
+                future::join(grpc_server, http_server).await.1
             })
         })
         .expect("failed to start prover server");
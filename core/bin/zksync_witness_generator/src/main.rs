@@ -3,6 +3,7 @@ use std::cell::RefCell;
 use zksync_config::ZkSyncConfig;
 use zksync_prometheus_exporter::run_prometheus_exporter;
 use zksync_storage::ConnectionPool;
+use zksync_tree_cache::TreeCacheStorage;
 use zksync_witness_generator::database::Database;
 use zksync_witness_generator::run_prover_server;
 
@@ -25,8 +26,10 @@ async fn main() -> anyhow::Result<()> {
     }
 
     let connection_pool = ConnectionPool::new(Some(WITNESS_GENERATOR_CONNECTION_POOL_SIZE));
-    let database = Database::new(connection_pool.clone());
     let zksync_config = ZkSyncConfig::from_env();
+    let tree_cache =
+        TreeCacheStorage::open(&zksync_config.db, zksync_tree_cache::WITNESS_GENERATOR)?;
+    let database = Database::new(connection_pool.clone(), tree_cache);
 
     // Run prometheus data exporter.
     let (prometheus_task_handle, _) =
@@ -1,9 +1,11 @@
 // Built-in
 use std::clone::Clone;
+use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 // External uses
+use bigdecimal::BigDecimal;
 use chrono::Utc;
 use tokio::sync::RwLock;
 use tokio::time::delay_for;
@@ -11,12 +13,18 @@ use tokio::time::delay_for;
 use zksync_crypto::params::account_tree_depth;
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
 use zksync_storage::chain::block::records::AccountTreeCache;
-use zksync_storage::prover::records::{StorageBlockWitness, StorageProverJobQueue, StoredProof};
+use zksync_storage::external_provers::{
+    records::{StorageExternalProver, StorageProverLease},
+    LeaseTerms,
+};
+use zksync_storage::prover::records::{
+    StorageBlockWitness, StorageProverJobProgress, StorageProverJobQueue, StoredProof,
+};
 use zksync_storage::StorageProcessor;
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
     block::Block,
-    prover::{ProverJob, ProverJobStatus, ProverJobType},
+    prover::{ProofProgress, ProverJob, ProverJobStatus, ProverJobType},
     AccountId, AccountMap, AccountTree, AccountUpdates, Address, BlockNumber,
 };
 // Local uses
@@ -31,6 +39,8 @@ pub struct MockDatabase {
     blocks: Arc<RwLock<Vec<Block>>>,
     account_tree_cache: Arc<RwLock<AccountTreeCache>>,
     accounts_state: Arc<RwLock<(u32, AccountMap)>>,
+    /// Reported progress by the job ID, along with the name of the prover.
+    proof_progress: Arc<RwLock<HashMap<i32, (String, ProofProgress)>>>,
 }
 
 impl MockDatabase {
@@ -48,6 +58,7 @@ impl MockDatabase {
                 tree_cache,
             })),
             accounts_state: Arc::new(RwLock::new((0, accounts))),
+            proof_progress: Default::default(),
         }
     }
 
@@ -266,6 +277,56 @@ impl DatabaseInterface for MockDatabase {
         Ok(())
     }
 
+    async fn record_proof_progress(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        job_id: i32,
+        prover_name: &str,
+        progress: ProofProgress,
+    ) -> anyhow::Result<()> {
+        self.proof_progress
+            .write()
+            .await
+            .insert(job_id, (prover_name.to_string(), progress));
+
+        Ok(())
+    }
+
+    async fn load_block_proof_progress(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        block_number: BlockNumber,
+    ) -> anyhow::Result<Vec<StorageProverJobProgress>> {
+        let proof_progress = self.proof_progress.read().await;
+        let block_number = i64::from(*block_number);
+        let jobs = self
+            .prover_job_queue
+            .read()
+            .await
+            .1
+            .iter()
+            .filter(|job| job.first_block <= block_number && job.last_block >= block_number)
+            .map(|job| {
+                let progress = proof_progress.get(&job.id);
+                StorageProverJobProgress {
+                    job_id: job.id,
+                    job_type: job.job_type.clone(),
+                    job_status: job.job_status,
+                    first_block: job.first_block,
+                    last_block: job.last_block,
+                    updated_by: job.updated_by.clone(),
+                    updated_at: job.updated_at,
+                    prover_name: progress.map(|(prover_name, _)| prover_name.clone()),
+                    stage: progress.map(|(_, progress)| progress.stage().to_string()),
+                    stage_started_at: progress.map(|_| job.updated_at),
+                    progress_updated_at: progress.map(|_| job.updated_at),
+                }
+            })
+            .collect();
+
+        Ok(jobs)
+    }
+
     async fn store_proof(
         &self,
         _: &mut StorageProcessor<'_>,
@@ -321,6 +382,24 @@ impl DatabaseInterface for MockDatabase {
         Ok(())
     }
 
+    async fn release_prover_job(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        job_id: i32,
+    ) -> anyhow::Result<()> {
+        let prover_job_queue = &mut self.prover_job_queue.write().await.1;
+
+        for job in prover_job_queue.iter_mut() {
+            if job.id == job_id && job.job_status == ProverJobStatus::InProgress.to_number() {
+                job.job_status = ProverJobStatus::Idle.to_number();
+                job.updated_at = Utc::now();
+                job.updated_by = "server_release_job".to_string();
+            }
+        }
+
+        Ok(())
+    }
+
     async fn load_committed_state(
         &self,
         _: &mut StorageProcessor<'_>,
@@ -396,4 +475,74 @@ impl DatabaseInterface for MockDatabase {
 
         Ok(())
     }
+
+    async fn register_external_prover(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        _name: &str,
+        _api_key: &str,
+        _reward_address: Address,
+        _stake: BigDecimal,
+    ) -> anyhow::Result<i64> {
+        anyhow::bail!("External provers are not supported by the mock database")
+    }
+
+    async fn load_external_prover(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        _api_key: &str,
+    ) -> anyhow::Result<Option<StorageExternalProver>> {
+        Ok(None)
+    }
+
+    async fn lease_job_to_external_prover(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        _prover: &StorageExternalProver,
+        _terms: &LeaseTerms,
+    ) -> anyhow::Result<Option<(ProverJob, StorageProverLease)>> {
+        Ok(None)
+    }
+
+    async fn load_external_prover_lease(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        _prover_id: i64,
+        _lease_id: i64,
+    ) -> anyhow::Result<Option<StorageProverLease>> {
+        Ok(None)
+    }
+
+    async fn load_external_prover_leases(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        _prover_id: i64,
+        _limit: u32,
+    ) -> anyhow::Result<Vec<StorageProverLease>> {
+        Ok(Vec::new())
+    }
+
+    async fn complete_external_prover_lease(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        _lease_id: i64,
+    ) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+
+    async fn slash_external_prover_lease(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        _lease_id: i64,
+        _reason: &str,
+    ) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+
+    async fn load_expired_external_prover_leases(
+        &self,
+        _: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<i64>> {
+        Ok(Vec::new())
+    }
 }
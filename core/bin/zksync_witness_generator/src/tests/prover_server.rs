@@ -6,9 +6,9 @@ use num::BigUint;
 // Workspace deps
 use zksync_config::ZkSyncConfig;
 use zksync_crypto::franklin_crypto::bellman::pairing::ff::{PrimeField, PrimeFieldRepr};
-use zksync_prover::{client, ApiClient};
+use zksync_prover::{client, grpc_client, ApiClient};
 use zksync_prover_utils::api::ProverInputRequest;
-use zksync_types::{block::Block, AccountId, BlockNumber, TokenId, H256};
+use zksync_types::{block::Block, prover::ProofProgress, AccountId, BlockNumber, TokenId, H256};
 // Local deps
 use super::mock::MockDatabase;
 use crate::{run_prover_server, DatabaseInterface};
@@ -17,6 +17,8 @@ const CORRECT_PROVER_SECRET_AUTH: &str = "42";
 const INCORRECT_PROVER_SECRET_AUTH: &str = "123";
 const SERVER_BIND_PORT: u16 = 8088;
 const SERVER_BIND_TO: &str = "127.0.0.1:8088";
+const GRPC_SERVER_BIND_PORT: u16 = 8089;
+const GRPC_SERVER_URL: &str = "http://127.0.0.1:8089";
 
 struct MockProverOptions(ZkSyncConfig);
 
@@ -26,6 +28,9 @@ impl Default for MockProverOptions {
 
         zksync_config.api.prover.port = SERVER_BIND_PORT;
         zksync_config.api.prover.url = SERVER_BIND_TO.to_string();
+        zksync_config.api.prover.grpc_enabled = true;
+        zksync_config.api.prover.grpc_port = GRPC_SERVER_BIND_PORT;
+        zksync_config.api.prover.grpc_url = GRPC_SERVER_URL.to_string();
         zksync_config.api.prover.secret_auth = CORRECT_PROVER_SECRET_AUTH.to_string();
         zksync_config.prover.prover.heartbeat_interval = 20000;
         zksync_config.prover.prover.cycle_wait = 500;
@@ -51,7 +56,55 @@ async fn test_api_client() {
     let database = MockDatabase::new();
     spawn_server(database.clone()).await;
     test_api_client_with_incorrect_secret_auth("tests1").await;
-    test_api_client_simple_simulation("test2", database).await;
+    test_api_client_simple_simulation("test2", database.clone()).await;
+    test_grpc_client_with_incorrect_secret_auth("test3").await;
+    test_grpc_client_simple_simulation("test4").await;
+}
+
+async fn test_grpc_client_with_incorrect_secret_auth(prover_name: &str) {
+    let client =
+        grpc_client::GrpcApiClient::new(GRPC_SERVER_URL, INCORRECT_PROVER_SECRET_AUTH).unwrap();
+
+    let get_job_error = client
+        .get_job(ProverInputRequest {
+            prover_name: prover_name.to_string(),
+            aux_data: Default::default(),
+        })
+        .await
+        .err()
+        .unwrap()
+        .to_string();
+
+    assert!(get_job_error.contains("authorization error"));
+}
+
+/// Takes over the job of the prover stopped at the end of `test_api_client_simple_simulation`,
+/// so the job data is streamed over gRPC.
+async fn test_grpc_client_simple_simulation(prover_name: &str) {
+    let client =
+        grpc_client::GrpcApiClient::new(GRPC_SERVER_URL, CORRECT_PROVER_SECRET_AUTH).unwrap();
+
+    MockDatabase::wait_for_stale_job_stale_idle().await;
+
+    // Should return job.
+    let job = client
+        .get_job(ProverInputRequest {
+            prover_name: prover_name.to_string(),
+            aux_data: Default::default(),
+        })
+        .await
+        .unwrap();
+    assert!(job.data.is_some());
+    assert_eq!(job.first_block, BlockNumber(1));
+
+    client
+        .working_on(job.job_id, prover_name, Some(ProofProgress::Proving))
+        .await
+        .unwrap();
+    client
+        .prover_stopped(prover_name.to_string())
+        .await
+        .unwrap();
 }
 
 async fn test_api_client_with_incorrect_secret_auth(prover_name: &str) {
@@ -143,6 +196,26 @@ async fn test_api_client_simple_simulation(prover_name: &str, database: MockData
         .await
         .unwrap();
     assert!(witness.is_some());
+
+    // The progress reported along with the heartbeat is stored for the job.
+    client
+        .working_on(job.job_id, prover_name, Some(ProofProgress::Setup))
+        .await
+        .unwrap();
+    let jobs = database
+        .load_block_proof_progress(&mut storage, BlockNumber(1))
+        .await
+        .unwrap();
+    let stored = jobs
+        .into_iter()
+        .find(|stored| stored.job_id == job.job_id)
+        .unwrap();
+    assert_eq!(stored.stage.as_deref(), Some("SETUP"));
+
+    client
+        .prover_stopped(prover_name.to_string())
+        .await
+        .unwrap();
 }
 
 pub async fn get_test_block() -> Block {
@@ -132,10 +132,11 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
                         .collect::<Vec<_>>();
                     updated_accounts.sort_unstable();
                     updated_accounts.dedup();
-                    for idx in updated_accounts {
-                        circuit_account_tree
-                            .insert(*idx, accounts.get(&idx).cloned().unwrap_or_default().into());
-                    }
+                    circuit_account_tree.insert_many(
+                        updated_accounts.into_iter().map(|idx| {
+                            (*idx, accounts.get(&idx).cloned().unwrap_or_default().into())
+                        }),
+                    );
                 }
                 circuit_account_tree.root_hash();
                 let account_tree_cache = circuit_account_tree.get_internals();
@@ -152,9 +153,11 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
                 .database
                 .load_committed_state(&mut storage, Some(block))
                 .await?;
-            for (id, account) in accounts {
-                circuit_account_tree.insert(*id, account.into());
-            }
+            circuit_account_tree.insert_many(
+                accounts
+                    .into_iter()
+                    .map(|(id, account)| (*id, account.into())),
+            );
             circuit_account_tree.root_hash();
             let account_tree_cache = circuit_account_tree.get_internals();
             self.database
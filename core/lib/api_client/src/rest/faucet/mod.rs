@@ -0,0 +1,72 @@
+//! Test tokens faucet part of API implementation.
+
+// Built-in uses
+
+// External uses
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+
+// Workspace uses
+use zksync_types::{tx::TxHash, Address, TokenLike};
+use zksync_utils::BigUintSerdeAsRadix10Str;
+
+// Local uses
+use crate::rest::v1::Client;
+use crate::rest::v1::ClientResult;
+
+// Data transfer objects.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FaucetInfo {
+    /// Symbols of the dispensed tokens, all the tokens are dispensed if empty.
+    pub tokens: Vec<String>,
+    /// Amount of the token sent per request, in the whole token units.
+    pub amount: u64,
+    pub captcha_required: bool,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum FaucetStatus {
+    Enabled(FaucetInfo),
+    Disabled,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FaucetRequest {
+    pub address: Address,
+    pub token: TokenLike,
+    /// Response of the captcha solved by the user, if the captcha is required.
+    pub captcha: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FaucetResponse {
+    pub tx_hash: TxHash,
+    /// Amount of the token sent, in the smallest token units.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+    /// Amount of the token sent, in the whole tokens, e.g. `1.5`.
+    pub formatted_amount: String,
+}
+
+const FAUCET_SCOPE: &str = "/api/faucet/v0.1/";
+
+impl Client {
+    pub async fn get_faucet_status(&self) -> ClientResult<FaucetStatus> {
+        self.get_with_scope(FAUCET_SCOPE, "status").send().await
+    }
+
+    /// Requests the test tokens to be sent to the given address.
+    pub async fn request_test_tokens(
+        &self,
+        request: FaucetRequest,
+    ) -> ClientResult<FaucetResponse> {
+        self.post_with_scope(FAUCET_SCOPE, "request")
+            .body(&request)
+            .send()
+            .await
+    }
+}
@@ -1,2 +1,3 @@
+pub mod faucet;
 pub mod forced_exit_requests;
 pub mod v1;
@@ -7,8 +7,11 @@ use std::{collections::BTreeMap, fmt::Display, str::FromStr};
 use serde::{Deserialize, Serialize};
 
 // Workspace uses
+use zksync_crypto::{circuit::account_proof::AccountStateProof, serialization::FrSerde, Fr};
 use zksync_types::{
-    tx::TxHash, AccountId, Address, BlockNumber, Nonce, PriorityOp, PubKeyHash, H256,
+    finality::Finality,
+    tx::{ChangePubKeyOnchainAuthStatus, TxHash},
+    AccountId, Address, BlockNumber, Nonce, PriorityOp, PubKeyHash, TokenLike, H256,
 };
 use zksync_utils::{remove_prefix, BigUintSerdeWrapper};
 
@@ -16,6 +19,7 @@ use zksync_utils::{remove_prefix, BigUintSerdeWrapper};
 use super::{
     client::{Client, ClientError},
     transactions::Receipt,
+    FinalityQuery,
 };
 
 // Data transfer objects
@@ -41,6 +45,18 @@ pub struct AccountState {
     pub nonce: Nonce,
     /// Hash of the account's owner public key.
     pub pub_key_hash: PubKeyHash,
+    /// Finality of the state: the committed state is the latest one known to the server,
+    /// while the verified state is final.
+    pub finality: Finality,
+}
+
+/// Account state obtained in the last block that has reached the requested finality.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStateAtFinality {
+    /// Block the state is obtained in.
+    pub block_number: BlockNumber,
+    pub state: AccountState,
 }
 
 /// Pending amount for the deposit.
@@ -135,6 +151,8 @@ pub struct AccountTxReceipt {
     #[serde(flatten)]
     pub receipt: Receipt,
     pub hash: TxHash,
+    /// Finality of the transaction, `None` if it's rejected.
+    pub finality: Option<Finality>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
@@ -144,6 +162,8 @@ pub struct AccountOpReceipt {
     #[serde(flatten)]
     pub receipt: Receipt,
     pub hash: H256,
+    /// Finality of the block the operation is executed in.
+    pub finality: Finality,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
@@ -153,6 +173,35 @@ pub struct PendingAccountOpReceipt {
     pub hash: H256,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStateProofQuery {
+    /// Block to prove the balance at. If omitted, the last verified block is used.
+    pub block: Option<BlockNumber>,
+}
+
+/// Onchain `ChangePubKey` authorization query.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePubKeyAuthQuery {
+    /// Nonce of the `ChangePubKey` transaction.
+    pub nonce: Nonce,
+    /// New public key hash to be set by the transaction.
+    pub pub_key_hash: PubKeyHash,
+}
+
+/// Proof of the account token balance against the state root of the block.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStateProofInfo {
+    pub block_number: BlockNumber,
+    /// State root of the block, the same as the one stored in the zkSync contract.
+    #[serde(with = "FrSerde")]
+    pub state_root: Fr,
+    pub balance: BigUintSerdeWrapper,
+    pub proof: AccountStateProof,
+}
+
 impl From<AccountId> for AccountQuery {
     fn from(v: AccountId) -> Self {
         Self::Id(v)
@@ -262,6 +311,22 @@ impl Client {
         self.get(&format!("accounts/{}", account)).send().await
     }
 
+    /// Gets the account state obtained in the last block that has reached the requested
+    /// finality, or null if the account doesn't exist in that block. If the finality is not
+    /// specified, the latest state known to the server is returned.
+    pub async fn account_state(
+        &self,
+        account: impl Into<AccountQuery>,
+        finality: Option<Finality>,
+    ) -> Result<Option<AccountStateAtFinality>, ClientError> {
+        let account = account.into();
+
+        self.get(&format!("accounts/{}/state", account))
+            .query(&FinalityQuery::new(finality))
+            .send()
+            .await
+    }
+
     pub async fn account_tx_receipts(
         &self,
         account: impl Into<AccountQuery>,
@@ -276,6 +341,24 @@ impl Client {
             .await
     }
 
+    /// Gets the receipts of the account transactions. Receipts of the transactions that
+    /// haven't reached the requested finality are skipped, so there may be less than `limit` ones.
+    pub async fn account_tx_receipts_at_finality(
+        &self,
+        account: impl Into<AccountQuery>,
+        from: AccountReceipts,
+        limit: u32,
+        finality: Finality,
+    ) -> Result<Vec<AccountTxReceipt>, ClientError> {
+        let account = account.into();
+
+        self.get(&format!("accounts/{}/transactions/receipts", account))
+            .query(&AccountReceiptsQuery::new(from, limit))
+            .query(&FinalityQuery::new(Some(finality)))
+            .send()
+            .await
+    }
+
     pub async fn account_op_receipts(
         &self,
         account: impl Into<AccountQuery>,
@@ -290,6 +373,24 @@ impl Client {
             .await
     }
 
+    /// Gets the receipts of the account priority operations. Receipts of the operations that
+    /// haven't reached the requested finality are skipped, so there may be less than `limit` ones.
+    pub async fn account_op_receipts_at_finality(
+        &self,
+        account: impl Into<AccountQuery>,
+        from: AccountReceipts,
+        limit: u32,
+        finality: Finality,
+    ) -> Result<Vec<AccountOpReceipt>, ClientError> {
+        let account = account.into();
+
+        self.get(&format!("accounts/{}/operations/receipts", account))
+            .query(&AccountReceiptsQuery::new(from, limit))
+            .query(&FinalityQuery::new(Some(finality)))
+            .send()
+            .await
+    }
+
     pub async fn account_pending_ops(
         &self,
         account: impl Into<AccountQuery>,
@@ -300,4 +401,40 @@ impl Client {
             .send()
             .await
     }
+
+    /// Gets the Merkle proof of the account token balance.
+    pub async fn account_state_proof(
+        &self,
+        account: impl Into<AccountQuery>,
+        token: &TokenLike,
+        block: Option<BlockNumber>,
+    ) -> Result<Option<AccountStateProofInfo>, ClientError> {
+        let account = account.into();
+
+        self.get(&format!("accounts/{}/proofs/{}", account, token))
+            .query(&AccountStateProofQuery { block })
+            .send()
+            .await
+    }
+
+    /// Checks whether the onchain authorization (`setAuthPubkeyHash` call of the zkSync contract)
+    /// exists for the `ChangePubKey` transaction with the given nonce and public key hash.
+    /// Wallets can use it to choose between the `Onchain` and `ECDSA` authorization types.
+    pub async fn change_pubkey_onchain_auth(
+        &self,
+        address: Address,
+        nonce: Nonce,
+        pub_key_hash: PubKeyHash,
+    ) -> Result<ChangePubKeyOnchainAuthStatus, ClientError> {
+        self.get(&format!(
+            "accounts/{}/change_pubkey/onchain_auth",
+            AccountQuery::Address(address)
+        ))
+        .query(&ChangePubKeyAuthQuery {
+            nonce,
+            pub_key_hash,
+        })
+        .send()
+        .await
+    }
 }
@@ -0,0 +1,33 @@
+//! Account activations part of API implementation.
+
+// Built-in uses
+
+// External uses
+
+// Workspace uses
+use zksync_types::{
+    activations::{ActivationHint, PrepaidActivation},
+    address_checksum::to_checksum_address,
+    Address,
+};
+
+// Local uses
+use super::client::{Client, ClientError};
+
+/// Account activations API part.
+impl Client {
+    /// Prepays the activation of the recipient of the transfer.
+    pub async fn prepay_activation(
+        &self,
+        activation: PrepaidActivation,
+    ) -> Result<(), ClientError> {
+        self.post("activations").body(&activation).send().await
+    }
+
+    /// Returns what is required to activate the account.
+    pub async fn activation_hint(&self, address: Address) -> Result<ActivationHint, ClientError> {
+        self.get(&format!("activations/{}", to_checksum_address(&address)))
+            .send()
+            .await
+    }
+}
@@ -0,0 +1,44 @@
+//! Aliases part of API implementation.
+
+// Built-in uses
+
+// External uses
+
+// Workspace uses
+use zksync_types::{
+    account_alias::{AccountAlias, AliasRegistrationRequest},
+    address_checksum::to_checksum_address,
+    Address,
+};
+
+// Local uses
+use super::client::{Client, ClientError};
+
+/// Aliases API part.
+impl Client {
+    /// Registers the alias of the account.
+    pub async fn register_alias(
+        &self,
+        request: AliasRegistrationRequest,
+    ) -> Result<AccountAlias, ClientError> {
+        self.post("aliases").body(&request).send().await
+    }
+
+    /// Resolves the alias to the account it's registered by.
+    pub async fn resolve_alias(&self, alias: &str) -> Result<Option<AccountAlias>, ClientError> {
+        self.get(&format!("aliases/{}", alias)).send().await
+    }
+
+    /// Returns the alias registered by the account.
+    pub async fn account_alias(
+        &self,
+        address: Address,
+    ) -> Result<Option<AccountAlias>, ClientError> {
+        self.get(&format!(
+            "aliases/account/{}",
+            to_checksum_address(&address)
+        ))
+        .send()
+        .await
+    }
+}
@@ -0,0 +1,62 @@
+//! Attestations part of API implementation.
+
+// Built-in uses
+
+// External uses
+
+// Workspace uses
+use zksync_types::{
+    address_attestation::{
+        AddressAttestation, AttestationChallenge, AttestationChallengeRequest, AttestationResponse,
+    },
+    address_checksum::to_checksum_address,
+    Address, H256,
+};
+
+// Local uses
+use super::client::{Client, ClientError};
+
+/// Attestations API part.
+impl Client {
+    /// Requests the challenge to prove the ownership of the address.
+    pub async fn attestation_challenge(
+        &self,
+        address: Address,
+    ) -> Result<AttestationChallenge, ClientError> {
+        self.post("attestations/challenges")
+            .body(&AttestationChallengeRequest { address })
+            .send()
+            .await
+    }
+
+    /// Submits the signed challenge and returns the outcome of the attestation.
+    pub async fn submit_attestation(
+        &self,
+        response: AttestationResponse,
+    ) -> Result<AddressAttestation, ClientError> {
+        self.post("attestations").body(&response).send().await
+    }
+
+    /// Returns the attestation by its challenge.
+    pub async fn attestation(
+        &self,
+        challenge: H256,
+    ) -> Result<Option<AddressAttestation>, ClientError> {
+        self.get(&format!("attestations/challenges/{:?}", challenge))
+            .send()
+            .await
+    }
+
+    /// Returns the latest attestations of the address.
+    pub async fn address_attestations(
+        &self,
+        address: Address,
+    ) -> Result<Vec<AddressAttestation>, ClientError> {
+        self.get(&format!(
+            "attestations/address/{}",
+            to_checksum_address(&address)
+        ))
+        .send()
+        .await
+    }
+}
@@ -9,12 +9,16 @@ use serde_json::Value;
 
 // Workspace uses
 use zksync_crypto::{serialization::FrSerde, Fr};
-use zksync_types::{tx::TxHash, BlockNumber};
+use zksync_types::{
+    block::BlockRevert, finality::Finality, tx::TxHash, AccountId, Address, BlockNumber, Nonce,
+    PubKeyHash, TokenId,
+};
+use zksync_utils::BigUintSerdeWrapper;
 
 // Local uses
 use super::{
     client::{self, Client},
-    Pagination,
+    FinalityQuery, Pagination,
 };
 
 // Data transfer objects.
@@ -30,6 +34,8 @@ pub struct BlockInfo {
     pub verify_tx_hash: Option<TxHash>,
     pub committed_at: DateTime<Utc>,
     pub verified_at: Option<DateTime<Utc>>,
+    /// Finality the block has reached on L1.
+    pub finality: Finality,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -41,6 +47,54 @@ pub struct TransactionInfo {
     pub success: Option<bool>,
     pub fail_reason: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// Finality the block of the transaction has reached on L1.
+    pub finality: Finality,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct BlockRevertsQuery {
+    pub limit: u32,
+}
+
+/// Changes of the accounts state made by the block.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockStateDiff {
+    pub block_number: BlockNumber,
+    /// Changed accounts ordered by ID.
+    pub accounts: Vec<AccountStateDiff>,
+}
+
+/// Changes of the account state made by the block. If the account was changed
+/// several times, only the states before and after the block are reported.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStateDiff {
+    pub account_id: AccountId,
+    /// Address of the account if it was created in the block.
+    pub created: Option<Address>,
+    /// Address of the account if it was deleted in the block.
+    pub deleted: Option<Address>,
+    pub old_nonce: Nonce,
+    pub new_nonce: Nonce,
+    /// Changed balances ordered by token ID.
+    pub balances: Vec<BalanceDiff>,
+    pub pub_key_hash: Option<PubKeyHashDiff>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceDiff {
+    pub token: TokenId,
+    pub old_balance: BigUintSerdeWrapper,
+    pub new_balance: BigUintSerdeWrapper,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PubKeyHashDiff {
+    pub old_pub_key_hash: PubKeyHash,
+    pub new_pub_key_hash: PubKeyHash,
 }
 
 /// Blocks API part.
@@ -53,6 +107,19 @@ impl Client {
         self.get(&format!("blocks/{}", *block_number)).send().await
     }
 
+    /// Returns information about block with the specified number or null if block doesn't exist
+    /// or hasn't reached the requested finality.
+    pub async fn block_by_id_at_finality(
+        &self,
+        block_number: BlockNumber,
+        finality: Finality,
+    ) -> client::Result<Option<BlockInfo>> {
+        self.get(&format!("blocks/{}", *block_number))
+            .query(&FinalityQuery::new(Some(finality)))
+            .send()
+            .await
+    }
+
     /// Returns information about transactions of the block with the specified number.
     pub async fn block_transactions(
         &self,
@@ -63,6 +130,29 @@ impl Client {
             .await
     }
 
+    /// Returns information about transactions of the block with the specified number,
+    /// empty if the block hasn't reached the requested finality.
+    pub async fn block_transactions_at_finality(
+        &self,
+        block_number: BlockNumber,
+        finality: Finality,
+    ) -> client::Result<Vec<TransactionInfo>> {
+        self.get(&format!("blocks/{}/transactions", *block_number))
+            .query(&FinalityQuery::new(Some(finality)))
+            .send()
+            .await
+    }
+
+    /// Returns the changes of the accounts state made by the block or null if block doesn't exist.
+    pub async fn block_state_diff(
+        &self,
+        block_number: BlockNumber,
+    ) -> client::Result<Option<BlockStateDiff>> {
+        self.get(&format!("blocks/{}/state_diff", *block_number))
+            .send()
+            .await
+    }
+
     /// Returns information about several blocks in a range.
     pub async fn blocks_range(
         &self,
@@ -74,4 +164,27 @@ impl Client {
             .send()
             .await
     }
+
+    /// Returns information about several blocks in a range. Only the blocks that have reached
+    /// the requested finality are returned.
+    pub async fn blocks_range_at_finality(
+        &self,
+        from: Pagination,
+        limit: u32,
+        finality: Finality,
+    ) -> client::Result<Vec<BlockInfo>> {
+        self.get("blocks")
+            .query(&from.into_query(limit))
+            .query(&FinalityQuery::new(Some(finality)))
+            .send()
+            .await
+    }
+
+    /// Returns the latest reverts of the unverified blocks, newest first.
+    pub async fn block_reverts(&self, limit: u32) -> client::Result<Vec<BlockRevert>> {
+        self.get("blocks/reverts")
+            .query(&BlockRevertsQuery { limit })
+            .send()
+            .await
+    }
 }
@@ -134,6 +134,14 @@ impl ClientRequestBuilder {
         }
     }
 
+    /// Enables the bearer authentication with the given token.
+    pub fn bearer_auth(self, token: impl std::fmt::Display) -> Self {
+        Self {
+            inner: self.inner.bearer_auth(token),
+            url: self.url,
+        }
+    }
+
     /// Constructs the Request and sends it to the target URL, returning a future Response.
     ///
     /// This method takes account of the responses structure and the error handling specific.
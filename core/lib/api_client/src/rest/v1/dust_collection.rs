@@ -0,0 +1,42 @@
+//! Dust collection part of API implementation.
+
+// Built-in uses
+
+// External uses
+
+// Workspace uses
+use zksync_types::{
+    address_checksum::to_checksum_address,
+    dust_collection::{DustCollectionOptOut, DustCollectionStatus},
+    Address,
+};
+
+// Local uses
+use super::client::{Client, ClientError};
+
+/// Dust collection API part.
+impl Client {
+    /// Excludes the account from the dust collection.
+    pub async fn dust_collection_opt_out(
+        &self,
+        opt_out: DustCollectionOptOut,
+    ) -> Result<(), ClientError> {
+        self.post("dust_collection/opt_outs")
+            .body(&opt_out)
+            .send()
+            .await
+    }
+
+    /// Returns whether the account is excluded from the dust collection.
+    pub async fn dust_collection_status(
+        &self,
+        address: Address,
+    ) -> Result<DustCollectionStatus, ClientError> {
+        self.get(&format!(
+            "dust_collection/opt_outs/{}",
+            to_checksum_address(&address)
+        ))
+        .send()
+        .await
+    }
+}
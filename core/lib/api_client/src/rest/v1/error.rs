@@ -5,6 +5,7 @@ use std::fmt::{self, Display};
 use serde::{Deserialize, Serialize};
 
 // Workspace uses
+use zksync_types::api_error::ApiErrorCode;
 
 // Local uses
 
@@ -27,6 +28,16 @@ pub struct ErrorBody {
     /// Internal error code.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub code: Option<u64>,
+    /// Name of the stable API error code. Unlike `code`, it tells apart all the errors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_type: Option<ApiErrorCode>,
+}
+
+impl ErrorBody {
+    /// Returns the stable API error code, if the error has it.
+    pub fn error_code(&self) -> Option<ApiErrorCode> {
+        self.error_type
+    }
 }
 
 impl Display for ErrorBody {
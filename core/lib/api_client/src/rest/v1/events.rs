@@ -0,0 +1,39 @@
+//! Event log part of API implementation.
+
+// Built-in uses
+
+// External uses
+use serde::{Deserialize, Serialize};
+
+// Workspace uses
+use zksync_types::event::{EventOffset, EventRecord};
+
+// Local uses
+use super::client::{Client, ClientError};
+
+// Data transfer objects.
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EventsQuery {
+    /// Offset of the first returned event (inclusive).
+    pub from: EventOffset,
+    pub limit: u32,
+}
+
+/// Event log API part.
+impl Client {
+    /// Gets up to `limit` events starting from the given offset, in the offset order.
+    /// To continue consuming, the next request should start from the offset following
+    /// the offset of the last returned event.
+    pub async fn events(
+        &self,
+        from: EventOffset,
+        limit: u32,
+    ) -> Result<Vec<EventRecord>, ClientError> {
+        self.get("events")
+            .query(&EventsQuery { from, limit })
+            .send()
+            .await
+    }
+}
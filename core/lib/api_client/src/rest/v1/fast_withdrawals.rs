@@ -0,0 +1,67 @@
+//! Fast withdrawals part of API implementation.
+
+// Built-in uses
+
+// External uses
+use serde::{Deserialize, Serialize};
+
+// Workspace uses
+use zksync_types::{
+    fast_withdrawals::{FastWithdrawalIntent, FastWithdrawalIntentId, FastWithdrawalOffer},
+    tx::{TxEthSignature, TxHash},
+    TokenId, ZkSyncTx,
+};
+
+// Local uses
+use super::{client::Client, client::ClientError, transactions::IncomingTx};
+
+// Data transfer objects.
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingIntentsQuery {
+    /// Only the intents for this token are returned, if set.
+    pub token: Option<TokenId>,
+    pub limit: u32,
+}
+
+/// Fast withdrawals API part.
+impl Client {
+    /// Offers the withdrawal to the liquidity providers.
+    pub async fn submit_fast_withdrawal_intent(
+        &self,
+        intent: FastWithdrawalIntent,
+    ) -> Result<FastWithdrawalIntentId, ClientError> {
+        self.post("fast_withdrawals").body(&intent).send().await
+    }
+
+    /// Gets the oldest intents that are not fulfilled yet.
+    pub async fn pending_fast_withdrawals(
+        &self,
+        token: Option<TokenId>,
+        limit: u32,
+    ) -> Result<Vec<FastWithdrawalOffer>, ClientError> {
+        self.get("fast_withdrawals/pending")
+            .query(&PendingIntentsQuery { token, limit })
+            .send()
+            .await
+    }
+
+    /// Fulfills the intent with the payout transfer. Returns the hashes of the transfer
+    /// and the withdrawal.
+    pub async fn fulfill_fast_withdrawal(
+        &self,
+        id: FastWithdrawalIntentId,
+        transfer: ZkSyncTx,
+        signature: Option<TxEthSignature>,
+    ) -> Result<Vec<TxHash>, ClientError> {
+        self.post(&format!("fast_withdrawals/{}/fulfill", id))
+            .body(&IncomingTx {
+                tx: transfer,
+                signature,
+                idempotency_key: None,
+            })
+            .send()
+            .await
+    }
+}
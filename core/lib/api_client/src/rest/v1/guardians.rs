@@ -0,0 +1,57 @@
+//! Guardians part of API implementation.
+
+// Built-in uses
+
+// External uses
+
+// Workspace uses
+use zksync_types::{
+    guardians::{
+        GuardianRecovery, GuardianRecoveryRequest, GuardianRegistration,
+        GuardianRegistrationRequest,
+    },
+    AccountId,
+};
+
+// Local uses
+use super::client::{Client, ClientError};
+
+/// Guardians API part.
+impl Client {
+    /// Registers the guardian set of the account, replacing the previously registered one.
+    pub async fn register_guardians(
+        &self,
+        request: GuardianRegistrationRequest,
+    ) -> Result<GuardianRegistration, ClientError> {
+        self.post("guardians").body(&request).send().await
+    }
+
+    /// Returns the guardian set registered by the account.
+    pub async fn guardians(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<GuardianRegistration>, ClientError> {
+        self.get(&format!("guardians/{}", account_id)).send().await
+    }
+
+    /// Initiates the recovery of the account signed by the quorum of its guardians.
+    pub async fn initiate_guardian_recovery(
+        &self,
+        request: GuardianRecoveryRequest,
+    ) -> Result<GuardianRecovery, ClientError> {
+        self.post("guardians/recoveries")
+            .body(&request)
+            .send()
+            .await
+    }
+
+    /// Returns the recoveries initiated for the account, newest first.
+    pub async fn guardian_recoveries(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Vec<GuardianRecovery>, ClientError> {
+        self.get(&format!("guardians/{}/recoveries", account_id))
+            .send()
+            .await
+    }
+}
@@ -0,0 +1,42 @@
+//! L1 messages part of API implementation.
+
+// Built-in uses
+
+// External uses
+use serde::{Deserialize, Serialize};
+
+// Workspace uses
+use zksync_types::{
+    address_checksum::to_checksum_address, l1_message::DeliveredL1Message, Address,
+};
+
+// Local uses
+use super::client::{Client, ClientError};
+
+// Data transfer objects.
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct L1MessagesQuery {
+    /// Serial ID of the first returned message (inclusive).
+    pub from_id: u64,
+    pub limit: u32,
+}
+
+/// L1 messages API part.
+impl Client {
+    /// Gets up to `limit` messages sent from L1 to the account, starting from the message
+    /// with the given serial ID. To continue consuming, the next request should start from
+    /// the ID following the ID of the last returned message.
+    pub async fn l1_messages(
+        &self,
+        address: Address,
+        from_id: u64,
+        limit: u32,
+    ) -> Result<Vec<DeliveredL1Message>, ClientError> {
+        self.get(&format!("messages/{}", to_checksum_address(&address)))
+            .query(&L1MessagesQuery { from_id, limit })
+            .send()
+            .await
+    }
+}
@@ -4,31 +4,57 @@
 use serde::{Deserialize, Serialize};
 
 // Workspace uses
-use zksync_types::BlockNumber;
+use zksync_types::{finality::Finality, BlockNumber};
 
 // Public uses
 pub use self::{
-    blocks::{BlockInfo, TransactionInfo},
+    blocks::{
+        AccountStateDiff, BalanceDiff, BlockInfo, BlockRevertsQuery, BlockStateDiff,
+        PubKeyHashDiff, TransactionInfo,
+    },
     client::{Client, ClientError, Result as ClientResult},
     config::Contracts,
     error::ErrorBody,
-    operations::{PriorityOpData, PriorityOpQuery, PriorityOpQueryError, PriorityOpReceipt},
-    search::BlockSearchQuery,
+    events::EventsQuery,
+    fast_withdrawals::PendingIntentsQuery,
+    messages::L1MessagesQuery,
+    operations::{
+        PriorityOpCostsQuery, PriorityOpData, PriorityOpQuery, PriorityOpQueryError,
+        PriorityOpReceipt, PriorityQueueItem, ProcessedPriorityOps,
+    },
+    search::{BlockSearchQuery, SearchResult},
+    signed_responses::{
+        canonical_json, recover_response_signer, request_hash, response_signature_message,
+        ResponseSignerInfo, RESPONSE_SIGNATURE_HEADER, RESPONSE_SIGNER_PATH,
+        RESPONSE_TIMESTAMP_HEADER,
+    },
     tokens::{TokenPriceKind, TokenPriceQuery},
     transactions::{
         FastProcessingQuery, IncomingTx, IncomingTxBatch, IncomingTxBatchForFee, IncomingTxForFee,
-        Receipt, TxData,
+        PackingDiagnostics, Receipt, SimulatedAccountState, SimulatedTx, TxAdmission, TxData,
+        TxRejection, TxSimulationRequest, TxSimulationResult, TxStatus, TxTrace, TxTraceStep,
     },
 };
 
 // Local uses
 pub mod accounts;
+mod activations;
+mod aliases;
+mod attestations;
 mod blocks;
 mod client;
 mod config;
+mod dust_collection;
 mod error;
+mod events;
+mod fast_withdrawals;
+mod guardians;
+mod messages;
+mod nonce_reservations;
 mod operations;
 mod search;
+mod signed_responses;
+mod snapshots;
 mod tokens;
 mod transactions;
 
@@ -50,6 +76,34 @@ pub struct PaginationQuery {
     limit: u32,
 }
 
+/// Finality query parameter, `?finality=pending|committed|verified|executed`.
+///
+/// The data that hasn't reached the requested finality yet is omitted from the responses,
+/// so the clients may choose between the latest and the final data. If the parameter is not
+/// set, the latest data known to the server is returned.
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq, Default)]
+pub struct FinalityQuery {
+    pub finality: Option<Finality>,
+}
+
+impl FinalityQuery {
+    pub fn new(finality: Option<Finality>) -> Self {
+        Self { finality }
+    }
+
+    /// Returns the finality the data is required to reach.
+    pub fn min_finality(self) -> Finality {
+        self.finality.unwrap_or_default()
+    }
+
+    /// Checks whether the transaction has reached the requested finality. Rejected transactions
+    /// (i.e. the ones with no finality) are reported regardless of the requested finality,
+    /// since they will never reach any.
+    pub fn is_reached_by(self, finality: Option<Finality>) -> bool {
+        finality.map_or(true, |finality| finality >= self.min_finality())
+    }
+}
+
 /// Pagination request parameter.
 ///
 /// Used together with the limit parameter to perform pagination.
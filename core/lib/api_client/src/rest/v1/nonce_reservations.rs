@@ -0,0 +1,39 @@
+//! Nonce reservations part of API implementation.
+
+// Built-in uses
+
+// External uses
+
+// Workspace uses
+use zksync_types::{
+    address_checksum::to_checksum_address,
+    nonce_reservation::{NonceReservation, NonceReservationRequest},
+    Address,
+};
+
+// Local uses
+use super::client::{Client, ClientError};
+
+/// Nonce reservations API part.
+impl Client {
+    /// Reserves the range of nonces of the account.
+    pub async fn reserve_nonces(
+        &self,
+        request: NonceReservationRequest,
+    ) -> Result<NonceReservation, ClientError> {
+        self.post("nonce_reservations").body(&request).send().await
+    }
+
+    /// Returns the active nonce reservations of the account.
+    pub async fn nonce_reservations(
+        &self,
+        address: Address,
+    ) -> Result<Vec<NonceReservation>, ClientError> {
+        self.get(&format!(
+            "nonce_reservations/{}",
+            to_checksum_address(&address)
+        ))
+        .send()
+        .await
+    }
+}
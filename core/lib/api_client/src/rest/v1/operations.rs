@@ -4,16 +4,22 @@
 use std::{fmt::Display, str::FromStr};
 
 // External uses
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 // Local uses
 use super::{
     client::{Client, ClientError},
     transactions::Receipt,
+    FinalityQuery,
 };
 
 // Workspace uses
-use zksync_types::{ZkSyncOp, H256};
+use zksync_types::{
+    finality::Finality,
+    priority_op_cost::{PriorityOpCost, PriorityOpCostSummary},
+    ZkSyncOp, ZkSyncPriorityOp, H256,
+};
 
 // Data transfer objects.
 
@@ -33,6 +39,8 @@ pub struct PriorityOpReceipt {
     #[serde(flatten)]
     pub status: Receipt,
     pub index: Option<u32>,
+    /// Finality of the block the operation is executed in.
+    pub finality: Finality,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -43,6 +51,39 @@ pub struct PriorityOpData {
     pub serial_id: u64,
 }
 
+/// Priority operation confirmed on L1 and waiting for inclusion into a zkSync block.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PriorityQueueItem {
+    pub serial_id: u64,
+    /// Zero-based position of the operation in the queue.
+    pub position: usize,
+    pub eth_hash: H256,
+    pub eth_block: u64,
+    /// Ethereum block until which the operation must be processed.
+    pub deadline_block: u64,
+    pub data: ZkSyncPriorityOp,
+    /// Estimated inclusion time based on the recent block cadence.
+    /// `None` if there is not enough data to make an estimation.
+    pub estimated_inclusion: Option<DateTime<Utc>>,
+}
+
+/// Serial IDs of the processed priority operations.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessedPriorityOps {
+    /// Serial ID of the last executed operation, including the ones in the pending block.
+    pub last_executed_serial_id: Option<u64>,
+    /// Serial ID of the last operation included into a sealed block.
+    pub last_sealed_serial_id: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct PriorityOpCostsQuery {
+    /// Amount of the latest operations of every type the averages are calculated from.
+    pub limit: u32,
+}
+
 impl From<u64> for PriorityOpQuery {
     fn from(v: u64) -> Self {
         Self::Id(v)
@@ -111,7 +152,20 @@ impl Client {
             .await
     }
 
-    /// Gets priority operation receipt.
+    /// Gets priority operation receipt, or null if the operation hasn't reached
+    /// the requested finality.
+    pub async fn priority_op_at_finality(
+        &self,
+        query: impl Into<PriorityOpQuery>,
+        finality: Finality,
+    ) -> Result<Option<PriorityOpReceipt>, ClientError> {
+        self.get(&format!("operations/{}", query.into()))
+            .query(&FinalityQuery::new(Some(finality)))
+            .send()
+            .await
+    }
+
+    /// Gets priority operation data.
     pub async fn priority_op_data(
         &self,
         query: impl Into<PriorityOpQuery>,
@@ -120,4 +174,35 @@ impl Client {
             .send()
             .await
     }
+
+    /// Gets the L1 costs of the priority operation.
+    pub async fn priority_op_cost(
+        &self,
+        query: impl Into<PriorityOpQuery>,
+    ) -> Result<Option<PriorityOpCost>, ClientError> {
+        self.get(&format!("operations/{}/cost", query.into()))
+            .send()
+            .await
+    }
+
+    /// Gets the average L1 costs of the latest priority operations of every type.
+    pub async fn priority_op_costs(
+        &self,
+        limit: u32,
+    ) -> Result<Vec<PriorityOpCostSummary>, ClientError> {
+        self.get("operations/costs")
+            .query(&PriorityOpCostsQuery { limit })
+            .send()
+            .await
+    }
+
+    /// Gets the priority operations pending inclusion, in order of processing.
+    pub async fn priority_queue(&self) -> Result<Vec<PriorityQueueItem>, ClientError> {
+        self.get("priority_queue").send().await
+    }
+
+    /// Gets the serial IDs of the last processed priority operations.
+    pub async fn processed_priority_ops(&self) -> Result<ProcessedPriorityOps, ClientError> {
+        self.get("priority_queue/processed").send().await
+    }
 }
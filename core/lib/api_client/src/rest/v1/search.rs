@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 
 // Workspace uses
 use zksync_crypto::{convert::FeConvert, Fr};
-use zksync_types::{tx::TxHash, BlockNumber};
+use zksync_types::{tx::TxHash, AccountId, Address, BlockNumber, TokenId, H256};
 
 // Local uses
 use super::{
@@ -51,6 +51,44 @@ impl From<TxHash> for BlockSearchQuery {
     }
 }
 
+/// Entity found by the unified search along with the data required to link to it.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SearchResult {
+    /// The query is an account address. Addresses are always resolved, since the account may
+    /// not be created in the zkSync network yet.
+    #[serde(rename_all = "camelCase")]
+    Account {
+        address: Address,
+        account_id: Option<AccountId>,
+    },
+    /// The query is the hash of the executed transaction.
+    #[serde(rename_all = "camelCase")]
+    Transaction {
+        tx_hash: TxHash,
+        block_number: BlockNumber,
+    },
+    /// The query is the number, the state root hash or the commit/verify Ethereum transaction
+    /// hash of the block.
+    #[serde(rename_all = "camelCase")]
+    Block { block_number: BlockNumber },
+    /// The query is the serial ID or the Ethereum transaction hash of the executed
+    /// priority operation.
+    #[serde(rename_all = "camelCase")]
+    PriorityOperation {
+        serial_id: u64,
+        eth_hash: H256,
+        block_number: BlockNumber,
+    },
+    /// The query is the token symbol.
+    #[serde(rename_all = "camelCase")]
+    Token {
+        token_id: TokenId,
+        symbol: String,
+        address: Address,
+    },
+}
+
 /// Search API part.
 impl Client {
     /// Performs a block search with an uncertain query, which can be either of:
@@ -64,4 +102,23 @@ impl Client {
     ) -> client::Result<Option<BlockInfo>> {
         self.get("search").query(&query.into()).send().await
     }
+
+    /// Resolves an uncertain query to all the matching entities, the query can be either of:
+    ///
+    /// - The account address.
+    /// - Hash of the executed transaction.
+    /// - The number, the state root hash or the hash of commit/verify Ethereum transaction
+    ///   for the block.
+    /// - The serial ID or the Ethereum transaction hash of the priority operation.
+    /// - The token symbol.
+    ///
+    /// Returns an empty list if nothing matches the query.
+    pub async fn search(&self, query: impl Into<String>) -> client::Result<Vec<SearchResult>> {
+        self.get("search/unified")
+            .query(&BlockSearchQuery {
+                query: query.into(),
+            })
+            .send()
+            .await
+    }
 }
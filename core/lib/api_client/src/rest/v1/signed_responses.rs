@@ -0,0 +1,106 @@
+//! Operator-signed responses part of API implementation.
+//!
+//! If enabled on the server, the account info and transaction status responses carry the
+//! operator signature in the `x-zksync-signature` header. The body is the canonical JSON of
+//! the response (object keys are sorted, no whitespace), so the data can be cached and relayed
+//! along with the proof of its origin. The signer address is published at the well-known endpoint.
+//!
+//! The signature is an Ethereum signed message signature of the request hash, the moment the
+//! response was signed and the body (see `response_signature_message`). The request hash binds
+//! the response to the request it answers, so it can't be passed off as the answer to another
+//! request, and the timestamp from the `x-zksync-signature-timestamp` header lets the clients
+//! reject the stale responses.
+
+// Built-in uses
+
+// External uses
+use parity_crypto::Keccak256;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+// Workspace uses
+use zksync_types::{tx::PackedEthSignature, Address, H256};
+
+// Local uses
+use super::client::{self, Client};
+
+/// Header carrying the operator signature of the response.
+pub const RESPONSE_SIGNATURE_HEADER: &str = "x-zksync-signature";
+/// Header carrying the UNIX timestamp (in seconds) of the moment the response was signed.
+pub const RESPONSE_TIMESTAMP_HEADER: &str = "x-zksync-signature-timestamp";
+/// Path of the endpoint publishing the address of the responses signer.
+pub const RESPONSE_SIGNER_PATH: &str = "/.well-known/zksync-response-signer";
+
+// Data transfer objects.
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseSignerInfo {
+    /// Address of the operator key signing the responses.
+    pub address: Address,
+}
+
+/// Serializes the value into the canonical JSON: object keys are sorted recursively
+/// and no whitespace is added.
+pub fn canonical_json(value: &Value) -> String {
+    fn canonicalize(value: &Value) -> Value {
+        match value {
+            Value::Object(object) => {
+                let mut keys: Vec<_> = object.keys().collect();
+                keys.sort();
+                let sorted: Map<_, _> = keys
+                    .into_iter()
+                    .map(|key| (key.clone(), canonicalize(&object[key])))
+                    .collect();
+                Value::Object(sorted)
+            }
+            Value::Array(items) => Value::Array(items.iter().map(canonicalize).collect()),
+            other => other.clone(),
+        }
+    }
+
+    canonicalize(value).to_string()
+}
+
+/// Returns the hash of the request the response is signed for, i.e. `keccak256` of the
+/// request method and the path with the query as received by the server, e.g.
+/// `GET /api/v1/accounts/1`.
+pub fn request_hash(method: &str, path_and_query: &str) -> H256 {
+    H256::from(
+        format!("{} {}", method.to_uppercase(), path_and_query)
+            .as_bytes()
+            .keccak256(),
+    )
+}
+
+/// Returns the message signed by the operator: the request hash, the big-endian timestamp
+/// and the response body.
+pub fn response_signature_message(request_hash: H256, timestamp: u64, body: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(H256::len_bytes() + 8 + body.len());
+    message.extend_from_slice(request_hash.as_bytes());
+    message.extend_from_slice(&timestamp.to_be_bytes());
+    message.extend_from_slice(body);
+    message
+}
+
+/// Recovers the address of the signer of the response to the request given the values
+/// of the `x-zksync-signature` and `x-zksync-signature-timestamp` headers.
+pub fn recover_response_signer(
+    request_hash: H256,
+    timestamp: u64,
+    body: &[u8],
+    signature: &str,
+) -> Option<Address> {
+    let signature = hex::decode(signature.trim_start_matches("0x")).ok()?;
+    PackedEthSignature::deserialize_packed(&signature)
+        .ok()?
+        .signature_recover_signer(&response_signature_message(request_hash, timestamp, body))
+        .ok()
+}
+
+/// Signed responses API part.
+impl Client {
+    pub async fn response_signer(&self) -> client::Result<ResponseSignerInfo> {
+        self.get_with_scope("", RESPONSE_SIGNER_PATH).send().await
+    }
+}
@@ -0,0 +1,40 @@
+//! Snapshots part of API implementation.
+
+// Built-in uses
+
+// External uses
+
+// Workspace uses
+use zksync_types::{
+    snapshot::{SnapshotChunk, SnapshotMetadata},
+    BlockNumber,
+};
+
+// Local uses
+use super::client::{Client, ClientError};
+
+/// Snapshots API part.
+impl Client {
+    /// Returns the metadata of the latest state snapshot.
+    /// The snapshots are served to the clients with the authorization token only.
+    pub async fn latest_snapshot(&self, auth_token: &str) -> Result<SnapshotMetadata, ClientError> {
+        self.get("snapshots/latest")
+            .bearer_auth(auth_token)
+            .send()
+            .await
+    }
+
+    /// Returns the chunk of the snapshot made at the given block,
+    /// or `None` if the snapshot is not served anymore.
+    pub async fn snapshot_chunk(
+        &self,
+        auth_token: &str,
+        block_number: BlockNumber,
+        index: usize,
+    ) -> Result<Option<SnapshotChunk>, ClientError> {
+        self.get(&format!("snapshots/{}/chunks/{}", *block_number, index))
+            .bearer_auth(auth_token)
+            .send()
+            .await
+    }
+}
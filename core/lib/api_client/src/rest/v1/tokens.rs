@@ -7,7 +7,7 @@ use bigdecimal::BigDecimal;
 use serde::{Deserialize, Serialize};
 
 // Workspace uses
-use zksync_types::{Token, TokenLike};
+use zksync_types::{tokens::TokenFeeStatus, Token, TokenLike};
 
 // Local uses
 use super::client::{self, Client};
@@ -48,4 +48,14 @@ impl Client {
             .send()
             .await
     }
+
+    /// Returns whether the token can be used to pay fees, and why not if it can't.
+    pub async fn token_fee_status(
+        &self,
+        token: &TokenLike,
+    ) -> client::Result<Option<TokenFeeStatus>> {
+        self.get(&format!("tokens/{}/fee_status", token))
+            .send()
+            .await
+    }
 }
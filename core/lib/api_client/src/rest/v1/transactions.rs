@@ -1,18 +1,26 @@
 //! Transactions part of API implementation.
 
 // Built-in uses
+use std::collections::BTreeMap;
 
 // External uses
 use serde::{Deserialize, Serialize};
 
 // Workspace uses
 use zksync_types::{
+    api_error::{ApiErrorCategory, ApiErrorCode},
+    fee_refund::EffectiveFee,
+    finality::{Finality, FinalizedBlocks},
+    helpers::PackableAmounts,
     tx::{EthBatchSignatures, EthSignData, TxEthSignature, TxHash},
-    Address, BatchFee, BlockNumber, Fee, SignedZkSyncTx, TokenLike, TxFeeTypes, ZkSyncTx,
+    withdrawal_execution::WithdrawalExecution,
+    AccountId, Address, BatchFee, BlockNumber, Fee, Nonce, PubKeyHash, SignedZkSyncTx, TokenId,
+    TokenLike, TxFeeTypes, ZkSyncTx,
 };
+use zksync_utils::BigUintSerdeWrapper;
 
 // Local uses
-use super::{client::Client, client::ClientError, Pagination};
+use super::{client::Client, client::ClientError, FinalityQuery, Pagination};
 
 // Data transfer objects.
 
@@ -44,6 +52,10 @@ pub struct TxData {
 pub struct IncomingTx {
     pub tx: ZkSyncTx,
     pub signature: Option<TxEthSignature>,
+    /// Key identifying the submission. Resubmitting the same transactions with the same key
+    /// returns the hashes of the originally submitted transactions instead of an error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -60,6 +72,10 @@ pub struct IncomingTxBatchForFee {
     pub tx_types: Vec<TxFeeTypes>,
     pub addresses: Vec<Address>,
     pub token_like: TokenLike,
+    /// Sender of the transactions, if they are all sent in the fee token by the same account.
+    /// Such transactions are charged for their marginal cost rather than as the standalone ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender: Option<Address>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -67,6 +83,18 @@ pub struct IncomingTxBatchForFee {
 pub struct IncomingTxBatch {
     pub txs: Vec<ZkSyncTx>,
     pub signature: EthBatchSignatures,
+    /// Key identifying the submission. Resubmitting the same transactions with the same key
+    /// returns the hashes of the originally submitted transactions instead of an error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+}
+
+/// Closest packable values of the amount, both for the transfer amount and the fee.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackingDiagnostics {
+    pub token_amount: PackableAmounts,
+    pub fee_amount: PackableAmounts,
 }
 
 /// Transaction (or priority operation) receipt.
@@ -86,6 +114,173 @@ pub enum Receipt {
     Rejected { reason: Option<String> },
 }
 
+impl Receipt {
+    /// Returns the finality the transaction has reached, `None` if it's rejected.
+    pub fn finality(&self, finalized: &FinalizedBlocks) -> Option<Finality> {
+        match self {
+            Receipt::Pending | Receipt::Executed => Some(Finality::Pending),
+            Receipt::Committed { block } | Receipt::Verified { block } => {
+                Some(finalized.block_finality(*block))
+            }
+            Receipt::Rejected { .. } => None,
+        }
+    }
+}
+
+/// Receipt of the transaction along with the finality it has reached.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TxStatus {
+    #[serde(flatten)]
+    pub receipt: Receipt,
+    /// Finality of the transaction, `None` if it's rejected.
+    pub finality: Option<Finality>,
+}
+
+impl TxStatus {
+    pub fn new(receipt: Receipt, finalized: &FinalizedBlocks) -> Self {
+        let finality = receipt.finality(finalized);
+        Self { receipt, finality }
+    }
+}
+
+/// Changes of the accounts state made by the executed transaction.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TxTrace {
+    pub tx_hash: TxHash,
+    pub block_number: BlockNumber,
+    /// Changes in the order they were made by the transaction. The fee is credited
+    /// to the operator account when the block is sealed, so it's not a part of the trace.
+    pub steps: Vec<TxTraceStep>,
+}
+
+/// Single change of the account state.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TxTraceStep {
+    #[serde(rename_all = "camelCase")]
+    CreateAccount {
+        account_id: AccountId,
+        address: Address,
+        nonce: Nonce,
+    },
+    #[serde(rename_all = "camelCase")]
+    DeleteAccount {
+        account_id: AccountId,
+        address: Address,
+        nonce: Nonce,
+    },
+    #[serde(rename_all = "camelCase")]
+    UpdateBalance {
+        account_id: AccountId,
+        token: TokenId,
+        old_balance: BigUintSerdeWrapper,
+        new_balance: BigUintSerdeWrapper,
+        old_nonce: Nonce,
+        new_nonce: Nonce,
+    },
+    #[serde(rename_all = "camelCase")]
+    ChangePubKeyHash {
+        account_id: AccountId,
+        old_pub_key_hash: PubKeyHash,
+        new_pub_key_hash: PubKeyHash,
+        old_nonce: Nonce,
+        new_nonce: Nonce,
+    },
+}
+
+/// Transactions to execute against the current committed state without persisting the results.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TxSimulationRequest {
+    /// Transactions executed one after another. If one of them fails, none are applied,
+    /// just like in the batch.
+    pub txs: Vec<ZkSyncTx>,
+    /// Whether the transactions are not signed. Unsigned transactions are executed as if
+    /// they were signed by the current signing keys of their senders.
+    #[serde(default)]
+    pub unsigned: bool,
+}
+
+/// Would-be result of the transactions execution.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TxSimulationResult {
+    pub success: bool,
+    /// Index of the failed transaction, if any.
+    pub failed_tx_index: Option<usize>,
+    pub fail_reason: Option<String>,
+    /// Executed transactions, empty if the execution failed.
+    pub txs: Vec<SimulatedTx>,
+    /// Resulting state of the accounts changed by the transactions, empty if the execution failed.
+    pub accounts: Vec<SimulatedAccountState>,
+}
+
+/// Transaction executed by the simulation.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedTx {
+    pub tx_hash: TxHash,
+    pub fee_token: TokenId,
+    /// Fee charged from the sender.
+    pub fee: BigUintSerdeWrapper,
+}
+
+/// Resulting state of the account changed by the simulated transactions.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedAccountState {
+    pub address: Address,
+    /// ID of the account, `None` if the account would be created by the transactions.
+    pub account_id: Option<AccountId>,
+    pub nonce: Nonce,
+    /// Resulting balances of the changed tokens.
+    pub balances: BTreeMap<TokenId, BigUintSerdeWrapper>,
+}
+
+/// Verdict of the submission checks run on the transactions without submitting them.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TxAdmission {
+    /// Whether the transactions would be accepted if they were submitted now.
+    pub accepted: bool,
+    /// Error the submission would fail with, if any.
+    pub rejection: Option<TxRejection>,
+}
+
+/// Reason the transactions would be rejected for.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TxRejection {
+    /// Code of the error, the same as returned by the submission endpoints.
+    pub code: u64,
+    pub error_type: ApiErrorCode,
+    pub category: ApiErrorCategory,
+    pub message: String,
+}
+
+impl TxAdmission {
+    pub fn accepted() -> Self {
+        Self {
+            accepted: true,
+            rejection: None,
+        }
+    }
+
+    pub fn rejected(code: u64, error_type: ApiErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            accepted: false,
+            rejection: Some(TxRejection {
+                code,
+                error_type,
+                category: error_type.category(),
+                message: message.into(),
+            }),
+        }
+    }
+}
+
 impl From<TxData> for SignedZkSyncTx {
     fn from(inner: TxData) -> Self {
         Self {
@@ -115,7 +310,77 @@ impl Client {
     ) -> Result<TxHash, ClientError> {
         self.post("transactions/submit")
             .query(&FastProcessingQuery { fast_processing })
-            .body(&IncomingTx { tx, signature })
+            .body(&IncomingTx {
+                tx,
+                signature,
+                idempotency_key: None,
+            })
+            .send()
+            .await
+    }
+
+    /// Sends a new transaction to the memory pool. The submission can be safely retried with
+    /// the same idempotency key: the transaction is sent only once.
+    pub async fn submit_tx_idempotent(
+        &self,
+        tx: ZkSyncTx,
+        signature: Option<TxEthSignature>,
+        fast_processing: Option<bool>,
+        idempotency_key: String,
+    ) -> Result<TxHash, ClientError> {
+        self.post("transactions/submit")
+            .query(&FastProcessingQuery { fast_processing })
+            .body(&IncomingTx {
+                tx,
+                signature,
+                idempotency_key: Some(idempotency_key),
+            })
+            .send()
+            .await
+    }
+
+    /// Checks whether the transaction would be accepted by the memory pool, without submitting it.
+    pub async fn would_accept_tx(
+        &self,
+        tx: ZkSyncTx,
+        signature: Option<TxEthSignature>,
+        fast_processing: Option<bool>,
+    ) -> Result<TxAdmission, ClientError> {
+        self.post("transactions/would_accept")
+            .query(&FastProcessingQuery { fast_processing })
+            .body(&IncomingTx {
+                tx,
+                signature,
+                idempotency_key: None,
+            })
+            .send()
+            .await
+    }
+
+    /// Checks whether the transactions batch would be accepted by the memory pool, without submitting it.
+    pub async fn would_accept_txs_batch(
+        &self,
+        txs: Vec<ZkSyncTx>,
+        signature: EthBatchSignatures,
+    ) -> Result<TxAdmission, ClientError> {
+        self.post("transactions/would_accept/batch")
+            .body(&IncomingTxBatch {
+                txs,
+                signature,
+                idempotency_key: None,
+            })
+            .send()
+            .await
+    }
+
+    /// Executes the transactions against the current committed state without submitting them.
+    pub async fn simulate_txs(
+        &self,
+        txs: Vec<ZkSyncTx>,
+        unsigned: bool,
+    ) -> Result<TxSimulationResult, ClientError> {
+        self.post("transactions/simulate")
+            .body(&TxSimulationRequest { txs, unsigned })
             .send()
             .await
     }
@@ -143,12 +408,14 @@ impl Client {
         tx_types: Vec<TxFeeTypes>,
         addresses: Vec<Address>,
         token_like: TokenLike,
+        sender: Option<Address>,
     ) -> Result<BatchFee, ClientError> {
         self.post("transactions/fee/batch")
             .body(&IncomingTxBatchForFee {
                 tx_types,
                 addresses,
                 token_like,
+                sender,
             })
             .send()
             .await
@@ -161,14 +428,49 @@ impl Client {
         signature: EthBatchSignatures,
     ) -> Result<Vec<TxHash>, ClientError> {
         self.post("transactions/submit/batch")
-            .body(&IncomingTxBatch { txs, signature })
+            .body(&IncomingTxBatch {
+                txs,
+                signature,
+                idempotency_key: None,
+            })
+            .send()
+            .await
+    }
+
+    /// Sends a new transactions batch to the memory pool. The submission can be safely retried
+    /// with the same idempotency key: the batch is sent only once.
+    pub async fn submit_tx_batch_idempotent(
+        &self,
+        txs: Vec<ZkSyncTx>,
+        signature: EthBatchSignatures,
+        idempotency_key: String,
+    ) -> Result<Vec<TxHash>, ClientError> {
+        self.post("transactions/submit/batch")
+            .body(&IncomingTxBatch {
+                txs,
+                signature,
+                idempotency_key: Some(idempotency_key),
+            })
             .send()
             .await
     }
 
     /// Gets actual transaction receipt.
-    pub async fn tx_status(&self, tx_hash: TxHash) -> Result<Option<Receipt>, ClientError> {
+    pub async fn tx_status(&self, tx_hash: TxHash) -> Result<Option<TxStatus>, ClientError> {
+        self.get(&format!("transactions/{}", tx_hash.to_string()))
+            .send()
+            .await
+    }
+
+    /// Gets actual transaction receipt, or null if the transaction hasn't reached
+    /// the requested finality.
+    pub async fn tx_status_at_finality(
+        &self,
+        tx_hash: TxHash,
+        finality: Finality,
+    ) -> Result<Option<TxStatus>, ClientError> {
         self.get(&format!("transactions/{}", tx_hash.to_string()))
+            .query(&FinalityQuery::new(Some(finality)))
             .send()
             .await
     }
@@ -180,12 +482,43 @@ impl Client {
             .await
     }
 
+    /// Gets the changes of the accounts state made by the executed transaction.
+    pub async fn tx_trace(&self, tx_hash: TxHash) -> Result<Option<TxTrace>, ClientError> {
+        self.get(&format!("transactions/{}/trace", tx_hash.to_string()))
+            .send()
+            .await
+    }
+
+    /// Gets the L1 transaction which executed the withdrawal.
+    pub async fn withdrawal_execution(
+        &self,
+        tx_hash: TxHash,
+    ) -> Result<Option<WithdrawalExecution>, ClientError> {
+        self.get(&format!("transactions/{}/withdrawal", tx_hash.to_string()))
+            .send()
+            .await
+    }
+
+    /// Gets the fee paid for the transaction, taking its refund into account.
+    /// Returns `None` if no part of the fee is to be refunded.
+    pub async fn effective_fee(
+        &self,
+        tx_hash: TxHash,
+    ) -> Result<Option<EffectiveFee>, ClientError> {
+        self.get(&format!(
+            "transactions/{}/effective_fee",
+            tx_hash.to_string()
+        ))
+        .send()
+        .await
+    }
+
     /// Gets transaction receipt by ID.
     pub async fn tx_receipt_by_id(
         &self,
         tx_hash: TxHash,
         receipt_id: u32,
-    ) -> Result<Option<Receipt>, ClientError> {
+    ) -> Result<Option<TxStatus>, ClientError> {
         self.get(&format!(
             "transactions/{}/receipts/{}",
             tx_hash.to_string(),
@@ -201,10 +534,20 @@ impl Client {
         tx_hash: TxHash,
         from: Pagination,
         limit: u32,
-    ) -> Result<Vec<Receipt>, ClientError> {
+    ) -> Result<Vec<TxStatus>, ClientError> {
         self.get(&format!("transactions/{}/receipts", tx_hash.to_string()))
             .query(&from.into_query(limit))
             .send()
             .await
     }
+
+    /// Returns the closest packable values of the given amount (in the token base units).
+    pub async fn packable_amounts(
+        &self,
+        amount: impl ToString,
+    ) -> Result<PackingDiagnostics, ClientError> {
+        self.get(&format!("transactions/packable/{}", amount.to_string()))
+            .send()
+            .await
+    }
 }
@@ -155,9 +155,9 @@ pub fn create_exit_circuit_with_public_input(
             token: Some(token_id_fe),
             witness: OperationBranchWitness {
                 account_witness,
-                account_path: audit_path,
+                account_path: audit_path.into(),
                 balance_value: Some(balance),
-                balance_subtree_path: audit_balance_path,
+                balance_subtree_path: audit_balance_path.into(),
             },
         },
     }
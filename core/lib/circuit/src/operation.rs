@@ -1,3 +1,5 @@
+// Built-in
+use std::sync::Arc;
 // External
 use serde::{Deserialize, Serialize};
 use zksync_crypto::franklin_crypto::{
@@ -8,13 +10,18 @@ use zksync_crypto::franklin_crypto::{
 // Workspace
 use crate::account::AccountWitness;
 
+/// Witness of the account and balance in the tree.
+///
+/// An operation spanning several chunks repeats the same branches in every chunk, so the audit
+/// paths are shared between the copies instead of being cloned: a block witness consists of
+/// thousands of paths, and most of them are duplicates.
 #[derive(Clone, Debug)]
 pub struct OperationBranchWitness<E: RescueEngine> {
     pub account_witness: AccountWitness<E>,
-    pub account_path: Vec<Option<E::Fr>>,
+    pub account_path: Arc<[Option<E::Fr>]>,
 
     pub balance_value: Option<E::Fr>,
-    pub balance_subtree_path: Vec<Option<E::Fr>>,
+    pub balance_subtree_path: Arc<[Option<E::Fr>]>,
 }
 
 #[derive(Clone, Debug)]
@@ -1,6 +1,10 @@
 // Built-in
+use std::{collections::HashMap, fmt, sync::Arc};
 // External
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::{
+    de::{self, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 // Workspace
 use zksync_crypto::ff::PrimeField;
 use zksync_crypto::franklin_crypto::alt_babyjubjub::AltJubjubBn256;
@@ -162,14 +166,124 @@ pub struct OperationBranchDef {
 pub struct OperationBranchWitnessDef {
     #[serde(with = "AccountWitnessDef")]
     pub account_witness: AccountWitness<Engine>,
-    #[serde(with = "VecOptionalFrSerde")]
-    pub account_path: Vec<Option<Fr>>,
+    #[serde(with = "SharedPathSerde")]
+    pub account_path: Arc<[Option<Fr>]>,
     #[serde(with = "OptionalFrSerde")]
     pub balance_value: Option<Fr>,
-    #[serde(with = "VecOptionalFrSerde")]
-    pub balance_subtree_path: Vec<Option<Fr>>,
+    #[serde(with = "SharedPathSerde")]
+    pub balance_subtree_path: Arc<[Option<Fr>]>,
+}
+
+struct SharedPathSerde;
+
+impl SharedPathSerde {
+    fn serialize<S>(path: &[Option<Fr>], ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        VecOptionalFrSerde::serialize(path, ser)
+    }
+
+    fn deserialize<'de, D>(deserializer: D) -> Result<Arc<[Option<Fr>]>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        VecOptionalFrSerde::deserialize(deserializer).map(Into::into)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct OperationWrapper(#[serde(with = "OperationDef")] Operation<Engine>);
+
+#[derive(Serialize, Deserialize)]
+struct PathWrapper(#[serde(with = "SharedPathSerde")] Arc<[Option<Fr>]>);
+
+/// Operations with the interned audit paths: every distinct path is stored once in `paths`,
+/// and the operations are stored with the empty paths, which are restored from `path_indices`.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InternedOperations {
+    paths: Vec<PathWrapper>,
+    /// Indices of the lhs account, lhs balance, rhs account and rhs balance paths of each operation.
+    path_indices: Vec<[u32; 4]>,
+    operations: Vec<OperationWrapper>,
+}
+
+impl InternedOperations {
+    fn new(operations: &[Operation<Engine>]) -> Self {
+        // The paths are shared by the witness builder, so the identical paths
+        // are found by their address, without comparing the contents.
+        let mut indices = HashMap::new();
+        let mut paths = Vec::new();
+        let mut intern = |path: &Arc<[Option<Fr>]>| -> u32 {
+            let key = (path.as_ptr() as usize, path.len());
+            *indices.entry(key).or_insert_with(|| {
+                paths.push(PathWrapper(path.clone()));
+                (paths.len() - 1) as u32
+            })
+        };
+
+        let empty_path: Arc<[Option<Fr>]> = Arc::new([]);
+        let mut path_indices = Vec::with_capacity(operations.len());
+        let mut stripped_operations = Vec::with_capacity(operations.len());
+        for operation in operations {
+            path_indices.push([
+                intern(&operation.lhs.witness.account_path),
+                intern(&operation.lhs.witness.balance_subtree_path),
+                intern(&operation.rhs.witness.account_path),
+                intern(&operation.rhs.witness.balance_subtree_path),
+            ]);
+
+            let mut operation = operation.clone();
+            operation.lhs.witness.account_path = empty_path.clone();
+            operation.lhs.witness.balance_subtree_path = empty_path.clone();
+            operation.rhs.witness.account_path = empty_path.clone();
+            operation.rhs.witness.balance_subtree_path = empty_path.clone();
+            stripped_operations.push(OperationWrapper(operation));
+        }
+
+        Self {
+            paths,
+            path_indices,
+            operations: stripped_operations,
+        }
+    }
+
+    fn into_operations<E: de::Error>(self) -> Result<Vec<Operation<Engine>>, E> {
+        if self.path_indices.len() != self.operations.len() {
+            return Err(E::custom(format!(
+                "{} path indices for {} operations",
+                self.path_indices.len(),
+                self.operations.len()
+            )));
+        }
+
+        let paths = self.paths;
+        let path = |index: u32| {
+            paths
+                .get(index as usize)
+                .map(|PathWrapper(path)| path.clone())
+                .ok_or_else(|| E::custom(format!("Unknown audit path index {}", index)))
+        };
+        self.operations
+            .into_iter()
+            .zip(self.path_indices)
+            .map(
+                |(OperationWrapper(mut operation), indices)| -> Result<_, E> {
+                    operation.lhs.witness.account_path = path(indices[0])?;
+                    operation.lhs.witness.balance_subtree_path = path(indices[1])?;
+                    operation.rhs.witness.account_path = path(indices[2])?;
+                    operation.rhs.witness.balance_subtree_path = path(indices[3])?;
+                    Ok(operation)
+                },
+            )
+            .collect()
+    }
 }
 
+/// Serializes the operations with the interned audit paths, see `InternedOperations`.
+/// The plain list of operations is accepted as well, so the jobs stored in the legacy
+/// (JSON) format can still be deserialized.
 pub struct VecOperationsSerde;
 
 impl VecOperationsSerde {
@@ -177,21 +291,159 @@ impl VecOperationsSerde {
     where
         S: Serializer,
     {
-        #[derive(Serialize)]
-        struct Wrapper(#[serde(with = "OperationDef")] Operation<Engine>);
-
-        let v = operations.iter().map(|a| Wrapper(a.clone())).collect();
-        Vec::serialize(&v, ser)
+        InternedOperations::new(operations).serialize(ser)
     }
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<Operation<Engine>>, D::Error>
     where
         D: Deserializer<'de>,
     {
-        #[derive(Deserialize)]
-        struct Wrapper(#[serde(with = "OperationDef")] Operation<Engine>);
+        // The format is chosen by the visitor rather than by the untagged enum,
+        // since the latter would buffer the whole witness before parsing it.
+        struct OperationsVisitor;
+
+        impl<'de> Visitor<'de> for OperationsVisitor {
+            type Value = Vec<Operation<Engine>>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a list of operations or interned operations")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut operations = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+                while let Some(OperationWrapper(operation)) = seq.next_element()? {
+                    operations.push(operation);
+                }
+                Ok(operations)
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                InternedOperations::deserialize(de::value::MapAccessDeserializer::new(map))?
+                    .into_operations()
+            }
+        }
+
+        // Binary formats can't tell the list from the map, but they've never used the legacy format.
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(OperationsVisitor)
+        } else {
+            InternedOperations::deserialize(deserializer)?.into_operations()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::witness::{
+        tests::test_utils::{
+            WitnessTestAccount, ZkSyncStateGenerator, BLOCK_TIMESTAMP, FEE_ACCOUNT_ID,
+        },
+        transfer::TransferWitness,
+        utils::SigDataInput,
+        Witness, WitnessBuilder,
+    };
+    use num::BigUint;
+    use zksync_crypto::params::CHUNK_BIT_WIDTH;
+    use zksync_types::{operations::TransferOp, AccountId, BlockNumber, TokenId};
+
+    /// Builds the witness of a block with a transfer, padded with noops.
+    fn block_operations(block_chunks: usize) -> Vec<Operation<Engine>> {
+        let accounts = vec![
+            WitnessTestAccount::new(AccountId(1), 500),
+            WitnessTestAccount::new_empty(AccountId(2)),
+        ];
+        let (from, to) = (&accounts[0], &accounts[1]);
+        let (_, mut tree) = ZkSyncStateGenerator::generate(&accounts);
+        let mut witness_builder =
+            WitnessBuilder::new(&mut tree, FEE_ACCOUNT_ID, BlockNumber(1), BLOCK_TIMESTAMP);
+
+        let transfer_op = TransferOp {
+            tx: from
+                .zksync_account
+                .sign_transfer(
+                    TokenId(0),
+                    "",
+                    BigUint::from(100u32),
+                    BigUint::from(1u32),
+                    &to.account.address,
+                    None,
+                    true,
+                    Default::default(),
+                )
+                .0,
+            from: from.id,
+            to: to.id,
+        };
+        let input = SigDataInput::from_transfer_op(&transfer_op).expect("can't sign transfer");
+        let witness = TransferWitness::apply_tx(&mut witness_builder.account_tree, &transfer_op);
+        witness_builder.add_operation_with_pubdata(
+            witness.calculate_operations(input),
+            witness.get_pubdata(),
+            witness.get_offset_commitment_data(),
+        );
+        witness_builder.extend_pubdata_with_noops(block_chunks);
+        assert_eq!(
+            witness_builder.pubdata.len(),
+            block_chunks * CHUNK_BIT_WIDTH
+        );
+        witness_builder.operations
+    }
+
+    fn serialize_legacy(operations: &[Operation<Engine>]) -> String {
+        let operations: Vec<_> = operations.iter().cloned().map(OperationWrapper).collect();
+        serde_json::to_string(&operations).unwrap()
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Operations(#[serde(with = "VecOperationsSerde")] Vec<Operation<Engine>>);
+
+    fn assert_operations_eq(lhs: &[Operation<Engine>], rhs: &[Operation<Engine>]) {
+        // `Operation` doesn't implement `PartialEq`, so the legacy representations are compared.
+        assert_eq!(serialize_legacy(lhs), serialize_legacy(rhs));
+    }
+
+    #[test]
+    fn interned_operations_roundtrip() {
+        let operations = block_operations(20);
+        let serialized = serde_json::to_string(&Operations(operations.clone())).unwrap();
+        let Operations(deserialized) = serde_json::from_str(&serialized).unwrap();
+        assert_operations_eq(&operations, &deserialized);
+
+        // The deserialized paths are shared as well.
+        assert!(Arc::ptr_eq(
+            &deserialized[18].lhs.witness.account_path,
+            &deserialized[19].rhs.witness.account_path
+        ));
+    }
+
+    #[test]
+    fn legacy_operations_are_accepted() {
+        let operations = block_operations(10);
+        let Operations(deserialized) =
+            serde_json::from_str(&serialize_legacy(&operations)).unwrap();
+        assert_operations_eq(&operations, &deserialized);
+    }
 
-        let v = Vec::deserialize(deserializer)?;
-        Ok(v.into_iter().map(|Wrapper(a)| a).collect())
+    /// Checks that the interned witness is at least twice smaller than the legacy one.
+    #[test]
+    fn interned_operations_size() {
+        let operations = block_operations(100);
+        let legacy_size = serialize_legacy(&operations).len();
+        let interned_size = serde_json::to_string(&Operations(operations))
+            .unwrap()
+            .len();
+        assert!(
+            interned_size * 2 < legacy_size,
+            "legacy {} bytes, interned {} bytes",
+            legacy_size,
+            interned_size
+        );
     }
 }
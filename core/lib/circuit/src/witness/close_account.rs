@@ -158,9 +158,9 @@ impl CloseAccountWitness<Bn256> {
                 token: Some(Fr::zero()),
                 witness: OperationBranchWitness {
                     account_witness: account_witness_before,
-                    account_path: audit_path_before,
+                    account_path: audit_path_before.into(),
                     balance_value: Some(balance_before),
-                    balance_subtree_path: audit_balance_path_before,
+                    balance_subtree_path: audit_balance_path_before.into(),
                 },
             },
             after: OperationBranch {
@@ -168,9 +168,9 @@ impl CloseAccountWitness<Bn256> {
                 token: Some(Fr::zero()),
                 witness: OperationBranchWitness {
                     account_witness: account_witness_after,
-                    account_path: audit_path_after,
+                    account_path: audit_path_after.into(),
                     balance_value: Some(balance_after),
-                    balance_subtree_path: audit_balance_path_after,
+                    balance_subtree_path: audit_balance_path_after.into(),
                 },
             },
             args: OperationArguments {
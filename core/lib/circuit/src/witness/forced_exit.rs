@@ -351,9 +351,9 @@ impl ForcedExitWitness<Bn256> {
                 token: Some(token_fe),
                 witness: OperationBranchWitness {
                     account_witness: account_witness_initiator_before,
-                    account_path: audit_path_initiator_before,
+                    account_path: audit_path_initiator_before.into(),
                     balance_value: Some(balance_initiator_before),
-                    balance_subtree_path: audit_balance_path_initiator_before,
+                    balance_subtree_path: audit_balance_path_initiator_before.into(),
                 },
             },
             initiator_intermediate: OperationBranch {
@@ -361,9 +361,9 @@ impl ForcedExitWitness<Bn256> {
                 token: Some(token_fe),
                 witness: OperationBranchWitness {
                     account_witness: account_witness_initiator_intermediate.clone(),
-                    account_path: audit_path_initiator_intermediate,
+                    account_path: audit_path_initiator_intermediate.into(),
                     balance_value: Some(balance_initiator_intermediate),
-                    balance_subtree_path: audit_balance_path_initiator_intermediate,
+                    balance_subtree_path: audit_balance_path_initiator_intermediate.into(),
                 },
             },
             initiator_after: OperationBranch {
@@ -371,9 +371,9 @@ impl ForcedExitWitness<Bn256> {
                 token: Some(token_fe),
                 witness: OperationBranchWitness {
                     account_witness: account_witness_initiator_intermediate,
-                    account_path: audit_path_initiator_after,
+                    account_path: audit_path_initiator_after.into(),
                     balance_value: Some(balance_initiator_intermediate),
-                    balance_subtree_path: audit_balance_path_initiator_after,
+                    balance_subtree_path: audit_balance_path_initiator_after.into(),
                 },
             },
             target_before: OperationBranch {
@@ -381,9 +381,9 @@ impl ForcedExitWitness<Bn256> {
                 token: Some(token_fe),
                 witness: OperationBranchWitness {
                     account_witness: account_witness_target_intermediate.clone(),
-                    account_path: audit_path_target_before,
+                    account_path: audit_path_target_before.into(),
                     balance_value: Some(balance_target_intermediate),
-                    balance_subtree_path: audit_balance_path_target_before,
+                    balance_subtree_path: audit_balance_path_target_before.into(),
                 },
             },
             target_intermediate: OperationBranch {
@@ -391,9 +391,9 @@ impl ForcedExitWitness<Bn256> {
                 token: Some(token_fe),
                 witness: OperationBranchWitness {
                     account_witness: account_witness_target_intermediate,
-                    account_path: audit_path_target_intermediate,
+                    account_path: audit_path_target_intermediate.into(),
                     balance_value: Some(balance_target_intermediate),
-                    balance_subtree_path: audit_balance_path_target_intermediate,
+                    balance_subtree_path: audit_balance_path_target_intermediate.into(),
                 },
             },
             target_after: OperationBranch {
@@ -401,9 +401,9 @@ impl ForcedExitWitness<Bn256> {
                 token: Some(token_fe),
                 witness: OperationBranchWitness {
                     account_witness: account_witness_target_after,
-                    account_path: audit_path_target_after,
+                    account_path: audit_path_target_after.into(),
                     balance_value: Some(balance_target_after),
-                    balance_subtree_path: audit_balance_path_target_after,
+                    balance_subtree_path: audit_balance_path_target_after.into(),
                 },
             },
             args: OperationArguments {
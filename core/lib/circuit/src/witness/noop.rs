@@ -1,3 +1,5 @@
+// Built-in deps
+use std::sync::Arc;
 // External deps
 use zksync_crypto::franklin_crypto::bellman::pairing::{
     bn256::{Bn256, Fr},
@@ -37,6 +39,8 @@ pub fn noop_operation(tree: &CircuitAccountTree, acc_id: u32) -> Operation<Bn256
         .map(|x| le_bit_vector_into_field_element(&x.to_vec()))
         .collect();
     let (audit_account, audit_balance) = get_audits(tree, acc_id, 0);
+    let audit_account: Arc<[_]> = audit_account.into();
+    let audit_balance: Arc<[_]> = audit_balance.into();
 
     Operation {
         new_root: Some(tree.root_hash()),
@@ -358,9 +358,9 @@ impl TransferToNewWitness<Bn256> {
                 token: Some(token_fe),
                 witness: OperationBranchWitness {
                     account_witness: account_witness_from_before,
-                    account_path: audit_path_from_before,
+                    account_path: audit_path_from_before.into(),
                     balance_value: Some(balance_from_before),
-                    balance_subtree_path: audit_balance_path_from_before,
+                    balance_subtree_path: audit_balance_path_from_before.into(),
                 },
             },
             from_intermediate: OperationBranch {
@@ -368,9 +368,9 @@ impl TransferToNewWitness<Bn256> {
                 token: Some(token_fe),
                 witness: OperationBranchWitness {
                     account_witness: account_witness_from_intermediate.clone(),
-                    account_path: audit_path_from_intermediate,
+                    account_path: audit_path_from_intermediate.into(),
                     balance_value: Some(balance_from_intermediate),
-                    balance_subtree_path: audit_balance_path_from_intermediate,
+                    balance_subtree_path: audit_balance_path_from_intermediate.into(),
                 },
             },
             from_after: OperationBranch {
@@ -378,9 +378,9 @@ impl TransferToNewWitness<Bn256> {
                 token: Some(token_fe),
                 witness: OperationBranchWitness {
                     account_witness: account_witness_from_intermediate,
-                    account_path: audit_path_from_after,
+                    account_path: audit_path_from_after.into(),
                     balance_value: Some(balance_from_intermediate),
-                    balance_subtree_path: audit_balance_path_from_after,
+                    balance_subtree_path: audit_balance_path_from_after.into(),
                 },
             },
             to_before: OperationBranch {
@@ -388,9 +388,9 @@ impl TransferToNewWitness<Bn256> {
                 token: Some(token_fe),
                 witness: OperationBranchWitness {
                     account_witness: account_witness_to_intermediate.clone(),
-                    account_path: audit_path_to_before,
+                    account_path: audit_path_to_before.into(),
                     balance_value: Some(balance_to_intermediate),
-                    balance_subtree_path: audit_balance_path_to_before,
+                    balance_subtree_path: audit_balance_path_to_before.into(),
                 },
             },
             to_intermediate: OperationBranch {
@@ -398,9 +398,9 @@ impl TransferToNewWitness<Bn256> {
                 token: Some(token_fe),
                 witness: OperationBranchWitness {
                     account_witness: account_witness_to_intermediate,
-                    account_path: audit_path_to_intermediate,
+                    account_path: audit_path_to_intermediate.into(),
                     balance_value: Some(balance_to_intermediate),
-                    balance_subtree_path: audit_balance_path_to_intermediate,
+                    balance_subtree_path: audit_balance_path_to_intermediate.into(),
                 },
             },
             to_after: OperationBranch {
@@ -408,9 +408,9 @@ impl TransferToNewWitness<Bn256> {
                 token: Some(token_fe),
                 witness: OperationBranchWitness {
                     account_witness: account_witness_to_after,
-                    account_path: audit_path_to_after,
+                    account_path: audit_path_to_after.into(),
                     balance_value: Some(balance_to_after),
-                    balance_subtree_path: audit_balance_path_to_after,
+                    balance_subtree_path: audit_balance_path_to_after.into(),
                 },
             },
             args: OperationArguments {
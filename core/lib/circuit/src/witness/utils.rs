@@ -111,11 +111,10 @@ impl<'a> WitnessBuilder<'a> {
         let chunks_remaining = block_size_chunks
             .checked_sub(chunks_used)
             .expect("failed to get number of noops");
+        // Noops don't change the tree, so a single operation (and its audit paths) is shared.
+        let noop = crate::witness::noop::noop_operation(&self.account_tree, *self.fee_account_id);
         for _ in 0..chunks_remaining {
-            self.operations.push(crate::witness::noop::noop_operation(
-                &self.account_tree,
-                *self.fee_account_id,
-            ));
+            self.operations.push(noop.clone());
             self.pubdata.extend(vec![false; CHUNK_BIT_WIDTH]);
             self.offset_commitment.extend(vec![false; 8])
         }
@@ -3,7 +3,7 @@ use serde::Deserialize;
 /// Built-in uses
 use std::net::SocketAddr;
 // Workspace uses
-use zksync_types::AccountId;
+use zksync_types::{address_checksum::ChecksumStrictness, AccountId, Address, H256};
 // Local uses
 use crate::envy_load;
 
@@ -54,6 +54,34 @@ pub struct Common {
 
     pub max_number_of_transactions_per_batch: u64,
     pub max_number_of_authors_per_batch: u64,
+    /// Tokens which contracts are checked for paused transfers and blacklisted recipients
+    /// before accepting a withdrawal, so the funds don't get stuck in the zkSync contract.
+    pub withdrawal_checked_tokens: Vec<Address>,
+    /// Period during which the idempotency keys supplied with the submitted transactions
+    /// are remembered.
+    pub idempotency_key_ttl_hours: u64,
+    /// Whether the state shared by the API server replicas (e.g. the paid fee subsidies and
+    /// the submission rate limits) is kept in Redis or in the database rather than in memory.
+    pub stateless: bool,
+    /// URL of the Redis server keeping the counters shared by the replicas in the stateless mode
+    /// (e.g. the rate limits), empty if the counters are kept in the database.
+    pub shared_counters_redis_url: String,
+    /// Max number of transactions an account can submit per minute, 0 means no limit.
+    pub max_txs_per_account_per_minute: u64,
+    /// How strictly the EIP-55 checksums of the addresses passed to the API are checked.
+    pub address_checksum: ChecksumStrictness,
+}
+
+impl Common {
+    /// Returns the URL of the Redis server keeping the shared counters, if it's configured.
+    pub fn shared_counters_redis_url(&self) -> Option<&str> {
+        Some(self.shared_counters_redis_url.as_str()).filter(|url| !url.is_empty())
+    }
+
+    /// Converts `self.idempotency_key_ttl_hours` into `chrono::Duration`
+    pub fn idempotency_key_ttl(&self) -> chrono::Duration {
+        chrono::Duration::hours(self.idempotency_key_ttl_hours as i64)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -78,6 +106,12 @@ pub struct ProverApi {
     pub port: u16,
     /// URL to access API server.
     pub url: String,
+    /// Whether the gRPC interface of the prover server is served.
+    pub grpc_enabled: bool,
+    /// Port to which the gRPC interface of the prover server is listening.
+    pub grpc_port: u16,
+    /// URL to access the gRPC interface of the prover server.
+    pub grpc_url: String,
     /// Secret used to generate access token (JWT).
     pub secret_auth: String,
 }
@@ -86,6 +120,10 @@ impl ProverApi {
     pub fn bind_addr(&self) -> SocketAddr {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.port)
     }
+
+    pub fn grpc_bind_addr(&self) -> SocketAddr {
+        SocketAddr::new("0.0.0.0".parse().unwrap(), self.grpc_port)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -108,12 +146,51 @@ pub struct RestApi {
     pub port: u16,
     /// URL to access API server.
     pub url: String,
+    /// Origins allowed to access the public API (`v0.1`, `v0.2` and `v1`) from the browser.
+    /// `*` allows any origin.
+    pub cors_allowed_origins: Vec<String>,
+    /// Origins allowed to access the forced exit requests API from the browser.
+    /// `*` allows any origin.
+    pub forced_exit_cors_allowed_origins: Vec<String>,
+    /// Whether the balance and transaction status responses are signed by the operator.
+    pub sign_responses: bool,
+    /// Ethereum private key the responses are signed with.
+    pub response_signing_key: H256,
+    /// Whether the accounts can register the aliases to receive funds by the short names.
+    pub aliases_enabled: bool,
+    /// Whether the accounts can register the guardians to recover the access to the account.
+    pub guardians_enabled: bool,
+    /// Whether the snapshots of the state are served to bootstrap the new server instances.
+    pub snapshots_enabled: bool,
+    /// Secret for the authorization tokens (JWT) of the snapshot requests.
+    pub snapshots_secret_auth: String,
+    /// Time the idle connections are kept alive for, in seconds. 0 disables the keep-alive.
+    pub keep_alive_secs: u64,
+    /// Time the client has to send the request headers within, in milliseconds.
+    pub client_timeout_ms: u64,
+    /// Max number of the concurrent connections per server worker.
+    pub max_connections: usize,
+    /// Max number of the concurrent TLS handshakes per server worker.
+    pub max_connection_rate: usize,
+    /// Max number of the pending connections awaiting to be accepted.
+    pub backlog: u32,
+    /// Path to the PEM-encoded TLS certificate chain, empty if the API is served over plain HTTP.
+    /// HTTP/2 is only available over TLS.
+    pub tls_cert_path: String,
+    /// Path to the PEM-encoded TLS private key.
+    pub tls_key_path: String,
 }
 
 impl RestApi {
     pub fn bind_addr(&self) -> SocketAddr {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.port)
     }
+
+    /// Returns the paths to the TLS certificate chain and private key, if they're configured.
+    pub fn tls_files(&self) -> Option<(&str, &str)> {
+        Some((self.tls_cert_path.as_str(), self.tls_key_path.as_str()))
+            .filter(|(cert, key)| !cert.is_empty() && !key.is_empty())
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -122,6 +199,8 @@ pub struct JsonRpc {
     pub http_port: u16,
     /// URL to access HTTP RPC server.
     pub http_url: String,
+    /// Whether the connections to the HTTP RPC server are kept alive between the requests.
+    pub http_keep_alive: bool,
     /// Port to which the WebSocket RPC server is listening.
     pub ws_port: u16,
     /// URL to access WebSocket RPC server.
@@ -147,7 +226,7 @@ pub struct Prometheus {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::configs::test_utils::set_env;
+    use crate::configs::test_utils::{hash, set_env};
     use std::net::IpAddr;
 
     fn expected_config() -> ApiConfig {
@@ -159,6 +238,14 @@ mod tests {
                 max_number_of_transactions_per_batch: 200,
                 max_number_of_authors_per_batch: 10,
                 fee_free_accounts: vec![AccountId(4078), AccountId(387)],
+                withdrawal_checked_tokens: vec!["a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"
+                    .parse()
+                    .unwrap()],
+                idempotency_key_ttl_hours: 24,
+                stateless: true,
+                shared_counters_redis_url: "redis://127.0.0.1:6379".into(),
+                max_txs_per_account_per_minute: 60,
+                address_checksum: ChecksumStrictness::Strict,
             },
             admin: AdminApi {
                 port: 8080,
@@ -168,10 +255,31 @@ mod tests {
             rest: RestApi {
                 port: 3001,
                 url: "http://127.0.0.1:3001".into(),
+                cors_allowed_origins: vec!["*".into()],
+                forced_exit_cors_allowed_origins: vec![
+                    "https://wallet.zksync.io".into(),
+                    "https://rinkeby.zksync.io".into(),
+                ],
+                sign_responses: true,
+                response_signing_key: hash(
+                    "0x27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be",
+                ),
+                aliases_enabled: true,
+                guardians_enabled: true,
+                snapshots_enabled: false,
+                snapshots_secret_auth: "sample".into(),
+                keep_alive_secs: 75,
+                client_timeout_ms: 5000,
+                max_connections: 25000,
+                max_connection_rate: 256,
+                backlog: 2048,
+                tls_cert_path: String::new(),
+                tls_key_path: String::new(),
             },
             json_rpc: JsonRpc {
                 http_port: 3030,
                 http_url: "http://127.0.0.1:3030".into(),
+                http_keep_alive: true,
                 ws_port: 3031,
                 ws_url: "ws://127.0.0.1:3031".into(),
             },
@@ -182,6 +290,9 @@ mod tests {
             prover: ProverApi {
                 port: 8088,
                 url: "http://127.0.0.1:8088".into(),
+                grpc_enabled: true,
+                grpc_port: 8089,
+                grpc_url: "http://127.0.0.1:8089".into(),
                 secret_auth: "sample".into(),
             },
             prometheus: Prometheus { port: 3312 },
@@ -197,19 +308,44 @@ API_COMMON_FEE_FREE_ACCOUNTS=4078,387
 API_COMMON_ENFORCE_PUBKEY_CHANGE_FEE=true
 API_COMMON_MAX_NUMBER_OF_TRANSACTIONS_PER_BATCH=200
 API_COMMON_MAX_NUMBER_OF_AUTHORS_PER_BATCH=10
+API_COMMON_WITHDRAWAL_CHECKED_TOKENS="0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"
+API_COMMON_IDEMPOTENCY_KEY_TTL_HOURS=24
+API_COMMON_STATELESS=true
+API_COMMON_SHARED_COUNTERS_REDIS_URL="redis://127.0.0.1:6379"
+API_COMMON_MAX_TXS_PER_ACCOUNT_PER_MINUTE=60
+API_COMMON_ADDRESS_CHECKSUM="strict"
 API_ADMIN_PORT="8080"
 API_ADMIN_URL="http://127.0.0.1:8080"
 API_ADMIN_SECRET_AUTH="sample"
 API_REST_PORT="3001"
 API_REST_URL="http://127.0.0.1:3001"
+API_REST_CORS_ALLOWED_ORIGINS="*"
+API_REST_FORCED_EXIT_CORS_ALLOWED_ORIGINS="https://wallet.zksync.io,https://rinkeby.zksync.io"
+API_REST_SIGN_RESPONSES="true"
+API_REST_RESPONSE_SIGNING_KEY="0x27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be"
+API_REST_ALIASES_ENABLED="true"
+API_REST_GUARDIANS_ENABLED="true"
+API_REST_SNAPSHOTS_ENABLED="false"
+API_REST_SNAPSHOTS_SECRET_AUTH="sample"
+API_REST_KEEP_ALIVE_SECS="75"
+API_REST_CLIENT_TIMEOUT_MS="5000"
+API_REST_MAX_CONNECTIONS="25000"
+API_REST_MAX_CONNECTION_RATE="256"
+API_REST_BACKLOG="2048"
+API_REST_TLS_CERT_PATH=""
+API_REST_TLS_KEY_PATH=""
 API_JSON_RPC_HTTP_PORT="3030"
 API_JSON_RPC_HTTP_URL="http://127.0.0.1:3030"
+API_JSON_RPC_HTTP_KEEP_ALIVE="true"
 API_JSON_RPC_WS_PORT="3031"
 API_JSON_RPC_WS_URL="ws://127.0.0.1:3031"
 API_PRIVATE_PORT="8090"
 API_PRIVATE_URL="http://127.0.0.1:8090"
 API_PROVER_PORT="8088"
 API_PROVER_URL="http://127.0.0.1:8088"
+API_PROVER_GRPC_ENABLED="true"
+API_PROVER_GRPC_PORT="8089"
+API_PROVER_GRPC_URL="http://127.0.0.1:8089"
 API_PROVER_SECRET_AUTH="sample"
 API_PROMETHEUS_PORT="3312"
         "#;
@@ -233,10 +369,15 @@ API_PROMETHEUS_PORT="3312"
             config.prover.bind_addr(),
             SocketAddr::new(bind_broadcast_addr, config.prover.port)
         );
+        assert_eq!(
+            config.prover.grpc_bind_addr(),
+            SocketAddr::new(bind_broadcast_addr, config.prover.grpc_port)
+        );
         assert_eq!(
             config.rest.bind_addr(),
             SocketAddr::new(bind_broadcast_addr, config.rest.port)
         );
+        assert_eq!(config.rest.tls_files(), None);
         assert_eq!(
             config.private.bind_addr(),
             SocketAddr::new(bind_broadcast_addr, config.private.port)
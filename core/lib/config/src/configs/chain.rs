@@ -4,6 +4,8 @@ use serde::Deserialize;
 use std::time::Duration;
 // Local uses
 use zksync_crypto::{convert::FeConvert, priv_key_from_fs, Fs, PrivateKey};
+use zksync_types::amount_bounds::{AmountBounds, InvalidAmountBound};
+use zksync_types::fee_sweep::{InvalidSweepThreshold, SweepThresholds};
 use zksync_types::network::Network;
 use zksync_types::Address;
 
@@ -17,6 +19,14 @@ pub struct ChainConfig {
     pub eth: Eth,
     /// State keeper / block generating configuration.
     pub state_keeper: StateKeeper,
+    /// Mempool configuration.
+    pub mempool: Mempool,
+    /// Throttling of the new transactions when the blocks processing lags behind.
+    pub backpressure: Backpressure,
+    /// Sweeps of the collected fees to the treasury.
+    pub fee_sweep: FeeSweep,
+    /// Refunds of the fees paid over the actual fee.
+    pub fee_refund: FeeRefund,
 }
 
 impl ChainConfig {
@@ -25,6 +35,10 @@ impl ChainConfig {
             circuit: envy_load!("circuit", "CHAIN_CIRCUIT_"),
             eth: envy_load!("eth", "CHAIN_ETH_"),
             state_keeper: envy_load!("state_keeper", "CHAIN_STATE_KEEPER_"),
+            mempool: envy_load!("mempool", "CHAIN_MEMPOOL_"),
+            backpressure: envy_load!("backpressure", "CHAIN_BACKPRESSURE_"),
+            fee_sweep: envy_load!("fee_sweep", "CHAIN_FEE_SWEEP_"),
+            fee_refund: envy_load!("fee_refund", "CHAIN_FEE_REFUND_"),
         }
     }
 }
@@ -81,6 +95,18 @@ pub struct StateKeeper {
     pub miniblock_iterations: u64,
     /// Maximum amount of miniblock iterations in case of block containing a fast withdrawal request.
     pub fast_block_miniblock_iterations: u64,
+    /// Whether the size of each block is chosen based on the load instead of always using
+    /// the largest of `block_chunk_sizes`. Smaller blocks are faster to prove.
+    pub adaptive_block_size: bool,
+    /// Whether the miniblock iterations limit is adjusted to keep the average interval between
+    /// the blocks at `target_block_time`. `miniblock_iterations` is used as the initial limit then.
+    pub adaptive_block_time: bool,
+    /// Target average interval between the sealed blocks, in milliseconds.
+    pub target_block_time: u64,
+    /// Lower bound of the adjusted miniblock iterations limit.
+    pub min_miniblock_iterations: u64,
+    /// Upper bound of the adjusted miniblock iterations limit.
+    pub max_miniblock_iterations: u64,
     pub fee_account_addr: Address,
     pub aggregated_proof_sizes: Vec<usize>,
     pub max_aggregated_blocks_to_commit: usize,
@@ -100,6 +126,11 @@ impl StateKeeper {
         Duration::from_millis(self.miniblock_iteration_interval)
     }
 
+    /// Converts `self.target_block_time` into `Duration`.
+    pub fn target_block_time(&self) -> Duration {
+        Duration::from_millis(self.target_block_time)
+    }
+
     pub fn block_commit_deadline(&self) -> Duration {
         Duration::from_secs(self.block_commit_deadline)
     }
@@ -124,6 +155,134 @@ impl StateKeeper {
     }
 }
 
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct Mempool {
+    /// Interval between the consistency checks of the mempool against the database, in seconds.
+    pub consistency_check_interval: u64,
+    /// Whether divergences found by the consistency check should be corrected automatically.
+    /// If not set, divergences are only reported.
+    pub consistency_auto_correct: bool,
+    /// Max amount of nonces an account can reserve at once.
+    pub max_nonce_reservation_size: u32,
+    /// Period after which the nonce reservation expires, in seconds.
+    pub nonce_reservation_ttl: u64,
+    /// Period after which the transactions which were not executed expire, in seconds.
+    /// Transactions expire earlier if their `valid_until` timestamp passes.
+    pub tx_ttl: u64,
+    /// Interval between the checks for the expired transactions, in seconds.
+    pub tx_expiry_check_interval: u64,
+    /// Max amount of `ChangePubKey` transactions an account can make within the window.
+    /// The limit is not applied if it's zero.
+    pub change_pubkey_limit: u32,
+    /// Window of the `ChangePubKey` rate limit, in seconds.
+    pub change_pubkey_limit_window: u64,
+    /// Accounts the `ChangePubKey` rate limit is not applied to.
+    pub change_pubkey_limit_exempt_accounts: Vec<Address>,
+    /// Sanity bounds of the amounts and fees as `token_id:max_amount:max_fee` entries.
+    /// Transactions exceeding them are rejected, tokens without the bounds are not checked.
+    pub amount_bounds: Vec<String>,
+    /// Max amount of the transactions of a single account proposed for a block.
+    /// The limit is not applied if it's zero.
+    pub max_account_txs_per_block: u32,
+}
+
+impl Mempool {
+    /// Converts `self.consistency_check_interval` into `Duration`.
+    pub fn consistency_check_interval(&self) -> Duration {
+        Duration::from_secs(self.consistency_check_interval)
+    }
+
+    /// Converts `self.nonce_reservation_ttl` into `Duration`.
+    pub fn nonce_reservation_ttl(&self) -> Duration {
+        Duration::from_secs(self.nonce_reservation_ttl)
+    }
+
+    /// Converts `self.tx_ttl` into `Duration`.
+    pub fn tx_ttl(&self) -> Duration {
+        Duration::from_secs(self.tx_ttl)
+    }
+
+    /// Converts `self.tx_expiry_check_interval` into `Duration`.
+    pub fn tx_expiry_check_interval(&self) -> Duration {
+        Duration::from_secs(self.tx_expiry_check_interval)
+    }
+
+    /// Converts `self.change_pubkey_limit_window` into `Duration`.
+    pub fn change_pubkey_limit_window(&self) -> Duration {
+        Duration::from_secs(self.change_pubkey_limit_window)
+    }
+
+    /// Parses `self.amount_bounds`.
+    pub fn amount_bounds(&self) -> Result<AmountBounds, InvalidAmountBound> {
+        AmountBounds::parse(&self.amount_bounds)
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct Backpressure {
+    /// Whether the new transactions are rejected while the blocks processing lags behind.
+    pub enabled: bool,
+    /// Interval between the checks of the blocks processing backlog, in seconds.
+    pub check_interval: u64,
+    /// Max amount of the created blocks which are not yet committed on L1.
+    pub max_uncommitted_blocks: u32,
+    /// Max amount of the prover jobs which are not completed yet.
+    pub max_pending_prover_jobs: u32,
+    /// Time the clients are asked to wait before resubmitting the rejected transactions, in seconds.
+    pub retry_after: u64,
+}
+
+impl Backpressure {
+    /// Converts `self.check_interval` into `Duration`.
+    pub fn check_interval(&self) -> Duration {
+        Duration::from_secs(self.check_interval)
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct FeeSweep {
+    /// Whether the collected fees are swept from the fee account (`state_keeper.fee_account_addr`)
+    /// to the treasury.
+    pub enabled: bool,
+    /// Interval between the checks of the fee account balances, in seconds.
+    pub interval: u64,
+    /// Address the fees are swept to.
+    pub treasury_address: Address,
+    /// Whether the fees are withdrawn to the treasury address on L1 instead of being transferred on L2.
+    pub withdraw: bool,
+    /// Balances the tokens are swept at as `token_id:threshold` entries.
+    /// Tokens without the threshold are not swept.
+    pub thresholds: Vec<String>,
+    /// zkSync private key of the fee account. Its public key hash must be set for the fee account.
+    pub fee_account_private_key: String,
+}
+
+impl FeeSweep {
+    /// Converts `self.interval` into `Duration`.
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval)
+    }
+
+    /// Parses `self.thresholds`.
+    pub fn thresholds(&self) -> Result<SweepThresholds, InvalidSweepThreshold> {
+        SweepThresholds::parse(&self.thresholds)
+    }
+
+    pub fn fee_account_private_key(&self) -> PrivateKey {
+        let fs = Fs::from_hex(&self.fee_account_private_key)
+            .expect("failed to parse fee account private key");
+        priv_key_from_fs(fs)
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct FeeRefund {
+    /// Whether the part of the signed fee exceeding the fee computed on the transaction submission
+    /// is refunded to the sender once the transaction is executed. Refunds are transferred from
+    /// the fee account with the key and at the interval of `fee_sweep`.
+    pub enabled: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,6 +307,11 @@ mod tests {
                 miniblock_iteration_interval: 200,
                 miniblock_iterations: 10,
                 fast_block_miniblock_iterations: 5,
+                adaptive_block_size: true,
+                adaptive_block_time: true,
+                target_block_time: 2000,
+                min_miniblock_iterations: 2,
+                max_miniblock_iterations: 50,
                 fee_account_addr: addr("de03a0B5963f75f1C8485B355fF6D30f3093BDE7"),
                 aggregated_proof_sizes: vec![1, 5],
                 max_aggregated_blocks_to_commit: 3,
@@ -160,6 +324,37 @@ mod tests {
                 last_tx_signer_private_key: "0xaabbeecc".into(),
                 last_tx_signer_address: addr("da03a0b5963f75f1c8485b355ff6d30f3093bde7"),
             },
+            mempool: Mempool {
+                consistency_check_interval: 300,
+                consistency_auto_correct: false,
+                max_nonce_reservation_size: 100,
+                nonce_reservation_ttl: 600,
+                tx_ttl: 86400,
+                tx_expiry_check_interval: 60,
+                change_pubkey_limit: 5,
+                change_pubkey_limit_window: 86400,
+                change_pubkey_limit_exempt_accounts: vec![addr(
+                    "de03a0B5963f75f1C8485B355fF6D30f3093BDE7",
+                )],
+                amount_bounds: vec!["0:1000000000000000000000000:1000000000000000000".into()],
+                max_account_txs_per_block: 20,
+            },
+            backpressure: Backpressure {
+                enabled: true,
+                check_interval: 10,
+                max_uncommitted_blocks: 100,
+                max_pending_prover_jobs: 200,
+                retry_after: 30,
+            },
+            fee_sweep: FeeSweep {
+                enabled: true,
+                interval: 3600,
+                treasury_address: addr("da03a0b5963f75f1c8485b355ff6d30f3093bde7"),
+                withdraw: false,
+                thresholds: vec!["0:1000000000000000000".into()],
+                fee_account_private_key: "0xaabbeecc".into(),
+            },
+            fee_refund: FeeRefund { enabled: true },
         }
     }
 
@@ -179,6 +374,11 @@ CHAIN_STATE_KEEPER_BLOCK_CHUNK_SIZES="6,30"
 CHAIN_STATE_KEEPER_MINIBLOCK_ITERATION_INTERVAL="200"
 CHAIN_STATE_KEEPER_MINIBLOCK_ITERATIONS="10"
 CHAIN_STATE_KEEPER_FAST_BLOCK_MINIBLOCK_ITERATIONS="5"
+CHAIN_STATE_KEEPER_ADAPTIVE_BLOCK_SIZE="true"
+CHAIN_STATE_KEEPER_ADAPTIVE_BLOCK_TIME="true"
+CHAIN_STATE_KEEPER_TARGET_BLOCK_TIME="2000"
+CHAIN_STATE_KEEPER_MIN_MINIBLOCK_ITERATIONS="2"
+CHAIN_STATE_KEEPER_MAX_MINIBLOCK_ITERATIONS="50"
 CHAIN_STATE_KEEPER_FEE_ACCOUNT_ADDR="0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
 CHAIN_STATE_KEEPER_AGGREGATED_PROOF_SIZES="1,5"
 CHAIN_STATE_KEEPER_MAX_AGGREGATED_BLOCKS_TO_COMMIT="3"
@@ -190,6 +390,29 @@ CHAIN_STATE_KEEPER_MAX_AGGREGATED_TX_GAS="4000000"
 CHAIN_STATE_KEEPER_LAST_TX_SIGNER_USED="false"
 CHAIN_STATE_KEEPER_LAST_TX_SIGNER_ADDRESS="0xda03a0b5963f75f1c8485b355ff6d30f3093bde7"
 CHAIN_STATE_KEEPER_LAST_TX_SIGNER_PRIVATE_KEY="0xaabbeecc"
+CHAIN_MEMPOOL_CONSISTENCY_CHECK_INTERVAL="300"
+CHAIN_MEMPOOL_CONSISTENCY_AUTO_CORRECT="false"
+CHAIN_MEMPOOL_MAX_NONCE_RESERVATION_SIZE="100"
+CHAIN_MEMPOOL_NONCE_RESERVATION_TTL="600"
+CHAIN_MEMPOOL_TX_TTL="86400"
+CHAIN_MEMPOOL_TX_EXPIRY_CHECK_INTERVAL="60"
+CHAIN_MEMPOOL_CHANGE_PUBKEY_LIMIT="5"
+CHAIN_MEMPOOL_CHANGE_PUBKEY_LIMIT_WINDOW="86400"
+CHAIN_MEMPOOL_CHANGE_PUBKEY_LIMIT_EXEMPT_ACCOUNTS="0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
+CHAIN_MEMPOOL_AMOUNT_BOUNDS="0:1000000000000000000000000:1000000000000000000"
+CHAIN_MEMPOOL_MAX_ACCOUNT_TXS_PER_BLOCK="20"
+CHAIN_BACKPRESSURE_ENABLED="true"
+CHAIN_BACKPRESSURE_CHECK_INTERVAL="10"
+CHAIN_BACKPRESSURE_MAX_UNCOMMITTED_BLOCKS="100"
+CHAIN_BACKPRESSURE_MAX_PENDING_PROVER_JOBS="200"
+CHAIN_BACKPRESSURE_RETRY_AFTER="30"
+CHAIN_FEE_SWEEP_ENABLED="true"
+CHAIN_FEE_SWEEP_INTERVAL="3600"
+CHAIN_FEE_SWEEP_TREASURY_ADDRESS="0xda03a0b5963f75f1c8485b355ff6d30f3093bde7"
+CHAIN_FEE_SWEEP_WITHDRAW="false"
+CHAIN_FEE_SWEEP_THRESHOLDS="0:1000000000000000000"
+CHAIN_FEE_SWEEP_FEE_ACCOUNT_PRIVATE_KEY="0xaabbeecc"
+CHAIN_FEE_REFUND_ENABLED="true"
         "#;
         set_env(config);
 
@@ -206,5 +429,29 @@ CHAIN_STATE_KEEPER_LAST_TX_SIGNER_PRIVATE_KEY="0xaabbeecc"
             config.state_keeper.miniblock_iteration_interval(),
             Duration::from_millis(config.state_keeper.miniblock_iteration_interval)
         );
+        assert_eq!(
+            config.mempool.consistency_check_interval(),
+            Duration::from_secs(config.mempool.consistency_check_interval)
+        );
+        assert_eq!(
+            config.mempool.nonce_reservation_ttl(),
+            Duration::from_secs(config.mempool.nonce_reservation_ttl)
+        );
+        assert_eq!(
+            config.mempool.tx_ttl(),
+            Duration::from_secs(config.mempool.tx_ttl)
+        );
+        assert_eq!(
+            config.mempool.change_pubkey_limit_window(),
+            Duration::from_secs(config.mempool.change_pubkey_limit_window)
+        );
+        assert_eq!(
+            config.backpressure.check_interval(),
+            Duration::from_secs(config.backpressure.check_interval)
+        );
+        assert_eq!(
+            config.fee_sweep.interval(),
+            Duration::from_secs(config.fee_sweep.interval)
+        );
     }
 }
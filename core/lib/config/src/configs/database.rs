@@ -7,6 +7,14 @@ use serde::Deserialize;
 // Local uses
 use crate::envy_load;
 
+/// Storage of the account tree caches.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TreeCacheBackend {
+    Postgres,
+    RocksDb,
+}
+
 /// Used database configuration.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct DBConfig {
@@ -18,6 +26,14 @@ pub struct DBConfig {
     pub rejected_transactions_max_age: u64,
     /// Sleep time (in hours) of the actor responsible for deleting failed transactions from the database.
     pub rejected_transactions_cleaner_interval: u64,
+    /// Storage of the account tree caches.
+    pub tree_cache_backend: TreeCacheBackend,
+    /// Directory of the embedded RocksDB database with the account tree caches.
+    pub tree_cache_path: String,
+    /// Size of the RocksDB block cache, in megabytes.
+    pub tree_cache_size_mb: usize,
+    /// Amount of the latest account tree caches kept in the storage, the older ones are removed.
+    pub tree_cache_keep_blocks: usize,
 }
 
 impl DBConfig {
@@ -47,6 +63,10 @@ mod tests {
             url: "postgres://postgres@localhost/plasma".into(),
             rejected_transactions_max_age: 336,
             rejected_transactions_cleaner_interval: 24,
+            tree_cache_backend: TreeCacheBackend::RocksDb,
+            tree_cache_path: "./db/tree_cache".into(),
+            tree_cache_size_mb: 256,
+            tree_cache_keep_blocks: 16,
         }
     }
 
@@ -57,6 +77,10 @@ DATABASE_POOL_SIZE="10"
 DATABASE_URL="postgres://postgres@localhost/plasma"
 DATABASE_REJECTED_TRANSACTIONS_MAX_AGE="336"
 DATABASE_REJECTED_TRANSACTIONS_CLEANER_INTERVAL="24"
+DATABASE_TREE_CACHE_BACKEND="rocksdb"
+DATABASE_TREE_CACHE_PATH="./db/tree_cache"
+DATABASE_TREE_CACHE_SIZE_MB="256"
+DATABASE_TREE_CACHE_KEEP_BLOCKS="16"
         "#;
         set_env(config);
 
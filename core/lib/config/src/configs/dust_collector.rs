@@ -0,0 +1,69 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use serde::Deserialize;
+// Local uses
+use crate::envy_load;
+
+/// Configuration of the dust accounts collector.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct DustCollectorConfig {
+    /// Whether the dust collector is enabled.
+    pub enabled: bool,
+    /// Accounts with the total value of balances below this threshold (in USD) are considered dust.
+    pub threshold_usd: f64,
+    /// Accounts not updated for this period are considered inactive. Value in days.
+    pub inactivity_period: u64,
+    /// How often the dust accounts are looked for. Value in seconds.
+    pub collection_interval: u64,
+    /// Max amount of the accounts collected within one iteration.
+    pub max_accounts_per_iteration: u32,
+}
+
+impl DustCollectorConfig {
+    pub fn from_env() -> Self {
+        envy_load!("dust_collector", "DUST_COLLECTOR_")
+    }
+
+    /// Converts `self.inactivity_period` into `chrono::Duration`
+    pub fn inactivity_period(&self) -> chrono::Duration {
+        chrono::Duration::days(self.inactivity_period as i64)
+    }
+
+    /// Converts `self.collection_interval` into `Duration`
+    pub fn collection_interval(&self) -> Duration {
+        Duration::from_secs(self.collection_interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> DustCollectorConfig {
+        DustCollectorConfig {
+            enabled: true,
+            threshold_usd: 0.5,
+            inactivity_period: 180,
+            collection_interval: 3600,
+            max_accounts_per_iteration: 50,
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+DUST_COLLECTOR_ENABLED="true"
+DUST_COLLECTOR_THRESHOLD_USD="0.5"
+DUST_COLLECTOR_INACTIVITY_PERIOD="180"
+DUST_COLLECTOR_COLLECTION_INTERVAL="3600"
+DUST_COLLECTOR_MAX_ACCOUNTS_PER_ITERATION="50"
+        "#;
+        set_env(config);
+
+        let actual = DustCollectorConfig::from_env();
+        assert_eq!(actual, expected_config());
+        assert_eq!(actual.inactivity_period(), chrono::Duration::days(180));
+    }
+}
@@ -1,3 +1,5 @@
+// Built-in uses
+use std::time::Duration;
 // External uses
 use serde::Deserialize;
 // Local uses
@@ -14,6 +16,13 @@ pub struct ETHClientConfig {
     pub gas_price_factor: f64,
     /// Address of the Ethereum node API.
     pub web3_url: Vec<String>,
+    /// Timeout for a single request to the Ethereum node, in seconds.
+    pub request_timeout_secs: u64,
+    /// Maximum amount of retries for the failed request to the Ethereum node.
+    pub max_request_retries: u32,
+    /// Base delay between the retries of the failed request, in milliseconds.
+    /// Actual delay grows exponentially with each attempt and is randomized.
+    pub retry_base_delay_ms: u64,
 }
 
 impl ETHClientConfig {
@@ -28,6 +37,16 @@ impl ETHClientConfig {
             .cloned()
             .expect("Should be at least one")
     }
+
+    /// Converts `self.request_timeout_secs` into `Duration`.
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_secs(self.request_timeout_secs)
+    }
+
+    /// Converts `self.retry_base_delay_ms` into `Duration`.
+    pub fn retry_base_delay(&self) -> Duration {
+        Duration::from_millis(self.retry_base_delay_ms)
+    }
 }
 
 #[cfg(test)]
@@ -43,6 +62,9 @@ mod tests {
                 "http://127.0.0.1:8545".into(),
                 "http://127.0.0.1:8546".into(),
             ],
+            request_timeout_secs: 30,
+            max_request_retries: 3,
+            retry_base_delay_ms: 500,
         }
     }
 
@@ -52,6 +74,9 @@ mod tests {
 ETH_CLIENT_CHAIN_ID="9"
 ETH_CLIENT_GAS_PRICE_FACTOR="1"
 ETH_CLIENT_WEB3_URL="http://127.0.0.1:8545,http://127.0.0.1:8546"
+ETH_CLIENT_REQUEST_TIMEOUT_SECS="30"
+ETH_CLIENT_MAX_REQUEST_RETRIES="3"
+ETH_CLIENT_RETRY_BASE_DELAY_MS="500"
         "#;
         set_env(config);
 
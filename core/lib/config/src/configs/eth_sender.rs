@@ -14,6 +14,8 @@ pub struct ETHSenderConfig {
     pub sender: Sender,
     /// Options related to the `gas_adjuster` submodule.
     pub gas_price_limit: GasLimit,
+    /// Options of the shadow network the operations are additionally sent to.
+    pub shadow: Shadow,
 }
 
 impl ETHSenderConfig {
@@ -24,6 +26,7 @@ impl ETHSenderConfig {
                 "eth_sender.gas_price_limit",
                 "ETH_SENDER_GAS_PRICE_LIMIT_"
             ),
+            shadow: envy_load!("eth_sender.shadow", "ETH_SENDER_SHADOW_"),
         }
     }
 }
@@ -44,6 +47,9 @@ pub struct Sender {
     pub max_txs_in_flight: u64,
     /// Whether sender should interact with L1 or not.
     pub is_enabled: bool,
+    /// Version of the deployed zkSync contract protocol. Determines whether
+    /// the Ethereum signatures of the L2 transactions must be bound to the network.
+    pub protocol_version: u32,
 }
 
 impl Sender {
@@ -63,6 +69,11 @@ pub struct GasLimit {
     pub sample_interval: u64,
     /// Scale factor for gas price limit (used by GasAdjuster).
     pub scale_factor: f64,
+    /// Share of the difference between the stale gas price limit and the scaled average network price
+    /// removed on every limit update, from 0 (no decay) to 1.
+    pub decay_rate: f64,
+    /// Gas price limit is never decayed below this value (in wei).
+    pub decay_floor: u64,
 }
 
 impl GasLimit {
@@ -77,6 +88,29 @@ impl GasLimit {
     }
 }
 
+/// Shadow network is a secondary network (e.g. a mainnet fork or a testnet with the contract deployed
+/// from the mainnet state) the operations of the actual network are additionally sent to by a
+/// separate `eth_sender` instance, so the contract upgrades can be rehearsed on the actual data.
+/// The shadow instance has its own nonce and gas price tracking and doesn't affect the actual network.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct Shadow {
+    /// Whether the operations are sent to the shadow network.
+    pub is_enabled: bool,
+    /// Name of the shadow network. The state of each network is stored separately,
+    /// so a new name should be used for a new fork.
+    pub network: String,
+    /// Addresses of the shadow network Ethereum node API.
+    pub web3_url: Vec<String>,
+    /// Chain ID of the shadow network.
+    pub chain_id: u8,
+    /// Address of the zkSync contract in the shadow network.
+    pub contract_addr: Address,
+    /// Private key of the operator account in the shadow network.
+    pub operator_private_key: H256,
+    /// Address of the operator account in the shadow network.
+    pub operator_commit_eth_addr: Address,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -90,6 +124,7 @@ mod tests {
                 tx_poll_period: 3,
                 max_txs_in_flight: 3,
                 is_enabled: true,
+                protocol_version: 5,
                 operator_private_key: hash(
                     "27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be",
                 ),
@@ -100,6 +135,19 @@ mod tests {
                 update_interval: 150,
                 sample_interval: 15,
                 scale_factor: 1.0f64,
+                decay_rate: 0.25f64,
+                decay_floor: 1000000000,
+            },
+            shadow: Shadow {
+                is_enabled: true,
+                network: "mainnet-fork".into(),
+                web3_url: vec!["http://127.0.0.1:8555".into()],
+                chain_id: 1,
+                contract_addr: addr("70a0F165d6f8054d0d0CF8dFd4DD2005f0AF6B55"),
+                operator_private_key: hash(
+                    "27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be",
+                ),
+                operator_commit_eth_addr: addr("de03a0B5963f75f1C8485B355fF6D30f3093BDE7"),
             },
         }
     }
@@ -112,12 +160,22 @@ ETH_SENDER_SENDER_EXPECTED_WAIT_TIME_BLOCK="30"
 ETH_SENDER_SENDER_TX_POLL_PERIOD="3"
 ETH_SENDER_SENDER_MAX_TXS_IN_FLIGHT="3"
 ETH_SENDER_SENDER_IS_ENABLED="true"
+ETH_SENDER_SENDER_PROTOCOL_VERSION="5"
 ETH_SENDER_SENDER_OPERATOR_PRIVATE_KEY="0x27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be"
 ETH_SENDER_SENDER_OPERATOR_COMMIT_ETH_ADDR="0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
 ETH_SENDER_GAS_PRICE_LIMIT_DEFAULT="400000000000"
 ETH_SENDER_GAS_PRICE_LIMIT_UPDATE_INTERVAL="150"
 ETH_SENDER_GAS_PRICE_LIMIT_SAMPLE_INTERVAL="15"
 ETH_SENDER_GAS_PRICE_LIMIT_SCALE_FACTOR="1"
+ETH_SENDER_GAS_PRICE_LIMIT_DECAY_RATE="0.25"
+ETH_SENDER_GAS_PRICE_LIMIT_DECAY_FLOOR="1000000000"
+ETH_SENDER_SHADOW_IS_ENABLED="true"
+ETH_SENDER_SHADOW_NETWORK="mainnet-fork"
+ETH_SENDER_SHADOW_WEB3_URL="http://127.0.0.1:8555"
+ETH_SENDER_SHADOW_CHAIN_ID="1"
+ETH_SENDER_SHADOW_CONTRACT_ADDR="0x70a0F165d6f8054d0d0CF8dFd4DD2005f0AF6B55"
+ETH_SENDER_SHADOW_OPERATOR_PRIVATE_KEY="0x27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be"
+ETH_SENDER_SHADOW_OPERATOR_COMMIT_ETH_ADDR="0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
         "#;
         set_env(config);
 
@@ -2,6 +2,8 @@
 use std::time::Duration;
 // External uses
 use serde::Deserialize;
+// Workspace uses
+use zksync_types::H256;
 // Local uses
 use crate::envy_load;
 
@@ -14,6 +16,34 @@ pub struct ETHWatchConfig {
     /// How often we want to poll the Ethereum node.
     /// Value in milliseconds.
     pub eth_node_poll_interval: u64,
+    /// Whether the received priority operations should be persisted in the database.
+    /// In this mode only the recent blocks are queried from the Ethereum node on restart,
+    /// so the server can run against the node that doesn't keep the old logs.
+    pub persist_events: bool,
+    /// Amount of the latest Ethereum blocks queried for the priority operations
+    /// if there are no persisted operations and no data restore state to start from.
+    /// Must not be less than the priority operations expiration period (35000 blocks).
+    pub recent_blocks_window: u64,
+    /// Whether the committed blocks should be cross-checked against the data submitted to L1.
+    /// Block production is halted if the local state diverges from the committed one.
+    pub verify_committed_state: bool,
+    /// How often the newly confirmed commits are cross-checked against L1.
+    /// Value in seconds.
+    pub state_verification_interval: u64,
+    /// Whether the operator can refund the deposits which funds would get stuck on L2,
+    /// e.g. because of the invalid recipient. Such deposits are flagged regardless of this option.
+    pub deposit_refunds_enabled: bool,
+    /// Ethereum private key of the account the deposit refunds are sent from.
+    /// Must not be the operator key, since the refunds are sent independently of the `eth_sender`.
+    pub refund_account_private_key: H256,
+    /// Min amount of the blocks the logs are queried for in one request.
+    /// Ranges are shrunk down to this size when the Ethereum node fails to serve the larger ones.
+    pub logs_min_block_range: u64,
+    /// Max amount of the blocks the logs are queried for in one request.
+    pub logs_max_block_range: u64,
+    /// Timeout of the logs request, the request taking longer is retried with the smaller range.
+    /// Value in milliseconds.
+    pub logs_request_timeout: u64,
 }
 
 impl ETHWatchConfig {
@@ -25,17 +55,38 @@ impl ETHWatchConfig {
     pub fn poll_interval(&self) -> Duration {
         Duration::from_millis(self.eth_node_poll_interval)
     }
+
+    /// Converts `self.state_verification_interval` into `Duration`.
+    pub fn state_verification_interval(&self) -> Duration {
+        Duration::from_secs(self.state_verification_interval)
+    }
+
+    /// Converts `self.logs_request_timeout` into `Duration`.
+    pub fn logs_request_timeout(&self) -> Duration {
+        Duration::from_millis(self.logs_request_timeout)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::configs::test_utils::set_env;
+    use crate::configs::test_utils::{hash, set_env};
 
     fn expected_config() -> ETHWatchConfig {
         ETHWatchConfig {
             confirmations_for_eth_event: 0,
             eth_node_poll_interval: 300,
+            persist_events: false,
+            recent_blocks_window: 35000,
+            verify_committed_state: true,
+            state_verification_interval: 10,
+            deposit_refunds_enabled: true,
+            refund_account_private_key: hash(
+                "0x7726827caac94a7f9e1b160f7ea819f172f7b6f9d2a97f992c38edeab82d4110",
+            ),
+            logs_min_block_range: 10,
+            logs_max_block_range: 5000,
+            logs_request_timeout: 15000,
         }
     }
 
@@ -44,6 +95,15 @@ mod tests {
         let config = r#"
 ETH_WATCH_CONFIRMATIONS_FOR_ETH_EVENT="0"
 ETH_WATCH_ETH_NODE_POLL_INTERVAL="300"
+ETH_WATCH_PERSIST_EVENTS="false"
+ETH_WATCH_RECENT_BLOCKS_WINDOW="35000"
+ETH_WATCH_VERIFY_COMMITTED_STATE="true"
+ETH_WATCH_STATE_VERIFICATION_INTERVAL="10"
+ETH_WATCH_DEPOSIT_REFUNDS_ENABLED="true"
+ETH_WATCH_REFUND_ACCOUNT_PRIVATE_KEY="0x7726827caac94a7f9e1b160f7ea819f172f7b6f9d2a97f992c38edeab82d4110"
+ETH_WATCH_LOGS_MIN_BLOCK_RANGE="10"
+ETH_WATCH_LOGS_MAX_BLOCK_RANGE="5000"
+ETH_WATCH_LOGS_REQUEST_TIMEOUT="15000"
         "#;
         set_env(config);
 
@@ -60,5 +120,13 @@ ETH_WATCH_ETH_NODE_POLL_INTERVAL="300"
             config.poll_interval(),
             Duration::from_millis(config.eth_node_poll_interval)
         );
+        assert_eq!(
+            config.state_verification_interval(),
+            Duration::from_secs(config.state_verification_interval)
+        );
+        assert_eq!(
+            config.logs_request_timeout(),
+            Duration::from_millis(config.logs_request_timeout)
+        );
     }
 }
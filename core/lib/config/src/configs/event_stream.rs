@@ -0,0 +1,85 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use serde::Deserialize;
+// Local uses
+use crate::envy_load;
+
+/// Message broker the events are published to.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub enum EventStreamBackend {
+    Kafka,
+    Nats,
+}
+
+/// Configuration of the publisher streaming the chain events to the message broker.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct EventStreamConfig {
+    /// Whether the events are published.
+    pub enabled: bool,
+    /// Message broker the events are published to.
+    /// The server must be built with the corresponding feature (`kafka` or `nats`).
+    /// Only Kafka delivers the events at least once, NATS doesn't persist them.
+    pub backend: EventStreamBackend,
+    /// Comma-separated list of Kafka brokers or the NATS server URL.
+    pub servers: String,
+    /// Kafka topic or NATS subject the events are published to.
+    pub topic: String,
+    /// How often the new events are checked. Value in milliseconds.
+    pub publish_interval: u64,
+    /// Max amount of the events published within one iteration.
+    pub batch_size: u32,
+    /// Max time to wait for the broker acknowledgement (Kafka) or the flush (NATS). In milliseconds.
+    pub publish_timeout: u64,
+}
+
+impl EventStreamConfig {
+    pub fn from_env() -> Self {
+        envy_load!("event_stream", "EVENT_STREAM_")
+    }
+
+    /// Converts `self.publish_interval` into `Duration`
+    pub fn publish_interval(&self) -> Duration {
+        Duration::from_millis(self.publish_interval)
+    }
+
+    /// Converts `self.publish_timeout` into `Duration`
+    pub fn publish_timeout(&self) -> Duration {
+        Duration::from_millis(self.publish_timeout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> EventStreamConfig {
+        EventStreamConfig {
+            enabled: true,
+            backend: EventStreamBackend::Nats,
+            servers: "nats://127.0.0.1:4222".into(),
+            topic: "zksync.events".into(),
+            publish_interval: 500,
+            batch_size: 1000,
+            publish_timeout: 10000,
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+EVENT_STREAM_ENABLED="true"
+EVENT_STREAM_BACKEND="Nats"
+EVENT_STREAM_SERVERS="nats://127.0.0.1:4222"
+EVENT_STREAM_TOPIC="zksync.events"
+EVENT_STREAM_PUBLISH_INTERVAL="500"
+EVENT_STREAM_BATCH_SIZE="1000"
+EVENT_STREAM_PUBLISH_TIMEOUT="10000"
+        "#;
+        set_env(config);
+
+        let actual = EventStreamConfig::from_env();
+        assert_eq!(actual, expected_config());
+    }
+}
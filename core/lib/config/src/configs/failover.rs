@@ -0,0 +1,59 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use serde::Deserialize;
+// Local uses
+use crate::envy_load;
+
+/// Configuration of the active/standby failover of the server instances.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct FailoverConfig {
+    /// Whether the server instances compete for the leadership. When disabled, the instance
+    /// considers itself the only one and runs all the components right away.
+    pub enabled: bool,
+    /// ID of the Postgres advisory lock representing the leadership.
+    /// Must be the same for all the instances operating the same network.
+    pub lock_id: i64,
+    /// How often the standby instance tries to take over the leadership and the leader
+    /// checks that it still holds one. Value in milliseconds.
+    pub check_interval: u64,
+}
+
+impl FailoverConfig {
+    pub fn from_env() -> Self {
+        envy_load!("failover", "FAILOVER_")
+    }
+
+    /// Converts `self.check_interval` into `Duration`
+    pub fn check_interval(&self) -> Duration {
+        Duration::from_millis(self.check_interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> FailoverConfig {
+        FailoverConfig {
+            enabled: true,
+            lock_id: 271,
+            check_interval: 5000,
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+FAILOVER_ENABLED="true"
+FAILOVER_LOCK_ID="271"
+FAILOVER_CHECK_INTERVAL="5000"
+        "#;
+        set_env(config);
+
+        let actual = FailoverConfig::from_env();
+        assert_eq!(actual, expected_config());
+        assert_eq!(actual.check_interval(), Duration::from_millis(5000));
+    }
+}
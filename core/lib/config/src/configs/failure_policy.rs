@@ -0,0 +1,92 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use serde::Deserialize;
+// Local uses
+use crate::envy_load;
+
+/// Behavior of the server when the external dependency is not available.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FailurePolicy {
+    /// The request is processed as if the dependency didn't object, in the degraded way
+    /// where possible (e.g. using the last known data).
+    Open,
+    /// The request is rejected until the dependency is available again.
+    Closed,
+}
+
+impl FailurePolicy {
+    pub fn is_open(self) -> bool {
+        self == Self::Open
+    }
+}
+
+/// Policies applied when the external dependencies of the server fail.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct FailurePolicyConfig {
+    /// Token price sources are not available or only the primary one is. When open, the fees
+    /// are quoted from the last known price and the prices not cross-checked with the secondary
+    /// source are accepted. When closed, the fees are not quoted in the affected tokens.
+    pub price_feed: FailurePolicy,
+    /// Screening API is not available. When open, the recipient is considered not screened.
+    /// When closed, the recipient is considered screened.
+    pub screening: FailurePolicy,
+    /// State shared by the API server replicas (the submission rate limits and the paid fee
+    /// subsidies) can't be loaded in time. When open, the rate limits are not enforced
+    /// and no subsidies are paid. When closed, the transactions are rejected.
+    pub shared_state: FailurePolicy,
+    /// Max time to wait for the shared state, the slower query is considered failed.
+    /// Value in milliseconds, 0 means no timeout.
+    pub shared_state_timeout: u64,
+}
+
+impl FailurePolicyConfig {
+    pub fn from_env() -> Self {
+        envy_load!("failure_policy", "FAILURE_POLICY_")
+    }
+
+    /// Converts `self.shared_state_timeout` into `Duration`, `None` if there is no timeout.
+    pub fn shared_state_timeout(&self) -> Option<Duration> {
+        Some(self.shared_state_timeout)
+            .filter(|&timeout| timeout > 0)
+            .map(Duration::from_millis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> FailurePolicyConfig {
+        FailurePolicyConfig {
+            price_feed: FailurePolicy::Closed,
+            screening: FailurePolicy::Open,
+            shared_state: FailurePolicy::Closed,
+            shared_state_timeout: 1500,
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+FAILURE_POLICY_PRICE_FEED="closed"
+FAILURE_POLICY_SCREENING="open"
+FAILURE_POLICY_SHARED_STATE="closed"
+FAILURE_POLICY_SHARED_STATE_TIMEOUT="1500"
+        "#;
+        set_env(config);
+
+        let actual = FailurePolicyConfig::from_env();
+        assert_eq!(actual, expected_config());
+        assert!(actual.screening.is_open());
+        assert_eq!(
+            actual.shared_state_timeout(),
+            Some(Duration::from_millis(1500))
+        );
+
+        set_env(r#"FAILURE_POLICY_SHARED_STATE_TIMEOUT="0""#);
+        assert_eq!(FailurePolicyConfig::from_env().shared_state_timeout(), None);
+    }
+}
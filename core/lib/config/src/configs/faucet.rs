@@ -0,0 +1,102 @@
+// Built-in uses
+use std::net::IpAddr;
+// External uses
+use serde::Deserialize;
+// Workspace uses
+use zksync_types::Address;
+// Local uses
+use crate::envy_load;
+
+/// Configuration of the test tokens faucet.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct FaucetConfig {
+    /// Whether the faucet endpoints are served. Must only be enabled on the test networks.
+    pub enabled: bool,
+    /// Address of the account the test tokens are sent from.
+    pub account_address: Address,
+    /// zkSync private key of the account the test tokens are sent from.
+    pub account_private_key: String,
+    /// Amount of the token sent per request, in the whole token units.
+    pub amount: u64,
+    /// Symbols of the tokens dispensed by the faucet. All the tokens are dispensed if empty.
+    pub tokens: Vec<String>,
+    /// Max number of requests per recipient address per day.
+    pub requests_per_address: u64,
+    /// Max number of requests per IP address per day.
+    pub requests_per_ip: u64,
+    /// IP address of the reverse proxy the requests come through. The IP address of the client
+    /// is taken from the `X-Forwarded-For` header of the requests coming from this address only.
+    /// The peer address is used if empty.
+    pub trusted_proxy: String,
+    /// URL of the captcha verification service (e.g. hCaptcha or reCAPTCHA `siteverify`).
+    /// Captcha is not required if empty.
+    pub captcha_verify_url: String,
+    /// Secret key of the captcha verification service.
+    pub captcha_secret: String,
+}
+
+impl FaucetConfig {
+    pub fn from_env() -> Self {
+        envy_load!("faucet", "FAUCET_")
+    }
+
+    pub fn trusted_proxy(&self) -> Option<IpAddr> {
+        if self.trusted_proxy.is_empty() {
+            return None;
+        }
+        Some(
+            self.trusted_proxy
+                .parse()
+                .expect("Invalid faucet trusted proxy address"),
+        )
+    }
+
+    /// Whether the requests have to be confirmed by a captcha.
+    pub fn captcha_required(&self) -> bool {
+        !self.captcha_verify_url.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::{addr, set_env};
+
+    fn expected_config() -> FaucetConfig {
+        FaucetConfig {
+            enabled: true,
+            account_address: addr("de03a0b5963f75f1c8485b355ff6d30f3093bde7"),
+            account_private_key:
+                "0x0559b9f000b4e4bbb7fe02e1374cef9623c2ab7c3791204b490e1f229191d104".into(),
+            amount: 100,
+            tokens: vec!["ETH".into(), "DAI".into()],
+            requests_per_address: 1,
+            requests_per_ip: 10,
+            trusted_proxy: "10.0.0.1".into(),
+            captcha_verify_url: "https://hcaptcha.com/siteverify".into(),
+            captcha_secret: "secret".into(),
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+FAUCET_ENABLED="true"
+FAUCET_ACCOUNT_ADDRESS="0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
+FAUCET_ACCOUNT_PRIVATE_KEY="0x0559b9f000b4e4bbb7fe02e1374cef9623c2ab7c3791204b490e1f229191d104"
+FAUCET_AMOUNT="100"
+FAUCET_TOKENS="ETH,DAI"
+FAUCET_REQUESTS_PER_ADDRESS="1"
+FAUCET_REQUESTS_PER_IP="10"
+FAUCET_TRUSTED_PROXY="10.0.0.1"
+FAUCET_CAPTCHA_VERIFY_URL="https://hcaptcha.com/siteverify"
+FAUCET_CAPTCHA_SECRET="secret"
+        "#;
+        set_env(config);
+
+        let actual = FaucetConfig::from_env();
+        assert_eq!(actual, expected_config());
+        assert!(actual.captcha_required());
+        assert_eq!(actual.trusted_proxy(), Some("10.0.0.1".parse().unwrap()));
+    }
+}
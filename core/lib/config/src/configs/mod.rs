@@ -1,10 +1,13 @@
 // Public re-exports
 pub use self::{
     api::ApiConfig, chain::ChainConfig, contracts::ContractsConfig, database::DBConfig,
-    dev_liquidity_token_watcher::DevLiquidityTokenWatcherConfig, eth_client::ETHClientConfig,
-    eth_sender::ETHSenderConfig, eth_watch::ETHWatchConfig,
+    dev_liquidity_token_watcher::DevLiquidityTokenWatcherConfig,
+    dust_collector::DustCollectorConfig, eth_client::ETHClientConfig, eth_sender::ETHSenderConfig,
+    eth_watch::ETHWatchConfig, event_stream::EventStreamConfig, failover::FailoverConfig,
+    failure_policy::FailurePolicyConfig, faucet::FaucetConfig,
     forced_exit_requests::ForcedExitRequestsConfig, gateway_watcher::GatewayWatcherConfig,
-    misc::MiscConfig, prover::ProverConfig, ticker::TickerConfig,
+    misc::MiscConfig, prover::ProverConfig, screening::ScreeningConfig, ticker::TickerConfig,
+    webhooks::WebhooksConfig,
 };
 
 pub mod api;
@@ -12,28 +15,37 @@ pub mod chain;
 pub mod contracts;
 pub mod database;
 pub mod dev_liquidity_token_watcher;
+pub mod dust_collector;
 pub mod eth_client;
 pub mod eth_sender;
 pub mod eth_watch;
+pub mod event_stream;
+pub mod failover;
+pub mod failure_policy;
+pub mod faucet;
 pub mod forced_exit_requests;
 pub mod gateway_watcher;
 pub mod misc;
 pub mod prover;
+pub mod screening;
 pub mod ticker;
+pub mod webhooks;
 
 #[cfg(test)]
 pub(crate) mod test_utils;
 
-/// Convenience macro that loads the structure from the environment variable given the prefix.
+/// Convenience macro that loads the structure from the layered config source
+/// (config files listed in `ZKSYNC_CONFIG_FILE` overridden by the environment variables)
+/// given the prefix.
 ///
 /// # Panics
 ///
-/// Panics if the config cannot be loaded from the environment variables.
+/// Panics if the config cannot be loaded.
 #[macro_export]
 macro_rules! envy_load {
     ($name:expr, $prefix:expr) => {
-        envy::prefixed($prefix)
-            .from_env()
-            .unwrap_or_else(|err| panic!("Cannot load config <{}>: {}", $name, err))
+        $crate::loader::ConfigSource::from_env()
+            .and_then(|source| source.load($name, $prefix))
+            .unwrap_or_else(|err| panic!("{}", err))
     };
 }
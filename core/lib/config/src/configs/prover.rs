@@ -11,6 +11,7 @@ pub struct ProverConfig {
     pub prover: Prover,
     pub core: Core,
     pub witness_generator: WitnessGenerator,
+    pub external: ExternalProvers,
 }
 
 impl ProverConfig {
@@ -19,6 +20,7 @@ impl ProverConfig {
             prover: envy_load!("prover.prover", "PROVER_PROVER_"),
             core: envy_load!("prover.core", "PROVER_CORE_"),
             witness_generator: envy_load!("prover.witness_generator", "PROVER_WITNESS_GENERATOR_"),
+            external: envy_load!("prover.external", "PROVER_EXTERNAL_"),
         }
     }
 }
@@ -32,6 +34,8 @@ pub struct Prover {
     pub cycle_wait: u64,
     /// Timeout for the requests to the prover server in seconds.
     pub request_timeout: u64,
+    /// Whether the prover talks to the prover server over gRPC instead of HTTP.
+    pub use_grpc: bool,
 }
 
 impl Prover {
@@ -82,6 +86,28 @@ impl WitnessGenerator {
     }
 }
 
+/// Settings of the API for the third-party provers.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ExternalProvers {
+    /// Whether the external provers API is enabled.
+    pub enabled: bool,
+    /// Time given to the external prover to prove the leased job in seconds.
+    pub lease_timeout: u64,
+    /// Part of the prover stake locked for each leased job, in wei.
+    pub job_collateral: u64,
+    /// Payment for the single block proof, in wei.
+    pub single_proof_payment: u64,
+    /// Payment for the aggregated proof, in wei.
+    pub aggregated_proof_payment: u64,
+}
+
+impl ExternalProvers {
+    /// Converts `self.lease_timeout` into `Duration`.
+    pub fn lease_timeout(&self) -> Duration {
+        Duration::from_secs(self.lease_timeout)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -93,6 +119,7 @@ mod tests {
                 heartbeat_interval: 1000,
                 cycle_wait: 500,
                 request_timeout: 10,
+                use_grpc: false,
             },
             core: Core {
                 gone_timeout: 60000,
@@ -102,6 +129,13 @@ mod tests {
                 prepare_data_interval: 500,
                 witness_generators: 2,
             },
+            external: ExternalProvers {
+                enabled: true,
+                lease_timeout: 3600,
+                job_collateral: 1_000_000_000_000_000_000,
+                single_proof_payment: 10_000_000_000_000_000,
+                aggregated_proof_payment: 50_000_000_000_000_000,
+            },
         }
     }
 
@@ -111,10 +145,16 @@ mod tests {
 PROVER_PROVER_HEARTBEAT_INTERVAL="1000"
 PROVER_PROVER_CYCLE_WAIT="500"
 PROVER_PROVER_REQUEST_TIMEOUT="10"
+PROVER_PROVER_USE_GRPC="false"
 PROVER_CORE_GONE_TIMEOUT="60000"
 PROVER_CORE_IDLE_PROVERS="1"
 PROVER_WITNESS_GENERATOR_PREPARE_DATA_INTERVAL="500"
 PROVER_WITNESS_GENERATOR_WITNESS_GENERATORS="2"
+PROVER_EXTERNAL_ENABLED="true"
+PROVER_EXTERNAL_LEASE_TIMEOUT="3600"
+PROVER_EXTERNAL_JOB_COLLATERAL="1000000000000000000"
+PROVER_EXTERNAL_SINGLE_PROOF_PAYMENT="10000000000000000"
+PROVER_EXTERNAL_AGGREGATED_PROOF_PAYMENT="50000000000000000"
         "#;
         set_env(config);
 
@@ -149,5 +189,10 @@ PROVER_WITNESS_GENERATOR_WITNESS_GENERATORS="2"
             config.witness_generator.prepare_data_interval(),
             Duration::from_millis(config.witness_generator.prepare_data_interval)
         );
+
+        assert_eq!(
+            config.external.lease_timeout(),
+            Duration::from_secs(config.external.lease_timeout)
+        );
     }
 }
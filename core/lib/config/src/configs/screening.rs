@@ -0,0 +1,95 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use serde::Deserialize;
+// Workspace uses
+use zksync_types::{screening::ScreeningAction, Address};
+// Local uses
+use crate::envy_load;
+
+/// Configuration of the screening of the deposits and transfers recipients.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ScreeningConfig {
+    /// Whether the recipients are screened.
+    pub enabled: bool,
+    /// Action taken on the transfers to the screened addresses: "Reject", "Flag" or "Delay".
+    /// Deposits to the screened addresses are always flagged.
+    pub action: ScreeningAction,
+    /// Local list of the screened addresses.
+    pub screened_addresses: Vec<Address>,
+    /// URL of the external screening API, empty if not used.
+    /// The API is requested as `GET {api_url}/{address}` and responds with `{ "screened": bool }`.
+    pub api_url: String,
+    /// Period the external API responses are cached for. Value in seconds.
+    pub api_cache_ttl: u64,
+    /// Max time to wait for the external API response. Value in milliseconds.
+    pub api_timeout: u64,
+    /// Period the delayed transactions are held in the mempool for. Value in seconds.
+    pub delay: u64,
+}
+
+impl ScreeningConfig {
+    pub fn from_env() -> Self {
+        envy_load!("screening", "SCREENING_")
+    }
+
+    /// Returns the URL of the external screening API, if it's configured.
+    pub fn api_url(&self) -> Option<&str> {
+        Some(self.api_url.as_str()).filter(|url| !url.is_empty())
+    }
+
+    /// Converts `self.api_cache_ttl` into `Duration`
+    pub fn api_cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.api_cache_ttl)
+    }
+
+    /// Converts `self.api_timeout` into `Duration`
+    pub fn api_timeout(&self) -> Duration {
+        Duration::from_millis(self.api_timeout)
+    }
+
+    /// Converts `self.delay` into `chrono::Duration`
+    pub fn delay(&self) -> chrono::Duration {
+        chrono::Duration::seconds(self.delay as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::{addr, set_env};
+
+    fn expected_config() -> ScreeningConfig {
+        ScreeningConfig {
+            enabled: true,
+            action: ScreeningAction::Delay,
+            screened_addresses: vec![
+                addr("8ba1f109551bd432803012645ac136ddd64dba72"),
+                addr("de0b295669a9fd93d5f28d9ec85e40f4cb697bae"),
+            ],
+            api_url: "http://127.0.0.1:9090/screening".into(),
+            api_cache_ttl: 3600,
+            api_timeout: 3000,
+            delay: 86400,
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+SCREENING_ENABLED="true"
+SCREENING_ACTION="Delay"
+SCREENING_SCREENED_ADDRESSES="0x8ba1f109551bd432803012645ac136ddd64dba72,0xde0b295669a9fd93d5f28d9ec85e40f4cb697bae"
+SCREENING_API_URL="http://127.0.0.1:9090/screening"
+SCREENING_API_CACHE_TTL="3600"
+SCREENING_API_TIMEOUT="3000"
+SCREENING_DELAY="86400"
+        "#;
+        set_env(config);
+
+        let actual = ScreeningConfig::from_env();
+        assert_eq!(actual, expected_config());
+        assert_eq!(actual.api_url(), Some("http://127.0.0.1:9090/screening"));
+        assert_eq!(actual.delay(), chrono::Duration::days(1));
+    }
+}
@@ -1,5 +1,6 @@
 // Built-in uses
 use std::collections::HashMap;
+use std::time::Duration;
 // External uses
 use num::rational::Ratio;
 use num::BigUint;
@@ -41,8 +42,28 @@ pub struct TickerConfig {
     /// List of tokens for which subsidies are disabled.
     pub not_subsidized_tokens: Vec<Address>,
     /// List of tokens for which subsidies are disabled.
-    subsidized_tokens: Vec<Address>,
-    subsidized_tokens_limits: Vec<BigUint>,
+    pub(crate) subsidized_tokens: Vec<Address>,
+    pub(crate) subsidized_tokens_limits: Vec<BigUint>,
+    /// List of tokens for which the fee is increased by the markup.
+    pub(crate) fee_markup_tokens: Vec<Address>,
+    /// Fee markups (in percents) for the `fee_markup_tokens`.
+    pub(crate) fee_markup_percents: Vec<u32>,
+    /// Whether the token prices are cross-checked with the secondary price source, i.e. the one
+    /// not chosen as `token_price_source`.
+    pub price_sanity_check_enabled: bool,
+    /// Max divergence (in percents) of the token price from the one reported by the secondary
+    /// source. Fee quoting for the token is suspended while the divergence is greater.
+    pub max_price_divergence_percent: u32,
+    /// Whether the fee quotes are cached and refreshed in the background.
+    pub fee_quote_cache_enabled: bool,
+    /// Max age of the cached fee quote served to the API, in milliseconds.
+    pub fee_quote_max_staleness_ms: u64,
+    /// Interval between the background refreshes of the fee quotes, in milliseconds.
+    pub fee_quote_refresh_interval_ms: u64,
+    /// Number of the most requested (fee type, token) pairs refreshed in the background.
+    pub fee_quote_cache_top_pairs: usize,
+    /// Tokens the quotes of all fee types are calculated for upon start and kept fresh.
+    pub fee_quote_warmup_tokens: Vec<Address>,
 }
 
 impl TickerConfig {
@@ -60,6 +81,44 @@ impl TickerConfig {
         (self.token_price_source, url)
     }
 
+    /// Returns the type and the API URL of the secondary price source the token prices are
+    /// cross-checked with, if the check is enabled.
+    pub fn secondary_price_source(&self) -> Option<(TokenPriceSource, &str)> {
+        if !self.price_sanity_check_enabled {
+            return None;
+        }
+
+        let source = match self.token_price_source {
+            TokenPriceSource::CoinGecko => (
+                TokenPriceSource::CoinMarketCap,
+                self.coinmarketcap_base_url.as_ref(),
+            ),
+            TokenPriceSource::CoinMarketCap => (
+                TokenPriceSource::CoinGecko,
+                self.coingecko_base_url.as_ref(),
+            ),
+        };
+        Some(source)
+    }
+
+    /// Returns the max age of the cached fee quote.
+    pub fn fee_quote_max_staleness(&self) -> Duration {
+        Duration::from_millis(self.fee_quote_max_staleness_ms)
+    }
+
+    /// Returns the interval between the background refreshes of the fee quotes.
+    pub fn fee_quote_refresh_interval(&self) -> Duration {
+        Duration::from_millis(self.fee_quote_refresh_interval_ms)
+    }
+
+    /// Returns the max allowed divergence of the prices reported by the primary and secondary sources.
+    pub fn max_price_divergence(&self) -> Ratio<BigUint> {
+        Ratio::new(
+            BigUint::from(self.max_price_divergence_percent),
+            BigUint::from(100u32),
+        )
+    }
+
     pub fn get_subsidy_limits(&self) -> HashMap<Address, Ratio<BigUint>> {
         assert_eq!(
             self.subsidized_tokens.len(),
@@ -78,6 +137,25 @@ impl TickerConfig {
             )
             .collect()
     }
+
+    /// Returns the multipliers applied to the fees paid in the tokens with the markup.
+    pub fn get_fee_markups(&self) -> HashMap<Address, Ratio<BigUint>> {
+        assert_eq!(
+            self.fee_markup_tokens.len(),
+            self.fee_markup_percents.len(),
+            "Number of tokens with the fee markup and markups should be equal"
+        );
+
+        self.fee_markup_tokens
+            .iter()
+            .cloned()
+            .zip(
+                self.fee_markup_percents.iter().map(|percents| {
+                    Ratio::new(BigUint::from(100 + percents), BigUint::from(100u32))
+                }),
+            )
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -103,6 +181,15 @@ mod tests {
             ],
             subsidized_tokens: vec![addr("0bc529c00c6401aef6d220be8c6ea1667f6ad93e")],
             subsidized_tokens_limits: vec![156u32.into()],
+            fee_markup_tokens: vec![addr("34083bbd70d394110487feaa087da875a54624ec")],
+            fee_markup_percents: vec![15],
+            price_sanity_check_enabled: true,
+            max_price_divergence_percent: 10,
+            fee_quote_cache_enabled: true,
+            fee_quote_max_staleness_ms: 10_000,
+            fee_quote_refresh_interval_ms: 5_000,
+            fee_quote_cache_top_pairs: 20,
+            fee_quote_warmup_tokens: vec![addr("0000000000000000000000000000000000000000")],
         }
     }
 
@@ -122,6 +209,15 @@ FEE_TICKER_LIQUIDITY_VOLUME=100
 FEE_TICKER_NUMBER_OF_TICKER_ACTORS="4"
 FEE_TICKER_SUBSIDIZED_TOKENS="0x0bc529c00c6401aef6d220be8c6ea1667f6ad93e"
 FEE_TICKER_SUBSIDIZED_TOKENS_LIMITS=156
+FEE_TICKER_FEE_MARKUP_TOKENS="0x34083bbd70d394110487feaa087da875a54624ec"
+FEE_TICKER_FEE_MARKUP_PERCENTS=15
+FEE_TICKER_PRICE_SANITY_CHECK_ENABLED=true
+FEE_TICKER_MAX_PRICE_DIVERGENCE_PERCENT=10
+FEE_TICKER_FEE_QUOTE_CACHE_ENABLED=true
+FEE_TICKER_FEE_QUOTE_MAX_STALENESS_MS=10000
+FEE_TICKER_FEE_QUOTE_REFRESH_INTERVAL_MS=5000
+FEE_TICKER_FEE_QUOTE_CACHE_TOP_PAIRS=20
+FEE_TICKER_FEE_QUOTE_WARMUP_TOKENS="0x0000000000000000000000000000000000000000"
         "#;
         set_env(config);
 
@@ -145,11 +241,32 @@ FEE_TICKER_SUBSIDIZED_TOKENS_LIMITS=156
             config.price_source(),
             (TokenPriceSource::CoinGecko, COINGECKO_URL)
         );
+        assert_eq!(
+            config.secondary_price_source(),
+            Some((TokenPriceSource::CoinMarketCap, COINMARKETCAP_URL))
+        );
 
         config.token_price_source = TokenPriceSource::CoinMarketCap;
         assert_eq!(
             config.price_source(),
             (TokenPriceSource::CoinMarketCap, COINMARKETCAP_URL)
         );
+        assert_eq!(
+            config.secondary_price_source(),
+            Some((TokenPriceSource::CoinGecko, COINGECKO_URL))
+        );
+        assert_eq!(
+            config.max_price_divergence(),
+            Ratio::new(BigUint::from(1u32), BigUint::from(10u32))
+        );
+
+        config.price_sanity_check_enabled = false;
+        assert_eq!(config.secondary_price_source(), None);
+
+        let markups = config.get_fee_markups();
+        assert_eq!(
+            markups[&addr("34083bbd70d394110487feaa087da875a54624ec")],
+            Ratio::new(BigUint::from(115u32), BigUint::from(100u32))
+        );
     }
 }
@@ -0,0 +1,79 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use serde::Deserialize;
+// Local uses
+use crate::envy_load;
+
+/// Configuration of the webhook dispatcher.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct WebhooksConfig {
+    /// Whether the webhook dispatcher is enabled.
+    pub enabled: bool,
+    /// How often the new events are checked and the pending deliveries are sent.
+    /// Value in milliseconds.
+    pub dispatch_interval: u64,
+    /// Max request timeout. In milliseconds.
+    pub request_timeout: u64,
+    /// Delay before the first retry of the failed delivery, doubled with every next attempt.
+    /// Value in milliseconds.
+    pub retry_base_delay: u64,
+    /// Amount of attempts after which the delivery is considered failed.
+    pub max_attempts: u32,
+}
+
+impl WebhooksConfig {
+    pub fn from_env() -> Self {
+        envy_load!("webhooks", "WEBHOOKS_")
+    }
+
+    /// Converts `self.dispatch_interval` into `Duration`
+    pub fn dispatch_interval(&self) -> Duration {
+        Duration::from_millis(self.dispatch_interval)
+    }
+
+    /// Converts `self.request_timeout` into `Duration`
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_millis(self.request_timeout)
+    }
+
+    /// Returns the delay before the next attempt of the delivery which has already
+    /// failed `attempts` times.
+    pub fn retry_delay(&self, attempts: u32) -> Duration {
+        let factor = 1u64 << attempts.saturating_sub(1).min(16);
+        Duration::from_millis(self.retry_base_delay.saturating_mul(factor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> WebhooksConfig {
+        WebhooksConfig {
+            enabled: true,
+            dispatch_interval: 1000,
+            request_timeout: 5000,
+            retry_base_delay: 2000,
+            max_attempts: 10,
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+WEBHOOKS_ENABLED="true"
+WEBHOOKS_DISPATCH_INTERVAL="1000"
+WEBHOOKS_REQUEST_TIMEOUT="5000"
+WEBHOOKS_RETRY_BASE_DELAY="2000"
+WEBHOOKS_MAX_ATTEMPTS="10"
+        "#;
+        set_env(config);
+
+        let actual = WebhooksConfig::from_env();
+        assert_eq!(actual, expected_config());
+        assert_eq!(actual.retry_delay(1), Duration::from_secs(2));
+        assert_eq!(actual.retry_delay(3), Duration::from_secs(8));
+    }
+}
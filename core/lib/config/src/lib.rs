@@ -1,13 +1,22 @@
 use serde::Deserialize;
 
-pub use crate::configs::{
-    ApiConfig, ChainConfig, ContractsConfig, DBConfig, DevLiquidityTokenWatcherConfig,
-    ETHClientConfig, ETHSenderConfig, ETHWatchConfig, ForcedExitRequestsConfig,
-    GatewayWatcherConfig, MiscConfig, ProverConfig, TickerConfig,
+pub use crate::{
+    configs::{
+        ApiConfig, ChainConfig, ContractsConfig, DBConfig, DevLiquidityTokenWatcherConfig,
+        DustCollectorConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig, EventStreamConfig,
+        FailoverConfig, FailurePolicyConfig, FaucetConfig, ForcedExitRequestsConfig,
+        GatewayWatcherConfig, MiscConfig, ProverConfig, ScreeningConfig, TickerConfig,
+        WebhooksConfig,
+    },
+    loader::{ConfigError, ConfigErrors, ConfigSource},
+    reload::{ConfigReloader, Reloadable, ReloadableParams},
 };
 
 pub mod configs;
+pub mod loader;
+pub mod reload;
 pub mod test_config;
+mod validation;
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct ZkSyncConfig {
@@ -22,6 +31,13 @@ pub struct ZkSyncConfig {
     pub prover: ProverConfig,
     pub ticker: TickerConfig,
     pub forced_exit_requests: ForcedExitRequestsConfig,
+    pub webhooks: WebhooksConfig,
+    pub event_stream: EventStreamConfig,
+    pub dust_collector: DustCollectorConfig,
+    pub faucet: FaucetConfig,
+    pub failover: FailoverConfig,
+    pub screening: ScreeningConfig,
+    pub failure_policy: FailurePolicyConfig,
 }
 
 impl ZkSyncConfig {
@@ -38,6 +54,13 @@ impl ZkSyncConfig {
             prover: ProverConfig::from_env(),
             ticker: TickerConfig::from_env(),
             forced_exit_requests: ForcedExitRequestsConfig::from_env(),
+            webhooks: WebhooksConfig::from_env(),
+            event_stream: EventStreamConfig::from_env(),
+            dust_collector: DustCollectorConfig::from_env(),
+            faucet: FaucetConfig::from_env(),
+            failover: FailoverConfig::from_env(),
+            screening: ScreeningConfig::from_env(),
+            failure_policy: FailurePolicyConfig::from_env(),
         }
     }
 }
@@ -0,0 +1,263 @@
+//! Layered configuration source.
+//!
+//! The configuration is assembled from the following layers, each next layer overriding the previous one:
+//!
+//! 1. TOML files listed (comma-separated) in the `ZKSYNC_CONFIG_FILE` environment variable, in order.
+//!    The files have the same layout as the ones in `etc/env`: nested tables are flattened into the
+//!    variable names, e.g. `[eth_sender.sender] wait_confirmations = 1` becomes
+//!    `ETH_SENDER_SENDER_WAIT_CONFIRMATIONS=1`, and arrays are joined with commas.
+//! 2. Environment variables.
+//!
+//! Thus, the existing env-based deployments work without changes, while the config file allows
+//! to keep the whole configuration in a single place and to override only the relevant values
+//! via environment.
+
+// Built-in uses
+use std::{collections::HashMap, env, fs, path::PathBuf, str::FromStr};
+// External uses
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+/// Name of the environment variable containing the comma-separated list of the config files.
+pub const CONFIG_FILE_VAR: &str = "ZKSYNC_CONFIG_FILE";
+
+/// Error that occurred while loading or validating the configuration.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("Cannot read config file {path}: {error}")]
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    #[error("Cannot parse config file {path}: {error}")]
+    Parse {
+        path: PathBuf,
+        error: toml::de::Error,
+    },
+    #[error("Cannot load config <{name}>: value {variable} is not set (set the environment variable or the corresponding key in the config file)")]
+    Missing { name: String, variable: String },
+    #[error("Cannot load config <{name}>: {error} (check the values with the {prefix} prefix)")]
+    Invalid {
+        name: String,
+        prefix: String,
+        error: String,
+    },
+    #[error("Invalid value of {key}: {reason}")]
+    Validation { key: String, reason: String },
+}
+
+impl ConfigError {
+    pub fn validation(key: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::Validation {
+            key: key.into(),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// List of all the problems found in the configuration.
+#[derive(Debug, Error)]
+#[error("{}", format_errors(.0))]
+pub struct ConfigErrors(pub Vec<ConfigError>);
+
+impl From<ConfigError> for ConfigErrors {
+    fn from(err: ConfigError) -> Self {
+        Self(vec![err])
+    }
+}
+
+fn format_errors(errors: &[ConfigError]) -> String {
+    let mut message = String::from("Invalid configuration:");
+    for err in errors {
+        message.push_str("\n  - ");
+        message.push_str(&err.to_string());
+    }
+    message
+}
+
+/// Flat set of the configuration variables collected from all the layers.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigSource {
+    vars: HashMap<String, String>,
+}
+
+impl ConfigSource {
+    /// Collects the variables from the config files listed in `ZKSYNC_CONFIG_FILE` (if any)
+    /// and from the environment.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let mut source = Self::default();
+        if let Ok(files) = env::var(CONFIG_FILE_VAR) {
+            for path in files
+                .split(',')
+                .map(str::trim)
+                .filter(|path| !path.is_empty())
+            {
+                source = source.with_file(path)?;
+            }
+        }
+
+        Ok(source.with_env())
+    }
+
+    /// Adds the variables from the TOML file, overriding the already set ones.
+    pub fn with_file(self, path: impl Into<PathBuf>) -> Result<Self, ConfigError> {
+        let path = path.into();
+        let contents = fs::read_to_string(&path).map_err(|error| ConfigError::Io {
+            path: path.clone(),
+            error,
+        })?;
+        self.with_toml(&contents)
+            .map_err(|error| ConfigError::Parse { path, error })
+    }
+
+    /// Adds the variables from the TOML document, overriding the already set ones.
+    pub fn with_toml(mut self, contents: &str) -> Result<Self, toml::de::Error> {
+        let table: toml::value::Table = toml::from_str(contents)?;
+        flatten_table("", &table, &mut self.vars);
+        Ok(self)
+    }
+
+    /// Adds the environment variables, overriding the already set ones.
+    pub fn with_env(mut self) -> Self {
+        self.vars.extend(env::vars());
+        self
+    }
+
+    /// Sets a single variable.
+    pub fn with_var(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.vars.insert(name.into(), value.into());
+        self
+    }
+
+    /// Returns the raw value of the variable.
+    pub fn var(&self, name: &str) -> Option<&str> {
+        self.vars.get(name).map(String::as_str)
+    }
+
+    /// Parses the value of the variable.
+    pub fn parse<T>(&self, name: &str) -> Result<T, ConfigError>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        let value = self.var(name).ok_or_else(|| ConfigError::Missing {
+            name: name.to_lowercase(),
+            variable: name.to_string(),
+        })?;
+        value.parse().map_err(|err: T::Err| {
+            ConfigError::validation(name, format!("cannot parse {:?}: {}", value, err))
+        })
+    }
+
+    /// Loads the structure from the variables with the given prefix.
+    pub fn load<T: DeserializeOwned>(&self, name: &str, prefix: &str) -> Result<T, ConfigError> {
+        envy::prefixed(prefix)
+            .from_iter(self.vars.clone())
+            .map_err(|err| match err {
+                envy::Error::MissingValue(field) => ConfigError::Missing {
+                    name: name.to_string(),
+                    variable: format!("{}{}", prefix, field.to_uppercase()),
+                },
+                envy::Error::Custom(error) => ConfigError::Invalid {
+                    name: name.to_string(),
+                    prefix: prefix.to_string(),
+                    error,
+                },
+            })
+    }
+}
+
+/// Flattens the TOML table the same way `zk config compile` does.
+fn flatten_table(prefix: &str, table: &toml::value::Table, vars: &mut HashMap<String, String>) {
+    for (key, value) in table {
+        let name = format!("{}{}", prefix, key.to_uppercase());
+        match value {
+            toml::Value::Table(nested) => flatten_table(&format!("{}_", name), nested, vars),
+            toml::Value::Array(items) => {
+                let items: Vec<_> = items.iter().map(scalar_to_string).collect();
+                vars.insert(name, items.join(","));
+            }
+            scalar => {
+                vars.insert(name, scalar_to_string(scalar));
+            }
+        }
+    }
+}
+
+fn scalar_to_string(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(value) => value.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::eth_sender::GasLimit;
+
+    const CONFIG: &str = r#"
+[eth_sender.gas_price_limit]
+default=400000000000
+update_interval=150
+sample_interval=15
+scale_factor=1.0
+decay_rate=0.25
+decay_floor=1000000000
+
+[chain.state_keeper]
+block_chunk_sizes=[10, 32, 72]
+"#;
+
+    #[test]
+    fn flattens_toml() {
+        let source = ConfigSource::default().with_toml(CONFIG).unwrap();
+
+        assert_eq!(
+            source.var("ETH_SENDER_GAS_PRICE_LIMIT_UPDATE_INTERVAL"),
+            Some("150")
+        );
+        assert_eq!(
+            source.var("CHAIN_STATE_KEEPER_BLOCK_CHUNK_SIZES"),
+            Some("10,32,72")
+        );
+    }
+
+    #[test]
+    fn later_layers_override() {
+        let source = ConfigSource::default()
+            .with_toml(CONFIG)
+            .unwrap()
+            .with_var("ETH_SENDER_GAS_PRICE_LIMIT_SCALE_FACTOR", "2.5");
+
+        let limit: GasLimit = source
+            .load("eth_sender.gas_price_limit", "ETH_SENDER_GAS_PRICE_LIMIT_")
+            .unwrap();
+        assert_eq!(
+            limit,
+            GasLimit {
+                default: 400000000000,
+                update_interval: 150,
+                sample_interval: 15,
+                scale_factor: 2.5,
+                decay_rate: 0.25,
+                decay_floor: 1000000000,
+            }
+        );
+    }
+
+    #[test]
+    fn reports_missing_variable() {
+        let source = ConfigSource::default().with_toml(CONFIG).unwrap();
+
+        let err = source
+            .load::<GasLimit>("eth_sender.gas_price_limit", "ETH_SENDER_MISSING_")
+            .unwrap_err();
+        match err {
+            ConfigError::Missing { variable, .. } => {
+                assert_eq!(variable, "ETH_SENDER_MISSING_DEFAULT")
+            }
+            other => panic!("Unexpected error: {}", other),
+        }
+    }
+}
@@ -0,0 +1,276 @@
+//! Hot-reload of the configuration values that are safe to change on a running server.
+//!
+//! Only the values listed in `ReloadableParams` can be changed without a restart:
+//!
+//! - `FEE_TICKER_FAST_PROCESSING_COEFF`: fee markup for the fast withdrawals.
+//! - `ETH_SENDER_SENDER_TX_POLL_PERIOD`: Ethereum node polling period of `eth_sender`.
+//! - `ETH_SENDER_SENDER_EXPECTED_WAIT_TIME_BLOCK`: amount of blocks after which the
+//!   L1 transaction is considered stuck and is resubmitted.
+//!
+//! Gas price limit options (`ETH_SENDER_GAS_PRICE_LIMIT_*`) are not cached by the `GasAdjuster`
+//! and are re-read from the config source on every update, so they don't need an explicit reload.
+//!
+//! The reload is triggered either by the `SIGHUP` signal or by the admin API request.
+//! Since the environment of a running process can't be changed, new values must be set
+//! in the config files listed in `ZKSYNC_CONFIG_FILE` (and must not be overridden by the
+//! environment variables). New values are validated before being applied, so an incorrect
+//! update leaves the current configuration intact.
+
+// Built-in uses
+use std::{
+    fmt,
+    sync::{Arc, Mutex, RwLock, RwLockReadGuard},
+};
+// External uses
+use serde::Serialize;
+// Local uses
+use crate::{
+    configs::eth_sender::ETHSenderConfig,
+    loader::{ConfigError, ConfigErrors, ConfigSource},
+    ZkSyncConfig,
+};
+
+/// Configuration values that can be changed without a restart.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReloadableParams {
+    /// Coefficient for the fee price for fast withdrawal requests.
+    pub fast_processing_coeff: f64,
+    /// Ethereum node polling period of `eth_sender` in seconds.
+    pub tx_poll_period: u64,
+    /// Amount of blocks we will wait before considering L1 transaction stuck.
+    pub expected_wait_time_block: u64,
+}
+
+impl ReloadableParams {
+    pub fn from_config(config: &ZkSyncConfig) -> Self {
+        Self {
+            fast_processing_coeff: config.ticker.fast_processing_coeff,
+            tx_poll_period: config.eth_sender.sender.tx_poll_period,
+            expected_wait_time_block: config.eth_sender.sender.expected_wait_time_block,
+        }
+    }
+
+    /// Loads the values from the config source and validates them.
+    pub fn load(source: &ConfigSource) -> Result<Self, ConfigErrors> {
+        let mut errors = Vec::new();
+        let fast_processing_coeff = source
+            .parse("FEE_TICKER_FAST_PROCESSING_COEFF")
+            .map_err(|err| errors.push(err));
+        let tx_poll_period = source
+            .parse("ETH_SENDER_SENDER_TX_POLL_PERIOD")
+            .map_err(|err| errors.push(err));
+        let expected_wait_time_block = source
+            .parse("ETH_SENDER_SENDER_EXPECTED_WAIT_TIME_BLOCK")
+            .map_err(|err| errors.push(err));
+
+        let params = match (
+            fast_processing_coeff,
+            tx_poll_period,
+            expected_wait_time_block,
+        ) {
+            (Ok(fast_processing_coeff), Ok(tx_poll_period), Ok(expected_wait_time_block)) => Self {
+                fast_processing_coeff,
+                tx_poll_period,
+                expected_wait_time_block,
+            },
+            _ => return Err(ConfigErrors(errors)),
+        };
+        params.validate()?;
+        Ok(params)
+    }
+
+    /// Checks the values the same way as `ZkSyncConfig::validate` does.
+    pub fn validate(&self) -> Result<(), ConfigErrors> {
+        let mut errors = Vec::new();
+        if self.fast_processing_coeff < 1.0 {
+            errors.push(ConfigError::validation(
+                "FEE_TICKER_FAST_PROCESSING_COEFF",
+                "must be at least 1.0, otherwise fast withdrawals are cheaper than the regular ones",
+            ));
+        }
+        if self.expected_wait_time_block == 0 {
+            errors.push(ConfigError::validation(
+                "ETH_SENDER_SENDER_EXPECTED_WAIT_TIME_BLOCK",
+                "must be positive, otherwise every transaction is considered stuck",
+            ));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigErrors(errors))
+        }
+    }
+
+    /// Updates the reloadable values of the `eth_sender` config.
+    pub fn apply_to_eth_sender(&self, config: &mut ETHSenderConfig) {
+        config.sender.tx_poll_period = self.tx_poll_period;
+        config.sender.expected_wait_time_block = self.expected_wait_time_block;
+    }
+}
+
+/// Shared value that may be updated while the server is running.
+#[derive(Debug, Default)]
+pub struct Reloadable<T> {
+    inner: Arc<RwLock<T>>,
+}
+
+impl<T> Clone for Reloadable<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T> From<T> for Reloadable<T> {
+    fn from(value: T) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(value)),
+        }
+    }
+}
+
+impl<T> Reloadable<T> {
+    /// Locks the current value for reading. The guard must not be held across `.await` points.
+    pub fn read(&self) -> RwLockReadGuard<'_, T> {
+        self.inner.read().unwrap()
+    }
+
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        f(&mut self.inner.write().unwrap());
+    }
+}
+
+impl<T: Clone> Reloadable<T> {
+    /// Returns a copy of the current value.
+    pub fn get(&self) -> T {
+        self.read().clone()
+    }
+}
+
+type Listener = Box<dyn Fn(&ReloadableParams) + Send + Sync>;
+
+/// Reloads the `ReloadableParams` and notifies the subscribed components about the changes.
+#[derive(Clone)]
+pub struct ConfigReloader {
+    current: Arc<Mutex<ReloadableParams>>,
+    listeners: Arc<Mutex<Vec<Listener>>>,
+}
+
+impl fmt::Debug for ConfigReloader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfigReloader")
+            .field("current", &self.current)
+            .finish()
+    }
+}
+
+impl ConfigReloader {
+    pub fn new(config: &ZkSyncConfig) -> Self {
+        Self {
+            current: Arc::new(Mutex::new(ReloadableParams::from_config(config))),
+            listeners: Arc::default(),
+        }
+    }
+
+    /// Returns the currently applied values.
+    pub fn current(&self) -> ReloadableParams {
+        self.current.lock().unwrap().clone()
+    }
+
+    /// Registers a callback invoked every time the values are changed.
+    pub fn subscribe(&self, listener: impl Fn(&ReloadableParams) + Send + Sync + 'static) {
+        self.listeners.lock().unwrap().push(Box::new(listener));
+    }
+
+    /// Re-reads the config files and the environment and applies the new values.
+    pub fn reload(&self) -> Result<ReloadableParams, ConfigErrors> {
+        let source = ConfigSource::from_env()?;
+        self.reload_from(&source)
+    }
+
+    /// Applies the values from the provided config source.
+    pub fn reload_from(&self, source: &ConfigSource) -> Result<ReloadableParams, ConfigErrors> {
+        let params = ReloadableParams::load(source)?;
+
+        let mut current = self.current.lock().unwrap();
+        if *current != params {
+            tracing::info!("Applying reloaded config: {:?}", params);
+            *current = params.clone();
+            for listener in self.listeners.lock().unwrap().iter() {
+                listener(&params);
+            }
+        }
+        Ok(params)
+    }
+
+    /// Spawns a task reloading the config every time the process receives `SIGHUP`.
+    #[cfg(unix)]
+    pub fn reload_on_sighup(self) -> tokio::task::JoinHandle<()> {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        tokio::spawn(async move {
+            let mut hangups = signal(SignalKind::hangup()).expect("Cannot listen to SIGHUP");
+            while hangups.recv().await.is_some() {
+                if let Err(errors) = self.reload() {
+                    tracing::error!("Config is not reloaded. {}", errors);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> ReloadableParams {
+        ReloadableParams {
+            fast_processing_coeff: 10.0,
+            tx_poll_period: 3,
+            expected_wait_time_block: 30,
+        }
+    }
+
+    fn reloader() -> ConfigReloader {
+        ConfigReloader {
+            current: Arc::new(Mutex::new(params())),
+            listeners: Arc::default(),
+        }
+    }
+
+    fn source(coeff: &str) -> ConfigSource {
+        ConfigSource::default()
+            .with_var("FEE_TICKER_FAST_PROCESSING_COEFF", coeff)
+            .with_var("ETH_SENDER_SENDER_TX_POLL_PERIOD", "3")
+            .with_var("ETH_SENDER_SENDER_EXPECTED_WAIT_TIME_BLOCK", "30")
+    }
+
+    #[test]
+    fn notifies_subscribers() {
+        let reloader = reloader();
+        let coeff = Reloadable::from(0.0);
+        reloader.subscribe({
+            let coeff = coeff.clone();
+            move |params| coeff.update(|coeff| *coeff = params.fast_processing_coeff)
+        });
+
+        let params = reloader.reload_from(&source("15")).unwrap();
+        assert_eq!(params.fast_processing_coeff, 15.0);
+        assert_eq!(reloader.current(), params);
+        assert_eq!(coeff.get(), 15.0);
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        let reloader = reloader();
+
+        let errors = reloader.reload_from(&source("0.5")).unwrap_err();
+        assert_eq!(errors.0.len(), 1);
+        assert_eq!(reloader.current(), params());
+
+        let errors = reloader.reload_from(&ConfigSource::default()).unwrap_err();
+        assert_eq!(errors.0.len(), 3);
+    }
+}
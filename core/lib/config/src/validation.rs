@@ -0,0 +1,247 @@
+//! Consistency checks of the loaded configuration.
+//!
+//! Deserialization only ensures that every value has the correct type, while some values
+//! are only meaningful together (e.g. block sizes must be supported by the circuit).
+//! Such problems are reported at startup instead of making the server fail later.
+
+// Built-in uses
+use std::collections::HashMap;
+// Local uses
+use crate::{
+    configs::{
+        chain::ChainConfig,
+        eth_sender::{GasLimit, Sender},
+        ApiConfig, ETHClientConfig, TickerConfig,
+    },
+    loader::{ConfigError, ConfigErrors},
+    ZkSyncConfig,
+};
+
+impl ZkSyncConfig {
+    /// Checks the configuration, returning all the found problems.
+    pub fn validate(&self) -> Result<(), ConfigErrors> {
+        let mut errors = Vec::new();
+        validate_chain(&self.chain, &mut errors);
+        validate_api(&self.api, &mut errors);
+        validate_eth_client(&self.eth_client, &mut errors);
+        validate_sender(&self.eth_sender.sender, &mut errors);
+        validate_gas_limit(&self.eth_sender.gas_price_limit, &mut errors);
+        validate_ticker(&self.ticker, &mut errors);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigErrors(errors))
+        }
+    }
+}
+
+fn validate_chain(config: &ChainConfig, errors: &mut Vec<ConfigError>) {
+    let circuit = &config.circuit;
+    if circuit.supported_block_chunks_sizes.len()
+        != circuit.supported_block_chunks_sizes_setup_powers.len()
+    {
+        errors.push(ConfigError::validation(
+            "CHAIN_CIRCUIT_SUPPORTED_BLOCK_CHUNKS_SIZES_SETUP_POWERS",
+            "must have a setup power for each of the supported block sizes",
+        ));
+    }
+    if circuit.supported_aggregated_proof_sizes.len()
+        != circuit.supported_aggregated_proof_sizes_setup_power2.len()
+    {
+        errors.push(ConfigError::validation(
+            "CHAIN_CIRCUIT_SUPPORTED_AGGREGATED_PROOF_SIZES_SETUP_POWER2",
+            "must have a setup power for each of the supported aggregated proof sizes",
+        ));
+    }
+
+    let block_chunk_sizes = &config.state_keeper.block_chunk_sizes;
+    if block_chunk_sizes.is_empty() {
+        errors.push(ConfigError::validation(
+            "CHAIN_STATE_KEEPER_BLOCK_CHUNK_SIZES",
+            "at least one block size must be set",
+        ));
+    }
+    if block_chunk_sizes
+        .windows(2)
+        .any(|sizes| sizes[0] >= sizes[1])
+    {
+        errors.push(ConfigError::validation(
+            "CHAIN_STATE_KEEPER_BLOCK_CHUNK_SIZES",
+            "block sizes must be sorted in ascending order",
+        ));
+    }
+    for size in block_chunk_sizes {
+        if !circuit.supported_block_chunks_sizes.contains(size) {
+            errors.push(ConfigError::validation(
+                "CHAIN_STATE_KEEPER_BLOCK_CHUNK_SIZES",
+                format!(
+                    "block size {} is not in CHAIN_CIRCUIT_SUPPORTED_BLOCK_CHUNKS_SIZES, such blocks will never be proven",
+                    size
+                ),
+            ));
+        }
+    }
+    if config.state_keeper.miniblock_iterations == 0 {
+        errors.push(ConfigError::validation(
+            "CHAIN_STATE_KEEPER_MINIBLOCK_ITERATIONS",
+            "must be positive",
+        ));
+    }
+    if config.state_keeper.adaptive_block_time {
+        if config.state_keeper.target_block_time == 0 {
+            errors.push(ConfigError::validation(
+                "CHAIN_STATE_KEEPER_TARGET_BLOCK_TIME",
+                "must be positive",
+            ));
+        }
+        if config.state_keeper.min_miniblock_iterations == 0
+            || config.state_keeper.min_miniblock_iterations
+                > config.state_keeper.max_miniblock_iterations
+        {
+            errors.push(ConfigError::validation(
+                "CHAIN_STATE_KEEPER_MIN_MINIBLOCK_ITERATIONS",
+                "must be positive and not greater than CHAIN_STATE_KEEPER_MAX_MINIBLOCK_ITERATIONS",
+            ));
+        }
+    }
+    if let Err(err) = config.mempool.amount_bounds() {
+        errors.push(ConfigError::validation(
+            "CHAIN_MEMPOOL_AMOUNT_BOUNDS",
+            err.to_string(),
+        ));
+    }
+    if let Err(err) = config.fee_sweep.thresholds() {
+        errors.push(ConfigError::validation(
+            "CHAIN_FEE_SWEEP_THRESHOLDS",
+            err.to_string(),
+        ));
+    }
+    if (config.fee_sweep.enabled || config.fee_refund.enabled) && config.fee_sweep.interval == 0 {
+        errors.push(ConfigError::validation(
+            "CHAIN_FEE_SWEEP_INTERVAL",
+            "must be positive",
+        ));
+    }
+}
+
+fn validate_api(config: &ApiConfig, errors: &mut Vec<ConfigError>) {
+    let mut ports = vec![
+        ("API_ADMIN_PORT", config.admin.port),
+        ("API_REST_PORT", config.rest.port),
+        ("API_JSON_RPC_HTTP_PORT", config.json_rpc.http_port),
+        ("API_JSON_RPC_WS_PORT", config.json_rpc.ws_port),
+        ("API_PRIVATE_PORT", config.private.port),
+        ("API_PROVER_PORT", config.prover.port),
+        ("API_PROMETHEUS_PORT", config.prometheus.port),
+    ];
+    if config.prover.grpc_enabled {
+        ports.push(("API_PROVER_GRPC_PORT", config.prover.grpc_port));
+    }
+    let cors_options = [
+        (
+            "API_REST_CORS_ALLOWED_ORIGINS",
+            &config.rest.cors_allowed_origins,
+        ),
+        (
+            "API_REST_FORCED_EXIT_CORS_ALLOWED_ORIGINS",
+            &config.rest.forced_exit_cors_allowed_origins,
+        ),
+    ];
+    for (key, origins) in cors_options.iter() {
+        if origins.is_empty() {
+            errors.push(ConfigError::validation(
+                *key,
+                "at least one origin must be set, use \"*\" to allow any origin",
+            ));
+        }
+    }
+
+    let mut used_ports = HashMap::new();
+    for (key, port) in ports.iter() {
+        if let Some(other_key) = used_ports.insert(port, key) {
+            errors.push(ConfigError::validation(
+                *key,
+                format!("port {} is already used by {}", port, other_key),
+            ));
+        }
+    }
+}
+
+fn validate_eth_client(config: &ETHClientConfig, errors: &mut Vec<ConfigError>) {
+    if config.web3_url.is_empty() {
+        errors.push(ConfigError::validation(
+            "ETH_CLIENT_WEB3_URL",
+            "at least one Ethereum node URL must be set",
+        ));
+    }
+    if config.gas_price_factor <= 0.0 {
+        errors.push(ConfigError::validation(
+            "ETH_CLIENT_GAS_PRICE_FACTOR",
+            "must be positive",
+        ));
+    }
+}
+
+fn validate_sender(config: &Sender, errors: &mut Vec<ConfigError>) {
+    if config.max_txs_in_flight == 0 {
+        errors.push(ConfigError::validation(
+            "ETH_SENDER_SENDER_MAX_TXS_IN_FLIGHT",
+            "must be positive, otherwise no transactions will be sent",
+        ));
+    }
+    if config.wait_confirmations == 0 {
+        errors.push(ConfigError::validation(
+            "ETH_SENDER_SENDER_WAIT_CONFIRMATIONS",
+            "must be positive",
+        ));
+    }
+    if config.expected_wait_time_block == 0 {
+        errors.push(ConfigError::validation(
+            "ETH_SENDER_SENDER_EXPECTED_WAIT_TIME_BLOCK",
+            "must be positive, otherwise every transaction is considered stuck",
+        ));
+    }
+}
+
+fn validate_gas_limit(config: &GasLimit, errors: &mut Vec<ConfigError>) {
+    if config.scale_factor <= 0.0 {
+        errors.push(ConfigError::validation(
+            "ETH_SENDER_GAS_PRICE_LIMIT_SCALE_FACTOR",
+            "must be positive",
+        ));
+    }
+    if !(0.0..=1.0).contains(&config.decay_rate) {
+        errors.push(ConfigError::validation(
+            "ETH_SENDER_GAS_PRICE_LIMIT_DECAY_RATE",
+            "must be within [0, 1]",
+        ));
+    }
+}
+
+fn validate_ticker(config: &TickerConfig, errors: &mut Vec<ConfigError>) {
+    if config.fast_processing_coeff < 1.0 {
+        errors.push(ConfigError::validation(
+            "FEE_TICKER_FAST_PROCESSING_COEFF",
+            "must be at least 1.0, otherwise fast withdrawals are cheaper than the regular ones",
+        ));
+    }
+    if config.number_of_ticker_actors == 0 {
+        errors.push(ConfigError::validation(
+            "FEE_TICKER_NUMBER_OF_TICKER_ACTORS",
+            "must be positive",
+        ));
+    }
+    if config.subsidized_tokens.len() != config.subsidized_tokens_limits.len() {
+        errors.push(ConfigError::validation(
+            "FEE_TICKER_SUBSIDIZED_TOKENS_LIMITS",
+            "must have a limit for each of FEE_TICKER_SUBSIDIZED_TOKENS",
+        ));
+    }
+    if config.fee_markup_tokens.len() != config.fee_markup_percents.len() {
+        errors.push(ConfigError::validation(
+            "FEE_TICKER_FEE_MARKUP_PERCENTS",
+            "must have a markup for each of FEE_TICKER_FEE_MARKUP_TOKENS",
+        ));
+    }
+}
@@ -19,6 +19,8 @@ const UPGRADE_GATEKEEPER_CONTRACT_FILE: &str =
     "contracts/artifacts/cache/solpp-generated-contracts/UpgradeGatekeeper.sol/UpgradeGatekeeper.json";
 const FORCED_EXIT_CONTRACT_FILE: &str =
     "contracts/artifacts/cache/solpp-generated-contracts/ForcedExit.sol/ForcedExit.json";
+const ITOKEN_RESTRICTIONS_CONTRACT_FILE: &str =
+    "contracts/artifacts/cache/solpp-generated-contracts/dev-contracts/ITokenRestrictions.sol/ITokenRestrictions.json";
 
 fn read_file_to_json_value(path: &str) -> io::Result<serde_json::Value> {
     let zksync_home = std::env::var("ZKSYNC_HOME").unwrap_or_else(|_| ".".into());
@@ -116,3 +118,12 @@ pub fn forced_exit_contract() -> Contract {
         .to_string();
     Contract::load(abi_string.as_bytes()).expect("forced_exit contract abi")
 }
+
+pub fn token_restrictions_contract() -> Contract {
+    let abi_string = read_file_to_json_value(ITOKEN_RESTRICTIONS_CONTRACT_FILE)
+        .expect("couldn't read ITOKEN_RESTRICTIONS_CONTRACT_FILE")
+        .get("abi")
+        .expect("couldn't get abi from ITOKEN_RESTRICTIONS_CONTRACT_FILE")
+        .to_string();
+    Contract::load(abi_string.as_bytes()).expect("token restrictions contract abi")
+}
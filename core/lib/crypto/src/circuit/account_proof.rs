@@ -0,0 +1,219 @@
+//! Proofs of the account token balances against the state root.
+//!
+//! The state root commits to the account tree, and every account leaf commits to the root of
+//! the account balance tree. Thus the balance proof consists of two Merkle paths: from the
+//! balance leaf to the root of the balance tree, and from the account leaf to the state root.
+//! The proofs can be checked against the roots published on L1 without trusting the server.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    circuit::account::{Balance, CircuitAccount},
+    franklin_crypto::bellman::pairing::ff::Field,
+    merkle_tree::{hasher::Hasher, RescueHasher, SparseMerkleTree},
+    params,
+    primitives::{GetBits, GetBitsFixed},
+    serialization::FrSerde,
+    Engine, Fr,
+};
+
+/// Node of the Merkle path. Paths are ordered from the leaf level to the root.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MerklePathNode {
+    /// Hash of the sibling node.
+    #[serde(with = "FrSerde")]
+    pub sibling: Fr,
+    /// Whether the node on the path is the right child of its parent.
+    pub is_right: bool,
+}
+
+impl From<(Fr, bool)> for MerklePathNode {
+    fn from((sibling, is_right): (Fr, bool)) -> Self {
+        Self { sibling, is_right }
+    }
+}
+
+/// Proof of the token balance of the account.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStateProof {
+    pub account_id: u32,
+    pub token_id: u32,
+    #[serde(with = "FrSerde")]
+    pub nonce: Fr,
+    #[serde(with = "FrSerde")]
+    pub pub_key_hash: Fr,
+    #[serde(with = "FrSerde")]
+    pub address: Fr,
+    #[serde(with = "FrSerde")]
+    pub balance: Fr,
+    /// Path from the balance leaf to the root of the account balance tree.
+    pub balance_path: Vec<MerklePathNode>,
+    /// Path from the account leaf to the state root.
+    pub account_path: Vec<MerklePathNode>,
+}
+
+impl AccountStateProof {
+    /// Creates a proof for the token balance of the account stored in the tree.
+    ///
+    /// `account` must be the circuit representation of the tree item with `account_id` index.
+    pub fn new<T>(
+        tree: &SparseMerkleTree<T, Fr, RescueHasher<Engine>>,
+        account_id: u32,
+        account: &CircuitAccount<Engine>,
+        token_id: u32,
+    ) -> Self
+    where
+        T: GetBits + Default + Sync,
+    {
+        let balance = account
+            .subtree
+            .get(token_id)
+            .map(|balance| balance.value)
+            .unwrap_or_else(Fr::zero);
+
+        Self {
+            account_id,
+            token_id,
+            nonce: account.nonce,
+            pub_key_hash: account.pub_key_hash,
+            address: account.address,
+            balance,
+            balance_path: into_path(account.subtree.merkle_path(token_id)),
+            account_path: into_path(tree.merkle_path(account_id)),
+        }
+    }
+
+    /// Computes the state root committed to by the proof.
+    ///
+    /// Returns `None` if the paths are malformed, e.g. do not lead to the leaves
+    /// with the claimed indices.
+    pub fn compute_root(&self) -> Option<Fr> {
+        let hasher = RescueHasher::<Engine>::default();
+
+        let balance_leaf = Balance::<Engine> {
+            value: self.balance,
+        };
+        let balance_root = fold_path(
+            &hasher,
+            hasher.hash_bits(balance_leaf.get_bits_le()),
+            &self.balance_path,
+            self.token_id,
+            params::balance_tree_depth(),
+        )?;
+        let state_root = hasher.hash_elements(vec![balance_root, Fr::zero()]);
+
+        let mut account_leaf = Vec::with_capacity(params::LEAF_DATA_BIT_WIDTH);
+        account_leaf.extend(self.nonce.get_bits_le_fixed(params::NONCE_BIT_WIDTH));
+        account_leaf.extend(
+            self.pub_key_hash
+                .get_bits_le_fixed(params::NEW_PUBKEY_HASH_WIDTH),
+        );
+        account_leaf.extend(self.address.get_bits_le_fixed(params::ADDRESS_WIDTH));
+        let mut state_root_bits = state_root.get_bits_le_fixed(params::FR_BIT_WIDTH);
+        state_root_bits.resize(params::FR_BIT_WIDTH_PADDED, false);
+        account_leaf.extend(state_root_bits);
+
+        fold_path(
+            &hasher,
+            hasher.hash_bits(account_leaf),
+            &self.account_path,
+            self.account_id,
+            params::account_tree_depth(),
+        )
+    }
+
+    /// Checks that the proof is valid for the given state root.
+    pub fn verify(&self, root: Fr) -> bool {
+        self.compute_root() == Some(root)
+    }
+}
+
+fn into_path(path: Vec<(Fr, bool)>) -> Vec<MerklePathNode> {
+    path.into_iter().map(MerklePathNode::from).collect()
+}
+
+/// Hashes the leaf along the path, checking that the path leads to the leaf with the given index.
+fn fold_path(
+    hasher: &RescueHasher<Engine>,
+    leaf_hash: Fr,
+    path: &[MerklePathNode],
+    index: u32,
+    depth: usize,
+) -> Option<Fr> {
+    if path.len() != depth {
+        return None;
+    }
+
+    let mut path_index = 0u64;
+    let mut hash = leaf_hash;
+    for (level, node) in path.iter().enumerate() {
+        let (lhs, rhs) = if node.is_right {
+            path_index |= 1 << level;
+            (node.sibling, hash)
+        } else {
+            (hash, node.sibling)
+        };
+        hash = hasher.compress(&lhs, &rhs, level);
+    }
+
+    if path_index == u64::from(index) {
+        Some(hash)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{circuit::CircuitAccountTree, franklin_crypto::bellman::pairing::ff::PrimeField};
+
+    fn account(nonce: &str, balances: &[(u32, &str)]) -> CircuitAccount<Engine> {
+        let mut account = CircuitAccount::default();
+        account.nonce = Fr::from_str(nonce).unwrap();
+        account.address = Fr::from_str("42").unwrap();
+        for (token, value) in balances {
+            account.subtree.insert(
+                *token,
+                Balance {
+                    value: Fr::from_str(value).unwrap(),
+                },
+            );
+        }
+        account
+    }
+
+    #[test]
+    fn account_state_proof() {
+        let mut tree = CircuitAccountTree::new(params::account_tree_depth());
+        let first = account("1", &[(0, "100"), (3, "5")]);
+        let second = account("7", &[(3, "25")]);
+        tree.insert(0, first);
+        tree.insert(5, second.clone());
+        let root = tree.root_hash();
+
+        let proof = AccountStateProof::new(&tree, 5, &second, 3);
+        assert_eq!(proof.balance, Fr::from_str("25").unwrap());
+        assert!(proof.verify(root));
+
+        // Zero balances can be proven as well.
+        let proof_of_zero = AccountStateProof::new(&tree, 5, &second, 0);
+        assert!(proof_of_zero.balance.is_zero());
+        assert!(proof_of_zero.verify(root));
+
+        let mut forged = proof.clone();
+        forged.balance = Fr::from_str("26").unwrap();
+        assert!(!forged.verify(root));
+
+        // The path must lead to the claimed account.
+        let mut forged = proof.clone();
+        forged.account_id = 4;
+        assert!(!forged.verify(root));
+
+        let mut forged = proof;
+        forged.account_path.pop();
+        assert_eq!(forged.compute_root(), None);
+    }
+}
@@ -1,4 +1,5 @@
 pub use account::CircuitAccountTree;
 
 pub mod account;
+pub mod account_proof;
 pub mod utils;
@@ -6,4 +6,58 @@ pub trait Hasher<Hash> {
     fn hash_elements<I: IntoIterator<Item = Hash>>(&self, elements: I) -> Hash;
     /// Merges two hashes into one.
     fn compress(&self, lhs: &Hash, rhs: &Hash, i: usize) -> Hash;
+
+    /// Gets the hashes of several independent bit sequences.
+    /// The result is the same as of `hash_bits` applied to every sequence, but
+    /// the backend may compute the hashes in parallel.
+    fn hash_bits_batch(&self, values: Vec<Vec<bool>>) -> Vec<Hash> {
+        values
+            .into_iter()
+            .map(|value| self.hash_bits(value))
+            .collect()
+    }
+}
+
+/// Strategy used by the hasher to process the batches of independent inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HasherBackend {
+    /// Inputs are hashed one by one in the current thread.
+    Sequential,
+    /// Inputs are distributed between the threads of the `rayon` thread pool.
+    /// Batches smaller than `min_batch_size` are hashed sequentially, since
+    /// the scheduling overhead is bigger than the gain for them.
+    Parallel { min_batch_size: usize },
+}
+
+impl HasherBackend {
+    /// Minimal batch size worth parallelizing for the Rescue hash.
+    pub const DEFAULT_MIN_BATCH_SIZE: usize = 16;
+
+    /// Returns `true` if the batch of the given size should be hashed in parallel.
+    pub fn is_parallel(self, batch_size: usize) -> bool {
+        match self {
+            Self::Sequential => false,
+            Self::Parallel { min_batch_size } => batch_size >= min_batch_size,
+        }
+    }
+}
+
+impl Default for HasherBackend {
+    fn default() -> Self {
+        Self::Parallel {
+            min_batch_size: Self::DEFAULT_MIN_BATCH_SIZE,
+        }
+    }
+}
+
+impl std::str::FromStr for HasherBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sequential" => Ok(Self::Sequential),
+            "parallel" => Ok(Self::default()),
+            other => Err(format!("Unknown hasher backend: {}", other)),
+        }
+    }
 }
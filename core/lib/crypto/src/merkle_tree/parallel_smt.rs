@@ -165,8 +165,20 @@ where
     /// capacity of the tree, since the given height will not be
     /// exceeded).
     pub fn new(tree_depth: Depth) -> Self {
+        Self::with_hasher(tree_depth, H::default())
+    }
+}
+
+impl<T, Hash, H> SparseMerkleTree<T, Hash, H>
+where
+    T: GetBits + Default,
+    Hash: Clone + Debug,
+    H: Hasher<Hash>,
+{
+    /// Creates a new tree of certain depth using the provided hasher,
+    /// e.g. one with a non-default hashing backend.
+    pub fn with_hasher(tree_depth: Depth, hasher: H) -> Self {
         assert!(tree_depth > 1);
-        let hasher = H::default();
         let items = FnvHashMap::default();
         let nodes = vec![Node {
             index: NodeIndex(1),
@@ -381,6 +393,36 @@ where
         }
     }
 
+    /// Inserts several elements to the tree.
+    ///
+    /// Unlike the sequence of `insert` calls, the hashes of the inserted leaves are
+    /// calculated at once via `Hasher::hash_bits_batch`, which lets the hasher backend
+    /// process them in parallel. It's preferable for the big updates, e.g. when the tree
+    /// is restored from the database.
+    pub fn insert_many(&mut self, items: impl IntoIterator<Item = (u32, T)>) {
+        let mut item_indices = Vec::new();
+        for (item_index, item) in items {
+            self.insert(item_index, item);
+            item_indices.push(item_index as ItemIndex);
+        }
+        item_indices.sort_unstable();
+        item_indices.dedup();
+
+        let leaves_bits = item_indices
+            .iter()
+            .map(|item_index| self.items[item_index].get_bits_le())
+            .collect();
+        let leaves_hashes = self.hasher.hash_bits_batch(leaves_bits);
+
+        // The leaves are not cached after the insertion, so their hashes can be stored
+        // in the cache directly. All the parent nodes are already invalidated.
+        let mut cache = self.cache.write().expect("write lock");
+        for (item_index, hash) in item_indices.into_iter().zip(leaves_hashes) {
+            let leaf_index = NodeIndex((1 << self.tree_depth) + item_index);
+            cache.insert(leaf_index, hash);
+        }
+    }
+
     /// Removes an element with a given index, and returns the removed
     /// element (if it existed in the tree).
     pub fn remove(&mut self, index: u32) -> Option<T> {
@@ -631,8 +673,18 @@ where
                 // leaf node: return item hash
                 let item_index: ItemIndex = (node.index.0 - (1 << self.tree_depth)) as ItemIndex;
 
-                let item_bits = self.items[&item_index].get_bits_le();
-                let item_hash = self.hasher.hash_bits(item_bits);
+                // The leaf hash may be cached by `insert_many` even if the leaf is not
+                // a direct child of its parent node, so the cache is checked here as well.
+                let cached = self
+                    .cache
+                    .read()
+                    .expect("Read lock")
+                    .get(&node.index)
+                    .cloned();
+                let item_hash = cached.unwrap_or_else(|| {
+                    let item_bits = self.items[&item_index].get_bits_le();
+                    self.hasher.hash_bits(item_bits)
+                });
 
                 // There are no underlying updates for leaf node.
                 let updates = vec![];
@@ -6,17 +6,21 @@ use crate::franklin_crypto::{
     rescue::{rescue_hash, RescueEngine},
 };
 
-use super::hasher::Hasher;
+use super::hasher::{Hasher, HasherBackend};
 use core::fmt;
+use rayon::prelude::*;
 
 /// Default hasher for the zkSync state hash calculation.
 pub struct RescueHasher<E: RescueEngine> {
     params: &'static E::Params,
+    backend: HasherBackend,
 }
 
 impl<E: RescueEngine> fmt::Debug for RescueHasher<E> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("RescueHasher").finish()
+        f.debug_struct("RescueHasher")
+            .field("backend", &self.backend)
+            .finish()
     }
 }
 
@@ -28,10 +32,22 @@ impl<E: RescueEngine> Clone for RescueHasher<E> {
     fn clone(&self) -> Self {
         Self {
             params: self.params,
+            backend: self.backend,
         }
     }
 }
 
+impl<E: RescueEngine> RescueHasher<E> {
+    /// Returns the hasher processing the batches with the given backend.
+    pub fn with_backend(self, backend: HasherBackend) -> Self {
+        Self { backend, ..self }
+    }
+
+    pub fn backend(&self) -> HasherBackend {
+        self.backend
+    }
+}
+
 impl<E: RescueEngine> Hasher<E::Fr> for RescueHasher<E> {
     fn hash_bits<I: IntoIterator<Item = bool>>(&self, input: I) -> E::Fr {
         let bits: Vec<bool> = input.into_iter().collect();
@@ -56,6 +72,20 @@ impl<E: RescueEngine> Hasher<E::Fr> for RescueHasher<E> {
         assert_eq!(sponge_output.len(), 1);
         sponge_output[0]
     }
+
+    fn hash_bits_batch(&self, values: Vec<Vec<bool>>) -> Vec<E::Fr> {
+        if self.backend.is_parallel(values.len()) {
+            values
+                .into_par_iter()
+                .map(|value| self.hash_bits(value))
+                .collect()
+        } else {
+            values
+                .into_iter()
+                .map(|value| self.hash_bits(value))
+                .collect()
+        }
+    }
 }
 
 pub type BabyRescueHasher = RescueHasher<Bn256>;
@@ -64,6 +94,7 @@ impl Default for RescueHasher<Bn256> {
     fn default() -> Self {
         Self {
             params: &crate::params::RESCUE_PARAMS,
+            backend: HasherBackend::default(),
         }
     }
 }
@@ -76,3 +107,20 @@ fn test_resue_hash() {
     hasher.compress(&hash, &hash, 0);
     hasher.compress(&hash, &hash, 1);
 }
+
+#[test]
+fn test_rescue_hash_batch() {
+    let inputs: Vec<Vec<bool>> = (0..32u8)
+        .map(|i| (0..8).map(|bit| i & (1 << bit) != 0).collect())
+        .collect();
+
+    let parallel = BabyRescueHasher::default();
+    let sequential = BabyRescueHasher::default().with_backend(HasherBackend::Sequential);
+
+    let expected: Vec<_> = inputs
+        .iter()
+        .map(|input| sequential.hash_bits(input.clone()))
+        .collect();
+    assert_eq!(parallel.hash_bits_batch(inputs.clone()), expected);
+    assert_eq!(sequential.hash_bits_batch(inputs), expected);
+}
@@ -1,5 +1,5 @@
 use crate::{
-    merkle_tree::{parallel_smt, RescueHasher},
+    merkle_tree::{hasher::HasherBackend, parallel_smt, RescueHasher},
     rand::{Rng, SeedableRng, XorShiftRng},
     Engine, Fr,
 };
@@ -169,3 +169,37 @@ fn big_test() {
     let root_hash: Fr = crate::ff::from_hex(&input.root_hash).unwrap();
     assert_eq!(root_hash, tree.root_hash());
 }
+
+/// Checks that the batch insertion results in the same tree as the sequential one,
+/// regardless of the hasher backend.
+#[test]
+fn insert_many() {
+    let depth = 8;
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let elements: Vec<(u32, u64)> = (0..100)
+        .map(|_| (rng.gen_range(0, 1 << depth), rng.gen()))
+        .collect();
+
+    let mut expected_tree =
+        parallel_smt::SparseMerkleTree::<u64, Fr, RescueHasher<Engine>>::new(depth);
+    for (idx, item) in &elements {
+        expected_tree.insert(*idx, *item);
+    }
+    let expected_root = expected_tree.root_hash();
+
+    for backend in vec![HasherBackend::Sequential, HasherBackend::default()] {
+        let hasher = RescueHasher::<Engine>::default().with_backend(backend);
+        let mut tree = parallel_smt::SparseMerkleTree::<u64, Fr, _>::with_hasher(depth, hasher);
+        tree.insert_many(elements.clone());
+        assert_eq!(tree.root_hash(), expected_root);
+
+        // Update the existing leaves once the hashes are cached.
+        tree.insert_many(elements.iter().take(10).map(|(idx, item)| (*idx, item + 1)));
+        let mut expected_tree = expected_tree.clone();
+        for (idx, item) in elements.iter().take(10) {
+            expected_tree.insert(*idx, item + 1);
+        }
+        assert_eq!(tree.root_hash(), expected_tree.root_hash());
+    }
+}
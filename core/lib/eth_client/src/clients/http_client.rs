@@ -391,6 +391,7 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
                     .saturating_sub(tx_block_number)
                     .as_u64();
                 let success = status.as_u64() == 1;
+                let gas_used = receipt.as_ref().and_then(|receipt| receipt.gas_used);
 
                 // Set the receipt only for failures.
                 let receipt = if success {
@@ -403,6 +404,7 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
                     confirmations,
                     success,
                     receipt,
+                    gas_used,
                 }))
             }
             _ => Ok(None),
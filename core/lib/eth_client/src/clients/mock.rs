@@ -5,7 +5,7 @@ use ethabi::{Address, Contract};
 use std::collections::{HashMap, HashSet};
 use tokio::sync::RwLock;
 use web3::contract::tokens::{Detokenize, Tokenize};
-use web3::contract::Options;
+use web3::contract::{Error as ContractError, Options};
 use web3::transports::Http;
 use web3::types::{BlockId, Filter, Log, Transaction, U64};
 
@@ -16,12 +16,24 @@ use crate::{
     SignedCallResult,
 };
 
+/// Response of the mocked contract function call.
+#[derive(Debug, Clone)]
+pub enum MockCallResponse {
+    /// Values returned by the function.
+    Output(Vec<ethabi::Token>),
+    /// Call is reverted, e.g. the function is not implemented by the contract.
+    Revert,
+    /// Ethereum node can't be reached.
+    Unavailable,
+}
+
 #[derive(Debug)]
 struct MockEthereumInner {
     block_number: u64,
     gas_price: U256,
     tx_statuses: Arc<RwLock<HashMap<H256, ExecutedTxStatus>>>,
     sent_txs: Arc<RwLock<HashSet<Vec<u8>>>>,
+    contract_calls: Arc<RwLock<HashMap<(Address, String), MockCallResponse>>>,
 }
 
 /// Mock Ethereum client is capable of recording all the incoming requests for the further analysis.
@@ -37,6 +49,7 @@ impl Default for MockEthereumInner {
             gas_price: 100.into(),
             tx_statuses: Default::default(),
             sent_txs: Default::default(),
+            contract_calls: Default::default(),
         }
     }
 }
@@ -83,6 +96,7 @@ impl MockEthereum {
             confirmations,
             success: true,
             receipt: None,
+            gas_used: None,
         };
         self.inner.tx_statuses.write().await.insert(tx_hash, status);
     }
@@ -95,9 +109,25 @@ impl MockEthereum {
             confirmations,
             success: false,
             receipt: Some(Default::default()),
+            gas_used: None,
         };
         self.inner.tx_statuses.write().await.insert(*hash, status);
     }
+
+    /// Sets the response of the `func` function of the `contract` for the contract calls.
+    pub async fn set_contract_call(
+        &self,
+        contract: Address,
+        func: &str,
+        response: MockCallResponse,
+    ) {
+        self.inner
+            .contract_calls
+            .write()
+            .await
+            .insert((contract, func.to_owned()), response);
+    }
+
     pub async fn get_tx_status(&self, hash: H256) -> anyhow::Result<Option<ExecutedTxStatus>> {
         Ok(self.inner.tx_statuses.read().await.get(&hash).cloned())
     }
@@ -181,8 +211,14 @@ impl MockEthereum {
         unreachable!()
     }
 
-    pub async fn tx_receipt(&self, _tx_hash: H256) -> Result<Option<TransactionReceipt>, Error> {
-        unreachable!()
+    pub async fn tx_receipt(&self, tx_hash: H256) -> Result<Option<TransactionReceipt>, Error> {
+        Ok(self
+            .inner
+            .tx_statuses
+            .read()
+            .await
+            .get(&tx_hash)
+            .and_then(|status| status.receipt.clone()))
     }
 
     pub async fn eth_balance(&self, _address: Address) -> Result<U256, Error> {
@@ -237,12 +273,12 @@ impl MockEthereum {
     #[allow(clippy::too_many_arguments)]
     pub async fn call_contract_function<R, A, B, P>(
         &self,
-        _func: &str,
+        func: &str,
         _params: P,
         _from: A,
         _options: Options,
         _block: B,
-        _token_address: Address,
+        token_address: Address,
         _erc20_abi: ethabi::Contract,
     ) -> Result<R, anyhow::Error>
     where
@@ -251,7 +287,29 @@ impl MockEthereum {
         B: Into<Option<BlockId>>,
         P: Tokenize,
     {
-        todo!()
+        let response = self
+            .inner
+            .contract_calls
+            .read()
+            .await
+            .get(&(token_address, func.to_owned()))
+            .cloned()
+            .unwrap_or_else(|| panic!("Call of `{}` is not mocked", func));
+        // Errors are the same as the ones returned by `web3` for the real calls.
+        let result = match response {
+            MockCallResponse::Output(tokens) => R::from_tokens(tokens),
+            MockCallResponse::Revert => {
+                Err(ContractError::Api(web3::Error::Rpc(web3::rpc::Error {
+                    code: web3::rpc::ErrorCode::ServerError(-32000),
+                    message: "execution reverted".to_owned(),
+                    data: None,
+                })))
+            }
+            MockCallResponse::Unavailable => Err(ContractError::Api(web3::Error::Transport(
+                "Connection refused".to_owned(),
+            ))),
+        };
+        Ok(result?)
     }
 
     pub fn create_contract(
@@ -26,13 +26,22 @@ pub struct MultiplexerEthereumClient {
 
 macro_rules! multiple_call {
     ($self:expr, $func:ident($($attr:expr),*)) => {
+        let mut last_error = None;
         for (name, client) in $self.clients() {
             match client.$func($($attr.clone()),*).await {
                 Ok(res) => return Ok(res),
-                Err(err) => vlog::error!("Error in interface: {}, {} ", name, err),
+                Err(err) => {
+                    vlog::error!("Error in interface: {}, {} ", name, err);
+                    last_error = Some(err);
+                }
             }
         }
-        anyhow::bail!("All interfaces was wrong please try again")
+        // The error of the last interface is kept, so the callers can tell the reverted calls
+        // from the unavailable nodes.
+        return Err(match last_error {
+            Some(err) => err.context("All interfaces was wrong please try again"),
+            None => anyhow::format_err!("All interfaces was wrong please try again"),
+        })
     };
 }
 
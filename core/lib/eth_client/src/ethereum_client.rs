@@ -0,0 +1,166 @@
+//! Common interface of the Ethereum clients.
+//!
+//! `EthereumClient` covers the requests to the Ethereum node made by the server components.
+//! It is implemented by every client (direct, multiplexed and mock), by `EthereumGateway`
+//! and by the layers from the `middleware` module, so the layers can be stacked on top of
+//! any client. Contract calls with the generic arguments are not a part of the interface
+//! and are only provided by `EthereumGateway`.
+
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use web3::contract::Options;
+use web3::types::{Address, Filter, Log, Transaction, U64};
+use zksync_eth_signer::PrivateKeySigner;
+use zksync_types::{TransactionReceipt, H160, H256, U256};
+
+use crate::clients::mock::MockEthereum;
+use crate::ethereum_gateway::{ExecutedTxStatus, FailureInfo, SignedCallResult};
+use crate::{ETHDirectClient, EthereumGateway, MultiplexerEthereumClient};
+
+#[async_trait]
+pub trait EthereumClient: Debug + Send + Sync {
+    /// Returns the next *expected* nonce with respect to the transactions
+    /// in the mempool.
+    async fn pending_nonce(&self) -> anyhow::Result<U256>;
+
+    /// Returns the account nonce based on the last *mined* block.
+    async fn current_nonce(&self) -> anyhow::Result<U256>;
+
+    async fn block_number(&self) -> anyhow::Result<U64>;
+
+    async fn get_gas_price(&self) -> anyhow::Result<U256>;
+
+    /// Returns the balance of the operator account.
+    async fn sender_eth_balance(&self) -> anyhow::Result<U256>;
+
+    /// Signs the transaction to the main contract given the previously encoded data.
+    /// Fills in gas/nonce if not supplied inside options.
+    async fn sign_prepared_tx(
+        &self,
+        data: Vec<u8>,
+        options: Options,
+    ) -> anyhow::Result<SignedCallResult>;
+
+    /// Signs the transaction to the given contract given the previously encoded data.
+    /// Fills in gas/nonce if not supplied inside options.
+    async fn sign_prepared_tx_for_addr(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        options: Options,
+    ) -> anyhow::Result<SignedCallResult>;
+
+    /// Sends the transaction to the Ethereum blockchain.
+    /// Transaction is expected to be encoded as the byte sequence.
+    async fn send_raw_tx(&self, tx: Vec<u8>) -> anyhow::Result<H256>;
+
+    async fn tx_receipt(&self, tx_hash: H256) -> anyhow::Result<Option<TransactionReceipt>>;
+
+    async fn failure_reason(&self, tx_hash: H256) -> anyhow::Result<Option<FailureInfo>>;
+
+    async fn eth_balance(&self, address: Address) -> anyhow::Result<U256>;
+
+    /// Returns the amount of the token the main contract is allowed to spend
+    /// from the operator account.
+    async fn allowance(
+        &self,
+        token_address: Address,
+        erc20_abi: ethabi::Contract,
+    ) -> anyhow::Result<U256>;
+
+    async fn get_tx_status(&self, hash: H256) -> anyhow::Result<Option<ExecutedTxStatus>>;
+
+    async fn logs(&self, filter: Filter) -> anyhow::Result<Vec<Log>>;
+
+    async fn get_tx(&self, hash: H256) -> anyhow::Result<Option<Transaction>>;
+}
+
+/// Implements `EthereumClient` for the client by calling its inherent methods.
+macro_rules! impl_ethereum_client {
+    ($client:ty) => {
+        #[async_trait]
+        impl EthereumClient for $client {
+            async fn pending_nonce(&self) -> anyhow::Result<U256> {
+                <$client>::pending_nonce(self).await
+            }
+
+            async fn current_nonce(&self) -> anyhow::Result<U256> {
+                <$client>::current_nonce(self).await
+            }
+
+            async fn block_number(&self) -> anyhow::Result<U64> {
+                <$client>::block_number(self).await
+            }
+
+            async fn get_gas_price(&self) -> anyhow::Result<U256> {
+                <$client>::get_gas_price(self).await
+            }
+
+            async fn sender_eth_balance(&self) -> anyhow::Result<U256> {
+                <$client>::sender_eth_balance(self).await
+            }
+
+            async fn sign_prepared_tx(
+                &self,
+                data: Vec<u8>,
+                options: Options,
+            ) -> anyhow::Result<SignedCallResult> {
+                <$client>::sign_prepared_tx(self, data, options).await
+            }
+
+            async fn sign_prepared_tx_for_addr(
+                &self,
+                data: Vec<u8>,
+                contract_addr: H160,
+                options: Options,
+            ) -> anyhow::Result<SignedCallResult> {
+                <$client>::sign_prepared_tx_for_addr(self, data, contract_addr, options).await
+            }
+
+            async fn send_raw_tx(&self, tx: Vec<u8>) -> anyhow::Result<H256> {
+                <$client>::send_raw_tx(self, tx).await
+            }
+
+            async fn tx_receipt(
+                &self,
+                tx_hash: H256,
+            ) -> anyhow::Result<Option<TransactionReceipt>> {
+                <$client>::tx_receipt(self, tx_hash).await
+            }
+
+            async fn failure_reason(&self, tx_hash: H256) -> anyhow::Result<Option<FailureInfo>> {
+                <$client>::failure_reason(self, tx_hash).await
+            }
+
+            async fn eth_balance(&self, address: Address) -> anyhow::Result<U256> {
+                <$client>::eth_balance(self, address).await
+            }
+
+            async fn allowance(
+                &self,
+                token_address: Address,
+                erc20_abi: ethabi::Contract,
+            ) -> anyhow::Result<U256> {
+                <$client>::allowance(self, token_address, erc20_abi).await
+            }
+
+            async fn get_tx_status(&self, hash: H256) -> anyhow::Result<Option<ExecutedTxStatus>> {
+                <$client>::get_tx_status(self, hash).await
+            }
+
+            async fn logs(&self, filter: Filter) -> anyhow::Result<Vec<Log>> {
+                <$client>::logs(self, filter).await
+            }
+
+            async fn get_tx(&self, hash: H256) -> anyhow::Result<Option<Transaction>> {
+                <$client>::get_tx(self, hash).await
+            }
+        }
+    };
+}
+
+impl_ethereum_client!(ETHDirectClient<PrivateKeySigner>);
+impl_ethereum_client!(MultiplexerEthereumClient);
+impl_ethereum_client!(MockEthereum);
+impl_ethereum_client!(EthereumGateway);
@@ -4,6 +4,7 @@ use web3::transports::Http;
 use web3::types::{Address, BlockId, Filter, Log, Transaction, U64};
 
 use std::fmt::Debug;
+use std::sync::Arc;
 use zksync_config::ZkSyncConfig;
 use zksync_contracts::zksync_contract;
 use zksync_eth_signer::PrivateKeySigner;
@@ -11,6 +12,8 @@ use zksync_types::{TransactionReceipt, H160, H256, U256};
 
 use crate::clients::mock::MockEthereum;
 use crate::clients::multiplexer::MultiplexerEthereumClient;
+use crate::ethereum_client::EthereumClient;
+use crate::middleware::with_middleware;
 use crate::ETHDirectClient;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -31,6 +34,8 @@ pub struct ExecutedTxStatus {
     /// Receipt for a transaction. Will be set to `Some` only if the transaction
     /// failed during execution.
     pub receipt: Option<TransactionReceipt>,
+    /// Amount of gas used by the transaction, if known.
+    pub gas_used: Option<U256>,
 }
 /// Information about transaction failure.
 #[derive(Debug, Clone)]
@@ -41,15 +46,50 @@ pub struct FailureInfo {
     pub gas_limit: U256,
 }
 
+/// Gateway wrapped into the middleware layers (see the `middleware` module).
+///
+/// Requests covered by the `EthereumClient` trait are sent through the layers, while
+/// the contract calls with the generic arguments are sent to the base gateway directly.
+#[derive(Debug, Clone)]
+pub struct LayeredGateway {
+    base: Box<EthereumGateway>,
+    client: Arc<dyn EthereumClient>,
+}
+
+impl LayeredGateway {
+    pub fn new(base: EthereumGateway, client: Arc<dyn EthereumClient>) -> Self {
+        Self {
+            base: Box::new(base),
+            client,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum EthereumGateway {
     Direct(ETHDirectClient<PrivateKeySigner>),
     Multiplexed(MultiplexerEthereumClient),
     Mock(MockEthereum),
+    Layered(LayeredGateway),
 }
 
 impl EthereumGateway {
+    /// Creates the gateway wrapped into the middleware layers configured
+    /// in `config.eth_client`.
     pub fn from_config(config: &ZkSyncConfig) -> Self {
+        let base = Self::base_from_config(config);
+        let client = match &base {
+            EthereumGateway::Direct(client) => with_middleware(client.clone(), &config.eth_client),
+            EthereumGateway::Multiplexed(client) => {
+                with_middleware(client.clone(), &config.eth_client)
+            }
+            _ => unreachable!("Only direct and multiplexed clients are created from config"),
+        };
+
+        EthereumGateway::Layered(LayeredGateway::new(base, client))
+    }
+
+    fn base_from_config(config: &ZkSyncConfig) -> Self {
         if config.eth_client.web3_url.len() == 1 {
             let transport = web3::transports::Http::new(&config.eth_client.web3_url()).unwrap();
 
@@ -92,6 +132,26 @@ macro_rules! delegate_call {
             Self::Direct(d) => d.$method($($args),*).await,
             Self::Multiplexed(d) => d.$method($($args),*).await,
             Self::Mock(d) => d.$method($($args),*).await,
+            Self::Layered(d) => d.client.$method($($args),*).await,
+        }
+    }
+}
+
+/// Same as `delegate_call`, but always uses the base client of the layered gateway.
+///
+/// Used for the methods that are not covered by the `EthereumClient` trait.
+macro_rules! delegate_base_call {
+    ($self:ident.$method:ident($($args:ident),*)) => {
+        match $self {
+            Self::Direct(d) => d.$method($($args),*).await,
+            Self::Multiplexed(d) => d.$method($($args),*).await,
+            Self::Mock(d) => d.$method($($args),*).await,
+            Self::Layered(d) => match d.base.as_ref() {
+                Self::Direct(d) => d.$method($($args),*).await,
+                Self::Multiplexed(d) => d.$method($($args),*).await,
+                Self::Mock(d) => d.$method($($args),*).await,
+                Self::Layered(_) => unreachable!("Layered gateways are not nested"),
+            },
         }
     }
 }
@@ -202,7 +262,7 @@ impl EthereumGateway {
         B: Into<Option<BlockId>> + Clone,
         P: Tokenize + Clone,
     {
-        delegate_call!(self.call_main_contract_function(func, params, from, options, block))
+        delegate_base_call!(self.call_main_contract_function(func, params, from, options, block))
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -222,7 +282,7 @@ impl EthereumGateway {
         B: Into<Option<BlockId>> + Clone,
         P: Tokenize + Clone,
     {
-        delegate_call!(self.call_contract_function(
+        delegate_base_call!(self.call_contract_function(
             func,
             params,
             from,
@@ -242,6 +302,7 @@ impl EthereumGateway {
             EthereumGateway::Multiplexed(c) => c.encode_tx_data(func, params),
             EthereumGateway::Direct(c) => c.encode_tx_data(func, params),
             EthereumGateway::Mock(c) => c.encode_tx_data(func, params),
+            EthereumGateway::Layered(c) => c.base.encode_tx_data(func, params),
         }
     }
 
@@ -250,6 +311,7 @@ impl EthereumGateway {
             EthereumGateway::Multiplexed(c) => c.create_contract(address, contract),
             EthereumGateway::Direct(c) => c.create_contract(address, contract),
             EthereumGateway::Mock(c) => c.create_contract(address, contract),
+            EthereumGateway::Layered(c) => c.base.create_contract(address, contract),
         }
     }
 
@@ -258,7 +320,23 @@ impl EthereumGateway {
     }
 
     pub fn is_multiplexed(&self) -> bool {
-        matches!(self, EthereumGateway::Multiplexed(_))
+        matches!(self.base(), EthereumGateway::Multiplexed(_))
+    }
+
+    /// Returns the gateway without the middleware layers.
+    pub fn base(&self) -> &EthereumGateway {
+        match self {
+            EthereumGateway::Layered(c) => c.base.as_ref(),
+            _ => self,
+        }
+    }
+
+    /// Same as `base`, but takes the ownership of the gateway.
+    pub fn into_base(self) -> EthereumGateway {
+        match self {
+            EthereumGateway::Layered(c) => *c.base,
+            _ => self,
+        }
     }
 
     pub fn get_mut_mock(&mut self) -> Option<&mut MockEthereum> {
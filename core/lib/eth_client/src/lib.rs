@@ -1,5 +1,8 @@
 pub mod clients;
+pub mod ethereum_client;
 pub mod ethereum_gateway;
+pub mod middleware;
 pub use clients::http_client::ETHDirectClient;
 pub use clients::multiplexer::MultiplexerEthereumClient;
+pub use ethereum_client::EthereumClient;
 pub use ethereum_gateway::{EthereumGateway, SignedCallResult};
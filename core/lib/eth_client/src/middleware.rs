@@ -0,0 +1,648 @@
+//! Composable layers for the `EthereumClient`.
+//!
+//! Every layer wraps another `EthereumClient` and implements the same trait, so the
+//! layers can be stacked in any order. `with_middleware` applies the default stack
+//! configured via `ETHClientConfig`:
+//!
+//! `Metrics` -> `Retry` -> `Deduplicate` -> `Timeout` -> client
+//!
+//! Thus the metrics observe the overall latency of the request including the retries,
+//! and every attempt is limited by the timeout separately. The timeout is applied to the
+//! request sent to the node rather than to the callers awaiting it: otherwise a hung shared
+//! request would stay in flight forever, and every identical request would join it.
+
+// Built-in uses
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+// External uses
+use async_trait::async_trait;
+use futures::future::{BoxFuture, FutureExt, Shared};
+use rand::Rng;
+use web3::contract::Options;
+use web3::types::{Address, Filter, Log, Transaction, U64};
+// Workspace uses
+use zksync_config::ETHClientConfig;
+use zksync_types::{TransactionReceipt, H160, H256, U256};
+// Local uses
+use crate::ethereum_client::EthereumClient;
+use crate::ethereum_gateway::{ExecutedTxStatus, FailureInfo, SignedCallResult};
+
+/// Upper bound for the delay between two consecutive retries.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Wraps `client` into the default set of layers configured by `config`.
+pub fn with_middleware<C>(client: C, config: &ETHClientConfig) -> Arc<dyn EthereumClient>
+where
+    C: EthereumClient + 'static,
+{
+    layered(
+        client,
+        config.request_timeout(),
+        config.max_request_retries,
+        config.retry_base_delay(),
+    )
+}
+
+fn layered<C>(
+    client: C,
+    request_timeout: Duration,
+    max_retries: u32,
+    retry_base_delay: Duration,
+) -> Arc<dyn EthereumClient>
+where
+    C: EthereumClient + 'static,
+{
+    let client = Timeout::new(client, request_timeout);
+    let client = Deduplicate::new(client);
+    let client = Retry::new(client, max_retries, retry_base_delay);
+    Arc::new(Metrics::new(client))
+}
+
+/// Implements `EthereumClient` for the layer by passing every request through its `call` method.
+macro_rules! impl_layer {
+    ($layer:ident) => {
+        #[async_trait]
+        impl<C: EthereumClient> EthereumClient for $layer<C> {
+            async fn pending_nonce(&self) -> anyhow::Result<U256> {
+                self.call("pending_nonce", || self.inner.pending_nonce())
+                    .await
+            }
+
+            async fn current_nonce(&self) -> anyhow::Result<U256> {
+                self.call("current_nonce", || self.inner.current_nonce())
+                    .await
+            }
+
+            async fn block_number(&self) -> anyhow::Result<U64> {
+                self.call("block_number", || self.inner.block_number())
+                    .await
+            }
+
+            async fn get_gas_price(&self) -> anyhow::Result<U256> {
+                self.call("get_gas_price", || self.inner.get_gas_price())
+                    .await
+            }
+
+            async fn sender_eth_balance(&self) -> anyhow::Result<U256> {
+                self.call("sender_eth_balance", || self.inner.sender_eth_balance())
+                    .await
+            }
+
+            async fn sign_prepared_tx(
+                &self,
+                data: Vec<u8>,
+                options: Options,
+            ) -> anyhow::Result<SignedCallResult> {
+                self.call("sign_prepared_tx", || {
+                    self.inner.sign_prepared_tx(data.clone(), options.clone())
+                })
+                .await
+            }
+
+            async fn sign_prepared_tx_for_addr(
+                &self,
+                data: Vec<u8>,
+                contract_addr: H160,
+                options: Options,
+            ) -> anyhow::Result<SignedCallResult> {
+                self.call("sign_prepared_tx_for_addr", || {
+                    self.inner.sign_prepared_tx_for_addr(
+                        data.clone(),
+                        contract_addr,
+                        options.clone(),
+                    )
+                })
+                .await
+            }
+
+            async fn send_raw_tx(&self, tx: Vec<u8>) -> anyhow::Result<H256> {
+                self.call("send_raw_tx", || self.inner.send_raw_tx(tx.clone()))
+                    .await
+            }
+
+            async fn tx_receipt(
+                &self,
+                tx_hash: H256,
+            ) -> anyhow::Result<Option<TransactionReceipt>> {
+                self.call("tx_receipt", || self.inner.tx_receipt(tx_hash))
+                    .await
+            }
+
+            async fn failure_reason(&self, tx_hash: H256) -> anyhow::Result<Option<FailureInfo>> {
+                self.call("failure_reason", || self.inner.failure_reason(tx_hash))
+                    .await
+            }
+
+            async fn eth_balance(&self, address: Address) -> anyhow::Result<U256> {
+                self.call("eth_balance", || self.inner.eth_balance(address))
+                    .await
+            }
+
+            async fn allowance(
+                &self,
+                token_address: Address,
+                erc20_abi: ethabi::Contract,
+            ) -> anyhow::Result<U256> {
+                self.call("allowance", || {
+                    self.inner.allowance(token_address, erc20_abi.clone())
+                })
+                .await
+            }
+
+            async fn get_tx_status(&self, hash: H256) -> anyhow::Result<Option<ExecutedTxStatus>> {
+                self.call("get_tx_status", || self.inner.get_tx_status(hash))
+                    .await
+            }
+
+            async fn logs(&self, filter: Filter) -> anyhow::Result<Vec<Log>> {
+                self.call("logs", || self.inner.logs(filter.clone())).await
+            }
+
+            async fn get_tx(&self, hash: H256) -> anyhow::Result<Option<Transaction>> {
+                self.call("get_tx", || self.inner.get_tx(hash)).await
+            }
+        }
+    };
+}
+
+/// Fails the request if it was not completed within the given time.
+#[derive(Debug)]
+pub struct Timeout<C> {
+    inner: C,
+    timeout: Duration,
+}
+
+impl<C: EthereumClient> Timeout<C> {
+    pub fn new(inner: C, timeout: Duration) -> Self {
+        Self { inner, timeout }
+    }
+
+    async fn call<T, F, Fut>(&self, method: &'static str, request: F) -> anyhow::Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        match tokio::time::timeout(self.timeout, request()).await {
+            Ok(result) => result,
+            Err(_) => anyhow::bail!(
+                "Request `{}` to the Ethereum node timed out after {:?}",
+                method,
+                self.timeout
+            ),
+        }
+    }
+}
+
+impl_layer!(Timeout);
+
+/// Repeats the failed requests using the exponential backoff with the full jitter.
+///
+/// Sending of the raw transaction is never repeated: the error in this case is meaningful
+/// to the caller (e.g. the nonce is too low), and `eth_sender` has its own logic of
+/// resending the stuck transactions.
+#[derive(Debug)]
+pub struct Retry<C> {
+    inner: C,
+    max_retries: u32,
+    base_delay: Duration,
+}
+
+impl<C: EthereumClient> Retry<C> {
+    /// Methods that are sent to the inner client only once.
+    const NOT_RETRIED: &'static [&'static str] = &["send_raw_tx"];
+
+    pub fn new(inner: C, max_retries: u32, base_delay: Duration) -> Self {
+        Self {
+            inner,
+            max_retries,
+            base_delay,
+        }
+    }
+
+    /// Chooses the delay before the next attempt uniformly from `[0, base_delay * 2^attempt]`.
+    fn retry_delay(&self, attempt: u32) -> Duration {
+        let max_delay = self
+            .base_delay
+            .checked_mul(1 << attempt.min(16))
+            .map_or(MAX_RETRY_DELAY, |delay| delay.min(MAX_RETRY_DELAY));
+
+        let max_delay_ms = max_delay.as_millis() as u64;
+        Duration::from_millis(rand::thread_rng().gen_range(0, max_delay_ms + 1))
+    }
+
+    async fn call<T, F, Fut>(&self, method: &'static str, request: F) -> anyhow::Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let max_retries = if Self::NOT_RETRIED.contains(&method) {
+            0
+        } else {
+            self.max_retries
+        };
+
+        let mut attempt = 0;
+        loop {
+            match request().await {
+                Ok(result) => return Ok(result),
+                Err(err) if attempt < max_retries => {
+                    let delay = self.retry_delay(attempt);
+                    vlog::warn!(
+                        "Request `{}` to the Ethereum node failed: {}. Retrying in {:?}",
+                        method,
+                        err,
+                        delay
+                    );
+                    metrics::counter!("eth_client.request_retries", 1, "method" => method);
+
+                    tokio::time::delay_for(delay).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+impl_layer!(Retry);
+
+/// Reports the latency and the errors of the requests.
+#[derive(Debug)]
+pub struct Metrics<C> {
+    inner: C,
+}
+
+impl<C: EthereumClient> Metrics<C> {
+    pub fn new(inner: C) -> Self {
+        Self { inner }
+    }
+
+    async fn call<T, F, Fut>(&self, method: &'static str, request: F) -> anyhow::Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let start = Instant::now();
+        let result = request().await;
+
+        metrics::histogram!("eth_client.request", start.elapsed(), "method" => method);
+        if result.is_err() {
+            metrics::counter!("eth_client.request_errors", 1, "method" => method);
+        }
+        result
+    }
+}
+
+impl_layer!(Metrics);
+
+type SharedRequest<T> = Shared<BoxFuture<'static, Result<T, String>>>;
+
+/// Requests of the same kind that are currently being processed.
+struct InFlight<T> {
+    requests: Arc<Mutex<HashMap<String, SharedRequest<T>>>>,
+}
+
+impl<T> Default for InFlight<T> {
+    fn default() -> Self {
+        Self {
+            requests: Default::default(),
+        }
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> InFlight<T> {
+    /// Awaits the identical request if it's already being processed, and sends `request` otherwise.
+    async fn run<F>(&self, key: String, request: F) -> anyhow::Result<T>
+    where
+        F: Future<Output = anyhow::Result<T>> + Send + 'static,
+    {
+        let shared = {
+            let mut requests = self.requests.lock().unwrap();
+            let requests_handle = Arc::clone(&self.requests);
+            requests
+                .entry(key.clone())
+                .or_insert_with(move || {
+                    async move {
+                        let result = request.await.map_err(|err| format!("{:#}", err));
+                        // Requests made after this point have to be sent to the node again.
+                        requests_handle.lock().unwrap().remove(&key);
+                        result
+                    }
+                    .boxed()
+                    .shared()
+                })
+                .clone()
+        };
+
+        shared.await.map_err(anyhow::Error::msg)
+    }
+}
+
+/// Sends the identical concurrent read requests to the node only once.
+///
+/// Only the requests that are polled by several components simultaneously are
+/// deduplicated: `block_number`, `get_gas_price` and `logs`. The rest are passed
+/// to the inner client as is.
+pub struct Deduplicate<C> {
+    inner: Arc<C>,
+    block_number: InFlight<U64>,
+    gas_price: InFlight<U256>,
+    logs: InFlight<Vec<Log>>,
+}
+
+impl<C> fmt::Debug for Deduplicate<C>
+where
+    C: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Deduplicate")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<C: EthereumClient + 'static> Deduplicate<C> {
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            block_number: InFlight::default(),
+            gas_price: InFlight::default(),
+            logs: InFlight::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl<C: EthereumClient + 'static> EthereumClient for Deduplicate<C> {
+    async fn pending_nonce(&self) -> anyhow::Result<U256> {
+        self.inner.pending_nonce().await
+    }
+
+    async fn current_nonce(&self) -> anyhow::Result<U256> {
+        self.inner.current_nonce().await
+    }
+
+    async fn block_number(&self) -> anyhow::Result<U64> {
+        let inner = Arc::clone(&self.inner);
+        self.block_number
+            .run(String::new(), async move { inner.block_number().await })
+            .await
+    }
+
+    async fn get_gas_price(&self) -> anyhow::Result<U256> {
+        let inner = Arc::clone(&self.inner);
+        self.gas_price
+            .run(String::new(), async move { inner.get_gas_price().await })
+            .await
+    }
+
+    async fn sender_eth_balance(&self) -> anyhow::Result<U256> {
+        self.inner.sender_eth_balance().await
+    }
+
+    async fn sign_prepared_tx(
+        &self,
+        data: Vec<u8>,
+        options: Options,
+    ) -> anyhow::Result<SignedCallResult> {
+        self.inner.sign_prepared_tx(data, options).await
+    }
+
+    async fn sign_prepared_tx_for_addr(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        options: Options,
+    ) -> anyhow::Result<SignedCallResult> {
+        self.inner
+            .sign_prepared_tx_for_addr(data, contract_addr, options)
+            .await
+    }
+
+    async fn send_raw_tx(&self, tx: Vec<u8>) -> anyhow::Result<H256> {
+        self.inner.send_raw_tx(tx).await
+    }
+
+    async fn tx_receipt(&self, tx_hash: H256) -> anyhow::Result<Option<TransactionReceipt>> {
+        self.inner.tx_receipt(tx_hash).await
+    }
+
+    async fn failure_reason(&self, tx_hash: H256) -> anyhow::Result<Option<FailureInfo>> {
+        self.inner.failure_reason(tx_hash).await
+    }
+
+    async fn eth_balance(&self, address: Address) -> anyhow::Result<U256> {
+        self.inner.eth_balance(address).await
+    }
+
+    async fn allowance(
+        &self,
+        token_address: Address,
+        erc20_abi: ethabi::Contract,
+    ) -> anyhow::Result<U256> {
+        self.inner.allowance(token_address, erc20_abi).await
+    }
+
+    async fn get_tx_status(&self, hash: H256) -> anyhow::Result<Option<ExecutedTxStatus>> {
+        self.inner.get_tx_status(hash).await
+    }
+
+    async fn logs(&self, filter: Filter) -> anyhow::Result<Vec<Log>> {
+        let key = format!("{:?}", filter);
+        let inner = Arc::clone(&self.inner);
+        self.logs
+            .run(key, async move { inner.logs(filter).await })
+            .await
+    }
+
+    async fn get_tx(&self, hash: H256) -> anyhow::Result<Option<Transaction>> {
+        self.inner.get_tx(hash).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Client counting the requests, the first `hung_requests` of which never complete,
+    /// and the next `failed_requests` fail.
+    #[derive(Debug, Default)]
+    struct TestClient {
+        requests: AtomicUsize,
+        hung_requests: usize,
+        failed_requests: usize,
+        delay: Duration,
+    }
+
+    impl TestClient {
+        fn requests(&self) -> usize {
+            self.requests.load(Ordering::SeqCst)
+        }
+
+        async fn request(&self) -> anyhow::Result<U64> {
+            let request = self.requests.fetch_add(1, Ordering::SeqCst);
+            if request < self.hung_requests {
+                futures::future::pending::<()>().await;
+            }
+            tokio::time::delay_for(self.delay).await;
+            if request < self.hung_requests + self.failed_requests {
+                anyhow::bail!("Request #{} failed", request);
+            }
+            Ok(U64::from(request))
+        }
+    }
+
+    #[async_trait]
+    impl EthereumClient for Arc<TestClient> {
+        async fn pending_nonce(&self) -> anyhow::Result<U256> {
+            unimplemented!()
+        }
+
+        async fn current_nonce(&self) -> anyhow::Result<U256> {
+            unimplemented!()
+        }
+
+        async fn block_number(&self) -> anyhow::Result<U64> {
+            self.request().await
+        }
+
+        async fn get_gas_price(&self) -> anyhow::Result<U256> {
+            self.request().await.map(|number| number.as_u64().into())
+        }
+
+        async fn sender_eth_balance(&self) -> anyhow::Result<U256> {
+            unimplemented!()
+        }
+
+        async fn sign_prepared_tx(
+            &self,
+            _data: Vec<u8>,
+            _options: Options,
+        ) -> anyhow::Result<SignedCallResult> {
+            unimplemented!()
+        }
+
+        async fn sign_prepared_tx_for_addr(
+            &self,
+            _data: Vec<u8>,
+            _contract_addr: H160,
+            _options: Options,
+        ) -> anyhow::Result<SignedCallResult> {
+            unimplemented!()
+        }
+
+        async fn send_raw_tx(&self, _tx: Vec<u8>) -> anyhow::Result<H256> {
+            self.request().await.map(|_| H256::zero())
+        }
+
+        async fn tx_receipt(&self, _tx_hash: H256) -> anyhow::Result<Option<TransactionReceipt>> {
+            unimplemented!()
+        }
+
+        async fn failure_reason(&self, _tx_hash: H256) -> anyhow::Result<Option<FailureInfo>> {
+            unimplemented!()
+        }
+
+        async fn eth_balance(&self, _address: Address) -> anyhow::Result<U256> {
+            unimplemented!()
+        }
+
+        async fn allowance(
+            &self,
+            _token_address: Address,
+            _erc20_abi: ethabi::Contract,
+        ) -> anyhow::Result<U256> {
+            unimplemented!()
+        }
+
+        async fn get_tx_status(&self, _hash: H256) -> anyhow::Result<Option<ExecutedTxStatus>> {
+            unimplemented!()
+        }
+
+        async fn logs(&self, _filter: Filter) -> anyhow::Result<Vec<Log>> {
+            self.request().await.map(|_| Vec::new())
+        }
+
+        async fn get_tx(&self, _hash: H256) -> anyhow::Result<Option<Transaction>> {
+            unimplemented!()
+        }
+    }
+
+    fn layered_client(
+        client: &Arc<TestClient>,
+        request_timeout_ms: u64,
+        max_retries: u32,
+    ) -> Arc<dyn EthereumClient> {
+        layered(
+            client.clone(),
+            Duration::from_millis(request_timeout_ms),
+            max_retries,
+            Duration::from_millis(1),
+        )
+    }
+
+    /// Checks that the concurrent identical requests are sent to the node once.
+    #[tokio::test]
+    async fn identical_requests_are_deduplicated() {
+        let client = Arc::new(TestClient {
+            delay: Duration::from_millis(50),
+            ..TestClient::default()
+        });
+        let layered = layered_client(&client, 1_000, 0);
+
+        let (first, second, gas_price) = futures::join!(
+            layered.block_number(),
+            layered.block_number(),
+            layered.get_gas_price()
+        );
+        assert_eq!(first.unwrap(), second.unwrap());
+        assert!(gas_price.is_ok());
+        assert_eq!(client.requests(), 2);
+
+        // Requests made after the completion are sent again.
+        layered.block_number().await.unwrap();
+        assert_eq!(client.requests(), 3);
+    }
+
+    /// Checks that the hung request is timed out and isn't shared with the later requests.
+    #[tokio::test]
+    async fn hung_request_is_not_shared() {
+        let client = Arc::new(TestClient {
+            hung_requests: 1,
+            ..TestClient::default()
+        });
+        let layered = layered_client(&client, 50, 0);
+
+        let err = layered.block_number().await.unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{}", err);
+        assert_eq!(layered.block_number().await.unwrap(), U64::from(1));
+        assert_eq!(client.requests(), 2);
+    }
+
+    /// Checks that the failed requests are retried, except for sending the transactions.
+    #[tokio::test]
+    async fn failed_requests_are_retried() {
+        let client = Arc::new(TestClient {
+            failed_requests: 2,
+            ..TestClient::default()
+        });
+        let layered = layered_client(&client, 1_000, 2);
+
+        layered.send_raw_tx(Vec::new()).await.unwrap_err();
+        assert_eq!(client.requests(), 1);
+        assert_eq!(layered.block_number().await.unwrap(), U64::from(2));
+        assert_eq!(client.requests(), 3);
+
+        let client = Arc::new(TestClient {
+            failed_requests: 2,
+            ..TestClient::default()
+        });
+        let layered = layered_client(&client, 1_000, 1);
+        layered.logs(Filter::default()).await.unwrap_err();
+        assert_eq!(client.requests(), 2);
+    }
+}
@@ -53,7 +53,7 @@ impl MultiplexedGatewayWatcher {
         task_limit: Option<usize>,
     ) -> Self {
         Self {
-            client: match gateway {
+            client: match gateway.into_base() {
                 EthereumGateway::Multiplexed(client) => client,
                 _ => {
                     panic!("Ethereum Gateway Watcher: multiplexed client expected")
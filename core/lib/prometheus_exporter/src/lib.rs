@@ -1,4 +1,17 @@
-//! This module handles metric export to the Prometheus server
+//! This module handles metric export to the Prometheus server.
+//!
+//! Metrics are exposed in the Prometheus text format on `0.0.0.0:<port>/metrics`.
+//! Besides the timings reported by every server component, the following
+//! component-specific metrics are collected:
+//!
+//! - `mempool.size`: amount of transactions waiting in the mempool.
+//! - `state_keeper.executed_operations` (counter): executed operations, its rate is the state keeper TPS.
+//! - `state_keeper.block_seal_latency`: time between the block creation and its sealing.
+//! - `eth_sender.pending_operations`, `eth_sender.queued_operations`: operations sent to L1
+//!   and not yet confirmed / waiting to be sent.
+//! - `eth_sender.gas_spent` (counter): gas spent on the confirmed L1 transactions.
+//! - `prover.pending_jobs`: depth of the prover job queue.
+//! - `api.rest.request`, `api.rpc.*`: API request latencies.
 
 use metrics_exporter_prometheus::PrometheusBuilder;
 use std::time::Duration;
@@ -59,6 +72,13 @@ pub fn run_prometheus_exporter(
                     }
                 }
 
+                let pending_jobs = transaction
+                    .prover_schema()
+                    .pending_jobs_count()
+                    .await
+                    .expect("unable to load prover jobs count");
+                metrics::gauge!("prover.pending_jobs", pending_jobs as f64);
+
                 transaction
                     .commit()
                     .await
@@ -148,3 +148,17 @@ pub fn gen_aggregate_proof(
         aggr_limbs,
     })
 }
+
+/// Verifies the aggregated proof against the verification key of the recursive circuit
+/// for the amount of the aggregated blocks.
+pub fn verify_aggregated_proof(proof: &AggregatedProof) -> anyhow::Result<bool> {
+    let vk = VkAggregate::read(File::open(get_recursive_verification_key_path(
+        proof.individual_vk_inputs.len(),
+    ))?)?;
+    let valid = verify::<_, _, RollingKeccakTranscript<<Engine as ScalarEngine>::Fr>>(
+        &vk,
+        &proof.proof,
+        None,
+    )?;
+    Ok(valid)
+}
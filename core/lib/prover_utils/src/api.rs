@@ -1,7 +1,10 @@
+use num::BigUint;
 use serde::{Deserialize, Serialize};
-use zksync_basic_types::BlockNumber;
+use zksync_basic_types::{Address, BlockNumber};
 use zksync_circuit::serialization::ProverData;
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
+use zksync_types::prover::{ProofProgress, ProverLeaseStatus};
+use zksync_utils::BigUintSerdeAsRadix10Str;
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ProverInputRequest {
@@ -21,6 +24,10 @@ pub struct ProverInputResponse {
     pub first_block: BlockNumber,
     pub last_block: BlockNumber,
     pub data: Option<JobRequestData>,
+    /// Version of the protocol between the components the job was created by,
+    /// see `zksync_types::protocol_version`.
+    #[serde(default)]
+    pub protocol_version: Option<u32>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -38,6 +45,9 @@ pub struct ProverOutputRequest {
     pub first_block: BlockNumber,
     pub last_block: BlockNumber,
     pub data: JobResultData,
+    /// Version of the protocol between the components the proof was created by.
+    #[serde(default)]
+    pub protocol_version: Option<u32>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -51,9 +61,145 @@ pub enum JobResultData {
 pub struct WorkingOn {
     pub prover_name: String,
     pub job_id: i32,
+    /// Stage of the proof generation, `None` if the prover doesn't report it.
+    #[serde(default)]
+    pub progress: Option<ProofProgress>,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ProverStopped {
     pub prover_name: String,
 }
+
+/// Notification of the prover refusing to prove the job, e.g. created by the server
+/// of the incompatible version, so that the job is given to another prover.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ReleasedJob {
+    pub prover_name: String,
+    pub job_id: i32,
+}
+
+/// Prover job leased to an external prover.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExternalJobLease {
+    pub lease_id: i64,
+    pub job_id: i32,
+    pub first_block: BlockNumber,
+    pub last_block: BlockNumber,
+    /// UNIX timestamp the proof must be submitted before, otherwise the collateral is slashed.
+    pub deadline: i64,
+    /// Part of the prover stake locked until the proof is submitted, in wei.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub collateral: BigUint,
+    /// Payment for the proof, in wei.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub payment: BigUint,
+    pub data: JobRequestData,
+    /// Version of the protocol between the components the job was created by.
+    /// External provers must not prove the jobs of the versions they don't support.
+    #[serde(default)]
+    pub protocol_version: Option<u32>,
+}
+
+/// Notification of the external prover about the ongoing work on the leased job.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExternalHeartbeat {
+    pub lease_id: i64,
+}
+
+/// Proof for the job leased to an external prover.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExternalProofSubmission {
+    pub lease_id: i64,
+    pub data: JobResultData,
+    /// Version of the protocol between the components the proof was created by.
+    #[serde(default)]
+    pub protocol_version: Option<u32>,
+}
+
+/// Lease of the prover job as seen by the external prover.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExternalLeaseInfo {
+    pub lease_id: i64,
+    pub job_id: i32,
+    pub status: ProverLeaseStatus,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub collateral: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub payment: BigUint,
+    pub deadline: i64,
+    pub slash_reason: Option<String>,
+}
+
+/// State of the external prover account. All the amounts are in wei.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExternalProverAccount {
+    pub name: String,
+    pub reward_address: Address,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub stake: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub locked_stake: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub slashed_amount: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub earned_amount: BigUint,
+    /// The latest leases of the prover.
+    pub leases: Vec<ExternalLeaseInfo>,
+}
+
+/// Input of the endpoint registering an external prover.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExternalProverRegistration {
+    pub name: String,
+    pub reward_address: Address,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub stake: BigUint,
+}
+
+/// Credentials of the registered external prover.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExternalProverCredentials {
+    pub prover_id: i64,
+    pub api_key: String,
+}
+
+/// Input of the endpoint slashing the collateral of an external prover lease,
+/// e.g. once its proof is rejected.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExternalLeaseSlashing {
+    pub lease_id: i64,
+    pub reason: String,
+}
+
+/// State of the prover job covering the block, as reported by the prover working on it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProverJobProgress {
+    pub job_id: i32,
+    pub job_type: String,
+    pub job_status: String,
+    pub first_block: BlockNumber,
+    pub last_block: BlockNumber,
+    /// Name of the prover that has updated the job last, e.g. by the heartbeat.
+    pub updated_by: String,
+    /// UNIX timestamp of the last job update.
+    pub updated_at: i64,
+    /// Name of the prover that has reported the progress.
+    pub prover_name: Option<String>,
+    /// Last reported progress, `None` if the prover hasn't reported it yet.
+    pub progress: Option<ProofProgress>,
+    /// UNIX timestamp the reported stage has started at.
+    pub stage_started_at: Option<i64>,
+    /// UNIX timestamp of the last progress report.
+    pub progress_updated_at: Option<i64>,
+}
+
+/// Progress of the proof generation for the block.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockProofProgress {
+    pub block_number: BlockNumber,
+    /// Jobs covering the block: the single block proof and the aggregated proofs, if created.
+    pub jobs: Vec<ProverJobProgress>,
+}
@@ -0,0 +1,228 @@
+//! Encodings of the payloads exchanged between the prover server and the provers.
+//!
+//! Witnesses of the big blocks are hundreds of megabytes of JSON, so the payloads may be
+//! encoded with `bincode` and compressed with `zstd` instead. The encoding is negotiated:
+//! the prover lists the encodings it accepts in the `Accept` header, and the server picks
+//! the most compact one it supports. The payload encoding is detected by its `Content-Type`,
+//! so the components that don't know about the binary encodings keep using JSON.
+
+// Built-in deps
+use std::io::Read;
+// External deps
+use anyhow::{ensure, format_err};
+use bincode::Options;
+use serde::{de::DeserializeOwned, Serialize};
+
+pub const CONTENT_TYPE_JSON: &str = "application/json";
+pub const CONTENT_TYPE_BINCODE: &str = "application/x-bincode";
+pub const CONTENT_TYPE_BINCODE_ZSTD: &str = "application/x-bincode+zstd";
+
+/// Compression level of `zstd`, the higher levels are too slow for the witnesses.
+const ZSTD_LEVEL: i32 = 3;
+/// Maximum size of the decoded payload, in bytes. The compressed payloads may be decoded
+/// into arbitrary amounts of data, so the decoding stops once the limit is exceeded.
+pub const MAX_DECODED_PAYLOAD_SIZE: u64 = 1 << 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadEncoding {
+    Json,
+    Bincode,
+    BincodeZstd,
+}
+
+impl Default for PayloadEncoding {
+    fn default() -> Self {
+        Self::Json
+    }
+}
+
+impl PayloadEncoding {
+    /// Encodings in the order of preference.
+    pub const ALL: [PayloadEncoding; 3] = [Self::BincodeZstd, Self::Bincode, Self::Json];
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Json => CONTENT_TYPE_JSON,
+            Self::Bincode => CONTENT_TYPE_BINCODE,
+            Self::BincodeZstd => CONTENT_TYPE_BINCODE_ZSTD,
+        }
+    }
+
+    /// Value of the `Accept` header listing all the supported encodings.
+    pub fn accept_header() -> String {
+        Self::ALL
+            .iter()
+            .map(|encoding| encoding.content_type())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Detects the encoding by the `Content-Type` of the payload.
+    /// The payloads without the content type are JSON.
+    pub fn from_content_type(content_type: Option<&str>) -> anyhow::Result<Self> {
+        let content_type = match content_type {
+            Some(content_type) => content_type.split(';').next().unwrap_or_default().trim(),
+            None => return Ok(Self::Json),
+        };
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|encoding| encoding.content_type().eq_ignore_ascii_case(content_type))
+            .ok_or_else(|| format_err!("unsupported payload content type: {}", content_type))
+    }
+
+    /// Picks the most preferred encoding listed in the `Accept` header.
+    /// The clients that don't send the header get JSON.
+    pub fn negotiate(accept: Option<&str>) -> Self {
+        let accepted: Vec<_> = accept
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|content_type| content_type.split(';').next())
+            .map(str::trim)
+            .collect();
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|encoding| {
+                accepted
+                    .iter()
+                    .any(|content_type| encoding.content_type().eq_ignore_ascii_case(content_type))
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn encode<T: Serialize>(self, value: &T) -> anyhow::Result<Vec<u8>> {
+        let bytes = match self {
+            Self::Json => serde_json::to_vec(value)?,
+            Self::Bincode => bincode::serialize(value)?,
+            Self::BincodeZstd => zstd::encode_all(&bincode::serialize(value)?[..], ZSTD_LEVEL)?,
+        };
+        Ok(bytes)
+    }
+
+    pub fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> anyhow::Result<T> {
+        let value = match self {
+            Self::Json => serde_json::from_slice(bytes)?,
+            Self::Bincode => bincode_options().deserialize(bytes)?,
+            Self::BincodeZstd => {
+                bincode_options().deserialize(&decompress(bytes, MAX_DECODED_PAYLOAD_SIZE)?)?
+            }
+        };
+        Ok(value)
+    }
+}
+
+/// Options of `bincode::deserialize` limited by `MAX_DECODED_PAYLOAD_SIZE`, so the length
+/// prefixes of the malformed payloads can't make it allocate too much.
+fn bincode_options() -> impl Options {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+        .with_limit(MAX_DECODED_PAYLOAD_SIZE)
+}
+
+fn decompress(bytes: &[u8], max_size: u64) -> anyhow::Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    zstd::stream::read::Decoder::new(bytes)?
+        .take(max_size + 1)
+        .read_to_end(&mut decompressed)?;
+    ensure!(
+        decompressed.len() as u64 <= max_size,
+        "decompressed payload exceeds {} bytes",
+        max_size
+    );
+    Ok(decompressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Payload {
+        id: u64,
+        name: String,
+        data: Vec<u8>,
+    }
+
+    fn payload() -> Payload {
+        Payload {
+            id: 42,
+            name: "witness".to_string(),
+            data: vec![7; 1000],
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        for &encoding in &PayloadEncoding::ALL {
+            let bytes = encoding.encode(&payload()).unwrap();
+            assert_eq!(
+                encoding.decode::<Payload>(&bytes).unwrap(),
+                payload(),
+                "{:?}",
+                encoding
+            );
+        }
+        // The repeated data is compressed.
+        let compressed = PayloadEncoding::BincodeZstd.encode(&payload()).unwrap();
+        let uncompressed = PayloadEncoding::Bincode.encode(&payload()).unwrap();
+        assert!(compressed.len() < uncompressed.len());
+        // The payloads are compatible with the plain `bincode`.
+        assert_eq!(uncompressed, bincode::serialize(&payload()).unwrap());
+        assert!(PayloadEncoding::Json
+            .decode::<Payload>(&compressed)
+            .is_err());
+    }
+
+    #[test]
+    fn oversized_payloads_are_rejected() {
+        // The length prefix of the vector claims more data than allowed.
+        let mut bytes = bincode::serialize(&payload()).unwrap();
+        let len_offset = 8 + 8 + "witness".len();
+        bytes[len_offset..len_offset + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(PayloadEncoding::Bincode.decode::<Payload>(&bytes).is_err());
+
+        let compressed = zstd::encode_all(&[0u8; 1001][..], ZSTD_LEVEL).unwrap();
+        assert_eq!(decompress(&compressed, 1001).unwrap().len(), 1001);
+        assert!(decompress(&compressed, 1000).is_err());
+    }
+
+    #[test]
+    fn negotiate() {
+        assert_eq!(PayloadEncoding::negotiate(None), PayloadEncoding::Json);
+        assert_eq!(
+            PayloadEncoding::negotiate(Some(&PayloadEncoding::accept_header())),
+            PayloadEncoding::BincodeZstd
+        );
+        // The most compact encoding is picked regardless of the order of the header.
+        assert_eq!(
+            PayloadEncoding::negotiate(Some("application/json, application/x-bincode; q=0.5")),
+            PayloadEncoding::Bincode
+        );
+        assert_eq!(
+            PayloadEncoding::negotiate(Some("text/html, */*")),
+            PayloadEncoding::Json
+        );
+    }
+
+    #[test]
+    fn content_type() {
+        for &encoding in &PayloadEncoding::ALL {
+            assert_eq!(
+                PayloadEncoding::from_content_type(Some(encoding.content_type())).unwrap(),
+                encoding
+            );
+        }
+        assert_eq!(
+            PayloadEncoding::from_content_type(None).unwrap(),
+            PayloadEncoding::Json
+        );
+        assert_eq!(
+            PayloadEncoding::from_content_type(Some("Application/JSON; charset=utf-8")).unwrap(),
+            PayloadEncoding::Json
+        );
+        assert!(PayloadEncoding::from_content_type(Some("text/plain")).is_err());
+    }
+}
@@ -11,13 +11,18 @@ use zksync_crypto::Engine;
 pub fn get_keys_root_dir() -> PathBuf {
     let mut out_dir = PathBuf::new();
     out_dir.push(&std::env::var("ZKSYNC_HOME").unwrap_or_else(|_| "/".to_owned()));
-    out_dir.push(&std::env::var("CHAIN_CIRCUIT_KEY_DIR").expect("KEY_DIR not set"));
-    out_dir.push(&format!(
-        "account-{}_balance-{}",
+    out_dir.push(get_keys_version());
+    out_dir
+}
+
+/// Version of the circuit keys, i.e. the keys directory relative to `ZKSYNC_HOME`.
+pub fn get_keys_version() -> String {
+    format!(
+        "{}/account-{}_balance-{}",
+        std::env::var("CHAIN_CIRCUIT_KEY_DIR").expect("KEY_DIR not set"),
         account_tree_depth(),
         balance_tree_depth(),
-    ));
-    out_dir
+    )
 }
 
 fn base_universal_setup_dir() -> Result<PathBuf, anyhow::Error> {
@@ -0,0 +1,99 @@
+//! gRPC interface of the prover server, see `proto/prover.proto` for the protocol description.
+//!
+//! The job data and the proofs are sent as the streams of chunks, the helpers of this module
+//! split the payloads into the chunks and assemble them back.
+
+// External deps
+use futures::{Stream, StreamExt};
+use tonic::Status;
+
+pub mod proto {
+    tonic::include_proto!("zksync.prover");
+}
+
+use self::proto::{job_part, proof_part, JobHeader, JobPart, ProofHeader, ProofPart};
+
+/// Metadata key of the JWT authenticating the requests.
+pub const AUTHORIZATION_METADATA: &str = "authorization";
+/// Maximum size of the payload chunk, the messages are kept well below the gRPC 4 MiB limit.
+pub const PAYLOAD_CHUNK_SIZE: usize = 1 << 20;
+
+/// Splits the payload into the chunks of `PAYLOAD_CHUNK_SIZE` bytes.
+pub fn payload_chunks(payload: Vec<u8>) -> impl Iterator<Item = Vec<u8>> {
+    (0..payload.len())
+        .step_by(PAYLOAD_CHUNK_SIZE)
+        .map(move |start| payload[start..payload.len().min(start + PAYLOAD_CHUNK_SIZE)].to_vec())
+}
+
+/// Returns the header of the job followed by the job data chunks.
+pub fn job_parts(header: JobHeader, data: Vec<u8>) -> impl Iterator<Item = JobPart> {
+    std::iter::once(job_part::Part::Header(header))
+        .chain(payload_chunks(data).map(job_part::Part::Data))
+        .map(|part| JobPart { part: Some(part) })
+}
+
+/// Returns the header of the proof followed by the proof data chunks.
+pub fn proof_parts(header: ProofHeader, data: Vec<u8>) -> impl Iterator<Item = ProofPart> {
+    std::iter::once(proof_part::Part::Header(header))
+        .chain(payload_chunks(data).map(proof_part::Part::Data))
+        .map(|part| ProofPart { part: Some(part) })
+}
+
+/// Part of the stream sent by `job_parts` or `proof_parts`.
+pub enum StreamPart<H> {
+    Header(H),
+    Chunk(Vec<u8>),
+}
+
+pub trait IntoStreamPart {
+    type Header;
+
+    fn into_stream_part(self) -> Option<StreamPart<Self::Header>>;
+}
+
+impl IntoStreamPart for JobPart {
+    type Header = JobHeader;
+
+    fn into_stream_part(self) -> Option<StreamPart<JobHeader>> {
+        Some(match self.part? {
+            job_part::Part::Header(header) => StreamPart::Header(header),
+            job_part::Part::Data(chunk) => StreamPart::Chunk(chunk),
+        })
+    }
+}
+
+impl IntoStreamPart for ProofPart {
+    type Header = ProofHeader;
+
+    fn into_stream_part(self) -> Option<StreamPart<ProofHeader>> {
+        Some(match self.part? {
+            proof_part::Part::Header(header) => StreamPart::Header(header),
+            proof_part::Part::Data(chunk) => StreamPart::Chunk(chunk),
+        })
+    }
+}
+
+/// Receives the header and the payload sent by `job_parts` or `proof_parts`.
+/// Returns `None` if the stream is empty.
+pub async fn receive_parts<P, S>(mut stream: S) -> Result<Option<(P::Header, Vec<u8>)>, Status>
+where
+    P: IntoStreamPart,
+    S: Stream<Item = Result<P, Status>> + Unpin,
+{
+    let header = match stream.next().await.transpose()? {
+        Some(part) => match part.into_stream_part() {
+            Some(StreamPart::Header(header)) => header,
+            _ => return Err(Status::invalid_argument("the header is expected first")),
+        },
+        None => return Ok(None),
+    };
+
+    let mut payload = Vec::new();
+    while let Some(part) = stream.next().await.transpose()? {
+        match part.into_stream_part() {
+            Some(StreamPart::Chunk(chunk)) => payload.extend(chunk),
+            _ => return Err(Status::invalid_argument("the payload chunk is expected")),
+        }
+    }
+    Ok(Some((header, payload)))
+}
@@ -21,11 +21,14 @@ use zksync_crypto::params::RECURSIVE_CIRCUIT_VK_TREE_DEPTH;
 use zksync_crypto::proof::SingleProof;
 use zksync_crypto::recursive_aggregation_circuit::circuit::create_vks_tree;
 use zksync_crypto::{Engine, Fr};
+use zksync_types::prover::ProofProgress;
 
 pub mod aggregated_proofs;
 pub mod api;
+pub mod encoding;
 pub mod exit_proof;
 pub mod fs_utils;
+pub mod grpc;
 pub mod network_utils;
 
 pub const SETUP_MIN_POW2: u32 = 20;
@@ -42,6 +45,18 @@ impl PlonkVerificationKey {
         Ok(Self(verification_key))
     }
 
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, anyhow::Error> {
+        Ok(Self(VerificationKey::read(bytes)?))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.0
+            .write(&mut bytes)
+            .expect("Verification key can't be written to the buffer");
+        bytes
+    }
+
     pub fn read_verification_key_for_exit_circuit() -> Result<Self, anyhow::Error> {
         let verification_key =
             VerificationKey::read(File::open(get_exodus_verification_key_path())?)?;
@@ -75,8 +90,25 @@ impl SetupForStepByStepProver {
         circuit: C,
         download_setup_file: bool,
     ) -> Result<Self, anyhow::Error> {
+        Self::prepare_setup_for_step_by_step_prover_with_progress(
+            circuit,
+            download_setup_file,
+            |_| {},
+        )
+    }
+
+    /// Same as `prepare_setup_for_step_by_step_prover`, but reports the stages of the setup
+    /// preparation as they start.
+    pub fn prepare_setup_for_step_by_step_prover_with_progress<C: Circuit<Engine> + Clone>(
+        circuit: C,
+        download_setup_file: bool,
+        report_progress: impl Fn(ProofProgress),
+    ) -> Result<Self, anyhow::Error> {
+        report_progress(ProofProgress::Transpilation);
         let hints = transpile(circuit.clone())?;
+        report_progress(ProofProgress::Setup);
         let setup_polynomials = setup(circuit, &hints)?;
+        report_progress(ProofProgress::UniversalSetup);
         let size = setup_polynomials.n.next_power_of_two().trailing_zeros();
         let setup_power_of_two = std::cmp::max(size, SETUP_MIN_POW2); // for exit circuit
         let key_monomial_form = Some(get_universal_setup_monomial_form(
@@ -96,8 +128,7 @@ impl SetupForStepByStepProver {
         circuit: C,
         vk: &PlonkVerificationKey,
     ) -> Result<SingleProof, anyhow::Error> {
-        let rns_params =
-            RnsParameters::<Engine, <Engine as EngineTrait>::Fq>::new_for_field(68, 110, 4);
+        let rns_params = block_proof_rns_params();
         let rescue_params = Bn256RescueParams::new_checked_2_into_1();
 
         let transcript_params = (&rescue_params, &rns_params);
@@ -112,13 +143,32 @@ impl SetupForStepByStepProver {
             Some(transcript_params),
         )?;
 
-        let valid =
-            verify::<_, _, RescueTranscriptForRNS<Engine>>(&proof, &vk.0, Some(transcript_params))?;
-        anyhow::ensure!(valid, "proof for block is invalid");
-        Ok(proof.into())
+        let proof = SingleProof::from(proof);
+        anyhow::ensure!(
+            verify_block_proof(&proof, vk)?,
+            "proof for block is invalid"
+        );
+        Ok(proof)
     }
 }
 
+fn block_proof_rns_params() -> RnsParameters<Engine, <Engine as EngineTrait>::Fq> {
+    RnsParameters::<Engine, <Engine as EngineTrait>::Fq>::new_for_field(68, 110, 4)
+}
+
+/// Verifies the proof of the block against the verification key of the block size.
+pub fn verify_block_proof(
+    proof: &SingleProof,
+    vk: &PlonkVerificationKey,
+) -> Result<bool, anyhow::Error> {
+    let rns_params = block_proof_rns_params();
+    let rescue_params = Bn256RescueParams::new_checked_2_into_1();
+    let transcript_params = (&rescue_params, &rns_params);
+    let valid =
+        verify::<_, _, RescueTranscriptForRNS<Engine>>(&proof.0, &vk.0, Some(transcript_params))?;
+    Ok(valid)
+}
+
 impl Drop for SetupForStepByStepProver {
     fn drop(&mut self) {
         let setup = self
@@ -78,9 +78,15 @@ impl ZkSyncState {
             empty.next_free_id = AccountId(*sorted_accounts.last().unwrap().0 + 1);
         }
         empty.block_number = current_block;
-        for (id, account) in sorted_accounts {
-            empty.insert_account(id, account);
+        for (id, account) in &sorted_accounts {
+            empty.account_id_by_address.insert(account.address, *id);
         }
+        // Leaf hashes of the restored accounts are calculated in one batch.
+        empty.balance_tree.insert_many(
+            sorted_accounts
+                .into_iter()
+                .map(|(id, account)| (*id, account)),
+        );
         empty
     }
 
@@ -0,0 +1,6 @@
+-- Operator-controlled settings of the tokens, managed via the admin API.
+CREATE TABLE token_flags (
+    token_id INTEGER NOT NULL PRIMARY KEY REFERENCES tokens (id) ON UPDATE CASCADE ON DELETE CASCADE,
+    disabled BOOLEAN NOT NULL DEFAULT false,
+    fee_eligible BOOLEAN NOT NULL DEFAULT false
+);
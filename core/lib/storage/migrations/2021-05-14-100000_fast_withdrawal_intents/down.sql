@@ -0,0 +1 @@
+DROP TABLE IF EXISTS fast_withdrawal_intents;
@@ -0,0 +1,18 @@
+-- Intents to sell the withdrawals to the liquidity providers (see `zksync_types::fast_withdrawals`).
+CREATE TABLE fast_withdrawal_intents (
+    id BIGSERIAL PRIMARY KEY,
+    -- Hash of the withdrawal transaction.
+    tx_hash BYTEA NOT NULL UNIQUE,
+    account_id BIGINT NOT NULL,
+    token_id INTEGER NOT NULL,
+    liquidity_provider TEXT NOT NULL,
+    intent JSONB NOT NULL,
+    -- UNIX timestamp after which the withdrawal can't be executed.
+    valid_until BIGINT NOT NULL,
+    created_at TIMESTAMP with time zone NOT NULL,
+    -- Hash of the payout transfer.
+    fulfilled_by BYTEA,
+    fulfilled_at TIMESTAMP with time zone
+);
+CREATE INDEX fast_withdrawal_intents_pending_idx
+    ON fast_withdrawal_intents (created_at) WHERE fulfilled_at IS NULL;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS reverted_transactions;
+DROP TABLE IF EXISTS block_reverts;
@@ -0,0 +1,18 @@
+-- Reverts of the unverified blocks performed by the operator.
+CREATE TABLE block_reverts (
+    id BIGSERIAL PRIMARY KEY,
+    last_correct_block BIGINT NOT NULL,
+    last_reverted_block BIGINT NOT NULL,
+    -- `true` if the transactions of the reverted blocks were returned to the mempool,
+    -- `false` if they were rejected.
+    txs_returned_to_mempool BOOLEAN NOT NULL,
+    reverted_at TIMESTAMP with time zone NOT NULL
+);
+
+-- Transactions rejected because the blocks containing them were reverted.
+CREATE TABLE reverted_transactions (
+    tx_hash BYTEA NOT NULL PRIMARY KEY,
+    revert_id BIGINT NOT NULL REFERENCES block_reverts (id) ON DELETE CASCADE,
+    block_number BIGINT NOT NULL
+);
+CREATE INDEX reverted_transactions_revert_id_idx ON reverted_transactions (revert_id);
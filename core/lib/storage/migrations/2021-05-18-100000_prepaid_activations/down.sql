@@ -0,0 +1 @@
+DROP TABLE IF EXISTS prepaid_activations;
@@ -0,0 +1,7 @@
+-- Transfers prepaying the activation of the recipient accounts (see `zksync_types::activations`).
+CREATE TABLE prepaid_activations (
+    recipient TEXT PRIMARY KEY,
+    payer TEXT NOT NULL,
+    activation JSONB NOT NULL,
+    created_at TIMESTAMP with time zone NOT NULL
+);
@@ -0,0 +1,6 @@
+-- Alternative hashes of the transactions (e.g. Keccak-256 of the tx bytes),
+-- allowing to look the transaction up by the hash computed by Ethereum-oriented tools.
+CREATE TABLE tx_hash_aliases (
+    alias BYTEA PRIMARY KEY,
+    tx_hash BYTEA NOT NULL
+);
@@ -0,0 +1,9 @@
+-- Durable log of the chain events (see `zksync_types::event`).
+-- Offsets are assigned while holding a table lock, so they are ordered the same way as the commits.
+CREATE TABLE event_log (
+    id BIGSERIAL PRIMARY KEY,
+    block_number BIGINT NOT NULL,
+    event_type TEXT NOT NULL,
+    event_data JSONB NOT NULL,
+    created_at TIMESTAMP with time zone NOT NULL
+);
@@ -0,0 +1,32 @@
+-- URLs subscribed to the webhook notifications (see `zksync_types::webhooks`).
+CREATE TABLE webhook_subscriptions (
+    id BIGSERIAL PRIMARY KEY,
+    url TEXT NOT NULL,
+    event_type TEXT NOT NULL,
+    address TEXT,
+    -- Secret used to sign the notification payloads.
+    secret TEXT NOT NULL,
+    created_at TIMESTAMP with time zone NOT NULL
+);
+
+-- Notifications sent (or to be sent) to the subscribers.
+CREATE TABLE webhook_deliveries (
+    id BIGSERIAL PRIMARY KEY,
+    subscription_id BIGINT NOT NULL REFERENCES webhook_subscriptions (id) ON DELETE CASCADE,
+    event_offset BIGINT NOT NULL,
+    payload JSONB NOT NULL,
+    status TEXT NOT NULL,
+    attempts INT NOT NULL,
+    last_error TEXT,
+    next_attempt_at TIMESTAMP with time zone NOT NULL,
+    created_at TIMESTAMP with time zone NOT NULL,
+    delivered_at TIMESTAMP with time zone
+);
+CREATE INDEX webhook_deliveries_subscription_id_idx ON webhook_deliveries (subscription_id);
+CREATE INDEX webhook_deliveries_pending_idx ON webhook_deliveries (next_attempt_at) WHERE status = 'pending';
+
+-- Offset of the next event log entry to be processed by the webhook dispatcher.
+CREATE TABLE webhook_dispatcher_state (
+    id BOOL PRIMARY KEY DEFAULT true CHECK (id),
+    next_event_offset BIGINT NOT NULL
+);
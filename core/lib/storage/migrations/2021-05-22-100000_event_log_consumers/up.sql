@@ -0,0 +1,5 @@
+-- Progress of the services consuming the event log: offset of the next event to be processed.
+CREATE TABLE event_log_consumers (
+    name TEXT PRIMARY KEY,
+    next_event_offset BIGINT NOT NULL
+);
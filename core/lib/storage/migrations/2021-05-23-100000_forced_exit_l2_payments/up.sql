@@ -0,0 +1,10 @@
+-- L2 transfers paying for the ForcedExit requests. Every transfer is handled at most once.
+CREATE TABLE forced_exit_l2_payments (
+    tx_hash BYTEA PRIMARY KEY,
+    from_address BYTEA NOT NULL,
+    amount NUMERIC NOT NULL,
+    request_id BIGINT,
+    status TEXT NOT NULL,
+    refund_tx_hash BYTEA,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
@@ -0,0 +1,10 @@
+-- Fees collected by the operator, aggregated by the token and the hour of the transaction execution.
+-- USD value is computed using the token price at the moment of aggregation.
+CREATE TABLE operator_revenue (
+    period_start TIMESTAMP WITH TIME ZONE NOT NULL,
+    token_id INTEGER NOT NULL,
+    fee_amount NUMERIC NOT NULL,
+    fee_usd NUMERIC NOT NULL,
+    tx_count BIGINT NOT NULL,
+    PRIMARY KEY (period_start, token_id)
+);
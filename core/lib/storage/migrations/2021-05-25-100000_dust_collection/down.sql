@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS dust_collections;
+DROP TABLE IF EXISTS dust_collection_opt_outs;
@@ -0,0 +1,12 @@
+-- Accounts excluded from the dust collection by their owners.
+CREATE TABLE dust_collection_opt_outs (
+    address BYTEA PRIMARY KEY,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+-- The latest collection of every dust account.
+CREATE TABLE dust_collections (
+    account_id BIGINT PRIMARY KEY,
+    tx_hashes JSONB NOT NULL,
+    collected_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS key_usage_audit_append_only ON key_usage_audit;
+DROP FUNCTION IF EXISTS key_usage_audit_append_only;
+DROP TABLE IF EXISTS key_usage_audit;
@@ -0,0 +1,22 @@
+CREATE TABLE key_usage_audit (
+    id BIGSERIAL PRIMARY KEY,
+    key_type TEXT NOT NULL,
+    operation TEXT NOT NULL,
+    payload_hash BYTEA NOT NULL,
+    success BOOLEAN NOT NULL,
+    error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX key_usage_audit_created_at_idx ON key_usage_audit (created_at);
+
+-- The audit log is append-only.
+CREATE FUNCTION key_usage_audit_append_only() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'key_usage_audit is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER key_usage_audit_append_only
+    BEFORE UPDATE OR DELETE ON key_usage_audit
+    FOR EACH ROW EXECUTE PROCEDURE key_usage_audit_append_only();
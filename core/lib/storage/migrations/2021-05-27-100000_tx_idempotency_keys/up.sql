@@ -0,0 +1,11 @@
+-- Idempotency keys supplied by the clients along with the submitted transactions.
+-- `request_hashes` are the hashes of the transactions sent with the key, `tx_hashes` are
+-- the hashes of the transactions accepted to the mempool (NULL while the submission is in progress).
+CREATE TABLE tx_idempotency_keys (
+    idempotency_key TEXT PRIMARY KEY,
+    request_hashes JSONB NOT NULL,
+    tx_hashes JSONB,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX tx_idempotency_keys_created_at_idx ON tx_idempotency_keys (created_at);
@@ -0,0 +1,7 @@
+-- Gas cost of the withdrawals measured from the confirmed `executeBlocks` transactions.
+CREATE TABLE withdrawal_gas_costs (
+    token_id INTEGER PRIMARY KEY REFERENCES tokens(id) ON UPDATE CASCADE,
+    gas_cost BIGINT NOT NULL,
+    samples BIGINT NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS eth_watch_state;
+DROP TABLE IF EXISTS eth_watch_priority_ops;
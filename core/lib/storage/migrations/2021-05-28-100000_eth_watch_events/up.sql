@@ -0,0 +1,17 @@
+-- Priority operations consumed by `eth_watch`, persisted to run the server
+-- against the Ethereum nodes that do not keep the old logs.
+CREATE TABLE eth_watch_priority_ops (
+    serial_id BIGINT PRIMARY KEY,
+    eth_block BIGINT NOT NULL,
+    operation JSONB NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+CREATE INDEX eth_watch_priority_ops_eth_block_idx ON eth_watch_priority_ops (eth_block);
+
+CREATE TABLE eth_watch_state (
+    -- enforce single record
+    id bool PRIMARY KEY NOT NULL DEFAULT true,
+    CONSTRAINT single_eth_watch_state CHECK (id),
+    -- Last Ethereum block for which all the priority operations are persisted
+    last_processed_block BIGINT NOT NULL
+);
@@ -0,0 +1,10 @@
+-- Ranges of the account updates made by the executed L2 transactions,
+-- captured by the state keeper to trace the transactions execution.
+CREATE TABLE tx_account_updates (
+    tx_hash bytea PRIMARY KEY,
+    block_number BIGINT NOT NULL,
+    -- `update_order_id` of the first update made by the transaction
+    first_update_order_id INT NOT NULL,
+    updates_count INT NOT NULL
+);
+CREATE INDEX tx_account_updates_block_number_idx ON tx_account_updates (block_number);
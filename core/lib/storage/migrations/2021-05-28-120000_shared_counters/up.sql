@@ -0,0 +1,7 @@
+-- Counters shared by the API server replicas, e.g. paid fee subsidies and rate limits.
+-- The counter is reset once it's updated after `expires_at`.
+CREATE TABLE shared_counters (
+    key TEXT PRIMARY KEY,
+    value NUMERIC NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
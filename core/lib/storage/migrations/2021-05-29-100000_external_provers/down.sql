@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS external_prover_leases;
+DROP TABLE IF EXISTS external_provers;
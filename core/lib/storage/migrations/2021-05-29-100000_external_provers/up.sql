@@ -0,0 +1,38 @@
+-- Third-party provers contributing proving capacity through the external prover API.
+-- All the amounts are in wei.
+CREATE TABLE external_provers (
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    -- SHA-256 hash of the API key the prover authenticates with.
+    api_key_hash BYTEA NOT NULL UNIQUE,
+    -- Address the payments for the proven jobs are sent to.
+    reward_address BYTEA NOT NULL,
+    -- Collateral deposited by the prover, reduced by the slashed amounts.
+    stake NUMERIC NOT NULL,
+    -- Part of the stake locked as the collateral of the active leases.
+    locked_stake NUMERIC NOT NULL DEFAULT 0,
+    slashed_amount NUMERIC NOT NULL DEFAULT 0,
+    earned_amount NUMERIC NOT NULL DEFAULT 0,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+-- Prover jobs leased to the external provers.
+CREATE TABLE external_prover_leases (
+    id BIGSERIAL PRIMARY KEY,
+    prover_id BIGINT NOT NULL REFERENCES external_provers (id),
+    job_id INT NOT NULL REFERENCES prover_job_queue (id) ON DELETE CASCADE,
+    first_block BIGINT NOT NULL,
+    last_block BIGINT NOT NULL,
+    job_type TEXT NOT NULL,
+    collateral NUMERIC NOT NULL,
+    payment NUMERIC NOT NULL,
+    status TEXT NOT NULL,
+    slash_reason TEXT,
+    leased_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    deadline TIMESTAMP WITH TIME ZONE NOT NULL,
+    finished_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX external_prover_leases_prover_id_idx ON external_prover_leases (prover_id);
+CREATE INDEX external_prover_leases_status_idx ON external_prover_leases (status);
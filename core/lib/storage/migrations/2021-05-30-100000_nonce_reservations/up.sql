@@ -0,0 +1,15 @@
+-- Ranges of nonces reserved by the accounts sending transactions concurrently.
+CREATE TABLE nonce_reservations (
+    id BIGSERIAL PRIMARY KEY,
+    address BYTEA NOT NULL,
+    first_nonce BIGINT NOT NULL,
+    -- Last reserved nonce, inclusive.
+    last_nonce BIGINT NOT NULL,
+    -- Timestamp of the signed request, used to reject the replayed requests.
+    request_timestamp BIGINT NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    UNIQUE (address, request_timestamp)
+);
+
+CREATE INDEX nonce_reservations_expires_at_idx ON nonce_reservations (expires_at);
@@ -0,0 +1,10 @@
+-- L1 transactions executing the withdrawals, parsed from the `executeBlocks` transaction receipts.
+CREATE TABLE withdrawal_executions (
+    -- Hash of the L2 transaction, or the hash of the L1 transaction for the `FullExit` operations.
+    withdrawal_hash BYTEA PRIMARY KEY,
+    block_number BIGINT NOT NULL,
+    eth_tx_hash BYTEA NOT NULL,
+    -- `COMPLETED` if the funds were transferred, `PENDING` if they were stored in the pending balance.
+    status TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
@@ -0,0 +1,15 @@
+-- Audit trail of the transactions and priority operations sending funds to the screened addresses.
+CREATE TABLE screening_audit (
+    id BIGSERIAL PRIMARY KEY,
+    address BYTEA NOT NULL,
+    tx_hash BYTEA,
+    -- Deposits are screened every time the block containing them is proposed, so they're unique.
+    priority_op_serial_id BIGINT UNIQUE,
+    action TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    -- Time the delayed transaction is released for execution at.
+    release_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX screening_audit_release_at_idx ON screening_audit (release_at);
@@ -0,0 +1,13 @@
+-- L1 gas spent by the operator on the priority operations, attributed from the block transactions.
+CREATE TABLE priority_op_costs (
+    serial_id BIGINT PRIMARY KEY,
+    block_number BIGINT NOT NULL,
+    op_type TEXT NOT NULL,
+    commit_gas BIGINT,
+    commit_fee NUMERIC,
+    execute_gas BIGINT,
+    execute_fee NUMERIC,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX priority_op_costs_op_type_idx ON priority_op_costs (op_type, serial_id);
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS expired_transactions;
@@ -0,0 +1,7 @@
+-- Transactions removed from the mempool since they were not executed in time.
+CREATE TABLE expired_transactions (
+    id BIGSERIAL PRIMARY KEY,
+    tx_hash BYTEA NOT NULL UNIQUE,
+    reason TEXT NOT NULL,
+    expired_at TIMESTAMP with time zone NOT NULL
+);
@@ -0,0 +1,8 @@
+-- Human-readable aliases of the accounts, one alias per account.
+CREATE TABLE account_aliases (
+    alias TEXT PRIMARY KEY,
+    address BYTEA NOT NULL UNIQUE,
+    -- Timestamp of the signed registration request, used to reject the replayed requests.
+    request_timestamp BIGINT NOT NULL,
+    registered_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
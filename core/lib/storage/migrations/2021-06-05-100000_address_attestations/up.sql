@@ -0,0 +1,12 @@
+-- Challenges issued to prove the ownership of the Ethereum addresses, along with their outcomes.
+CREATE TABLE address_attestations (
+    challenge BYTEA PRIMARY KEY,
+    address BYTEA NOT NULL,
+    account_id BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- NULL until the signed response to the challenge is received.
+    verified BOOLEAN,
+    attested_at TIMESTAMP WITH TIME ZONE
+);
+CREATE INDEX address_attestations_address_idx ON address_attestations (address, created_at);
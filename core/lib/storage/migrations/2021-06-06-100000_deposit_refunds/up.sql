@@ -0,0 +1,15 @@
+-- Deposits which funds would get stuck on L2, along with the progress of their refunds.
+CREATE TABLE deposit_refunds (
+    serial_id BIGINT PRIMARY KEY,
+    eth_hash BYTEA NOT NULL,
+    from_address BYTEA NOT NULL,
+    to_address BYTEA NOT NULL,
+    token INTEGER NOT NULL,
+    amount NUMERIC NOT NULL,
+    reason TEXT NOT NULL,
+    status TEXT NOT NULL,
+    refund_tx_hash BYTEA,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+CREATE INDEX deposit_refunds_status_idx ON deposit_refunds (status);
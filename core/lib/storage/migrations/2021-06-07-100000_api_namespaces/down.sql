@@ -0,0 +1,2 @@
+ALTER TABLE webhook_subscriptions DROP COLUMN IF EXISTS namespace;
+DROP TABLE IF EXISTS api_namespaces;
@@ -0,0 +1,12 @@
+-- Namespaces of the API serving the white-label frontends.
+CREATE TABLE api_namespaces (
+    name TEXT PRIMARY KEY,
+    -- Only the hash of the API key is stored.
+    api_key_hash BYTEA NOT NULL UNIQUE,
+    requests_per_minute BIGINT NOT NULL,
+    fee_markup_percent BIGINT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+ALTER TABLE webhook_subscriptions
+    ADD COLUMN namespace TEXT REFERENCES api_namespaces (name) ON DELETE CASCADE;
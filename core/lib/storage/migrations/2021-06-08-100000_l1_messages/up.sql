@@ -0,0 +1,13 @@
+-- Messages sent to the zkSync accounts from L1, recorded in the blocks they're delivered in.
+CREATE TABLE l1_messages (
+    serial_id BIGINT PRIMARY KEY,
+    sender BYTEA NOT NULL,
+    recipient BYTEA NOT NULL,
+    payload BYTEA NOT NULL,
+    eth_hash BYTEA NOT NULL,
+    eth_block BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+CREATE INDEX l1_messages_recipient_idx ON l1_messages (recipient, serial_id);
+CREATE INDEX l1_messages_block_number_idx ON l1_messages (block_number);
@@ -0,0 +1,14 @@
+-- Sweeps of the collected fees from the fee account to the treasury.
+CREATE TABLE fee_sweeps (
+    id BIGSERIAL PRIMARY KEY,
+    token INTEGER NOT NULL,
+    amount NUMERIC NOT NULL,
+    treasury BYTEA NOT NULL,
+    kind TEXT NOT NULL,
+    tx_hash BYTEA NOT NULL,
+    status TEXT NOT NULL,
+    fail_reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+CREATE INDEX fee_sweeps_status_idx ON fee_sweeps (status);
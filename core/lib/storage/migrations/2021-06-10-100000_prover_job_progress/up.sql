@@ -0,0 +1,9 @@
+-- Progress of the proof generation reported by the provers working on the jobs.
+CREATE TABLE prover_job_progress (
+    job_id INTEGER PRIMARY KEY REFERENCES prover_job_queue (id) ON DELETE CASCADE,
+    prover_name TEXT NOT NULL,
+    stage TEXT NOT NULL,
+    percent INTEGER,
+    stage_started_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
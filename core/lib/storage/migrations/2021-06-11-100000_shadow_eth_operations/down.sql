@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS shadow_eth_tx_hashes;
+DROP TABLE IF EXISTS shadow_eth_operations;
+DROP TABLE IF EXISTS shadow_eth_parameters;
@@ -0,0 +1,41 @@
+-- State of the `eth_sender` instances sending the operations to the shadow networks
+-- (e.g. a mainnet fork), tracked separately from the state of the actual network.
+CREATE TABLE shadow_eth_parameters (
+    network TEXT PRIMARY KEY,
+    nonce BIGINT NOT NULL,
+    gas_price_limit BIGINT NOT NULL,
+    average_gas_price BIGINT,
+    -- Last blocks the operations were sent to the shadow network for.
+    last_committed_block BIGINT NOT NULL,
+    last_verified_block BIGINT NOT NULL,
+    last_executed_block BIGINT NOT NULL,
+    -- Last blocks the operations were taken into processing for.
+    loaded_committed_block BIGINT NOT NULL,
+    loaded_verified_block BIGINT NOT NULL,
+    loaded_executed_block BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE TABLE shadow_eth_operations (
+    id BIGSERIAL PRIMARY KEY,
+    network TEXT NOT NULL REFERENCES shadow_eth_parameters (network) ON DELETE CASCADE,
+    op_id BIGINT NOT NULL REFERENCES aggregate_operations (id) ON DELETE CASCADE,
+    op_type TEXT NOT NULL,
+    nonce BIGINT NOT NULL,
+    last_deadline_block BIGINT NOT NULL,
+    last_used_gas_price NUMERIC NOT NULL,
+    raw_tx BYTEA NOT NULL,
+    confirmed BOOLEAN NOT NULL DEFAULT false,
+    final_hash BYTEA,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    UNIQUE (network, op_id)
+);
+
+CREATE TABLE shadow_eth_tx_hashes (
+    id BIGSERIAL PRIMARY KEY,
+    eth_op_id BIGINT NOT NULL REFERENCES shadow_eth_operations (id) ON DELETE CASCADE,
+    tx_hash BYTEA NOT NULL
+);
+
+CREATE INDEX shadow_eth_tx_hashes_eth_op_id_idx ON shadow_eth_tx_hashes (eth_op_id);
+CREATE INDEX shadow_eth_tx_hashes_tx_hash_idx ON shadow_eth_tx_hashes (tx_hash);
@@ -0,0 +1,16 @@
+-- Refunds of the fees paid over the fee computed by the server on submission.
+CREATE TABLE fee_refunds (
+    id BIGSERIAL PRIMARY KEY,
+    tx_hash BYTEA NOT NULL UNIQUE,
+    address BYTEA NOT NULL,
+    token INTEGER NOT NULL,
+    signed_fee NUMERIC NOT NULL,
+    actual_fee NUMERIC NOT NULL,
+    refund NUMERIC NOT NULL DEFAULT 0,
+    refund_tx_hash BYTEA,
+    status TEXT NOT NULL,
+    fail_reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+CREATE INDEX fee_refunds_status_idx ON fee_refunds (status);
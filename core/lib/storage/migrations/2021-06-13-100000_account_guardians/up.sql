@@ -0,0 +1,22 @@
+-- Guardian sets registered by the accounts for the social recovery, one set per account.
+CREATE TABLE account_guardians (
+    account_id BIGINT PRIMARY KEY,
+    address BYTEA NOT NULL,
+    -- Version of the set signed by the owner, used to reject the replayed registrations.
+    version BIGINT NOT NULL,
+    guardian_set JSONB NOT NULL,
+    owner_signature BYTEA NOT NULL,
+    registered_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+-- Recoveries initiated by the guardians, the `ChangePubKey` is accepted after `executable_at`.
+CREATE TABLE guardian_recoveries (
+    id BIGSERIAL PRIMARY KEY,
+    account_id BIGINT NOT NULL REFERENCES account_guardians (account_id) ON DELETE CASCADE,
+    new_pk_hash BYTEA NOT NULL,
+    nonce BIGINT NOT NULL,
+    signatures JSONB NOT NULL,
+    initiated_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    executable_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    UNIQUE (account_id, new_pk_hash, nonce)
+);
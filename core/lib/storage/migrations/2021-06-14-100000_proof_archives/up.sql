@@ -0,0 +1,8 @@
+-- Archives of the verified blocks exported for the long-term retention, see `proof-archiver`.
+CREATE TABLE proof_archives (
+    block_number BIGINT PRIMARY KEY,
+    file_name TEXT NOT NULL,
+    -- Hash of the archive file, so the retained copy can be checked against the record.
+    archive_hash BYTEA NOT NULL,
+    exported_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
@@ -0,0 +1,2 @@
+ALTER TABLE deposit_refunds DROP COLUMN IF EXISTS refund_raw_tx;
+ALTER TABLE deposit_refunds DROP COLUMN IF EXISTS refund_tx_nonce;
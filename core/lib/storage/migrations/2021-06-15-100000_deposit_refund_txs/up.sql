@@ -0,0 +1,4 @@
+-- The refund transaction is stored before it's broadcast, so it can be resent
+-- if it's dropped or if the server restarts before sending it.
+ALTER TABLE deposit_refunds ADD COLUMN refund_tx_nonce BIGINT;
+ALTER TABLE deposit_refunds ADD COLUMN refund_raw_tx BYTEA;
@@ -0,0 +1,8 @@
+-- Messages received by `eth_watch` and not necessarily delivered yet, persisted along with
+-- the priority operations so that the watcher doesn't lose them on restart.
+CREATE TABLE eth_watch_l1_messages (
+    serial_id BIGINT PRIMARY KEY,
+    eth_block BIGINT NOT NULL,
+    message JSONB NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
@@ -0,0 +1 @@
+DROP INDEX IF EXISTS address_attestations_pending_idx;
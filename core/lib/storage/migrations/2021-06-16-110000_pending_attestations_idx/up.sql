@@ -0,0 +1,2 @@
+-- Challenges waiting for the response, used to limit and clean them up.
+CREATE INDEX address_attestations_pending_idx ON address_attestations (expires_at) WHERE verified IS NULL;
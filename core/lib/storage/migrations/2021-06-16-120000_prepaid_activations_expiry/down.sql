@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS prepaid_activations_expires_at_idx;
+ALTER TABLE prepaid_activations DROP COLUMN IF EXISTS expires_at;
@@ -0,0 +1,5 @@
+-- Prepaid activations are held until they expire (see `zksync_types::activations`).
+ALTER TABLE prepaid_activations ADD COLUMN expires_at TIMESTAMP with time zone;
+UPDATE prepaid_activations SET expires_at = created_at + interval '7 days';
+ALTER TABLE prepaid_activations ALTER COLUMN expires_at SET NOT NULL;
+CREATE INDEX prepaid_activations_expires_at_idx ON prepaid_activations (expires_at);
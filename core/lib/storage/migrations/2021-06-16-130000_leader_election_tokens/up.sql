@@ -0,0 +1,7 @@
+-- Fencing tokens of the leadership (see `zksync_storage::leader_election`): the token is
+-- increased every time the leadership is taken over, so the writes of the previous leader
+-- are rejected once the new one is elected.
+CREATE TABLE leader_election_tokens (
+    lock_id BIGINT PRIMARY KEY,
+    token BIGINT NOT NULL
+);
@@ -0,0 +1,5 @@
+-- Rows locked by the reservations of the account, so the concurrent reservations
+-- of the same account don't overlap while the other accounts aren't blocked.
+CREATE TABLE nonce_reservation_accounts (
+    address BYTEA PRIMARY KEY
+);
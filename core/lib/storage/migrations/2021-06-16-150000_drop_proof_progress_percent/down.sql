@@ -0,0 +1 @@
+ALTER TABLE prover_job_progress ADD COLUMN percent INTEGER;
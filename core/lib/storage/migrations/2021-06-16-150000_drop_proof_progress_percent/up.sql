@@ -0,0 +1,2 @@
+-- The provers report the stages of the proof generation only, without the percentages.
+ALTER TABLE prover_job_progress DROP COLUMN percent;
@@ -0,0 +1,7 @@
+-- Version of the token data, incremented on every change of the tokens,
+-- so that the token caches of all the API server instances can detect the changes.
+CREATE TABLE tokens_version (
+    id BOOLEAN NOT NULL PRIMARY KEY DEFAULT true CHECK (id),
+    version BIGINT NOT NULL
+);
+INSERT INTO tokens_version (id, version) VALUES (true, 0);
@@ -0,0 +1,8 @@
+-- Block production halt, set once the local state diverges from L1 or the block
+-- would violate the priority operations continuity. It's kept between the restarts
+-- of the server until the blocks are reverted.
+CREATE TABLE block_production_halt (
+    id BOOLEAN NOT NULL PRIMARY KEY DEFAULT true CHECK (id),
+    reason TEXT NOT NULL,
+    halted_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
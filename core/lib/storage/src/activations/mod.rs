@@ -0,0 +1,102 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::{DateTime, Utc};
+use sqlx::Done;
+// Workspace imports
+use zksync_types::{activations::PrepaidActivation, Address};
+// Local imports
+use crate::{utils::address_to_stored_string, QueryResult, StorageProcessor};
+
+pub mod records;
+
+use records::DbPrepaidActivation;
+
+/// Activations schema handles the `prepaid_activations` table, storing the transfers
+/// prepaying the `ChangePubKey` of the recipient accounts.
+#[derive(Debug)]
+pub struct ActivationsSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> ActivationsSchema<'a, 'c> {
+    /// Stores the prepaid activation, replacing the expired one.
+    /// Returns `false` if the activation of the same account is already prepaid.
+    pub async fn store_prepaid_activation(
+        &mut self,
+        activation: &PrepaidActivation,
+        created_at: DateTime<Utc>,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO prepaid_activations ( recipient, payer, activation, created_at, expires_at )
+            VALUES ( $1, $2, $3, $4, $5 )
+            ON CONFLICT (recipient) DO UPDATE
+            SET payer = $2, activation = $3, created_at = $4, expires_at = $5
+            WHERE prepaid_activations.expires_at <= $4
+            "#,
+            address_to_stored_string(&activation.recipient()),
+            address_to_stored_string(&activation.payer()),
+            serde_json::to_value(activation)?,
+            created_at,
+            activation.expires_at(created_at),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.activations.store_prepaid_activation", start.elapsed());
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Loads the unexpired prepaid activation of the account along with the moment it was prepaid.
+    pub async fn get_prepaid_activation(
+        &mut self,
+        recipient: Address,
+    ) -> QueryResult<Option<(PrepaidActivation, DateTime<Utc>)>> {
+        let start = Instant::now();
+        let activation = sqlx::query_as!(
+            DbPrepaidActivation,
+            "SELECT * FROM prepaid_activations WHERE recipient = $1 AND expires_at > now()",
+            address_to_stored_string(&recipient),
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|activation| {
+            (
+                serde_json::from_value(activation.activation)
+                    .expect("Invalid prepaid activation has been stored"),
+                activation.created_at,
+            )
+        });
+
+        metrics::histogram!("sql.activations.get_prepaid_activation", start.elapsed());
+        Ok(activation)
+    }
+
+    /// Removes the prepaid activation once it's used or can't be used anymore.
+    pub async fn remove_prepaid_activation(&mut self, recipient: Address) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "DELETE FROM prepaid_activations WHERE recipient = $1",
+            address_to_stored_string(&recipient),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.activations.remove_prepaid_activation", start.elapsed());
+        Ok(())
+    }
+
+    /// Removes the expired prepaid activations. Returns the number of the removed ones.
+    pub async fn remove_expired_prepaid_activations(&mut self) -> QueryResult<u64> {
+        let start = Instant::now();
+        let result = sqlx::query!("DELETE FROM prepaid_activations WHERE expires_at <= now()")
+            .execute(self.0.conn())
+            .await?;
+
+        metrics::histogram!(
+            "sql.activations.remove_expired_prepaid_activations",
+            start.elapsed()
+        );
+        Ok(result.rows_affected())
+    }
+}
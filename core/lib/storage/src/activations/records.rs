@@ -0,0 +1,13 @@
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+// Local imports
+
+#[derive(Debug, Clone)]
+pub struct DbPrepaidActivation {
+    pub recipient: String,
+    pub payer: String,
+    pub activation: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
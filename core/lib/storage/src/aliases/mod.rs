@@ -0,0 +1,121 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+// Workspace imports
+use zksync_types::{account_alias::AccountAlias, Address};
+// Local imports
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+use records::DbAccountAlias;
+
+/// Outcome of the alias registration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AliasRegistration {
+    Registered,
+    /// The alias belongs to another account.
+    AliasTaken,
+    /// The account has registered an alias with a newer request.
+    OutdatedRequest,
+}
+
+/// Aliases schema handles the `account_aliases` table, storing the human-readable
+/// aliases of the accounts.
+#[derive(Debug)]
+pub struct AliasesSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> AliasesSchema<'a, 'c> {
+    /// Registers the alias of the account, releasing its previous alias.
+    /// The request timestamp must be greater than the one of the previous registration.
+    pub async fn register_alias(
+        &mut self,
+        alias: &str,
+        address: Address,
+        request_timestamp: u64,
+    ) -> QueryResult<AliasRegistration> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        // Concurrent registrations of the same alias must not override each other.
+        sqlx::query!("LOCK TABLE account_aliases IN EXCLUSIVE MODE")
+            .execute(transaction.conn())
+            .await?;
+        let owner = sqlx::query!(
+            "SELECT address FROM account_aliases WHERE alias = $1",
+            alias
+        )
+        .fetch_optional(transaction.conn())
+        .await?;
+        if matches!(owner, Some(owner) if owner.address != address.as_bytes()) {
+            return Ok(AliasRegistration::AliasTaken);
+        }
+        let last_request = sqlx::query!(
+            "SELECT request_timestamp FROM account_aliases WHERE address = $1",
+            address.as_bytes()
+        )
+        .fetch_optional(transaction.conn())
+        .await?;
+        if matches!(last_request, Some(last) if last.request_timestamp >= request_timestamp as i64)
+        {
+            return Ok(AliasRegistration::OutdatedRequest);
+        }
+
+        sqlx::query!(
+            "DELETE FROM account_aliases WHERE address = $1",
+            address.as_bytes()
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO account_aliases ( alias, address, request_timestamp )
+            VALUES ( $1, $2, $3 )
+            "#,
+            alias,
+            address.as_bytes(),
+            request_timestamp as i64,
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.aliases.register_alias", start.elapsed());
+        Ok(AliasRegistration::Registered)
+    }
+
+    /// Loads the registration of the alias.
+    pub async fn get_alias(&mut self, alias: &str) -> QueryResult<Option<AccountAlias>> {
+        let start = Instant::now();
+        let alias = sqlx::query_as!(
+            DbAccountAlias,
+            "SELECT * FROM account_aliases WHERE alias = $1",
+            alias
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(AccountAlias::from);
+
+        metrics::histogram!("sql.aliases.get_alias", start.elapsed());
+        Ok(alias)
+    }
+
+    /// Loads the alias registered by the account.
+    pub async fn get_account_alias(
+        &mut self,
+        address: Address,
+    ) -> QueryResult<Option<AccountAlias>> {
+        let start = Instant::now();
+        let alias = sqlx::query_as!(
+            DbAccountAlias,
+            "SELECT * FROM account_aliases WHERE address = $1",
+            address.as_bytes()
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(AccountAlias::from);
+
+        metrics::histogram!("sql.aliases.get_account_alias", start.elapsed());
+        Ok(alias)
+    }
+}
@@ -0,0 +1,23 @@
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_types::{account_alias::AccountAlias, Address};
+// Local imports
+
+#[derive(Debug, Clone)]
+pub struct DbAccountAlias {
+    pub alias: String,
+    pub address: Vec<u8>,
+    pub request_timestamp: i64,
+    pub registered_at: DateTime<Utc>,
+}
+
+impl From<DbAccountAlias> for AccountAlias {
+    fn from(alias: DbAccountAlias) -> Self {
+        Self {
+            alias: alias.alias,
+            address: Address::from_slice(&alias.address),
+            registered_at: alias.registered_at,
+        }
+    }
+}
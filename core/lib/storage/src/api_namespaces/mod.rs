@@ -0,0 +1,128 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use sqlx::Done;
+// Workspace imports
+use zksync_types::{
+    api_namespace::{ApiNamespace, ApiNamespaceLimits},
+    H256,
+};
+// Local imports
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+use records::DbApiNamespace;
+
+/// API namespaces schema handles the `api_namespaces` table, storing the namespaces
+/// of the white-label frontends along with their quotas.
+#[derive(Debug)]
+pub struct ApiNamespacesSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> ApiNamespacesSchema<'a, 'c> {
+    /// Creates the namespace. Returns `None` if the name or the API key is already taken.
+    pub async fn add_namespace(
+        &mut self,
+        name: &str,
+        api_key_hash: H256,
+        limits: ApiNamespaceLimits,
+    ) -> QueryResult<Option<ApiNamespace>> {
+        let start = Instant::now();
+        let namespace = sqlx::query_as!(
+            DbApiNamespace,
+            r#"
+            INSERT INTO api_namespaces ( name, api_key_hash, requests_per_minute, fee_markup_percent )
+            VALUES ( $1, $2, $3, $4 )
+            ON CONFLICT DO NOTHING
+            RETURNING name, requests_per_minute, fee_markup_percent, created_at
+            "#,
+            name,
+            api_key_hash.as_bytes(),
+            i64::from(limits.requests_per_minute),
+            limits.fee_markup_percent.map(i64::from),
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.api_namespaces.add_namespace", start.elapsed());
+        Ok(namespace.map(ApiNamespace::from))
+    }
+
+    /// Loads all the namespaces ordered by name.
+    pub async fn load_namespaces(&mut self) -> QueryResult<Vec<ApiNamespace>> {
+        let start = Instant::now();
+        let namespaces = sqlx::query_as!(
+            DbApiNamespace,
+            r#"
+            SELECT name, requests_per_minute, fee_markup_percent, created_at FROM api_namespaces
+            ORDER BY name ASC
+            "#
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(ApiNamespace::from)
+        .collect();
+
+        metrics::histogram!("sql.api_namespaces.load_namespaces", start.elapsed());
+        Ok(namespaces)
+    }
+
+    /// Returns the namespace the API key belongs to.
+    pub async fn get_namespace_by_key(
+        &mut self,
+        api_key_hash: H256,
+    ) -> QueryResult<Option<ApiNamespace>> {
+        let start = Instant::now();
+        let namespace = sqlx::query_as!(
+            DbApiNamespace,
+            r#"
+            SELECT name, requests_per_minute, fee_markup_percent, created_at FROM api_namespaces
+            WHERE api_key_hash = $1
+            "#,
+            api_key_hash.as_bytes()
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.api_namespaces.get_namespace_by_key", start.elapsed());
+        Ok(namespace.map(ApiNamespace::from))
+    }
+
+    /// Updates the quotas of the namespace. Returns `None` if there is no such namespace.
+    pub async fn update_limits(
+        &mut self,
+        name: &str,
+        limits: ApiNamespaceLimits,
+    ) -> QueryResult<Option<ApiNamespace>> {
+        let start = Instant::now();
+        let namespace = sqlx::query_as!(
+            DbApiNamespace,
+            r#"
+            UPDATE api_namespaces SET requests_per_minute = $2, fee_markup_percent = $3
+            WHERE name = $1
+            RETURNING name, requests_per_minute, fee_markup_percent, created_at
+            "#,
+            name,
+            i64::from(limits.requests_per_minute),
+            limits.fee_markup_percent.map(i64::from),
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.api_namespaces.update_limits", start.elapsed());
+        Ok(namespace.map(ApiNamespace::from))
+    }
+
+    /// Removes the namespace along with its webhook subscriptions.
+    /// Returns `false` if there is no such namespace.
+    pub async fn remove_namespace(&mut self, name: &str) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!("DELETE FROM api_namespaces WHERE name = $1", name)
+            .execute(self.0.conn())
+            .await?;
+
+        metrics::histogram!("sql.api_namespaces.remove_namespace", start.elapsed());
+        Ok(result.rows_affected() == 1)
+    }
+}
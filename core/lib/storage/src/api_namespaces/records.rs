@@ -0,0 +1,24 @@
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_types::api_namespace::ApiNamespace;
+// Local imports
+
+#[derive(Debug, Clone)]
+pub struct DbApiNamespace {
+    pub name: String,
+    pub requests_per_minute: i64,
+    pub fee_markup_percent: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<DbApiNamespace> for ApiNamespace {
+    fn from(namespace: DbApiNamespace) -> Self {
+        Self {
+            name: namespace.name,
+            requests_per_minute: namespace.requests_per_minute as u32,
+            fee_markup_percent: namespace.fee_markup_percent.map(|markup| markup as u32),
+            created_at: namespace.created_at,
+        }
+    }
+}
@@ -0,0 +1,148 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+// Workspace imports
+use zksync_types::{
+    address_attestation::{AddressAttestation, AttestationChallenge},
+    Address, H256,
+};
+// Local imports
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+use records::DbAddressAttestation;
+
+/// Attestations schema handles the `address_attestations` table, storing the challenges
+/// issued to prove the ownership of the addresses and the outcomes of their verification.
+#[derive(Debug)]
+pub struct AttestationsSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> AttestationsSchema<'a, 'c> {
+    /// Stores the issued challenge.
+    pub async fn store_challenge(&mut self, challenge: &AttestationChallenge) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO address_attestations ( challenge, address, account_id, expires_at )
+            VALUES ( $1, $2, $3, $4 )
+            "#,
+            challenge.challenge.as_bytes(),
+            challenge.address.as_bytes(),
+            *challenge.account_id as i64,
+            challenge.expires_at,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.attestations.store_challenge", start.elapsed());
+        Ok(())
+    }
+
+    /// Returns the number of the challenges of the address waiting for the response.
+    pub async fn count_pending_challenges(&mut self, address: Address) -> QueryResult<u32> {
+        let start = Instant::now();
+        let count = sqlx::query!(
+            r#"
+            SELECT COUNT(*) as "count!" FROM address_attestations
+            WHERE address = $1 AND verified IS NULL AND expires_at > now()
+            "#,
+            address.as_bytes()
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .count;
+
+        metrics::histogram!("sql.attestations.count_pending_challenges", start.elapsed());
+        Ok(count as u32)
+    }
+
+    /// Removes the challenges that expired without the response.
+    /// Returns the number of the removed challenges.
+    pub async fn remove_expired_challenges(&mut self) -> QueryResult<u64> {
+        let start = Instant::now();
+        let removed = sqlx::query!(
+            "DELETE FROM address_attestations WHERE verified IS NULL AND expires_at <= now()"
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected();
+
+        metrics::histogram!(
+            "sql.attestations.remove_expired_challenges",
+            start.elapsed()
+        );
+        Ok(removed)
+    }
+
+    /// Loads the attestation by its challenge.
+    pub async fn get_attestation(
+        &mut self,
+        challenge: H256,
+    ) -> QueryResult<Option<AddressAttestation>> {
+        let start = Instant::now();
+        let attestation = sqlx::query_as!(
+            DbAddressAttestation,
+            "SELECT * FROM address_attestations WHERE challenge = $1",
+            challenge.as_bytes()
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(AddressAttestation::from);
+
+        metrics::histogram!("sql.attestations.get_attestation", start.elapsed());
+        Ok(attestation)
+    }
+
+    /// Records the correct signature of the challenge. Each challenge can be attested once,
+    /// and only until it expires, so `None` is returned for the attested and expired challenges.
+    pub async fn record_verified(
+        &mut self,
+        challenge: H256,
+    ) -> QueryResult<Option<AddressAttestation>> {
+        let start = Instant::now();
+        let attestation = sqlx::query_as!(
+            DbAddressAttestation,
+            r#"
+            UPDATE address_attestations SET verified = true, attested_at = now()
+            WHERE challenge = $1 AND verified IS NULL AND expires_at > now()
+            RETURNING *
+            "#,
+            challenge.as_bytes()
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(AddressAttestation::from);
+
+        metrics::histogram!("sql.attestations.record_verified", start.elapsed());
+        Ok(attestation)
+    }
+
+    /// Loads the latest attestations of the address, newest first. The challenges waiting
+    /// for the response are not included.
+    pub async fn address_attestations(
+        &mut self,
+        address: Address,
+        limit: u32,
+    ) -> QueryResult<Vec<AddressAttestation>> {
+        let start = Instant::now();
+        let attestations = sqlx::query_as!(
+            DbAddressAttestation,
+            r#"
+            SELECT * FROM address_attestations WHERE address = $1 AND verified IS NOT NULL
+            ORDER BY attested_at DESC
+            LIMIT $2
+            "#,
+            address.as_bytes(),
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(AddressAttestation::from)
+        .collect();
+
+        metrics::histogram!("sql.attestations.address_attestations", start.elapsed());
+        Ok(attestations)
+    }
+}
@@ -0,0 +1,40 @@
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_types::{
+    address_attestation::{AddressAttestation, AttestationStatus},
+    AccountId, Address, H256,
+};
+// Local imports
+
+#[derive(Debug, Clone)]
+pub struct DbAddressAttestation {
+    pub challenge: Vec<u8>,
+    pub address: Vec<u8>,
+    pub account_id: i64,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub verified: Option<bool>,
+    pub attested_at: Option<DateTime<Utc>>,
+}
+
+impl From<DbAddressAttestation> for AddressAttestation {
+    fn from(attestation: DbAddressAttestation) -> Self {
+        let status = if attestation.verified == Some(true) {
+            AttestationStatus::Verified
+        } else if attestation.expires_at <= Utc::now() {
+            AttestationStatus::Expired
+        } else {
+            AttestationStatus::Pending
+        };
+        Self {
+            challenge: H256::from_slice(&attestation.challenge),
+            address: Address::from_slice(&attestation.address),
+            account_id: AccountId(attestation.account_id as u32),
+            status,
+            created_at: attestation.created_at,
+            expires_at: attestation.expires_at,
+            attested_at: attestation.attested_at,
+        }
+    }
+}
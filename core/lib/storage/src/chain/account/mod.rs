@@ -3,7 +3,7 @@ use std::time::Instant;
 // External imports
 use sqlx::Acquire;
 // Workspace imports
-use zksync_types::{Account, AccountId, AccountUpdates, Address};
+use zksync_types::{Account, AccountId, AccountUpdates, Address, BlockNumber};
 // Local imports
 use self::records::*;
 use crate::diff::StorageAccountDiff;
@@ -121,6 +121,27 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
     pub async fn last_committed_state_for_account(
         &mut self,
         account_id: AccountId,
+    ) -> QueryResult<Option<Account>> {
+        let start = Instant::now();
+        let account_state = self
+            .account_state_at_block(account_id, BlockNumber(u32::MAX))
+            .await?;
+
+        metrics::histogram!(
+            "sql.chain.account.last_committed_state_for_account",
+            start.elapsed()
+        );
+        Ok(account_state)
+    }
+
+    /// Loads the state of the account obtained in the block with the given number.
+    ///
+    /// Only the changes made after the last verified block are stored as diffs, so for the
+    /// blocks preceding it the last verified state is returned.
+    pub async fn account_state_at_block(
+        &mut self,
+        account_id: AccountId,
+        block_number: BlockNumber,
     ) -> QueryResult<Option<Account>> {
         let start = Instant::now();
         let mut transaction = self.0.start_transaction().await?;
@@ -136,10 +157,11 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
             StorageAccountUpdate,
             "
                 SELECT * FROM account_balance_updates
-                WHERE account_id = $1 AND block_number > $2
+                WHERE account_id = $1 AND block_number > $2 AND block_number <= $3
             ",
             i64::from(*account_id),
-            last_block
+            last_block,
+            i64::from(*block_number)
         )
         .fetch_all(transaction.conn())
         .await?;
@@ -148,10 +170,11 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
             StorageAccountCreation,
             "
                 SELECT * FROM account_creates
-                WHERE account_id = $1 AND block_number > $2
+                WHERE account_id = $1 AND block_number > $2 AND block_number <= $3
             ",
             i64::from(*account_id),
-            last_block
+            last_block,
+            i64::from(*block_number)
         )
         .fetch_all(transaction.conn())
         .await?;
@@ -160,10 +183,11 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
             StorageAccountPubkeyUpdate,
             "
                 SELECT * FROM account_pubkey_updates
-                WHERE account_id = $1 AND block_number > $2
+                WHERE account_id = $1 AND block_number > $2 AND block_number <= $3
             ",
             i64::from(*account_id),
-            last_block
+            last_block,
+            i64::from(*block_number)
         )
         .fetch_all(transaction.conn())
         .await?;
@@ -202,10 +226,7 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
 
         transaction.commit().await?;
 
-        metrics::histogram!(
-            "sql.chain.account.last_committed_state_for_account",
-            start.elapsed()
-        );
+        metrics::histogram!("sql.chain.account.account_state_at_block", start.elapsed());
         Ok(account_state)
     }
 
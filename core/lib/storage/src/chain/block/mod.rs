@@ -6,13 +6,15 @@ use zksync_basic_types::{H256, U256};
 use zksync_crypto::convert::FeConvert;
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
-    block::{Block, BlockMetadata, ExecutedOperations, PendingBlock},
+    block::{Block, BlockMetadata, BlockRevert, ExecutedOperations, PendingBlock},
+    event::ChainEvent,
+    finality::FinalizedBlocks,
     AccountId, BlockNumber, Fr, ZkSyncOp,
 };
 // Local imports
 use self::records::{
     AccountTreeCache, BlockDetails, BlockTransactionItem, StorageBlock, StorageBlockMetadata,
-    StoragePendingBlock,
+    StorageBlockRevert, StoragePendingBlock,
 };
 use crate::{
     chain::account::records::EthAccountType,
@@ -23,6 +25,7 @@ use crate::{
         },
         OperationsSchema,
     },
+    event::EventSchema,
     QueryResult, StorageProcessor,
 };
 
@@ -491,6 +494,31 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         result
     }
 
+    /// Returns the timestamps of the latest saved blocks, starting from the newest one.
+    pub async fn get_recent_block_timestamps(&mut self, limit: u32) -> QueryResult<Vec<u64>> {
+        let start = Instant::now();
+        let timestamps = sqlx::query!(
+            r#"
+            SELECT timestamp as "timestamp!" FROM blocks
+            WHERE timestamp IS NOT NULL
+            ORDER BY number DESC
+            LIMIT $1
+            "#,
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| row.timestamp as u64)
+        .collect();
+
+        metrics::histogram!(
+            "sql.chain.block.get_recent_block_timestamps",
+            start.elapsed()
+        );
+        Ok(timestamps)
+    }
+
     /// Returns the number of last block saved to the database.
     pub async fn get_last_saved_block(&mut self) -> QueryResult<BlockNumber> {
         let start = Instant::now();
@@ -503,6 +531,24 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         Ok(BlockNumber(count as u32))
     }
 
+    /// Returns the serial ID of the first priority operation not processed by the saved blocks.
+    pub async fn get_next_sealed_priority_op_serial_id(&mut self) -> QueryResult<u64> {
+        let start = Instant::now();
+        let next_serial_id = sqlx::query!(
+            "SELECT unprocessed_prior_op_after FROM blocks ORDER BY number DESC LIMIT 1"
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|block| block.unprocessed_prior_op_after as u64)
+        .unwrap_or(0);
+
+        metrics::histogram!(
+            "sql.chain.block.get_next_sealed_priority_op_serial_id",
+            start.elapsed()
+        );
+        Ok(next_serial_id)
+    }
+
     /// Returns the number of last block for which an aggregated operation exists.
     pub async fn get_last_committed_block(&mut self) -> QueryResult<BlockNumber> {
         let start = Instant::now();
@@ -541,6 +587,39 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         result
     }
 
+    /// Returns the last blocks that have reached each of the finality levels,
+    /// i.e. the blocks which commit, proof and execute operations are confirmed on Ethereum.
+    pub async fn load_finalized_blocks(&mut self) -> QueryResult<FinalizedBlocks> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let mut operations = OperationsSchema(&mut transaction);
+        let committed = operations
+            .get_last_block_by_aggregated_action(AggregatedActionType::CommitBlocks, Some(true))
+            .await?;
+        let verified = operations
+            .get_last_block_by_aggregated_action(
+                AggregatedActionType::PublishProofBlocksOnchain,
+                Some(true),
+            )
+            .await?;
+        let executed = operations
+            .get_last_block_by_aggregated_action(AggregatedActionType::ExecuteBlocks, Some(true))
+            .await?;
+        transaction.commit().await?;
+
+        // Executed blocks are verified and committed by definition, even if the
+        // corresponding operations weren't stored (e.g. for the restored data).
+        let verified = std::cmp::max(verified, executed);
+        let committed = std::cmp::max(committed, verified);
+
+        metrics::histogram!("sql.chain.block.load_finalized_blocks", start.elapsed());
+        Ok(FinalizedBlocks {
+            committed,
+            verified,
+            executed,
+        })
+    }
+
     /// Helper method for retrieving pending blocks from the database.
     async fn load_storage_pending_block(&mut self) -> QueryResult<Option<StoragePendingBlock>> {
         let start = Instant::now();
@@ -702,6 +781,8 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         let verify_gas_limit = block.verify_gas_limit.as_u64() as i64;
         let commitment = block.block_commitment.as_bytes().to_vec();
         let timestamp = Some(block.timestamp as i64);
+        let events =
+            ChainEvent::block_execution_events(block.block_number, &block.block_transactions);
 
         BlockSchema(&mut transaction)
             .save_block_transactions(block.block_number, block.block_transactions)
@@ -741,6 +822,8 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         ).execute(transaction.conn())
         .await?;
 
+        EventSchema(&mut transaction).store_events(&events).await?;
+
         transaction.commit().await?;
 
         metrics::histogram!("sql.chain.block.save_block", start.elapsed());
@@ -901,4 +984,144 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         metrics::histogram!("sql.chain.block.remove_account_tree_cache", start.elapsed());
         Ok(())
     }
+
+    /// Removes all the account tree caches except for the `keep` latest ones.
+    pub async fn remove_old_account_tree_caches(&mut self, keep: usize) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "
+            DELETE FROM account_tree_cache
+            WHERE block < (
+                SELECT block FROM account_tree_cache
+                ORDER BY block DESC
+                OFFSET $1 LIMIT 1
+            )
+            ",
+            keep.saturating_sub(1) as i64
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.block.remove_old_account_tree_caches",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Records the revert of the blocks with numbers greater than `last_correct_block`.
+    pub async fn record_block_revert(
+        &mut self,
+        last_correct_block: BlockNumber,
+        last_reverted_block: BlockNumber,
+        txs_returned_to_mempool: bool,
+    ) -> QueryResult<i64> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let id = sqlx::query!(
+            r#"
+            INSERT INTO block_reverts ( last_correct_block, last_reverted_block, txs_returned_to_mempool, reverted_at )
+            VALUES ( $1, $2, $3, now() )
+            RETURNING id
+            "#,
+            i64::from(*last_correct_block),
+            i64::from(*last_reverted_block),
+            txs_returned_to_mempool,
+        )
+        .fetch_one(transaction.conn())
+        .await?
+        .id;
+
+        EventSchema(&mut transaction)
+            .store_events(&[ChainEvent::BlocksReverted { last_correct_block }])
+            .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.chain.block.record_block_revert", start.elapsed());
+        Ok(id)
+    }
+
+    /// Loads the latest block reverts, newest first.
+    pub async fn load_block_reverts(&mut self, limit: u32) -> QueryResult<Vec<BlockRevert>> {
+        let start = Instant::now();
+        let reverts = sqlx::query_as!(
+            StorageBlockRevert,
+            "SELECT * FROM block_reverts ORDER BY id DESC LIMIT $1",
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|revert| BlockRevert {
+            id: revert.id,
+            last_correct_block: BlockNumber(revert.last_correct_block as u32),
+            last_reverted_block: BlockNumber(revert.last_reverted_block as u32),
+            txs_returned_to_mempool: revert.txs_returned_to_mempool,
+            reverted_at: revert.reverted_at,
+        })
+        .collect();
+
+        metrics::histogram!("sql.chain.block.load_block_reverts", start.elapsed());
+        Ok(reverts)
+    }
+
+    /// Returns the number of the reverted block which contained the transaction,
+    /// if the transaction was rejected because of the revert.
+    pub async fn get_reverted_tx_block(
+        &mut self,
+        tx_hash: &[u8],
+    ) -> QueryResult<Option<BlockNumber>> {
+        let start = Instant::now();
+        let block_number = sqlx::query!(
+            "SELECT block_number FROM reverted_transactions WHERE tx_hash = $1",
+            tx_hash
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|row| BlockNumber(row.block_number as u32));
+
+        metrics::histogram!("sql.chain.block.get_reverted_tx_block", start.elapsed());
+        Ok(block_number)
+    }
+
+    /// Halts the block production. The first reason is kept if the production is already halted.
+    pub async fn halt_block_production(&mut self, reason: &str) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "INSERT INTO block_production_halt (id, reason, halted_at) VALUES (true, $1, now())
+            ON CONFLICT (id) DO NOTHING",
+            reason
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.block.halt_block_production", start.elapsed());
+        Ok(())
+    }
+
+    /// Returns the reason of the block production halt, if the production is halted.
+    pub async fn load_block_production_halt(&mut self) -> QueryResult<Option<String>> {
+        let start = Instant::now();
+        let reason = sqlx::query!("SELECT reason FROM block_production_halt")
+            .fetch_optional(self.0.conn())
+            .await?
+            .map(|row| row.reason);
+
+        metrics::histogram!(
+            "sql.chain.block.load_block_production_halt",
+            start.elapsed()
+        );
+        Ok(reason)
+    }
+
+    /// Lifts the block production halt.
+    pub async fn resume_block_production(&mut self) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!("DELETE FROM block_production_halt")
+            .execute(self.0.conn())
+            .await?;
+
+        metrics::histogram!("sql.chain.block.resume_block_production", start.elapsed());
+        Ok(())
+    }
 }
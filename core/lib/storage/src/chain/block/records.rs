@@ -83,3 +83,12 @@ pub struct StorageBlockMetadata {
     pub block_number: i64,
     pub fast_processing: bool,
 }
+
+#[derive(Debug, FromRow)]
+pub struct StorageBlockRevert {
+    pub id: i64,
+    pub last_correct_block: i64,
+    pub last_reverted_block: i64,
+    pub txs_returned_to_mempool: bool,
+    pub reverted_at: DateTime<Utc>,
+}
@@ -1,15 +1,16 @@
 // Built-in deps
 use std::{collections::VecDeque, convert::TryFrom, time::Instant};
 // External imports
+use chrono::{DateTime, Utc};
 use itertools::Itertools;
 // Workspace imports
 use zksync_types::{
     mempool::SignedTxVariant,
     tx::{TxEthSignature, TxHash},
-    BlockNumber, SignedZkSyncTx,
+    Address, BlockNumber, SignedZkSyncTx, ZkSyncTx,
 };
 // Local imports
-use self::records::MempoolTx;
+use self::records::{MempoolTx, StoredExpiredTx};
 use crate::{QueryResult, StorageProcessor};
 
 pub mod records;
@@ -207,6 +208,9 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
+        let batch_txs: Vec<_> = txs.iter().map(|tx| &tx.tx).collect();
+        self.store_tx_hash_aliases(&batch_txs).await?;
+
         // If there're signatures for the whole batch, store them too.
         for signature in eth_signatures {
             let signature = serde_json::to_value(signature)?;
@@ -247,10 +251,39 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         .execute(self.0.conn())
         .await?;
 
+        self.store_tx_hash_aliases(&[&tx_data.tx]).await?;
+
         metrics::histogram!("sql.chain.mempool.insert_tx", start.elapsed());
         Ok(())
     }
 
+    /// Stores the Ethereum-style hashes of the transactions as aliases of their hashes.
+    async fn store_tx_hash_aliases(&mut self, txs: &[&ZkSyncTx]) -> QueryResult<()> {
+        let (aliases, tx_hashes): (Vec<_>, Vec<_>) = txs
+            .iter()
+            .map(|tx| {
+                (
+                    tx.eth_hash().as_bytes().to_vec(),
+                    tx.hash().as_ref().to_vec(),
+                )
+            })
+            .unzip();
+
+        sqlx::query!(
+            "INSERT INTO tx_hash_aliases (alias, tx_hash)
+            SELECT u.alias, u.tx_hash
+                FROM UNNEST ($1::bytea[], $2::bytea[])
+                AS u(alias, tx_hash)
+            ON CONFLICT (alias) DO NOTHING",
+            &aliases,
+            &tx_hashes,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn remove_tx(&mut self, tx: &[u8]) -> QueryResult<()> {
         let start = Instant::now();
         let tx_hash = hex::encode(tx);
@@ -283,6 +316,184 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Loads the hashes of the transactions added to the mempool before the given time.
+    pub async fn load_txs_created_before(
+        &mut self,
+        cutoff: DateTime<Utc>,
+    ) -> QueryResult<Vec<TxHash>> {
+        let start = Instant::now();
+        let tx_hashes = sqlx::query!(
+            "SELECT tx_hash FROM mempool_txs
+            WHERE created_at < $1",
+            cutoff
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| {
+            hex::decode(&row.tx_hash)
+                .ok()
+                .and_then(|bytes| TxHash::from_slice(&bytes))
+                .ok_or_else(|| {
+                    anyhow::format_err!("Incorrect tx hash in the mempool: {}", row.tx_hash)
+                })
+        })
+        .collect::<QueryResult<_>>()?;
+
+        metrics::histogram!("sql.chain.mempool.load_txs_created_before", start.elapsed());
+        Ok(tx_hashes)
+    }
+
+    /// Loads the times of the `ChangePubKey` transactions of the account made since the given time,
+    /// both executed successfully and awaiting in the mempool, oldest first.
+    pub async fn load_change_pubkey_times(
+        &mut self,
+        address: Address,
+        since: DateTime<Utc>,
+    ) -> QueryResult<Vec<DateTime<Utc>>> {
+        let start = Instant::now();
+        let times = sqlx::query!(
+            r#"
+            SELECT created_at AS "created_at!" FROM executed_transactions
+            WHERE from_account = $1 AND success = true AND created_at >= $2
+                AND tx->>'type' = 'ChangePubKey'
+            UNION ALL
+            SELECT created_at AS "created_at!" FROM mempool_txs
+            WHERE created_at >= $2 AND tx->>'type' = 'ChangePubKey' AND tx->>'account' = $3
+                AND NOT EXISTS (
+                    SELECT 1 FROM executed_transactions
+                    WHERE executed_transactions.tx_hash = decode(mempool_txs.tx_hash, 'hex')
+                )
+            ORDER BY 1
+            "#,
+            address.as_bytes(),
+            since,
+            format!("{:?}", address),
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| row.created_at)
+        .collect();
+
+        metrics::histogram!(
+            "sql.chain.mempool.load_change_pubkey_times",
+            start.elapsed()
+        );
+        Ok(times)
+    }
+
+    /// Removes the expired transactions from the mempool, recording the reasons they expired for.
+    pub async fn expire_txs(
+        &mut self,
+        txs: &[(TxHash, String)],
+        expired_at: DateTime<Utc>,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let (tx_hashes, reasons): (Vec<_>, Vec<_>) = txs
+            .iter()
+            .map(|(tx_hash, reason)| (tx_hash.as_ref().to_vec(), reason.clone()))
+            .unzip();
+        let hex_tx_hashes: Vec<_> = tx_hashes.iter().map(hex::encode).collect();
+
+        let mut transaction = self.0.start_transaction().await?;
+        sqlx::query!(
+            "INSERT INTO expired_transactions (tx_hash, reason, expired_at)
+            SELECT u.tx_hash, u.reason, $3
+                FROM UNNEST ($1::bytea[], $2::text[])
+                AS u(tx_hash, reason)
+            ON CONFLICT (tx_hash) DO UPDATE
+            SET reason = EXCLUDED.reason, expired_at = EXCLUDED.expired_at",
+            &tx_hashes,
+            &reasons,
+            expired_at
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "DELETE FROM mempool_txs
+            WHERE tx_hash = ANY($1)",
+            &hex_tx_hashes
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.chain.mempool.expire_txs", start.elapsed());
+        Ok(())
+    }
+
+    /// Returns the expired transaction with the given hash, unless it was submitted
+    /// to the mempool again.
+    pub async fn get_expired_tx(
+        &mut self,
+        tx_hash: TxHash,
+    ) -> QueryResult<Option<StoredExpiredTx>> {
+        let start = Instant::now();
+        let expired_tx = sqlx::query_as!(
+            StoredExpiredTx,
+            "SELECT * FROM expired_transactions
+            WHERE tx_hash = $1
+                AND NOT EXISTS (SELECT 1 FROM mempool_txs WHERE mempool_txs.tx_hash = $2)",
+            tx_hash.as_ref(),
+            hex::encode(tx_hash.as_ref())
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.mempool.get_expired_tx", start.elapsed());
+        Ok(expired_tx)
+    }
+
+    /// Loads the transactions expired after the one with the given ID, in order of expiry.
+    pub async fn load_expired_txs(
+        &mut self,
+        after_id: i64,
+        limit: u32,
+    ) -> QueryResult<Vec<StoredExpiredTx>> {
+        let start = Instant::now();
+        let expired_txs = sqlx::query_as!(
+            StoredExpiredTx,
+            "SELECT * FROM expired_transactions
+            WHERE id > $1
+            ORDER BY id
+            LIMIT $2",
+            after_id,
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.mempool.load_expired_txs", start.elapsed());
+        Ok(expired_txs)
+    }
+
+    /// Returns the ID of the last expired transaction, or 0 if there are none.
+    pub async fn last_expired_tx_id(&mut self) -> QueryResult<i64> {
+        let start = Instant::now();
+        let id = sqlx::query!("SELECT MAX(id) AS id FROM expired_transactions")
+            .fetch_one(self.0.conn())
+            .await?
+            .id
+            .unwrap_or_default();
+
+        metrics::histogram!("sql.chain.mempool.last_expired_tx_id", start.elapsed());
+        Ok(id)
+    }
+
+    /// Returns the number of transactions in the memory pool.
+    pub async fn count_txs(&mut self) -> QueryResult<u32> {
+        let start = Instant::now();
+        let count = sqlx::query!("SELECT COUNT(*) FROM mempool_txs")
+            .fetch_one(self.0.conn())
+            .await?
+            .count
+            .unwrap_or(0) as u32;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "count_txs");
+        Ok(count)
+    }
+
     /// Checks if the memory pool contains transaction with the given hash.
     pub async fn contains_tx(&mut self, tx_hash: TxHash) -> QueryResult<bool> {
         let start = Instant::now();
@@ -411,4 +622,42 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         );
         Ok(())
     }
+
+    /// Rejects the transactions of the blocks with numbers greater than `last_block`
+    /// (e.g. because the blocks are reverted), recording them as reverted by `revert_id`.
+    pub async fn reject_executed_txs(
+        &mut self,
+        last_block: BlockNumber,
+        revert_id: i64,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO reverted_transactions (tx_hash, revert_id, block_number)
+            SELECT tx_hash, $2, block_number FROM executed_transactions
+            WHERE block_number > $1
+            ON CONFLICT (tx_hash) DO UPDATE
+            SET revert_id = $2, block_number = EXCLUDED.block_number
+        "#,
+            *last_block as i64,
+            revert_id
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM executed_transactions
+            WHERE block_number > $1
+        "#,
+            *last_block as i64
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.chain.mempool.reject_executed_txs", start.elapsed());
+        Ok(())
+    }
 }
@@ -20,6 +20,15 @@ pub struct MempoolTx {
     pub batch_id: i64,
 }
 
+/// Transaction removed from the mempool since it was not executed in time.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StoredExpiredTx {
+    pub id: i64,
+    pub tx_hash: Vec<u8>,
+    pub reason: String,
+    pub expired_at: DateTime<Utc>,
+}
+
 impl TryFrom<MempoolTx> for SignedZkSyncTx {
     type Error = serde_json::Error;
 
@@ -1,17 +1,24 @@
 // Built-in deps
-use std::time::Instant;
+use std::{str::FromStr, time::Instant};
 // External imports
 use chrono::{Duration, Utc};
 // Workspace imports
-use zksync_types::{tx::TxHash, BlockNumber};
+use zksync_types::{
+    event::ChainEvent,
+    tx::TxHash,
+    withdrawal_execution::{WithdrawalExecution, WithdrawalExecutionStatus},
+    BlockNumber,
+};
 // Local imports
 use self::records::{
     NewExecutedPriorityOperation, NewExecutedTransaction, StoredAggregatedOperation,
     StoredCompleteWithdrawalsTransaction, StoredExecutedPriorityOperation, StoredPendingWithdrawal,
+    StoredWithdrawalExecution,
 };
 use crate::chain::operations::records::StoredExecutedTransaction;
 use crate::chain::operations_ext::OperationsExtSchema;
 use crate::ethereum::EthereumSchema;
+use crate::event::EventSchema;
 use crate::{chain::mempool::MempoolSchema, QueryResult, StorageProcessor};
 use zksync_basic_types::H256;
 use zksync_types::aggregated_operations::{AggregatedActionType, AggregatedOperation};
@@ -115,6 +122,24 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         Ok(op)
     }
 
+    /// Returns the serial ID of the first priority operation that is not executed yet.
+    pub async fn get_next_priority_op_serial_id(&mut self) -> QueryResult<u64> {
+        let start = Instant::now();
+        let next_serial_id =
+            sqlx::query!("SELECT MAX(priority_op_serialid) FROM executed_priority_operations")
+                .fetch_one(self.0.conn())
+                .await?
+                .max
+                .map(|serial_id| serial_id as u64 + 1)
+                .unwrap_or(0);
+
+        metrics::histogram!(
+            "sql.chain.operations.get_next_priority_op_serial_id",
+            start.elapsed()
+        );
+        Ok(next_serial_id)
+    }
+
     /// Retrieves priority operation from the database given its hash.
     pub async fn get_executed_priority_operation_by_hash(
         &mut self,
@@ -136,6 +161,8 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         Ok(op)
     }
 
+    /// Marks the aggregated operations within the block range as confirmed on L1.
+    /// Emits the `BlockCommitted` or `BlockVerified` events for the newly confirmed blocks.
     pub async fn confirm_aggregated_operations(
         &mut self,
         first_block: BlockNumber,
@@ -143,17 +170,70 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         action_type: AggregatedActionType,
     ) -> QueryResult<()> {
         let start = Instant::now();
-        sqlx::query!(
+        let mut transaction = self.0.start_transaction().await?;
+        let mut confirmed_ranges = sqlx::query!(
             "UPDATE aggregate_operations
                 SET confirmed = $1
-                WHERE from_block >= $2 AND to_block <= $3 AND action_type = $4",
+                WHERE from_block >= $2 AND to_block <= $3 AND action_type = $4 AND confirmed != $1
+                RETURNING from_block, to_block",
             true,
             i64::from(*first_block),
             i64::from(*last_block),
             action_type.to_string()
         )
-        .execute(self.0.conn())
-        .await?;
+        .fetch_all(transaction.conn())
+        .await?
+        .into_iter()
+        .map(|row| (row.from_block, row.to_block))
+        .collect::<Vec<_>>();
+        confirmed_ranges.sort_unstable();
+
+        let mut events = Vec::new();
+        for (from_block, to_block) in confirmed_ranges {
+            match action_type {
+                AggregatedActionType::CommitBlocks => {
+                    events.extend((from_block..=to_block).map(|block_number| {
+                        ChainEvent::BlockCommitted {
+                            block_number: BlockNumber(block_number as u32),
+                        }
+                    }));
+                }
+                AggregatedActionType::ExecuteBlocks => {
+                    let withdrawals = sqlx::query!(
+                        r#"
+                        SELECT block_number, tx_hash FROM executed_transactions
+                        WHERE block_number BETWEEN $1 AND $2 AND success = true
+                            AND tx->>'type' IN ('Withdraw', 'ForcedExit')
+                        ORDER BY block_number, block_index
+                        "#,
+                        from_block,
+                        to_block
+                    )
+                    .fetch_all(transaction.conn())
+                    .await?;
+
+                    for block_number in from_block..=to_block {
+                        events.push(ChainEvent::BlockVerified {
+                            block_number: BlockNumber(block_number as u32),
+                        });
+                        events.extend(
+                            withdrawals
+                                .iter()
+                                .filter(|withdrawal| withdrawal.block_number == block_number)
+                                .map(|withdrawal| ChainEvent::WithdrawalCompleted {
+                                    block_number: BlockNumber(block_number as u32),
+                                    tx_hash: TxHash::from_slice(&withdrawal.tx_hash)
+                                        .expect("Invalid tx hash has been stored"),
+                                }),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+        EventSchema(&mut transaction).store_events(&events).await?;
+        transaction.commit().await?;
+
         metrics::histogram!(
             "sql.chain.operations.confirm_aggregated_operations",
             start.elapsed()
@@ -354,7 +434,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         };
 
         let withdrawal_hash = EthereumSchema(self.0)
-            .aggregated_op_final_hash(block_number)
+            .aggregated_op_final_hash(block_number, AggregatedActionType::ExecuteBlocks)
             .await?;
 
         metrics::histogram!(
@@ -364,6 +444,65 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         Ok(withdrawal_hash)
     }
 
+    /// Stores the L1 transactions executing the withdrawals.
+    pub async fn store_withdrawal_executions(
+        &mut self,
+        executions: &[WithdrawalExecution],
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        for execution in executions {
+            sqlx::query!(
+                r#"
+                INSERT INTO withdrawal_executions ( withdrawal_hash, block_number, eth_tx_hash, status )
+                VALUES ( $1, $2, $3, $4 )
+                ON CONFLICT (withdrawal_hash)
+                DO UPDATE SET block_number = $2, eth_tx_hash = $3, status = $4
+                "#,
+                execution.withdrawal_hash.as_ref(),
+                i64::from(*execution.block_number),
+                execution.eth_tx_hash.as_bytes(),
+                execution.status.to_string(),
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!(
+            "sql.chain.operations.store_withdrawal_executions",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Loads the L1 transaction executing the withdrawal, if the receipt of the
+    /// `executeBlocks` transaction was processed.
+    pub async fn withdrawal_execution(
+        &mut self,
+        withdrawal_hash: &TxHash,
+    ) -> QueryResult<Option<WithdrawalExecution>> {
+        let start = Instant::now();
+        let execution = sqlx::query_as!(
+            StoredWithdrawalExecution,
+            "SELECT * FROM withdrawal_executions WHERE withdrawal_hash = $1",
+            withdrawal_hash.as_ref(),
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|execution| WithdrawalExecution {
+            withdrawal_hash: TxHash::from_slice(&execution.withdrawal_hash)
+                .expect("Incorrect withdrawal hash stored in the database"),
+            block_number: BlockNumber(execution.block_number as u32),
+            eth_tx_hash: H256::from_slice(&execution.eth_tx_hash),
+            status: WithdrawalExecutionStatus::from_str(&execution.status)
+                .expect("Incorrect withdrawal execution status stored in the database"),
+        });
+
+        metrics::histogram!("sql.chain.operations.withdrawal_execution", start.elapsed());
+        Ok(execution)
+    }
+
     /// Returns the hash of the Ethereum transaction in which the
     /// funds were withdrawn corresponding to the withdraw operation on L2.
     pub async fn eth_tx_for_withdrawal(
@@ -372,6 +511,15 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
     ) -> QueryResult<Option<H256>> {
         let start = Instant::now();
 
+        // The transaction parsed from the `executeBlocks` receipt is the most precise source.
+        if let Some(execution) = self.withdrawal_execution(withdrawal_hash).await? {
+            metrics::histogram!(
+                "sql.chain.operations.eth_tx_for_withdrawal",
+                start.elapsed()
+            );
+            return Ok(Some(execution.eth_tx_hash));
+        }
+
         // For a long time, the operation `CompleteWithdrawals` was used to withdraw funds,
         // now it is used `ExecuteBlocks`, so we should check each of the possible options.
         let eth_withdraw_tx_for_execute_block = self
@@ -531,16 +679,34 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         Ok(())
     }
 
-    // Removes aggregate operations and bindings for blocks with number greater than `last_block`
+    // Removes aggregate operations and bindings for blocks with number greater than `last_block`.
+    //
+    // Operations that start before `last_block` and end after it are truncated, except for
+    // the proof operations which are not published yet: the aggregated proof covers the removed
+    // blocks as well, so such operations are removed and created again for the new blocks.
     pub async fn remove_aggregate_operations_and_bindings(
         &mut self,
         last_block: BlockNumber,
     ) -> QueryResult<()> {
         let start = Instant::now();
         let mut transaction = self.0.start_transaction().await?;
+        let proof_action_types = vec![
+            AggregatedActionType::CreateProofBlocks.to_string(),
+            AggregatedActionType::PublishProofBlocksOnchain.to_string(),
+        ];
         let op_ids: Vec<i64> = sqlx::query!(
-            "SELECT id FROM aggregate_operations WHERE from_block > $1",
-            *last_block as i64
+            r#"SELECT id FROM aggregate_operations
+            WHERE from_block > $1 OR (
+                to_block > $1 AND action_type = ANY($2) AND NOT EXISTS (
+                    SELECT 1 FROM aggregate_operations AS published
+                    WHERE published.action_type = $3
+                        AND published.from_block = aggregate_operations.from_block
+                        AND published.confirmed = true
+                )
+            )"#,
+            *last_block as i64,
+            &proof_action_types,
+            AggregatedActionType::PublishProofBlocksOnchain.to_string()
         )
         .fetch_all(transaction.conn())
         .await?
@@ -574,8 +740,8 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
             .execute(transaction.conn())
             .await?;
         sqlx::query!(
-            "DELETE FROM aggregate_operations WHERE from_block > $1",
-            *last_block as i64
+            "DELETE FROM aggregate_operations WHERE id = ANY($1)",
+            &op_ids
         )
         .execute(transaction.conn())
         .await?;
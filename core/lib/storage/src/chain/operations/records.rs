@@ -98,6 +98,15 @@ pub struct StoredCompleteWithdrawalsTransaction {
     pub pending_withdrawals_queue_end_index: i64,
 }
 
+#[derive(Debug, Clone)]
+pub struct StoredWithdrawalExecution {
+    pub withdrawal_hash: Vec<u8>,
+    pub block_number: i64,
+    pub eth_tx_hash: Vec<u8>,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, FromRow)]
 pub struct StoredAggregatedOperation {
     pub id: i64,
@@ -6,7 +6,7 @@ use chrono::{DateTime, Utc};
 
 // Workspace imports
 use zksync_types::aggregated_operations::AggregatedActionType;
-use zksync_types::{Address, BlockNumber, TokenId};
+use zksync_types::{tx::TxHash, Address, BlockNumber, TokenId};
 
 // Local imports
 use self::records::{
@@ -38,6 +38,23 @@ pub enum SearchDirection {
 pub struct OperationsExtSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
 
 impl<'a, 'c> OperationsExtSchema<'a, 'c> {
+    /// Returns the canonical hash of the transaction given either its canonical hash,
+    /// or one of its aliases (e.g. the Keccak-256 hash of the transaction bytes).
+    pub async fn resolve_tx_hash(&mut self, hash: TxHash) -> QueryResult<TxHash> {
+        let start = Instant::now();
+        let tx_hash = sqlx::query!(
+            "SELECT tx_hash FROM tx_hash_aliases WHERE alias = $1",
+            hash.as_ref()
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .and_then(|row| TxHash::from_slice(&row.tx_hash))
+        .unwrap_or(hash);
+
+        metrics::histogram!("sql.chain.operations_ext.resolve_tx_hash", start.elapsed());
+        Ok(tx_hash)
+    }
+
     pub async fn tx_receipt(&mut self, hash: &[u8]) -> QueryResult<Option<TxReceiptResponse>> {
         let start = Instant::now();
         let tx = OperationsSchema(self.0)
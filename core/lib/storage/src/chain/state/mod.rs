@@ -1,11 +1,12 @@
 // Built-in deps
-use std::{cmp, collections::HashMap, time::Instant};
+use std::{cmp, collections::HashMap, ops::Range, time::Instant};
 // External imports
 use num::BigInt;
 use sqlx::types::BigDecimal;
 // Workspace imports
 use zksync_types::{
     helpers::{apply_updates, reverse_updates},
+    tx::TxHash,
     AccountId, AccountMap, AccountUpdate, AccountUpdates, BlockNumber, PubKeyHash,
 };
 // Local imports
@@ -550,6 +551,117 @@ impl<'a, 'c> StateSchema<'a, 'c> {
         result
     }
 
+    /// Stores the ranges of the account updates made by the executed transactions.
+    /// Ranges are given in terms of `update_order_id` of the updates within the block.
+    pub async fn store_tx_account_updates(
+        &mut self,
+        block_number: BlockNumber,
+        tx_updates: &[(TxHash, Range<usize>)],
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        for (tx_hash, updates) in tx_updates {
+            sqlx::query!(
+                "INSERT INTO tx_account_updates (tx_hash, block_number, first_update_order_id, updates_count)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (tx_hash) DO UPDATE
+                SET block_number = $2, first_update_order_id = $3, updates_count = $4",
+                tx_hash.as_ref(),
+                i64::from(*block_number),
+                updates.start as i32,
+                updates.len() as i32,
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.chain.state.store_tx_account_updates", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the account updates made by the executed transaction in the order of execution,
+    /// along with the number of the block the transaction was executed in.
+    /// Returns `None` if the updates of the transaction were not stored.
+    pub async fn load_tx_account_updates(
+        &mut self,
+        tx_hash: &TxHash,
+    ) -> QueryResult<Option<(BlockNumber, AccountUpdates)>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let tx_updates = sqlx::query!(
+            "SELECT block_number, first_update_order_id, updates_count FROM tx_account_updates
+            WHERE tx_hash = $1",
+            tx_hash.as_ref()
+        )
+        .fetch_optional(transaction.conn())
+        .await?;
+        let tx_updates = match tx_updates {
+            Some(tx_updates) => tx_updates,
+            None => return Ok(None),
+        };
+        let block_number = tx_updates.block_number;
+        let first_update_order_id = tx_updates.first_update_order_id;
+        let last_update_order_id = first_update_order_id + tx_updates.updates_count;
+
+        let account_balance_diff = sqlx::query_as!(
+            StorageAccountUpdate,
+            "SELECT * FROM account_balance_updates
+            WHERE block_number = $1 AND update_order_id >= $2 AND update_order_id < $3",
+            block_number,
+            first_update_order_id,
+            last_update_order_id,
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        let account_creation_diff = sqlx::query_as!(
+            StorageAccountCreation,
+            "SELECT * FROM account_creates
+            WHERE block_number = $1 AND update_order_id >= $2 AND update_order_id < $3",
+            block_number,
+            first_update_order_id,
+            last_update_order_id,
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        let account_pubkey_diff = sqlx::query_as!(
+            StorageAccountPubkeyUpdate,
+            "SELECT * FROM account_pubkey_updates
+            WHERE block_number = $1 AND update_order_id >= $2 AND update_order_id < $3",
+            block_number,
+            first_update_order_id,
+            last_update_order_id,
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        let mut account_diff: Vec<StorageAccountDiff> = Vec::new();
+        account_diff.extend(account_balance_diff.into_iter().map(From::from));
+        account_diff.extend(account_creation_diff.into_iter().map(From::from));
+        account_diff.extend(account_pubkey_diff.into_iter().map(From::from));
+        account_diff.sort_by(StorageAccountDiff::cmp_order);
+        let account_updates = account_diff.into_iter().map(From::from).collect();
+
+        metrics::histogram!("sql.chain.state.load_tx_account_updates", start.elapsed());
+        Ok(Some((BlockNumber(block_number as u32), account_updates)))
+    }
+
+    // Removes account updates ranges of transactions for blocks with number greater than `last_block`
+    pub async fn remove_tx_account_updates(&mut self, last_block: BlockNumber) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "DELETE FROM tx_account_updates WHERE block_number > $1",
+            *last_block as i64
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.state.remove_tx_account_updates", start.elapsed());
+        Ok(())
+    }
+
     // Removes account balance updates for blocks with number greater than `last_block`
     pub async fn remove_account_balance_updates(
         &mut self,
@@ -0,0 +1,66 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::{Duration, Utc};
+use sqlx::types::BigDecimal;
+// Workspace imports
+// Local imports
+use crate::{QueryResult, StorageProcessor};
+
+/// Counters schema handles the `shared_counters` table, storing the counters shared
+/// by the API server replicas. Each counter is reset once the period it was started
+/// for expires.
+#[derive(Debug)]
+pub struct CountersSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> CountersSchema<'a, 'c> {
+    /// Adds `amount` to the counter and returns its new value. If the counter doesn't exist
+    /// or is expired, it's started anew for the given `period`.
+    pub async fn increment(
+        &mut self,
+        key: &str,
+        amount: BigDecimal,
+        period: Duration,
+    ) -> QueryResult<BigDecimal> {
+        let start = Instant::now();
+        let now = Utc::now();
+        let value = sqlx::query!(
+            r#"
+            INSERT INTO shared_counters ( key, value, expires_at )
+            VALUES ( $1, $2, $4 )
+            ON CONFLICT (key) DO UPDATE SET
+                value = CASE WHEN shared_counters.expires_at <= $3
+                    THEN $2 ELSE shared_counters.value + $2 END,
+                expires_at = CASE WHEN shared_counters.expires_at <= $3
+                    THEN $4 ELSE shared_counters.expires_at END
+            RETURNING value
+            "#,
+            key,
+            amount,
+            now,
+            now + period,
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .value;
+
+        metrics::histogram!("sql.counters.increment", start.elapsed());
+        Ok(value)
+    }
+
+    /// Returns the current value of the counter, zero if it doesn't exist or is expired.
+    pub async fn load(&mut self, key: &str) -> QueryResult<BigDecimal> {
+        let start = Instant::now();
+        let value = sqlx::query!(
+            "SELECT value FROM shared_counters WHERE key = $1 AND expires_at > now()",
+            key
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|record| record.value)
+        .unwrap_or_default();
+
+        metrics::histogram!("sql.counters.load", start.elapsed());
+        Ok(value)
+    }
+}
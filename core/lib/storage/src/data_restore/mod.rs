@@ -184,6 +184,26 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
         Ok(stored)
     }
 
+    /// Same as `load_last_watched_block_number`, but returns `None` if the data restore
+    /// was never run on this database.
+    pub async fn try_load_last_watched_block_number(
+        &mut self,
+    ) -> QueryResult<Option<StoredLastWatchedEthBlockNumber>> {
+        let start = Instant::now();
+        let stored = sqlx::query_as!(
+            StoredLastWatchedEthBlockNumber,
+            "SELECT * FROM data_restore_last_watched_eth_block LIMIT 1",
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.data_restore.try_load_last_watched_block_number",
+            start.elapsed()
+        );
+        Ok(stored)
+    }
+
     fn new_storage_state(&self, state: impl ToString) -> NewStorageState {
         NewStorageState {
             storage_state: state.to_string(),
@@ -0,0 +1,190 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use num::BigInt;
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{
+    deposit_refund::{DepositFailureReason, DepositRefund, DepositRefundStatus},
+    Deposit, SerialId, H256,
+};
+// Local imports
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+use records::DbDepositRefund;
+
+/// Deposit refunds schema handles the `deposit_refunds` table, storing the deposits which
+/// funds would get stuck on L2 and the progress of their refunds.
+#[derive(Debug)]
+pub struct DepositRefundsSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> DepositRefundsSchema<'a, 'c> {
+    /// Flags the deposit, unless it's already flagged.
+    /// Returns `true` if the deposit is flagged for the first time.
+    pub async fn flag_deposit(
+        &mut self,
+        serial_id: SerialId,
+        eth_hash: H256,
+        deposit: &Deposit,
+        reason: DepositFailureReason,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO deposit_refunds ( serial_id, eth_hash, from_address, to_address, token, amount, reason, status )
+            VALUES ( $1, $2, $3, $4, $5, $6, $7, $8 )
+            ON CONFLICT ( serial_id ) DO NOTHING
+            "#,
+            serial_id as i64,
+            eth_hash.as_bytes(),
+            deposit.from.as_bytes(),
+            deposit.to.as_bytes(),
+            *deposit.token as i32,
+            BigDecimal::from(BigInt::from(deposit.amount.clone())),
+            reason.as_str(),
+            DepositRefundStatus::Flagged.as_str(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.deposit_refunds.flag_deposit", start.elapsed());
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get_refund(&mut self, serial_id: SerialId) -> QueryResult<Option<DepositRefund>> {
+        let start = Instant::now();
+        let refund = sqlx::query_as!(
+            DbDepositRefund,
+            "SELECT * FROM deposit_refunds WHERE serial_id = $1",
+            serial_id as i64
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(DepositRefund::from);
+
+        metrics::histogram!("sql.deposit_refunds.get_refund", start.elapsed());
+        Ok(refund)
+    }
+
+    /// Loads the flagged deposits in the given status, oldest first.
+    pub async fn load_refunds(
+        &mut self,
+        status: DepositRefundStatus,
+        limit: u32,
+    ) -> QueryResult<Vec<DepositRefund>> {
+        let start = Instant::now();
+        let refunds = sqlx::query_as!(
+            DbDepositRefund,
+            r#"
+            SELECT * FROM deposit_refunds WHERE status = $1
+            ORDER BY serial_id
+            LIMIT $2
+            "#,
+            status.as_str(),
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(DepositRefund::from)
+        .collect();
+
+        metrics::histogram!("sql.deposit_refunds.load_refunds", start.elapsed());
+        Ok(refunds)
+    }
+
+    /// Returns the nonce for the next refund transaction according to the stored ones,
+    /// or `None` if no refund transactions were stored.
+    pub async fn next_refund_nonce(&mut self) -> QueryResult<Option<u64>> {
+        let start = Instant::now();
+        let max_nonce =
+            sqlx::query!(r#"SELECT MAX(refund_tx_nonce) as "max_nonce" FROM deposit_refunds"#)
+                .fetch_one(self.0.conn())
+                .await?
+                .max_nonce;
+
+        metrics::histogram!("sql.deposit_refunds.next_refund_nonce", start.elapsed());
+        Ok(max_nonce.map(|nonce| nonce as u64 + 1))
+    }
+
+    /// Stores the signed refund transaction and moves the requested refund to the `sent` status.
+    /// The transaction must be stored before it's broadcast, so it's never sent twice with different nonces.
+    /// Returns `false` if the refund is not requested.
+    pub async fn store_refund_tx(
+        &mut self,
+        serial_id: SerialId,
+        tx_hash: H256,
+        nonce: u64,
+        raw_tx: &[u8],
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            r#"
+            UPDATE deposit_refunds
+            SET status = $5, refund_tx_hash = $2, refund_tx_nonce = $3, refund_raw_tx = $4, updated_at = now()
+            WHERE serial_id = $1 AND status = $6
+            "#,
+            serial_id as i64,
+            tx_hash.as_bytes(),
+            nonce as i64,
+            raw_tx,
+            DepositRefundStatus::Sent.as_str(),
+            DepositRefundStatus::Requested.as_str(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.deposit_refunds.store_refund_tx", start.elapsed());
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Loads the signed transaction of the sent refund, so it can be broadcast again.
+    pub async fn get_refund_raw_tx(&mut self, serial_id: SerialId) -> QueryResult<Option<Vec<u8>>> {
+        let start = Instant::now();
+        let raw_tx = sqlx::query!(
+            "SELECT refund_raw_tx FROM deposit_refunds WHERE serial_id = $1",
+            serial_id as i64
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .and_then(|record| record.refund_raw_tx);
+
+        metrics::histogram!("sql.deposit_refunds.get_refund_raw_tx", start.elapsed());
+        Ok(raw_tx)
+    }
+
+    /// Moves the refund to the new status if it's in one of the expected ones.
+    /// The refund transaction hash is kept unless the new one is provided.
+    /// Returns `false` if the refund is not in the expected status.
+    pub async fn update_status(
+        &mut self,
+        serial_id: SerialId,
+        expected: &[DepositRefundStatus],
+        status: DepositRefundStatus,
+        refund_tx_hash: Option<H256>,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|status| status.as_str().to_owned())
+            .collect();
+        let result = sqlx::query!(
+            r#"
+            UPDATE deposit_refunds
+            SET status = $2, refund_tx_hash = COALESCE($3, refund_tx_hash), updated_at = now()
+            WHERE serial_id = $1 AND status = ANY($4)
+            "#,
+            serial_id as i64,
+            status.as_str(),
+            refund_tx_hash.as_ref().map(|hash| hash.as_bytes()),
+            &expected,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.deposit_refunds.update_status", start.elapsed());
+        Ok(result.rows_affected() > 0)
+    }
+}
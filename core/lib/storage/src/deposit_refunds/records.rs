@@ -0,0 +1,47 @@
+// External imports
+use chrono::{DateTime, Utc};
+use num::bigint::ToBigInt;
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{deposit_refund::DepositRefund, Address, TokenId, H256};
+// Local imports
+
+#[derive(Debug, Clone)]
+pub struct DbDepositRefund {
+    pub serial_id: i64,
+    pub eth_hash: Vec<u8>,
+    pub from_address: Vec<u8>,
+    pub to_address: Vec<u8>,
+    pub token: i32,
+    pub amount: BigDecimal,
+    pub reason: String,
+    pub status: String,
+    pub refund_tx_hash: Option<Vec<u8>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub refund_tx_nonce: Option<i64>,
+    pub refund_raw_tx: Option<Vec<u8>>,
+}
+
+impl From<DbDepositRefund> for DepositRefund {
+    fn from(refund: DbDepositRefund) -> Self {
+        Self {
+            serial_id: refund.serial_id as u64,
+            eth_hash: H256::from_slice(&refund.eth_hash),
+            from: Address::from_slice(&refund.from_address),
+            to: Address::from_slice(&refund.to_address),
+            token: TokenId(refund.token as u16),
+            amount: refund
+                .amount
+                .to_bigint()
+                .and_then(|amount| amount.to_biguint())
+                .expect("Invalid deposit amount is stored"),
+            reason: refund.reason.parse().expect("Invalid reason is stored"),
+            status: refund.status.parse().expect("Invalid status is stored"),
+            refund_tx_hash: refund.refund_tx_hash.map(|hash| H256::from_slice(&hash)),
+            refund_tx_nonce: refund.refund_tx_nonce.map(|nonce| nonce as u64),
+            created_at: refund.created_at,
+            updated_at: refund.updated_at,
+        }
+    }
+}
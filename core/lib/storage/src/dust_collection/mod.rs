@@ -0,0 +1,154 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::{DateTime, Utc};
+use num::bigint::ToBigInt;
+use sqlx::Done;
+// Workspace imports
+use zksync_types::{
+    dust_collection::DustCandidate, tx::TxHash, AccountId, Address, PubKeyHash, TokenId,
+};
+// Local imports
+use crate::{QueryResult, StorageProcessor};
+
+/// DustCollection schema handles the `dust_collection_opt_outs` table, storing the accounts
+/// excluded from the dust collection, and the `dust_collections` table, storing the latest
+/// collection of every account.
+#[derive(Debug)]
+pub struct DustCollectionSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> DustCollectionSchema<'a, 'c> {
+    /// Excludes the account from the dust collection.
+    /// Returns `false` if the account has already been opted out.
+    pub async fn store_opt_out(&mut self, address: Address) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO dust_collection_opt_outs ( address, created_at )
+            VALUES ( $1, now() )
+            ON CONFLICT (address) DO NOTHING
+            "#,
+            address.as_bytes(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.dust_collection.store_opt_out", start.elapsed());
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Returns the moment the account was opted out, if it was.
+    pub async fn get_opt_out(&mut self, address: Address) -> QueryResult<Option<DateTime<Utc>>> {
+        let start = Instant::now();
+        let created_at = sqlx::query!(
+            "SELECT created_at FROM dust_collection_opt_outs WHERE address = $1",
+            address.as_bytes(),
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|row| row.created_at);
+
+        metrics::histogram!("sql.dust_collection.get_opt_out", start.elapsed());
+        Ok(created_at)
+    }
+
+    /// Loads the accounts which may be collected, ordered by the account ID starting
+    /// from `from_account_id`.
+    ///
+    /// Candidates are the verified accounts without a signing key which have non-zero balances,
+    /// were not updated since `inactive_since` and were not opted out. Accounts collected after
+    /// their last update are skipped, since their `ForcedExit` transactions are not verified yet.
+    pub async fn load_candidates(
+        &mut self,
+        inactive_since: DateTime<Utc>,
+        from_account_id: AccountId,
+        limit: u32,
+    ) -> QueryResult<Vec<DustCandidate>> {
+        let start = Instant::now();
+        let rows = sqlx::query!(
+            r#"
+            WITH candidates AS (
+                SELECT accounts.id, accounts.address FROM accounts
+                INNER JOIN blocks ON blocks.number = accounts.last_block
+                WHERE accounts.id >= $3
+                    AND accounts.pubkey_hash = $1
+                    AND blocks.timestamp < $2
+                    AND EXISTS (
+                        SELECT 1 FROM balances
+                        WHERE balances.account_id = accounts.id AND balances.balance > 0
+                    )
+                    AND NOT EXISTS (
+                        SELECT 1 FROM dust_collection_opt_outs
+                        WHERE dust_collection_opt_outs.address = accounts.address
+                    )
+                    AND NOT EXISTS (
+                        SELECT 1 FROM dust_collections
+                        WHERE dust_collections.account_id = accounts.id
+                            AND dust_collections.collected_at > to_timestamp(blocks.timestamp)
+                    )
+                ORDER BY accounts.id
+                LIMIT $4
+            )
+            SELECT candidates.id, candidates.address, balances.coin_id, balances.balance
+            FROM candidates
+            INNER JOIN balances ON balances.account_id = candidates.id
+            WHERE balances.balance > 0
+            ORDER BY candidates.id, balances.coin_id
+            "#,
+            &PubKeyHash::zero().data[..],
+            inactive_since.timestamp(),
+            i64::from(*from_account_id),
+            i64::from(limit),
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let mut candidates: Vec<DustCandidate> = Vec::new();
+        for row in rows {
+            let account_id = AccountId(row.id as u32);
+            let balance = row
+                .balance
+                .to_bigint()
+                .and_then(|balance| balance.to_biguint())
+                .expect("Invalid balance has been stored");
+            let balance = (TokenId(row.coin_id as u16), balance);
+
+            match candidates.last_mut() {
+                Some(candidate) if candidate.account_id == account_id => {
+                    candidate.balances.push(balance)
+                }
+                _ => candidates.push(DustCandidate {
+                    account_id,
+                    address: Address::from_slice(&row.address),
+                    balances: vec![balance],
+                }),
+            }
+        }
+
+        metrics::histogram!("sql.dust_collection.load_candidates", start.elapsed());
+        Ok(candidates)
+    }
+
+    /// Stores the `ForcedExit` transactions collecting the account.
+    pub async fn record_collection(
+        &mut self,
+        account_id: AccountId,
+        tx_hashes: &[TxHash],
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO dust_collections ( account_id, tx_hashes, collected_at )
+            VALUES ( $1, $2, now() )
+            ON CONFLICT (account_id) DO UPDATE SET tx_hashes = $2, collected_at = now()
+            "#,
+            i64::from(*account_id),
+            serde_json::to_value(tx_hashes)?,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.dust_collection.record_collection", start.elapsed());
+        Ok(())
+    }
+}
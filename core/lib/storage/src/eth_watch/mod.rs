@@ -0,0 +1,132 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+// Workspace imports
+use zksync_types::{l1_message::L1Message, PriorityOp};
+// Local imports
+use self::records::{StoredEthWatchL1Message, StoredEthWatchPriorityOp};
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Ethereum watcher schema persists the priority operations and L1 messages consumed by `eth_watch`,
+/// so that on restart the watcher doesn't have to query the old logs from the Ethereum node.
+#[derive(Debug)]
+pub struct EthWatchSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> EthWatchSchema<'a, 'c> {
+    /// Stores the priority operations and the messages received from Ethereum and marks all
+    /// the blocks up to `last_processed_block` as processed. Already stored events are ignored.
+    pub async fn store_events(
+        &mut self,
+        ops: &[PriorityOp],
+        messages: &[L1Message],
+        last_processed_block: u64,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        for op in ops {
+            sqlx::query!(
+                r#"
+                INSERT INTO eth_watch_priority_ops ( serial_id, eth_block, operation )
+                VALUES ( $1, $2, $3 )
+                ON CONFLICT (serial_id) DO NOTHING
+                "#,
+                op.serial_id as i64,
+                op.eth_block as i64,
+                serde_json::to_value(op)?,
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
+        for message in messages {
+            sqlx::query!(
+                r#"
+                INSERT INTO eth_watch_l1_messages ( serial_id, eth_block, message )
+                VALUES ( $1, $2, $3 )
+                ON CONFLICT (serial_id) DO NOTHING
+                "#,
+                message.serial_id as i64,
+                message.eth_block as i64,
+                serde_json::to_value(message)?,
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO eth_watch_state ( id, last_processed_block )
+            VALUES ( true, $1 )
+            ON CONFLICT (id) DO UPDATE SET last_processed_block = $1
+            "#,
+            last_processed_block as i64
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.eth_watch.store_events", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the priority operations emitted in the Ethereum blocks starting from `from_block`,
+    /// ordered by the serial ID.
+    pub async fn load_priority_ops(&mut self, from_block: u64) -> QueryResult<Vec<PriorityOp>> {
+        let start = Instant::now();
+        let ops = sqlx::query_as!(
+            StoredEthWatchPriorityOp,
+            r#"
+            SELECT serial_id, eth_block, operation FROM eth_watch_priority_ops
+            WHERE eth_block >= $1
+            ORDER BY serial_id
+            "#,
+            from_block as i64
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(StoredEthWatchPriorityOp::into_priority_op)
+        .collect();
+
+        metrics::histogram!("sql.eth_watch.load_priority_ops", start.elapsed());
+        Ok(ops)
+    }
+
+    /// Loads the messages starting from the one with the `from_id` serial ID, ordered by the serial ID.
+    pub async fn load_l1_messages(&mut self, from_id: u64) -> QueryResult<Vec<L1Message>> {
+        let start = Instant::now();
+        let messages = sqlx::query_as!(
+            StoredEthWatchL1Message,
+            r#"
+            SELECT serial_id, eth_block, message FROM eth_watch_l1_messages
+            WHERE serial_id >= $1
+            ORDER BY serial_id
+            "#,
+            from_id as i64
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(StoredEthWatchL1Message::into_l1_message)
+        .collect();
+
+        metrics::histogram!("sql.eth_watch.load_l1_messages", start.elapsed());
+        Ok(messages)
+    }
+
+    /// Loads the last Ethereum block for which all the priority operations are persisted.
+    /// Returns `None` if the operations were never stored.
+    pub async fn load_last_processed_block(&mut self) -> QueryResult<Option<u64>> {
+        let start = Instant::now();
+        let block =
+            sqlx::query!("SELECT last_processed_block FROM eth_watch_state WHERE id = true")
+                .fetch_optional(self.0.conn())
+                .await?
+                .map(|record| record.last_processed_block as u64);
+
+        metrics::histogram!("sql.eth_watch.load_last_processed_block", start.elapsed());
+        Ok(block)
+    }
+}
@@ -0,0 +1,32 @@
+// External imports
+use serde_json::Value;
+use sqlx::FromRow;
+// Workspace imports
+use zksync_types::{l1_message::L1Message, PriorityOp};
+// Local imports
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StoredEthWatchPriorityOp {
+    pub serial_id: i64,
+    pub eth_block: i64,
+    pub operation: Value,
+}
+
+impl StoredEthWatchPriorityOp {
+    pub fn into_priority_op(self) -> PriorityOp {
+        serde_json::from_value(self.operation).expect("Unparsable PriorityOp in db")
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StoredEthWatchL1Message {
+    pub serial_id: i64,
+    pub eth_block: i64,
+    pub message: Value,
+}
+
+impl StoredEthWatchL1Message {
+    pub fn into_l1_message(self) -> L1Message {
+        serde_json::from_value(self.message).expect("Unparsable L1Message in db")
+    }
+}
@@ -121,6 +121,32 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         Ok(ops)
     }
 
+    /// Returns the number of the operations sent to Ethereum but not confirmed yet.
+    pub async fn count_unconfirmed_operations(&mut self) -> QueryResult<u32> {
+        let start = Instant::now();
+        let count = sqlx::query!("SELECT COUNT(*) FROM eth_operations WHERE confirmed = false")
+            .fetch_one(self.0.conn())
+            .await?
+            .count
+            .unwrap_or(0) as u32;
+
+        metrics::histogram!("sql.ethereum.count_unconfirmed_operations", start.elapsed());
+        Ok(count)
+    }
+
+    /// Returns the number of the aggregated operations which are not sent to Ethereum yet.
+    pub async fn count_unprocessed_operations(&mut self) -> QueryResult<u32> {
+        let start = Instant::now();
+        let count = sqlx::query!("SELECT COUNT(*) FROM eth_unprocessed_aggregated_ops")
+            .fetch_one(self.0.conn())
+            .await?
+            .count
+            .unwrap_or(0) as u32;
+
+        metrics::histogram!("sql.ethereum.count_unprocessed_operations", start.elapsed());
+        Ok(count)
+    }
+
     /// Load all the aggregated operations that have no confirmation yet and have not yet been sent to Ethereum.
     /// Should be used after server restart only.
     pub async fn restore_unprocessed_operations(&mut self) -> QueryResult<()> {
@@ -593,9 +619,12 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Returns the hash of the confirmed Ethereum transaction which performed
+    /// the aggregated operation of the given type affecting the block.
     pub async fn aggregated_op_final_hash(
         &mut self,
         block_number: BlockNumber,
+        action_type: AggregatedActionType,
     ) -> QueryResult<Option<H256>> {
         let eth_operation = sqlx::query_as!(
             StorageETHOperation,
@@ -606,7 +635,7 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
                 ($1 BETWEEN from_block AND to_block) AND action_type = $2 AND eth_operations.confirmed = true 
             LIMIT 1",
             i64::from(*block_number),
-            AggregatedActionType::ExecuteBlocks.to_string(),
+            action_type.to_string(),
         )
         .fetch_optional(self.0.conn())
         .await?;
@@ -0,0 +1,141 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+// Workspace imports
+use zksync_types::event::{ChainEvent, EventOffset, EventRecord};
+// Local imports
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+use records::StoredEvent;
+
+/// Event schema handles the `event_log` table, storing the durable log of the chain events
+/// consumed by the external services.
+#[derive(Debug)]
+pub struct EventSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> EventSchema<'a, 'c> {
+    /// Appends the events to the log.
+    ///
+    /// Should be called in the same database transaction as the changes described by the events.
+    /// The table is locked until the transaction is finished, so the offsets are ordered the same
+    /// way as the commits and a consumer can never observe an offset gap which is filled later.
+    pub async fn store_events(&mut self, events: &[ChainEvent]) -> QueryResult<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        sqlx::query!("LOCK TABLE event_log IN EXCLUSIVE MODE")
+            .execute(transaction.conn())
+            .await?;
+
+        for event in events {
+            sqlx::query!(
+                r#"
+                INSERT INTO event_log ( block_number, event_type, event_data, created_at )
+                VALUES ( $1, $2, $3, now() )
+                "#,
+                i64::from(*event.block_number()),
+                event.event_type(),
+                serde_json::to_value(event)?,
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.event.store_events", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads up to `limit` events starting from the given offset (inclusive), in the offset order.
+    pub async fn load_events(
+        &mut self,
+        from_offset: EventOffset,
+        limit: u32,
+    ) -> QueryResult<Vec<EventRecord>> {
+        let start = Instant::now();
+        let events = sqlx::query_as!(
+            StoredEvent,
+            "SELECT * FROM event_log WHERE id >= $1 ORDER BY id ASC LIMIT $2",
+            from_offset as i64,
+            i64::from(limit),
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|event| {
+            let offset = event.id as EventOffset;
+            let chain_event = serde_json::from_value(event.event_data).map_err(|err| {
+                anyhow::format_err!("Invalid event is stored at offset {}: {}", offset, err)
+            })?;
+            Ok(EventRecord {
+                offset,
+                created_at: event.created_at,
+                event: chain_event,
+            })
+        })
+        .collect::<QueryResult<_>>()?;
+
+        metrics::histogram!("sql.event.load_events", start.elapsed());
+        Ok(events)
+    }
+
+    /// Returns the offset of the latest stored event, if any.
+    pub async fn get_last_offset(&mut self) -> QueryResult<Option<EventOffset>> {
+        let start = Instant::now();
+        let offset = sqlx::query!("SELECT MAX(id) FROM event_log")
+            .fetch_one(self.0.conn())
+            .await?
+            .max
+            .map(|offset| offset as EventOffset);
+
+        metrics::histogram!("sql.event.get_last_offset", start.elapsed());
+        Ok(offset)
+    }
+
+    /// Returns the offset of the next event to be processed by the given consumer.
+    /// Returns `None` if the consumer has never processed any events.
+    pub async fn get_consumer_offset(
+        &mut self,
+        consumer: &str,
+    ) -> QueryResult<Option<EventOffset>> {
+        let start = Instant::now();
+        let offset = sqlx::query!(
+            "SELECT next_event_offset FROM event_log_consumers WHERE name = $1",
+            consumer
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|row| row.next_event_offset as EventOffset);
+
+        metrics::histogram!("sql.event.get_consumer_offset", start.elapsed());
+        Ok(offset)
+    }
+
+    /// Stores the offset of the next event to be processed by the given consumer.
+    pub async fn update_consumer_offset(
+        &mut self,
+        consumer: &str,
+        next_event_offset: EventOffset,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO event_log_consumers ( name, next_event_offset )
+            VALUES ( $1, $2 )
+            ON CONFLICT (name) DO UPDATE SET next_event_offset = $2
+            "#,
+            consumer,
+            next_event_offset as i64,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.event.update_consumer_offset", start.elapsed());
+        Ok(())
+    }
+}
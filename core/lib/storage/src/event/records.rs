@@ -0,0 +1,13 @@
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+// Local imports
+
+#[derive(Debug, Clone)]
+pub struct StoredEvent {
+    pub id: i64,
+    pub block_number: i64,
+    pub event_type: String,
+    pub event_data: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
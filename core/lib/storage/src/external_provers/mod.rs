@@ -0,0 +1,351 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::{Duration, Utc};
+use sqlx::{types::BigDecimal, Done};
+// Workspace imports
+use zksync_types::{
+    prover::{ProverJob, ProverJobStatus, ProverJobType, ProverLeaseStatus},
+    Address,
+};
+// Local imports
+use self::records::{StorageExternalProver, StorageProverLease};
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Name the prover jobs leased to the external prover are updated by.
+pub fn external_worker_name(prover_name: &str) -> String {
+    format!("external:{}", prover_name)
+}
+
+/// Terms the prover jobs are leased to the external provers on.
+#[derive(Debug, Clone)]
+pub struct LeaseTerms {
+    /// Part of the prover stake locked until the job is proven.
+    pub collateral: BigDecimal,
+    /// Payment for the single block proof.
+    pub single_proof_payment: BigDecimal,
+    /// Payment for the aggregated proof.
+    pub aggregated_proof_payment: BigDecimal,
+    /// Time given to prove the job.
+    pub duration: Duration,
+}
+
+/// External provers schema handles the identities of the third-party provers,
+/// the prover jobs leased to them and the accounting of their stakes and payments.
+#[derive(Debug)]
+pub struct ExternalProversSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> ExternalProversSchema<'a, 'c> {
+    /// Registers the external prover authenticated by the given API key.
+    pub async fn register_prover(
+        &mut self,
+        name: &str,
+        api_key: &str,
+        reward_address: Address,
+        stake: BigDecimal,
+    ) -> QueryResult<i64> {
+        let start = Instant::now();
+        let id = sqlx::query!(
+            r#"
+            INSERT INTO external_provers ( name, api_key_hash, reward_address, stake )
+            VALUES ( $1, sha256($2), $3, $4 )
+            RETURNING id
+            "#,
+            name,
+            api_key.as_bytes(),
+            reward_address.as_bytes(),
+            stake,
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .id;
+
+        metrics::histogram!("sql.external_provers.register_prover", start.elapsed());
+        Ok(id)
+    }
+
+    /// Loads the active prover authenticated by the given API key.
+    pub async fn prover_by_api_key(
+        &mut self,
+        api_key: &str,
+    ) -> QueryResult<Option<StorageExternalProver>> {
+        let start = Instant::now();
+        let prover = sqlx::query_as!(
+            StorageExternalProver,
+            "SELECT * FROM external_provers WHERE api_key_hash = sha256($1) AND is_active",
+            api_key.as_bytes(),
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.external_provers.prover_by_api_key", start.elapsed());
+        Ok(prover)
+    }
+
+    /// Loads the prover by its ID.
+    pub async fn load_prover(
+        &mut self,
+        prover_id: i64,
+    ) -> QueryResult<Option<StorageExternalProver>> {
+        let start = Instant::now();
+        let prover = sqlx::query_as!(
+            StorageExternalProver,
+            "SELECT * FROM external_provers WHERE id = $1",
+            prover_id,
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.external_provers.load_prover", start.elapsed());
+        Ok(prover)
+    }
+
+    /// Leases the next idle prover job to the prover, locking the collateral.
+    /// Returns `None` if there are no idle jobs or the free stake of the prover
+    /// doesn't cover the collateral.
+    pub async fn lease_job(
+        &mut self,
+        prover: &StorageExternalProver,
+        terms: &LeaseTerms,
+    ) -> QueryResult<Option<(ProverJob, StorageProverLease)>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let locked = sqlx::query!(
+            r#"
+            UPDATE external_provers SET locked_stake = locked_stake + $2
+            WHERE id = $1 AND stake - locked_stake >= $2
+            "#,
+            prover.id,
+            terms.collateral.clone(),
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+        if locked == 0 {
+            return Ok(None);
+        }
+
+        let job = match transaction
+            .prover_schema()
+            .get_idle_prover_job_from_job_queue()
+            .await?
+        {
+            Some(job) => job,
+            None => return Ok(None),
+        };
+        transaction
+            .prover_schema()
+            .record_prover_is_working(job.job_id, &external_worker_name(&prover.name))
+            .await?;
+
+        let job_type = sqlx::query!(
+            "SELECT job_type FROM prover_job_queue WHERE id = $1",
+            job.job_id
+        )
+        .fetch_one(transaction.conn())
+        .await?
+        .job_type;
+        let payment = if job_type == ProverJobType::AggregatedProof.to_string() {
+            terms.aggregated_proof_payment.clone()
+        } else {
+            terms.single_proof_payment.clone()
+        };
+
+        let lease = sqlx::query_as!(
+            StorageProverLease,
+            r#"
+            INSERT INTO external_prover_leases (
+                prover_id, job_id, first_block, last_block, job_type,
+                collateral, payment, status, deadline
+            )
+            VALUES ( $1, $2, $3, $4, $5, $6, $7, $8, $9 )
+            RETURNING *
+            "#,
+            prover.id,
+            job.job_id,
+            i64::from(*job.first_block),
+            i64::from(*job.last_block),
+            job_type,
+            terms.collateral.clone(),
+            payment,
+            ProverLeaseStatus::Leased.to_string(),
+            Utc::now() + terms.duration,
+        )
+        .fetch_one(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.external_provers.lease_job", start.elapsed());
+        Ok(Some((job, lease)))
+    }
+
+    /// Loads the active lease of the prover.
+    pub async fn load_active_lease(
+        &mut self,
+        prover_id: i64,
+        lease_id: i64,
+    ) -> QueryResult<Option<StorageProverLease>> {
+        let start = Instant::now();
+        let lease = sqlx::query_as!(
+            StorageProverLease,
+            "SELECT * FROM external_prover_leases WHERE id = $1 AND prover_id = $2 AND status = $3",
+            lease_id,
+            prover_id,
+            ProverLeaseStatus::Leased.to_string(),
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.external_provers.load_active_lease", start.elapsed());
+        Ok(lease)
+    }
+
+    /// Loads the latest leases of the prover.
+    pub async fn load_leases(
+        &mut self,
+        prover_id: i64,
+        limit: u32,
+    ) -> QueryResult<Vec<StorageProverLease>> {
+        let start = Instant::now();
+        let leases = sqlx::query_as!(
+            StorageProverLease,
+            "SELECT * FROM external_prover_leases WHERE prover_id = $1 ORDER BY id DESC LIMIT $2",
+            prover_id,
+            i64::from(limit),
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.external_provers.load_leases", start.elapsed());
+        Ok(leases)
+    }
+
+    /// Marks the lease as completed once the proof is stored: unlocks the collateral
+    /// and accounts the payment. Returns `false` if the lease is not active.
+    pub async fn complete_lease(&mut self, lease_id: i64) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let lease = sqlx::query!(
+            r#"
+            UPDATE external_prover_leases SET status = $2, finished_at = now()
+            WHERE id = $1 AND status = $3
+            RETURNING prover_id, collateral, payment
+            "#,
+            lease_id,
+            ProverLeaseStatus::Completed.to_string(),
+            ProverLeaseStatus::Leased.to_string(),
+        )
+        .fetch_optional(transaction.conn())
+        .await?;
+        let lease = match lease {
+            Some(lease) => lease,
+            None => return Ok(false),
+        };
+
+        sqlx::query!(
+            r#"
+            UPDATE external_provers
+            SET locked_stake = locked_stake - $2, earned_amount = earned_amount + $3
+            WHERE id = $1
+            "#,
+            lease.prover_id,
+            lease.collateral,
+            lease.payment,
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.external_provers.complete_lease", start.elapsed());
+        Ok(true)
+    }
+
+    /// Slashes the collateral of the active lease and returns the job to the queue
+    /// if it's still assigned to the prover. Returns `false` if the lease is not active.
+    pub async fn slash_lease(&mut self, lease_id: i64, reason: &str) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let lease = sqlx::query!(
+            r#"
+            UPDATE external_prover_leases SET status = $2, slash_reason = $3, finished_at = now()
+            WHERE id = $1 AND status = $4
+            RETURNING prover_id, job_id, collateral
+            "#,
+            lease_id,
+            ProverLeaseStatus::Slashed.to_string(),
+            reason,
+            ProverLeaseStatus::Leased.to_string(),
+        )
+        .fetch_optional(transaction.conn())
+        .await?;
+        let lease = match lease {
+            Some(lease) => lease,
+            None => return Ok(false),
+        };
+
+        let prover_name = sqlx::query!(
+            r#"
+            UPDATE external_provers
+            SET locked_stake = locked_stake - $2,
+                stake = stake - $2,
+                slashed_amount = slashed_amount + $2
+            WHERE id = $1
+            RETURNING name
+            "#,
+            lease.prover_id,
+            lease.collateral,
+        )
+        .fetch_one(transaction.conn())
+        .await?
+        .name;
+
+        sqlx::query!(
+            r#"
+            UPDATE prover_job_queue SET (job_status, updated_at, updated_by) = ($1, now(), 'server_slash_lease')
+            WHERE id = $2 AND job_status = $3 AND updated_by = $4
+            "#,
+            ProverJobStatus::Idle.to_number(),
+            lease.job_id,
+            ProverJobStatus::InProgress.to_number(),
+            external_worker_name(&prover_name),
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        metrics::counter!("external_provers.slashed_leases", 1);
+        metrics::histogram!("sql.external_provers.slash_lease", start.elapsed());
+        Ok(true)
+    }
+
+    /// Returns the IDs of the active leases which are overdue or which jobs are no longer
+    /// assigned to the prover, e.g. because the prover stopped sending the heartbeats.
+    pub async fn expired_leases(&mut self) -> QueryResult<Vec<i64>> {
+        let start = Instant::now();
+        let leases = sqlx::query!(
+            r#"
+            SELECT external_prover_leases.id FROM external_prover_leases
+            INNER JOIN external_provers ON external_provers.id = external_prover_leases.prover_id
+            INNER JOIN prover_job_queue ON prover_job_queue.id = external_prover_leases.job_id
+            WHERE external_prover_leases.status = $1 AND (
+                external_prover_leases.deadline <= now()
+                OR prover_job_queue.job_status != $2
+                OR prover_job_queue.updated_by != ('external:' || external_provers.name)
+            )
+            "#,
+            ProverLeaseStatus::Leased.to_string(),
+            ProverJobStatus::InProgress.to_number(),
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|record| record.id)
+        .collect();
+
+        metrics::histogram!("sql.external_provers.expired_leases", start.elapsed());
+        Ok(leases)
+    }
+}
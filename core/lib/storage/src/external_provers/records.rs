@@ -0,0 +1,43 @@
+// External imports
+use chrono::prelude::*;
+use sqlx::{types::BigDecimal, FromRow};
+// Workspace imports
+// Local imports
+
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StorageExternalProver {
+    pub id: i64,
+    pub name: String,
+    pub api_key_hash: Vec<u8>,
+    pub reward_address: Vec<u8>,
+    pub stake: BigDecimal,
+    pub locked_stake: BigDecimal,
+    pub slashed_amount: BigDecimal,
+    pub earned_amount: BigDecimal,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl StorageExternalProver {
+    /// Part of the stake which can be locked as the collateral of the new leases.
+    pub fn free_stake(&self) -> BigDecimal {
+        &self.stake - &self.locked_stake
+    }
+}
+
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StorageProverLease {
+    pub id: i64,
+    pub prover_id: i64,
+    pub job_id: i32,
+    pub first_block: i64,
+    pub last_block: i64,
+    pub job_type: String,
+    pub collateral: BigDecimal,
+    pub payment: BigDecimal,
+    pub status: String,
+    pub slash_reason: Option<String>,
+    pub leased_at: DateTime<Utc>,
+    pub deadline: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
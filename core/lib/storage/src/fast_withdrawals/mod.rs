@@ -0,0 +1,207 @@
+// Built-in deps
+use std::{convert::TryFrom, time::Instant};
+// External imports
+use chrono::{DateTime, Utc};
+use sqlx::Done;
+// Workspace imports
+use zksync_types::{
+    fast_withdrawals::{FastWithdrawalIntent, FastWithdrawalIntentId, StoredFastWithdrawalIntent},
+    tx::TxHash,
+    TokenId, ZkSyncTx,
+};
+// Local imports
+use crate::{utils::address_to_stored_string, QueryResult, StorageProcessor};
+
+pub mod records;
+
+use records::DbFastWithdrawalIntent;
+
+/// FastWithdrawals schema handles the `fast_withdrawal_intents` table, storing the intents
+/// to sell the withdrawals to the liquidity providers.
+#[derive(Debug)]
+pub struct FastWithdrawalsSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> FastWithdrawalsSchema<'a, 'c> {
+    /// Stores the intent. Returns `None` if the intent for the same withdrawal already exists.
+    pub async fn store_intent(
+        &mut self,
+        intent: &FastWithdrawalIntent,
+        created_at: DateTime<Utc>,
+    ) -> QueryResult<Option<FastWithdrawalIntentId>> {
+        let start = Instant::now();
+        let withdraw = &intent.withdraw;
+        let tx_hash = ZkSyncTx::from(withdraw.clone()).hash();
+        let valid_until = i64::try_from(intent.valid_until(created_at)).unwrap_or(i64::MAX);
+
+        let id = sqlx::query!(
+            r#"
+            INSERT INTO fast_withdrawal_intents
+                ( tx_hash, account_id, token_id, liquidity_provider, intent, valid_until, created_at )
+            VALUES ( $1, $2, $3, $4, $5, $6, $7 )
+            ON CONFLICT (tx_hash) DO NOTHING
+            RETURNING id
+            "#,
+            tx_hash.as_ref(),
+            i64::from(*withdraw.account_id),
+            i32::from(*withdraw.token),
+            address_to_stored_string(&intent.liquidity_provider()),
+            serde_json::to_value(intent)?,
+            valid_until,
+            created_at,
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|row| row.id);
+
+        metrics::histogram!("sql.fast_withdrawals.store_intent", start.elapsed());
+        Ok(id)
+    }
+
+    pub async fn get_intent(
+        &mut self,
+        id: FastWithdrawalIntentId,
+    ) -> QueryResult<Option<StoredFastWithdrawalIntent>> {
+        let start = Instant::now();
+        let intent = sqlx::query_as!(
+            DbFastWithdrawalIntent,
+            "SELECT * FROM fast_withdrawal_intents WHERE id = $1",
+            id
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(StoredFastWithdrawalIntent::from);
+
+        metrics::histogram!("sql.fast_withdrawals.get_intent", start.elapsed());
+        Ok(intent)
+    }
+
+    /// Loads the oldest intents that are neither fulfilled nor expired at the moment `now`
+    /// (UNIX timestamp), optionally filtered by the token.
+    pub async fn load_pending_intents(
+        &mut self,
+        token: Option<TokenId>,
+        now: u64,
+        limit: u32,
+    ) -> QueryResult<Vec<StoredFastWithdrawalIntent>> {
+        let start = Instant::now();
+        let intents = sqlx::query_as!(
+            DbFastWithdrawalIntent,
+            r#"
+            SELECT * FROM fast_withdrawal_intents
+            WHERE fulfilled_at IS NULL
+                AND valid_until > $1
+                AND ($2::integer IS NULL OR token_id = $2)
+            ORDER BY created_at ASC
+            LIMIT $3
+            "#,
+            i64::try_from(now).unwrap_or(i64::MAX),
+            token.map(|token| i32::from(*token)),
+            i64::from(limit),
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(StoredFastWithdrawalIntent::from)
+        .collect();
+
+        metrics::histogram!("sql.fast_withdrawals.load_pending_intents", start.elapsed());
+        Ok(intents)
+    }
+
+    /// Marks the intent as fulfilled by the payout transfer.
+    /// Returns `false` if the intent is already fulfilled by another liquidity provider.
+    pub async fn claim_intent(
+        &mut self,
+        id: FastWithdrawalIntentId,
+        fulfilled_by: TxHash,
+        fulfilled_at: DateTime<Utc>,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            r#"
+            UPDATE fast_withdrawal_intents
+                SET fulfilled_by = $2, fulfilled_at = $3
+                WHERE id = $1 AND fulfilled_at IS NULL
+            "#,
+            id,
+            fulfilled_by.as_ref(),
+            fulfilled_at,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.fast_withdrawals.claim_intent", start.elapsed());
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Makes the intent available again, e.g. if the payout transfer was rejected.
+    pub async fn release_intent(&mut self, id: FastWithdrawalIntentId) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            UPDATE fast_withdrawal_intents
+                SET fulfilled_by = NULL, fulfilled_at = NULL
+                WHERE id = $1
+            "#,
+            id,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.fast_withdrawals.release_intent", start.elapsed());
+        Ok(())
+    }
+
+    /// Removes the intent which withdrawal can't be executed anymore.
+    pub async fn remove_intent(&mut self, id: FastWithdrawalIntentId) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!("DELETE FROM fast_withdrawal_intents WHERE id = $1", id)
+            .execute(self.0.conn())
+            .await?;
+
+        metrics::histogram!("sql.fast_withdrawals.remove_intent", start.elapsed());
+        Ok(())
+    }
+
+    /// Removes the unfulfilled intents expired at the moment `now` (UNIX timestamp).
+    /// Returns the number of the removed intents.
+    pub async fn remove_expired_intents(&mut self, now: u64) -> QueryResult<u64> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            "DELETE FROM fast_withdrawal_intents WHERE fulfilled_at IS NULL AND valid_until <= $1",
+            i64::try_from(now).unwrap_or(i64::MAX),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.fast_withdrawals.remove_expired_intents",
+            start.elapsed()
+        );
+        Ok(result.rows_affected())
+    }
+
+    /// Makes the intents fulfilled by the transfers rejected because of the block revert
+    /// available again.
+    pub async fn release_reverted_intents(&mut self, revert_id: i64) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            UPDATE fast_withdrawal_intents
+                SET fulfilled_by = NULL, fulfilled_at = NULL
+                WHERE fulfilled_by IN (
+                    SELECT tx_hash FROM reverted_transactions WHERE revert_id = $1
+                )
+            "#,
+            revert_id,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.fast_withdrawals.release_reverted_intents",
+            start.elapsed()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_types::{fast_withdrawals::StoredFastWithdrawalIntent, tx::TxHash};
+// Local imports
+
+#[derive(Debug, Clone)]
+pub struct DbFastWithdrawalIntent {
+    pub id: i64,
+    pub tx_hash: Vec<u8>,
+    pub account_id: i64,
+    pub token_id: i32,
+    pub liquidity_provider: String,
+    pub intent: serde_json::Value,
+    pub valid_until: i64,
+    pub created_at: DateTime<Utc>,
+    pub fulfilled_by: Option<Vec<u8>>,
+    pub fulfilled_at: Option<DateTime<Utc>>,
+}
+
+impl From<DbFastWithdrawalIntent> for StoredFastWithdrawalIntent {
+    fn from(val: DbFastWithdrawalIntent) -> Self {
+        Self {
+            id: val.id,
+            intent: serde_json::from_value(val.intent)
+                .expect("Invalid fast withdrawal intent has been stored"),
+            created_at: val.created_at,
+            valid_until: val.valid_until.max(0) as u64,
+            fulfilled_by: val
+                .fulfilled_by
+                .map(|hash| TxHash::from_slice(&hash).expect("Invalid stored tx hash")),
+            fulfilled_at: val.fulfilled_at,
+        }
+    }
+}
@@ -0,0 +1,206 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use num::{BigInt, BigUint};
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{
+    fee_refund::{FeeRefund, FeeRefundStatus},
+    tx::TxHash,
+    Address, TokenId,
+};
+// Local imports
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+use records::DbFeeRefund;
+
+fn to_decimal(value: &BigUint) -> BigDecimal {
+    BigDecimal::from(BigInt::from(value.clone()))
+}
+
+/// Fee refunds schema handles the `fee_refunds` table, storing the differences between the fees
+/// signed by the users and the fees computed by the server, along with the transfers refunding them.
+#[derive(Debug)]
+pub struct FeeRefundsSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> FeeRefundsSchema<'a, 'c> {
+    /// Records the fee refund of the submitted transaction. Returns `false` if the refund
+    /// of the transaction is already recorded, e.g. because it was submitted twice.
+    pub async fn store_refund(
+        &mut self,
+        tx_hash: &TxHash,
+        address: Address,
+        token: TokenId,
+        signed_fee: &BigUint,
+        actual_fee: &BigUint,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO fee_refunds ( tx_hash, address, token, signed_fee, actual_fee, status )
+            VALUES ( $1, $2, $3, $4, $5, $6 )
+            ON CONFLICT (tx_hash) DO NOTHING
+            "#,
+            tx_hash.as_ref(),
+            address.as_bytes(),
+            *token as i32,
+            to_decimal(signed_fee),
+            to_decimal(actual_fee),
+            FeeRefundStatus::Pending.as_str(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.fee_refunds.store_refund", start.elapsed());
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Loads the fee refund of the transaction.
+    pub async fn get_refund(&mut self, tx_hash: &TxHash) -> QueryResult<Option<FeeRefund>> {
+        let start = Instant::now();
+        let refund = sqlx::query_as!(
+            DbFeeRefund,
+            "SELECT * FROM fee_refunds WHERE tx_hash = $1",
+            tx_hash.as_ref()
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(FeeRefund::from);
+
+        metrics::histogram!("sql.fee_refunds.get_refund", start.elapsed());
+        Ok(refund)
+    }
+
+    /// Loads up to `limit` refunds, newest first. Refunds in any status are loaded if `status` is not set.
+    pub async fn load_refunds(
+        &mut self,
+        status: Option<FeeRefundStatus>,
+        limit: u32,
+    ) -> QueryResult<Vec<FeeRefund>> {
+        let start = Instant::now();
+        let refunds = sqlx::query_as!(
+            DbFeeRefund,
+            r#"
+            SELECT * FROM fee_refunds
+            WHERE $1::text IS NULL OR status = $1
+            ORDER BY id DESC
+            LIMIT $2
+            "#,
+            status.map(FeeRefundStatus::as_str),
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(FeeRefund::from)
+        .collect();
+
+        metrics::histogram!("sql.fee_refunds.load_refunds", start.elapsed());
+        Ok(refunds)
+    }
+
+    /// Loads up to `limit` oldest refunds in the status, so they're processed in order.
+    pub async fn load_oldest_refunds(
+        &mut self,
+        status: FeeRefundStatus,
+        limit: u32,
+    ) -> QueryResult<Vec<FeeRefund>> {
+        let start = Instant::now();
+        let refunds = sqlx::query_as!(
+            DbFeeRefund,
+            r#"
+            SELECT * FROM fee_refunds
+            WHERE status = $1
+            ORDER BY id ASC
+            LIMIT $2
+            "#,
+            status.as_str(),
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(FeeRefund::from)
+        .collect();
+
+        metrics::histogram!("sql.fee_refunds.load_oldest_refunds", start.elapsed());
+        Ok(refunds)
+    }
+
+    /// Records the signed refund transfer before it's added to the mempool, the refund stays pending.
+    /// Returns `false` if the refund is not pending.
+    pub async fn record_refund_tx(
+        &mut self,
+        id: i64,
+        refund: &BigUint,
+        refund_tx_hash: &TxHash,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            r#"
+            UPDATE fee_refunds
+            SET refund = $2, refund_tx_hash = $3, updated_at = now()
+            WHERE id = $1 AND status = $4
+            "#,
+            id,
+            to_decimal(refund),
+            refund_tx_hash.as_ref(),
+            FeeRefundStatus::Pending.as_str(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.fee_refunds.record_refund_tx", start.elapsed());
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Marks the refund sent once its recorded transfer is accepted by the mempool.
+    /// Returns `false` if the refund is not pending or its transfer is not recorded.
+    pub async fn mark_sent(&mut self, id: i64) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            r#"
+            UPDATE fee_refunds
+            SET status = $2, updated_at = now()
+            WHERE id = $1 AND status = $3 AND refund_tx_hash IS NOT NULL
+            "#,
+            id,
+            FeeRefundStatus::Sent.as_str(),
+            FeeRefundStatus::Pending.as_str(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.fee_refunds.mark_sent", start.elapsed());
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Records the outcome of the refund. Returns `false` if the refund is already resolved.
+    pub async fn update_status(
+        &mut self,
+        id: i64,
+        status: FeeRefundStatus,
+        fail_reason: Option<String>,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            r#"
+            UPDATE fee_refunds
+            SET status = $2, fail_reason = $3, updated_at = now()
+            WHERE id = $1 AND status IN ($4, $5)
+            "#,
+            id,
+            status.as_str(),
+            fail_reason,
+            FeeRefundStatus::Pending.as_str(),
+            FeeRefundStatus::Sent.as_str(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.fee_refunds.update_status", start.elapsed());
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,51 @@
+// External imports
+use chrono::{DateTime, Utc};
+use num::bigint::ToBigInt;
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{fee_refund::FeeRefund, tx::TxHash, Address, TokenId};
+// Local imports
+
+#[derive(Debug, Clone)]
+pub struct DbFeeRefund {
+    pub id: i64,
+    pub tx_hash: Vec<u8>,
+    pub address: Vec<u8>,
+    pub token: i32,
+    pub signed_fee: BigDecimal,
+    pub actual_fee: BigDecimal,
+    pub refund: BigDecimal,
+    pub refund_tx_hash: Option<Vec<u8>>,
+    pub status: String,
+    pub fail_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+fn to_biguint(value: BigDecimal) -> num::BigUint {
+    value
+        .to_bigint()
+        .and_then(|value| value.to_biguint())
+        .expect("Invalid fee amount is stored")
+}
+
+impl From<DbFeeRefund> for FeeRefund {
+    fn from(refund: DbFeeRefund) -> Self {
+        Self {
+            id: refund.id,
+            tx_hash: TxHash::from_slice(&refund.tx_hash).expect("Invalid tx hash is stored"),
+            address: Address::from_slice(&refund.address),
+            token: TokenId(refund.token as u16),
+            signed_fee: to_biguint(refund.signed_fee),
+            actual_fee: to_biguint(refund.actual_fee),
+            refund: to_biguint(refund.refund),
+            refund_tx_hash: refund
+                .refund_tx_hash
+                .map(|hash| TxHash::from_slice(&hash).expect("Invalid tx hash is stored")),
+            status: refund.status.parse().expect("Invalid status is stored"),
+            fail_reason: refund.fail_reason,
+            created_at: refund.created_at,
+            updated_at: refund.updated_at,
+        }
+    }
+}
@@ -0,0 +1,109 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use num::{BigInt, BigUint};
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{
+    fee_sweep::{FeeSweep, FeeSweepKind, FeeSweepStatus},
+    tx::TxHash,
+    Address, TokenId,
+};
+// Local imports
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+use records::DbFeeSweep;
+
+/// Fee sweeps schema handles the `fee_sweeps` table, storing the transactions moving the collected fees
+/// from the fee account to the treasury along with their outcome.
+#[derive(Debug)]
+pub struct FeeSweepsSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> FeeSweepsSchema<'a, 'c> {
+    /// Records the sweep transaction added to the mempool. Returns the ID of the sweep.
+    pub async fn store_sweep(
+        &mut self,
+        token: TokenId,
+        amount: &BigUint,
+        treasury: Address,
+        kind: FeeSweepKind,
+        tx_hash: &TxHash,
+    ) -> QueryResult<i64> {
+        let start = Instant::now();
+        let id = sqlx::query!(
+            r#"
+            INSERT INTO fee_sweeps ( token, amount, treasury, kind, tx_hash, status )
+            VALUES ( $1, $2, $3, $4, $5, $6 )
+            RETURNING id
+            "#,
+            *token as i32,
+            BigDecimal::from(BigInt::from(amount.clone())),
+            treasury.as_bytes(),
+            kind.as_str(),
+            tx_hash.as_ref(),
+            FeeSweepStatus::Sent.as_str(),
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .id;
+
+        metrics::histogram!("sql.fee_sweeps.store_sweep", start.elapsed());
+        Ok(id)
+    }
+
+    /// Loads up to `limit` sweeps, newest first. Sweeps in any status are loaded if `status` is not set.
+    pub async fn load_sweeps(
+        &mut self,
+        status: Option<FeeSweepStatus>,
+        limit: u32,
+    ) -> QueryResult<Vec<FeeSweep>> {
+        let start = Instant::now();
+        let sweeps = sqlx::query_as!(
+            DbFeeSweep,
+            r#"
+            SELECT * FROM fee_sweeps
+            WHERE $1::text IS NULL OR status = $1
+            ORDER BY id DESC
+            LIMIT $2
+            "#,
+            status.map(FeeSweepStatus::as_str),
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(FeeSweep::from)
+        .collect();
+
+        metrics::histogram!("sql.fee_sweeps.load_sweeps", start.elapsed());
+        Ok(sweeps)
+    }
+
+    /// Records the outcome of the sent sweep. Returns `false` if the sweep is not in the `sent` status.
+    pub async fn update_status(
+        &mut self,
+        id: i64,
+        status: FeeSweepStatus,
+        fail_reason: Option<String>,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            r#"
+            UPDATE fee_sweeps
+            SET status = $2, fail_reason = $3, updated_at = now()
+            WHERE id = $1 AND status = $4
+            "#,
+            id,
+            status.as_str(),
+            fail_reason,
+            FeeSweepStatus::Sent.as_str(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.fee_sweeps.update_status", start.elapsed());
+        Ok(result.rows_affected() > 0)
+    }
+}
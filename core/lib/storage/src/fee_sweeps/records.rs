@@ -0,0 +1,42 @@
+// External imports
+use chrono::{DateTime, Utc};
+use num::bigint::ToBigInt;
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{fee_sweep::FeeSweep, tx::TxHash, Address, TokenId};
+// Local imports
+
+#[derive(Debug, Clone)]
+pub struct DbFeeSweep {
+    pub id: i64,
+    pub token: i32,
+    pub amount: BigDecimal,
+    pub treasury: Vec<u8>,
+    pub kind: String,
+    pub tx_hash: Vec<u8>,
+    pub status: String,
+    pub fail_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<DbFeeSweep> for FeeSweep {
+    fn from(sweep: DbFeeSweep) -> Self {
+        Self {
+            id: sweep.id,
+            token: TokenId(sweep.token as u16),
+            amount: sweep
+                .amount
+                .to_bigint()
+                .and_then(|amount| amount.to_biguint())
+                .expect("Invalid sweep amount is stored"),
+            treasury: Address::from_slice(&sweep.treasury),
+            kind: sweep.kind.parse().expect("Invalid sweep kind is stored"),
+            tx_hash: TxHash::from_slice(&sweep.tx_hash).expect("Invalid tx hash is stored"),
+            status: sweep.status.parse().expect("Invalid status is stored"),
+            fail_reason: sweep.fail_reason,
+            created_at: sweep.created_at,
+            updated_at: sweep.updated_at,
+        }
+    }
+}
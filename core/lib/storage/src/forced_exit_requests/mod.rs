@@ -8,7 +8,8 @@ use std::{ops::Sub, time::Instant};
 // Local imports
 use crate::{QueryResult, StorageProcessor};
 use zksync_types::forced_exit_requests::{
-    ForcedExitRequest, ForcedExitRequestId, SaveForcedExitRequestQuery,
+    ForcedExitL2Payment, ForcedExitPaymentStatus, ForcedExitRequest, ForcedExitRequestId,
+    SaveForcedExitRequestQuery,
 };
 
 use zksync_types::tx::TxHash;
@@ -17,7 +18,7 @@ pub mod records;
 
 mod utils;
 
-use records::DbForcedExitRequest;
+use records::{DbForcedExitL2Payment, DbForcedExitRequest};
 
 use crate::utils::address_to_stored_string;
 
@@ -217,4 +218,101 @@ impl<'a, 'c> ForcedExitRequestsSchema<'a, 'c> {
 
         Ok(())
     }
+
+    /// Loads the L2 payment for the ForcedExit request by the hash of the paying transfer.
+    pub async fn get_l2_payment(
+        &mut self,
+        tx_hash: TxHash,
+    ) -> QueryResult<Option<ForcedExitL2Payment>> {
+        let start = Instant::now();
+
+        let payment = sqlx::query_as!(
+            DbForcedExitL2Payment,
+            r#"
+            SELECT * FROM forced_exit_l2_payments
+            WHERE tx_hash = $1
+            "#,
+            tx_hash.as_ref()
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|r| r.into());
+
+        metrics::histogram!("sql.forced_exit_requests.get_l2_payment", start.elapsed());
+
+        Ok(payment)
+    }
+
+    /// Stores the processed L2 payment for the ForcedExit request.
+    /// Returns `false` if the payment was already stored.
+    pub async fn store_l2_payment(&mut self, payment: &ForcedExitL2Payment) -> QueryResult<bool> {
+        let start = Instant::now();
+
+        let amount = BigDecimal::from(BigInt::from(payment.amount.clone()));
+        let refund_tx_hash: Option<&[u8]> =
+            payment.refund_tx_hash.as_ref().map(|hash| hash.as_ref());
+        let rows = sqlx::query!(
+            r#"
+            INSERT INTO forced_exit_l2_payments
+                ( tx_hash, from_address, amount, request_id, status, refund_tx_hash, created_at )
+            VALUES ( $1, $2, $3, $4, $5, $6, $7 )
+            ON CONFLICT (tx_hash) DO NOTHING
+            "#,
+            payment.tx_hash.as_ref(),
+            payment.from.as_bytes(),
+            amount,
+            payment.request_id,
+            payment.status.as_str(),
+            refund_tx_hash,
+            payment.created_at,
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected();
+
+        metrics::histogram!("sql.forced_exit_requests.store_l2_payment", start.elapsed());
+
+        Ok(rows > 0)
+    }
+
+    /// Updates the status of the stored L2 payment.
+    pub async fn update_l2_payment_status(
+        &mut self,
+        tx_hash: TxHash,
+        status: ForcedExitPaymentStatus,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+
+        sqlx::query!(
+            "UPDATE forced_exit_l2_payments SET status = $1 WHERE tx_hash = $2",
+            status.as_str(),
+            tx_hash.as_ref()
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.forced_exit_requests.update_l2_payment_status",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Removes the stored L2 payment, so it's processed again.
+    pub async fn remove_l2_payment(&mut self, tx_hash: TxHash) -> QueryResult<()> {
+        let start = Instant::now();
+
+        sqlx::query!(
+            "DELETE FROM forced_exit_l2_payments WHERE tx_hash = $1",
+            tx_hash.as_ref()
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.forced_exit_requests.remove_l2_payment",
+            start.elapsed()
+        );
+        Ok(())
+    }
 }
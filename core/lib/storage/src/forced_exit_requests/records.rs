@@ -2,8 +2,8 @@ use crate::utils::{address_to_stored_string, stored_str_address_to_address};
 use chrono::{DateTime, Utc};
 use num::{bigint::ToBigInt, BigInt};
 use sqlx::types::BigDecimal;
-use zksync_basic_types::TokenId;
-use zksync_types::forced_exit_requests::ForcedExitRequest;
+use zksync_basic_types::{Address, TokenId};
+use zksync_types::forced_exit_requests::{ForcedExitL2Payment, ForcedExitRequest};
 use zksync_types::tx::TxHash;
 
 use super::utils;
@@ -65,3 +65,40 @@ impl From<DbForcedExitRequest> for ForcedExitRequest {
         }
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct DbForcedExitL2Payment {
+    pub tx_hash: Vec<u8>,
+    pub from_address: Vec<u8>,
+    pub amount: BigDecimal,
+    pub request_id: Option<i64>,
+    pub status: String,
+    pub refund_tx_hash: Option<Vec<u8>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<DbForcedExitL2Payment> for ForcedExitL2Payment {
+    fn from(val: DbForcedExitL2Payment) -> Self {
+        let amount = val
+            .amount
+            .to_bigint()
+            .map(|int| int.to_biguint())
+            .flatten()
+            .expect("Invalid forced exit payment has been stored");
+
+        ForcedExitL2Payment {
+            tx_hash: TxHash::from_slice(&val.tx_hash).expect("Invalid tx hash has been stored"),
+            from: Address::from_slice(&val.from_address),
+            amount,
+            request_id: val.request_id,
+            status: val
+                .status
+                .parse()
+                .expect("Invalid forced exit payment status has been stored"),
+            refund_tx_hash: val
+                .refund_tx_hash
+                .map(|hash| TxHash::from_slice(&hash).expect("Invalid tx hash has been stored")),
+            created_at: val.created_at,
+        }
+    }
+}
@@ -0,0 +1,177 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::{Duration, Utc};
+// Workspace imports
+use zksync_types::{
+    account::PubKeyHash,
+    guardians::{GuardianRecovery, GuardianRegistration, GuardianSignature},
+    AccountId, Nonce,
+};
+// Local imports
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+use records::{DbGuardianRecovery, DbGuardianRegistration};
+
+/// Guardians schema handles the `account_guardians` and `guardian_recoveries` tables, storing
+/// the guardian sets registered by the accounts and the recoveries initiated by the guardians.
+#[derive(Debug)]
+pub struct GuardiansSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> GuardiansSchema<'a, 'c> {
+    /// Registers the guardian set of the account, replacing the previous one and cancelling
+    /// the recoveries initiated by its guardians.
+    /// Returns `false` if the version of the set is not greater than the one of the registered set.
+    pub async fn register_guardians(
+        &mut self,
+        registration: &GuardianRegistration,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let account_id = i64::from(*registration.account_id);
+        let version = i64::from(registration.guardian_set.version);
+        let registered = sqlx::query!(
+            "SELECT version FROM account_guardians WHERE account_id = $1 FOR UPDATE",
+            account_id
+        )
+        .fetch_optional(transaction.conn())
+        .await?;
+        if matches!(registered, Some(registered) if registered.version >= version) {
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            "DELETE FROM guardian_recoveries WHERE account_id = $1",
+            account_id
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO account_guardians ( account_id, address, version, guardian_set, owner_signature, registered_at )
+            VALUES ( $1, $2, $3, $4, $5, $6 )
+            ON CONFLICT (account_id)
+            DO UPDATE SET address = $2, version = $3, guardian_set = $4, owner_signature = $5, registered_at = $6
+            "#,
+            account_id,
+            registration.address.as_bytes(),
+            version,
+            serde_json::to_value(&registration.guardian_set).unwrap(),
+            registration.owner_signature.serialize_packed().to_vec(),
+            registration.registered_at,
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.guardians.register_guardians", start.elapsed());
+        Ok(true)
+    }
+
+    /// Loads the guardian set registered by the account.
+    pub async fn get_guardians(
+        &mut self,
+        account_id: AccountId,
+    ) -> QueryResult<Option<GuardianRegistration>> {
+        let start = Instant::now();
+        let registration = sqlx::query_as!(
+            DbGuardianRegistration,
+            "SELECT * FROM account_guardians WHERE account_id = $1",
+            i64::from(*account_id)
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(GuardianRegistration::from);
+
+        metrics::histogram!("sql.guardians.get_guardians", start.elapsed());
+        Ok(registration)
+    }
+
+    /// Stores the recovery initiated by the guardians of the registered set, the recovery
+    /// becomes executable once the timelock of the set passes.
+    /// Returns the stored recovery, which is the previously initiated one if the same recovery
+    /// is initiated again, so the timelock can't be restarted.
+    pub async fn initiate_recovery(
+        &mut self,
+        account_id: AccountId,
+        new_pk_hash: &PubKeyHash,
+        nonce: Nonce,
+        signatures: &[GuardianSignature],
+        timelock: Duration,
+    ) -> QueryResult<GuardianRecovery> {
+        let start = Instant::now();
+        let initiated_at = Utc::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO guardian_recoveries ( account_id, new_pk_hash, nonce, signatures, initiated_at, executable_at )
+            VALUES ( $1, $2, $3, $4, $5, $6 )
+            ON CONFLICT (account_id, new_pk_hash, nonce) DO NOTHING
+            "#,
+            i64::from(*account_id),
+            new_pk_hash.data.to_vec(),
+            i64::from(*nonce),
+            serde_json::to_value(signatures).unwrap(),
+            initiated_at,
+            initiated_at + timelock,
+        )
+        .execute(self.0.conn())
+        .await?;
+        let recovery = self
+            .get_recovery(account_id, new_pk_hash, nonce)
+            .await?
+            .expect("Initiated recovery is not stored");
+
+        metrics::histogram!("sql.guardians.initiate_recovery", start.elapsed());
+        Ok(recovery)
+    }
+
+    /// Loads the recovery of the account to the given public key hash.
+    pub async fn get_recovery(
+        &mut self,
+        account_id: AccountId,
+        new_pk_hash: &PubKeyHash,
+        nonce: Nonce,
+    ) -> QueryResult<Option<GuardianRecovery>> {
+        let start = Instant::now();
+        let recovery = sqlx::query_as!(
+            DbGuardianRecovery,
+            r#"
+            SELECT * FROM guardian_recoveries
+            WHERE account_id = $1 AND new_pk_hash = $2 AND nonce = $3
+            "#,
+            i64::from(*account_id),
+            new_pk_hash.data.to_vec(),
+            i64::from(*nonce),
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(GuardianRecovery::from);
+
+        metrics::histogram!("sql.guardians.get_recovery", start.elapsed());
+        Ok(recovery)
+    }
+
+    /// Loads the recoveries of the account, newest first.
+    pub async fn load_recoveries(
+        &mut self,
+        account_id: AccountId,
+    ) -> QueryResult<Vec<GuardianRecovery>> {
+        let start = Instant::now();
+        let recoveries = sqlx::query_as!(
+            DbGuardianRecovery,
+            "SELECT * FROM guardian_recoveries WHERE account_id = $1 ORDER BY id DESC",
+            i64::from(*account_id)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(GuardianRecovery::from)
+        .collect();
+
+        metrics::histogram!("sql.guardians.load_recoveries", start.elapsed());
+        Ok(recoveries)
+    }
+}
@@ -0,0 +1,61 @@
+// External imports
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+// Workspace imports
+use zksync_types::{
+    account::PubKeyHash,
+    guardians::{GuardianRecovery, GuardianRegistration},
+    tx::PackedEthSignature,
+    AccountId, Address, Nonce,
+};
+// Local imports
+
+#[derive(Debug, Clone)]
+pub struct DbGuardianRegistration {
+    pub account_id: i64,
+    pub address: Vec<u8>,
+    pub version: i64,
+    pub guardian_set: Value,
+    pub owner_signature: Vec<u8>,
+    pub registered_at: DateTime<Utc>,
+}
+
+impl From<DbGuardianRegistration> for GuardianRegistration {
+    fn from(registration: DbGuardianRegistration) -> Self {
+        Self {
+            account_id: AccountId(registration.account_id as u32),
+            address: Address::from_slice(&registration.address),
+            guardian_set: serde_json::from_value(registration.guardian_set)
+                .expect("Invalid guardian set is stored"),
+            owner_signature: PackedEthSignature::deserialize_packed(&registration.owner_signature)
+                .expect("Invalid owner signature is stored"),
+            registered_at: registration.registered_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DbGuardianRecovery {
+    pub id: i64,
+    pub account_id: i64,
+    pub new_pk_hash: Vec<u8>,
+    pub nonce: i64,
+    pub signatures: Value,
+    pub initiated_at: DateTime<Utc>,
+    pub executable_at: DateTime<Utc>,
+}
+
+impl From<DbGuardianRecovery> for GuardianRecovery {
+    fn from(recovery: DbGuardianRecovery) -> Self {
+        Self {
+            account_id: AccountId(recovery.account_id as u32),
+            new_pk_hash: PubKeyHash::from_bytes(&recovery.new_pk_hash)
+                .expect("Invalid pubkey hash is stored"),
+            nonce: Nonce(recovery.nonce as u32),
+            signatures: serde_json::from_value(recovery.signatures)
+                .expect("Invalid guardian signatures are stored"),
+            initiated_at: recovery.initiated_at,
+            executable_at: recovery.executable_at,
+        }
+    }
+}
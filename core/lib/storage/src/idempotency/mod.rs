@@ -0,0 +1,109 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::{DateTime, Utc};
+use sqlx::Done;
+// Workspace imports
+use zksync_types::tx::TxHash;
+// Local imports
+use self::records::{IdempotentSubmission, StoredIdempotencyKey};
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Idempotency schema handles the `tx_idempotency_keys` table, storing the idempotency keys
+/// supplied by the clients along with the submitted transactions.
+#[derive(Debug)]
+pub struct IdempotencySchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> IdempotencySchema<'a, 'c> {
+    /// Reserves the idempotency key for the submission of the given transactions.
+    /// Keys created before `valid_since` are expired and removed. Keys of the submissions
+    /// still in progress since before `pending_valid_since` are considered abandoned
+    /// (e.g. the server crashed in the middle of the submission) and are removed as well.
+    ///
+    /// Returns `None` if the key is reserved, or the submission previously made with the key.
+    pub async fn reserve_key(
+        &mut self,
+        idempotency_key: &str,
+        request_hashes: &[TxHash],
+        valid_since: DateTime<Utc>,
+        pending_valid_since: DateTime<Utc>,
+    ) -> QueryResult<Option<IdempotentSubmission>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM tx_idempotency_keys
+            WHERE created_at < $1 OR (tx_hashes IS NULL AND created_at < $2)
+            "#,
+            valid_since,
+            pending_valid_since
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO tx_idempotency_keys ( idempotency_key, request_hashes, created_at )
+            VALUES ( $1, $2, now() )
+            ON CONFLICT (idempotency_key) DO NOTHING
+            "#,
+            idempotency_key,
+            serde_json::to_value(request_hashes)?,
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        let submission = if result.rows_affected() == 1 {
+            None
+        } else {
+            let record = sqlx::query_as!(
+                StoredIdempotencyKey,
+                "SELECT * FROM tx_idempotency_keys WHERE idempotency_key = $1",
+                idempotency_key
+            )
+            .fetch_one(transaction.conn())
+            .await?;
+            Some(record.into())
+        };
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.idempotency.reserve_key", start.elapsed());
+        Ok(submission)
+    }
+
+    /// Stores the hashes of the transactions accepted to the mempool for the reserved key.
+    pub async fn complete_submission(
+        &mut self,
+        idempotency_key: &str,
+        tx_hashes: &[TxHash],
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "UPDATE tx_idempotency_keys SET tx_hashes = $2 WHERE idempotency_key = $1",
+            idempotency_key,
+            serde_json::to_value(tx_hashes)?,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.idempotency.complete_submission", start.elapsed());
+        Ok(())
+    }
+
+    /// Removes the key reserved for the failed submission, so it can be reused by the client.
+    pub async fn release_key(&mut self, idempotency_key: &str) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "DELETE FROM tx_idempotency_keys WHERE idempotency_key = $1 AND tx_hashes IS NULL",
+            idempotency_key
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.idempotency.release_key", start.elapsed());
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+// External imports
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+// Workspace imports
+use zksync_types::tx::TxHash;
+// Local imports
+
+#[derive(Debug, Clone)]
+pub struct StoredIdempotencyKey {
+    pub idempotency_key: String,
+    pub request_hashes: Value,
+    pub tx_hashes: Option<Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Submission made with the idempotency key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdempotentSubmission {
+    /// Hashes of the transactions sent with the key.
+    pub request_hashes: Vec<TxHash>,
+    /// Hashes of the transactions accepted to the mempool.
+    /// `None` if the submission is still in progress.
+    pub tx_hashes: Option<Vec<TxHash>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<StoredIdempotencyKey> for IdempotentSubmission {
+    fn from(val: StoredIdempotencyKey) -> Self {
+        Self {
+            request_hashes: serde_json::from_value(val.request_hashes)
+                .expect("Invalid transaction hashes have been stored"),
+            tx_hashes: val.tx_hashes.map(|hashes| {
+                serde_json::from_value(hashes).expect("Invalid transaction hashes have been stored")
+            }),
+            created_at: val.created_at,
+        }
+    }
+}
@@ -0,0 +1,74 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_types::key_audit::{KeyUsage, KeyUsageRecord, OperatorKey};
+// Local imports
+use self::records::StoredKeyUsage;
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// KeyAudit schema handles the `key_usage_audit` table, storing the append-only log
+/// of the operator keys usage.
+#[derive(Debug)]
+pub struct KeyAuditSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> KeyAuditSchema<'a, 'c> {
+    /// Appends the key usage to the audit log.
+    pub async fn record_key_usage(&mut self, usage: &KeyUsage) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO key_usage_audit ( key_type, operation, payload_hash, success, error )
+            VALUES ( $1, $2, $3, $4, $5 )
+            "#,
+            usage.key.as_str(),
+            usage.operation,
+            usage.payload_hash.as_bytes(),
+            usage.success,
+            usage.error,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.key_audit.record_key_usage", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads up to `limit` audit log entries made within the `[from, to)` interval,
+    /// ordered by ID and starting after `after_id`. Entries of all the keys are loaded
+    /// unless the key is specified.
+    pub async fn load_key_usage(
+        &mut self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        key: Option<OperatorKey>,
+        after_id: Option<i64>,
+        limit: u32,
+    ) -> QueryResult<Vec<KeyUsageRecord>> {
+        let start = Instant::now();
+        let records = sqlx::query_as!(
+            StoredKeyUsage,
+            r#"
+            SELECT * FROM key_usage_audit
+            WHERE created_at >= $1 AND created_at < $2
+                AND ($3::TEXT IS NULL OR key_type = $3)
+                AND id > $4
+            ORDER BY id ASC
+            LIMIT $5
+            "#,
+            from,
+            to,
+            key.map(OperatorKey::as_str),
+            after_id.unwrap_or(0),
+            i64::from(limit),
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.key_audit.load_key_usage", start.elapsed());
+        Ok(records.into_iter().map(From::from).collect())
+    }
+}
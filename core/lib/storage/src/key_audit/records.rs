@@ -0,0 +1,38 @@
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_types::{
+    key_audit::{KeyUsage, KeyUsageRecord},
+    H256,
+};
+// Local imports
+
+#[derive(Debug, Clone)]
+pub struct StoredKeyUsage {
+    pub id: i64,
+    pub key_type: String,
+    pub operation: String,
+    pub payload_hash: Vec<u8>,
+    pub success: bool,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<StoredKeyUsage> for KeyUsageRecord {
+    fn from(val: StoredKeyUsage) -> Self {
+        Self {
+            id: val.id,
+            usage: KeyUsage {
+                key: val
+                    .key_type
+                    .parse()
+                    .expect("Invalid operator key has been stored"),
+                operation: val.operation,
+                payload_hash: H256::from_slice(&val.payload_hash),
+                success: val.success,
+                error: val.error,
+            },
+            created_at: val.created_at,
+        }
+    }
+}
@@ -0,0 +1,111 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+// Workspace imports
+use zksync_types::{
+    l1_message::{DeliveredL1Message, L1Message},
+    Address, BlockNumber,
+};
+// Local imports
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+use records::DbL1Message;
+
+/// L1 messages schema handles the `l1_messages` table, storing the messages sent
+/// to the zkSync accounts from L1 along with the blocks they're delivered in.
+#[derive(Debug)]
+pub struct L1MessagesSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> L1MessagesSchema<'a, 'c> {
+    /// Records the messages delivered in the block. Already recorded messages are ignored.
+    pub async fn store_messages(
+        &mut self,
+        block_number: BlockNumber,
+        messages: &[L1Message],
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        for message in messages {
+            sqlx::query!(
+                r#"
+                INSERT INTO l1_messages ( serial_id, sender, recipient, payload, eth_hash, eth_block, block_number )
+                VALUES ( $1, $2, $3, $4, $5, $6, $7 )
+                ON CONFLICT (serial_id) DO NOTHING
+                "#,
+                message.serial_id as i64,
+                message.sender.as_bytes(),
+                message.recipient.as_bytes(),
+                &message.payload,
+                message.eth_hash.as_bytes(),
+                message.eth_block as i64,
+                i64::from(*block_number),
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.l1_messages.store_messages", start.elapsed());
+        Ok(())
+    }
+
+    /// Returns the serial ID of the first message not delivered yet.
+    pub async fn next_message_id(&mut self) -> QueryResult<u64> {
+        let start = Instant::now();
+        let last_id = sqlx::query!("SELECT MAX(serial_id) FROM l1_messages")
+            .fetch_one(self.0.conn())
+            .await?
+            .max;
+
+        metrics::histogram!("sql.l1_messages.next_message_id", start.elapsed());
+        Ok(last_id.map_or(0, |id| id as u64 + 1))
+    }
+
+    /// Loads up to `limit` messages delivered to the account, starting from the message
+    /// with the `from_id` serial ID.
+    pub async fn load_account_messages(
+        &mut self,
+        recipient: Address,
+        from_id: u64,
+        limit: u32,
+    ) -> QueryResult<Vec<DeliveredL1Message>> {
+        let start = Instant::now();
+        let messages = sqlx::query_as!(
+            DbL1Message,
+            r#"
+            SELECT * FROM l1_messages
+            WHERE recipient = $1 AND serial_id >= $2
+            ORDER BY serial_id
+            LIMIT $3
+            "#,
+            recipient.as_bytes(),
+            from_id as i64,
+            i64::from(limit),
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(DeliveredL1Message::from)
+        .collect();
+
+        metrics::histogram!("sql.l1_messages.load_account_messages", start.elapsed());
+        Ok(messages)
+    }
+
+    /// Removes the messages delivered in the blocks with number greater than `last_block`,
+    /// so they're delivered again once the blocks are reverted.
+    pub async fn remove_messages(&mut self, last_block: BlockNumber) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "DELETE FROM l1_messages WHERE block_number > $1",
+            i64::from(*last_block)
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.l1_messages.remove_messages", start.elapsed());
+        Ok(())
+    }
+}
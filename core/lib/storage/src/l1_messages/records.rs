@@ -0,0 +1,36 @@
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_types::{
+    l1_message::{DeliveredL1Message, L1Message},
+    Address, BlockNumber, H256,
+};
+// Local imports
+
+#[derive(Debug, Clone)]
+pub struct DbL1Message {
+    pub serial_id: i64,
+    pub sender: Vec<u8>,
+    pub recipient: Vec<u8>,
+    pub payload: Vec<u8>,
+    pub eth_hash: Vec<u8>,
+    pub eth_block: i64,
+    pub block_number: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<DbL1Message> for DeliveredL1Message {
+    fn from(message: DbL1Message) -> Self {
+        Self {
+            message: L1Message {
+                serial_id: message.serial_id as u64,
+                sender: Address::from_slice(&message.sender),
+                recipient: Address::from_slice(&message.recipient),
+                payload: message.payload,
+                eth_hash: H256::from_slice(&message.eth_hash),
+                eth_block: message.eth_block as u64,
+            },
+            block_number: BlockNumber(message.block_number as u32),
+        }
+    }
+}
@@ -0,0 +1,134 @@
+// Built-in deps
+use std::time::Instant;
+// Local imports
+use crate::{QueryResult, StorageProcessor};
+
+/// Fencing token of the leadership, issued by `LeaderElectionSchema::issue_fencing_token`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FencingToken {
+    pub lock_id: i64,
+    pub token: i64,
+}
+
+/// Leader election schema coordinates the server instances running in the active/standby mode.
+///
+/// The leadership is represented by the session-level Postgres advisory lock, so it's held as long
+/// as the connection that acquired it is alive and is released automatically once the leader
+/// instance dies or loses the connection to the database. Thus, the schema is supposed to be used
+/// with the dedicated connection (see `StorageProcessor::establish_connection`) rather than
+/// the pooled one.
+///
+/// The lock alone doesn't stop the previous leader which has not yet noticed the loss of its
+/// connection from writing to the database, so every takeover issues the new fencing token. The
+/// writes are made in the transactions checking the token (see `check_fencing_token`), so once
+/// the token is increased, the writes of the previous leader fail.
+#[derive(Debug)]
+pub struct LeaderElectionSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> LeaderElectionSchema<'a, 'c> {
+    /// Tries to acquire the leadership identified by the lock ID without waiting.
+    /// Returns `true` if the leadership is held by this connection.
+    pub async fn try_acquire_leadership(&mut self, lock_id: i64) -> QueryResult<bool> {
+        let start = Instant::now();
+        let acquired = sqlx::query!(r#"SELECT pg_try_advisory_lock($1) AS "acquired!""#, lock_id)
+            .fetch_one(self.0.conn())
+            .await?
+            .acquired;
+
+        metrics::histogram!(
+            "sql.leader_election.try_acquire_leadership",
+            start.elapsed()
+        );
+        Ok(acquired)
+    }
+
+    /// Issues the fencing token of the leadership just acquired by this connection.
+    /// The tokens increase with every takeover.
+    pub async fn issue_fencing_token(&mut self, lock_id: i64) -> QueryResult<FencingToken> {
+        let start = Instant::now();
+        let token = sqlx::query!(
+            r#"
+            INSERT INTO leader_election_tokens (lock_id, token)
+            VALUES ($1, 1)
+            ON CONFLICT (lock_id)
+            DO UPDATE SET token = leader_election_tokens.token + 1
+            RETURNING token
+            "#,
+            lock_id
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .token;
+
+        metrics::histogram!("sql.leader_election.issue_fencing_token", start.elapsed());
+        Ok(FencingToken { lock_id, token })
+    }
+
+    /// Checks that the leadership is still held by this connection, i.e. the advisory lock
+    /// is granted to its backend, and that it has not been taken over since the token was issued.
+    pub async fn check_leadership(&mut self, token: FencingToken) -> QueryResult<bool> {
+        let start = Instant::now();
+        // The 64-bit advisory lock key is stored as the two halves in the `classid` and `objid`
+        // columns, `objsubid` is 1 for the single-key locks.
+        let held = sqlx::query!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM pg_locks
+                WHERE locktype = 'advisory'
+                    AND granted
+                    AND pid = pg_backend_pid()
+                    AND classid = (($1 >> 32) & 4294967295)::oid
+                    AND objid = ($1 & 4294967295)::oid
+                    AND objsubid = 1
+            ) AND EXISTS (
+                SELECT 1 FROM leader_election_tokens
+                WHERE lock_id = $1 AND token = $2
+            ) AS "held!"
+            "#,
+            token.lock_id,
+            token.token
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .held;
+
+        metrics::histogram!("sql.leader_election.check_leadership", start.elapsed());
+        Ok(held)
+    }
+
+    /// Fails unless the fencing token is the latest one issued. Supposed to be called within the
+    /// transaction of the leader write: the token row is locked until the transaction ends, so
+    /// the next leader can't be elected in the middle of it.
+    pub async fn check_fencing_token(&mut self, token: FencingToken) -> QueryResult<()> {
+        let start = Instant::now();
+        let latest_token = sqlx::query!(
+            "SELECT token FROM leader_election_tokens WHERE lock_id = $1 FOR SHARE",
+            token.lock_id
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|row| row.token);
+
+        metrics::histogram!("sql.leader_election.check_fencing_token", start.elapsed());
+        if latest_token != Some(token.token) {
+            anyhow::bail!(
+                "Fencing token {} is outdated, the latest one is {:?}",
+                token.token,
+                latest_token
+            );
+        }
+        Ok(())
+    }
+
+    /// Gives up the leadership. Returns `false` if the leadership was not held by this connection.
+    pub async fn release_leadership(&mut self, lock_id: i64) -> QueryResult<bool> {
+        let start = Instant::now();
+        let released = sqlx::query!(r#"SELECT pg_advisory_unlock($1) AS "released!""#, lock_id)
+            .fetch_one(self.0.conn())
+            .await?
+            .released;
+
+        metrics::histogram!("sql.leader_election.release_leadership", start.elapsed());
+        Ok(released)
+    }
+}
@@ -86,17 +86,42 @@ use crate::connection::{holder::ConnectionHolder, PooledConnection};
 #[cfg(test)]
 mod tests;
 
+pub mod activations;
+pub mod aliases;
+pub mod api_namespaces;
+pub mod attestations;
 pub mod chain;
 pub mod config;
 pub mod connection;
+pub mod counters;
 pub mod data_restore;
+pub mod deposit_refunds;
 pub mod diff;
+pub mod dust_collection;
+pub mod eth_watch;
 pub mod ethereum;
+pub mod event;
+pub mod external_provers;
+pub mod fast_withdrawals;
+pub mod fee_refunds;
+pub mod fee_sweeps;
 pub mod forced_exit_requests;
+pub mod guardians;
+pub mod idempotency;
+pub mod key_audit;
+pub mod l1_messages;
+pub mod leader_election;
+pub mod nonce_reservations;
+pub mod priority_op_costs;
+pub mod proof_archives;
 pub mod prover;
+pub mod revenue;
+pub mod screening;
+pub mod shadow_ethereum;
 pub mod test_data;
 pub mod tokens;
 mod utils;
+pub mod webhooks;
 
 use forced_exit_requests::ForcedExitRequestsSchema;
 
@@ -186,6 +211,26 @@ impl<'a> StorageProcessor<'a> {
         }
     }
 
+    /// Gains access to the `Activations` schema.
+    pub fn activations_schema(&mut self) -> activations::ActivationsSchema<'_, 'a> {
+        activations::ActivationsSchema(self)
+    }
+
+    /// Gains access to the `Aliases` schema.
+    pub fn aliases_schema(&mut self) -> aliases::AliasesSchema<'_, 'a> {
+        aliases::AliasesSchema(self)
+    }
+
+    /// Gains access to the `ApiNamespaces` schema.
+    pub fn api_namespaces_schema(&mut self) -> api_namespaces::ApiNamespacesSchema<'_, 'a> {
+        api_namespaces::ApiNamespacesSchema(self)
+    }
+
+    /// Gains access to the `Attestations` schema.
+    pub fn attestations_schema(&mut self) -> attestations::AttestationsSchema<'_, 'a> {
+        attestations::AttestationsSchema(self)
+    }
+
     /// Gains access to the `Chain` schemas.
     pub fn chain(&mut self) -> chain::ChainIntermediator<'_, 'a> {
         chain::ChainIntermediator(self)
@@ -196,16 +241,98 @@ impl<'a> StorageProcessor<'a> {
         config::ConfigSchema(self)
     }
 
+    /// Gains access to the `Counters` schema.
+    pub fn counters_schema(&mut self) -> counters::CountersSchema<'_, 'a> {
+        counters::CountersSchema(self)
+    }
+
     /// Gains access to the `DataRestore` schema.
     pub fn data_restore_schema(&mut self) -> data_restore::DataRestoreSchema<'_, 'a> {
         data_restore::DataRestoreSchema(self)
     }
 
+    /// Gains access to the `DepositRefunds` schema.
+    pub fn deposit_refunds_schema(&mut self) -> deposit_refunds::DepositRefundsSchema<'_, 'a> {
+        deposit_refunds::DepositRefundsSchema(self)
+    }
+
+    /// Gains access to the `DustCollection` schema.
+    pub fn dust_collection_schema(&mut self) -> dust_collection::DustCollectionSchema<'_, 'a> {
+        dust_collection::DustCollectionSchema(self)
+    }
+
+    /// Gains access to the `EthWatch` schema.
+    pub fn eth_watch_schema(&mut self) -> eth_watch::EthWatchSchema<'_, 'a> {
+        eth_watch::EthWatchSchema(self)
+    }
+
     /// Gains access to the `Ethereum` schema.
     pub fn ethereum_schema(&mut self) -> ethereum::EthereumSchema<'_, 'a> {
         ethereum::EthereumSchema(self)
     }
 
+    /// Gains access to the `Event` schema.
+    pub fn event_schema(&mut self) -> event::EventSchema<'_, 'a> {
+        event::EventSchema(self)
+    }
+
+    /// Gains access to the `ExternalProvers` schema.
+    pub fn external_provers_schema(&mut self) -> external_provers::ExternalProversSchema<'_, 'a> {
+        external_provers::ExternalProversSchema(self)
+    }
+
+    /// Gains access to the `FeeRefunds` schema.
+    pub fn fee_refunds_schema(&mut self) -> fee_refunds::FeeRefundsSchema<'_, 'a> {
+        fee_refunds::FeeRefundsSchema(self)
+    }
+
+    /// Gains access to the `FeeSweeps` schema.
+    pub fn fee_sweeps_schema(&mut self) -> fee_sweeps::FeeSweepsSchema<'_, 'a> {
+        fee_sweeps::FeeSweepsSchema(self)
+    }
+
+    /// Gains access to the `Guardians` schema.
+    pub fn guardians_schema(&mut self) -> guardians::GuardiansSchema<'_, 'a> {
+        guardians::GuardiansSchema(self)
+    }
+
+    /// Gains access to the `Idempotency` schema.
+    pub fn idempotency_schema(&mut self) -> idempotency::IdempotencySchema<'_, 'a> {
+        idempotency::IdempotencySchema(self)
+    }
+
+    /// Gains access to the `KeyAudit` schema.
+    pub fn key_audit_schema(&mut self) -> key_audit::KeyAuditSchema<'_, 'a> {
+        key_audit::KeyAuditSchema(self)
+    }
+
+    /// Gains access to the `L1Messages` schema.
+    pub fn l1_messages_schema(&mut self) -> l1_messages::L1MessagesSchema<'_, 'a> {
+        l1_messages::L1MessagesSchema(self)
+    }
+
+    /// Gains access to the `LeaderElection` schema.
+    pub fn leader_election_schema(&mut self) -> leader_election::LeaderElectionSchema<'_, 'a> {
+        leader_election::LeaderElectionSchema(self)
+    }
+
+    /// Gains access to the `NonceReservations` schema.
+    pub fn nonce_reservations_schema(
+        &mut self,
+    ) -> nonce_reservations::NonceReservationsSchema<'_, 'a> {
+        nonce_reservations::NonceReservationsSchema(self)
+    }
+
+    /// Gains access to the `PriorityOpCosts` schema.
+    pub fn priority_op_costs_schema(&mut self) -> priority_op_costs::PriorityOpCostsSchema<'_, 'a> {
+        priority_op_costs::PriorityOpCostsSchema(self)
+    }
+
+    /// Gains access to the `ProofArchives` schema.
+    pub fn proof_archives_schema(&mut self) -> proof_archives::ProofArchivesSchema<'_, 'a> {
+        proof_archives::ProofArchivesSchema(self)
+    }
+
     /// Gains access to the `Prover` schema.
     pub fn prover_schema(&mut self) -> prover::ProverSchema<'_, 'a> {
         prover::ProverSchema(self)
@@ -220,6 +347,31 @@ impl<'a> StorageProcessor<'a> {
         ForcedExitRequestsSchema(self)
     }
 
+    /// Gains access to the `FastWithdrawals` schema.
+    pub fn fast_withdrawals_schema(&mut self) -> fast_withdrawals::FastWithdrawalsSchema<'_, 'a> {
+        fast_withdrawals::FastWithdrawalsSchema(self)
+    }
+
+    /// Gains access to the `Revenue` schema.
+    pub fn revenue_schema(&mut self) -> revenue::RevenueSchema<'_, 'a> {
+        revenue::RevenueSchema(self)
+    }
+
+    /// Gains access to the `Screening` schema.
+    pub fn screening_schema(&mut self) -> screening::ScreeningSchema<'_, 'a> {
+        screening::ScreeningSchema(self)
+    }
+
+    /// Gains access to the `ShadowEthereum` schema.
+    pub fn shadow_ethereum_schema(&mut self) -> shadow_ethereum::ShadowEthereumSchema<'_, 'a> {
+        shadow_ethereum::ShadowEthereumSchema(self)
+    }
+
+    /// Gains access to the `Webhooks` schema.
+    pub fn webhooks_schema(&mut self) -> webhooks::WebhooksSchema<'_, 'a> {
+        webhooks::WebhooksSchema(self)
+    }
+
     fn conn(&mut self) -> &mut PgConnection {
         match &mut self.conn {
             ConnectionHolder::Pooled(conn) => conn,
@@ -0,0 +1,187 @@
+// Built-in deps
+use std::{cmp::max, convert::TryFrom, time::Instant};
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_types::{nonce_reservation::NonceReservation, Address, Nonce};
+// Local imports
+use crate::{QueryResult, StorageProcessor};
+
+/// Outcome of the nonce reservation request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NonceReservationOutcome {
+    Reserved(NonceReservation),
+    /// The request with the same timestamp has already been processed.
+    Replayed,
+    /// The reserved range would exceed the maximum nonce.
+    NonceOverflow,
+}
+
+/// NonceReservations schema handles the `nonce_reservations` table, storing the ranges
+/// of nonces reserved by the accounts.
+#[derive(Debug)]
+pub struct NonceReservationsSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> NonceReservationsSchema<'a, 'c> {
+    /// Reserves `count` nonces of the account following both its committed nonce
+    /// and its active reservations.
+    pub async fn reserve_nonces(
+        &mut self,
+        address: Address,
+        committed_nonce: Nonce,
+        count: u32,
+        request_timestamp: u64,
+        expires_at: DateTime<Utc>,
+    ) -> QueryResult<NonceReservationOutcome> {
+        let start = Instant::now();
+        let request_timestamp = i64::try_from(request_timestamp)?;
+        let mut transaction = self.0.start_transaction().await?;
+
+        // Concurrent reservations of the same account must not overlap, so they lock
+        // the row of the account until the transaction ends.
+        sqlx::query!(
+            r#"
+            INSERT INTO nonce_reservation_accounts ( address )
+            VALUES ( $1 )
+            ON CONFLICT (address) DO UPDATE SET address = EXCLUDED.address
+            "#,
+            address.as_bytes(),
+        )
+        .execute(transaction.conn())
+        .await?;
+        let last_reserved = sqlx::query!(
+            r#"
+            SELECT MAX(last_nonce) AS last_nonce FROM nonce_reservations
+            WHERE address = $1 AND expires_at > now()
+            "#,
+            address.as_bytes(),
+        )
+        .fetch_one(transaction.conn())
+        .await?
+        .last_nonce;
+
+        let first_nonce = match last_reserved {
+            Some(last_reserved) => max(i64::from(*committed_nonce), last_reserved + 1),
+            None => i64::from(*committed_nonce),
+        };
+        let last_nonce = first_nonce + i64::from(count) - 1;
+        let (first_nonce_u32, last_nonce_u32) =
+            match (u32::try_from(first_nonce), u32::try_from(last_nonce)) {
+                (Ok(first_nonce), Ok(last_nonce)) if count > 0 => {
+                    (Nonce(first_nonce), Nonce(last_nonce))
+                }
+                _ => return Ok(NonceReservationOutcome::NonceOverflow),
+            };
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO nonce_reservations ( address, first_nonce, last_nonce, request_timestamp, expires_at )
+            VALUES ( $1, $2, $3, $4, $5 )
+            ON CONFLICT (address, request_timestamp) DO NOTHING
+            RETURNING id
+            "#,
+            address.as_bytes(),
+            first_nonce,
+            last_nonce,
+            request_timestamp,
+            expires_at,
+        )
+        .fetch_optional(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.nonce_reservations.reserve_nonces", start.elapsed());
+        Ok(match inserted {
+            Some(_) => NonceReservationOutcome::Reserved(NonceReservation {
+                address,
+                first_nonce: first_nonce_u32,
+                last_nonce: last_nonce_u32,
+                expires_at,
+            }),
+            None => NonceReservationOutcome::Replayed,
+        })
+    }
+
+    /// Removes the expired reservations. The requests are checked for the replays against
+    /// the stored reservations, so the reservations of the requests signed after the given
+    /// timestamp, which may still be accepted, are kept.
+    /// Returns the number of the removed reservations.
+    pub async fn remove_expired_reservations(
+        &mut self,
+        accepted_since_timestamp: u64,
+    ) -> QueryResult<u64> {
+        let start = Instant::now();
+        let removed = sqlx::query!(
+            "DELETE FROM nonce_reservations WHERE expires_at <= now() AND request_timestamp < $1",
+            i64::try_from(accepted_since_timestamp)?
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected();
+
+        metrics::histogram!(
+            "sql.nonce_reservations.remove_expired_reservations",
+            start.elapsed()
+        );
+        Ok(removed)
+    }
+
+    /// Loads the active reservations of the account ordered by the first reserved nonce.
+    pub async fn load_active_reservations(
+        &mut self,
+        address: Address,
+    ) -> QueryResult<Vec<NonceReservation>> {
+        let start = Instant::now();
+        let reservations = sqlx::query!(
+            r#"
+            SELECT first_nonce, last_nonce, expires_at FROM nonce_reservations
+            WHERE address = $1 AND expires_at > now()
+            ORDER BY first_nonce
+            "#,
+            address.as_bytes(),
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| NonceReservation {
+            address,
+            first_nonce: Nonce(row.first_nonce as u32),
+            last_nonce: Nonce(row.last_nonce as u32),
+            expires_at: row.expires_at,
+        })
+        .collect();
+
+        metrics::histogram!(
+            "sql.nonce_reservations.load_active_reservations",
+            start.elapsed()
+        );
+        Ok(reservations)
+    }
+
+    /// Loads the active reservations of all the accounts.
+    pub async fn load_all_active_reservations(&mut self) -> QueryResult<Vec<NonceReservation>> {
+        let start = Instant::now();
+        let reservations = sqlx::query!(
+            r#"
+            SELECT address, first_nonce, last_nonce, expires_at FROM nonce_reservations
+            WHERE expires_at > now()
+            ORDER BY first_nonce
+            "#,
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| NonceReservation {
+            address: Address::from_slice(&row.address),
+            first_nonce: Nonce(row.first_nonce as u32),
+            last_nonce: Nonce(row.last_nonce as u32),
+            expires_at: row.expires_at,
+        })
+        .collect();
+
+        metrics::histogram!(
+            "sql.nonce_reservations.load_all_active_reservations",
+            start.elapsed()
+        );
+        Ok(reservations)
+    }
+}
@@ -0,0 +1,147 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use num::{bigint::ToBigInt, BigInt};
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{
+    aggregated_operations::AggregatedActionType,
+    priority_op_cost::{PriorityOpCost, PriorityOpCostSummary, PriorityOpGas},
+    SerialId,
+};
+// Local imports
+use self::records::StoredPriorityOpCost;
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Priority operation costs schema stores the L1 gas spent by the operator on the priority
+/// operations in the `commitBlocks` and `executeBlocks` transactions.
+#[derive(Debug)]
+pub struct PriorityOpCostsSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> PriorityOpCostsSchema<'a, 'c> {
+    /// Stores the gas attributed to the priority operations by the confirmed transaction.
+    /// Gas of the operations other than `CommitBlocks` and `ExecuteBlocks` is ignored.
+    pub async fn record_priority_op_gas(
+        &mut self,
+        action: AggregatedActionType,
+        costs: &[PriorityOpGas],
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        for cost in costs {
+            let serial_id = cost.serial_id as i64;
+            let block_number = i64::from(*cost.block_number);
+            let gas = cost.gas as i64;
+            let fee = BigDecimal::from(BigInt::from(cost.fee.clone()));
+            match action {
+                AggregatedActionType::CommitBlocks => {
+                    sqlx::query!(
+                        r#"
+                        INSERT INTO priority_op_costs (
+                            serial_id, block_number, op_type, commit_gas, commit_fee
+                        )
+                        VALUES ( $1, $2, $3, $4, $5 )
+                        ON CONFLICT (serial_id)
+                        DO UPDATE SET commit_gas = $4, commit_fee = $5, updated_at = now()
+                        "#,
+                        serial_id,
+                        block_number,
+                        cost.op_type,
+                        gas,
+                        fee,
+                    )
+                    .execute(transaction.conn())
+                    .await?;
+                }
+                AggregatedActionType::ExecuteBlocks => {
+                    sqlx::query!(
+                        r#"
+                        INSERT INTO priority_op_costs (
+                            serial_id, block_number, op_type, execute_gas, execute_fee
+                        )
+                        VALUES ( $1, $2, $3, $4, $5 )
+                        ON CONFLICT (serial_id)
+                        DO UPDATE SET execute_gas = $4, execute_fee = $5, updated_at = now()
+                        "#,
+                        serial_id,
+                        block_number,
+                        cost.op_type,
+                        gas,
+                        fee,
+                    )
+                    .execute(transaction.conn())
+                    .await?;
+                }
+                _ => {}
+            }
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!(
+            "sql.priority_op_costs.record_priority_op_gas",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Loads the L1 costs of the priority operation.
+    pub async fn load_cost(&mut self, serial_id: SerialId) -> QueryResult<Option<PriorityOpCost>> {
+        let start = Instant::now();
+        let cost = sqlx::query_as!(
+            StoredPriorityOpCost,
+            r#"
+            SELECT serial_id, block_number, op_type, commit_gas, commit_fee, execute_gas, execute_fee
+            FROM priority_op_costs WHERE serial_id = $1
+            "#,
+            serial_id as i64,
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.priority_op_costs.load_cost", start.elapsed());
+        Ok(cost.map(From::from))
+    }
+
+    /// Calculates the average costs of the latest `limit` priority operations of every type
+    /// which were both committed and executed.
+    pub async fn load_summary(&mut self, limit: u32) -> QueryResult<Vec<PriorityOpCostSummary>> {
+        let start = Instant::now();
+        let summary = sqlx::query!(
+            r#"
+            SELECT
+                op_type,
+                COUNT(*) as "ops_count!",
+                AVG(commit_gas + execute_gas)::BIGINT as "avg_gas!",
+                AVG(commit_fee + execute_fee) as "avg_fee!"
+            FROM (
+                SELECT *, ROW_NUMBER() OVER (PARTITION BY op_type ORDER BY serial_id DESC) AS op_rank
+                FROM priority_op_costs
+                WHERE commit_gas IS NOT NULL AND execute_gas IS NOT NULL
+            ) latest
+            WHERE op_rank <= $1
+            GROUP BY op_type
+            ORDER BY op_type
+            "#,
+            i64::from(limit),
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|record| PriorityOpCostSummary {
+            op_type: record.op_type,
+            ops_count: record.ops_count as u64,
+            avg_gas: record.avg_gas as u64,
+            avg_fee: record
+                .avg_fee
+                .to_bigint()
+                .and_then(|int| int.to_biguint())
+                .expect("Invalid priority operation fee has been stored"),
+        })
+        .collect();
+
+        metrics::histogram!("sql.priority_op_costs.load_summary", start.elapsed());
+        Ok(summary)
+    }
+}
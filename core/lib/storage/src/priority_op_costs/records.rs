@@ -0,0 +1,37 @@
+// External imports
+use num::bigint::ToBigInt;
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{priority_op_cost::PriorityOpCost, BlockNumber};
+// Local imports
+
+#[derive(Debug, Clone)]
+pub struct StoredPriorityOpCost {
+    pub serial_id: i64,
+    pub block_number: i64,
+    pub op_type: String,
+    pub commit_gas: Option<i64>,
+    pub commit_fee: Option<BigDecimal>,
+    pub execute_gas: Option<i64>,
+    pub execute_fee: Option<BigDecimal>,
+}
+
+fn fee_from_stored(fee: BigDecimal) -> num::BigUint {
+    fee.to_bigint()
+        .and_then(|int| int.to_biguint())
+        .expect("Invalid priority operation fee has been stored")
+}
+
+impl From<StoredPriorityOpCost> for PriorityOpCost {
+    fn from(val: StoredPriorityOpCost) -> Self {
+        Self {
+            serial_id: val.serial_id as u64,
+            block_number: BlockNumber(val.block_number as u32),
+            op_type: val.op_type,
+            commit_gas: val.commit_gas.map(|gas| gas as u64),
+            commit_fee: val.commit_fee.map(|fee| fee_from_stored(fee).into()),
+            execute_gas: val.execute_gas.map(|gas| gas as u64),
+            execute_fee: val.execute_fee.map(|fee| fee_from_stored(fee).into()),
+        }
+    }
+}
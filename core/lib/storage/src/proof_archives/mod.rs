@@ -0,0 +1,76 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+// Workspace imports
+use zksync_types::{proof_archive::ProofArchiveRecord, BlockNumber, H256};
+// Local imports
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+use records::DbProofArchive;
+
+/// Proof archives schema handles the `proof_archives` table, recording the archives of
+/// the verified blocks exported for the long-term retention.
+#[derive(Debug)]
+pub struct ProofArchivesSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> ProofArchivesSchema<'a, 'c> {
+    /// Records the exported archive of the block, replacing the record of the previous export.
+    pub async fn record_archive(
+        &mut self,
+        block_number: BlockNumber,
+        file_name: &str,
+        archive_hash: H256,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO proof_archives ( block_number, file_name, archive_hash )
+            VALUES ( $1, $2, $3 )
+            ON CONFLICT (block_number)
+            DO UPDATE SET file_name = $2, archive_hash = $3, exported_at = now()
+            "#,
+            i64::from(*block_number),
+            file_name,
+            archive_hash.as_bytes(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.proof_archives.record_archive", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the record of the archive of the block.
+    pub async fn get_archive(
+        &mut self,
+        block_number: BlockNumber,
+    ) -> QueryResult<Option<ProofArchiveRecord>> {
+        let start = Instant::now();
+        let archive = sqlx::query_as!(
+            DbProofArchive,
+            "SELECT * FROM proof_archives WHERE block_number = $1",
+            i64::from(*block_number)
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(ProofArchiveRecord::from);
+
+        metrics::histogram!("sql.proof_archives.get_archive", start.elapsed());
+        Ok(archive)
+    }
+
+    /// Returns the number of the last block the archive is exported for.
+    pub async fn last_archived_block(&mut self) -> QueryResult<Option<BlockNumber>> {
+        let start = Instant::now();
+        let block_number = sqlx::query!("SELECT max(block_number) FROM proof_archives")
+            .fetch_one(self.0.conn())
+            .await?
+            .max
+            .map(|block_number| BlockNumber(block_number as u32));
+
+        metrics::histogram!("sql.proof_archives.last_archived_block", start.elapsed());
+        Ok(block_number)
+    }
+}
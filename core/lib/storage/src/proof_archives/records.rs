@@ -0,0 +1,24 @@
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_types::{proof_archive::ProofArchiveRecord, BlockNumber, H256};
+// Local imports
+
+#[derive(Debug, Clone)]
+pub struct DbProofArchive {
+    pub block_number: i64,
+    pub file_name: String,
+    pub archive_hash: Vec<u8>,
+    pub exported_at: DateTime<Utc>,
+}
+
+impl From<DbProofArchive> for ProofArchiveRecord {
+    fn from(archive: DbProofArchive) -> Self {
+        Self {
+            block_number: BlockNumber(archive.block_number as u32),
+            file_name: archive.file_name,
+            archive_hash: H256::from_slice(&archive.archive_hash),
+            exported_at: archive.exported_at,
+        }
+    }
+}
@@ -6,13 +6,15 @@ use sqlx::Done;
 // Workspace imports
 use zksync_types::BlockNumber;
 // Local imports
-use self::records::{StorageProverJobQueue, StoredAggregatedProof, StoredProof};
+use self::records::{
+    StorageProverJobProgress, StorageProverJobQueue, StoredAggregatedProof, StoredProof,
+};
 use crate::chain::operations::OperationsSchema;
 use crate::prover::records::StorageBlockWitness;
 use crate::{QueryResult, StorageProcessor};
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
 use zksync_types::aggregated_operations::AggregatedActionType;
-use zksync_types::prover::{ProverJob, ProverJobStatus, ProverJobType};
+use zksync_types::prover::{ProofProgress, ProverJob, ProverJobStatus, ProverJobType};
 
 pub mod records;
 
@@ -110,6 +112,10 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             )
             .execute(transaction.conn())
             .await?;
+            // The progress reported by the previous prover of the job is no longer relevant.
+            sqlx::query!("DELETE FROM prover_job_progress WHERE job_id = $1", job.id)
+                .execute(transaction.conn())
+                .await?;
 
             Some(ProverJob::new(
                 job.id,
@@ -146,6 +152,75 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Stores the stage of the proof generation reported by the prover working on the job.
+    pub async fn record_proof_progress(
+        &mut self,
+        job_id: i32,
+        prover_name: &str,
+        progress: ProofProgress,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        // The stage start time is kept while the same prover reports the same stage,
+        // so it's visible how long the stage takes.
+        sqlx::query!(
+            "INSERT INTO prover_job_progress (job_id, prover_name, stage)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (job_id) DO UPDATE SET
+                stage_started_at = CASE
+                    WHEN prover_job_progress.stage = EXCLUDED.stage
+                        AND prover_job_progress.prover_name = EXCLUDED.prover_name
+                    THEN prover_job_progress.stage_started_at
+                    ELSE now()
+                END,
+                prover_name = EXCLUDED.prover_name,
+                stage = EXCLUDED.stage,
+                updated_at = now()",
+            job_id,
+            prover_name,
+            progress.stage(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "record_proof_progress");
+        Ok(())
+    }
+
+    /// Loads the prover jobs covering the block along with the progress reported by the provers.
+    pub async fn load_block_proof_progress(
+        &mut self,
+        block_number: BlockNumber,
+    ) -> QueryResult<Vec<StorageProverJobProgress>> {
+        let start = Instant::now();
+        let jobs = sqlx::query_as!(
+            StorageProverJobProgress,
+            r#"
+            SELECT
+                prover_job_queue.id AS job_id,
+                prover_job_queue.job_type,
+                prover_job_queue.job_status,
+                prover_job_queue.first_block,
+                prover_job_queue.last_block,
+                prover_job_queue.updated_by,
+                prover_job_queue.updated_at,
+                prover_job_progress.prover_name AS "prover_name?",
+                prover_job_progress.stage AS "stage?",
+                prover_job_progress.stage_started_at AS "stage_started_at?",
+                prover_job_progress.updated_at AS "progress_updated_at?"
+            FROM prover_job_queue
+            LEFT JOIN prover_job_progress ON prover_job_progress.job_id = prover_job_queue.id
+            WHERE prover_job_queue.first_block <= $1 AND prover_job_queue.last_block >= $1
+            ORDER BY prover_job_queue.id
+            "#,
+            i64::from(*block_number),
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "load_block_proof_progress");
+        Ok(jobs)
+    }
+
     /// Marks the prover as stopped.
     pub async fn record_prover_stop(&mut self, prover_name: &str) -> QueryResult<()> {
         let start = Instant::now();
@@ -164,6 +239,24 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Returns the job to the queue, so that it's given to another prover.
+    pub async fn release_prover_job(&mut self, job_id: i32) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "UPDATE prover_job_queue
+            SET (updated_at, updated_by, job_status) = (now(), 'server_release_job', $1)
+            WHERE id = $2 and job_status = $3",
+            ProverJobStatus::Idle.to_number(),
+            job_id,
+            ProverJobStatus::InProgress.to_number()
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "release_prover_job");
+        Ok(())
+    }
+
     /// Stores the proof for a block.
     pub async fn store_proof(
         &mut self,
@@ -284,6 +377,33 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(proof)
     }
 
+    /// Gets the last stored aggregated proof including the proof of the block, along with
+    /// the range of the blocks it's made for.
+    pub async fn load_aggregated_proof_for_block(
+        &mut self,
+        block_number: BlockNumber,
+    ) -> QueryResult<Option<(BlockNumber, BlockNumber, AggregatedProof)>> {
+        let start = Instant::now();
+        let proof = sqlx::query_as!(
+            StoredAggregatedProof,
+            "SELECT * FROM aggregated_proofs WHERE first_block <= $1 AND last_block >= $1
+            ORDER BY created_at DESC LIMIT 1",
+            i64::from(*block_number),
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|stored| {
+            (
+                BlockNumber(stored.first_block as u32),
+                BlockNumber(stored.last_block as u32),
+                serde_json::from_value(stored.proof).unwrap(),
+            )
+        });
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "load_aggregated_proof_for_block");
+        Ok(proof)
+    }
+
     /// Stores witness for a block
     pub async fn store_witness(
         &mut self,
@@ -408,24 +528,17 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(())
     }
 
-    // Removes blocks with number greater than `last_block` from prover job queue
+    // Removes blocks with number greater than `last_block` from prover job queue.
+    // Aggregated jobs that cover any of the removed blocks are removed as well,
+    // since their job data contains the proofs of the removed blocks.
     pub async fn remove_prover_jobs(&mut self, last_block: BlockNumber) -> QueryResult<()> {
         let start = Instant::now();
-        let mut transaction = self.0.start_transaction().await?;
         sqlx::query!(
-            "DELETE FROM prover_job_queue WHERE first_block > $1",
+            "DELETE FROM prover_job_queue WHERE last_block > $1",
             *last_block as i64
         )
-        .execute(transaction.conn())
-        .await?;
-
-        sqlx::query!(
-            "UPDATE prover_job_queue SET last_block = $1 WHERE last_block > $1",
-            *last_block as i64
-        )
-        .execute(transaction.conn())
+        .execute(self.0.conn())
         .await?;
-        transaction.commit().await?;
 
         metrics::histogram!("sql", start.elapsed(), "prover" => "remove_prover_jobs");
         Ok(())
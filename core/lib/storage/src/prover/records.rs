@@ -69,3 +69,19 @@ pub struct StorageProverJobQueue {
     pub last_block: i64,
     pub job_data: serde_json::Value,
 }
+
+/// Prover job joined with the progress reported by the prover.
+#[derive(Debug, Clone, FromRow)]
+pub struct StorageProverJobProgress {
+    pub job_id: i32,
+    pub job_type: String,
+    pub job_status: i32,
+    pub first_block: i64,
+    pub last_block: i64,
+    pub updated_by: String,
+    pub updated_at: DateTime<Utc>,
+    pub prover_name: Option<String>,
+    pub stage: Option<String>,
+    pub stage_started_at: Option<DateTime<Utc>>,
+    pub progress_updated_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,104 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::{DateTime, Utc};
+use num::BigInt;
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{
+    event::EventOffset,
+    revenue::{CollectedFees, RevenuePeriod, TokenRevenue},
+};
+use zksync_utils::ratio_to_big_decimal;
+// Local imports
+use crate::{tokens::STORED_USD_PRICE_PRECISION, QueryResult, StorageProcessor};
+
+pub mod records;
+
+use records::DbTokenRevenue;
+
+/// Revenue schema handles the `operator_revenue` table, storing the fees collected by the operator
+/// aggregated by the token and the hour.
+#[derive(Debug)]
+pub struct RevenueSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> RevenueSchema<'a, 'c> {
+    /// Adds the collected fees to the hourly aggregates and moves the event log consumer
+    /// to the next event atomically, so the fees of every block are counted exactly once.
+    pub async fn store_collected_fees(
+        &mut self,
+        consumer: &str,
+        next_event_offset: EventOffset,
+        fees: &[CollectedFees],
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        for fees in fees {
+            sqlx::query!(
+                r#"
+                INSERT INTO operator_revenue ( period_start, token_id, fee_amount, fee_usd, tx_count )
+                VALUES ( $1, $2, $3, $4, $5 )
+                ON CONFLICT (period_start, token_id)
+                DO UPDATE SET
+                    fee_amount = operator_revenue.fee_amount + $3,
+                    fee_usd = operator_revenue.fee_usd + $4,
+                    tx_count = operator_revenue.tx_count + $5
+                "#,
+                fees.period_start,
+                i32::from(*fees.token_id),
+                BigDecimal::from(BigInt::from(fees.amount.clone())),
+                ratio_to_big_decimal(&fees.amount_usd, STORED_USD_PRICE_PRECISION),
+                fees.tx_count as i64,
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
+
+        transaction
+            .event_schema()
+            .update_consumer_offset(consumer, next_event_offset)
+            .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.revenue.store_collected_fees", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the revenue collected within `[from, to)` interval, grouped by the period
+    /// and the token.
+    pub async fn load_revenue(
+        &mut self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        period: RevenuePeriod,
+    ) -> QueryResult<Vec<TokenRevenue>> {
+        let start = Instant::now();
+        let revenue = sqlx::query_as!(
+            DbTokenRevenue,
+            r#"
+            SELECT
+                date_trunc($3, period_start) AS "period_start!",
+                token_id,
+                SUM(fee_amount) AS "fee_amount!",
+                SUM(fee_usd) AS "fee_usd!",
+                SUM(tx_count)::BIGINT AS "tx_count!"
+            FROM operator_revenue
+            WHERE period_start >= $1 AND period_start < $2
+            GROUP BY 1, token_id
+            ORDER BY 1, token_id
+            "#,
+            from,
+            to,
+            period.as_str(),
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(TokenRevenue::from)
+        .collect();
+
+        metrics::histogram!("sql.revenue.load_revenue", start.elapsed());
+        Ok(revenue)
+    }
+}
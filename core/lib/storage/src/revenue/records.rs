@@ -0,0 +1,33 @@
+// External imports
+use chrono::{DateTime, Utc};
+use num::bigint::ToBigInt;
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{revenue::TokenRevenue, TokenId};
+use zksync_utils::big_decimal_to_ratio;
+// Local imports
+
+#[derive(Debug, Clone)]
+pub struct DbTokenRevenue {
+    pub period_start: DateTime<Utc>,
+    pub token_id: i32,
+    pub fee_amount: BigDecimal,
+    pub fee_usd: BigDecimal,
+    pub tx_count: i64,
+}
+
+impl From<DbTokenRevenue> for TokenRevenue {
+    fn from(val: DbTokenRevenue) -> Self {
+        Self {
+            period_start: val.period_start,
+            token_id: TokenId(val.token_id as u16),
+            fee_amount: val
+                .fee_amount
+                .to_bigint()
+                .and_then(|int| int.to_biguint())
+                .expect("Invalid fee amount has been stored"),
+            fee_usd: big_decimal_to_ratio(&val.fee_usd).expect("Fee value could not be negative"),
+            tx_count: val.tx_count as u64,
+        }
+    }
+}
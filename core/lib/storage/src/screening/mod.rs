@@ -0,0 +1,96 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_types::{
+    screening::{ScreeningAction, ScreeningAuditEntry, ScreeningRecord},
+    tx::TxHash,
+};
+// Local imports
+use self::records::StoredScreeningRecord;
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Screening schema handles the `screening_audit` table, storing the audit trail of the
+/// transactions and priority operations sending funds to the screened addresses.
+#[derive(Debug)]
+pub struct ScreeningSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> ScreeningSchema<'a, 'c> {
+    /// Appends the screening decision to the audit trail.
+    /// Priority operations which were already screened are skipped.
+    pub async fn record_screening(&mut self, record: &ScreeningRecord) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO screening_audit (
+                address, tx_hash, priority_op_serial_id, action, reason, release_at
+            )
+            VALUES ( $1, $2, $3, $4, $5, $6 )
+            ON CONFLICT (priority_op_serial_id) DO NOTHING
+            "#,
+            record.address.as_bytes(),
+            record.tx_hash.as_ref().map(|hash| hash.as_ref()),
+            record.priority_op_serial_id.map(|id| id as i64),
+            record.action.as_str(),
+            record.reason,
+            record.release_at,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.screening.record_screening", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the hashes of the delayed transactions released after the given time,
+    /// together with their release times.
+    pub async fn load_delays(
+        &mut self,
+        released_after: DateTime<Utc>,
+    ) -> QueryResult<Vec<(TxHash, DateTime<Utc>)>> {
+        let start = Instant::now();
+        let delays = sqlx::query!(
+            r#"
+            SELECT tx_hash as "tx_hash!", release_at as "release_at!" FROM screening_audit
+            WHERE action = $1 AND tx_hash IS NOT NULL AND release_at > $2
+            "#,
+            ScreeningAction::Delay.as_str(),
+            released_after,
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|record| {
+            let tx_hash =
+                TxHash::from_slice(&record.tx_hash).expect("Invalid tx hash has been stored");
+            (tx_hash, record.release_at)
+        })
+        .collect();
+
+        metrics::histogram!("sql.screening.load_delays", start.elapsed());
+        Ok(delays)
+    }
+
+    /// Loads up to `limit` audit trail entries ordered by ID and starting after `after_id`.
+    pub async fn load_audit_trail(
+        &mut self,
+        after_id: Option<i64>,
+        limit: u32,
+    ) -> QueryResult<Vec<ScreeningAuditEntry>> {
+        let start = Instant::now();
+        let entries = sqlx::query_as!(
+            StoredScreeningRecord,
+            "SELECT * FROM screening_audit WHERE id > $1 ORDER BY id ASC LIMIT $2",
+            after_id.unwrap_or(0),
+            i64::from(limit),
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.screening.load_audit_trail", start.elapsed());
+        Ok(entries.into_iter().map(From::from).collect())
+    }
+}
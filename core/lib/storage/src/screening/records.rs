@@ -0,0 +1,43 @@
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_types::{
+    screening::{ScreeningAuditEntry, ScreeningRecord},
+    tx::TxHash,
+    Address,
+};
+// Local imports
+
+#[derive(Debug, Clone)]
+pub struct StoredScreeningRecord {
+    pub id: i64,
+    pub address: Vec<u8>,
+    pub tx_hash: Option<Vec<u8>>,
+    pub priority_op_serial_id: Option<i64>,
+    pub action: String,
+    pub reason: String,
+    pub release_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<StoredScreeningRecord> for ScreeningAuditEntry {
+    fn from(val: StoredScreeningRecord) -> Self {
+        Self {
+            id: val.id,
+            record: ScreeningRecord {
+                address: Address::from_slice(&val.address),
+                tx_hash: val.tx_hash.map(|hash| {
+                    TxHash::from_slice(&hash).expect("Invalid tx hash has been stored")
+                }),
+                priority_op_serial_id: val.priority_op_serial_id.map(|id| id as u64),
+                action: val
+                    .action
+                    .parse()
+                    .expect("Invalid screening action has been stored"),
+                reason: val.reason,
+                release_at: val.release_at,
+            },
+            created_at: val.created_at,
+        }
+    }
+}
@@ -0,0 +1,521 @@
+// Built-in deps
+use std::{collections::VecDeque, convert::TryFrom, str::FromStr, time::Instant};
+// External imports
+use anyhow::format_err;
+use num::{BigInt, BigUint};
+use sqlx::types::BigDecimal;
+use zksync_basic_types::{H256, U256};
+// Workspace imports
+use zksync_types::aggregated_operations::{AggregatedActionType, AggregatedOperation};
+use zksync_types::ethereum::{ETHOperation, InsertedOperationResponse};
+// Local imports
+use self::records::{ShadowETHParams, StorageShadowETHOperation};
+use crate::{
+    chain::operations::records::StoredAggregatedOperation,
+    ethereum::records::{ETHStats, ETHTxHash},
+    QueryResult, StorageProcessor,
+};
+
+pub mod records;
+
+/// Shadow Ethereum schema stores the state of the `eth_sender` instances sending the aggregated
+/// operations to the shadow networks, e.g. to a mainnet fork used to rehearse a contract upgrade.
+///
+/// Each shadow network has its own nonce, gas price limit and the list of the sent transactions,
+/// so it doesn't affect the actual network. The operations are taken from `aggregate_operations`
+/// regardless of their state on the actual network, starting with the blocks the shadow network
+/// contract hasn't processed at the moment the network was initialized.
+#[derive(Debug)]
+pub struct ShadowEthereumSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> ShadowEthereumSchema<'a, 'c> {
+    /// Initializes the state of the shadow network unless it's already initialized.
+    /// The operations are sent to the network starting with the blocks after the given ones.
+    /// Returns `false` if the network was initialized before.
+    pub async fn initialize_network(
+        &mut self,
+        network: &str,
+        nonce: U256,
+        gas_price_limit: U256,
+        stats: ETHStats,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let nonce = i64::try_from(nonce).map_err(|err| format_err!("Invalid nonce: {}", err))?;
+        let gas_price_limit = i64::try_from(gas_price_limit)
+            .map_err(|err| format_err!("Invalid gas price limit: {}", err))?;
+
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO shadow_eth_parameters (
+                network, nonce, gas_price_limit,
+                last_committed_block, last_verified_block, last_executed_block,
+                loaded_committed_block, loaded_verified_block, loaded_executed_block
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $4, $5, $6)
+            ON CONFLICT (network) DO NOTHING
+            "#,
+            network,
+            nonce,
+            gas_price_limit,
+            stats.last_committed_block,
+            stats.last_verified_block,
+            stats.last_executed_block,
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected()
+            > 0;
+
+        metrics::histogram!("sql.shadow_ethereum.initialize_network", start.elapsed());
+        Ok(inserted)
+    }
+
+    /// Returns whether the state of the shadow network is initialized.
+    pub async fn is_network_initialized(&mut self, network: &str) -> QueryResult<bool> {
+        let start = Instant::now();
+        let initialized = sqlx::query!(
+            "SELECT COUNT(*) FROM shadow_eth_parameters WHERE network = $1",
+            network
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .count
+        .unwrap_or(0)
+            > 0;
+
+        metrics::histogram!(
+            "sql.shadow_ethereum.is_network_initialized",
+            start.elapsed()
+        );
+        Ok(initialized)
+    }
+
+    /// Loads the operations sent to the shadow network but not confirmed yet,
+    /// each operation has a list of sent Ethereum transactions.
+    pub async fn load_unconfirmed_operations(
+        &mut self,
+        network: &str,
+    ) -> QueryResult<VecDeque<ETHOperation>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let eth_ops = sqlx::query_as!(
+            StorageShadowETHOperation,
+            r#"
+            SELECT id, network, op_id, op_type, nonce, last_deadline_block, last_used_gas_price,
+                raw_tx, confirmed, final_hash
+            FROM shadow_eth_operations
+            WHERE network = $1 AND confirmed = false
+            ORDER BY id ASC
+            "#,
+            network
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let mut ops = VecDeque::with_capacity(eth_ops.len());
+        for eth_op in eth_ops {
+            let raw_op = sqlx::query_as!(
+                StoredAggregatedOperation,
+                "SELECT * FROM aggregate_operations WHERE id = $1",
+                eth_op.op_id
+            )
+            .fetch_one(transaction.conn())
+            .await?;
+
+            let eth_tx_hashes = sqlx::query_as!(
+                ETHTxHash,
+                "SELECT * FROM shadow_eth_tx_hashes
+                WHERE eth_op_id = $1
+                ORDER BY id ASC",
+                eth_op.id
+            )
+            .fetch_all(transaction.conn())
+            .await?;
+            assert!(
+                !eth_tx_hashes.is_empty(),
+                "No hashes stored for the shadow Ethereum operation"
+            );
+
+            let op_type = AggregatedActionType::from_str(eth_op.op_type.as_ref())
+                .expect("Stored operation type must have a valid value");
+            let last_used_gas_price =
+                U256::from_str(&eth_op.last_used_gas_price.to_string()).unwrap();
+            let used_tx_hashes = eth_tx_hashes
+                .iter()
+                .map(|entry| H256::from_slice(&entry.tx_hash))
+                .collect();
+            let final_hash = eth_op.final_hash.map(|hash| H256::from_slice(&hash));
+
+            ops.push_back(ETHOperation {
+                id: eth_op.id,
+                op_type,
+                op: Some(raw_op.into_aggregated_op()),
+                nonce: eth_op.nonce.into(),
+                last_deadline_block: eth_op.last_deadline_block as u64,
+                last_used_gas_price,
+                used_tx_hashes,
+                encoded_tx_data: eth_op.raw_tx,
+                confirmed: eth_op.confirmed,
+                final_hash,
+            });
+        }
+
+        transaction.commit().await?;
+
+        metrics::histogram!(
+            "sql.shadow_ethereum.load_unconfirmed_operations",
+            start.elapsed()
+        );
+        Ok(ops)
+    }
+
+    /// Makes the operations which were taken into processing but not sent to the shadow network
+    /// available for loading again. Should be used after the restart only.
+    pub async fn restore_unprocessed_operations(&mut self, network: &str) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "UPDATE shadow_eth_parameters
+            SET loaded_committed_block = last_committed_block,
+                loaded_verified_block = last_verified_block,
+                loaded_executed_block = last_executed_block
+            WHERE network = $1",
+            network
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.shadow_ethereum.restore_unprocessed_operations",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Loads the aggregated operations not taken into processing for the shadow network yet.
+    /// Operations starting at the already processed blocks are skipped.
+    pub async fn load_unprocessed_operations(
+        &mut self,
+        network: &str,
+    ) -> QueryResult<Vec<(i64, AggregatedOperation)>> {
+        let start = Instant::now();
+        let operations = sqlx::query_as!(
+            StoredAggregatedOperation,
+            r#"
+            SELECT aggregate_operations.* FROM aggregate_operations, shadow_eth_parameters
+            WHERE network = $1 AND (
+                (action_type = $2 AND from_block > loaded_committed_block)
+                OR (action_type = $3 AND from_block > loaded_verified_block)
+                OR (action_type = $4 AND from_block > loaded_executed_block)
+            )
+            ORDER BY id ASC
+            "#,
+            network,
+            AggregatedActionType::CommitBlocks.to_string(),
+            AggregatedActionType::PublishProofBlocksOnchain.to_string(),
+            AggregatedActionType::ExecuteBlocks.to_string(),
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(StoredAggregatedOperation::into_aggregated_op)
+        .collect();
+
+        metrics::histogram!(
+            "sql.shadow_ethereum.load_unprocessed_operations",
+            start.elapsed()
+        );
+        Ok(operations)
+    }
+
+    /// Marks the given aggregated operations as taken into processing for the shadow network.
+    pub async fn remove_unprocessed_operations(
+        &mut self,
+        network: &str,
+        operations_id: Vec<i64>,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        // `GREATEST` ignores `NULL`s, i.e. the types of operations absent in the list.
+        sqlx::query!(
+            r#"
+            WITH loaded AS (
+                SELECT action_type, MAX(to_block) AS to_block FROM aggregate_operations
+                WHERE id = ANY($2)
+                GROUP BY action_type
+            )
+            UPDATE shadow_eth_parameters
+            SET loaded_committed_block = GREATEST(
+                    loaded_committed_block,
+                    (SELECT to_block FROM loaded WHERE action_type = $3)
+                ),
+                loaded_verified_block = GREATEST(
+                    loaded_verified_block,
+                    (SELECT to_block FROM loaded WHERE action_type = $4)
+                ),
+                loaded_executed_block = GREATEST(
+                    loaded_executed_block,
+                    (SELECT to_block FROM loaded WHERE action_type = $5)
+                )
+            WHERE network = $1
+            "#,
+            network,
+            &operations_id,
+            AggregatedActionType::CommitBlocks.to_string(),
+            AggregatedActionType::PublishProofBlocksOnchain.to_string(),
+            AggregatedActionType::ExecuteBlocks.to_string(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.shadow_ethereum.remove_unprocessed_operations",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Stores the operation sent to the shadow network, assigning it the next nonce of the network.
+    pub async fn save_new_eth_tx(
+        &mut self,
+        network: &str,
+        operation: (i64, AggregatedOperation),
+        last_deadline_block: i64,
+        last_used_gas_price: BigUint,
+        raw_tx: Vec<u8>,
+    ) -> QueryResult<InsertedOperationResponse> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let (op_id, operation) = operation;
+        let (first_block, last_block) = operation.get_block_range();
+        let (first_block, last_block) = (i64::from(*first_block), i64::from(*last_block));
+        let op_type = operation.get_action_type();
+
+        // The nonce is assigned along with the operation stats update, so they can't diverge.
+        let params = ShadowEthereumSchema(&mut transaction)
+            .load_params(network)
+            .await?;
+        let expected_first_block = match op_type {
+            AggregatedActionType::CommitBlocks => params.last_committed_block + 1,
+            AggregatedActionType::PublishProofBlocksOnchain => params.last_verified_block + 1,
+            AggregatedActionType::ExecuteBlocks => params.last_executed_block + 1,
+            AggregatedActionType::CreateProofBlocks => {
+                return Err(format_err!("CreateProofBlocks is not sent to Ethereum"));
+            }
+        };
+        if first_block != expected_first_block {
+            return Err(format_err!(
+                "Operation {} for blocks {}-{} doesn't follow the last sent one in the shadow network {}",
+                op_type,
+                first_block,
+                last_block,
+                network
+            ));
+        }
+        let mut stats = ETHStats::from(&params);
+        match op_type {
+            AggregatedActionType::CommitBlocks => stats.last_committed_block = last_block,
+            AggregatedActionType::PublishProofBlocksOnchain => {
+                stats.last_verified_block = last_block
+            }
+            _ => stats.last_executed_block = last_block,
+        }
+        sqlx::query!(
+            "UPDATE shadow_eth_parameters
+            SET nonce = $2, last_committed_block = $3, last_verified_block = $4, last_executed_block = $5
+            WHERE network = $1",
+            network,
+            params.nonce + 1,
+            stats.last_committed_block,
+            stats.last_verified_block,
+            stats.last_executed_block,
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        let last_used_gas_price = BigDecimal::from(BigInt::from(last_used_gas_price));
+        let id = sqlx::query!(
+            "INSERT INTO shadow_eth_operations (network, op_id, op_type, nonce, last_deadline_block, last_used_gas_price, raw_tx)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id",
+            network,
+            op_id,
+            op_type.to_string(),
+            params.nonce,
+            last_deadline_block,
+            last_used_gas_price,
+            raw_tx,
+        )
+        .fetch_one(transaction.conn())
+        .await?
+        .id;
+
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.shadow_ethereum.save_new_eth_tx", start.elapsed());
+        Ok(InsertedOperationResponse {
+            id,
+            nonce: params.nonce.into(),
+        })
+    }
+
+    /// Adds a tx hash entry associated with the shadow Ethereum operation.
+    pub async fn add_hash_entry(&mut self, eth_op_id: i64, hash: &H256) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "INSERT INTO shadow_eth_tx_hashes (eth_op_id, tx_hash) VALUES ($1, $2)",
+            eth_op_id,
+            hash.as_bytes()
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.shadow_ethereum.add_hash_entry", start.elapsed());
+        Ok(())
+    }
+
+    /// Updates the shadow Ethereum operation with the data of the replacement transaction.
+    pub async fn update_eth_tx(
+        &mut self,
+        eth_op_id: i64,
+        new_deadline_block: i64,
+        new_gas_value: BigUint,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let new_gas_price = BigDecimal::from(BigInt::from(new_gas_value));
+        sqlx::query!(
+            "UPDATE shadow_eth_operations
+            SET last_used_gas_price = $1, last_deadline_block = $2
+            WHERE id = $3",
+            new_gas_price,
+            new_deadline_block,
+            eth_op_id
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.shadow_ethereum.update_eth_tx", start.elapsed());
+        Ok(())
+    }
+
+    /// Marks the shadow Ethereum operation performed by the transaction as confirmed.
+    /// Unlike the actual network, the confirmation doesn't affect the state of the blocks.
+    pub async fn confirm_eth_tx(&mut self, network: &str, hash: &H256) -> QueryResult<()> {
+        let start = Instant::now();
+        let updated = sqlx::query!(
+            "UPDATE shadow_eth_operations
+            SET confirmed = true, final_hash = $2
+            WHERE network = $1 AND id IN (
+                SELECT eth_op_id FROM shadow_eth_tx_hashes WHERE tx_hash = $2
+            )",
+            network,
+            hash.as_bytes()
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected();
+        if updated == 0 {
+            return Err(format_err!(
+                "Transaction {:#x} is not sent to the shadow network {}",
+                hash,
+                network
+            ));
+        }
+
+        metrics::histogram!("sql.shadow_ethereum.confirm_eth_tx", start.elapsed());
+        Ok(())
+    }
+
+    /// Returns whether the operation sent to the shadow network before the given one is confirmed.
+    /// Returns `true` for the first operation of the network.
+    pub async fn is_previous_operation_confirmed(
+        &mut self,
+        network: &str,
+        eth_op_id: i64,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let confirmed = sqlx::query!(
+            "SELECT confirmed FROM shadow_eth_operations
+            WHERE network = $1 AND id < $2
+            ORDER BY id DESC
+            LIMIT 1",
+            network,
+            eth_op_id
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|op| op.confirmed)
+        .unwrap_or(true);
+
+        metrics::histogram!(
+            "sql.shadow_ethereum.is_previous_operation_confirmed",
+            start.elapsed()
+        );
+        Ok(confirmed)
+    }
+
+    /// Loads the stats of the operations sent to the shadow network.
+    pub async fn load_stats(&mut self, network: &str) -> QueryResult<ETHStats> {
+        let start = Instant::now();
+        let params = self.load_params(network).await?;
+
+        metrics::histogram!("sql.shadow_ethereum.load_stats", start.elapsed());
+        Ok(ETHStats::from(&params))
+    }
+
+    pub async fn load_gas_price_limit(&mut self, network: &str) -> QueryResult<U256> {
+        let start = Instant::now();
+        let params = self.load_params(network).await?;
+        let gas_price_limit =
+            U256::try_from(params.gas_price_limit).expect("Negative gas limit value stored in DB");
+
+        metrics::histogram!("sql.shadow_ethereum.load_gas_price_limit", start.elapsed());
+        Ok(gas_price_limit)
+    }
+
+    /// Updates the gas price limit of the shadow network.
+    pub async fn update_gas_price(
+        &mut self,
+        network: &str,
+        gas_price_limit: U256,
+        average_gas_price: U256,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let gas_price_limit: i64 =
+            i64::try_from(gas_price_limit).expect("Can't convert U256 to i64");
+        let average_gas_price: i64 =
+            i64::try_from(average_gas_price).expect("Can't convert U256 to i64");
+
+        sqlx::query!(
+            "UPDATE shadow_eth_parameters
+            SET gas_price_limit = $2, average_gas_price = $3
+            WHERE network = $1",
+            network,
+            gas_price_limit,
+            average_gas_price
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.shadow_ethereum.update_gas_price", start.elapsed());
+        Ok(())
+    }
+
+    async fn load_params(&mut self, network: &str) -> QueryResult<ShadowETHParams> {
+        let params = sqlx::query_as!(
+            ShadowETHParams,
+            r#"
+            SELECT network, nonce, gas_price_limit, average_gas_price,
+                last_committed_block, last_verified_block, last_executed_block,
+                loaded_committed_block, loaded_verified_block, loaded_executed_block
+            FROM shadow_eth_parameters
+            WHERE network = $1
+            "#,
+            network
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .ok_or_else(|| format_err!("Shadow network {} is not initialized", network))?;
+        Ok(params)
+    }
+}
@@ -0,0 +1,43 @@
+// External imports
+use sqlx::{types::BigDecimal, FromRow};
+// Workspace imports
+// Local imports
+use crate::ethereum::records::ETHStats;
+
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StorageShadowETHOperation {
+    pub id: i64,
+    pub network: String,
+    pub op_id: i64,
+    pub op_type: String,
+    pub nonce: i64,
+    pub last_deadline_block: i64,
+    pub last_used_gas_price: BigDecimal,
+    pub raw_tx: Vec<u8>,
+    pub confirmed: bool,
+    pub final_hash: Option<Vec<u8>>,
+}
+
+#[derive(Debug, FromRow, PartialEq)]
+pub struct ShadowETHParams {
+    pub network: String,
+    pub nonce: i64,
+    pub gas_price_limit: i64,
+    pub average_gas_price: Option<i64>,
+    pub last_committed_block: i64,
+    pub last_verified_block: i64,
+    pub last_executed_block: i64,
+    pub loaded_committed_block: i64,
+    pub loaded_verified_block: i64,
+    pub loaded_executed_block: i64,
+}
+
+impl From<&ShadowETHParams> for ETHStats {
+    fn from(params: &ShadowETHParams) -> Self {
+        Self {
+            last_committed_block: params.last_committed_block,
+            last_verified_block: params.last_verified_block,
+            last_executed_block: params.last_executed_block,
+        }
+    }
+}
@@ -0,0 +1,133 @@
+// External imports
+use chrono::{Duration, Utc};
+// Workspace imports
+use zksync_types::{
+    activations::PrepaidActivation, tx::TimeRange, AccountId, Address, Nonce, TokenId, Transfer,
+};
+// Local imports
+use crate::{tests::db_test, QueryResult, StorageProcessor};
+
+fn activation(recipient: Address, nonce: u32) -> PrepaidActivation {
+    let fee_payment = Transfer::new(
+        AccountId(1),
+        Address::repeat_byte(1),
+        recipient,
+        TokenId(0),
+        0u64.into(),
+        10u64.into(),
+        Nonce(nonce),
+        TimeRange::default(),
+        None,
+    );
+    PrepaidActivation {
+        fee_payment,
+        eth_signature: None,
+    }
+}
+
+/// Checks that the activation of the account can be prepaid only once until it's used.
+#[db_test]
+async fn prepaid_activations(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let recipient = Address::repeat_byte(2);
+    let first = activation(recipient, 0);
+    let second = activation(recipient, 1);
+
+    assert!(storage
+        .activations_schema()
+        .get_prepaid_activation(recipient)
+        .await?
+        .is_none());
+    assert!(
+        storage
+            .activations_schema()
+            .store_prepaid_activation(&first, Utc::now())
+            .await?
+    );
+    assert!(
+        !storage
+            .activations_schema()
+            .store_prepaid_activation(&second, Utc::now())
+            .await?
+    );
+
+    let (stored, _) = storage
+        .activations_schema()
+        .get_prepaid_activation(recipient)
+        .await?
+        .expect("Activation should be stored");
+    assert_eq!(stored.fee_payment.nonce, Nonce(0));
+    assert_eq!(stored.payer(), Address::repeat_byte(1));
+
+    storage
+        .activations_schema()
+        .remove_prepaid_activation(recipient)
+        .await?;
+    assert!(storage
+        .activations_schema()
+        .get_prepaid_activation(recipient)
+        .await?
+        .is_none());
+    assert!(
+        storage
+            .activations_schema()
+            .store_prepaid_activation(&second, Utc::now())
+            .await?
+    );
+
+    Ok(())
+}
+
+/// Checks that the expired activations are not loaded, can be replaced and are removed.
+#[db_test]
+async fn prepaid_activations_expiry(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let expired_recipient = Address::repeat_byte(2);
+    let recipient = Address::repeat_byte(3);
+    let prepaid_at = Utc::now() - Duration::days(8);
+
+    assert!(
+        storage
+            .activations_schema()
+            .store_prepaid_activation(&activation(expired_recipient, 0), prepaid_at)
+            .await?
+    );
+    assert!(
+        storage
+            .activations_schema()
+            .store_prepaid_activation(&activation(recipient, 1), prepaid_at)
+            .await?
+    );
+    assert!(storage
+        .activations_schema()
+        .get_prepaid_activation(expired_recipient)
+        .await?
+        .is_none());
+
+    // The expired activation is replaced by the new one.
+    assert!(
+        storage
+            .activations_schema()
+            .store_prepaid_activation(&activation(recipient, 2), Utc::now())
+            .await?
+    );
+    let (stored, _) = storage
+        .activations_schema()
+        .get_prepaid_activation(recipient)
+        .await?
+        .expect("Activation should be stored");
+    assert_eq!(stored.fee_payment.nonce, Nonce(2));
+
+    assert_eq!(
+        storage
+            .activations_schema()
+            .remove_expired_prepaid_activations()
+            .await?,
+        1
+    );
+    assert!(storage
+        .activations_schema()
+        .get_prepaid_activation(recipient)
+        .await?
+        .is_some());
+
+    Ok(())
+}
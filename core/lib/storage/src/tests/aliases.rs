@@ -0,0 +1,62 @@
+// External imports
+// Workspace imports
+use zksync_types::Address;
+// Local imports
+use crate::{aliases::AliasRegistration, tests::db_test, QueryResult, StorageProcessor};
+
+/// Checks that the aliases are unique, that re-registration releases the previous alias
+/// of the account, and that the replayed requests are rejected.
+#[db_test]
+async fn register_aliases(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let alice = Address::repeat_byte(1);
+    let bob = Address::repeat_byte(2);
+
+    let registration = storage
+        .aliases_schema()
+        .register_alias("alice", alice, 1000)
+        .await?;
+    assert_eq!(registration, AliasRegistration::Registered);
+    let alias = storage
+        .aliases_schema()
+        .get_alias("alice")
+        .await?
+        .expect("Alias was not stored");
+    assert_eq!(alias.address, alice);
+
+    // The alias of another account can't be taken.
+    let registration = storage
+        .aliases_schema()
+        .register_alias("alice", bob, 1000)
+        .await?;
+    assert_eq!(registration, AliasRegistration::AliasTaken);
+
+    // The request can't be replayed.
+    let registration = storage
+        .aliases_schema()
+        .register_alias("alice-2", alice, 1000)
+        .await?;
+    assert_eq!(registration, AliasRegistration::OutdatedRequest);
+
+    // The new alias replaces the previous one.
+    let registration = storage
+        .aliases_schema()
+        .register_alias("alice-2", alice, 1001)
+        .await?;
+    assert_eq!(registration, AliasRegistration::Registered);
+    assert!(storage.aliases_schema().get_alias("alice").await?.is_none());
+    let alias = storage
+        .aliases_schema()
+        .get_account_alias(alice)
+        .await?
+        .expect("Alias was not stored");
+    assert_eq!(alias.alias, "alice-2");
+
+    // The released alias can be taken by another account.
+    let registration = storage
+        .aliases_schema()
+        .register_alias("alice", bob, 1000)
+        .await?;
+    assert_eq!(registration, AliasRegistration::Registered);
+
+    Ok(())
+}
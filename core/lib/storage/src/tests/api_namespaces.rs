@@ -0,0 +1,102 @@
+// External imports
+// Workspace imports
+use zksync_types::{
+    api_namespace::{ApiNamespace, ApiNamespaceLimits},
+    webhooks::WebhookEventType,
+};
+// Local imports
+use crate::{tests::db_test, QueryResult, StorageProcessor};
+
+/// Checks the lifecycle of the namespace: creation with a unique name and API key,
+/// lookup by the API key, update of the quotas and removal along with its webhooks.
+#[db_test]
+async fn api_namespaces(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let key_hash = ApiNamespace::api_key_hash("key");
+    let limits = ApiNamespaceLimits {
+        requests_per_minute: 100,
+        fee_markup_percent: Some(5),
+    };
+
+    let namespace = storage
+        .api_namespaces_schema()
+        .add_namespace("wallet", key_hash, limits)
+        .await?
+        .expect("Namespace was not created");
+    assert_eq!(namespace.requests_per_minute, 100);
+    assert_eq!(namespace.fee_markup_percent, Some(5));
+
+    // Both the name and the API key must be unique.
+    let other_key_hash = ApiNamespace::api_key_hash("other key");
+    assert!(storage
+        .api_namespaces_schema()
+        .add_namespace("wallet", other_key_hash, limits)
+        .await?
+        .is_none());
+    assert!(storage
+        .api_namespaces_schema()
+        .add_namespace("other", key_hash, limits)
+        .await?
+        .is_none());
+
+    assert_eq!(
+        storage
+            .api_namespaces_schema()
+            .get_namespace_by_key(key_hash)
+            .await?,
+        Some(namespace)
+    );
+    assert_eq!(
+        storage
+            .api_namespaces_schema()
+            .get_namespace_by_key(other_key_hash)
+            .await?,
+        None
+    );
+
+    let new_limits = ApiNamespaceLimits {
+        requests_per_minute: 0,
+        fee_markup_percent: None,
+    };
+    let updated = storage
+        .api_namespaces_schema()
+        .update_limits("wallet", new_limits)
+        .await?
+        .expect("Namespace was not updated");
+    assert_eq!(updated.requests_per_minute, 0);
+    assert_eq!(updated.fee_markup_percent, None);
+    assert!(storage
+        .api_namespaces_schema()
+        .update_limits("other", new_limits)
+        .await?
+        .is_none());
+
+    // Webhooks of the namespace are removed along with it.
+    storage
+        .webhooks_schema()
+        .add_subscription(
+            "http://127.0.0.1:8080",
+            WebhookEventType::BlockVerified,
+            None,
+            "secret",
+            Some("wallet"),
+        )
+        .await?;
+    assert!(
+        storage
+            .api_namespaces_schema()
+            .remove_namespace("wallet")
+            .await?
+    );
+    assert!(storage
+        .webhooks_schema()
+        .load_subscriptions()
+        .await?
+        .is_empty());
+    assert!(storage
+        .api_namespaces_schema()
+        .load_namespaces()
+        .await?
+        .is_empty());
+
+    Ok(())
+}
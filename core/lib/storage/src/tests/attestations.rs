@@ -0,0 +1,141 @@
+// External imports
+use chrono::{Duration, Utc};
+// Workspace imports
+use zksync_types::{
+    address_attestation::{AttestationChallenge, AttestationStatus},
+    AccountId, Address, H256,
+};
+// Local imports
+use crate::{tests::db_test, QueryResult, StorageProcessor};
+
+/// Checks that the challenges can be answered only once and only until they expire.
+#[db_test]
+async fn record_attestations(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let address = Address::repeat_byte(1);
+    let challenge = AttestationChallenge::new(
+        H256::repeat_byte(1),
+        address,
+        AccountId(1),
+        Utc::now() + Duration::minutes(10),
+        None,
+    );
+    let expired_challenge = AttestationChallenge::new(
+        H256::repeat_byte(2),
+        address,
+        AccountId(1),
+        Utc::now() - Duration::minutes(1),
+        None,
+    );
+    for challenge in &[&expired_challenge, &challenge] {
+        storage
+            .attestations_schema()
+            .store_challenge(challenge)
+            .await?;
+    }
+
+    let attestation = storage
+        .attestations_schema()
+        .get_attestation(challenge.challenge)
+        .await?
+        .expect("Challenge was not stored");
+    assert_eq!(attestation.status, AttestationStatus::Pending);
+    assert_eq!(attestation.account_id, AccountId(1));
+
+    let attestation = storage
+        .attestations_schema()
+        .record_verified(challenge.challenge)
+        .await?
+        .expect("Attestation was not recorded");
+    assert_eq!(attestation.status, AttestationStatus::Verified);
+    assert!(attestation.attested_at.is_some());
+
+    // The challenge can't be answered twice.
+    let attestation = storage
+        .attestations_schema()
+        .record_verified(challenge.challenge)
+        .await?;
+    assert!(attestation.is_none());
+
+    // The expired challenge can't be answered.
+    let attestation = storage
+        .attestations_schema()
+        .record_verified(expired_challenge.challenge)
+        .await?;
+    assert!(attestation.is_none());
+
+    assert_eq!(
+        storage
+            .attestations_schema()
+            .get_attestation(expired_challenge.challenge)
+            .await?
+            .expect("Challenge was not stored")
+            .status,
+        AttestationStatus::Expired
+    );
+
+    // Only the attested challenges are returned for the address.
+    let attestations = storage
+        .attestations_schema()
+        .address_attestations(address, 10)
+        .await?;
+    assert_eq!(attestations.len(), 1);
+    assert_eq!(attestations[0].challenge, challenge.challenge);
+    assert_eq!(attestations[0].status, AttestationStatus::Verified);
+
+    Ok(())
+}
+
+/// Checks that the pending challenges are counted, and that the expired ones are removed.
+#[db_test]
+async fn pending_challenges(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let address = Address::repeat_byte(1);
+    let challenge = |byte: u8, expires_in: Duration| {
+        AttestationChallenge::new(
+            H256::repeat_byte(byte),
+            address,
+            AccountId(1),
+            Utc::now() + expires_in,
+            None,
+        )
+    };
+    for challenge in &[
+        challenge(1, Duration::minutes(10)),
+        challenge(2, Duration::minutes(10)),
+        challenge(3, Duration::minutes(-1)),
+    ] {
+        storage
+            .attestations_schema()
+            .store_challenge(challenge)
+            .await?;
+    }
+    storage
+        .attestations_schema()
+        .record_verified(H256::repeat_byte(2))
+        .await?;
+
+    let mut schema = storage.attestations_schema();
+    assert_eq!(schema.count_pending_challenges(address).await?, 1);
+    assert_eq!(
+        schema
+            .count_pending_challenges(Address::repeat_byte(2))
+            .await?,
+        0
+    );
+
+    assert_eq!(schema.remove_expired_challenges().await?, 1);
+    assert!(schema
+        .get_attestation(H256::repeat_byte(3))
+        .await?
+        .is_none());
+    // The pending and the attested challenges are kept.
+    assert!(schema
+        .get_attestation(H256::repeat_byte(1))
+        .await?
+        .is_some());
+    assert!(schema
+        .get_attestation(H256::repeat_byte(2))
+        .await?
+        .is_some());
+
+    Ok(())
+}
@@ -154,3 +154,54 @@ async fn stored_accounts(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
 
     Ok(())
 }
+
+/// Checks that the account state is available starting from the block the account is created in.
+#[db_test]
+async fn account_state_at_block(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut rng = create_rng();
+
+    // Create several accounts in the first block and several more in the second one.
+    let (accounts_block_1, updates_block_1) = apply_random_updates(AccountMap::default(), &mut rng);
+    let (accounts_block_2, updates_block_2) =
+        apply_random_updates(accounts_block_1.clone(), &mut rng);
+    for (block_number, updates) in &[(1, updates_block_1), (2, updates_block_2)] {
+        let block_number = BlockNumber(*block_number);
+        BlockSchema(&mut storage)
+            .save_block(gen_sample_block(block_number, 100, Default::default()))
+            .await?;
+        StateSchema(&mut storage)
+            .commit_state_update(block_number, updates, 0)
+            .await?;
+    }
+
+    for account_id in accounts_block_2.keys() {
+        let created_in_block = if accounts_block_1.contains_key(account_id) {
+            1
+        } else {
+            2
+        };
+        let account = AccountSchema(&mut storage)
+            .last_committed_state_for_account(*account_id)
+            .await?;
+        assert!(account.is_some());
+
+        for block_number in 0..=2 {
+            let expected = if block_number < created_in_block {
+                None
+            } else {
+                account.clone()
+            };
+            assert_eq!(
+                AccountSchema(&mut storage)
+                    .account_state_at_block(*account_id, BlockNumber(block_number))
+                    .await?,
+                expected,
+                "account {} at block {}",
+                **account_id,
+                block_number
+            );
+        }
+    }
+
+    Ok(())
+}
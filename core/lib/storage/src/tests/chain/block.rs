@@ -2,8 +2,9 @@
 // Workspace imports
 use zksync_crypto::{convert::FeConvert, rand::XorShiftRng};
 use zksync_types::{
-    aggregated_operations::AggregatedActionType, helpers::apply_updates, tx::ChangePubKeyType,
-    AccountId, AccountMap, AccountUpdate, AccountUpdates, BlockNumber, TokenId, H256,
+    aggregated_operations::AggregatedActionType, finality::FinalizedBlocks, helpers::apply_updates,
+    tx::ChangePubKeyType, AccountId, AccountMap, AccountUpdate, AccountUpdates, BlockNumber,
+    TokenId, H256,
 };
 // Local imports
 use crate::{
@@ -478,6 +479,109 @@ async fn test_block_range(mut storage: StorageProcessor<'_>) -> QueryResult<()>
     Ok(())
 }
 
+/// Checks that the blocks reach the finality levels once the corresponding operations
+/// are confirmed on Ethereum.
+#[db_test]
+async fn test_finalized_blocks(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    /// Stores the aggregated operation for the block, and confirms it if required.
+    async fn store_action(
+        storage: &mut StorageProcessor<'_>,
+        block_number: BlockNumber,
+        action_type: AggregatedActionType,
+        confirm: bool,
+    ) -> QueryResult<()> {
+        OperationsSchema(storage)
+            .store_aggregated_action(gen_unique_aggregated_operation(
+                block_number,
+                action_type,
+                BLOCK_SIZE_CHUNKS,
+            ))
+            .await?;
+        let (id, op) = OperationsSchema(storage)
+            .get_aggregated_op_that_affects_block(action_type, block_number)
+            .await?
+            .unwrap();
+        if !confirm {
+            return Ok(());
+        }
+
+        let eth_tx_hash = dummy_ethereum_tx_hash(id);
+        let response = EthereumSchema(storage)
+            .save_new_eth_tx(
+                action_type,
+                Some((id, op)),
+                100,
+                100u32.into(),
+                Default::default(),
+            )
+            .await?;
+        EthereumSchema(storage)
+            .add_hash_entry(response.id, &eth_tx_hash)
+            .await?;
+        EthereumSchema(storage).confirm_eth_tx(&eth_tx_hash).await
+    }
+
+    // Required since we use `EthereumSchema` in this test.
+    EthereumSchema(&mut storage).initialize_eth_data().await?;
+    assert_eq!(
+        BlockSchema(&mut storage).load_finalized_blocks().await?,
+        FinalizedBlocks::default()
+    );
+
+    for block_number in 1..=4 {
+        let block_number = BlockNumber(block_number);
+        BlockSchema(&mut storage)
+            .save_block(gen_sample_block(
+                block_number,
+                BLOCK_SIZE_CHUNKS,
+                Default::default(),
+            ))
+            .await?;
+        // The commit of the last block is not confirmed yet.
+        store_action(
+            &mut storage,
+            block_number,
+            AggregatedActionType::CommitBlocks,
+            *block_number < 4,
+        )
+        .await?;
+    }
+    store_action(
+        &mut storage,
+        BlockNumber(1),
+        AggregatedActionType::PublishProofBlocksOnchain,
+        true,
+    )
+    .await?;
+    assert_eq!(
+        BlockSchema(&mut storage).load_finalized_blocks().await?,
+        FinalizedBlocks {
+            committed: BlockNumber(3),
+            verified: BlockNumber(1),
+            executed: BlockNumber(0),
+        }
+    );
+
+    // No proof is published for the block, but the executed blocks are verified anyway.
+    store_action(
+        &mut storage,
+        BlockNumber(2),
+        AggregatedActionType::ExecuteBlocks,
+        true,
+    )
+    .await?;
+    assert_eq!(
+        BlockSchema(&mut storage).load_finalized_blocks().await?,
+        FinalizedBlocks {
+            committed: BlockNumber(3),
+            verified: BlockNumber(2),
+            executed: BlockNumber(2),
+        }
+    );
+
+    Ok(())
+}
+
 /// Checks the correctness of the processing of committed unconfirmed transactions.
 #[db_test]
 async fn unconfirmed_transaction(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -930,6 +1034,37 @@ async fn test_operations_counter(mut storage: StorageProcessor<'_>) -> QueryResu
     Ok(())
 }
 
+/// Check that the priority operations processed by the saved blocks are counted correctly.
+#[db_test]
+async fn test_next_sealed_priority_op_serial_id(
+    mut storage: StorageProcessor<'_>,
+) -> QueryResult<()> {
+    assert_eq!(
+        BlockSchema(&mut storage)
+            .get_next_sealed_priority_op_serial_id()
+            .await?,
+        0
+    );
+
+    for (block_number, processed_priority_ops) in vec![(1, (0, 3)), (2, (3, 3)), (3, (3, 5))] {
+        let mut block = gen_sample_block(
+            BlockNumber(block_number),
+            BLOCK_SIZE_CHUNKS,
+            Default::default(),
+        );
+        block.processed_priority_ops = processed_priority_ops;
+        BlockSchema(&mut storage).save_block(block).await?;
+        assert_eq!(
+            BlockSchema(&mut storage)
+                .get_next_sealed_priority_op_serial_id()
+                .await?,
+            processed_priority_ops.1
+        );
+    }
+
+    Ok(())
+}
+
 /// Check that blocks are removed correctly.
 #[db_test]
 async fn test_remove_blocks(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -1027,3 +1162,81 @@ async fn test_remove_account_tree_cache(mut storage: StorageProcessor<'_>) -> Qu
 
     Ok(())
 }
+
+/// Check that only the latest account tree caches are kept.
+#[db_test]
+async fn test_remove_old_account_tree_caches(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    for block_number in 1..=5 {
+        BlockSchema(&mut storage)
+            .save_block(gen_sample_block(
+                BlockNumber(block_number),
+                BLOCK_SIZE_CHUNKS,
+                Default::default(),
+            ))
+            .await?;
+        BlockSchema(&mut storage)
+            .store_account_tree_cache(BlockNumber(block_number), serde_json::Value::default())
+            .await?;
+    }
+
+    // Nothing is removed while there are fewer caches than kept.
+    BlockSchema(&mut storage)
+        .remove_old_account_tree_caches(10)
+        .await?;
+    assert!(BlockSchema(&mut storage)
+        .get_account_tree_cache_block(BlockNumber(1))
+        .await?
+        .is_some());
+
+    BlockSchema(&mut storage)
+        .remove_old_account_tree_caches(2)
+        .await?;
+    for block_number in 1..=3 {
+        assert!(BlockSchema(&mut storage)
+            .get_account_tree_cache_block(BlockNumber(block_number))
+            .await?
+            .is_none());
+    }
+    for block_number in 4..=5 {
+        assert!(BlockSchema(&mut storage)
+            .get_account_tree_cache_block(BlockNumber(block_number))
+            .await?
+            .is_some());
+    }
+
+    Ok(())
+}
+
+/// Check that the block production halt keeps its first reason until it's lifted.
+#[db_test]
+async fn test_block_production_halt(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert_eq!(
+        BlockSchema(&mut storage)
+            .load_block_production_halt()
+            .await?,
+        None
+    );
+
+    BlockSchema(&mut storage)
+        .halt_block_production("state divergence")
+        .await?;
+    BlockSchema(&mut storage)
+        .halt_block_production("continuity violation")
+        .await?;
+    assert_eq!(
+        BlockSchema(&mut storage)
+            .load_block_production_halt()
+            .await?,
+        Some("state divergence".to_string())
+    );
+
+    BlockSchema(&mut storage).resume_block_production().await?;
+    assert_eq!(
+        BlockSchema(&mut storage)
+            .load_block_production_halt()
+            .await?,
+        None
+    );
+
+    Ok(())
+}
@@ -3,7 +3,7 @@ use zksync_crypto::rand::{Rng, SeedableRng, XorShiftRng};
 // Workspace imports
 use zksync_types::{
     mempool::SignedTxVariant,
-    tx::{ChangePubKey, Transfer, Withdraw},
+    tx::{ChangePubKey, Transfer, TxHash, Withdraw},
     AccountId, Address, BlockNumber, Nonce, SignedZkSyncTx, TokenId, ZkSyncTx,
 };
 // Local imports
@@ -11,8 +11,10 @@ use crate::test_data::gen_eth_sign_data;
 use crate::tests::db_test;
 use crate::{
     chain::{
+        block::BlockSchema,
         mempool::MempoolSchema,
         operations::{records::NewExecutedTransaction, OperationsSchema},
+        operations_ext::OperationsExtSchema,
     },
     QueryResult, StorageProcessor,
 };
@@ -155,6 +157,25 @@ async fn store_load(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     Ok(())
 }
 
+/// Checks that the transactions of the batches are counted one by one.
+#[db_test]
+async fn count_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert_eq!(MempoolSchema(&mut storage).count_txs().await?, 0);
+
+    let txs = gen_transfers(5);
+    for tx in &txs[..2] {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+    assert_eq!(MempoolSchema(&mut storage).count_txs().await?, 2);
+
+    MempoolSchema(&mut storage)
+        .insert_batch(&txs[2..], vec![])
+        .await?;
+    assert_eq!(MempoolSchema(&mut storage).count_txs().await?, 5);
+
+    Ok(())
+}
+
 /// Checks the save&load routine for mempool schema.
 #[db_test]
 async fn store_load_batch(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -215,6 +236,35 @@ async fn store_load_batch(mut storage: StorageProcessor<'_>) -> QueryResult<()>
     Ok(())
 }
 
+/// Checks that the stored txs can be looked up by their Ethereum-style hashes.
+#[db_test]
+async fn resolve_tx_hash_aliases(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = gen_transfers(3);
+    MempoolSchema(&mut storage).insert_tx(&txs[0]).await?;
+    MempoolSchema(&mut storage)
+        .insert_batch(&txs[1..], vec![])
+        .await?;
+
+    for tx in &txs {
+        let eth_hash = TxHash::from_slice(tx.eth_hash().as_bytes()).unwrap();
+        assert_eq!(
+            OperationsExtSchema(&mut storage)
+                .resolve_tx_hash(eth_hash)
+                .await?,
+            tx.hash()
+        );
+        // Canonical hash is resolved to itself.
+        assert_eq!(
+            OperationsExtSchema(&mut storage)
+                .resolve_tx_hash(tx.hash())
+                .await?,
+            tx.hash()
+        );
+    }
+
+    Ok(())
+}
+
 /// Checks that removed txs won't appear on the next load.
 #[db_test]
 async fn remove_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -348,6 +398,97 @@ async fn contains_and_get_tx(mut storage: StorageProcessor<'_>) -> QueryResult<(
     Ok(())
 }
 
+/// Checks that the expired txs are removed from the mempool and their expiry reasons are stored.
+#[db_test]
+async fn expire_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = gen_transfers(3);
+    for tx in &txs {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+    assert_eq!(MempoolSchema(&mut storage).last_expired_tx_id().await?, 0);
+
+    // No txs were added before the epoch, all of them were added before the next hour.
+    let now = chrono::Utc::now();
+    assert!(MempoolSchema(&mut storage)
+        .load_txs_created_before(chrono::MIN_DATETIME)
+        .await?
+        .is_empty());
+    let stale_txs = MempoolSchema(&mut storage)
+        .load_txs_created_before(now + chrono::Duration::hours(1))
+        .await?;
+    assert_eq!(stale_txs.len(), txs.len());
+
+    let expired = vec![
+        (txs[0].hash(), "expired".to_owned()),
+        (txs[1].hash(), "valid_until passed".to_owned()),
+    ];
+    MempoolSchema(&mut storage)
+        .expire_txs(&expired, now)
+        .await?;
+
+    // Only the not expired tx is left in the mempool.
+    let txs_from_db = MempoolSchema(&mut storage).load_txs().await?;
+    assert_eq!(txs_from_db.len(), 1);
+    assert_eq!(unwrap_tx(txs_from_db[0].clone()).hash(), txs[2].hash());
+
+    let expired_tx = MempoolSchema(&mut storage)
+        .get_expired_tx(txs[1].hash())
+        .await?
+        .expect("Expired tx is not stored");
+    assert_eq!(expired_tx.tx_hash, txs[1].hash().as_ref().to_vec());
+    assert_eq!(expired_tx.reason, "valid_until passed");
+    assert!(MempoolSchema(&mut storage)
+        .get_expired_tx(txs[2].hash())
+        .await?
+        .is_none());
+
+    let expired_txs = MempoolSchema(&mut storage).load_expired_txs(0, 10).await?;
+    assert_eq!(expired_txs.len(), 2);
+    let last_id = MempoolSchema(&mut storage).last_expired_tx_id().await?;
+    assert_eq!(last_id, expired_txs[1].id);
+    assert!(MempoolSchema(&mut storage)
+        .load_expired_txs(last_id, 10)
+        .await?
+        .is_empty());
+
+    // The resubmitted tx is not reported as expired.
+    MempoolSchema(&mut storage).insert_tx(&txs[0]).await?;
+    assert!(MempoolSchema(&mut storage)
+        .get_expired_tx(txs[0].hash())
+        .await?
+        .is_none());
+
+    Ok(())
+}
+
+/// Checks that only the `ChangePubKey` transactions of the account are counted by the rate limit.
+#[db_test]
+async fn change_pubkey_times(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = franklin_txs();
+    for tx in &txs {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+    let cpk_account = txs[3].account();
+    let since = chrono::Utc::now() - chrono::Duration::hours(1);
+
+    let times = MempoolSchema(&mut storage)
+        .load_change_pubkey_times(cpk_account, since)
+        .await?;
+    assert_eq!(times.len(), 1);
+    // Transfers are not counted.
+    let times = MempoolSchema(&mut storage)
+        .load_change_pubkey_times(txs[0].account(), since)
+        .await?;
+    assert!(times.is_empty());
+    // Only the transactions made within the window are counted.
+    let times = MempoolSchema(&mut storage)
+        .load_change_pubkey_times(cpk_account, chrono::Utc::now() + chrono::Duration::hours(1))
+        .await?;
+    assert!(times.is_empty());
+
+    Ok(())
+}
+
 /// Checks that returning executed txs to mempool works correctly.
 #[db_test]
 async fn test_return_executed_txs_to_mempool(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -400,3 +541,69 @@ async fn test_return_executed_txs_to_mempool(mut storage: StorageProcessor<'_>)
 
     Ok(())
 }
+
+/// Checks that txs of the reverted blocks are rejected and recorded.
+#[db_test]
+async fn test_reject_executed_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = gen_transfers(5);
+
+    for block_number in 1..=5 {
+        let tx_data = txs.get(block_number - 1).unwrap();
+        let executed_tx = NewExecutedTransaction {
+            block_number: block_number as i64,
+            tx_hash: tx_data.hash().as_ref().to_vec(),
+            tx: serde_json::to_value(&tx_data.tx).unwrap(),
+            operation: Default::default(),
+            from_account: Default::default(),
+            to_account: None,
+            success: true,
+            fail_reason: None,
+            block_index: None,
+            primary_account_address: Default::default(),
+            nonce: Default::default(),
+            created_at: chrono::Utc::now(),
+            eth_sign_data: None,
+            batch_id: None,
+        };
+
+        OperationsSchema(&mut storage)
+            .store_executed_tx(executed_tx)
+            .await?;
+    }
+
+    let revert_id = BlockSchema(&mut storage)
+        .record_block_revert(BlockNumber(3), BlockNumber(5), false)
+        .await?;
+    MempoolSchema(&mut storage)
+        .reject_executed_txs(BlockNumber(3), revert_id)
+        .await?;
+
+    // Rejected txs are not returned to the mempool.
+    assert!(MempoolSchema(&mut storage).load_txs().await?.is_empty());
+    for block_number in 1..=5 {
+        let tx_hash = txs.get(block_number - 1).unwrap().hash();
+        let tx_in_executed = OperationsSchema(&mut storage)
+            .get_executed_operation(tx_hash.as_ref())
+            .await?
+            .is_some();
+        let reverted_block = BlockSchema(&mut storage)
+            .get_reverted_tx_block(tx_hash.as_ref())
+            .await?;
+        if block_number <= 3 {
+            assert!(tx_in_executed);
+            assert_eq!(reverted_block, None);
+        } else {
+            assert!(!tx_in_executed);
+            assert_eq!(reverted_block, Some(BlockNumber(block_number as u32)));
+        }
+    }
+
+    let reverts = BlockSchema(&mut storage).load_block_reverts(10).await?;
+    assert_eq!(reverts.len(), 1);
+    assert_eq!(reverts[0].id, revert_id);
+    assert_eq!(reverts[0].last_correct_block, BlockNumber(3));
+    assert_eq!(reverts[0].last_reverted_block, BlockNumber(5));
+    assert!(!reverts[0].txs_returned_to_mempool);
+
+    Ok(())
+}
@@ -1,7 +1,14 @@
 // External imports
 use chrono::{Duration, Utc};
 // Workspace imports
-use zksync_types::{aggregated_operations::AggregatedActionType, BlockNumber};
+use zksync_types::{
+    aggregated_operations::{
+        AggregatedActionType, AggregatedOperation, BlocksCreateProofOperation, BlocksProofOperation,
+    },
+    tx::TxHash,
+    withdrawal_execution::{WithdrawalExecution, WithdrawalExecutionStatus},
+    BlockNumber, H256,
+};
 // Local imports
 use crate::{
     chain::{
@@ -11,7 +18,7 @@ use crate::{
             OperationsSchema,
         },
     },
-    test_data::gen_unique_aggregated_operation,
+    test_data::{gen_sample_block, gen_unique_aggregated_operation},
     tests::db_test,
     QueryResult, StorageProcessor,
 };
@@ -447,3 +454,107 @@ async fn test_remove_eth_unprocessed_aggregated_ops(
 
     Ok(())
 }
+
+/// Checks that the revert of the committed blocks removes the unpublished proof operations
+/// covering the reverted blocks, while the commit operations are kept for the remaining blocks.
+#[db_test]
+async fn test_remove_unpublished_proof_operations(
+    mut storage: StorageProcessor<'_>,
+) -> QueryResult<()> {
+    let blocks: Vec<_> = (1..=3)
+        .map(|block_number| gen_sample_block(BlockNumber(block_number), 100, Vec::new()))
+        .collect();
+    for block in &blocks {
+        OperationsSchema(&mut storage)
+            .store_aggregated_action(gen_unique_aggregated_operation(
+                block.block_number,
+                AggregatedActionType::CommitBlocks,
+                100,
+            ))
+            .await?;
+    }
+    // Blocks 1-3 are committed in turbo mode, the aggregated proof for them is not published yet.
+    OperationsSchema(&mut storage)
+        .store_aggregated_action(AggregatedOperation::CreateProofBlocks(
+            BlocksCreateProofOperation {
+                blocks: blocks.clone(),
+                proofs_to_pad: 0,
+            },
+        ))
+        .await?;
+    OperationsSchema(&mut storage)
+        .store_aggregated_action(AggregatedOperation::PublishProofBlocksOnchain(
+            BlocksProofOperation {
+                blocks,
+                proof: Default::default(),
+            },
+        ))
+        .await?;
+
+    OperationsSchema(&mut storage)
+        .remove_aggregate_operations_and_bindings(BlockNumber(2))
+        .await?;
+
+    for (action_type, expected_block) in &[
+        (AggregatedActionType::CommitBlocks, BlockNumber(2)),
+        (AggregatedActionType::CreateProofBlocks, BlockNumber(0)),
+        (
+            AggregatedActionType::PublishProofBlocksOnchain,
+            BlockNumber(0),
+        ),
+    ] {
+        let last_block = OperationsSchema(&mut storage)
+            .get_last_affected_block_by_aggregated_action(*action_type)
+            .await?;
+        assert_eq!(last_block, *expected_block, "{:?}", action_type);
+    }
+
+    Ok(())
+}
+
+/// Checks that the withdrawal executions are stored and take precedence when looking up
+/// the L1 transaction of the withdrawal.
+#[db_test]
+async fn withdrawal_executions(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let withdrawal_hash = TxHash::from_slice(&[1u8; 32]).unwrap();
+    assert!(OperationsSchema(&mut storage)
+        .withdrawal_execution(&withdrawal_hash)
+        .await?
+        .is_none());
+
+    let mut execution = WithdrawalExecution {
+        withdrawal_hash,
+        block_number: BlockNumber(1),
+        eth_tx_hash: H256::repeat_byte(2),
+        status: WithdrawalExecutionStatus::Pending,
+    };
+    OperationsSchema(&mut storage)
+        .store_withdrawal_executions(&[execution.clone()])
+        .await?;
+    assert_eq!(
+        OperationsSchema(&mut storage)
+            .withdrawal_execution(&withdrawal_hash)
+            .await?,
+        Some(execution.clone())
+    );
+
+    // Receipt of the same block may be processed again.
+    execution.status = WithdrawalExecutionStatus::Completed;
+    OperationsSchema(&mut storage)
+        .store_withdrawal_executions(&[execution.clone()])
+        .await?;
+    assert_eq!(
+        OperationsSchema(&mut storage)
+            .withdrawal_execution(&withdrawal_hash)
+            .await?,
+        Some(execution)
+    );
+    assert_eq!(
+        OperationsSchema(&mut storage)
+            .eth_tx_for_withdrawal(&withdrawal_hash)
+            .await?,
+        Some(H256::repeat_byte(2))
+    );
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 // External imports
 // Workspace imports
 use zksync_types::aggregated_operations::AggregatedActionType;
-use zksync_types::{helpers::apply_updates, AccountMap, BlockNumber};
+use zksync_types::{helpers::apply_updates, tx::TxHash, AccountMap, BlockNumber};
 // Local imports
 use super::block::apply_random_updates;
 use crate::{
@@ -265,3 +265,53 @@ async fn test_remove_account_updates(mut storage: StorageProcessor<'_>) -> Query
     assert_eq!(diff2, diff3);
     Ok(())
 }
+
+/// Checks that the account updates made by the transactions are loaded correctly.
+#[db_test]
+async fn tx_account_updates(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut rng = create_rng();
+
+    let (_, updates) = apply_random_updates(AccountMap::default(), &mut rng);
+    assert!(updates.len() > 2);
+    let first_tx = TxHash::from_slice(&[1; 32]).unwrap();
+    let second_tx = TxHash::from_slice(&[2; 32]).unwrap();
+
+    // Updates are stored in two parts, as it happens with the pending block.
+    let split = updates.len() / 2;
+    StateSchema(&mut storage)
+        .commit_state_update(BlockNumber(1), &updates[..split], 0)
+        .await?;
+    StateSchema(&mut storage)
+        .store_tx_account_updates(BlockNumber(1), &[(first_tx, 0..split)])
+        .await?;
+    StateSchema(&mut storage)
+        .commit_state_update(BlockNumber(1), &updates[split..], split)
+        .await?;
+    StateSchema(&mut storage)
+        .store_tx_account_updates(BlockNumber(1), &[(second_tx, split + 1..updates.len())])
+        .await?;
+
+    assert_eq!(
+        StateSchema(&mut storage)
+            .load_tx_account_updates(&first_tx)
+            .await?,
+        Some((BlockNumber(1), updates[..split].to_vec()))
+    );
+    assert_eq!(
+        StateSchema(&mut storage)
+            .load_tx_account_updates(&second_tx)
+            .await?,
+        Some((BlockNumber(1), updates[split + 1..].to_vec()))
+    );
+
+    // Updates of the reverted blocks are removed.
+    StateSchema(&mut storage)
+        .remove_tx_account_updates(BlockNumber(0))
+        .await?;
+    assert!(StateSchema(&mut storage)
+        .load_tx_account_updates(&first_tx)
+        .await?
+        .is_none());
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+// External imports
+use chrono::Duration;
+use sqlx::types::BigDecimal;
+// Workspace imports
+// Local imports
+use crate::{tests::db_test, QueryResult, StorageProcessor};
+
+/// Checks that the counters are incremented and reset once expired.
+#[db_test]
+async fn shared_counters(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let period = Duration::hours(1);
+    assert_eq!(
+        storage.counters_schema().load("counter").await?,
+        BigDecimal::from(0)
+    );
+
+    let value = storage
+        .counters_schema()
+        .increment("counter", BigDecimal::from(2), period)
+        .await?;
+    assert_eq!(value, BigDecimal::from(2));
+    let value = storage
+        .counters_schema()
+        .increment("counter", BigDecimal::from(3), period)
+        .await?;
+    assert_eq!(value, BigDecimal::from(5));
+    assert_eq!(
+        storage.counters_schema().load("counter").await?,
+        BigDecimal::from(5)
+    );
+    assert_eq!(
+        storage.counters_schema().load("other_counter").await?,
+        BigDecimal::from(0)
+    );
+
+    // Expired counter is started anew.
+    storage
+        .counters_schema()
+        .increment("expired", BigDecimal::from(7), -period)
+        .await?;
+    assert_eq!(
+        storage.counters_schema().load("expired").await?,
+        BigDecimal::from(0)
+    );
+    let value = storage
+        .counters_schema()
+        .increment("expired", BigDecimal::from(1), period)
+        .await?;
+    assert_eq!(value, BigDecimal::from(1));
+
+    Ok(())
+}
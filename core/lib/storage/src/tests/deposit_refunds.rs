@@ -0,0 +1,189 @@
+// External imports
+use num::BigUint;
+// Workspace imports
+use zksync_types::{
+    deposit_refund::{DepositFailureReason, DepositRefundStatus},
+    Address, Deposit, TokenId, H256,
+};
+// Local imports
+use crate::{tests::db_test, QueryResult, StorageProcessor};
+
+/// Checks that the deposits are flagged once and the refunds move only between the expected statuses.
+#[db_test]
+async fn deposit_refunds_flow(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let deposit = Deposit {
+        from: Address::repeat_byte(1),
+        token: TokenId(2),
+        amount: BigUint::from(10u64.pow(18)),
+        to: Address::zero(),
+    };
+    let eth_hash = H256::repeat_byte(3);
+
+    let flagged = storage
+        .deposit_refunds_schema()
+        .flag_deposit(
+            5,
+            eth_hash,
+            &deposit,
+            DepositFailureReason::InvalidRecipient,
+        )
+        .await?;
+    assert!(flagged);
+    // The same priority operation is not flagged twice.
+    let flagged = storage
+        .deposit_refunds_schema()
+        .flag_deposit(
+            5,
+            eth_hash,
+            &deposit,
+            DepositFailureReason::InvalidRecipient,
+        )
+        .await?;
+    assert!(!flagged);
+
+    let refund = storage
+        .deposit_refunds_schema()
+        .get_refund(5)
+        .await?
+        .expect("Deposit was not flagged");
+    assert_eq!(refund.from, deposit.from);
+    assert_eq!(refund.amount, deposit.amount);
+    assert_eq!(refund.reason, DepositFailureReason::InvalidRecipient);
+    assert_eq!(refund.status, DepositRefundStatus::Flagged);
+
+    // The refund can't be sent before it's requested.
+    let updated = storage
+        .deposit_refunds_schema()
+        .update_status(
+            5,
+            &[DepositRefundStatus::Requested],
+            DepositRefundStatus::Sent,
+            Some(H256::repeat_byte(4)),
+        )
+        .await?;
+    assert!(!updated);
+
+    let updated = storage
+        .deposit_refunds_schema()
+        .update_status(
+            5,
+            &[DepositRefundStatus::Flagged, DepositRefundStatus::Failed],
+            DepositRefundStatus::Requested,
+            None,
+        )
+        .await?;
+    assert!(updated);
+    let requested = storage
+        .deposit_refunds_schema()
+        .load_refunds(DepositRefundStatus::Requested, 10)
+        .await?;
+    assert_eq!(requested.len(), 1);
+    assert_eq!(requested[0].serial_id, 5);
+
+    for (expected, status, hash) in vec![
+        (
+            DepositRefundStatus::Requested,
+            DepositRefundStatus::Sent,
+            Some(H256::repeat_byte(4)),
+        ),
+        (
+            DepositRefundStatus::Sent,
+            DepositRefundStatus::Confirmed,
+            None,
+        ),
+    ] {
+        let updated = storage
+            .deposit_refunds_schema()
+            .update_status(5, &[expected], status, hash)
+            .await?;
+        assert!(updated);
+    }
+
+    // The refund transaction hash is kept once the refund is confirmed.
+    let refund = storage
+        .deposit_refunds_schema()
+        .get_refund(5)
+        .await?
+        .unwrap();
+    assert_eq!(refund.status, DepositRefundStatus::Confirmed);
+    assert_eq!(refund.refund_tx_hash, Some(H256::repeat_byte(4)));
+
+    Ok(())
+}
+
+/// Checks that the refund transactions are stored with their nonces before being sent.
+#[db_test]
+async fn deposit_refund_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let deposit = Deposit {
+        from: Address::repeat_byte(1),
+        token: TokenId(0),
+        amount: BigUint::from(100u32),
+        to: Address::zero(),
+    };
+    assert_eq!(
+        storage.deposit_refunds_schema().next_refund_nonce().await?,
+        None
+    );
+
+    for serial_id in 1..=2 {
+        storage
+            .deposit_refunds_schema()
+            .flag_deposit(
+                serial_id,
+                H256::repeat_byte(serial_id as u8),
+                &deposit,
+                DepositFailureReason::InvalidRecipient,
+            )
+            .await?;
+    }
+    // Only the requested refunds can be sent.
+    let stored = storage
+        .deposit_refunds_schema()
+        .store_refund_tx(1, H256::repeat_byte(0xaa), 7, &[1, 2, 3])
+        .await?;
+    assert!(!stored);
+
+    storage
+        .deposit_refunds_schema()
+        .update_status(
+            1,
+            &[DepositRefundStatus::Flagged],
+            DepositRefundStatus::Requested,
+            None,
+        )
+        .await?;
+    let stored = storage
+        .deposit_refunds_schema()
+        .store_refund_tx(1, H256::repeat_byte(0xaa), 7, &[1, 2, 3])
+        .await?;
+    assert!(stored);
+
+    let refund = storage
+        .deposit_refunds_schema()
+        .get_refund(1)
+        .await?
+        .unwrap();
+    assert_eq!(refund.status, DepositRefundStatus::Sent);
+    assert_eq!(refund.refund_tx_hash, Some(H256::repeat_byte(0xaa)));
+    assert_eq!(refund.refund_tx_nonce, Some(7));
+    assert_eq!(
+        storage
+            .deposit_refunds_schema()
+            .get_refund_raw_tx(1)
+            .await?,
+        Some(vec![1, 2, 3])
+    );
+    assert_eq!(
+        storage
+            .deposit_refunds_schema()
+            .get_refund_raw_tx(2)
+            .await?,
+        None
+    );
+    assert_eq!(
+        storage.deposit_refunds_schema().next_refund_nonce().await?,
+        Some(8)
+    );
+
+    Ok(())
+}
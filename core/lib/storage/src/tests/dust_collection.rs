@@ -0,0 +1,141 @@
+// External imports
+use chrono::{Duration, Utc};
+use num::BigUint;
+// Workspace imports
+use zksync_types::{
+    aggregated_operations::AggregatedActionType, tx::TxHash, AccountId, AccountUpdate, Address,
+    BlockNumber, Nonce, PubKeyHash, TokenId,
+};
+// Local imports
+use crate::{
+    test_data::{gen_sample_block, gen_unique_aggregated_operation},
+    tests::db_test,
+    QueryResult, StorageProcessor,
+};
+
+fn create_account(id: u32, address: Address, balance: u32) -> Vec<(AccountId, AccountUpdate)> {
+    vec![
+        (
+            AccountId(id),
+            AccountUpdate::Create {
+                address,
+                nonce: Nonce(0),
+            },
+        ),
+        (
+            AccountId(id),
+            AccountUpdate::UpdateBalance {
+                old_nonce: Nonce(0),
+                new_nonce: Nonce(0),
+                balance_update: (TokenId(0), BigUint::from(0u32), BigUint::from(balance)),
+            },
+        ),
+    ]
+}
+
+/// Checks that only the inactive accounts without a signing key which were not opted out
+/// are the candidates for the dust collection.
+#[db_test]
+async fn dust_candidates(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let collectable = Address::repeat_byte(1);
+    let opted_out = Address::repeat_byte(2);
+    let activated = Address::repeat_byte(3);
+    let empty = Address::repeat_byte(4);
+
+    let mut updates = Vec::new();
+    updates.extend(create_account(1, collectable, 10));
+    updates.extend(create_account(2, opted_out, 10));
+    updates.extend(create_account(3, activated, 10));
+    updates.push((
+        AccountId(3),
+        AccountUpdate::ChangePubKeyHash {
+            old_pub_key_hash: PubKeyHash::zero(),
+            new_pub_key_hash: PubKeyHash { data: [1u8; 20] },
+            old_nonce: Nonce(0),
+            new_nonce: Nonce(1),
+        },
+    ));
+    updates.extend(create_account(4, empty, 0));
+
+    // The block timestamp is zero, so all the accounts are inactive.
+    storage
+        .chain()
+        .block_schema()
+        .save_block(gen_sample_block(BlockNumber(1), 100, Default::default()))
+        .await?;
+    storage
+        .chain()
+        .state_schema()
+        .commit_state_update(BlockNumber(1), &updates, 0)
+        .await?;
+    storage
+        .chain()
+        .operations_schema()
+        .store_aggregated_action(gen_unique_aggregated_operation(
+            BlockNumber(1),
+            AggregatedActionType::ExecuteBlocks,
+            100,
+        ))
+        .await?;
+    storage
+        .chain()
+        .state_schema()
+        .apply_state_update(BlockNumber(1))
+        .await?;
+
+    assert!(
+        storage
+            .dust_collection_schema()
+            .store_opt_out(opted_out)
+            .await?
+    );
+    assert!(
+        !storage
+            .dust_collection_schema()
+            .store_opt_out(opted_out)
+            .await?
+    );
+    assert!(storage
+        .dust_collection_schema()
+        .get_opt_out(opted_out)
+        .await?
+        .is_some());
+    assert!(storage
+        .dust_collection_schema()
+        .get_opt_out(collectable)
+        .await?
+        .is_none());
+
+    let inactive_since = Utc::now() - Duration::days(1);
+    let candidates = storage
+        .dust_collection_schema()
+        .load_candidates(inactive_since, AccountId(0), 10)
+        .await?;
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].account_id, AccountId(1));
+    assert_eq!(candidates[0].address, collectable);
+    assert_eq!(
+        candidates[0].balances,
+        vec![(TokenId(0), BigUint::from(10u32))]
+    );
+
+    // Accounts before the cursor are skipped.
+    assert!(storage
+        .dust_collection_schema()
+        .load_candidates(inactive_since, AccountId(2), 10)
+        .await?
+        .is_empty());
+
+    // Collected account is not returned until its `ForcedExit` is verified.
+    storage
+        .dust_collection_schema()
+        .record_collection(AccountId(1), &[TxHash::default()])
+        .await?;
+    assert!(storage
+        .dust_collection_schema()
+        .load_candidates(inactive_since, AccountId(0), 10)
+        .await?
+        .is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+// External imports
+// Workspace imports
+use zksync_types::{
+    l1_message::L1Message, Address, Deposit, PriorityOp, TokenId, ZkSyncPriorityOp, H256,
+};
+// Local imports
+use crate::{tests::db_test, QueryResult, StorageProcessor};
+
+fn priority_op(serial_id: u64, eth_block: u64) -> PriorityOp {
+    PriorityOp {
+        serial_id,
+        data: ZkSyncPriorityOp::Deposit(Deposit {
+            from: Default::default(),
+            token: TokenId(0),
+            amount: 100u32.into(),
+            to: Default::default(),
+        }),
+        deadline_block: 0,
+        eth_hash: H256::from_low_u64_be(serial_id),
+        eth_block,
+    }
+}
+
+fn l1_message(serial_id: u64, eth_block: u64) -> L1Message {
+    L1Message {
+        serial_id,
+        sender: Address::repeat_byte(1),
+        recipient: Address::repeat_byte(2),
+        payload: vec![serial_id as u8],
+        eth_hash: H256::from_low_u64_be(serial_id),
+        eth_block,
+    }
+}
+
+/// Checks that the priority operations and the last processed block are stored and loaded.
+#[db_test]
+async fn eth_watch_priority_ops(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert_eq!(
+        storage
+            .eth_watch_schema()
+            .load_last_processed_block()
+            .await?,
+        None
+    );
+
+    storage
+        .eth_watch_schema()
+        .store_events(&[priority_op(0, 10), priority_op(1, 20)], &[], 25)
+        .await?;
+    // Operations that are already stored are ignored.
+    storage
+        .eth_watch_schema()
+        .store_events(&[priority_op(1, 20), priority_op(2, 30)], &[], 35)
+        .await?;
+
+    assert_eq!(
+        storage
+            .eth_watch_schema()
+            .load_last_processed_block()
+            .await?,
+        Some(35)
+    );
+
+    let ops = storage.eth_watch_schema().load_priority_ops(15).await?;
+    let serial_ids: Vec<_> = ops.iter().map(|op| op.serial_id).collect();
+    assert_eq!(serial_ids, vec![1, 2]);
+    assert_eq!(ops[0].eth_hash, H256::from_low_u64_be(1));
+    assert_eq!(ops[0].eth_block, 20);
+
+    Ok(())
+}
+
+/// Checks that the received L1 messages are stored along with the processed block and loaded
+/// starting from the requested serial ID.
+#[db_test]
+async fn eth_watch_l1_messages(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    storage
+        .eth_watch_schema()
+        .store_events(&[], &[l1_message(0, 10), l1_message(1, 20)], 25)
+        .await?;
+    // Messages that are already stored are ignored.
+    storage
+        .eth_watch_schema()
+        .store_events(
+            &[priority_op(0, 30)],
+            &[l1_message(1, 20), l1_message(2, 30)],
+            35,
+        )
+        .await?;
+
+    assert_eq!(
+        storage
+            .eth_watch_schema()
+            .load_last_processed_block()
+            .await?,
+        Some(35)
+    );
+    assert_eq!(
+        storage.eth_watch_schema().load_l1_messages(1).await?,
+        vec![l1_message(1, 20), l1_message(2, 30)]
+    );
+    assert!(storage
+        .eth_watch_schema()
+        .load_l1_messages(3)
+        .await?
+        .is_empty());
+
+    Ok(())
+}
@@ -367,6 +367,85 @@ async fn ethereum_unprocessed(mut storage: StorageProcessor<'_>) -> QueryResult<
     Ok(())
 }
 
+/// Checks that the operations are counted as unprocessed until they're sent to Ethereum,
+/// and as unconfirmed until they're confirmed.
+#[db_test]
+async fn ethereum_operation_counts(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    EthereumSchema(&mut storage).initialize_eth_data().await?;
+    assert_eq!(
+        EthereumSchema(&mut storage)
+            .count_unprocessed_operations()
+            .await?,
+        0
+    );
+    assert_eq!(
+        EthereumSchema(&mut storage)
+            .count_unconfirmed_operations()
+            .await?,
+        0
+    );
+
+    let block_number = BlockNumber(1);
+    OperationsSchema(&mut storage)
+        .store_aggregated_action(gen_unique_aggregated_operation(
+            block_number,
+            AggregatedActionType::CommitBlocks,
+            BLOCK_SIZE_CHUNKS,
+        ))
+        .await?;
+    let op = OperationsSchema(&mut storage)
+        .get_aggregated_op_that_affects_block(AggregatedActionType::CommitBlocks, block_number)
+        .await?;
+    assert_eq!(
+        EthereumSchema(&mut storage)
+            .count_unprocessed_operations()
+            .await?,
+        1
+    );
+
+    // Send the operation to Ethereum.
+    let params = EthereumTxParams::new("CommitBlocks".into(), op.clone());
+    let response = EthereumSchema(&mut storage)
+        .save_new_eth_tx(
+            AggregatedActionType::CommitBlocks,
+            params.op.clone(),
+            params.deadline_block as i64,
+            params.gas_price.clone(),
+            params.raw_tx.clone(),
+        )
+        .await?;
+    EthereumSchema(&mut storage)
+        .add_hash_entry(response.id, &params.hash)
+        .await?;
+    EthereumSchema(&mut storage)
+        .remove_unprocessed_operations(vec![op.unwrap().0])
+        .await?;
+    assert_eq!(
+        EthereumSchema(&mut storage)
+            .count_unprocessed_operations()
+            .await?,
+        0
+    );
+    assert_eq!(
+        EthereumSchema(&mut storage)
+            .count_unconfirmed_operations()
+            .await?,
+        1
+    );
+
+    EthereumSchema(&mut storage)
+        .confirm_eth_tx(&params.hash)
+        .await?;
+    assert_eq!(
+        EthereumSchema(&mut storage)
+            .count_unconfirmed_operations()
+            .await?,
+        0
+    );
+
+    Ok(())
+}
+
 /// Simple test for store/load of (average) gas price.
 #[db_test]
 async fn ethereum_gas_update(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -0,0 +1,103 @@
+// External imports
+// Workspace imports
+use zksync_types::{event::ChainEvent, BlockNumber};
+// Local imports
+use crate::{tests::db_test, QueryResult, StorageProcessor};
+
+/// Checks that the stored events can be consumed starting from any offset in the order of storing.
+#[db_test]
+async fn event_log_offsets(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert_eq!(storage.event_schema().get_last_offset().await?, None);
+
+    let events = (1..=3)
+        .map(|block| ChainEvent::BlockSealed {
+            block_number: BlockNumber(block),
+        })
+        .collect::<Vec<_>>();
+    storage.event_schema().store_events(&events).await?;
+    storage
+        .event_schema()
+        .store_events(&[ChainEvent::BlockCommitted {
+            block_number: BlockNumber(1),
+        }])
+        .await?;
+
+    let last_offset = storage
+        .event_schema()
+        .get_last_offset()
+        .await?
+        .expect("Events are not stored");
+
+    let all_events = storage.event_schema().load_events(0, 10).await?;
+    assert_eq!(all_events.len(), 4);
+    assert_eq!(all_events.last().unwrap().offset, last_offset);
+    assert!(all_events
+        .windows(2)
+        .all(|pair| pair[0].offset < pair[1].offset));
+    assert_eq!(all_events[0].event.event_type(), "blockSealed");
+    assert_eq!(all_events[3].event.event_type(), "blockCommitted");
+
+    // Consumer resumes from the offset following the last processed one.
+    let resumed = storage
+        .event_schema()
+        .load_events(all_events[1].offset + 1, 1)
+        .await?;
+    assert_eq!(resumed.len(), 1);
+    assert_eq!(resumed[0].offset, all_events[2].offset);
+    assert_eq!(resumed[0].event.block_number(), BlockNumber(3));
+
+    assert!(storage
+        .event_schema()
+        .load_events(last_offset + 1, 10)
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+/// Checks that the malformed stored event is reported as an error.
+#[db_test]
+async fn event_log_malformed_event(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    sqlx::query(
+        "INSERT INTO event_log ( block_number, event_type, event_data, created_at )
+        VALUES ( 1, 'unknown', '{}', now() )",
+    )
+    .execute(storage.conn())
+    .await?;
+
+    assert!(storage.event_schema().load_events(0, 10).await.is_err());
+    Ok(())
+}
+
+/// Checks that the consumers' progress is stored independently.
+#[db_test]
+async fn event_log_consumers(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert_eq!(
+        storage.event_schema().get_consumer_offset("first").await?,
+        None
+    );
+
+    storage
+        .event_schema()
+        .update_consumer_offset("first", 10)
+        .await?;
+    storage
+        .event_schema()
+        .update_consumer_offset("second", 5)
+        .await?;
+    storage
+        .event_schema()
+        .update_consumer_offset("first", 12)
+        .await?;
+
+    assert_eq!(
+        storage.event_schema().get_consumer_offset("first").await?,
+        Some(12)
+    );
+    assert_eq!(
+        storage.event_schema().get_consumer_offset("second").await?,
+        Some(5)
+    );
+
+    Ok(())
+}
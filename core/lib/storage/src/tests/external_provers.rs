@@ -0,0 +1,157 @@
+// External imports
+use chrono::Duration;
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{
+    prover::{ProverJobType, ProverLeaseStatus},
+    Address, BlockNumber,
+};
+// Local imports
+use crate::external_provers::LeaseTerms;
+use crate::tests::db_test;
+use crate::{QueryResult, StorageProcessor};
+
+fn lease_terms() -> LeaseTerms {
+    LeaseTerms {
+        collateral: BigDecimal::from(10),
+        single_proof_payment: BigDecimal::from(2),
+        aggregated_proof_payment: BigDecimal::from(5),
+        duration: Duration::minutes(10),
+    }
+}
+
+/// Checks that the jobs are leased against the prover stake and the payments
+/// and the slashed collateral are accounted.
+#[db_test]
+async fn external_prover_leases(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let prover_id = storage
+        .external_provers_schema()
+        .register_prover(
+            "prover",
+            "api_key",
+            Address::repeat_byte(1),
+            BigDecimal::from(15),
+        )
+        .await?;
+    assert!(storage
+        .external_provers_schema()
+        .prover_by_api_key("wrong_key")
+        .await?
+        .is_none());
+    let prover = storage
+        .external_provers_schema()
+        .prover_by_api_key("api_key")
+        .await?
+        .expect("Prover should be registered");
+    assert_eq!(prover.id, prover_id);
+
+    // There are no jobs to lease.
+    assert!(storage
+        .external_provers_schema()
+        .lease_job(&prover, &lease_terms())
+        .await?
+        .is_none());
+
+    for block in 1..=2 {
+        storage
+            .prover_schema()
+            .add_prover_job_to_job_queue(
+                BlockNumber(block),
+                BlockNumber(block),
+                Default::default(),
+                1,
+                ProverJobType::SingleProof,
+            )
+            .await?;
+    }
+
+    let (job, lease) = storage
+        .external_provers_schema()
+        .lease_job(&prover, &lease_terms())
+        .await?
+        .expect("Job should be leased");
+    assert_eq!(job.first_block, BlockNumber(1));
+    assert_eq!(lease.job_id, job.job_id);
+    assert_eq!((lease.first_block, lease.last_block), (1, 1));
+    assert_eq!(lease.job_type, ProverJobType::SingleProof.to_string());
+    assert_eq!(lease.payment, BigDecimal::from(2));
+    assert_eq!(lease.status, ProverLeaseStatus::Leased.to_string());
+
+    // The free stake doesn't cover the collateral of the second lease.
+    assert!(storage
+        .external_provers_schema()
+        .lease_job(&prover, &lease_terms())
+        .await?
+        .is_none());
+
+    assert!(
+        storage
+            .external_provers_schema()
+            .complete_lease(lease.id)
+            .await?
+    );
+    assert!(
+        !storage
+            .external_provers_schema()
+            .complete_lease(lease.id)
+            .await?
+    );
+    let prover = storage
+        .external_provers_schema()
+        .load_prover(prover_id)
+        .await?
+        .unwrap();
+    assert_eq!(prover.locked_stake, BigDecimal::from(0));
+    assert_eq!(prover.earned_amount, BigDecimal::from(2));
+
+    // The overdue lease is slashed and its job is returned to the queue.
+    let terms = LeaseTerms {
+        duration: -Duration::minutes(1),
+        ..lease_terms()
+    };
+    let (_, lease) = storage
+        .external_provers_schema()
+        .lease_job(&prover, &terms)
+        .await?
+        .expect("Job should be leased");
+    let expired = storage.external_provers_schema().expired_leases().await?;
+    assert_eq!(expired, vec![lease.id]);
+    assert!(
+        storage
+            .external_provers_schema()
+            .slash_lease(lease.id, "Lease expired")
+            .await?
+    );
+    assert!(storage
+        .external_provers_schema()
+        .expired_leases()
+        .await?
+        .is_empty());
+
+    let prover = storage
+        .external_provers_schema()
+        .load_prover(prover_id)
+        .await?
+        .unwrap();
+    assert_eq!(prover.stake, BigDecimal::from(5));
+    assert_eq!(prover.locked_stake, BigDecimal::from(0));
+    assert_eq!(prover.slashed_amount, BigDecimal::from(10));
+    assert_eq!(
+        storage
+            .prover_schema()
+            .get_idle_prover_job_from_job_queue()
+            .await?
+            .map(|job| job.first_block),
+        Some(BlockNumber(2))
+    );
+
+    let leases = storage
+        .external_provers_schema()
+        .load_leases(prover_id, 10)
+        .await?;
+    assert_eq!(leases.len(), 2);
+    assert_eq!(leases[0].status, ProverLeaseStatus::Slashed.to_string());
+    assert_eq!(leases[1].status, ProverLeaseStatus::Completed.to_string());
+
+    Ok(())
+}
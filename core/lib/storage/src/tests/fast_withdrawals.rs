@@ -0,0 +1,176 @@
+// External imports
+use chrono::Utc;
+// Workspace imports
+use zksync_types::{
+    fast_withdrawals::FastWithdrawalIntent,
+    tx::{TimeRange, TxHash},
+    AccountId, Address, Nonce, TokenId, Withdraw,
+};
+// Local imports
+use crate::{tests::db_test, QueryResult, StorageProcessor};
+
+fn intent(nonce: u32, token: TokenId, valid_until: u64) -> FastWithdrawalIntent {
+    let withdraw = Withdraw::new(
+        AccountId(1),
+        Address::repeat_byte(1),
+        Address::repeat_byte(2),
+        token,
+        100u64.into(),
+        1u64.into(),
+        Nonce(nonce),
+        TimeRange::new(0, valid_until),
+        None,
+    );
+    FastWithdrawalIntent {
+        withdraw,
+        payout: 95u64.into(),
+        eth_signature: None,
+    }
+}
+
+/// Checks the lifecycle of the intent: it's listed until being claimed by a liquidity provider,
+/// and can be claimed only once.
+#[db_test]
+async fn fast_withdrawal_intents(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let now = Utc::now();
+    let first = intent(0, TokenId(0), 1_000);
+    let second = intent(1, TokenId(1), 2_000);
+
+    let first_id = storage
+        .fast_withdrawals_schema()
+        .store_intent(&first, now)
+        .await?
+        .expect("Intent should be stored");
+    let second_id = storage
+        .fast_withdrawals_schema()
+        .store_intent(&second, now)
+        .await?
+        .expect("Intent should be stored");
+    // The same withdrawal can't be sold twice.
+    assert!(storage
+        .fast_withdrawals_schema()
+        .store_intent(&first, now)
+        .await?
+        .is_none());
+
+    let stored = storage
+        .fast_withdrawals_schema()
+        .get_intent(first_id)
+        .await?
+        .unwrap();
+    assert_eq!(stored.intent.payout, first.payout);
+    assert_eq!(stored.intent.withdraw.nonce, first.withdraw.nonce);
+
+    let pending = storage
+        .fast_withdrawals_schema()
+        .load_pending_intents(None, 500, 10)
+        .await?;
+    assert_eq!(pending.len(), 2);
+    // Expired intents are not listed.
+    let pending = storage
+        .fast_withdrawals_schema()
+        .load_pending_intents(None, 1_500, 10)
+        .await?;
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].id, second_id);
+    let pending = storage
+        .fast_withdrawals_schema()
+        .load_pending_intents(Some(TokenId(0)), 500, 10)
+        .await?;
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].id, first_id);
+
+    let transfer_hash = TxHash::from_slice(&[7; 32]).unwrap();
+    assert!(
+        storage
+            .fast_withdrawals_schema()
+            .claim_intent(first_id, transfer_hash, now)
+            .await?
+    );
+    assert!(
+        !storage
+            .fast_withdrawals_schema()
+            .claim_intent(first_id, transfer_hash, now)
+            .await?
+    );
+    let stored = storage
+        .fast_withdrawals_schema()
+        .get_intent(first_id)
+        .await?
+        .unwrap();
+    assert_eq!(stored.fulfilled_by, Some(transfer_hash));
+    let pending = storage
+        .fast_withdrawals_schema()
+        .load_pending_intents(None, 500, 10)
+        .await?;
+    assert_eq!(pending.len(), 1);
+
+    storage
+        .fast_withdrawals_schema()
+        .release_intent(first_id)
+        .await?;
+    storage
+        .fast_withdrawals_schema()
+        .remove_intent(second_id)
+        .await?;
+    let pending = storage
+        .fast_withdrawals_schema()
+        .load_pending_intents(None, 500, 10)
+        .await?;
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].id, first_id);
+    assert!(pending[0].fulfilled_by.is_none());
+
+    Ok(())
+}
+
+/// Checks that only the unfulfilled expired intents are removed.
+#[db_test]
+async fn expired_fast_withdrawal_intents(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let now = Utc::now();
+    let expired_id = storage
+        .fast_withdrawals_schema()
+        .store_intent(&intent(0, TokenId(0), 1_000), now)
+        .await?
+        .expect("Intent should be stored");
+    let fulfilled_id = storage
+        .fast_withdrawals_schema()
+        .store_intent(&intent(1, TokenId(0), 1_000), now)
+        .await?
+        .expect("Intent should be stored");
+    let pending_id = storage
+        .fast_withdrawals_schema()
+        .store_intent(&intent(2, TokenId(0), 2_000), now)
+        .await?
+        .expect("Intent should be stored");
+    storage
+        .fast_withdrawals_schema()
+        .claim_intent(fulfilled_id, TxHash::from_slice(&[7; 32]).unwrap(), now)
+        .await?;
+
+    assert_eq!(
+        storage
+            .fast_withdrawals_schema()
+            .remove_expired_intents(1_500)
+            .await?,
+        1
+    );
+    assert!(storage
+        .fast_withdrawals_schema()
+        .get_intent(expired_id)
+        .await?
+        .is_none());
+    assert!(storage
+        .fast_withdrawals_schema()
+        .get_intent(fulfilled_id)
+        .await?
+        .is_some());
+    let pending = storage
+        .fast_withdrawals_schema()
+        .get_intent(pending_id)
+        .await?
+        .expect("Intent should be stored");
+    assert_eq!(pending.valid_until, 2_000);
+
+    Ok(())
+}
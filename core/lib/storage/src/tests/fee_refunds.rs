@@ -0,0 +1,129 @@
+// External imports
+use num::BigUint;
+// Workspace imports
+use zksync_types::{fee_refund::FeeRefundStatus, tx::TxHash, Address, TokenId};
+// Local imports
+use crate::{tests::db_test, QueryResult, StorageProcessor};
+
+/// Checks that the refunds are stored once per transaction and go through the statuses.
+#[db_test]
+async fn fee_refunds_flow(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let address = Address::repeat_byte(1);
+    let signed_fee = BigUint::from(1050u32);
+    let actual_fee = BigUint::from(1000u32);
+    let first_hash = TxHash::from_slice(&[1; 32]).unwrap();
+    let second_hash = TxHash::from_slice(&[2; 32]).unwrap();
+    let refund_hash = TxHash::from_slice(&[3; 32]).unwrap();
+
+    for tx_hash in &[first_hash, second_hash] {
+        let stored = storage
+            .fee_refunds_schema()
+            .store_refund(tx_hash, address, TokenId(0), &signed_fee, &actual_fee)
+            .await?;
+        assert!(stored);
+    }
+    // The refund of the resubmitted transaction is not recorded twice.
+    let stored = storage
+        .fee_refunds_schema()
+        .store_refund(&first_hash, address, TokenId(0), &signed_fee, &signed_fee)
+        .await?;
+    assert!(!stored);
+
+    let pending = storage
+        .fee_refunds_schema()
+        .load_oldest_refunds(FeeRefundStatus::Pending, 10)
+        .await?;
+    assert_eq!(pending.len(), 2);
+    assert_eq!(pending[0].tx_hash, first_hash);
+    assert_eq!(pending[0].actual_fee, actual_fee);
+    assert_eq!(pending[0].refund, BigUint::from(0u32));
+    assert_eq!(pending[1].tx_hash, second_hash);
+
+    let refund = BigUint::from(48u32);
+    // The refund is sent only once its transfer is recorded.
+    assert!(
+        !storage
+            .fee_refunds_schema()
+            .mark_sent(pending[0].id)
+            .await?
+    );
+    assert!(
+        storage
+            .fee_refunds_schema()
+            .record_refund_tx(pending[0].id, &refund, &refund_hash)
+            .await?
+    );
+    let recorded = storage
+        .fee_refunds_schema()
+        .get_refund(&first_hash)
+        .await?
+        .unwrap();
+    assert_eq!(recorded.status, FeeRefundStatus::Pending);
+    assert_eq!(recorded.refund_tx_hash, Some(refund_hash));
+    assert!(
+        storage
+            .fee_refunds_schema()
+            .mark_sent(pending[0].id)
+            .await?
+    );
+    // Only the pending refunds are sent.
+    assert!(
+        !storage
+            .fee_refunds_schema()
+            .mark_sent(pending[0].id)
+            .await?
+    );
+    assert!(
+        !storage
+            .fee_refunds_schema()
+            .record_refund_tx(pending[0].id, &refund, &refund_hash)
+            .await?
+    );
+    assert!(
+        storage
+            .fee_refunds_schema()
+            .update_status(pending[0].id, FeeRefundStatus::Refunded, None)
+            .await?
+    );
+    assert!(
+        storage
+            .fee_refunds_schema()
+            .update_status(
+                pending[1].id,
+                FeeRefundStatus::Cancelled,
+                Some("Transaction failed".to_owned())
+            )
+            .await?
+    );
+    // The outcome is recorded only once.
+    assert!(
+        !storage
+            .fee_refunds_schema()
+            .update_status(pending[1].id, FeeRefundStatus::Refunded, None)
+            .await?
+    );
+
+    let refunded = storage
+        .fee_refunds_schema()
+        .get_refund(&first_hash)
+        .await?
+        .unwrap();
+    assert_eq!(refunded.status, FeeRefundStatus::Refunded);
+    assert_eq!(refunded.refund_tx_hash, Some(refund_hash));
+    assert_eq!(refunded.effective_fee(), BigUint::from(1002u32));
+
+    let refunds = storage.fee_refunds_schema().load_refunds(None, 10).await?;
+    assert_eq!(refunds.len(), 2);
+    assert_eq!(refunds[0].status, FeeRefundStatus::Cancelled);
+    assert_eq!(
+        refunds[0].fail_reason.as_deref(),
+        Some("Transaction failed")
+    );
+    assert!(storage
+        .fee_refunds_schema()
+        .load_oldest_refunds(FeeRefundStatus::Pending, 10)
+        .await?
+        .is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+// External imports
+use num::BigUint;
+// Workspace imports
+use zksync_types::{
+    fee_sweep::{FeeSweepKind, FeeSweepStatus},
+    tx::TxHash,
+    Address, TokenId,
+};
+// Local imports
+use crate::{tests::db_test, QueryResult, StorageProcessor};
+
+/// Checks that the sweeps are stored and their outcome is recorded only once.
+#[db_test]
+async fn fee_sweeps_flow(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let treasury = Address::repeat_byte(1);
+    let amount = BigUint::from(10u64.pow(18));
+    let tx_hash = TxHash::from_slice(&[2; 32]).unwrap();
+
+    let first_id = storage
+        .fee_sweeps_schema()
+        .store_sweep(
+            TokenId(0),
+            &amount,
+            treasury,
+            FeeSweepKind::Transfer,
+            &tx_hash,
+        )
+        .await?;
+    let second_id = storage
+        .fee_sweeps_schema()
+        .store_sweep(
+            TokenId(1),
+            &amount,
+            treasury,
+            FeeSweepKind::Withdraw,
+            &tx_hash,
+        )
+        .await?;
+
+    let sweeps = storage
+        .fee_sweeps_schema()
+        .load_sweeps(Some(FeeSweepStatus::Sent), 10)
+        .await?;
+    assert_eq!(sweeps.len(), 2);
+    // Newest sweeps go first.
+    assert_eq!(sweeps[0].id, second_id);
+    assert_eq!(sweeps[0].token, TokenId(1));
+    assert_eq!(sweeps[0].kind, FeeSweepKind::Withdraw);
+    assert_eq!(sweeps[1].id, first_id);
+    assert_eq!(sweeps[1].amount, amount);
+    assert_eq!(sweeps[1].treasury, treasury);
+    assert_eq!(sweeps[1].tx_hash, tx_hash);
+
+    let updated = storage
+        .fee_sweeps_schema()
+        .update_status(first_id, FeeSweepStatus::Executed, None)
+        .await?;
+    assert!(updated);
+    let updated = storage
+        .fee_sweeps_schema()
+        .update_status(
+            second_id,
+            FeeSweepStatus::Failed,
+            Some("Not enough balance".to_owned()),
+        )
+        .await?;
+    assert!(updated);
+    // The outcome is recorded only once.
+    let updated = storage
+        .fee_sweeps_schema()
+        .update_status(first_id, FeeSweepStatus::Failed, None)
+        .await?;
+    assert!(!updated);
+
+    assert!(storage
+        .fee_sweeps_schema()
+        .load_sweeps(Some(FeeSweepStatus::Sent), 10)
+        .await?
+        .is_empty());
+    let sweeps = storage.fee_sweeps_schema().load_sweeps(None, 10).await?;
+    assert_eq!(sweeps.len(), 2);
+    assert_eq!(sweeps[0].status, FeeSweepStatus::Failed);
+    assert_eq!(sweeps[0].fail_reason.as_deref(), Some("Not enough balance"));
+    assert_eq!(sweeps[1].status, FeeSweepStatus::Executed);
+
+    Ok(())
+}
@@ -11,7 +11,9 @@ use chrono::{Duration, Timelike, Utc};
 use num::{BigUint, FromPrimitive};
 use zksync_basic_types::Address;
 use zksync_types::{
-    forced_exit_requests::{ForcedExitRequest, SaveForcedExitRequestQuery},
+    forced_exit_requests::{
+        ForcedExitL2Payment, ForcedExitPaymentStatus, ForcedExitRequest, SaveForcedExitRequestQuery,
+    },
     tx::TxHash,
 };
 
@@ -201,3 +203,99 @@ async fn delete_old_requests(mut storage: StorageProcessor<'_>) -> QueryResult<(
 
     Ok(())
 }
+
+#[db_test]
+async fn store_l2_payment(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let tx_hash = TxHash::from_str(
+        "sync-tx:796018689b3e323894f44fb0093856ec3832908c626dea357a9bd1b25f9d11bf",
+    )
+    .unwrap();
+    let refund_tx_hash = TxHash::from_str(
+        "sync-tx:1111111111111111111111111111111111111111111111111111111111111111",
+    )
+    .unwrap();
+
+    assert!(ForcedExitRequestsSchema(&mut storage)
+        .get_l2_payment(tx_hash)
+        .await?
+        .is_none());
+
+    let payment = ForcedExitL2Payment {
+        tx_hash,
+        from: Address::from_str("c0f97CC918C9d6fA4E9fc6be61a6a06589D199b2").unwrap(),
+        amount: BigUint::from_str("30000000000000012").unwrap(),
+        request_id: Some(12),
+        status: ForcedExitPaymentStatus::Refunded,
+        refund_tx_hash: Some(refund_tx_hash),
+        created_at: Utc::now().with_nanosecond(0).unwrap(),
+    };
+    assert!(
+        ForcedExitRequestsSchema(&mut storage)
+            .store_l2_payment(&payment)
+            .await?
+    );
+
+    // The same payment can not be stored twice.
+    let duplicate = ForcedExitL2Payment {
+        status: ForcedExitPaymentStatus::Fulfilled,
+        refund_tx_hash: None,
+        ..payment.clone()
+    };
+    assert!(
+        !ForcedExitRequestsSchema(&mut storage)
+            .store_l2_payment(&duplicate)
+            .await?
+    );
+
+    let stored = ForcedExitRequestsSchema(&mut storage)
+        .get_l2_payment(tx_hash)
+        .await?;
+    assert_eq!(stored, Some(payment));
+
+    Ok(())
+}
+
+#[db_test]
+async fn update_l2_payment(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let tx_hash = TxHash::from_str(
+        "sync-tx:796018689b3e323894f44fb0093856ec3832908c626dea357a9bd1b25f9d11bf",
+    )
+    .unwrap();
+    let payment = ForcedExitL2Payment {
+        tx_hash,
+        from: Address::from_str("c0f97CC918C9d6fA4E9fc6be61a6a06589D199b2").unwrap(),
+        amount: BigUint::from_str("30000000000000012").unwrap(),
+        request_id: Some(12),
+        status: ForcedExitPaymentStatus::Pending,
+        refund_tx_hash: None,
+        created_at: Utc::now().with_nanosecond(0).unwrap(),
+    };
+    ForcedExitRequestsSchema(&mut storage)
+        .store_l2_payment(&payment)
+        .await?;
+
+    ForcedExitRequestsSchema(&mut storage)
+        .update_l2_payment_status(tx_hash, ForcedExitPaymentStatus::Fulfilled)
+        .await?;
+    let stored = ForcedExitRequestsSchema(&mut storage)
+        .get_l2_payment(tx_hash)
+        .await?
+        .unwrap();
+    assert_eq!(stored.status, ForcedExitPaymentStatus::Fulfilled);
+
+    // The removed payment can be stored again.
+    ForcedExitRequestsSchema(&mut storage)
+        .remove_l2_payment(tx_hash)
+        .await?;
+    assert!(ForcedExitRequestsSchema(&mut storage)
+        .get_l2_payment(tx_hash)
+        .await?
+        .is_none());
+    assert!(
+        ForcedExitRequestsSchema(&mut storage)
+            .store_l2_payment(&payment)
+            .await?
+    );
+
+    Ok(())
+}
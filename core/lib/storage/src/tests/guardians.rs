@@ -0,0 +1,107 @@
+// External imports
+use chrono::{Duration, Utc};
+// Workspace imports
+use zksync_types::{
+    account::PubKeyHash,
+    guardians::{GuardianRegistration, GuardianSet},
+    tx::{PackedEthSignature, SignatureDomain},
+    AccountId, Address, Nonce, H256,
+};
+// Local imports
+use crate::{tests::db_test, QueryResult, StorageProcessor};
+
+fn registration(version: u32) -> GuardianRegistration {
+    let owner_key = H256::repeat_byte(7);
+    let account_id = AccountId(1);
+    let domain = SignatureDomain::new(1, Address::repeat_byte(9));
+    let guardian_set = GuardianSet {
+        version,
+        guardians: vec![Address::repeat_byte(1), Address::repeat_byte(2)],
+        quorum: 2,
+        timelock: 3600,
+    };
+    GuardianRegistration {
+        account_id,
+        address: PackedEthSignature::address_from_private_key(&owner_key).unwrap(),
+        owner_signature: PackedEthSignature::sign(
+            &owner_key,
+            guardian_set.hash(account_id, &domain).as_bytes(),
+        )
+        .unwrap(),
+        guardian_set,
+        registered_at: Utc::now(),
+    }
+}
+
+/// Checks that the guardian sets are replaced by the newer versions only, that the recoveries
+/// can't restart the timelock, and that the registration of the new set cancels them.
+#[db_test]
+async fn guardians_and_recoveries(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let account_id = AccountId(1);
+    let new_pk_hash = PubKeyHash::from_bytes(&[3; 20]).unwrap();
+
+    assert!(
+        storage
+            .guardians_schema()
+            .register_guardians(&registration(1))
+            .await?
+    );
+    let registered = storage
+        .guardians_schema()
+        .get_guardians(account_id)
+        .await?
+        .expect("Guardians were not stored");
+    assert_eq!(registered.guardian_set, registration(1).guardian_set);
+
+    let recovery = storage
+        .guardians_schema()
+        .initiate_recovery(
+            account_id,
+            &new_pk_hash,
+            Nonce(0),
+            &[],
+            Duration::seconds(3600),
+        )
+        .await?;
+    assert_eq!(
+        recovery.executable_at - recovery.initiated_at,
+        Duration::seconds(3600)
+    );
+    // Initiating the same recovery again keeps the original timelock.
+    let repeated = storage
+        .guardians_schema()
+        .initiate_recovery(account_id, &new_pk_hash, Nonce(0), &[], Duration::zero())
+        .await?;
+    assert_eq!(repeated.executable_at, recovery.executable_at);
+
+    // The registration can't be replayed.
+    assert!(
+        !storage
+            .guardians_schema()
+            .register_guardians(&registration(1))
+            .await?
+    );
+    assert_eq!(
+        storage
+            .guardians_schema()
+            .load_recoveries(account_id)
+            .await?
+            .len(),
+        1
+    );
+
+    // The new set cancels the recoveries.
+    assert!(
+        storage
+            .guardians_schema()
+            .register_guardians(&registration(2))
+            .await?
+    );
+    assert!(storage
+        .guardians_schema()
+        .get_recovery(account_id, &new_pk_hash, Nonce(0))
+        .await?
+        .is_none());
+
+    Ok(())
+}
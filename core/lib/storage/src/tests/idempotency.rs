@@ -0,0 +1,125 @@
+// External imports
+use chrono::{Duration, Utc};
+// Workspace imports
+use zksync_types::tx::TxHash;
+// Local imports
+use crate::{tests::db_test, QueryResult, StorageProcessor};
+
+/// Checks that the idempotency key is reserved once, stores the submission result
+/// and expires after the configured period.
+#[db_test]
+async fn idempotency_keys(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let request_hashes = vec![TxHash::from_slice(&[1; 32]).unwrap()];
+    let tx_hashes = vec![TxHash::from_slice(&[2; 32]).unwrap()];
+    let valid_since = Utc::now() - Duration::hours(1);
+
+    let submission = storage
+        .idempotency_schema()
+        .reserve_key("payment-1", &request_hashes, valid_since, valid_since)
+        .await?;
+    assert!(submission.is_none());
+
+    // The key is reserved until the submission is completed.
+    let submission = storage
+        .idempotency_schema()
+        .reserve_key("payment-1", &request_hashes, valid_since, valid_since)
+        .await?
+        .unwrap();
+    assert_eq!(submission.request_hashes, request_hashes);
+    assert!(submission.tx_hashes.is_none());
+
+    storage
+        .idempotency_schema()
+        .complete_submission("payment-1", &tx_hashes)
+        .await?;
+    let submission = storage
+        .idempotency_schema()
+        .reserve_key("payment-1", &request_hashes, valid_since, valid_since)
+        .await?
+        .unwrap();
+    assert_eq!(submission.tx_hashes, Some(tx_hashes));
+
+    // Keys of the completed submissions are not released.
+    storage
+        .idempotency_schema()
+        .release_key("payment-1")
+        .await?;
+    assert!(storage
+        .idempotency_schema()
+        .reserve_key("payment-1", &request_hashes, valid_since, valid_since)
+        .await?
+        .is_some());
+
+    // Keys of the failed submissions can be reused.
+    storage
+        .idempotency_schema()
+        .reserve_key("payment-2", &request_hashes, valid_since, valid_since)
+        .await?;
+    storage
+        .idempotency_schema()
+        .release_key("payment-2")
+        .await?;
+    assert!(storage
+        .idempotency_schema()
+        .reserve_key("payment-2", &request_hashes, valid_since, valid_since)
+        .await?
+        .is_none());
+
+    // Expired keys are removed.
+    sqlx::query("UPDATE tx_idempotency_keys SET created_at = $1 WHERE idempotency_key = $2")
+        .bind(Utc::now() - Duration::hours(2))
+        .bind("payment-1")
+        .execute(storage.conn())
+        .await?;
+    assert!(storage
+        .idempotency_schema()
+        .reserve_key("payment-1", &request_hashes, valid_since, valid_since)
+        .await?
+        .is_none());
+
+    Ok(())
+}
+
+/// Checks that the keys of the abandoned submissions are released after the timeout,
+/// while the keys of the completed ones are kept until they expire.
+#[db_test]
+async fn abandoned_submissions(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let request_hashes = vec![TxHash::from_slice(&[1; 32]).unwrap()];
+    let tx_hashes = vec![TxHash::from_slice(&[2; 32]).unwrap()];
+    let valid_since = Utc::now() - Duration::hours(1);
+    let pending_valid_since = Utc::now() - Duration::minutes(1);
+
+    for key in &["pending", "completed"] {
+        storage
+            .idempotency_schema()
+            .reserve_key(key, &request_hashes, valid_since, pending_valid_since)
+            .await?;
+    }
+    storage
+        .idempotency_schema()
+        .complete_submission("completed", &tx_hashes)
+        .await?;
+    sqlx::query("UPDATE tx_idempotency_keys SET created_at = $1")
+        .bind(Utc::now() - Duration::minutes(2))
+        .execute(storage.conn())
+        .await?;
+
+    assert!(storage
+        .idempotency_schema()
+        .reserve_key("pending", &request_hashes, valid_since, pending_valid_since)
+        .await?
+        .is_none());
+    let submission = storage
+        .idempotency_schema()
+        .reserve_key(
+            "completed",
+            &request_hashes,
+            valid_since,
+            pending_valid_since,
+        )
+        .await?
+        .unwrap();
+    assert_eq!(submission.tx_hashes, Some(tx_hashes));
+
+    Ok(())
+}
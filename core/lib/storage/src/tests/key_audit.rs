@@ -0,0 +1,67 @@
+// External imports
+use chrono::{Duration, Utc};
+// Workspace imports
+use zksync_types::key_audit::{KeyUsage, OperatorKey};
+// Local imports
+use crate::{tests::db_test, QueryResult, StorageProcessor};
+
+/// Checks that the key usage is appended to the audit log and can be exported.
+#[db_test]
+async fn key_usage_audit(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let usages = vec![
+        KeyUsage::new(OperatorKey::OperatorEth, "CommitBlocks", b"commit", Ok(())),
+        KeyUsage::new(
+            OperatorKey::ForcedExitSender,
+            "ForcedExit",
+            b"forced exit",
+            Ok(()),
+        ),
+        KeyUsage::new(
+            OperatorKey::OperatorEth,
+            "ExecuteBlocks",
+            b"execute",
+            Err("Signer is unavailable".to_owned()),
+        ),
+    ];
+    for usage in &usages {
+        storage.key_audit_schema().record_key_usage(usage).await?;
+    }
+
+    let from = Utc::now() - Duration::hours(1);
+    let to = Utc::now() + Duration::hours(1);
+    let records = storage
+        .key_audit_schema()
+        .load_key_usage(from, to, None, None, 10)
+        .await?;
+    assert_eq!(
+        records
+            .iter()
+            .map(|record| record.usage.clone())
+            .collect::<Vec<_>>(),
+        usages
+    );
+
+    // Filter by the key.
+    let records = storage
+        .key_audit_schema()
+        .load_key_usage(from, to, Some(OperatorKey::OperatorEth), None, 10)
+        .await?;
+    assert_eq!(records.len(), 2);
+    assert!(!records[1].usage.success);
+
+    // Pagination.
+    let page = storage
+        .key_audit_schema()
+        .load_key_usage(from, to, None, Some(records[0].id), 1)
+        .await?;
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].usage, usages[1]);
+
+    // Entries can't be changed or removed.
+    let result = sqlx::query("DELETE FROM key_usage_audit")
+        .execute(storage.conn())
+        .await;
+    assert!(result.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+// External imports
+// Workspace imports
+use zksync_types::{l1_message::L1Message, Address, BlockNumber, H256};
+// Local imports
+use crate::{tests::db_test, QueryResult, StorageProcessor};
+
+fn message(serial_id: u64, recipient: Address) -> L1Message {
+    L1Message {
+        serial_id,
+        sender: Address::repeat_byte(0xff),
+        recipient,
+        payload: vec![serial_id as u8; 4],
+        eth_hash: H256::repeat_byte(serial_id as u8),
+        eth_block: 10 + serial_id,
+    }
+}
+
+/// Checks that the messages are stored once and loaded per recipient,
+/// and that the messages of the reverted blocks are removed.
+#[db_test]
+async fn store_and_load_messages(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let alice = Address::repeat_byte(1);
+    let bob = Address::repeat_byte(2);
+    assert_eq!(storage.l1_messages_schema().next_message_id().await?, 0);
+
+    let first_block = vec![message(0, alice), message(1, bob)];
+    let second_block = vec![message(2, alice)];
+    storage
+        .l1_messages_schema()
+        .store_messages(BlockNumber(1), &first_block)
+        .await?;
+    storage
+        .l1_messages_schema()
+        .store_messages(BlockNumber(2), &second_block)
+        .await?;
+    // Messages are delivered only once.
+    storage
+        .l1_messages_schema()
+        .store_messages(BlockNumber(3), &second_block)
+        .await?;
+    assert_eq!(storage.l1_messages_schema().next_message_id().await?, 3);
+
+    let messages = storage
+        .l1_messages_schema()
+        .load_account_messages(alice, 0, 10)
+        .await?;
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].message, first_block[0]);
+    assert_eq!(messages[0].block_number, BlockNumber(1));
+    assert_eq!(messages[1].message, second_block[0]);
+    assert_eq!(messages[1].block_number, BlockNumber(2));
+
+    // Messages are loaded starting from the requested one.
+    let messages = storage
+        .l1_messages_schema()
+        .load_account_messages(alice, 1, 10)
+        .await?;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].message.serial_id, 2);
+    let messages = storage
+        .l1_messages_schema()
+        .load_account_messages(alice, 0, 1)
+        .await?;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].message.serial_id, 0);
+
+    storage
+        .l1_messages_schema()
+        .remove_messages(BlockNumber(1))
+        .await?;
+    assert_eq!(storage.l1_messages_schema().next_message_id().await?, 2);
+    let messages = storage
+        .l1_messages_schema()
+        .load_account_messages(alice, 0, 10)
+        .await?;
+    assert_eq!(messages.len(), 1);
+
+    Ok(())
+}
@@ -0,0 +1,160 @@
+// Local imports
+use crate::{tests::db_test, QueryResult, StorageProcessor};
+
+/// Lock ID which doesn't interfere with the one used by the server.
+const TEST_LOCK_ID: i64 = 0x7e57;
+
+/// Checks that the leadership is held by a single connection until it's released.
+#[db_test]
+async fn leadership_is_exclusive(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut standby = StorageProcessor::establish_connection().await?;
+
+    assert!(
+        storage
+            .leader_election_schema()
+            .try_acquire_leadership(TEST_LOCK_ID)
+            .await?
+    );
+    let token = storage
+        .leader_election_schema()
+        .issue_fencing_token(TEST_LOCK_ID)
+        .await?;
+    assert!(
+        storage
+            .leader_election_schema()
+            .check_leadership(token)
+            .await?
+    );
+    // The lock is held by the leader connection only.
+    assert!(
+        !standby
+            .leader_election_schema()
+            .check_leadership(token)
+            .await?
+    );
+    assert!(
+        !standby
+            .leader_election_schema()
+            .try_acquire_leadership(TEST_LOCK_ID)
+            .await?
+    );
+
+    // Once the leader gives up, the standby takes over.
+    assert!(
+        storage
+            .leader_election_schema()
+            .release_leadership(TEST_LOCK_ID)
+            .await?
+    );
+    assert!(
+        !storage
+            .leader_election_schema()
+            .release_leadership(TEST_LOCK_ID)
+            .await?
+    );
+    assert!(
+        standby
+            .leader_election_schema()
+            .try_acquire_leadership(TEST_LOCK_ID)
+            .await?
+    );
+    assert!(
+        standby
+            .leader_election_schema()
+            .release_leadership(TEST_LOCK_ID)
+            .await?
+    );
+
+    Ok(())
+}
+
+/// Checks that the writes fenced with the token of the previous leadership fail once
+/// the leadership is taken over.
+#[db_test]
+async fn fencing_tokens(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut standby = StorageProcessor::establish_connection().await?;
+
+    assert!(
+        storage
+            .leader_election_schema()
+            .try_acquire_leadership(TEST_LOCK_ID)
+            .await?
+    );
+    let old_token = storage
+        .leader_election_schema()
+        .issue_fencing_token(TEST_LOCK_ID)
+        .await?;
+    storage
+        .leader_election_schema()
+        .check_fencing_token(old_token)
+        .await?;
+
+    // The lock is lost, while the previous leader may still be running.
+    assert!(
+        storage
+            .leader_election_schema()
+            .release_leadership(TEST_LOCK_ID)
+            .await?
+    );
+    assert!(
+        standby
+            .leader_election_schema()
+            .try_acquire_leadership(TEST_LOCK_ID)
+            .await?
+    );
+    assert!(
+        !storage
+            .leader_election_schema()
+            .check_leadership(old_token)
+            .await?
+    );
+    assert!(
+        standby
+            .leader_election_schema()
+            .release_leadership(TEST_LOCK_ID)
+            .await?
+    );
+
+    // The tokens are issued within the test transaction, so the next leadership is taken
+    // by the same connection: the token rows of the other connection would wait for it.
+    assert!(
+        storage
+            .leader_election_schema()
+            .try_acquire_leadership(TEST_LOCK_ID)
+            .await?
+    );
+    let new_token = storage
+        .leader_election_schema()
+        .issue_fencing_token(TEST_LOCK_ID)
+        .await?;
+    assert!(new_token.token > old_token.token);
+    assert!(storage
+        .leader_election_schema()
+        .check_fencing_token(old_token)
+        .await
+        .is_err());
+    assert!(
+        !storage
+            .leader_election_schema()
+            .check_leadership(old_token)
+            .await?
+    );
+    storage
+        .leader_election_schema()
+        .check_fencing_token(new_token)
+        .await?;
+    assert!(
+        storage
+            .leader_election_schema()
+            .check_leadership(new_token)
+            .await?
+    );
+    assert!(
+        storage
+            .leader_election_schema()
+            .release_leadership(TEST_LOCK_ID)
+            .await?
+    );
+
+    Ok(())
+}
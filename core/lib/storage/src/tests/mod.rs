@@ -28,13 +28,38 @@
 use zksync_crypto::rand::{SeedableRng, XorShiftRng};
 // use diesel::Connection;
 
+mod activations;
+mod aliases;
+mod api_namespaces;
+mod attestations;
 pub(crate) mod chain;
 mod config;
+mod counters;
 mod data_restore;
+mod deposit_refunds;
+mod dust_collection;
+mod eth_watch;
 mod ethereum;
+mod event;
+mod external_provers;
+mod fast_withdrawals;
+mod fee_refunds;
+mod fee_sweeps;
 mod forced_exit_requests;
+mod guardians;
+mod idempotency;
+mod key_audit;
+mod l1_messages;
+mod leader_election;
+mod nonce_reservations;
+mod priority_op_costs;
+mod proof_archives;
 mod prover;
+mod revenue;
+mod screening;
+mod shadow_ethereum;
 mod tokens;
+mod webhooks;
 
 pub use db_test_macro::test as db_test;
 
@@ -0,0 +1,179 @@
+// External imports
+use chrono::{Duration, Utc};
+// Workspace imports
+use zksync_types::{nonce_reservation::NonceReservation, Address, Nonce};
+// Local imports
+use crate::{
+    nonce_reservations::NonceReservationOutcome, tests::db_test, QueryResult, StorageProcessor,
+};
+
+fn reserved(outcome: NonceReservationOutcome) -> NonceReservation {
+    match outcome {
+        NonceReservationOutcome::Reserved(reservation) => reservation,
+        outcome => panic!("Reservation was not stored: {:?}", outcome),
+    }
+}
+
+/// Checks that the reserved ranges of the account follow each other and the committed nonce,
+/// and that the replayed requests are rejected.
+#[db_test]
+async fn reserved_ranges_do_not_overlap(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let address = Address::repeat_byte(1);
+    let expires_at = Utc::now() + Duration::minutes(10);
+
+    let first = reserved(
+        storage
+            .nonce_reservations_schema()
+            .reserve_nonces(address, Nonce(5), 10, 1000, expires_at)
+            .await?,
+    );
+    assert_eq!(first.first_nonce, Nonce(5));
+    assert_eq!(first.last_nonce, Nonce(14));
+    assert!(first.contains(Nonce(14)));
+    assert!(!first.contains(Nonce(15)));
+
+    // The next range starts after the active reservation.
+    let second = reserved(
+        storage
+            .nonce_reservations_schema()
+            .reserve_nonces(address, Nonce(5), 2, 1001, expires_at)
+            .await?,
+    );
+    assert_eq!(second.first_nonce, Nonce(15));
+    assert_eq!(second.last_nonce, Nonce(16));
+
+    // The request with the same timestamp can't be replayed.
+    assert_eq!(
+        storage
+            .nonce_reservations_schema()
+            .reserve_nonces(address, Nonce(5), 2, 1001, expires_at)
+            .await?,
+        NonceReservationOutcome::Replayed
+    );
+
+    // Expired reservations are ignored.
+    let other = Address::repeat_byte(2);
+    storage
+        .nonce_reservations_schema()
+        .reserve_nonces(other, Nonce(0), 10, 1000, Utc::now() - Duration::minutes(1))
+        .await?;
+    let reservation = reserved(
+        storage
+            .nonce_reservations_schema()
+            .reserve_nonces(other, Nonce(3), 1, 1001, expires_at)
+            .await?,
+    );
+    assert_eq!(reservation.first_nonce, Nonce(3));
+
+    assert_eq!(
+        storage
+            .nonce_reservations_schema()
+            .load_active_reservations(address)
+            .await?,
+        vec![first, second]
+    );
+    assert_eq!(
+        storage
+            .nonce_reservations_schema()
+            .load_all_active_reservations()
+            .await?
+            .len(),
+        3
+    );
+
+    Ok(())
+}
+
+/// Checks that the ranges past the maximum nonce are not reserved.
+#[db_test]
+async fn nonce_overflow(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let address = Address::repeat_byte(1);
+    let expires_at = Utc::now() + Duration::minutes(10);
+
+    let last = reserved(
+        storage
+            .nonce_reservations_schema()
+            .reserve_nonces(address, Nonce(u32::MAX - 1), 2, 1000, expires_at)
+            .await?,
+    );
+    assert_eq!(last.last_nonce, Nonce(u32::MAX));
+
+    assert_eq!(
+        storage
+            .nonce_reservations_schema()
+            .reserve_nonces(address, Nonce(0), 1, 1001, expires_at)
+            .await?,
+        NonceReservationOutcome::NonceOverflow
+    );
+    assert_eq!(
+        storage
+            .nonce_reservations_schema()
+            .reserve_nonces(
+                Address::repeat_byte(2),
+                Nonce(u32::MAX),
+                2,
+                1000,
+                expires_at
+            )
+            .await?,
+        NonceReservationOutcome::NonceOverflow
+    );
+    assert!(storage
+        .nonce_reservations_schema()
+        .reserve_nonces(address, Nonce(0), 1, u64::MAX, expires_at)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+/// Checks that the expired reservations are removed once their requests can't be replayed.
+#[db_test]
+async fn expired_reservations_are_removed(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let address = Address::repeat_byte(1);
+    let expired_at = Utc::now() - Duration::minutes(1);
+
+    for &request_timestamp in &[1000, 2000] {
+        storage
+            .nonce_reservations_schema()
+            .reserve_nonces(address, Nonce(0), 1, request_timestamp, expired_at)
+            .await?;
+    }
+    let active = reserved(
+        storage
+            .nonce_reservations_schema()
+            .reserve_nonces(
+                address,
+                Nonce(0),
+                1,
+                500,
+                Utc::now() + Duration::minutes(10),
+            )
+            .await?,
+    );
+
+    // The request signed at 2000 may still be replayed, so its reservation is kept.
+    assert_eq!(
+        storage
+            .nonce_reservations_schema()
+            .remove_expired_reservations(1500)
+            .await?,
+        1
+    );
+    assert_eq!(
+        storage
+            .nonce_reservations_schema()
+            .reserve_nonces(address, Nonce(0), 1, 2000, expired_at)
+            .await?,
+        NonceReservationOutcome::Replayed
+    );
+    assert_eq!(
+        storage
+            .nonce_reservations_schema()
+            .load_active_reservations(address)
+            .await?,
+        vec![active]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+// External imports
+use num::BigUint;
+// Workspace imports
+use zksync_types::{
+    aggregated_operations::AggregatedActionType,
+    priority_op_cost::{PriorityOpCostSummary, PriorityOpGas},
+    BlockNumber,
+};
+// Local imports
+use crate::{tests::db_test, QueryResult, StorageProcessor};
+
+fn priority_op_gas(serial_id: u64, op_type: &'static str, gas: u64) -> PriorityOpGas {
+    PriorityOpGas {
+        serial_id,
+        block_number: BlockNumber(1),
+        op_type,
+        gas,
+        fee: BigUint::from(gas * 10),
+    }
+}
+
+/// Checks that the commit and execute costs of the priority operations are stored and averaged.
+#[db_test]
+async fn priority_op_costs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let commit_costs = vec![
+        priority_op_gas(0, "Deposit", 1000),
+        priority_op_gas(1, "Deposit", 3000),
+        priority_op_gas(2, "FullExit", 5000),
+    ];
+    storage
+        .priority_op_costs_schema()
+        .record_priority_op_gas(AggregatedActionType::CommitBlocks, &commit_costs)
+        .await?;
+
+    // Operations which were not executed yet are not averaged.
+    let cost = storage
+        .priority_op_costs_schema()
+        .load_cost(0)
+        .await?
+        .expect("Cost was not stored");
+    assert_eq!(cost.commit_gas, Some(1000));
+    assert_eq!(cost.execute_gas, None);
+    assert!(storage
+        .priority_op_costs_schema()
+        .load_summary(10)
+        .await?
+        .is_empty());
+
+    let execute_costs = vec![
+        priority_op_gas(0, "Deposit", 100),
+        priority_op_gas(1, "Deposit", 100),
+        priority_op_gas(2, "FullExit", 2000),
+    ];
+    storage
+        .priority_op_costs_schema()
+        .record_priority_op_gas(AggregatedActionType::ExecuteBlocks, &execute_costs)
+        .await?;
+
+    let cost = storage
+        .priority_op_costs_schema()
+        .load_cost(2)
+        .await?
+        .expect("Cost was not stored");
+    assert_eq!(cost.op_type, "FullExit");
+    assert_eq!(cost.execute_gas, Some(2000));
+    assert_eq!(cost.execute_fee, Some(BigUint::from(20000u32).into()));
+
+    let summary = storage.priority_op_costs_schema().load_summary(10).await?;
+    assert_eq!(
+        summary,
+        vec![
+            PriorityOpCostSummary {
+                op_type: "Deposit".into(),
+                ops_count: 2,
+                avg_gas: 2100,
+                avg_fee: BigUint::from(21000u32),
+            },
+            PriorityOpCostSummary {
+                op_type: "FullExit".into(),
+                ops_count: 1,
+                avg_gas: 7000,
+                avg_fee: BigUint::from(70000u32),
+            },
+        ]
+    );
+
+    // Only the latest operations are averaged.
+    let summary = storage.priority_op_costs_schema().load_summary(1).await?;
+    assert_eq!(summary[0].avg_gas, 3100);
+
+    Ok(())
+}
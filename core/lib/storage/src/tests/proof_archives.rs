@@ -0,0 +1,53 @@
+// Workspace imports
+use zksync_types::{BlockNumber, H256};
+// Local imports
+use crate::{tests::db_test, QueryResult, StorageProcessor};
+
+/// Checks that the exported archives are recorded and the re-exported ones replace the records.
+#[db_test]
+async fn proof_archives(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert_eq!(
+        storage
+            .proof_archives_schema()
+            .last_archived_block()
+            .await?,
+        None
+    );
+
+    for block in 1..=3 {
+        storage
+            .proof_archives_schema()
+            .record_archive(
+                BlockNumber(block),
+                &format!("block-{}.json", block),
+                H256::repeat_byte(block as u8),
+            )
+            .await?;
+    }
+    assert_eq!(
+        storage
+            .proof_archives_schema()
+            .last_archived_block()
+            .await?,
+        Some(BlockNumber(3))
+    );
+
+    storage
+        .proof_archives_schema()
+        .record_archive(BlockNumber(2), "block-2-new.json", H256::repeat_byte(0xff))
+        .await?;
+    let archive = storage
+        .proof_archives_schema()
+        .get_archive(BlockNumber(2))
+        .await?
+        .expect("Archive is not recorded");
+    assert_eq!(archive.file_name, "block-2-new.json");
+    assert_eq!(archive.archive_hash, H256::repeat_byte(0xff));
+    assert!(storage
+        .proof_archives_schema()
+        .get_archive(BlockNumber(4))
+        .await?
+        .is_none());
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 // External imports
 use anyhow::format_err;
 // Workspace imports
-use zksync_types::prover::{ProverJob, ProverJobType};
+use zksync_types::prover::{ProofProgress, ProverJob, ProverJobType};
 // Local imports
 use crate::test_data::{gen_sample_block, get_sample_aggregated_proof, get_sample_single_proof};
 use crate::tests::db_test;
@@ -386,6 +386,17 @@ async fn test_remove_proofs(mut storage: StorageProcessor<'_>) -> QueryResult<()
         .store_aggregated_proof(job_id, BlockNumber(3), BlockNumber(5), &aggregated_proof)
         .await?;
 
+    // The aggregated proof is found by any of its blocks.
+    let (first_block, last_block, _) = ProverSchema(&mut storage)
+        .load_aggregated_proof_for_block(BlockNumber(4))
+        .await?
+        .expect("Aggregated proof is not found");
+    assert_eq!((first_block, last_block), (BlockNumber(3), BlockNumber(5)));
+    assert!(ProverSchema(&mut storage)
+        .load_aggregated_proof_for_block(BlockNumber(6))
+        .await?
+        .is_none());
+
     // Remove aggregated proofs for blocks with numbers greater than 3. It means that proof for 3-5 blocks should be deleted.
     ProverSchema(&mut storage)
         .remove_aggregated_proofs(BlockNumber(3))
@@ -409,11 +420,11 @@ async fn test_remove_proofs(mut storage: StorageProcessor<'_>) -> QueryResult<()
 async fn test_remove_prover_jobs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     let job_data = serde_json::Value::default();
 
-    // Insert jobs for blocks 1-3 and 4-5.
+    // Insert jobs for blocks 1-2, 3-4 and 5-5.
     ProverSchema(&mut storage)
         .add_prover_job_to_job_queue(
             BlockNumber(1),
-            BlockNumber(3),
+            BlockNumber(2),
             job_data.clone(),
             1,
             ProverJobType::AggregatedProof,
@@ -421,17 +432,27 @@ async fn test_remove_prover_jobs(mut storage: StorageProcessor<'_>) -> QueryResu
         .await?;
     ProverSchema(&mut storage)
         .add_prover_job_to_job_queue(
+            BlockNumber(3),
             BlockNumber(4),
-            BlockNumber(5),
             job_data.clone(),
             1,
             ProverJobType::AggregatedProof,
         )
         .await?;
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(5),
+            BlockNumber(5),
+            job_data.clone(),
+            1,
+            ProverJobType::SingleProof,
+        )
+        .await?;
 
-    // Remove prover_jobs for blocks with numbers greater than 2. After that only one job for 1-2 blocks should left.
+    // Remove prover_jobs for blocks with numbers greater than 3. The aggregated job for 3-4 blocks
+    // contains the proof of the removed block, so only the job for 1-2 blocks should left.
     ProverSchema(&mut storage)
-        .remove_prover_jobs(BlockNumber(2))
+        .remove_prover_jobs(BlockNumber(3))
         .await?;
     assert_eq!(
         ProverSchema(&mut storage)
@@ -443,3 +464,103 @@ async fn test_remove_prover_jobs(mut storage: StorageProcessor<'_>) -> QueryResu
 
     Ok(())
 }
+
+/// Checks that the progress reported by the provers is stored and loaded for the blocks.
+#[db_test]
+async fn test_proof_progress(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    let job_data = serde_json::Value::default();
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(1),
+            BlockNumber(1),
+            job_data.clone(),
+            1,
+            ProverJobType::SingleProof,
+        )
+        .await?;
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(1),
+            BlockNumber(2),
+            job_data,
+            0,
+            ProverJobType::AggregatedProof,
+        )
+        .await?;
+
+    // Jobs are reported without the progress until the prover reports it.
+    let jobs = ProverSchema(&mut storage)
+        .load_block_proof_progress(BlockNumber(1))
+        .await?;
+    assert_eq!(jobs.len(), 2);
+    assert!(jobs.iter().all(|job| job.stage.is_none()));
+
+    let job = get_idle_job_from_queue(&mut storage).await?;
+    ProverSchema(&mut storage)
+        .record_prover_is_working(job.job_id, "test_prover")
+        .await?;
+    ProverSchema(&mut storage)
+        .record_proof_progress(job.job_id, "test_prover", ProofProgress::WitnessBuilt)
+        .await?;
+    ProverSchema(&mut storage)
+        .record_proof_progress(job.job_id, "test_prover", ProofProgress::Setup)
+        .await?;
+
+    let jobs = ProverSchema(&mut storage)
+        .load_block_proof_progress(BlockNumber(job.first_block.0))
+        .await?;
+    let stored = jobs
+        .iter()
+        .find(|stored| stored.job_id == job.job_id)
+        .expect("job is not loaded");
+    let progress = ProofProgress::from_stage(stored.stage.as_deref().unwrap()).unwrap();
+    assert_eq!(progress, ProofProgress::Setup);
+    assert_eq!(stored.prover_name.as_deref(), Some("test_prover"));
+
+    // The job given to another prover starts without the progress.
+    ProverSchema(&mut storage)
+        .record_prover_stop("test_prover")
+        .await?;
+    let next_job = get_idle_job_from_queue(&mut storage).await?;
+    assert_eq!(next_job.job_id, job.job_id);
+    let jobs = ProverSchema(&mut storage)
+        .load_block_proof_progress(BlockNumber(job.first_block.0))
+        .await?;
+    assert!(jobs.iter().all(|job| job.stage.is_none()));
+
+    Ok(())
+}
+
+/// Checks that the released job is given to the next prover.
+#[db_test]
+async fn test_release_prover_job(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+
+    ProverSchema(&mut storage)
+        .add_prover_job_to_job_queue(
+            BlockNumber(1),
+            BlockNumber(1),
+            Default::default(),
+            1,
+            ProverJobType::SingleProof,
+        )
+        .await?;
+
+    let job = get_idle_job_from_queue(&mut storage).await?;
+    assert!(ProverSchema(&mut storage)
+        .get_idle_prover_job_from_job_queue()
+        .await?
+        .is_none());
+
+    ProverSchema(&mut storage)
+        .release_prover_job(job.job_id)
+        .await?;
+    let next_job = get_idle_job_from_queue(&mut storage).await?;
+    assert_eq!(next_job.job_id, job.job_id);
+
+    Ok(())
+}
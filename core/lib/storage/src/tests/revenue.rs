@@ -0,0 +1,77 @@
+// External imports
+use chrono::{DateTime, Utc};
+use num::{rational::Ratio, BigUint};
+// Workspace imports
+use zksync_types::{
+    revenue::{CollectedFees, RevenuePeriod},
+    TokenId,
+};
+// Local imports
+use crate::{tests::db_test, QueryResult, StorageProcessor};
+
+fn fees(period_start: &str, token: u16, amount: u32, amount_usd: u32) -> CollectedFees {
+    CollectedFees {
+        period_start: period_start.parse().unwrap(),
+        token_id: TokenId(token),
+        amount: BigUint::from(amount),
+        amount_usd: Ratio::from_integer(BigUint::from(amount_usd)),
+        tx_count: 1,
+    }
+}
+
+/// Checks that the collected fees are summed up and reported by the requested periods.
+#[db_test]
+async fn operator_revenue(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    storage
+        .revenue_schema()
+        .store_collected_fees(
+            "revenue",
+            5,
+            &[
+                fees("2021-05-24T10:00:00Z", 0, 100, 1),
+                fees("2021-05-24T11:00:00Z", 0, 50, 2),
+                fees("2021-05-24T11:00:00Z", 1, 7, 3),
+            ],
+        )
+        .await?;
+    // Fees of the same hour are added to the stored ones.
+    storage
+        .revenue_schema()
+        .store_collected_fees("revenue", 9, &[fees("2021-05-24T11:00:00Z", 0, 25, 4)])
+        .await?;
+    assert_eq!(
+        storage
+            .event_schema()
+            .get_consumer_offset("revenue")
+            .await?,
+        Some(9)
+    );
+
+    let from: DateTime<Utc> = "2021-05-24T00:00:00Z".parse().unwrap();
+    let to: DateTime<Utc> = "2021-05-25T00:00:00Z".parse().unwrap();
+    let hourly = storage
+        .revenue_schema()
+        .load_revenue(from, to, RevenuePeriod::Hour)
+        .await?;
+    assert_eq!(hourly.len(), 3);
+    assert_eq!(
+        hourly[1].period_start,
+        "2021-05-24T11:00:00Z".parse::<DateTime<Utc>>()?
+    );
+    assert_eq!(hourly[1].token_id, TokenId(0));
+    assert_eq!(hourly[1].fee_amount, BigUint::from(75u32));
+    assert_eq!(hourly[1].fee_usd, Ratio::from_integer(BigUint::from(6u32)));
+    assert_eq!(hourly[1].tx_count, 2);
+
+    let daily = storage
+        .revenue_schema()
+        .load_revenue(from, to, RevenuePeriod::Day)
+        .await?;
+    assert_eq!(daily.len(), 2);
+    assert_eq!(daily[0].period_start, from);
+    assert_eq!(daily[0].fee_amount, BigUint::from(175u32));
+    assert_eq!(daily[0].tx_count, 3);
+    assert_eq!(daily[1].token_id, TokenId(1));
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+// External imports
+use chrono::{DateTime, Duration, TimeZone, Utc};
+// Workspace imports
+use zksync_types::{
+    screening::{ScreeningAction, ScreeningRecord},
+    tx::TxHash,
+    Address,
+};
+// Local imports
+use crate::{tests::db_test, QueryResult, StorageProcessor};
+
+fn screening_record(
+    tx_hash: Option<TxHash>,
+    priority_op_serial_id: Option<u64>,
+    action: ScreeningAction,
+    release_at: Option<DateTime<Utc>>,
+) -> ScreeningRecord {
+    ScreeningRecord {
+        address: Address::repeat_byte(7),
+        tx_hash,
+        priority_op_serial_id,
+        action,
+        reason: "listed in the local screening list".to_owned(),
+        release_at,
+    }
+}
+
+/// Checks that the screening decisions are appended to the audit trail,
+/// the deposits are recorded once and the pending delays are loaded.
+#[db_test]
+async fn screening_audit_trail(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Postgres keeps the timestamps with the microseconds precision.
+    let now = Utc.timestamp(Utc::now().timestamp(), 0);
+    let delayed_hash = TxHash::from_slice(&[1; 32]).unwrap();
+    let released_hash = TxHash::from_slice(&[2; 32]).unwrap();
+    let records = vec![
+        screening_record(
+            Some(delayed_hash),
+            None,
+            ScreeningAction::Delay,
+            Some(now + Duration::hours(1)),
+        ),
+        screening_record(
+            Some(released_hash),
+            None,
+            ScreeningAction::Delay,
+            Some(now - Duration::hours(1)),
+        ),
+        screening_record(None, Some(5), ScreeningAction::Flag, None),
+        screening_record(
+            Some(TxHash::from_slice(&[3; 32]).unwrap()),
+            None,
+            ScreeningAction::Reject,
+            None,
+        ),
+    ];
+    for record in &records {
+        storage.screening_schema().record_screening(record).await?;
+    }
+    // The deposit screened again while proposing the next block is not recorded twice.
+    storage
+        .screening_schema()
+        .record_screening(&records[2])
+        .await?;
+
+    let entries = storage
+        .screening_schema()
+        .load_audit_trail(None, 10)
+        .await?;
+    assert_eq!(
+        entries
+            .iter()
+            .map(|entry| entry.record.clone())
+            .collect::<Vec<_>>(),
+        records
+    );
+
+    // Pagination.
+    let page = storage
+        .screening_schema()
+        .load_audit_trail(Some(entries[1].id), 1)
+        .await?;
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].record, records[2]);
+
+    // Only the delays which are not released yet are loaded.
+    let delays = storage.screening_schema().load_delays(now).await?;
+    assert_eq!(delays.len(), 1);
+    assert_eq!(delays[0].0, delayed_hash);
+
+    Ok(())
+}
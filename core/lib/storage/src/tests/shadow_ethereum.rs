@@ -0,0 +1,221 @@
+// External imports
+use num::BigUint;
+// Workspace imports
+use zksync_basic_types::{H256, U256};
+use zksync_types::{
+    aggregated_operations::{AggregatedActionType, AggregatedOperation},
+    BlockNumber,
+};
+// Local imports
+use crate::{
+    ethereum::records::ETHStats,
+    test_data::{gen_unique_aggregated_operation, BLOCK_SIZE_CHUNKS},
+    tests::db_test,
+    QueryResult, StorageProcessor,
+};
+
+const NETWORK: &str = "fork";
+
+async fn store_operation(
+    storage: &mut StorageProcessor<'_>,
+    action: AggregatedActionType,
+    block_number: BlockNumber,
+) -> QueryResult<(i64, AggregatedOperation)> {
+    storage
+        .chain()
+        .operations_schema()
+        .store_aggregated_action(gen_unique_aggregated_operation(
+            block_number,
+            action,
+            BLOCK_SIZE_CHUNKS,
+        ))
+        .await?;
+    let operation = storage
+        .chain()
+        .operations_schema()
+        .get_aggregated_op_that_affects_block(action, block_number)
+        .await?
+        .expect("Operation is just stored");
+    Ok(operation)
+}
+
+/// Checks that the operations are sent to the shadow network starting with the blocks
+/// not processed by its contract, with the nonce and the stats of the network.
+#[db_test]
+async fn shadow_network_operations(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // The block #1 is already committed in the shadow network.
+    let stats = ETHStats {
+        last_committed_block: 1,
+        last_verified_block: 0,
+        last_executed_block: 0,
+    };
+    assert!(
+        !storage
+            .shadow_ethereum_schema()
+            .is_network_initialized(NETWORK)
+            .await?
+    );
+    assert!(
+        storage
+            .shadow_ethereum_schema()
+            .initialize_network(NETWORK, 5.into(), 100.into(), stats)
+            .await?
+    );
+    let stats = ETHStats {
+        last_committed_block: 0,
+        last_verified_block: 0,
+        last_executed_block: 0,
+    };
+    // The state of the initialized network is kept.
+    assert!(
+        !storage
+            .shadow_ethereum_schema()
+            .initialize_network(NETWORK, 0.into(), 100.into(), stats)
+            .await?
+    );
+
+    store_operation(
+        &mut storage,
+        AggregatedActionType::CommitBlocks,
+        BlockNumber(1),
+    )
+    .await?;
+    let commit = store_operation(
+        &mut storage,
+        AggregatedActionType::CommitBlocks,
+        BlockNumber(2),
+    )
+    .await?;
+    let verify = store_operation(
+        &mut storage,
+        AggregatedActionType::PublishProofBlocksOnchain,
+        BlockNumber(1),
+    )
+    .await?;
+
+    let operations = storage
+        .shadow_ethereum_schema()
+        .load_unprocessed_operations(NETWORK)
+        .await?;
+    let ids: Vec<_> = operations.iter().map(|(id, _)| *id).collect();
+    assert_eq!(ids, vec![commit.0, verify.0]);
+
+    // Loaded operations are not returned again until the restart.
+    storage
+        .shadow_ethereum_schema()
+        .remove_unprocessed_operations(NETWORK, ids.clone())
+        .await?;
+    assert!(storage
+        .shadow_ethereum_schema()
+        .load_unprocessed_operations(NETWORK)
+        .await?
+        .is_empty());
+    storage
+        .shadow_ethereum_schema()
+        .restore_unprocessed_operations(NETWORK)
+        .await?;
+    assert_eq!(
+        storage
+            .shadow_ethereum_schema()
+            .load_unprocessed_operations(NETWORK)
+            .await?
+            .len(),
+        2
+    );
+
+    let commit_response = storage
+        .shadow_ethereum_schema()
+        .save_new_eth_tx(NETWORK, commit.clone(), 100, BigUint::from(10u32), vec![1])
+        .await?;
+    let verify_response = storage
+        .shadow_ethereum_schema()
+        .save_new_eth_tx(NETWORK, verify, 100, BigUint::from(10u32), vec![2])
+        .await?;
+    assert_eq!(commit_response.nonce, U256::from(5));
+    assert_eq!(verify_response.nonce, U256::from(6));
+    // The block can't be committed twice.
+    assert!(storage
+        .shadow_ethereum_schema()
+        .save_new_eth_tx(NETWORK, commit, 100, BigUint::from(10u32), vec![1])
+        .await
+        .is_err());
+
+    let commit_hash = H256::repeat_byte(1);
+    storage
+        .shadow_ethereum_schema()
+        .add_hash_entry(commit_response.id, &commit_hash)
+        .await?;
+    storage
+        .shadow_ethereum_schema()
+        .add_hash_entry(verify_response.id, &H256::repeat_byte(2))
+        .await?;
+    storage
+        .shadow_ethereum_schema()
+        .update_eth_tx(verify_response.id, 120, BigUint::from(20u32))
+        .await?;
+
+    let unconfirmed = storage
+        .shadow_ethereum_schema()
+        .load_unconfirmed_operations(NETWORK)
+        .await?;
+    assert_eq!(unconfirmed.len(), 2);
+    assert_eq!(unconfirmed[0].used_tx_hashes, vec![commit_hash]);
+    assert_eq!(unconfirmed[1].last_deadline_block, 120);
+    assert_eq!(unconfirmed[1].last_used_gas_price, U256::from(20));
+
+    assert!(
+        storage
+            .shadow_ethereum_schema()
+            .is_previous_operation_confirmed(NETWORK, commit_response.id)
+            .await?
+    );
+    assert!(
+        !storage
+            .shadow_ethereum_schema()
+            .is_previous_operation_confirmed(NETWORK, verify_response.id)
+            .await?
+    );
+    storage
+        .shadow_ethereum_schema()
+        .confirm_eth_tx(NETWORK, &commit_hash)
+        .await?;
+    assert!(
+        storage
+            .shadow_ethereum_schema()
+            .is_previous_operation_confirmed(NETWORK, verify_response.id)
+            .await?
+    );
+    assert_eq!(
+        storage
+            .shadow_ethereum_schema()
+            .load_unconfirmed_operations(NETWORK)
+            .await?
+            .len(),
+        1
+    );
+
+    let stats = storage.shadow_ethereum_schema().load_stats(NETWORK).await?;
+    assert_eq!(stats.last_committed_block, 2);
+    assert_eq!(stats.last_verified_block, 1);
+    assert_eq!(stats.last_executed_block, 0);
+
+    // The actual network is not affected.
+    assert!(storage
+        .ethereum_schema()
+        .load_unconfirmed_operations()
+        .await?
+        .is_empty());
+
+    storage
+        .shadow_ethereum_schema()
+        .update_gas_price(NETWORK, 200.into(), 150.into())
+        .await?;
+    assert_eq!(
+        storage
+            .shadow_ethereum_schema()
+            .load_gas_price_limit(NETWORK)
+            .await?,
+        U256::from(200)
+    );
+    Ok(())
+}
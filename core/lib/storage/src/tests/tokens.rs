@@ -1,7 +1,11 @@
 // External imports
 use num::{rational::Ratio, BigUint};
 // Workspace imports
-use zksync_types::{tokens::TokenMarketVolume, Token, TokenId, TokenLike, TokenPrice};
+use zksync_types::{
+    tokens::{TokenFlags, TokenMarketVolume},
+    withdrawal_gas::WithdrawalGasCost,
+    Token, TokenId, TokenLike, TokenPrice,
+};
 use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal};
 // Local imports
 use crate::tests::db_test;
@@ -107,6 +111,96 @@ async fn tokens_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     Ok(())
 }
 
+/// Checks that the token metadata can be fixed and the token flags are stored.
+#[db_test]
+async fn token_metadata_and_flags(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let token = Token {
+        id: TokenId(1),
+        address: "0000000000000000000000000000000000000001".parse().unwrap(),
+        symbol: "ABC".into(),
+        decimals: 9,
+    };
+    storage.tokens_schema().store_token(token.clone()).await?;
+
+    // Update the metadata of the existing token.
+    assert!(
+        storage
+            .tokens_schema()
+            .update_token_metadata(token.id, "XYZ", 18)
+            .await?
+    );
+    let updated = storage
+        .tokens_schema()
+        .get_token(TokenLike::Id(token.id))
+        .await?
+        .expect("token by id not found");
+    assert_eq!(updated, Token::new(token.id, token.address, "XYZ", 18));
+
+    // Unknown token can't be updated.
+    assert!(
+        !storage
+            .tokens_schema()
+            .update_token_metadata(TokenId(100), "XYZ", 18)
+            .await?
+    );
+
+    // Flags are not set by default.
+    assert_eq!(
+        storage.tokens_schema().get_token_flags(token.id).await?,
+        TokenFlags::default()
+    );
+    let flags = TokenFlags {
+        disabled: true,
+        fee_eligible: false,
+    };
+    storage
+        .tokens_schema()
+        .store_token_flags(token.id, flags)
+        .await?;
+    assert_eq!(
+        storage.tokens_schema().get_token_flags(token.id).await?,
+        flags
+    );
+
+    Ok(())
+}
+
+/// Checks that every change of the tokens increments the tokens version.
+#[db_test]
+async fn tokens_version(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let token = Token {
+        id: TokenId(1),
+        address: "0000000000000000000000000000000000000001".parse().unwrap(),
+        symbol: "ABC".into(),
+        decimals: 9,
+    };
+    let initial_version = storage.tokens_schema().load_tokens_version().await?;
+
+    storage.tokens_schema().store_token(token.clone()).await?;
+    assert_eq!(
+        storage.tokens_schema().load_tokens_version().await?,
+        initial_version + 1
+    );
+    storage
+        .tokens_schema()
+        .update_token_metadata(token.id, "XYZ", 18)
+        .await?;
+    assert_eq!(
+        storage.tokens_schema().load_tokens_version().await?,
+        initial_version + 2
+    );
+    storage
+        .tokens_schema()
+        .store_token_flags(token.id, TokenFlags::default())
+        .await?;
+    assert_eq!(
+        storage.tokens_schema().load_tokens_version().await?,
+        initial_version + 3
+    );
+
+    Ok(())
+}
+
 /// Checks the store/load routine for `ticker_price` table.
 #[db_test]
 async fn test_ticker_price(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -193,3 +287,45 @@ async fn test_market_volume(mut storage: StorageProcessor<'_>) -> QueryResult<()
 
     Ok(())
 }
+
+/// Checks the store/load routine for the calibrated withdrawal gas costs.
+#[db_test]
+async fn test_withdrawal_gas_costs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    const TOKEN_ID: TokenId = TokenId(0);
+
+    assert!(storage
+        .tokens_schema()
+        .get_withdrawal_gas_cost(TOKEN_ID)
+        .await?
+        .is_none());
+
+    let mut cost = WithdrawalGasCost::new(TOKEN_ID);
+    cost.add_samples(120_000, 3);
+    storage
+        .tokens_schema()
+        .store_withdrawal_gas_costs(&[cost.clone()])
+        .await?;
+
+    cost.add_samples(60_000, 3);
+    storage
+        .tokens_schema()
+        .store_withdrawal_gas_costs(&[cost.clone()])
+        .await?;
+
+    let loaded = storage
+        .tokens_schema()
+        .get_withdrawal_gas_cost(TOKEN_ID)
+        .await?
+        .expect("couldn't load withdrawal gas cost");
+    assert_eq!((loaded.gas_cost, loaded.samples), (90_000, 6));
+    assert_eq!(
+        storage
+            .tokens_schema()
+            .load_withdrawal_gas_costs()
+            .await?
+            .len(),
+        1
+    );
+
+    Ok(())
+}
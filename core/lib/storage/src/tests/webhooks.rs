@@ -0,0 +1,96 @@
+// External imports
+use chrono::{Duration, Utc};
+// Workspace imports
+use zksync_types::{
+    webhooks::{WebhookDeliveryStatus, WebhookEventType, WebhookNotification},
+    Address, BlockNumber,
+};
+// Local imports
+use crate::{tests::db_test, QueryResult, StorageProcessor};
+
+/// Checks the lifecycle of the webhook deliveries: enqueueing along with the dispatcher offset,
+/// retries of the failed attempts and the successful delivery.
+#[db_test]
+async fn webhook_deliveries(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let address = Address::repeat_byte(1);
+    let subscription = storage
+        .webhooks_schema()
+        .add_subscription(
+            "http://127.0.0.1:8080",
+            WebhookEventType::BlockVerified,
+            Some(address),
+            "secret",
+            None,
+        )
+        .await?;
+    assert_eq!(subscription.address, Some(address));
+    assert_eq!(
+        storage.webhooks_schema().load_subscriptions().await?.len(),
+        1
+    );
+    assert_eq!(
+        storage.webhooks_schema().get_dispatcher_offset().await?,
+        None
+    );
+
+    let notification = WebhookNotification::BlockVerified {
+        block_number: BlockNumber(1),
+    };
+    storage
+        .webhooks_schema()
+        .enqueue_deliveries(11, &[(subscription.id, 10, notification)])
+        .await?;
+    assert_eq!(
+        storage.webhooks_schema().get_dispatcher_offset().await?,
+        Some(11)
+    );
+
+    let due = storage.webhooks_schema().load_due_deliveries(10).await?;
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].secret, "secret");
+    let delivery_id = due[0].delivery.id;
+
+    // Failed attempt postpones the delivery.
+    storage
+        .webhooks_schema()
+        .record_failed_attempt(
+            delivery_id,
+            "timeout",
+            Some(Utc::now() + Duration::hours(1)),
+        )
+        .await?;
+    assert!(storage
+        .webhooks_schema()
+        .load_due_deliveries(10)
+        .await?
+        .is_empty());
+
+    storage
+        .webhooks_schema()
+        .mark_delivered(delivery_id)
+        .await?;
+    let deliveries = storage
+        .webhooks_schema()
+        .load_deliveries(subscription.id, 10)
+        .await?;
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!(deliveries[0].status, WebhookDeliveryStatus::Delivered);
+    assert_eq!(deliveries[0].attempts, 2);
+    assert_eq!(deliveries[0].event_offset, 10);
+    assert!(deliveries[0].delivered_at.is_some());
+
+    // Deliveries are removed along with the subscription.
+    assert!(
+        storage
+            .webhooks_schema()
+            .remove_subscription(subscription.id)
+            .await?
+    );
+    assert!(storage
+        .webhooks_schema()
+        .load_deliveries(subscription.id, 10)
+        .await?
+        .is_empty());
+
+    Ok(())
+}
@@ -4,13 +4,13 @@ use std::time::Instant;
 // External imports
 use num::{rational::Ratio, BigUint};
 // Workspace imports
-use zksync_types::{Token, TokenId, TokenLike, TokenPrice};
+use zksync_types::{withdrawal_gas::WithdrawalGasCost, Token, TokenId, TokenLike, TokenPrice};
 use zksync_utils::ratio_to_big_decimal;
 // Local imports
-use self::records::{DBMarketVolume, DbTickerPrice, DbToken};
+use self::records::{DBMarketVolume, DbTickerPrice, DbToken, DbTokenFlags, DbWithdrawalGasCost};
 use crate::utils::address_to_stored_string;
 use crate::{QueryResult, StorageProcessor};
-use zksync_types::tokens::TokenMarketVolume;
+use zksync_types::tokens::{TokenFlags, TokenMarketVolume};
 
 pub mod records;
 
@@ -26,6 +26,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
     /// Persists the token in the database.
     pub async fn store_token(&mut self, token: Token) -> QueryResult<()> {
         let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
         sqlx::query!(
             r#"
             INSERT INTO tokens ( id, address, symbol, decimals )
@@ -39,13 +40,112 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
             token.symbol,
             i16::from(token.decimals),
         )
-        .execute(self.0.conn())
+        .execute(transaction.conn())
         .await?;
+        transaction.tokens_schema().bump_tokens_version().await?;
+        transaction.commit().await?;
 
         metrics::histogram!("sql.token.store_token", start.elapsed());
         Ok(())
     }
 
+    /// Updates the symbol and decimals of the stored token.
+    /// Returns `false` if there is no token with such ID.
+    pub async fn update_token_metadata(
+        &mut self,
+        token_id: TokenId,
+        symbol: &str,
+        decimals: u8,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let updated = sqlx::query!(
+            r#"
+            UPDATE tokens SET symbol = $2, decimals = $3
+            WHERE id = $1
+            "#,
+            i32::from(*token_id),
+            symbol,
+            i16::from(decimals),
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+        transaction.tokens_schema().bump_tokens_version().await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.token.update_token_metadata", start.elapsed());
+        Ok(updated > 0)
+    }
+
+    /// Returns the operator-controlled settings of the token.
+    /// Tokens that were never configured have the default settings.
+    pub async fn get_token_flags(&mut self, token_id: TokenId) -> QueryResult<TokenFlags> {
+        let start = Instant::now();
+        let flags = sqlx::query_as!(
+            DbTokenFlags,
+            r#"
+            SELECT * FROM token_flags
+            WHERE token_id = $1
+            "#,
+            i32::from(*token_id)
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.get_token_flags", start.elapsed());
+        Ok(flags.map(TokenFlags::from).unwrap_or_default())
+    }
+
+    /// Updates the operator-controlled settings of the token.
+    pub async fn store_token_flags(
+        &mut self,
+        token_id: TokenId,
+        flags: TokenFlags,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO token_flags ( token_id, disabled, fee_eligible )
+            VALUES ( $1, $2, $3 )
+            ON CONFLICT (token_id)
+            DO
+              UPDATE SET disabled = $2, fee_eligible = $3
+            "#,
+            i32::from(*token_id),
+            flags.disabled,
+            flags.fee_eligible,
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.tokens_schema().bump_tokens_version().await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.token.store_token_flags", start.elapsed());
+        Ok(())
+    }
+
+    /// Returns the version of the token data, which is incremented every time the tokens
+    /// or their flags are changed. Used by the token caches to detect the outdated entries.
+    pub async fn load_tokens_version(&mut self) -> QueryResult<u64> {
+        let start = Instant::now();
+        let version = sqlx::query!("SELECT version FROM tokens_version")
+            .fetch_one(self.0.conn())
+            .await?
+            .version;
+
+        metrics::histogram!("sql.token.load_tokens_version", start.elapsed());
+        Ok(version as u64)
+    }
+
+    async fn bump_tokens_version(&mut self) -> QueryResult<()> {
+        sqlx::query!("UPDATE tokens_version SET version = version + 1")
+            .execute(self.0.conn())
+            .await?;
+        Ok(())
+    }
+
     /// Loads all the stored tokens from the database.
     /// Alongside with the tokens added via `store_token` method, the default `ETH` token
     /// is returned.
@@ -270,4 +370,67 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         metrics::histogram!("sql.token.update_historical_ticker_price", start.elapsed());
         Ok(())
     }
+
+    /// Loads the calibrated withdrawal gas costs of all the tokens.
+    pub async fn load_withdrawal_gas_costs(&mut self) -> QueryResult<Vec<WithdrawalGasCost>> {
+        let start = Instant::now();
+        let records = sqlx::query_as!(
+            DbWithdrawalGasCost,
+            "SELECT * FROM withdrawal_gas_costs ORDER BY token_id ASC"
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.load_withdrawal_gas_costs", start.elapsed());
+        Ok(records.into_iter().map(WithdrawalGasCost::from).collect())
+    }
+
+    /// Returns the calibrated withdrawal gas cost of the token, if any withdrawals
+    /// of the token were measured.
+    pub async fn get_withdrawal_gas_cost(
+        &mut self,
+        token_id: TokenId,
+    ) -> QueryResult<Option<WithdrawalGasCost>> {
+        let start = Instant::now();
+        let record = sqlx::query_as!(
+            DbWithdrawalGasCost,
+            "SELECT * FROM withdrawal_gas_costs WHERE token_id = $1",
+            i32::from(*token_id)
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.get_withdrawal_gas_cost", start.elapsed());
+        Ok(record.map(WithdrawalGasCost::from))
+    }
+
+    /// Stores the calibrated withdrawal gas costs.
+    pub async fn store_withdrawal_gas_costs(
+        &mut self,
+        costs: &[WithdrawalGasCost],
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        for cost in costs {
+            sqlx::query!(
+                r#"
+                INSERT INTO withdrawal_gas_costs ( token_id, gas_cost, samples, updated_at )
+                VALUES ( $1, $2, $3, $4 )
+                ON CONFLICT (token_id)
+                DO
+                  UPDATE SET gas_cost = $2, samples = $3, updated_at = $4
+                "#,
+                i32::from(*cost.token_id),
+                cost.gas_cost as i64,
+                cost.samples as i64,
+                cost.updated_at,
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.token.store_withdrawal_gas_costs", start.elapsed());
+        Ok(())
+    }
 }
@@ -5,8 +5,8 @@ use sqlx::{types::BigDecimal, FromRow};
 // Local imports
 use crate::utils::{address_to_stored_string, stored_str_address_to_address};
 use chrono::{DateTime, Utc};
-use zksync_types::tokens::{TokenMarketVolume, TokenPrice};
-use zksync_types::{Token, TokenId};
+use zksync_types::tokens::{TokenFlags, TokenMarketVolume, TokenPrice};
+use zksync_types::{withdrawal_gas::WithdrawalGasCost, Token, TokenId};
 use zksync_utils::big_decimal_to_ratio;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, FromRow)]
@@ -71,3 +71,38 @@ impl From<DBMarketVolume> for TokenMarketVolume {
         }
     }
 }
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbTokenFlags {
+    pub token_id: i32,
+    pub disabled: bool,
+    pub fee_eligible: bool,
+}
+
+impl From<DbTokenFlags> for TokenFlags {
+    fn from(val: DbTokenFlags) -> Self {
+        Self {
+            disabled: val.disabled,
+            fee_eligible: val.fee_eligible,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DbWithdrawalGasCost {
+    pub token_id: i32,
+    pub gas_cost: i64,
+    pub samples: i64,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<DbWithdrawalGasCost> for WithdrawalGasCost {
+    fn from(val: DbWithdrawalGasCost) -> Self {
+        Self {
+            token_id: TokenId(val.token_id as u16),
+            gas_cost: val.gas_cost as u64,
+            samples: val.samples as u64,
+            updated_at: val.updated_at,
+        }
+    }
+}
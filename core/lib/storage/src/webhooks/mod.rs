@@ -0,0 +1,261 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::{DateTime, Utc};
+use sqlx::Done;
+// Workspace imports
+use zksync_types::{
+    event::EventOffset,
+    webhooks::{
+        WebhookDelivery, WebhookDeliveryStatus, WebhookEventType, WebhookNotification,
+        WebhookSubscription, WebhookSubscriptionId,
+    },
+    Address,
+};
+// Local imports
+use crate::{utils::address_to_stored_string, QueryResult, StorageProcessor};
+
+pub mod records;
+
+use records::{PendingWebhookDelivery, StoredWebhookDelivery, StoredWebhookSubscription};
+
+/// Webhooks schema handles the webhook subscriptions, the deliveries of the notifications
+/// and the progress of the webhook dispatcher in the event log.
+#[derive(Debug)]
+pub struct WebhooksSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> WebhooksSchema<'a, 'c> {
+    /// Subscribes the URL to the notifications of the given type.
+    /// The subscription may belong to an API namespace.
+    pub async fn add_subscription(
+        &mut self,
+        url: &str,
+        event_type: WebhookEventType,
+        address: Option<Address>,
+        secret: &str,
+        namespace: Option<&str>,
+    ) -> QueryResult<WebhookSubscription> {
+        let start = Instant::now();
+        let subscription = sqlx::query_as!(
+            StoredWebhookSubscription,
+            r#"
+            INSERT INTO webhook_subscriptions ( url, event_type, address, secret, created_at, namespace )
+            VALUES ( $1, $2, $3, $4, now(), $5 )
+            RETURNING *
+            "#,
+            url,
+            event_type.as_str(),
+            address.as_ref().map(address_to_stored_string),
+            secret,
+            namespace,
+        )
+        .fetch_one(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.webhooks.add_subscription", start.elapsed());
+        Ok(subscription.into())
+    }
+
+    /// Loads all the webhook subscriptions.
+    pub async fn load_subscriptions(&mut self) -> QueryResult<Vec<WebhookSubscription>> {
+        let start = Instant::now();
+        let subscriptions = sqlx::query_as!(
+            StoredWebhookSubscription,
+            "SELECT * FROM webhook_subscriptions ORDER BY id ASC"
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(WebhookSubscription::from)
+        .collect();
+
+        metrics::histogram!("sql.webhooks.load_subscriptions", start.elapsed());
+        Ok(subscriptions)
+    }
+
+    /// Removes the subscription along with its deliveries.
+    /// Returns `false` if there is no such subscription.
+    pub async fn remove_subscription(&mut self, id: WebhookSubscriptionId) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!("DELETE FROM webhook_subscriptions WHERE id = $1", id)
+            .execute(self.0.conn())
+            .await?;
+
+        metrics::histogram!("sql.webhooks.remove_subscription", start.elapsed());
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Loads the latest deliveries of the subscription, newest first.
+    pub async fn load_deliveries(
+        &mut self,
+        subscription_id: WebhookSubscriptionId,
+        limit: u32,
+    ) -> QueryResult<Vec<WebhookDelivery>> {
+        let start = Instant::now();
+        let deliveries = sqlx::query_as!(
+            StoredWebhookDelivery,
+            "SELECT * FROM webhook_deliveries WHERE subscription_id = $1 ORDER BY id DESC LIMIT $2",
+            subscription_id,
+            i64::from(limit),
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(WebhookDelivery::from)
+        .collect();
+
+        metrics::histogram!("sql.webhooks.load_deliveries", start.elapsed());
+        Ok(deliveries)
+    }
+
+    /// Returns the offset of the next event to be processed by the dispatcher.
+    /// Returns `None` if the dispatcher has never processed any events.
+    pub async fn get_dispatcher_offset(&mut self) -> QueryResult<Option<EventOffset>> {
+        let start = Instant::now();
+        let offset = sqlx::query!("SELECT next_event_offset FROM webhook_dispatcher_state")
+            .fetch_optional(self.0.conn())
+            .await?
+            .map(|row| row.next_event_offset as EventOffset);
+
+        metrics::histogram!("sql.webhooks.get_dispatcher_offset", start.elapsed());
+        Ok(offset)
+    }
+
+    /// Stores the notifications derived from the processed events and moves the dispatcher
+    /// to the next event atomically, so every event is handled exactly once.
+    pub async fn enqueue_deliveries(
+        &mut self,
+        next_event_offset: EventOffset,
+        deliveries: &[(WebhookSubscriptionId, EventOffset, WebhookNotification)],
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        for (subscription_id, event_offset, payload) in deliveries {
+            sqlx::query!(
+                r#"
+                INSERT INTO webhook_deliveries ( subscription_id, event_offset, payload, status, attempts, next_attempt_at, created_at )
+                VALUES ( $1, $2, $3, $4, 0, now(), now() )
+                "#,
+                subscription_id,
+                *event_offset as i64,
+                serde_json::to_value(payload)?,
+                WebhookDeliveryStatus::Pending.as_str(),
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO webhook_dispatcher_state ( id, next_event_offset )
+            VALUES ( true, $1 )
+            ON CONFLICT (id) DO UPDATE SET next_event_offset = $1
+            "#,
+            next_event_offset as i64,
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.webhooks.enqueue_deliveries", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the pending deliveries which next attempt is due, oldest first.
+    pub async fn load_due_deliveries(
+        &mut self,
+        limit: u32,
+    ) -> QueryResult<Vec<PendingWebhookDelivery>> {
+        let start = Instant::now();
+        let deliveries = sqlx::query!(
+            r#"
+            SELECT webhook_deliveries.*, webhook_subscriptions.url, webhook_subscriptions.secret
+            FROM webhook_deliveries
+            INNER JOIN webhook_subscriptions
+                ON webhook_subscriptions.id = webhook_deliveries.subscription_id
+            WHERE webhook_deliveries.status = $1 AND webhook_deliveries.next_attempt_at <= now()
+            ORDER BY webhook_deliveries.next_attempt_at ASC
+            LIMIT $2
+            "#,
+            WebhookDeliveryStatus::Pending.as_str(),
+            i64::from(limit),
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| PendingWebhookDelivery {
+            delivery: StoredWebhookDelivery {
+                id: row.id,
+                subscription_id: row.subscription_id,
+                event_offset: row.event_offset,
+                payload: row.payload,
+                status: row.status,
+                attempts: row.attempts,
+                last_error: row.last_error,
+                next_attempt_at: row.next_attempt_at,
+                created_at: row.created_at,
+                delivered_at: row.delivered_at,
+            }
+            .into(),
+            url: row.url,
+            secret: row.secret,
+        })
+        .collect();
+
+        metrics::histogram!("sql.webhooks.load_due_deliveries", start.elapsed());
+        Ok(deliveries)
+    }
+
+    /// Marks the delivery as successfully sent.
+    pub async fn mark_delivered(&mut self, id: i64) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = $2, attempts = attempts + 1, last_error = NULL, delivered_at = now()
+            WHERE id = $1
+            "#,
+            id,
+            WebhookDeliveryStatus::Delivered.as_str(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.webhooks.mark_delivered", start.elapsed());
+        Ok(())
+    }
+
+    /// Records the failed delivery attempt. If `next_attempt_at` is `None`, the delivery
+    /// won't be retried anymore.
+    pub async fn record_failed_attempt(
+        &mut self,
+        id: i64,
+        error: &str,
+        next_attempt_at: Option<DateTime<Utc>>,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let status = if next_attempt_at.is_some() {
+            WebhookDeliveryStatus::Pending
+        } else {
+            WebhookDeliveryStatus::Failed
+        };
+        sqlx::query!(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = $2, attempts = attempts + 1, last_error = $3,
+                next_attempt_at = COALESCE($4, next_attempt_at)
+            WHERE id = $1
+            "#,
+            id,
+            status.as_str(),
+            error,
+            next_attempt_at,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.webhooks.record_failed_attempt", start.elapsed());
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_types::webhooks::{WebhookDelivery, WebhookSubscription};
+// Local imports
+use crate::utils::stored_str_address_to_address;
+
+#[derive(Debug, Clone)]
+pub struct StoredWebhookSubscription {
+    pub id: i64,
+    pub url: String,
+    pub event_type: String,
+    pub address: Option<String>,
+    pub secret: String,
+    pub created_at: DateTime<Utc>,
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StoredWebhookDelivery {
+    pub id: i64,
+    pub subscription_id: i64,
+    pub event_offset: i64,
+    pub payload: serde_json::Value,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+/// Delivery which should be attempted, along with the subscription details.
+#[derive(Debug, Clone)]
+pub struct PendingWebhookDelivery {
+    pub delivery: WebhookDelivery,
+    pub url: String,
+    pub secret: String,
+}
+
+impl From<StoredWebhookSubscription> for WebhookSubscription {
+    fn from(stored: StoredWebhookSubscription) -> Self {
+        Self {
+            id: stored.id,
+            url: stored.url,
+            event_type: stored
+                .event_type
+                .parse()
+                .expect("Invalid webhook event type has been stored"),
+            address: stored.address.as_deref().map(stored_str_address_to_address),
+            namespace: stored.namespace,
+            created_at: stored.created_at,
+        }
+    }
+}
+
+impl From<StoredWebhookDelivery> for WebhookDelivery {
+    fn from(stored: StoredWebhookDelivery) -> Self {
+        Self {
+            id: stored.id,
+            subscription_id: stored.subscription_id,
+            event_offset: stored.event_offset as u64,
+            payload: serde_json::from_value(stored.payload)
+                .expect("Invalid webhook payload has been stored"),
+            status: stored
+                .status
+                .parse()
+                .expect("Invalid webhook delivery status has been stored"),
+            attempts: stored.attempts as u32,
+            last_error: stored.last_error,
+            next_attempt_at: stored.next_attempt_at,
+            created_at: stored.created_at,
+            delivered_at: stored.delivered_at,
+        }
+    }
+}
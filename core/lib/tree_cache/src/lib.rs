@@ -0,0 +1,178 @@
+//! Storage of the account tree caches.
+//!
+//! The account tree cache contains the intermediate nodes and hashes of the account tree,
+//! so the tree doesn't have to be rehashed from scratch on restart. By default the caches are
+//! stored in Postgres as JSON. For the large states they take gigabytes, so they can be stored
+//! in an embedded RocksDB database on the local disk instead: the caches are compressed, and
+//! only the configured block cache is held in memory.
+//!
+//! Every component keeps its caches in its own RocksDB database, since the RocksDB database
+//! can't be opened by several processes at once. The caches stored in Postgres are moved
+//! to RocksDB with the `tree_cache_migrator` tool.
+//!
+//! RocksDB also keeps the accounts of the latest tree, so the state keeper restores the whole
+//! tree from RocksDB instead of loading the state from Postgres and rebuilding the tree from it.
+//! Only the configured amount of the latest caches is kept in either storage.
+
+// Built-in deps
+use std::{path::Path, sync::Arc};
+// Workspace deps
+use zksync_config::configs::database::{DBConfig, TreeCacheBackend};
+use zksync_storage::StorageProcessor;
+use zksync_types::{Account, AccountId, BlockNumber};
+// Local deps
+pub use crate::rocksdb_cache::RocksDbTreeCache;
+
+mod rocksdb_cache;
+
+/// Component storing the caches of the state keeper's account tree.
+pub const STATE_KEEPER: &str = "state_keeper";
+/// Component storing the caches of the witness generator's account tree.
+pub const WITNESS_GENERATOR: &str = "witness_generator";
+/// All the components storing the account tree caches.
+pub const COMPONENTS: &[&str] = &[STATE_KEEPER, WITNESS_GENERATOR];
+
+#[derive(Debug, Clone)]
+enum Backend {
+    Postgres,
+    RocksDb(Arc<RocksDbTreeCache>),
+}
+
+/// Storage of the account tree caches of a component.
+#[derive(Debug, Clone)]
+pub struct TreeCacheStorage {
+    backend: Backend,
+    /// Amount of the latest caches kept in the storage, `0` means all of them are kept.
+    keep_blocks: usize,
+}
+
+impl TreeCacheStorage {
+    /// Opens the storage of the account tree caches of the component.
+    pub fn open(config: &DBConfig, component: &str) -> anyhow::Result<Self> {
+        let backend = match config.tree_cache_backend {
+            TreeCacheBackend::Postgres => Backend::Postgres,
+            TreeCacheBackend::RocksDb => {
+                let path = Path::new(&config.tree_cache_path).join(component);
+                let cache = RocksDbTreeCache::open(path, config.tree_cache_size_mb)?;
+                Backend::RocksDb(Arc::new(cache))
+            }
+        };
+        Ok(Self {
+            backend,
+            keep_blocks: config.tree_cache_keep_blocks,
+        })
+    }
+
+    /// Whether the storage keeps the accounts of the tree along with the cache.
+    pub fn stores_accounts(&self) -> bool {
+        matches!(self.backend, Backend::RocksDb(_))
+    }
+
+    /// Loads the latest stored cache along with the number of the block it corresponds to.
+    pub async fn load_latest(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Option<(BlockNumber, serde_json::Value)>> {
+        match &self.backend {
+            Backend::Postgres => {
+                storage
+                    .chain()
+                    .block_schema()
+                    .get_account_tree_cache()
+                    .await
+            }
+            Backend::RocksDb(cache) => cache.load_latest(),
+        }
+    }
+
+    /// Loads the cache of the account tree at the given block.
+    pub async fn load(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        block: BlockNumber,
+    ) -> anyhow::Result<Option<serde_json::Value>> {
+        match &self.backend {
+            Backend::Postgres => {
+                storage
+                    .chain()
+                    .block_schema()
+                    .get_account_tree_cache_block(block)
+                    .await
+            }
+            Backend::RocksDb(cache) => cache.load(block),
+        }
+    }
+
+    /// Stores the cache of the account tree at the given block, removing the caches older than
+    /// the kept ones.
+    pub async fn store(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        block: BlockNumber,
+        tree_cache: serde_json::Value,
+    ) -> anyhow::Result<()> {
+        match &self.backend {
+            Backend::Postgres => {
+                let mut block_schema = storage.chain().block_schema();
+                block_schema
+                    .store_account_tree_cache(block, tree_cache)
+                    .await?;
+                if self.keep_blocks > 0 {
+                    block_schema
+                        .remove_old_account_tree_caches(self.keep_blocks)
+                        .await?;
+                }
+                Ok(())
+            }
+            Backend::RocksDb(cache) => {
+                cache.store(block, &tree_cache)?;
+                if self.keep_blocks > 0 {
+                    cache.prune(self.keep_blocks)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Stores the accounts of the account tree at the given block, if the storage keeps them.
+    /// The cache of the tree at the block must be stored as well.
+    pub fn store_accounts<'a>(
+        &self,
+        block: BlockNumber,
+        accounts: impl IntoIterator<Item = (AccountId, &'a Account)>,
+    ) -> anyhow::Result<()> {
+        match &self.backend {
+            Backend::Postgres => Ok(()),
+            Backend::RocksDb(cache) => cache.store_accounts(block, accounts),
+        }
+    }
+
+    /// Loads the accounts of the account tree at the given block, if they're stored.
+    pub fn load_accounts(
+        &self,
+        block: BlockNumber,
+    ) -> anyhow::Result<Option<Vec<(AccountId, Account)>>> {
+        match &self.backend {
+            Backend::Postgres => Ok(None),
+            Backend::RocksDb(cache) => cache.load_accounts(block),
+        }
+    }
+
+    /// Removes the caches of the blocks after the given one, e.g. when the blocks are reverted.
+    pub async fn remove_after(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        last_block: BlockNumber,
+    ) -> anyhow::Result<()> {
+        match &self.backend {
+            Backend::Postgres => {
+                storage
+                    .chain()
+                    .block_schema()
+                    .remove_account_tree_cache(last_block)
+                    .await
+            }
+            Backend::RocksDb(cache) => cache.remove_after(last_block),
+        }
+    }
+}
@@ -0,0 +1,300 @@
+// Built-in deps
+use std::{convert::TryInto, path::Path, time::Instant};
+// External deps
+use rocksdb::{
+    BlockBasedOptions, Cache, ColumnFamily, DBCompressionType, Direction, IteratorMode, Options,
+    WriteBatch, DB,
+};
+// Workspace deps
+use zksync_types::{Account, AccountId, BlockNumber};
+
+const BYTES_PER_MB: usize = 1 << 20;
+/// Column family with the accounts of the tree, keyed by the block number and the account ID.
+const ACCOUNTS_CF: &str = "accounts";
+
+/// Account tree caches stored in the embedded RocksDB database.
+/// The caches are keyed by the big-endian block numbers, so they are ordered by the block.
+/// Along with the cache, the accounts of the tree at the block may be stored, so the whole tree
+/// is restored from RocksDB without loading the state from Postgres.
+pub struct RocksDbTreeCache {
+    db: DB,
+}
+
+impl std::fmt::Debug for RocksDbTreeCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RocksDbTreeCache")
+            .field("path", &self.db.path())
+            .finish()
+    }
+}
+
+impl RocksDbTreeCache {
+    /// Opens the database, creating it if it doesn't exist.
+    /// The size of the block cache held in memory is given in megabytes.
+    pub fn open(path: impl AsRef<Path>, cache_size_mb: usize) -> anyhow::Result<Self> {
+        let mut block_options = BlockBasedOptions::default();
+        block_options.set_block_cache(&Cache::new_lru_cache(cache_size_mb * BYTES_PER_MB)?);
+
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        options.create_missing_column_families(true);
+        options.set_compression_type(DBCompressionType::Lz4);
+        options.set_block_based_table_factory(&block_options);
+
+        let db = DB::open_cf(&options, path, &["default", ACCOUNTS_CF])?;
+        Ok(Self { db })
+    }
+
+    fn accounts_cf(&self) -> &ColumnFamily {
+        self.db
+            .cf_handle(ACCOUNTS_CF)
+            .expect("Accounts column family is created on open")
+    }
+
+    fn key(block: BlockNumber) -> [u8; 4] {
+        block.0.to_be_bytes()
+    }
+
+    fn account_key(block: BlockNumber, account_id: AccountId) -> [u8; 8] {
+        let mut key = [0u8; 8];
+        key[..4].copy_from_slice(&Self::key(block));
+        key[4..].copy_from_slice(&account_id.0.to_be_bytes());
+        key
+    }
+
+    fn block_from_key(key: &[u8]) -> anyhow::Result<BlockNumber> {
+        let key = key
+            .try_into()
+            .map_err(|_| anyhow::format_err!("Invalid account tree cache key: {:?}", key))?;
+        Ok(BlockNumber(u32::from_be_bytes(key)))
+    }
+
+    pub fn load_latest(&self) -> anyhow::Result<Option<(BlockNumber, serde_json::Value)>> {
+        let start = Instant::now();
+        let tree_cache = match self.db.iterator(IteratorMode::End).next() {
+            Some((key, value)) => {
+                Some((Self::block_from_key(&key)?, serde_json::from_slice(&value)?))
+            }
+            None => None,
+        };
+
+        metrics::histogram!("tree_cache.rocksdb.load_latest", start.elapsed());
+        Ok(tree_cache)
+    }
+
+    pub fn load(&self, block: BlockNumber) -> anyhow::Result<Option<serde_json::Value>> {
+        let start = Instant::now();
+        let tree_cache = match self.db.get(Self::key(block))? {
+            Some(value) => Some(serde_json::from_slice(&value)?),
+            None => None,
+        };
+
+        metrics::histogram!("tree_cache.rocksdb.load", start.elapsed());
+        Ok(tree_cache)
+    }
+
+    pub fn store(&self, block: BlockNumber, tree_cache: &serde_json::Value) -> anyhow::Result<()> {
+        let start = Instant::now();
+        self.db
+            .put(Self::key(block), serde_json::to_vec(tree_cache)?)?;
+
+        metrics::histogram!("tree_cache.rocksdb.store", start.elapsed());
+        Ok(())
+    }
+
+    /// Stores the accounts of the tree at the block, replacing the previously stored ones.
+    pub fn store_accounts<'a>(
+        &self,
+        block: BlockNumber,
+        accounts: impl IntoIterator<Item = (AccountId, &'a Account)>,
+    ) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let mut batch = WriteBatch::default();
+        self.delete_accounts(&mut batch, block);
+        for (account_id, account) in accounts {
+            batch.put_cf(
+                self.accounts_cf(),
+                Self::account_key(block, account_id),
+                serde_json::to_vec(account)?,
+            );
+        }
+        self.db.write(batch)?;
+
+        metrics::histogram!("tree_cache.rocksdb.store_accounts", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the accounts of the tree at the block, if they're stored.
+    pub fn load_accounts(
+        &self,
+        block: BlockNumber,
+    ) -> anyhow::Result<Option<Vec<(AccountId, Account)>>> {
+        let start = Instant::now();
+        let prefix = Self::key(block);
+        let mut accounts = Vec::new();
+        for (key, value) in self.db.iterator_cf(
+            self.accounts_cf(),
+            IteratorMode::From(&prefix, Direction::Forward),
+        ) {
+            if !key.starts_with(&prefix) {
+                break;
+            }
+            let account_id = key[prefix.len()..].try_into().map_err(|_| {
+                anyhow::format_err!("Invalid account tree cache account key: {:?}", key)
+            })?;
+            accounts.push((
+                AccountId(u32::from_be_bytes(account_id)),
+                serde_json::from_slice(&value)?,
+            ));
+        }
+
+        metrics::histogram!("tree_cache.rocksdb.load_accounts", start.elapsed());
+        // The tree always has the fee account, so there are no stored accounts if it's empty.
+        Ok(if accounts.is_empty() {
+            None
+        } else {
+            Some(accounts)
+        })
+    }
+
+    fn delete_accounts(&self, batch: &mut WriteBatch, block: BlockNumber) {
+        let prefix = Self::key(block);
+        for (key, _) in self.db.iterator_cf(
+            self.accounts_cf(),
+            IteratorMode::From(&prefix, Direction::Forward),
+        ) {
+            if !key.starts_with(&prefix) {
+                break;
+            }
+            batch.delete_cf(self.accounts_cf(), key);
+        }
+    }
+
+    fn delete_blocks(&self, blocks: &[BlockNumber]) -> anyhow::Result<()> {
+        let mut batch = WriteBatch::default();
+        for &block in blocks {
+            batch.delete(Self::key(block));
+            self.delete_accounts(&mut batch, block);
+        }
+        self.db.write(batch)?;
+        Ok(())
+    }
+
+    /// Removes the caches of the blocks after the given one.
+    pub fn remove_after(&self, last_block: BlockNumber) -> anyhow::Result<()> {
+        let first_removed = Self::key(last_block + 1);
+        let blocks = self
+            .db
+            .iterator(IteratorMode::From(&first_removed, Direction::Forward))
+            .map(|(key, _)| Self::block_from_key(&key))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        self.delete_blocks(&blocks)
+    }
+
+    /// Removes all the caches except for the `keep` latest ones.
+    pub fn prune(&self, keep: usize) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let blocks = self
+            .db
+            .iterator(IteratorMode::End)
+            .skip(keep)
+            .map(|(key, _)| Self::block_from_key(&key))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        self.delete_blocks(&blocks)?;
+
+        metrics::histogram!("tree_cache.rocksdb.prune", start.elapsed());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn store_and_load_tree_caches() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = RocksDbTreeCache::open(dir.path(), 1).unwrap();
+        assert!(cache.load_latest().unwrap().is_none());
+
+        for block in vec![1, 2, 256, 300] {
+            cache
+                .store(BlockNumber(block), &json!({ "block": block }))
+                .unwrap();
+        }
+        assert_eq!(
+            cache.load(BlockNumber(2)).unwrap(),
+            Some(json!({ "block": 2 }))
+        );
+        assert_eq!(cache.load(BlockNumber(3)).unwrap(), None);
+        // The caches are ordered by the block number, not by the bytes of the little-endian number.
+        assert_eq!(
+            cache.load_latest().unwrap(),
+            Some((BlockNumber(300), json!({ "block": 300 })))
+        );
+
+        let fee_account = Account::default_with_address(&Default::default());
+        cache
+            .store_accounts(BlockNumber(256), vec![(AccountId(0), &fee_account)])
+            .unwrap();
+        assert_eq!(
+            cache
+                .load_accounts(BlockNumber(256))
+                .unwrap()
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(cache.load_accounts(BlockNumber(2)).unwrap().is_none());
+
+        cache.remove_after(BlockNumber(2)).unwrap();
+        assert!(cache.load_accounts(BlockNumber(256)).unwrap().is_none());
+        assert_eq!(
+            cache.load_latest().unwrap(),
+            Some((BlockNumber(2), json!({ "block": 2 })))
+        );
+        assert_eq!(cache.load(BlockNumber(256)).unwrap(), None);
+
+        // The caches persist after the database is reopened.
+        drop(cache);
+        let cache = RocksDbTreeCache::open(dir.path(), 1).unwrap();
+        assert_eq!(
+            cache.load(BlockNumber(1)).unwrap(),
+            Some(json!({ "block": 1 }))
+        );
+    }
+
+    #[test]
+    fn prune_tree_caches() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = RocksDbTreeCache::open(dir.path(), 1).unwrap();
+        let account = Account::default_with_address(&Default::default());
+        for block in 1..=5 {
+            cache
+                .store(BlockNumber(block), &json!({ "block": block }))
+                .unwrap();
+            cache
+                .store_accounts(BlockNumber(block), vec![(AccountId(block), &account)])
+                .unwrap();
+        }
+
+        cache.prune(2).unwrap();
+        for block in 1..=3 {
+            assert_eq!(cache.load(BlockNumber(block)).unwrap(), None);
+            assert!(cache.load_accounts(BlockNumber(block)).unwrap().is_none());
+        }
+        for block in 4..=5 {
+            assert!(cache.load(BlockNumber(block)).unwrap().is_some());
+            let accounts = cache.load_accounts(BlockNumber(block)).unwrap().unwrap();
+            assert_eq!(accounts[0].0, AccountId(block));
+        }
+
+        // Pruning with fewer caches than kept doesn't remove anything.
+        cache.prune(16).unwrap();
+        assert_eq!(
+            cache.load_latest().unwrap(),
+            Some((BlockNumber(5), json!({ "block": 5 })))
+        );
+    }
+}
@@ -132,10 +132,57 @@ fn smt_root_hash_cached(b: &mut Bencher<'_>) {
     );
 }
 
+/// Measures the time of filling an empty SMT and obtaining its root hash,
+/// with the leaves inserted one by one.
+fn smt_fill_sequential(b: &mut Bencher<'_>) {
+    let depth = zksync_crypto::params::account_tree_depth();
+    let accounts: Vec<_> = (0..N_ACCOUNTS).map(gen_account).collect();
+    let tree = RealSMT::new(depth);
+
+    let setup = || (tree.clone(), accounts.clone());
+
+    b.iter_batched(
+        setup,
+        |(mut tree, accounts)| {
+            for (id, account) in accounts.into_iter().enumerate() {
+                tree.insert(id as u32, account);
+            }
+            let _hash = black_box(tree.root_hash());
+        },
+        BatchSize::SmallInput,
+    );
+}
+
+/// Measures the time of filling an empty SMT and obtaining its root hash,
+/// with the leaves inserted in one batch.
+fn smt_fill_batch(b: &mut Bencher<'_>) {
+    let depth = zksync_crypto::params::account_tree_depth();
+    let accounts: Vec<_> = (0..N_ACCOUNTS).map(gen_account).collect();
+    let tree = RealSMT::new(depth);
+
+    let setup = || (tree.clone(), accounts.clone());
+
+    b.iter_batched(
+        setup,
+        |(mut tree, accounts)| {
+            tree.insert_many(
+                accounts
+                    .into_iter()
+                    .enumerate()
+                    .map(|(id, account)| (id as u32, account)),
+            );
+            let _hash = black_box(tree.root_hash());
+        },
+        BatchSize::SmallInput,
+    );
+}
+
 pub fn bench_merkle_tree(c: &mut Criterion) {
     c.bench_function("Parallel SMT create", smt_create);
     c.bench_function("Parallel SMT insert (empty)", smt_insert_empty);
     c.bench_function("Parallel SMT insert (filled)", smt_insert_filled);
     c.bench_function("Parallel SMT root hash", smt_root_hash);
     c.bench_function("Parallel SMT root hash (cached)", smt_root_hash_cached);
+    c.bench_function("Parallel SMT fill (sequential)", smt_fill_sequential);
+    c.bench_function("Parallel SMT fill (batch)", smt_fill_batch);
 }
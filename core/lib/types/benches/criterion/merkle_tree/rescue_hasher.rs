@@ -1,11 +1,15 @@
 //! Benchmarks for the Parallel Sparse Merkle Tree.
 
 use criterion::{black_box, BatchSize, Bencher, Criterion, Throughput};
-use zksync_crypto::merkle_tree::{hasher::Hasher, RescueHasher};
+use zksync_crypto::merkle_tree::{
+    hasher::{Hasher, HasherBackend},
+    RescueHasher,
+};
 use zksync_crypto::Engine;
 
 const SMALL_INPUT_SIZE: usize = 16; // 16 bits / 2 bytes
 const BIG_INPUT_SIZE: usize = zksync_crypto::params::MAX_CIRCUIT_MSG_HASH_BITS; // Biggest supported size.
+const BATCH_SIZE: usize = 256;
 
 /// Creates a boolean vector for `PedersonHasher` input.
 fn generate_input(size: usize) -> Vec<bool> {
@@ -48,6 +52,22 @@ fn rescue_big(b: &mut Bencher<'_>) {
     );
 }
 
+/// Measures the hashing time for a batch of account-sized inputs with the given backend.
+fn rescue_batch(b: &mut Bencher<'_>, backend: HasherBackend) {
+    let hasher = RescueHasher::<Engine>::default().with_backend(backend);
+    let inputs: Vec<Vec<bool>> = vec![generate_input(BIG_INPUT_SIZE); BATCH_SIZE];
+
+    let setup = || (hasher.clone(), inputs.clone());
+
+    b.iter_batched(
+        setup,
+        |(hasher, inputs)| {
+            let _ = hasher.hash_bits_batch(black_box(inputs));
+        },
+        BatchSize::SmallInput,
+    );
+}
+
 pub fn bench_rescue_hasher(c: &mut Criterion) {
     let mut small_input_group = c.benchmark_group("Small input");
     small_input_group.throughput(Throughput::Bytes((SMALL_INPUT_SIZE / 8) as u64));
@@ -58,4 +78,14 @@ pub fn bench_rescue_hasher(c: &mut Criterion) {
     big_input_group.throughput(Throughput::Bytes((BIG_INPUT_SIZE / 8) as u64));
     big_input_group.bench_function("Rescue Hasher", rescue_big);
     big_input_group.finish();
+
+    let mut batch_group = c.benchmark_group("Batch input");
+    batch_group.throughput(Throughput::Elements(BATCH_SIZE as u64));
+    batch_group.bench_function("Rescue Hasher (sequential)", |b| {
+        rescue_batch(b, HasherBackend::Sequential)
+    });
+    batch_group.bench_function("Rescue Hasher (parallel)", |b| {
+        rescue_batch(b, HasherBackend::default())
+    });
+    batch_group.finish();
 }
@@ -0,0 +1,164 @@
+//! Aliases of the accounts.
+//!
+//! Accounts can register short human-readable names, so the users can send funds to an alias
+//! instead of the full address. Aliases are maintained by the operator outside of the L2 state:
+//! the owner of the account registers the alias by signing a message with its Ethereum key,
+//! and the clients resolve the alias to the address via the API before signing the transaction.
+//! Each account has at most one alias, registering a new one releases the previous alias.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zksync_basic_types::Address;
+
+use crate::tx::{PackedEthSignature, SignatureDomain};
+
+pub const MIN_ALIAS_LENGTH: usize = 3;
+pub const MAX_ALIAS_LENGTH: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum AccountAliasError {
+    #[error(
+        "Alias must be from {} to {} characters long",
+        MIN_ALIAS_LENGTH,
+        MAX_ALIAS_LENGTH
+    )]
+    InvalidLength,
+    #[error(
+        "Alias must start with a letter and contain only lowercase letters, digits, '-' and '_'"
+    )]
+    InvalidCharacters,
+}
+
+/// Checks that the alias is well-formed. Aliases can't be confused with the addresses,
+/// since they start with a letter and are shorter than the hex-encoded address.
+pub fn validate_alias(alias: &str) -> Result<(), AccountAliasError> {
+    if alias.len() < MIN_ALIAS_LENGTH || alias.len() > MAX_ALIAS_LENGTH {
+        return Err(AccountAliasError::InvalidLength);
+    }
+
+    let starts_with_letter = alias
+        .chars()
+        .next()
+        .map_or(false, |first| first.is_ascii_lowercase());
+    let valid_chars = alias
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if !starts_with_letter || !valid_chars {
+        return Err(AccountAliasError::InvalidCharacters);
+    }
+    Ok(())
+}
+
+/// Request to register the alias of the account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AliasRegistrationRequest {
+    pub alias: String,
+    pub address: Address,
+    /// Time the request was signed at, in milliseconds since the Unix epoch.
+    /// Must be greater than the timestamp of the previous registration of the account.
+    pub timestamp: u64,
+    /// Ethereum signature of the `AliasRegistrationRequest::message`.
+    pub signature: PackedEthSignature,
+}
+
+impl AliasRegistrationRequest {
+    /// Returns the message to be signed by the owner of the account, bound to the network
+    /// if the domain is required (see `SignatureDomain`).
+    pub fn message(
+        alias: &str,
+        address: Address,
+        timestamp: u64,
+        domain: Option<&SignatureDomain>,
+    ) -> String {
+        let message = format!(
+            "Register zkSync alias: {}\nAccount: {:?}\nTimestamp: {}",
+            alias, address, timestamp
+        );
+        SignatureDomain::apply_optional(domain, message)
+    }
+
+    /// Checks that the request is signed by the owner of the account.
+    pub fn verify(&self, domain: Option<&SignatureDomain>) -> bool {
+        self.signature
+            .signature_recover_signer(
+                Self::message(&self.alias, self.address, self.timestamp, domain).as_bytes(),
+            )
+            .map(|signer| signer == self.address)
+            .unwrap_or(false)
+    }
+}
+
+/// Alias registered by the account.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountAlias {
+    pub alias: String,
+    pub address: Address,
+    pub registered_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_basic_types::H256;
+
+    #[test]
+    fn alias_validation() {
+        for alias in &["alice", "bob-42", "a_b", "abcdefghijklmnopqrstuvwxyz012345"] {
+            assert_eq!(validate_alias(alias), Ok(()), "{}", alias);
+        }
+
+        assert_eq!(validate_alias("al"), Err(AccountAliasError::InvalidLength));
+        assert_eq!(
+            validate_alias(&"a".repeat(MAX_ALIAS_LENGTH + 1)),
+            Err(AccountAliasError::InvalidLength)
+        );
+        for alias in &["Alice", "42bob", "0xdead", "al ice", "-alice", "alicé"] {
+            assert_eq!(
+                validate_alias(alias),
+                Err(AccountAliasError::InvalidCharacters),
+                "{}",
+                alias
+            );
+        }
+    }
+
+    #[test]
+    fn registration_request_signature() {
+        let private_key = H256::repeat_byte(7);
+        let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let domain = SignatureDomain::new(1, Address::repeat_byte(9));
+        let signature = PackedEthSignature::sign(
+            &private_key,
+            AliasRegistrationRequest::message("alice", address, 1000, Some(&domain)).as_bytes(),
+        )
+        .unwrap();
+
+        let request = AliasRegistrationRequest {
+            alias: "alice".into(),
+            address,
+            timestamp: 1000,
+            signature,
+        };
+        assert!(request.verify(Some(&domain)));
+        // The signature is bound to the network.
+        assert!(!request.verify(None));
+
+        // The signature is bound to the alias.
+        let request = AliasRegistrationRequest {
+            alias: "bob".into(),
+            ..request
+        };
+        assert!(!request.verify(Some(&domain)));
+
+        // Only the owner of the account can register its alias.
+        let request = AliasRegistrationRequest {
+            alias: "alice".into(),
+            address: Address::repeat_byte(1),
+            ..request
+        };
+        assert!(!request.verify(Some(&domain)));
+    }
+}
@@ -0,0 +1,183 @@
+//! Prepaid account activations.
+//!
+//! Account which received funds can't move them until its signing key is set via `ChangePubKey`,
+//! and the `ChangePubKey` fee has to be paid in a token suitable for fees. Fresh recipients often
+//! have no such token, so the sender of the funds can prepay the activation: they sign a transfer
+//! to the recipient which fee covers both the transfer and the `ChangePubKey` of the recipient.
+//!
+//! The prepaid transfer is held by the server until the recipient submits `ChangePubKey` with
+//! zero fee. Then both transactions are sent to the mempool as a batch, so the recipient doesn't
+//! need any actions on the Ethereum side or any funds to pay for the activation.
+//!
+//! The prepaid transfer is held for at most `PREPAID_ACTIVATION_LIFETIME_DAYS`, or until the end of its
+//! time range if it's earlier. Since the funds are moved only once the transfer is executed, nothing
+//! has to be refunded once it expires. The payer can reclaim the prepaid fee before that by sending
+//! any transaction with the nonce of the held transfer: such transfer can't be executed anymore,
+//! so it's dropped and the activation of the recipient can be prepaid again.
+
+use std::convert::TryFrom;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use num::Zero;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zksync_basic_types::{AccountId, Address, Nonce};
+
+use crate::tx::{Transfer, TxEthSignature};
+
+/// Maximum number of days the prepaid transfer is held by the server.
+pub const PREPAID_ACTIVATION_LIFETIME_DAYS: i64 = 7;
+
+/// Transfer prepaying the activation of the recipient account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrepaidActivation {
+    /// Transfer to the recipient which fee covers its `ChangePubKey` as well.
+    pub fee_payment: Transfer,
+    /// Ethereum signature of the transfer. Not required for CREATE2 accounts.
+    pub eth_signature: Option<TxEthSignature>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum PrepaidActivationError {
+    #[error("Activation fee must be positive")]
+    ZeroFee,
+    #[error("Account can't prepay its own activation")]
+    SelfActivation,
+    #[error("Transfer is incorrect")]
+    IncorrectTransfer,
+}
+
+impl PrepaidActivation {
+    /// Account which activation is prepaid.
+    pub fn recipient(&self) -> Address {
+        self.fee_payment.to
+    }
+
+    /// Account which pays for the activation.
+    pub fn payer(&self) -> Address {
+        self.fee_payment.from
+    }
+
+    /// Returns the moment the activation prepaid at `created_at` expires.
+    pub fn expires_at(&self, created_at: DateTime<Utc>) -> DateTime<Utc> {
+        let max_expires_at = created_at + Duration::days(PREPAID_ACTIVATION_LIFETIME_DAYS);
+        let valid_until = i64::try_from(self.fee_payment.time_range.valid_until)
+            .ok()
+            .and_then(|timestamp| Utc.timestamp_opt(timestamp, 0).single());
+        match valid_until {
+            Some(valid_until) => std::cmp::min(valid_until, max_expires_at),
+            None => max_expires_at,
+        }
+    }
+
+    /// Checks the transfer data and its zkSync signature.
+    pub fn check_correctness(&mut self) -> Result<(), PrepaidActivationError> {
+        if self.fee_payment.fee.is_zero() {
+            return Err(PrepaidActivationError::ZeroFee);
+        }
+        if self.recipient() == self.payer() {
+            return Err(PrepaidActivationError::SelfActivation);
+        }
+        if !self.fee_payment.check_correctness() {
+            return Err(PrepaidActivationError::IncorrectTransfer);
+        }
+        Ok(())
+    }
+}
+
+/// Activation status of the account, telling the account owner what is required
+/// to start using it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivationHint {
+    pub address: Address,
+    /// Account ID to be signed in `ChangePubKey`, if the account already exists.
+    pub account_id: Option<AccountId>,
+    /// Nonce to be signed in `ChangePubKey`, if the account already exists.
+    pub nonce: Option<Nonce>,
+    /// Whether the signing key of the account is set.
+    pub activated: bool,
+    /// Account which prepaid the activation. If set, `ChangePubKey` can be submitted with zero fee.
+    pub prepaid_by: Option<Address>,
+    pub prepaid_at: Option<DateTime<Utc>>,
+    /// Moment the prepaid transfer is dropped unless the account is activated.
+    pub prepaid_until: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tx::TimeRange;
+    use zksync_basic_types::TokenId;
+
+    fn activation(from: Address, to: Address, fee: u64) -> PrepaidActivation {
+        activation_with_time_range(from, to, fee, TimeRange::default())
+    }
+
+    fn activation_with_time_range(
+        from: Address,
+        to: Address,
+        fee: u64,
+        time_range: TimeRange,
+    ) -> PrepaidActivation {
+        let fee_payment = Transfer::new(
+            AccountId(1),
+            from,
+            to,
+            TokenId(0),
+            0u64.into(),
+            fee.into(),
+            Nonce(0),
+            time_range,
+            None,
+        );
+        PrepaidActivation {
+            fee_payment,
+            eth_signature: None,
+        }
+    }
+
+    #[test]
+    fn prepaid_activation_correctness() {
+        let payer = Address::repeat_byte(1);
+        let recipient = Address::repeat_byte(2);
+
+        assert_eq!(
+            activation(payer, recipient, 0).check_correctness(),
+            Err(PrepaidActivationError::ZeroFee)
+        );
+        assert_eq!(
+            activation(payer, payer, 10).check_correctness(),
+            Err(PrepaidActivationError::SelfActivation)
+        );
+        // Transfer data is correct, but the transfer is not signed.
+        assert_eq!(
+            activation(payer, recipient, 10).check_correctness(),
+            Err(PrepaidActivationError::IncorrectTransfer)
+        );
+        let activation = activation(payer, recipient, 10);
+        assert_eq!(activation.recipient(), recipient);
+        assert_eq!(activation.payer(), payer);
+    }
+
+    #[test]
+    fn prepaid_activation_expiry() {
+        let payer = Address::repeat_byte(1);
+        let recipient = Address::repeat_byte(2);
+        let created_at = Utc.timestamp(1_600_000_000, 0);
+
+        // The transfer valid forever is held for the maximum lifetime.
+        assert_eq!(
+            activation(payer, recipient, 10).expires_at(created_at),
+            created_at + Duration::days(PREPAID_ACTIVATION_LIFETIME_DAYS)
+        );
+        // The transfer is not held after the end of its time range.
+        let valid_until = created_at + Duration::hours(1);
+        let time_range = TimeRange::new(0, valid_until.timestamp() as u64);
+        assert_eq!(
+            activation_with_time_range(payer, recipient, 10, time_range).expires_at(created_at),
+            valid_until
+        );
+    }
+}
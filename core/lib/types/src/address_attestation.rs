@@ -0,0 +1,164 @@
+//! Attestations of the Ethereum address ownership.
+//!
+//! Exchanges verifying the deposit addresses need a proof that the user controls the Ethereum
+//! address of the zkSync account. The server issues a single-use challenge for the address,
+//! the owner signs the challenge message with its Ethereum key, and the server records the
+//! attestation once the signature is correct. The challenge must be answered before it expires,
+//! incorrect signatures are rejected without changing the challenge, and the challenges that
+//! expired without the response are removed.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{AccountId, Address, H256};
+
+use crate::tx::{PackedEthSignature, SignatureDomain};
+
+/// Time for the owner of the address to answer the challenge, in seconds.
+pub const CHALLENGE_LIFETIME_SECS: i64 = 10 * 60;
+
+/// Max number of the challenges of the address waiting for the response at once.
+pub const MAX_PENDING_CHALLENGES: u32 = 5;
+
+/// Request to issue the challenge for the address.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttestationChallengeRequest {
+    pub address: Address,
+}
+
+/// Challenge to be signed by the owner of the address.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AttestationChallenge {
+    pub challenge: H256,
+    pub address: Address,
+    pub account_id: AccountId,
+    /// Message to be signed with the Ethereum key of the address.
+    pub message: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl AttestationChallenge {
+    pub fn new(
+        challenge: H256,
+        address: Address,
+        account_id: AccountId,
+        expires_at: DateTime<Utc>,
+        domain: Option<&SignatureDomain>,
+    ) -> Self {
+        Self {
+            challenge,
+            address,
+            account_id,
+            message: Self::message(challenge, address, account_id, domain),
+            expires_at,
+        }
+    }
+
+    /// Returns the message to be signed by the owner of the address, bound to the network
+    /// if the domain is required (see `SignatureDomain`).
+    pub fn message(
+        challenge: H256,
+        address: Address,
+        account_id: AccountId,
+        domain: Option<&SignatureDomain>,
+    ) -> String {
+        let message = format!(
+            "Confirm the ownership of the zkSync account\nAccount: {:?}\nAccount ID: {}\nChallenge: {:?}",
+            address, account_id, challenge
+        );
+        SignatureDomain::apply_optional(domain, message)
+    }
+}
+
+/// Signed response to the challenge.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttestationResponse {
+    pub challenge: H256,
+    /// Ethereum signature of the `AttestationChallenge::message`.
+    pub signature: PackedEthSignature,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AttestationStatus {
+    /// The challenge is waiting for the signed response.
+    Pending,
+    /// The challenge is signed by the owner of the address.
+    Verified,
+    /// The challenge wasn't answered in time.
+    Expired,
+}
+
+/// Attestation of the address ownership.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressAttestation {
+    pub challenge: H256,
+    pub address: Address,
+    pub account_id: AccountId,
+    pub status: AttestationStatus,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// Time the signed response was received at.
+    pub attested_at: Option<DateTime<Utc>>,
+}
+
+impl AddressAttestation {
+    /// Checks that the signature of the challenge message is made by the owner of the address.
+    pub fn verify(&self, signature: &PackedEthSignature, domain: Option<&SignatureDomain>) -> bool {
+        let message =
+            AttestationChallenge::message(self.challenge, self.address, self.account_id, domain);
+        signature
+            .signature_recover_signer(message.as_bytes())
+            .map(|signer| signer == self.address)
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attestation_signature() {
+        let private_key = H256::repeat_byte(7);
+        let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let domain = SignatureDomain::new(1, Address::repeat_byte(9));
+        let challenge = AttestationChallenge::new(
+            H256::repeat_byte(1),
+            address,
+            AccountId(5),
+            Utc::now(),
+            Some(&domain),
+        );
+        let signature =
+            PackedEthSignature::sign(&private_key, challenge.message.as_bytes()).unwrap();
+
+        let attestation = AddressAttestation {
+            challenge: challenge.challenge,
+            address,
+            account_id: challenge.account_id,
+            status: AttestationStatus::Pending,
+            created_at: Utc::now(),
+            expires_at: challenge.expires_at,
+            attested_at: None,
+        };
+        assert!(attestation.verify(&signature, Some(&domain)));
+        // The signature is bound to the network.
+        assert!(!attestation.verify(&signature, None));
+
+        // The signature is bound to the challenge.
+        let other_challenge = AddressAttestation {
+            challenge: H256::repeat_byte(2),
+            ..attestation.clone()
+        };
+        assert!(!other_challenge.verify(&signature, Some(&domain)));
+
+        // The challenge must be signed by the owner of the address.
+        let other_key = H256::repeat_byte(8);
+        let signature = PackedEthSignature::sign(&other_key, challenge.message.as_bytes()).unwrap();
+        assert!(!attestation.verify(&signature, Some(&domain)));
+    }
+}
@@ -0,0 +1,272 @@
+//! EIP-55 checksums of the Ethereum addresses.
+//!
+//! The checksum is encoded in the case of the hex digits of the address: a letter is uppercase
+//! if the corresponding nibble of the `keccak256` hash of the lowercase address is 8 or greater.
+//! A mistyped address fails the check with high probability, so the funds are not sent to the
+//! address nobody owns. The all-lowercase and all-uppercase addresses carry no checksum.
+
+// Built-in uses
+use std::str::FromStr;
+// External uses
+use parity_crypto::Keccak256;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+// Workspace uses
+use zksync_basic_types::Address;
+
+const ADDRESS_HEX_LEN: usize = 40;
+/// Strings this many characters longer or shorter than the address are treated as the mistyped
+/// addresses, see `looks_like_address`.
+const ADDRESS_LEN_TOLERANCE: usize = 2;
+
+/// How strictly the checksums of the addresses are checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecksumStrictness {
+    /// Checksums are not checked.
+    Disabled,
+    /// Mixed-case addresses must have the valid checksum, the addresses without the checksum are accepted.
+    MixedCase,
+    /// All the addresses typed by the users (the path segments and the query parameters) must have
+    /// the valid checksum. The JSON values are produced by the client libraries, which serialize
+    /// the addresses in lowercase, so they're checked as in the `MixedCase` mode.
+    Strict,
+}
+
+impl Default for ChecksumStrictness {
+    fn default() -> Self {
+        Self::MixedCase
+    }
+}
+
+impl FromStr for ChecksumStrictness {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "disabled" => Ok(Self::Disabled),
+            "mixed_case" => Ok(Self::MixedCase),
+            "strict" => Ok(Self::Strict),
+            other => Err(format!("Unknown checksum strictness: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Error, PartialEq)]
+pub enum AddressError {
+    #[error("Malformed address '{0}', expected 20 bytes in hex")]
+    Malformed(String),
+    #[error("Invalid checksum of the address '{0}'")]
+    BadChecksum(String),
+}
+
+/// Returns the address in the EIP-55 checksummed form, `0x`-prefixed.
+pub fn to_checksum_address(address: &Address) -> String {
+    let hex = hex::encode(address.as_bytes());
+    let hash = hex.as_bytes().keccak256();
+    let checksummed: String = hex
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = if i % 2 == 0 {
+                hash[i / 2] >> 4
+            } else {
+                hash[i / 2] & 0x0f
+            };
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+    format!("0x{}", checksummed)
+}
+
+/// Parses the address in hex, optionally `0x`-prefixed, checking its checksum according to the strictness.
+pub fn parse_address(value: &str, strictness: ChecksumStrictness) -> Result<Address, AddressError> {
+    let hex = Some(value.strip_prefix("0x").unwrap_or(value))
+        .filter(|hex| hex.len() == ADDRESS_HEX_LEN && hex.chars().all(|c| c.is_ascii_hexdigit()))
+        .ok_or_else(|| AddressError::Malformed(value.to_owned()))?;
+    let address = Address::from_slice(
+        &hex::decode(hex).map_err(|_| AddressError::Malformed(value.to_owned()))?,
+    );
+
+    let has_checksum =
+        hex.chars().any(|c| c.is_ascii_lowercase()) && hex.chars().any(|c| c.is_ascii_uppercase());
+    let check = match strictness {
+        ChecksumStrictness::Disabled => false,
+        ChecksumStrictness::MixedCase => has_checksum,
+        ChecksumStrictness::Strict => true,
+    };
+    if check && to_checksum_address(&address)[2..] != *hex {
+        return Err(AddressError::BadChecksum(value.to_owned()));
+    }
+    Ok(address)
+}
+
+/// Checks whether the string is meant to be the address, i.e. it's `0x`-prefixed and has about
+/// the length of 20 bytes in hex. The zkSync APIs encode no other values this way (the hashes
+/// are much longer, and the public key hashes are `sync:`-prefixed), so the strings with a few
+/// characters missing, extra or not being hex digits are the mistyped addresses, which
+/// `parse_address` reports as malformed.
+pub fn looks_like_address(value: &str) -> bool {
+    match value.strip_prefix("0x") {
+        Some(hex) => {
+            let len = hex.chars().count();
+            len + ADDRESS_LEN_TOLERANCE >= ADDRESS_HEX_LEN
+                && len <= ADDRESS_HEX_LEN + ADDRESS_LEN_TOLERANCE
+        }
+        None => false,
+    }
+}
+
+/// Checks the checksums of all the addresses in the JSON value, e.g. the request parameters.
+/// The addresses are recognized by their form, see `looks_like_address`. The JSON values are
+/// serialized by the client libraries, so the lowercase addresses are accepted even in the
+/// `Strict` mode.
+pub fn check_json_addresses(
+    value: &Value,
+    strictness: ChecksumStrictness,
+) -> Result<(), AddressError> {
+    let strictness = match strictness {
+        ChecksumStrictness::Strict => ChecksumStrictness::MixedCase,
+        strictness => strictness,
+    };
+    check_json_values(value, strictness)
+}
+
+fn check_json_values(value: &Value, strictness: ChecksumStrictness) -> Result<(), AddressError> {
+    match value {
+        Value::String(value) if looks_like_address(value) => {
+            parse_address(value, strictness).map(drop)
+        }
+        Value::Array(values) => values
+            .iter()
+            .try_for_each(|value| check_json_values(value, strictness)),
+        Value::Object(values) => values
+            .values()
+            .try_for_each(|value| check_json_values(value, strictness)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Test vectors from EIP-55.
+    const CHECKSUMMED: &[&str] = &[
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ];
+
+    #[test]
+    fn checksummed_addresses() {
+        for value in CHECKSUMMED {
+            let address = parse_address(value, ChecksumStrictness::Strict).unwrap();
+            assert_eq!(to_checksum_address(&address), *value);
+
+            let lowercase = value.to_lowercase();
+            assert_eq!(
+                parse_address(&lowercase, ChecksumStrictness::MixedCase),
+                Ok(address)
+            );
+            assert_eq!(
+                parse_address(&lowercase, ChecksumStrictness::Strict),
+                Err(AddressError::BadChecksum(lowercase.clone()))
+            );
+        }
+
+        // Single letter with the wrong case.
+        let typo = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD";
+        assert_eq!(
+            parse_address(typo, ChecksumStrictness::MixedCase),
+            Err(AddressError::BadChecksum(typo.to_owned()))
+        );
+        assert!(parse_address(typo, ChecksumStrictness::Disabled).is_ok());
+        // The prefix is optional.
+        assert!(parse_address(&CHECKSUMMED[0][2..], ChecksumStrictness::Strict).is_ok());
+        assert!(parse_address(&typo[2..], ChecksumStrictness::MixedCase).is_err());
+
+        for malformed in &[
+            "sync:5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA",
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAedd",
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeg",
+        ] {
+            assert_eq!(
+                parse_address(malformed, ChecksumStrictness::Disabled),
+                Err(AddressError::Malformed(malformed.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn json_addresses() {
+        let typo = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD";
+        let valid = json!({
+            "to": CHECKSUMMED[0],
+            "from": CHECKSUMMED[1].to_lowercase(),
+            "txHash": format!("0x{}", "aB".repeat(32)),
+            "txs": [{ "to": CHECKSUMMED[2] }],
+        });
+        assert_eq!(
+            check_json_addresses(&valid, ChecksumStrictness::MixedCase),
+            Ok(())
+        );
+
+        let invalid = json!({ "txs": [{ "to": CHECKSUMMED[2] }, { "to": typo }] });
+        assert_eq!(
+            check_json_addresses(&invalid, ChecksumStrictness::MixedCase),
+            Err(AddressError::BadChecksum(typo.to_owned()))
+        );
+        assert_eq!(
+            check_json_addresses(&invalid, ChecksumStrictness::Disabled),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn malformed_addresses_are_detected() {
+        let short = &CHECKSUMMED[0][..41];
+        let non_hex = format!("{}g", &CHECKSUMMED[0][..41]);
+        let long = format!("{}0", CHECKSUMMED[0]);
+        for value in &[short, non_hex.as_str(), long.as_str()] {
+            assert!(looks_like_address(value), "{}", value);
+            assert_eq!(
+                check_json_addresses(&json!({ "to": value }), ChecksumStrictness::MixedCase),
+                Err(AddressError::Malformed(value.to_string()))
+            );
+        }
+
+        // The values of the other forms are left as is.
+        for value in &[
+            format!("0x{}", "aB".repeat(32)),
+            "0x3b9aca00".to_string(),
+            format!("sync:{}", &CHECKSUMMED[0][2..]),
+            CHECKSUMMED[0][2..].to_string(),
+        ] {
+            assert!(!looks_like_address(value), "{}", value);
+        }
+    }
+
+    #[test]
+    fn lowercase_json_addresses_are_accepted_in_strict_mode() {
+        let lowercase = json!({ "to": CHECKSUMMED[0].to_lowercase() });
+        assert_eq!(
+            check_json_addresses(&lowercase, ChecksumStrictness::Strict),
+            Ok(())
+        );
+
+        let typo = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD";
+        assert_eq!(
+            check_json_addresses(&json!([typo]), ChecksumStrictness::Strict),
+            Err(AddressError::BadChecksum(typo.to_owned()))
+        );
+    }
+}
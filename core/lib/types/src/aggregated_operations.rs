@@ -1,4 +1,5 @@
 use crate::block::Block;
+use crate::block_commitment::StoredBlockInfo;
 use ethabi::Token;
 use serde::{Deserialize, Serialize};
 use zksync_basic_types::{BlockNumber, U256};
@@ -11,20 +12,7 @@ pub struct BlocksCommitOperation {
 }
 
 pub fn stored_block_info(block: &Block) -> Token {
-    Token::Tuple(vec![
-        Token::Uint(U256::from(*block.block_number)),
-        Token::Uint(U256::from(block.number_of_processed_prior_ops())),
-        Token::FixedBytes(
-            block
-                .get_onchain_operations_block_info()
-                .1
-                .as_bytes()
-                .to_vec(),
-        ),
-        Token::Uint(U256::from(block.timestamp)),
-        Token::FixedBytes(block.get_eth_encoded_root().as_bytes().to_vec()),
-        Token::FixedBytes(block.block_commitment.as_bytes().to_vec()),
-    ])
+    StoredBlockInfo::from(block).to_token()
 }
 
 impl BlocksCommitOperation {
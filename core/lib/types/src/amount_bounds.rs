@@ -0,0 +1,190 @@
+//! Sanity bounds of the operation amounts.
+//!
+//! The amounts and fees are packed into the floating point formats, so an encoding bug may
+//! produce an absurd value instead of failing. As a defense in depth, the operator can set
+//! the upper bounds of the amounts and fees per token: the transactions exceeding them are
+//! rejected by the mempool, and the operations exceeding them are rejected when the pubdata
+//! is decoded. Tokens without the configured bounds are not checked.
+
+use std::{collections::HashMap, str::FromStr};
+
+use num::BigUint;
+use thiserror::Error;
+use zksync_basic_types::TokenId;
+
+use crate::ZkSyncTx;
+
+/// Upper bounds of the amounts and fees in the token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenAmountBounds {
+    pub max_amount: BigUint,
+    pub max_fee: BigUint,
+}
+
+#[derive(Debug, Error, PartialEq)]
+#[error("Invalid amount bound '{0}', expected 'token_id:max_amount:max_fee'")]
+pub struct InvalidAmountBound(String);
+
+#[derive(Debug, Error, PartialEq)]
+pub enum TxAmountBoundsError {
+    #[error("Amount {amount} of token {token} exceeds the allowed maximum")]
+    AmountOutOfBounds { token: TokenId, amount: BigUint },
+    #[error("Fee {fee} in token {token} exceeds the allowed maximum")]
+    FeeOutOfBounds { token: TokenId, fee: BigUint },
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AmountBounds {
+    tokens: HashMap<TokenId, TokenAmountBounds>,
+}
+
+impl AmountBounds {
+    pub fn new(tokens: HashMap<TokenId, TokenAmountBounds>) -> Self {
+        Self { tokens }
+    }
+
+    /// Parses the bounds from the `token_id:max_amount:max_fee` entries of the config.
+    pub fn parse(entries: &[String]) -> Result<Self, InvalidAmountBound> {
+        let tokens = entries
+            .iter()
+            .map(|entry| {
+                let invalid = || InvalidAmountBound(entry.clone());
+                let parts: Vec<_> = entry.split(':').collect();
+                match parts.as_slice() {
+                    [token, max_amount, max_fee] => Ok((
+                        TokenId(u16::from_str(token).map_err(|_| invalid())?),
+                        TokenAmountBounds {
+                            max_amount: BigUint::from_str(max_amount).map_err(|_| invalid())?,
+                            max_fee: BigUint::from_str(max_fee).map_err(|_| invalid())?,
+                        },
+                    )),
+                    _ => Err(invalid()),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { tokens })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Checks whether the amount exceeds the bound of the token.
+    pub fn amount_exceeds(&self, token: TokenId, amount: &BigUint) -> bool {
+        self.tokens
+            .get(&token)
+            .map_or(false, |bounds| *amount > bounds.max_amount)
+    }
+
+    /// Checks whether the fee exceeds the bound of the token.
+    pub fn fee_exceeds(&self, token: TokenId, fee: &BigUint) -> bool {
+        self.tokens
+            .get(&token)
+            .map_or(false, |bounds| *fee > bounds.max_fee)
+    }
+
+    fn check_amount(&self, token: TokenId, amount: &BigUint) -> Result<(), TxAmountBoundsError> {
+        if self.amount_exceeds(token, amount) {
+            return Err(TxAmountBoundsError::AmountOutOfBounds {
+                token,
+                amount: amount.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    fn check_fee(&self, token: TokenId, fee: &BigUint) -> Result<(), TxAmountBoundsError> {
+        if self.fee_exceeds(token, fee) {
+            return Err(TxAmountBoundsError::FeeOutOfBounds {
+                token,
+                fee: fee.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks the amount and the fee of the transaction against the bounds.
+    pub fn check_tx(&self, tx: &ZkSyncTx) -> Result<(), TxAmountBoundsError> {
+        match tx {
+            ZkSyncTx::Transfer(tx) => {
+                self.check_amount(tx.token, &tx.amount)?;
+                self.check_fee(tx.token, &tx.fee)
+            }
+            ZkSyncTx::Withdraw(tx) => {
+                self.check_amount(tx.token, &tx.amount)?;
+                self.check_fee(tx.token, &tx.fee)
+            }
+            ZkSyncTx::ChangePubKey(tx) => self.check_fee(tx.fee_token, &tx.fee),
+            ZkSyncTx::ForcedExit(tx) => self.check_fee(tx.token, &tx.fee),
+            ZkSyncTx::Close(_) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AccountId, Address, Nonce, Transfer};
+
+    fn bounds() -> AmountBounds {
+        AmountBounds::parse(&["0:1000:10".to_string()]).unwrap()
+    }
+
+    fn transfer(token: TokenId, amount: u32, fee: u32) -> ZkSyncTx {
+        Transfer::new(
+            AccountId(0),
+            Address::zero(),
+            Address::zero(),
+            token,
+            BigUint::from(amount),
+            BigUint::from(fee),
+            Nonce(0),
+            Default::default(),
+            None,
+        )
+        .into()
+    }
+
+    #[test]
+    fn parse_bounds() {
+        assert!(AmountBounds::parse(&[]).unwrap().is_empty());
+        assert_eq!(
+            bounds().tokens[&TokenId(0)],
+            TokenAmountBounds {
+                max_amount: BigUint::from(1000u32),
+                max_fee: BigUint::from(10u32),
+            }
+        );
+        for entry in &["0:1000", "0:1000:10:1", "eth:1000:10", "0:-1:10"] {
+            assert!(
+                AmountBounds::parse(&[entry.to_string()]).is_err(),
+                "{}",
+                entry
+            );
+        }
+    }
+
+    #[test]
+    fn check_tx_bounds() {
+        let bounds = bounds();
+        assert!(bounds.check_tx(&transfer(TokenId(0), 1000, 10)).is_ok());
+        assert_eq!(
+            bounds.check_tx(&transfer(TokenId(0), 1001, 10)),
+            Err(TxAmountBoundsError::AmountOutOfBounds {
+                token: TokenId(0),
+                amount: BigUint::from(1001u32),
+            })
+        );
+        assert_eq!(
+            bounds.check_tx(&transfer(TokenId(0), 1000, 11)),
+            Err(TxAmountBoundsError::FeeOutOfBounds {
+                token: TokenId(0),
+                fee: BigUint::from(11u32),
+            })
+        );
+        // Tokens without the bounds are not checked.
+        assert!(bounds
+            .check_tx(&transfer(TokenId(1), 1_000_000, 1000))
+            .is_ok());
+    }
+}
@@ -0,0 +1,323 @@
+//! Stable error codes of the zkSync API.
+//!
+//! Errors of the transaction submission carry the same error names (`errorType`) in the JSON RPC
+//! and REST APIs, so the clients can handle them without parsing the messages.
+//!
+//! The numeric codes of the APIs are kept as they were before the errors were typed: they differ
+//! between the APIs, and some of them are shared by several errors (e.g. the JSON RPC `300`),
+//! so the error name is the way to tell the errors apart. The numeric codes are never reassigned:
+//! new errors get new codes appended to the existing ones, and the codes of the removed errors
+//! are not reused.
+
+use serde::{Deserialize, Serialize};
+
+/// Group of the API errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ApiErrorCategory {
+    /// The transaction is rejected by the mempool or the API checks.
+    Mempool,
+    /// The signatures of the transaction are missing or incorrect.
+    Signature,
+    /// The fee is too low or can't be paid in the chosen token.
+    Fee,
+    /// The server failed to process the request, it may succeed if retried later.
+    Internal,
+}
+
+/// Machine-readable code of the API error.
+///
+/// The code is identified by its name (`errorType`), while the numeric codes depend on the API:
+/// see [`ApiErrorCode::rpc_code`] and [`ApiErrorCode::rest_code`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ApiErrorCode {
+    NonceMismatch,
+    InvalidParams,
+    IncorrectTx,
+    TxFeeTooLow,
+    InappropriateFeeToken,
+    WithdrawalTokenPaused,
+    RecipientScreened,
+    IncorrectBatchTx,
+    EmptyBatch,
+    BatchTooBig,
+    BatchWithdrawalsOverload,
+    WithdrawalRecipientBlacklisted,
+    TxBatchFeeTooLow,
+    ChangePubKeyRateLimited,
+    AmountOutOfBounds,
+    FeeOutOfBounds,
+    MalformedAddress,
+    InvalidAddressChecksum,
+    GuardianRecoveryNotInitiated,
+    GuardianRecoveryTimelocked,
+
+    MissingEthSignature,
+    EIP1271SignatureVerificationFail,
+    IncorrectEthSignature,
+    ChangePkNotAuthorized,
+    EthSignaturesLimitExceeded,
+
+    CoreServerUnavailable,
+    AccountCloseDisabled,
+    RateLimitExceeded,
+    UnsupportedFastProcessing,
+    FeeQuotingSuspended,
+    ShuttingDown,
+    StorageUnavailable,
+    Internal,
+    Other,
+    ServerOverloaded,
+    EthereumUnavailable,
+}
+
+impl ApiErrorCode {
+    /// JSON RPC code shared by the errors which had no specific code.
+    pub const RPC_OTHER_CODE: i64 = 300;
+    /// REST code shared by all the errors of adding the transactions to the mempool.
+    pub const REST_TX_ADD_CODE: u64 = 105;
+
+    /// All the error codes.
+    pub const ALL: &'static [ApiErrorCode] = &[
+        Self::NonceMismatch,
+        Self::InvalidParams,
+        Self::IncorrectTx,
+        Self::TxFeeTooLow,
+        Self::InappropriateFeeToken,
+        Self::WithdrawalTokenPaused,
+        Self::RecipientScreened,
+        Self::IncorrectBatchTx,
+        Self::EmptyBatch,
+        Self::BatchTooBig,
+        Self::BatchWithdrawalsOverload,
+        Self::WithdrawalRecipientBlacklisted,
+        Self::TxBatchFeeTooLow,
+        Self::ChangePubKeyRateLimited,
+        Self::AmountOutOfBounds,
+        Self::FeeOutOfBounds,
+        Self::MalformedAddress,
+        Self::InvalidAddressChecksum,
+        Self::GuardianRecoveryNotInitiated,
+        Self::GuardianRecoveryTimelocked,
+        Self::MissingEthSignature,
+        Self::EIP1271SignatureVerificationFail,
+        Self::IncorrectEthSignature,
+        Self::ChangePkNotAuthorized,
+        Self::EthSignaturesLimitExceeded,
+        Self::CoreServerUnavailable,
+        Self::AccountCloseDisabled,
+        Self::RateLimitExceeded,
+        Self::UnsupportedFastProcessing,
+        Self::FeeQuotingSuspended,
+        Self::ShuttingDown,
+        Self::StorageUnavailable,
+        Self::Internal,
+        Self::Other,
+        Self::ServerOverloaded,
+        Self::EthereumUnavailable,
+    ];
+
+    /// Returns the code of the JSON RPC error. The errors which existed before the codes were
+    /// typed keep their codes, even if they're shared with the other errors.
+    pub fn rpc_code(self) -> i64 {
+        match self {
+            Self::NonceMismatch => 101,
+            Self::InvalidParams => -32602,
+            Self::IncorrectTx | Self::IncorrectBatchTx => 103,
+            Self::TxFeeTooLow | Self::TxBatchFeeTooLow => 104,
+            Self::InappropriateFeeToken => 105,
+            Self::WithdrawalTokenPaused | Self::WithdrawalRecipientBlacklisted => 106,
+            Self::RecipientScreened => 107,
+            Self::ChangePubKeyRateLimited => 108,
+            Self::AmountOutOfBounds => 109,
+            Self::FeeOutOfBounds => 110,
+            Self::MalformedAddress => 111,
+            Self::InvalidAddressChecksum => 112,
+            Self::GuardianRecoveryNotInitiated => 113,
+            Self::GuardianRecoveryTimelocked => 114,
+
+            Self::MissingEthSignature => 200,
+            Self::EIP1271SignatureVerificationFail => 201,
+            Self::IncorrectEthSignature => 202,
+            Self::ChangePkNotAuthorized => 203,
+
+            Self::EmptyBatch
+            | Self::BatchTooBig
+            | Self::BatchWithdrawalsOverload
+            | Self::EthSignaturesLimitExceeded
+            | Self::CoreServerUnavailable
+            | Self::ShuttingDown
+            | Self::StorageUnavailable => Self::RPC_OTHER_CODE,
+            Self::AccountCloseDisabled => 301,
+            Self::RateLimitExceeded => 302,
+            Self::UnsupportedFastProcessing => 303,
+            Self::FeeQuotingSuspended => 304,
+            Self::ServerOverloaded => 305,
+            Self::EthereumUnavailable => 306,
+
+            Self::Internal | Self::Other => -32603,
+        }
+    }
+
+    /// Returns the code of the REST API error. All the mempool errors share
+    /// the [`Self::REST_TX_ADD_CODE`] code, as they always did.
+    pub fn rest_code(self) -> u64 {
+        match self {
+            Self::AccountCloseDisabled => 101,
+            Self::InvalidParams => 102,
+            Self::UnsupportedFastProcessing => 103,
+            Self::IncorrectTx => 104,
+            Self::InappropriateFeeToken => 106,
+            Self::RateLimitExceeded => 107,
+            Self::FeeQuotingSuspended => 108,
+            Self::Internal => 110,
+            Self::CoreServerUnavailable => 111,
+            Self::Other => 112,
+            Self::MalformedAddress => 113,
+            Self::InvalidAddressChecksum => 114,
+            _ => Self::REST_TX_ADD_CODE,
+        }
+    }
+
+    pub fn category(self) -> ApiErrorCategory {
+        match self {
+            Self::NonceMismatch
+            | Self::InvalidParams
+            | Self::IncorrectTx
+            | Self::WithdrawalTokenPaused
+            | Self::RecipientScreened
+            | Self::IncorrectBatchTx
+            | Self::EmptyBatch
+            | Self::BatchTooBig
+            | Self::BatchWithdrawalsOverload
+            | Self::WithdrawalRecipientBlacklisted
+            | Self::ChangePubKeyRateLimited
+            | Self::AmountOutOfBounds
+            | Self::FeeOutOfBounds
+            | Self::MalformedAddress
+            | Self::InvalidAddressChecksum
+            | Self::GuardianRecoveryNotInitiated
+            | Self::GuardianRecoveryTimelocked
+            | Self::AccountCloseDisabled
+            | Self::RateLimitExceeded
+            | Self::UnsupportedFastProcessing
+            | Self::Other => ApiErrorCategory::Mempool,
+            Self::MissingEthSignature
+            | Self::EIP1271SignatureVerificationFail
+            | Self::IncorrectEthSignature
+            | Self::ChangePkNotAuthorized
+            | Self::EthSignaturesLimitExceeded => ApiErrorCategory::Signature,
+            Self::TxFeeTooLow
+            | Self::TxBatchFeeTooLow
+            | Self::InappropriateFeeToken
+            | Self::FeeQuotingSuspended => ApiErrorCategory::Fee,
+            Self::CoreServerUnavailable
+            | Self::ShuttingDown
+            | Self::StorageUnavailable
+            | Self::Internal
+            | Self::ServerOverloaded
+            | Self::EthereumUnavailable => ApiErrorCategory::Internal,
+        }
+    }
+}
+
+/// Machine-readable part of the API error, e.g. the `data` field of the JSON RPC error.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiErrorInfo {
+    /// Name of the error code, e.g. `NonceMismatch`.
+    pub error_type: ApiErrorCode,
+    pub category: ApiErrorCategory,
+    /// Details of the error, e.g. the cause of the internal fault.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl ApiErrorInfo {
+    pub fn new(error_type: ApiErrorCode, detail: Option<String>) -> Self {
+        Self {
+            error_type,
+            category: error_type.category(),
+            detail,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn legacy_error_codes() {
+        let rpc_codes = [
+            (ApiErrorCode::NonceMismatch, 101),
+            (ApiErrorCode::IncorrectTx, 103),
+            (ApiErrorCode::TxFeeTooLow, 104),
+            (ApiErrorCode::InappropriateFeeToken, 105),
+            (ApiErrorCode::WithdrawalRecipientBlacklisted, 106),
+            (ApiErrorCode::RecipientScreened, 107),
+            (ApiErrorCode::MissingEthSignature, 200),
+            (ApiErrorCode::ChangePkNotAuthorized, 203),
+            (ApiErrorCode::CoreServerUnavailable, 300),
+            (ApiErrorCode::AccountCloseDisabled, 301),
+            (ApiErrorCode::RateLimitExceeded, 302),
+            (ApiErrorCode::FeeQuotingSuspended, 304),
+            (ApiErrorCode::InvalidParams, -32602),
+            (ApiErrorCode::Internal, -32603),
+        ];
+        for (error, code) in &rpc_codes {
+            assert_eq!(error.rpc_code(), *code, "{:?}", error);
+        }
+
+        let rest_codes = [
+            (ApiErrorCode::AccountCloseDisabled, 101),
+            (ApiErrorCode::InvalidParams, 102),
+            (ApiErrorCode::UnsupportedFastProcessing, 103),
+            (ApiErrorCode::IncorrectTx, 104),
+            (ApiErrorCode::NonceMismatch, 105),
+            (ApiErrorCode::InappropriateFeeToken, 106),
+            (ApiErrorCode::RateLimitExceeded, 107),
+            (ApiErrorCode::FeeQuotingSuspended, 108),
+            (ApiErrorCode::Internal, 110),
+            (ApiErrorCode::CoreServerUnavailable, 111),
+            (ApiErrorCode::Other, 112),
+        ];
+        for (error, code) in &rest_codes {
+            assert_eq!(error.rest_code(), *code, "{:?}", error);
+        }
+    }
+
+    /// Codes appended for the new errors must not collide with the existing ones.
+    #[test]
+    fn new_error_codes_are_unique() {
+        let new_errors = [
+            ApiErrorCode::ChangePubKeyRateLimited,
+            ApiErrorCode::AmountOutOfBounds,
+            ApiErrorCode::FeeOutOfBounds,
+            ApiErrorCode::MalformedAddress,
+            ApiErrorCode::InvalidAddressChecksum,
+            ApiErrorCode::GuardianRecoveryNotInitiated,
+            ApiErrorCode::GuardianRecoveryTimelocked,
+            ApiErrorCode::ServerOverloaded,
+            ApiErrorCode::EthereumUnavailable,
+        ];
+        for new_error in &new_errors {
+            for error in ApiErrorCode::ALL.iter().filter(|error| *error != new_error) {
+                assert_ne!(new_error.rpc_code(), error.rpc_code(), "{:?}", error);
+                if new_error.rest_code() != ApiErrorCode::REST_TX_ADD_CODE {
+                    assert_ne!(new_error.rest_code(), error.rest_code(), "{:?}", error);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn error_info_serialization() {
+        let info = ApiErrorInfo::new(ApiErrorCode::NonceMismatch, None);
+        assert_eq!(
+            serde_json::to_value(&info).unwrap(),
+            serde_json::json!({ "errorType": "NonceMismatch", "category": "mempool" })
+        );
+    }
+}
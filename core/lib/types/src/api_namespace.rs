@@ -0,0 +1,85 @@
+//! Namespaces of the API serving the white-label frontends.
+//!
+//! A single deployment can serve several branded frontends. Each frontend gets its own
+//! namespace: the requests carrying the API key of the namespace are counted against its
+//! own quota, fee quotes served to it include its fee markup, and its webhook subscriptions
+//! are kept apart from the ones of the other namespaces. Requests without an API key are
+//! served as before. Only the hash of the API key is stored.
+
+use chrono::{DateTime, Utc};
+use parity_crypto::Keccak256;
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::H256;
+
+pub const MAX_NAMESPACE_NAME_LENGTH: usize = 32;
+
+/// Namespace of the API along with its quotas.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiNamespace {
+    pub name: String,
+    /// Maximum number of requests per minute, `0` means no limit.
+    pub requests_per_minute: u32,
+    /// Markup of the fees quoted to the namespace, in percents.
+    pub fee_markup_percent: Option<u32>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ApiNamespace {
+    /// Returns the hash the API key is stored as.
+    pub fn api_key_hash(api_key: &str) -> H256 {
+        H256::from(api_key.as_bytes().keccak256())
+    }
+
+    /// Checks that the name consists of the lowercase letters, digits, '-' and '_'.
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty()
+            && name.len() <= MAX_NAMESPACE_NAME_LENGTH
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    }
+}
+
+/// Namespace settings set by the operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiNamespaceLimits {
+    /// Maximum number of requests per minute, `0` means no limit.
+    pub requests_per_minute: u32,
+    /// Markup of the fees quoted to the namespace, in percents.
+    pub fee_markup_percent: Option<u32>,
+}
+
+/// Created namespace along with its API key. The key is not stored, so it's returned only once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedApiNamespace {
+    pub namespace: ApiNamespace,
+    pub api_key: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BatchFee;
+    use num::BigUint;
+
+    #[test]
+    fn namespace_names() {
+        for name in &["wallet", "my-wallet_2"] {
+            assert!(ApiNamespace::is_valid_name(name), "{}", name);
+        }
+        for name in &["", "Wallet", "my wallet", "a".repeat(33).as_str()] {
+            assert!(!ApiNamespace::is_valid_name(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn fee_markup() {
+        let fee = BatchFee {
+            total_fee: BigUint::from(1000u32),
+        };
+        assert_eq!(fee.with_markup(10).total_fee, BigUint::from(1100u32));
+    }
+}
@@ -3,10 +3,11 @@
 use super::PriorityOp;
 use super::ZkSyncOp;
 use super::{AccountId, BlockNumber, Fr};
+use crate::block_commitment::block_commitment;
+use crate::priority_ops::{check_serial_id_continuity, SerialIdContinuityError};
 use crate::SignedZkSyncTx;
 use chrono::Utc;
 use chrono::{DateTime, TimeZone};
-use parity_crypto::digest::sha256;
 use parity_crypto::Keccak256;
 use serde::{Deserialize, Serialize};
 use zksync_basic_types::{H256, U256};
@@ -296,6 +297,28 @@ impl Block {
         self.processed_priority_ops.1 - self.processed_priority_ops.0
     }
 
+    /// Checks that the block processes the priority operations of its `processed_priority_ops`
+    /// range, each exactly once and in the queue order.
+    pub fn check_priority_ops_continuity(&self) -> Result<(), SerialIdContinuityError> {
+        let (first_serial_id, next_serial_id) = self.processed_priority_ops;
+        let serial_ids = self
+            .block_transactions
+            .iter()
+            .filter_map(|operation| match operation {
+                ExecutedOperations::PriorityOp(op) => Some(op.priority_op.serial_id),
+                ExecutedOperations::Tx(_) => None,
+            });
+        let last_serial_id = check_serial_id_continuity(first_serial_id, serial_ids)?;
+        if last_serial_id != next_serial_id {
+            // The range claims the operations which are not included into the block.
+            return Err(SerialIdContinuityError::Gap {
+                expected: last_serial_id,
+                found: next_serial_id,
+            });
+        }
+        Ok(())
+    }
+
     fn chunks_used(&self) -> usize {
         self.block_transactions
             .iter()
@@ -383,7 +406,9 @@ impl Block {
         res
     }
 
-    fn get_commitment(
+    /// Computes the block commitment the same way as the zkSync contract does
+    /// when the block is committed, see `block_commitment` module for details.
+    pub fn get_commitment(
         block_number: BlockNumber,
         fee_account: AccountId,
         old_state_hash: H256,
@@ -392,24 +417,15 @@ impl Block {
         onchain_op_commitment: &[u8],
         public_data: &[u8],
     ) -> H256 {
-        let mut hash_arg = vec![0u8; 64];
-        U256::from(*block_number).to_big_endian(&mut hash_arg[0..32]);
-        U256::from(*fee_account).to_big_endian(&mut hash_arg[32..]);
-        hash_arg = sha256(&hash_arg).to_vec();
-
-        hash_arg.extend_from_slice(&old_state_hash.as_bytes());
-        hash_arg = sha256(&hash_arg).to_vec();
-
-        hash_arg.extend_from_slice(&new_state_hash.as_bytes());
-        hash_arg = sha256(&hash_arg).to_vec();
-
-        hash_arg.resize(64, 0u8);
-        U256::from(timestamp).to_big_endian(&mut hash_arg[32..]);
-        hash_arg = sha256(&hash_arg).to_vec();
-
-        hash_arg.extend_from_slice(&public_data);
-        hash_arg.extend_from_slice(&onchain_op_commitment);
-        H256::from_slice(&sha256(&hash_arg))
+        block_commitment(
+            block_number,
+            fee_account,
+            old_state_hash,
+            new_state_hash,
+            timestamp,
+            onchain_op_commitment,
+            public_data,
+        )
     }
 
     pub fn processable_ops_pubdata(&self) -> Vec<Vec<u8>> {
@@ -448,6 +464,21 @@ pub fn smallest_block_size_for_chunks(
     );
 }
 
+/// Record of the unverified blocks reverted by the operator.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockRevert {
+    pub id: i64,
+    /// Last block kept after the revert.
+    pub last_correct_block: BlockNumber,
+    /// Last block committed before the revert.
+    pub last_reverted_block: BlockNumber,
+    /// `true` if the transactions of the reverted blocks were returned to the mempool,
+    /// `false` if they were rejected.
+    pub txs_returned_to_mempool: bool,
+    pub reverted_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone)]
 pub struct OnchainOperationsBlockInfo {
     pub public_data_offset: u32,
@@ -0,0 +1,280 @@
+//! Block commitment calculator.
+//!
+//! The commitment binds the block data sent to L1 to the block proof: the zkSync contract computes it
+//! in `commitBlocks` and keeps it in the `StoredBlockInfo` of the block, and the circuit computes it
+//! from the same data. This module re-derives the commitment along with the rest of `StoredBlockInfo`
+//! from the data available on L1 alone, i.e. from the arguments of the `commitBlocks` call, so the
+//! values submitted by the operator can be checked independently of the server.
+//!
+//! The interface of the module is stable: [`CommitmentInput`], [`StoredBlockInfo`] and
+//! [`compute_block_commitment`] mirror the contract structures and change only along with them.
+//! The server uses the same functions to compute the commitments of its blocks.
+//!
+//! Unlike the contract, the calculator doesn't rely on the offsets of the onchain operations passed
+//! in the calldata: the operations are located by parsing the public data itself. The offsets are
+//! covered by the commitment, so the commitment of a block with the forged offsets doesn't match the
+//! proof anyway.
+
+// Built-in uses
+// External uses
+use ethabi::Token;
+use parity_crypto::{digest::sha256, Keccak256};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+// Workspace uses
+use zksync_basic_types::{AccountId, BlockNumber, H256, U256};
+use zksync_crypto::params::CHUNK_BYTES;
+use zksync_utils::ZeroPrefixHexSerde;
+// Local uses
+use crate::{
+    block::Block,
+    operations::ZkSyncOp,
+    pubdata_compression::{decompress_pubdata, PubdataDecompressionError},
+};
+
+#[derive(Debug, Error, PartialEq)]
+pub enum CommitmentError {
+    #[error("Public data length {0} is not a multiple of the chunk size")]
+    InvalidPublicDataLength(usize),
+    #[error("Unknown operation type {op_type} at the public data offset {offset}")]
+    UnknownOperationType { offset: usize, op_type: u8 },
+    #[error("Operation at the public data offset {offset} can't be decoded: {reason}")]
+    InvalidOperation { offset: usize, reason: String },
+    #[error("Compressed public data can't be decompressed: {0}")]
+    Decompression(#[from] PubdataDecompressionError),
+    #[error("Unexpected commitBlocks arguments: {0}")]
+    InvalidCalldata(String),
+}
+
+/// Data of the block as it's committed on L1, enough to re-derive the commitment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitmentInput {
+    pub block_number: BlockNumber,
+    /// ID of the account the fees of the block are collected to.
+    pub fee_account: AccountId,
+    /// Root hash of the state before the block, i.e. the state hash of the previous block.
+    pub previous_state_hash: H256,
+    /// Root hash of the state after the block (the rollup root).
+    pub state_hash: H256,
+    pub timestamp: u64,
+    /// Public data of the block, either as is or compressed (see `pubdata_compression`).
+    #[serde(with = "ZeroPrefixHexSerde")]
+    pub public_data: Vec<u8>,
+}
+
+/// Block data stored by the contract (`StoredBlockInfo`). Only the hash of this structure is kept
+/// in the contract storage, and the structure itself is passed back to the contract by the
+/// subsequent `commitBlocks`, `proveBlocks` and `executeBlocks` calls.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredBlockInfo {
+    pub block_number: BlockNumber,
+    /// Number of the priority operations processed in the block.
+    pub priority_operations: u64,
+    /// Hash of the public data of the operations to be processed once the block is executed
+    /// (i.e. withdrawals and exits).
+    pub pending_onchain_operations_hash: H256,
+    pub timestamp: u64,
+    pub state_hash: H256,
+    pub commitment: H256,
+}
+
+impl StoredBlockInfo {
+    /// Returns the hash the contract keeps for the block, `keccak256(abi.encode(info))`.
+    pub fn hash(&self) -> H256 {
+        H256::from(ethabi::encode(&[self.to_token()]).keccak256())
+    }
+
+    /// Encodes the structure as the contract call argument.
+    pub fn to_token(&self) -> Token {
+        Token::Tuple(vec![
+            Token::Uint(U256::from(*self.block_number)),
+            Token::Uint(U256::from(self.priority_operations)),
+            Token::FixedBytes(self.pending_onchain_operations_hash.as_bytes().to_vec()),
+            Token::Uint(U256::from(self.timestamp)),
+            Token::FixedBytes(self.state_hash.as_bytes().to_vec()),
+            Token::FixedBytes(self.commitment.as_bytes().to_vec()),
+        ])
+    }
+
+    /// Decodes the structure from the contract call argument.
+    pub fn from_token(token: &Token) -> Result<Self, CommitmentError> {
+        let fields = match token {
+            Token::Tuple(fields) if fields.len() == 6 => fields,
+            _ => return Err(unexpected("StoredBlockInfo", token)),
+        };
+        Ok(Self {
+            block_number: BlockNumber(decode_uint(&fields[0])?.low_u32()),
+            priority_operations: decode_uint(&fields[1])?.low_u64(),
+            pending_onchain_operations_hash: decode_fixed_bytes(&fields[2])?,
+            timestamp: decode_uint(&fields[3])?.low_u64(),
+            state_hash: decode_fixed_bytes(&fields[4])?,
+            commitment: decode_fixed_bytes(&fields[5])?,
+        })
+    }
+}
+
+impl From<&Block> for StoredBlockInfo {
+    fn from(block: &Block) -> Self {
+        Self {
+            block_number: block.block_number,
+            priority_operations: block.number_of_processed_prior_ops(),
+            pending_onchain_operations_hash: block.get_onchain_operations_block_info().1,
+            timestamp: block.timestamp,
+            state_hash: block.get_eth_encoded_root(),
+            commitment: block.block_commitment,
+        }
+    }
+}
+
+/// Computes the commitment and the rest of `StoredBlockInfo` of the block committed on L1.
+pub fn compute_block_commitment(
+    input: &CommitmentInput,
+) -> Result<StoredBlockInfo, CommitmentError> {
+    // The commitment is always computed over the uncompressed public data.
+    let public_data = decompress_pubdata(&input.public_data)?;
+    if public_data.len() % CHUNK_BYTES != 0 {
+        return Err(CommitmentError::InvalidPublicDataLength(public_data.len()));
+    }
+
+    let mut onchain_op_commitment = vec![0u8; public_data.len() / CHUNK_BYTES];
+    let mut pending_onchain_operations_hash = Vec::new().keccak256();
+    let mut priority_operations = 0;
+    let mut offset = 0;
+    while offset < public_data.len() {
+        let op_type = public_data[offset];
+        let op_len = ZkSyncOp::public_data_length(op_type)
+            .map_err(|_| CommitmentError::UnknownOperationType { offset, op_type })?;
+        let op_data = public_data.get(offset..offset + op_len).ok_or_else(|| {
+            CommitmentError::InvalidOperation {
+                offset,
+                reason: "operation is truncated".to_owned(),
+            }
+        })?;
+        let op = ZkSyncOp::from_public_data(op_data).map_err(|err| {
+            CommitmentError::InvalidOperation {
+                offset,
+                reason: err.to_string(),
+            }
+        })?;
+
+        if op.is_onchain_operation() {
+            onchain_op_commitment[offset / CHUNK_BYTES] = 0x01;
+        }
+        if op.is_processable_onchain_operation() {
+            pending_onchain_operations_hash = [&pending_onchain_operations_hash, op_data]
+                .concat()
+                .keccak256();
+        }
+        if op.is_priority_op() {
+            priority_operations += 1;
+        }
+        offset += op_len;
+    }
+
+    let commitment = block_commitment(
+        input.block_number,
+        input.fee_account,
+        input.previous_state_hash,
+        input.state_hash,
+        input.timestamp,
+        &onchain_op_commitment,
+        &public_data,
+    );
+    Ok(StoredBlockInfo {
+        block_number: input.block_number,
+        priority_operations,
+        pending_onchain_operations_hash: H256::from(pending_onchain_operations_hash),
+        timestamp: input.timestamp,
+        state_hash: input.state_hash,
+        commitment,
+    })
+}
+
+/// Computes the block commitment the same way as the zkSync contract does when the block is committed.
+///
+/// `onchain_op_commitment` has a byte per chunk of the uncompressed `public_data`,
+/// set to `0x01` for the first chunk of every onchain operation.
+pub fn block_commitment(
+    block_number: BlockNumber,
+    fee_account: AccountId,
+    old_state_hash: H256,
+    new_state_hash: H256,
+    timestamp: u64,
+    onchain_op_commitment: &[u8],
+    public_data: &[u8],
+) -> H256 {
+    let mut hash_arg = vec![0u8; 64];
+    U256::from(*block_number).to_big_endian(&mut hash_arg[0..32]);
+    U256::from(*fee_account).to_big_endian(&mut hash_arg[32..]);
+    hash_arg = sha256(&hash_arg).to_vec();
+
+    hash_arg.extend_from_slice(&old_state_hash.as_bytes());
+    hash_arg = sha256(&hash_arg).to_vec();
+
+    hash_arg.extend_from_slice(&new_state_hash.as_bytes());
+    hash_arg = sha256(&hash_arg).to_vec();
+
+    hash_arg.resize(64, 0u8);
+    U256::from(timestamp).to_big_endian(&mut hash_arg[32..]);
+    hash_arg = sha256(&hash_arg).to_vec();
+
+    hash_arg.extend_from_slice(&public_data);
+    hash_arg.extend_from_slice(&onchain_op_commitment);
+    H256::from_slice(&sha256(&hash_arg))
+}
+
+/// Decodes the arguments of the `commitBlocks` call (`StoredBlockInfo` of the last committed block
+/// and the list of `CommitBlockInfo`) into the inputs of the committed blocks.
+pub fn decode_commit_blocks(tokens: &[Token]) -> Result<Vec<CommitmentInput>, CommitmentError> {
+    let (last_committed_block, new_blocks) = match tokens {
+        [last_committed_block, Token::Array(new_blocks)] => (
+            StoredBlockInfo::from_token(last_committed_block)?,
+            new_blocks,
+        ),
+        _ => return Err(CommitmentError::InvalidCalldata(format!("{:?}", tokens))),
+    };
+
+    let mut previous_state_hash = last_committed_block.state_hash;
+    let mut inputs = Vec::with_capacity(new_blocks.len());
+    for block in new_blocks {
+        // `CommitBlockInfo` is `(newStateHash, publicData, timestamp, onchainOperations, blockNumber, feeAccount)`.
+        let fields = match block {
+            Token::Tuple(fields) if fields.len() == 6 => fields,
+            _ => return Err(unexpected("CommitBlockInfo", block)),
+        };
+        let input = CommitmentInput {
+            block_number: BlockNumber(decode_uint(&fields[4])?.low_u32()),
+            fee_account: AccountId(decode_uint(&fields[5])?.low_u32()),
+            previous_state_hash,
+            state_hash: decode_fixed_bytes(&fields[0])?,
+            timestamp: decode_uint(&fields[2])?.low_u64(),
+            public_data: match &fields[1] {
+                Token::Bytes(bytes) => bytes.clone(),
+                other => return Err(unexpected("bytes", other)),
+            },
+        };
+        previous_state_hash = input.state_hash;
+        inputs.push(input);
+    }
+    Ok(inputs)
+}
+
+fn unexpected(expected: &str, token: &Token) -> CommitmentError {
+    CommitmentError::InvalidCalldata(format!("expected {}, got {:?}", expected, token))
+}
+
+fn decode_fixed_bytes(token: &Token) -> Result<H256, CommitmentError> {
+    match token {
+        Token::FixedBytes(bytes) if bytes.len() == 32 => Ok(H256::from_slice(bytes)),
+        _ => Err(unexpected("bytes32", token)),
+    }
+}
+
+fn decode_uint(token: &Token) -> Result<U256, CommitmentError> {
+    match token {
+        Token::Uint(value) => Ok(*value),
+        _ => Err(unexpected("uint", token)),
+    }
+}
@@ -0,0 +1,162 @@
+//! Refunds of the deposits which funds would get stuck on L2.
+//!
+//! Priority operations can't be skipped, so the deposit is always credited on L2, even if nobody
+//! is able to use the funds, e.g. when the recipient is the zero address. `eth_watch` flags
+//! such deposits once they're received, and the operator decides whether the depositor is refunded.
+//! The refund is a separate L1 transaction sending the deposited amount back to the depositor
+//! from the operator's refund account, its progress is tracked by the server.
+//!
+//! Only the deposits nobody can ever use are flagged. E.g. the funds of a paused token are
+//! still owned by the recipient and can be withdrawn once the token is unpaused, so refunding
+//! them would pay the deposit twice.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{Address, TokenId, H256};
+use zksync_utils::BigUintSerdeAsRadix10Str;
+
+use crate::{Deposit, SerialId};
+
+/// Reason the deposit funds would get stuck.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DepositFailureReason {
+    /// Nobody controls the recipient account, e.g. it's the zero address.
+    InvalidRecipient,
+}
+
+impl DepositFailureReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::InvalidRecipient => "invalidRecipient",
+        }
+    }
+
+    /// Checks whether the deposit recipient can use the deposited funds.
+    pub fn check_recipient(deposit: &Deposit, zksync_contract: Address) -> Option<Self> {
+        if deposit.to.is_zero() || deposit.to == zksync_contract {
+            Some(Self::InvalidRecipient)
+        } else {
+            None
+        }
+    }
+}
+
+impl FromStr for DepositFailureReason {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "invalidRecipient" => Ok(Self::InvalidRecipient),
+            other => Err(format!("Unknown deposit failure reason: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for DepositFailureReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Status of the flagged deposit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DepositRefundStatus {
+    /// The deposit awaits the operator decision.
+    Flagged,
+    /// The operator requested the refund, the transaction is not sent yet.
+    Requested,
+    /// The refund transaction is signed and stored, it's (re)sent until it gets the confirmations.
+    Sent,
+    /// The refund transaction is confirmed.
+    Confirmed,
+    /// The refund transaction failed, the refund can be requested again.
+    Failed,
+    /// The operator decided not to refund the deposit.
+    Dismissed,
+}
+
+impl DepositRefundStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Flagged => "flagged",
+            Self::Requested => "requested",
+            Self::Sent => "sent",
+            Self::Confirmed => "confirmed",
+            Self::Failed => "failed",
+            Self::Dismissed => "dismissed",
+        }
+    }
+
+    /// Whether the refund of the deposit in this status can be requested.
+    pub fn is_refundable(self) -> bool {
+        matches!(self, Self::Flagged | Self::Failed)
+    }
+}
+
+impl FromStr for DepositRefundStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "flagged" => Ok(Self::Flagged),
+            "requested" => Ok(Self::Requested),
+            "sent" => Ok(Self::Sent),
+            "confirmed" => Ok(Self::Confirmed),
+            "failed" => Ok(Self::Failed),
+            "dismissed" => Ok(Self::Dismissed),
+            other => Err(format!("Unknown deposit refund status: {}", other)),
+        }
+    }
+}
+
+/// Deposit flagged by `eth_watch`, along with the progress of its refund.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositRefund {
+    pub serial_id: SerialId,
+    pub eth_hash: H256,
+    pub from: Address,
+    pub to: Address,
+    pub token: TokenId,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+    pub reason: DepositFailureReason,
+    pub status: DepositRefundStatus,
+    pub refund_tx_hash: Option<H256>,
+    /// Nonce of the refund transaction, the transaction is resent with it until it's mined.
+    pub refund_tx_nonce: Option<u64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deposit_recipient_check() {
+        let contract = Address::repeat_byte(0xcc);
+        let deposit = |to| Deposit {
+            from: Address::repeat_byte(1),
+            token: TokenId(0),
+            amount: BigUint::from(100u32),
+            to,
+        };
+
+        assert_eq!(
+            DepositFailureReason::check_recipient(&deposit(Address::repeat_byte(2)), contract),
+            None
+        );
+        for to in vec![Address::zero(), contract] {
+            assert_eq!(
+                DepositFailureReason::check_recipient(&deposit(to), contract),
+                Some(DepositFailureReason::InvalidRecipient)
+            );
+        }
+    }
+}
@@ -0,0 +1,102 @@
+//! Collection of the dust accounts.
+//!
+//! Accounts holding only a negligible value and not used for a long time are emptied by the
+//! operator: all of their balances are withdrawn to the account address on L1 with `ForcedExit`
+//! transactions paid by the operator. `ForcedExit` can only be applied to the accounts without
+//! a signing key, so the accounts that were ever activated are never collected.
+//!
+//! Owners who want to keep their accounts intact can opt out by signing the opt-out message
+//! with the Ethereum key of the account. The registry of the opted out accounts is public.
+
+use chrono::{DateTime, Utc};
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{AccountId, Address, TokenId};
+
+use crate::tx::{PackedEthSignature, SignatureDomain};
+
+/// Request to exclude the account from the dust collection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DustCollectionOptOut {
+    pub address: Address,
+    /// Ethereum signature of the `DustCollectionOptOut::message`.
+    pub signature: PackedEthSignature,
+}
+
+impl DustCollectionOptOut {
+    /// Returns the message to be signed by the owner of the account, bound to the network
+    /// if the domain is required (see `SignatureDomain`).
+    pub fn message(address: Address, domain: Option<&SignatureDomain>) -> String {
+        let message = format!(
+            "Opt out of the zkSync dust collection.\nAccount: {:?}",
+            address
+        );
+        SignatureDomain::apply_optional(domain, message)
+    }
+
+    /// Checks that the request is signed by the owner of the account.
+    pub fn verify(&self, domain: Option<&SignatureDomain>) -> bool {
+        self.signature
+            .signature_recover_signer(Self::message(self.address, domain).as_bytes())
+            .map(|signer| signer == self.address)
+            .unwrap_or(false)
+    }
+}
+
+/// Dust collection status of the account.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DustCollectionStatus {
+    pub address: Address,
+    /// Whether the account is excluded from the dust collection.
+    pub opted_out: bool,
+    pub opted_out_at: Option<DateTime<Utc>>,
+}
+
+/// Account which may be collected, i.e. it has no signing key and was not updated
+/// for the configured period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DustCandidate {
+    pub account_id: AccountId,
+    pub address: Address,
+    /// Non-zero balances of the account.
+    pub balances: Vec<(TokenId, BigUint)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_basic_types::H256;
+
+    #[test]
+    fn opt_out_signature() {
+        let private_key = H256::repeat_byte(7);
+        let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let domain = SignatureDomain::new(1, Address::repeat_byte(9));
+        let sign = |address: Address| {
+            PackedEthSignature::sign(
+                &private_key,
+                DustCollectionOptOut::message(address, Some(&domain)).as_bytes(),
+            )
+            .unwrap()
+        };
+
+        let opt_out = DustCollectionOptOut {
+            address,
+            signature: sign(address),
+        };
+        assert!(opt_out.verify(Some(&domain)));
+        // The signature is bound to the network.
+        assert!(!opt_out.verify(None));
+        assert!(!opt_out.verify(Some(&SignatureDomain::new(4, domain.contract_address))));
+
+        // Only the owner of the account can opt it out.
+        let other = Address::repeat_byte(1);
+        let opt_out = DustCollectionOptOut {
+            address: other,
+            signature: sign(other),
+        };
+        assert!(!opt_out.verify(Some(&domain)));
+    }
+}
@@ -0,0 +1,154 @@
+//! Durable log of the chain events.
+//!
+//! Every event is stored in the same database transaction as the changes it describes and gets
+//! an offset from a monotonically increasing sequence. Offsets are assigned in the commit order,
+//! so a consumer which processed all the events up to some offset can resume from the next one
+//! without missing or duplicating anything. This allows e.g. exchanges to credit the deposits
+//! exactly once.
+//!
+//! Note that sealed blocks may be reverted until they are committed. Consumers which need finality
+//! should act on the `BlockVerified` event rather than on the events of the block execution.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{BlockNumber, H256};
+
+use crate::{
+    block::ExecutedOperations,
+    tx::{TxHash, ZkSyncTx},
+    ZkSyncPriorityOp,
+};
+
+/// Offset of the event in the event log.
+pub type EventOffset = u64;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ChainEvent {
+    /// L2 transaction was executed in the sealed block.
+    #[serde(rename_all = "camelCase")]
+    TxExecuted {
+        block_number: BlockNumber,
+        block_index: Option<u32>,
+        tx_hash: TxHash,
+        tx: ZkSyncTx,
+        success: bool,
+        fail_reason: Option<String>,
+    },
+    /// L1 priority operation (e.g. deposit) was executed in the sealed block.
+    #[serde(rename_all = "camelCase")]
+    PriorityOpExecuted {
+        block_number: BlockNumber,
+        block_index: u32,
+        serial_id: u64,
+        eth_hash: H256,
+        op: ZkSyncPriorityOp,
+    },
+    /// Block was sealed. Emitted after the events of the operations executed in the block.
+    #[serde(rename_all = "camelCase")]
+    BlockSealed { block_number: BlockNumber },
+    /// Blocks after `last_correct_block` were reverted along with their operations.
+    #[serde(rename_all = "camelCase")]
+    BlocksReverted { last_correct_block: BlockNumber },
+    /// Commitment of the block was confirmed on L1.
+    #[serde(rename_all = "camelCase")]
+    BlockCommitted { block_number: BlockNumber },
+    /// Block was verified and executed on L1, its state is final.
+    #[serde(rename_all = "camelCase")]
+    BlockVerified { block_number: BlockNumber },
+    /// Funds of the L2 withdrawal (`Withdraw` or `ForcedExit`) were sent to L1 with the execution
+    /// of the block.
+    /// Full exits are reported via `PriorityOpExecuted` and `BlockVerified` events.
+    #[serde(rename_all = "camelCase")]
+    WithdrawalCompleted {
+        block_number: BlockNumber,
+        tx_hash: TxHash,
+    },
+}
+
+impl ChainEvent {
+    /// Returns the events describing the execution of the operations in the sealed block.
+    pub fn block_execution_events(
+        block_number: BlockNumber,
+        operations: &[ExecutedOperations],
+    ) -> Vec<Self> {
+        operations
+            .iter()
+            .map(|operation| match operation {
+                ExecutedOperations::Tx(tx) => Self::TxExecuted {
+                    block_number,
+                    block_index: tx.block_index,
+                    tx_hash: tx.signed_tx.tx.hash(),
+                    tx: tx.signed_tx.tx.clone(),
+                    success: tx.success,
+                    fail_reason: tx.fail_reason.clone(),
+                },
+                ExecutedOperations::PriorityOp(op) => Self::PriorityOpExecuted {
+                    block_number,
+                    block_index: op.block_index,
+                    serial_id: op.priority_op.serial_id,
+                    eth_hash: op.priority_op.eth_hash,
+                    op: op.priority_op.data.clone(),
+                },
+            })
+            .chain(std::iter::once(Self::BlockSealed { block_number }))
+            .collect()
+    }
+
+    /// Returns the number of the block the event relates to.
+    pub fn block_number(&self) -> BlockNumber {
+        match self {
+            Self::TxExecuted { block_number, .. }
+            | Self::PriorityOpExecuted { block_number, .. }
+            | Self::BlockSealed { block_number }
+            | Self::BlockCommitted { block_number }
+            | Self::BlockVerified { block_number }
+            | Self::WithdrawalCompleted { block_number, .. } => *block_number,
+            Self::BlocksReverted { last_correct_block } => *last_correct_block,
+        }
+    }
+
+    /// Returns the name of the event type.
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            Self::TxExecuted { .. } => "txExecuted",
+            Self::PriorityOpExecuted { .. } => "priorityOpExecuted",
+            Self::BlockSealed { .. } => "blockSealed",
+            Self::BlocksReverted { .. } => "blocksReverted",
+            Self::BlockCommitted { .. } => "blockCommitted",
+            Self::BlockVerified { .. } => "blockVerified",
+            Self::WithdrawalCompleted { .. } => "withdrawalCompleted",
+        }
+    }
+}
+
+/// Event stored in the event log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventRecord {
+    pub offset: EventOffset,
+    pub created_at: DateTime<Utc>,
+    pub event: ChainEvent,
+}
+
+/// Version of the `StreamedEvent` schema. Incremented on every incompatible change of the
+/// streamed events, so the consumers can detect the messages they can't handle.
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+/// Event published to the message broker.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamedEvent {
+    pub schema_version: u32,
+    #[serde(flatten)]
+    pub record: EventRecord,
+}
+
+impl From<EventRecord> for StreamedEvent {
+    fn from(record: EventRecord) -> Self {
+        Self {
+            schema_version: EVENT_SCHEMA_VERSION,
+            record,
+        }
+    }
+}
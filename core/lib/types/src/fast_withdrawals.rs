@@ -0,0 +1,222 @@
+//! Fast withdrawals via the liquidity providers.
+//!
+//! Funds withdrawn from zkSync can be claimed on L1 only after the block with the withdrawal
+//! is verified. User who doesn't want to wait can sell the pending withdrawal to a liquidity
+//! provider (LP): the user signs a withdrawal to the L1 address of the LP together with an
+//! intent stating the `payout` the LP has to transfer to the user in L2.
+//!
+//! LPs discover the pending intents via the API and fulfill them by providing the payout transfer.
+//! The server executes the transfer and the withdrawal atomically in one batch, and the withdrawal
+//! is processed as a fast one. The withdrawal itself is never disclosed before it's fulfilled,
+//! so it can't be executed without the payout.
+//!
+//! The intent is offered for at most `FAST_WITHDRAWAL_INTENT_LIFETIME_SECS`, or until the end of
+//! the withdrawal time range if it's earlier. The user cancels the intent by using the nonce of the
+//! withdrawal in another transaction: such intent can't be fulfilled anymore and is removed.
+
+use chrono::{DateTime, Utc};
+use num::{BigUint, Zero};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zksync_basic_types::{AccountId, Address, Nonce, TokenId};
+use zksync_utils::{format_units, BigUintSerdeAsRadix10Str};
+
+use crate::tx::{TxEthSignature, TxHash, Withdraw};
+
+pub type FastWithdrawalIntentId = i64;
+
+/// Maximum time the intent is offered to the liquidity providers, in seconds.
+pub const FAST_WITHDRAWAL_INTENT_LIFETIME_SECS: u64 = 24 * 60 * 60;
+
+/// Intent to sell the withdrawal to the liquidity provider, signed by the user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FastWithdrawalIntent {
+    /// Withdrawal to the L1 address of the liquidity provider.
+    pub withdraw: Withdraw,
+    /// Amount of the withdrawn token the liquidity provider transfers to the user in L2.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub payout: BigUint,
+    /// Ethereum signature of the intent message. Not required for CREATE2 accounts.
+    pub eth_signature: Option<TxEthSignature>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum FastWithdrawalIntentError {
+    #[error("Payout must be positive and must not exceed the withdrawn amount")]
+    IncorrectPayout,
+    #[error("Withdrawal is already marked as fast")]
+    FastWithdrawal,
+    #[error("Withdrawal is incorrect")]
+    IncorrectWithdraw,
+}
+
+impl FastWithdrawalIntent {
+    /// L1 address receiving the withdrawn funds, which is also the L2 address paying the payout.
+    pub fn liquidity_provider(&self) -> Address {
+        self.withdraw.to
+    }
+
+    /// Message that should be signed by the Ethereum key of the account.
+    /// It authorizes both the withdrawal and the payout it's sold for.
+    pub fn get_ethereum_sign_message(&self, token_symbol: &str, decimals: u8) -> String {
+        format!(
+            "{}\nFast withdrawal payout: {} {}",
+            self.withdraw
+                .get_ethereum_sign_message(token_symbol, decimals),
+            format_units(&self.payout, decimals),
+            token_symbol,
+        )
+    }
+
+    /// Returns the UNIX timestamp after which the intent submitted at `created_at` can't be fulfilled.
+    pub fn valid_until(&self, created_at: DateTime<Utc>) -> u64 {
+        let max_valid_until = (created_at.timestamp().max(0) as u64)
+            .saturating_add(FAST_WITHDRAWAL_INTENT_LIFETIME_SECS);
+        let withdraw_valid_until = self
+            .withdraw
+            .time_range
+            .map(|time_range| time_range.valid_until)
+            .unwrap_or(u64::MAX);
+        std::cmp::min(withdraw_valid_until, max_valid_until)
+    }
+
+    /// Checks the intent data and the zkSync signature of the withdrawal.
+    pub fn check_correctness(&mut self) -> Result<(), FastWithdrawalIntentError> {
+        if self.payout.is_zero() || self.payout > self.withdraw.amount {
+            return Err(FastWithdrawalIntentError::IncorrectPayout);
+        }
+        // `fast` flag is set by the server once the intent is fulfilled.
+        if self.withdraw.fast {
+            return Err(FastWithdrawalIntentError::FastWithdrawal);
+        }
+        if !self.withdraw.check_correctness() {
+            return Err(FastWithdrawalIntentError::IncorrectWithdraw);
+        }
+        Ok(())
+    }
+
+    /// Returns the public part of the intent the liquidity providers can see.
+    pub fn offer(
+        &self,
+        id: FastWithdrawalIntentId,
+        created_at: DateTime<Utc>,
+    ) -> FastWithdrawalOffer {
+        FastWithdrawalOffer {
+            id,
+            account_id: self.withdraw.account_id,
+            user: self.withdraw.from,
+            liquidity_provider: self.liquidity_provider(),
+            token: self.withdraw.token,
+            amount: self.withdraw.amount.clone(),
+            payout: self.payout.clone(),
+            fee: self.withdraw.fee.clone(),
+            nonce: self.withdraw.nonce,
+            valid_until: self.valid_until(created_at),
+            created_at,
+        }
+    }
+}
+
+/// Intent accepted by the server.
+#[derive(Debug, Clone)]
+pub struct StoredFastWithdrawalIntent {
+    pub id: FastWithdrawalIntentId,
+    pub intent: FastWithdrawalIntent,
+    pub created_at: DateTime<Utc>,
+    /// UNIX timestamp after which the intent can't be fulfilled.
+    pub valid_until: u64,
+    /// Hash of the payout transfer.
+    pub fulfilled_by: Option<TxHash>,
+    pub fulfilled_at: Option<DateTime<Utc>>,
+}
+
+/// Pending intent as seen by the liquidity providers.
+/// Signatures are not disclosed, so the withdrawal can't be executed without the payout.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FastWithdrawalOffer {
+    pub id: FastWithdrawalIntentId,
+    pub account_id: AccountId,
+    pub user: Address,
+    pub liquidity_provider: Address,
+    pub token: TokenId,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub payout: BigUint,
+    /// Withdrawal fee paid by the user.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub fee: BigUint,
+    pub nonce: Nonce,
+    /// UNIX timestamp after which the intent can't be fulfilled.
+    pub valid_until: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tx::TimeRange;
+
+    fn intent(payout: u64) -> FastWithdrawalIntent {
+        let withdraw = Withdraw::new(
+            AccountId(1),
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            TokenId(0),
+            100u64.into(),
+            1u64.into(),
+            Nonce(3),
+            TimeRange::default(),
+            None,
+        );
+        FastWithdrawalIntent {
+            withdraw,
+            payout: payout.into(),
+            eth_signature: None,
+        }
+    }
+
+    #[test]
+    fn payout_bounds() {
+        assert_eq!(
+            intent(0).check_correctness(),
+            Err(FastWithdrawalIntentError::IncorrectPayout)
+        );
+        assert_eq!(
+            intent(101).check_correctness(),
+            Err(FastWithdrawalIntentError::IncorrectPayout)
+        );
+        // Payout is correct, but the withdrawal is not signed.
+        assert_eq!(
+            intent(95).check_correctness(),
+            Err(FastWithdrawalIntentError::IncorrectWithdraw)
+        );
+    }
+
+    #[test]
+    fn sign_message_includes_payout() {
+        let intent = intent(95);
+        let message = intent.get_ethereum_sign_message("ETH", 18);
+        assert!(message.starts_with(&intent.withdraw.get_ethereum_sign_message("ETH", 18)));
+        assert!(message.ends_with("Fast withdrawal payout: 0.000000000000000095 ETH"));
+        assert_eq!(intent.liquidity_provider(), Address::repeat_byte(2));
+    }
+
+    #[test]
+    fn intent_expiry() {
+        let created_at = Utc::now();
+        let timestamp = created_at.timestamp() as u64;
+
+        let mut intent = intent(95);
+        assert_eq!(
+            intent.valid_until(created_at),
+            timestamp + FAST_WITHDRAWAL_INTENT_LIFETIME_SECS
+        );
+        // The intent is not offered after the end of the withdrawal time range.
+        intent.withdraw.time_range = Some(TimeRange::new(0, timestamp + 60));
+        assert_eq!(intent.valid_until(created_at), timestamp + 60);
+        assert_eq!(intent.offer(1, created_at).valid_until, timestamp + 60);
+    }
+}
@@ -48,6 +48,22 @@ impl BatchFee {
         total_fee = closest_packable_fee_amount(&total_fee);
         BatchFee { total_fee }
     }
+
+    /// Increases the fee by the given markup in percents.
+    pub fn with_markup(self, markup_percent: u32) -> Self {
+        let total_fee = Ratio::from_integer(self.total_fee) * markup_ratio(markup_percent);
+        let total_fee = round_precision(&total_fee, 18).ceil().to_integer();
+        BatchFee {
+            total_fee: closest_packable_fee_amount(&total_fee),
+        }
+    }
+}
+
+fn markup_ratio(markup_percent: u32) -> Ratio<BigUint> {
+    Ratio::new(
+        BigUint::from(100u64 + u64::from(markup_percent)),
+        BigUint::from(100u32),
+    )
 }
 
 impl Fee {
@@ -68,6 +84,18 @@ impl Fee {
             total_fee,
         }
     }
+
+    /// Increases the fee by the given markup in percents.
+    pub fn with_markup(self, markup_percent: u32) -> Self {
+        let markup = markup_ratio(markup_percent);
+        Self::new(
+            self.fee_type,
+            Ratio::from_integer(self.zkp_fee) * &markup,
+            Ratio::from_integer(self.gas_fee) * &markup,
+            self.gas_tx_amount,
+            self.gas_price_wei,
+        )
+    }
 }
 
 fn total_fee(zkp_fee: &Ratio<BigUint>, gas_fee: &Ratio<BigUint>) -> (BigUint, BigUint, BigUint) {
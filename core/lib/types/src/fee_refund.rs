@@ -0,0 +1,194 @@
+//! Refunds of the fees paid over the actual fee.
+//!
+//! The fee of the transaction is a part of its signed data, so the block always charges the signed
+//! fee. With the refunds enabled, the server records the fee it computed for the transaction on
+//! submission, and once the transaction is executed, the difference between the signed fee and the
+//! computed one is transferred back to the sender from the fee account. This way the users pay what
+//! the transactions actually cost even if their wallets quote the fees with a margin.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use num::{BigUint, Zero};
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{Address, TokenId};
+use zksync_utils::BigUintSerdeAsRadix10Str;
+
+use crate::{tokens::Token, tx::TxHash};
+
+/// Status of the fee refund.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FeeRefundStatus {
+    /// The transaction is not executed yet.
+    Pending,
+    /// The refund transfer is added to the mempool.
+    Sent,
+    /// The refund transfer is executed in a sealed block.
+    Refunded,
+    /// The refund transfer is rejected.
+    Failed,
+    /// Nothing is refunded, e.g. because the transaction itself failed.
+    Cancelled,
+}
+
+impl FeeRefundStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Sent => "sent",
+            Self::Refunded => "refunded",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl FromStr for FeeRefundStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "sent" => Ok(Self::Sent),
+            "refunded" => Ok(Self::Refunded),
+            "failed" => Ok(Self::Failed),
+            "cancelled" => Ok(Self::Cancelled),
+            other => Err(format!("Unknown fee refund status: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for FeeRefundStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Recorded refund of the fee paid over the actual one.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeRefund {
+    pub id: i64,
+    /// Hash of the transaction the fee is refunded for.
+    pub tx_hash: TxHash,
+    /// Account which paid the fee.
+    pub address: Address,
+    pub token: TokenId,
+    /// Fee signed by the user and charged by the block.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub signed_fee: BigUint,
+    /// Fee computed by the server when the transaction was submitted.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub actual_fee: BigUint,
+    /// Amount of the refund transfer, zero until it's sent. It's rounded down to the packable amount,
+    /// so it can be slightly less than the difference of the fees.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub refund: BigUint,
+    pub refund_tx_hash: Option<TxHash>,
+    pub status: FeeRefundStatus,
+    pub fail_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl FeeRefund {
+    /// Returns the part of the signed fee to be refunded.
+    pub fn differential(&self) -> BigUint {
+        if self.signed_fee > self.actual_fee {
+            &self.signed_fee - &self.actual_fee
+        } else {
+            BigUint::zero()
+        }
+    }
+
+    /// Returns the fee paid by the user so far, i.e. the signed fee minus the executed refund.
+    pub fn effective_fee(&self) -> BigUint {
+        if self.status == FeeRefundStatus::Refunded {
+            &self.signed_fee - &self.refund
+        } else {
+            self.signed_fee.clone()
+        }
+    }
+}
+
+/// Fee paid for the transaction, as reported by the API.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveFee {
+    pub tx_hash: TxHash,
+    pub token: TokenId,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub signed_fee: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub actual_fee: BigUint,
+    /// Signed fee minus the executed refund.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub effective_fee: BigUint,
+    /// Effective fee in the whole tokens, e.g. `0.0015`.
+    pub formatted_effective_fee: String,
+    pub refund_status: FeeRefundStatus,
+    pub refund_tx_hash: Option<TxHash>,
+}
+
+impl EffectiveFee {
+    /// Creates the effective fee of the refund charged in the given token.
+    pub fn new(refund: FeeRefund, token: &Token) -> Self {
+        let effective_fee = refund.effective_fee();
+        Self {
+            formatted_effective_fee: token.format_amount(&effective_fee),
+            effective_fee,
+            tx_hash: refund.tx_hash,
+            token: refund.token,
+            signed_fee: refund.signed_fee,
+            actual_fee: refund.actual_fee,
+            refund_status: refund.status,
+            refund_tx_hash: refund.refund_tx_hash,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn effective_fee() {
+        let mut refund = FeeRefund {
+            id: 1,
+            tx_hash: TxHash::default(),
+            address: Address::repeat_byte(1),
+            token: TokenId(0),
+            signed_fee: BigUint::from(1050u32),
+            actual_fee: BigUint::from(1000u32),
+            refund: BigUint::zero(),
+            refund_tx_hash: None,
+            status: FeeRefundStatus::Pending,
+            fail_reason: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        assert_eq!(refund.differential(), BigUint::from(50u32));
+        assert_eq!(refund.effective_fee(), BigUint::from(1050u32));
+
+        // Only the executed refund reduces the fee.
+        refund.refund = BigUint::from(48u32);
+        refund.status = FeeRefundStatus::Sent;
+        assert_eq!(refund.effective_fee(), BigUint::from(1050u32));
+        refund.status = FeeRefundStatus::Refunded;
+        let effective_fee =
+            EffectiveFee::new(refund, &Token::new(TokenId(0), Address::zero(), "ETH", 6));
+        assert_eq!(effective_fee.effective_fee, BigUint::from(1002u32));
+        assert_eq!(effective_fee.formatted_effective_fee, "0.001002");
+
+        for status in &[
+            FeeRefundStatus::Pending,
+            FeeRefundStatus::Sent,
+            FeeRefundStatus::Refunded,
+            FeeRefundStatus::Failed,
+            FeeRefundStatus::Cancelled,
+        ] {
+            assert_eq!(status.as_str().parse::<FeeRefundStatus>(), Ok(*status));
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! Sweeps of the fees collected by the operator.
+//!
+//! The fees of every block are credited to the fee account, which can be separate from the account
+//! committing the blocks on L1. The fee sweeper moves the collected fees from the fee account to the
+//! treasury once the balance of the token reaches the configured threshold, either with an L2 transfer
+//! or with a withdrawal to L1. Every sweep is recorded along with its outcome, so the recorded sweeps
+//! form the audit trail of the collected fees leaving the fee account.
+
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zksync_basic_types::{Address, TokenId};
+use zksync_utils::BigUintSerdeAsRadix10Str;
+
+use crate::tx::TxHash;
+
+/// The way the fees are moved to the treasury.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FeeSweepKind {
+    /// L2 transfer to the treasury account.
+    Transfer,
+    /// Withdrawal to the treasury address on L1.
+    Withdraw,
+}
+
+impl FeeSweepKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Transfer => "transfer",
+            Self::Withdraw => "withdraw",
+        }
+    }
+}
+
+impl FromStr for FeeSweepKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "transfer" => Ok(Self::Transfer),
+            "withdraw" => Ok(Self::Withdraw),
+            other => Err(format!("Unknown fee sweep kind: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for FeeSweepKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Status of the sweep transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FeeSweepStatus {
+    /// The transaction is added to the mempool.
+    Sent,
+    /// The transaction is executed in a sealed block.
+    Executed,
+    /// The transaction is rejected by the state keeper.
+    Failed,
+}
+
+impl FeeSweepStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Sent => "sent",
+            Self::Executed => "executed",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl FromStr for FeeSweepStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sent" => Ok(Self::Sent),
+            "executed" => Ok(Self::Executed),
+            "failed" => Ok(Self::Failed),
+            other => Err(format!("Unknown fee sweep status: {}", other)),
+        }
+    }
+}
+
+/// Recorded sweep of the fees in one token.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeSweep {
+    pub id: i64,
+    pub token: TokenId,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+    pub treasury: Address,
+    pub kind: FeeSweepKind,
+    pub tx_hash: TxHash,
+    pub status: FeeSweepStatus,
+    pub fail_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Error, PartialEq)]
+#[error("Invalid fee sweep threshold '{0}', expected 'token_id:threshold'")]
+pub struct InvalidSweepThreshold(String);
+
+/// Balances of the fee account the tokens are swept at. Tokens without the threshold are not swept.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SweepThresholds {
+    tokens: BTreeMap<TokenId, BigUint>,
+}
+
+impl SweepThresholds {
+    /// Parses the thresholds from the `token_id:threshold` entries of the config.
+    pub fn parse(entries: &[String]) -> Result<Self, InvalidSweepThreshold> {
+        let tokens = entries
+            .iter()
+            .map(|entry| {
+                let invalid = || InvalidSweepThreshold(entry.clone());
+                let parts: Vec<_> = entry.split(':').collect();
+                match parts.as_slice() {
+                    [token, threshold] => Ok((
+                        TokenId(u16::from_str(token).map_err(|_| invalid())?),
+                        BigUint::from_str(threshold).map_err(|_| invalid())?,
+                    )),
+                    _ => Err(invalid()),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { tokens })
+    }
+
+    /// Returns the tokens with the thresholds, ordered by the token ID.
+    pub fn iter(&self) -> impl Iterator<Item = (TokenId, &BigUint)> {
+        self.tokens
+            .iter()
+            .map(|(token, threshold)| (*token, threshold))
+    }
+
+    /// Checks whether the balance of the token should be swept.
+    pub fn is_reached(&self, token: TokenId, balance: &BigUint) -> bool {
+        self.tokens.get(&token).map_or(false, |threshold| {
+            *balance >= *threshold && *balance > BigUint::from(0u32)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sweep_thresholds() {
+        let thresholds = SweepThresholds::parse(&["0:1000".to_owned(), "2:0".to_owned()]).unwrap();
+        assert!(thresholds.is_reached(TokenId(0), &BigUint::from(1000u32)));
+        assert!(!thresholds.is_reached(TokenId(0), &BigUint::from(999u32)));
+        // Zero balance is never swept.
+        assert!(!thresholds.is_reached(TokenId(2), &BigUint::from(0u32)));
+        assert!(thresholds.is_reached(TokenId(2), &BigUint::from(1u32)));
+        // Tokens without the threshold are not swept.
+        assert!(!thresholds.is_reached(TokenId(1), &BigUint::from(1000u32)));
+
+        for entry in &["0", "0:1:2", "x:1", "0:-1"] {
+            assert_eq!(
+                SweepThresholds::parse(&[entry.to_string()]),
+                Err(InvalidSweepThreshold(entry.to_string()))
+            );
+        }
+    }
+}
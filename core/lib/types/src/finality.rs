@@ -0,0 +1,117 @@
+//! Finality of the data served by the API.
+//!
+//! Every block goes through the following stages once it's sealed by the server:
+//! the block is committed on L1 (`commitBlocks`), its proof is verified by the contract
+//! (`proveBlocks`), and finally the block is executed (`executeBlocks`), so its withdrawals
+//! are sent and the state becomes final. Each stage counts only once the corresponding
+//! Ethereum transaction is confirmed.
+//!
+//! Note that the `verified` flags of the older API responses correspond to the `Executed`
+//! finality, since they are set once the blocks are executed.
+
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::BlockNumber;
+
+use crate::ActionType;
+
+/// Finality level, from the weakest to the strongest one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Finality {
+    /// The data is known to the server only: the transaction is in the mempool
+    /// or the block is not committed on L1 yet.
+    Pending,
+    /// The block is committed on L1.
+    Committed,
+    /// The proof of the block is verified on L1.
+    Verified,
+    /// The block is executed on L1, the state is final.
+    Executed,
+}
+
+impl Default for Finality {
+    fn default() -> Self {
+        Self::Pending
+    }
+}
+
+impl From<ActionType> for Finality {
+    fn from(action: ActionType) -> Self {
+        match action {
+            ActionType::COMMIT => Self::Committed,
+            ActionType::VERIFY => Self::Executed,
+        }
+    }
+}
+
+/// Last blocks that have reached each of the finality levels on L1.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinalizedBlocks {
+    pub committed: BlockNumber,
+    pub verified: BlockNumber,
+    pub executed: BlockNumber,
+}
+
+impl FinalizedBlocks {
+    /// Returns the finality the block has reached.
+    pub fn block_finality(&self, block_number: BlockNumber) -> Finality {
+        if block_number <= self.executed {
+            Finality::Executed
+        } else if block_number <= self.verified {
+            Finality::Verified
+        } else if block_number <= self.committed {
+            Finality::Committed
+        } else {
+            Finality::Pending
+        }
+    }
+
+    /// Returns the last block that has reached the finality,
+    /// `None` if there is no bound (i.e. for the pending data).
+    pub fn last_block(&self, finality: Finality) -> Option<BlockNumber> {
+        match finality {
+            Finality::Pending => None,
+            Finality::Committed => Some(self.committed),
+            Finality::Verified => Some(self.verified),
+            Finality::Executed => Some(self.executed),
+        }
+    }
+
+    /// Checks whether the block has reached the finality.
+    pub fn is_finalized(&self, block_number: BlockNumber, finality: Finality) -> bool {
+        self.block_finality(block_number) >= finality
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_finality() {
+        let finalized = FinalizedBlocks {
+            committed: BlockNumber(10),
+            verified: BlockNumber(7),
+            executed: BlockNumber(5),
+        };
+
+        assert_eq!(finalized.block_finality(BlockNumber(3)), Finality::Executed);
+        assert_eq!(finalized.block_finality(BlockNumber(5)), Finality::Executed);
+        assert_eq!(finalized.block_finality(BlockNumber(6)), Finality::Verified);
+        assert_eq!(
+            finalized.block_finality(BlockNumber(10)),
+            Finality::Committed
+        );
+        assert_eq!(finalized.block_finality(BlockNumber(11)), Finality::Pending);
+
+        assert!(finalized.is_finalized(BlockNumber(6), Finality::Committed));
+        assert!(!finalized.is_finalized(BlockNumber(6), Finality::Executed));
+        assert!(finalized.is_finalized(BlockNumber(100), Finality::Pending));
+        assert_eq!(finalized.last_block(Finality::Pending), None);
+        assert_eq!(
+            finalized.last_block(Finality::Verified),
+            Some(BlockNumber(7))
+        );
+    }
+}
@@ -9,7 +9,7 @@ use serde::{Deserialize, Serialize};
 pub type ForcedExitRequestId = i64;
 
 use ethabi::{decode, ParamType};
-use std::convert::TryFrom;
+use std::{convert::TryFrom, str::FromStr};
 use zksync_basic_types::Log;
 
 use crate::tx::TxHash;
@@ -44,6 +44,58 @@ pub struct FundsReceivedEvent {
     pub block_number: u64,
 }
 
+/// Outcome of the ForcedExit fee paid with an L2 transfer to the ForcedExit sender account.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum ForcedExitPaymentStatus {
+    /// Transactions for the payment are sent, but not committed yet.
+    Pending,
+    /// ForcedExit transactions for the paid request were sent.
+    Fulfilled,
+    /// Payment did not match any valid request and was sent back to the payer.
+    Refunded,
+}
+
+impl ForcedExitPaymentStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Fulfilled => "fulfilled",
+            Self::Refunded => "refunded",
+        }
+    }
+}
+
+impl FromStr for ForcedExitPaymentStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "fulfilled" => Ok(Self::Fulfilled),
+            "refunded" => Ok(Self::Refunded),
+            other => Err(format!("Unknown ForcedExit payment status: {}", other)),
+        }
+    }
+}
+
+/// L2 transfer paying for the ForcedExit request.
+///
+/// Request ID is encoded in the lower digits of the transferred amount, the same way
+/// as for the payments sent to the ForcedExit contract on L1.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct ForcedExitL2Payment {
+    pub tx_hash: TxHash,
+    pub from: Address,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+    pub request_id: Option<ForcedExitRequestId>,
+    pub status: ForcedExitPaymentStatus,
+    pub refund_tx_hash: Option<TxHash>,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct ForcedExitEligibilityResponse {
     pub eligible: bool,
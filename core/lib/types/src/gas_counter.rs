@@ -5,7 +5,10 @@
 // Workspace deps
 use zksync_basic_types::*;
 // Local deps
-use crate::{config::MAX_WITHDRAWALS_TO_COMPLETE_IN_A_CALL, Block, ZkSyncOp};
+use crate::{
+    config::MAX_WITHDRAWALS_TO_COMPLETE_IN_A_CALL, tokens::ChangePubKeyFeeTypeArg,
+    tx::ChangePubKeyType, Block, ZkSyncOp,
+};
 
 /// Amount of gas that we can afford to spend in one transaction.
 /// This value must be big enough to fit big blocks with expensive transactions,
@@ -29,6 +32,8 @@ impl CommitCost {
     pub const CHANGE_PUBKEY_COST_OFFCHAIN: u64 = 11_050;
     pub const CHANGE_PUBKEY_COST_ONCHAIN: u64 = 5_530;
     pub const CHANGE_PUBKEY_COST_CREATE2: u64 = 7_330;
+    // Estimated for the largest guardian set: up to 9 signatures to recover and ~800 bytes of the witness.
+    pub const CHANGE_PUBKEY_COST_GUARDIANS: u64 = 55_000;
     pub const TRANSFER_COST: u64 = 250;
     pub const TRANSFER_TO_NEW_COST: u64 = 780;
     pub const FULL_EXIT_COST: u64 = 7_000;
@@ -43,6 +48,12 @@ impl CommitCost {
         let cost = match op {
             ZkSyncOp::Noop(_) => 0,
             ZkSyncOp::Deposit(_) => Self::DEPOSIT_COST,
+            ZkSyncOp::ChangePubKeyOffchain(change_pubkey)
+                if change_pubkey.tx.get_change_pubkey_fee_type()
+                    == ChangePubKeyFeeTypeArg::ContractsV4Version(ChangePubKeyType::Guardians) =>
+            {
+                Self::CHANGE_PUBKEY_COST_GUARDIANS
+            }
             ZkSyncOp::ChangePubKeyOffchain(_change_pubkey) => {
                 Self::OLD_CHANGE_PUBKEY_COST_OFFCHAIN
                 // TODO: Restore when we figure out why this failed [ZKS-554]
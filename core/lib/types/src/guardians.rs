@@ -0,0 +1,361 @@
+//! Social recovery of the accounts via guardians.
+//!
+//! The owner of the account registers the set of guardians, i.e. the Ethereum addresses trusted
+//! to restore the access to the account, together with the number of them required to agree (the
+//! quorum) and the timelock. If the owner loses the signing key, the guardians sign the recovery
+//! message binding the new public key hash to the current nonce of the account, and after the
+//! timelock passes, anyone can submit the `ChangePubKey` transaction authorized by the guardian
+//! signatures instead of the owner one.
+//!
+//! The contract checks that the guardian set in the witness is signed by the owner and that
+//! the quorum of its guardians signed the new public key hash. The registry of the current sets
+//! and the timelock are maintained by the operator: the mempool only accepts the recovery for the
+//! latest registered set once the timelock of the initiated recovery has passed, and checks the set
+//! again before proposing the recovery for the block. The owner cancels the recovery by executing
+//! any transaction (so the nonce changes) or by registering another set.
+//!
+//! The hash of the set is bound to the chain ID and the address of the contract (see `SignatureDomain`),
+//! so neither the owner signature nor the guardian ones can be replayed on another network.
+//!
+//! Trust model: the registrations and the recoveries are not recorded on-chain, so neither the
+//! contract nor the circuit can check the version of the set and the timelock. The owner trusts
+//! the operator to enforce them: the operator colluding with the quorum of any guardian set ever
+//! signed by the owner can change the key without waiting for the timelock. The owner who suspects
+//! the guardians of a previous set must move the funds to a new account rather than rely on the
+//! registration of another set.
+
+use chrono::{DateTime, Utc};
+use parity_crypto::Keccak256;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zksync_basic_types::{AccountId, Address, Nonce, H256};
+
+use crate::{
+    account::PubKeyHash,
+    tx::{PackedEthSignature, SignatureDomain},
+};
+
+/// Maximum number of the guardians of the account.
+/// Bounds the size of the witness, so the `ChangePubKey` fits into `CommitCost::CHANGE_PUBKEY_COST_GUARDIANS`.
+pub const MAX_GUARDIANS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum GuardianSetError {
+    #[error("Guardian set must contain from 1 to {} guardians", MAX_GUARDIANS)]
+    InvalidSize,
+    #[error("Quorum must be from 1 to the number of the guardians")]
+    InvalidQuorum,
+    #[error("Guardians must be distinct non-zero addresses")]
+    InvalidGuardian,
+}
+
+/// Guardians of the account and the conditions of the recovery.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GuardianSet {
+    /// Version of the set, must increase with every registration of the account.
+    /// Makes the previous registrations impossible to replay.
+    pub version: u32,
+    pub guardians: Vec<Address>,
+    /// Number of the guardian signatures required for the recovery.
+    pub quorum: u8,
+    /// Time between the initiation of the recovery and the moment it can be executed, in seconds.
+    pub timelock: u32,
+}
+
+impl GuardianSet {
+    /// Checks that the set is well-formed.
+    pub fn check(&self) -> Result<(), GuardianSetError> {
+        if self.guardians.is_empty() || self.guardians.len() > MAX_GUARDIANS {
+            return Err(GuardianSetError::InvalidSize);
+        }
+        if self.quorum == 0 || self.quorum as usize > self.guardians.len() {
+            return Err(GuardianSetError::InvalidQuorum);
+        }
+        let distinct = self
+            .guardians
+            .iter()
+            .enumerate()
+            .all(|(i, guardian)| !self.guardians[..i].contains(guardian));
+        if !distinct || self.guardians.contains(&Address::zero()) {
+            return Err(GuardianSetError::InvalidGuardian);
+        }
+        Ok(())
+    }
+
+    /// Returns the part of the `ChangePubKey` witness encoding the set.
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&self.version.to_be_bytes());
+        bytes.push(self.quorum);
+        bytes.extend_from_slice(&self.timelock.to_be_bytes());
+        bytes.push(self.guardians.len() as u8);
+        for guardian in &self.guardians {
+            bytes.extend_from_slice(guardian.as_bytes());
+        }
+        bytes
+    }
+
+    /// Returns the hash of the set to be signed by the owner of the account.
+    pub fn hash(&self, account_id: AccountId, domain: &SignatureDomain) -> H256 {
+        let mut bytes = b"zkSync guardians".to_vec();
+        bytes.extend_from_slice(&domain.encode());
+        bytes.extend_from_slice(&account_id.0.to_be_bytes());
+        bytes.extend_from_slice(&self.version.to_be_bytes());
+        bytes.push(self.quorum);
+        bytes.extend_from_slice(&self.timelock.to_be_bytes());
+        for guardian in &self.guardians {
+            bytes.extend_from_slice(guardian.as_bytes());
+        }
+        H256::from(bytes.keccak256())
+    }
+
+    /// Returns the hash of the recovery message to be signed by the guardians.
+    pub fn recovery_hash(
+        &self,
+        account_id: AccountId,
+        domain: &SignatureDomain,
+        new_pk_hash: &PubKeyHash,
+        nonce: Nonce,
+    ) -> H256 {
+        let mut bytes = b"zkSync recovery".to_vec();
+        bytes.extend_from_slice(self.hash(account_id, domain).as_bytes());
+        bytes.extend_from_slice(&new_pk_hash.data);
+        bytes.extend_from_slice(&nonce.0.to_be_bytes());
+        H256::from(bytes.keccak256())
+    }
+
+    /// Checks that the signatures are made by the quorum of distinct guardians, given in the order
+    /// of the guardian indices.
+    pub fn verify_quorum(&self, hash: &H256, signatures: &[GuardianSignature]) -> bool {
+        if signatures.len() != self.quorum as usize {
+            return false;
+        }
+        let ordered = signatures
+            .windows(2)
+            .all(|pair| pair[0].index < pair[1].index);
+        ordered
+            && signatures.iter().all(|signature| {
+                let signer = signature
+                    .signature
+                    .signature_recover_signer(hash.as_bytes())
+                    .ok();
+                signer.is_some() && self.guardians.get(signature.index as usize) == signer.as_ref()
+            })
+    }
+
+    /// Checks that the set is signed by the owner of the account.
+    pub fn verify_owner(
+        &self,
+        account_id: AccountId,
+        domain: &SignatureDomain,
+        owner: Address,
+        signature: &PackedEthSignature,
+    ) -> bool {
+        signature
+            .signature_recover_signer(self.hash(account_id, domain).as_bytes())
+            .map(|signer| signer == owner)
+            .unwrap_or(false)
+    }
+}
+
+/// Signature of the recovery message by the guardian.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GuardianSignature {
+    /// Index of the guardian in the set.
+    pub index: u8,
+    pub signature: PackedEthSignature,
+}
+
+/// Authorization of the `ChangePubKey` transaction by the guardians of the account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePubKeyGuardiansData {
+    /// Network the signatures are made for. It's not a part of the witness: the contract checks
+    /// the signatures against its own domain, and the API rejects the transactions for the other ones.
+    pub domain: SignatureDomain,
+    pub guardian_set: GuardianSet,
+    /// Signature of the guardian set by the owner of the account.
+    pub owner_signature: PackedEthSignature,
+    /// Signatures of the recovery message by the guardians.
+    pub signatures: Vec<GuardianSignature>,
+}
+
+impl ChangePubKeyGuardiansData {
+    /// Checks that the guardian set is signed by the owner and the quorum of its guardians
+    /// signed the new public key hash.
+    pub fn verify(
+        &self,
+        account_id: AccountId,
+        owner: Address,
+        new_pk_hash: &PubKeyHash,
+        nonce: Nonce,
+    ) -> bool {
+        let recovery_hash =
+            self.guardian_set
+                .recovery_hash(account_id, &self.domain, new_pk_hash, nonce);
+        self.guardian_set.check().is_ok()
+            && self.guardian_set.verify_owner(
+                account_id,
+                &self.domain,
+                owner,
+                &self.owner_signature,
+            )
+            && self
+                .guardian_set
+                .verify_quorum(&recovery_hash, &self.signatures)
+    }
+
+    /// Returns the witness checked by the contract, without the leading type byte.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = self.guardian_set.encode();
+        bytes.extend_from_slice(&self.owner_signature.serialize_packed());
+        for signature in &self.signatures {
+            bytes.push(signature.index);
+            bytes.extend_from_slice(&signature.signature.serialize_packed());
+        }
+        bytes
+    }
+}
+
+/// Request to register the guardians of the account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuardianRegistrationRequest {
+    pub account_id: AccountId,
+    pub guardian_set: GuardianSet,
+    /// Signature of the `GuardianSet::hash` by the owner of the account.
+    pub owner_signature: PackedEthSignature,
+}
+
+/// Guardians registered by the account.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GuardianRegistration {
+    pub account_id: AccountId,
+    pub address: Address,
+    pub guardian_set: GuardianSet,
+    pub owner_signature: PackedEthSignature,
+    pub registered_at: DateTime<Utc>,
+}
+
+/// Request to initiate the recovery of the account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuardianRecoveryRequest {
+    pub account_id: AccountId,
+    pub new_pk_hash: PubKeyHash,
+    /// Nonce of the `ChangePubKey` transaction, i.e. the current nonce of the account.
+    pub nonce: Nonce,
+    /// Signatures of the `GuardianSet::recovery_hash` by the quorum of the guardians.
+    pub signatures: Vec<GuardianSignature>,
+}
+
+/// Initiated recovery of the account.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GuardianRecovery {
+    pub account_id: AccountId,
+    pub new_pk_hash: PubKeyHash,
+    pub nonce: Nonce,
+    pub signatures: Vec<GuardianSignature>,
+    pub initiated_at: DateTime<Utc>,
+    /// Time the `ChangePubKey` transaction is accepted from.
+    pub executable_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guardian_keys() -> Vec<H256> {
+        (1..=3).map(H256::repeat_byte).collect()
+    }
+
+    fn guardian_set() -> GuardianSet {
+        GuardianSet {
+            version: 1,
+            guardians: guardian_keys()
+                .iter()
+                .map(|key| PackedEthSignature::address_from_private_key(key).unwrap())
+                .collect(),
+            quorum: 2,
+            timelock: 86400,
+        }
+    }
+
+    #[test]
+    fn guardian_set_check() {
+        assert_eq!(guardian_set().check(), Ok(()));
+
+        let mut set = guardian_set();
+        set.quorum = 4;
+        assert_eq!(set.check(), Err(GuardianSetError::InvalidQuorum));
+        set.quorum = 0;
+        assert_eq!(set.check(), Err(GuardianSetError::InvalidQuorum));
+
+        let mut set = guardian_set();
+        set.guardians.push(set.guardians[0]);
+        assert_eq!(set.check(), Err(GuardianSetError::InvalidGuardian));
+        set.guardians = vec![Address::zero()];
+        set.quorum = 1;
+        assert_eq!(set.check(), Err(GuardianSetError::InvalidGuardian));
+        set.guardians = Vec::new();
+        assert_eq!(set.check(), Err(GuardianSetError::InvalidSize));
+    }
+
+    #[test]
+    fn guardians_authorization() {
+        let owner_key = H256::repeat_byte(7);
+        let owner = PackedEthSignature::address_from_private_key(&owner_key).unwrap();
+        let account_id = AccountId(5);
+        let new_pk_hash = PubKeyHash::default();
+        let nonce = Nonce(3);
+
+        let domain = SignatureDomain::new(1, Address::repeat_byte(9));
+
+        let set = guardian_set();
+        let owner_signature =
+            PackedEthSignature::sign(&owner_key, set.hash(account_id, &domain).as_bytes()).unwrap();
+        let recovery_hash = set.recovery_hash(account_id, &domain, &new_pk_hash, nonce);
+        let sign = |index: u8| GuardianSignature {
+            index,
+            signature: PackedEthSignature::sign(
+                &guardian_keys()[index as usize],
+                recovery_hash.as_bytes(),
+            )
+            .unwrap(),
+        };
+
+        let mut data = ChangePubKeyGuardiansData {
+            domain,
+            guardian_set: set,
+            owner_signature,
+            signatures: vec![sign(0), sign(2)],
+        };
+        assert!(data.verify(account_id, owner, &new_pk_hash, nonce));
+        // The recovery is bound to the nonce and the owner.
+        assert!(!data.verify(account_id, owner, &new_pk_hash, Nonce(4)));
+        assert!(!data.verify(account_id, Address::repeat_byte(1), &new_pk_hash, nonce));
+        // The signatures can't be replayed on another network.
+        data.domain.chain_id = 4;
+        assert!(!data.verify(account_id, owner, &new_pk_hash, nonce));
+        data.domain = domain;
+
+        // Same guardian can't sign twice, and the signatures must match the indices.
+        data.signatures = vec![sign(0), sign(0)];
+        assert!(!data.verify(account_id, owner, &new_pk_hash, nonce));
+        data.signatures = vec![sign(2), sign(0)];
+        assert!(!data.verify(account_id, owner, &new_pk_hash, nonce));
+        data.signatures = vec![sign(1)];
+        assert!(!data.verify(account_id, owner, &new_pk_hash, nonce));
+
+        data.signatures = vec![sign(1), sign(2)];
+        let witness = data.encode();
+        // version, quorum, timelock, count, 3 guardians, owner signature, 2 guardian signatures
+        assert_eq!(witness.len(), 4 + 1 + 4 + 1 + 3 * 20 + 65 + 2 * 66);
+        assert_eq!(witness[4], 2);
+        assert_eq!(witness[9], 3);
+    }
+}
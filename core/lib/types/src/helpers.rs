@@ -1,6 +1,8 @@
-use num::{BigUint, FromPrimitive};
+use num::{BigUint, FromPrimitive, One};
+use serde::{Deserialize, Serialize};
 use zksync_crypto::params;
 use zksync_crypto::primitives::FloatConversions;
+use zksync_utils::BigUintSerdeAsRadix10Str;
 
 use crate::{Account, AccountMap, AccountUpdates};
 
@@ -131,6 +133,98 @@ pub fn closest_greater_or_eq_packable_token_amount(amount: &BigUint) -> BigUint
     unpack_token_amount(&fee_packed).expect("token amount repacking")
 }
 
+/// Closest amounts representable in the packed form.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackableAmounts {
+    /// Closest packable amount less or equal to the provided one.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub floor: BigUint,
+    /// Closest packable amount greater or equal to the provided one.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub ceil: BigUint,
+    /// Whether the provided amount is packable as is.
+    pub lossless: bool,
+}
+
+/// Returns the largest amount the packing with the given parameters accepts: the amounts above it
+/// either don't fit into the packed form or overflow the `u128` arithmetic of the packing.
+fn max_packing_input(exponent_len: usize, mantissa_len: usize) -> BigUint {
+    let max_balance = (BigUint::one() << params::BALANCE_BIT_WIDTH) - 1u32;
+    let mut limit = (BigUint::one() << mantissa_len) - 1u32;
+    for _ in 0..(1u32 << exponent_len) - 1 {
+        let next = &limit * 10u32;
+        if next > max_balance {
+            break;
+        }
+        limit = next;
+    }
+    limit
+}
+
+impl PackableAmounts {
+    /// Returns the closest packable token amounts, or `None` if the amount is too large
+    /// to be packed at all.
+    pub fn try_token_amount(amount: &BigUint) -> Option<Self> {
+        let max_amount = max_packing_input(
+            params::AMOUNT_EXPONENT_BIT_WIDTH,
+            params::AMOUNT_MANTISSA_BIT_WIDTH,
+        );
+        if *amount > max_amount {
+            return None;
+        }
+        Some(Self::token_amount(amount))
+    }
+
+    /// Returns the closest packable fee amounts, or `None` if the amount is too large
+    /// to be packed at all.
+    pub fn try_fee_amount(amount: &BigUint) -> Option<Self> {
+        let max_amount = max_packing_input(
+            params::FEE_EXPONENT_BIT_WIDTH,
+            params::FEE_MANTISSA_BIT_WIDTH,
+        );
+        if *amount > max_amount {
+            return None;
+        }
+        Some(Self::fee_amount(amount))
+    }
+
+    /// Returns the closest packable token amounts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the amount is too large to be packed, see `try_token_amount`.
+    pub fn token_amount(amount: &BigUint) -> Self {
+        Self::new(
+            amount,
+            closest_packable_token_amount(amount),
+            closest_greater_or_eq_packable_token_amount(amount),
+        )
+    }
+
+    /// Returns the closest packable fee amounts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the amount is too large to be packed, see `try_fee_amount`.
+    pub fn fee_amount(amount: &BigUint) -> Self {
+        Self::new(
+            amount,
+            closest_packable_fee_amount(amount),
+            closest_greater_or_eq_packable_fee_amount(amount),
+        )
+    }
+
+    fn new(amount: &BigUint, floor: BigUint, ceil: BigUint) -> Self {
+        let lossless = &floor == amount;
+        Self {
+            floor,
+            ceil,
+            lossless,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -223,6 +317,55 @@ mod test {
         );
     }
 
+    #[test]
+    fn packable_amounts() {
+        let token = BigUint::from(123_456_789_123_456_789u64);
+        let amounts = PackableAmounts::token_amount(&token);
+        assert!(!amounts.lossless);
+        assert!(amounts.floor < token && token < amounts.ceil);
+        assert!(is_token_amount_packable(&amounts.floor));
+        assert!(is_token_amount_packable(&amounts.ceil));
+
+        let fee = closest_packable_fee_amount(&BigUint::from(1_234_123_424u32));
+        assert_eq!(
+            PackableAmounts::fee_amount(&fee),
+            PackableAmounts {
+                floor: fee.clone(),
+                ceil: fee,
+                lossless: true,
+            }
+        );
+    }
+
+    #[test]
+    fn too_large_packable_amounts() {
+        let max_balance = (BigUint::one() << params::BALANCE_BIT_WIDTH) - 1u32;
+        assert!(PackableAmounts::try_token_amount(&max_balance).is_none());
+        assert!(PackableAmounts::try_fee_amount(&max_balance).is_none());
+        assert!(PackableAmounts::try_token_amount(&(BigUint::one() << 200)).is_none());
+
+        // The largest accepted amounts are packed without panics.
+        let max_token_amount = max_packing_input(
+            params::AMOUNT_EXPONENT_BIT_WIDTH,
+            params::AMOUNT_MANTISSA_BIT_WIDTH,
+        );
+        let amounts = PackableAmounts::try_token_amount(&max_token_amount).unwrap();
+        assert!(amounts.lossless);
+        assert!(PackableAmounts::try_token_amount(&(&max_token_amount + 1u32)).is_none());
+
+        let max_fee = max_packing_input(
+            params::FEE_EXPONENT_BIT_WIDTH,
+            params::FEE_MANTISSA_BIT_WIDTH,
+        );
+        assert_eq!(
+            max_fee,
+            BigUint::from(2047u32) * BigUint::from(10u32).pow(31u32)
+        );
+        let amounts = PackableAmounts::try_fee_amount(&max_fee).unwrap();
+        assert!(amounts.lossless);
+        assert!(PackableAmounts::try_fee_amount(&(&max_fee + 1u32)).is_none());
+    }
+
     #[test]
     fn token_like_serialization() {
         #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -0,0 +1,134 @@
+//! Audit log of the operator keys usage.
+//!
+//! Every signature made by the server with the operator keys is recorded to the append-only
+//! audit log: the key, the type of the signed operation, the Keccak-256 hash of the signed payload
+//! and the outcome of the signing. The log is exported for the compliance review, so every entry
+//! can be matched against the transactions observed on L1 and in the zkSync network.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use parity_crypto::Keccak256;
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::H256;
+
+/// Key held by the operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OperatorKey {
+    /// Ethereum key used to send the block operations to the zkSync contract.
+    OperatorEth,
+    /// zkSync key of the account sending the `ForcedExit` transactions.
+    ForcedExitSender,
+    /// zkSync key of the fee account sweeping the collected fees to the treasury.
+    FeeAccount,
+    /// Ethereum key used to send the block operations to the contract in the shadow network.
+    ShadowOperatorEth,
+}
+
+impl OperatorKey {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::OperatorEth => "operator_eth",
+            Self::ForcedExitSender => "forced_exit_sender",
+            Self::FeeAccount => "fee_account",
+            Self::ShadowOperatorEth => "shadow_operator_eth",
+        }
+    }
+}
+
+impl fmt::Display for OperatorKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for OperatorKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "operator_eth" => Ok(Self::OperatorEth),
+            "forced_exit_sender" => Ok(Self::ForcedExitSender),
+            "fee_account" => Ok(Self::FeeAccount),
+            "shadow_operator_eth" => Ok(Self::ShadowOperatorEth),
+            other => Err(format!("Unknown operator key: {}", other)),
+        }
+    }
+}
+
+/// Single usage of the operator key.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyUsage {
+    pub key: OperatorKey,
+    /// Type of the signed operation, e.g. `CommitBlocks` or `ForcedExit`.
+    pub operation: String,
+    /// Keccak-256 hash of the signed payload.
+    pub payload_hash: H256,
+    pub success: bool,
+    /// Description of the signing failure.
+    pub error: Option<String>,
+}
+
+impl KeyUsage {
+    pub fn new(
+        key: OperatorKey,
+        operation: impl Into<String>,
+        payload: &[u8],
+        outcome: Result<(), String>,
+    ) -> Self {
+        Self {
+            key,
+            operation: operation.into(),
+            payload_hash: H256::from(payload.keccak256()),
+            success: outcome.is_ok(),
+            error: outcome.err(),
+        }
+    }
+}
+
+/// Entry of the audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyUsageRecord {
+    pub id: i64,
+    #[serde(flatten)]
+    pub usage: KeyUsage,
+    pub created_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_usage() {
+        for key in &[
+            OperatorKey::OperatorEth,
+            OperatorKey::ForcedExitSender,
+            OperatorKey::FeeAccount,
+            OperatorKey::ShadowOperatorEth,
+        ] {
+            assert_eq!(key.as_str().parse::<OperatorKey>().unwrap(), *key);
+        }
+
+        let usage = KeyUsage::new(
+            OperatorKey::OperatorEth,
+            "CommitBlocks",
+            b"calldata",
+            Ok(()),
+        );
+        assert!(usage.success);
+        assert_eq!(usage.payload_hash, H256::from(b"calldata".keccak256()));
+
+        let usage = KeyUsage::new(
+            OperatorKey::OperatorEth,
+            "CommitBlocks",
+            b"calldata",
+            Err("Signer is unavailable".to_owned()),
+        );
+        assert!(!usage.success);
+        assert_eq!(usage.error.as_deref(), Some("Signer is unavailable"));
+    }
+}
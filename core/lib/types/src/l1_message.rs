@@ -0,0 +1,86 @@
+//! Messages sent to the zkSync accounts from L1.
+//!
+//! Any L1 contract (or user) can notify the zkSync account by calling `sendMessage` of the zkSync
+//! contract, which emits the `MessageSent` event. Messages don't change the L2 state: the server
+//! watches the events and records the confirmed messages in the blocks they're delivered in, and
+//! the recipients fetch them via the API. This is the first step toward the composable bridges,
+//! e.g. an L1 contract may notify the L2 account about the funds locked for it.
+
+use std::convert::TryFrom;
+
+use ethabi::{decode, ParamType};
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{Address, BlockNumber, Log, H256, U256};
+use zksync_utils::ZeroPrefixHexSerde;
+
+/// Message sent to the zkSync account from L1.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1Message {
+    /// Unique ID of the message, messages are numbered in the order they're sent.
+    pub serial_id: u64,
+    /// L1 address that has sent the message.
+    pub sender: Address,
+    /// Address of the zkSync account the message is addressed to.
+    pub recipient: Address,
+    #[serde(with = "ZeroPrefixHexSerde")]
+    pub payload: Vec<u8>,
+    /// Hash of the Ethereum transaction that has sent the message.
+    pub eth_hash: H256,
+    /// Block in which Ethereum transaction was included.
+    pub eth_block: u64,
+}
+
+impl TryFrom<Log> for L1Message {
+    type Error = ethabi::Error;
+
+    fn try_from(event: Log) -> Result<L1Message, ethabi::Error> {
+        // Sender and recipient are indexed, so they're stored in the topics.
+        let topic_address = |index: usize| {
+            event
+                .topics
+                .get(index)
+                .map(|topic| Address::from_slice(&topic.as_bytes()[12..]))
+                .ok_or(ethabi::Error::InvalidData)
+        };
+        let sender = topic_address(1)?;
+        let recipient = topic_address(2)?;
+
+        let mut dec_ev = decode(
+            &[
+                ParamType::Uint(64), // Serial id
+                ParamType::Bytes,    // Payload
+            ],
+            &event.data.0,
+        )?;
+
+        Ok(L1Message {
+            serial_id: dec_ev
+                .remove(0)
+                .to_uint()
+                .as_ref()
+                .map(U256::as_u64)
+                .unwrap(),
+            sender,
+            recipient,
+            payload: dec_ev.remove(0).to_bytes().unwrap(),
+            eth_hash: event
+                .transaction_hash
+                .expect("Event transaction hash is missing"),
+            eth_block: event
+                .block_number
+                .expect("Event block number is missing")
+                .as_u64(),
+        })
+    }
+}
+
+/// Message recorded in the zkSync block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveredL1Message {
+    #[serde(flatten)]
+    pub message: L1Message,
+    /// Number of the block the message is delivered in.
+    pub block_number: BlockNumber,
+}
@@ -37,21 +37,51 @@
 //! [`Account`]: ./account/struct.Account.html
 
 pub mod account;
+pub mod account_alias;
+pub mod activations;
+pub mod address_attestation;
+pub mod address_checksum;
 pub mod aggregated_operations;
+pub mod amount_bounds;
+pub mod api_error;
+pub mod api_namespace;
 pub mod block;
+pub mod block_commitment;
 pub mod config;
+pub mod deposit_refund;
+pub mod dust_collection;
 pub mod ethereum;
+pub mod event;
+pub mod fast_withdrawals;
 pub mod fee;
+pub mod fee_refund;
+pub mod fee_sweep;
+pub mod finality;
 pub mod forced_exit_requests;
 pub mod gas_counter;
+pub mod guardians;
 pub mod helpers;
+pub mod key_audit;
+pub mod l1_message;
 pub mod mempool;
 pub mod network;
+pub mod nonce_reservation;
 pub mod operations;
+pub mod priority_op_cost;
 pub mod priority_ops;
+pub mod proof_archive;
+pub mod protocol_version;
 pub mod prover;
+pub mod pubdata_compression;
+pub mod revenue;
+pub mod screening;
+pub mod snapshot;
+pub mod token_amount;
 pub mod tokens;
 pub mod tx;
+pub mod webhooks;
+pub mod withdrawal_execution;
+pub mod withdrawal_gas;
 mod utils;
 
 #[cfg(test)]
@@ -0,0 +1,123 @@
+//! Reservation of the account nonces.
+//!
+//! Senders producing many transactions at once (e.g. market makers) can reserve a contiguous
+//! range of nonces following the committed nonce of the account. Transactions with the nonces
+//! from the reserved range are kept in the mempool even if they arrive out of order, and are
+//! included into the block once the gap before them is filled.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{Address, Nonce};
+
+use crate::tx::{PackedEthSignature, SignatureDomain};
+
+/// Request to reserve the range of nonces of the account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NonceReservationRequest {
+    pub address: Address,
+    /// Amount of the nonces to reserve.
+    pub count: u32,
+    /// Time the request was signed at, in milliseconds since the Unix epoch.
+    /// Each timestamp can be used only once.
+    pub timestamp: u64,
+    /// Ethereum signature of the `NonceReservationRequest::message`.
+    pub signature: PackedEthSignature,
+}
+
+impl NonceReservationRequest {
+    /// Returns the message to be signed by the owner of the account, bound to the network
+    /// if the domain is required (see `SignatureDomain`).
+    pub fn message(
+        address: Address,
+        count: u32,
+        timestamp: u64,
+        domain: Option<&SignatureDomain>,
+    ) -> String {
+        let message = format!(
+            "Reserve {} zkSync nonces.\nAccount: {:?}\nTimestamp: {}",
+            count, address, timestamp
+        );
+        SignatureDomain::apply_optional(domain, message)
+    }
+
+    /// Checks that the request is signed by the owner of the account.
+    pub fn verify(&self, domain: Option<&SignatureDomain>) -> bool {
+        self.signature
+            .signature_recover_signer(
+                Self::message(self.address, self.count, self.timestamp, domain).as_bytes(),
+            )
+            .map(|signer| signer == self.address)
+            .unwrap_or(false)
+    }
+}
+
+/// Range of nonces reserved by the account.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NonceReservation {
+    pub address: Address,
+    pub first_nonce: Nonce,
+    /// Last reserved nonce, inclusive.
+    pub last_nonce: Nonce,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl NonceReservation {
+    /// Checks whether the nonce belongs to the reserved range.
+    pub fn contains(&self, nonce: Nonce) -> bool {
+        self.first_nonce <= nonce && nonce <= self.last_nonce
+    }
+
+    /// Checks whether the reservation is still active.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        now < self.expires_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_basic_types::H256;
+
+    #[test]
+    fn reservation_request_signature() {
+        let private_key = H256::repeat_byte(7);
+        let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let domain = SignatureDomain::new(1, Address::repeat_byte(9));
+        let sign = |address: Address, count: u32| {
+            PackedEthSignature::sign(
+                &private_key,
+                NonceReservationRequest::message(address, count, 1000, Some(&domain)).as_bytes(),
+            )
+            .unwrap()
+        };
+
+        let request = NonceReservationRequest {
+            address,
+            count: 10,
+            timestamp: 1000,
+            signature: sign(address, 10),
+        };
+        assert!(request.verify(Some(&domain)));
+        // The signature is bound to the network.
+        assert!(!request.verify(None));
+
+        // The signature is bound to the amount of the reserved nonces.
+        let request = NonceReservationRequest {
+            count: 11,
+            ..request
+        };
+        assert!(!request.verify(Some(&domain)));
+
+        // Only the owner of the account can reserve its nonces.
+        let other = Address::repeat_byte(1);
+        let request = NonceReservationRequest {
+            address: other,
+            count: 10,
+            timestamp: 1000,
+            signature: sign(other, 10),
+        };
+        assert!(!request.verify(Some(&domain)));
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     helpers::{pack_fee_amount, unpack_fee_amount},
-    operations::error::ChangePubkeyOpError,
+    operations::error::{ChangePubkeyOpError, PubdataLocation},
     tx::ChangePubKey,
     AccountId, Address, Nonce, PubKeyHash, TokenId,
 };
@@ -56,21 +56,34 @@ impl ChangePubKeyOp {
         let fee_token_offset = nonce_offset + NONCE_BIT_WIDTH / 8;
         let fee_offset = fee_token_offset + TOKEN_BIT_WIDTH / 8;
         let end = fee_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8;
+        let location = |range| PubdataLocation::field(Self::OP_CODE, bytes, range);
 
         if bytes.len() < end {
-            return Err(ChangePubkeyOpError::PubdataSizeMismatch);
+            return Err(ChangePubkeyOpError::PubdataSizeMismatch(
+                PubdataLocation::pubdata(Self::OP_CODE, bytes, end),
+            ));
         }
 
-        let account_id = u32::from_bytes(&bytes[account_id_offset..pk_hash_offset])
-            .ok_or(ChangePubkeyOpError::CannotGetAccountId)?;
-        let new_pk_hash = PubKeyHash::from_bytes(&bytes[pk_hash_offset..account_offset])?;
+        let account_id =
+            u32::from_bytes(&bytes[account_id_offset..pk_hash_offset]).ok_or_else(|| {
+                ChangePubkeyOpError::CannotGetAccountId(location(account_id_offset..pk_hash_offset))
+            })?;
+        let new_pk_hash =
+            PubKeyHash::from_bytes(&bytes[pk_hash_offset..account_offset]).map_err(|source| {
+                ChangePubkeyOpError::CannotDecodePubkey {
+                    source,
+                    location: location(pk_hash_offset..account_offset),
+                }
+            })?;
         let account = Address::from_slice(&bytes[account_offset..nonce_offset]);
-        let nonce = u32::from_bytes(&bytes[nonce_offset..fee_token_offset])
-            .ok_or(ChangePubkeyOpError::CannotGetNonce)?;
-        let fee_token = u16::from_bytes(&bytes[fee_token_offset..fee_offset])
-            .ok_or(ChangePubkeyOpError::CannotGetFeeTokenId)?;
-        let fee =
-            unpack_fee_amount(&bytes[fee_offset..end]).ok_or(ChangePubkeyOpError::CannotGetFee)?;
+        let nonce = u32::from_bytes(&bytes[nonce_offset..fee_token_offset]).ok_or_else(|| {
+            ChangePubkeyOpError::CannotGetNonce(location(nonce_offset..fee_token_offset))
+        })?;
+        let fee_token = u16::from_bytes(&bytes[fee_token_offset..fee_offset]).ok_or_else(|| {
+            ChangePubkeyOpError::CannotGetFeeTokenId(location(fee_token_offset..fee_offset))
+        })?;
+        let fee = unpack_fee_amount(&bytes[fee_offset..end])
+            .ok_or_else(|| ChangePubkeyOpError::CannotGetFee(location(fee_offset..end)))?;
 
         Ok(ChangePubKeyOp {
             tx: ChangePubKey::new(
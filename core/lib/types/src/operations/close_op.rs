@@ -1,4 +1,8 @@
-use crate::{operations::error::CloseOpError, tx::TxSignature, AccountId, Address, Close, Nonce};
+use crate::{
+    operations::error::{CloseOpError, PubdataLocation},
+    tx::TxSignature,
+    AccountId, Address, Close, Nonce,
+};
 use serde::{Deserialize, Serialize};
 use zksync_crypto::{
     params::{ACCOUNT_ID_BIT_WIDTH, CHUNK_BYTES},
@@ -24,14 +28,24 @@ impl CloseOp {
 
     pub fn from_public_data(bytes: &[u8]) -> Result<Self, CloseOpError> {
         if bytes.len() != Self::CHUNKS * CHUNK_BYTES {
-            return Err(CloseOpError::PubdataSizeMismatch);
+            return Err(CloseOpError::PubdataSizeMismatch(PubdataLocation::pubdata(
+                Self::OP_CODE,
+                bytes,
+                Self::CHUNKS * CHUNK_BYTES,
+            )));
         }
 
+        let location = |range| PubdataLocation::field(Self::OP_CODE, bytes, range);
+
         let account_id_offset = 1;
         let account_id = u32::from_bytes(
             &bytes[account_id_offset..account_id_offset + ACCOUNT_ID_BIT_WIDTH / 8],
         )
-        .ok_or(CloseOpError::CannotGetFromAccountId)?;
+        .ok_or_else(|| {
+            CloseOpError::CannotGetFromAccountId(location(
+                account_id_offset..account_id_offset + ACCOUNT_ID_BIT_WIDTH / 8,
+            ))
+        })?;
         let account_address = Address::zero(); // From pubdata it is unknown
         let nonce = 0; // From pubdata it is unknown
         let signature = TxSignature::default(); // From pubdata it is unknown
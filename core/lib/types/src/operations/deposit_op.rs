@@ -1,4 +1,7 @@
-use crate::{operations::error::DepositOpError, AccountId, Address, Deposit, TokenId};
+use crate::{
+    operations::error::{DepositOpError, PubdataLocation},
+    AccountId, Address, Deposit, TokenId,
+};
 use num::{BigUint, ToPrimitive};
 use serde::{Deserialize, Serialize};
 use zksync_crypto::{
@@ -31,9 +34,13 @@ impl DepositOp {
 
     pub fn from_public_data(bytes: &[u8]) -> Result<Self, DepositOpError> {
         if bytes.len() != Self::CHUNKS * CHUNK_BYTES {
-            return Err(DepositOpError::PubdataSizeMismatch);
+            return Err(DepositOpError::PubdataSizeMismatch(
+                PubdataLocation::pubdata(Self::OP_CODE, bytes, Self::CHUNKS * CHUNK_BYTES),
+            ));
         }
 
+        let location = |range| PubdataLocation::field(Self::OP_CODE, bytes, range);
+
         let account_id_offset = 1;
         let token_id_offset = account_id_offset + ACCOUNT_ID_BIT_WIDTH / 8;
         let amount_offset = token_id_offset + TOKEN_BIT_WIDTH / 8;
@@ -42,12 +49,24 @@ impl DepositOp {
         let account_id = u32::from_bytes(
             &bytes[account_id_offset..account_id_offset + ACCOUNT_ID_BIT_WIDTH / 8],
         )
-        .ok_or(DepositOpError::CannotGetAccountId)?;
+        .ok_or_else(|| {
+            DepositOpError::CannotGetAccountId(location(
+                account_id_offset..account_id_offset + ACCOUNT_ID_BIT_WIDTH / 8,
+            ))
+        })?;
         let token = u16::from_bytes(&bytes[token_id_offset..token_id_offset + TOKEN_BIT_WIDTH / 8])
-            .ok_or(DepositOpError::CannotGetTokenId)?;
+            .ok_or_else(|| {
+                DepositOpError::CannotGetTokenId(location(
+                    token_id_offset..token_id_offset + TOKEN_BIT_WIDTH / 8,
+                ))
+            })?;
         let amount = BigUint::from(
             u128::from_bytes(&bytes[amount_offset..amount_offset + BALANCE_BIT_WIDTH / 8])
-                .ok_or(DepositOpError::CannotGetAmount)?,
+                .ok_or_else(|| {
+                    DepositOpError::CannotGetAmount(location(
+                        amount_offset..amount_offset + BALANCE_BIT_WIDTH / 8,
+                    ))
+                })?,
         );
         let to = Address::from_slice(
             &bytes[account_address_offset..account_address_offset + FR_ADDRESS_LEN],
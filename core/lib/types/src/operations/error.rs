@@ -1,112 +1,181 @@
 use crate::account::error::PubkeyHashDecodingError;
+use num::BigUint;
+use std::{fmt, ops::Range};
 use thiserror::Error;
+use zksync_basic_types::TokenId;
+
+/// Location of the malformed data in the operation pubdata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PubdataLocation {
+    /// Type byte of the decoded operation.
+    pub op_code: u8,
+    /// Offset of the malformed field from the beginning of the operation pubdata.
+    pub offset: usize,
+    pub expected_len: usize,
+    /// Amount of bytes available for the field.
+    pub actual_len: usize,
+}
+
+impl PubdataLocation {
+    /// Location of the whole operation pubdata.
+    pub fn pubdata(op_code: u8, bytes: &[u8], expected_len: usize) -> Self {
+        Self {
+            op_code,
+            offset: 0,
+            expected_len,
+            actual_len: bytes.len(),
+        }
+    }
+
+    /// Location of the field occupying the `range` of the operation pubdata.
+    pub fn field(op_code: u8, bytes: &[u8], range: Range<usize>) -> Self {
+        let expected_len = range.len();
+        Self {
+            op_code,
+            offset: range.start,
+            expected_len,
+            actual_len: bytes.len().saturating_sub(range.start).min(expected_len),
+        }
+    }
+}
+
+impl fmt::Display for PubdataLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "operation type 0x{:02x}, offset {}, expected {} bytes, got {}",
+            self.op_code, self.offset, self.expected_len, self.actual_len
+        )
+    }
+}
 
 #[derive(Debug, Error, PartialEq)]
 pub enum ChangePubkeyOpError {
-    #[error("Wrong bytes length for change pubkey pubdata")]
-    PubdataSizeMismatch,
-    #[error("Cannot decode pubkey: {0}")]
-    CannotDecodePubkey(#[from] PubkeyHashDecodingError),
-    #[error("Failed to get account id")]
-    CannotGetAccountId,
-    #[error("Failed to get nonce")]
-    CannotGetNonce,
-    #[error("Failed to get fee token id")]
-    CannotGetFeeTokenId,
-    #[error("Failed to get fee")]
-    CannotGetFee,
+    #[error("Wrong bytes length for change pubkey pubdata ({0})")]
+    PubdataSizeMismatch(PubdataLocation),
+    #[error("Cannot decode pubkey: {source} ({location})")]
+    CannotDecodePubkey {
+        source: PubkeyHashDecodingError,
+        location: PubdataLocation,
+    },
+    #[error("Failed to get account id ({0})")]
+    CannotGetAccountId(PubdataLocation),
+    #[error("Failed to get nonce ({0})")]
+    CannotGetNonce(PubdataLocation),
+    #[error("Failed to get fee token id ({0})")]
+    CannotGetFeeTokenId(PubdataLocation),
+    #[error("Failed to get fee ({0})")]
+    CannotGetFee(PubdataLocation),
+    #[error("Fee {fee} in token {token} exceeds the allowed maximum")]
+    FeeOutOfBounds { token: TokenId, fee: BigUint },
 }
 
 #[derive(Debug, Error, PartialEq)]
 pub enum CloseOpError {
-    #[error("Wrong bytes length for close pubdata")]
-    PubdataSizeMismatch,
-    #[error("Failed to get from account id")]
-    CannotGetFromAccountId,
+    #[error("Wrong bytes length for close pubdata ({0})")]
+    PubdataSizeMismatch(PubdataLocation),
+    #[error("Failed to get from account id ({0})")]
+    CannotGetFromAccountId(PubdataLocation),
 }
 
 #[derive(Debug, Error, PartialEq)]
 pub enum DepositOpError {
-    #[error("Wrong bytes length for deposit pubdata")]
-    PubdataSizeMismatch,
-    #[error("Failed to get account id")]
-    CannotGetAccountId,
-    #[error("Failed to get token id")]
-    CannotGetTokenId,
-    #[error("Failed to get amount")]
-    CannotGetAmount,
+    #[error("Wrong bytes length for deposit pubdata ({0})")]
+    PubdataSizeMismatch(PubdataLocation),
+    #[error("Failed to get account id ({0})")]
+    CannotGetAccountId(PubdataLocation),
+    #[error("Failed to get token id ({0})")]
+    CannotGetTokenId(PubdataLocation),
+    #[error("Failed to get amount ({0})")]
+    CannotGetAmount(PubdataLocation),
 }
 
 #[derive(Debug, Error, PartialEq)]
 pub enum ForcedExitOpError {
-    #[error("Wrong bytes length for forced exit pubdata")]
-    PubdataSizeMismatch,
-    #[error("Failed to get initiator account id")]
-    CannotGetInitiatorAccountId,
-    #[error("Failed to get target account id")]
-    CannotGetTargetAccountId,
-    #[error("Failed to get token id")]
-    CannotGetTokenId,
-    #[error("Failed to get amount")]
-    CannotGetAmount,
-    #[error("Failed to get fee")]
-    CannotGetFee,
+    #[error("Wrong bytes length for forced exit pubdata ({0})")]
+    PubdataSizeMismatch(PubdataLocation),
+    #[error("Failed to get initiator account id ({0})")]
+    CannotGetInitiatorAccountId(PubdataLocation),
+    #[error("Failed to get target account id ({0})")]
+    CannotGetTargetAccountId(PubdataLocation),
+    #[error("Failed to get token id ({0})")]
+    CannotGetTokenId(PubdataLocation),
+    #[error("Failed to get amount ({0})")]
+    CannotGetAmount(PubdataLocation),
+    #[error("Failed to get fee ({0})")]
+    CannotGetFee(PubdataLocation),
+    #[error("Amount {amount} of token {token} exceeds the allowed maximum")]
+    AmountOutOfBounds { token: TokenId, amount: BigUint },
+    #[error("Fee {fee} in token {token} exceeds the allowed maximum")]
+    FeeOutOfBounds { token: TokenId, fee: BigUint },
 }
 
 #[derive(Debug, Error, PartialEq)]
 pub enum FullExitOpError {
-    #[error("Wrong bytes length for full exit pubdata")]
-    PubdataSizeMismatch,
-    #[error("Failed to get account id")]
-    CannotGetAccountId,
-    #[error("Failed to get token id")]
-    CannotGetTokenId,
-    #[error("Failed to get amount")]
-    CannotGetAmount,
+    #[error("Wrong bytes length for full exit pubdata ({0})")]
+    PubdataSizeMismatch(PubdataLocation),
+    #[error("Failed to get account id ({0})")]
+    CannotGetAccountId(PubdataLocation),
+    #[error("Failed to get token id ({0})")]
+    CannotGetTokenId(PubdataLocation),
+    #[error("Failed to get amount ({0})")]
+    CannotGetAmount(PubdataLocation),
 }
 
 #[derive(Debug, Error, PartialEq)]
 pub enum NoopOpError {
-    #[error("Wrong pubdata for noop operation")]
-    IncorrectPubdata,
+    #[error("Wrong pubdata for noop operation ({0})")]
+    IncorrectPubdata(PubdataLocation),
 }
 
 #[derive(Debug, Error, PartialEq)]
 pub enum TransferOpError {
-    #[error("Wrong bytes length for transfer pubdata")]
-    PubdataSizeMismatch,
-    #[error("Failed to get from account id")]
-    CannotGetFromAccountId,
-    #[error("Failed to get to account id")]
-    CannotGetToAccountId,
-    #[error("Failed to get token id")]
-    CannotGetTokenId,
-    #[error("Failed to get amount")]
-    CannotGetAmount,
-    #[error("Failed to get fee")]
-    CannotGetFee,
+    #[error("Wrong bytes length for transfer pubdata ({0})")]
+    PubdataSizeMismatch(PubdataLocation),
+    #[error("Failed to get from account id ({0})")]
+    CannotGetFromAccountId(PubdataLocation),
+    #[error("Failed to get to account id ({0})")]
+    CannotGetToAccountId(PubdataLocation),
+    #[error("Failed to get token id ({0})")]
+    CannotGetTokenId(PubdataLocation),
+    #[error("Failed to get amount ({0})")]
+    CannotGetAmount(PubdataLocation),
+    #[error("Failed to get fee ({0})")]
+    CannotGetFee(PubdataLocation),
+    #[error("Amount {amount} of token {token} exceeds the allowed maximum")]
+    AmountOutOfBounds { token: TokenId, amount: BigUint },
+    #[error("Fee {fee} in token {token} exceeds the allowed maximum")]
+    FeeOutOfBounds { token: TokenId, fee: BigUint },
 }
 
 #[derive(Debug, Error, PartialEq)]
 pub enum WithdrawOpError {
-    #[error("Wrong bytes length for withdraw pubdata")]
-    PubdataSizeMismatch,
-    #[error("Failed to get account id")]
-    CannotGetAccountId,
-    #[error("Failed to get token id")]
-    CannotGetTokenId,
-    #[error("Failed to get amount")]
-    CannotGetAmount,
-    #[error("Failed to get fee")]
-    CannotGetFee,
+    #[error("Wrong bytes length for withdraw pubdata ({0})")]
+    PubdataSizeMismatch(PubdataLocation),
+    #[error("Failed to get account id ({0})")]
+    CannotGetAccountId(PubdataLocation),
+    #[error("Failed to get token id ({0})")]
+    CannotGetTokenId(PubdataLocation),
+    #[error("Failed to get amount ({0})")]
+    CannotGetAmount(PubdataLocation),
+    #[error("Failed to get fee ({0})")]
+    CannotGetFee(PubdataLocation),
+    #[error("Amount {amount} of token {token} exceeds the allowed maximum")]
+    AmountOutOfBounds { token: TokenId, amount: BigUint },
+    #[error("Fee {fee} in token {token} exceeds the allowed maximum")]
+    FeeOutOfBounds { token: TokenId, fee: BigUint },
 }
 
 #[derive(Debug, Error, PartialEq)]
 pub enum PublicDataDecodeError {
     #[error("Cannot decode empty public data")]
     EmptyData,
-    #[error("Unknown operation type")]
-    UnknownOperationType,
+    #[error("Unknown operation type 0x{0:02x}")]
+    UnknownOperationType(u8),
+    #[error(
+        "Non-canonical pubdata encoding (operation type 0x{op_code:02x}, first mismatch at offset {offset})"
+    )]
+    NonCanonicalEncoding { op_code: u8, offset: usize },
     #[error(transparent)]
     ChangePubkeyOpError(#[from] ChangePubkeyOpError),
     #[error(transparent)]
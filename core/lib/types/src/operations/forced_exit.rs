@@ -1,6 +1,6 @@
 use crate::{
     helpers::{pack_fee_amount, unpack_fee_amount},
-    operations::error::ForcedExitOpError,
+    operations::error::{ForcedExitOpError, PubdataLocation},
     AccountId, Address, ForcedExit, Nonce, TokenId,
 };
 use num::{BigUint, FromPrimitive, ToPrimitive};
@@ -59,8 +59,12 @@ impl ForcedExitOp {
 
     pub fn from_public_data(bytes: &[u8]) -> Result<Self, ForcedExitOpError> {
         if bytes.len() != Self::CHUNKS * CHUNK_BYTES {
-            return Err(ForcedExitOpError::PubdataSizeMismatch);
+            return Err(ForcedExitOpError::PubdataSizeMismatch(
+                PubdataLocation::pubdata(Self::OP_CODE, bytes, Self::CHUNKS * CHUNK_BYTES),
+            ));
         }
+
+        let location = |range| PubdataLocation::field(Self::OP_CODE, bytes, range);
         let initiator_account_id_offset = 1;
         let target_account_id_offset = initiator_account_id_offset + ACCOUNT_ID_BIT_WIDTH / 8;
         let token_id_offset = target_account_id_offset + ACCOUNT_ID_BIT_WIDTH / 8;
@@ -71,18 +75,32 @@ impl ForcedExitOp {
 
         let initiator_account_id =
             u32::from_bytes(&bytes[initiator_account_id_offset..target_account_id_offset])
-                .ok_or(ForcedExitOpError::CannotGetInitiatorAccountId)?;
+                .ok_or_else(|| {
+                    ForcedExitOpError::CannotGetInitiatorAccountId(location(
+                        initiator_account_id_offset..target_account_id_offset,
+                    ))
+                })?;
         let target_account_id = u32::from_bytes(&bytes[target_account_id_offset..token_id_offset])
-            .ok_or(ForcedExitOpError::CannotGetTargetAccountId)?;
-        let token = u16::from_bytes(&bytes[token_id_offset..amount_offset])
-            .ok_or(ForcedExitOpError::CannotGetTokenId)?;
+            .ok_or_else(|| {
+                ForcedExitOpError::CannotGetTargetAccountId(location(
+                    target_account_id_offset..token_id_offset,
+                ))
+            })?;
+        let token = u16::from_bytes(&bytes[token_id_offset..amount_offset]).ok_or_else(|| {
+            ForcedExitOpError::CannotGetTokenId(location(token_id_offset..amount_offset))
+        })?;
         let amount = BigUint::from_u128(
             u128::from_bytes(&bytes[amount_offset..amount_offset + BALANCE_BIT_WIDTH / 8])
-                .ok_or(ForcedExitOpError::CannotGetAmount)?,
+                .ok_or_else(|| {
+                    ForcedExitOpError::CannotGetAmount(location(
+                        amount_offset..amount_offset + BALANCE_BIT_WIDTH / 8,
+                    ))
+                })?,
         )
         .unwrap();
-        let fee = unpack_fee_amount(&bytes[fee_offset..eth_address_offset])
-            .ok_or(ForcedExitOpError::CannotGetFee)?;
+        let fee = unpack_fee_amount(&bytes[fee_offset..eth_address_offset]).ok_or_else(|| {
+            ForcedExitOpError::CannotGetFee(location(fee_offset..eth_address_offset))
+        })?;
         let target = Address::from_slice(&bytes[eth_address_offset..eth_address_end]);
 
         let nonce = 0; // From pubdata it is unknown
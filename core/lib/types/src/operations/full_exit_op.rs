@@ -1,4 +1,7 @@
-use crate::{operations::error::FullExitOpError, AccountId, Address, FullExit, TokenId};
+use crate::{
+    operations::error::{FullExitOpError, PubdataLocation},
+    AccountId, Address, FullExit, TokenId,
+};
 use num::{BigUint, FromPrimitive, ToPrimitive};
 use serde::{Deserialize, Serialize};
 use zksync_crypto::{
@@ -60,22 +63,33 @@ impl FullExitOp {
 
     pub fn from_public_data(bytes: &[u8]) -> Result<Self, FullExitOpError> {
         if bytes.len() != Self::CHUNKS * CHUNK_BYTES {
-            return Err(FullExitOpError::PubdataSizeMismatch);
+            return Err(FullExitOpError::PubdataSizeMismatch(
+                PubdataLocation::pubdata(Self::OP_CODE, bytes, Self::CHUNKS * CHUNK_BYTES),
+            ));
         }
 
+        let location = |range| PubdataLocation::field(Self::OP_CODE, bytes, range);
+
         let account_id_offset = 1;
         let eth_address_offset = account_id_offset + ACCOUNT_ID_BIT_WIDTH / 8;
         let token_offset = eth_address_offset + ETH_ADDRESS_BIT_WIDTH / 8;
         let amount_offset = token_offset + TOKEN_BIT_WIDTH / 8;
 
         let account_id = u32::from_bytes(&bytes[account_id_offset..eth_address_offset])
-            .ok_or(FullExitOpError::CannotGetAccountId)?;
+            .ok_or_else(|| {
+                FullExitOpError::CannotGetAccountId(location(account_id_offset..eth_address_offset))
+            })?;
         let eth_address = Address::from_slice(&bytes[eth_address_offset..token_offset]);
-        let token = u16::from_bytes(&bytes[token_offset..amount_offset])
-            .ok_or(FullExitOpError::CannotGetTokenId)?;
+        let token = u16::from_bytes(&bytes[token_offset..amount_offset]).ok_or_else(|| {
+            FullExitOpError::CannotGetTokenId(location(token_offset..amount_offset))
+        })?;
         let amount = BigUint::from_u128(
             u128::from_bytes(&bytes[amount_offset..amount_offset + BALANCE_BIT_WIDTH / 8])
-                .ok_or(FullExitOpError::CannotGetAmount)?,
+                .ok_or_else(|| {
+                    FullExitOpError::CannotGetAmount(location(
+                        amount_offset..amount_offset + BALANCE_BIT_WIDTH / 8,
+                    ))
+                })?,
         )
         .unwrap();
 
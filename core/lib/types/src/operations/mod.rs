@@ -23,7 +23,11 @@ pub use self::{
     full_exit_op::FullExitOp, noop_op::NoopOp, transfer_op::TransferOp,
     transfer_to_new_op::TransferToNewOp, withdraw_op::WithdrawOp,
 };
-use crate::operations::error::{PublicDataDecodeError, UnexpectedOperationType};
+use crate::amount_bounds::AmountBounds;
+use crate::operations::error::{
+    ChangePubkeyOpError, ForcedExitOpError, PublicDataDecodeError, TransferOpError,
+    UnexpectedOperationType, WithdrawOpError,
+};
 use zksync_basic_types::AccountId;
 
 /// zkSync network operation.
@@ -135,8 +139,94 @@ impl ZkSyncOp {
             ForcedExitOp::OP_CODE => Ok(ZkSyncOp::ForcedExit(Box::new(
                 ForcedExitOp::from_public_data(&bytes)?,
             ))),
-            _ => Err(PublicDataDecodeError::UnknownOperationType),
+            _ => Err(PublicDataDecodeError::UnknownOperationType(op_type)),
+        }
+    }
+
+    /// Same as `from_public_data`, but additionally checks that the public data is the canonical
+    /// encoding of the decoded operation (e.g. amounts are packed canonically and the padding is zeroed),
+    /// so the state restored from it can't diverge between implementations.
+    pub fn from_public_data_strict(bytes: &[u8]) -> Result<Self, PublicDataDecodeError> {
+        let op = Self::from_public_data(bytes)?;
+
+        let encoded = op.public_data();
+        let mismatch = encoded
+            .iter()
+            .zip(bytes)
+            .position(|(expected, actual)| expected != actual)
+            .or_else(|| {
+                if encoded.len() != bytes.len() {
+                    Some(encoded.len().min(bytes.len()))
+                } else {
+                    None
+                }
+            });
+        if let Some(offset) = mismatch {
+            return Err(PublicDataDecodeError::NonCanonicalEncoding {
+                op_code: bytes[0],
+                offset,
+            });
+        }
+        Ok(op)
+    }
+
+    /// Checks the amounts and fees of the decoded L2 operation against the sanity bounds,
+    /// so an absurd value produced by a faulty encoding is noticed. The priority operations
+    /// are not checked.
+    pub fn check_amount_bounds(&self, bounds: &AmountBounds) -> Result<(), PublicDataDecodeError> {
+        macro_rules! check_amount {
+            ($error:ident, $token:expr, $amount:expr) => {
+                if bounds.amount_exceeds($token, $amount) {
+                    return Err($error::AmountOutOfBounds {
+                        token: $token,
+                        amount: $amount.clone(),
+                    }
+                    .into());
+                }
+            };
+        }
+        macro_rules! check_fee {
+            ($error:ident, $token:expr, $fee:expr) => {
+                if bounds.fee_exceeds($token, $fee) {
+                    return Err($error::FeeOutOfBounds {
+                        token: $token,
+                        fee: $fee.clone(),
+                    }
+                    .into());
+                }
+            };
+        }
+
+        match self {
+            ZkSyncOp::Transfer(op) => {
+                check_amount!(TransferOpError, op.tx.token, &op.tx.amount);
+                check_fee!(TransferOpError, op.tx.token, &op.tx.fee);
+            }
+            ZkSyncOp::TransferToNew(op) => {
+                check_amount!(TransferOpError, op.tx.token, &op.tx.amount);
+                check_fee!(TransferOpError, op.tx.token, &op.tx.fee);
+            }
+            ZkSyncOp::Withdraw(op) => {
+                check_amount!(WithdrawOpError, op.tx.token, &op.tx.amount);
+                check_fee!(WithdrawOpError, op.tx.token, &op.tx.fee);
+            }
+            ZkSyncOp::ForcedExit(op) => {
+                if let Some(amount) = &op.withdraw_amount {
+                    check_amount!(ForcedExitOpError, op.tx.token, &amount.0);
+                }
+                check_fee!(ForcedExitOpError, op.tx.token, &op.tx.fee);
+            }
+            ZkSyncOp::ChangePubKeyOffchain(op) => {
+                check_fee!(ChangePubkeyOpError, op.tx.fee_token, &op.tx.fee);
+            }
+            // The priority operations are requested on L1 and their amounts are already limited
+            // by the contract, the operator can't refuse to process them anyway.
+            ZkSyncOp::Deposit(_)
+            | ZkSyncOp::FullExit(_)
+            | ZkSyncOp::Close(_)
+            | ZkSyncOp::Noop(_) => {}
         }
+        Ok(())
     }
 
     /// Returns the expected number of chunks for a certain type of operation.
@@ -1,4 +1,4 @@
-use crate::operations::error::NoopOpError;
+use crate::operations::error::{NoopOpError, PubdataLocation};
 use serde::{Deserialize, Serialize};
 use zksync_basic_types::AccountId;
 use zksync_crypto::params::CHUNK_BYTES;
@@ -13,7 +13,11 @@ impl NoopOp {
 
     pub fn from_public_data(bytes: &[u8]) -> Result<Self, NoopOpError> {
         if bytes != [0; CHUNK_BYTES] {
-            return Err(NoopOpError::IncorrectPubdata);
+            return Err(NoopOpError::IncorrectPubdata(PubdataLocation::pubdata(
+                Self::OP_CODE,
+                bytes,
+                Self::CHUNKS * CHUNK_BYTES,
+            )));
         }
         Ok(Self {})
     }
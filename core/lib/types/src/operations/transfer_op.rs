@@ -1,6 +1,6 @@
 use crate::{
     helpers::{pack_fee_amount, pack_token_amount, unpack_fee_amount, unpack_token_amount},
-    operations::error::TransferOpError,
+    operations::error::{PubdataLocation, TransferOpError},
     AccountId, Address, Nonce, TokenId, Transfer,
 };
 use serde::{Deserialize, Serialize};
@@ -37,9 +37,13 @@ impl TransferOp {
 
     pub fn from_public_data(bytes: &[u8]) -> Result<Self, TransferOpError> {
         if bytes.len() != Self::CHUNKS * CHUNK_BYTES {
-            return Err(TransferOpError::PubdataSizeMismatch);
+            return Err(TransferOpError::PubdataSizeMismatch(
+                PubdataLocation::pubdata(Self::OP_CODE, bytes, Self::CHUNKS * CHUNK_BYTES),
+            ));
         }
 
+        let location = |range| PubdataLocation::field(Self::OP_CODE, bytes, range);
+
         let from_offset = 1;
         let token_id_offset = from_offset + ACCOUNT_ID_BIT_WIDTH / 8;
         let to_offset = token_id_offset + TOKEN_BIT_WIDTH / 8;
@@ -50,21 +54,42 @@ impl TransferOp {
         let from_address = Address::zero(); // From pubdata its unknown
         let to_address = Address::zero(); // From pubdata its unknown
         let token = u16::from_bytes(&bytes[token_id_offset..token_id_offset + TOKEN_BIT_WIDTH / 8])
-            .ok_or(TransferOpError::CannotGetTokenId)?;
+            .ok_or_else(|| {
+                TransferOpError::CannotGetTokenId(location(
+                    token_id_offset..token_id_offset + TOKEN_BIT_WIDTH / 8,
+                ))
+            })?;
         let amount = unpack_token_amount(
             &bytes[amount_offset
                 ..amount_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8],
         )
-        .ok_or(TransferOpError::CannotGetAmount)?;
+        .ok_or_else(|| {
+            TransferOpError::CannotGetAmount(location(
+                amount_offset
+                    ..amount_offset + (AMOUNT_EXPONENT_BIT_WIDTH + AMOUNT_MANTISSA_BIT_WIDTH) / 8,
+            ))
+        })?;
         let fee = unpack_fee_amount(
             &bytes[fee_offset..fee_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8],
         )
-        .ok_or(TransferOpError::CannotGetFee)?;
+        .ok_or_else(|| {
+            TransferOpError::CannotGetFee(location(
+                fee_offset..fee_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8,
+            ))
+        })?;
         let nonce = 0; // It is unknown from pubdata
         let from_id = u32::from_bytes(&bytes[from_offset..from_offset + ACCOUNT_ID_BIT_WIDTH / 8])
-            .ok_or(TransferOpError::CannotGetFromAccountId)?;
+            .ok_or_else(|| {
+                TransferOpError::CannotGetFromAccountId(location(
+                    from_offset..from_offset + ACCOUNT_ID_BIT_WIDTH / 8,
+                ))
+            })?;
         let to_id = u32::from_bytes(&bytes[to_offset..to_offset + ACCOUNT_ID_BIT_WIDTH / 8])
-            .ok_or(TransferOpError::CannotGetToAccountId)?;
+            .ok_or_else(|| {
+                TransferOpError::CannotGetToAccountId(location(
+                    to_offset..to_offset + ACCOUNT_ID_BIT_WIDTH / 8,
+                ))
+            })?;
         let time_range = Default::default();
 
         Ok(Self {
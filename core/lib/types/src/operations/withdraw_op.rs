@@ -1,6 +1,6 @@
 use crate::{
     helpers::{pack_fee_amount, unpack_fee_amount},
-    operations::error::WithdrawOpError,
+    operations::error::{PubdataLocation, WithdrawOpError},
     AccountId, Address, Nonce, TokenId, Withdraw,
 };
 use num::{BigUint, FromPrimitive, ToPrimitive};
@@ -47,9 +47,13 @@ impl WithdrawOp {
 
     pub fn from_public_data(bytes: &[u8]) -> Result<Self, WithdrawOpError> {
         if bytes.len() != Self::CHUNKS * CHUNK_BYTES {
-            return Err(WithdrawOpError::PubdataSizeMismatch);
+            return Err(WithdrawOpError::PubdataSizeMismatch(
+                PubdataLocation::pubdata(Self::OP_CODE, bytes, Self::CHUNKS * CHUNK_BYTES),
+            ));
         }
 
+        let location = |range| PubdataLocation::field(Self::OP_CODE, bytes, range);
+
         let account_offset = 1;
         let token_id_offset = account_offset + ACCOUNT_ID_BIT_WIDTH / 8;
         let amount_offset = token_id_offset + TOKEN_BIT_WIDTH / 8;
@@ -58,22 +62,38 @@ impl WithdrawOp {
 
         let account_id =
             u32::from_bytes(&bytes[account_offset..account_offset + ACCOUNT_ID_BIT_WIDTH / 8])
-                .ok_or(WithdrawOpError::CannotGetAccountId)?;
+                .ok_or_else(|| {
+                    WithdrawOpError::CannotGetAccountId(location(
+                        account_offset..account_offset + ACCOUNT_ID_BIT_WIDTH / 8,
+                    ))
+                })?;
         let from = Address::zero(); // From pubdata it is unknown
         let token = u16::from_bytes(&bytes[token_id_offset..token_id_offset + TOKEN_BIT_WIDTH / 8])
-            .ok_or(WithdrawOpError::CannotGetTokenId)?;
+            .ok_or_else(|| {
+                WithdrawOpError::CannotGetTokenId(location(
+                    token_id_offset..token_id_offset + TOKEN_BIT_WIDTH / 8,
+                ))
+            })?;
         let to = Address::from_slice(
             &bytes[eth_address_offset..eth_address_offset + ETH_ADDRESS_BIT_WIDTH / 8],
         );
         let amount = BigUint::from_u128(
             u128::from_bytes(&bytes[amount_offset..amount_offset + BALANCE_BIT_WIDTH / 8])
-                .ok_or(WithdrawOpError::CannotGetAmount)?,
+                .ok_or_else(|| {
+                    WithdrawOpError::CannotGetAmount(location(
+                        amount_offset..amount_offset + BALANCE_BIT_WIDTH / 8,
+                    ))
+                })?,
         )
         .unwrap();
         let fee = unpack_fee_amount(
             &bytes[fee_offset..fee_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8],
         )
-        .ok_or(WithdrawOpError::CannotGetFee)?;
+        .ok_or_else(|| {
+            WithdrawOpError::CannotGetFee(location(
+                fee_offset..fee_offset + (FEE_EXPONENT_BIT_WIDTH + FEE_MANTISSA_BIT_WIDTH) / 8,
+            ))
+        })?;
         let nonce = 0; // From pubdata it is unknown
         let time_range = Default::default();
 
@@ -0,0 +1,136 @@
+//! Accounting of the L1 costs of the priority operations.
+//!
+//! Deposits and full exits are paid for on L1 by the users, but the operator spends gas processing
+//! them in the `commitBlocks` and `executeBlocks` transactions. Gas used by every confirmed
+//! transaction is attributed to the priority operations of its blocks, so the operators can
+//! calibrate the priority operation fee parameters of the zkSync contract.
+
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::BlockNumber;
+use zksync_crypto::params::CHUNK_BYTES;
+use zksync_utils::{BigUintSerdeAsRadix10Str, BigUintSerdeWrapper};
+
+use crate::{
+    aggregated_operations::AggregatedActionType,
+    block::{Block, ExecutedOperations},
+    gas_counter::{CommitCost, VerifyCost},
+    SerialId, ZkSyncOp, ZkSyncPriorityOp,
+};
+
+/// Gas paid for one byte of the transaction calldata.
+pub const CALLDATA_BYTE_GAS: u64 = 16;
+
+/// L1 costs of the priority operation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriorityOpCost {
+    pub serial_id: SerialId,
+    pub block_number: BlockNumber,
+    /// Type of the operation, `Deposit` or `FullExit`.
+    pub op_type: String,
+    /// Gas attributed to the operation in the `commitBlocks` transaction.
+    pub commit_gas: Option<u64>,
+    /// Cost of `commit_gas` in wei.
+    pub commit_fee: Option<BigUintSerdeWrapper>,
+    /// Gas attributed to the operation in the `executeBlocks` transaction.
+    pub execute_gas: Option<u64>,
+    /// Cost of `execute_gas` in wei.
+    pub execute_fee: Option<BigUintSerdeWrapper>,
+}
+
+/// Average L1 costs of the latest priority operations of one type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriorityOpCostSummary {
+    pub op_type: String,
+    /// Number of the operations the averages are calculated from.
+    pub ops_count: u64,
+    /// Average gas spent on the operation by the `commitBlocks` and `executeBlocks` transactions.
+    pub avg_gas: u64,
+    /// Average cost of the operation in wei.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub avg_fee: BigUint,
+}
+
+/// Gas attributed to the priority operation in one transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriorityOpGas {
+    pub serial_id: SerialId,
+    pub block_number: BlockNumber,
+    pub op_type: &'static str,
+    pub gas: u64,
+    /// Cost of the gas in wei.
+    pub fee: BigUint,
+}
+
+/// Returns the type name of the priority operation.
+pub fn priority_op_type(op: &ZkSyncPriorityOp) -> &'static str {
+    match op {
+        ZkSyncPriorityOp::Deposit(_) => "Deposit",
+        ZkSyncPriorityOp::FullExit(_) => "FullExit",
+    }
+}
+
+/// Estimates the gas the operation costs in the transaction of the given type.
+fn estimated_op_gas(op: &ZkSyncOp, action: AggregatedActionType) -> u64 {
+    match action {
+        AggregatedActionType::CommitBlocks => {
+            let calldata_gas = (op.chunks() * CHUNK_BYTES) as u64 * CALLDATA_BYTE_GAS;
+            CommitCost::op_cost(op).as_u64() + calldata_gas
+        }
+        AggregatedActionType::ExecuteBlocks => VerifyCost::op_cost(op).as_u64(),
+        _ => 0,
+    }
+}
+
+/// Attributes the gas used by the `commitBlocks` or `executeBlocks` transaction to the priority
+/// operations of its blocks.
+///
+/// Gas of every operation is estimated with `CommitCost` (plus the calldata of its public data)
+/// or `VerifyCost`, and the estimations are scaled to match the gas actually used by the transaction.
+pub fn attribute_priority_op_gas(
+    blocks: &[Block],
+    action: AggregatedActionType,
+    gas_used: u64,
+    gas_price: &BigUint,
+) -> Vec<PriorityOpGas> {
+    let base_cost = match action {
+        AggregatedActionType::CommitBlocks => CommitCost::BASE_COST,
+        AggregatedActionType::ExecuteBlocks => VerifyCost::BASE_COST,
+        _ => return Vec::new(),
+    };
+
+    let mut expected_cost = u128::from(base_cost) * blocks.len() as u128;
+    let mut priority_ops = Vec::new();
+    for block in blocks {
+        for executed_op in &block.block_transactions {
+            let op = match executed_op.get_executed_op() {
+                Some(op) => op,
+                None => continue,
+            };
+            let op_cost = estimated_op_gas(op, action);
+            expected_cost += u128::from(op_cost);
+            if let ExecutedOperations::PriorityOp(priority_op) = executed_op {
+                priority_ops.push((block.block_number, &priority_op.priority_op, op_cost));
+            }
+        }
+    }
+    if expected_cost == 0 {
+        return Vec::new();
+    }
+
+    priority_ops
+        .into_iter()
+        .map(|(block_number, priority_op, op_cost)| {
+            let gas = (u128::from(op_cost) * u128::from(gas_used) / expected_cost) as u64;
+            PriorityOpGas {
+                serial_id: priority_op.serial_id,
+                block_number,
+                op_type: priority_op_type(&priority_op.data),
+                gas,
+                fee: BigUint::from(gas) * gas_price,
+            }
+        })
+        .collect()
+}
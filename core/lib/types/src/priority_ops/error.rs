@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::SerialId;
+
 #[derive(Debug, Error)]
 pub enum LogParseError {
     #[error("PubData length mismatch")]
@@ -9,3 +11,15 @@ pub enum LogParseError {
     #[error("Ethereum ABI error: {0}")]
     AbiError(#[from] ethabi::Error),
 }
+
+/// Violation of the serial ID continuity of the processed priority operations.
+///
+/// Every priority operation must be processed exactly once and in the order it was added
+/// to the queue on L1, otherwise the block is rejected by the contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum SerialIdContinuityError {
+    #[error("Priority operation #{0} is processed twice")]
+    Duplicate(SerialId),
+    #[error("Priority operation #{expected} is skipped, got #{found} instead")]
+    Gap { expected: SerialId, found: SerialId },
+}
@@ -23,6 +23,8 @@ mod error;
 #[cfg(test)]
 mod tests;
 
+pub use error::SerialIdContinuityError;
+
 /// Deposit priority operation transfers funds from the L1 account to the desired L2 account.
 /// If the target L2 account didn't exist at the moment of the operation execution, a new
 /// account will be created.
@@ -279,3 +281,25 @@ impl PriorityOp {
         )
     }
 }
+
+/// Checks that the serial IDs go one after another, starting from `next_serial_id`.
+/// Returns the serial ID following the last checked one.
+pub fn check_serial_id_continuity(
+    next_serial_id: SerialId,
+    serial_ids: impl IntoIterator<Item = SerialId>,
+) -> Result<SerialId, SerialIdContinuityError> {
+    serial_ids
+        .into_iter()
+        .try_fold(next_serial_id, |expected, serial_id| {
+            if serial_id < expected {
+                Err(SerialIdContinuityError::Duplicate(serial_id))
+            } else if serial_id > expected {
+                Err(SerialIdContinuityError::Gap {
+                    expected,
+                    found: serial_id,
+                })
+            } else {
+                Ok(expected + 1)
+            }
+        })
+}
@@ -109,3 +109,26 @@ mod backward_compatibility {
         let _new_value: PriorityOp = serde_json::from_value(old_serialized).unwrap();
     }
 }
+
+#[test]
+fn serial_id_continuity() {
+    use super::{check_serial_id_continuity, SerialIdContinuityError};
+
+    assert_eq!(check_serial_id_continuity(5, vec![]), Ok(5));
+    assert_eq!(check_serial_id_continuity(5, vec![5, 6, 7]), Ok(8));
+    assert_eq!(
+        check_serial_id_continuity(5, vec![5, 6, 6]),
+        Err(SerialIdContinuityError::Duplicate(6))
+    );
+    assert_eq!(
+        check_serial_id_continuity(5, vec![4]),
+        Err(SerialIdContinuityError::Duplicate(4))
+    );
+    assert_eq!(
+        check_serial_id_continuity(5, vec![5, 7]),
+        Err(SerialIdContinuityError::Gap {
+            expected: 6,
+            found: 7
+        })
+    );
+}
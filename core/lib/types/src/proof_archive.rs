@@ -0,0 +1,238 @@
+//! Archives of the verified blocks kept for the long-term audit retention.
+//!
+//! An archive holds everything needed to check a verified block without the server database:
+//! the data the block is committed with, the commitment derived from it, the proofs taking the
+//! commitment as the public input, and the verification key the block proof is checked with.
+//! The archive is a single JSON document starting with the format name and version, so it can be
+//! interpreted without the sources of the exporting server.
+//!
+//! [`ProofArchive::check`] checks that the parts of the archive match each other: the commitment
+//! is re-derived from the public data, the proofs must be made for this commitment, and the
+//! verification key must match its hash. Checking the block proof itself is done by the
+//! `proof-archiver` tool.
+
+// Built-in uses
+// External uses
+use chrono::{DateTime, Utc};
+use parity_crypto::Keccak256;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+// Workspace uses
+use zksync_basic_types::{BlockNumber, H256, U256};
+use zksync_crypto::proof::{EncodedAggregatedProof, SingleProof};
+use zksync_utils::ZeroPrefixHexSerde;
+// Local uses
+use crate::{
+    block::Block,
+    block_commitment::{
+        compute_block_commitment, CommitmentError, CommitmentInput, StoredBlockInfo,
+    },
+};
+
+/// Name of the archive format, the first field of every archive.
+pub const PROOF_ARCHIVE_FORMAT: &str = "zksync-proof-archive";
+/// Version of the archive format produced by this code.
+pub const PROOF_ARCHIVE_VERSION: u32 = 2;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ProofArchiveError {
+    #[error("Unsupported archive format '{format}' version {version}")]
+    UnsupportedFormat { format: String, version: u32 },
+    #[error("Archive can't be decoded: {0}")]
+    Malformed(String),
+    #[error("Block commitment can't be computed: {0}")]
+    Commitment(#[from] CommitmentError),
+    #[error("Stored block info doesn't match the public data of the block")]
+    StoredBlockInfoMismatch,
+    #[error("Public input doesn't match the block commitment")]
+    PublicInputMismatch,
+    #[error("Block proof is not made for the block commitment")]
+    ProofInputMismatch,
+    #[error("Aggregated proof doesn't contain the block commitment")]
+    AggregatedProofMismatch,
+    #[error("Verification key doesn't match its hash")]
+    VerificationKeyHashMismatch,
+}
+
+/// Leading fields of the archive, the rest of the archive is interpreted according to them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofArchiveHeader {
+    pub format: String,
+    pub version: u32,
+}
+
+/// Aggregated proof including the block proof, as it's sent to the contract.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedAggregatedProof {
+    pub first_block: BlockNumber,
+    pub last_block: BlockNumber,
+    pub proof: EncodedAggregatedProof,
+}
+
+/// Verification key of the block proof, as it's stored in the keys directory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedVerificationKey {
+    /// Version of the circuit keys, i.e. the keys directory relative to `ZKSYNC_HOME`.
+    pub key_version: String,
+    /// `keccak256` of the key, to compare with the key the contract was deployed with.
+    pub key_hash: H256,
+    #[serde(with = "ZeroPrefixHexSerde")]
+    pub key: Vec<u8>,
+}
+
+impl ArchivedVerificationKey {
+    pub fn new(key_version: String, key: Vec<u8>) -> Self {
+        Self {
+            key_version,
+            key_hash: H256::from(key.keccak256()),
+            key,
+        }
+    }
+}
+
+/// Archive of the verified block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofArchive {
+    #[serde(flatten)]
+    pub header: ProofArchiveHeader,
+    pub block_number: BlockNumber,
+    /// Size of the block in chunks, determines the verification key of the block proof.
+    pub block_chunks_size: usize,
+    /// Data the block is committed with on L1, the public data is not compressed.
+    pub commitment_input: CommitmentInput,
+    /// Block data kept by the contract, including the commitment.
+    pub stored_block_info: StoredBlockInfo,
+    /// Public input of the block proof derived from the commitment.
+    pub public_input: U256,
+    /// Proof of the block as it's produced by the prover.
+    pub proof: SingleProof,
+    /// Verification key of the block size the block proof is checked with.
+    pub verification_key: ArchivedVerificationKey,
+    /// Aggregated proof verified by the contract, if it's still stored by the server.
+    pub aggregated_proof: Option<ArchivedAggregatedProof>,
+    pub exported_at: DateTime<Utc>,
+}
+
+/// Record of the exported archive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofArchiveRecord {
+    pub block_number: BlockNumber,
+    pub file_name: String,
+    /// Hash of the archive file, see [`ProofArchive::archive_hash`].
+    pub archive_hash: H256,
+    pub exported_at: DateTime<Utc>,
+}
+
+/// Clears the three highest bits of the value, so it fits into the field element.
+/// The contract applies the same mask (`INPUT_MASK`) to the commitments of the blocks.
+fn mask_input(value: U256) -> U256 {
+    value & (U256::max_value() >> 3)
+}
+
+/// Returns the public input of the block proof, which is the masked block commitment.
+pub fn commitment_public_input(commitment: H256) -> U256 {
+    mask_input(U256::from_big_endian(commitment.as_bytes()))
+}
+
+impl ProofArchive {
+    /// Creates the archive of the block. `previous_state_hash` is the root hash of the state
+    /// before the block, i.e. the one of the previous block.
+    pub fn new(
+        block: &Block,
+        previous_state_hash: H256,
+        proof: SingleProof,
+        verification_key: ArchivedVerificationKey,
+        aggregated_proof: Option<ArchivedAggregatedProof>,
+    ) -> Self {
+        let stored_block_info = StoredBlockInfo::from(block);
+        Self {
+            header: ProofArchiveHeader {
+                format: PROOF_ARCHIVE_FORMAT.to_owned(),
+                version: PROOF_ARCHIVE_VERSION,
+            },
+            block_number: block.block_number,
+            block_chunks_size: block.block_chunks_size,
+            commitment_input: CommitmentInput {
+                block_number: block.block_number,
+                fee_account: block.fee_account,
+                previous_state_hash,
+                state_hash: block.get_eth_encoded_root(),
+                timestamp: block.timestamp,
+                public_data: block.get_eth_public_data(),
+            },
+            public_input: commitment_public_input(stored_block_info.commitment),
+            stored_block_info,
+            proof,
+            verification_key,
+            aggregated_proof,
+            exported_at: Utc::now(),
+        }
+    }
+
+    /// Decodes the archive, rejecting the unknown formats and versions before reading the rest.
+    pub fn from_json(bytes: &[u8]) -> Result<Self, ProofArchiveError> {
+        let header: ProofArchiveHeader = serde_json::from_slice(bytes)
+            .map_err(|err| ProofArchiveError::Malformed(err.to_string()))?;
+        if header.format != PROOF_ARCHIVE_FORMAT || header.version != PROOF_ARCHIVE_VERSION {
+            return Err(ProofArchiveError::UnsupportedFormat {
+                format: header.format,
+                version: header.version,
+            });
+        }
+        serde_json::from_slice(bytes).map_err(|err| ProofArchiveError::Malformed(err.to_string()))
+    }
+
+    pub fn to_json(&self) -> Vec<u8> {
+        serde_json::to_vec_pretty(self).expect("Proof archive can't be serialized")
+    }
+
+    /// Hash of the archive file recorded by the server, `keccak256` of the file contents.
+    pub fn archive_hash(bytes: &[u8]) -> H256 {
+        H256::from(bytes.keccak256())
+    }
+
+    /// Checks that the parts of the archive match each other. The validity of the proofs
+    /// themselves is not checked.
+    pub fn check(&self) -> Result<(), ProofArchiveError> {
+        let stored_block_info = compute_block_commitment(&self.commitment_input)?;
+        if stored_block_info != self.stored_block_info
+            || stored_block_info.block_number != self.block_number
+        {
+            return Err(ProofArchiveError::StoredBlockInfoMismatch);
+        }
+
+        let public_input = commitment_public_input(stored_block_info.commitment);
+        if public_input != self.public_input {
+            return Err(ProofArchiveError::PublicInputMismatch);
+        }
+        if self.proof.serialize_single_proof().inputs != [public_input] {
+            return Err(ProofArchiveError::ProofInputMismatch);
+        }
+        if H256::from(self.verification_key.key.keccak256()) != self.verification_key.key_hash {
+            return Err(ProofArchiveError::VerificationKeyHashMismatch);
+        }
+
+        if let Some(aggregated) = &self.aggregated_proof {
+            let index = self
+                .block_number
+                .checked_sub(*aggregated.first_block)
+                .filter(|_| self.block_number <= aggregated.last_block)
+                .ok_or(ProofArchiveError::AggregatedProofMismatch)?;
+            let commitment = aggregated
+                .proof
+                .individual_vk_inputs
+                .get(index as usize)
+                .copied()
+                .map(mask_input);
+            if commitment != Some(public_input) {
+                return Err(ProofArchiveError::AggregatedProofMismatch);
+            }
+        }
+        Ok(())
+    }
+}
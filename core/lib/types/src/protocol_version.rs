@@ -0,0 +1,104 @@
+//! Version of the protocol between the zkSync components.
+//!
+//! The API server, the Core (mempool and state keeper), the prover server and the provers are
+//! deployed separately, so during a rolling upgrade the components of different versions may
+//! communicate with each other. Requests between the components carry the protocol version
+//! of the sender in the [`PROTOCOL_VERSION_HEADER`], and the prover jobs carry the version of
+//! the prover server which created them. Requests and jobs of the incompatible versions are
+//! rejected instead of being processed, since e.g. a prover of such a version may produce
+//! an invalid proof for the job.
+//!
+//! The version must be bumped whenever the data passed between the components changes.
+//! Components accept the versions starting from [`MIN_COMPATIBLE_PROTOCOL_VERSION`], which is
+//! raised to the new version only if the change is incompatible, e.g. the witness format of
+//! the prover jobs changes. A component can't know whether the newer versions are compatible
+//! with it, so they are accepted: the newer side rejects the older one by its own minimum,
+//! that's why both sides of every exchange check the version of the other one.
+
+use thiserror::Error;
+
+/// Version of the protocol between the zkSync components.
+pub const COMPONENTS_PROTOCOL_VERSION: u32 = 1;
+
+/// Oldest version of the protocol compatible with the current one.
+pub const MIN_COMPATIBLE_PROTOCOL_VERSION: u32 = 1;
+
+/// Name of the HTTP header carrying the protocol version of the request sender.
+pub const PROTOCOL_VERSION_HEADER: &str = "zksync-protocol-version";
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ProtocolVersionError {
+    #[error("Protocol version is not specified, expected version {expected}")]
+    Missing { expected: u32 },
+    #[error("Protocol version is incorrect: {0}")]
+    Invalid(String),
+    #[error("Incompatible protocol version {actual}, expected {min_compatible} or newer")]
+    Incompatible { min_compatible: u32, actual: u32 },
+}
+
+/// Checks that the component of the other side uses a compatible protocol version.
+pub fn check_protocol_version(actual: Option<u32>) -> Result<(), ProtocolVersionError> {
+    match actual {
+        None => Err(ProtocolVersionError::Missing {
+            expected: COMPONENTS_PROTOCOL_VERSION,
+        }),
+        Some(actual) if actual < MIN_COMPATIBLE_PROTOCOL_VERSION => {
+            Err(ProtocolVersionError::Incompatible {
+                min_compatible: MIN_COMPATIBLE_PROTOCOL_VERSION,
+                actual,
+            })
+        }
+        Some(_) => Ok(()),
+    }
+}
+
+/// Checks the value of the [`PROTOCOL_VERSION_HEADER`] of the request.
+pub fn check_protocol_version_header(value: Option<&str>) -> Result<(), ProtocolVersionError> {
+    let actual = value
+        .map(|value| {
+            value
+                .trim()
+                .parse()
+                .map_err(|_| ProtocolVersionError::Invalid(value.to_owned()))
+        })
+        .transpose()?;
+    check_protocol_version(actual)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn protocol_version_header_check() {
+        let current = COMPONENTS_PROTOCOL_VERSION.to_string();
+        assert_eq!(check_protocol_version_header(Some(&current)), Ok(()));
+
+        assert_eq!(
+            check_protocol_version_header(None),
+            Err(ProtocolVersionError::Missing {
+                expected: COMPONENTS_PROTOCOL_VERSION
+            })
+        );
+        assert!(matches!(
+            check_protocol_version_header(Some("v1")),
+            Err(ProtocolVersionError::Invalid(_))
+        ));
+    }
+
+    /// Checks that the versions since the oldest compatible one are accepted, including
+    /// the newer ones.
+    #[test]
+    fn compatible_protocol_versions() {
+        for version in MIN_COMPATIBLE_PROTOCOL_VERSION..=COMPONENTS_PROTOCOL_VERSION + 1 {
+            assert_eq!(check_protocol_version(Some(version)), Ok(()));
+        }
+        assert_eq!(
+            check_protocol_version(Some(MIN_COMPATIBLE_PROTOCOL_VERSION - 1)),
+            Err(ProtocolVersionError::Incompatible {
+                min_compatible: MIN_COMPATIBLE_PROTOCOL_VERSION,
+                actual: MIN_COMPATIBLE_PROTOCOL_VERSION - 1,
+            })
+        );
+    }
+}
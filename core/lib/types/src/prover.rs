@@ -1,3 +1,6 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use zksync_basic_types::BlockNumber;
 
@@ -72,3 +75,94 @@ impl ToString for ProverJobType {
 #[derive(Debug, Error, PartialEq)]
 #[error("Incorrect ProverJobStatus number: {0}")]
 pub struct IncorrectProverJobStatus(pub i32);
+
+/// Status of the prover job leased to an external prover.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProverLeaseStatus {
+    /// The job is being proven, the collateral is locked.
+    Leased,
+    /// The proof is accepted, the collateral is unlocked and the payment is accounted.
+    Completed,
+    /// The collateral is slashed, e.g. because the lease expired.
+    Slashed,
+}
+
+impl ToString for ProverLeaseStatus {
+    fn to_string(&self) -> String {
+        match self {
+            ProverLeaseStatus::Leased => String::from("LEASED"),
+            ProverLeaseStatus::Completed => String::from("COMPLETED"),
+            ProverLeaseStatus::Slashed => String::from("SLASHED"),
+        }
+    }
+}
+
+impl FromStr for ProverLeaseStatus {
+    type Err = IncorrectProverLeaseStatus;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "LEASED" => ProverLeaseStatus::Leased,
+            "COMPLETED" => ProverLeaseStatus::Completed,
+            "SLASHED" => ProverLeaseStatus::Slashed,
+            _ => return Err(IncorrectProverLeaseStatus(s.to_owned())),
+        })
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+#[error("Incorrect ProverLeaseStatus: {0}")]
+pub struct IncorrectProverLeaseStatus(pub String);
+
+/// Stage of the proof generation reported by the prover along with the heartbeats.
+/// The stages are reported as they start. The proving libraries don't report their progress,
+/// so there are no percentages within the stages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "stage", rename_all = "camelCase")]
+pub enum ProofProgress {
+    /// The circuit witness is built from the job data.
+    WitnessBuilt,
+    /// The circuit is being transpiled into the PLONK constraint system, the first step of the setup.
+    /// The setup is skipped if the prover has it cached for the block size.
+    Transpilation,
+    /// The setup polynomials of the circuit are being computed.
+    Setup,
+    /// The universal setup (CRS) of the circuit size is being loaded.
+    UniversalSetup,
+    /// The proof is being computed: the circuit is synthesized with the witness, then the FFTs and
+    /// the commitments take most of the proving time.
+    Proving,
+    /// The proof is generated and is being published.
+    Done,
+}
+
+impl ProofProgress {
+    pub fn stage(&self) -> &'static str {
+        match self {
+            ProofProgress::WitnessBuilt => "WITNESS_BUILT",
+            ProofProgress::Transpilation => "TRANSPILATION",
+            ProofProgress::Setup => "SETUP",
+            ProofProgress::UniversalSetup => "UNIVERSAL_SETUP",
+            ProofProgress::Proving => "PROVING",
+            ProofProgress::Done => "DONE",
+        }
+    }
+
+    /// Restores the progress from the stage name stored in the database.
+    pub fn from_stage(stage: &str) -> Result<Self, IncorrectProofProgress> {
+        Ok(match stage {
+            "WITNESS_BUILT" => ProofProgress::WitnessBuilt,
+            "TRANSPILATION" => ProofProgress::Transpilation,
+            "SETUP" => ProofProgress::Setup,
+            "UNIVERSAL_SETUP" => ProofProgress::UniversalSetup,
+            "PROVING" => ProofProgress::Proving,
+            "DONE" => ProofProgress::Done,
+            _ => return Err(IncorrectProofProgress(stage.to_owned())),
+        })
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+#[error("Incorrect ProofProgress stage: {0}")]
+pub struct IncorrectProofProgress(pub String);
@@ -0,0 +1,311 @@
+//! Compression format of the block public data.
+//!
+//! Calldata is the main cost of the block commitment, so the public data is meant to be sent
+//! compressed and decompressed by the contract before checking the block commitment. The commitment
+//! itself is calculated over the uncompressed public data, so the circuit is not affected.
+//!
+//! The contract doesn't decompress the public data yet, so the commit transactions carry
+//! the uncompressed public data, and the compression must not be used on the commit path until
+//! the contract-side decoder is deployed. The decoders of the commit transactions (data restore,
+//! L1 state verifier) accept both formats already.
+//!
+//! The compressed public data has the following layout:
+//!
+//! ```text
+//! marker (1 byte) | format version (1 byte) | uncompressed length (4 bytes, BE) | deflate stream
+//! ```
+//!
+//! Before being deflated, the operations are delta-encoded: every account ID and token ID
+//! in the operation is replaced with the zigzag varint-encoded difference with the previous
+//! ID of the same kind in the block. Blocks usually touch a small set of accounts and tokens,
+//! so most of the IDs shrink to a single byte.
+//!
+//! The marker byte is not a valid operation type, so the uncompressed public data of the
+//! blocks committed before the upgrade is distinguished from the compressed one.
+
+// Built-in uses
+use std::io::{Read, Write};
+// External uses
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+use thiserror::Error;
+// Workspace uses
+use zksync_crypto::params::{
+    ACCOUNT_ID_BIT_WIDTH, CHUNK_BYTES, ETH_ADDRESS_BIT_WIDTH, NONCE_BIT_WIDTH, TOKEN_BIT_WIDTH,
+};
+// Local uses
+use crate::operations::{
+    ChangePubKeyOp, CloseOp, DepositOp, ForcedExitOp, FullExitOp, TransferOp, TransferToNewOp,
+    WithdrawOp, ZkSyncOp,
+};
+
+/// First byte of the compressed public data.
+pub const COMPRESSED_PUBDATA_MARKER: u8 = 0xff;
+/// Version of the compressed public data format.
+pub const COMPRESSED_PUBDATA_FORMAT_VERSION: u8 = 1;
+
+const HEADER_LEN: usize = 6;
+const ACCOUNT_ID_BYTES: usize = ACCOUNT_ID_BIT_WIDTH / 8;
+const TOKEN_BYTES: usize = TOKEN_BIT_WIDTH / 8;
+const ADDRESS_BYTES: usize = ETH_ADDRESS_BIT_WIDTH / 8;
+const PACKED_AMOUNT_BYTES: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum IdKind {
+    Account,
+    Token,
+}
+
+impl IdKind {
+    fn width(self) -> usize {
+        match self {
+            IdKind::Account => ACCOUNT_ID_BYTES,
+            IdKind::Token => TOKEN_BYTES,
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum PubdataDecompressionError {
+    #[error("Unsupported compressed pubdata format version: {0}")]
+    UnsupportedFormat(u8),
+    #[error("Compressed pubdata is truncated")]
+    Truncated,
+    #[error("Compressed pubdata stream is malformed: {0}")]
+    Malformed(String),
+    #[error("Unexpected operation type in the compressed pubdata: {0}")]
+    UnexpectedOperationType(u8),
+    #[error("Decompressed pubdata length mismatch: expected {expected}, got {actual}")]
+    LengthMismatch { expected: usize, actual: usize },
+}
+
+/// Returns whether the public data is compressed.
+pub fn is_compressed_pubdata(data: &[u8]) -> bool {
+    data.first() == Some(&COMPRESSED_PUBDATA_MARKER)
+}
+
+/// Offsets of the account and token ID fields in the public data of the operation.
+fn id_fields(op_type: u8) -> Option<&'static [(usize, IdKind)]> {
+    const ACCOUNT: IdKind = IdKind::Account;
+    const TOKEN: IdKind = IdKind::Token;
+    // Every operation starts with the operation type byte.
+    const FIRST: usize = 1;
+    const AFTER_ACCOUNT: usize = FIRST + ACCOUNT_ID_BYTES;
+    const AFTER_ACCOUNT_TOKEN: usize = AFTER_ACCOUNT + TOKEN_BYTES;
+
+    let fields: &'static [(usize, IdKind)] = match op_type {
+        DepositOp::OP_CODE | WithdrawOp::OP_CODE => &[(FIRST, ACCOUNT), (AFTER_ACCOUNT, TOKEN)],
+        TransferOp::OP_CODE => &[
+            (FIRST, ACCOUNT),
+            (AFTER_ACCOUNT, TOKEN),
+            (AFTER_ACCOUNT_TOKEN, ACCOUNT),
+        ],
+        TransferToNewOp::OP_CODE => &[
+            (FIRST, ACCOUNT),
+            (AFTER_ACCOUNT, TOKEN),
+            (
+                AFTER_ACCOUNT_TOKEN + PACKED_AMOUNT_BYTES + ADDRESS_BYTES,
+                ACCOUNT,
+            ),
+        ],
+        CloseOp::OP_CODE => &[(FIRST, ACCOUNT)],
+        FullExitOp::OP_CODE => &[(FIRST, ACCOUNT), (AFTER_ACCOUNT + ADDRESS_BYTES, TOKEN)],
+        ChangePubKeyOp::OP_CODE => &[
+            (FIRST, ACCOUNT),
+            (
+                AFTER_ACCOUNT + 2 * ADDRESS_BYTES + NONCE_BIT_WIDTH / 8,
+                TOKEN,
+            ),
+        ],
+        ForcedExitOp::OP_CODE => &[
+            (FIRST, ACCOUNT),
+            (AFTER_ACCOUNT, ACCOUNT),
+            (AFTER_ACCOUNT + ACCOUNT_ID_BYTES, TOKEN),
+        ],
+        op_type => {
+            // Noop operations don't have any IDs.
+            ZkSyncOp::public_data_length(op_type).ok()?;
+            &[]
+        }
+    };
+    Some(fields)
+}
+
+/// The last IDs of every kind seen in the block.
+#[derive(Debug, Default)]
+struct LastIds {
+    account: i64,
+    token: i64,
+}
+
+impl LastIds {
+    fn get_mut(&mut self, kind: IdKind) -> &mut i64 {
+        match kind {
+            IdKind::Account => &mut self.account,
+            IdKind::Token => &mut self.token,
+        }
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn read_varint(data: &[u8], pos: &mut usize) -> Result<u64, PubdataDecompressionError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *data.get(*pos).ok_or(PubdataDecompressionError::Truncated)?;
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(PubdataDecompressionError::Malformed(
+        "varint is too long".into(),
+    ))
+}
+
+fn take<'a>(
+    data: &'a [u8],
+    pos: &mut usize,
+    len: usize,
+) -> Result<&'a [u8], PubdataDecompressionError> {
+    let bytes = data
+        .get(*pos..*pos + len)
+        .ok_or(PubdataDecompressionError::Truncated)?;
+    *pos += len;
+    Ok(bytes)
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+fn read_be_id(bytes: &[u8]) -> i64 {
+    bytes
+        .iter()
+        .fold(0i64, |acc, &byte| (acc << 8) | i64::from(byte))
+}
+
+fn write_be_id(out: &mut Vec<u8>, id: i64, width: usize) {
+    out.extend_from_slice(&id.to_be_bytes()[8 - width..]);
+}
+
+/// Replaces the IDs in the operations with the deltas.
+fn delta_encode(pubdata: &[u8]) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(pubdata.len());
+    let mut last_ids = LastIds::default();
+    let mut pos = 0;
+    while pos < pubdata.len() {
+        let op_type = pubdata[pos];
+        let fields = id_fields(op_type).expect("Block pubdata consists of the known operations");
+        let op_len = ZkSyncOp::public_data_length(op_type).unwrap() * CHUNK_BYTES;
+        let op = &pubdata[pos..pos + op_len];
+
+        let mut op_pos = 0;
+        for &(offset, kind) in fields {
+            encoded.extend_from_slice(&op[op_pos..offset]);
+            let id = read_be_id(&op[offset..offset + kind.width()]);
+            let last = last_ids.get_mut(kind);
+            write_varint(&mut encoded, zigzag(id - *last));
+            *last = id;
+            op_pos = offset + kind.width();
+        }
+        encoded.extend_from_slice(&op[op_pos..]);
+        pos += op_len;
+    }
+    encoded
+}
+
+/// Restores the IDs in the delta-encoded operations.
+fn delta_decode(encoded: &[u8], max_len: usize) -> Result<Vec<u8>, PubdataDecompressionError> {
+    let mut pubdata = Vec::with_capacity(max_len);
+    let mut last_ids = LastIds::default();
+    let mut pos = 0;
+
+    while pos < encoded.len() {
+        let op_type = encoded[pos];
+        let fields = id_fields(op_type)
+            .ok_or(PubdataDecompressionError::UnexpectedOperationType(op_type))?;
+        let op_len = ZkSyncOp::public_data_length(op_type).unwrap() * CHUNK_BYTES;
+        if pubdata.len() + op_len > max_len {
+            return Err(PubdataDecompressionError::LengthMismatch {
+                expected: max_len,
+                actual: pubdata.len() + op_len,
+            });
+        }
+
+        let mut op_pos = 0;
+        for &(offset, kind) in fields {
+            pubdata.extend_from_slice(take(encoded, &mut pos, offset - op_pos)?);
+            let delta = unzigzag(read_varint(encoded, &mut pos)?);
+            let last = last_ids.get_mut(kind);
+            *last = last
+                .checked_add(delta)
+                .filter(|id| *id >= 0 && *id < 1 << (8 * kind.width()))
+                .ok_or_else(|| PubdataDecompressionError::Malformed("ID is out of range".into()))?;
+            write_be_id(&mut pubdata, *last, kind.width());
+            op_pos = offset + kind.width();
+        }
+        pubdata.extend_from_slice(take(encoded, &mut pos, op_len - op_pos)?);
+    }
+    Ok(pubdata)
+}
+
+/// Compresses the block public data.
+///
+/// # Panics
+///
+/// Panics if the public data is not a sequence of the zkSync operations, which never happens
+/// for the public data of the blocks created by the server.
+pub fn compress_pubdata(pubdata: &[u8]) -> Vec<u8> {
+    let mut compressed = vec![COMPRESSED_PUBDATA_MARKER, COMPRESSED_PUBDATA_FORMAT_VERSION];
+    compressed.extend_from_slice(&(pubdata.len() as u32).to_be_bytes());
+
+    let mut encoder = DeflateEncoder::new(compressed, Compression::best());
+    encoder
+        .write_all(&delta_encode(pubdata))
+        .expect("Writing to the vector can't fail");
+    encoder.finish().expect("Writing to the vector can't fail")
+}
+
+/// Decompresses the block public data. The uncompressed public data is returned as is.
+pub fn decompress_pubdata(data: &[u8]) -> Result<Vec<u8>, PubdataDecompressionError> {
+    if !is_compressed_pubdata(data) {
+        return Ok(data.to_vec());
+    }
+    if data.len() < HEADER_LEN {
+        return Err(PubdataDecompressionError::Truncated);
+    }
+    if data[1] != COMPRESSED_PUBDATA_FORMAT_VERSION {
+        return Err(PubdataDecompressionError::UnsupportedFormat(data[1]));
+    }
+    let mut length = [0u8; 4];
+    length.copy_from_slice(&data[2..HEADER_LEN]);
+    let expected = u32::from_be_bytes(length) as usize;
+
+    // Every ID grows by one byte at most when delta-encoded, so the encoded operations are
+    // never twice as long as the original ones. This bounds the memory used by a malicious input.
+    let mut encoded = Vec::new();
+    DeflateDecoder::new(&data[HEADER_LEN..])
+        .take(2 * expected as u64)
+        .read_to_end(&mut encoded)
+        .map_err(|err| PubdataDecompressionError::Malformed(err.to_string()))?;
+
+    let pubdata = delta_decode(&encoded, expected)?;
+    if pubdata.len() != expected {
+        return Err(PubdataDecompressionError::LengthMismatch {
+            expected,
+            actual: pubdata.len(),
+        });
+    }
+    Ok(pubdata)
+}
@@ -0,0 +1,222 @@
+//! Operator revenue reporting.
+//!
+//! Fees collected by the operator are aggregated by the token and by the hour the transaction
+//! was executed at. Every aggregate stores the fee amount along with its USD valuation at the
+//! moment of aggregation, so the reports do not depend on the current token prices.
+//! Longer periods are derived from the hourly aggregates.
+
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use num::{rational::Ratio, BigUint};
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::TokenId;
+use zksync_utils::{BigUintSerdeAsRadix10Str, UnsignedRatioSerializeAsDecimal};
+
+use crate::{block::ExecutedOperations, TokenLike};
+
+/// Length of the period the revenue is reported for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RevenuePeriod {
+    Hour,
+    Day,
+}
+
+impl RevenuePeriod {
+    /// Returns the period name as accepted by the `date_trunc` SQL function.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Hour => "hour",
+            Self::Day => "day",
+        }
+    }
+
+    fn seconds(self) -> i64 {
+        match self {
+            Self::Hour => 3600,
+            Self::Day => 86400,
+        }
+    }
+
+    /// Returns the beginning of the period containing the given moment.
+    pub fn start_of(self, time: DateTime<Utc>) -> DateTime<Utc> {
+        let timestamp = time.timestamp();
+        let start = timestamp - timestamp.rem_euclid(self.seconds());
+        DateTime::from_utc(NaiveDateTime::from_timestamp(start, 0), Utc)
+    }
+}
+
+impl fmt::Display for RevenuePeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RevenuePeriod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hour" => Ok(Self::Hour),
+            "day" => Ok(Self::Day),
+            other => Err(format!("Unknown revenue period: {}", other)),
+        }
+    }
+}
+
+/// Fees collected in one token within one hour.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollectedFees {
+    pub period_start: DateTime<Utc>,
+    pub token_id: TokenId,
+    pub amount: BigUint,
+    /// Value of the fees in USD using the token price at the moment of aggregation.
+    pub amount_usd: Ratio<BigUint>,
+    pub tx_count: u64,
+}
+
+impl CollectedFees {
+    /// Sums the fees of the successfully executed transactions by the token and the hour.
+    /// USD valuation is left zero and should be filled in by the caller.
+    pub fn aggregate(operations: &[ExecutedOperations]) -> Vec<Self> {
+        let mut fees: HashMap<(DateTime<Utc>, TokenId), Self> = HashMap::new();
+        for operation in operations {
+            let tx = match operation {
+                ExecutedOperations::Tx(tx) if tx.success => tx,
+                _ => continue,
+            };
+            let (token_id, fee) = match tx.signed_tx.tx.get_fee_info() {
+                Some((_, TokenLike::Id(token_id), _, fee)) => (token_id, fee),
+                _ => continue,
+            };
+
+            let period_start = RevenuePeriod::Hour.start_of(tx.created_at);
+            let entry = fees
+                .entry((period_start, token_id))
+                .or_insert_with(|| Self {
+                    period_start,
+                    token_id,
+                    amount: BigUint::from(0u32),
+                    amount_usd: Ratio::from_integer(BigUint::from(0u32)),
+                    tx_count: 0,
+                });
+            entry.amount += fee;
+            entry.tx_count += 1;
+        }
+
+        let mut fees: Vec<_> = fees.into_iter().map(|(_, fees)| fees).collect();
+        fees.sort_by_key(|fees| (fees.period_start, fees.token_id));
+        fees
+    }
+}
+
+/// Revenue of the operator in one token for the reported period.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenRevenue {
+    pub period_start: DateTime<Utc>,
+    pub token_id: TokenId,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub fee_amount: BigUint,
+    #[serde(with = "UnsignedRatioSerializeAsDecimal")]
+    pub fee_usd: Ratio<BigUint>,
+    pub tx_count: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        tx::{TimeRange, Transfer},
+        Address, ExecutedTx, Nonce, SignedZkSyncTx,
+    };
+    use zksync_basic_types::AccountId;
+
+    fn transfer(token: u16, fee: u64, created_at: &str, success: bool) -> ExecutedOperations {
+        let transfer = Transfer::new(
+            AccountId(1),
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            TokenId(token),
+            100u64.into(),
+            fee.into(),
+            Nonce(0),
+            TimeRange::default(),
+            None,
+        );
+        ExecutedOperations::Tx(Box::new(ExecutedTx {
+            signed_tx: SignedZkSyncTx {
+                tx: transfer.into(),
+                eth_sign_data: None,
+            },
+            success,
+            op: None,
+            fail_reason: None,
+            block_index: None,
+            created_at: created_at.parse().unwrap(),
+            batch_id: None,
+        }))
+    }
+
+    #[test]
+    fn period_start() {
+        let time: DateTime<Utc> = "2021-05-24T13:45:10Z".parse().unwrap();
+        assert_eq!(
+            RevenuePeriod::Hour.start_of(time),
+            "2021-05-24T13:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(
+            RevenuePeriod::Day.start_of(time),
+            "2021-05-24T00:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+    }
+
+    #[test]
+    fn fees_aggregation() {
+        let operations = vec![
+            transfer(0, 10, "2021-05-24T13:05:00Z", true),
+            transfer(0, 15, "2021-05-24T13:55:00Z", true),
+            transfer(1, 7, "2021-05-24T13:30:00Z", true),
+            transfer(0, 20, "2021-05-24T14:00:00Z", true),
+            // Failed transactions do not pay fees.
+            transfer(0, 100, "2021-05-24T13:10:00Z", false),
+        ];
+
+        let fees = CollectedFees::aggregate(&operations);
+        let summary: Vec<_> = fees
+            .iter()
+            .map(|fees| {
+                (
+                    fees.period_start.to_rfc3339(),
+                    *fees.token_id,
+                    fees.amount.clone(),
+                    fees.tx_count,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "2021-05-24T13:00:00+00:00".to_owned(),
+                    0,
+                    BigUint::from(25u32),
+                    2
+                ),
+                (
+                    "2021-05-24T13:00:00+00:00".to_owned(),
+                    1,
+                    BigUint::from(7u32),
+                    1
+                ),
+                (
+                    "2021-05-24T14:00:00+00:00".to_owned(),
+                    0,
+                    BigUint::from(20u32),
+                    1
+                ),
+            ]
+        );
+    }
+}
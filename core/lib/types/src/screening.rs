@@ -0,0 +1,101 @@
+//! Screening of the addresses receiving funds in the zkSync network.
+//!
+//! Regulated operators may be required to screen the recipients of the deposits and transfers
+//! against the sanctions lists. When the recipient is screened, the configured action is taken
+//! on the transaction and the decision is appended to the audit trail.
+//!
+//! Priority operations are enforced by the zkSync contract, so the deposits to the screened
+//! addresses can't be rejected or delayed and are always flagged.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::Address;
+
+use crate::tx::TxHash;
+
+/// Action taken on the transaction sending funds to the screened address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ScreeningAction {
+    /// The transaction is not accepted to the mempool.
+    Reject,
+    /// The transaction is processed as usual and recorded to the audit trail.
+    Flag,
+    /// The transaction is held in the mempool for the configured period,
+    /// giving the operator time to review it.
+    Delay,
+}
+
+impl ScreeningAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Reject => "reject",
+            Self::Flag => "flag",
+            Self::Delay => "delay",
+        }
+    }
+}
+
+impl fmt::Display for ScreeningAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ScreeningAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "reject" => Ok(Self::Reject),
+            "flag" => Ok(Self::Flag),
+            "delay" => Ok(Self::Delay),
+            other => Err(format!("Unknown screening action: {}", other)),
+        }
+    }
+}
+
+/// Screened transaction or priority operation recorded to the audit trail.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreeningRecord {
+    /// Screened recipient.
+    pub address: Address,
+    /// Hash of the screened transaction, set for the L2 transactions.
+    pub tx_hash: Option<TxHash>,
+    /// Serial ID of the screened priority operation, set for the deposits.
+    pub priority_op_serial_id: Option<u64>,
+    pub action: ScreeningAction,
+    /// Why the recipient was screened, e.g. the list it was found in.
+    pub reason: String,
+    /// Time the delayed transaction is released for execution at.
+    pub release_at: Option<DateTime<Utc>>,
+}
+
+/// Entry of the audit trail.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreeningAuditEntry {
+    pub id: i64,
+    #[serde(flatten)]
+    pub record: ScreeningRecord,
+    pub created_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn screening_action_string_roundtrip() {
+        for action in &[
+            ScreeningAction::Reject,
+            ScreeningAction::Flag,
+            ScreeningAction::Delay,
+        ] {
+            assert_eq!(action.to_string().parse::<ScreeningAction>(), Ok(*action));
+        }
+        assert!("block".parse::<ScreeningAction>().is_err());
+    }
+}
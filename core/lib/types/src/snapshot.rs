@@ -0,0 +1,115 @@
+//! Snapshots of the state used to bootstrap the new server instances.
+//!
+//! A server serves the snapshot of its last verified state: the metadata describing the block
+//! the snapshot is made at, and the accounts split into the chunks of a fixed size. A new
+//! replica downloads the chunks from the peer, rebuilds the account tree and checks its root
+//! against the snapshot block, which in turn is checked against the commitment on L1. Thus the
+//! replica doesn't have to trust the peer and doesn't have to replay the history.
+
+use chrono::{DateTime, Utc};
+use num::{BigUint, Zero};
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{AccountId, BlockNumber, H256};
+
+use crate::{
+    account::{AccountUpdate, PubKeyHash},
+    block::Block,
+    Account, AccountUpdates, Token,
+};
+
+/// Number of the accounts in a single snapshot chunk.
+pub const SNAPSHOT_CHUNK_SIZE: usize = 10_000;
+
+/// Description of the snapshot made at the verified block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotMetadata {
+    /// The last verified block, the snapshot contains the state after this block.
+    pub block: Block,
+    /// Hash of the L1 transaction committing the block.
+    pub commit_tx_hash: Option<H256>,
+    pub tokens: Vec<Token>,
+    pub accounts_count: usize,
+    pub chunk_size: usize,
+    pub chunks_count: usize,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SnapshotMetadata {
+    pub fn block_number(&self) -> BlockNumber {
+        self.block.block_number
+    }
+}
+
+/// Accounts of the snapshot ordered by their IDs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotChunk {
+    pub block_number: BlockNumber,
+    pub index: usize,
+    pub accounts: Vec<(AccountId, Account)>,
+}
+
+/// Returns the updates creating the account with the same state from scratch.
+pub fn account_creation_updates(id: AccountId, account: &Account) -> AccountUpdates {
+    let mut updates = vec![(
+        id,
+        AccountUpdate::Create {
+            address: account.address,
+            nonce: account.nonce,
+        },
+    )];
+    if account.pub_key_hash != PubKeyHash::default() {
+        updates.push((
+            id,
+            AccountUpdate::ChangePubKeyHash {
+                old_pub_key_hash: PubKeyHash::default(),
+                new_pub_key_hash: account.pub_key_hash,
+                old_nonce: account.nonce,
+                new_nonce: account.nonce,
+            },
+        ));
+    }
+
+    let mut balances: Vec<_> = account.get_nonzero_balances().into_iter().collect();
+    balances.sort_by_key(|(token, _)| *token);
+    updates.extend(balances.into_iter().map(|(token, balance)| {
+        (
+            id,
+            AccountUpdate::UpdateBalance {
+                old_nonce: account.nonce,
+                new_nonce: account.nonce,
+                balance_update: (token, BigUint::zero(), balance.0),
+            },
+        )
+    }));
+    updates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Address, Nonce, TokenId};
+
+    #[test]
+    fn creation_updates_restore_account() {
+        let mut account = Account::default_with_address(&Address::repeat_byte(0x11));
+        account.nonce = Nonce(5);
+        account.pub_key_hash =
+            PubKeyHash::from_hex("sync:0102030405060708090a0b0c0d0e0f1011121314").unwrap();
+        account.set_balance(TokenId(0), BigUint::from(100u32));
+        account.set_balance(TokenId(3), BigUint::from(7u32));
+        account.set_balance(TokenId(4), BigUint::zero());
+
+        let updates = account_creation_updates(AccountId(1), &account);
+        let updates: Vec<_> = updates.into_iter().map(|(_, update)| update).collect();
+        assert_eq!(Account::apply_updates(None, &updates), Some(account));
+
+        // The account without the public key and balances is created by a single update.
+        let empty_account = Account::default_with_address(&Address::repeat_byte(0x22));
+        assert_eq!(
+            account_creation_updates(AccountId(2), &empty_account).len(),
+            1
+        );
+    }
+}
@@ -3,7 +3,7 @@ use zksync_crypto::ff::Field;
 use zksync_crypto::Fr;
 
 use super::utils::*;
-use crate::block::Block;
+use crate::{block::Block, priority_ops::SerialIdContinuityError, ExecutedOperations};
 
 /// Checks that we cannot create a block with invalid block sizes provided.
 #[test]
@@ -166,3 +166,57 @@ fn test_get_withdrawals_data() {
     // No more corresponding operations left.
     assert!(block.get_withdrawals_data().is_empty());
 }
+
+#[test]
+fn test_priority_ops_continuity() {
+    let full_exit = |serial_id| {
+        let mut operation = create_full_exit_op();
+        if let ExecutedOperations::PriorityOp(op) = &mut operation {
+            op.priority_op.serial_id = serial_id;
+        }
+        operation
+    };
+    let block = |operations, processed_priority_ops| {
+        Block::new(
+            BlockNumber(1),
+            Fr::one(),
+            AccountId(0),
+            operations,
+            processed_priority_ops,
+            100,
+            1_000_000.into(),
+            1_500_000.into(),
+            H256::default(),
+            0,
+        )
+    };
+
+    let operations = vec![full_exit(3), create_withdraw_tx(), full_exit(4)];
+    assert_eq!(
+        block(operations.clone(), (3, 5)).check_priority_ops_continuity(),
+        Ok(())
+    );
+    assert_eq!(
+        block(vec![create_withdraw_tx()], (3, 3)).check_priority_ops_continuity(),
+        Ok(())
+    );
+    // The range must match the operations included into the block.
+    assert_eq!(
+        block(operations.clone(), (2, 4)).check_priority_ops_continuity(),
+        Err(SerialIdContinuityError::Gap {
+            expected: 2,
+            found: 3
+        })
+    );
+    assert_eq!(
+        block(operations, (3, 6)).check_priority_ops_continuity(),
+        Err(SerialIdContinuityError::Gap {
+            expected: 5,
+            found: 6
+        })
+    );
+    assert_eq!(
+        block(vec![full_exit(3), full_exit(3)], (3, 4)).check_priority_ops_continuity(),
+        Err(SerialIdContinuityError::Duplicate(3))
+    );
+}
@@ -0,0 +1,114 @@
+use zksync_basic_types::{AccountId, BlockNumber, H256};
+use zksync_crypto::ff::Field;
+use zksync_crypto::params::CHUNK_BYTES;
+use zksync_crypto::Fr;
+
+use super::utils::*;
+use crate::block::Block;
+use crate::block_commitment::*;
+use crate::pubdata_compression::compress_pubdata;
+
+pub(super) fn block() -> Block {
+    Block::new_from_available_block_sizes(
+        BlockNumber(5),
+        Fr::one(),
+        AccountId(3),
+        vec![
+            create_change_pubkey_tx(),
+            create_full_exit_op(),
+            create_withdraw_tx(),
+        ],
+        (10, 11),
+        &[20, 50],
+        1_000_000.into(),
+        1_500_000.into(),
+        H256::repeat_byte(0x11),
+        1_600_000_000,
+    )
+}
+
+fn input(block: &Block) -> CommitmentInput {
+    CommitmentInput {
+        block_number: block.block_number,
+        fee_account: block.fee_account,
+        previous_state_hash: H256::repeat_byte(0x11),
+        state_hash: block.get_eth_encoded_root(),
+        timestamp: block.timestamp,
+        public_data: block.get_eth_public_data(),
+    }
+}
+
+/// Checks that the values stored by the contract are re-derived from the public data,
+/// both uncompressed and compressed.
+#[test]
+fn commitment_from_public_data() {
+    let block = block();
+    let expected = StoredBlockInfo::from(&block);
+    assert_eq!(expected.priority_operations, 1);
+
+    let mut input = input(&block);
+    assert_eq!(compute_block_commitment(&input), Ok(expected.clone()));
+    input.public_data = compress_pubdata(&input.public_data);
+    assert_eq!(compute_block_commitment(&input), Ok(expected.clone()));
+
+    // The contract keeps the hash of the ABI-encoded structure.
+    let decoded = StoredBlockInfo::from_token(&expected.to_token()).unwrap();
+    assert_eq!(decoded.hash(), expected.hash());
+    assert_ne!(expected.hash(), H256::zero());
+}
+
+/// Checks that the invalid public data is reported.
+#[test]
+fn invalid_public_data() {
+    let block = block();
+    let mut input = input(&block);
+    let public_data_len = block.block_chunks_size * CHUNK_BYTES;
+
+    input.public_data.pop();
+    assert_eq!(
+        compute_block_commitment(&input),
+        Err(CommitmentError::InvalidPublicDataLength(
+            public_data_len - 1
+        ))
+    );
+
+    // The last chunk of the block is a noop padding.
+    input.public_data.push(0);
+    let offset = public_data_len - CHUNK_BYTES;
+    input.public_data[offset] = 0xfe;
+    assert_eq!(
+        compute_block_commitment(&input),
+        Err(CommitmentError::UnknownOperationType {
+            offset,
+            op_type: 0xfe
+        })
+    );
+}
+
+/// Checks that the inputs of the blocks are decoded from the `commitBlocks` arguments.
+#[test]
+fn decode_commit_blocks_arguments() {
+    let previous = block();
+    let block = block();
+    let tokens = vec![
+        StoredBlockInfo::from(&previous).to_token(),
+        ethabi::Token::Array(vec![ethabi::Token::Tuple(vec![
+            ethabi::Token::FixedBytes(block.get_eth_encoded_root().as_bytes().to_vec()),
+            ethabi::Token::Bytes(block.get_eth_public_data()),
+            ethabi::Token::Uint(block.timestamp.into()),
+            ethabi::Token::Array(vec![]),
+            ethabi::Token::Uint((*block.block_number).into()),
+            ethabi::Token::Uint((*block.fee_account).into()),
+        ])]),
+    ];
+
+    let inputs = decode_commit_blocks(&tokens).unwrap();
+    assert_eq!(
+        inputs,
+        vec![CommitmentInput {
+            previous_state_hash: previous.get_eth_encoded_root(),
+            ..input(&block)
+        }]
+    );
+    assert!(decode_commit_blocks(&tokens[1..]).is_err());
+}
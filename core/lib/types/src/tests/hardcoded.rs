@@ -226,6 +226,99 @@ pub mod operations_test {
         );
     }
 
+    #[test]
+    fn test_decode_errors_context() {
+        let mut transfer = hex::decode(TRANSFER_PUBLIC_DATA).unwrap();
+        transfer.pop();
+        assert_eq!(
+            TransferOp::from_public_data(&transfer)
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "Wrong bytes length for transfer pubdata (operation type 0x05, offset 0, expected {} bytes, got {})",
+                transfer.len() + 1,
+                transfer.len()
+            )
+        );
+
+        assert_eq!(
+            crate::ZkSyncOp::from_public_data(&[0xff, 0x00])
+                .unwrap_err()
+                .to_string(),
+            "Unknown operation type 0xff"
+        );
+    }
+
+    #[test]
+    fn test_amount_bounds() {
+        use crate::amount_bounds::{AmountBounds, TokenAmountBounds};
+        use crate::operations::error::{PublicDataDecodeError, TransferOpError};
+
+        let op =
+            crate::ZkSyncOp::from_public_data(&hex::decode(TRANSFER_PUBLIC_DATA).unwrap()).unwrap();
+        let (token, amount, fee) = match &op {
+            crate::ZkSyncOp::Transfer(op) => (op.tx.token, op.tx.amount.clone(), op.tx.fee.clone()),
+            _ => panic!("Transfer operation expected"),
+        };
+        let bounds = |max_amount: &BigUint, max_fee: &BigUint| {
+            AmountBounds::new(
+                vec![(
+                    token,
+                    TokenAmountBounds {
+                        max_amount: max_amount.clone(),
+                        max_fee: max_fee.clone(),
+                    },
+                )]
+                .into_iter()
+                .collect(),
+            )
+        };
+
+        assert!(op.check_amount_bounds(&AmountBounds::default()).is_ok());
+        assert!(op.check_amount_bounds(&bounds(&amount, &fee)).is_ok());
+        assert_eq!(
+            op.check_amount_bounds(&bounds(&(&amount - 1u32), &fee)),
+            Err(PublicDataDecodeError::TransferOpError(
+                TransferOpError::AmountOutOfBounds {
+                    token,
+                    amount: amount.clone(),
+                }
+            ))
+        );
+        assert_eq!(
+            op.check_amount_bounds(&bounds(&amount, &(&fee - 1u32))),
+            Err(PublicDataDecodeError::TransferOpError(
+                TransferOpError::FeeOutOfBounds { token, fee }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_amount_bounds_skip_priority_ops() {
+        use crate::amount_bounds::{AmountBounds, TokenAmountBounds};
+
+        let op =
+            crate::ZkSyncOp::from_public_data(&hex::decode(DEPOSIT_PUBLIC_DATA).unwrap()).unwrap();
+        let token = match &op {
+            crate::ZkSyncOp::Deposit(op) => op.priority_op.token,
+            _ => panic!("Deposit operation expected"),
+        };
+        let bounds = AmountBounds::new(
+            vec![(
+                token,
+                TokenAmountBounds {
+                    max_amount: BigUint::from(0u32),
+                    max_fee: BigUint::from(0u32),
+                },
+            )]
+            .into_iter()
+            .collect(),
+        );
+
+        // Deposits are requested on L1, so they are applied whatever the bounds are.
+        assert!(op.check_amount_bounds(&bounds).is_ok());
+    }
+
     #[test]
     fn test_eth_witness() {
         // TODO: Change pre-defined input / output after merging breaking to dev (ZKS-131).
@@ -1,3 +1,7 @@
 mod block;
+mod block_commitment;
 mod hardcoded;
+mod priority_op_cost;
+mod proof_archive;
+mod pubdata_compression;
 pub mod utils;
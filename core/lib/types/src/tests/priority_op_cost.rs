@@ -0,0 +1,78 @@
+use num::BigUint;
+use zksync_basic_types::{AccountId, BlockNumber, H256};
+use zksync_crypto::ff::Field;
+use zksync_crypto::params::CHUNK_BYTES;
+use zksync_crypto::Fr;
+
+use super::utils::*;
+use crate::aggregated_operations::AggregatedActionType;
+use crate::block::Block;
+use crate::gas_counter::{CommitCost, VerifyCost};
+use crate::operations::{FullExitOp, WithdrawOp};
+use crate::priority_op_cost::*;
+
+fn calldata_gas(chunks: usize) -> u64 {
+    (chunks * CHUNK_BYTES) as u64 * CALLDATA_BYTE_GAS
+}
+
+/// Checks that the gas used by the block transactions is attributed to the priority operations
+/// proportionally to their estimated costs.
+#[test]
+fn priority_op_gas_attribution() {
+    let block = Block::new(
+        BlockNumber(5),
+        Fr::one(),
+        AccountId(0),
+        vec![create_full_exit_op(), create_withdraw_tx()],
+        (0, 1),
+        100,
+        1_000_000.into(),
+        1_500_000.into(),
+        H256::default(),
+        0,
+    );
+    let blocks = vec![block];
+    let gas_price = BigUint::from(10u32);
+
+    let full_exit_commit_cost = CommitCost::FULL_EXIT_COST + calldata_gas(FullExitOp::CHUNKS);
+    let expected_commit_cost = CommitCost::BASE_COST
+        + full_exit_commit_cost
+        + CommitCost::WITHDRAW_COST
+        + calldata_gas(WithdrawOp::CHUNKS);
+    // The transaction used twice as much gas as estimated.
+    let costs = attribute_priority_op_gas(
+        &blocks,
+        AggregatedActionType::CommitBlocks,
+        2 * expected_commit_cost,
+        &gas_price,
+    );
+    assert_eq!(
+        costs,
+        vec![PriorityOpGas {
+            serial_id: 0,
+            block_number: BlockNumber(5),
+            op_type: "FullExit",
+            gas: 2 * full_exit_commit_cost,
+            fee: BigUint::from(20 * full_exit_commit_cost),
+        }]
+    );
+
+    let expected_execute_cost =
+        VerifyCost::BASE_COST + VerifyCost::FULL_EXIT_COST + VerifyCost::WITHDRAW_COST;
+    let costs = attribute_priority_op_gas(
+        &blocks,
+        AggregatedActionType::ExecuteBlocks,
+        expected_execute_cost,
+        &gas_price,
+    );
+    assert_eq!(costs[0].gas, VerifyCost::FULL_EXIT_COST);
+
+    // Proofs are not attributed to the operations.
+    assert!(attribute_priority_op_gas(
+        &blocks,
+        AggregatedActionType::PublishProofBlocksOnchain,
+        1_000_000,
+        &gas_price
+    )
+    .is_empty());
+}
@@ -0,0 +1,96 @@
+use zksync_basic_types::{BlockNumber, H256, U256};
+use zksync_crypto::{convert::FeConvert, proof::SingleProof, Fr};
+
+use super::block_commitment::block;
+use crate::proof_archive::*;
+
+fn archive() -> ProofArchive {
+    let block = block();
+    let public_input = commitment_public_input(block.block_commitment);
+    let mut proof = SingleProof::default();
+    proof.0.input_values = vec![Fr::from_hex(&format!("{:x}", public_input)).unwrap()];
+    let verification_key =
+        ArchivedVerificationKey::new("keys/test".to_owned(), vec![0x01, 0x02, 0x03]);
+    ProofArchive::new(
+        &block,
+        H256::repeat_byte(0x11),
+        proof,
+        verification_key,
+        None,
+    )
+}
+
+/// Checks that the archive is decoded only if its format is known.
+#[test]
+fn archive_format() {
+    let archive = archive();
+    let json = archive.to_json();
+    let decoded = ProofArchive::from_json(&json).unwrap();
+    assert_eq!(decoded.header, archive.header);
+    assert_eq!(decoded.stored_block_info, archive.stored_block_info);
+    assert_eq!(decoded.check(), Ok(()));
+
+    let mut value: serde_json::Value = serde_json::from_slice(&json).unwrap();
+    value["version"] = (PROOF_ARCHIVE_VERSION + 1).into();
+    assert_eq!(
+        ProofArchive::from_json(&serde_json::to_vec(&value).unwrap()).unwrap_err(),
+        ProofArchiveError::UnsupportedFormat {
+            format: PROOF_ARCHIVE_FORMAT.to_owned(),
+            version: PROOF_ARCHIVE_VERSION + 1,
+        }
+    );
+    assert!(matches!(
+        ProofArchive::from_json(b"{}"),
+        Err(ProofArchiveError::Malformed(_))
+    ));
+}
+
+/// Checks that the parts of the archive are checked against each other.
+#[test]
+fn archive_consistency() {
+    let archive = archive();
+    assert_eq!(archive.check(), Ok(()));
+
+    let mut tampered = archive.clone();
+    tampered.commitment_input.timestamp += 1;
+    assert_eq!(
+        tampered.check(),
+        Err(ProofArchiveError::StoredBlockInfoMismatch)
+    );
+
+    let mut tampered = archive.clone();
+    tampered.public_input = U256::one();
+    assert_eq!(
+        tampered.check(),
+        Err(ProofArchiveError::PublicInputMismatch)
+    );
+
+    let mut tampered = archive.clone();
+    tampered.proof = SingleProof::default();
+    assert_eq!(tampered.check(), Err(ProofArchiveError::ProofInputMismatch));
+
+    let mut tampered = archive.clone();
+    tampered.verification_key.key[0] ^= 0xff;
+    assert_eq!(
+        tampered.check(),
+        Err(ProofArchiveError::VerificationKeyHashMismatch)
+    );
+
+    // The aggregated proof must contain the commitment at the position of the block.
+    let mut aggregated = ArchivedAggregatedProof {
+        first_block: BlockNumber(4),
+        last_block: BlockNumber(5),
+        proof: Default::default(),
+    };
+    aggregated.proof.individual_vk_inputs = vec![U256::zero(), archive.public_input];
+    let mut with_aggregated = archive.clone();
+    with_aggregated.aggregated_proof = Some(aggregated.clone());
+    assert_eq!(with_aggregated.check(), Ok(()));
+
+    aggregated.first_block = BlockNumber(5);
+    with_aggregated.aggregated_proof = Some(aggregated);
+    assert_eq!(
+        with_aggregated.check(),
+        Err(ProofArchiveError::AggregatedProofMismatch)
+    );
+}
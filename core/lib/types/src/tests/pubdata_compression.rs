@@ -0,0 +1,84 @@
+use zksync_basic_types::{AccountId, BlockNumber, H256};
+use zksync_crypto::ff::Field;
+use zksync_crypto::params::CHUNK_BYTES;
+use zksync_crypto::Fr;
+
+use super::utils::*;
+use crate::block::Block;
+use crate::operations::ZkSyncOp;
+use crate::pubdata_compression::*;
+
+/// Creates the public data of the operation of the given type filled with the arbitrary bytes.
+fn arbitrary_op(op_type: u8, seed: u8) -> Vec<u8> {
+    let len = ZkSyncOp::public_data_length(op_type).unwrap() * CHUNK_BYTES;
+    let mut op = vec![op_type];
+    op.extend((1..len).map(|i| (i as u8).wrapping_mul(31).wrapping_add(seed)));
+    op
+}
+
+/// Checks that the public data of all the operation types survives the round trip.
+#[test]
+fn compression_round_trip() {
+    let mut pubdata = Vec::new();
+    for seed in 0..3 {
+        for op_type in 0x00..=0x08 {
+            pubdata.extend(arbitrary_op(op_type, seed * 97));
+        }
+    }
+
+    let compressed = compress_pubdata(&pubdata);
+    assert!(is_compressed_pubdata(&compressed));
+    assert_eq!(decompress_pubdata(&compressed), Ok(pubdata));
+}
+
+/// Checks that the block public data is compressed and the uncompressed one is passed as is.
+#[test]
+fn block_pubdata_compression() {
+    let block = Block::new(
+        BlockNumber(1),
+        Fr::one(),
+        AccountId(0),
+        vec![
+            create_change_pubkey_tx(),
+            create_full_exit_op(),
+            create_withdraw_tx(),
+        ],
+        (0, 0),
+        100,
+        1_000_000.into(),
+        1_500_000.into(),
+        H256::default(),
+        0,
+    );
+    let pubdata = block.get_eth_public_data();
+
+    let compressed = compress_pubdata(&pubdata);
+    assert!(compressed.len() < pubdata.len() / 10);
+    assert_eq!(decompress_pubdata(&compressed), Ok(pubdata.clone()));
+
+    assert!(!is_compressed_pubdata(&pubdata));
+    assert_eq!(decompress_pubdata(&pubdata), Ok(pubdata));
+}
+
+/// Checks that the malformed compressed public data is rejected.
+#[test]
+fn malformed_pubdata() {
+    let pubdata = arbitrary_op(0x05, 0);
+    let compressed = compress_pubdata(&pubdata);
+
+    let mut unsupported = compressed.clone();
+    unsupported[1] = COMPRESSED_PUBDATA_FORMAT_VERSION + 1;
+    assert_eq!(
+        decompress_pubdata(&unsupported),
+        Err(PubdataDecompressionError::UnsupportedFormat(
+            COMPRESSED_PUBDATA_FORMAT_VERSION + 1
+        ))
+    );
+
+    assert!(decompress_pubdata(&compressed[..4]).is_err());
+    assert!(decompress_pubdata(&compressed[..compressed.len() - 2]).is_err());
+
+    let mut wrong_length = compressed;
+    wrong_length[5] += 1;
+    assert!(decompress_pubdata(&wrong_length).is_err());
+}
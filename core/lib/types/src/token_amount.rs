@@ -0,0 +1,324 @@
+//! Conversions of the token amounts between the smallest token units and the decimal strings.
+//!
+//! Amounts are stored and signed in the smallest units of the token (e.g. wei), while the users
+//! see and type them in the whole tokens, so the conversion depends on the decimals of the token.
+//! The conversions here are exact: the amounts are never passed through the floating point types,
+//! and rounding happens only where it's explicitly requested.
+//!
+//! The strings are locale-independent: only the ASCII digits and the `.` decimal separator are
+//! used, there are no signs, exponents, whitespace or digit grouping. The parsers reject anything
+//! else, so `1,5` is an error rather than `15` or `1.5` depending on who reads it.
+
+// External uses
+use num::{rational::Ratio, BigUint, Integer, One, Zero};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Rounding applied when the amount has more fractional digits than requested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rounding {
+    /// Towards zero, the extra digits are dropped.
+    Down,
+    /// Away from zero, any nonzero extra digit adds a unit.
+    Up,
+    /// To the nearest value, the halves are rounded up.
+    HalfUp,
+    /// To the nearest value, the halves are rounded to the even digit (banker's rounding).
+    HalfEven,
+}
+
+#[derive(Debug, Clone, Error, PartialEq)]
+pub enum AmountParseError {
+    #[error("Amount is empty")]
+    Empty,
+    #[error("Unexpected character '{0}' in the amount, only digits and '.' are allowed")]
+    InvalidCharacter(char),
+    #[error("Malformed amount '{0}'")]
+    Malformed(String),
+    #[error("Amount has more than {0} fractional digits")]
+    TooManyDecimals(u8),
+}
+
+/// Returns the amount of the smallest units in one token, i.e. `10^decimals`.
+pub fn token_unit(decimals: u8) -> BigUint {
+    pow10(u32::from(decimals))
+}
+
+/// Converts the amount in the smallest units into the exact amount of the tokens.
+pub fn to_token_units(amount: &BigUint, decimals: u8) -> Ratio<BigUint> {
+    Ratio::new(amount.clone(), token_unit(decimals))
+}
+
+/// Formats the amount in the smallest units as the exact decimal amount of the tokens,
+/// without the trailing zeros: `1500000` with 6 decimals is `1.5`, `1000000` is `1`.
+///
+/// Unlike `zksync_utils::format_units`, the whole amounts have no `.0` suffix. The latter
+/// follows `ethers` and must stay as is, since its output is a part of the signed messages.
+pub fn format_token_amount(amount: &BigUint, decimals: u8) -> String {
+    let (integer, fraction) = amount.div_rem(&token_unit(decimals));
+    if fraction.is_zero() {
+        return integer.to_string();
+    }
+
+    let fraction = format!(
+        "{:0>width$}",
+        fraction.to_str_radix(10),
+        width = usize::from(decimals)
+    );
+    format!("{}.{}", integer, fraction.trim_end_matches('0'))
+}
+
+/// Formats the amount in the smallest units as the decimal amount of the tokens with exactly
+/// `precision` fractional digits, rounding the rest: `1234567` with 6 decimals and precision 2
+/// is `1.23`, while `1000000` is `1.00`.
+pub fn format_token_amount_rounded(
+    amount: &BigUint,
+    decimals: u8,
+    precision: u8,
+    rounding: Rounding,
+) -> String {
+    let amount = if precision >= decimals {
+        amount * pow10(u32::from(precision - decimals))
+    } else {
+        div_rounded(amount, &pow10(u32::from(decimals - precision)), rounding)
+    };
+    if precision == 0 {
+        return amount.to_string();
+    }
+
+    let (integer, fraction) = amount.div_rem(&token_unit(precision));
+    format!(
+        "{}.{:0>width$}",
+        integer,
+        fraction.to_str_radix(10),
+        width = usize::from(precision)
+    )
+}
+
+/// Parses the decimal amount of the tokens into the smallest units. The amount must be
+/// representable exactly: `1.5` with 6 decimals is `1500000`, while `0.0000001` is an error.
+/// Extra fractional zeros are accepted.
+pub fn parse_token_amount(value: &str, decimals: u8) -> Result<BigUint, AmountParseError> {
+    let amount = DecimalAmount::parse(value)?;
+    let extra_digits = amount.fraction_digits.saturating_sub(u32::from(decimals));
+    if extra_digits > 0 && !amount.digits.is_multiple_of(&pow10(extra_digits)) {
+        return Err(AmountParseError::TooManyDecimals(decimals));
+    }
+    Ok(amount.to_units(decimals, Rounding::Down))
+}
+
+/// Parses the decimal amount of the tokens into the smallest units, rounding the digits
+/// beyond the token decimals.
+pub fn parse_token_amount_rounded(
+    value: &str,
+    decimals: u8,
+    rounding: Rounding,
+) -> Result<BigUint, AmountParseError> {
+    Ok(DecimalAmount::parse(value)?.to_units(decimals, rounding))
+}
+
+fn pow10(exponent: u32) -> BigUint {
+    BigUint::from(10u32).pow(exponent)
+}
+
+fn div_rounded(value: &BigUint, divisor: &BigUint, rounding: Rounding) -> BigUint {
+    let (quotient, remainder) = value.div_rem(divisor);
+    if remainder.is_zero() {
+        return quotient;
+    }
+
+    let round_up = match rounding {
+        Rounding::Down => false,
+        Rounding::Up => true,
+        Rounding::HalfUp => &remainder * 2u32 >= *divisor,
+        Rounding::HalfEven => {
+            let doubled = &remainder * 2u32;
+            doubled > *divisor || (doubled == *divisor && quotient.is_odd())
+        }
+    };
+    if round_up {
+        quotient + BigUint::one()
+    } else {
+        quotient
+    }
+}
+
+/// Decimal amount as the integer of all its digits and the position of the separator.
+struct DecimalAmount {
+    digits: BigUint,
+    fraction_digits: u32,
+}
+
+impl DecimalAmount {
+    fn parse(value: &str) -> Result<Self, AmountParseError> {
+        if value.is_empty() {
+            return Err(AmountParseError::Empty);
+        }
+        if let Some(ch) = value.chars().find(|ch| !ch.is_ascii_digit() && *ch != '.') {
+            return Err(AmountParseError::InvalidCharacter(ch));
+        }
+
+        let mut parts = value.splitn(2, '.');
+        let integer = parts.next().unwrap_or_default();
+        let fraction = parts.next().unwrap_or_default();
+        // Both parts must have digits if the separator is present, and there must be
+        // no more separators.
+        let malformed = integer.is_empty()
+            || (value.contains('.') && fraction.is_empty())
+            || fraction.contains('.');
+        if malformed {
+            return Err(AmountParseError::Malformed(value.to_string()));
+        }
+
+        let digits = format!("{}{}", integer, fraction)
+            .parse()
+            .map_err(|_| AmountParseError::Malformed(value.to_string()))?;
+        Ok(Self {
+            digits,
+            fraction_digits: fraction.len() as u32,
+        })
+    }
+
+    fn to_units(&self, decimals: u8, rounding: Rounding) -> BigUint {
+        let decimals = u32::from(decimals);
+        if self.fraction_digits <= decimals {
+            &self.digits * pow10(decimals - self.fraction_digits)
+        } else {
+            div_rounded(
+                &self.digits,
+                &pow10(self.fraction_digits - decimals),
+                rounding,
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn units(value: u64) -> BigUint {
+        BigUint::from(value)
+    }
+
+    #[test]
+    fn format_amount() {
+        assert_eq!(format_token_amount(&units(0), 18), "0");
+        assert_eq!(format_token_amount(&units(1_500_000), 6), "1.5");
+        assert_eq!(format_token_amount(&units(1_000_000), 6), "1");
+        assert_eq!(format_token_amount(&units(1), 6), "0.000001");
+        assert_eq!(format_token_amount(&units(123_000_456), 6), "123.000456");
+        assert_eq!(format_token_amount(&units(42), 0), "42");
+        assert_eq!(
+            format_token_amount(&"123456789012345678901234567890".parse().unwrap(), 18),
+            "123456789012.34567890123456789"
+        );
+    }
+
+    #[test]
+    fn format_amount_rounded() {
+        let cases = [
+            // (amount, decimals, precision, rounding, expected)
+            (1_234_567, 6, 2, Rounding::Down, "1.23"),
+            (1_234_567, 6, 2, Rounding::Up, "1.24"),
+            (1_230_000, 6, 2, Rounding::Up, "1.23"),
+            (1_235_000, 6, 2, Rounding::HalfUp, "1.24"),
+            (1_234_999, 6, 2, Rounding::HalfUp, "1.23"),
+            // Halves go to the even digit, anything above the half goes up.
+            (1_235_000, 6, 2, Rounding::HalfEven, "1.24"),
+            (1_245_000, 6, 2, Rounding::HalfEven, "1.24"),
+            (1_245_001, 6, 2, Rounding::HalfEven, "1.25"),
+            (2_500_000, 6, 0, Rounding::HalfEven, "2"),
+            (3_500_000, 6, 0, Rounding::HalfEven, "4"),
+            (999_999, 6, 2, Rounding::HalfUp, "1.00"),
+            (1_000_000, 6, 2, Rounding::Down, "1.00"),
+            (15, 1, 3, Rounding::Down, "1.500"),
+            (0, 18, 4, Rounding::Up, "0.0000"),
+        ];
+        for &(amount, decimals, precision, rounding, expected) in &cases {
+            assert_eq!(
+                format_token_amount_rounded(&units(amount), decimals, precision, rounding),
+                expected,
+                "{} with {} decimals, precision {}, {:?}",
+                amount,
+                decimals,
+                precision,
+                rounding
+            );
+        }
+    }
+
+    #[test]
+    fn parse_amount() {
+        assert_eq!(parse_token_amount("1.5", 6), Ok(units(1_500_000)));
+        assert_eq!(parse_token_amount("1", 6), Ok(units(1_000_000)));
+        assert_eq!(parse_token_amount("0.000001", 6), Ok(units(1)));
+        assert_eq!(parse_token_amount("007.10", 2), Ok(units(710)));
+        assert_eq!(parse_token_amount("1.500000000", 6), Ok(units(1_500_000)));
+        assert_eq!(
+            parse_token_amount("123456789012.34567890123456789", 18),
+            Ok("123456789012345678901234567890".parse().unwrap())
+        );
+
+        assert_eq!(
+            parse_token_amount("0.0000001", 6),
+            Err(AmountParseError::TooManyDecimals(6))
+        );
+        assert_eq!(parse_token_amount("", 6), Err(AmountParseError::Empty));
+        for (value, ch) in &[("1,5", ','), ("-1", '-'), (" 1", ' '), ("1e6", 'e')] {
+            assert_eq!(
+                parse_token_amount(value, 6),
+                Err(AmountParseError::InvalidCharacter(*ch))
+            );
+        }
+        for value in &[".5", "1.", ".", "1.2.3"] {
+            assert_eq!(
+                parse_token_amount(value, 6),
+                Err(AmountParseError::Malformed(value.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn parse_amount_rounded() {
+        assert_eq!(
+            parse_token_amount_rounded("1.2345", 2, Rounding::Down),
+            Ok(units(123))
+        );
+        assert_eq!(
+            parse_token_amount_rounded("1.2301", 2, Rounding::Up),
+            Ok(units(124))
+        );
+        assert_eq!(
+            parse_token_amount_rounded("1.225", 2, Rounding::HalfUp),
+            Ok(units(123))
+        );
+        assert_eq!(
+            parse_token_amount_rounded("1.225", 2, Rounding::HalfEven),
+            Ok(units(122))
+        );
+        assert_eq!(
+            parse_token_amount_rounded("1.235", 2, Rounding::HalfEven),
+            Ok(units(124))
+        );
+        assert_eq!(
+            parse_token_amount_rounded("1.5", 6, Rounding::Up),
+            Ok(units(1_500_000))
+        );
+    }
+
+    #[test]
+    fn roundtrip() {
+        for &amount in &[0, 1, 10, 1_000_001, 123_456_789, u64::max_value()] {
+            for &decimals in &[0, 1, 6, 18] {
+                let formatted = format_token_amount(&units(amount), decimals);
+                assert_eq!(parse_token_amount(&formatted, decimals), Ok(units(amount)));
+            }
+        }
+        assert_eq!(
+            to_token_units(&units(1_500_000), 6),
+            Ratio::new(units(3), units(2))
+        );
+    }
+}
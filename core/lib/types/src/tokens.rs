@@ -1,4 +1,8 @@
-use crate::{tx::ChangePubKeyType, Address, TokenId};
+use crate::{
+    token_amount::{format_token_amount, parse_token_amount, AmountParseError},
+    tx::ChangePubKeyType,
+    Address, TokenId,
+};
 use chrono::{DateTime, Utc};
 use num::{rational::Ratio, BigUint};
 use serde::{Deserialize, Serialize};
@@ -118,6 +122,16 @@ impl Token {
             decimals,
         }
     }
+
+    /// Formats the amount in the smallest units of the token as the decimal amount of the tokens.
+    pub fn format_amount(&self, amount: &BigUint) -> String {
+        format_token_amount(amount, self.decimals)
+    }
+
+    /// Parses the decimal amount of the tokens into the smallest units of the token.
+    pub fn parse_amount(&self, value: &str) -> Result<BigUint, AmountParseError> {
+        parse_token_amount(value, self.decimals)
+    }
 }
 
 // Hidden as it relies on the filesystem structure, which can be different for reverse dependencies.
@@ -149,6 +163,65 @@ pub struct TokenMarketVolume {
     pub last_updated: DateTime<Utc>,
 }
 
+/// Operator-controlled settings of the token.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenFlags {
+    /// Disabled token can't be used to pay fees. Transfers and withdrawals of the token
+    /// are not affected, since users must always be able to withdraw their funds.
+    pub disabled: bool,
+    /// Token can be used to pay fees regardless of its market volume.
+    pub fee_eligible: bool,
+}
+
+/// Reason the token can't be used to pay fees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FeeTokenIneligibility {
+    /// The token is disabled by the operator.
+    OperatorDisabled,
+    /// The market volume of the token is below the required one.
+    InsufficientLiquidity,
+    /// The price of the token is not known to the price sources.
+    PriceUnavailable,
+    /// The price sources disagree on the price of the token.
+    UnreliablePrice,
+}
+
+/// Whether the token can be used to pay fees, as decided by the fee ticker.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenFeeStatus {
+    pub token_id: TokenId,
+    pub eligible: bool,
+    pub reason: Option<FeeTokenIneligibility>,
+    /// Time the token price used for the fees was updated at.
+    pub price_updated_at: Option<DateTime<Utc>>,
+    /// The price couldn't be refreshed, so the fees are calculated with the last known one.
+    pub price_stale: bool,
+}
+
+impl TokenFeeStatus {
+    pub fn eligible(token_id: TokenId, price: &TokenPrice, price_stale: bool) -> Self {
+        Self {
+            token_id,
+            eligible: true,
+            reason: None,
+            price_updated_at: Some(price.last_updated),
+            price_stale,
+        }
+    }
+
+    pub fn ineligible(token_id: TokenId, reason: FeeTokenIneligibility) -> Self {
+        Self {
+            token_id,
+            eligible: false,
+            reason: Some(reason),
+            price_updated_at: None,
+            price_stale: false,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Hash, Eq)]
 #[serde(untagged)]
 pub enum ChangePubKeyFeeTypeArg {
@@ -16,6 +16,7 @@ use zksync_utils::{format_units, BigUintSerdeAsRadix10Str};
 
 use super::{PackedEthSignature, TimeRange, TxSignature, VerifiedSignatureCache};
 use crate::{
+    guardians::ChangePubKeyGuardiansData,
     tokens::ChangePubKeyFeeTypeArg,
     tx::error::{ChangePubkeySignedDataError, TransactionSignatureError},
 };
@@ -25,6 +26,7 @@ pub enum ChangePubKeyType {
     Onchain,
     ECDSA,
     CREATE2,
+    Guardians,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,6 +68,8 @@ pub enum ChangePubKeyEthAuthData {
     Onchain,
     ECDSA(ChangePubKeyECDSAData),
     CREATE2(ChangePubKeyCREATE2Data),
+    /// Public key change authorized by the guardians of the account, see the `guardians` module.
+    Guardians(ChangePubKeyGuardiansData),
 }
 
 impl ChangePubKeyEthAuthData {
@@ -81,6 +85,10 @@ impl ChangePubKeyEthAuthData {
         matches!(self, ChangePubKeyEthAuthData::CREATE2(..))
     }
 
+    pub fn is_guardians(&self) -> bool {
+        matches!(self, ChangePubKeyEthAuthData::Guardians(..))
+    }
+
     pub fn get_eth_witness(&self) -> Vec<u8> {
         match self {
             ChangePubKeyEthAuthData::Onchain => Vec::new(),
@@ -101,6 +109,11 @@ impl ChangePubKeyEthAuthData {
                 bytes.extend_from_slice(code_hash.as_bytes());
                 bytes
             }
+            ChangePubKeyEthAuthData::Guardians(guardians_data) => {
+                let mut bytes = vec![0x03];
+                bytes.extend_from_slice(&guardians_data.encode());
+                bytes
+            }
         }
     }
 
@@ -109,10 +122,27 @@ impl ChangePubKeyEthAuthData {
             ChangePubKeyEthAuthData::Onchain => ChangePubKeyType::Onchain,
             ChangePubKeyEthAuthData::ECDSA(_) => ChangePubKeyType::ECDSA,
             ChangePubKeyEthAuthData::CREATE2(_) => ChangePubKeyType::CREATE2,
+            ChangePubKeyEthAuthData::Guardians(_) => ChangePubKeyType::Guardians,
         }
     }
 }
 
+/// Status of the onchain authorization of the `ChangePubKey` transaction, i.e. whether
+/// the `setAuthPubkeyHash` function of the zkSync contract was called by the account
+/// owner for the given nonce and public key hash.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePubKeyOnchainAuthStatus {
+    pub address: Address,
+    pub nonce: Nonce,
+    pub pub_key_hash: PubKeyHash,
+    /// Whether the `ChangePubKey` transaction with the `Onchain` authorization will be accepted.
+    /// Authorizations with a requested reset are not considered valid.
+    pub authorized: bool,
+    /// Number of the Ethereum block the status is known for.
+    pub eth_block: u64,
+}
+
 /// `ChangePubKey` transaction is used to set the owner's public key hash
 /// associated with the account.
 ///
@@ -346,6 +376,13 @@ impl ChangePubKey {
                     let create2_address = create2_data.get_address(&self.new_pk_hash);
                     create2_address == self.account
                 }
+                // The registered guardian set and the timelock are checked by the mempool.
+                ChangePubKeyEthAuthData::Guardians(guardians_data) => guardians_data.verify(
+                    self.account_id,
+                    self.account,
+                    &self.new_pk_hash,
+                    self.nonce,
+                ),
             }
         } else if let Some(old_eth_signature) = &self.eth_signature {
             let recovered_address = self
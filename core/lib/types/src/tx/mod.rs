@@ -18,7 +18,7 @@ pub use self::close::Close;
 pub use self::{
     change_pubkey::{
         ChangePubKey, ChangePubKeyCREATE2Data, ChangePubKeyECDSAData, ChangePubKeyEthAuthData,
-        ChangePubKeyType,
+        ChangePubKeyOnchainAuthStatus, ChangePubKeyType,
     },
     forced_exit::ForcedExit,
     transfer::Transfer,
@@ -28,10 +28,16 @@ pub use self::{
 
 // Re-export primitives associated with transactions.
 pub use self::primitives::{
-    eip1271_signature::EIP1271Signature, eth_batch_sign_data::EthBatchSignData,
-    eth_batch_signature::EthBatchSignatures, eth_signature::TxEthSignature,
-    packed_eth_signature::PackedEthSignature, packed_public_key::PackedPublicKey,
-    packed_signature::PackedSignature, signature::TxSignature, time_range::TimeRange,
+    eip1271_signature::EIP1271Signature,
+    eth_batch_sign_data::EthBatchSignData,
+    eth_batch_signature::EthBatchSignatures,
+    eth_signature::TxEthSignature,
+    packed_eth_signature::PackedEthSignature,
+    packed_public_key::PackedPublicKey,
+    packed_signature::PackedSignature,
+    signature::TxSignature,
+    signature_domain::{SignatureDomain, SIGNATURE_DOMAIN_PROTOCOL_VERSION},
+    time_range::TimeRange,
     tx_hash::TxHash,
 };
 
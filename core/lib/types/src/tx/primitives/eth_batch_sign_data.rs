@@ -3,7 +3,7 @@ use itertools::Itertools;
 // Workspace uses
 use zksync_basic_types::Address;
 // Local uses
-use super::eth_signature::TxEthSignature;
+use super::{eth_signature::TxEthSignature, signature_domain::SignatureDomain};
 use crate::{Token, ZkSyncTx};
 use thiserror::Error;
 
@@ -37,6 +37,15 @@ impl EthBatchSignData {
         })
     }
 
+    /// Binds the batch message to the network if the domain is required, see `SignatureDomain`.
+    pub fn with_domain(mut self, domain: Option<&SignatureDomain>) -> Self {
+        if let Some(domain) = domain {
+            let message = String::from_utf8(self.message).expect("Batch message is a valid string");
+            self.message = domain.apply(&message).into_bytes();
+        }
+        self
+    }
+
     /// Construct the message user is expected to sign for the given batch.
     pub fn get_batch_sign_message(txs: Vec<(ZkSyncTx, Token, Address)>) -> Vec<u8> {
         let grouped = txs.into_iter().group_by(|tx| tx.2);
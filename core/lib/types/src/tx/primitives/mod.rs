@@ -7,6 +7,7 @@ pub mod packed_public_key;
 pub mod packed_signature;
 pub mod signature;
 pub mod signature_cache;
+pub mod signature_domain;
 pub mod time_range;
 pub mod tx_hash;
 
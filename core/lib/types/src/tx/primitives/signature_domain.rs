@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::Address;
+
+/// First protocol version which requires the Ethereum signatures of the L2 transactions
+/// to be bound to the network they're submitted to.
+pub const SIGNATURE_DOMAIN_PROTOCOL_VERSION: u32 = 6;
+
+/// Identifies the zkSync network the transaction messages are signed for, so that the
+/// signature cannot be replayed on the other networks: the chain ID tells apart mainnet
+/// and testnets, and the address of the zkSync contract tells apart the forks deployed
+/// on the same chain.
+///
+/// The domain is appended to the messages signed by the Ethereum keys of the accounts
+/// (the single transactions, the batches and the requests to the API). zkSync signatures
+/// are checked by the circuit against the transaction bytes and are not affected.
+/// The hashes checked by the contract (i.e. of the guardian sets) always include
+/// the `encode`d domain, since the contract binds them to itself anyway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureDomain {
+    pub chain_id: u64,
+    pub contract_address: Address,
+}
+
+impl SignatureDomain {
+    pub fn new(chain_id: u64, contract_address: Address) -> Self {
+        Self {
+            chain_id,
+            contract_address,
+        }
+    }
+
+    /// Returns the domain if the given protocol version requires the signatures to be bound to it.
+    pub fn for_protocol_version(
+        protocol_version: u32,
+        chain_id: u64,
+        contract_address: Address,
+    ) -> Option<Self> {
+        if protocol_version >= SIGNATURE_DOMAIN_PROTOCOL_VERSION {
+            Some(Self::new(chain_id, contract_address))
+        } else {
+            None
+        }
+    }
+
+    /// Appends the domain lines to the message signed by the Ethereum key:
+    ///
+    /// ```text
+    /// Chain ID: {chain_id}
+    /// zkSync: {contract_address}
+    /// ```
+    pub fn apply(&self, message: &str) -> String {
+        format!(
+            "{message}\n\
+            Chain ID: {chain_id}\n\
+            zkSync: 0x{contract}",
+            message = message,
+            chain_id = self.chain_id,
+            contract = hex::encode(self.contract_address),
+        )
+    }
+
+    /// Returns the domain as it's packed into the hashed messages checked by the contract:
+    /// the chain ID as `uint256` followed by the contract address.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![0u8; 24];
+        bytes.extend_from_slice(&self.chain_id.to_be_bytes());
+        bytes.extend_from_slice(self.contract_address.as_bytes());
+        bytes
+    }
+
+    /// Appends the domain to the message if it's present.
+    pub fn apply_optional(domain: Option<&Self>, message: String) -> String {
+        match domain {
+            Some(domain) => domain.apply(&message),
+            None => message,
+        }
+    }
+}
@@ -130,3 +130,72 @@ fn test_batch_message() {
     let message = EthBatchSignData::get_batch_sign_message(txs);
     assert_eq!(message, expected.into_bytes());
 }
+
+/// Checks that the domain is appended to the signed messages only since the protocol version
+/// which requires it.
+#[test]
+fn test_signature_domain() {
+    let contract_address = Address::from_str("2e46cd9538248826ede540012c0e8d13f223d587").unwrap();
+    assert!(SignatureDomain::for_protocol_version(
+        SIGNATURE_DOMAIN_PROTOCOL_VERSION - 1,
+        1,
+        contract_address
+    )
+    .is_none());
+    let domain = SignatureDomain::for_protocol_version(
+        SIGNATURE_DOMAIN_PROTOCOL_VERSION,
+        1,
+        contract_address,
+    )
+    .unwrap();
+
+    let transfer = get_transfer();
+    let message = transfer.get_ethereum_sign_message("ETH", 18);
+    assert_eq!(
+        domain.apply(&message),
+        format!(
+            "{}\n\
+            Chain ID: 1\n\
+            zkSync: 0x2e46cd9538248826ede540012c0e8d13f223d587",
+            message
+        )
+    );
+    assert_ne!(
+        domain.apply(&message),
+        SignatureDomain::new(4, contract_address).apply(&message)
+    );
+    assert_eq!(
+        SignatureDomain::apply_optional(None, message.clone()),
+        message
+    );
+
+    let token = Token::new(TokenId(0), Default::default(), "ETH", 18);
+    let txs = vec![(ZkSyncTx::from(transfer.clone()), token, transfer.from)];
+    let batch_message = EthBatchSignData::get_batch_sign_message(txs.clone());
+    let batch_sign_data = EthBatchSignData::new(txs, Vec::new())
+        .unwrap()
+        .with_domain(Some(&domain));
+    assert_eq!(
+        batch_sign_data.message,
+        domain
+            .apply(&String::from_utf8(batch_message).unwrap())
+            .into_bytes()
+    );
+}
+
+#[test]
+fn test_tx_hash_forms() {
+    let tx = ZkSyncTx::from(get_transfer());
+    let tx_hash = tx.hash();
+    let hex_hash = hex::encode(tx_hash.as_ref());
+
+    assert_eq!(TxHash::from_str(&tx_hash.to_string()).unwrap(), tx_hash);
+    assert_eq!(
+        TxHash::from_str(&format!("0x{}", hex_hash)).unwrap(),
+        tx_hash
+    );
+    assert!(TxHash::from_str(&hex_hash).is_err());
+
+    // Ethereum-style hash is calculated from the same bytes with another hash function.
+    assert_ne!(tx.eth_hash().as_bytes(), tx_hash.as_ref());
+}
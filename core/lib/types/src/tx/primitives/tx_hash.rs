@@ -4,6 +4,8 @@ use thiserror::Error;
 
 /// Transaction hash.
 /// Essentially, a SHA-256 hash of transaction bytes encoded according to the zkSync protocol.
+///
+/// Canonical string form is `sync-tx:` prefixed, but the `0x` prefixed form is accepted as well.
 #[derive(Debug, Copy, Clone, PartialEq, Default, Eq, Hash, PartialOrd, Ord)]
 pub struct TxHash {
     pub(crate) data: [u8; 32],
@@ -41,10 +43,14 @@ impl FromStr for TxHash {
     type Err = TxHashDecodeError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if !s.starts_with("sync-tx:") {
+        let hex_str = if let Some(hex_str) = s.strip_prefix("sync-tx:") {
+            hex_str
+        } else if let Some(hex_str) = s.strip_prefix("0x") {
+            hex_str
+        } else {
             return Err(TxHashDecodeError::PrefixError);
-        }
-        let bytes = hex::decode(&s[8..])?;
+        };
+        let bytes = hex::decode(hex_str)?;
         if bytes.len() != 32 {
             return Err(TxHashDecodeError::IncorrectHashLength);
         }
@@ -56,7 +62,7 @@ impl FromStr for TxHash {
 
 #[derive(Debug, Error)]
 pub enum TxHashDecodeError {
-    #[error("TxHash should start with sync-tx:")]
+    #[error("TxHash should start with sync-tx: or 0x")]
     PrefixError,
     #[error("Cannot decode Hex: {0}")]
     DecodeHex(#[from] hex::FromHexError),
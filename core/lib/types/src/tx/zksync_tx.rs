@@ -1,8 +1,8 @@
 use num::BigUint;
-use parity_crypto::digest::sha256;
+use parity_crypto::{digest::sha256, Keccak256};
 use serde::{Deserialize, Serialize};
 
-use zksync_basic_types::{AccountId, Address};
+use zksync_basic_types::{AccountId, Address, H256};
 
 use crate::{
     operations::ChangePubKeyOp,
@@ -95,20 +95,20 @@ impl std::ops::Deref for SignedZkSyncTx {
 impl ZkSyncTx {
     /// Returns the hash of the transaction.
     pub fn hash(&self) -> TxHash {
-        let bytes = match self {
-            ZkSyncTx::Transfer(tx) => tx.get_bytes(),
-            ZkSyncTx::Withdraw(tx) => tx.get_bytes(),
-            ZkSyncTx::Close(tx) => tx.get_bytes(),
-            ZkSyncTx::ChangePubKey(tx) => tx.get_bytes(),
-            ZkSyncTx::ForcedExit(tx) => tx.get_bytes(),
-        };
-
-        let hash = sha256(&bytes);
+        let hash = sha256(&self.get_bytes());
         let mut out = [0u8; 32];
         out.copy_from_slice(&hash);
         TxHash { data: out }
     }
 
+    /// Returns the Keccak-256 hash of the transaction bytes.
+    ///
+    /// Ethereum-oriented tools may identify the transaction by this hash rather than
+    /// by the canonical one, so the server stores it as an alias of the transaction hash.
+    pub fn eth_hash(&self) -> H256 {
+        H256::from(self.get_bytes().keccak256())
+    }
+
     /// Returns the account affected by the transaction.
     pub fn account(&self) -> Address {
         match self {
@@ -316,4 +316,15 @@ impl ZkSyncTx {
             ZkSyncTx::Close(tx) => tx.time_range.valid_from,
         }
     }
+
+    /// Returns the unix format timestamp of the last moment when transaction execution is valid.
+    pub fn valid_until(&self) -> u64 {
+        match self {
+            ZkSyncTx::Transfer(tx) => tx.time_range.unwrap_or_default().valid_until,
+            ZkSyncTx::Withdraw(tx) => tx.time_range.unwrap_or_default().valid_until,
+            ZkSyncTx::ChangePubKey(tx) => tx.time_range.unwrap_or_default().valid_until,
+            ZkSyncTx::ForcedExit(tx) => tx.time_range.valid_until,
+            ZkSyncTx::Close(tx) => tx.time_range.valid_until,
+        }
+    }
 }
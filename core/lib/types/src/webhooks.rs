@@ -0,0 +1,278 @@
+//! Webhook notifications.
+//!
+//! Operator subscribes external URLs to the chosen kinds of notifications, optionally filtered
+//! by the account address. Notifications are derived from the event log (see `zksync_types::event`
+//! module) and are sent as JSON payloads signed with the secret of the subscription. Every delivery
+//! is stored along with its status, so failed deliveries can be inspected.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{Address, BlockNumber, TokenId, H256};
+use zksync_utils::BigUintSerdeAsRadix10Str;
+
+use crate::{
+    block::ExecutedOperations, event::EventOffset, tx::TxHash, ZkSyncOp, ZkSyncPriorityOp, ZkSyncTx,
+};
+
+pub type WebhookSubscriptionId = i64;
+
+/// Kind of the notifications the webhook is subscribed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookEventType {
+    /// Deposit is included into a verified block.
+    DepositFinalized,
+    /// Block is verified and executed on L1.
+    BlockVerified,
+    /// Funds of the withdrawal are sent to L1.
+    WithdrawalCompleted,
+}
+
+impl WebhookEventType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::DepositFinalized => "depositFinalized",
+            Self::BlockVerified => "blockVerified",
+            Self::WithdrawalCompleted => "withdrawalCompleted",
+        }
+    }
+}
+
+impl fmt::Display for WebhookEventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WebhookEventType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "depositFinalized" => Ok(Self::DepositFinalized),
+            "blockVerified" => Ok(Self::BlockVerified),
+            "withdrawalCompleted" => Ok(Self::WithdrawalCompleted),
+            other => Err(format!("Unknown webhook event type: {}", other)),
+        }
+    }
+}
+
+/// URL subscribed to the notifications of the certain kind.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSubscription {
+    pub id: WebhookSubscriptionId,
+    pub url: String,
+    pub event_type: WebhookEventType,
+    /// If set, only the notifications related to this address are sent.
+    /// Ignored for the `BlockVerified` notifications.
+    pub address: Option<Address>,
+    /// API namespace the subscription belongs to, if any.
+    pub namespace: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Payload of the webhook request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum WebhookNotification {
+    #[serde(rename_all = "camelCase")]
+    DepositFinalized {
+        block_number: BlockNumber,
+        serial_id: u64,
+        eth_hash: H256,
+        from: Address,
+        to: Address,
+        token: TokenId,
+        #[serde(with = "BigUintSerdeAsRadix10Str")]
+        amount: BigUint,
+    },
+    #[serde(rename_all = "camelCase")]
+    BlockVerified { block_number: BlockNumber },
+    #[serde(rename_all = "camelCase")]
+    WithdrawalCompleted {
+        block_number: BlockNumber,
+        tx_hash: TxHash,
+        from: Address,
+        to: Address,
+        token: TokenId,
+        #[serde(with = "BigUintSerdeAsRadix10Str")]
+        amount: BigUint,
+    },
+}
+
+impl WebhookNotification {
+    /// Returns the notifications about the verification of the block with the given operations.
+    pub fn for_verified_block(
+        block_number: BlockNumber,
+        operations: &[ExecutedOperations],
+    ) -> Vec<Self> {
+        let mut notifications = vec![Self::BlockVerified { block_number }];
+        for operation in operations {
+            match operation {
+                ExecutedOperations::PriorityOp(op) => {
+                    if let ZkSyncPriorityOp::Deposit(deposit) = &op.priority_op.data {
+                        notifications.push(Self::DepositFinalized {
+                            block_number,
+                            serial_id: op.priority_op.serial_id,
+                            eth_hash: op.priority_op.eth_hash,
+                            from: deposit.from,
+                            to: deposit.to,
+                            token: deposit.token,
+                            amount: deposit.amount.clone(),
+                        });
+                    }
+                }
+                ExecutedOperations::Tx(tx) if tx.success => {
+                    let tx_hash = tx.signed_tx.tx.hash();
+                    match (&tx.signed_tx.tx, &tx.op) {
+                        (ZkSyncTx::Withdraw(withdraw), _) => {
+                            notifications.push(Self::WithdrawalCompleted {
+                                block_number,
+                                tx_hash,
+                                from: withdraw.from,
+                                to: withdraw.to,
+                                token: withdraw.token,
+                                amount: withdraw.amount.clone(),
+                            });
+                        }
+                        (ZkSyncTx::ForcedExit(forced_exit), Some(ZkSyncOp::ForcedExit(op))) => {
+                            if let Some(amount) = &op.withdraw_amount {
+                                notifications.push(Self::WithdrawalCompleted {
+                                    block_number,
+                                    tx_hash,
+                                    from: forced_exit.target,
+                                    to: forced_exit.target,
+                                    token: forced_exit.token,
+                                    amount: amount.0.clone(),
+                                });
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+        notifications
+    }
+
+    pub fn event_type(&self) -> WebhookEventType {
+        match self {
+            Self::DepositFinalized { .. } => WebhookEventType::DepositFinalized,
+            Self::BlockVerified { .. } => WebhookEventType::BlockVerified,
+            Self::WithdrawalCompleted { .. } => WebhookEventType::WithdrawalCompleted,
+        }
+    }
+
+    /// Checks whether the notification should be sent to the subscriber.
+    pub fn matches(&self, subscription: &WebhookSubscription) -> bool {
+        if self.event_type() != subscription.event_type {
+            return false;
+        }
+        match (self, subscription.address) {
+            (_, None) | (Self::BlockVerified { .. }, _) => true,
+            (Self::DepositFinalized { to, .. }, Some(address)) => *to == address,
+            (Self::WithdrawalCompleted { from, to, .. }, Some(address)) => {
+                *from == address || *to == address
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookDeliveryStatus {
+    /// Delivery is not attempted yet or will be retried.
+    Pending,
+    /// Subscriber responded with a successful status.
+    Delivered,
+    /// All the attempts failed, delivery won't be retried.
+    Failed,
+}
+
+impl WebhookDeliveryStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Delivered => "delivered",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl FromStr for WebhookDeliveryStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "delivered" => Ok(Self::Delivered),
+            "failed" => Ok(Self::Failed),
+            other => Err(format!("Unknown webhook delivery status: {}", other)),
+        }
+    }
+}
+
+/// Notification sent (or to be sent) to the subscriber.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub subscription_id: WebhookSubscriptionId,
+    /// Offset of the event the notification is derived from.
+    pub event_offset: EventOffset,
+    pub payload: WebhookNotification,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscription(event_type: WebhookEventType, address: Option<Address>) -> WebhookSubscription {
+        WebhookSubscription {
+            id: 1,
+            url: "http://127.0.0.1:8080".to_owned(),
+            event_type,
+            address,
+            namespace: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn notification_matching() {
+        let recipient = Address::repeat_byte(1);
+        let deposit = WebhookNotification::DepositFinalized {
+            block_number: BlockNumber(1),
+            serial_id: 0,
+            eth_hash: H256::zero(),
+            from: Address::repeat_byte(2),
+            to: recipient,
+            token: TokenId(0),
+            amount: 100u64.into(),
+        };
+        let block = WebhookNotification::BlockVerified {
+            block_number: BlockNumber(1),
+        };
+
+        let deposits = WebhookEventType::DepositFinalized;
+        assert!(deposit.matches(&subscription(deposits, None)));
+        assert!(deposit.matches(&subscription(deposits, Some(recipient))));
+        assert!(!deposit.matches(&subscription(deposits, Some(Address::repeat_byte(2)))));
+        assert!(!deposit.matches(&subscription(WebhookEventType::BlockVerified, None)));
+
+        let blocks = WebhookEventType::BlockVerified;
+        assert!(block.matches(&subscription(blocks, Some(recipient))));
+        assert!(!block.matches(&subscription(deposits, None)));
+    }
+}
@@ -0,0 +1,245 @@
+//! Linkage of the withdrawals to the L1 transactions executing them.
+//!
+//! Withdrawals are sent to their recipients by the `executeBlocks` transactions. For every
+//! withdrawal the contract attempts the transfer and emits the `Withdrawal` event if it succeeds.
+//! If the transfer fails (e.g. the recipient contract rejects ETH or the token transfer reverts),
+//! the funds are stored in the pending balance of the recipient, which must be withdrawn with
+//! the `withdrawPendingBalance` contract method.
+//!
+//! `Withdrawal` events don't contain the recipients, so the events of the confirmed `executeBlocks`
+//! transaction are matched with the withdrawals of the executed blocks in the execution order.
+//!
+//! The matching is exact as long as the consecutive withdrawals of the same token differ in amounts.
+//! The withdrawals of the same token and amount are indistinguishable by their events: if only some
+//! of them were stored in the pending balances, the events are assigned to the earliest ones. In that
+//! case the number of the pending withdrawals is still correct, but they may be attributed to the
+//! wrong transactions. Such statuses are not corrected later (e.g. by checking the pending balances
+//! of the recipients), so they must not be relied upon to decide whether the funds were received.
+
+use std::{fmt, str::FromStr};
+
+use num::BigUint;
+use parity_crypto::Keccak256;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zksync_basic_types::{BlockNumber, Log, TokenId, H256, U256};
+
+use crate::{block::Block, tx::TxHash, ExecutedOperations, ZkSyncOp};
+
+/// Result of the withdrawal execution on L1.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WithdrawalExecutionStatus {
+    /// The funds were transferred to the recipient.
+    Completed,
+    /// The transfer failed and the funds were stored in the pending balance of the recipient.
+    Pending,
+}
+
+impl fmt::Display for WithdrawalExecutionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WithdrawalExecutionStatus::Completed => write!(f, "COMPLETED"),
+            WithdrawalExecutionStatus::Pending => write!(f, "PENDING"),
+        }
+    }
+}
+
+impl FromStr for WithdrawalExecutionStatus {
+    type Err = IncorrectWithdrawalExecutionStatus;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "COMPLETED" => WithdrawalExecutionStatus::Completed,
+            "PENDING" => WithdrawalExecutionStatus::Pending,
+            _ => return Err(IncorrectWithdrawalExecutionStatus(s.to_owned())),
+        })
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+#[error("Incorrect WithdrawalExecutionStatus: {0}")]
+pub struct IncorrectWithdrawalExecutionStatus(pub String);
+
+/// L1 transaction which executed the withdrawal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalExecution {
+    /// Hash of the L2 transaction, or the hash of the L1 transaction for the `FullExit` operations.
+    pub withdrawal_hash: TxHash,
+    pub block_number: BlockNumber,
+    /// Hash of the `executeBlocks` transaction.
+    pub eth_tx_hash: H256,
+    pub status: WithdrawalExecutionStatus,
+}
+
+/// Withdrawal performed by the `executeBlocks` transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockWithdrawal {
+    pub withdrawal_hash: TxHash,
+    pub block_number: BlockNumber,
+    pub token_id: TokenId,
+    pub amount: BigUint,
+}
+
+/// Returns the withdrawals of the block in their execution order.
+pub fn block_withdrawals(block: &Block) -> Vec<BlockWithdrawal> {
+    block
+        .block_transactions
+        .iter()
+        .filter_map(|executed| {
+            let op = executed.get_executed_op()?;
+            let (token_id, amount) = match op {
+                ZkSyncOp::Withdraw(op) => (op.tx.token, op.tx.amount.clone()),
+                ZkSyncOp::ForcedExit(op) => (
+                    op.tx.token,
+                    op.withdraw_amount
+                        .clone()
+                        .map(|amount| amount.0)
+                        .unwrap_or_default(),
+                ),
+                ZkSyncOp::FullExit(op) => (
+                    op.priority_op.token,
+                    op.withdraw_amount
+                        .clone()
+                        .map(|amount| amount.0)
+                        .unwrap_or_default(),
+                ),
+                _ => return None,
+            };
+            let withdrawal_hash = match executed {
+                ExecutedOperations::Tx(tx) => tx.signed_tx.hash(),
+                ExecutedOperations::PriorityOp(op) => {
+                    TxHash::from_slice(op.priority_op.eth_hash.as_bytes())?
+                }
+            };
+
+            Some(BlockWithdrawal {
+                withdrawal_hash,
+                block_number: block.block_number,
+                token_id,
+                amount,
+            })
+        })
+        .collect()
+}
+
+/// Decodes the `Withdrawal(uint16 indexed tokenId, uint128 amount)` events from the logs.
+pub fn parse_withdrawal_events(logs: &[Log]) -> Vec<(TokenId, BigUint)> {
+    let signature = H256::from(b"Withdrawal(uint16,uint128)".keccak256());
+    logs.iter()
+        .filter(|log| log.topics.len() == 2 && log.topics[0] == signature)
+        .map(|log| {
+            let token_id = U256::from_big_endian(log.topics[1].as_bytes()).low_u32() as u16;
+            (TokenId(token_id), BigUint::from_bytes_be(&log.data.0))
+        })
+        .collect()
+}
+
+/// Matches the withdrawals with the `Withdrawal` events of the `executeBlocks` transaction.
+/// Withdrawals without the event were stored in the pending balances of the recipients.
+///
+/// Each event is matched with the earliest unmatched withdrawal of the same token and amount,
+/// see the module docs for the ambiguity this implies.
+pub fn match_withdrawal_events(
+    withdrawals: Vec<BlockWithdrawal>,
+    events: &[(TokenId, BigUint)],
+    eth_tx_hash: H256,
+) -> Vec<WithdrawalExecution> {
+    let mut events = events.iter().peekable();
+    withdrawals
+        .into_iter()
+        .map(|withdrawal| {
+            let completed = events.peek().map_or(false, |(token_id, amount)| {
+                *token_id == withdrawal.token_id && *amount == withdrawal.amount
+            });
+            let status = if completed {
+                events.next();
+                WithdrawalExecutionStatus::Completed
+            } else {
+                WithdrawalExecutionStatus::Pending
+            };
+
+            WithdrawalExecution {
+                withdrawal_hash: withdrawal.withdrawal_hash,
+                block_number: withdrawal.block_number,
+                eth_tx_hash,
+                status,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use web3::types::Bytes;
+
+    fn withdrawal(id: u8, token_id: u16, amount: u32) -> BlockWithdrawal {
+        BlockWithdrawal {
+            withdrawal_hash: TxHash::from_slice(&[id; 32]).unwrap(),
+            block_number: BlockNumber(1),
+            token_id: TokenId(token_id),
+            amount: amount.into(),
+        }
+    }
+
+    fn withdrawal_log(token_id: u16, amount: u32) -> Log {
+        let mut data = [0u8; 32];
+        U256::from(amount).to_big_endian(&mut data);
+        Log {
+            address: Default::default(),
+            topics: vec![
+                H256::from(b"Withdrawal(uint16,uint128)".keccak256()),
+                H256::from_low_u64_be(token_id.into()),
+            ],
+            data: Bytes(data.to_vec()),
+            block_hash: None,
+            block_number: None,
+            transaction_hash: None,
+            transaction_index: None,
+            log_index: None,
+            transaction_log_index: None,
+            log_type: None,
+            removed: None,
+        }
+    }
+
+    #[test]
+    fn withdrawals_are_matched_in_order() {
+        let logs = vec![withdrawal_log(0, 100), withdrawal_log(1, 300)];
+        let events = parse_withdrawal_events(&logs);
+        assert_eq!(
+            events,
+            vec![(TokenId(0), 100u32.into()), (TokenId(1), 300u32.into())]
+        );
+
+        let withdrawals = vec![
+            withdrawal(1, 0, 100),
+            withdrawal(2, 1, 200),
+            withdrawal(3, 1, 300),
+        ];
+        let statuses: Vec<_> = match_withdrawal_events(withdrawals, &events, H256::zero())
+            .into_iter()
+            .map(|execution| execution.status)
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                WithdrawalExecutionStatus::Completed,
+                WithdrawalExecutionStatus::Pending,
+                WithdrawalExecutionStatus::Completed,
+            ]
+        );
+    }
+
+    /// Checks that the events of the indistinguishable withdrawals go to the earliest ones.
+    #[test]
+    fn same_withdrawals_are_matched_to_the_earliest() {
+        let events = vec![(TokenId(0), 100u32.into())];
+        let withdrawals = vec![withdrawal(1, 0, 100), withdrawal(2, 0, 100)];
+        let executions = match_withdrawal_events(withdrawals, &events, H256::zero());
+        assert_eq!(executions[0].status, WithdrawalExecutionStatus::Completed);
+        assert_eq!(executions[1].status, WithdrawalExecutionStatus::Pending);
+    }
+}
@@ -0,0 +1,222 @@
+//! Calibration of the withdrawal gas costs.
+//!
+//! Withdrawals of some ERC-20 tokens (e.g. rebasing tokens or tokens with transfer hooks) cost
+//! far more L1 gas than the flat `VerifyCost::WITHDRAW_COST` estimation assumes. Gas used by every
+//! confirmed `executeBlocks` transaction is attributed to the withdrawals it performs, and the
+//! running average for every token is used to quote the withdrawal fees.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::TokenId;
+
+use crate::{
+    gas_counter::{GasCounter, VerifyCost},
+    ZkSyncOp,
+};
+
+/// Max number of the withdrawals in the running average, so the estimation follows
+/// the changes of the token contracts.
+pub const MAX_AVERAGED_SAMPLES: u64 = 100;
+/// Upper bound of the gas cost of one withdrawal, limiting the effect of the outliers.
+pub const MAX_WITHDRAWAL_GAS_COST: u64 =
+    VerifyCost::WITHDRAW_COST + GasCounter::COMPLETE_WITHDRAWALS_ERC20_COST;
+
+/// Calibrated gas cost of the withdrawal in one token.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalGasCost {
+    pub token_id: TokenId,
+    /// Average gas spent on one withdrawal by the `executeBlocks` transactions.
+    pub gas_cost: u64,
+    /// Number of the withdrawals the average is calculated from.
+    pub samples: u64,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WithdrawalGasCost {
+    /// Creates the estimation for the token without the measured withdrawals.
+    pub fn new(token_id: TokenId) -> Self {
+        Self {
+            token_id,
+            gas_cost: VerifyCost::WITHDRAW_COST,
+            samples: 0,
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Adds the withdrawals with the measured gas cost to the running average.
+    pub fn add_samples(&mut self, gas_cost: u64, count: u64) {
+        let samples = (self.samples + count).min(MAX_AVERAGED_SAMPLES);
+        let count = count.min(samples);
+        if samples == 0 {
+            return;
+        }
+
+        let total = u128::from(self.gas_cost) * u128::from(samples - count)
+            + u128::from(gas_cost) * u128::from(count);
+        self.gas_cost = (total / u128::from(samples)) as u64;
+        self.samples = samples;
+        self.updated_at = Utc::now();
+    }
+
+    /// Returns the gas the withdrawal costs above the flat estimation.
+    pub fn extra_gas_cost(&self) -> u64 {
+        self.gas_cost.saturating_sub(VerifyCost::WITHDRAW_COST)
+    }
+}
+
+/// Withdrawals in one token performed by the `executeBlocks` transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WithdrawalGasSample {
+    pub token_id: TokenId,
+    pub count: u64,
+    /// Gas attributed to one withdrawal.
+    pub gas_cost: u64,
+}
+
+/// Attributes the gas used by the `executeBlocks` transaction to the withdrawals it performs.
+///
+/// Gas spent on the blocks and the other operations is estimated with `VerifyCost`, and the rest
+/// is split between the withdrawals proportionally to the known gas costs of their tokens.
+pub fn attribute_withdrawal_gas<'a>(
+    ops: impl IntoIterator<Item = &'a ZkSyncOp>,
+    blocks_count: usize,
+    gas_used: u64,
+    known_costs: &HashMap<TokenId, u64>,
+) -> Vec<WithdrawalGasSample> {
+    let mut other_cost = VerifyCost::BASE_COST * blocks_count as u64;
+    let mut withdrawals = BTreeMap::new();
+    for op in ops {
+        let token_id = match op {
+            ZkSyncOp::Withdraw(op) => op.tx.token,
+            ZkSyncOp::ForcedExit(op) => op.tx.token,
+            _ => {
+                other_cost += VerifyCost::op_cost(op).as_u64();
+                continue;
+            }
+        };
+        *withdrawals.entry(token_id).or_insert(0u64) += 1;
+    }
+
+    let known_cost = |token_id: &TokenId| {
+        known_costs
+            .get(token_id)
+            .copied()
+            .unwrap_or(VerifyCost::WITHDRAW_COST)
+    };
+    let expected_cost: u128 = withdrawals
+        .iter()
+        .map(|(token_id, count)| u128::from(*count) * u128::from(known_cost(token_id)))
+        .sum();
+    if expected_cost == 0 || gas_used <= other_cost {
+        return Vec::new();
+    }
+
+    let withdrawals_gas = u128::from(gas_used - other_cost);
+    withdrawals
+        .into_iter()
+        .map(|(token_id, count)| {
+            let gas_cost = u128::from(known_cost(&token_id)) * withdrawals_gas / expected_cost;
+            WithdrawalGasSample {
+                token_id,
+                count,
+                gas_cost: (gas_cost as u64).min(MAX_WITHDRAWAL_GAS_COST),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        operations::{TransferOp, WithdrawOp},
+        tx::{TimeRange, Transfer, Withdraw},
+        AccountId, Address, Nonce,
+    };
+
+    fn withdraw(token: u16) -> ZkSyncOp {
+        let tx = Withdraw::new(
+            AccountId(1),
+            Address::repeat_byte(1),
+            Address::repeat_byte(1),
+            TokenId(token),
+            100u64.into(),
+            10u64.into(),
+            Nonce(0),
+            TimeRange::default(),
+            None,
+        );
+        ZkSyncOp::Withdraw(Box::new(WithdrawOp {
+            tx,
+            account_id: AccountId(1),
+        }))
+    }
+
+    fn transfer() -> ZkSyncOp {
+        let tx = Transfer::new(
+            AccountId(1),
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            TokenId(0),
+            100u64.into(),
+            10u64.into(),
+            Nonce(0),
+            TimeRange::default(),
+            None,
+        );
+        ZkSyncOp::Transfer(Box::new(TransferOp {
+            tx,
+            from: AccountId(1),
+            to: AccountId(2),
+        }))
+    }
+
+    #[test]
+    fn gas_attribution() {
+        let ops = vec![withdraw(1), withdraw(1), transfer()];
+        let gas_used = VerifyCost::BASE_COST + 2 * 150_000;
+        let samples = attribute_withdrawal_gas(&ops, 1, gas_used, &HashMap::new());
+        assert_eq!(
+            samples,
+            vec![WithdrawalGasSample {
+                token_id: TokenId(1),
+                count: 2,
+                gas_cost: 150_000,
+            }]
+        );
+
+        // Gas is split proportionally to the known costs.
+        let ops = vec![withdraw(0), withdraw(1)];
+        let known_costs = vec![(TokenId(0), 50_000), (TokenId(1), 150_000)]
+            .into_iter()
+            .collect();
+        let gas_used = VerifyCost::BASE_COST + 400_000;
+        let samples = attribute_withdrawal_gas(&ops, 1, gas_used, &known_costs);
+        let costs: Vec<_> = samples.iter().map(|sample| sample.gas_cost).collect();
+        assert_eq!(costs, vec![100_000, MAX_WITHDRAWAL_GAS_COST]);
+
+        // Transactions without withdrawals are not used for the calibration.
+        let ops = vec![transfer()];
+        assert!(attribute_withdrawal_gas(&ops, 1, 1_000_000, &HashMap::new()).is_empty());
+    }
+
+    #[test]
+    fn running_average() {
+        let mut cost = WithdrawalGasCost::new(TokenId(1));
+        cost.add_samples(100_000, 1);
+        assert_eq!((cost.gas_cost, cost.samples), (100_000, 1));
+        cost.add_samples(200_000, 1);
+        assert_eq!((cost.gas_cost, cost.samples), (150_000, 2));
+        assert_eq!(cost.extra_gas_cost(), 150_000 - VerifyCost::WITHDRAW_COST);
+
+        // Only the latest withdrawals are averaged.
+        cost.add_samples(50_000, MAX_AVERAGED_SAMPLES);
+        assert_eq!(
+            (cost.gas_cost, cost.samples),
+            (50_000, MAX_AVERAGED_SAMPLES)
+        );
+    }
+}
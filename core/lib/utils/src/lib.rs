@@ -6,7 +6,9 @@ mod format;
 mod macros;
 pub mod panic_notify;
 mod serde_wrappers;
+pub mod shutdown;
 mod string;
+pub mod supervisor;
 
 pub use convert::*;
 pub use env_tools::*;
@@ -0,0 +1,213 @@
+//! Graceful shutdown coordination.
+//!
+//! Once the shutdown is triggered (e.g. by `SIGTERM`), every component observes the `ShutdownSignal`
+//! and finishes its work in progress: the API stops accepting new transactions, the state keeper
+//! seals the pending block, `eth_sender` completes the in-flight broadcasts, etc. Each component
+//! holds a `DrainGuard` obtained from the `ShutdownCoordinator` and drops it once it's drained,
+//! so the process can exit as soon as all the guards are dropped. The components restarted by
+//! the supervisor obtain a new guard for each restart from the `DrainRegistry`.
+
+// Built-in deps
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+// External uses
+use futures::{channel::mpsc, StreamExt};
+use tokio::sync::watch;
+// Local uses
+
+/// Handle for the components to be notified about the shutdown.
+#[derive(Debug, Clone)]
+pub struct ShutdownSignal(watch::Receiver<bool>);
+
+impl ShutdownSignal {
+    /// Returns `true` if the shutdown was requested.
+    pub fn is_triggered(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Waits until the shutdown is requested.
+    pub async fn recv(&mut self) {
+        while !self.is_triggered() {
+            if self.0.recv().await.is_none() {
+                // Coordinator is dropped without requesting the shutdown, so it will never happen.
+                futures::future::pending::<()>().await;
+            }
+        }
+    }
+}
+
+/// Cloneable handle which can be used to request the shutdown, e.g. from the signal handler.
+#[derive(Debug, Clone)]
+pub struct ShutdownTrigger(Arc<watch::Sender<bool>>);
+
+impl ShutdownTrigger {
+    pub fn trigger(&self) {
+        self.0.broadcast(true).unwrap_or_default();
+    }
+}
+
+/// Token of the component that has to finish its work before the process exits.
+/// The component is considered drained once the guard is dropped.
+#[derive(Debug)]
+pub struct DrainGuard {
+    component: &'static str,
+    pending: Arc<Mutex<BTreeSet<&'static str>>>,
+    /// `None` if the guard is obtained once the coordinator is already waiting for the drain.
+    _drained: Option<mpsc::Sender<()>>,
+}
+
+impl Drop for DrainGuard {
+    fn drop(&mut self) {
+        self.pending.lock().unwrap().remove(self.component);
+        vlog::info!("Component {} is drained", self.component);
+    }
+}
+
+/// Cloneable handle to register the components, e.g. to obtain a new `DrainGuard` each time
+/// the supervised component is restarted.
+#[derive(Debug, Clone)]
+pub struct DrainRegistry {
+    pending: Arc<Mutex<BTreeSet<&'static str>>>,
+    /// Taken once the coordinator starts waiting for the drain, so the registry held by
+    /// the running components doesn't prevent it from finishing.
+    drained_sender: Arc<Mutex<Option<mpsc::Sender<()>>>>,
+}
+
+impl DrainRegistry {
+    /// Registers the component which has to be drained before the process exits.
+    pub fn register(&self, component: &'static str) -> DrainGuard {
+        self.pending.lock().unwrap().insert(component);
+        DrainGuard {
+            component,
+            pending: self.pending.clone(),
+            _drained: self.drained_sender.lock().unwrap().clone(),
+        }
+    }
+}
+
+/// Orchestrates the graceful shutdown of the components running in the same process.
+#[derive(Debug)]
+pub struct ShutdownCoordinator {
+    trigger: ShutdownTrigger,
+    signal: ShutdownSignal,
+    registry: DrainRegistry,
+    drained_receiver: mpsc::Receiver<()>,
+}
+
+impl Default for ShutdownCoordinator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShutdownCoordinator {
+    pub fn new() -> Self {
+        let (trigger, signal) = watch::channel(false);
+        let (drained_sender, drained_receiver) = mpsc::channel(1);
+        Self {
+            trigger: ShutdownTrigger(Arc::new(trigger)),
+            signal: ShutdownSignal(signal),
+            registry: DrainRegistry {
+                pending: Arc::default(),
+                drained_sender: Arc::new(Mutex::new(Some(drained_sender))),
+            },
+            drained_receiver,
+        }
+    }
+
+    pub fn signal(&self) -> ShutdownSignal {
+        self.signal.clone()
+    }
+
+    pub fn trigger(&self) -> ShutdownTrigger {
+        self.trigger.clone()
+    }
+
+    pub fn registry(&self) -> DrainRegistry {
+        self.registry.clone()
+    }
+
+    /// Registers the component which has to be drained before the process exits.
+    pub fn register(&self, component: &'static str) -> DrainGuard {
+        self.registry.register(component)
+    }
+
+    /// Waits until all the registered components are drained.
+    /// Returns the names of the components that weren't drained within the timeout.
+    pub async fn wait_drained(self, timeout: Duration) -> Vec<&'static str> {
+        let Self {
+            registry,
+            mut drained_receiver,
+            ..
+        } = self;
+        drop(registry.drained_sender.lock().unwrap().take());
+        let pending = registry.pending;
+
+        // Receiver yields `None` once all the guards (holding the senders) are dropped.
+        let drained = drained_receiver.next();
+        if tokio::time::timeout(timeout, drained).await.is_err() {
+            return pending.lock().unwrap().iter().copied().collect();
+        }
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn waits_for_all_components() {
+        let coordinator = ShutdownCoordinator::new();
+        let mut signal = coordinator.signal();
+        let first = coordinator.register("first");
+        let second = coordinator.register("second");
+        assert!(!signal.is_triggered());
+
+        coordinator.trigger().trigger();
+        signal.recv().await;
+        assert!(coordinator.signal().is_triggered());
+
+        drop(first);
+        tokio::spawn(async move {
+            tokio::time::delay_for(Duration::from_millis(10)).await;
+            drop(second);
+        });
+        let not_drained = coordinator.wait_drained(Duration::from_secs(10)).await;
+        assert!(not_drained.is_empty());
+    }
+
+    /// Checks that the guard obtained from the registry, e.g. by the restarted component,
+    /// is waited for as well, and the registry itself doesn't hold the coordinator.
+    #[tokio::test]
+    async fn waits_for_registered_components() {
+        let coordinator = ShutdownCoordinator::new();
+        let registry = coordinator.registry();
+        drop(registry.register("restarted"));
+        let restarted = registry.register("restarted");
+
+        tokio::spawn(async move {
+            tokio::time::delay_for(Duration::from_millis(10)).await;
+            drop(restarted);
+        });
+        let not_drained = coordinator.wait_drained(Duration::from_secs(10)).await;
+        assert!(not_drained.is_empty());
+
+        let coordinator = ShutdownCoordinator::new();
+        let _stuck = coordinator.registry().register("stuck");
+        let not_drained = coordinator.wait_drained(Duration::from_millis(10)).await;
+        assert_eq!(not_drained, vec!["stuck"]);
+    }
+
+    #[tokio::test]
+    async fn reports_stuck_components() {
+        let coordinator = ShutdownCoordinator::new();
+        let _stuck = coordinator.register("stuck");
+
+        let not_drained = coordinator.wait_drained(Duration::from_millis(10)).await;
+        assert_eq!(not_drained, vec!["stuck"]);
+    }
+}
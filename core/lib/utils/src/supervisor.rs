@@ -0,0 +1,327 @@
+//! Supervision of the long-running actors.
+//!
+//! A supervised component is spawned from a factory creating the component future. If the component
+//! panics, the supervisor restarts it with an exponential backoff. If the component keeps failing
+//! (`max_failures` failures within the `failure_window`), the circuit breaker opens: the component is
+//! not restarted during the `cooldown` period, after which one more attempt is made.
+//!
+//! Normal completion of the component future (e.g. on shutdown) is not considered a failure, so the
+//! supervisor finishes as well and the task handle returned by `Supervisor::spawn` resolves.
+//!
+//! Components that consume a channel should read it through the `SharedReceiver`, so the requests
+//! sent while the component is being restarted are processed by its next instance.
+
+// Built-in deps
+use std::{
+    any::Any,
+    collections::{BTreeMap, VecDeque},
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex, PoisonError},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+// External uses
+use futures::{channel::mpsc, Stream};
+use serde::Serialize;
+use tokio::task::JoinHandle;
+// Local uses
+
+/// Restart rules for the supervised components.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RestartPolicy {
+    /// Delay before the first restart, doubled after each consecutive failure.
+    pub initial_backoff: Duration,
+    /// Upper bound for the delay between restarts.
+    pub max_backoff: Duration,
+    /// Amount of failures within the `failure_window` that opens the circuit breaker.
+    pub max_failures: usize,
+    /// Failures older than this are forgotten.
+    pub failure_window: Duration,
+    /// Time the component is kept stopped once the circuit breaker is open.
+    pub cooldown: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            max_failures: 5,
+            failure_window: Duration::from_secs(600),
+            cooldown: Duration::from_secs(600),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ComponentState {
+    Running,
+    /// Component has failed and will be restarted after the backoff.
+    Restarting,
+    /// Component has failed too many times and is stopped until the cooldown is over.
+    CircuitOpen,
+    /// Component has finished its work.
+    Finished,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentStatus {
+    pub state: ComponentState,
+    /// Total amount of restarts since the server start.
+    pub restarts: u64,
+    /// Message of the last panic, if any.
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    /// `true` if none of the components is failed at the moment.
+    pub healthy: bool,
+    pub components: BTreeMap<String, ComponentStatus>,
+}
+
+/// Spawns the components and keeps track of their status.
+#[derive(Debug, Clone, Default)]
+pub struct Supervisor {
+    policy: RestartPolicy,
+    components: Arc<Mutex<BTreeMap<String, ComponentStatus>>>,
+}
+
+impl Supervisor {
+    pub fn new(policy: RestartPolicy) -> Self {
+        Self {
+            policy,
+            components: Arc::default(),
+        }
+    }
+
+    /// Returns the status of all the supervised components.
+    pub fn health(&self) -> HealthReport {
+        let components = self.components.lock().unwrap().clone();
+        let healthy = components.values().all(|status| {
+            matches!(
+                status.state,
+                ComponentState::Running | ComponentState::Finished
+            )
+        });
+        HealthReport {
+            healthy,
+            components,
+        }
+    }
+
+    /// Spawns the component created by the `factory` and restarts it every time it panics.
+    /// The factory is called once for each (re)start.
+    pub fn spawn<F, Fut>(&self, name: impl Into<String>, factory: F) -> JoinHandle<()>
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let name = name.into();
+        self.components.lock().unwrap().insert(
+            name.clone(),
+            ComponentStatus {
+                state: ComponentState::Running,
+                restarts: 0,
+                last_error: None,
+            },
+        );
+        tokio::spawn(self.clone().supervise(name, factory))
+    }
+
+    async fn supervise<F, Fut>(self, name: String, mut factory: F)
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let mut failures = VecDeque::new();
+        let mut backoff = self.policy.initial_backoff;
+        loop {
+            self.update(&name, |status| status.state = ComponentState::Running);
+            let started_at = Instant::now();
+
+            let error = match tokio::spawn(factory()).await {
+                Ok(()) => {
+                    self.update(&name, |status| status.state = ComponentState::Finished);
+                    return;
+                }
+                Err(err) if err.is_panic() => panic_message(err.into_panic()),
+                // The task is cancelled only if the runtime is shutting down.
+                Err(_) => return,
+            };
+            vlog::error!("Component {} failed: {}", name, error);
+            metrics::counter!("supervisor.failures", 1, "component" => name.clone());
+
+            let now = Instant::now();
+            if now - started_at > self.policy.failure_window {
+                // Component worked fine for a long time, so the failure is not a consecutive one.
+                backoff = self.policy.initial_backoff;
+            }
+            failures.push_back(now);
+            while let Some(failed_at) = failures.front() {
+                if now - *failed_at <= self.policy.failure_window {
+                    break;
+                }
+                failures.pop_front();
+            }
+
+            let (state, delay) = if failures.len() >= self.policy.max_failures {
+                vlog::error!(
+                    "Component {} failed {} times in a row, it will be restarted in {:?}",
+                    name,
+                    failures.len(),
+                    self.policy.cooldown
+                );
+                failures.clear();
+                backoff = self.policy.initial_backoff;
+                (ComponentState::CircuitOpen, self.policy.cooldown)
+            } else {
+                let delay = backoff;
+                backoff = std::cmp::min(backoff * 2, self.policy.max_backoff);
+                (ComponentState::Restarting, delay)
+            };
+            self.update(&name, |status| {
+                status.state = state;
+                status.restarts += 1;
+                status.last_error = Some(error);
+            });
+            tokio::time::delay_for(delay).await;
+        }
+    }
+
+    fn update(&self, name: &str, f: impl FnOnce(&mut ComponentStatus)) {
+        let mut components = self.components.lock().unwrap();
+        if let Some(status) = components.get_mut(name) {
+            f(status);
+            let up = if status.state == ComponentState::Running {
+                1.0
+            } else {
+                0.0
+            };
+            metrics::gauge!("supervisor.component_up", up, "component" => name.to_string());
+        }
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Channel receiver which outlives the restarts of the component reading it.
+///
+/// Only one instance of the component is running at a time, so the lock is never contended.
+#[derive(Debug)]
+pub struct SharedReceiver<T>(Arc<Mutex<mpsc::Receiver<T>>>);
+
+impl<T> Clone for SharedReceiver<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> From<mpsc::Receiver<T>> for SharedReceiver<T> {
+    fn from(receiver: mpsc::Receiver<T>) -> Self {
+        Self(Arc::new(Mutex::new(receiver)))
+    }
+}
+
+impl<T> Stream for SharedReceiver<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        // The component may panic while holding the receiver, which doesn't affect the receiver itself.
+        let mut receiver = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        Pin::new(&mut *receiver).poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{SinkExt, StreamExt};
+
+    fn policy() -> RestartPolicy {
+        RestartPolicy {
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(10),
+            max_failures: 3,
+            failure_window: Duration::from_secs(60),
+            cooldown: Duration::from_secs(60),
+        }
+    }
+
+    /// Waits until the component reaches the state. The delays of the test policy are short,
+    /// so only the restarts taking the `cooldown` can make it wait for long.
+    async fn wait_for_state(supervisor: &Supervisor, name: &str, state: ComponentState) {
+        while supervisor.health().components[name].state != state {
+            tokio::time::delay_for(Duration::from_millis(1)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn restarts_failed_component() {
+        let supervisor = Supervisor::new(policy());
+        let (mut sender, receiver) = mpsc::channel(8);
+        let receiver = SharedReceiver::from(receiver);
+        let (mut results, mut processed) = mpsc::channel(8);
+
+        let handle = supervisor.spawn("echo", move || {
+            let mut receiver = receiver.clone();
+            let mut results = results.clone();
+            async move {
+                while let Some(value) = receiver.next().await {
+                    if value == 0 {
+                        panic!("zero is not supported");
+                    }
+                    results.send(value).await.unwrap();
+                }
+            }
+        });
+
+        for value in &[1, 0, 2] {
+            sender.send(*value).await.unwrap();
+        }
+        assert_eq!(processed.next().await, Some(1));
+        assert_eq!(processed.next().await, Some(2));
+
+        let status = &supervisor.health().components["echo"];
+        assert_eq!(status.state, ComponentState::Running);
+        assert_eq!(status.restarts, 1);
+        assert_eq!(status.last_error.as_deref(), Some("zero is not supported"));
+
+        drop(sender);
+        handle.await.unwrap();
+        assert_eq!(
+            supervisor.health().components["echo"].state,
+            ComponentState::Finished
+        );
+        assert!(supervisor.health().healthy);
+    }
+
+    #[tokio::test]
+    async fn opens_circuit_breaker() {
+        let supervisor = Supervisor::new(policy());
+        let _handle = supervisor.spawn("faulty", || async { panic!("always fails") });
+
+        // The component is not restarted during the cooldown, so the state is final.
+        wait_for_state(&supervisor, "faulty", ComponentState::CircuitOpen).await;
+
+        let health = supervisor.health();
+        assert!(!health.healthy);
+        assert_eq!(
+            health.components["faulty"].state,
+            ComponentState::CircuitOpen
+        );
+        assert_eq!(health.components["faulty"].restarts, 3);
+    }
+}
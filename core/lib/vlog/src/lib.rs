@@ -6,19 +6,47 @@
 //!
 //! The format of the logs in stdout can be `plain` or` json` and is set by the `MISC_LOG_FORMAT` env variable.
 //!
+//! ## Correlation IDs
+//!
+//! To make it possible to trace a single transaction across the components, the logs are
+//! structured with the following fields:
+//!
+//! - `request_id`: ID of the API request (taken from the `X-Request-Id` header or generated via
+//!   [`new_correlation_id`]), attached as a span to everything logged while handling the request.
+//! - `tx_hash`: hash of the transaction, used by the API, mempool and state keeper.
+//! - `block_number`: number of the block the transaction was included into, used by the state keeper
+//!   and the committer.
+//! - `eth_op_id`, `block_from`, `block_to`: ID of the Ethereum operation and the range of blocks it
+//!   is sent for, used by the `eth_sender`.
+//!
+//! In the `json` format, fields of the current span and the list of the entered spans are
+//! attached to every log entry.
+//!
+//! With the `otlp` feature the spans can also be exported to an OpenTelemetry collector,
+//! see the `telemetry` module for details.
+//!
 //! Full documentation for the `tracing` crate here https://docs.rs/tracing/
 //!
 //! Integration with sentry for catching errors and react on them immediately
 //! https://docs.sentry.io/platforms/rust/
 //!
 
-use std::{borrow::Cow, str::FromStr};
+use std::{
+    borrow::Cow,
+    str::FromStr,
+    sync::atomic::{AtomicU64, Ordering},
+};
 
 pub use sentry;
 use sentry::{types::Dsn, ClientInitGuard};
+use tracing_subscriber::{registry::LookupSpan, util::SubscriberInitExt};
 
 pub use tracing as __tracing;
-pub use tracing::{debug, info, log, trace};
+pub use tracing::{debug, debug_span, info, info_span, log, trace, Instrument, Span};
+
+pub use telemetry::{set_remote_parent, trace_context_headers};
+
+mod telemetry;
 
 #[macro_export]
 macro_rules! warn {
@@ -53,6 +81,35 @@ fn get_sentry_url() -> Option<Dsn> {
     None
 }
 
+/// Generates a new correlation ID.
+///
+/// IDs are unique within the process and are prefixed with the process ID,
+/// so the IDs generated by different server components don't collide.
+pub fn new_correlation_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let sequence = COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("{:x}-{:x}", std::process::id(), sequence)
+}
+
+/// Installs the subscriber globally, extending it with the OTLP exporter if it's configured.
+fn init_subscriber<S>(subscriber: S)
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a> + Send + Sync + 'static,
+{
+    #[cfg(feature = "otlp")]
+    {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        if let Some(otlp_layer) = telemetry::otlp_layer() {
+            subscriber.with(otlp_layer).init();
+            return;
+        }
+    }
+
+    subscriber.init();
+}
+
 /// Initialize logging with tracing and set up log format
 ///
 /// If the sentry URL is provided via an environment variable, this function will also initialize sentry.
@@ -60,15 +117,20 @@ fn get_sentry_url() -> Option<Dsn> {
 /// https://docs.sentry.io/platforms/rust/#configure
 pub fn init() -> Option<ClientInitGuard> {
     let log_format = std::env::var("MISC_LOG_FORMAT").unwrap_or_else(|_| "plain".to_string());
+    let builder = tracing_subscriber::fmt::Subscriber::builder()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env());
     match log_format.as_str() {
-        "plain" => tracing_subscriber::fmt::init(),
+        "plain" => init_subscriber(builder.finish()),
         "json" => {
             let timer = tracing_subscriber::fmt::time::ChronoUtc::rfc3339();
-            tracing_subscriber::fmt::Subscriber::builder()
-                .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-                .with_timer(timer)
-                .json()
-                .init();
+            init_subscriber(
+                builder
+                    .with_timer(timer)
+                    .json()
+                    .with_current_span(true)
+                    .with_span_list(true)
+                    .finish(),
+            );
         }
         _ => panic!("MISC_LOG_FORMAT has an unexpected value {}", log_format),
     };
@@ -0,0 +1,118 @@
+//! Export of the spans to an OpenTelemetry collector (e.g. Jaeger or Tempo).
+//!
+//! Export is available with the `otlp` feature and is configured via the environment variables:
+//!
+//! - `MISC_OTLP_ENDPOINT`: address of the OTLP (gRPC) collector, e.g. `http://localhost:4317`.
+//!   Spans are not exported if the variable is not set or is set to `unset`.
+//! - `MISC_OTLP_SAMPLING_RATIO`: share of the traces to be exported, `1.0` by default.
+//!
+//! Components communicating through HTTP link their spans with the
+//! [W3C Trace Context](https://www.w3.org/TR/trace-context/) headers: the caller serializes the
+//! context of its span with [`trace_context_headers`] and the callee restores it with
+//! [`set_remote_parent`]. Inside a single process, the span should be passed along with the
+//! request sent through a channel and the handler should be instrumented with it.
+//!
+//! Without the `otlp` feature both functions do nothing.
+
+use std::collections::HashMap;
+
+use tracing::Span;
+
+/// Serializes the context of the span into the trace context headers.
+pub fn trace_context_headers(span: &Span) -> HashMap<String, String> {
+    #[allow(unused_mut)]
+    let mut headers = HashMap::new();
+
+    #[cfg(feature = "otlp")]
+    {
+        use opentelemetry::{
+            propagation::TextMapPropagator, sdk::propagation::TraceContextPropagator,
+        };
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+        TraceContextPropagator::new().inject_context(&span.context(), &mut headers);
+    }
+    #[cfg(not(feature = "otlp"))]
+    let _ = span;
+
+    headers
+}
+
+/// Makes the span a child of the remote span described by the trace context headers.
+/// Headers not related to the trace context are ignored.
+pub fn set_remote_parent<K, V>(span: &Span, headers: impl IntoIterator<Item = (K, V)>)
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    #[cfg(feature = "otlp")]
+    {
+        use opentelemetry::{
+            propagation::TextMapPropagator, sdk::propagation::TraceContextPropagator,
+        };
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+        let headers: HashMap<String, String> = headers
+            .into_iter()
+            .map(|(key, value)| (key.as_ref().to_lowercase(), value.as_ref().to_string()))
+            .collect();
+        span.set_parent(TraceContextPropagator::new().extract(&headers));
+    }
+    #[cfg(not(feature = "otlp"))]
+    let _ = (span, headers);
+}
+
+/// Creates a layer exporting the spans to the OTLP collector,
+/// or `None` if the collector endpoint is not configured.
+#[cfg(feature = "otlp")]
+pub(crate) fn otlp_layer<S>(
+) -> Option<tracing_opentelemetry::OpenTelemetryLayer<S, opentelemetry::sdk::trace::Tracer>>
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    use opentelemetry::{
+        sdk::{
+            trace::{self, Sampler},
+            Resource,
+        },
+        KeyValue,
+    };
+
+    let endpoint = std::env::var("MISC_OTLP_ENDPOINT")
+        .ok()
+        .filter(|endpoint| endpoint != "unset")?;
+    let sampling_ratio = std::env::var("MISC_OTLP_SAMPLING_RATIO")
+        .map(|ratio| {
+            ratio
+                .parse()
+                .expect("MISC_OTLP_SAMPLING_RATIO must be a floating point number")
+        })
+        .unwrap_or(1.0);
+    // All the components started within one binary are reported as a single service.
+    let service_name = std::env::current_exe()
+        .ok()
+        .and_then(|path| {
+            path.file_stem()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "zksync".to_string());
+
+    let trace_config = trace::config()
+        // Sampling decision of the remote parent is respected, so the trace is either
+        // exported by all the components or by none of them.
+        .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+            sampling_ratio,
+        ))))
+        .with_resource(Resource::new(vec![KeyValue::new(
+            "service.name",
+            service_name,
+        )]));
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .with_endpoint(endpoint)
+        .with_trace_config(trace_config)
+        .with_grpcio()
+        .install_simple()
+        .expect("failed to install the OTLP exporter");
+
+    Some(tracing_opentelemetry::layer().with_tracer(tracer))
+}
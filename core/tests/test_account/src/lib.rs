@@ -340,6 +340,9 @@ impl ZkSyncAccount {
                     panic!("CREATE2 ChangePubKey can only be executed for CREATE2 account");
                 }
             }
+            ChangePubKeyType::Guardians => {
+                panic!("Guardians ChangePubKey requires the signatures of the guardians")
+            }
         };
         change_pubkey.eth_auth_data = Some(eth_auth_data);
 
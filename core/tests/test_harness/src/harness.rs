@@ -0,0 +1,374 @@
+// Built-in deps
+use std::{collections::HashMap, time::Duration};
+// External uses
+use futures::{
+    channel::{mpsc, oneshot},
+    SinkExt, StreamExt,
+};
+use num::BigUint;
+use tokio::task::JoinHandle;
+// Workspace uses
+use zksync_core::{
+    committer::CommitRequest,
+    mempool::ProposedBlock,
+    state_keeper::{
+        start_state_keeper, StateKeeperRequest, ZkSyncStateInitParams, ZkSyncStateKeeper,
+    },
+};
+use zksync_types::{
+    block::{Block, PendingBlock},
+    mempool::SignedTxVariant,
+    Account, AccountId, AccountUpdates, Address, BlockNumber, PriorityOp, SignedZkSyncTx, TokenId,
+    H256,
+};
+// Local uses
+use crate::{
+    mock_l1::MockL1,
+    sim::{FaultConfig, FaultInjector, FaultPoint, SimClock},
+};
+
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Configuration of the in-process zkSync network.
+#[derive(Debug, Clone)]
+pub struct HarnessConfig {
+    /// Address of the operator account which collects fees.
+    pub fee_account: Address,
+    /// Supported block sizes in chunks, sorted in ascending order.
+    pub block_chunk_sizes: Vec<usize>,
+    /// Amount of mini-blocks after which the pending block is sealed.
+    pub max_miniblock_iterations: usize,
+    /// Amount of confirmations for every L1 transaction.
+    pub l1_confirmations: u64,
+    /// Faults to inject into the L1, database and prover interactions.
+    pub faults: FaultConfig,
+    /// Virtual time to wait before retrying a failed step.
+    pub retry_interval: Duration,
+}
+
+impl Default for HarnessConfig {
+    fn default() -> Self {
+        Self {
+            fee_account: Address::from_low_u64_be(0xfee),
+            block_chunk_sizes: vec![10, 32, 72],
+            // Blocks are sealed by the harness explicitly, so the state keeper
+            // should never seal them on its own.
+            max_miniblock_iterations: usize::MAX,
+            l1_confirmations: crate::mock_l1::DEFAULT_CONFIRMATIONS,
+            faults: FaultConfig::default(),
+            retry_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// In-memory replacement of the database tables touched by the committer.
+#[derive(Debug, Default)]
+pub struct MockStorage {
+    /// Sealed blocks that were received from the state keeper.
+    pub blocks: Vec<Block>,
+    /// Latest pending block reported by the state keeper.
+    pub pending_block: Option<PendingBlock>,
+    /// Account updates for each of the sealed blocks.
+    pub account_updates: HashMap<BlockNumber, AccountUpdates>,
+}
+
+impl MockStorage {
+    fn store(&mut self, request: CommitRequest) {
+        match request {
+            CommitRequest::PendingBlock((pending_block, _)) => {
+                self.pending_block = Some(pending_block);
+            }
+            CommitRequest::Block((block_commit_request, _)) => {
+                let block = block_commit_request.block;
+                self.pending_block = None;
+                self.account_updates
+                    .insert(block.block_number, block_commit_request.accounts_updated);
+                self.blocks.push(block);
+            }
+            CommitRequest::Flush(notify) => {
+                notify.send(()).unwrap_or_default();
+            }
+        }
+    }
+
+    pub fn last_block_number(&self) -> BlockNumber {
+        self.blocks
+            .last()
+            .map(|block| block.block_number)
+            .unwrap_or_default()
+    }
+}
+
+/// Spins up the zkSync state keeper in the current tokio runtime and connects
+/// it to the `MockL1` and `MockStorage` fakes, so that the full
+/// "deposit → block → commit → proof → execute" flow can be driven
+/// step-by-step from tests without `geth` or `Postgres`.
+///
+/// Every method waits until the state keeper processes all the previously
+/// sent requests, thus the outcome of each step is fully deterministic.
+pub struct TestHarness {
+    pub l1: MockL1,
+    pub storage: MockStorage,
+    config: HarnessConfig,
+    faults: FaultInjector,
+    last_reported_block: BlockNumber,
+    state_keeper_requests: mpsc::Sender<StateKeeperRequest>,
+    commit_requests: mpsc::Receiver<CommitRequest>,
+    state_keeper_task: JoinHandle<()>,
+}
+
+impl TestHarness {
+    /// Creates a network with the default configuration.
+    pub fn new() -> Self {
+        Self::with_config(HarnessConfig::default())
+    }
+
+    /// Creates a network with genesis state containing only the fee account.
+    ///
+    /// Must be called within the tokio runtime, since the state keeper is spawned as a task.
+    pub fn with_config(config: HarnessConfig) -> Self {
+        let mut genesis = ZkSyncStateInitParams::new();
+        genesis.insert_account(
+            AccountId(0),
+            Account::default_with_address(&config.fee_account),
+        );
+
+        let (state_keeper_requests, requests_receiver) = mpsc::channel(CHANNEL_CAPACITY);
+        let (commit_sender, commit_requests) = mpsc::channel(CHANNEL_CAPACITY);
+        let state_keeper = ZkSyncStateKeeper::new(
+            genesis,
+            config.fee_account,
+            requests_receiver,
+            commit_sender,
+            config.block_chunk_sizes.clone(),
+            config.max_miniblock_iterations,
+            config.max_miniblock_iterations,
+            None,
+        );
+        let state_keeper_task = start_state_keeper(state_keeper, None);
+
+        let faults = FaultInjector::new(config.faults.clone(), SimClock::default());
+        Self {
+            l1: MockL1::with_faults(config.l1_confirmations, faults.clone()),
+            storage: MockStorage::default(),
+            config,
+            faults,
+            last_reported_block: BlockNumber(0),
+            state_keeper_requests,
+            commit_requests,
+            state_keeper_task,
+        }
+    }
+
+    pub fn config(&self) -> &HarnessConfig {
+        &self.config
+    }
+
+    /// Returns the fault injector shared by all the simulated components.
+    pub fn faults(&self) -> &FaultInjector {
+        &self.faults
+    }
+
+    /// Returns the virtual clock of the simulation.
+    pub fn clock(&self) -> &SimClock {
+        self.faults.clock()
+    }
+
+    /// Requests a deposit on L1 and immediately reports it to the state keeper.
+    pub async fn deposit(
+        &mut self,
+        to: Address,
+        token: TokenId,
+        amount: impl Into<BigUint>,
+    ) -> PriorityOp {
+        let priority_op = self.l1.deposit(to, token, amount.into(), to).await;
+        self.process_priority_queue().await;
+        priority_op
+    }
+
+    /// Reports all the new L1 priority operations to the state keeper.
+    pub async fn process_priority_queue(&mut self) {
+        let priority_ops = self.l1.take_new_priority_ops();
+        if priority_ops.is_empty() {
+            return;
+        }
+
+        self.execute_miniblock(ProposedBlock {
+            priority_ops,
+            txs: Vec::new(),
+            l1_messages: Vec::new(),
+        })
+        .await;
+    }
+
+    /// Executes the provided transactions in the pending block.
+    pub async fn execute_txs(&mut self, txs: Vec<SignedZkSyncTx>) {
+        self.execute_miniblock(ProposedBlock {
+            priority_ops: Vec::new(),
+            txs: txs.into_iter().map(SignedTxVariant::from).collect(),
+            l1_messages: Vec::new(),
+        })
+        .await;
+    }
+
+    /// Executes an arbitrary mini-block proposal.
+    pub async fn execute_miniblock(&mut self, proposed_block: ProposedBlock) {
+        self.send_request(StateKeeperRequest::ExecuteMiniBlock(proposed_block))
+            .await;
+        self.sync().await;
+    }
+
+    /// Seals the pending block and returns all the blocks sealed since the last call
+    /// (the state keeper may seal blocks on its own if they don't fit into the largest block size).
+    pub async fn seal_block(&mut self) -> Vec<Block> {
+        self.send_request(StateKeeperRequest::SealBlock).await;
+        self.sync().await;
+
+        let last_reported_block = self.last_reported_block;
+        self.last_reported_block = self.storage.last_block_number();
+        self.storage
+            .blocks
+            .iter()
+            .filter(|block| block.block_number > last_reported_block)
+            .cloned()
+            .collect()
+    }
+
+    /// Commits all the sealed blocks that were not committed yet.
+    /// Returns the hash of the L1 transaction, if anything was committed.
+    pub async fn commit_blocks(&mut self) -> anyhow::Result<Option<H256>> {
+        self.faults.check(FaultPoint::Database)?;
+
+        let last_committed = self.l1.last_committed_block();
+        let blocks = self
+            .storage
+            .blocks
+            .iter()
+            .filter(|block| block.block_number > last_committed)
+            .cloned()
+            .collect::<Vec<_>>();
+        if blocks.is_empty() {
+            return Ok(None);
+        }
+
+        self.l1.commit_blocks(blocks).await.map(Some)
+    }
+
+    /// Publishes a proof for all the committed blocks.
+    pub async fn prove_blocks(&mut self) -> anyhow::Result<Option<H256>> {
+        let last_committed = self.l1.last_committed_block();
+        if last_committed == self.l1.last_proven_block() {
+            return Ok(None);
+        }
+
+        if self.faults.inject(FaultPoint::ProverTimeout) {
+            let timeout = self.faults.config().prover_timeout;
+            self.clock().advance(timeout);
+            anyhow::bail!("Prover didn't produce a proof within {:?}", timeout);
+        }
+
+        self.l1.prove_blocks(last_committed).await.map(Some)
+    }
+
+    /// Executes all the proven blocks.
+    pub async fn execute_blocks(&mut self) -> anyhow::Result<Option<H256>> {
+        let last_proven = self.l1.last_proven_block();
+        if last_proven == self.l1.last_executed_block() {
+            return Ok(None);
+        }
+
+        self.l1.execute_blocks(last_proven).await.map(Some)
+    }
+
+    /// Seals the pending block and passes it through the whole L1 pipeline.
+    pub async fn finalize_block(&mut self) -> anyhow::Result<Vec<Block>> {
+        let blocks = self.seal_block().await;
+        self.commit_blocks().await?;
+        self.prove_blocks().await?;
+        self.execute_blocks().await?;
+
+        Ok(blocks)
+    }
+
+    /// Passes all the sealed blocks through the L1 pipeline, retrying every failed step
+    /// after `retry_interval` of virtual time. Fails if any step fails `max_attempts` times in a row.
+    pub async fn execute_with_retries(&mut self, max_attempts: usize) -> anyhow::Result<()> {
+        let target_block = self.storage.last_block_number();
+        let mut failed_attempts = 0;
+        while self.l1.last_executed_block() < target_block {
+            let step_result = if self.l1.last_committed_block() < target_block {
+                self.commit_blocks().await
+            } else if self.l1.last_proven_block() < target_block {
+                self.prove_blocks().await
+            } else {
+                self.execute_blocks().await
+            };
+
+            match step_result {
+                Ok(_) => failed_attempts = 0,
+                Err(error) => {
+                    failed_attempts += 1;
+                    if failed_attempts >= max_attempts {
+                        return Err(error);
+                    }
+                    vlog::debug!("Simulated step failed, retrying: {}", error);
+                    self.clock().advance(self.config.retry_interval);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Loads the account state from the state keeper.
+    pub async fn account(&mut self, address: Address) -> Option<(AccountId, Account)> {
+        let (sender, receiver) = oneshot::channel();
+        self.send_request(StateKeeperRequest::GetAccount(address, sender))
+            .await;
+        receiver.await.expect("State keeper dropped the request")
+    }
+
+    /// Returns the balance of the account, or zero if account doesn't exist.
+    pub async fn balance(&mut self, address: Address, token: TokenId) -> BigUint {
+        self.account(address)
+            .await
+            .map(|(_, account)| account.get_balance(token))
+            .unwrap_or_default()
+    }
+
+    /// Stops the state keeper and waits for its task to finish.
+    pub async fn stop(self) {
+        drop(self.state_keeper_requests);
+        self.state_keeper_task
+            .await
+            .expect("State keeper task panicked");
+    }
+
+    async fn send_request(&mut self, request: StateKeeperRequest) {
+        self.state_keeper_requests
+            .send(request)
+            .await
+            .expect("State keeper is not running");
+    }
+
+    /// Waits until the state keeper handles all the requests sent so far
+    /// and moves its output into the `MockStorage`.
+    async fn sync(&mut self) {
+        // Requests are processed sequentially, so once we have a response for this one
+        // all the previous requests have been handled as well.
+        let (sender, receiver) = oneshot::channel();
+        self.send_request(StateKeeperRequest::GetLastUnprocessedPriorityOp(sender))
+            .await;
+        receiver.await.expect("State keeper dropped the request");
+
+        while let Ok(Some(request)) = self.commit_requests.try_next() {
+            self.storage.store(request);
+        }
+    }
+}
+
+impl Default for TestHarness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,35 @@
+//! In-process test harness for the zkSync server.
+//!
+//! Unlike `testkit`, this crate doesn't require `geth` or `Postgres` to be running:
+//! the state keeper is spawned in the current runtime and is connected to the
+//! in-memory fakes of the L1 contract (`MockL1`, built on top of `MockEthereum`)
+//! and of the database (`MockStorage`).
+//!
+//! Everything is driven explicitly by the test, which makes scenarios fast and
+//! reproducible:
+//!
+//! ```ignore
+//! let mut harness = TestHarness::new();
+//! harness.deposit(address, TokenId(0), 100u32).await;
+//! let blocks = harness.finalize_block().await?;
+//! assert_eq!(harness.l1.last_executed_block(), blocks[0].block_number);
+//! ```
+//!
+//! For the fault-tolerance scenarios the harness can be run in the simulation mode
+//! (see the `sim` module): failures of the L1 RPC, stuck L1 transactions, database
+//! errors and prover timeouts are injected according to a seeded `FaultConfig`,
+//! so every failing run can be reproduced by its seed.
+
+pub use self::{
+    harness::{HarnessConfig, MockStorage, TestHarness},
+    mock_l1::{MockL1, SentL1Tx},
+    sim::{FaultConfig, FaultInjector, FaultPoint, Scheduler, SimClock},
+};
+pub use zksync_test_account as zksync_account;
+
+pub mod harness;
+pub mod mock_l1;
+pub mod sim;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,396 @@
+//! In-memory model of the zkSync L1 contract.
+//!
+//! `MockL1` keeps the contract-side bookkeeping (priority queue, committed/proven/executed
+//! block counters) and routes every aggregated operation through the `MockEthereum` client,
+//! so that the recorded transactions can be inspected the same way `eth_sender` tests do.
+
+// Built-in deps
+use std::collections::VecDeque;
+// External uses
+use anyhow::{ensure, format_err};
+use num::BigUint;
+use web3::contract::Options;
+// Workspace uses
+use zksync_crypto::{ff::Field, proof::EncodedAggregatedProof};
+use zksync_eth_client::{clients::mock::MockEthereum, EthereumGateway};
+use zksync_types::{
+    aggregated_operations::{
+        AggregatedActionType, AggregatedOperation, BlocksCommitOperation, BlocksExecuteOperation,
+        BlocksProofOperation,
+    },
+    block::Block,
+    AccountId, Address, BlockNumber, Deposit, Fr, FullExit, PriorityOp, SerialId, TokenId,
+    ZkSyncPriorityOp, H256,
+};
+// Local uses
+use crate::sim::{FaultInjector, FaultPoint};
+
+/// Amount of L1 blocks during which the priority operation must be processed.
+/// Mirrors the `PRIORITY_EXPIRATION` constant of the contract.
+pub const PRIORITY_EXPIRATION: u64 = 35_000;
+/// Amount of confirmations every sent L1 transaction receives.
+pub const DEFAULT_CONFIRMATIONS: u64 = 1;
+
+/// L1 transaction sent by the harness on behalf of the operator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SentL1Tx {
+    pub action: AggregatedActionType,
+    pub block_range: (BlockNumber, BlockNumber),
+    pub hash: H256,
+    /// Whether the transaction was mined. Stuck transactions are never mined
+    /// and must be replaced by a new one with the same nonce.
+    pub mined: bool,
+}
+
+/// Deterministic in-process replacement for the zkSync contract deployed on Ethereum.
+#[derive(Debug)]
+pub struct MockL1 {
+    gateway: EthereumGateway,
+    faults: FaultInjector,
+    confirmations: u64,
+    operator_nonce: u64,
+
+    next_serial_id: SerialId,
+    /// Priority operations that were not executed yet.
+    priority_queue: VecDeque<PriorityOp>,
+    /// Amount of operations from `priority_queue` that were already reported to L2.
+    delivered_priority_ops: usize,
+
+    committed_blocks: Vec<Block>,
+    last_proven_block: BlockNumber,
+    last_executed_block: BlockNumber,
+    sent_txs: Vec<SentL1Tx>,
+}
+
+impl Default for MockL1 {
+    fn default() -> Self {
+        Self::new(DEFAULT_CONFIRMATIONS)
+    }
+}
+
+impl MockL1 {
+    /// Creates an empty L1 with genesis block only.
+    pub fn new(confirmations: u64) -> Self {
+        Self::with_faults(confirmations, FaultInjector::default())
+    }
+
+    /// Creates an empty L1 which fails RPC calls and leaves transactions
+    /// stuck according to the provided fault injector.
+    pub fn with_faults(confirmations: u64, faults: FaultInjector) -> Self {
+        Self {
+            gateway: EthereumGateway::Mock(MockEthereum::default()),
+            faults,
+            confirmations,
+            operator_nonce: 0,
+            next_serial_id: 0,
+            priority_queue: VecDeque::new(),
+            delivered_priority_ops: 0,
+            committed_blocks: Vec::new(),
+            last_proven_block: BlockNumber(0),
+            last_executed_block: BlockNumber(0),
+            sent_txs: Vec::new(),
+        }
+    }
+
+    /// Returns the underlying mock Ethereum client.
+    ///
+    /// Note that the client must not be cloned: `MockEthereum` requires unique access
+    /// to its state to emulate new L1 blocks.
+    pub fn gateway(&self) -> &EthereumGateway {
+        &self.gateway
+    }
+
+    /// Returns the current L1 block number.
+    pub async fn block_number(&self) -> u64 {
+        self.gateway
+            .block_number()
+            .await
+            .expect("Mock client never fails")
+            .as_u64()
+    }
+
+    /// Emulates mining of `count` empty L1 blocks.
+    pub async fn mine_blocks(&mut self, count: u64) {
+        let current = self.block_number().await;
+        self.mock()
+            .set_block_number((current + count).into())
+            .await
+            .expect("Mock client never fails");
+    }
+
+    /// Registers a deposit in the priority queue, as `depositETH` / `depositERC20` would do.
+    pub async fn deposit(
+        &mut self,
+        from: Address,
+        token: TokenId,
+        amount: BigUint,
+        to: Address,
+    ) -> PriorityOp {
+        let deposit = Deposit {
+            from,
+            token,
+            amount,
+            to,
+        };
+        self.add_priority_op(ZkSyncPriorityOp::Deposit(deposit))
+            .await
+    }
+
+    /// Registers a full exit request in the priority queue.
+    pub async fn full_exit(
+        &mut self,
+        account_id: AccountId,
+        eth_address: Address,
+        token: TokenId,
+    ) -> PriorityOp {
+        let full_exit = FullExit {
+            account_id,
+            eth_address,
+            token,
+        };
+        self.add_priority_op(ZkSyncPriorityOp::FullExit(full_exit))
+            .await
+    }
+
+    /// Returns priority operations which were not reported to L2 yet,
+    /// emulating the `eth_watch` polling.
+    pub fn take_new_priority_ops(&mut self) -> Vec<PriorityOp> {
+        let new_ops = self
+            .priority_queue
+            .iter()
+            .skip(self.delivered_priority_ops)
+            .cloned()
+            .collect::<Vec<_>>();
+        self.delivered_priority_ops += new_ops.len();
+        new_ops
+    }
+
+    /// Amount of priority operations that were not executed on L1 yet.
+    pub fn priority_queue_len(&self) -> usize {
+        self.priority_queue.len()
+    }
+
+    pub fn last_committed_block(&self) -> BlockNumber {
+        self.committed_blocks
+            .last()
+            .map(|block| block.block_number)
+            .unwrap_or_default()
+    }
+
+    pub fn last_proven_block(&self) -> BlockNumber {
+        self.last_proven_block
+    }
+
+    pub fn last_executed_block(&self) -> BlockNumber {
+        self.last_executed_block
+    }
+
+    /// Returns all the transactions sent to L1 in the order of sending.
+    pub fn sent_txs(&self) -> &[SentL1Tx] {
+        &self.sent_txs
+    }
+
+    /// Commits blocks, checking the same invariants as `commitBlocks` contract method:
+    /// blocks must be sequential, and priority operations must be processed in order.
+    pub async fn commit_blocks(&mut self, blocks: Vec<Block>) -> anyhow::Result<H256> {
+        ensure!(
+            !blocks.is_empty(),
+            "Attempt to commit an empty list of blocks"
+        );
+
+        let mut expected_block = self.last_committed_block() + 1;
+        let mut expected_priority_op = self
+            .committed_blocks
+            .last()
+            .map(|block| block.processed_priority_ops.1)
+            .unwrap_or_else(|| self.first_unexecuted_serial_id());
+        for block in &blocks {
+            ensure!(
+                block.block_number == expected_block,
+                "Block #{} was committed out of order, expected #{}",
+                *block.block_number,
+                *expected_block
+            );
+            ensure!(
+                block.processed_priority_ops.0 == expected_priority_op,
+                "Block #{} processes priority operations starting from {}, expected {}",
+                *block.block_number,
+                block.processed_priority_ops.0,
+                expected_priority_op
+            );
+            ensure!(
+                block.processed_priority_ops.1 <= self.next_serial_id,
+                "Block #{} processes priority operations that were not requested on L1",
+                *block.block_number
+            );
+            expected_block = block.block_number + 1;
+            expected_priority_op = block.processed_priority_ops.1;
+        }
+
+        let last_committed_block = self.committed_blocks.last().cloned().unwrap_or_else(|| {
+            Block::new(
+                BlockNumber(0),
+                Fr::zero(),
+                AccountId(0),
+                Vec::new(),
+                (0, 0),
+                0,
+                0.into(),
+                0.into(),
+                H256::default(),
+                0,
+            )
+        });
+        let operation = BlocksCommitOperation {
+            last_committed_block,
+            blocks: blocks.clone(),
+        };
+        let hash = self.send_operation(operation.into()).await?;
+        self.committed_blocks.extend(blocks);
+
+        Ok(hash)
+    }
+
+    /// Publishes a (mock) aggregated proof for the committed blocks up to `last_block`.
+    pub async fn prove_blocks(&mut self, last_block: BlockNumber) -> anyhow::Result<H256> {
+        ensure!(
+            last_block > self.last_proven_block && last_block <= self.last_committed_block(),
+            "Block #{} cannot be proven: last proven #{}, last committed #{}",
+            *last_block,
+            *self.last_proven_block,
+            *self.last_committed_block()
+        );
+
+        let operation = BlocksProofOperation {
+            blocks: self.blocks_range(self.last_proven_block + 1, last_block),
+            proof: EncodedAggregatedProof::default(),
+        };
+        let hash = self.send_operation(operation.into()).await?;
+        self.last_proven_block = last_block;
+
+        Ok(hash)
+    }
+
+    /// Executes the proven blocks up to `last_block`, removing the processed
+    /// operations from the priority queue.
+    pub async fn execute_blocks(&mut self, last_block: BlockNumber) -> anyhow::Result<H256> {
+        ensure!(
+            last_block > self.last_executed_block && last_block <= self.last_proven_block,
+            "Block #{} cannot be executed: last executed #{}, last proven #{}",
+            *last_block,
+            *self.last_executed_block,
+            *self.last_proven_block
+        );
+
+        let blocks = self.blocks_range(self.last_executed_block + 1, last_block);
+        let processed_ops: u64 = blocks
+            .iter()
+            .map(|block| block.number_of_processed_prior_ops())
+            .sum();
+
+        let operation = BlocksExecuteOperation { blocks };
+        let hash = self.send_operation(operation.into()).await?;
+
+        for _ in 0..processed_ops {
+            self.priority_queue
+                .pop_front()
+                .ok_or_else(|| format_err!("Executed more priority operations than requested"))?;
+            self.delivered_priority_ops -= 1;
+        }
+        self.last_executed_block = last_block;
+
+        Ok(hash)
+    }
+
+    async fn add_priority_op(&mut self, data: ZkSyncPriorityOp) -> PriorityOp {
+        let eth_block = self.block_number().await;
+        let serial_id = self.next_serial_id;
+        self.next_serial_id += 1;
+
+        let priority_op = PriorityOp {
+            serial_id,
+            data,
+            deadline_block: eth_block + PRIORITY_EXPIRATION,
+            // Hash is derived from the serial ID to keep the runs reproducible.
+            eth_hash: H256::from_low_u64_be(serial_id + 1),
+            eth_block,
+        };
+        self.priority_queue.push_back(priority_op.clone());
+        self.mine_blocks(1).await;
+
+        priority_op
+    }
+
+    fn first_unexecuted_serial_id(&self) -> SerialId {
+        self.priority_queue
+            .front()
+            .map(|op| op.serial_id)
+            .unwrap_or(self.next_serial_id)
+    }
+
+    fn blocks_range(&self, from: BlockNumber, to: BlockNumber) -> Vec<Block> {
+        self.committed_blocks
+            .iter()
+            .filter(|block| block.block_number >= from && block.block_number <= to)
+            .cloned()
+            .collect()
+    }
+
+    async fn send_operation(&mut self, operation: AggregatedOperation) -> anyhow::Result<H256> {
+        self.faults.check(FaultPoint::EthRpc)?;
+
+        let action = operation.get_action_type();
+        let block_range = operation.get_block_range();
+        let raw_tx = match &operation {
+            AggregatedOperation::CommitBlocks(op) => op.get_eth_tx_args(),
+            AggregatedOperation::PublishProofBlocksOnchain(op) => op.get_eth_tx_args(),
+            AggregatedOperation::ExecuteBlocks(op) => op.get_eth_tx_args(),
+            AggregatedOperation::CreateProofBlocks(_) => {
+                anyhow::bail!("`CreateProofBlocks` is not an L1 operation")
+            }
+        };
+        let raw_tx = self.gateway.encode_tx_data(&action.to_string(), raw_tx);
+
+        // Every attempt must be distinguishable by its hash, thus we bump the gas price
+        // for each replacement of a stuck transaction, just like `eth_sender` does.
+        let attempt = self
+            .sent_txs
+            .iter()
+            .rev()
+            .take_while(|tx| !tx.mined)
+            .count();
+        let options = Options {
+            nonce: Some(self.operator_nonce.into()),
+            gas_price: Some((100 + attempt).into()),
+            ..Default::default()
+        };
+
+        let signed_tx = self.gateway.sign_prepared_tx(raw_tx, options).await?;
+        let hash = self.gateway.send_raw_tx(signed_tx.raw_tx).await?;
+
+        let mined = !self.faults.inject(FaultPoint::StuckTx);
+        self.sent_txs.push(SentL1Tx {
+            action,
+            block_range,
+            hash,
+            mined,
+        });
+        if !mined {
+            anyhow::bail!("Transaction {:?} is stuck", hash);
+        }
+
+        let confirmations = self.confirmations;
+        self.mock()
+            .add_successfull_execution(hash, confirmations)
+            .await;
+        self.operator_nonce += 1;
+
+        Ok(hash)
+    }
+
+    fn mock(&mut self) -> &mut MockEthereum {
+        self.gateway
+            .get_mut_mock()
+            .expect("MockL1 always uses mock Ethereum client")
+    }
+}
@@ -0,0 +1,277 @@
+//! Deterministic simulation utilities.
+//!
+//! In the simulation mode every source of nondeterminism is controlled by the test:
+//!
+//! - `SimClock` provides the virtual time, which only moves when the test advances it.
+//! - `FaultInjector` decides (using an RNG seeded from the `FaultConfig`) whether an
+//!   Ethereum RPC call fails, whether a sent transaction gets stuck, whether a database
+//!   access fails and whether the prover times out.
+//! - `Scheduler` orders concurrent events by their virtual time, breaking ties with
+//!   the same seeded RNG, which allows to reproduce a particular interleaving (e.g. a race
+//!   between two mempool requests) by re-running the test with the same seed.
+//!
+//! Running the same scenario with the same seed always yields the same sequence of faults,
+//! which is recorded in the injector log and can be compared between runs.
+
+// Built-in deps
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    sync::{
+        atomic::{self, AtomicU64},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+// Workspace uses
+use zksync_crypto::rand::{Rng, SeedableRng, XorShiftRng};
+
+/// Virtual clock shared between all the simulated components.
+#[derive(Debug, Clone, Default)]
+pub struct SimClock {
+    millis: Arc<AtomicU64>,
+}
+
+impl SimClock {
+    /// Returns the time elapsed since the start of the simulation.
+    pub fn now(&self) -> Duration {
+        Duration::from_millis(self.millis.load(atomic::Ordering::SeqCst))
+    }
+
+    /// Moves the clock forward.
+    pub fn advance(&self, by: Duration) {
+        self.millis
+            .fetch_add(by.as_millis() as u64, atomic::Ordering::SeqCst);
+    }
+
+    /// Moves the clock to the provided point in time. Time never goes backwards.
+    pub fn advance_to(&self, at: Duration) {
+        self.millis
+            .fetch_max(at.as_millis() as u64, atomic::Ordering::SeqCst);
+    }
+}
+
+/// Place in the system where the fault may be injected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FaultPoint {
+    /// Ethereum RPC request returns an error.
+    EthRpc,
+    /// Sent Ethereum transaction is never mined (e.g. because of a low gas price).
+    StuckTx,
+    /// Database request returns an error.
+    Database,
+    /// Prover doesn't produce a proof within the timeout.
+    ProverTimeout,
+}
+
+/// Fault that was injected during the simulation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InjectedFault {
+    pub point: FaultPoint,
+    pub at: Duration,
+}
+
+/// Probabilities of the faults for each of the fault points.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FaultConfig {
+    /// Seed for all the random decisions taken during the simulation.
+    pub seed: u64,
+    pub eth_rpc_failure_rate: f64,
+    pub stuck_tx_rate: f64,
+    pub db_failure_rate: f64,
+    pub prover_timeout_rate: f64,
+    /// Virtual time consumed by a prover before it's considered timed out.
+    pub prover_timeout: Duration,
+}
+
+impl Default for FaultConfig {
+    /// Default configuration doesn't inject any faults.
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            eth_rpc_failure_rate: 0.0,
+            stuck_tx_rate: 0.0,
+            db_failure_rate: 0.0,
+            prover_timeout_rate: 0.0,
+            prover_timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+impl FaultConfig {
+    /// Creates a configuration with the same failure rate for every fault point.
+    pub fn uniform(seed: u64, failure_rate: f64) -> Self {
+        Self {
+            seed,
+            eth_rpc_failure_rate: failure_rate,
+            stuck_tx_rate: failure_rate,
+            db_failure_rate: failure_rate,
+            prover_timeout_rate: failure_rate,
+            ..Default::default()
+        }
+    }
+
+    fn rate(&self, point: FaultPoint) -> f64 {
+        match point {
+            FaultPoint::EthRpc => self.eth_rpc_failure_rate,
+            FaultPoint::StuckTx => self.stuck_tx_rate,
+            FaultPoint::Database => self.db_failure_rate,
+            FaultPoint::ProverTimeout => self.prover_timeout_rate,
+        }
+    }
+}
+
+/// Creates an RNG from the simulation seed.
+pub fn seeded_rng(seed: u64) -> XorShiftRng {
+    // `XorShiftRng` must not be seeded with all zeroes.
+    XorShiftRng::from_seed([seed as u32, (seed >> 32) as u32, 0x9E37_79B9, 0x7F4A_7C15])
+}
+
+#[derive(Debug)]
+struct FaultInjectorInner {
+    config: FaultConfig,
+    rng: XorShiftRng,
+    log: Vec<InjectedFault>,
+}
+
+/// Seeded source of the faults. Cloned handles share the same state,
+/// so the order of the decisions is defined by the order of calls.
+#[derive(Debug, Clone)]
+pub struct FaultInjector {
+    inner: Arc<Mutex<FaultInjectorInner>>,
+    clock: SimClock,
+}
+
+impl Default for FaultInjector {
+    fn default() -> Self {
+        Self::new(FaultConfig::default(), SimClock::default())
+    }
+}
+
+impl FaultInjector {
+    pub fn new(config: FaultConfig, clock: SimClock) -> Self {
+        let rng = seeded_rng(config.seed);
+        Self {
+            inner: Arc::new(Mutex::new(FaultInjectorInner {
+                config,
+                rng,
+                log: Vec::new(),
+            })),
+            clock,
+        }
+    }
+
+    pub fn clock(&self) -> &SimClock {
+        &self.clock
+    }
+
+    pub fn config(&self) -> FaultConfig {
+        self.inner.lock().unwrap().config.clone()
+    }
+
+    /// Decides whether the fault should be injected at the provided point.
+    /// Every injected fault is recorded in the log.
+    pub fn inject(&self, point: FaultPoint) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        let rate = inner.config.rate(point);
+        if rate <= 0.0 {
+            // Don't consume randomness for the disabled fault points, so enabling
+            // one kind of faults doesn't reshuffle the others.
+            return false;
+        }
+
+        let injected = inner.rng.gen::<f64>() < rate;
+        if injected {
+            let at = self.clock.now();
+            inner.log.push(InjectedFault { point, at });
+        }
+        injected
+    }
+
+    /// Returns an error if the fault should be injected at the provided point.
+    pub fn check(&self, point: FaultPoint) -> anyhow::Result<()> {
+        if self.inject(point) {
+            anyhow::bail!("Injected fault: {:?}", point);
+        }
+        Ok(())
+    }
+
+    /// Returns all the faults injected so far.
+    pub fn log(&self) -> Vec<InjectedFault> {
+        self.inner.lock().unwrap().log.clone()
+    }
+}
+
+#[derive(Debug)]
+struct ScheduledEvent<T> {
+    at: Duration,
+    tie_breaker: u64,
+    event: T,
+}
+
+impl<T> PartialEq for ScheduledEvent<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for ScheduledEvent<T> {}
+
+impl<T> PartialOrd for ScheduledEvent<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for ScheduledEvent<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.at, self.tie_breaker).cmp(&(other.at, other.tie_breaker))
+    }
+}
+
+/// Seeded event scheduler.
+///
+/// Events are delivered in the order of their virtual time. Events scheduled
+/// for the same moment are delivered in a pseudo-random (but reproducible) order.
+#[derive(Debug)]
+pub struct Scheduler<T> {
+    clock: SimClock,
+    rng: XorShiftRng,
+    queue: BinaryHeap<Reverse<ScheduledEvent<T>>>,
+}
+
+impl<T> Scheduler<T> {
+    pub fn new(seed: u64, clock: SimClock) -> Self {
+        Self {
+            clock,
+            rng: seeded_rng(seed),
+            queue: BinaryHeap::new(),
+        }
+    }
+
+    /// Schedules an event to happen after `delay` from the current virtual time.
+    pub fn schedule(&mut self, delay: Duration, event: T) {
+        let at = self.clock.now() + delay;
+        let tie_breaker = self.rng.gen();
+        self.queue.push(Reverse(ScheduledEvent {
+            at,
+            tie_breaker,
+            event,
+        }));
+    }
+
+    /// Returns the next event, advancing the clock to the moment it happens.
+    pub fn next_event(&mut self) -> Option<T> {
+        let Reverse(scheduled) = self.queue.pop()?;
+        self.clock.advance_to(scheduled.at);
+        Some(scheduled.event)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+}
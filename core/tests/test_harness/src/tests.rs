@@ -0,0 +1,200 @@
+use std::time::Duration;
+
+use num::BigUint;
+use zksync_test_account::ZkSyncAccount;
+use zksync_types::{
+    aggregated_operations::AggregatedActionType, tx::TimeRange, BlockNumber, SignedZkSyncTx,
+    TokenId, ZkSyncTx,
+};
+
+use crate::{FaultConfig, HarnessConfig, Scheduler, SimClock, TestHarness};
+
+const ETH: TokenId = TokenId(0);
+
+/// Checks the whole lifecycle of the deposit: from the L1 request to the block execution.
+#[tokio::test]
+async fn deposit_is_executed() {
+    let mut harness = TestHarness::new();
+    let account = ZkSyncAccount::rand();
+
+    harness.deposit(account.address, ETH, 1_000u32).await;
+    assert_eq!(harness.l1.priority_queue_len(), 1);
+
+    let blocks = harness.finalize_block().await.unwrap();
+    assert_eq!(blocks.len(), 1);
+    assert_eq!(blocks[0].block_number, BlockNumber(1));
+    assert_eq!(blocks[0].processed_priority_ops, (0, 1));
+
+    assert_eq!(harness.l1.last_executed_block(), BlockNumber(1));
+    assert_eq!(harness.l1.priority_queue_len(), 0);
+    assert_eq!(
+        harness.balance(account.address, ETH).await,
+        BigUint::from(1_000u32)
+    );
+
+    let actions = harness
+        .l1
+        .sent_txs()
+        .iter()
+        .map(|tx| tx.action)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        actions,
+        vec![
+            AggregatedActionType::CommitBlocks,
+            AggregatedActionType::PublishProofBlocksOnchain,
+            AggregatedActionType::ExecuteBlocks,
+        ]
+    );
+    for tx in harness.l1.sent_txs() {
+        let status = harness
+            .l1
+            .gateway()
+            .get_tx_status(tx.hash)
+            .await
+            .unwrap()
+            .expect("Sent tx must have a status");
+        assert!(status.success);
+    }
+
+    harness.stop().await;
+}
+
+/// Checks that L2 transactions can be executed on top of the deposited funds.
+#[tokio::test]
+async fn transfer_is_executed() {
+    let mut harness = TestHarness::new();
+    let sender = ZkSyncAccount::rand();
+    let recipient = ZkSyncAccount::rand();
+
+    harness.deposit(sender.address, ETH, 1_000u32).await;
+    harness.finalize_block().await.unwrap();
+
+    let (sender_id, _) = harness.account(sender.address).await.unwrap();
+    sender.set_account_id(Some(sender_id));
+    let (transfer, _) = sender.sign_transfer(
+        ETH,
+        "ETH",
+        BigUint::from(400u32),
+        BigUint::from(0u32),
+        &recipient.address,
+        None,
+        true,
+        TimeRange::default(),
+    );
+    harness
+        .execute_txs(vec![SignedZkSyncTx {
+            tx: ZkSyncTx::Transfer(Box::new(transfer)),
+            eth_sign_data: None,
+        }])
+        .await;
+
+    let blocks = harness.finalize_block().await.unwrap();
+    assert_eq!(blocks.len(), 1);
+    assert_eq!(blocks[0].block_number, BlockNumber(2));
+    assert_eq!(harness.l1.last_executed_block(), BlockNumber(2));
+
+    assert_eq!(
+        harness.balance(sender.address, ETH).await,
+        BigUint::from(600u32)
+    );
+    assert_eq!(
+        harness.balance(recipient.address, ETH).await,
+        BigUint::from(400u32)
+    );
+
+    harness.stop().await;
+}
+
+/// Checks that `MockL1` enforces the same ordering rules as the contract.
+#[tokio::test]
+async fn blocks_must_be_committed_in_order() {
+    let mut harness = TestHarness::new();
+    let account = ZkSyncAccount::rand();
+
+    harness.deposit(account.address, ETH, 1u32).await;
+    harness.seal_block().await;
+    harness.deposit(account.address, ETH, 1u32).await;
+    let second_block = harness.seal_block().await;
+
+    assert!(harness.l1.commit_blocks(second_block).await.is_err());
+    assert!(harness.l1.prove_blocks(BlockNumber(1)).await.is_err());
+
+    harness.commit_blocks().await.unwrap();
+    assert_eq!(harness.l1.last_committed_block(), BlockNumber(2));
+    assert!(harness.l1.execute_blocks(BlockNumber(1)).await.is_err());
+
+    harness.stop().await;
+}
+
+/// Runs a simple deposit scenario with the provided faults and returns the fault log
+/// along with the L1 transactions sent.
+async fn run_faulty_scenario(faults: FaultConfig) -> (Vec<crate::sim::InjectedFault>, usize) {
+    let mut harness = TestHarness::with_config(HarnessConfig {
+        faults,
+        ..Default::default()
+    });
+    // Accounts are created deterministically, so the scenario doesn't depend on `thread_rng`.
+    let recipients = (1..=3u64)
+        .map(zksync_types::Address::from_low_u64_be)
+        .collect::<Vec<_>>();
+
+    for recipient in &recipients {
+        harness.deposit(*recipient, ETH, 10u32).await;
+        harness.seal_block().await;
+    }
+    harness.execute_with_retries(100).await.unwrap();
+
+    assert_eq!(harness.l1.last_executed_block(), BlockNumber(3));
+    assert_eq!(harness.l1.priority_queue_len(), 0);
+    for recipient in recipients {
+        assert_eq!(harness.balance(recipient, ETH).await, BigUint::from(10u32));
+    }
+
+    let log = harness.faults().log();
+    let sent_txs = harness.l1.sent_txs().len();
+    harness.stop().await;
+    (log, sent_txs)
+}
+
+/// Checks that the network recovers from the injected faults and that
+/// the same seed always produces the same sequence of faults.
+#[tokio::test]
+async fn faults_are_reproducible() {
+    let (clean_log, clean_txs) = run_faulty_scenario(FaultConfig::default()).await;
+    assert!(clean_log.is_empty());
+
+    let faults = FaultConfig::uniform(42, 0.3);
+    let (first_log, first_txs) = run_faulty_scenario(faults.clone()).await;
+    let (second_log, second_txs) = run_faulty_scenario(faults).await;
+
+    assert!(!first_log.is_empty(), "Faults were not injected");
+    assert!(first_txs >= clean_txs);
+    assert_eq!(first_log, second_log);
+    assert_eq!(first_txs, second_txs);
+}
+
+/// Checks that the scheduler orders events by time and resolves ties deterministically.
+#[test]
+fn scheduler_is_deterministic() {
+    let run = |seed| {
+        let clock = SimClock::default();
+        let mut scheduler = Scheduler::new(seed, clock.clone());
+        scheduler.schedule(Duration::from_secs(2), "late");
+        for event in &["a", "b", "c", "d"] {
+            scheduler.schedule(Duration::from_secs(1), *event);
+        }
+
+        let mut events = Vec::new();
+        while let Some(event) = scheduler.next_event() {
+            events.push(event);
+        }
+        assert_eq!(clock.now(), Duration::from_secs(2));
+        events
+    };
+
+    let events = run(1);
+    assert_eq!(events.len(), 5);
+    assert_eq!(events.last(), Some(&"late"));
+    assert_eq!(events, run(1));
+}
@@ -311,6 +311,7 @@ impl TestSetup {
         let block = ProposedBlock {
             priority_ops: Vec::new(),
             txs: vec![SignedTxVariant::from(SignedZkSyncTx::from(tx))],
+            l1_messages: Vec::new(),
         };
 
         // Request miniblock execution.
@@ -393,6 +394,7 @@ impl TestSetup {
         let block = ProposedBlock {
             priority_ops: vec![op],
             txs: Vec::new(),
+            l1_messages: Vec::new(),
         };
 
         // Request miniblock execution.
@@ -765,7 +767,7 @@ impl TestSetup {
                 CommitRequest::Block((new_block, _)) => {
                     return new_block;
                 }
-                CommitRequest::PendingBlock(_) => {
+                CommitRequest::PendingBlock(_) | CommitRequest::Flush(_) => {
                     // Pending blocks are ignored.
                 }
             }
@@ -791,6 +793,9 @@ impl TestSetup {
             CommitRequest::PendingBlock(_) => {
                 // Nothing to be done.
             }
+            CommitRequest::Flush(_) => {
+                panic!("Expected pending block, got flush request");
+            }
         }
     }
 
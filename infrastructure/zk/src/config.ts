@@ -20,7 +20,14 @@ const CONFIG_FILES = [
     'prover.toml',
     'rust.toml',
     'private.toml',
-    'forced_exit_requests.toml'
+    'forced_exit_requests.toml',
+    'webhooks.toml',
+    'event_stream.toml',
+    'dust_collector.toml',
+    'faucet.toml',
+    'failover.toml',
+    'screening.toml',
+    'failure_policy.toml'
 ];
 
 async function getEnvironment(): Promise<string> {
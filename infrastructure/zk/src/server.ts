@@ -42,6 +42,14 @@ export async function genesis() {
     env.modify_contracts_toml('CONTRACTS_GENESIS_ROOT', genesisRoot);
 }
 
+export async function auditReplay() {
+    await utils.spawn('cargo run --bin zksync_server --release -- --audit-replay | tee audit-report.json');
+}
+
+export async function snapshotSync(peerUrl: string) {
+    await utils.spawn(`cargo run --bin zksync_server --release -- --snapshot-sync ${peerUrl}`);
+}
+
 // This functions deposits funds onto the forced exit sender account
 // This is needed to make sure that it has the account id
 async function prepareForcedExitRequestAccount() {
@@ -88,9 +96,15 @@ async function prepareForcedExitRequestAccount() {
 export const command = new Command('server')
     .description('start zksync server')
     .option('--genesis', 'generate genesis data via server')
+    .option('--audit-replay', 'replay all the stored blocks from genesis and print the signed report')
+    .option('--snapshot-sync <peer-url>', 'initialize the empty database with the state snapshot of another server')
     .action(async (cmd: Command) => {
         if (cmd.genesis) {
             await genesis();
+        } else if (cmd.auditReplay) {
+            await auditReplay();
+        } else if (cmd.snapshotSync) {
+            await snapshotSync(cmd.snapshotSync);
         } else {
             await server();
         }
@@ -1,6 +1,7 @@
 pub use jsonrpc_core::types::response::Failure as RpcFailure;
 use thiserror::Error;
 use zksync_eth_signer::error::SignerError;
+use zksync_types::api_error::{ApiErrorCode, ApiErrorInfo};
 
 #[derive(Debug, Error, PartialEq)]
 pub enum ClientError {
@@ -21,6 +22,8 @@ pub enum ClientError {
     UnknownToken,
     #[error("Incorrect address")]
     IncorrectAddress,
+    #[error("Invalid address checksum")]
+    InvalidAddressChecksum,
 
     #[error("Operation timeout")]
     OperationTimeout,
@@ -44,3 +47,21 @@ pub enum ClientError {
     #[error("Other")]
     Other,
 }
+
+impl ClientError {
+    /// Returns the stable code of the error returned by the zkSync API.
+    pub fn api_error_code(&self) -> Option<ApiErrorCode> {
+        match self {
+            Self::RpcError(failure) => api_error_info(&failure.error).map(|info| info.error_type),
+            _ => None,
+        }
+    }
+}
+
+/// Decodes the name and the category of the API error passed in the `data` field of the JSON RPC error.
+pub(crate) fn api_error_info(error: &jsonrpc_core::Error) -> Option<ApiErrorInfo> {
+    error
+        .data
+        .clone()
+        .and_then(|data| serde_json::from_value(data).ok())
+}
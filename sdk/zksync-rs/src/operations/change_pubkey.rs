@@ -3,7 +3,7 @@ use zksync_eth_signer::EthereumSigner;
 use zksync_types::{
     helpers::{closest_packable_fee_amount, is_fee_amount_packable},
     tokens::TxFeeTypes,
-    tx::ChangePubKeyType,
+    tx::{ChangePubKeyCREATE2Data, ChangePubKeyType},
     Nonce, Token, TokenLike, ZkSyncTx,
 };
 
@@ -16,6 +16,7 @@ use zksync_types::tokens::ChangePubKeyFeeTypeArg;
 pub struct ChangePubKeyBuilder<'a, S: EthereumSigner, P: Provider> {
     wallet: &'a Wallet<S, P>,
     onchain_auth: bool,
+    create2_data: Option<ChangePubKeyCREATE2Data>,
     fee_token: Option<Token>,
     fee: Option<BigUint>,
     nonce: Option<Nonce>,
@@ -33,6 +34,7 @@ where
         Self {
             wallet,
             onchain_auth: false,
+            create2_data: None,
             fee_token: None,
             fee: None,
             nonce: None,
@@ -50,19 +52,20 @@ where
         let fee = match self.fee {
             Some(fee) => fee,
             None => {
+                let auth_type = if self.create2_data.is_some() {
+                    ChangePubKeyType::CREATE2
+                } else if self.onchain_auth {
+                    ChangePubKeyType::Onchain
+                } else {
+                    ChangePubKeyType::ECDSA
+                };
                 let fee = self
                     .wallet
                     .provider
                     .get_tx_fee(
-                        if self.onchain_auth {
-                            TxFeeTypes::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
-                                ChangePubKeyType::Onchain,
-                            ))
-                        } else {
-                            TxFeeTypes::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
-                                ChangePubKeyType::ECDSA,
-                            ))
-                        },
+                        TxFeeTypes::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
+                            auth_type,
+                        )),
                         self.wallet.address(),
                         fee_token.id,
                     )
@@ -85,13 +88,22 @@ where
 
         let time_range = Default::default();
 
-        Ok(ZkSyncTx::from(
-            self.wallet
-                .signer
-                .sign_change_pubkey_tx(nonce, self.onchain_auth, fee_token, fee, time_range)
-                .await
-                .map_err(ClientError::SigningError)?,
-        ))
+        let signer = &self.wallet.signer;
+        let change_pubkey = match self.create2_data {
+            Some(create2_data) => {
+                signer
+                    .sign_change_pubkey_tx_create2(nonce, create2_data, fee_token, fee, time_range)
+                    .await
+            }
+            None => {
+                signer
+                    .sign_change_pubkey_tx(nonce, self.onchain_auth, fee_token, fee, time_range)
+                    .await
+            }
+        }
+        .map_err(ClientError::SigningError)?;
+
+        Ok(ZkSyncTx::from(change_pubkey))
     }
 
     /// Sends the transaction, returning the handle for its awaiting.
@@ -143,6 +155,14 @@ where
         Ok(self)
     }
 
+    /// Authorizes the key change with the CREATE2 data of the smart contract wallet instead of
+    /// the Ethereum signature. The wallet address must be derived from the data and the new
+    /// public key hash.
+    pub fn create2(mut self, create2_data: ChangePubKeyCREATE2Data) -> Self {
+        self.create2_data = Some(create2_data);
+        self
+    }
+
     /// Sets the transaction nonce.
     pub fn nonce(mut self, nonce: Nonce) -> Self {
         self.nonce = Some(nonce);
@@ -111,6 +111,10 @@ impl<P: Provider> SyncTransactionHandle<P> {
             }
 
             let response = self.provider.tx_info(self.hash).await?;
+            // Transactions expired in the mempool fail without being included into a block.
+            if !response.executed && response.success == Some(false) {
+                return Ok(response);
+            }
             if let Some(block) = &response.block {
                 if condition(block) {
                     return Ok(response);
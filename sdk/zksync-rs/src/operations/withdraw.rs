@@ -10,7 +10,11 @@ use zksync_types::{
 };
 
 use crate::{
-    error::ClientError, operations::SyncTransactionHandle, provider::Provider, wallet::Wallet,
+    error::ClientError,
+    operations::SyncTransactionHandle,
+    provider::Provider,
+    utils::parse_address,
+    wallet::Wallet,
 };
 
 #[derive(Debug)]
@@ -173,12 +177,10 @@ where
     /// Same as `WithdrawBuilder::to`, but accepts a string address value.
     ///
     /// Provided string value must be a correct address in a hexadecimal form,
-    /// otherwise an error will be returned.
+    /// otherwise an error will be returned. The EIP-55 checksum of the address is checked
+    /// according to `Wallet::address_checksum`, so the mistyped addresses are rejected.
     pub fn str_to(mut self, to: impl AsRef<str>) -> Result<Self, ClientError> {
-        let to: Address = to
-            .as_ref()
-            .parse()
-            .map_err(|_| ClientError::IncorrectAddress)?;
+        let to = parse_address(to, self.wallet.address_checksum)?;
 
         self.to = Some(to);
         Ok(self)
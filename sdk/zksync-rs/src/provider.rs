@@ -11,6 +11,7 @@ use num::BigUint;
 
 // Workspace uses
 use zksync_types::{
+    api_error::{ApiErrorCategory, ApiErrorCode},
     network::Network,
     tx::{PackedEthSignature, TxHash, ZkSyncTx},
     Address, TokenLike, TxFeeTypes,
@@ -18,7 +19,10 @@ use zksync_types::{
 
 // Local uses
 use self::messages::JsonRpcRequest;
-use crate::{error::ClientError, types::*};
+use crate::{
+    error::{api_error_info, ClientError},
+    types::*,
+};
 
 /// Returns a corresponding address for a provided network name.
 pub fn get_rpc_addr(network: Network) -> &'static str {
@@ -254,16 +258,22 @@ impl RpcProvider {
             let result = self.post_raw(&message).await;
 
             /// Determines if the error code is recoverable or not.
-            fn is_recoverable(code: &ErrorCode) -> bool {
-                code == &ErrorCode::InternalError
-                // This is a communication error code, so we can make attempt to retry request.
-                || code == &ErrorCode::ServerError(300)
+            fn is_recoverable(error: &jsonrpc_core::Error) -> bool {
+                // Internal faults of the server, e.g. the communication errors,
+                // so we can make attempt to retry request.
+                match api_error_info(error) {
+                    Some(info) => info.category == ApiErrorCategory::Internal,
+                    None => {
+                        error.code == ErrorCode::InternalError
+                            || error.code == ErrorCode::ServerError(ApiErrorCode::RPC_OTHER_CODE)
+                    }
+                }
             }
 
             let should_retry = match result.as_ref() {
                 Err(ClientError::NetworkError(..)) => true,
-                Err(ClientError::RpcError(fail)) => is_recoverable(&fail.error.code),
-                Ok(Output::Failure(fail)) => is_recoverable(&fail.error.code),
+                Err(ClientError::RpcError(fail)) => is_recoverable(&fail.error),
+                Ok(Output::Failure(fail)) => is_recoverable(&fail.error),
                 _ => false,
             };
 
@@ -2,11 +2,15 @@
 use std::fmt;
 use zksync_eth_signer::error::SignerError;
 use zksync_eth_signer::EthereumSigner;
-use zksync_types::tx::{ChangePubKeyECDSAData, ChangePubKeyEthAuthData, TimeRange, TxEthSignature};
+use zksync_types::tx::{
+    ChangePubKeyCREATE2Data, ChangePubKeyECDSAData, ChangePubKeyEthAuthData, SignatureDomain,
+    TimeRange, TxEthSignature,
+};
 // External uses
 use num::BigUint;
 // Workspace uses
 use zksync_crypto::PrivateKey;
+use zksync_types::nonce_reservation::NonceReservationRequest;
 use zksync_types::tx::{ChangePubKey, PackedEthSignature};
 use zksync_types::{
     AccountId, Address, ForcedExit, Nonce, PubKeyHash, Token, Transfer, Withdraw, H256,
@@ -24,6 +28,7 @@ pub struct Signer<S: EthereumSigner> {
     pub(crate) private_key: PrivateKey,
     pub(crate) eth_signer: Option<S>,
     pub(crate) account_id: Option<AccountId>,
+    pub(crate) signature_domain: Option<SignatureDomain>,
 }
 
 impl<S: EthereumSigner> fmt::Debug for Signer<S> {
@@ -49,6 +54,7 @@ impl<S: EthereumSigner> Signer<S> {
             address,
             eth_signer,
             account_id: None,
+            signature_domain: None,
         }
     }
 
@@ -73,17 +79,35 @@ impl<S: EthereumSigner> Signer<S> {
         self.account_id
     }
 
-    pub async fn sign_change_pubkey_tx(
+    /// Sets the network the Ethereum signatures of the transactions are bound to.
+    /// Required by the servers running the protocol version `SIGNATURE_DOMAIN_PROTOCOL_VERSION` or newer.
+    pub fn set_signature_domain(&mut self, signature_domain: Option<SignatureDomain>) {
+        self.signature_domain = signature_domain;
+    }
+
+    pub fn get_signature_domain(&self) -> Option<SignatureDomain> {
+        self.signature_domain
+    }
+
+    async fn sign_eth_message(
+        &self,
+        signer: &S,
+        message: String,
+    ) -> Result<TxEthSignature, SignerError> {
+        let message = SignatureDomain::apply_optional(self.signature_domain.as_ref(), message);
+        signer.sign_message(message.as_bytes()).await
+    }
+
+    fn new_change_pubkey_tx(
         &self,
         nonce: Nonce,
-        auth_onchain: bool,
         fee_token: Token,
         fee: BigUint,
         time_range: TimeRange,
     ) -> Result<ChangePubKey, SignerError> {
         let account_id = self.account_id.ok_or(SignerError::NoSigningKey)?;
 
-        let mut change_pubkey = ChangePubKey::new_signed(
+        ChangePubKey::new_signed(
             account_id,
             self.address,
             self.pubkey_hash,
@@ -94,7 +118,18 @@ impl<S: EthereumSigner> Signer<S> {
             None,
             &self.private_key,
         )
-        .map_err(signing_failed_error)?;
+        .map_err(signing_failed_error)
+    }
+
+    pub async fn sign_change_pubkey_tx(
+        &self,
+        nonce: Nonce,
+        auth_onchain: bool,
+        fee_token: Token,
+        fee: BigUint,
+        time_range: TimeRange,
+    ) -> Result<ChangePubKey, SignerError> {
+        let mut change_pubkey = self.new_change_pubkey_tx(nonce, fee_token, fee, time_range)?;
 
         let eth_auth_data = if auth_onchain {
             ChangePubKeyEthAuthData::Onchain
@@ -134,6 +169,28 @@ impl<S: EthereumSigner> Signer<S> {
         Ok(change_pubkey)
     }
 
+    /// Signs the `ChangePubKey` transaction of the smart contract wallet deployed with CREATE2.
+    /// Such transaction is authorized by the account address itself, which must be derived from
+    /// the CREATE2 data and the new public key hash, so the Ethereum signature is not required.
+    pub async fn sign_change_pubkey_tx_create2(
+        &self,
+        nonce: Nonce,
+        create2_data: ChangePubKeyCREATE2Data,
+        fee_token: Token,
+        fee: BigUint,
+        time_range: TimeRange,
+    ) -> Result<ChangePubKey, SignerError> {
+        if create2_data.get_address(&self.pubkey_hash) != self.address {
+            return Err(SignerError::CustomError(
+                "CREATE2 data doesn't match the account address".to_string(),
+            ));
+        }
+
+        let mut change_pubkey = self.new_change_pubkey_tx(nonce, fee_token, fee, time_range)?;
+        change_pubkey.eth_auth_data = Some(ChangePubKeyEthAuthData::CREATE2(create2_data));
+        Ok(change_pubkey)
+    }
+
     pub async fn sign_transfer(
         &self,
         token: Token,
@@ -161,7 +218,7 @@ impl<S: EthereumSigner> Signer<S> {
         let eth_signature = match &self.eth_signer {
             Some(signer) => {
                 let message = transfer.get_ethereum_sign_message(&token.symbol, token.decimals);
-                let signature = signer.sign_message(&message.as_bytes()).await?;
+                let signature = self.sign_eth_message(signer, message).await?;
 
                 if let TxEthSignature::EthereumSignature(packed_signature) = signature {
                     Some(packed_signature)
@@ -202,7 +259,7 @@ impl<S: EthereumSigner> Signer<S> {
         let eth_signature = match &self.eth_signer {
             Some(signer) => {
                 let message = withdraw.get_ethereum_sign_message(&token.symbol, token.decimals);
-                let signature = signer.sign_message(&message.as_bytes()).await?;
+                let signature = self.sign_eth_message(signer, message).await?;
 
                 if let TxEthSignature::EthereumSignature(packed_signature) = signature {
                     Some(packed_signature)
@@ -240,7 +297,7 @@ impl<S: EthereumSigner> Signer<S> {
         let eth_signature = match &self.eth_signer {
             Some(signer) => {
                 let message = forced_exit.get_ethereum_sign_message(&token.symbol, token.decimals);
-                let signature = signer.sign_message(&message.as_bytes()).await?;
+                let signature = self.sign_eth_message(signer, message).await?;
 
                 if let TxEthSignature::EthereumSignature(packed_signature) = signature {
                     Some(packed_signature)
@@ -253,4 +310,44 @@ impl<S: EthereumSigner> Signer<S> {
 
         Ok((forced_exit, eth_signature))
     }
+
+    /// Signs the request to reserve `count` nonces of the account, so the transactions
+    /// with these nonces can be signed and sent concurrently.
+    /// `timestamp` is the current time in milliseconds, it must be unique for every request.
+    pub async fn sign_nonce_reservation(
+        &self,
+        count: u32,
+        timestamp: u64,
+    ) -> Result<NonceReservationRequest, SignerError> {
+        let eth_signer = self
+            .eth_signer
+            .as_ref()
+            .ok_or(SignerError::MissingEthSigner)?;
+
+        let message = NonceReservationRequest::message(
+            self.address,
+            count,
+            timestamp,
+            self.signature_domain.as_ref(),
+        );
+        let signature = eth_signer
+            .sign_message(message.as_bytes())
+            .await
+            .map_err(signing_failed_error)?;
+        let signature = match signature {
+            TxEthSignature::EthereumSignature(packed_signature) => packed_signature,
+            TxEthSignature::EIP1271Signature(..) => {
+                return Err(SignerError::CustomError(
+                    "Can't sign nonce reservation request with EIP1271 signer".to_string(),
+                ))
+            }
+        };
+
+        Ok(NonceReservationRequest {
+            address: self.address,
+            count,
+            timestamp,
+            signature,
+        })
+    }
 }
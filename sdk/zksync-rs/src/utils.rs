@@ -8,16 +8,37 @@ use zksync_crypto::bellman::{pairing::ff::PrimeField, PrimeFieldRepr};
 use zksync_crypto::franklin_crypto::alt_babyjubjub::fs::FsRepr;
 use zksync_crypto::{priv_key_from_fs, Fs, PrivateKey};
 use zksync_eth_signer::EthereumSigner;
-use zksync_types::{AccountId, U256};
+use zksync_types::{address_checksum::AddressError, AccountId, Address, U256};
 
 use crate::{error::ClientError, provider::Provider, wallet::Wallet};
 
 // Public re-exports.
+pub use zksync_types::address_checksum::{to_checksum_address, ChecksumStrictness};
 pub use zksync_types::helpers::{
     closest_greater_or_eq_packable_fee_amount, closest_greater_or_eq_packable_token_amount,
     closest_packable_fee_amount, closest_packable_token_amount, is_fee_amount_packable,
-    is_token_amount_packable, pack_fee_amount, pack_token_amount,
+    is_token_amount_packable, pack_fee_amount, pack_token_amount, PackableAmounts,
 };
+pub use zksync_types::token_amount::{
+    format_token_amount, format_token_amount_rounded, parse_token_amount,
+    parse_token_amount_rounded, AmountParseError, Rounding,
+};
+
+/// Parses the address in hex, checking its EIP-55 checksum according to the strictness.
+///
+/// Malformed addresses are reported as `ClientError::IncorrectAddress`, and the addresses
+/// with the wrong checksum (most likely mistyped) as `ClientError::InvalidAddressChecksum`.
+pub fn parse_address(
+    value: impl AsRef<str>,
+    strictness: ChecksumStrictness,
+) -> Result<Address, ClientError> {
+    zksync_types::address_checksum::parse_address(value.as_ref(), strictness).map_err(|err| {
+        match err {
+            AddressError::Malformed(_) => ClientError::IncorrectAddress,
+            AddressError::BadChecksum(_) => ClientError::InvalidAddressChecksum,
+        }
+    })
+}
 
 /// Generates a new `PrivateKey` from seed using a deterministic algorithm:
 /// seed is hashed via `sha256` hash (twice), and the output treated as a `PrivateKey`.
@@ -1,6 +1,6 @@
 use num::BigUint;
 use zksync_eth_signer::EthereumSigner;
-use zksync_types::{AccountId, Address, TokenLike};
+use zksync_types::{tx::SignatureDomain, AccountId, Address, TokenLike};
 
 use crate::{
     credentials::WalletCredentials,
@@ -11,6 +11,7 @@ use crate::{
     signer::Signer,
     tokens_cache::TokensCache,
     types::{AccountInfo, BlockStatus},
+    utils::ChecksumStrictness,
 };
 
 #[derive(Debug)]
@@ -18,6 +19,9 @@ pub struct Wallet<S: EthereumSigner, P: Provider> {
     pub provider: P,
     pub signer: Signer<S>,
     pub tokens: TokensCache,
+    /// How strictly the checksums of the addresses passed as strings are checked,
+    /// `ChecksumStrictness::MixedCase` by default.
+    pub address_checksum: ChecksumStrictness,
 }
 
 impl<S, P> Wallet<S, P>
@@ -37,6 +41,7 @@ where
             provider,
             signer,
             tokens,
+            address_checksum: ChecksumStrictness::default(),
         })
     }
 
@@ -51,6 +56,24 @@ where
         Ok(())
     }
 
+    /// Binds the Ethereum signatures of the transactions to the network the provider is connected to,
+    /// so they can't be replayed on the other zkSync networks. Must be invoked once the server
+    /// requires the signature domain (protocol version `SIGNATURE_DOMAIN_PROTOCOL_VERSION` or newer).
+    pub async fn enable_signature_domain(&mut self) -> Result<(), ClientError> {
+        let address_response = self.provider.contract_address().await?;
+        let contract_address = address_response
+            .main_contract
+            .trim_start_matches("0x")
+            .parse()
+            .map_err(|err| ClientError::MalformedResponse(format!("{}", err)))?;
+        self.signer.set_signature_domain(Some(SignatureDomain::new(
+            self.provider.network().chain_id().into(),
+            contract_address,
+        )));
+
+        Ok(())
+    }
+
     /// Returns the wallet address.
     pub fn address(&self) -> Address {
         self.signer.address
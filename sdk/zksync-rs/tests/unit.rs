@@ -286,6 +286,51 @@ mod signatures_with_vectors {
         }
     }
 
+    #[tokio::test]
+    async fn test_change_pubkey_create2_signature() {
+        use zksync_types::{tx::ChangePubKeyCREATE2Data, PubKeyHash};
+
+        let private_key = private_key_from_seed(&[1; 32]).unwrap();
+        let create2_data = ChangePubKeyCREATE2Data {
+            creator_address: Address::repeat_byte(0x11),
+            salt_arg: H256::repeat_byte(0x22),
+            code_hash: H256::repeat_byte(0x33),
+        };
+        let address = create2_data.get_address(&PubKeyHash::from_privkey(&private_key));
+        let token = Token::new(TokenId(0), Address::zero(), "ETH", 18);
+
+        let mut signer = Signer::new(private_key, address, None::<PrivateKeySigner>);
+        signer.set_account_id(Some(AccountId(1)));
+        let change_pub_key = signer
+            .sign_change_pubkey_tx_create2(
+                Nonce(0),
+                create2_data.clone(),
+                token.clone(),
+                0u32.into(),
+                Default::default(),
+            )
+            .await
+            .expect("Change pub key signing error");
+        assert!(matches!(
+            change_pub_key.eth_auth_data,
+            Some(ChangePubKeyEthAuthData::CREATE2(_))
+        ));
+        assert!(change_pub_key.check_correctness());
+
+        // The CREATE2 data of the other wallet can't authorize the key change.
+        signer.address = Address::repeat_byte(0x44);
+        assert!(signer
+            .sign_change_pubkey_tx_create2(
+                Nonce(0),
+                create2_data,
+                token,
+                0u32.into(),
+                Default::default(),
+            )
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_change_pubkey_signature() {
         let test_vectors = TestVectorsConfig::load();
@@ -278,7 +278,9 @@ export class BatchBuilder {
         messages.push(`Nonce: ${batchNonce}`);
         return {
             txs: processedTxs,
-            message: messages.filter((part) => part.length != 0).join('\n')
+            message: this.wallet.ethMessageSigner.applySignatureDomain(
+                messages.filter((part) => part.length != 0).join('\n')
+            )
         };
     }
 }
@@ -1,13 +1,34 @@
 import * as ethers from 'ethers';
-import { TxEthSignature, EthSignerType, PubKeyHash } from './types';
-import { getSignedBytesFromMessage, signMessagePersonalAPI, getChangePubkeyMessage } from './utils';
+import { TxEthSignature, EthSignerType, PubKeyHash, SignatureDomain } from './types';
+import {
+    getSignedBytesFromMessage,
+    signMessagePersonalAPI,
+    getChangePubkeyMessage,
+    applySignatureDomain
+} from './utils';
 
 /**
  * Wrapper around `ethers.Signer` which provides convenient methods to get and sign messages required for zkSync.
  */
 export class EthMessageSigner {
+    private signatureDomain?: SignatureDomain;
+
     constructor(private ethSigner: ethers.Signer, private ethSignerType?: EthSignerType) {}
 
+    /**
+     * Sets the network the signed transaction messages are bound to.
+     */
+    setSignatureDomain(signatureDomain?: SignatureDomain) {
+        this.signatureDomain = signatureDomain;
+    }
+
+    /**
+     * Appends the network domain (if set) to the complete message of the transaction or batch.
+     */
+    applySignatureDomain(message: string): string {
+        return applySignatureDomain(message, this.signatureDomain);
+    }
+
     async getEthMessageSignature(message: ethers.utils.BytesLike): Promise<TxEthSignature> {
         if (this.ethSignerType == null) {
             throw new Error('ethSignerType is unknown');
@@ -37,7 +58,7 @@ export class EthMessageSigner {
         }
         humanReadableTxInfo += `Nonce: ${transfer.nonce}`;
 
-        return humanReadableTxInfo;
+        return this.applySignatureDomain(humanReadableTxInfo);
     }
 
     async ethSignTransfer(transfer: {
@@ -76,7 +97,7 @@ export class EthMessageSigner {
         }
         humanReadableTxInfo += `Nonce: ${withdraw.nonce}`;
 
-        return humanReadableTxInfo;
+        return this.applySignatureDomain(humanReadableTxInfo);
     }
 
     getForcedExitEthSignMessage(forcedExit: {
@@ -87,7 +108,7 @@ export class EthMessageSigner {
     }): string {
         let humanReadableTxInfo = this.getForcedExitEthMessagePart(forcedExit);
         humanReadableTxInfo += `\nNonce: ${forcedExit.nonce}`;
-        return humanReadableTxInfo;
+        return this.applySignatureDomain(humanReadableTxInfo);
     }
 
     getTransferEthMessagePart(tx: {
@@ -211,11 +211,13 @@ export class Provider {
         } else {
             while (true) {
                 const transactionStatus = await this.getTxReceipt(hash);
+                // Transactions expired in the mempool fail without being included into a block.
+                const expired = !transactionStatus.executed && transactionStatus.success === false;
                 const notifyDone =
                     action == 'COMMIT'
                         ? transactionStatus.block && transactionStatus.block.committed
                         : transactionStatus.block && transactionStatus.block.verified;
-                if (notifyDone) {
+                if (notifyDone || expired) {
                     return transactionStatus;
                 } else {
                     await sleep(this.pollIntervalMilliSecs);
@@ -183,6 +183,37 @@ export interface ContractAddress {
     govContract: string;
 }
 
+/**
+ * Network the Ethereum signatures of the transactions are bound to,
+ * required by the servers running the protocol version 6 or newer.
+ */
+export interface SignatureDomain {
+    chainId: number;
+    contractAddress: Address;
+}
+
+/**
+ * Request reserving the range of nonces of the account, see `Wallet.getNonceReservationRequest`.
+ */
+export interface NonceReservationRequest {
+    address: Address;
+    count: number;
+    // Time the request was signed at, in milliseconds.
+    timestamp: number;
+    signature: string;
+}
+
+/**
+ * Request registering the alias of the account, see `Wallet.getAliasRegistrationRequest`.
+ */
+export interface AliasRegistrationRequest {
+    alias: string;
+    address: Address;
+    // Time the request was signed at, in milliseconds.
+    timestamp: number;
+    signature: string;
+}
+
 export interface Tokens {
     // Tokens are indexed by their symbol (e.g. "ETH")
     [token: string]: {
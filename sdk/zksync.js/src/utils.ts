@@ -12,7 +12,8 @@ import {
     ForcedExit,
     ChangePubKey,
     Withdraw,
-    CloseAccount
+    CloseAccount,
+    SignatureDomain
 } from './types';
 
 // Max number of tokens for the current version, it is determined by the zkSync circuit implementation.
@@ -383,6 +384,17 @@ export class TokenSet {
     }
 }
 
+/**
+ * Appends the network domain to the message signed by the Ethereum key, so the signature
+ * cannot be replayed on the other zkSync networks.
+ */
+export function applySignatureDomain(message: string, domain?: SignatureDomain): string {
+    if (domain == null) {
+        return message;
+    }
+    return `${message}\nChain ID: ${domain.chainId}\nzkSync: ${domain.contractAddress.toLowerCase()}`;
+}
+
 export function getChangePubkeyMessage(
     pubKeyHash: PubKeyHash,
     nonce: number,
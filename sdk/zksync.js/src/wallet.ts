@@ -23,7 +23,9 @@ import {
     ChangePubKeyOnchain,
     ChangePubKeyECDSA,
     ChangePubKeyCREATE2,
-    Create2Data
+    Create2Data,
+    NonceReservationRequest,
+    AliasRegistrationRequest
 } from './types';
 import {
     ERC20_APPROVE_TRESHOLD,
@@ -67,6 +69,18 @@ export class Wallet {
         return this;
     }
 
+    /**
+     * Binds the Ethereum signatures of the transactions to the network of the provider,
+     * so they cannot be replayed on the other zkSync networks. Required by the servers
+     * running the protocol version 6 or newer.
+     */
+    async enableSignatureDomain() {
+        this.ethMessageSigner.setSignatureDomain({
+            chainId: await this.ethSigner.getChainId(),
+            contractAddress: this.provider.contractAddress.mainContract
+        });
+    }
+
     static async fromEthSigner(
         ethWallet: ethers.Signer,
         provider: Provider,
@@ -351,7 +365,9 @@ export class Wallet {
         }
 
         messages.push(`Nonce: ${batchNonce}`);
-        const message = messages.filter((part) => part.length != 0).join('\n');
+        const message = this.ethMessageSigner.applySignatureDomain(
+            messages.filter((part) => part.length != 0).join('\n')
+        );
         const ethSignatures =
             this.ethSigner instanceof Create2WalletSigner
                 ? []
@@ -750,6 +766,48 @@ export class Wallet {
         }
     }
 
+    /**
+     * Signs the request reserving `count` nonces of the account, so the transactions with these nonces
+     * can be signed and sent concurrently. The request is submitted to `/api/v1/nonce_reservations`.
+     */
+    async getNonceReservationRequest(count: number, timestamp: number = Date.now()): Promise<NonceReservationRequest> {
+        const message = this.ethMessageSigner.applySignatureDomain(
+            `Reserve ${count} zkSync nonces.\n` +
+                `Account: ${this.address().toLowerCase()}\n` +
+                `Timestamp: ${timestamp}`
+        );
+        const { signature } = await this.getEthMessageSignature(message);
+        return {
+            address: this.address(),
+            count,
+            timestamp,
+            signature
+        };
+    }
+
+    /**
+     * Signs the request registering the alias of the account, so the funds can be sent to the alias
+     * instead of the address. The request is submitted to `/api/v1/aliases`, and the registered aliases
+     * are resolved via `/api/v1/aliases/{alias}`.
+     */
+    async getAliasRegistrationRequest(
+        alias: string,
+        timestamp: number = Date.now()
+    ): Promise<AliasRegistrationRequest> {
+        const message = this.ethMessageSigner.applySignatureDomain(
+            `Register zkSync alias: ${alias}\n` +
+                `Account: ${this.address().toLowerCase()}\n` +
+                `Timestamp: ${timestamp}`
+        );
+        const { signature } = await this.getEthMessageSignature(message);
+        return {
+            alias,
+            address: this.address(),
+            timestamp,
+            signature
+        };
+    }
+
     async getAccountId(): Promise<number | undefined> {
         return (await this.provider.getState(this.address())).id;
     }